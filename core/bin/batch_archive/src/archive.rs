@@ -0,0 +1,569 @@
+//! Portable archive format for a range of L1 batches and its offline verification.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use anyhow::Context as _;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zkevm_test_harness_1_3_3::{
+    abstract_zksync_circuit::concrete_circuits::{
+        ZkSyncCircuit, ZkSyncProof, ZkSyncVerificationKey,
+    },
+    bellman::{bn256::Bn256, plonk::better_better_cs::setup::VerificationKey},
+    ff::to_hex,
+    witness::oracle::VmWitnessOracle,
+};
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_object_store::StoredObject;
+use zksync_prover_interface::outputs::L1BatchProofForL1;
+use zksync_system_constants::{
+    BLOB1_LINEAR_HASH_KEY, BLOB2_LINEAR_HASH_KEY, L2_TO_L1_LOGS_TREE_ROOT_KEY,
+    PUBDATA_CHUNK_PUBLISHER_ADDRESS, STATE_DIFF_HASH_KEY,
+};
+use zksync_types::{
+    commitment::{
+        pre_boojum_serialize_commitments, proof_public_input, serialize_commitments,
+        BlobCommitment, L1BatchMetadata, L1BatchWithMetadata,
+    },
+    l2_to_l1_log::l2_to_l1_logs_tree_size,
+    web3::signing::keccak256,
+    Address, Bytes, L1BatchNumber, H256, U256,
+};
+
+/// Version of the archive format. Must be bumped on incompatible changes.
+pub(crate) const ARCHIVE_VERSION: u32 = 1;
+
+/// Commitment data of the L1 batch preceding the archived range. Used to compute the public input
+/// of the proof for the first archived batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ArchivedAnchor {
+    pub number: L1BatchNumber,
+    pub root_hash: H256,
+    pub commitment: H256,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ArchivedBatch {
+    pub l1_batch: L1BatchWithMetadata,
+    /// Pubdata published for the batch on L1.
+    pub pubdata: Bytes,
+    pub blob_commitments: Option<Vec<BlobCommitment>>,
+    /// Final proof for the batch serialized in the object store format, if the proof is available.
+    pub proof: Option<Bytes>,
+}
+
+/// Archive with data for a contiguous range of L1 batches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BatchArchive {
+    pub version: u32,
+    pub anchor: Option<ArchivedAnchor>,
+    pub batches: Vec<ArchivedBatch>,
+}
+
+/// Writes the value to the specified path as gzipped JSON.
+pub(crate) fn write_json_gz<T: Serialize>(value: &T, path: &Path) -> anyhow::Result<()> {
+    let file = fs::File::create(path)
+        .with_context(|| format!("failed creating file `{}`", path.display()))?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    serde_json::to_writer(&mut encoder, value).context("failed serializing JSON")?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Reads a value written with [`write_json_gz()`].
+pub(crate) fn read_json_gz<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("failed opening file `{}`", path.display()))?;
+    serde_json::from_reader(GzDecoder::new(BufReader::new(file)))
+        .context("failed deserializing JSON")
+}
+
+impl BatchArchive {
+    /// Writes the archive to the specified path as gzipped JSON.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        write_json_gz(self, path).context("failed writing archive")
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let archive: Self = read_json_gz(path).context("failed reading archive")?;
+        anyhow::ensure!(
+            archive.version == ARCHIVE_VERSION,
+            "unsupported archive version {}; expected {ARCHIVE_VERSION}",
+            archive.version
+        );
+        Ok(archive)
+    }
+}
+
+/// Verification key for final batch proofs, in the JSON format used by the prover keystore
+/// (`snark_verification_scheduler_key.json`).
+pub(crate) type SnarkVerificationKey =
+    VerificationKey<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>;
+
+pub(crate) fn read_verification_key(path: &Path) -> anyhow::Result<SnarkVerificationKey> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("failed reading verification key `{}`", path.display()))?;
+    serde_json::from_str(&json).context("malformed verification key")
+}
+
+/// Result of verifying a single archived L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct BatchVerification {
+    pub number: L1BatchNumber,
+    pub commitment: H256,
+    /// Public input of the batch proof; `None` for the first batch if the archive has no anchor.
+    pub public_input: Option<U256>,
+    pub has_proof: bool,
+    /// Whether the batch proof is verified against the public input using the verification key.
+    pub is_proof_verified: bool,
+    /// Whether the batch commitment is checked against a trusted commitment (e.g., one taken from L1).
+    pub is_anchored: bool,
+    pub errors: Vec<String>,
+}
+
+/// Hash of the pass-through data for the batch, i.e. the rollup shared state followed by the (always empty)
+/// zkPorter shared state.
+fn pass_through_data_hash(metadata: &L1BatchMetadata) -> H256 {
+    let mut bytes = Vec::with_capacity(80);
+    bytes.extend_from_slice(&metadata.rollup_last_leaf_index.to_be_bytes());
+    bytes.extend_from_slice(metadata.root_hash.as_bytes());
+    bytes.extend_from_slice(&0_u64.to_be_bytes());
+    bytes.extend_from_slice(H256::zero().as_bytes());
+    H256(keccak256(&bytes))
+}
+
+fn commitment(metadata: &L1BatchMetadata) -> H256 {
+    let mut bytes = Vec::with_capacity(96);
+    bytes.extend_from_slice(metadata.pass_through_data_hash.as_bytes());
+    bytes.extend_from_slice(metadata.meta_parameters_hash.as_bytes());
+    bytes.extend_from_slice(metadata.aux_data_hash.as_bytes());
+    H256(keccak256(&bytes))
+}
+
+fn system_log_value(
+    batch: &L1BatchWithMetadata,
+    sender: Option<Address>,
+    key: u32,
+) -> Option<H256> {
+    let key = H256::from_low_u64_be(key.into());
+    batch.header.system_logs.iter().find_map(|log| {
+        let is_matching = log.0.key == key && sender.map_or(true, |sender| log.0.sender == sender);
+        is_matching.then_some(log.0.value)
+    })
+}
+
+/// Root of the Merkle tree built from user L2-to-L1 logs of the batch.
+fn l2_l1_logs_merkle_root(batch: &L1BatchWithMetadata) -> anyhow::Result<H256> {
+    let header = &batch.header;
+    let protocol_version = header.protocol_version.context("no protocol version")?;
+    let tree_size = l2_to_l1_logs_tree_size(protocol_version);
+    anyhow::ensure!(
+        header.l2_to_l1_logs.len() <= tree_size,
+        "too many L2-to-L1 logs: {}, while at most {tree_size} are allowed",
+        header.l2_to_l1_logs.len()
+    );
+    let leaves = header.l2_to_l1_logs.iter().map(|log| log.0.to_bytes());
+    Ok(MiniMerkleTree::new(leaves, Some(tree_size)).merkle_root())
+}
+
+/// Recomputes the hash of the auxiliary output for the batch in the same way as the L1 contracts do,
+/// so that the batch commitment is bound to the archived logs, state diffs and blobs.
+fn aux_data_hash(batch: &ArchivedBatch) -> anyhow::Result<H256> {
+    let header = &batch.l1_batch.header;
+    let metadata = &batch.l1_batch.metadata;
+    let protocol_version = header.protocol_version.context("no protocol version")?;
+
+    let mut bytes = vec![];
+    if protocol_version.is_pre_boojum() {
+        let l2_l1_logs_compressed = pre_boojum_serialize_commitments(&header.l2_to_l1_logs);
+        let initial_writes = metadata
+            .initial_writes_compressed
+            .as_deref()
+            .context("no initial writes")?;
+        let repeated_writes = metadata
+            .repeated_writes_compressed
+            .as_deref()
+            .context("no repeated writes")?;
+        bytes.extend_from_slice(l2_l1_logs_merkle_root(&batch.l1_batch)?.as_bytes());
+        bytes.extend_from_slice(&keccak256(&l2_l1_logs_compressed));
+        bytes.extend_from_slice(&keccak256(initial_writes));
+        bytes.extend_from_slice(&keccak256(repeated_writes));
+        return Ok(H256(keccak256(&bytes)));
+    }
+
+    let system_logs_compressed = serialize_commitments(&header.system_logs);
+    let state_diffs_hash = system_log_value(&batch.l1_batch, None, STATE_DIFF_HASH_KEY)
+        .context("no state diff hash in system logs")?;
+    let bootloader_initial_content_commitment = metadata
+        .bootloader_initial_content_commitment
+        .context("no bootloader initial content commitment")?;
+    let events_queue_commitment = metadata
+        .events_queue_commitment
+        .context("no events queue commitment")?;
+    bytes.extend_from_slice(&keccak256(&system_logs_compressed));
+    bytes.extend_from_slice(state_diffs_hash.as_bytes());
+    bytes.extend_from_slice(bootloader_initial_content_commitment.as_bytes());
+    bytes.extend_from_slice(events_queue_commitment.as_bytes());
+
+    if protocol_version.is_1_4_1() {
+        // Commitments to the KZG pubdata are zeroes by convention.
+        bytes.extend_from_slice(&[0; 128]);
+    } else if protocol_version.is_post_1_4_2() {
+        let blob_keys = [BLOB1_LINEAR_HASH_KEY, BLOB2_LINEAR_HASH_KEY];
+        let blob_commitments = batch.blob_commitments.as_deref().unwrap_or_default();
+        for (i, key) in blob_keys.into_iter().enumerate() {
+            let linear_hash =
+                system_log_value(&batch.l1_batch, Some(PUBDATA_CHUNK_PUBLISHER_ADDRESS), key)
+                    .unwrap_or_default();
+            let commitment = blob_commitments.get(i).copied().unwrap_or(BlobCommitment {
+                linear_hash: H256::zero(),
+                commitment: H256::zero(),
+            });
+            anyhow::ensure!(
+                commitment.linear_hash == linear_hash,
+                "linear hash of blob #{i} doesn't match system logs"
+            );
+            bytes.extend_from_slice(linear_hash.as_bytes());
+            bytes.extend_from_slice(commitment.commitment.as_bytes());
+        }
+    }
+    Ok(H256(keccak256(&bytes)))
+}
+
+/// Verifies the batch proof against the expected public input.
+fn verify_proof(
+    proof: &Bytes,
+    public_input: Option<U256>,
+    verification_key: Option<&SnarkVerificationKey>,
+) -> Result<(), String> {
+    let proof = L1BatchProofForL1::deserialize(proof.0.clone())
+        .map_err(|err| format!("proof cannot be deserialized: {err}"))?;
+    let public_input = public_input
+        .ok_or("proof cannot be verified without the commitment of the previous batch")?;
+    let verification_key =
+        verification_key.ok_or("proof cannot be verified without a verification key")?;
+
+    let proof_inputs: Result<Vec<_>, _> = proof
+        .scheduler_proof
+        .inputs
+        .iter()
+        .map(|input| U256::from_str_radix(&to_hex(input), 16))
+        .collect();
+    let proof_inputs = proof_inputs.map_err(|err| format!("malformed proof inputs: {err}"))?;
+    if proof_inputs != [public_input] {
+        return Err(format!(
+            "proof public inputs {proof_inputs:?} differ from the expected public input {public_input:?}"
+        ));
+    }
+
+    let verification_key =
+        ZkSyncVerificationKey::from_verification_key_and_numeric_type(0, verification_key.clone());
+    let proof = ZkSyncProof::from_proof_and_numeric_type(0, proof.scheduler_proof);
+    if !verification_key.verify_proof(&proof) {
+        return Err("proof is invalid for the verification key".to_owned());
+    }
+    Ok(())
+}
+
+fn verify_batch(batch: &ArchivedBatch, errors: &mut Vec<String>) {
+    let header = &batch.l1_batch.header;
+    let metadata = &batch.l1_batch.metadata;
+
+    if pass_through_data_hash(metadata) != metadata.pass_through_data_hash {
+        errors.push("pass-through data hash doesn't match the root hash".to_owned());
+    }
+    if metadata.block_meta_params.hash() != metadata.meta_parameters_hash {
+        errors.push("meta parameters hash doesn't match meta parameters".to_owned());
+    }
+    let meta_params = &metadata.block_meta_params;
+    let contract_hashes = &header.base_system_contracts_hashes;
+    if meta_params.bootloader_code_hash != contract_hashes.bootloader
+        || meta_params.default_aa_code_hash != contract_hashes.default_aa
+    {
+        errors.push("meta parameters don't match base system contracts of the batch".to_owned());
+    }
+    match aux_data_hash(batch) {
+        Ok(hash) if hash == metadata.aux_data_hash => {}
+        Ok(_) => errors.push("aux data hash doesn't match logs, state diffs and blobs".to_owned()),
+        Err(err) => errors.push(format!("aux data hash cannot be computed: {err:#}")),
+    }
+    match l2_l1_logs_merkle_root(&batch.l1_batch) {
+        Ok(root) => {
+            if root != metadata.l2_l1_merkle_root {
+                errors.push("L2-to-L1 logs Merkle root doesn't match the logs".to_owned());
+            }
+            let is_post_boojum = header
+                .protocol_version
+                .map_or(false, |version| !version.is_pre_boojum());
+            let root_from_logs =
+                system_log_value(&batch.l1_batch, None, L2_TO_L1_LOGS_TREE_ROOT_KEY);
+            if is_post_boojum && root_from_logs != Some(root) {
+                errors.push("L2-to-L1 logs Merkle root doesn't match system logs".to_owned());
+            }
+        }
+        Err(err) => errors.push(format!(
+            "L2-to-L1 logs Merkle root cannot be computed: {err:#}"
+        )),
+    }
+    if commitment(metadata) != metadata.commitment {
+        errors.push("commitment doesn't match its components".to_owned());
+    }
+    if batch.pubdata.0 != batch.l1_batch.construct_pubdata() {
+        errors.push("pubdata doesn't match logs, messages, bytecodes and state diffs".to_owned());
+    }
+}
+
+/// Verifies the archive without accessing the network. `trusted_commitments` are commitments obtained
+/// from a trusted source (e.g., `BlockCommit` events on L1); archived batches are checked against them.
+/// Archived proofs are verified using `verification_key`; if the key is not provided, batches with proofs
+/// fail verification.
+pub(crate) fn verify_archive(
+    archive: &BatchArchive,
+    trusted_commitments: &HashMap<L1BatchNumber, H256>,
+    verification_key: Option<&SnarkVerificationKey>,
+) -> Vec<BatchVerification> {
+    let mut prev = archive
+        .anchor
+        .as_ref()
+        .map(|anchor| (anchor.number, anchor.commitment));
+    let mut results = Vec::with_capacity(archive.batches.len());
+    for batch in &archive.batches {
+        let number = batch.l1_batch.header.number;
+        let commitment = batch.l1_batch.metadata.commitment;
+        let mut errors = vec![];
+        verify_batch(batch, &mut errors);
+
+        let public_input = match prev {
+            Some((prev_number, prev_commitment)) if prev_number + 1 == number => {
+                Some(proof_public_input(prev_commitment, commitment))
+            }
+            Some((prev_number, _)) => {
+                errors.push(format!(
+                    "batch doesn't follow the previous batch #{prev_number} in the archive"
+                ));
+                None
+            }
+            None => None,
+        };
+        let mut is_proof_verified = false;
+        if let Some(proof) = &batch.proof {
+            match verify_proof(proof, public_input, verification_key) {
+                Ok(()) => is_proof_verified = true,
+                Err(err) => errors.push(err),
+            }
+        }
+        let trusted_commitment = trusted_commitments.get(&number);
+        if let Some(trusted_commitment) = trusted_commitment {
+            if *trusted_commitment != commitment {
+                errors.push(format!(
+                    "commitment differs from the trusted commitment {trusted_commitment:?}"
+                ));
+            }
+        }
+
+        results.push(BatchVerification {
+            number,
+            commitment,
+            public_input,
+            has_proof: batch.proof.is_some(),
+            is_proof_verified,
+            is_anchored: trusted_commitment.is_some(),
+            errors,
+        });
+        prev = Some((number, commitment));
+    }
+    results
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use tempfile::TempDir;
+    use zksync_types::{
+        block::L1BatchHeader,
+        commitment::L1BatchMetaParameters,
+        l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log},
+        ProtocolVersionId,
+    };
+
+    use super::*;
+
+    fn system_log(key: u32, value: H256) -> SystemL2ToL1Log {
+        SystemL2ToL1Log(L2ToL1Log {
+            key: H256::from_low_u64_be(key.into()),
+            value,
+            ..L2ToL1Log::default()
+        })
+    }
+
+    pub(crate) fn create_batch(number: u32) -> ArchivedBatch {
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        header.system_logs = vec![system_log(
+            STATE_DIFF_HASH_KEY,
+            H256::repeat_byte(number as u8),
+        )];
+        let block_meta_params = L1BatchMetaParameters {
+            zkporter_is_available: false,
+            bootloader_code_hash: header.base_system_contracts_hashes.bootloader,
+            default_aa_code_hash: header.base_system_contracts_hashes.default_aa,
+        };
+        let mut metadata = L1BatchMetadata {
+            root_hash: H256::repeat_byte(number as u8),
+            rollup_last_leaf_index: number.into(),
+            merkle_root_hash: H256::zero(),
+            initial_writes_compressed: None,
+            repeated_writes_compressed: None,
+            commitment: H256::zero(),
+            l2_l1_merkle_root: H256::zero(),
+            meta_parameters_hash: block_meta_params.hash(),
+            block_meta_params,
+            aux_data_hash: H256::zero(),
+            pass_through_data_hash: H256::zero(),
+            events_queue_commitment: Some(H256::repeat_byte(0xee)),
+            bootloader_initial_content_commitment: Some(H256::repeat_byte(0xbb)),
+            state_diffs_compressed: vec![1, 2, 3],
+        };
+        metadata.pass_through_data_hash = pass_through_data_hash(&metadata);
+
+        let l1_batch = L1BatchWithMetadata {
+            header,
+            metadata,
+            raw_published_factory_deps: vec![],
+        };
+        let mut batch = ArchivedBatch {
+            pubdata: l1_batch.construct_pubdata().into(),
+            l1_batch,
+            blob_commitments: None,
+            proof: None,
+        };
+        let l2_l1_merkle_root = l2_l1_logs_merkle_root(&batch.l1_batch).unwrap();
+        batch.l1_batch.metadata.l2_l1_merkle_root = l2_l1_merkle_root;
+        batch
+            .l1_batch
+            .header
+            .system_logs
+            .push(system_log(L2_TO_L1_LOGS_TREE_ROOT_KEY, l2_l1_merkle_root));
+        batch.l1_batch.metadata.aux_data_hash = aux_data_hash(&batch).unwrap();
+        batch.l1_batch.metadata.commitment = commitment(&batch.l1_batch.metadata);
+        batch
+    }
+
+    fn create_archive() -> BatchArchive {
+        let anchor = create_batch(1);
+        BatchArchive {
+            version: ARCHIVE_VERSION,
+            anchor: Some(ArchivedAnchor {
+                number: L1BatchNumber(1),
+                root_hash: anchor.l1_batch.metadata.root_hash,
+                commitment: anchor.l1_batch.metadata.commitment,
+            }),
+            batches: (2..5).map(create_batch).collect(),
+        }
+    }
+
+    #[test]
+    fn verifying_valid_archive() {
+        let archive = create_archive();
+        let trusted_commitments = HashMap::from([(
+            L1BatchNumber(4),
+            archive.batches[2].l1_batch.metadata.commitment,
+        )]);
+        let results = verify_archive(&archive, &trusted_commitments, None);
+
+        assert_eq!(results.len(), 3);
+        for result in &results {
+            assert!(result.errors.is_empty(), "{result:?}");
+            assert!(result.public_input.is_some());
+        }
+        assert!(results[2].is_anchored);
+        let expected_public_input = proof_public_input(
+            archive.anchor.as_ref().unwrap().commitment,
+            archive.batches[0].l1_batch.metadata.commitment,
+        );
+        assert_eq!(results[0].public_input, Some(expected_public_input));
+    }
+
+    #[test]
+    fn detecting_tampered_batches() {
+        let mut archive = create_archive();
+        archive.batches[0].l1_batch.metadata.root_hash = H256::repeat_byte(0xff);
+        archive.batches[1].pubdata.0.push(0);
+        archive.batches.remove(2);
+        archive.batches.push(create_batch(5));
+        let trusted_commitments = HashMap::from([(L1BatchNumber(5), H256::zero())]);
+
+        let results = verify_archive(&archive, &trusted_commitments, None);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].errors[0].contains("pass-through"), "{results:?}");
+        assert!(results[1].errors[0].contains("pubdata"), "{results:?}");
+        assert_eq!(results[2].errors.len(), 2, "{results:?}");
+        assert_eq!(results[2].public_input, None);
+    }
+
+    #[test]
+    fn detecting_tampered_aux_data() {
+        let mut archive = create_archive();
+        // Tamper with data covered by the aux data hash, but not by other checks.
+        archive.batches[0].l1_batch.metadata.events_queue_commitment = Some(H256::zero());
+        archive.batches[1].l1_batch.header.system_logs[0].0.value = H256::zero();
+        archive.batches[2].blob_commitments = Some(vec![BlobCommitment {
+            linear_hash: H256::zero(),
+            commitment: H256::repeat_byte(1),
+        }]);
+
+        let results = verify_archive(&archive, &HashMap::new(), None);
+        for result in &results {
+            assert_eq!(result.errors.len(), 1, "{result:?}");
+            assert!(result.errors[0].contains("aux data hash"), "{result:?}");
+        }
+    }
+
+    #[test]
+    fn detecting_tampered_l2_to_l1_logs() {
+        let mut archive = create_archive();
+        archive.batches[0]
+            .l1_batch
+            .header
+            .l2_to_l1_logs
+            .push(Default::default());
+
+        let results = verify_archive(&archive, &HashMap::new(), None);
+        let errors = &results[0].errors;
+        assert!(
+            errors.iter().any(|err| err.contains("Merkle root")),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn proofs_are_not_accepted_without_verification() {
+        let mut archive = create_archive();
+        archive.batches[0].proof = Some(Bytes(vec![1, 2, 3]));
+
+        let results = verify_archive(&archive, &HashMap::new(), None);
+        assert!(!results[0].is_proof_verified);
+        assert!(
+            results[0].errors[0].contains("cannot be deserialized"),
+            "{results:?}"
+        );
+    }
+
+    #[test]
+    fn archive_roundtrip() {
+        let archive = create_archive();
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("archive.json.gz");
+        archive.write(&path).unwrap();
+        let restored = BatchArchive::read(&path).unwrap();
+        assert_eq!(restored, archive);
+    }
+}
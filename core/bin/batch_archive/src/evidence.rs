@@ -0,0 +1,253 @@
+//! Signed evidence bundles for a single L1 batch.
+//!
+//! A bundle contains everything a third party needs to independently re-verify an L1 batch: its header
+//! and metadata, pubdata, proof, verifier key hashes for the batch protocol version, and references
+//! to L1 transactions committing, proving and executing the batch. The bundle is signed by the operator,
+//! so that it can be used as evidence in disputes and audits: the operator cannot repudiate the data it has signed.
+
+use std::path::Path;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    protocol_version::L1VerifierConfig, web3::signing::keccak256, Address, Bytes,
+    PackedEthSignature, H256,
+};
+
+use crate::archive::{
+    read_json_gz, verify_archive, write_json_gz, ArchivedAnchor, ArchivedBatch, BatchArchive,
+    BatchVerification, SnarkVerificationKey, ARCHIVE_VERSION,
+};
+
+/// Version of the evidence bundle format. Must be bumped on incompatible changes.
+pub(crate) const EVIDENCE_VERSION: u32 = 1;
+
+/// References to L1 transactions for an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct L1TxReferences {
+    /// Address of the main zkSync contract on L1 the transactions are sent to.
+    pub diamond_proxy_addr: Address,
+    pub commit_tx_hash: Option<H256>,
+    pub prove_tx_hash: Option<H256>,
+    pub execute_tx_hash: Option<H256>,
+}
+
+/// Evidence for a single L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct EvidenceBundle {
+    pub version: u32,
+    /// Commitment data of the preceding L1 batch, used to compute the proof public input.
+    pub anchor: Option<ArchivedAnchor>,
+    pub batch: ArchivedBatch,
+    /// Verifier key hashes for the protocol version of the batch.
+    pub verifier_config: L1VerifierConfig,
+    pub l1_txs: L1TxReferences,
+}
+
+/// Computes the EIP-191 hash of a personal message: `keccak256("\x19Ethereum Signed Message:\n" || len || payload)`,
+/// where `len` is the decimal byte length of the payload.
+fn personal_message_hash(payload: &[u8]) -> H256 {
+    let mut bytes = format!("\x19Ethereum Signed Message:\n{}", payload.len()).into_bytes();
+    bytes.extend_from_slice(payload);
+    H256(keccak256(&bytes))
+}
+
+impl EvidenceBundle {
+    /// Signs the bundle with the specified private key.
+    pub fn sign(&self, private_key: &H256) -> anyhow::Result<SignedEvidenceBundle> {
+        let payload = serde_json::to_string(self).context("failed serializing evidence bundle")?;
+        let signed_bytes = personal_message_hash(payload.as_bytes());
+        let signature = PackedEthSignature::sign_raw(private_key, &signed_bytes)
+            .context("failed signing evidence bundle")?;
+        Ok(SignedEvidenceBundle {
+            payload,
+            signature: Bytes(signature.serialize_packed().to_vec()),
+        })
+    }
+}
+
+/// Signed [`EvidenceBundle`]. The signature is an EIP-191 personal message signature over the payload
+/// (i.e., the one produced by `personal_sign`), so that it can be checked with standard Ethereum tooling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SignedEvidenceBundle {
+    /// Serialized [`EvidenceBundle`]. Kept as a string so that the signed bytes are preserved exactly.
+    pub payload: String,
+    pub signature: Bytes,
+}
+
+impl SignedEvidenceBundle {
+    /// Writes the signed bundle to the specified path as gzipped JSON.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        write_json_gz(self, path).context("failed writing evidence bundle")
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        read_json_gz(path).context("failed reading evidence bundle")
+    }
+
+    /// Recovers the signer of the bundle and deserializes it. If `expected_signer` is specified,
+    /// checks that the bundle is signed by it.
+    pub fn open(
+        &self,
+        expected_signer: Option<Address>,
+    ) -> anyhow::Result<(EvidenceBundle, Address)> {
+        let signature = PackedEthSignature::deserialize_packed(&self.signature.0)
+            .context("malformed evidence bundle signature")?;
+        let signed_bytes = personal_message_hash(self.payload.as_bytes());
+        let signer = signature
+            .signature_recover_signer(&signed_bytes)
+            .context("cannot recover evidence bundle signer")?;
+        if let Some(expected_signer) = expected_signer {
+            anyhow::ensure!(
+                signer == expected_signer,
+                "evidence bundle is signed by {signer:?}, while {expected_signer:?} is expected"
+            );
+        }
+
+        let bundle: EvidenceBundle =
+            serde_json::from_str(&self.payload).context("failed deserializing evidence bundle")?;
+        anyhow::ensure!(
+            bundle.version == EVIDENCE_VERSION,
+            "unsupported evidence bundle version {}; expected {EVIDENCE_VERSION}",
+            bundle.version
+        );
+        Ok((bundle, signer))
+    }
+}
+
+/// Verifies the evidence bundle without accessing the network. If `trusted_commitment` is specified
+/// (e.g., taken from the `BlockCommit` event on L1), the batch commitment is checked against it.
+/// The bundled proof is verified using `verification_key`; a bundle with a proof that cannot be verified
+/// (e.g., because the key is not provided) fails verification.
+pub(crate) fn verify_evidence(
+    bundle: &EvidenceBundle,
+    trusted_commitment: Option<H256>,
+    verification_key: Option<&SnarkVerificationKey>,
+) -> BatchVerification {
+    let number = bundle.batch.l1_batch.header.number;
+    let archive = BatchArchive {
+        version: ARCHIVE_VERSION,
+        anchor: bundle.anchor.clone(),
+        batches: vec![bundle.batch.clone()],
+    };
+    let trusted_commitments = trusted_commitment
+        .map(|commitment| (number, commitment))
+        .into_iter()
+        .collect();
+    let mut verification = verify_archive(&archive, &trusted_commitments, verification_key)
+        .pop()
+        .expect("no verification for archived batch");
+
+    if bundle.anchor.is_none() && number.0 > 0 {
+        verification
+            .errors
+            .push("no data for the previous batch to compute the proof public input".to_owned());
+    }
+    if bundle.l1_txs.prove_tx_hash.is_some() && bundle.batch.proof.is_none() {
+        verification
+            .errors
+            .push("batch is proven on L1, but the bundle has no proof".to_owned());
+    }
+    if bundle.verifier_config.recursion_scheduler_level_vk_hash == H256::zero() {
+        verification
+            .errors
+            .push("verifier key hash is not set".to_owned());
+    }
+    verification
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::L1BatchNumber;
+
+    use super::*;
+    use crate::archive::tests::create_batch;
+
+    fn create_bundle() -> EvidenceBundle {
+        let anchor = create_batch(1);
+        EvidenceBundle {
+            version: EVIDENCE_VERSION,
+            anchor: Some(ArchivedAnchor {
+                number: L1BatchNumber(1),
+                root_hash: anchor.l1_batch.metadata.root_hash,
+                commitment: anchor.l1_batch.metadata.commitment,
+            }),
+            batch: create_batch(2),
+            verifier_config: L1VerifierConfig {
+                params: Default::default(),
+                recursion_scheduler_level_vk_hash: H256::repeat_byte(0x11),
+            },
+            l1_txs: L1TxReferences {
+                diamond_proxy_addr: Address::repeat_byte(0x22),
+                commit_tx_hash: Some(H256::repeat_byte(0x33)),
+                prove_tx_hash: None,
+                execute_tx_hash: None,
+            },
+        }
+    }
+
+    #[test]
+    fn signing_and_opening_bundle() {
+        let private_key = H256::repeat_byte(0xef);
+        let signer = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let bundle = create_bundle();
+        let signed_bundle = bundle.sign(&private_key).unwrap();
+
+        let (opened_bundle, recovered_signer) = signed_bundle.open(Some(signer)).unwrap();
+        assert_eq!(opened_bundle, bundle);
+        assert_eq!(recovered_signer, signer);
+
+        // Check that the signature is an EIP-191 personal message signature.
+        let signature = PackedEthSignature::deserialize_packed(&signed_bundle.signature.0).unwrap();
+        let prefixed_payload = format!(
+            "\x19Ethereum Signed Message:\n{}{}",
+            signed_bundle.payload.len(),
+            signed_bundle.payload
+        );
+        let recovered_signer = signature
+            .signature_recover_signer(&H256(keccak256(prefixed_payload.as_bytes())))
+            .unwrap();
+        assert_eq!(recovered_signer, signer);
+
+        let err = signed_bundle
+            .open(Some(Address::repeat_byte(1)))
+            .unwrap_err();
+        assert!(err.to_string().contains("signed by"), "{err}");
+
+        let mut tampered_bundle = signed_bundle;
+        tampered_bundle.payload = tampered_bundle.payload.replace("0x33", "0x34");
+        let err = tampered_bundle.open(Some(signer)).unwrap_err();
+        assert!(err.to_string().contains("signed by"), "{err}");
+    }
+
+    #[test]
+    fn verifying_bundle() {
+        let mut bundle = create_bundle();
+        let commitment = bundle.batch.l1_batch.metadata.commitment;
+        let verification = verify_evidence(&bundle, Some(commitment), None);
+        assert!(verification.errors.is_empty(), "{verification:?}");
+        assert!(verification.is_anchored);
+        assert!(verification.public_input.is_some());
+
+        bundle.l1_txs.prove_tx_hash = Some(H256::repeat_byte(0x44));
+        let verification = verify_evidence(&bundle, Some(H256::zero()), None);
+        assert_eq!(verification.errors.len(), 2, "{verification:?}");
+        assert!(
+            verification.errors[1].contains("no proof"),
+            "{verification:?}"
+        );
+    }
+
+    #[test]
+    fn bundled_proof_must_be_verified() {
+        let mut bundle = create_bundle();
+        bundle.batch.proof = Some(Bytes(vec![1, 2, 3]));
+        bundle.l1_txs.prove_tx_hash = Some(H256::repeat_byte(0x44));
+        let commitment = bundle.batch.l1_batch.metadata.commitment;
+
+        let verification = verify_evidence(&bundle, Some(commitment), None);
+        assert!(!verification.is_proof_verified);
+        assert_eq!(verification.errors.len(), 1, "{verification:?}");
+        assert!(verification.errors[0].contains("proof"), "{verification:?}");
+    }
+}
@@ -0,0 +1,377 @@
+//! Tool exporting a range of L1 batches (headers, metadata, pubdata and proofs) into a portable archive,
+//! and verifying such an archive on a machine without network access. Allows auditors to verify chain segments
+//! in air-gapped environments. Also exports signed evidence bundles for single L1 batches,
+//! which can be used in dispute and audit workflows.
+
+use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use zksync_config::{ContractsConfig, ObjectStoreConfig, PostgresConfig};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_object_store::{ObjectStore, ObjectStoreError, ObjectStoreFactory, StoredObject};
+use zksync_prover_interface::outputs::L1BatchProofForL1;
+use zksync_types::{Address, L1BatchNumber, H256};
+
+use crate::{
+    archive::{
+        read_verification_key, verify_archive, ArchivedAnchor, ArchivedBatch, BatchArchive,
+        ARCHIVE_VERSION,
+    },
+    evidence::{
+        verify_evidence, EvidenceBundle, L1TxReferences, SignedEvidenceBundle, EVIDENCE_VERSION,
+    },
+};
+
+mod archive;
+mod evidence;
+
+/// Env variable with the hex-encoded private key used to sign evidence bundles.
+const EVIDENCE_SIGNING_KEY_VAR: &str = "BATCH_EVIDENCE_SIGNING_KEY";
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "L1 batch archive tool", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exports a range of L1 batches from Postgres and the object store into an archive.
+    Export {
+        /// First L1 batch to export (inclusive).
+        #[arg(long)]
+        from_batch: u32,
+        /// Last L1 batch to export (inclusive).
+        #[arg(long)]
+        to_batch: u32,
+        /// Path to the created archive file.
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Imports an archive and verifies it. Does not require network access.
+    Verify {
+        /// Path to the archive file.
+        #[arg(long)]
+        input: PathBuf,
+        /// Path to a JSON file with trusted batch commitments (e.g., taken from `BlockCommit` events on L1)
+        /// in the `{ "<batch number>": "0x<commitment>" }` format.
+        #[arg(long)]
+        trusted_commitments: Option<PathBuf>,
+        /// Path to the JSON verification key for final batch proofs (`snark_verification_scheduler_key.json`
+        /// in the prover keystore). Required to verify archived proofs.
+        #[arg(long)]
+        verification_key: Option<PathBuf>,
+    },
+    /// Exports a signed evidence bundle for a single L1 batch. The signing key is read from
+    /// the `BATCH_EVIDENCE_SIGNING_KEY` env variable.
+    ExportEvidence {
+        /// L1 batch to export.
+        #[arg(long)]
+        batch: u32,
+        /// Path to the created bundle file.
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Checks the signature of an evidence bundle and verifies the bundled L1 batch. Does not require
+    /// network access.
+    VerifyEvidence {
+        /// Path to the bundle file.
+        #[arg(long)]
+        input: PathBuf,
+        /// Expected signer of the bundle. If not specified, the bundle is accepted from any signer.
+        #[arg(long)]
+        signer: Option<Address>,
+        /// Trusted commitment of the batch (e.g., taken from the `BlockCommit` event on L1).
+        #[arg(long)]
+        trusted_commitment: Option<H256>,
+        /// Path to the JSON verification key for final batch proofs. Required to verify the bundled proof.
+        /// The key must be obtained from a trusted source; the bundle only contains the key hash.
+        #[arg(long)]
+        verification_key: Option<PathBuf>,
+    },
+}
+
+async fn load_proof(
+    blob_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let key = L1BatchProofForL1::encode_key(l1_batch_number);
+    match blob_store.get_raw(L1BatchProofForL1::BUCKET, &key).await {
+        Ok(bytes) => Ok(Some(bytes)),
+        // The proof may be not generated yet, or removed by the artifacts retention policy.
+        Err(ObjectStoreError::KeyNotFound(_)) => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("failed loading proof `{key}`")),
+    }
+}
+
+async fn export(
+    pool: &ConnectionPool,
+    blob_store: &dyn ObjectStore,
+    from_batch: L1BatchNumber,
+    to_batch: L1BatchNumber,
+) -> anyhow::Result<BatchArchive> {
+    anyhow::ensure!(
+        from_batch <= to_batch,
+        "invalid L1 batch range {from_batch}..={to_batch}"
+    );
+    let mut storage = pool.access_storage().await?;
+
+    let anchor = match from_batch.0.checked_sub(1) {
+        Some(prev_batch) => {
+            let prev_batch = storage
+                .blocks_dal()
+                .get_l1_batch_metadata(L1BatchNumber(prev_batch))
+                .await?
+                .with_context(|| format!("no metadata for L1 batch #{prev_batch}"))?;
+            Some(ArchivedAnchor {
+                number: prev_batch.header.number,
+                root_hash: prev_batch.metadata.root_hash,
+                commitment: prev_batch.metadata.commitment,
+            })
+        }
+        None => None,
+    };
+
+    let mut batches = vec![];
+    for number in from_batch.0..=to_batch.0 {
+        let number = L1BatchNumber(number);
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(number)
+            .await?
+            .with_context(|| format!("no metadata for L1 batch #{number}"))?;
+        let blob_commitments = storage
+            .blocks_dal()
+            .get_l1_batch_blob_commitments(number)
+            .await?;
+        let proof = load_proof(blob_store, number).await?;
+        if proof.is_none() {
+            tracing::warn!("Proof for L1 batch #{number} is not available; it won't be archived");
+        }
+        tracing::info!("Exported L1 batch #{number}");
+
+        batches.push(ArchivedBatch {
+            pubdata: l1_batch.construct_pubdata().into(),
+            l1_batch,
+            blob_commitments,
+            proof: proof.map(Into::into),
+        });
+    }
+    Ok(BatchArchive {
+        version: ARCHIVE_VERSION,
+        anchor,
+        batches,
+    })
+}
+
+async fn export_evidence(
+    pool: &ConnectionPool,
+    blob_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+    diamond_proxy_addr: Address,
+) -> anyhow::Result<EvidenceBundle> {
+    let archive = export(pool, blob_store, l1_batch_number, l1_batch_number).await?;
+    let batch = archive.batches.into_iter().next().unwrap();
+
+    let mut storage = pool.access_storage().await?;
+    let protocol_version = batch
+        .l1_batch
+        .header
+        .protocol_version
+        .with_context(|| format!("L1 batch #{l1_batch_number} has no protocol version"))?;
+    let verifier_config = storage
+        .protocol_versions_dal()
+        .l1_verifier_config_for_version(protocol_version)
+        .await
+        .with_context(|| format!("no verifier config for protocol version {protocol_version:?}"))?;
+    let details = storage
+        .blocks_web3_dal()
+        .get_l1_batch_details(l1_batch_number)
+        .await?
+        .with_context(|| format!("no details for L1 batch #{l1_batch_number}"))?;
+    if details.base.commit_tx_hash.is_none() {
+        tracing::warn!("L1 batch #{l1_batch_number} is not committed on L1 yet");
+    }
+
+    Ok(EvidenceBundle {
+        version: EVIDENCE_VERSION,
+        anchor: archive.anchor,
+        batch,
+        verifier_config,
+        l1_txs: L1TxReferences {
+            diamond_proxy_addr,
+            commit_tx_hash: details.base.commit_tx_hash,
+            prove_tx_hash: details.base.prove_tx_hash,
+            execute_tx_hash: details.base.execute_tx_hash,
+        },
+    })
+}
+
+fn verify_evidence_bundle(
+    input: PathBuf,
+    signer: Option<Address>,
+    trusted_commitment: Option<H256>,
+    verification_key: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let signed_bundle = SignedEvidenceBundle::read(&input)?;
+    let verification_key = verification_key
+        .as_deref()
+        .map(read_verification_key)
+        .transpose()?;
+    let (bundle, recovered_signer) = signed_bundle.open(signer)?;
+    if signer.is_none() {
+        tracing::warn!(
+            "Expected signer is not specified; the bundle is signed by {recovered_signer:?}"
+        );
+    }
+    if trusted_commitment.is_none() {
+        tracing::warn!(
+            "Bundled batch is not checked against a trusted commitment; the bundle is only checked \
+             for internal consistency"
+        );
+    }
+
+    let verification = verify_evidence(&bundle, trusted_commitment, verification_key.as_ref());
+    let report = serde_json::json!({
+        "signer": recovered_signer,
+        "verifier_config": bundle.verifier_config,
+        "l1_txs": bundle.l1_txs,
+        "verification": verification,
+    });
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    anyhow::ensure!(
+        verification.errors.is_empty(),
+        "L1 batch #{} failed verification",
+        verification.number
+    );
+    Ok(())
+}
+
+async fn create_stores() -> anyhow::Result<(ConnectionPool, Arc<dyn ObjectStore>)> {
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let object_store_config =
+        ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+    let blob_store = ObjectStoreFactory::new(object_store_config)
+        .create_store()
+        .await;
+    Ok((pool, blob_store))
+}
+
+fn verify(
+    input: PathBuf,
+    trusted_commitments: Option<PathBuf>,
+    verification_key: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let archive = BatchArchive::read(&input)?;
+    let verification_key = verification_key
+        .as_deref()
+        .map(read_verification_key)
+        .transpose()?;
+    let trusted_commitments: HashMap<L1BatchNumber, H256> = match trusted_commitments {
+        Some(path) => {
+            let json = fs::read_to_string(&path).with_context(|| {
+                format!("failed reading trusted commitments `{}`", path.display())
+            })?;
+            let commitments: HashMap<u32, H256> =
+                serde_json::from_str(&json).context("malformed trusted commitments")?;
+            commitments
+                .into_iter()
+                .map(|(number, commitment)| (L1BatchNumber(number), commitment))
+                .collect()
+        }
+        None => HashMap::new(),
+    };
+
+    let results = verify_archive(&archive, &trusted_commitments, verification_key.as_ref());
+    let failed_count = results
+        .iter()
+        .filter(|result| !result.errors.is_empty())
+        .count();
+    if !results.iter().any(|result| result.is_anchored) {
+        tracing::warn!(
+            "No archived batches are checked against trusted commitments; the archive is only checked \
+             for internal consistency"
+        );
+    }
+    println!("{}", serde_json::to_string_pretty(&results)?);
+    anyhow::ensure!(
+        failed_count == 0,
+        "{failed_count} out of {} archived L1 batches failed verification",
+        results.len()
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(vlog::LogFormat::Plain)
+        .build();
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Export {
+            from_batch,
+            to_batch,
+            output,
+        } => {
+            let (pool, blob_store) = create_stores().await?;
+            let archive = export(
+                &pool,
+                &*blob_store,
+                L1BatchNumber(from_batch),
+                L1BatchNumber(to_batch),
+            )
+            .await?;
+            archive.write(&output)?;
+            tracing::info!(
+                "Exported {} L1 batches to `{}`",
+                archive.batches.len(),
+                output.display()
+            );
+            Ok(())
+        }
+        Command::Verify {
+            input,
+            trusted_commitments,
+            verification_key,
+        } => verify(input, trusted_commitments, verification_key),
+        Command::ExportEvidence { batch, output } => {
+            let signing_key = std::env::var(EVIDENCE_SIGNING_KEY_VAR)
+                .with_context(|| format!("`{EVIDENCE_SIGNING_KEY_VAR}` env variable is not set"))?;
+            let signing_key: H256 = signing_key.parse().with_context(|| {
+                format!("`{EVIDENCE_SIGNING_KEY_VAR}` is not a valid private key")
+            })?;
+            let contracts_config =
+                ContractsConfig::from_env().context("ContractsConfig::from_env()")?;
+            let (pool, blob_store) = create_stores().await?;
+
+            let bundle = export_evidence(
+                &pool,
+                &*blob_store,
+                L1BatchNumber(batch),
+                contracts_config.diamond_proxy_addr,
+            )
+            .await?;
+            bundle.sign(&signing_key)?.write(&output)?;
+            tracing::info!(
+                "Exported evidence bundle for L1 batch #{batch} to `{}`",
+                output.display()
+            );
+            Ok(())
+        }
+        Command::VerifyEvidence {
+            input,
+            signer,
+            trusted_commitment,
+            verification_key,
+        } => verify_evidence_bundle(input, signer, trusted_commitment, verification_key),
+    }
+}
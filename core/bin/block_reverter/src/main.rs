@@ -3,13 +3,14 @@ use clap::{Parser, Subcommand};
 use tokio::io::{self, AsyncReadExt};
 use zksync_config::{
     configs::ObservabilityConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig,
-    PostgresConfig,
+    ObjectStoreConfig, PostgresConfig,
 };
 use zksync_core::block_reverter::{
     BlockReverter, BlockReverterEthConfig, BlockReverterFlags, L1ExecutedBatchesRevert,
 };
 use zksync_dal::ConnectionPool;
-use zksync_env_config::FromEnv;
+use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
+use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{L1BatchNumber, U256};
 
 #[derive(Debug, Parser)]
@@ -17,6 +18,9 @@ use zksync_types::{L1BatchNumber, U256};
 struct Cli {
     #[command(subcommand)]
     command: Command,
+    /// Identity of the operator recorded in the audit log. If not specified, the OS user running the tool is used.
+    #[arg(long, global = true)]
+    actor: Option<String>,
 }
 
 #[derive(Debug, Subcommand)]
@@ -64,6 +68,25 @@ enum Command {
         allow_executed_block_reversion: bool,
     },
 
+    /// Reverts L1 batches in all node components: Postgres, Merkle tree, state keeper cache, object store
+    /// artifacts, prover jobs and unconfirmed L1 transactions. Prints a pre-flight impact report and asks
+    /// for confirmation before reverting. An interrupted revert can be resumed by running the command again.
+    #[command(name = "revert-batches")]
+    RevertBatches {
+        /// Number of the last L1 batch to keep.
+        #[arg(long)]
+        l1_batch_number: u32,
+        /// Only prints the impact report without reverting anything.
+        #[arg(long)]
+        dry_run: bool,
+        /// Displays the impact report as a JSON object, so that it is machine-readable.
+        #[arg(long)]
+        json: bool,
+        /// Reverts without asking for confirmation.
+        #[arg(long)]
+        yes: bool,
+    },
+
     /// Clears failed L1 transactions.
     #[command(name = "clear-failed-transactions")]
     ClearFailedL1Transactions,
@@ -71,6 +94,7 @@ enum Command {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
     let observability_config =
         ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
     let log_format: vlog::LogFormat = observability_config
@@ -110,8 +134,11 @@ async fn main() -> anyhow::Result<()> {
         connection_pool,
         L1ExecutedBatchesRevert::Disallowed,
     );
+    if let Some(actor) = cli.actor {
+        block_reverter = block_reverter.with_audit_actor(actor);
+    }
 
-    match Cli::parse().command {
+    match cli.command {
         Command::Display { json } => {
             let suggested_values = block_reverter.suggested_values().await;
             if json {
@@ -184,7 +211,73 @@ async fn main() -> anyhow::Result<()> {
             }
             block_reverter
                 .rollback_db(L1BatchNumber(l1_batch_number), flags)
-                .await
+                .await?;
+        }
+        Command::RevertBatches {
+            l1_batch_number,
+            dry_run,
+            json,
+            yes,
+        } => {
+            let object_store_config =
+                ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+            let prover_object_store_config = ProverObjectStoreConfig::from_env()
+                .context("ProverObjectStoreConfig::from_env()")?;
+            let prover_connection_pool = ConnectionPool::builder(
+                postgres_config.prover_url()?,
+                postgres_config.max_connections()?,
+            )
+            .build()
+            .await
+            .context("failed to build a prover connection pool")?;
+            block_reverter = block_reverter
+                .with_object_store(
+                    ObjectStoreFactory::new(object_store_config)
+                        .create_store()
+                        .await,
+                )
+                .with_prover_object_store(
+                    ObjectStoreFactory::new(prover_object_store_config.0)
+                        .create_store()
+                        .await,
+                )
+                .with_prover_connection_pool(prover_connection_pool);
+
+            let last_l1_batch_to_keep = L1BatchNumber(l1_batch_number);
+            let flags = BlockReverterFlags::all();
+            let report = block_reverter
+                .impact_report(last_l1_batch_to_keep, flags)
+                .await;
+            if json {
+                println!("{}", serde_json::to_string(&report).unwrap());
+            } else {
+                println!("Revert impact report: {report:#?}");
+            }
+            if dry_run || report.reverted_l1_batches == 0 {
+                return Ok(());
+            }
+
+            if report.reverted_committed_l1_batches > 0 {
+                println!(
+                    "{} of reverted L1 batches are committed on L1. Make sure that they are reverted on L1 \
+                     using `send-eth-transaction` before proceeding",
+                    report.reverted_committed_l1_batches
+                );
+            }
+            if !yes {
+                println!(
+                    "Are you sure you want to revert {} L1 batches? Print y/n",
+                    report.reverted_l1_batches
+                );
+                let mut input = [0u8];
+                io::stdin().read_exact(&mut input).await.unwrap();
+                if input[0] != b'y' && input[0] != b'Y' {
+                    std::process::exit(0);
+                }
+            }
+            block_reverter
+                .rollback_db(last_l1_batch_to_keep, flags)
+                .await?;
         }
         Command::ClearFailedL1Transactions => block_reverter.clear_failed_l1_transactions().await,
     }
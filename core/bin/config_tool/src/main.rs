@@ -0,0 +1,108 @@
+//! Tool exporting the JSON schema of node configs and validating config bundles offline,
+//! so that config errors are caught before deployment rather than when the node boots.
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use zksync_core::consensus;
+use zksync_protobuf_config::schema::{self, config_schema, validate_bundle, ConfigSection};
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Node config tool", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Outputs the JSON schema of config bundles.
+    Schema {
+        /// Path to an `.env` file (e.g., `etc/env/target/dev.env`) to take field defaults from.
+        #[arg(long)]
+        env_defaults: Option<PathBuf>,
+        /// Path to the output file. If not specified, the schema is printed to stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Validates a config bundle, i.e. a JSON object mapping section names to configs
+    /// in the protobuf JSON format. Exits with an error if the bundle is invalid.
+    Validate {
+        /// Path to the config bundle.
+        bundle: PathBuf,
+    },
+}
+
+/// Returns all sections of a config bundle, including ones defined outside the config crate.
+fn config_sections() -> Vec<ConfigSection> {
+    let mut sections = schema::config_sections();
+    // The consensus config is loaded from the JSON file at `CONSENSUS_CONFIG_PATH`, not from env variables.
+    sections.push(ConfigSection::with_proto_fmt::<consensus::config::Config>(
+        "consensus",
+        None,
+    ));
+    sections
+}
+
+/// Parses `KEY=VALUE` lines from an `.env` file, skipping empty lines and comments.
+fn parse_env_file(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            Some((key.trim().to_owned(), value.to_owned()))
+        })
+        .collect()
+}
+
+fn main() -> anyhow::Result<()> {
+    let sections = config_sections();
+    match Cli::parse().command {
+        Command::Schema {
+            env_defaults,
+            output,
+        } => {
+            let env_defaults = match env_defaults {
+                Some(path) => {
+                    let contents = fs::read_to_string(&path)
+                        .with_context(|| format!("failed reading {path:?}"))?;
+                    parse_env_file(&contents)
+                }
+                None => HashMap::new(),
+            };
+            let schema = config_schema(&sections, &env_defaults);
+            let schema = serde_json::to_string_pretty(&schema)?;
+            if let Some(output) = output {
+                fs::write(&output, schema).with_context(|| format!("failed writing {output:?}"))?;
+            } else {
+                println!("{schema}");
+            }
+        }
+        Command::Validate { bundle } => {
+            let contents = fs::read_to_string(&bundle)
+                .with_context(|| format!("failed reading {bundle:?}"))?;
+            let bundle: serde_json::Value =
+                serde_json::from_str(&contents).context("config bundle is not valid JSON")?;
+            let errors = validate_bundle(&sections, &bundle);
+            for error in &errors {
+                eprintln!("{error}");
+            }
+            anyhow::ensure!(
+                errors.is_empty(),
+                "config bundle has {} error(s)",
+                errors.len()
+            );
+            println!("Config bundle is valid");
+        }
+    }
+    Ok(())
+}
@@ -1,19 +1,34 @@
-use std::{env, time::Duration};
+use std::{env, num::NonZeroU32, time::Duration};
 
 use anyhow::Context;
 use serde::Deserialize;
 use url::Url;
 use zksync_basic_types::{Address, L1ChainId, L2ChainId};
-use zksync_config::ObjectStoreConfig;
+use zksync_config::{
+    configs::{
+        api::MempoolFeedMode,
+        eth_client::{L1FinalityMode, L1FinalitySource},
+    },
+    ObjectStoreConfig,
+};
 use zksync_consensus_roles::node;
 use zksync_core::{
     api_server::{
         tx_sender::TxSenderConfig,
-        web3::{state::InternalApiConfig, Namespace},
+        web3::{
+            state::{InternalApiConfig, MempoolFeedConfig},
+            Namespace,
+        },
     },
     consensus,
+    state_keeper::TxSizeLimits,
+};
+use zksync_types::{
+    api::{BatchSealLimits, BridgeAddresses, ChainParameters},
+    commitment::L1BatchCommitmentMode,
+    fee_model::FeeParams,
+    pubdata_da::PubdataDA,
 };
-use zksync_types::{api::BridgeAddresses, fee_model::FeeParams};
 use zksync_web3_decl::{
     error::ClientRpcContext,
     jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
@@ -30,6 +45,10 @@ const BYTES_IN_MEGABYTE: usize = 1_024 * 1_024;
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct RemoteENConfig {
     pub bridgehub_proxy_addr: Option<Address>,
+    pub l1_shared_bridge_proxy_addr: Option<Address>,
+    pub state_transition_proxy_addr: Option<Address>,
+    pub validator_timelock_addr: Option<Address>,
+    pub governance_addr: Option<Address>,
     pub diamond_proxy_addr: Address,
     pub l1_erc20_bridge_proxy_addr: Address,
     pub l2_erc20_bridge_addr: Address,
@@ -39,6 +58,8 @@ pub struct RemoteENConfig {
     pub l2_chain_id: L2ChainId,
     pub l1_chain_id: L1ChainId,
     pub max_pubdata_per_batch: u64,
+    /// Chain parameters reported by the main node; `None` if the main node doesn't report them.
+    pub chain_parameters: Option<ChainParameters>,
 }
 
 impl RemoteENConfig {
@@ -53,6 +74,8 @@ impl RemoteENConfig {
             .await?;
         // In case EN is connected to the old server version without `get_bridgehub_contract` method.
         let bridgehub_proxy_addr = client.get_bridgehub_contract().await.ok().flatten();
+        // Same for `get_contract_addresses`.
+        let contract_addresses = client.get_contract_addresses().await.ok();
         let diamond_proxy_addr = client
             .get_main_contract()
             .rpc_context("get_main_contract")
@@ -67,6 +90,9 @@ impl RemoteENConfig {
             .get_fee_params()
             .rpc_context("get_fee_params")
             .await?;
+        // In case EN is connected to the old server version without `get_chain_parameters` method.
+        let chain_parameters = client.get_chain_parameters().await.ok();
+
         let max_pubdata_per_batch = match fee_params {
             FeeParams::V1(_) => {
                 const MAX_V1_PUBDATA_PER_BATCH: u64 = 100_000;
@@ -78,6 +104,18 @@ impl RemoteENConfig {
 
         Ok(Self {
             bridgehub_proxy_addr,
+            l1_shared_bridge_proxy_addr: contract_addresses
+                .as_ref()
+                .and_then(|addresses| addresses.shared_bridge),
+            state_transition_proxy_addr: contract_addresses
+                .as_ref()
+                .and_then(|addresses| addresses.state_transition_manager),
+            validator_timelock_addr: contract_addresses
+                .as_ref()
+                .and_then(|addresses| addresses.validator_timelock),
+            governance_addr: contract_addresses
+                .as_ref()
+                .and_then(|addresses| addresses.governance),
             diamond_proxy_addr,
             l2_testnet_paymaster_addr,
             l1_erc20_bridge_proxy_addr: bridges.l1_erc20_default_bridge,
@@ -87,8 +125,19 @@ impl RemoteENConfig {
             l2_chain_id,
             l1_chain_id,
             max_pubdata_per_batch,
+            chain_parameters,
         })
     }
+
+    /// Returns transaction size limits of the main node, falling back to the protocol bounds
+    /// if the main node doesn't report them.
+    pub fn tx_size_limits(&self) -> TxSizeLimits {
+        self.chain_parameters
+            .as_ref()
+            .map_or_else(TxSizeLimits::default, |params| {
+                TxSizeLimits::from_api_limits(&params.tx_limits)
+            })
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -109,6 +158,9 @@ pub struct OptionalENConfig {
     /// Max possible limit of subscriptions to be in the API state at once.
     #[serde(default = "OptionalENConfig::default_subscriptions_limit")]
     pub subscriptions_limit: usize,
+    /// If set, WebSocket subscriptions are exported to Postgres on shutdown and can be resumed
+    /// within this number of seconds on another node sharing the database.
+    pub subscription_handoff_ttl_sec: Option<u64>,
     /// Max possible limit of entities to be requested via API at once.
     #[serde(default = "OptionalENConfig::default_req_entities_limit")]
     pub req_entities_limit: usize,
@@ -118,6 +170,24 @@ pub struct OptionalENConfig {
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
     pub vm_execution_cache_misses_limit: Option<usize>,
+    /// Maximum number of successful transaction validation results cached by the API server. If not set or set to 0,
+    /// validation results are not cached.
+    pub validation_cache_capacity: Option<usize>,
+    /// Maximum number of `eth_call` results cached by the API server. Only calls pinned to a block hash are cached.
+    /// If not set or set to 0, call results are not cached.
+    pub call_cache_capacity: Option<usize>,
+    /// Time budget in milliseconds for VM executions performed for a single API request. If not set,
+    /// execution time is not limited.
+    pub vm_execution_time_budget_ms: Option<u64>,
+    /// Maximum number of concurrently processed `debug_` requests per API server. If set, `debug_` methods are processed
+    /// by a dedicated worker pool instead of sharing the VM concurrency limit with other namespaces.
+    pub debug_namespace_concurrency_limit: Option<usize>,
+    /// Maximum number of `debug_` requests per minute processed by each API server. If not set, `debug_` requests
+    /// are not rate-limited.
+    pub debug_namespace_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of transactions in a block traced by `debug_traceBlock*` methods. If not set, blocks of any size
+    /// can be traced.
+    pub debug_trace_block_tx_limit: Option<usize>,
     /// Note: Deprecated option, no longer in use. Left to display a warning in case someone used them.
     pub transactions_per_sec_limit: Option<u32>,
     /// Limit for fee history block range.
@@ -154,6 +224,9 @@ pub struct OptionalENConfig {
     /// values cache will be disabled.
     #[serde(default = "OptionalENConfig::default_latest_values_cache_size_mb")]
     latest_values_cache_size_mb: usize,
+    /// URL of a Redis instance shared by multiple nodes and used as a second-level cache for smart contract bytecodes
+    /// and initial writes. If not set, only local caches are used.
+    pub shared_cache_url: Option<String>,
     /// Enabled JSON RPC API namespaces.
     api_namespaces: Option<Vec<Namespace>>,
     /// Whether to support methods installing filters and querying filter changes.
@@ -165,6 +238,14 @@ pub struct OptionalENConfig {
     /// different node.
     #[serde(default)]
     pub filters_disabled: bool,
+    /// Whether to reject legacy transactions signed without an EIP-155 chain ID.
+    /// Such transactions are logged and counted in metrics regardless of this setting.
+    #[serde(default)]
+    pub reject_txs_without_chain_id: bool,
+    /// Whether `eth_getLogs` queries choose an index strategy based on the `events` table statistics instead of
+    /// relying on the Postgres planner.
+    #[serde(default)]
+    pub logs_query_planner_enabled: bool,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -194,6 +275,9 @@ pub struct OptionalENConfig {
     /// otherwise if the L1 prices soar, the suggested gas price won't be sufficient to be included in block
     #[serde(default = "OptionalENConfig::default_gas_price_scale_factor")]
     pub gas_price_scale_factor: f64,
+    /// Relative fee premium for L1->L2 transactions used in fee estimation. Should match the premium
+    /// configured on the main node; if not set, L1->L2 transactions are estimated at cost.
+    pub priority_tx_fee_premium: Option<f64>,
 
     // Merkle tree config
     #[serde(default = "OptionalENConfig::default_metadata_calculator_delay")]
@@ -225,6 +309,8 @@ pub struct OptionalENConfig {
     database_long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     database_slow_query_threshold_ms: Option<u64>,
+    /// Threshold in milliseconds for the time a DB connection can be held before it's reported as potentially leaked.
+    database_leak_detection_threshold_ms: Option<u64>,
 
     // Other config settings
     /// Port on which the Prometheus exporter server is listening.
@@ -237,9 +323,25 @@ pub struct OptionalENConfig {
     /// 0 means that sealing is synchronous; this is mostly useful for performance comparison, testing etc.
     #[serde(default = "OptionalENConfig::default_miniblock_seal_queue_capacity")]
     pub miniblock_seal_queue_capacity: usize,
-    /// Path to KZG trusted setup path.
-    #[serde(default = "OptionalENConfig::default_kzg_trusted_setup_path")]
-    pub kzg_trusted_setup_path: String,
+    /// Path to KZG trusted setup file. If not specified, the trusted setup embedded into the binary is used.
+    #[serde(default)]
+    pub kzg_trusted_setup_path: Option<String>,
+    /// Mode in which the main node commits L1 batches (rollup or validium). Used by the consistency checker
+    /// to verify commit data published on L1.
+    #[serde(default)]
+    pub l1_batch_commitment_mode: L1BatchCommitmentMode,
+    /// How L1 blocks are considered reorg-safe by the consistency checker: `confirmations`, `finalized` or `hybrid`.
+    /// If not specified, L1 batches are checked as soon as they are committed.
+    #[serde(default)]
+    pub l1_finality_mode: Option<L1FinalityMode>,
+    /// Number of confirmations for the `confirmations` and `hybrid` finality modes.
+    #[serde(default)]
+    pub l1_finality_confirmations: Option<u64>,
+    /// URL of the L1 beacon node API (or a blob archive exposing the same `blob_sidecars` endpoint).
+    /// If specified, the consistency checker fetches blobs published by commit transactions and checks them
+    /// against local pubdata; otherwise, only commit calldata is checked.
+    #[serde(default)]
+    pub l1_beacon_api_url: Option<String>,
 }
 
 impl OptionalENConfig {
@@ -346,8 +448,9 @@ impl OptionalENConfig {
         10
     }
 
-    fn default_kzg_trusted_setup_path() -> String {
-        "./trusted_setup.json".to_owned()
+    pub fn l1_finality_source(&self) -> Option<L1FinalitySource> {
+        let mode = self.l1_finality_mode?;
+        Some(mode.to_source(self.l1_finality_confirmations))
     }
 
     pub fn polling_interval(&self) -> Duration {
@@ -398,6 +501,15 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    pub fn leak_detection_threshold(&self) -> Option<Duration> {
+        self.database_leak_detection_threshold_ms
+            .map(Duration::from_millis)
+    }
+
+    pub fn subscription_handoff_ttl(&self) -> Option<Duration> {
+        self.subscription_handoff_ttl_sec.map(Duration::from_secs)
+    }
+
     pub fn api_namespaces(&self) -> Vec<Namespace> {
         self.api_namespaces
             .clone()
@@ -478,13 +590,14 @@ impl PostgresConfig {
     }
 }
 
-pub(crate) fn read_consensus_config() -> anyhow::Result<consensus::FetcherConfig> {
+pub(crate) async fn read_consensus_config() -> anyhow::Result<consensus::FetcherConfig> {
     let path = std::env::var("EN_CONSENSUS_CONFIG_PATH")
         .context("EN_CONSENSUS_CONFIG_PATH env variable is not set")?;
     let cfg = std::fs::read_to_string(&path).context(path)?;
     let cfg: consensus::config::Config =
         consensus::config::decode_json(&cfg).context("failed decoding JSON")?;
-    let node_key: node::SecretKey = consensus::config::read_secret("EN_CONSENSUS_NODE_KEY")?;
+    let secrets = consensus::config::SecretReader::from_env("EN_CONSENSUS_").await?;
+    let node_key: node::SecretKey = secrets.read("EN_CONSENSUS_NODE_KEY").await?;
     Ok(consensus::FetcherConfig {
         executor: cfg.executor_config(node_key),
     })
@@ -612,11 +725,46 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
                 l2_weth_bridge: config.remote.l2_weth_bridge_addr,
             },
             bridgehub_proxy_addr: config.remote.bridgehub_proxy_addr,
+            l1_shared_bridge_proxy_addr: config.remote.l1_shared_bridge_proxy_addr,
+            state_transition_proxy_addr: config.remote.state_transition_proxy_addr,
+            validator_timelock_addr: config.remote.validator_timelock_addr,
+            governance_addr: config.remote.governance_addr,
             diamond_proxy_addr: config.remote.diamond_proxy_addr,
             l2_testnet_paymaster_addr: config.remote.l2_testnet_paymaster_addr,
             req_entities_limit: config.optional.req_entities_limit,
             fee_history_limit: config.optional.fee_history_limit,
             filters_disabled: config.optional.filters_disabled,
+            reject_txs_without_chain_id: config.optional.reject_txs_without_chain_id,
+            logs_query_planner_enabled: config.optional.logs_query_planner_enabled,
+            tx_size_limits: config.remote.tx_size_limits(),
+            fee_model_version: config
+                .remote
+                .chain_parameters
+                .as_ref()
+                .map_or_else(Default::default, |params| params.fee_model_version),
+            pubdata_da: config
+                .remote
+                .chain_parameters
+                .as_ref()
+                .map_or(PubdataDA::Calldata, |params| params.pubdata_da),
+            batch_seal_limits: config.remote.chain_parameters.as_ref().map_or(
+                BatchSealLimits {
+                    max_pubdata_per_batch: config.remote.max_pubdata_per_batch.into(),
+                    ..BatchSealLimits::default()
+                },
+                |params| params.batch_seal_limits,
+            ),
+            components: if config.consensus.is_some() {
+                vec!["ExternalNode".to_owned(), "Consensus".to_owned()]
+            } else {
+                vec!["ExternalNode".to_owned()]
+            },
+            // The external node doesn't have its own mempool; pending transactions it knows of
+            // come from synced blocks, so there's nothing to hide.
+            mempool_feed: MempoolFeedConfig {
+                mode: MempoolFeedMode::Public,
+                ..MempoolFeedConfig::default()
+            },
         }
     }
 }
@@ -641,6 +789,15 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
                 .optional
                 .l1_to_l2_transactions_compatibility_mode,
             max_pubdata_per_batch: config.remote.max_pubdata_per_batch,
+            priority_tx_fee_multiplier: 1.0
+                + config.optional.priority_tx_fee_premium.unwrap_or(0.0),
+            validation_cache_capacity: config.optional.validation_cache_capacity,
+            call_cache_capacity: config.optional.call_cache_capacity,
+            vm_execution_time_budget: config
+                .optional
+                .vm_execution_time_budget_ms
+                .map(Duration::from_millis),
+            tx_size_limits: config.remote.tx_size_limits(),
         }
     }
 }
@@ -5,7 +5,11 @@ use clap::Parser;
 use futures::{future::FusedFuture, FutureExt as _};
 use metrics::EN_METRICS;
 use prometheus_exporter::PrometheusExporterConfig;
-use tokio::{sync::watch, task, time::sleep};
+use tokio::{
+    sync::{broadcast, watch},
+    task,
+    time::sleep,
+};
 use zksync_basic_types::{Address, L2ChainId};
 use zksync_concurrency::{ctx, scope};
 use zksync_config::configs::database::MerkleTreeMode;
@@ -17,9 +21,10 @@ use zksync_core::{
         web3::{ApiBuilder, Namespace},
     },
     block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert},
+    bytecode_repair::BytecodeRepair,
     commitment_generator::CommitmentGenerator,
     consensus,
-    consistency_checker::ConsistencyChecker,
+    consistency_checker::{BeaconBlobClient, BlobClient, ConsistencyChecker},
     l1_gas_price::MainNodeFeeParamsFetcher,
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
     reorg_detector::ReorgDetector,
@@ -35,11 +40,11 @@ use zksync_core::{
 };
 use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
-use zksync_l1_contract_interface::i_executor::commit::kzg::KzgSettings;
-use zksync_state::PostgresStorageCaches;
+use zksync_l1_contract_interface::i_executor::commit::kzg::KzgSettingsProvider;
+use zksync_state::{PostgresStorageCaches, RedisCache};
 use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
-use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
+use zksync_web3_decl::jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 
 use crate::{
     config::{observability::observability_config_from_env, ExternalNodeConfig},
@@ -53,6 +58,10 @@ mod init;
 mod metrics;
 
 const RELEASE_MANIFEST: &str = include_str!("../../../../.github/release-please/manifest.json");
+/// Number of miniblock write sets buffered for storage subscriptions of the WS API server.
+const MINIBLOCK_WRITE_SETS_CAPACITY: usize = 128;
+/// Time-to-live for entries in the shared VM execution cache.
+const SHARED_CACHE_TTL: Duration = Duration::from_secs(3_600);
 
 /// Creates the state keeper configured to work in the external node mode.
 #[allow(clippy::too_many_arguments)]
@@ -133,9 +142,11 @@ async fn init_tasks(
     app_health.insert_custom_component(Arc::new(sync_state.clone()));
     let (action_queue_sender, action_queue) = ActionQueue::new();
 
-    let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
+    let (miniblock_write_sets, _) = broadcast::channel(MINIBLOCK_WRITE_SETS_CAPACITY);
+    let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::with_write_sets(
         connection_pool.clone(),
         config.optional.miniblock_seal_queue_capacity,
+        miniblock_write_sets.clone(),
     );
     task_handles.push(tokio::spawn(miniblock_sealer.run()));
     let pool = connection_pool.clone();
@@ -227,12 +238,20 @@ async fn init_tasks(
     };
     let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
         .await
-        .context("failed initializing metadata calculator")?;
+        .context("failed initializing metadata calculator")?
+        .with_l1_client(
+            &config
+                .required
+                .eth_client_url()
+                .context("L1 client URL is incorrect")?,
+        )?;
     app_health.insert_component(metadata_calculator.tree_health_check());
 
-    let kzg_settings = Some(Arc::new(KzgSettings::new(
-        &config.optional.kzg_trusted_setup_path,
-    )));
+    let kzg_settings_provider =
+        KzgSettingsProvider::from_path(config.optional.kzg_trusted_setup_path.as_ref());
+    let kzg_settings = kzg_settings_provider
+        .get()
+        .context("failed loading KZG settings")?;
     let consistency_checker = ConsistencyChecker::new(
         &config
             .required
@@ -243,8 +262,14 @@ async fn init_tasks(
             .build()
             .await
             .context("failed to build connection pool for ConsistencyChecker")?,
-        kzg_settings,
-    );
+        config.optional.l1_batch_commitment_mode,
+        Some(kzg_settings),
+    )
+    .with_finality_source(config.optional.l1_finality_source())
+    .with_blob_client(config.optional.l1_beacon_api_url.as_deref().map(|url| {
+        tracing::info!("Consistency checker will check blobs using beacon node API at {url}");
+        Box::new(BeaconBlobClient::new(url)) as Box<dyn BlobClient>
+    }));
     app_health.insert_component(consistency_checker.health_check().clone());
     let consistency_checker_handle = tokio::spawn(consistency_checker.run(stop_receiver.clone()));
 
@@ -269,10 +294,8 @@ async fn init_tasks(
         .build()
         .await
         .context("failed to build a commitment_generator_pool")?;
-    let commitment_generator = CommitmentGenerator::new(
-        commitment_generator_pool,
-        &config.optional.kzg_trusted_setup_path,
-    );
+    let commitment_generator =
+        CommitmentGenerator::new(commitment_generator_pool, kzg_settings_provider);
     app_health.insert_component(commitment_generator.health_check());
     let commitment_generator_handle = tokio::spawn(commitment_generator.run(stop_receiver.clone()));
 
@@ -310,6 +333,12 @@ async fn init_tasks(
             config.optional.factory_deps_cache_size() as u64,
             config.optional.initial_writes_cache_size() as u64,
         );
+        if let Some(url) = &config.optional.shared_cache_url {
+            let key_prefix = format!("zksync:{}:", config.remote.l2_chain_id.as_u64());
+            let shared_cache = RedisCache::new(url, key_prefix, SHARED_CACHE_TTL)
+                .context("failed initializing shared VM execution cache")?;
+            storage_caches = storage_caches.with_shared_cache(Arc::new(shared_cache));
+        }
         let latest_values_cache_size = config.optional.latest_values_cache_size() as u64;
         let cache_update_handle = (latest_values_cache_size > 0).then(|| {
             task::spawn_blocking(storage_caches.configure_storage_values_cache(
@@ -341,6 +370,13 @@ async fn init_tasks(
             .with_filter_limit(config.optional.filters_limit)
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
+            .with_debug_namespace_concurrency_limit(
+                config.optional.debug_namespace_concurrency_limit,
+            )
+            .with_debug_namespace_requests_per_minute_limit(
+                config.optional.debug_namespace_requests_per_minute_limit,
+            )
+            .with_debug_trace_block_tx_limit(config.optional.debug_trace_block_tx_limit)
             .with_tx_sender(tx_sender.clone(), vm_barrier.clone())
             .with_sync_state(sync_state.clone())
             .enable_api_namespaces(config.optional.api_namespaces())
@@ -353,11 +389,23 @@ async fn init_tasks(
             .ws(config.required.ws_port)
             .with_filter_limit(config.optional.filters_limit)
             .with_subscriptions_limit(config.optional.subscriptions_limit)
+            .with_subscription_handoff(
+                connection_pool.clone(),
+                config.optional.subscription_handoff_ttl(),
+            )
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
             .with_polling_interval(config.optional.polling_interval())
+            .with_debug_namespace_concurrency_limit(
+                config.optional.debug_namespace_concurrency_limit,
+            )
+            .with_debug_namespace_requests_per_minute_limit(
+                config.optional.debug_namespace_requests_per_minute_limit,
+            )
+            .with_debug_trace_block_tx_limit(config.optional.debug_trace_block_tx_limit)
             .with_tx_sender(tx_sender, vm_barrier)
             .with_sync_state(sync_state)
+            .with_storage_write_sets(miniblock_write_sets)
             .enable_api_namespaces(config.optional.api_namespaces())
             .build(stop_receiver.clone())
             .await
@@ -429,6 +477,13 @@ struct Cli {
     /// This is an experimental and incomplete feature; do not use unless you know what you're doing.
     #[arg(long, conflicts_with = "enable_consensus")]
     enable_snapshots_recovery: bool,
+    /// Backfill bytecode preimages missing from Postgres and exit. Preimages are fetched from the main node,
+    /// additional peers specified with `--bytecode-peer-url`, and L1 commit transactions.
+    #[arg(long, conflicts_with = "revert_pending_l1_batch")]
+    repair_bytecodes: bool,
+    /// JSON-RPC URL of an additional node to fetch missing bytecode preimages from. Can be specified multiple times.
+    #[arg(long, requires = "repair_bytecodes")]
+    bytecode_peer_url: Vec<String>,
 }
 
 #[tokio::main]
@@ -469,8 +524,11 @@ async fn main() -> anyhow::Result<()> {
             !opt.enable_snapshots_recovery,
             "Consensus logic does not support snapshot recovery yet"
         );
-        config.consensus =
-            Some(config::read_consensus_config().context("read_consensus_config()")?);
+        config.consensus = Some(
+            config::read_consensus_config()
+                .await
+                .context("read_consensus_config()")?,
+        );
     }
 
     if let Some(threshold) = config.optional.slow_query_threshold() {
@@ -479,6 +537,9 @@ async fn main() -> anyhow::Result<()> {
     if let Some(threshold) = config.optional.long_connection_threshold() {
         ConnectionPool::global_config().set_long_connection_threshold(threshold)?;
     }
+    if let Some(threshold) = config.optional.leak_detection_threshold() {
+        ConnectionPool::global_config().set_leak_detection_threshold(threshold)?;
+    }
 
     let connection_pool = ConnectionPool::builder(
         &config.postgres.database_url,
@@ -511,14 +572,48 @@ async fn main() -> anyhow::Result<()> {
 
         tracing::info!("Rolling back to l1 batch number {sealed_l1_batch_number}");
         reverter
-            .rollback_db(sealed_l1_batch_number, BlockReverterFlags::all())
-            .await;
+            .rollback_db(sealed_l1_batch_number, BlockReverterFlags::NODE_STORAGE)
+            .await?;
         tracing::info!(
             "Rollback successfully completed, the node has to restart to continue working"
         );
         return Ok(());
     }
 
+    if opt.repair_bytecodes {
+        let main_node_url = config
+            .required
+            .main_node_url()
+            .context("Main node URL is incorrect")?;
+        let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
+            .context("Failed creating JSON-RPC client for main node")?;
+        let mut repair =
+            BytecodeRepair::new(connection_pool.clone()).with_peer(Box::new(main_node_client));
+        for url in &opt.bytecode_peer_url {
+            let client = HttpClientBuilder::default()
+                .build(url)
+                .with_context(|| format!("Failed creating JSON-RPC client for peer {url}"))?;
+            repair = repair.with_peer(Box::new(client));
+        }
+        let l1_client_url = config
+            .required
+            .eth_client_url()
+            .context("L1 client URL is incorrect")?;
+        let report = repair.with_l1_client(&l1_client_url)?.run().await?;
+
+        tracing::info!(
+            "Backfilled {} out of {} missing bytecode preimages",
+            report.repaired_count,
+            report.missing_count
+        );
+        anyhow::ensure!(
+            report.unresolved.is_empty(),
+            "Failed finding preimages for bytecodes {:?}",
+            report.unresolved
+        );
+        return Ok(());
+    }
+
     let sigint_receiver = setup_sigint_handler();
     tracing::warn!("The external node is in the alpha phase, and should be used with caution.");
     tracing::info!("Started the external node");
@@ -618,10 +713,11 @@ async fn main() -> anyhow::Result<()> {
             None,
             connection_pool,
             L1ExecutedBatchesRevert::Allowed,
-        );
+        )
+        .with_audit_actor("reorg_detector");
         reverter
-            .rollback_db(last_correct_batch, BlockReverterFlags::all())
-            .await;
+            .rollback_db(last_correct_batch, BlockReverterFlags::NODE_STORAGE)
+            .await?;
         tracing::info!(
             "Rollback successfully completed, the node has to restart to continue working"
         );
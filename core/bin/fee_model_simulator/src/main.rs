@@ -0,0 +1,96 @@
+//! Tool replaying historical L2 transactions through a candidate fee model configuration and reporting
+//! projected revenue, user fee changes and the impact on the number of L1 batches.
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context as _;
+use clap::Parser;
+use zksync_config::{configs::chain::StateKeeperConfig, PostgresConfig};
+use zksync_core::fee_model::simulation::{FeeModelCandidate, FeeModelSimulator, SealLimits};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_types::MiniblockNumber;
+
+/// Number of miniblocks loaded from Postgres at once.
+const MINIBLOCK_CHUNK_SIZE: u32 = 1_000;
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Fee model simulator", long_about = None)]
+struct Cli {
+    /// Path to a JSON file with the candidate fee model configuration.
+    #[arg(long)]
+    candidate: PathBuf,
+    /// Number of days of historical traffic to replay.
+    #[arg(long, default_value_t = 7)]
+    days: u64,
+    /// UNIX timestamp (in seconds) of the end of the replayed period. If not specified, the current time is used.
+    #[arg(long)]
+    until: Option<u64>,
+    /// Path to the output file. If not specified, the report is printed to stdout.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let candidate = fs::read_to_string(&cli.candidate)
+        .with_context(|| format!("failed reading {:?}", cli.candidate))?;
+    let candidate: FeeModelCandidate =
+        serde_json::from_str(&candidate).context("malformed candidate fee model")?;
+
+    let until = match cli.until {
+        Some(until) => until,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("invalid system time")?
+            .as_secs(),
+    };
+    let since = until.saturating_sub(cli.days * 86_400);
+
+    let state_keeper_config =
+        StateKeeperConfig::from_env().context("StateKeeperConfig::from_env()")?;
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let mut storage = pool.access_storage().await?;
+    let miniblocks = storage
+        .blocks_dal()
+        .get_miniblock_range_by_timestamps(since..=until)
+        .await?
+        .with_context(|| format!("no miniblocks with timestamps in {since}..={until}"))?;
+
+    let mut simulator = FeeModelSimulator::new(candidate, SealLimits::new(&state_keeper_config));
+    let mut chunk_start = *miniblocks.start();
+    while chunk_start <= *miniblocks.end() {
+        let chunk_end =
+            MiniblockNumber((chunk_start.0 + MINIBLOCK_CHUNK_SIZE - 1).min(miniblocks.end().0));
+        let records = storage
+            .transactions_dal()
+            .get_tx_fee_records(chunk_start..=chunk_end)
+            .await?;
+        for record in &records {
+            simulator.process(record);
+        }
+        eprintln!(
+            "Processed miniblocks #{chunk_start}..=#{chunk_end} out of {miniblocks:?} ({} transactions)",
+            records.len()
+        );
+        chunk_start = chunk_end + 1;
+    }
+    drop(storage);
+
+    let report = serde_json::to_string_pretty(&simulator.report())?;
+    if let Some(output) = cli.output {
+        fs::write(&output, report).with_context(|| format!("failed writing {output:?}"))?;
+    } else {
+        println!("{report}");
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Retrieval of blobs from the beacon chain.
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_types::H256;
+
+#[derive(Debug, Deserialize)]
+struct BeaconResponse<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenesisResponse {
+    #[serde(deserialize_with = "deserialize_u64_string")]
+    genesis_time: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct SpecResponse {
+    #[serde(deserialize_with = "deserialize_u64_string")]
+    seconds_per_slot: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlobSidecar {
+    blob: String,
+    kzg_commitment: String,
+}
+
+fn deserialize_u64_string<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+fn decode_hex(value: &str) -> anyhow::Result<Vec<u8>> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    hex::decode(value).context("invalid hex string")
+}
+
+/// Client of the beacon node API retrieving blob sidecars. Blobs are only stored by beacon nodes
+/// for a limited time (~18 days) unless the node is configured to store all blobs, so an archival
+/// beacon node is required to reconstruct the state from genesis.
+#[derive(Debug)]
+pub(crate) struct BlobClient {
+    client: reqwest::Client,
+    url: String,
+    genesis_time: u64,
+    seconds_per_slot: u64,
+}
+
+impl BlobClient {
+    pub async fn new(url: String) -> anyhow::Result<Self> {
+        let client = reqwest::Client::new();
+        let url = url.trim_end_matches('/').to_owned();
+        let genesis: BeaconResponse<GenesisResponse> =
+            Self::get(&client, &format!("{url}/eth/v1/beacon/genesis"))
+                .await
+                .context("failed getting beacon chain genesis")?;
+        let spec: BeaconResponse<SpecResponse> =
+            Self::get(&client, &format!("{url}/eth/v1/config/spec"))
+                .await
+                .context("failed getting beacon chain spec")?;
+        anyhow::ensure!(
+            spec.data.seconds_per_slot > 0,
+            "invalid beacon chain spec: zero slot duration"
+        );
+
+        Ok(Self {
+            client,
+            url,
+            genesis_time: genesis.data.genesis_time,
+            seconds_per_slot: spec.data.seconds_per_slot,
+        })
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        client: &reqwest::Client,
+        url: &str,
+    ) -> anyhow::Result<T> {
+        let response = client.get(url).send().await?.error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Returns the blob with the specified KZG commitment included into the beacon block
+    /// for the L1 block with the specified timestamp.
+    pub async fn blob(
+        &self,
+        l1_block_timestamp: u64,
+        kzg_commitment: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        let slot = l1_block_timestamp
+            .checked_sub(self.genesis_time)
+            .context("L1 block predates the beacon chain genesis")?
+            / self.seconds_per_slot;
+        let url = format!("{}/eth/v1/beacon/blob_sidecars/{slot}", self.url);
+        let sidecars: BeaconResponse<Vec<BlobSidecar>> = Self::get(&self.client, &url)
+            .await
+            .with_context(|| format!("failed getting blob sidecars for slot {slot}"))?;
+
+        for sidecar in sidecars.data {
+            if decode_hex(&sidecar.kzg_commitment)? == kzg_commitment {
+                return decode_hex(&sidecar.blob);
+            }
+        }
+        let versioned_hash = H256(
+            zksync_l1_contract_interface::i_executor::commit::kzg::kzg_commitment_to_versioned_hash(
+                kzg_commitment,
+            ),
+        );
+        anyhow::bail!("blob with versioned hash {versioned_hash:?} is not found in slot {slot}")
+    }
+}
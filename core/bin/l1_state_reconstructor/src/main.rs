@@ -0,0 +1,183 @@
+//! Tool reconstructing the state of a zkSync chain from data published on L1.
+//!
+//! The tool scans `BlockCommit` events emitted by the main zkSync contract, decodes the corresponding
+//! `commitBatches` transactions and applies the published state diffs (from calldata or from blobs) to
+//! a Merkle tree. After each L1 batch, the root hash and the number of leaves in the tree are checked against
+//! the values committed on L1, so the reconstructed state is verified by construction.
+
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Context as _;
+use clap::Parser;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::{clients::QueryClient, EthInterface};
+use zksync_l1_contract_interface::i_executor::commit::kzg::KzgSettingsProvider;
+use zksync_merkle_tree::TreeEntry;
+use zksync_types::{Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256, U256};
+
+use crate::{
+    blobs::BlobClient,
+    reconstructor::{parse_protocol_version, ProtocolVersions, Reconstructor},
+    state::StateDb,
+};
+
+mod blobs;
+mod reconstructor;
+mod state;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Reconstructs the state of a zkSync chain from data published on L1",
+    long_about = None
+)]
+struct Cli {
+    /// URL of the L1 JSON-RPC API.
+    #[arg(long)]
+    l1_url: String,
+    /// URL of the beacon node API used to retrieve blobs. Required if pubdata is published in blobs.
+    /// Blobs are pruned by beacon nodes after ~18 days, so an archival node may be required.
+    #[arg(long)]
+    beacon_url: Option<String>,
+    /// Address of the main zkSync contract (diamond proxy).
+    #[arg(long)]
+    diamond_proxy_addr: Address,
+    /// Path to the directory with the reconstructed state. Reconstruction resumes from the last applied
+    /// L1 batch if the directory already contains state.
+    #[arg(long)]
+    db_path: PathBuf,
+    /// URL of a Postgres database of a node with the genesis of the reconstructed chain. Genesis state
+    /// is not published on L1, so it's required to initialize the state (but not to resume reconstruction).
+    /// The genesis state root is verified against L1.
+    #[arg(long)]
+    genesis_database_url: Option<String>,
+    /// Path to the KZG trusted setup used to verify blobs. If not specified, the embedded setup is used.
+    #[arg(long)]
+    kzg_trusted_setup_path: Option<PathBuf>,
+    /// Protocol versions of L1 batches as `first_l1_batch:version` pairs (e.g., `--protocol-version 1:20
+    /// --protocol-version 500:21`). Protocol versions are necessary to decode pubdata; L1 batches
+    /// not covered by the specified pairs are assumed to have the latest protocol version.
+    #[arg(long = "protocol-version", value_parser = parse_protocol_version)]
+    protocol_versions: Vec<(L1BatchNumber, ProtocolVersionId)>,
+    /// First L1 block to scan, e.g. the block in which the main zkSync contract was deployed.
+    #[arg(long, default_value_t = 0)]
+    from_block: u64,
+    /// Last L1 block to scan (inclusive). If not specified, the latest L1 block is used.
+    #[arg(long)]
+    to_block: Option<u64>,
+    /// Number of L1 blocks to request events for at once.
+    #[arg(long, default_value_t = 10_000)]
+    blocks_per_query: u64,
+    /// Keep following L1 after reaching the latest block.
+    #[arg(long)]
+    follow: bool,
+    /// Interval between polls in the follow mode.
+    #[arg(long, default_value_t = 60)]
+    poll_interval_sec: u64,
+}
+
+impl Cli {
+    async fn load_genesis(database_url: &str) -> anyhow::Result<Vec<TreeEntry>> {
+        let pool = ConnectionPool::singleton(database_url)
+            .build()
+            .await
+            .context("failed connecting to Postgres")?;
+        let mut storage = pool.access_storage().await?;
+        let entries = storage
+            .storage_logs_dal()
+            .get_tree_entries_for_miniblock(
+                MiniblockNumber(0),
+                H256::zero()..=H256::repeat_byte(0xff),
+            )
+            .await
+            .context("failed loading genesis storage logs")?;
+        anyhow::ensure!(!entries.is_empty(), "genesis storage logs are missing");
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| {
+                // Matches `StorageKey::hashed_key_u256()` used by the node tree.
+                let key = U256::from_little_endian(entry.key.as_bytes());
+                TreeEntry::new(key, entry.leaf_index, entry.value)
+            })
+            .collect())
+    }
+
+    async fn run(self) -> anyhow::Result<()> {
+        let mut state = StateDb::open(&self.db_path)?;
+        if state.next_l1_batch() == L1BatchNumber(0) {
+            let database_url = self
+                .genesis_database_url
+                .as_deref()
+                .context("state is not initialized; genesis database URL must be specified")?;
+            let entries = Self::load_genesis(database_url).await?;
+            let root_hash = state.init_genesis(entries)?;
+            tracing::info!("Initialized state with genesis; root hash: {root_hash:?}");
+        }
+        tracing::info!(
+            "Reconstructing state starting from L1 batch #{}",
+            state.next_l1_batch()
+        );
+
+        let l1_client = QueryClient::new(&self.l1_url).context("failed creating L1 client")?;
+        let blob_client = match self.beacon_url {
+            Some(url) => Some(BlobClient::new(url).await?),
+            None => None,
+        };
+        let kzg_settings = KzgSettingsProvider::from_path(self.kzg_trusted_setup_path.as_ref());
+        let mut reconstructor = Reconstructor::new(
+            Box::new(l1_client.clone()),
+            self.diamond_proxy_addr,
+            blob_client,
+            kzg_settings,
+            ProtocolVersions::new(self.protocol_versions),
+            state,
+        );
+
+        let blocks_per_query = self.blocks_per_query.max(1);
+        let mut from_block = self.from_block;
+        loop {
+            let to_block = match self.to_block {
+                Some(to_block) => to_block,
+                None => l1_client
+                    .block_number("l1_state_reconstructor")
+                    .await?
+                    .as_u64(),
+            };
+            while from_block <= to_block {
+                let chunk_end = (from_block + blocks_per_query - 1).min(to_block);
+                reconstructor
+                    .process_l1_blocks(from_block, chunk_end)
+                    .await
+                    .with_context(|| {
+                        format!("failed processing L1 blocks #{from_block}..=#{chunk_end}")
+                    })?;
+                tracing::info!(
+                    "Processed L1 blocks up to #{chunk_end}; next L1 batch to apply: #{}",
+                    reconstructor.state().next_l1_batch()
+                );
+                from_block = chunk_end + 1;
+            }
+
+            if !self.follow || self.to_block.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(self.poll_interval_sec)).await;
+        }
+
+        let state = reconstructor.state();
+        tracing::info!(
+            "Reconstructed state up to L1 batch #{}; root hash: {:?}",
+            state.next_l1_batch() - 1,
+            state.root_hash()
+        );
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _guard = vlog::ObservabilityBuilder::new().build();
+    Cli::parse().run().await
+}
@@ -0,0 +1,314 @@
+//! Scanning of L1 and applying state diffs from committed L1 batches.
+
+use std::collections::HashSet;
+
+use anyhow::Context as _;
+use zksync_contracts::zksync_contract;
+use zksync_eth_client::EthInterface;
+use zksync_l1_contract_interface::i_executor::{
+    calldata::ExecutorCalldata,
+    commit::{
+        kzg::{decode_blob, KzgSettingsProvider},
+        pubdata::{decode_blobs_pubdata, decode_commit_pubdata, DecodedPubdata},
+    },
+    structures::{CommitBatchInfo, PubdataPayload},
+};
+use zksync_types::{
+    ethabi::{self, ParamType},
+    web3::types::{BlockId, BlockNumber, FilterBuilder, Log},
+    Address, L1BatchNumber, ProtocolVersionId, H256, U256,
+};
+
+use crate::{
+    blobs::BlobClient,
+    state::{ExpectedState, StateDb},
+};
+
+const COMPONENT: &str = "l1_state_reconstructor";
+
+/// Protocol versions of L1 batches, specified as a list of `(first_l1_batch, version)` pairs.
+/// Protocol versions are not published in `commitBatches` calldata, but they are required to decode the calldata.
+#[derive(Debug, Clone)]
+pub(crate) struct ProtocolVersions(Vec<(L1BatchNumber, ProtocolVersionId)>);
+
+impl ProtocolVersions {
+    pub fn new(mut versions: Vec<(L1BatchNumber, ProtocolVersionId)>) -> Self {
+        versions.sort_unstable_by_key(|(number, _)| *number);
+        Self(versions)
+    }
+
+    fn get(&self, l1_batch: L1BatchNumber) -> ProtocolVersionId {
+        self.0
+            .iter()
+            .rev()
+            .find(|(first_l1_batch, _)| *first_l1_batch <= l1_batch)
+            .map_or_else(ProtocolVersionId::latest, |(_, version)| *version)
+    }
+}
+
+#[derive(Debug)]
+struct EventSignatures {
+    commit: H256,
+    revert: H256,
+}
+
+impl EventSignatures {
+    fn new() -> Self {
+        let contract = zksync_contract();
+        let signature = |name: &str| {
+            contract
+                .event(name)
+                .unwrap_or_else(|_| panic!("{name} event is missing in abi"))
+                .signature()
+        };
+        Self {
+            commit: signature("BlockCommit"),
+            revert: signature("BlocksRevert"),
+        }
+    }
+}
+
+/// Reconstructs the chain state by applying state diffs published in `commitBatches` transactions.
+#[derive(Debug)]
+pub(crate) struct Reconstructor {
+    l1_client: Box<dyn EthInterface>,
+    diamond_proxy: Address,
+    blob_client: Option<BlobClient>,
+    kzg_settings: KzgSettingsProvider,
+    protocol_versions: ProtocolVersions,
+    signatures: EventSignatures,
+    state: StateDb,
+}
+
+impl Reconstructor {
+    pub fn new(
+        l1_client: Box<dyn EthInterface>,
+        diamond_proxy: Address,
+        blob_client: Option<BlobClient>,
+        kzg_settings: KzgSettingsProvider,
+        protocol_versions: ProtocolVersions,
+        state: StateDb,
+    ) -> Self {
+        Self {
+            l1_client,
+            diamond_proxy,
+            blob_client,
+            kzg_settings,
+            protocol_versions,
+            signatures: EventSignatures::new(),
+            state,
+        }
+    }
+
+    pub fn state(&self) -> &StateDb {
+        &self.state
+    }
+
+    /// Processes diamond proxy events in the inclusive L1 block range.
+    pub async fn process_l1_blocks(
+        &mut self,
+        from_block: u64,
+        to_block: u64,
+    ) -> anyhow::Result<()> {
+        let filter = FilterBuilder::default()
+            .address(vec![self.diamond_proxy])
+            .from_block(BlockNumber::Number(from_block.into()))
+            .to_block(BlockNumber::Number(to_block.into()))
+            .topics(
+                Some(vec![self.signatures.commit, self.signatures.revert]),
+                None,
+                None,
+                None,
+            )
+            .build();
+        let mut logs = self.l1_client.logs(filter, COMPONENT).await?;
+        logs.sort_unstable_by_key(|log| (log.block_number, log.log_index));
+
+        // A single transaction may commit several L1 batches, each emitting a separate event.
+        let mut processed_commit_txs = HashSet::new();
+        for log in logs {
+            let signature = log.topics.first().copied();
+            if signature == Some(self.signatures.revert) {
+                self.process_revert(&log)?;
+                processed_commit_txs.clear();
+            } else if signature == Some(self.signatures.commit) {
+                let tx_hash = log.transaction_hash.context("event without tx hash")?;
+                if processed_commit_txs.insert(tx_hash) {
+                    self.process_commit_tx(&log, tx_hash).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn process_revert(&mut self, log: &Log) -> anyhow::Result<()> {
+        let tokens = ethabi::decode(&[ParamType::Uint(256); 3], &log.data.0)
+            .context("failed decoding `BlocksRevert` event")?;
+        let total_committed = tokens[0].clone().into_uint().context("not a uint")?;
+        anyhow::ensure!(
+            total_committed <= U256::from(u32::MAX),
+            "invalid L1 batch number {total_committed}"
+        );
+        let total_committed = total_committed.as_u32();
+        let last_applied = self.state.next_l1_batch().0.saturating_sub(1);
+        if total_committed < last_applied {
+            tracing::info!(
+                "L1 batches after #{total_committed} were reverted on L1; reverting state"
+            );
+            self.state.revert_to(L1BatchNumber(total_committed))?;
+        }
+        Ok(())
+    }
+
+    async fn process_commit_tx(&mut self, log: &Log, tx_hash: H256) -> anyhow::Result<()> {
+        let tx = self
+            .l1_client
+            .get_tx(tx_hash, COMPONENT)
+            .await?
+            .with_context(|| format!("commit transaction {tx_hash:?} is missing"))?;
+        let ExecutorCalldata::Commit(calldata) = ExecutorCalldata::decode(&tx.input.0)
+            .with_context(|| format!("failed decoding calldata of transaction {tx_hash:?}"))?
+        else {
+            anyhow::bail!(
+                "transaction {tx_hash:?} emitting `BlockCommit` is not a commit transaction"
+            );
+        };
+
+        let block_number = log.block_number.context("event without block number")?;
+        let block = self
+            .l1_client
+            .block(
+                BlockId::Number(BlockNumber::Number(block_number)),
+                COMPONENT,
+            )
+            .await?
+            .with_context(|| format!("L1 block #{block_number} is missing"))?;
+        let l1_block_timestamp = block.timestamp.as_u64();
+
+        let mut prev_batch = calldata.last_committed_batch;
+        for batch in &calldata.new_batches {
+            let next_l1_batch = self.state.next_l1_batch();
+            if batch.batch_number < next_l1_batch {
+                tracing::debug!("Skipping already applied L1 batch #{}", batch.batch_number);
+            } else if batch.batch_number == next_l1_batch {
+                // The previous batch is included into the calldata for batch #1 as well,
+                // which allows verifying the genesis state.
+                if prev_batch.batch_number + 1 == next_l1_batch {
+                    anyhow::ensure!(
+                        prev_batch.batch_hash == self.state.root_hash(),
+                        "state root for L1 batch #{} doesn't match the one committed on L1: expected {:?}, got {:?}",
+                        prev_batch.batch_number,
+                        prev_batch.batch_hash,
+                        self.state.root_hash()
+                    );
+                }
+                self.apply_batch(batch, l1_block_timestamp).await?;
+            } else {
+                anyhow::bail!(
+                    "L1 batch #{} is committed in transaction {tx_hash:?}, but the next L1 batch to apply is #{next_l1_batch}; \
+                     is the start L1 block correct?",
+                    batch.batch_number
+                );
+            }
+            prev_batch.batch_number = batch.batch_number;
+            prev_batch.batch_hash = batch.new_state_root;
+        }
+        Ok(())
+    }
+
+    async fn apply_batch(
+        &mut self,
+        batch: &CommitBatchInfo,
+        l1_block_timestamp: u64,
+    ) -> anyhow::Result<()> {
+        let number = batch.batch_number;
+        let pubdata = self
+            .decode_pubdata(batch, l1_block_timestamp)
+            .await
+            .with_context(|| format!("failed decoding pubdata for L1 batch #{number}"))?;
+        let expected = ExpectedState {
+            root_hash: batch.new_state_root,
+            // `indexRepeatedStorageChanges` is the next leaf index to be assigned.
+            leaf_count: batch.index_repeated_storage_changes.saturating_sub(1),
+        };
+        self.state
+            .apply_l1_batch(number, &pubdata.state_diffs, expected)?;
+        tracing::info!(
+            "Applied L1 batch #{number} with {} state diffs; state root: {:?}",
+            pubdata.state_diffs.len(),
+            expected.root_hash
+        );
+        Ok(())
+    }
+
+    async fn decode_pubdata(
+        &self,
+        batch: &CommitBatchInfo,
+        l1_block_timestamp: u64,
+    ) -> anyhow::Result<DecodedPubdata> {
+        let protocol_version = self.protocol_versions.get(batch.batch_number);
+        if protocol_version.is_pre_1_4_2() {
+            return Ok(decode_commit_pubdata(batch, protocol_version)?);
+        }
+        let PubdataPayload::Blobs(pubdata_commitments) = batch.pubdata_payload()? else {
+            return Ok(decode_commit_pubdata(batch, protocol_version)?);
+        };
+
+        let blob_client = self
+            .blob_client
+            .as_ref()
+            .context("pubdata is published in blobs, but beacon node URL is not specified")?;
+        let kzg_settings = self.kzg_settings.get()?;
+        let mut pubdata = vec![];
+        for pubdata_commitment in pubdata_commitments {
+            // Pubdata commitment format: opening point (16 bytes) || claimed value (32 bytes)
+            // || KZG commitment (48 bytes) || opening proof (48 bytes)
+            let kzg_commitment: &[u8; 48] = pubdata_commitment[48..96].try_into().unwrap();
+            let blob = blob_client.blob(l1_block_timestamp, kzg_commitment).await?;
+            pubdata.extend(decode_blob(&kzg_settings, &blob, kzg_commitment)?);
+        }
+        Ok(decode_blobs_pubdata(&pubdata)?)
+    }
+}
+
+pub(crate) fn parse_protocol_version(
+    value: &str,
+) -> Result<(L1BatchNumber, ProtocolVersionId), String> {
+    let (l1_batch, version) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected `first_l1_batch:version`, got `{value}`"))?;
+    let l1_batch = l1_batch
+        .parse()
+        .map_err(|err| format!("invalid L1 batch number `{l1_batch}`: {err}"))?;
+    let version: u16 = version
+        .parse()
+        .map_err(|err| format!("invalid protocol version `{version}`: {err}"))?;
+    let version = ProtocolVersionId::try_from(U256::from(version))?;
+    Ok((L1BatchNumber(l1_batch), version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn getting_protocol_versions() {
+        let versions = ProtocolVersions::new(vec![
+            parse_protocol_version("100:21").unwrap(),
+            parse_protocol_version("1:20").unwrap(),
+        ]);
+        assert_eq!(versions.get(L1BatchNumber(0)), ProtocolVersionId::latest());
+        assert_eq!(versions.get(L1BatchNumber(1)), ProtocolVersionId::Version20);
+        assert_eq!(
+            versions.get(L1BatchNumber(99)),
+            ProtocolVersionId::Version20
+        );
+        assert_eq!(
+            versions.get(L1BatchNumber(100)),
+            ProtocolVersionId::Version21
+        );
+
+        parse_protocol_version("100").unwrap_err();
+        parse_protocol_version("100:9999").unwrap_err();
+    }
+}
@@ -0,0 +1,326 @@
+//! Reconstructed state database.
+
+use std::{
+    fs,
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use zksync_l1_contract_interface::i_executor::commit::pubdata::{StateDiff, StateDiffKey};
+use zksync_merkle_tree::{Key, MerkleTree, RocksDBWrapper, TreeEntry};
+use zksync_types::{L1BatchNumber, H256, U256};
+use zksync_utils::{h256_to_u256, u256_to_h256};
+
+/// Size of a serialized tree key in the leaf keys file.
+const KEY_SIZE: u64 = 32;
+
+/// State of the chain after a certain L1 batch, as committed on L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ExpectedState {
+    pub root_hash: H256,
+    pub leaf_count: u64,
+}
+
+/// State database reconstructed from L1. Consists of the Merkle tree of storage slots (in the same format
+/// as the Merkle tree maintained by the node, so that it can be used to verify state roots and to produce proofs)
+/// and an append-only file mapping leaf indices to tree keys. The latter is necessary to apply repeated writes,
+/// which reference storage slots by their enumeration (= leaf) index.
+///
+/// Tree versions correspond to L1 batch numbers, just like in the node Merkle tree.
+#[derive(Debug)]
+pub(crate) struct StateDb {
+    tree: MerkleTree<RocksDBWrapper>,
+    leaf_keys: fs::File,
+    leaf_keys_path: PathBuf,
+}
+
+impl StateDb {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        fs::create_dir_all(path)
+            .with_context(|| format!("failed creating state directory {path:?}"))?;
+        let tree_path = path.join("tree");
+        let db = RocksDBWrapper::new(&tree_path)
+            .with_context(|| format!("failed opening Merkle tree at {tree_path:?}"))?;
+        let leaf_keys_path = path.join("leaf_keys.bin");
+        let leaf_keys = fs::OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&leaf_keys_path)
+            .with_context(|| format!("failed opening leaf keys file {leaf_keys_path:?}"))?;
+
+        let this = Self {
+            tree: MerkleTree::new(db),
+            leaf_keys,
+            leaf_keys_path,
+        };
+        // Leaf keys are persisted before extending the tree, so the file may contain extra keys
+        // if the process was interrupted while applying an L1 batch.
+        this.truncate_leaf_keys(this.leaf_count())?;
+        Ok(this)
+    }
+
+    /// Returns the number of the next L1 batch to be applied to the state.
+    pub fn next_l1_batch(&self) -> L1BatchNumber {
+        let number = self.tree.latest_version().map_or(0, |version| version + 1);
+        L1BatchNumber(u32::try_from(number).expect("L1 batch number overflow"))
+    }
+
+    pub fn root_hash(&self) -> H256 {
+        self.tree.latest_root_hash()
+    }
+
+    fn leaf_count(&self) -> u64 {
+        self.tree
+            .latest_version()
+            .and_then(|version| self.tree.leaf_count(version))
+            .unwrap_or(0)
+    }
+
+    fn truncate_leaf_keys(&self, leaf_count: u64) -> anyhow::Result<()> {
+        let path = &self.leaf_keys_path;
+        let file_len = self
+            .leaf_keys
+            .metadata()
+            .with_context(|| format!("failed getting metadata for {path:?}"))?
+            .len();
+        anyhow::ensure!(
+            file_len >= leaf_count * KEY_SIZE,
+            "leaf keys file {path:?} is inconsistent with the Merkle tree: it has {file_len} bytes, \
+             while the tree has {leaf_count} leaves"
+        );
+        self.leaf_keys
+            .set_len(leaf_count * KEY_SIZE)
+            .with_context(|| format!("failed truncating {path:?}"))
+    }
+
+    fn append_leaf_keys(&mut self, keys: &[Key]) -> anyhow::Result<()> {
+        let mut bytes = Vec::with_capacity(keys.len() * KEY_SIZE as usize);
+        for key in keys {
+            bytes.extend_from_slice(u256_to_h256(*key).as_bytes());
+        }
+        let path = &self.leaf_keys_path;
+        std::io::Write::write_all(&mut self.leaf_keys, &bytes)
+            .with_context(|| format!("failed writing leaf keys to {path:?}"))?;
+        self.leaf_keys
+            .sync_data()
+            .with_context(|| format!("failed syncing {path:?}"))
+    }
+
+    fn leaf_key(&self, leaf_index: u64) -> anyhow::Result<Key> {
+        anyhow::ensure!(leaf_index > 0, "leaf indices are 1-based");
+        let mut bytes = [0_u8; KEY_SIZE as usize];
+        self.leaf_keys
+            .read_exact_at(&mut bytes, (leaf_index - 1) * KEY_SIZE)
+            .with_context(|| format!("failed reading key for leaf #{leaf_index}"))?;
+        Ok(h256_to_u256(H256(bytes)))
+    }
+
+    /// Initializes the state with the genesis L1 batch. Genesis storage logs are not published on L1,
+    /// so they must be obtained separately.
+    pub fn init_genesis(&mut self, mut entries: Vec<TreeEntry>) -> anyhow::Result<H256> {
+        anyhow::ensure!(
+            self.next_l1_batch() == L1BatchNumber(0),
+            "state is already initialized"
+        );
+        entries.sort_unstable_by_key(|entry| entry.leaf_index);
+        for (i, entry) in entries.iter().enumerate() {
+            anyhow::ensure!(
+                entry.leaf_index == i as u64 + 1,
+                "genesis leaf indices are not sequential: expected #{}, got #{}",
+                i + 1,
+                entry.leaf_index
+            );
+        }
+
+        let keys: Vec<_> = entries.iter().map(|entry| entry.key).collect();
+        self.append_leaf_keys(&keys)?;
+        Ok(self.tree.extend(entries).root_hash)
+    }
+
+    /// Applies state diffs of an L1 batch and checks the resulting state against the one committed on L1.
+    /// If the check fails, the state is not changed.
+    pub fn apply_l1_batch(
+        &mut self,
+        number: L1BatchNumber,
+        state_diffs: &[StateDiff],
+        expected: ExpectedState,
+    ) -> anyhow::Result<()> {
+        let next_l1_batch = self.next_l1_batch();
+        anyhow::ensure!(
+            number == next_l1_batch,
+            "cannot apply L1 batch #{number}; the next L1 batch to apply is #{next_l1_batch}"
+        );
+        let prev_version = u64::from(number.0)
+            .checked_sub(1)
+            .context("genesis L1 batch cannot be applied from L1 data")?;
+        let prev_leaf_count = self.leaf_count();
+
+        // Initial writes are assigned leaf indices in the order they are published.
+        let mut new_keys = vec![];
+        let mut keys = Vec::with_capacity(state_diffs.len());
+        let mut leaf_indices = Vec::with_capacity(state_diffs.len());
+        for diff in state_diffs {
+            let (key, leaf_index) = match diff.key {
+                StateDiffKey::Initial(derived_key) => {
+                    // Matches `StorageKey::hashed_key_u256()` used by the node tree.
+                    let key = U256::from_little_endian(derived_key.as_bytes());
+                    new_keys.push(key);
+                    (key, prev_leaf_count + new_keys.len() as u64)
+                }
+                StateDiffKey::Repeated(leaf_index) => {
+                    anyhow::ensure!(
+                        leaf_index <= prev_leaf_count,
+                        "repeated write references leaf #{leaf_index}, while the tree only has \
+                         {prev_leaf_count} leaves"
+                    );
+                    (self.leaf_key(leaf_index)?, leaf_index)
+                }
+            };
+            keys.push(key);
+            leaf_indices.push(leaf_index);
+        }
+
+        let prev_entries = self
+            .tree
+            .entries(prev_version, &keys)
+            .context("failed reading previous values of updated slots")?;
+        let mut entries = Vec::with_capacity(state_diffs.len());
+        for (((diff, key), leaf_index), prev_entry) in state_diffs
+            .iter()
+            .zip(keys)
+            .zip(leaf_indices)
+            .zip(prev_entries)
+        {
+            match diff.key {
+                StateDiffKey::Initial(derived_key) => anyhow::ensure!(
+                    prev_entry.is_empty(),
+                    "initial write to slot {derived_key:?}, which is already present in the tree"
+                ),
+                StateDiffKey::Repeated(_) => anyhow::ensure!(
+                    prev_entry.leaf_index == leaf_index,
+                    "leaf keys file is inconsistent with the Merkle tree for leaf #{leaf_index}"
+                ),
+            }
+            let value = diff.update.apply(h256_to_u256(prev_entry.value));
+            entries.push(TreeEntry::new(key, leaf_index, u256_to_h256(value)));
+        }
+
+        self.append_leaf_keys(&new_keys)?;
+        let output = self.tree.extend(entries);
+        let actual = ExpectedState {
+            root_hash: output.root_hash,
+            leaf_count: output.leaf_count,
+        };
+        if actual != expected {
+            self.revert_to(L1BatchNumber(number.0 - 1))?;
+            anyhow::bail!(
+                "state after applying L1 batch #{number} doesn't match the state committed on L1: \
+                 expected {expected:?}, got {actual:?}"
+            );
+        }
+        Ok(())
+    }
+
+    /// Reverts the state to the state after the specified L1 batch.
+    pub fn revert_to(&mut self, last_l1_batch_to_keep: L1BatchNumber) -> anyhow::Result<()> {
+        let version = u64::from(last_l1_batch_to_keep.0);
+        let leaf_count = self
+            .tree
+            .leaf_count(version)
+            .with_context(|| format!("state for L1 batch #{last_l1_batch_to_keep} is missing"))?;
+        self.tree.truncate_recent_versions(version + 1);
+        self.truncate_leaf_keys(leaf_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_l1_contract_interface::i_executor::commit::pubdata::ValueUpdate;
+    use zksync_merkle_tree::PatchSet;
+
+    use super::*;
+
+    fn genesis_entries() -> Vec<TreeEntry> {
+        (1..=10)
+            .map(|i| TreeEntry::new(U256::from(i) << 128, i, H256::from_low_u64_be(i)))
+            .collect()
+    }
+
+    /// Computes the expected state using an in-memory tree.
+    fn expected_state(entries: Vec<Vec<TreeEntry>>) -> ExpectedState {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        let mut output = None;
+        for batch_entries in entries {
+            output = Some(tree.extend(batch_entries));
+        }
+        let output = output.unwrap();
+        ExpectedState {
+            root_hash: output.root_hash,
+            leaf_count: output.leaf_count,
+        }
+    }
+
+    #[test]
+    fn applying_state_diffs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut state = StateDb::open(dir.path()).unwrap();
+        state.init_genesis(genesis_entries()).unwrap();
+        assert_eq!(state.next_l1_batch(), L1BatchNumber(1));
+
+        let derived_key = H256::repeat_byte(0x23);
+        let state_diffs = [
+            StateDiff {
+                key: StateDiffKey::Initial(derived_key),
+                update: ValueUpdate::Transform(100.into()),
+            },
+            StateDiff {
+                key: StateDiffKey::Repeated(3),
+                update: ValueUpdate::Add(5.into()),
+            },
+        ];
+        let new_key = U256::from_little_endian(derived_key.as_bytes());
+        let first_batch_entries = vec![
+            TreeEntry::new(new_key, 11, H256::from_low_u64_be(100)),
+            TreeEntry::new(U256::from(3) << 128, 3, H256::from_low_u64_be(8)),
+        ];
+        let expected = expected_state(vec![genesis_entries(), first_batch_entries.clone()]);
+
+        let mut wrong_expected = expected;
+        wrong_expected.leaf_count += 1;
+        let err = state
+            .apply_l1_batch(L1BatchNumber(1), &state_diffs, wrong_expected)
+            .unwrap_err();
+        assert!(err.to_string().contains("doesn't match"), "{err}");
+        assert_eq!(state.next_l1_batch(), L1BatchNumber(1));
+
+        state
+            .apply_l1_batch(L1BatchNumber(1), &state_diffs, expected)
+            .unwrap();
+        assert_eq!(state.root_hash(), expected.root_hash);
+        assert_eq!(state.leaf_key(11).unwrap(), new_key);
+        drop(state);
+
+        // Check that the state is persisted, and that the new leaf can be updated.
+        let mut state = StateDb::open(dir.path()).unwrap();
+        assert_eq!(state.next_l1_batch(), L1BatchNumber(2));
+        let state_diffs = [StateDiff {
+            key: StateDiffKey::Repeated(11),
+            update: ValueUpdate::Sub(1.into()),
+        }];
+        let second_batch_entries = vec![TreeEntry::new(new_key, 11, H256::from_low_u64_be(99))];
+        let expected = expected_state(vec![
+            genesis_entries(),
+            first_batch_entries,
+            second_batch_entries,
+        ]);
+        state
+            .apply_l1_batch(L1BatchNumber(2), &state_diffs, expected)
+            .unwrap();
+
+        state.revert_to(L1BatchNumber(0)).unwrap();
+        assert_eq!(state.next_l1_batch(), L1BatchNumber(1));
+        assert_eq!(state.leaf_keys.metadata().unwrap().len(), 10 * KEY_SIZE);
+    }
+}
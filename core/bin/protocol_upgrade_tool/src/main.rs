@@ -0,0 +1,164 @@
+//! Tool constructing protocol upgrade payloads from declarative manifests and validating them
+//! against the current chain state.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use zksync_config::{ContractsConfig, ETHClientConfig, PostgresConfig};
+use zksync_contracts::zksync_contract;
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_eth_client::{clients::QueryClient, CallFunctionArgs, EthInterface};
+use zksync_protocol_upgrade::{ChainState, UpgradeManifest, UpgradePayload};
+use zksync_types::{
+    protocol_version::VerifierParams,
+    web3::contract::tokens::Detokenize,
+    Address, H256, U256,
+};
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Protocol upgrade tool", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Builds the upgrade payload from a manifest.
+    Build {
+        /// Path to the upgrade manifest.
+        manifest: PathBuf,
+        /// Path to the output file. If not specified, the payload is printed to stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Builds the upgrade payload and validates it against the chain state read from the diamond proxy
+    /// on L1 (configured via `ETH_CLIENT_WEB3_URL` and `CONTRACTS_DIAMOND_PROXY_ADDR`). The latest protocol
+    /// version in Postgres (configured via `DATABASE_URL`) is checked to agree with L1. Exits with an error
+    /// if the upgrade is invalid.
+    Validate {
+        /// Path to the upgrade manifest.
+        manifest: PathBuf,
+    },
+}
+
+fn build_payload(manifest_path: &Path) -> anyhow::Result<UpgradePayload> {
+    let manifest = UpgradeManifest::from_file(manifest_path)?;
+    let base_dir = manifest_path
+        .parent()
+        .context("manifest path has no parent directory")?;
+    let spec = manifest.load(base_dir)?;
+    spec.build()
+}
+
+async fn call_getter<T: Detokenize>(
+    client: &QueryClient,
+    diamond_proxy: Address,
+    name: &str,
+) -> anyhow::Result<T> {
+    let args = CallFunctionArgs::new(name, ()).for_contract(diamond_proxy, zksync_contract());
+    let tokens = client
+        .call_contract_function(args)
+        .await
+        .with_context(|| format!("failed calling `{name}` on diamond proxy {diamond_proxy:?}"))?;
+    T::from_tokens(tokens).with_context(|| format!("unexpected output of `{name}`"))
+}
+
+/// Loads the current chain state from the getters facet of the diamond proxy.
+async fn load_l1_state() -> anyhow::Result<ChainState> {
+    let eth_client_config = ETHClientConfig::from_env().context("ETHClientConfig::from_env()")?;
+    let contracts_config = ContractsConfig::from_env().context("ContractsConfig::from_env()")?;
+    let client =
+        QueryClient::new(&eth_client_config.web3_url).context("failed creating L1 client")?;
+    let diamond_proxy = contracts_config.diamond_proxy_addr;
+
+    let protocol_version: U256 = call_getter(&client, diamond_proxy, "getProtocolVersion").await?;
+    anyhow::ensure!(
+        protocol_version <= U256::from(u16::MAX),
+        "protocol version on L1 is out of range: {protocol_version}"
+    );
+    Ok(ChainState {
+        protocol_version: protocol_version.as_u32() as u16,
+        timestamp: None,
+        bootloader_code_hash: call_getter::<H256>(
+            &client,
+            diamond_proxy,
+            "getL2BootloaderBytecodeHash",
+        )
+        .await?,
+        default_account_code_hash: call_getter::<H256>(
+            &client,
+            diamond_proxy,
+            "getL2DefaultAccountBytecodeHash",
+        )
+        .await?,
+        verifier_address: call_getter::<Address>(&client, diamond_proxy, "getVerifier").await?,
+        verifier_params: call_getter::<VerifierParams>(&client, diamond_proxy, "getVerifierParams")
+            .await?,
+    })
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    match Cli::parse().command {
+        Command::Build { manifest, output } => {
+            let payload = build_payload(&manifest)?;
+            let payload = serde_json::to_string_pretty(&payload)?;
+            if let Some(output) = output {
+                fs::write(&output, payload)
+                    .with_context(|| format!("failed writing {output:?}"))?;
+            } else {
+                println!("{payload}");
+            }
+        }
+        Command::Validate { manifest } => {
+            let payload = build_payload(&manifest)?;
+            let mut current_state = load_l1_state().await?;
+
+            let postgres_config =
+                PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+            let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            let mut storage = pool.access_storage().await?;
+            let current_version_id = storage
+                .protocol_versions_dal()
+                .last_version_id()
+                .await
+                .context("no protocol versions in storage")?;
+            let current_version = storage
+                .protocol_versions_dal()
+                .get_protocol_version(current_version_id)
+                .await
+                .with_context(|| format!("protocol version {current_version_id:?} is missing"))?;
+            drop(storage);
+
+            let stored_state = ChainState::from(&current_version);
+            anyhow::ensure!(
+                stored_state.protocol_version == current_state.protocol_version,
+                "latest protocol version in Postgres ({}) differs from the version on L1 ({}); \
+                 wait until the node processes all upgrades executed on L1",
+                stored_state.protocol_version,
+                current_state.protocol_version
+            );
+            current_state.timestamp = stored_state.timestamp;
+
+            let errors = payload.validate(&current_state);            let errors = payload.validate(&current_version);
+            for error in &errors {
+                eprintln!("{error}");
+            }
+            anyhow::ensure!(errors.is_empty(), "upgrade has {} error(s)", errors.len());
+            println!(
+                "Upgrade to protocol version {} is valid (current version: {})",
+                payload.protocol_version, current_state.protocol_version
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,562 @@
+//! Checks of data published on L1 for a single L1 batch.
+
+use std::fmt;
+
+use anyhow::Context as _;
+use sha2::{Digest, Sha256};
+use zksync_eth_client::EthInterface;
+use zksync_types::{
+    api::{L1BatchDetails, L1BatchPublicInputs},
+    commitment::BlobCommitment,
+    ethabi::{self, ParamType, Token},
+    web3::{signing::keccak256, types::TransactionReceipt},
+    Address, L1BatchNumber, H256, U256,
+};
+use zksync_web3_decl::{jsonrpsee::http_client::HttpClient, namespaces::ZksNamespaceClient};
+
+const COMPONENT: &str = "rollup_watcher";
+
+const BLOCK_COMMIT_EVENT: &str = "BlockCommit(uint256,bytes32,bytes32)";
+const BLOCKS_VERIFICATION_EVENT: &str = "BlocksVerification(uint256,uint256)";
+const BLOCK_EXECUTION_EVENT: &str = "BlockExecution(uint256,bytes32,bytes32)";
+/// Signature of the post-Boojum `commitBatches` function of the main zkSync contract.
+const COMMIT_BATCHES_FUNCTION: &str = "commitBatches(\
+     (uint64,bytes32,uint64,uint256,bytes32,bytes32,uint256,bytes32),\
+     (uint64,uint64,uint64,bytes32,uint256,bytes32,bytes32,bytes32,bytes,bytes)[]\
+     )";
+
+const PUBDATA_SOURCE_CALLDATA: u8 = 0;
+const PUBDATA_SOURCE_BLOBS: u8 = 1;
+/// Size of the blob commitment appended to the pubdata published in calldata.
+const BLOB_COMMITMENT_SIZE: usize = 32;
+/// Size of the commitment to a single blob (opening point, claimed value, KZG commitment and opening proof).
+const PUBDATA_COMMITMENT_SIZE: usize = 144;
+const VERSIONED_HASH_VERSION_KZG: u8 = 1;
+
+fn event_topic(signature: &str) -> H256 {
+    H256(keccak256(signature.as_bytes()))
+}
+
+fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn batch_number_topic(number: L1BatchNumber) -> H256 {
+    let mut topic = [0_u8; 32];
+    U256::from(number.0).to_big_endian(&mut topic);
+    H256(topic)
+}
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CheckOutcome {
+    Passed,
+    /// Data to check is not published on L1 yet.
+    Pending,
+    Skipped(String),
+    Failed(String),
+}
+
+impl CheckOutcome {
+    fn failed(message: impl Into<String>) -> Self {
+        Self::Failed(message.into())
+    }
+}
+
+impl fmt::Display for CheckOutcome {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Passed => formatter.write_str("passed"),
+            Self::Pending => formatter.write_str("pending"),
+            Self::Skipped(reason) => write!(formatter, "skipped ({reason})"),
+            Self::Failed(reason) => write!(formatter, "FAILED ({reason})"),
+        }
+    }
+}
+
+/// Report on the data published on L1 for a single L1 batch.
+#[derive(Debug)]
+pub(crate) struct BatchReport {
+    pub number: L1BatchNumber,
+    pub commitment: CheckOutcome,
+    pub pubdata: CheckOutcome,
+    pub proof: CheckOutcome,
+    pub execution: CheckOutcome,
+}
+
+impl BatchReport {
+    fn outcomes(&self) -> [&CheckOutcome; 4] {
+        [
+            &self.commitment,
+            &self.pubdata,
+            &self.proof,
+            &self.execution,
+        ]
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.outcomes()
+            .iter()
+            .any(|outcome| matches!(outcome, CheckOutcome::Failed(_)))
+    }
+
+    /// Returns `true` if none of the checks for the batch will change in the future.
+    pub fn is_final(&self) -> bool {
+        self.has_failures() || self.execution == CheckOutcome::Passed
+    }
+}
+
+impl fmt::Display for BatchReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "L1 batch #{}: commitment {}, pubdata {}, proof {}, execution {}",
+            self.number, self.commitment, self.pubdata, self.proof, self.execution
+        )
+    }
+}
+
+/// Checks L1 batches of a chain against its main node and L1.
+#[derive(Debug)]
+pub(crate) struct BatchChecker {
+    main_node_client: HttpClient,
+    l1_client: Box<dyn EthInterface>,
+    main_contract: Address,
+    /// Root hash of the previous checked L1 batch used to check state continuity.
+    prev_root_hash: Option<(L1BatchNumber, H256)>,
+}
+
+impl BatchChecker {
+    pub fn new(
+        main_node_client: HttpClient,
+        l1_client: Box<dyn EthInterface>,
+        main_contract: Address,
+    ) -> Self {
+        Self {
+            main_node_client,
+            l1_client,
+            main_contract,
+            prev_root_hash: None,
+        }
+    }
+
+    /// Returns the number of the last sealed L1 batch reported by the main node.
+    pub async fn last_sealed_l1_batch(&self) -> anyhow::Result<L1BatchNumber> {
+        let number = self
+            .main_node_client
+            .get_l1_batch_number()
+            .await
+            .context("zks_L1BatchNumber")?;
+        Ok(L1BatchNumber(number.as_u32()))
+    }
+
+    /// Checks the specified L1 batch. Returns `Ok(None)` if the batch is not sealed yet.
+    pub async fn check(&mut self, number: L1BatchNumber) -> anyhow::Result<Option<BatchReport>> {
+        let Some(details) = self
+            .main_node_client
+            .get_l1_batch_details(number)
+            .await
+            .context("zks_getL1BatchDetails")?
+        else {
+            return Ok(None);
+        };
+        let public_inputs = self
+            .main_node_client
+            .get_l1_batch_public_inputs(number)
+            .await
+            .context("zks_getL1BatchPublicInputs")?;
+
+        let commitment = self
+            .check_commitment(&details, public_inputs.as_ref())
+            .await?;
+        let pubdata = if commitment == CheckOutcome::Passed {
+            self.check_pubdata(&details, public_inputs.as_ref()).await?
+        } else {
+            CheckOutcome::Skipped("commitment is not checked".to_owned())
+        };
+        let proof = self.check_proof(&details).await?;
+        let execution = self
+            .check_execution(&details, public_inputs.as_ref())
+            .await?;
+
+        if let Some(public_inputs) = &public_inputs {
+            self.prev_root_hash = Some((number, public_inputs.root_hash));
+        }
+        Ok(Some(BatchReport {
+            number,
+            commitment,
+            pubdata,
+            proof,
+            execution,
+        }))
+    }
+
+    /// Loads the receipt of an L1 transaction. Returns `Err(outcome)` if the receipt cannot be checked.
+    async fn load_receipt(
+        &self,
+        tx_hash: Option<H256>,
+    ) -> anyhow::Result<Result<TransactionReceipt, CheckOutcome>> {
+        let Some(tx_hash) = tx_hash else {
+            return Ok(Err(CheckOutcome::Pending));
+        };
+        let Some(receipt) = self.l1_client.tx_receipt(tx_hash, COMPONENT).await? else {
+            // The transaction may be not mined yet.
+            return Ok(Err(CheckOutcome::Pending));
+        };
+        if receipt.status != Some(1.into()) {
+            let message = format!("L1 transaction {tx_hash:?} has failed");
+            return Ok(Err(CheckOutcome::Failed(message)));
+        }
+        Ok(Ok(receipt))
+    }
+
+    /// Finds an event emitted by the main contract with the specified signature and first topic.
+    fn find_event<'a>(
+        &self,
+        receipt: &'a TransactionReceipt,
+        signature: &str,
+        first_topic: Option<H256>,
+    ) -> impl Iterator<Item = &'a [H256]> {
+        let main_contract = self.main_contract;
+        let event_topic = event_topic(signature);
+        receipt
+            .logs
+            .iter()
+            .filter(move |log| {
+                log.address == main_contract
+                    && log.topics.first() == Some(&event_topic)
+                    && first_topic.map_or(true, |topic| log.topics.get(1) == Some(&topic))
+            })
+            .map(|log| log.topics.as_slice())
+    }
+
+    /// Checks that the batch hash and commitment in the event emitted on L1 match the ones reported by the main node.
+    fn check_batch_event(
+        &self,
+        receipt: &TransactionReceipt,
+        signature: &str,
+        details: &L1BatchDetails,
+        public_inputs: Option<&L1BatchPublicInputs>,
+    ) -> CheckOutcome {
+        let number = details.number;
+        let Some(topics) = self
+            .find_event(receipt, signature, Some(batch_number_topic(number)))
+            .next()
+        else {
+            return CheckOutcome::failed(format!(
+                "no `{signature}` event for the batch in tx {:?}",
+                receipt.transaction_hash
+            ));
+        };
+        let (Some(&l1_root_hash), Some(&l1_commitment)) = (topics.get(2), topics.get(3)) else {
+            return CheckOutcome::failed(format!("malformed `{signature}` event"));
+        };
+
+        let Some(root_hash) = details.base.root_hash else {
+            return CheckOutcome::failed("main node doesn't report root hash for the batch");
+        };
+        if l1_root_hash != root_hash {
+            return CheckOutcome::failed(format!(
+                "root hash on L1 ({l1_root_hash:?}) differs from the one reported by the main node ({root_hash:?})"
+            ));
+        }
+        let Some(public_inputs) = public_inputs else {
+            return CheckOutcome::failed("main node doesn't report commitment for the batch");
+        };
+        if l1_commitment != public_inputs.commitment {
+            return CheckOutcome::failed(format!(
+                "commitment on L1 ({l1_commitment:?}) differs from the one reported by the main node ({:?})",
+                public_inputs.commitment
+            ));
+        }
+        CheckOutcome::Passed
+    }
+
+    async fn check_commitment(
+        &self,
+        details: &L1BatchDetails,
+        public_inputs: Option<&L1BatchPublicInputs>,
+    ) -> anyhow::Result<CheckOutcome> {
+        if let (Some((prev_number, prev_root_hash)), Some(public_inputs)) =
+            (self.prev_root_hash, public_inputs)
+        {
+            if prev_number + 1 == details.number && prev_root_hash != public_inputs.prev_root_hash {
+                return Ok(CheckOutcome::failed(format!(
+                    "previous root hash reported by the main node ({:?}) differs from the root hash \
+                     of L1 batch #{prev_number} ({prev_root_hash:?})",
+                    public_inputs.prev_root_hash
+                )));
+            }
+        }
+
+        let receipt = match self.load_receipt(details.base.commit_tx_hash).await? {
+            Ok(receipt) => receipt,
+            Err(outcome) => return Ok(outcome),
+        };
+        Ok(self.check_batch_event(&receipt, BLOCK_COMMIT_EVENT, details, public_inputs))
+    }
+
+    async fn check_pubdata(
+        &self,
+        details: &L1BatchDetails,
+        public_inputs: Option<&L1BatchPublicInputs>,
+    ) -> anyhow::Result<CheckOutcome> {
+        let tx_hash = details
+            .base
+            .commit_tx_hash
+            .context("commit tx hash is checked")?;
+        let tx = self
+            .l1_client
+            .get_tx(tx_hash, COMPONENT)
+            .await?
+            .with_context(|| format!("commit tx {tx_hash:?} is not found on L1"))?;
+        let Some(public_inputs) = public_inputs else {
+            return Ok(CheckOutcome::Skipped(
+                "main node doesn't report public inputs".to_owned(),
+            ));
+        };
+        Ok(check_commit_calldata(&tx.input.0, details, public_inputs))
+    }
+
+    async fn check_proof(&self, details: &L1BatchDetails) -> anyhow::Result<CheckOutcome> {
+        let receipt = match self.load_receipt(details.base.prove_tx_hash).await? {
+            Ok(receipt) => receipt,
+            Err(outcome) => return Ok(outcome),
+        };
+        let number = U256::from(details.number.0);
+        let is_verified = self
+            .find_event(&receipt, BLOCKS_VERIFICATION_EVENT, None)
+            .any(|topics| {
+                let (Some(prev_verified), Some(current_verified)) = (topics.get(1), topics.get(2))
+                else {
+                    return false;
+                };
+                let prev_verified = U256::from_big_endian(prev_verified.as_bytes());
+                let current_verified = U256::from_big_endian(current_verified.as_bytes());
+                prev_verified < number && number <= current_verified
+            });
+        Ok(if is_verified {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::failed(format!(
+                "no `{BLOCKS_VERIFICATION_EVENT}` event covering the batch in tx {:?}",
+                receipt.transaction_hash
+            ))
+        })
+    }
+
+    async fn check_execution(
+        &self,
+        details: &L1BatchDetails,
+        public_inputs: Option<&L1BatchPublicInputs>,
+    ) -> anyhow::Result<CheckOutcome> {
+        let receipt = match self.load_receipt(details.base.execute_tx_hash).await? {
+            Ok(receipt) => receipt,
+            Err(outcome) => return Ok(outcome),
+        };
+        Ok(self.check_batch_event(&receipt, BLOCK_EXECUTION_EVENT, details, public_inputs))
+    }
+}
+
+fn commit_batches_params() -> [ParamType; 2] {
+    let stored_batch_info = ParamType::Tuple(vec![
+        ParamType::Uint(64),
+        ParamType::FixedBytes(32),
+        ParamType::Uint(64),
+        ParamType::Uint(256),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+        ParamType::Uint(256),
+        ParamType::FixedBytes(32),
+    ]);
+    let commit_batch_info = ParamType::Tuple(vec![
+        ParamType::Uint(64),       // `batchNumber`
+        ParamType::Uint(64),       // `timestamp`
+        ParamType::Uint(64),       // `indexRepeatedStorageChanges`
+        ParamType::FixedBytes(32), // `newStateRoot`
+        ParamType::Uint(256),      // `numberOfLayer1Txs`
+        ParamType::FixedBytes(32), // `priorityOperationsHash`
+        ParamType::FixedBytes(32), // `bootloaderHeapInitialContentsHash`
+        ParamType::FixedBytes(32), // `eventsQueueStateHash`
+        ParamType::Bytes,          // `systemLogs`
+        ParamType::Bytes,          // `pubdataCommitments`
+    ]);
+    [
+        stored_batch_info,
+        ParamType::Array(Box::new(commit_batch_info)),
+    ]
+}
+
+/// Checks the commit data for the batch published in the commit transaction calldata.
+fn check_commit_calldata(
+    calldata: &[u8],
+    details: &L1BatchDetails,
+    public_inputs: &L1BatchPublicInputs,
+) -> CheckOutcome {
+    if calldata.get(..4) != Some(&function_selector(COMMIT_BATCHES_FUNCTION)) {
+        return CheckOutcome::Skipped("unsupported commit function".to_owned());
+    }
+    let Ok(mut tokens) = ethabi::decode(&commit_batches_params(), &calldata[4..]) else {
+        return CheckOutcome::failed("cannot decode commit calldata");
+    };
+    let Some(Token::Array(commitments)) = tokens.pop() else {
+        return CheckOutcome::failed("cannot decode commit calldata");
+    };
+    let batch_number = Token::Uint(details.number.0.into());
+    let commitment = commitments.into_iter().find_map(|token| match token {
+        Token::Tuple(fields) if fields.first() == Some(&batch_number) => Some(fields),
+        _ => None,
+    });
+    let Some(fields) = commitment else {
+        return CheckOutcome::failed("commit calldata doesn't contain the batch");
+    };
+    let [_, Token::Uint(timestamp), _, Token::FixedBytes(new_state_root), Token::Uint(l1_tx_count), Token::FixedBytes(priority_ops_hash), _, _, _, Token::Bytes(pubdata_commitments)] =
+        fields.as_slice()
+    else {
+        return CheckOutcome::failed("cannot decode commit calldata");
+    };
+
+    if *timestamp != details.base.timestamp.into() {
+        return CheckOutcome::failed(format!(
+            "timestamp on L1 ({timestamp}) differs from the one reported by the main node ({})",
+            details.base.timestamp
+        ));
+    }
+    if new_state_root.as_slice() != public_inputs.root_hash.as_bytes() {
+        return CheckOutcome::failed("state root in commit calldata differs from the root hash");
+    }
+    if *l1_tx_count != details.base.l1_tx_count.into() {
+        return CheckOutcome::failed(format!(
+            "number of L1 txs on L1 ({l1_tx_count}) differs from the one reported by the main node ({})",
+            details.base.l1_tx_count
+        ));
+    }
+    if priority_ops_hash.as_slice() != public_inputs.priority_operations_hash.as_bytes() {
+        return CheckOutcome::failed("priority operations hash in commit calldata differs");
+    }
+
+    match &public_inputs.blob_commitments {
+        Some(blob_commitments) => check_pubdata_commitments(pubdata_commitments, blob_commitments),
+        None if pubdata_commitments.is_empty() => CheckOutcome::failed("pubdata is not published"),
+        None => CheckOutcome::Skipped("pubdata commitments are not used for the batch".to_owned()),
+    }
+}
+
+/// Checks pubdata commitments published in commit calldata against the blob commitments reported by the main node.
+fn check_pubdata_commitments(
+    pubdata_commitments: &[u8],
+    blob_commitments: &[BlobCommitment],
+) -> CheckOutcome {
+    let Some((&source, data)) = pubdata_commitments.split_first() else {
+        return CheckOutcome::failed("pubdata is not published");
+    };
+    match source {
+        PUBDATA_SOURCE_CALLDATA => {
+            let Some(split_index) = data.len().checked_sub(BLOB_COMMITMENT_SIZE) else {
+                return CheckOutcome::failed("calldata pubdata is too short");
+            };
+            let (pubdata, commitment) = data.split_at(split_index);
+            let Some(expected) = blob_commitments.first() else {
+                return CheckOutcome::failed("main node doesn't report blob commitments");
+            };
+            if H256(keccak256(pubdata)) != expected.linear_hash {
+                return CheckOutcome::failed("hash of calldata pubdata differs from the blob hash");
+            }
+            if commitment != expected.commitment.as_bytes() {
+                return CheckOutcome::failed("blob commitment in calldata differs");
+            }
+            CheckOutcome::Passed
+        }
+        PUBDATA_SOURCE_BLOBS => {
+            if data.is_empty() || data.len() % PUBDATA_COMMITMENT_SIZE != 0 {
+                return CheckOutcome::failed("malformed blob pubdata commitments");
+            }
+            let chunks = data.chunks(PUBDATA_COMMITMENT_SIZE);
+            if chunks.len() > blob_commitments.len() {
+                return CheckOutcome::failed(
+                    "more blobs are published than reported by the main node",
+                );
+            }
+            for (i, (chunk, expected)) in chunks.zip(blob_commitments).enumerate() {
+                if blob_commitment(chunk) != expected.commitment {
+                    return CheckOutcome::failed(format!("commitment to blob #{i} differs"));
+                }
+            }
+            CheckOutcome::Passed
+        }
+        _ => CheckOutcome::failed(format!("unknown pubdata source {source}")),
+    }
+}
+
+/// Computes the blob commitment from a pubdata commitment in the format used by the L1 contract:
+/// opening point (16 bytes) || claimed value (32 bytes) || KZG commitment (48 bytes) || opening proof (48 bytes).
+fn blob_commitment(pubdata_commitment: &[u8]) -> H256 {
+    let opening_point = &pubdata_commitment[0..16];
+    let opening_value = &pubdata_commitment[16..48];
+    let kzg_commitment = &pubdata_commitment[48..96];
+    let mut versioned_hash: [u8; 32] = Sha256::digest(kzg_commitment).into();
+    versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256(keccak256(
+        &[&versioned_hash[..], opening_point, opening_value].concat(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checking_calldata_pubdata_commitments() {
+        let pubdata = b"test pubdata".to_vec();
+        let blob_commitment = BlobCommitment {
+            linear_hash: H256(keccak256(&pubdata)),
+            commitment: H256::repeat_byte(1),
+        };
+        let mut pubdata_commitments = vec![PUBDATA_SOURCE_CALLDATA];
+        pubdata_commitments.extend_from_slice(&pubdata);
+        pubdata_commitments.extend_from_slice(blob_commitment.commitment.as_bytes());
+
+        let outcome = check_pubdata_commitments(&pubdata_commitments, &[blob_commitment]);
+        assert_eq!(outcome, CheckOutcome::Passed);
+
+        let mut bogus_pubdata_commitments = pubdata_commitments.clone();
+        bogus_pubdata_commitments[1] ^= 1;
+        let outcome = check_pubdata_commitments(&bogus_pubdata_commitments, &[blob_commitment]);
+        assert!(matches!(outcome, CheckOutcome::Failed(_)), "{outcome:?}");
+
+        let outcome = check_pubdata_commitments(&[], &[blob_commitment]);
+        assert!(matches!(outcome, CheckOutcome::Failed(_)), "{outcome:?}");
+    }
+
+    #[test]
+    fn checking_blob_pubdata_commitments() {
+        let pubdata_commitment: Vec<u8> = (0..PUBDATA_COMMITMENT_SIZE as u8).collect();
+        let expected = BlobCommitment {
+            linear_hash: H256::zero(),
+            commitment: blob_commitment(&pubdata_commitment),
+        };
+        let mut pubdata_commitments = vec![PUBDATA_SOURCE_BLOBS];
+        pubdata_commitments.extend_from_slice(&pubdata_commitment);
+
+        let outcome = check_pubdata_commitments(&pubdata_commitments, &[expected]);
+        assert_eq!(outcome, CheckOutcome::Passed);
+
+        let bogus_expected = BlobCommitment {
+            commitment: H256::repeat_byte(1),
+            ..expected
+        };
+        let outcome = check_pubdata_commitments(&pubdata_commitments, &[bogus_expected]);
+        assert!(matches!(outcome, CheckOutcome::Failed(_)), "{outcome:?}");
+
+        pubdata_commitments.pop();
+        let outcome = check_pubdata_commitments(&pubdata_commitments, &[expected]);
+        assert!(matches!(outcome, CheckOutcome::Failed(_)), "{outcome:?}");
+    }
+
+    #[test]
+    fn batch_number_topic_is_correct() {
+        let topic = batch_number_topic(L1BatchNumber(0x1234));
+        assert_eq!(topic, H256::from_low_u64_be(0x1234));
+    }
+}
@@ -0,0 +1,152 @@
+//! Standalone monitor independently re-verifying data published on L1 by a zkSync chain.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use clap::Parser;
+use zksync_eth_client::clients::QueryClient;
+use zksync_types::L1BatchNumber;
+use zksync_web3_decl::{jsonrpsee::http_client::HttpClientBuilder, namespaces::ZksNamespaceClient};
+
+use crate::checks::BatchChecker;
+
+mod checks;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Re-verifies L1 batch commitments, pubdata and proofs published on L1 against the data \
+             reported by the main node",
+    long_about = None
+)]
+struct Cli {
+    /// URL of the main node JSON-RPC API.
+    #[arg(long)]
+    main_node_url: String,
+    /// URL of the L1 JSON-RPC API.
+    #[arg(long)]
+    l1_url: String,
+    /// First L1 batch to check (inclusive).
+    #[arg(long, default_value_t = 1)]
+    from_batch: u32,
+    /// Last L1 batch to check (inclusive). If not specified, the latest sealed L1 batch is used.
+    /// Ignored in the follow mode.
+    #[arg(long)]
+    to_batch: Option<u32>,
+    /// Follow the chain: check new L1 batches as they are published on L1, and re-check each batch
+    /// until it's executed.
+    #[arg(long)]
+    follow: bool,
+    /// Interval between polls in the follow mode.
+    #[arg(long, default_value_t = 60)]
+    poll_interval_sec: u64,
+    /// Stop after the first L1 batch failing checks.
+    #[arg(long)]
+    fail_fast: bool,
+}
+
+impl Cli {
+    async fn build_checker(&self) -> anyhow::Result<BatchChecker> {
+        let main_node_client = HttpClientBuilder::default()
+            .build(&self.main_node_url)
+            .context("failed creating main node client")?;
+        let main_contract = main_node_client
+            .get_main_contract()
+            .await
+            .context("zks_getMainContract")?;
+        let l1_client = QueryClient::new(&self.l1_url).context("failed creating L1 client")?;
+        tracing::info!("Using main zkSync contract {main_contract:?}");
+        Ok(BatchChecker::new(
+            main_node_client,
+            Box::new(l1_client),
+            main_contract,
+        ))
+    }
+
+    async fn run(self) -> anyhow::Result<()> {
+        let mut checker = self.build_checker().await?;
+        if self.follow {
+            self.follow(&mut checker).await
+        } else {
+            self.check_range(&mut checker).await
+        }
+    }
+
+    async fn check_range(&self, checker: &mut BatchChecker) -> anyhow::Result<()> {
+        let from_batch = L1BatchNumber(self.from_batch);
+        let to_batch = if let Some(number) = self.to_batch {
+            L1BatchNumber(number)
+        } else {
+            checker.last_sealed_l1_batch().await?
+        };
+        tracing::info!("Checking L1 batches #{from_batch}..=#{to_batch}");
+
+        let mut failed_batches = vec![];
+        let mut number = from_batch;
+        while number <= to_batch {
+            let report = checker
+                .check(number)
+                .await
+                .with_context(|| format!("failed checking L1 batch #{number}"))?
+                .with_context(|| format!("L1 batch #{number} is not sealed"))?;
+            if report.has_failures() {
+                tracing::error!("{report}");
+                failed_batches.push(number);
+                if self.fail_fast {
+                    break;
+                }
+            } else {
+                tracing::info!("{report}");
+            }
+            number += 1;
+        }
+
+        anyhow::ensure!(
+            failed_batches.is_empty(),
+            "Data published on L1 diverges from the main node data for L1 batches {failed_batches:?}"
+        );
+        tracing::info!("All checked L1 batches are consistent with L1");
+        Ok(())
+    }
+
+    async fn follow(&self, checker: &mut BatchChecker) -> anyhow::Result<()> {
+        let poll_interval = Duration::from_secs(self.poll_interval_sec);
+        let mut number = L1BatchNumber(self.from_batch);
+        tracing::info!("Following L1 batches starting from #{number}");
+
+        loop {
+            let report = checker
+                .check(number)
+                .await
+                .with_context(|| format!("failed checking L1 batch #{number}"))?;
+            let Some(report) = report else {
+                tracing::debug!("L1 batch #{number} is not sealed yet");
+                tokio::time::sleep(poll_interval).await;
+                continue;
+            };
+
+            if report.has_failures() {
+                tracing::error!("{report}");
+                anyhow::ensure!(
+                    !self.fail_fast,
+                    "Data published on L1 diverges from the main node data for L1 batch #{number}"
+                );
+            } else {
+                tracing::info!("{report}");
+            }
+
+            if report.is_final() {
+                number += 1;
+            } else {
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _guard = vlog::ObservabilityBuilder::new().build();
+    Cli::parse().run().await
+}
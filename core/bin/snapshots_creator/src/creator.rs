@@ -10,7 +10,8 @@ use zksync_object_store::ObjectStore;
 use zksync_types::{
     snapshots::{
         uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotManifest, SnapshotMetadata, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     L1BatchNumber, MiniblockNumber,
 };
@@ -22,6 +23,7 @@ use crate::tests::HandleEvent;
 /// Encapsulates progress of creating a particular storage snapshot.
 #[derive(Debug)]
 struct SnapshotProgress {
+    version: SnapshotVersion,
     l1_batch_number: L1BatchNumber,
     /// `true` if the snapshot is new (i.e., its progress is not recovered from Postgres).
     is_new_snapshot: bool,
@@ -32,6 +34,7 @@ struct SnapshotProgress {
 impl SnapshotProgress {
     fn new(l1_batch_number: L1BatchNumber, chunk_count: u64) -> Self {
         Self {
+            version: SnapshotVersion::LATEST,
             l1_batch_number,
             is_new_snapshot: true,
             chunk_count,
@@ -48,6 +51,7 @@ impl SnapshotProgress {
             .collect();
 
         Self {
+            version: snapshot.version,
             l1_batch_number: snapshot.l1_batch_number,
             is_new_snapshot: false,
             chunk_count: snapshot.storage_logs_filepaths.len() as u64,
@@ -229,6 +233,55 @@ impl SnapshotCreator {
         Ok(Some(SnapshotProgress::new(l1_batch_number, chunk_count)))
     }
 
+    /// Persists the manifest for a complete snapshot. Does nothing if the snapshot is incomplete
+    /// (which can only happen in tests).
+    async fn save_manifest(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<()> {
+        let mut master_conn = self
+            .master_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+        let snapshot = master_conn
+            .snapshots_dal()
+            .get_snapshot_metadata(l1_batch_number)
+            .await?
+            .with_context(|| format!("snapshot for L1 batch #{l1_batch_number} disappeared"))?;
+        drop(master_conn);
+
+        let storage_logs_chunks = snapshot
+            .storage_logs_filepaths
+            .into_iter()
+            .enumerate()
+            .map(|(chunk_id, filepath)| {
+                Some(SnapshotStorageLogsChunkMetadata {
+                    chunk_id: chunk_id as u64,
+                    filepath: filepath?,
+                })
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(storage_logs_chunks) = storage_logs_chunks else {
+            return Ok(());
+        };
+
+        let manifest = SnapshotManifest {
+            version: snapshot.version,
+            l1_batch_number,
+            miniblock_number,
+            factory_deps_filepath: snapshot.factory_deps_filepath,
+            storage_logs_chunks,
+        };
+        let filename = self
+            .blob_store
+            .put(l1_batch_number, &manifest)
+            .await
+            .context("Error storing snapshot manifest in blob store")?;
+        tracing::info!("Saved snapshot manifest to location: {filename}");
+        Ok(())
+    }
+
     /// Returns `Ok(None)` if a snapshot should not be created / resumed.
     async fn load_or_initialize_snapshot_progress(
         &self,
@@ -307,6 +360,7 @@ impl SnapshotCreator {
             master_conn
                 .snapshots_dal()
                 .add_snapshot(
+                    progress.version,
                     progress.l1_batch_number,
                     progress.chunk_count,
                     &factory_deps_output_file,
@@ -329,6 +383,11 @@ impl SnapshotCreator {
         });
         futures::future::try_join_all(tasks).await?;
 
+        if progress.version != SnapshotVersion::Version1 {
+            self.save_manifest(progress.l1_batch_number, last_miniblock_number_in_batch)
+                .await?;
+        }
+
         METRICS
             .snapshot_l1_batch
             .set(progress.l1_batch_number.0.into());
@@ -15,8 +15,9 @@ use zksync_object_store::ObjectStore;
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotManifest,
+        SnapshotStorageLog, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotVersion,
     },
     AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersion, StorageKey,
     StorageLog, H256,
@@ -149,6 +150,7 @@ async fn create_miniblock(
         base_system_contracts_hashes: Default::default(),
         protocol_version: Some(Default::default()),
         virtual_blocks: 0,
+        gas_limit: None,
     };
 
     conn.blocks_dal()
@@ -286,6 +288,22 @@ async fn persisting_snapshot_metadata() {
             .unwrap();
         assert!(path.ends_with(".proto.gzip"));
     }
+
+    assert_eq!(snapshot_metadata.version, SnapshotVersion::LATEST);
+    let object_store = object_store_factory.create_store().await;
+    let manifest: SnapshotManifest = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(manifest.version, SnapshotVersion::LATEST);
+    assert_eq!(manifest.l1_batch_number, snapshot_l1_batch_number);
+    assert_eq!(
+        manifest.factory_deps_filepath,
+        snapshot_metadata.factory_deps_filepath
+    );
+    let manifest_paths: Vec<_> = manifest
+        .storage_logs_chunks
+        .into_iter()
+        .map(|chunk| Some(chunk.filepath))
+        .collect();
+    assert_eq!(manifest_paths, snapshot_metadata.storage_logs_filepaths);
 }
 
 #[tokio::test]
@@ -0,0 +1,132 @@
+use std::time::Instant;
+
+use anyhow::Context as _;
+use clap::Parser;
+use vm_utils::conformance::{check_l1_batch_conformance, sample_l1_batches};
+use zksync_config::{
+    configs::{chain::NetworkConfig, ObservabilityConfig},
+    PostgresConfig,
+};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_types::L1BatchNumber;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Re-executes historical L1 batches under their original VM versions and compares outputs \
+             with the ones persisted in Postgres",
+    long_about = None
+)]
+struct Cli {
+    /// First L1 batch to sample (inclusive). If not specified, the earliest L1 batch that can be re-executed is used.
+    #[arg(long)]
+    from_batch: Option<u32>,
+    /// Last L1 batch to sample (inclusive). If not specified, the latest sealed L1 batch is used.
+    #[arg(long)]
+    to_batch: Option<u32>,
+    /// Number of L1 batches to re-execute, evenly spaced across the sampled range.
+    #[arg(long, default_value_t = 10)]
+    sample_size: usize,
+    /// Stop after the first non-conformant L1 batch.
+    #[arg(long)]
+    fail_fast: bool,
+}
+
+impl Cli {
+    async fn run(
+        self,
+        pool: &ConnectionPool,
+        network_config: &NetworkConfig,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage_tagged("vm_conformance").await?;
+        let from_batch = if let Some(number) = self.from_batch {
+            L1BatchNumber(number)
+        } else {
+            // The genesis batch cannot be re-executed, and neither can the snapshot batch after snapshot recovery.
+            let earliest_batch = storage
+                .blocks_dal()
+                .get_earliest_l1_batch_number()
+                .await?
+                .context("Postgres contains no L1 batches")?;
+            earliest_batch + 1
+        };
+        let to_batch = if let Some(number) = self.to_batch {
+            L1BatchNumber(number)
+        } else {
+            storage
+                .blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await?
+                .context("Postgres contains no L1 batches")?
+        };
+        drop(storage);
+
+        let l1_batches = sample_l1_batches(from_batch..=to_batch, self.sample_size);
+        tracing::info!(
+            "Checking VM conformance for {} L1 batches in #{from_batch}..=#{to_batch}: {l1_batches:?}",
+            l1_batches.len()
+        );
+
+        let mut non_conformant_batches = vec![];
+        for l1_batch_number in l1_batches {
+            let started_at = Instant::now();
+            let report =
+                check_l1_batch_conformance(pool, l1_batch_number, network_config.zksync_network_id)
+                    .await
+                    .with_context(|| format!("failed checking L1 batch #{l1_batch_number}"))?;
+            if report.is_conformant() {
+                tracing::info!("{report} (took {:?})", started_at.elapsed());
+            } else {
+                tracing::error!("{report}");
+                non_conformant_batches.push(l1_batch_number);
+                if self.fail_fast {
+                    break;
+                }
+            }
+            if !report.events_queue_checked {
+                tracing::warn!("Events queue for L1 batch #{l1_batch_number} is not persisted; skipped checking it");
+            }
+        }
+
+        anyhow::ensure!(
+            non_conformant_batches.is_empty(),
+            "Re-execution outputs differ from the persisted ones for L1 batches {non_conformant_batches:?}"
+        );
+        tracing::info!("All checked L1 batches are conformant");
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    // Re-execution only reads data, so we prefer using the replica DB if it's configured.
+    let database_url = postgres_config
+        .replica_url()
+        .or_else(|_| postgres_config.master_url())?;
+    // Each re-executed batch needs 2 connections at most.
+    let pool = ConnectionPool::builder(database_url, 2)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+
+    Cli::parse().run(&pool, &network_config).await
+}
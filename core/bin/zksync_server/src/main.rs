@@ -11,19 +11,25 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
+        AvailConfig, CelestiaConfig, DAClientConfig, DADispatcherConfig, EigenDAConfig,
         FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, KzgConfig,
-        ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
+        ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, RemoteConfigServiceConfig,
+        WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     GasAdjusterConfig, ObjectStoreConfig, PostgresConfig,
 };
 use zksync_core::{
-    genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
-    temp_config_store::TempConfigStore, Component, Components,
+    genesis_init, initialize_components, initialize_forensics_components, is_genesis_needed,
+    remote_config::{RemoteConfigClient, RemoteConfigWatcher},
+    setup_sigint_handler,
+    temp_config_store::TempConfigStore,
+    Component, Components,
 };
-use zksync_env_config::FromEnv;
+use zksync_dal::ConnectionPool;
+use zksync_env_config::{object_store::DAObjectStoreConfig, FromEnv};
 use zksync_storage::RocksDB;
-use zksync_utils::wait_for_tasks::wait_for_tasks;
+use zksync_utils::wait_for_tasks::{wait_for_tasks, wait_for_tasks_termination};
 
 mod config;
 
@@ -31,6 +37,9 @@ mod config;
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+/// Minimum time given to components to gracefully stop after a stop signal.
+const MIN_GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Parser)]
 #[command(author = "Matter Labs", version, about = "zkSync operator node", long_about = None)]
 struct Cli {
@@ -44,6 +53,10 @@ struct Cli {
     /// Rebuild tree.
     #[arg(long)]
     rebuild_tree: bool,
+    /// Run in the forensics mode: serve the read API (incl. debug traces) over a restored database without
+    /// accessing L1 or modifying the database. Only API components from `--components` are started.
+    #[arg(long, conflicts_with_all = ["genesis", "rebuild_tree"])]
+    forensics: bool,
     /// Comma-separated list of components to launch.
     #[arg(
         long,
@@ -126,17 +139,93 @@ async fn main() -> anyhow::Result<()> {
         gas_adjuster_config: GasAdjusterConfig::from_env().ok(),
         object_store_config: ObjectStoreConfig::from_env().ok(),
         kzg_config: KzgConfig::from_env().ok(),
+        da_dispatcher_config: DADispatcherConfig::from_env().ok(),
+        da_client_config: DAClientConfig::select(
+            AvailConfig::from_env().ok(),
+            EigenDAConfig::from_env().ok(),
+            CelestiaConfig::from_env().ok(),
+            DAObjectStoreConfig::from_env().ok().map(|config| config.0),
+        )
+        .context("da_client_config")?,
         consensus_config: None,
+        remote_config_updates: None,
+    };
+
+    let mut remote_config = None;
+    // If the remote config service is configured, errors in its configuration must not be silently ignored.
+    if std::env::var_os("REMOTE_CONFIG_URL").is_some() {
+        let remote_config_service =
+            RemoteConfigServiceConfig::from_env().context("RemoteConfigServiceConfig")?;
+        let client = RemoteConfigClient::new(&remote_config_service)?;
+        let postgres_config = configs.postgres_config.as_ref().context("PostgresConfig")?;
+        let remote_config_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build remote_config_pool")?;
+        // The database in the forensics mode must not be modified, so applied configs are not recorded.
+        let fetch_result = client
+            .fetch_on_startup(&remote_config_pool, !opt.forensics)
+            .await;
+        match fetch_result {
+            Ok(config) => {
+                tracing::info!("Fetched remote config with version {}", config.version);
+                config
+                    .apply_to_configs(&mut configs)
+                    .context("failed applying remote config")?;
+                remote_config = Some((
+                    client,
+                    config,
+                    remote_config_service.poll_interval(),
+                    remote_config_pool,
+                ));
+            }
+            Err(err) if !remote_config_service.required => {
+                tracing::warn!(
+                    "Failed fetching remote config, continuing with local config: {err:#}"
+                );
+            }
+            Err(err) => return Err(err.context("failed fetching remote config")),
+        }
+    }
+
+    let components = if opt.rebuild_tree {
+        vec![Component::Tree]
+    } else if opt.forensics {
+        let (components, skipped): (Vec<_>, Vec<_>) = opt
+            .components
+            .0
+            .into_iter()
+            .partition(|component| matches!(component, Component::HttpApi | Component::WsApi));
+        if !skipped.is_empty() {
+            tracing::warn!("Components {skipped:?} are not supported in the forensics mode and will not be started");
+        }
+        anyhow::ensure!(
+            !components.is_empty(),
+            "no API components are specified for the forensics mode"
+        );
+        components
+    } else {
+        let mut components = opt.components.0;
+        if let Some((_, config, ..)) = &remote_config {
+            config
+                .apply_component_toggles(&mut components)
+                .context("failed applying component toggles from remote config")?;
+        }
+        components
     };
 
-    if opt.components.0.contains(&Component::Consensus) {
-        configs.consensus_config =
-            Some(config::read_consensus_config().context("read_consensus_config()")?);
+    if components.contains(&Component::Consensus) {
+        configs.consensus_config = Some(
+            config::read_consensus_config()
+                .await
+                .context("read_consensus_config()")?,
+        );
     }
 
     let postgres_config = configs.postgres_config.clone().context("PostgresConfig")?;
 
-    if opt.genesis || is_genesis_needed(&postgres_config).await {
+    // The database in the forensics mode is expected to be restored from a backup and must not be modified.
+    if !opt.forensics && (opt.genesis || is_genesis_needed(&postgres_config).await) {
         let network = NetworkConfig::from_env().context("NetworkConfig")?;
         let eth_sender = ETHSenderConfig::from_env().context("ETHSenderConfig")?;
         let contracts = ContractsConfig::from_env().context("ContractsConfig")?;
@@ -156,17 +245,58 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
-    let components = if opt.rebuild_tree {
-        vec![Component::Tree]
-    } else {
-        opt.components.0
+    // Components drain in parallel after receiving a stop signal, so we wait for the slowest of them.
+    let api_shutdown_timeout = configs
+        .api_config
+        .as_ref()
+        .map_or(Duration::ZERO, |config| {
+            config.web3_json_rpc.shutdown_delay() + config.web3_json_rpc.shutdown_timeout()
+        });
+    let eth_sender_drain_timeout = configs
+        .eth_sender_config
+        .as_ref()
+        .and_then(|config| config.sender.shutdown_drain_timeout())
+        .unwrap_or_default();
+    let graceful_shutdown_timeout = MIN_GRACEFUL_SHUTDOWN_TIMEOUT
+        .max(api_shutdown_timeout)
+        .max(eth_sender_drain_timeout);
+
+    #[cfg(feature = "chaos")]
+    if let Ok(scenario_path) = std::env::var("CHAOS_SCENARIO_PATH") {
+        let scenario = std::fs::read_to_string(&scenario_path)
+            .with_context(|| format!("failed reading chaos scenario from `{scenario_path}`"))?;
+        let scenario: zksync_utils::chaos::ChaosScenario =
+            serde_json::from_str(&scenario).context("failed parsing chaos scenario")?;
+        tracing::warn!("Running chaos scenario from `{scenario_path}`");
+        // The scenario task isn't a core task since it's expected to finish.
+        tokio::spawn(scenario.run());
+    }
+
+    // The watcher writes to the audit log, so it's not run in the forensics mode.
+    let remote_config_watcher = match remote_config {
+        Some((client, config, Some(poll_interval), pool)) if !opt.forensics => {
+            let watcher =
+                RemoteConfigWatcher::new(client, config, poll_interval).with_audit_log(pool);
+            configs.remote_config_updates = Some(watcher.subscribe());
+            Some(watcher)
+        }
+        _ => None,
     };
 
     // Run core actors.
-    let (core_task_handles, stop_sender, cb_receiver, health_check_handle) =
+    let (mut core_task_handles, stop_sender, cb_receiver, health_check_handle) = if opt.forensics {
+        initialize_forensics_components(&configs, components)
+            .await
+            .context("Unable to start components in the forensics mode")?
+    } else {
         initialize_components(&configs, components)
             .await
-            .context("Unable to start Core actors")?;
+            .context("Unable to start Core actors")?
+    };
+
+    if let Some(watcher) = remote_config_watcher {
+        core_task_handles.push(tokio::spawn(watcher.run(stop_sender.subscribe())));
+    }
 
     tracing::info!("Running {} core task handlers", core_task_handles.len());
     let sigint_receiver = setup_sigint_handler();
@@ -175,7 +305,7 @@ async fn main() -> anyhow::Result<()> {
     let graceful_shutdown = None::<futures::future::Ready<()>>;
     let tasks_allowed_to_finish = false;
     tokio::select! {
-        _ = wait_for_tasks(core_task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
+        _ = wait_for_tasks(core_task_handles.iter_mut(), particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
         _ = sigint_receiver => {
             tracing::info!("Stop signal received, shutting down");
         },
@@ -189,11 +319,16 @@ async fn main() -> anyhow::Result<()> {
     }
 
     stop_sender.send(true).ok();
+    // Give components some time to gracefully stop (e.g., to seal the current miniblock, track in-flight
+    // L1 transactions and drain API requests).
+    if !wait_for_tasks_termination(&mut core_task_handles, graceful_shutdown_timeout).await {
+        tracing::warn!(
+            "Not all components stopped after {graceful_shutdown_timeout:?}; shutting down anyway"
+        );
+    }
     tokio::task::spawn_blocking(RocksDB::await_rocksdb_termination)
         .await
         .unwrap();
-    // Sleep for some time to let some components gracefully stop.
-    tokio::time::sleep(Duration::from_secs(5)).await;
     health_check_handle.stop().await;
     tracing::info!("Stopped");
     Ok(())
@@ -0,0 +1,324 @@
+//! Circuit breaker guarding against a mispriced base token.
+
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_config::configs::chain::CircuitBreakerConfig;
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Conversion ratio between the base token and ETH, i.e. the number of base token units per 1 wei.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct BaseTokenRatio {
+    pub ratio: f64,
+    /// UNIX timestamp (in seconds) at which the ratio was observed by its source.
+    pub observed_at: u64,
+}
+
+/// Source of the [`BaseTokenRatio`] used for fee conversions, e.g. a price oracle or an external API.
+#[async_trait::async_trait]
+pub trait BaseTokenRatioSource: std::fmt::Debug + Send + Sync {
+    /// Returns the latest known ratio, or `None` if the ratio wasn't observed yet.
+    async fn latest_ratio(&self) -> anyhow::Result<Option<BaseTokenRatio>>;
+}
+
+/// [`BaseTokenRatioSource`] fetching the ratio from an HTTP endpoint returning a JSON-serialized [`BaseTokenRatio`]
+/// (e.g., `{ "ratio": 1.5, "observed_at": 1700000000 }`).
+#[derive(Debug)]
+pub struct HttpBaseTokenRatioSource {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpBaseTokenRatioSource {
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(url: String) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()
+            .context("failed building HTTP client")?;
+        Ok(Self { client, url })
+    }
+}
+
+#[async_trait::async_trait]
+impl BaseTokenRatioSource for HttpBaseTokenRatioSource {
+    async fn latest_ratio(&self) -> anyhow::Result<Option<BaseTokenRatio>> {
+        let ratio = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .context("failed requesting base token ratio")?
+            .error_for_status()
+            .context("base token ratio endpoint returned an error")?
+            .json()
+            .await
+            .context("failed deserializing base token ratio")?;
+        Ok(Some(ratio))
+    }
+}
+
+/// Limits enforced by [`BaseTokenRatioChecker`].
+#[derive(Debug, Clone, Copy)]
+pub struct BaseTokenRatioLimits {
+    /// Maximum allowed relative change of the ratio within `window`, in percent.
+    pub max_change_percent: f64,
+    pub window: Duration,
+    /// Maximum allowed age of the latest observed ratio.
+    pub max_staleness: Duration,
+}
+
+impl BaseTokenRatioLimits {
+    pub fn new(config: &CircuitBreakerConfig) -> Self {
+        Self {
+            max_change_percent: config.base_token_ratio_max_change_percent(),
+            window: config.base_token_ratio_window(),
+            max_staleness: config.base_token_ratio_max_staleness(),
+        }
+    }
+}
+
+/// Trips if the base token ratio moves by more than the configured percentage within a time window,
+/// or becomes stale. Since intake of transactions priced with a wrong ratio would result in mispriced batches,
+/// it's safer to halt the system in this case.
+#[derive(Debug)]
+pub struct BaseTokenRatioChecker {
+    source: Box<dyn BaseTokenRatioSource>,
+    limits: BaseTokenRatioLimits,
+    history: Mutex<VecDeque<BaseTokenRatio>>,
+    /// Observation timestamp of the latest ratio successfully fetched from the source.
+    last_observed_at: Mutex<Option<u64>>,
+}
+
+impl BaseTokenRatioChecker {
+    pub fn new(source: Box<dyn BaseTokenRatioSource>, limits: BaseTokenRatioLimits) -> Self {
+        Self {
+            source,
+            limits,
+            history: Mutex::default(),
+            last_observed_at: Mutex::default(),
+        }
+    }
+
+    /// Records `observed_at` of a successfully fetched ratio and returns the latest observation timestamp.
+    fn record_observed_at(&self, observed_at: u64) -> u64 {
+        let mut last_observed_at = self
+            .last_observed_at
+            .lock()
+            .expect("last_observed_at is poisoned");
+        let observed_at = last_observed_at.map_or(observed_at, |last| last.max(observed_at));
+        *last_observed_at = Some(observed_at);
+        observed_at
+    }
+
+    fn check_staleness(&self, observed_at: u64) -> Result<(), CircuitBreakerError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("incorrect system time")
+            .as_secs();
+        let age = Duration::from_secs(now.saturating_sub(observed_at));
+        if age > self.limits.max_staleness {
+            return Err(CircuitBreakerError::StaleBaseTokenRatio(
+                age,
+                self.limits.max_staleness,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Records `ratio` and returns the maximum relative change (in percent) of the ratio within the window.
+    fn record_ratio(&self, ratio: BaseTokenRatio) -> f64 {
+        let mut history = self.history.lock().expect("history is poisoned");
+        if history
+            .back()
+            .map_or(true, |last| last.observed_at < ratio.observed_at)
+        {
+            history.push_back(ratio);
+        }
+        let window_start = ratio
+            .observed_at
+            .saturating_sub(self.limits.window.as_secs());
+        while history
+            .front()
+            .map_or(false, |first| first.observed_at < window_start)
+        {
+            history.pop_front();
+        }
+
+        let (min, max) = history
+            .iter()
+            .fold((f64::INFINITY, 0.0_f64), |(min, max), r| {
+                (min.min(r.ratio), max.max(r.ratio))
+            });
+        if min > 0.0 {
+            (max - min) / min * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for BaseTokenRatioChecker {
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let ratio = match self.source.latest_ratio().await {
+            Ok(Some(ratio)) => ratio,
+            Ok(None) => return Ok(()),
+            Err(err) => {
+                // Trip the breaker if the source is down for long enough for the last fetched ratio to become stale.
+                tracing::warn!("Failed getting base token ratio: {err:#}");
+                let last_observed_at = *self
+                    .last_observed_at
+                    .lock()
+                    .expect("last_observed_at is poisoned");
+                return match last_observed_at {
+                    Some(observed_at) => self.check_staleness(observed_at),
+                    None => Ok(()),
+                };
+            }
+        };
+
+        let observed_at = self.record_observed_at(ratio.observed_at);
+        self.check_staleness(observed_at)?;
+
+        let change_percent = self.record_ratio(ratio);
+        metrics::gauge!(
+            "circuit_breaker.base_token_ratio_change_percent",
+            change_percent
+        );
+        if change_percent > self.limits.max_change_percent {
+            return Err(CircuitBreakerError::VolatileBaseTokenRatio(
+                change_percent,
+                self.limits.window,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[derive(Debug, Default, Clone)]
+    struct MockSource {
+        ratio: Arc<Mutex<Option<BaseTokenRatio>>>,
+        is_down: Arc<AtomicBool>,
+    }
+
+    impl MockSource {
+        fn set(&self, ratio: f64, observed_at: u64) {
+            *self.ratio.lock().unwrap() = Some(BaseTokenRatio { ratio, observed_at });
+            self.is_down.store(false, Ordering::Relaxed);
+        }
+
+        fn fail(&self) {
+            self.is_down.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl BaseTokenRatioSource for MockSource {
+        async fn latest_ratio(&self) -> anyhow::Result<Option<BaseTokenRatio>> {
+            if self.is_down.load(Ordering::Relaxed) {
+                anyhow::bail!("source is down");
+            }
+            Ok(*self.ratio.lock().unwrap())
+        }
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    const LIMITS: BaseTokenRatioLimits = BaseTokenRatioLimits {
+        max_change_percent: 10.0,
+        window: Duration::from_secs(600),
+        max_staleness: Duration::from_secs(3_600),
+    };
+
+    #[test]
+    fn deserializing_ratio() {
+        let ratio: BaseTokenRatio =
+            serde_json::from_str(r#"{ "ratio": 1.5, "observed_at": 1700000000 }"#).unwrap();
+        assert_eq!(
+            ratio,
+            BaseTokenRatio {
+                ratio: 1.5,
+                observed_at: 1_700_000_000,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn checker_without_ratio() {
+        let checker = BaseTokenRatioChecker::new(Box::<MockSource>::default(), LIMITS);
+        checker.check().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn checker_trips_on_stale_ratio() {
+        let source = MockSource::default();
+        source.set(1.0, now() - 7_200);
+        let checker = BaseTokenRatioChecker::new(Box::new(source), LIMITS);
+        let err = checker.check().await.unwrap_err();
+        assert_matches!(err, CircuitBreakerError::StaleBaseTokenRatio(_, _));
+    }
+
+    #[tokio::test]
+    async fn checker_trips_on_stale_ratio_if_source_fails() {
+        let source = MockSource::default();
+        let checker = BaseTokenRatioChecker::new(Box::new(source.clone()), LIMITS);
+        // Failures before the first successful fetch don't trip the breaker.
+        source.fail();
+        checker.check().await.unwrap();
+
+        source.set(1.0, now() - 1_800);
+        checker.check().await.unwrap();
+        source.fail();
+        checker.check().await.unwrap();
+
+        // Simulate the source being down for longer than `max_staleness`.
+        *checker.last_observed_at.lock().unwrap() = Some(now() - 7_200);
+        let err = checker.check().await.unwrap_err();
+        assert_matches!(err, CircuitBreakerError::StaleBaseTokenRatio(_, _));
+    }
+
+    #[tokio::test]
+    async fn checker_trips_on_volatile_ratio() {
+        let source = MockSource::default();
+        let checker = BaseTokenRatioChecker::new(Box::new(source.clone()), LIMITS);
+        let start = now() - 1_000;
+
+        source.set(1.0, start);
+        checker.check().await.unwrap();
+        source.set(1.05, start + 200);
+        checker.check().await.unwrap();
+        // The first observation falls out of the window, so the change is computed relative to 1.05.
+        source.set(1.1, start + 700);
+        checker.check().await.unwrap();
+        source.set(1.25, start + 760);
+        let err = checker.check().await.unwrap_err();
+        assert_matches!(
+            err,
+            CircuitBreakerError::VolatileBaseTokenRatio(change, _) if change > 10.0
+        );
+    }
+}
@@ -6,6 +6,7 @@ use thiserror::Error;
 use tokio::sync::watch;
 use zksync_config::configs::chain::CircuitBreakerConfig;
 
+pub mod base_token_ratio;
 pub mod l1_txs;
 pub mod replication_lag;
 pub mod utils;
@@ -16,6 +17,10 @@ pub enum CircuitBreakerError {
     FailedL1Transaction,
     #[error("Replication lag ({0:?}) is above the threshold ({1:?})")]
     ReplicationLag(u32, u32),
+    #[error("Base token ratio was last updated {0:?} ago, which is above the threshold ({1:?})")]
+    StaleBaseTokenRatio(Duration, Duration),
+    #[error("Base token ratio has changed by {0:.2}% within {1:?}, which is above the threshold")]
+    VolatileBaseTokenRatio(f64, Duration),
 }
 
 /// Checks circuit breakers
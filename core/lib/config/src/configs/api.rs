@@ -1,10 +1,33 @@
-use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    num::NonZeroU32,
+    time::Duration,
+};
 
 use serde::Deserialize;
 use zksync_basic_types::H256;
 
 pub use crate::configs::PrometheusConfig;
 
+/// Visibility of pending transactions (i.e., transactions in the mempool) to API clients. Allows operators to choose
+/// their MEV / transparency policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolFeedMode {
+    /// Pending transactions are not published; `newPendingTransactions` subscriptions are rejected.
+    Private,
+    /// Hashes of pending transactions are published after a configurable delay.
+    Delayed,
+    /// Hashes of pending transactions are published as soon as they are received.
+    Public,
+}
+
+impl Default for MempoolFeedMode {
+    fn default() -> Self {
+        Self::Public
+    }
+}
+
 /// API configuration.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ApiConfig {
@@ -41,6 +64,11 @@ pub struct Web3JsonRpcConfig {
     /// different node.
     #[serde(default)]
     pub filters_disabled: bool,
+    /// Whether to reject legacy transactions that are not replay-protected, i.e., are signed
+    /// without an EIP-155 chain ID. Regardless of this setting, such transactions are logged and counted
+    /// in metrics, so that the amount of unprotected traffic can be audited before enabling enforcement.
+    #[serde(default)]
+    pub reject_txs_without_chain_id: bool,
     /// Max possible limit of filters to be in the state at once.
     pub filters_limit: Option<u32>,
     /// Max possible limit of subscriptions to be in the state at once.
@@ -96,6 +124,84 @@ pub struct Web3JsonRpcConfig {
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
     /// Tree API url, currently used to proxy `getProof` calls to the tree
     pub tree_api_url: Option<String>,
+    /// Path to a JSON ABI with custom Solidity errors used to decode revert reasons of failed transactions
+    /// returned by `zks_getTransactionDetails`. Standard `Error(string)` and `Panic(uint256)` reverts
+    /// are decoded regardless of this setting.
+    pub custom_errors_abi_path: Option<String>,
+    /// Path to a JSON file with the fee sponsorship policy, i.e. contracts and methods whose callers don't pay fees
+    /// if they use the operator-funded paymaster. If not set, no transactions are sponsored.
+    pub sponsorship_policy_path: Option<String>,
+    /// URL of a Redis instance (e.g., `redis://redis:6379`) shared by API server replicas and used as a second-level
+    /// cache for smart contract bytecodes and initial writes. If not set, only local caches are used.
+    pub shared_cache_url: Option<String>,
+    /// Delay in milliseconds between receiving a stop signal and stopping the server. During the delay, the server
+    /// reports its health as shutting down, but continues serving requests, so that load balancers can route traffic
+    /// away from it. The default value is 0.
+    pub shutdown_delay_ms: Option<u64>,
+    /// Timeout in milliseconds for draining in-flight requests and VM executions after the server is stopped.
+    /// The default value is 5,000 (5 seconds).
+    pub shutdown_timeout_ms: Option<u64>,
+    /// Maximum number of successful transaction validation results cached by the API server. A cached result is reused
+    /// if the same transaction is validated again and none of the storage slots read during validation has changed
+    /// since then. If not set or set to 0, validation results are not cached.
+    pub validation_cache_capacity: Option<usize>,
+    /// Time budget in milliseconds for VM executions performed for a single API request (e.g., `eth_call`,
+    /// `eth_estimateGas` or `debug_traceCall`). Once the budget is exceeded, execution is stopped, and the request
+    /// fails with a timeout error. If not set, execution time is not limited.
+    pub vm_execution_time_budget_ms: Option<u64>,
+    /// Maximum number of concurrently processed `debug_` requests. If set, `debug_` methods are processed by a dedicated
+    /// bounded worker pool, so that tracing load doesn't degrade latency of `eth_` / `zks_` methods. If not set,
+    /// `debug_` methods share the VM concurrency limit with other namespaces.
+    pub debug_namespace_concurrency_limit: Option<usize>,
+    /// Maximum number of `debug_` requests per minute processed by the API server. Requests above the limit
+    /// are rejected. If not set, `debug_` requests are not rate-limited.
+    pub debug_namespace_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of transactions in a block traced by `debug_traceBlockByNumber` / `debug_traceBlockByHash`.
+    /// Tracing larger blocks is rejected. If not set, blocks of any size can be traced.
+    pub debug_trace_block_tx_limit: Option<usize>,
+    /// URL of the transaction intake server running next to the sequencer. If set, the API server forwards accepted
+    /// transactions to this server instead of inserting them to the mempool directly. Must use the `https` scheme.
+    pub tx_forwarding_url: Option<String>,
+    /// Path to a PEM file with additional root certificates trusted when connecting to the transaction intake server
+    /// (e.g., if the server uses a certificate signed by an internal CA).
+    pub tx_forwarding_ca_cert_path: Option<String>,
+    /// Token used to authenticate forwarded transactions. Must be shared between the API servers
+    /// and the transaction intake server.
+    pub tx_forwarding_auth_token: Option<String>,
+    /// Capacity of the queue of transactions to be forwarded. If the queue is full, transaction submission
+    /// is rejected. The default value is 1,000.
+    pub tx_forwarding_queue_capacity: Option<usize>,
+    /// Port to which the transaction intake server is bound.
+    pub tx_intake_port: Option<u16>,
+    /// IP address to which the transaction intake server is bound. The default value is `127.0.0.1`.
+    pub tx_intake_bind_address: Option<IpAddr>,
+    /// Path to a PEM file with the TLS certificate chain of the transaction intake server. Required to run the server.
+    pub tx_intake_tls_cert_path: Option<String>,
+    /// Path to a PEM file with the private key for the TLS certificate of the transaction intake server.
+    /// Required to run the server.
+    pub tx_intake_tls_key_path: Option<String>,
+    /// Maximum number of `eth_call` results cached by the API server. Only calls pinned to a block hash are cached,
+    /// since their results are deterministic. If not set or set to 0, call results are not cached.
+    pub call_cache_capacity: Option<usize>,
+    /// Visibility of pending transactions via the `newPendingTransactions` WebSocket subscription.
+    /// If not set, pending transactions are public (the behavior before this option was introduced).
+    pub mempool_feed_mode: Option<MempoolFeedMode>,
+    /// Delay after which pending transactions are published in the delayed mempool feed mode. The default value is 10s.
+    pub mempool_feed_delay_ms: Option<u64>,
+    /// Maximum number of pending transaction hashes sent to a single `newPendingTransactions` subscriber per second.
+    /// Hashes exceeding the limit are dropped. If not set, the feed is not rate-limited.
+    pub mempool_feed_rate_limit: Option<NonZeroU32>,
+    /// If set, WebSocket subscriptions are exported to Postgres when the server shuts down, so that clients can resume
+    /// them on another replica using `zks_resumeSubscription`. Exported subscriptions can be resumed
+    /// within this number of seconds. If not set, subscriptions are not exported.
+    pub subscription_handoff_ttl_sec: Option<u64>,
+    /// Port of the internal server exposing the `admin` namespace (e.g., freezing the chain). The server is bound
+    /// to the loopback interface and is separate from the public API servers. If not set, the admin API is disabled.
+    pub admin_port: Option<u16>,
+    /// Whether `eth_getLogs` queries choose an index strategy based on the `events` table statistics instead of
+    /// relying on the Postgres planner. Disabled by default.
+    #[serde(default)]
+    pub logs_query_planner_enabled: bool,
 }
 
 impl Web3JsonRpcConfig {
@@ -110,6 +216,7 @@ impl Web3JsonRpcConfig {
             ws_url: "ws://localhost:3051".into(),
             req_entities_limit: Some(10000),
             filters_disabled: false,
+            reject_txs_without_chain_id: false,
             filters_limit: Some(10000),
             subscriptions_limit: Some(10000),
             pubsub_polling_interval: Some(200),
@@ -131,6 +238,31 @@ impl Web3JsonRpcConfig {
             max_response_body_size_mb: Default::default(),
             websocket_requests_per_minute_limit: Default::default(),
             tree_api_url: None,
+            custom_errors_abi_path: None,
+            sponsorship_policy_path: None,
+            shared_cache_url: None,
+            shutdown_delay_ms: None,
+            shutdown_timeout_ms: None,
+            validation_cache_capacity: None,
+            vm_execution_time_budget_ms: None,
+            debug_namespace_concurrency_limit: None,
+            debug_namespace_requests_per_minute_limit: None,
+            debug_trace_block_tx_limit: None,
+            tx_forwarding_url: None,
+            tx_forwarding_ca_cert_path: None,
+            tx_forwarding_auth_token: None,
+            tx_forwarding_queue_capacity: None,
+            tx_intake_port: None,
+            tx_intake_bind_address: None,
+            tx_intake_tls_cert_path: None,
+            tx_intake_tls_key_path: None,
+            call_cache_capacity: None,
+            mempool_feed_mode: None,
+            mempool_feed_delay_ms: None,
+            mempool_feed_rate_limit: None,
+            subscription_handoff_ttl_sec: None,
+            admin_port: None,
+            logs_query_planner_enabled: false,
         }
     }
 
@@ -210,6 +342,51 @@ impl Web3JsonRpcConfig {
     pub fn tree_api_url(&self) -> Option<String> {
         self.tree_api_url.clone()
     }
+
+    pub fn shutdown_delay(&self) -> Duration {
+        Duration::from_millis(self.shutdown_delay_ms.unwrap_or(0))
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(self.shutdown_timeout_ms.unwrap_or(5_000))
+    }
+
+    pub fn tx_forwarding_queue_capacity(&self) -> usize {
+        self.tx_forwarding_queue_capacity.unwrap_or(1_000)
+    }
+
+    /// Returns the address to which the transaction intake server is bound, or `None` if the server port is not set.
+    pub fn tx_intake_bind_addr(&self) -> Option<SocketAddr> {
+        let ip = self
+            .tx_intake_bind_address
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        Some(SocketAddr::new(ip, self.tx_intake_port?))
+    }
+
+    pub fn vm_execution_time_budget(&self) -> Option<Duration> {
+        self.vm_execution_time_budget_ms.map(Duration::from_millis)
+    }
+
+    pub fn mempool_feed_mode(&self) -> MempoolFeedMode {
+        self.mempool_feed_mode.unwrap_or_default()
+    }
+
+    pub fn mempool_feed_delay(&self) -> Duration {
+        Duration::from_millis(self.mempool_feed_delay_ms.unwrap_or(10_000))
+    }
+
+    pub fn subscription_handoff_ttl(&self) -> Option<Duration> {
+        self.subscription_handoff_ttl_sec.map(Duration::from_secs)
+    }
+
+    /// Private key used to sign responses of `zks_getSigned*` methods. Must be a dedicated key not used for any
+    /// other purpose; in particular, the node refuses to start if it matches an operator key. If not set,
+    /// these methods return an error. Like other private keys, it's only loaded from the environment when required.
+    pub fn response_signing_key(&self) -> Option<H256> {
+        std::env::var("API_WEB3_JSON_RPC_RESPONSE_SIGNING_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -1,7 +1,7 @@
 use std::{str::FromStr, time::Duration};
 
 use serde::Deserialize;
-use zksync_basic_types::{network::Network, Address, L2ChainId};
+use zksync_basic_types::{network::Network, Address, L2ChainId, H256};
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct NetworkConfig {
@@ -45,6 +45,16 @@ impl Default for FeeModelVersion {
     }
 }
 
+/// Mode in which L1 batches are committed on L1.
+///  - `Rollup`: pubdata of L1 batches is published on L1 (in calldata or blobs) as a part of commit transactions.
+///  - `Validium`: pubdata is not published on L1; commit transactions only contain the pubdata source marker.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+pub enum L1BatchCommitDataGeneratorMode {
+    #[default]
+    Rollup,
+    Validium,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq, Default)]
 pub struct StateKeeperConfig {
     /// The max number of slots for txs in a block before it should be sealed by the slots sealer.
@@ -116,6 +126,63 @@ pub struct StateKeeperConfig {
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     pub enum_index_migration_chunk_size: Option<usize>,
+
+    /// URL of an external service (e.g., a risk or compliance engine) notified about every transaction executed
+    /// by the state keeper before the containing miniblock is sealed. If not set, no notifications are sent.
+    pub tx_hook_url: Option<String>,
+    /// Timeout for the transaction hook in milliseconds. If set, the state keeper waits for the service verdict
+    /// and rejects L2 transactions the service rejects; otherwise, notifications are fire-and-forget.
+    pub tx_hook_timeout_ms: Option<u64>,
+
+    /// Relative premium charged on top of the base fee for processing L1->L2 (priority) transactions, e.g. 0.25
+    /// means that priority transactions must pay 125% of the base fee per gas. Allows to price congestion of the priority
+    /// queue instead of processing priority transactions at cost. If not set, no premium is charged.
+    pub priority_tx_fee_premium: Option<f64>,
+
+    /// Operator accounts whose L2 transactions are force-included, i.e. executed ahead of other mempool transactions
+    /// regardless of their score and the fair ordering bounds (e.g., for oracle updates or emergency actions).
+    /// Transactions are submitted via the regular API and must be signed by these accounts; they still must satisfy
+    /// the fee requirements. Each force-included transaction is logged by the state keeper.
+    #[serde(default)]
+    pub force_include_addresses: Vec<Address>,
+
+    /// Protocol version the shadow sequencer executes L1 batches with in step with the state keeper (e.g., a version
+    /// the network is going to be upgraded to). If not set, batches are executed with their own protocol version,
+    /// which allows to test a new VM implementation against live traffic.
+    pub shadow_protocol_version: Option<u16>,
+
+    /// Max number of factory dependencies in an L2 transaction. Cannot exceed the protocol bound; if not set,
+    /// the protocol bound is used.
+    pub max_factory_deps: Option<usize>,
+    /// Max size of L2 transaction calldata in bytes. If not set, calldata is only limited by the encoded
+    /// transaction size.
+    pub max_tx_calldata_size: Option<usize>,
+
+    /// Max number of seconds the timestamp of a new L1 batch may be ahead of the latest known L1 block. If the local
+    /// clock is further ahead (e.g., because of clock issues on the host), the state keeper waits before opening
+    /// a batch, since L1 contracts reject commitments with batch timestamps too far in the future. Should be lower
+    /// than the corresponding contract bound, accounting for L1 polling lag. If not set, drift is not checked.
+    /// The check is skipped while L1 data is stale (see `max_l1_staleness_sec` in the gas adjuster config), so that
+    /// an L1 RPC outage doesn't halt the state keeper.
+    pub max_l1_timestamp_drift_sec: Option<u64>,
+    /// Max number of sealed L1 batches without metadata, i.e. not processed by the metadata calculator yet.
+    /// When the lag exceeds half of this value, L1 batches are sealed less eagerly; when it exceeds the value,
+    /// the state keeper stops opening new batches until the metadata calculator catches up. If not set, the lag
+    /// is not checked.
+    pub max_tree_lag_batches: Option<u32>,
+    /// Same as `max_tree_lag_batches`, but for L1 batches with metadata not processed by the commitment generator.
+    /// Should only be set if the commitment generator is running.
+    pub max_commitment_lag_batches: Option<u32>,
+    /// Max amount of gas that can be used by transactions in a single miniblock. If a transaction doesn't fit
+    /// into the current miniblock, the miniblock is sealed and the transaction is moved to the next one.
+    /// The limit is reported as the block gas limit in the API. Only enforced for protocol versions supporting it;
+    /// should be not lower than the gas limit of a single transaction. If not set, miniblocks are only limited
+    /// by L1 batch seal criteria.
+    pub miniblock_gas_limit: Option<u64>,
+    /// Mode in which L1 batches are committed on L1 (rollup or validium). Should match the mode the L1 contracts
+    /// are deployed with. The default mode is rollup.
+    #[serde(default)]
+    pub l1_batch_commit_data_generator_mode: L1BatchCommitDataGeneratorMode,
 }
 
 impl StateKeeperConfig {
@@ -150,12 +217,56 @@ impl StateKeeperConfig {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: None,
+            tx_hook_url: None,
+            tx_hook_timeout_ms: None,
+            priority_tx_fee_premium: None,
+            force_include_addresses: vec![],
+            shadow_protocol_version: None,
+            max_factory_deps: None,
+            max_tx_calldata_size: None,
+            max_l1_timestamp_drift_sec: None,
+            max_tree_lag_batches: None,
+            max_commitment_lag_batches: None,
+            miniblock_gas_limit: None,
+            l1_batch_commit_data_generator_mode: L1BatchCommitDataGeneratorMode::Rollup,
         }
     }
 
     pub fn enum_index_migration_chunk_size(&self) -> usize {
         self.enum_index_migration_chunk_size.unwrap_or(1_000)
     }
+
+    pub fn tx_hook_timeout(&self) -> Option<Duration> {
+        self.tx_hook_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn max_l1_timestamp_drift(&self) -> Option<Duration> {
+        self.max_l1_timestamp_drift_sec.map(Duration::from_secs)
+    }
+
+    /// Returns the multiplier applied to the base fee per gas for priority transactions.
+    pub fn priority_tx_fee_multiplier(&self) -> f64 {
+        1.0 + self.priority_tx_fee_premium.unwrap_or(0.0)
+    }
+
+    /// Private key of the account signing recurring system transactions scheduled by the operator.
+    /// If not set, scheduled transactions are not submitted. The account must be funded to pay for
+    /// the transactions; it's force-included by the state keeper.
+    ///
+    /// Returns an error if the key is set, but is malformed.
+    // Don't load the private key, if it's not required.
+    pub fn scheduled_txs_private_key(&self) -> anyhow::Result<Option<H256>> {
+        std::env::var("CHAIN_STATE_KEEPER_SCHEDULED_TXS_PRIVATE_KEY")
+            .ok()
+            .map(|pk| {
+                pk.parse().map_err(|err| {
+                    anyhow::anyhow!(
+                        "CHAIN_STATE_KEEPER_SCHEDULED_TXS_PRIVATE_KEY is malformed: {err}"
+                    )
+                })
+            })
+            .transpose()
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -176,6 +287,15 @@ pub struct CircuitBreakerConfig {
     pub http_req_max_retry_number: usize,
     pub http_req_retry_interval_sec: u8,
     pub replication_lag_limit_sec: Option<u32>,
+    /// URL returning the conversion ratio between the base token and ETH as JSON
+    /// (`{ "ratio": 1.5, "observed_at": 1700000000 }`). If set, the node halts if the ratio is volatile or stale.
+    pub base_token_ratio_url: Option<String>,
+    /// Maximum allowed relative change of the base token ratio within the window, in percent. Default: 10.
+    pub base_token_ratio_max_change_percent: Option<f64>,
+    /// Window over which base token ratio changes are tracked. Default: 10 minutes.
+    pub base_token_ratio_window_sec: Option<u64>,
+    /// Maximum allowed age of the latest base token ratio. Default: 1 hour.
+    pub base_token_ratio_max_staleness_sec: Option<u64>,
 }
 
 impl CircuitBreakerConfig {
@@ -183,6 +303,18 @@ impl CircuitBreakerConfig {
         Duration::from_millis(self.sync_interval_ms)
     }
 
+    pub fn base_token_ratio_max_change_percent(&self) -> f64 {
+        self.base_token_ratio_max_change_percent.unwrap_or(10.0)
+    }
+
+    pub fn base_token_ratio_window(&self) -> Duration {
+        Duration::from_secs(self.base_token_ratio_window_sec.unwrap_or(600))
+    }
+
+    pub fn base_token_ratio_max_staleness(&self) -> Duration {
+        Duration::from_secs(self.base_token_ratio_max_staleness_sec.unwrap_or(3_600))
+    }
+
     pub fn http_req_retry_interval(&self) -> Duration {
         Duration::from_secs(self.http_req_retry_interval_sec as u64)
     }
@@ -196,6 +328,10 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// If set, the state keeper includes transactions in the order they've arrived to the mempool (FCFS),
+    /// deviating from it by at most the specified number of positions in the arrival sequence.
+    /// A transaction that would exceed this bound is deferred to the next miniblock.
+    pub fair_ordering_max_reordering: Option<u64>,
 }
 
 impl MempoolConfig {
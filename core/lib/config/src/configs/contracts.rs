@@ -47,6 +47,11 @@ pub struct ContractsConfig {
     pub state_transition_proxy_addr: Option<Address>,
     pub state_transition_impl_addr: Option<Address>,
     pub transparent_proxy_admin_addr: Option<Address>,
+    pub l1_shared_bridge_proxy_addr: Option<Address>,
+
+    /// Root hash of the genesis L1 batch the L1 contracts were initialized with. If set, it's checked against
+    /// the genesis batch in Postgres on server startup.
+    pub genesis_root: Option<H256>,
 }
 
 impl ContractsConfig {
@@ -89,6 +94,8 @@ impl ContractsConfig {
             bridgehub_impl_addr: Some(Address::repeat_byte(0x15)),
             state_transition_proxy_addr: Some(Address::repeat_byte(0x16)),
             state_transition_impl_addr: Some(Address::repeat_byte(0x17)),
+            l1_shared_bridge_proxy_addr: Some(Address::repeat_byte(0x18)),
+            genesis_root: Some(H256::repeat_byte(0x0a)),
         }
     }
 }
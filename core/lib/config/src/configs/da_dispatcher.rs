@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::ObjectStoreConfig;
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct DADispatcherConfig {
+    /// URL of the external DA layer client API.
+    pub da_client_url: String,
+    /// Interval between polling the database for new L1 batches and the DA layer for inclusion proofs.
+    #[serde(default = "DADispatcherConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u32,
+    /// Maximum number of L1 batches dispatched to the DA layer in a single iteration.
+    #[serde(default = "DADispatcherConfig::default_max_rows_to_dispatch")]
+    pub max_rows_to_dispatch: u32,
+    /// Maximum number of attempts to dispatch a single L1 batch or fetch its inclusion proof
+    /// before the dispatcher returns an error.
+    #[serde(default = "DADispatcherConfig::default_max_retries")]
+    pub max_retries: u16,
+}
+
+impl DADispatcherConfig {
+    const fn default_polling_interval_ms() -> u32 {
+        5_000
+    }
+
+    const fn default_max_rows_to_dispatch() -> u32 {
+        100
+    }
+
+    const fn default_max_retries() -> u16 {
+        5
+    }
+
+    pub fn for_tests() -> Self {
+        Self {
+            da_client_url: "http://127.0.0.1:4242".to_owned(),
+            polling_interval_ms: Self::default_polling_interval_ms(),
+            max_rows_to_dispatch: Self::default_max_rows_to_dispatch(),
+            max_retries: Self::default_max_retries(),
+        }
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms.into())
+    }
+}
+
+/// Configuration of the Avail DA layer client. If present, the DA dispatcher submits pubdata to Avail
+/// via the gas relay API at [`DADispatcherConfig::da_client_url`].
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct AvailConfig {
+    /// Application ID under which pubdata is submitted.
+    pub app_id: u32,
+    /// API key used to authenticate to the gas relay API.
+    pub api_key: String,
+    /// URL of the Avail node JSON-RPC API used to query Kate data proofs.
+    pub api_node_url: String,
+    /// URL of the Avail bridge API. If set, inclusion of a blob is only reported once it's attested
+    /// by the bridge on L1, and the bridge Merkle proof is used as inclusion data. Otherwise, the Kate data proof
+    /// of the blob is used.
+    pub bridge_api_url: Option<String>,
+}
+
+/// Configuration of the EigenDA client. If present, the DA dispatcher disperses pubdata via the EigenDA
+/// disperser gRPC API at [`DADispatcherConfig::da_client_url`].
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EigenDAConfig {
+    /// Account ID of the client (a hex-encoded public key) used by the disperser for rate limiting.
+    pub account_id: String,
+    /// Quorums that must sign blobs in addition to the required quorums of the network.
+    #[serde(default)]
+    pub custom_quorum_numbers: Vec<u32>,
+    /// Whether to wait until the EigenDA batch containing a blob is finalized on L1 before reporting the blob
+    /// as included. Otherwise, blobs are reported once the batch is confirmed.
+    #[serde(default)]
+    pub wait_for_finalization: bool,
+}
+
+/// Configuration of the Celestia client. If present, the DA dispatcher submits pubdata as blobs via the JSON-RPC API
+/// of a Celestia node at [`DADispatcherConfig::da_client_url`].
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct CelestiaConfig {
+    /// Hex-encoded ID of the version 0 namespace to submit blobs to (up to 10 bytes).
+    pub namespace: String,
+    /// Auth token for the node API.
+    pub auth_token: String,
+}
+
+/// Configuration of the client used by the DA dispatcher to submit pubdata. If no client is configured,
+/// the dispatcher uses the generic HTTP API at [`DADispatcherConfig::da_client_url`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DAClientConfig {
+    Avail(AvailConfig),
+    EigenDA(EigenDAConfig),
+    Celestia(CelestiaConfig),
+    /// Object store used as a DA layer (e.g., for private chains and tests).
+    ObjectStore(ObjectStoreConfig),
+}
+
+impl DAClientConfig {
+    /// Selects the DA client from the configs of individual clients. Returns an error if more than one client
+    /// is configured, since it's ambiguous which one should be used.
+    pub fn select(
+        avail: Option<AvailConfig>,
+        eigen_da: Option<EigenDAConfig>,
+        celestia: Option<CelestiaConfig>,
+        object_store: Option<ObjectStoreConfig>,
+    ) -> anyhow::Result<Option<Self>> {
+        let mut configs = [
+            avail.map(Self::Avail),
+            eigen_da.map(Self::EigenDA),
+            celestia.map(Self::Celestia),
+            object_store.map(Self::ObjectStore),
+        ]
+        .into_iter()
+        .flatten();
+        let config = configs.next();
+        if let Some(other_config) = configs.next() {
+            anyhow::bail!(
+                "At most one DA client can be configured, but both {} and {} are",
+                config.unwrap().name(),
+                other_config.name()
+            );
+        }
+        Ok(config)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Avail(_) => "Avail",
+            Self::EigenDA(_) => "EigenDA",
+            Self::Celestia(_) => "Celestia",
+            Self::ObjectStore(_) => "object store",
+        }
+    }
+}
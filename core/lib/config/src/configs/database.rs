@@ -144,6 +144,9 @@ pub struct PostgresConfig {
     pub long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     pub slow_query_threshold_ms: Option<u64>,
+    /// Threshold in milliseconds for the time a DB connection can be held before it's reported as potentially leaked.
+    /// If not set, leak detection is disabled.
+    pub leak_detection_threshold_ms: Option<u64>,
 }
 
 impl PostgresConfig {
@@ -190,4 +193,8 @@ impl PostgresConfig {
     pub fn slow_query_threshold(&self) -> Option<Duration> {
         self.slow_query_threshold_ms.map(Duration::from_millis)
     }
+
+    pub fn leak_detection_threshold(&self) -> Option<Duration> {
+        self.leak_detection_threshold_ms.map(Duration::from_millis)
+    }
 }
@@ -1,6 +1,10 @@
 // External uses
 use serde::Deserialize;
 
+/// Default number of confirmations for the [`L1FinalityMode::Confirmations`] and [`L1FinalityMode::Hybrid`] modes.
+/// Approximately corresponds to 2 epochs, i.e. the time it takes for a block to be finalized on Ethereum.
+pub const DEFAULT_L1_FINALITY_CONFIRMATIONS: u64 = 64;
+
 /// Configuration for the Ethereum gateways.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ETHClientConfig {
@@ -8,4 +12,59 @@ pub struct ETHClientConfig {
     pub chain_id: u64,
     /// Address of the Ethereum node API.
     pub web3_url: String,
+    /// How L1 blocks are considered reorg-safe. If specified, this is used consistently by components
+    /// that rely on L1 state being final (`eth_watch` and the consistency checker); otherwise, each component
+    /// uses its own legacy depth. The gas adjuster intentionally doesn't use this setting and always samples fees
+    /// from the latest L1 blocks, so that fee estimates follow the L1 fee market (see `GasAdjuster` docs).
+    #[serde(default)]
+    pub finality_mode: Option<L1FinalityMode>,
+    /// Number of confirmations for the `confirmations` and `hybrid` finality modes.
+    /// If not specified, [`DEFAULT_L1_FINALITY_CONFIRMATIONS`] is used.
+    #[serde(default)]
+    pub finality_confirmations: Option<u64>,
+}
+
+impl ETHClientConfig {
+    /// Returns the L1 finality source shared by components relying on final L1 state, or `None` if it's not configured.
+    pub fn finality_source(&self) -> Option<L1FinalitySource> {
+        let mode = self.finality_mode?;
+        Some(mode.to_source(self.finality_confirmations))
+    }
+}
+
+/// Mode of determining the latest reorg-safe L1 block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum L1FinalityMode {
+    /// Fixed number of confirmations on top of the latest L1 block.
+    Confirmations,
+    /// `finalized` block tag returned by the L1 node. Requires a node aware of the beacon chain consensus.
+    Finalized,
+    /// The earlier of the two blocks above. Guards against an L1 node reporting finality too eagerly
+    /// (e.g., on devnets), while never being less conservative than the fixed number of confirmations.
+    Hybrid,
+}
+
+impl L1FinalityMode {
+    /// Converts this mode to a finality source. If `confirmations` are not specified,
+    /// [`DEFAULT_L1_FINALITY_CONFIRMATIONS`] is used.
+    pub fn to_source(self, confirmations: Option<u64>) -> L1FinalitySource {
+        let confirmations = confirmations.unwrap_or(DEFAULT_L1_FINALITY_CONFIRMATIONS);
+        match self {
+            Self::Confirmations => L1FinalitySource::Confirmations(confirmations),
+            Self::Finalized => L1FinalitySource::Finalized,
+            Self::Hybrid => L1FinalitySource::Hybrid { confirmations },
+        }
+    }
+}
+
+/// Source of the latest reorg-safe L1 block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L1FinalitySource {
+    /// The latest L1 block minus the specified number of confirmations.
+    Confirmations(u64),
+    /// The block with the `finalized` tag.
+    Finalized,
+    /// The earlier of the finalized block and the latest L1 block minus the specified number of confirmations.
+    Hybrid { confirmations: u64 },
 }
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use serde::Deserialize;
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, H256};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -36,6 +36,24 @@ impl ETHSenderConfig {
                 max_acceptable_priority_fee_in_gwei: 100000000000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
                 pubdata_sending_mode: PubdataSendingMode::Calldata,
+                operator_balance_alert_threshold_gwei: None,
+                operator_runway_alert_threshold_hours: None,
+                base_token_addr: None,
+                operator_base_token_balance_alert_threshold: None,
+                execution_time_windows: vec![],
+                execution_approval_contract_addr: None,
+                disabled_publish_criteria: vec![],
+                dry_run: false,
+                shutdown_drain_timeout_sec: None,
+                priority_op_deadline_sec: None,
+                force_execute_on_priority_op_deadline: false,
+                signer_backend: SignerBackend::PrivateKey,
+                signer_url: None,
+                signer_region: None,
+                operator_signer_key_id: None,
+                blobs_operator_signer_key_id: None,
+                max_blobs_per_eth_tx: SenderConfig::default_max_blobs_per_eth_tx(),
+                auto_select_pubdata_da: false,
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 1000000000,
@@ -49,6 +67,8 @@ impl ETHSenderConfig {
                 num_samples_for_blob_base_fee_estimate: 10,
                 internal_pubdata_pricing_multiplier: 1.0,
                 max_blob_base_fee: None,
+                max_l1_staleness_sec: None,
+                internal_enforced_pubdata_price: None,
             },
         }
     }
@@ -72,6 +92,25 @@ pub enum PubdataSendingMode {
     #[default]
     Calldata,
     Blobs,
+    /// Pubdata is dispatched to an external DA layer by the DA dispatcher; only the DA inclusion data
+    /// is published on L1.
+    Custom,
+}
+
+/// Backend producing signatures for operator accounts.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum SignerBackend {
+    /// Raw private keys supplied via `ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY` and
+    /// `ETH_SENDER_SENDER_OPERATOR_BLOBS_PRIVATE_KEY` env variables.
+    #[default]
+    PrivateKey,
+    /// Remote signer implementing the web3signer Eth1 signing API. Can be backed by any web3signer key store,
+    /// e.g. an HSM accessed via PKCS#11.
+    Web3Signer,
+    /// Asymmetric `ECC_SECG_P256K1` key managed by AWS KMS.
+    AwsKms,
+    /// Asymmetric `EC_SIGN_SECP256K1_SHA256` key managed by GCP Cloud KMS.
+    GcpKms,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -110,6 +149,78 @@ pub struct SenderConfig {
 
     /// The mode in which we send pubdata, either Calldata or Blobs
     pub pubdata_sending_mode: PubdataSendingMode,
+
+    /// Balance of an operator account (in gwei) below which the operator balance monitor reports degraded health.
+    pub operator_balance_alert_threshold_gwei: Option<u64>,
+    /// Estimated runway of an operator account (in hours) below which the operator balance monitor
+    /// reports degraded health. The runway is estimated based on the recent spend rate of the account.
+    pub operator_runway_alert_threshold_hours: Option<u64>,
+    /// L1 address of the ERC-20 base token for chains with a custom base token. If set, the operator balance monitor
+    /// tracks balances of operator accounts in the base token in addition to ETH balances.
+    pub base_token_addr: Option<Address>,
+    /// Balance of an operator account in the base token (in whole tokens) below which the operator balance monitor
+    /// reports degraded health. Only used if `base_token_addr` is set.
+    pub operator_base_token_balance_alert_threshold: Option<u64>,
+
+    /// UTC time-of-day windows in the `HH:MM-HH:MM` format during which L1 batches are allowed to be executed.
+    /// A window may wrap around midnight (e.g., `22:00-02:00`). If empty, execution is allowed at any time.
+    #[serde(default)]
+    pub execution_time_windows: Vec<String>,
+    /// Address of the L1 contract approving execution of L1 batches, e.g. by the security council.
+    /// If set, an L1 batch is only executed after `isBatchExecutionApproved(batchNumber)` returns `true`.
+    pub execution_approval_contract_addr: Option<Address>,
+    /// Names of L1 batch publish criteria (e.g., `timestamp`) that are disabled for all aggregated operations.
+    /// Criteria capping the size of L1 transactions (`l1_batch_number`, `gas_limit` and `data_size`) cannot be
+    /// disabled. Disabling the `timestamp` criterion may indefinitely delay publishing L1 batch ranges that don't
+    /// trigger other criteria.
+    #[serde(default)]
+    pub disabled_publish_criteria: Vec<String>,
+    /// If set, L1 transactions are aggregated, persisted in Postgres and signed as usual, but are logged instead
+    /// of being broadcast to L1; signed transactions are not recorded in the transaction history. Intended for staging
+    /// environments and upgrade rehearsals; a database populated in this mode must not be used with dry run disabled,
+    /// since the persisted transactions would be sent then.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Timeout in seconds for tracking in-flight L1 transactions after a stop signal is received. While draining,
+    /// no new L1 transactions are sent, but in-flight ones are monitored (and resent if necessary) until they are
+    /// confirmed or the timeout elapses. If not set, `eth_tx_manager` stops immediately.
+    pub shutdown_drain_timeout_sec: Option<u64>,
+    /// Maximum time in seconds between receiving a priority operation from L1 and executing the L1 batch
+    /// containing it (e.g., the priority queue expiration window of the L1 contract). If set, the age of
+    /// the oldest unexecuted priority operation is monitored against this deadline.
+    pub priority_op_deadline_sec: Option<u64>,
+    /// If set together with `priority_op_deadline_sec`, an execute operation is formed regardless of other
+    /// publish criteria once the oldest unexecuted priority operation comes close to its deadline.
+    #[serde(default)]
+    pub force_execute_on_priority_op_deadline: bool,
+
+    /// Backend producing signatures for operator accounts.
+    #[serde(default)]
+    pub signer_backend: SignerBackend,
+    /// Base URL of the signing service. Required for the web3signer backend; for KMS backends, overrides
+    /// the default service endpoint.
+    pub signer_url: Option<String>,
+    /// AWS region of the KMS keys. Required for the AWS KMS backend.
+    pub signer_region: Option<String>,
+    /// Identifier of the operator key in the signing backend: a hex-encoded secp256k1 public key for web3signer,
+    /// a key ID or ARN for AWS KMS, or a crypto key version resource name for GCP KMS. Required for remote backends.
+    pub operator_signer_key_id: Option<String>,
+    /// Identifier of the blobs operator key in the signing backend. If not set, there is no blobs operator.
+    /// Ignored for the private key backend.
+    pub blobs_operator_signer_key_id: Option<String>,
+    /// Maximum number of blobs attached to a single commit transaction if pubdata is published in blobs. Commit
+    /// operations never include more blobs, and L1 batches with pubdata not fitting into this number of blobs
+    /// are not committed. Should not exceed the per-transaction blob limit of L1; can be lowered to cap blob usage
+    /// of the chain, or raised after L1 upgrades increasing the limit. Must fit pubdata of a single L1 batch
+    /// (`max_pubdata_per_batch` of the state keeper), which is checked on startup. Since the VM limits pubdata
+    /// of an L1 batch to 2 blobs, values above `2 * max_aggregated_blocks_to_commit` have no effect;
+    /// in particular, with the default `max_aggregated_blocks_to_commit = 1`, values above 2 have no effect.
+    #[serde(default = "SenderConfig::default_max_blobs_per_eth_tx")]
+    pub max_blobs_per_eth_tx: u32,
+    /// If set and pubdata is published in blobs, each commit operation publishes pubdata either in blobs or
+    /// in calldata, depending on which is cheaper given the current blob base fee and execution gas price.
+    #[serde(default)]
+    pub auto_select_pubdata_da: bool,
 }
 
 impl SenderConfig {
@@ -118,11 +229,24 @@ impl SenderConfig {
         Duration::from_secs(self.tx_poll_period)
     }
 
+    pub fn shutdown_drain_timeout(&self) -> Option<Duration> {
+        self.shutdown_drain_timeout_sec.map(Duration::from_secs)
+    }
+
+    pub fn priority_op_deadline(&self) -> Option<Duration> {
+        self.priority_op_deadline_sec.map(Duration::from_secs)
+    }
+
     /// Converts `self.aggregate_tx_poll_period` into `Duration`.
     pub fn aggregate_tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.aggregate_tx_poll_period)
     }
 
+    /// Default value of `max_blobs_per_eth_tx` equal to the EIP-4844 limit of blobs per transaction.
+    pub const fn default_max_blobs_per_eth_tx() -> u32 {
+        6
+    }
+
     // Don't load private key, if it's not required.
     pub fn private_key(&self) -> Option<H256> {
         std::env::var("ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY")
@@ -164,6 +288,13 @@ pub struct GasAdjusterConfig {
     pub internal_pubdata_pricing_multiplier: f64,
     /// Max blob base fee that is allowed to be used.
     pub max_blob_base_fee: Option<u64>,
+    /// Max age of L1 data in seconds. If no new L1 blocks are observed for this long (or the latest block returned
+    /// by the L1 provider is older than this), fee inputs are frozen at their last values, the API rejects new transactions
+    /// and health of the gas adjuster is degraded until fresh data arrives. If not set, staleness of L1 data is not checked.
+    pub max_l1_staleness_sec: Option<u64>,
+    /// Price of a pubdata byte (in wei) charged when pubdata is sent to a custom DA layer. Must be set
+    /// if `pubdata_sending_mode` is `Custom`, since the cost of the DA layer cannot be estimated from L1 data.
+    pub internal_enforced_pubdata_price: Option<u64>,
 }
 
 impl GasAdjusterConfig {
@@ -180,6 +311,10 @@ impl GasAdjusterConfig {
         self.max_blob_base_fee.unwrap_or(u64::MAX)
     }
 
+    pub fn max_l1_staleness(&self) -> Option<Duration> {
+        self.max_l1_staleness_sec.map(Duration::from_secs)
+    }
+
     pub const fn default_num_samples_for_blob_base_fee_estimate() -> usize {
         10
     }
@@ -2,6 +2,8 @@ use std::time::Duration;
 
 use serde::Deserialize;
 
+use super::eth_client::L1FinalitySource;
+
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ETHWatchConfig {
@@ -18,4 +20,11 @@ impl ETHWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Returns the finality source used by `eth_watch` if no shared source is configured
+    /// in [`ETHClientConfig`](super::ETHClientConfig).
+    pub fn legacy_finality_source(&self) -> L1FinalitySource {
+        self.confirmations_for_eth_event
+            .map_or(L1FinalitySource::Finalized, L1FinalitySource::Confirmations)
+    }
 }
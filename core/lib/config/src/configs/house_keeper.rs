@@ -16,4 +16,43 @@ pub struct HouseKeeperConfig {
     pub fri_prover_stats_reporting_interval_ms: u64,
     pub fri_proof_compressor_job_retrying_interval_ms: u64,
     pub fri_proof_compressor_stats_reporting_interval_ms: u64,
+    /// Number of days for which witness inputs are retained in the object store after the L1 batch is executed.
+    /// If not set, witness inputs are never removed.
+    pub witness_inputs_retention_days: Option<u32>,
+    /// Number of days for which final L1 batch proofs are retained in the object store after the L1 batch
+    /// is executed. If not set, proofs are never removed.
+    pub proofs_retention_days: Option<u32>,
+    /// Interval between checks for expired object store artifacts.
+    #[serde(default = "HouseKeeperConfig::default_artifacts_retention_interval_ms")]
+    pub artifacts_retention_interval_ms: u64,
+    /// If set, expired object store artifacts are only reported, but not removed.
+    #[serde(default)]
+    pub artifacts_retention_dry_run: bool,
+    /// Interval between consistency checks of prover DB job states against core DB L1 batch states.
+    #[serde(default = "HouseKeeperConfig::default_prover_db_consistency_check_interval_ms")]
+    pub prover_db_consistency_check_interval_ms: u64,
+    /// Interval between exports of new audit log entries to the object store. If not set,
+    /// the audit log is only kept in Postgres.
+    pub audit_log_export_interval_ms: Option<u64>,
+    /// Number of days after the L1 batch execution after which call traces and events for the batch are moved
+    /// from Postgres to the object store. The moved data is still available via the API, but with higher latency.
+    /// If not set, call traces and events are kept in Postgres.
+    pub cold_storage_threshold_days: Option<u32>,
+    /// Interval between checks for L1 batches which call traces and events should be moved to the object store.
+    #[serde(default = "HouseKeeperConfig::default_cold_storage_interval_ms")]
+    pub cold_storage_interval_ms: u64,
+}
+
+impl HouseKeeperConfig {
+    pub const fn default_artifacts_retention_interval_ms() -> u64 {
+        3_600_000 // 1 hour
+    }
+
+    pub const fn default_prover_db_consistency_check_interval_ms() -> u64 {
+        600_000 // 10 minutes
+    }
+
+    pub const fn default_cold_storage_interval_ms() -> u64 {
+        3_600_000 // 1 hour
+    }
 }
@@ -4,24 +4,17 @@ use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct KzgConfig {
-    /// Path to KZG trusted setup file.
-    #[serde(default = "KzgConfig::default_trusted_setup_path")]
-    pub trusted_setup_path: String,
+    /// Path to KZG trusted setup file. If not specified, the trusted setup embedded into the binary is used.
+    #[serde(default)]
+    pub trusted_setup_path: Option<String>,
 }
 
 impl KzgConfig {
-    fn default_trusted_setup_path() -> String {
-        "./trusted_setup.json".to_owned()
-    }
-
     pub fn for_tests() -> Self {
         let zksync_home = std::env::var("ZKSYNC_HOME").unwrap();
+        let trusted_setup_path = Path::new(&zksync_home).join("trusted_setup.json");
         Self {
-            trusted_setup_path: Path::new(&zksync_home)
-                .join("trusted_setup.json")
-                .to_str()
-                .unwrap()
-                .to_owned(),
+            trusted_setup_path: Some(trusted_setup_path.to_str().unwrap().to_owned()),
         }
     }
 }
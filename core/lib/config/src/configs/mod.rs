@@ -4,6 +4,9 @@ pub use self::{
     api::ApiConfig,
     contract_verifier::ContractVerifierConfig,
     contracts::ContractsConfig,
+    da_dispatcher::{
+        AvailConfig, CelestiaConfig, DAClientConfig, DADispatcherConfig, EigenDAConfig,
+    },
     database::{DBConfig, PostgresConfig},
     eth_client::ETHClientConfig,
     eth_sender::{ETHSenderConfig, GasAdjusterConfig},
@@ -17,6 +20,7 @@ pub use self::{
     object_store::ObjectStoreConfig,
     observability::ObservabilityConfig,
     proof_data_handler::ProofDataHandlerConfig,
+    remote_config::RemoteConfigServiceConfig,
     snapshots_creator::SnapshotsCreatorConfig,
     utils::PrometheusConfig,
     witness_generator::WitnessGeneratorConfig,
@@ -27,6 +31,7 @@ pub mod api;
 pub mod chain;
 pub mod contract_verifier;
 pub mod contracts;
+pub mod da_dispatcher;
 pub mod database;
 pub mod eth_client;
 pub mod eth_sender;
@@ -42,6 +47,7 @@ pub mod kzg;
 pub mod object_store;
 pub mod observability;
 pub mod proof_data_handler;
+pub mod remote_config;
 pub mod snapshots_creator;
 pub mod utils;
 pub mod witness_generator;
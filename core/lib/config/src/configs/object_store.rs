@@ -7,6 +7,16 @@ pub struct ObjectStoreConfig {
     pub mode: ObjectStoreMode,
     #[serde(default = "ObjectStoreConfig::default_max_retries")]
     pub max_retries: u16,
+    /// zstd compression level for stored objects. If not set, objects are stored uncompressed. Compressed objects
+    /// are stored under keys with the `.zst` suffix. Regardless of this setting, compressed objects are transparently
+    /// decompressed when read.
+    #[serde(default)]
+    pub compression_level: Option<u8>,
+    /// Base URL of the bucket (or the base path for the file-backed store) to replicate objects to. The replica
+    /// is accessed in the same mode and with the same credentials as the primary bucket. If set, objects are written
+    /// to both buckets, and reads fall back to the replica if the primary bucket is unavailable.
+    #[serde(default)]
+    pub replica_bucket_base_url: Option<String>,
 }
 
 impl ObjectStoreConfig {
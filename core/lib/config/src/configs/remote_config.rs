@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use zksync_basic_types::Address;
+
+/// Configuration of the remote config service used to toggle components and tune selected parameters
+/// without redeploying the node.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RemoteConfigServiceConfig {
+    /// URL returning the signed config payload, e.g. an HTTP gateway of etcd / Consul KV or a plain HTTP server.
+    pub url: String,
+    /// Address of the key that must have signed the payload. Payloads signed by other keys are rejected.
+    pub signer_address: Address,
+    /// Interval to poll the service for runtime updates. If not set, the config is only fetched on startup.
+    pub poll_interval_secs: Option<u64>,
+    /// If set, the node will refuse to start if the remote config cannot be fetched on startup.
+    /// Otherwise, the node will start with the local config only.
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl RemoteConfigServiceConfig {
+    pub fn poll_interval(&self) -> Option<Duration> {
+        self.poll_interval_secs.map(Duration::from_secs)
+    }
+}
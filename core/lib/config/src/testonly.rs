@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr},
+};
 
 use rand::{distributions::Alphanumeric, Rng};
 use zksync_basic_types::{
@@ -66,6 +69,12 @@ impl<T: RandomConfig + Eq + std::hash::Hash> RandomConfig for HashSet<T> {
     }
 }
 
+impl RandomConfig for IpAddr {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        IpAddr::V4(Ipv4Addr::from(g.rng.gen::<u32>()))
+    }
+}
+
 impl RandomConfig for bool {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         g.rng.gen()
@@ -154,6 +163,15 @@ impl RandomConfig for configs::chain::FeeModelVersion {
     }
 }
 
+impl RandomConfig for configs::chain::L1BatchCommitDataGeneratorMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Rollup,
+            _ => Self::Validium,
+        }
+    }
+}
+
 impl RandomConfig for configs::AlertsConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -183,6 +201,7 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             ws_url: g.gen(),
             req_entities_limit: g.gen(),
             filters_disabled: g.gen(),
+            reject_txs_without_chain_id: g.gen(),
             filters_limit: g.gen(),
             subscriptions_limit: g.gen(),
             pubsub_polling_interval: g.gen(),
@@ -204,6 +223,41 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             max_response_body_size_mb: g.gen(),
             websocket_requests_per_minute_limit: g.gen(),
             tree_api_url: g.gen(),
+            custom_errors_abi_path: g.gen(),
+            sponsorship_policy_path: g.gen(),
+            shared_cache_url: g.gen(),
+            shutdown_delay_ms: g.gen(),
+            shutdown_timeout_ms: g.gen(),
+            validation_cache_capacity: g.gen(),
+            vm_execution_time_budget_ms: g.gen(),
+            debug_namespace_concurrency_limit: g.gen(),
+            debug_namespace_requests_per_minute_limit: g.gen(),
+            debug_trace_block_tx_limit: g.gen(),
+            tx_forwarding_url: g.gen(),
+            tx_forwarding_ca_cert_path: g.gen(),
+            tx_forwarding_auth_token: g.gen(),
+            tx_forwarding_queue_capacity: g.gen(),
+            tx_intake_port: g.gen(),
+            tx_intake_bind_address: g.gen(),
+            tx_intake_tls_cert_path: g.gen(),
+            tx_intake_tls_key_path: g.gen(),
+            call_cache_capacity: g.gen(),
+            mempool_feed_mode: g.gen(),
+            mempool_feed_delay_ms: g.gen(),
+            mempool_feed_rate_limit: g.gen(),
+            subscription_handoff_ttl_sec: g.gen(),
+            admin_port: g.gen(),
+            logs_query_planner_enabled: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::api::MempoolFeedMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..3) {
+            0 => Self::Private,
+            1 => Self::Delayed,
+            _ => Self::Public,
         }
     }
 }
@@ -282,6 +336,18 @@ impl RandomConfig for configs::chain::StateKeeperConfig {
             virtual_blocks_per_miniblock: g.gen(),
             upload_witness_inputs_to_gcs: g.gen(),
             enum_index_migration_chunk_size: g.gen(),
+            tx_hook_url: g.gen(),
+            tx_hook_timeout_ms: g.gen(),
+            priority_tx_fee_premium: g.gen(),
+            force_include_addresses: g.gen(),
+            shadow_protocol_version: g.gen(),
+            max_factory_deps: g.gen(),
+            max_tx_calldata_size: g.gen(),
+            max_l1_timestamp_drift_sec: g.gen(),
+            max_tree_lag_batches: g.gen(),
+            max_commitment_lag_batches: g.gen(),
+            miniblock_gas_limit: g.gen(),
+            l1_batch_commit_data_generator_mode: g.gen(),
         }
     }
 }
@@ -301,6 +367,10 @@ impl RandomConfig for configs::chain::CircuitBreakerConfig {
             http_req_max_retry_number: g.gen(),
             http_req_retry_interval_sec: g.gen(),
             replication_lag_limit_sec: g.gen(),
+            base_token_ratio_url: g.gen(),
+            base_token_ratio_max_change_percent: g.gen(),
+            base_token_ratio_window_sec: g.gen(),
+            base_token_ratio_max_staleness_sec: g.gen(),
         }
     }
 }
@@ -314,6 +384,7 @@ impl RandomConfig for configs::chain::MempoolConfig {
             stuck_tx_timeout: g.gen(),
             remove_stuck_txs: g.gen(),
             delay_interval: g.gen(),
+            fair_ordering_max_reordering: g.gen(),
         }
     }
 }
@@ -373,6 +444,8 @@ impl RandomConfig for configs::ContractsConfig {
             state_transition_proxy_addr: g.gen(),
             state_transition_impl_addr: g.gen(),
             transparent_proxy_admin_addr: g.gen(),
+            l1_shared_bridge_proxy_addr: g.gen(),
+            genesis_root: g.gen(),
         }
     }
 }
@@ -420,6 +493,7 @@ impl RandomConfig for configs::database::PostgresConfig {
             statement_timeout_sec: g.gen(),
             long_connection_threshold_ms: g.gen(),
             slow_query_threshold_ms: g.gen(),
+            leak_detection_threshold_ms: g.gen(),
         }
     }
 }
@@ -429,6 +503,18 @@ impl RandomConfig for configs::ETHClientConfig {
         Self {
             chain_id: g.gen(),
             web3_url: g.gen(),
+            finality_mode: g.gen(),
+            finality_confirmations: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_client::L1FinalityMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..3) {
+            0 => Self::Confirmations,
+            1 => Self::Finalized,
+            _ => Self::Hybrid,
         }
     }
 }
@@ -463,9 +549,21 @@ impl RandomConfig for configs::eth_sender::ProofLoadingMode {
 
 impl RandomConfig for configs::eth_sender::PubdataSendingMode {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        match g.rng.gen_range(0..2) {
+        match g.rng.gen_range(0..3) {
             0 => Self::Calldata,
-            _ => Self::Blobs,
+            1 => Self::Blobs,
+            _ => Self::Custom,
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::SignerBackend {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..4) {
+            0 => Self::PrivateKey,
+            1 => Self::Web3Signer,
+            2 => Self::AwsKms,
+            _ => Self::GcpKms,
         }
     }
 }
@@ -491,6 +589,24 @@ impl RandomConfig for configs::eth_sender::SenderConfig {
             max_acceptable_priority_fee_in_gwei: g.gen(),
             proof_loading_mode: g.gen(),
             pubdata_sending_mode: PubdataSendingMode::Calldata,
+            operator_balance_alert_threshold_gwei: g.gen(),
+            operator_runway_alert_threshold_hours: g.gen(),
+            base_token_addr: g.gen(),
+            operator_base_token_balance_alert_threshold: g.gen(),
+            execution_time_windows: g.gen(),
+            execution_approval_contract_addr: g.gen(),
+            disabled_publish_criteria: g.gen(),
+            dry_run: g.gen(),
+            shutdown_drain_timeout_sec: g.gen(),
+            priority_op_deadline_sec: g.gen(),
+            force_execute_on_priority_op_deadline: g.gen(),
+            signer_backend: g.gen(),
+            signer_url: g.gen(),
+            signer_region: g.gen(),
+            operator_signer_key_id: g.gen(),
+            blobs_operator_signer_key_id: g.gen(),
+            max_blobs_per_eth_tx: g.gen(),
+            auto_select_pubdata_da: g.gen(),
         }
     }
 }
@@ -509,6 +625,8 @@ impl RandomConfig for configs::eth_sender::GasAdjusterConfig {
             num_samples_for_blob_base_fee_estimate: g.gen(),
             internal_pubdata_pricing_multiplier: g.gen(),
             max_blob_base_fee: g.gen(),
+            max_l1_staleness_sec: g.gen(),
+            internal_enforced_pubdata_price: g.gen(),
         }
     }
 }
@@ -652,6 +770,14 @@ impl RandomConfig for configs::house_keeper::HouseKeeperConfig {
             fri_prover_stats_reporting_interval_ms: g.gen(),
             fri_proof_compressor_job_retrying_interval_ms: g.gen(),
             fri_proof_compressor_stats_reporting_interval_ms: g.gen(),
+            witness_inputs_retention_days: g.gen(),
+            proofs_retention_days: g.gen(),
+            artifacts_retention_interval_ms: g.gen(),
+            artifacts_retention_dry_run: g.gen(),
+            prover_db_consistency_check_interval_ms: g.gen(),
+            audit_log_export_interval_ms: g.gen(),
+            cold_storage_threshold_days: g.gen(),
+            cold_storage_interval_ms: g.gen(),
         }
     }
 }
@@ -681,6 +807,8 @@ impl RandomConfig for configs::ObjectStoreConfig {
         Self {
             mode: g.gen(),
             max_retries: g.gen(),
+            compression_level: g.gen(),
+            replica_bucket_base_url: g.gen(),
         }
     }
 }
@@ -694,6 +822,58 @@ impl RandomConfig for configs::proof_data_handler::ProtocolVersionLoadingMode {
     }
 }
 
+impl RandomConfig for configs::DADispatcherConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            da_client_url: g.gen(),
+            polling_interval_ms: g.gen(),
+            max_rows_to_dispatch: g.gen(),
+            max_retries: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::AvailConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            app_id: g.gen(),
+            api_key: g.gen(),
+            api_node_url: g.gen(),
+            bridge_api_url: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::EigenDAConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            account_id: g.gen(),
+            custom_quorum_numbers: g.gen(),
+            wait_for_finalization: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::CelestiaConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            namespace: g.gen(),
+            auth_token: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::DAClientConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..4) {
+            0 => Self::Avail(g.gen()),
+            1 => Self::EigenDA(g.gen()),
+            2 => Self::Celestia(g.gen()),
+            _ => Self::ObjectStore(g.gen()),
+        }
+    }
+}
+
 impl RandomConfig for configs::ProofDataHandlerConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
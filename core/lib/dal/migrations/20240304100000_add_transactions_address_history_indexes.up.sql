@@ -0,0 +1,6 @@
+-- no-transaction
+-- `transactions` is written to on every miniblock, so indexes are built without locking the table for writes.
+-- `CONCURRENTLY` cannot run in a transaction, so each index is created by a separate migration.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS transactions_initiator_address_miniblock_number_idx
+    ON transactions (initiator_address, miniblock_number, index_in_block)
+    WHERE miniblock_number IS NOT NULL;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS eth_txs_l1_costs (
+    eth_tx_id INT PRIMARY KEY REFERENCES eth_txs (id) ON DELETE CASCADE,
+    gas_used NUMERIC(80) NOT NULL,
+    effective_gas_price NUMERIC(80) NOT NULL,
+    l1_block_number BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
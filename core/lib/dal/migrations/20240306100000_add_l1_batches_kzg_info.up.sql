@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS l1_batches_kzg_info (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    -- Serialized `KzgInfo` for each blob of the batch pubdata.
+    kzg_info BYTEA[] NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
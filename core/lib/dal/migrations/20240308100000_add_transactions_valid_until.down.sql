@@ -0,0 +1,2 @@
+ALTER TABLE transactions DROP COLUMN IF EXISTS valid_until_block;
+ALTER TABLE transactions DROP COLUMN IF EXISTS valid_until_timestamp;
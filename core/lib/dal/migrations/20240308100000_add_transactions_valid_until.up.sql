@@ -0,0 +1,2 @@
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS valid_until_timestamp BIGINT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS valid_until_block BIGINT;
@@ -0,0 +1,2 @@
+ALTER TABLE transactions DROP COLUMN IF EXISTS arrival_seq;
+DROP SEQUENCE IF EXISTS transactions_arrival_seq;
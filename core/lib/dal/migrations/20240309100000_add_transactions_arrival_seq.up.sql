@@ -0,0 +1,2 @@
+CREATE SEQUENCE IF NOT EXISTS transactions_arrival_seq;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS arrival_seq BIGINT;
@@ -0,0 +1,2 @@
+ALTER TABLE commitments DROP COLUMN IF EXISTS blob_commitments;
+ALTER TABLE commitments DROP COLUMN IF EXISTS blob_linear_hashes;
@@ -0,0 +1,2 @@
+ALTER TABLE commitments ADD COLUMN IF NOT EXISTS blob_linear_hashes BYTEA[];
+ALTER TABLE commitments ADD COLUMN IF NOT EXISTS blob_commitments BYTEA[];
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS data_availability (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    -- Opaque identifier of the pubdata blob returned by the DA layer.
+    blob_id TEXT NOT NULL,
+    -- Proof of the blob inclusion in the DA layer; set once the blob is included.
+    inclusion_data BYTEA,
+    sent_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE l1_batches
+    DROP COLUMN IF EXISTS operation_statistic;
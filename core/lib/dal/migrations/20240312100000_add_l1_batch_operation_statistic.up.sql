@@ -0,0 +1,2 @@
+ALTER TABLE l1_batches
+    ADD COLUMN IF NOT EXISTS operation_statistic JSONB;
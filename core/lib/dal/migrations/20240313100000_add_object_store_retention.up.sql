@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS object_store_retention (
+    -- Name of the retention policy, e.g. `witness_inputs`.
+    policy TEXT PRIMARY KEY,
+    -- Last L1 batch for which objects covered by the policy were removed.
+    last_removed_l1_batch BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS audit_log_exports;
+DROP TRIGGER IF EXISTS audit_log_append_only ON audit_log;
+DROP FUNCTION IF EXISTS audit_log_reject_modification;
+DROP TABLE IF EXISTS audit_log;
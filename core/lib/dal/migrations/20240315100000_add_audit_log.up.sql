@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    -- Privileged action, e.g. `block_revert`.
+    action TEXT NOT NULL,
+    -- Identity of the actor that has performed the action.
+    actor TEXT NOT NULL,
+    -- Action-specific parameters.
+    params JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+-- The audit log is append-only; entries may not be changed or removed.
+CREATE OR REPLACE FUNCTION audit_log_reject_modification() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_reject_modification();
+
+CREATE TABLE IF NOT EXISTS audit_log_exports (
+    -- Name of the export destination, e.g. `object_store`.
+    destination TEXT PRIMARY KEY,
+    -- ID of the last audit log entry exported to the destination.
+    last_exported_id BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,11 @@
+-- Fingerprints of the latest proofs generated for L1 batches. Unlike `proof_generation_details`, this table
+-- isn't linked to `l1_batches`, so fingerprints survive batch reverts and allow reusing proofs for batches
+-- re-sealed with identical contents.
+CREATE TABLE IF NOT EXISTS l1_batch_proof_fingerprints (
+    l1_batch_number BIGINT PRIMARY KEY,
+    -- Hash of the proof public inputs (previous and current batch commitments and the protocol version).
+    fingerprint BYTEA NOT NULL,
+    proof_blob_url TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS fri_batch_reproofs;
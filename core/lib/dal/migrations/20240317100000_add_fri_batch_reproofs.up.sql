@@ -0,0 +1,15 @@
+-- Batches requeued to be re-proven under a specific prover protocol version for validation purposes.
+-- Proofs generated for these batches are not sent to the server, so re-proving doesn't affect L1 state.
+CREATE TABLE IF NOT EXISTS fri_batch_reproofs (
+    l1_batch_number BIGINT PRIMARY KEY,
+    protocol_version INT NOT NULL REFERENCES prover_fri_protocol_versions (id),
+    original_protocol_version INT,
+    -- Object store key of the copy of the proof generated before re-proving.
+    original_proof_blob_url TEXT NOT NULL,
+    -- Numbers of prover jobs per aggregation round before re-proving.
+    original_circuit_counts JSONB NOT NULL,
+    -- Report comparing original and re-proven artifacts; set once re-proving is finished.
+    report JSONB,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
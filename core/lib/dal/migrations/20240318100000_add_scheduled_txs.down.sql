@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS scheduled_tx_submissions;
+DROP TABLE IF EXISTS scheduled_txs;
@@ -0,0 +1,27 @@
+-- Recurring system transactions (e.g., oracle keepers or fee sweeps) scheduled by the operator.
+-- Transactions are signed by the scheduled transactions signer configured for the state keeper.
+CREATE TABLE IF NOT EXISTS scheduled_txs (
+    name TEXT PRIMARY KEY,
+    contract_address BYTEA NOT NULL,
+    calldata BYTEA NOT NULL,
+    -- Gas limit of each submitted transaction. Scheduled transactions are not estimated,
+    -- so the limit should cover the worst-case execution.
+    gas_limit BIGINT NOT NULL,
+    interval_sec BIGINT NOT NULL,
+    is_enabled BOOLEAN NOT NULL,
+    -- Time after which the next transaction is submitted.
+    next_submission_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+-- Transactions submitted for scheduled transactions. Execution results are taken from the `transactions` table.
+CREATE TABLE IF NOT EXISTS scheduled_tx_submissions (
+    tx_hash BYTEA PRIMARY KEY,
+    schedule_name TEXT NOT NULL REFERENCES scheduled_txs (name) ON DELETE CASCADE,
+    nonce BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS scheduled_tx_submissions_schedule_name_idx
+    ON scheduled_tx_submissions (schedule_name);
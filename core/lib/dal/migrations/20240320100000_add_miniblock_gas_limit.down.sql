@@ -0,0 +1 @@
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS gas_limit;
@@ -0,0 +1,2 @@
+ALTER TABLE miniblocks
+    ADD COLUMN IF NOT EXISTS gas_limit BIGINT;
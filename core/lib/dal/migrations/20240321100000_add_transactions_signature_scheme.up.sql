@@ -0,0 +1,4 @@
+-- Signature scheme of L2 transactions detected from their signatures.
+-- Not backfilled; NULL for transactions inserted before the column was added and for non-L2 transactions.
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS signature_scheme TEXT;
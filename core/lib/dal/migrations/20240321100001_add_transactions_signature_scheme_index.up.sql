@@ -0,0 +1,6 @@
+-- no-transaction
+-- `transactions` is written to on every miniblock, so the index is built without locking the table for writes.
+-- `CONCURRENTLY` cannot run in a transaction, hence the migration directive above.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS transactions_signature_scheme_miniblock_number_idx
+    ON transactions (signature_scheme, miniblock_number, index_in_block)
+    WHERE signature_scheme IS NOT NULL AND miniblock_number IS NOT NULL;
@@ -0,0 +1,13 @@
+-- Hashes of prover artifacts (witness inputs and proofs) stored in the object store. Artifacts are checked against
+-- these hashes when they are loaded, so that substitution of an artifact in the object store is detected.
+-- Like `l1_batch_proof_fingerprints`, the table isn't linked to `l1_batches`, so that hashes survive batch reverts.
+CREATE TABLE IF NOT EXISTS l1_batch_artifact_hashes (
+    l1_batch_number BIGINT NOT NULL,
+    -- Artifact kind, e.g. `witness_input` or `proof`.
+    artifact TEXT NOT NULL,
+    -- `keccak256` of the serialized artifact.
+    hash BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, artifact)
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS solvency_reports;
@@ -0,0 +1,14 @@
+-- Cumulative bridge accounting for the base token produced by the solvency reporter for each sealed L1 batch.
+-- All amounts are in wei and include all L1 batches up to and including the reported one.
+CREATE TABLE IF NOT EXISTS solvency_reports (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    -- Total value minted on L2 by L1 transactions (i.e., deposits).
+    total_deposited NUMERIC(80) NOT NULL,
+    -- Total value of withdrawals initiated on L2.
+    total_withdrawn NUMERIC(80) NOT NULL,
+    -- L2 total supply expected from the genesis supply, deposits and withdrawals.
+    expected_supply NUMERIC(80) NOT NULL,
+    -- Actual L2 total supply of the base token after the L1 batch.
+    l2_total_supply NUMERIC(80) NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
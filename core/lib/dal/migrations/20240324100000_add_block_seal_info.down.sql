@@ -0,0 +1,4 @@
+ALTER TABLE miniblocks
+    DROP COLUMN IF EXISTS seal_info;
+ALTER TABLE l1_batches
+    DROP COLUMN IF EXISTS seal_info;
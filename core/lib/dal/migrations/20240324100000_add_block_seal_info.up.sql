@@ -0,0 +1,4 @@
+ALTER TABLE miniblocks
+    ADD COLUMN IF NOT EXISTS seal_info JSONB;
+ALTER TABLE l1_batches
+    ADD COLUMN IF NOT EXISTS seal_info JSONB;
@@ -0,0 +1,11 @@
+-- State of WebSocket subscriptions exported by API server replicas on shutdown, so that clients reconnecting
+-- to another replica can resume their subscriptions.
+CREATE TABLE IF NOT EXISTS exported_subscriptions (
+    -- Subscription ID assigned by the exporting replica.
+    id TEXT PRIMARY KEY,
+    -- Subscription type and parameters (e.g., log filter or watched storage slots).
+    params JSONB NOT NULL,
+    -- Number of the last miniblock for which notifications were sent to the subscriber, if known.
+    last_miniblock BIGINT,
+    exported_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,11 @@
+-- Changes of the chain freeze (emergency) state. The latest event defines whether the chain is currently frozen.
+CREATE TABLE IF NOT EXISTS chain_freeze_events (
+    id BIGSERIAL PRIMARY KEY,
+    is_frozen BOOLEAN NOT NULL,
+    -- Source of the change, e.g. `admin_rpc` or `l1`.
+    source TEXT NOT NULL,
+    reason TEXT,
+    -- Hash of the L1 transaction emitting the freeze / unfreeze event; ensures that L1 events are recorded once.
+    l1_tx_hash BYTEA UNIQUE,
+    created_at TIMESTAMP NOT NULL
+);
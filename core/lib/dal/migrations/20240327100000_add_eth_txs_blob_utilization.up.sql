@@ -0,0 +1,10 @@
+-- Utilization of blobs by commit transactions publishing pubdata in blobs.
+CREATE TABLE IF NOT EXISTS eth_txs_blob_utilization (
+    eth_tx_id INT PRIMARY KEY REFERENCES eth_txs (id) ON DELETE CASCADE,
+    first_l1_batch_number BIGINT NOT NULL,
+    last_l1_batch_number BIGINT NOT NULL,
+    blob_count INT NOT NULL,
+    -- Total size of pubdata published in blobs, in bytes.
+    pubdata_bytes BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
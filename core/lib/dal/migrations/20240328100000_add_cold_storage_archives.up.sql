@@ -0,0 +1,8 @@
+-- L1 batches for which call traces and events were moved from Postgres to the object store.
+CREATE TABLE IF NOT EXISTS cold_storage_archives (
+    l1_batch_number BIGINT PRIMARY KEY,
+    object_key TEXT NOT NULL,
+    call_traces_count INT NOT NULL,
+    events_count INT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
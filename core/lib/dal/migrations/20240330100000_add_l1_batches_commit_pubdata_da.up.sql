@@ -0,0 +1,2 @@
+-- DA mode (`PubdataDA` discriminant) used to publish pubdata of the L1 batch in its commit transaction.
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS commit_pubdata_da SMALLINT;
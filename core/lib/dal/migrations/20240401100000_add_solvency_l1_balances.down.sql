@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS solvency_token_reports;
+ALTER TABLE solvency_reports DROP COLUMN IF EXISTS l1_bridge_balance;
@@ -0,0 +1,20 @@
+-- Base token balance of the L1 contracts holding bridged funds, observed by the solvency reporter after the L1 batch
+-- was sealed. NULL if L1 balances weren't checked for the batch.
+ALTER TABLE solvency_reports ADD COLUMN IF NOT EXISTS l1_bridge_balance NUMERIC(80);
+
+-- Cumulative bridge accounting for ERC-20 tokens bridged via the L2 ERC-20 bridge. Rows are only written
+-- for L1 batches changing the token accounting, or for which L1 bridge balances were checked.
+CREATE TABLE IF NOT EXISTS solvency_token_reports (
+    l1_batch_number BIGINT NOT NULL REFERENCES l1_batches (number) ON DELETE CASCADE,
+    l2_token_address BYTEA NOT NULL,
+    -- Total value deposited via the L2 ERC-20 bridge.
+    total_deposited NUMERIC(80) NOT NULL,
+    -- Total value of withdrawals initiated via the L2 ERC-20 bridge.
+    total_withdrawn NUMERIC(80) NOT NULL,
+    -- Token balance of the L1 contracts holding bridged funds. NULL if L1 balances weren't checked for the batch.
+    l1_bridge_balance NUMERIC(80),
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, l2_token_address)
+);
+CREATE INDEX IF NOT EXISTS solvency_token_reports_token_idx
+    ON solvency_token_reports (l2_token_address, l1_batch_number DESC);
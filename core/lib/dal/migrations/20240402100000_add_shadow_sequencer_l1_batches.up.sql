@@ -0,0 +1,9 @@
+-- Results of executing L1 batches by the shadow sequencer.
+CREATE TABLE IF NOT EXISTS shadow_sequencer_l1_batches (
+    l1_batch_number BIGINT PRIMARY KEY,
+    -- Candidate protocol version; NULL if the L1 batch was executed with its own protocol version.
+    protocol_version INT,
+    divergences TEXT[] NOT NULL,
+    error TEXT,
+    created_at TIMESTAMP NOT NULL
+);
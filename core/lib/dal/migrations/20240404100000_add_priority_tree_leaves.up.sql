@@ -0,0 +1,13 @@
+-- Leaves of the priority operations Merkle tree (i.e., canonical hashes of priority operations) indexed by priority op ID.
+CREATE TABLE IF NOT EXISTS priority_tree_leaves (
+    priority_op_id BIGINT PRIMARY KEY,
+    leaf_hash BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+-- Migrate priority operations received before the table was introduced.
+INSERT INTO priority_tree_leaves (priority_op_id, leaf_hash, created_at)
+SELECT priority_op_id, hash, NOW()
+FROM transactions
+WHERE priority_op_id IS NOT NULL
+ON CONFLICT (priority_op_id) DO NOTHING;
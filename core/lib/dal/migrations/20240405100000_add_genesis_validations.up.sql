@@ -0,0 +1,7 @@
+-- Successful validations of the genesis L1 batch against the L1 diamond proxy. Allows skipping the L1 check
+-- on subsequent server starts.
+CREATE TABLE IF NOT EXISTS genesis_validations (
+    diamond_proxy_addr BYTEA PRIMARY KEY,
+    genesis_batch_hash BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
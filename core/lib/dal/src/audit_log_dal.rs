@@ -0,0 +1,264 @@
+use chrono::{DateTime, Utc};
+use zksync_types::audit_log::{AuditAction, AuditLogEntry};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Append-only log of privileged operations, such as block reverts or config reloads.
+#[derive(Debug)]
+pub struct AuditLogDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl AuditLogDal<'_, '_> {
+    /// Appends an entry to the audit log and returns its ID.
+    pub async fn insert_entry(
+        &mut self,
+        action: AuditAction,
+        actor: &str,
+        params: &serde_json::Value,
+    ) -> sqlx::Result<u64> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                audit_log (action, actor, params, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            RETURNING
+                id
+            "#,
+            action.as_str(),
+            actor,
+            params
+        )
+        .instrument("insert_audit_log_entry")
+        .with_arg("action", &action)
+        .with_arg("actor", &actor)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.id as u64)
+    }
+
+    /// Returns up to `limit` audit log entries with IDs greater than `after_id`, ordered by ID.
+    pub async fn get_entries(
+        &mut self,
+        after_id: u64,
+        limit: usize,
+    ) -> sqlx::Result<Vec<AuditLogEntry>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                action,
+                actor,
+                params,
+                created_at
+            FROM
+                audit_log
+            WHERE
+                id > $1
+            ORDER BY
+                id
+            LIMIT
+                $2
+            "#,
+            after_id as i64,
+            limit as i64
+        )
+        .instrument("get_audit_log_entries")
+        .with_arg("after_id", &after_id)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(AuditLogEntry {
+                    id: row.id as u64,
+                    action: row
+                        .action
+                        .parse()
+                        .map_err(|err: &str| sqlx::Error::Decode(err.into()))?,
+                    actor: row.actor,
+                    params: row.params,
+                    created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the latest audit log entry with the specified action.
+    pub async fn get_last_entry(
+        &mut self,
+        action: AuditAction,
+    ) -> sqlx::Result<Option<AuditLogEntry>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                action,
+                actor,
+                params,
+                created_at
+            FROM
+                audit_log
+            WHERE
+                action = $1
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#,
+            action.as_str()
+        )
+        .instrument("get_last_audit_log_entry")
+        .with_arg("action", &action)
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(Some(AuditLogEntry {
+            id: row.id as u64,
+            action,
+            actor: row.actor,
+            params: row.params,
+            created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+        }))
+    }
+
+    /// Returns the ID of the last audit log entry exported to the specified destination.
+    pub async fn get_last_exported_id(&mut self, destination: &str) -> sqlx::Result<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_exported_id
+            FROM
+                audit_log_exports
+            WHERE
+                destination = $1
+            "#,
+            destination
+        )
+        .instrument("get_last_exported_audit_log_id")
+        .with_arg("destination", &destination)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| row.last_exported_id as u64))
+    }
+
+    /// Sets the ID of the last audit log entry exported to the specified destination.
+    pub async fn set_last_exported_id(
+        &mut self,
+        destination: &str,
+        last_exported_id: u64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                audit_log_exports (destination, last_exported_id, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (destination) DO
+            UPDATE
+            SET
+                last_exported_id = excluded.last_exported_id,
+                updated_at = NOW()
+            "#,
+            destination,
+            last_exported_id as i64
+        )
+        .instrument("set_last_exported_audit_log_id")
+        .with_arg("destination", &destination)
+        .with_arg("last_exported_id", &last_exported_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn appending_and_reading_audit_log() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+
+        let params = json!({ "last_l1_batch_to_keep": 5 });
+        let first_id = conn
+            .audit_log_dal()
+            .insert_entry(AuditAction::BlockRevert, "alice", &params)
+            .await
+            .unwrap();
+        let second_id = conn
+            .audit_log_dal()
+            .insert_entry(AuditAction::ConfigReload, "0x01", &json!({ "version": 3 }))
+            .await
+            .unwrap();
+        assert!(second_id > first_id);
+
+        let entries = conn.audit_log_dal().get_entries(0, 10).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, first_id);
+        assert_eq!(entries[0].action, AuditAction::BlockRevert);
+        assert_eq!(entries[0].actor, "alice");
+        assert_eq!(entries[0].params, params);
+        assert_eq!(entries[1].action, AuditAction::ConfigReload);
+
+        let entries = conn
+            .audit_log_dal()
+            .get_entries(first_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, second_id);
+
+        let last_entry = conn
+            .audit_log_dal()
+            .get_last_entry(AuditAction::ConfigReload)
+            .await
+            .unwrap()
+            .expect("no config reload entry");
+        assert_eq!(last_entry.id, second_id);
+        assert_eq!(last_entry.params, json!({ "version": 3 }));
+        let last_entry = conn
+            .audit_log_dal()
+            .get_last_entry(AuditAction::JobRequeue)
+            .await
+            .unwrap();
+        assert!(last_entry.is_none());
+
+        // The log is append-only.
+        sqlx::query("DELETE FROM audit_log")
+            .execute(conn.conn())
+            .await
+            .unwrap_err();
+        sqlx::query("UPDATE audit_log SET actor = 'mallory'")
+            .execute(conn.conn())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn tracking_audit_log_exports() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.audit_log_dal();
+
+        assert_eq!(
+            dal.get_last_exported_id("object_store").await.unwrap(),
+            None
+        );
+        dal.set_last_exported_id("object_store", 3).await.unwrap();
+        dal.set_last_exported_id("object_store", 7).await.unwrap();
+        assert_eq!(
+            dal.get_last_exported_id("object_store").await.unwrap(),
+            Some(7)
+        );
+    }
+}
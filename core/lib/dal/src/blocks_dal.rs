@@ -2,16 +2,19 @@ use std::{
     collections::HashMap,
     convert::{Into, TryInto},
     ops,
+    str::FromStr,
 };
 
 use anyhow::Context as _;
 use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use chrono::NaiveDateTime;
 use sqlx::Row;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    block::{BlockGasCount, L1BatchHeader, L1BatchTreeData, MiniblockHeader},
-    circuit::CircuitStatistic,
-    commitment::{L1BatchCommitmentArtifacts, L1BatchWithMetadata},
+    block::{BlockGasCount, L1BatchHeader, L1BatchTreeData, MiniblockHeader, SealInfo},
+    circuit::{CircuitStatistic, VmOperationStatistic},
+    commitment::{BlobCommitment, L1BatchCommitmentArtifacts, L1BatchWithMetadata},
+    pubdata_da::PubdataDA,
     zk_evm_types::LogQuery,
     Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256, U256,
 };
@@ -22,6 +25,25 @@ use crate::{
     StorageProcessor,
 };
 
+/// Hashes of confirmed L1 transactions for an L1 batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct L1BatchEthTxHashes {
+    pub commit_tx_hash: Option<H256>,
+    pub prove_tx_hash: Option<H256>,
+    pub execute_tx_hash: Option<H256>,
+}
+
+impl L1BatchEthTxHashes {
+    /// Returns the hash of the confirmed transaction for the specified action.
+    pub fn get(&self, action: AggregatedActionType) -> Option<H256> {
+        match action {
+            AggregatedActionType::Commit => self.commit_tx_hash,
+            AggregatedActionType::PublishProofOnchain => self.prove_tx_hash,
+            AggregatedActionType::Execute => self.execute_tx_hash,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BlocksDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -43,6 +65,61 @@ impl BlocksDal<'_, '_> {
         Ok(count == 0)
     }
 
+    /// Checks whether the genesis L1 batch with the specified hash was successfully validated against
+    /// the diamond proxy at `diamond_proxy_addr`.
+    pub async fn is_genesis_batch_validated(
+        &mut self,
+        diamond_proxy_addr: Address,
+        genesis_batch_hash: H256,
+    ) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                genesis_batch_hash
+            FROM
+                genesis_validations
+            WHERE
+                diamond_proxy_addr = $1
+            "#,
+            diamond_proxy_addr.as_bytes()
+        )
+        .instrument("is_genesis_batch_validated")
+        .with_arg("diamond_proxy_addr", &diamond_proxy_addr)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map_or(false, |row| {
+            row.genesis_batch_hash == genesis_batch_hash.as_bytes()
+        }))
+    }
+
+    /// Records a successful validation of the genesis L1 batch against the diamond proxy at `diamond_proxy_addr`.
+    pub async fn mark_genesis_batch_validated(
+        &mut self,
+        diamond_proxy_addr: Address,
+        genesis_batch_hash: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                genesis_validations (diamond_proxy_addr, genesis_batch_hash, created_at)
+            VALUES
+                ($1, $2, NOW())
+            ON CONFLICT (diamond_proxy_addr) DO
+            UPDATE
+            SET
+                genesis_batch_hash = excluded.genesis_batch_hash,
+                created_at = excluded.created_at
+            "#,
+            diamond_proxy_addr.as_bytes(),
+            genesis_batch_hash.as_bytes()
+        )
+        .instrument("mark_genesis_batch_validated")
+        .with_arg("diamond_proxy_addr", &diamond_proxy_addr)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn get_sealed_l1_batch_number(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
         let row = sqlx::query!(
             r#"
@@ -116,6 +193,28 @@ impl BlocksDal<'_, '_> {
         Ok(row.number.map(|num| L1BatchNumber(num as u32)))
     }
 
+    /// Returns the number of the last L1 batch with a generated commitment, or `None` if there are no such L1 batches.
+    pub async fn get_last_l1_batch_number_with_commitment(
+        &mut self,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(number) AS "number"
+            FROM
+                l1_batches
+            WHERE
+                commitment IS NOT NULL
+            "#
+        )
+        .instrument("get_last_l1_batch_number_with_commitment")
+        .report_latency()
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.number.map(|num| L1BatchNumber(num as u32)))
+    }
+
     pub async fn get_next_l1_batch_ready_for_commitment_generation(
         &mut self,
     ) -> sqlx::Result<Option<L1BatchNumber>> {
@@ -142,6 +241,38 @@ impl BlocksDal<'_, '_> {
         Ok(row.map(|row| L1BatchNumber(row.number as u32)))
     }
 
+    /// Returns numbers of L1 batches preceding `next_l1_batch` that have no tree data (root hash and
+    /// rollup leaf index), in ascending order.
+    pub async fn get_l1_batches_without_tree_data(
+        &mut self,
+        next_l1_batch: L1BatchNumber,
+    ) -> sqlx::Result<Vec<L1BatchNumber>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                number
+            FROM
+                l1_batches
+            WHERE
+                hash IS NULL
+                AND number < $1
+            ORDER BY
+                number
+            "#,
+            next_l1_batch.0 as i64
+        )
+        .instrument("get_l1_batches_without_tree_data")
+        .with_arg("next_l1_batch", &next_l1_batch)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.number as u32))
+            .collect())
+    }
+
     /// Returns the number of the earliest L1 batch with metadata (= state hash) present in the DB,
     /// or `None` if there are no such L1 batches.
     pub async fn get_earliest_l1_batch_number_with_metadata(
@@ -356,6 +487,103 @@ impl BlocksDal<'_, '_> {
         Ok(Some(storage_refunds))
     }
 
+    /// Saves aggregated statistics of VM operations performed during the execution of the specified L1 batch.
+    pub async fn save_l1_batch_operation_statistic(
+        &mut self,
+        number: L1BatchNumber,
+        statistic: &VmOperationStatistic,
+    ) -> anyhow::Result<()> {
+        let statistic =
+            serde_json::to_value(statistic).expect("failed to serialize VM operation statistic");
+        let update_result = sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                operation_statistic = $1,
+                updated_at = NOW()
+            WHERE
+                number = $2
+            "#,
+            statistic,
+            number.0 as i64
+        )
+        .instrument("save_l1_batch_operation_statistic")
+        .with_arg("number", &number)
+        .execute(self.storage)
+        .await?;
+
+        anyhow::ensure!(
+            update_result.rows_affected() == 1,
+            "L1 batch #{number} doesn't exist"
+        );
+        Ok(())
+    }
+
+    /// Returns aggregated statistics of VM operations for the specified L1 batch, or `None` if the batch
+    /// doesn't exist or was sealed before the statistics were collected.
+    pub async fn get_l1_batch_operation_statistic(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<Option<VmOperationStatistic>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                operation_statistic
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("get_l1_batch_operation_statistic")
+        .report_latency()
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
+        .await?
+        else {
+            return Ok(None);
+        };
+        let Some(statistic) = row.operation_statistic else {
+            return Ok(None);
+        };
+
+        let statistic = serde_json::from_value(statistic)
+            .context("invalid VM operation statistic in Postgres")?;
+        Ok(Some(statistic))
+    }
+
+    /// Saves the seal reason and capacity statistics for the specified L1 batch.
+    pub async fn save_l1_batch_seal_info(
+        &mut self,
+        number: L1BatchNumber,
+        seal_info: &SealInfo,
+    ) -> anyhow::Result<()> {
+        let seal_info = serde_json::to_value(seal_info).expect("failed to serialize seal info");
+        let update_result = sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                seal_info = $1,
+                updated_at = NOW()
+            WHERE
+                number = $2
+            "#,
+            seal_info,
+            number.0 as i64
+        )
+        .instrument("save_l1_batch_seal_info")
+        .with_arg("number", &number)
+        .execute(self.storage)
+        .await?;
+
+        anyhow::ensure!(
+            update_result.rows_affected() == 1,
+            "L1 batch #{number} doesn't exist"
+        );
+        Ok(())
+    }
+
     pub async fn get_events_queue(
         &mut self,
         number: L1BatchNumber,
@@ -447,6 +675,61 @@ impl BlocksDal<'_, '_> {
         Ok(())
     }
 
+    /// Records the DA mode used to publish pubdata of the specified L1 batches in their commit transaction.
+    pub async fn set_commit_pubdata_da(
+        &mut self,
+        number_range: ops::RangeInclusive<L1BatchNumber>,
+        pubdata_da: PubdataDA,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                commit_pubdata_da = $1,
+                updated_at = NOW()
+            WHERE
+                number BETWEEN $2 AND $3
+            "#,
+            pubdata_da as i16,
+            i64::from(number_range.start().0),
+            i64::from(number_range.end().0)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the DA mode recorded for the commit transaction of the specified L1 batch, if any.
+    pub async fn get_commit_pubdata_da(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<PubdataDA>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                commit_pubdata_da
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            i64::from(number.0)
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        let Some(value) = row.and_then(|row| row.commit_pubdata_da) else {
+            return Ok(None);
+        };
+        let pubdata_da = u8::try_from(value)
+            .ok()
+            .and_then(|value| PubdataDA::try_from(value).ok())
+            .ok_or_else(|| {
+                sqlx::Error::Decode(format!("invalid commit pubdata DA: {value}").into())
+            })?;
+        Ok(Some(pubdata_da))
+    }
+
     pub async fn insert_l1_batch(
         &mut self,
         header: &L1BatchHeader,
@@ -581,6 +864,25 @@ impl BlocksDal<'_, '_> {
     pub async fn insert_miniblock(
         &mut self,
         miniblock_header: &MiniblockHeader,
+    ) -> anyhow::Result<()> {
+        self.insert_miniblock_inner(miniblock_header, None).await
+    }
+
+    /// Inserts a miniblock header together with the seal reason and capacity statistics for the miniblock.
+    pub async fn insert_miniblock_with_seal_info(
+        &mut self,
+        miniblock_header: &MiniblockHeader,
+        seal_info: &SealInfo,
+    ) -> anyhow::Result<()> {
+        let seal_info = serde_json::to_value(seal_info).expect("failed to serialize seal info");
+        self.insert_miniblock_inner(miniblock_header, Some(seal_info))
+            .await
+    }
+
+    async fn insert_miniblock_inner(
+        &mut self,
+        miniblock_header: &MiniblockHeader,
+        seal_info: Option<serde_json::Value>,
     ) -> anyhow::Result<()> {
         let base_fee_per_gas = BigDecimal::from_u64(miniblock_header.base_fee_per_gas)
             .context("base_fee_per_gas should fit in u64")?;
@@ -604,11 +906,33 @@ impl BlocksDal<'_, '_> {
                     protocol_version,
                     virtual_blocks,
                     fair_pubdata_price,
+                    gas_limit,
+                    seal_info,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, NOW(), NOW())
+                (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6,
+                    $7,
+                    $8,
+                    $9,
+                    $10,
+                    $11,
+                    $12,
+                    $13,
+                    $14,
+                    $15,
+                    $16,
+                    $17,
+                    NOW(),
+                    NOW()
+                )
             "#,
             miniblock_header.number.0 as i64,
             miniblock_header.timestamp as i64,
@@ -631,6 +955,8 @@ impl BlocksDal<'_, '_> {
             miniblock_header.protocol_version.map(|v| v as i32),
             miniblock_header.virtual_blocks as i64,
             miniblock_header.batch_fee_input.fair_pubdata_price() as i64,
+            miniblock_header.gas_limit.map(|limit| limit as i64),
+            seal_info,
         )
         .execute(self.storage.conn())
         .await?;
@@ -658,7 +984,8 @@ impl BlocksDal<'_, '_> {
                 default_aa_code_hash,
                 protocol_version,
                 virtual_blocks,
-                fair_pubdata_price
+                fair_pubdata_price,
+                gas_limit
             FROM
                 miniblocks
             ORDER BY
@@ -704,7 +1031,8 @@ impl BlocksDal<'_, '_> {
                 default_aa_code_hash,
                 protocol_version,
                 virtual_blocks,
-                fair_pubdata_price
+                fair_pubdata_price,
+                gas_limit
             FROM
                 miniblocks
             WHERE
@@ -890,18 +1218,47 @@ impl BlocksDal<'_, '_> {
             );
         }
 
+        let (blob_linear_hashes, blob_commitments): (Option<Vec<_>>, Option<Vec<_>>) =
+            match &commitment_artifacts.blob_commitments {
+                Some(blobs) => (
+                    Some(
+                        blobs
+                            .iter()
+                            .map(|blob| blob.linear_hash.0.to_vec())
+                            .collect(),
+                    ),
+                    Some(
+                        blobs
+                            .iter()
+                            .map(|blob| blob.commitment.0.to_vec())
+                            .collect(),
+                    ),
+                ),
+                None => (None, None),
+            };
         sqlx::query!(
             r#"
             INSERT INTO
-                commitments (l1_batch_number, events_queue_commitment, bootloader_initial_content_commitment)
+                commitments (
+                    l1_batch_number,
+                    events_queue_commitment,
+                    bootloader_initial_content_commitment,
+                    blob_linear_hashes,
+                    blob_commitments
+                )
             VALUES
-                ($1, $2, $3)
+                ($1, $2, $3, $4, $5)
             ON CONFLICT (l1_batch_number) DO NOTHING
             "#,
             number.0 as i64,
-            commitment_artifacts.aux_commitments.map(|a| a.events_queue_commitment.0.to_vec()),
-            commitment_artifacts.aux_commitments
+            commitment_artifacts
+                .aux_commitments
+                .map(|a| a.events_queue_commitment.0.to_vec()),
+            commitment_artifacts
+                .aux_commitments
                 .map(|a| a.bootloader_initial_content_commitment.0.to_vec()),
+            blob_linear_hashes.as_deref(),
+            blob_commitments.as_deref(),
         )
         .instrument("save_batch_aux_commitments")
         .with_arg("number", &number)
@@ -1089,6 +1446,88 @@ impl BlocksDal<'_, '_> {
         .map(|row| L1BatchNumber(row.number as u32)))
     }
 
+    /// Returns hashes of confirmed commit / prove / execute L1 transactions for all L1 batches in the specified range.
+    pub async fn get_l1_batch_eth_tx_hashes(
+        &mut self,
+        numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> sqlx::Result<HashMap<L1BatchNumber, L1BatchEthTxHashes>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batches.number,
+                commit_tx.tx_hash AS "commit_tx_hash?",
+                prove_tx.tx_hash AS "prove_tx_hash?",
+                execute_tx.tx_hash AS "execute_tx_hash?"
+            FROM
+                l1_batches
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                l1_batches.number BETWEEN $1 AND $2
+            "#,
+            i64::from(numbers.start().0),
+            i64::from(numbers.end().0)
+        )
+        .instrument("get_l1_batch_eth_tx_hashes")
+        .with_arg("numbers", &numbers)
+        .fetch_all(self.storage)
+        .await?;
+
+        let parse_hash = |hash: Option<String>| {
+            hash.map(|hash| H256::from_str(&hash).expect("Incorrect tx hash"))
+        };
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let hashes = L1BatchEthTxHashes {
+                    commit_tx_hash: parse_hash(row.commit_tx_hash),
+                    prove_tx_hash: parse_hash(row.prove_tx_hash),
+                    execute_tx_hash: parse_hash(row.execute_tx_hash),
+                };
+                (L1BatchNumber(row.number as u32), hashes)
+            })
+            .collect())
+    }
+
+    /// Returns the number of the last L1 batch for which an Ethereum execute tx was confirmed before the specified time.
+    pub async fn get_number_of_last_l1_batch_executed_before(
+        &mut self,
+        executed_before: NaiveDateTime,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                number
+            FROM
+                l1_batches
+                JOIN eth_txs_history AS execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id)
+            WHERE
+                execute_tx.confirmed_at IS NOT NULL
+                AND execute_tx.confirmed_at < $1
+            ORDER BY
+                number DESC
+            LIMIT
+                1
+            "#,
+            executed_before
+        )
+        .instrument("get_number_of_last_l1_batch_executed_before")
+        .with_arg("executed_before", &executed_before)
+        .fetch_optional(self.storage)
+        .await?
+        .map(|row| L1BatchNumber(row.number as u32)))
+    }
+
     /// This method returns batches that are confirmed on L1. That is, it doesn't wait for the proofs to be generated.
     ///
     /// # Params:
@@ -2068,15 +2507,44 @@ impl BlocksDal<'_, '_> {
         .map(|row| row.timestamp as u64))
     }
 
-    pub async fn set_protocol_version_for_pending_miniblocks(
+    /// Returns the range of miniblocks with timestamps in the specified range, or `None` if there are no such miniblocks.
+    pub async fn get_miniblock_range_by_timestamps(
         &mut self,
-        id: ProtocolVersionId,
-    ) -> sqlx::Result<()> {
-        sqlx::query!(
+        timestamps: ops::RangeInclusive<u64>,
+    ) -> sqlx::Result<Option<ops::RangeInclusive<MiniblockNumber>>> {
+        let row = sqlx::query!(
             r#"
-            UPDATE miniblocks
-            SET
-                protocol_version = $1
+            SELECT
+                MIN(number) AS "min_number",
+                MAX(number) AS "max_number"
+            FROM
+                miniblocks
+            WHERE
+                timestamp BETWEEN $1 AND $2
+            "#,
+            *timestamps.start() as i64,
+            *timestamps.end() as i64
+        )
+        .instrument("get_miniblock_range_by_timestamps")
+        .with_arg("timestamps", &timestamps)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row
+            .min_number
+            .zip(row.max_number)
+            .map(|(min, max)| MiniblockNumber(min as u32)..=MiniblockNumber(max as u32)))
+    }
+
+    pub async fn set_protocol_version_for_pending_miniblocks(
+        &mut self,
+        id: ProtocolVersionId,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE miniblocks
+            SET
+                protocol_version = $1
             WHERE
                 l1_batch_number IS NULL
             "#,
@@ -2146,6 +2614,144 @@ impl BlocksDal<'_, '_> {
         .await?
         .map(|row| row.virtual_blocks as u32))
     }
+
+    /// Returns uncommitted L1 batches with pubdata for which KZG info is not precomputed yet. Only L1 batches
+    /// with a generated commitment are returned since KZG info may be persisted by the commitment generator.
+    pub async fn get_l1_batches_without_kzg_info(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<(L1BatchNumber, Vec<u8>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batches.number,
+                l1_batches.pubdata_input AS "pubdata_input!"
+            FROM
+                l1_batches
+                LEFT JOIN l1_batches_kzg_info ON l1_batches_kzg_info.l1_batch_number = l1_batches.number
+            WHERE
+                l1_batches.eth_commit_tx_id IS NULL
+                AND l1_batches.pubdata_input IS NOT NULL
+                AND l1_batches.commitment IS NOT NULL
+                AND l1_batches_kzg_info.l1_batch_number IS NULL
+            ORDER BY
+                l1_batches.number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (L1BatchNumber(row.number as u32), row.pubdata_input))
+            .collect())
+    }
+
+    /// Saves serialized KZG info for each blob of the L1 batch pubdata.
+    pub async fn save_l1_batch_kzg_info(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        kzg_info: &[Vec<u8>],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batches_kzg_info (l1_batch_number, kzg_info, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                kzg_info = $2,
+                updated_at = NOW()
+            "#,
+            l1_batch_number.0 as i64,
+            kzg_info
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_l1_batch_kzg_info(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<Vec<Vec<u8>>>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                kzg_info
+            FROM
+                l1_batches_kzg_info
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(|row| row.kzg_info))
+    }
+
+    /// Returns commitments to the pubdata blobs of the specified L1 batch. Returns `None` if the batch doesn't have
+    /// a commitment yet, or if it doesn't use blobs (i.e., was produced by a protocol version before 1.4.2).
+    pub async fn get_l1_batch_blob_commitments(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<Vec<BlobCommitment>>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                blob_linear_hashes,
+                blob_commitments
+            FROM
+                commitments
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let (Some(linear_hashes), Some(commitments)) =
+            (row.blob_linear_hashes, row.blob_commitments)
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            linear_hashes
+                .iter()
+                .zip(&commitments)
+                .map(|(linear_hash, commitment)| BlobCommitment {
+                    linear_hash: H256::from_slice(linear_hash),
+                    commitment: H256::from_slice(commitment),
+                })
+                .collect(),
+        ))
+    }
+
+    /// Removes precomputed KZG info for L1 batches that already have a commit transaction, since it's not needed
+    /// once the transaction is created. Returns the number of removed rows.
+    pub async fn prune_committed_kzg_info(&mut self) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM l1_batches_kzg_info USING l1_batches
+            WHERE
+                l1_batches.number = l1_batches_kzg_info.l1_batch_number
+                AND l1_batches.eth_commit_tx_id IS NOT NULL
+            "#
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
 }
 
 /// Temporary methods for migrating `fee_account_address`.
@@ -2345,6 +2951,7 @@ impl BlocksDal<'_, '_> {
 mod tests {
     use zksync_contracts::BaseSystemContractsHashes;
     use zksync_types::{
+        commitment::L1BatchCommitmentHash,
         l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
         Address, ProtocolVersion, ProtocolVersionId,
     };
@@ -2408,6 +3015,214 @@ mod tests {
             .is_none());
     }
 
+    #[tokio::test]
+    async fn saving_blob_commitments() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        let blob_commitments = vec![
+            BlobCommitment {
+                linear_hash: H256::repeat_byte(1),
+                commitment: H256::repeat_byte(2),
+            },
+            BlobCommitment {
+                linear_hash: H256::zero(),
+                commitment: H256::zero(),
+            },
+        ];
+        let artifacts = L1BatchCommitmentArtifacts {
+            commitment_hash: L1BatchCommitmentHash {
+                pass_through_data: H256::repeat_byte(3),
+                aux_output: H256::repeat_byte(4),
+                meta_parameters: H256::repeat_byte(5),
+                commitment: H256::repeat_byte(6),
+            },
+            l2_l1_merkle_root: H256::zero(),
+            compressed_state_diffs: Some(vec![]),
+            compressed_initial_writes: None,
+            compressed_repeated_writes: None,
+            zkporter_is_available: false,
+            aux_commitments: None,
+            blob_commitments: Some(blob_commitments.clone()),
+        };
+        conn.blocks_dal()
+            .save_l1_batch_commitment_artifacts(L1BatchNumber(1), &artifacts)
+            .await
+            .unwrap();
+
+        let loaded = conn
+            .blocks_dal()
+            .get_l1_batch_blob_commitments(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(loaded, Some(blob_commitments));
+        let loaded = conn
+            .blocks_dal()
+            .get_l1_batch_blob_commitments(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(loaded, None);
+    }
+
+    #[tokio::test]
+    async fn saving_operation_statistic() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        let loaded = conn
+            .blocks_dal()
+            .get_l1_batch_operation_statistic(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(loaded, None);
+
+        let statistic = VmOperationStatistic {
+            arithmetic_opcodes: 1_000,
+            far_calls: 10,
+            storage_reads: 20,
+            storage_writes: 5,
+            keccak256_calls: 3,
+            decommitments: 2,
+            decommitted_words: 500,
+            unique_storage_reads: 15,
+            unique_storage_writes: 4,
+            ..VmOperationStatistic::default()
+        };
+        conn.blocks_dal()
+            .save_l1_batch_operation_statistic(L1BatchNumber(1), &statistic)
+            .await
+            .unwrap();
+        let loaded = conn
+            .blocks_dal()
+            .get_l1_batch_operation_statistic(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(loaded, Some(statistic));
+
+        conn.blocks_dal()
+            .save_l1_batch_operation_statistic(L1BatchNumber(2), &statistic)
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn getting_last_l1_batch_executed_before() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let now = chrono::Utc::now();
+        for number in 1..=3 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+        for (number, days_ago) in [(1, 10), (2, 3)] {
+            conn.eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(number),
+                    AggregatedActionType::Execute,
+                    H256::from_low_u64_be(number.into()),
+                    now - chrono::Duration::days(days_ago),
+                )
+                .await
+                .unwrap();
+        }
+
+        for (days_ago, expected) in [(0, Some(2)), (5, Some(1)), (20, None)] {
+            let executed_before = (now - chrono::Duration::days(days_ago)).naive_utc();
+            let number = conn
+                .blocks_dal()
+                .get_number_of_last_l1_batch_executed_before(executed_before)
+                .await
+                .unwrap();
+            assert_eq!(number, expected.map(L1BatchNumber), "{days_ago}");
+        }
+    }
+
+    #[tokio::test]
+    async fn getting_l1_batch_eth_tx_hashes() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=2 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+        for (action, hash_byte) in [
+            (AggregatedActionType::Commit, 1),
+            (AggregatedActionType::Execute, 3),
+        ] {
+            conn.eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(1),
+                    action,
+                    H256::repeat_byte(hash_byte),
+                    chrono::Utc::now(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let hashes = conn
+            .blocks_dal()
+            .get_l1_batch_eth_tx_hashes(L1BatchNumber(0)..=L1BatchNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(hashes.len(), 2);
+        let expected_hashes = L1BatchEthTxHashes {
+            commit_tx_hash: Some(H256::repeat_byte(1)),
+            prove_tx_hash: None,
+            execute_tx_hash: Some(H256::repeat_byte(3)),
+        };
+        assert_eq!(hashes[&L1BatchNumber(1)], expected_hashes);
+        assert_eq!(hashes[&L1BatchNumber(2)], L1BatchEthTxHashes::default());
+    }
+
     #[tokio::test]
     async fn getting_predicted_gas() {
         let pool = ConnectionPool::test_pool().await;
@@ -2468,6 +3283,57 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn recording_commit_pubdata_da() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100 * u64::from(number),
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::default(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+
+        let pubdata_da = conn
+            .blocks_dal()
+            .get_commit_pubdata_da(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(pubdata_da, None);
+
+        conn.blocks_dal()
+            .set_commit_pubdata_da(L1BatchNumber(1)..=L1BatchNumber(2), PubdataDA::Calldata)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .set_commit_pubdata_da(L1BatchNumber(2)..=L1BatchNumber(2), PubdataDA::Blobs)
+            .await
+            .unwrap();
+        for (number, expected) in [(1, PubdataDA::Calldata), (2, PubdataDA::Blobs)] {
+            let pubdata_da = conn
+                .blocks_dal()
+                .get_commit_pubdata_da(L1BatchNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(pubdata_da, Some(expected));
+        }
+        let pubdata_da = conn
+            .blocks_dal()
+            .get_commit_pubdata_da(L1BatchNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(pubdata_da, None);
+    }
+
     #[allow(deprecated)] // that's the whole point
     #[tokio::test]
     async fn checking_fee_account_address_in_l1_batches() {
@@ -2607,4 +3473,80 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn saving_and_pruning_kzg_info() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        header.pubdata_input = Some(vec![1; 32]);
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        // KZG info is only precomputed for batches with a generated commitment.
+        let batches = conn
+            .blocks_dal()
+            .get_l1_batches_without_kzg_info(10)
+            .await
+            .unwrap();
+        assert!(batches.is_empty());
+
+        let artifacts = L1BatchCommitmentArtifacts {
+            commitment_hash: L1BatchCommitmentHash {
+                pass_through_data: H256::repeat_byte(3),
+                aux_output: H256::repeat_byte(4),
+                meta_parameters: H256::repeat_byte(5),
+                commitment: H256::repeat_byte(6),
+            },
+            l2_l1_merkle_root: H256::zero(),
+            compressed_state_diffs: Some(vec![]),
+            compressed_initial_writes: None,
+            compressed_repeated_writes: None,
+            zkporter_is_available: false,
+            aux_commitments: None,
+            blob_commitments: None,
+        };
+        conn.blocks_dal()
+            .save_l1_batch_commitment_artifacts(L1BatchNumber(1), &artifacts)
+            .await
+            .unwrap();
+        let batches = conn
+            .blocks_dal()
+            .get_l1_batches_without_kzg_info(10)
+            .await
+            .unwrap();
+        assert_eq!(batches, [(L1BatchNumber(1), vec![1; 32])]);
+
+        let kzg_info = vec![vec![2; 64], vec![3; 64]];
+        conn.blocks_dal()
+            .save_l1_batch_kzg_info(L1BatchNumber(1), &kzg_info)
+            .await
+            .unwrap();
+        let loaded = conn
+            .blocks_dal()
+            .get_l1_batch_kzg_info(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(loaded, Some(kzg_info));
+        let batches = conn
+            .blocks_dal()
+            .get_l1_batches_without_kzg_info(10)
+            .await
+            .unwrap();
+        assert!(batches.is_empty());
+
+        // The batch isn't committed yet, so its KZG info must be retained.
+        let pruned = conn.blocks_dal().prune_committed_kzg_info().await.unwrap();
+        assert_eq!(pruned, 0);
+    }
 }
@@ -15,13 +15,15 @@ use crate::{
     models::{
         storage_block::{
             bind_block_where_sql_params, web3_block_number_to_sql, web3_block_where_sql,
-            ResolvedL1BatchForMiniblock, StorageBlockDetails, StorageL1BatchDetails,
+            ResolvedL1BatchForMiniblock, StorageBlockDetails, StorageGasPriceHistoryEntry,
+            StorageL1BatchDetails,
         },
         storage_transaction::{extract_web3_transaction, web3_transaction_select_sql, CallTrace},
     },
     StorageProcessor,
 };
 
+/// Gas limit reported for miniblocks produced without an enforced limit.
 const BLOCK_GAS_LIMIT: u32 = u32::MAX;
 
 #[derive(Debug)]
@@ -49,6 +51,7 @@ impl BlocksWeb3Dal<'_, '_> {
                 miniblocks.l1_batch_number,
                 miniblocks.timestamp,
                 miniblocks.base_fee_per_gas,
+                miniblocks.gas_limit as block_gas_limit,
                 prev_miniblock.hash as parent_hash,
                 l1_batches.timestamp as l1_batch_timestamp,
                 transactions.gas_limit as gas_limit,
@@ -90,6 +93,9 @@ impl BlocksWeb3Dal<'_, '_> {
                     .try_get("parent_hash")
                     .map_or_else(|_| H256::zero(), H256::from_slice);
                 let base_fee_per_gas = db_row.get::<BigDecimal, &str>("base_fee_per_gas");
+                let gas_limit = db_row
+                    .get::<Option<i64>, &str>("block_gas_limit")
+                    .map_or(BLOCK_GAS_LIMIT.into(), U256::from);
 
                 api::Block {
                     hash,
@@ -97,7 +103,7 @@ impl BlocksWeb3Dal<'_, '_> {
                     uncles_hash: EMPTY_UNCLES_HASH,
                     number,
                     l1_batch_number,
-                    gas_limit: BLOCK_GAS_LIMIT.into(),
+                    gas_limit,
                     base_fee_per_gas: bigdecimal_to_u256(base_fee_per_gas),
                     timestamp: db_row.get::<i64, &str>("timestamp").into(),
                     l1_batch_timestamp,
@@ -181,7 +187,8 @@ impl BlocksWeb3Dal<'_, '_> {
             SELECT
                 hash,
                 number,
-                timestamp
+                timestamp,
+                gas_limit
             FROM
                 miniblocks
             WHERE
@@ -204,7 +211,7 @@ impl BlocksWeb3Dal<'_, '_> {
             receipts_root: H256::zero(),
             number: Some(U64::from(row.number)),
             gas_used: U256::zero(),
-            gas_limit: U256::zero(),
+            gas_limit: row.gas_limit.map_or(BLOCK_GAS_LIMIT.into(), U256::from),
             base_fee_per_gas: None,
             extra_data: Bytes::default(),
             // TODO: include logs
@@ -506,6 +513,46 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(result)
     }
 
+    /// Returns fee inputs for at most `block_count` miniblocks starting from `from_block`
+    /// in ascending order of miniblock numbers.
+    pub async fn get_gas_price_history(
+        &mut self,
+        from_block: MiniblockNumber,
+        block_count: u64,
+    ) -> sqlx::Result<Vec<api::GasPriceHistoryEntry>> {
+        let rows = sqlx::query_as!(
+            StorageGasPriceHistoryEntry,
+            r#"
+            SELECT
+                number,
+                l1_batch_number,
+                timestamp,
+                base_fee_per_gas,
+                l1_gas_price,
+                l2_fair_gas_price,
+                fair_pubdata_price
+            FROM
+                miniblocks
+            WHERE
+                number >= $1
+            ORDER BY
+                number
+            LIMIT
+                $2
+            "#,
+            from_block.0 as i64,
+            block_count as i64
+        )
+        .instrument("get_gas_price_history")
+        .with_arg("from_block", &from_block)
+        .with_arg("block_count", &block_count)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     pub async fn get_block_details(
         &mut self,
         block_number: MiniblockNumber,
@@ -539,7 +586,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 miniblocks.bootloader_code_hash,
                 miniblocks.default_aa_code_hash,
                 miniblocks.protocol_version,
-                miniblocks.fee_account_address
+                miniblocks.fee_account_address,
+                miniblocks.seal_info
             FROM
                 miniblocks
                 LEFT JOIN l1_batches ON miniblocks.l1_batch_number = l1_batches.number
@@ -614,7 +662,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 mb.l1_gas_price,
                 mb.l2_fair_gas_price,
                 l1_batches.bootloader_code_hash,
-                l1_batches.default_aa_code_hash
+                l1_batches.default_aa_code_hash,
+                l1_batches.seal_info
             FROM
                 l1_batches
                 INNER JOIN mb ON TRUE
@@ -647,9 +696,13 @@ impl BlocksWeb3Dal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
+    use zksync_contracts::BaseSystemContractsHashes;
     use zksync_types::{
-        block::{MiniblockHasher, MiniblockHeader},
+        block::{L1BatchHeader, MiniblockHasher, MiniblockHeader, SealInfo},
         fee::TransactionExecutionMetrics,
+        fee_model::BatchFeeInput,
         Address, MiniblockNumber, ProtocolVersion, ProtocolVersionId,
     };
 
@@ -697,16 +750,34 @@ mod tests {
             assert!(block.transactions.is_empty());
             assert_eq!(block.number, U64::zero());
             assert_eq!(block.hash, block_hash);
+            assert_eq!(block.gas_limit, u32::MAX.into());
 
             let tx_count = conn.blocks_web3_dal().get_block_tx_count(block_id).await;
             assert_eq!(tx_count.unwrap(), Some((MiniblockNumber(0), 8.into())));
         }
 
-        let non_existing_block_hash = MiniblockHasher::new(MiniblockNumber(1), 1, H256::zero())
+        let header = MiniblockHeader {
+            gas_limit: Some(80_000_000),
+            ..create_miniblock_header(1)
+        };
+        conn.blocks_dal().insert_miniblock(&header).await.unwrap();
+        let block = conn
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(
+                api::BlockId::Number(api::BlockNumber::Number(1.into())),
+                false,
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.gas_limit, 80_000_000.into());
+
+        let non_existing_block_hash = MiniblockHasher::new(MiniblockNumber(2), 2, H256::zero())
             .finalize(ProtocolVersionId::latest());
         let non_existing_block_ids = [
             api::BlockId::Number(api::BlockNumber::Pending),
-            api::BlockId::Number(api::BlockNumber::Number(1.into())),
+            api::BlockId::Number(api::BlockNumber::Number(2.into())),
             api::BlockId::Hash(non_existing_block_hash),
         ];
         for block_id in non_existing_block_ids {
@@ -902,4 +973,107 @@ mod tests {
             assert_eq!(*trace, expected_trace);
         }
     }
+
+    #[tokio::test]
+    async fn getting_block_details_with_seal_info() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let miniblock_seal_info = SealInfo {
+            reason: Some("miniblock_timeout".to_owned()),
+            capacity_filled: BTreeMap::from([("miniblock_gas_limit".to_owned(), 12.5)]),
+        };
+        conn.blocks_dal()
+            .insert_miniblock_with_seal_info(&create_miniblock_header(2), &miniblock_seal_info)
+            .await
+            .unwrap();
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&l1_batch_header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let block_details = conn
+            .blocks_web3_dal()
+            .get_block_details(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block_details.base.seal_info, None);
+        let block_details = conn
+            .blocks_web3_dal()
+            .get_block_details(MiniblockNumber(2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block_details.base.seal_info, Some(miniblock_seal_info));
+
+        let l1_batch_seal_info = SealInfo {
+            reason: Some("slots".to_owned()),
+            capacity_filled: BTreeMap::from([("slots".to_owned(), 100.0), ("gas".to_owned(), 5.0)]),
+        };
+        conn.blocks_dal()
+            .save_l1_batch_seal_info(L1BatchNumber(1), &l1_batch_seal_info)
+            .await
+            .unwrap();
+
+        let l1_batch_details = conn
+            .blocks_web3_dal()
+            .get_l1_batch_details(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(l1_batch_details.base.seal_info, Some(l1_batch_seal_info));
+    }
+
+    #[tokio::test]
+    async fn getting_gas_price_history() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..3 {
+            let header = MiniblockHeader {
+                batch_fee_input: BatchFeeInput::pubdata_independent(100, 200 + number, 300),
+                ..create_miniblock_header(number as u32)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await.unwrap();
+        }
+
+        let history = conn
+            .blocks_web3_dal()
+            .get_gas_price_history(MiniblockNumber(1), 10)
+            .await
+            .unwrap();
+        let numbers: Vec<_> = history.iter().map(|entry| entry.number).collect();
+        assert_eq!(numbers, [MiniblockNumber(1), MiniblockNumber(2)]);
+        assert_eq!(history[1].l1_batch_number, None);
+        assert_eq!(history[1].l1_gas_price, 100.into());
+        assert_eq!(history[1].fair_l2_gas_price, 202.into());
+        assert_eq!(history[1].fair_pubdata_price, 300.into());
+
+        let history = conn
+            .blocks_web3_dal()
+            .get_gas_price_history(MiniblockNumber(0), 1)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].number, MiniblockNumber(0));
+    }
 }
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use zksync_types::{
+    chain_freeze::{ChainFreezeEvent, ChainFreezeSource},
+    H256,
+};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Log of chain freeze state changes.
+#[derive(Debug)]
+pub struct ChainFreezeDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ChainFreezeDal<'_, '_> {
+    /// Records a change of the chain freeze state and returns the recorded event. If `l1_tx_hash` is specified
+    /// and an event from the same L1 transaction is already recorded, returns `None`.
+    pub async fn insert_event(
+        &mut self,
+        is_frozen: bool,
+        source: ChainFreezeSource,
+        reason: Option<&str>,
+        l1_tx_hash: Option<H256>,
+    ) -> sqlx::Result<Option<ChainFreezeEvent>> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                chain_freeze_events (is_frozen, source, reason, l1_tx_hash, created_at)
+            VALUES
+                ($1, $2, $3, $4, NOW())
+            ON CONFLICT (l1_tx_hash) DO NOTHING
+            RETURNING
+                id,
+                created_at
+            "#,
+            is_frozen,
+            source.as_str(),
+            reason,
+            l1_tx_hash.as_ref().map(H256::as_bytes)
+        )
+        .instrument("insert_chain_freeze_event")
+        .with_arg("is_frozen", &is_frozen)
+        .with_arg("source", &source)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| ChainFreezeEvent {
+            id: row.id as u64,
+            is_frozen,
+            source,
+            reason: reason.map(str::to_owned),
+            l1_tx_hash,
+            created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+        }))
+    }
+
+    /// Returns the latest chain freeze event, or `None` if the chain freeze state has never changed.
+    pub async fn get_latest_event(&mut self) -> sqlx::Result<Option<ChainFreezeEvent>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                is_frozen,
+                source,
+                reason,
+                l1_tx_hash,
+                created_at
+            FROM
+                chain_freeze_events
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_latest_chain_freeze_event")
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(Some(ChainFreezeEvent {
+            id: row.id as u64,
+            is_frozen: row.is_frozen,
+            source: row
+                .source
+                .parse()
+                .map_err(|err: &str| sqlx::Error::Decode(err.into()))?,
+            reason: row.reason,
+            l1_tx_hash: row.l1_tx_hash.as_deref().map(H256::from_slice),
+            created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+        }))
+    }
+
+    /// Checks whether the chain is currently frozen.
+    pub async fn is_chain_frozen(&mut self) -> sqlx::Result<bool> {
+        let latest_event = self.get_latest_event().await?;
+        Ok(latest_event.map_or(false, |event| event.is_frozen))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn recording_chain_freeze_events() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.chain_freeze_dal();
+        assert_eq!(dal.get_latest_event().await.unwrap(), None);
+        assert!(!dal.is_chain_frozen().await.unwrap());
+
+        let event = dal
+            .insert_event(true, ChainFreezeSource::AdminRpc, Some("incident"), None)
+            .await
+            .unwrap()
+            .expect("event not recorded");
+        assert!(event.is_frozen);
+        assert_eq!(dal.get_latest_event().await.unwrap(), Some(event.clone()));
+        assert!(dal.is_chain_frozen().await.unwrap());
+
+        let l1_tx_hash = H256::repeat_byte(1);
+        let unfreeze_event = dal
+            .insert_event(false, ChainFreezeSource::L1, None, Some(l1_tx_hash))
+            .await
+            .unwrap()
+            .expect("event not recorded");
+        assert!(unfreeze_event.id > event.id);
+        assert_eq!(unfreeze_event.l1_tx_hash, Some(l1_tx_hash));
+        assert_eq!(dal.get_latest_event().await.unwrap(), Some(unfreeze_event));
+        assert!(!dal.is_chain_frozen().await.unwrap());
+
+        // Events from the same L1 transaction are only recorded once.
+        let duplicate_event = dal
+            .insert_event(true, ChainFreezeSource::L1, None, Some(l1_tx_hash))
+            .await
+            .unwrap();
+        assert_eq!(duplicate_event, None);
+        assert!(!dal.is_chain_frozen().await.unwrap());
+    }
+}
@@ -0,0 +1,340 @@
+use zksync_types::{
+    cold_storage::{ArchivedCallTrace, L1BatchColdArchive},
+    L1BatchNumber, MiniblockNumber, H256,
+};
+
+use crate::{
+    instrument::InstrumentExt,
+    models::{storage_event::StorageWeb3Log, storage_transaction::CallTrace},
+    StorageProcessor,
+};
+
+/// Tracks L1 batches for which call traces and events were moved from Postgres to the object store.
+#[derive(Debug)]
+pub struct ColdStorageDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ColdStorageDal<'_, '_> {
+    /// Loads call traces and events for the specified L1 batch from Postgres.
+    pub async fn get_l1_batch_archive(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<L1BatchColdArchive> {
+        let call_traces = sqlx::query!(
+            r#"
+            SELECT
+                call_traces.tx_hash,
+                transactions.miniblock_number AS "miniblock_number!",
+                call_traces.call_trace
+            FROM
+                call_traces
+                INNER JOIN transactions ON call_traces.tx_hash = transactions.hash
+                INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                miniblocks.l1_batch_number = $1
+            ORDER BY
+                transactions.miniblock_number,
+                transactions.index_in_block
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_l1_batch_archive#call_traces")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+        let call_traces = call_traces
+            .into_iter()
+            .map(|row| ArchivedCallTrace {
+                tx_hash: H256::from_slice(&row.tx_hash),
+                miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+                call_trace: CallTrace {
+                    call_trace: row.call_trace,
+                }
+                .into(),
+            })
+            .collect();
+
+        let events = sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            SELECT
+                miniblocks.hash AS "block_hash?",
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4,
+                value,
+                miniblock_number,
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                tx_hash,
+                tx_index_in_block,
+                event_index_in_block,
+                event_index_in_tx
+            FROM
+                events
+                INNER JOIN miniblocks ON events.miniblock_number = miniblocks.number
+            WHERE
+                miniblocks.l1_batch_number = $1
+            ORDER BY
+                miniblock_number,
+                event_index_in_block
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_l1_batch_archive#events")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(L1BatchColdArchive {
+            l1_batch_number,
+            call_traces,
+            events: events.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Records that call traces and events for the specified L1 batch are stored in the object store
+    /// under `object_key`, and removes them from Postgres.
+    pub async fn mark_l1_batch_archived(
+        &mut self,
+        archive: &L1BatchColdArchive,
+        object_key: &str,
+    ) -> sqlx::Result<()> {
+        let l1_batch_number = archive.l1_batch_number;
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM call_traces
+            WHERE
+                tx_hash IN (
+                    SELECT
+                        transactions.hash
+                    FROM
+                        transactions
+                        INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+                    WHERE
+                        miniblocks.l1_batch_number = $1
+                )
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("mark_l1_batch_archived#remove_call_traces")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(&mut transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM events
+            WHERE
+                miniblock_number IN (
+                    SELECT
+                        number
+                    FROM
+                        miniblocks
+                    WHERE
+                        l1_batch_number = $1
+                )
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("mark_l1_batch_archived#remove_events")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(&mut transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                cold_storage_archives (
+                    l1_batch_number,
+                    object_key,
+                    call_traces_count,
+                    events_count,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW())
+            "#,
+            l1_batch_number.0 as i64,
+            object_key,
+            archive.call_traces.len() as i32,
+            archive.events.len() as i32
+        )
+        .instrument("mark_l1_batch_archived")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(&mut transaction)
+        .await?;
+        transaction.commit().await
+    }
+
+    /// Returns the last L1 batch for which call traces and events were moved to the object store.
+    pub async fn get_last_archived_l1_batch(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number"
+            FROM
+                cold_storage_archives
+            "#
+        )
+        .instrument("get_last_archived_l1_batch")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Returns the object store key of the archive for the specified L1 batch, or `None` if the batch
+    /// is not archived.
+    pub async fn get_archive_key(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<String>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                object_key
+            FROM
+                cold_storage_archives
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_archive_key")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| row.object_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        block::L1BatchHeader, tx::IncludedTxLocation, vm_trace::Call, Address, ProtocolVersion,
+        ProtocolVersionId, VmEvent,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    async fn prepare_l1_batch(conn: &mut StorageProcessor<'_>) -> H256 {
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), Default::default())
+            .await;
+        let mut tx_result = mock_execution_result(tx);
+        tx_result.call_traces.push(Call {
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            ..Call::default()
+        });
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], 1.into())
+            .await;
+
+        let location = IncludedTxLocation {
+            tx_hash,
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let events: Vec<_> = (0..3)
+            .map(|i| VmEvent {
+                location: (L1BatchNumber(1), i),
+                address: Address::repeat_byte(i as u8),
+                indexed_topics: vec![H256::repeat_byte(i as u8)],
+                value: vec![i as u8],
+            })
+            .collect();
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &[(location, events.iter().collect())])
+            .await;
+
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        tx_hash
+    }
+
+    #[tokio::test]
+    async fn archiving_l1_batch() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let tx_hash = prepare_l1_batch(&mut conn).await;
+
+        let archive = conn
+            .cold_storage_dal()
+            .get_l1_batch_archive(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(archive.call_traces.len(), 1);
+        assert_eq!(archive.call_traces[0].tx_hash, tx_hash);
+        assert_eq!(archive.call_traces[0].miniblock_number, MiniblockNumber(1));
+        assert_eq!(archive.events.len(), 3);
+        assert_eq!(archive.events[2].address, Address::repeat_byte(2));
+
+        let last_archived = conn
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await
+            .unwrap();
+        assert_eq!(last_archived, None);
+
+        conn.cold_storage_dal()
+            .mark_l1_batch_archived(&archive, "l1_batch_1")
+            .await
+            .unwrap();
+        let last_archived = conn
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await
+            .unwrap();
+        assert_eq!(last_archived, Some(L1BatchNumber(1)));
+        let key = conn
+            .cold_storage_dal()
+            .get_archive_key(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(key.as_deref(), Some("l1_batch_1"));
+
+        let call_trace = conn
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap();
+        assert!(call_trace.is_none());
+        let archive = conn
+            .cold_storage_dal()
+            .get_l1_batch_archive(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(archive.call_traces.is_empty());
+        assert!(archive.events.is_empty());
+    }
+}
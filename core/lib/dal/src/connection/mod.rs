@@ -30,6 +30,7 @@ pub struct ConnectionPoolBuilder {
     max_size: u32,
     acquire_timeout: Duration,
     statement_timeout: Option<Duration>,
+    read_only: bool,
 }
 
 impl fmt::Debug for ConnectionPoolBuilder {
@@ -40,6 +41,7 @@ impl fmt::Debug for ConnectionPoolBuilder {
             .field("max_size", &self.max_size)
             .field("acquire_timeout", &self.acquire_timeout)
             .field("statement_timeout", &self.statement_timeout)
+            .field("read_only", &self.read_only)
             .finish()
     }
 }
@@ -63,6 +65,15 @@ impl ConnectionPoolBuilder {
         self
     }
 
+    /// Makes all transactions in the pool read-only by default, so that any attempt to modify the database fails.
+    /// See [Postgres docs] for semantics.
+    ///
+    /// [Postgres docs]: https://www.postgresql.org/docs/14/runtime-config-client.html
+    pub fn set_read_only(&mut self, read_only: bool) -> &mut Self {
+        self.read_only = read_only;
+        self
+    }
+
     /// Returns the maximum number of connections that can be allocated by the pool.
     pub fn max_size(&self) -> u32 {
         self.max_size
@@ -81,16 +92,27 @@ impl ConnectionPoolBuilder {
             let timeout_string = format!("{}s", timeout.as_secs());
             connect_options = connect_options.options([("statement_timeout", timeout_string)]);
         }
+        if self.read_only {
+            connect_options = connect_options.options([("default_transaction_read_only", "on")]);
+        }
         let pool = options
             .connect_with(connect_options)
             .await
             .context("Failed connecting to database")?;
         tracing::info!("Created DB pool with parameters {self:?}");
+
+        let leak_detection_threshold = ConnectionPool::global_config().leak_detection_threshold();
+        let traced_connections = leak_detection_threshold.map(|threshold| {
+            let connections = Arc::<TracedConnections>::default();
+            let detector = TracedConnections::detect_leaks(Arc::downgrade(&connections), threshold);
+            tokio::spawn(detector);
+            connections
+        });
         Ok(ConnectionPool {
             database_url: self.database_url.clone(),
             inner: pool,
             max_size: self.max_size,
-            traced_connections: None,
+            traced_connections,
         })
     }
 
@@ -192,6 +214,8 @@ pub struct GlobalConnectionPoolConfig {
     // We consider millisecond precision to be enough for config purposes.
     long_connection_threshold_ms: AtomicU64,
     slow_query_threshold_ms: AtomicU64,
+    /// Zero means that leak detection is disabled.
+    leak_detection_threshold_ms: AtomicU64,
 }
 
 impl GlobalConnectionPoolConfig {
@@ -199,6 +223,7 @@ impl GlobalConnectionPoolConfig {
         Self {
             long_connection_threshold_ms: AtomicU64::new(5_000), // 5 seconds
             slow_query_threshold_ms: AtomicU64::new(100),        // 0.1 seconds
+            leak_detection_threshold_ms: AtomicU64::new(0),
         }
     }
 
@@ -210,6 +235,11 @@ impl GlobalConnectionPoolConfig {
         Duration::from_millis(self.slow_query_threshold_ms.load(Ordering::Relaxed))
     }
 
+    fn leak_detection_threshold(&self) -> Option<Duration> {
+        let millis = self.leak_detection_threshold_ms.load(Ordering::Relaxed);
+        (millis > 0).then(|| Duration::from_millis(millis))
+    }
+
     /// Sets the threshold for the DB connection lifetime to denote a connection as long-living and log its details.
     pub fn set_long_connection_threshold(&self, threshold: Duration) -> anyhow::Result<&Self> {
         let millis = u64::try_from(threshold.as_millis())
@@ -229,6 +259,19 @@ impl GlobalConnectionPoolConfig {
         tracing::info!("Set slow query threshold to {threshold:?}");
         Ok(self)
     }
+
+    /// Sets the threshold for the time a DB connection can be held before it's reported as potentially leaked.
+    /// Only affects pools created after the call: such pools trace their active connections and periodically
+    /// report ones held for longer than the threshold (including holder backtraces sampled in debug builds).
+    pub fn set_leak_detection_threshold(&self, threshold: Duration) -> anyhow::Result<&Self> {
+        let millis = u64::try_from(threshold.as_millis())
+            .context("leak_detection_threshold is unreasonably large")?;
+        anyhow::ensure!(millis > 0, "leak_detection_threshold must be positive");
+        self.leak_detection_threshold_ms
+            .store(millis, Ordering::Relaxed);
+        tracing::info!("Set leak detection threshold to {threshold:?}");
+        Ok(self)
+    }
 }
 
 #[derive(Clone)]
@@ -294,6 +337,7 @@ impl ConnectionPool {
             max_size: max_pool_size,
             acquire_timeout: Duration::from_secs(30), // Default value used by `sqlx`
             statement_timeout: None,
+            read_only: false,
         }
     }
 
@@ -347,6 +391,9 @@ impl ConnectionPool {
         &self,
         tags: Option<StorageProcessorTags>,
     ) -> anyhow::Result<StorageProcessor<'_>> {
+        #[cfg(feature = "chaos")]
+        zksync_utils::chaos::fault_point("dal.connection").await?;
+
         let acquire_latency = CONNECTION_METRICS.acquire.start();
         let conn = self
             .acquire_connection_retried(tags.as_ref())
@@ -448,4 +495,35 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[tokio::test]
+    async fn setting_read_only_mode() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(1)
+            .await
+            .unwrap()
+            .database_url;
+
+        let pool = ConnectionPool::singleton(&db_url)
+            .set_read_only(true)
+            .build()
+            .await
+            .unwrap();
+
+        let mut storage = pool.access_storage().await.unwrap();
+        sqlx::query("SELECT 1")
+            .map(drop)
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        let err = sqlx::query("CREATE TABLE read_only_test (id INT)")
+            .execute(storage.conn())
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            sqlx::Error::Database(db_err) if db_err.message().contains("read-only transaction")
+        );
+    }
 }
@@ -1,12 +1,13 @@
 use std::{
+    backtrace::Backtrace,
     collections::HashMap,
     fmt,
     panic::Location,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        Mutex,
+        Mutex, Weak,
     },
-    time::{Instant, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
 use sqlx::{pool::PoolConnection, types::chrono, Connection, PgConnection, Postgres, Transaction};
@@ -41,6 +42,9 @@ impl fmt::Display for StorageProcessorTags {
 struct TracedConnectionInfo {
     tags: Option<StorageProcessorTags>,
     created_at: Instant,
+    /// Backtrace of the connection holder. Only sampled in debug builds.
+    backtrace: Option<Backtrace>,
+    reported_as_leaked: bool,
 }
 
 impl fmt::Debug for TracedConnectionInfo {
@@ -48,7 +52,11 @@ impl fmt::Debug for TracedConnectionInfo {
         let timestamp: chrono::DateTime<chrono::Utc> =
             (SystemTime::now() - self.created_at.elapsed()).into();
         let tags_display = StorageProcessorTags::display(self.tags.as_ref());
-        write!(formatter, "[{timestamp} - {tags_display}]")
+        write!(formatter, "[{timestamp} - {tags_display}]")?;
+        if let Some(backtrace) = &self.backtrace {
+            write!(formatter, "\nHolder backtrace:\n{backtrace}")?;
+        }
+        Ok(())
     }
 }
 
@@ -70,13 +78,33 @@ impl fmt::Debug for TracedConnections {
 }
 
 impl TracedConnections {
+    /// Probability to capture a backtrace of the connection holder in debug builds. Capturing backtraces
+    /// is expensive, so we don't do it for every connection.
+    #[cfg(debug_assertions)]
+    const BACKTRACE_SAMPLING_PROBABILITY: f64 = 0.1;
+    const MIN_LEAK_DETECTION_INTERVAL: Duration = Duration::from_secs(1);
+
     fn acquire(&self, tags: Option<StorageProcessorTags>, created_at: Instant) -> usize {
+        #[cfg(debug_assertions)]
+        let backtrace = rand::Rng::gen_bool(
+            &mut rand::thread_rng(),
+            Self::BACKTRACE_SAMPLING_PROBABILITY,
+        )
+        .then(Backtrace::force_capture);
+        #[cfg(not(debug_assertions))]
+        let backtrace = None;
+
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
         let mut guard = self
             .connections
             .lock()
             .expect("`TracedConnections` is poisoned");
-        let info = TracedConnectionInfo { tags, created_at };
+        let info = TracedConnectionInfo {
+            tags,
+            created_at,
+            backtrace,
+            reported_as_leaked: false,
+        };
         guard.insert(id, info);
         id
     }
@@ -88,6 +116,45 @@ impl TracedConnections {
             .expect("`TracedConnections` is poisoned");
         guard.remove(&connection_id);
     }
+
+    /// Periodically reports connections held for longer than `threshold`. Terminates after the connection pool
+    /// owning the traced connections is dropped.
+    pub(super) async fn detect_leaks(this: Weak<Self>, threshold: Duration) {
+        let interval = (threshold / 2).max(Self::MIN_LEAK_DETECTION_INTERVAL);
+        loop {
+            tokio::time::sleep(interval).await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.report_leaks(threshold);
+        }
+    }
+
+    /// Reports connections held for longer than `threshold`. Each connection is reported at most once.
+    /// Returns the number of newly reported connections.
+    fn report_leaks(&self, threshold: Duration) -> usize {
+        let mut guard = self
+            .connections
+            .lock()
+            .expect("`TracedConnections` is poisoned");
+        let mut reported_count = 0;
+        for info in guard.values_mut() {
+            let lifetime = info.created_at.elapsed();
+            if info.reported_as_leaked || lifetime <= threshold {
+                continue;
+            }
+            info.reported_as_leaked = true;
+            reported_count += 1;
+
+            let requester = info.tags.map_or("untagged", |tags| tags.requester);
+            CONNECTION_METRICS.leaked[&requester].inc();
+            tracing::warn!(
+                "DB connection is held for {lifetime:?}, which exceeds the leak detection threshold {threshold:?}; \
+                 the connection may be leaked: {info:?}"
+            );
+        }
+        reported_count
+    }
 }
 
 struct PooledStorageProcessor<'a> {
@@ -112,6 +179,7 @@ impl Drop for PooledStorageProcessor<'_> {
         if let Some(tags) = &self.tags {
             let lifetime = self.created_at.elapsed();
             CONNECTION_METRICS.lifetime[&tags.requester].observe(lifetime);
+            CONNECTION_METRICS.active[&tags.requester].dec_by(1);
 
             if lifetime > ConnectionPool::global_config().long_connection_threshold() {
                 let file = tags.location.file();
@@ -181,6 +249,9 @@ impl<'a> StorageProcessor<'a> {
         traced_connections: Option<&'a TracedConnections>,
     ) -> Self {
         let created_at = Instant::now();
+        if let Some(tags) = &tags {
+            CONNECTION_METRICS.active[&tags.requester].inc_by(1);
+        }
         let inner = StorageProcessorInner::Pooled(PooledStorageProcessor {
             connection,
             tags,
@@ -207,6 +278,8 @@ impl<'a> StorageProcessor<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::ConnectionPool;
 
     #[tokio::test]
@@ -247,4 +320,27 @@ mod tests {
         assert!(err.contains("Active connections"), "{err}");
         assert!(err.contains("requested by `test`"), "{err}");
     }
+
+    #[tokio::test]
+    async fn detecting_leaked_connections() {
+        let pool = ConnectionPool::constrained_test_pool(2).await;
+        let traced = pool.traced_connections.as_deref().unwrap();
+        let _leaked_connection = pool.access_storage_tagged("leaked").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let _connection = pool.access_storage_tagged("test").await.unwrap();
+
+        assert_eq!(traced.report_leaks(Duration::from_millis(25)), 1);
+        {
+            let traced = traced.connections.lock().unwrap();
+            let leaked: Vec<_> = traced
+                .values()
+                .filter(|info| info.reported_as_leaked)
+                .map(|info| info.tags.unwrap().requester)
+                .collect();
+            assert_eq!(leaked, ["leaked"]);
+        }
+        // Connections are reported only once.
+        assert_eq!(traced.report_leaks(Duration::from_millis(25)), 0);
+        assert_eq!(traced.report_leaks(Duration::ZERO), 1);
+    }
 }
@@ -0,0 +1,290 @@
+use chrono::NaiveDateTime;
+use zksync_types::{pubdata_da::DataAvailabilityBlob, L1BatchNumber};
+
+use crate::StorageProcessor;
+
+#[derive(Debug)]
+pub struct DataAvailabilityDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl DataAvailabilityDal<'_, '_> {
+    /// Records that the pubdata of the L1 batch was dispatched to the DA layer.
+    pub async fn insert_l1_batch_da(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        blob_id: &str,
+        sent_at: NaiveDateTime,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                data_availability (l1_batch_number, blob_id, sent_at, created_at, updated_at)
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT DO NOTHING
+            "#,
+            l1_batch_number.0 as i64,
+            blob_id,
+            sent_at
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Saves the proof of inclusion of the L1 batch pubdata in the DA layer.
+    pub async fn save_l1_batch_inclusion_data(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        inclusion_data: &[u8],
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE data_availability
+            SET
+                inclusion_data = $1,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $2
+                AND inclusion_data IS NULL
+            "#,
+            inclusion_data,
+            l1_batch_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the DA layer info for an L1 batch whose blob has expired in the DA layer, so that the batch
+    /// is dispatched again. Batches with an inclusion proof are never removed.
+    pub async fn remove_l1_batch_da(&mut self, l1_batch_number: L1BatchNumber) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM data_availability
+            WHERE
+                l1_batch_number = $1
+                AND inclusion_data IS NULL
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the first dispatched L1 batch that doesn't have an inclusion proof yet.
+    pub async fn get_first_da_blob_awaiting_inclusion(
+        &mut self,
+    ) -> sqlx::Result<Option<DataAvailabilityBlob>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                blob_id,
+                inclusion_data,
+                sent_at
+            FROM
+                data_availability
+            WHERE
+                inclusion_data IS NULL
+            ORDER BY
+                l1_batch_number
+            LIMIT
+                1
+            "#,
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(|row| DataAvailabilityBlob {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            blob_id: row.blob_id,
+            inclusion_data: row.inclusion_data,
+            sent_at: row.sent_at,
+        }))
+    }
+
+    /// Returns the DA layer info for the specified L1 batch, or `None` if the batch was not dispatched yet.
+    pub async fn get_da_blob(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<DataAvailabilityBlob>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                blob_id,
+                inclusion_data,
+                sent_at
+            FROM
+                data_availability
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(|row| DataAvailabilityBlob {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            blob_id: row.blob_id,
+            inclusion_data: row.inclusion_data,
+            sent_at: row.sent_at,
+        }))
+    }
+
+    /// Returns sealed L1 batches together with their pubdata that were not dispatched to the DA layer yet,
+    /// in the ascending order of batch numbers.
+    pub async fn get_ready_for_da_dispatch_l1_batches(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<(L1BatchNumber, Vec<u8>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batches.number,
+                l1_batches.pubdata_input AS "pubdata_input!"
+            FROM
+                l1_batches
+                LEFT JOIN data_availability ON data_availability.l1_batch_number = l1_batches.number
+            WHERE
+                l1_batches.eth_commit_tx_id IS NULL
+                AND l1_batches.number != 0
+                AND l1_batches.pubdata_input IS NOT NULL
+                AND data_availability.l1_batch_number IS NULL
+            ORDER BY
+                l1_batches.number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (L1BatchNumber(row.number as u32), row.pubdata_input))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn dispatching_l1_batches_to_da_layer() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            let mut header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            header.pubdata_input = Some(vec![number as u8; 32]);
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+
+        let ready = conn
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(10)
+            .await
+            .unwrap();
+        assert_eq!(
+            ready,
+            [
+                (L1BatchNumber(1), vec![1; 32]),
+                (L1BatchNumber(2), vec![2; 32])
+            ]
+        );
+
+        let sent_at = NaiveDateTime::from_timestamp_opt(1_000, 0).unwrap();
+        conn.data_availability_dal()
+            .insert_l1_batch_da(L1BatchNumber(1), "blob-1", sent_at)
+            .await
+            .unwrap();
+        let ready = conn
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(10)
+            .await
+            .unwrap();
+        assert_eq!(ready, [(L1BatchNumber(2), vec![2; 32])]);
+
+        let expected_blob = DataAvailabilityBlob {
+            l1_batch_number: L1BatchNumber(1),
+            blob_id: "blob-1".to_owned(),
+            inclusion_data: None,
+            sent_at,
+        };
+        let awaiting = conn
+            .data_availability_dal()
+            .get_first_da_blob_awaiting_inclusion()
+            .await
+            .unwrap();
+        assert_eq!(awaiting.as_ref(), Some(&expected_blob));
+
+        conn.data_availability_dal()
+            .save_l1_batch_inclusion_data(L1BatchNumber(1), &[42; 16])
+            .await
+            .unwrap();
+        let awaiting = conn
+            .data_availability_dal()
+            .get_first_da_blob_awaiting_inclusion()
+            .await
+            .unwrap();
+        assert_eq!(awaiting, None);
+        let blob = conn
+            .data_availability_dal()
+            .get_da_blob(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(blob.inclusion_data, Some(vec![42; 16]));
+        let blob = conn
+            .data_availability_dal()
+            .get_da_blob(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(blob, None);
+
+        // Blobs with inclusion data must not be removed.
+        conn.data_availability_dal()
+            .remove_l1_batch_da(L1BatchNumber(1))
+            .await
+            .unwrap();
+        conn.data_availability_dal()
+            .insert_l1_batch_da(L1BatchNumber(2), "blob-2", sent_at)
+            .await
+            .unwrap();
+        conn.data_availability_dal()
+            .remove_l1_batch_da(L1BatchNumber(2))
+            .await
+            .unwrap();
+        let blob = conn
+            .data_availability_dal()
+            .get_da_blob(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(blob.is_some());
+        let blob = conn
+            .data_availability_dal()
+            .get_da_blob(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(blob, None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{convert::TryFrom, str::FromStr};
+use std::{convert::TryFrom, ops, str::FromStr};
 
 use anyhow::Context as _;
 use sqlx::{
@@ -7,9 +7,12 @@ use sqlx::{
 };
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    eth_sender::{EthTx, EthTxBlobSidecar, TxHistory, TxHistoryToSend},
-    Address, L1BatchNumber, H256, U256,
+    eth_sender::{
+        CommitBlobUtilization, EthTx, EthTxBlobSidecar, EthTxL1Costs, TxHistory, TxHistoryToSend,
+    },
+    Address, L1BatchNumber, L1BlockNumber, H256, U256,
 };
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
 
 use crate::{
     models::storage_eth_tx::{
@@ -140,6 +143,43 @@ impl EthSenderDal<'_, '_> {
         Ok(txs.into_iter().map(|tx| tx.into()).collect())
     }
 
+    /// Same as [`Self::get_new_eth_txs()`], but only returns transactions with IDs greater than `last_eth_tx_id`.
+    /// Used in the dry run mode, in which transactions are not persisted in the tx history.
+    pub async fn get_new_eth_txs_after(
+        &mut self,
+        last_eth_tx_id: u32,
+        limit: u64,
+    ) -> sqlx::Result<Vec<EthTx>> {
+        let txs = sqlx::query_as!(
+            StorageEthTx,
+            r#"
+            SELECT
+                *
+            FROM
+                eth_txs
+            WHERE
+                id > GREATEST(
+                    (
+                        SELECT
+                            COALESCE(MAX(eth_tx_id), 0)
+                        FROM
+                            eth_txs_history
+                    ),
+                    $1
+                )
+            ORDER BY
+                id
+            LIMIT
+                $2
+            "#,
+            i64::from(last_eth_tx_id),
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        Ok(txs.into_iter().map(|tx| tx.into()).collect())
+    }
+
     pub async fn get_unsent_txs(&mut self) -> sqlx::Result<Vec<TxHistoryToSend>> {
         let txs = sqlx::query_as!(
             StorageTxHistoryToSend,
@@ -369,6 +409,236 @@ impl EthSenderDal<'_, '_> {
         Ok(Some(H256::from_str(tx_hash).context("invalid tx_hash")?))
     }
 
+    /// Checks whether a transaction with the specified hash was sent, but is not confirmed yet.
+    pub async fn is_tx_pending_confirmation(&mut self, tx_hash: H256) -> sqlx::Result<bool> {
+        let tx_hash = format!("{:#x}", tx_hash);
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        eth_txs_history
+                    WHERE
+                        tx_hash = $1
+                        AND confirmed_at IS NULL
+                ) AS "exists!"
+            "#,
+            tx_hash
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.exists)
+    }
+
+    /// Returns IDs and hashes of confirmed ETH transactions that don't have their L1 costs recorded yet.
+    /// Transactions are ordered by ascending ID.
+    pub async fn get_confirmed_txs_without_l1_costs(
+        &mut self,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(u32, H256)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                eth_txs.id,
+                eth_txs_history.tx_hash
+            FROM
+                eth_txs
+                JOIN eth_txs_history ON eth_txs.confirmed_eth_tx_history_id = eth_txs_history.id
+                LEFT JOIN eth_txs_l1_costs ON eth_txs_l1_costs.eth_tx_id = eth_txs.id
+            WHERE
+                eth_txs_l1_costs.eth_tx_id IS NULL
+            ORDER BY
+                eth_txs.id
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let tx_hash = H256::from_str(row.tx_hash.trim_start_matches("0x"))
+                    .context("invalid tx_hash")?;
+                Ok((row.id as u32, tx_hash))
+            })
+            .collect()
+    }
+
+    pub async fn save_l1_costs(
+        &mut self,
+        eth_tx_id: u32,
+        costs: &EthTxL1Costs,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_txs_l1_costs (
+                    eth_tx_id,
+                    gas_used,
+                    effective_gas_price,
+                    l1_block_number,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW(), NOW())
+            ON CONFLICT (eth_tx_id) DO
+            UPDATE
+            SET
+                gas_used = $2,
+                effective_gas_price = $3,
+                l1_block_number = $4,
+                updated_at = NOW()
+            "#,
+            eth_tx_id as i32,
+            u256_to_big_decimal(costs.gas_used),
+            u256_to_big_decimal(costs.effective_gas_price),
+            i64::from(costs.l1_block_number.0)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns actual L1 costs of the commit, prove and execute transactions for the specified L1 batch.
+    /// Transactions without recorded costs are skipped. Note that a single ETH transaction may
+    /// cover several L1 batches, so the returned costs are not amortized.
+    pub async fn get_l1_batch_l1_costs(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Vec<(AggregatedActionType, EthTxL1Costs)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                eth_txs.tx_type,
+                eth_txs_history.tx_hash,
+                eth_txs_l1_costs.gas_used,
+                eth_txs_l1_costs.effective_gas_price,
+                eth_txs_l1_costs.l1_block_number
+            FROM
+                l1_batches
+                JOIN eth_txs ON eth_txs.id IN (
+                    l1_batches.eth_commit_tx_id,
+                    l1_batches.eth_prove_tx_id,
+                    l1_batches.eth_execute_tx_id
+                )
+                JOIN eth_txs_history ON eth_txs.confirmed_eth_tx_history_id = eth_txs_history.id
+                JOIN eth_txs_l1_costs ON eth_txs_l1_costs.eth_tx_id = eth_txs.id
+            WHERE
+                l1_batches.number = $1
+            ORDER BY
+                eth_txs.id
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let tx_type = AggregatedActionType::from_str(&row.tx_type)
+                    .map_err(|err| anyhow::anyhow!("invalid tx_type: {err}"))?;
+                let tx_hash = H256::from_str(row.tx_hash.trim_start_matches("0x"))
+                    .context("invalid tx_hash")?;
+                let costs = EthTxL1Costs {
+                    tx_hash,
+                    gas_used: bigdecimal_to_u256(row.gas_used),
+                    effective_gas_price: bigdecimal_to_u256(row.effective_gas_price),
+                    l1_block_number: L1BlockNumber(row.l1_block_number as u32),
+                };
+                Ok((tx_type, costs))
+            })
+            .collect()
+    }
+
+    /// Records blob utilization for a commit transaction publishing pubdata in blobs.
+    pub async fn save_blob_utilization(
+        &mut self,
+        eth_tx_id: u32,
+        l1_batches: ops::RangeInclusive<L1BatchNumber>,
+        blob_count: u32,
+        pubdata_bytes: u64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_txs_blob_utilization (
+                    eth_tx_id,
+                    first_l1_batch_number,
+                    last_l1_batch_number,
+                    blob_count,
+                    pubdata_bytes,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (eth_tx_id) DO NOTHING
+            "#,
+            eth_tx_id as i32,
+            i64::from(l1_batches.start().0),
+            i64::from(l1_batches.end().0),
+            blob_count as i32,
+            pubdata_bytes as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns blob utilization for up to `limit` latest commit transactions, newest first. Fees are only
+    /// returned for transactions with recorded L1 costs.
+    pub async fn get_blob_utilization(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<CommitBlobUtilization>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                eth_txs_blob_utilization.eth_tx_id,
+                eth_txs_blob_utilization.first_l1_batch_number,
+                eth_txs_blob_utilization.last_l1_batch_number,
+                eth_txs_blob_utilization.blob_count,
+                eth_txs_blob_utilization.pubdata_bytes,
+                eth_txs_l1_costs.gas_used AS "gas_used?",
+                eth_txs_l1_costs.effective_gas_price AS "effective_gas_price?"
+            FROM
+                eth_txs_blob_utilization
+                LEFT JOIN eth_txs_l1_costs ON eth_txs_l1_costs.eth_tx_id = eth_txs_blob_utilization.eth_tx_id
+            ORDER BY
+                eth_txs_blob_utilization.eth_tx_id DESC
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let total_fee =
+                    row.gas_used
+                        .zip(row.effective_gas_price)
+                        .map(|(gas_used, gas_price)| {
+                            bigdecimal_to_u256(gas_used) * bigdecimal_to_u256(gas_price)
+                        });
+                CommitBlobUtilization {
+                    eth_tx_id: row.eth_tx_id as u32,
+                    l1_batches: L1BatchNumber(row.first_l1_batch_number as u32)
+                        ..=L1BatchNumber(row.last_l1_batch_number as u32),
+                    blob_count: row.blob_count as u32,
+                    pubdata_bytes: row.pubdata_bytes as u64,
+                    total_fee,
+                }
+            })
+            .collect())
+    }
+
     /// This method inserts a fake transaction into the database that would make the corresponding L1 batch
     /// to be considered committed/proven/executed.
     ///
@@ -615,4 +885,563 @@ impl EthSenderDal<'_, '_> {
         .await?;
         Ok(())
     }
+
+    /// Returns the number of unconfirmed L1 transactions that would be removed by
+    /// [`Self::delete_unconfirmed_txs_for_l1_batches_after()`], and the number of these transactions that were sent
+    /// to L1 and haven't failed (i.e., may still be mined).
+    pub async fn get_unconfirmed_txs_count_for_l1_batches_after(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> sqlx::Result<(u64, u64)> {
+        let row = sqlx::query!(
+            r#"
+            WITH
+                reverted_txs AS (
+                    SELECT
+                        id,
+                        from_addr,
+                        nonce,
+                        has_failed,
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                eth_txs_history
+                            WHERE
+                                eth_tx_id = eth_txs.id
+                        ) AS was_sent
+                    FROM
+                        eth_txs
+                    WHERE
+                        confirmed_eth_tx_history_id IS NULL
+                        AND id IN (
+                            SELECT
+                                UNNEST(ARRAY[eth_commit_tx_id, eth_prove_tx_id, eth_execute_tx_id])
+                            FROM
+                                l1_batches
+                            WHERE
+                                number > $1
+                        )
+                ),
+                first_gaps AS (
+                    SELECT
+                        from_addr,
+                        MIN(nonce) AS nonce
+                    FROM
+                        reverted_txs
+                    WHERE
+                        NOT was_sent
+                    GROUP BY
+                        from_addr
+                ),
+                affected_txs AS (
+                    SELECT
+                        id,
+                        was_sent,
+                        has_failed
+                    FROM
+                        reverted_txs
+                    UNION
+                    SELECT
+                        eth_txs.id,
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                eth_txs_history
+                            WHERE
+                                eth_tx_id = eth_txs.id
+                        ) AS was_sent,
+                        eth_txs.has_failed
+                    FROM
+                        eth_txs
+                        INNER JOIN first_gaps ON eth_txs.from_addr IS NOT DISTINCT FROM first_gaps.from_addr
+                        AND eth_txs.nonce > first_gaps.nonce
+                    WHERE
+                        eth_txs.confirmed_eth_tx_history_id IS NULL
+                )
+            SELECT
+                COUNT(*) AS "count!",
+                COUNT(*) FILTER (
+                    WHERE
+                        was_sent
+                        AND NOT has_failed
+                ) AS "inflight_count!"
+            FROM
+                affected_txs
+            "#,
+            i64::from(last_l1_batch_to_keep.0)
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok((row.count as u64, row.inflight_count as u64))
+    }
+
+    /// Removes unconfirmed L1 transactions referenced by L1 batches with numbers greater than `last_l1_batch_to_keep`.
+    /// Removing a transaction that was never sent leaves a gap in operator nonces, which would block all transactions
+    /// with greater nonces on L1. Hence, all unconfirmed transactions of the same sender starting from the first gap
+    /// are removed as well (this may include transactions for the retained L1 batches). References to removed
+    /// transactions from the retained L1 batches are reset, so that these batches are aggregated again and
+    /// get new nonces. Returns the number of removed transactions.
+    pub async fn delete_unconfirmed_txs_for_l1_batches_after(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> sqlx::Result<u64> {
+        // References from `l1_batches` and tx history are handled by `ON DELETE` clauses of the foreign keys.
+        let result = sqlx::query!(
+            r#"
+            WITH
+                reverted_txs AS (
+                    SELECT
+                        id,
+                        from_addr,
+                        nonce,
+                        has_failed,
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                eth_txs_history
+                            WHERE
+                                eth_tx_id = eth_txs.id
+                        ) AS was_sent
+                    FROM
+                        eth_txs
+                    WHERE
+                        confirmed_eth_tx_history_id IS NULL
+                        AND id IN (
+                            SELECT
+                                UNNEST(ARRAY[eth_commit_tx_id, eth_prove_tx_id, eth_execute_tx_id])
+                            FROM
+                                l1_batches
+                            WHERE
+                                number > $1
+                        )
+                ),
+                first_gaps AS (
+                    SELECT
+                        from_addr,
+                        MIN(nonce) AS nonce
+                    FROM
+                        reverted_txs
+                    WHERE
+                        NOT was_sent
+                    GROUP BY
+                        from_addr
+                ),
+                affected_txs AS (
+                    SELECT
+                        id,
+                        was_sent,
+                        has_failed
+                    FROM
+                        reverted_txs
+                    UNION
+                    SELECT
+                        eth_txs.id,
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                eth_txs_history
+                            WHERE
+                                eth_tx_id = eth_txs.id
+                        ) AS was_sent,
+                        eth_txs.has_failed
+                    FROM
+                        eth_txs
+                        INNER JOIN first_gaps ON eth_txs.from_addr IS NOT DISTINCT FROM first_gaps.from_addr
+                        AND eth_txs.nonce > first_gaps.nonce
+                    WHERE
+                        eth_txs.confirmed_eth_tx_history_id IS NULL
+                )
+            DELETE FROM eth_txs
+            WHERE
+                id IN (
+                    SELECT
+                        id
+                    FROM
+                        affected_txs
+                )
+            "#,
+            i64::from(last_l1_batch_to_keep.0)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn saving_and_loading_l1_costs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        let commit_tx_hash = H256::repeat_byte(1);
+        let prove_tx_hash = H256::repeat_byte(2);
+        for (tx_type, tx_hash) in [
+            (AggregatedActionType::Commit, commit_tx_hash),
+            (AggregatedActionType::PublishProofOnchain, prove_tx_hash),
+        ] {
+            conn.eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(L1BatchNumber(1), tx_type, tx_hash, Utc::now())
+                .await
+                .unwrap();
+        }
+
+        let pending_txs = conn
+            .eth_sender_dal()
+            .get_confirmed_txs_without_l1_costs(10)
+            .await
+            .unwrap();
+        let pending_hashes: Vec<_> = pending_txs.iter().map(|(_, hash)| *hash).collect();
+        assert_eq!(pending_hashes, [commit_tx_hash, prove_tx_hash]);
+        let (commit_tx_id, _) = pending_txs[0];
+
+        let costs = EthTxL1Costs {
+            tx_hash: commit_tx_hash,
+            gas_used: 150_000.into(),
+            effective_gas_price: 30_000_000_000_u64.into(),
+            l1_block_number: L1BlockNumber(42),
+        };
+        conn.eth_sender_dal()
+            .save_l1_costs(commit_tx_id, &costs)
+            .await
+            .unwrap();
+
+        let pending_txs = conn
+            .eth_sender_dal()
+            .get_confirmed_txs_without_l1_costs(10)
+            .await
+            .unwrap();
+        assert_eq!(pending_txs.len(), 1);
+        assert_eq!(pending_txs[0].1, prove_tx_hash);
+
+        let batch_costs = conn
+            .eth_sender_dal()
+            .get_l1_batch_l1_costs(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(batch_costs, [(AggregatedActionType::Commit, costs)]);
+    }
+
+    #[tokio::test]
+    async fn saving_and_loading_blob_utilization() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+
+        for (number, tx_hash) in [(1, H256::repeat_byte(1)), (2, H256::repeat_byte(2))] {
+            conn.eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(number),
+                    AggregatedActionType::Commit,
+                    tx_hash,
+                    Utc::now(),
+                )
+                .await
+                .unwrap();
+        }
+        let pending_txs = conn
+            .eth_sender_dal()
+            .get_confirmed_txs_without_l1_costs(10)
+            .await
+            .unwrap();
+        let commit_tx_ids: Vec<_> = pending_txs.iter().map(|(id, _)| *id).collect();
+        assert_eq!(commit_tx_ids.len(), 2);
+
+        conn.eth_sender_dal()
+            .save_blob_utilization(
+                commit_tx_ids[0],
+                L1BatchNumber(1)..=L1BatchNumber(1),
+                1,
+                50_000,
+            )
+            .await
+            .unwrap();
+        conn.eth_sender_dal()
+            .save_blob_utilization(
+                commit_tx_ids[1],
+                L1BatchNumber(2)..=L1BatchNumber(2),
+                2,
+                200_000,
+            )
+            .await
+            .unwrap();
+        let costs = EthTxL1Costs {
+            tx_hash: H256::repeat_byte(1),
+            gas_used: 150_000.into(),
+            effective_gas_price: 10.into(),
+            l1_block_number: L1BlockNumber(42),
+        };
+        conn.eth_sender_dal()
+            .save_l1_costs(commit_tx_ids[0], &costs)
+            .await
+            .unwrap();
+
+        let utilization = conn
+            .eth_sender_dal()
+            .get_blob_utilization(10)
+            .await
+            .unwrap();
+        assert_eq!(
+            utilization,
+            [
+                CommitBlobUtilization {
+                    eth_tx_id: commit_tx_ids[1],
+                    l1_batches: L1BatchNumber(2)..=L1BatchNumber(2),
+                    blob_count: 2,
+                    pubdata_bytes: 200_000,
+                    total_fee: None,
+                },
+                CommitBlobUtilization {
+                    eth_tx_id: commit_tx_ids[0],
+                    l1_batches: L1BatchNumber(1)..=L1BatchNumber(1),
+                    blob_count: 1,
+                    pubdata_bytes: 50_000,
+                    total_fee: Some(1_500_000.into()),
+                },
+            ]
+        );
+
+        let utilization = conn.eth_sender_dal().get_blob_utilization(1).await.unwrap();
+        assert_eq!(utilization.len(), 1);
+        assert_eq!(utilization[0].eth_tx_id, commit_tx_ids[1]);
+    }
+
+    #[tokio::test]
+    async fn deleting_unconfirmed_txs_for_reverted_l1_batches() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=3 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+        conn.eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Commit,
+                H256::repeat_byte(1),
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+
+        // Unconfirmed commit tx covering batches #2 and #3.
+        let commit_tx = conn
+            .eth_sender_dal()
+            .save_eth_tx(
+                1,
+                vec![],
+                AggregatedActionType::Commit,
+                Address::zero(),
+                0,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(2)..=L1BatchNumber(3),
+                commit_tx.id,
+                AggregatedActionType::Commit,
+            )
+            .await
+            .unwrap();
+
+        let counts = conn
+            .eth_sender_dal()
+            .get_unconfirmed_txs_count_for_l1_batches_after(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(counts, (1, 0));
+        let counts = conn
+            .eth_sender_dal()
+            .get_unconfirmed_txs_count_for_l1_batches_after(L1BatchNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(counts, (0, 0));
+
+        conn.eth_sender_dal()
+            .insert_tx_history(commit_tx.id, 100, 1, None, H256::repeat_byte(2), &[])
+            .await
+            .unwrap()
+            .unwrap();
+        let counts = conn
+            .eth_sender_dal()
+            .get_unconfirmed_txs_count_for_l1_batches_after(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(counts, (1, 1));
+
+        let deleted_count = conn
+            .eth_sender_dal()
+            .delete_unconfirmed_txs_for_l1_batches_after(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(deleted_count, 1);
+        assert!(conn
+            .eth_sender_dal()
+            .get_eth_tx(commit_tx.id)
+            .await
+            .unwrap()
+            .is_none());
+        // The confirmed commit tx for batch #1 must be retained.
+        let counts = conn
+            .eth_sender_dal()
+            .get_unconfirmed_txs_count_for_l1_batches_after(L1BatchNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(counts, (0, 0));
+        let commit_tx_count = conn
+            .eth_sender_dal()
+            .get_confirmed_txs_without_l1_costs(10)
+            .await
+            .unwrap()
+            .len();
+        assert_eq!(commit_tx_count, 1);
+    }
+
+    #[tokio::test]
+    async fn deleting_unconfirmed_txs_does_not_leave_nonce_gaps() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=2 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+        conn.eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Commit,
+                H256::repeat_byte(1),
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+
+        // Unsent commit tx for the reverted batch #2, followed by an unsent prove tx for the retained batch #1.
+        let mut tx_ids = vec![];
+        for (nonce, l1_batch_number, action_type) in [
+            (1, L1BatchNumber(2), AggregatedActionType::Commit),
+            (
+                2,
+                L1BatchNumber(1),
+                AggregatedActionType::PublishProofOnchain,
+            ),
+        ] {
+            let tx = conn
+                .eth_sender_dal()
+                .save_eth_tx(nonce, vec![], action_type, Address::zero(), 0, None, None)
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .set_eth_tx_id(l1_batch_number..=l1_batch_number, tx.id, action_type)
+                .await
+                .unwrap();
+            tx_ids.push(tx.id);
+        }
+        // Unsent tx of another sender, which must be retained.
+        let other_sender_tx = conn
+            .eth_sender_dal()
+            .save_eth_tx(
+                5,
+                vec![],
+                AggregatedActionType::Commit,
+                Address::zero(),
+                0,
+                Some(Address::repeat_byte(1)),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let counts = conn
+            .eth_sender_dal()
+            .get_unconfirmed_txs_count_for_l1_batches_after(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(counts, (2, 0));
+
+        let deleted_count = conn
+            .eth_sender_dal()
+            .delete_unconfirmed_txs_for_l1_batches_after(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(deleted_count, 2);
+        for tx_id in tx_ids {
+            assert!(conn
+                .eth_sender_dal()
+                .get_eth_tx(tx_id)
+                .await
+                .unwrap()
+                .is_none());
+        }
+        assert!(conn
+            .eth_sender_dal()
+            .get_eth_tx(other_sender_tx.id)
+            .await
+            .unwrap()
+            .is_some());
+        let next_nonce = conn.eth_sender_dal().get_next_nonce(None).await.unwrap();
+        assert_eq!(next_nonce, Some(1));
+    }
 }
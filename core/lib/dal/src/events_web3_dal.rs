@@ -1,11 +1,16 @@
+use std::collections::HashMap;
+
 use sqlx::Row;
 use zksync_types::{
-    api::{GetLogsFilter, Log},
+    api::{GetLogsFilter, Log, LogsQueryStrategy},
     Address, MiniblockNumber, H256,
 };
 
 use crate::{
-    instrument::InstrumentExt, models::storage_event::StorageWeb3Log, SqlxError, StorageProcessor,
+    instrument::InstrumentExt,
+    logs_query_planner::{ColumnStatistics, EventsStatistics},
+    models::storage_event::StorageWeb3Log,
+    SqlxError, StorageProcessor,
 };
 
 #[derive(Debug)]
@@ -20,9 +25,21 @@ impl EventsWeb3Dal<'_, '_> {
         &mut self,
         filter: &GetLogsFilter,
         offset: usize,
+    ) -> Result<Option<MiniblockNumber>, SqlxError> {
+        self.get_log_block_number_with_strategy(filter, offset, None)
+            .await
+    }
+
+    /// Same as [`Self::get_log_block_number()`], but enforces the specified query strategy.
+    pub async fn get_log_block_number_with_strategy(
+        &mut self,
+        filter: &GetLogsFilter,
+        offset: usize,
+        strategy: Option<LogsQueryStrategy>,
     ) -> Result<Option<MiniblockNumber>, SqlxError> {
         {
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(filter);
+            let (where_sql, arg_index) =
+                self.build_get_logs_where_clause_with_strategy(filter, strategy);
 
             let query = format!(
                 r#"
@@ -51,6 +68,7 @@ impl EventsWeb3Dal<'_, '_> {
                 .report_latency()
                 .with_arg("filter", filter)
                 .with_arg("offset", &offset)
+                .with_arg("strategy", &strategy)
                 .fetch_optional(self.storage)
                 .await?;
 
@@ -65,28 +83,21 @@ impl EventsWeb3Dal<'_, '_> {
         filter: GetLogsFilter,
         limit: usize,
     ) -> Result<Vec<Log>, SqlxError> {
-        {
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+        self.get_logs_with_strategy(filter, limit, None).await
+    }
 
-            let query = format!(
-                r#"
-                WITH events_select AS (
-                    SELECT
-                        address, topic1, topic2, topic3, topic4, value,
-                        miniblock_number, tx_hash, tx_index_in_block,
-                        event_index_in_block, event_index_in_tx
-                    FROM events
-                    WHERE {}
-                    ORDER BY miniblock_number ASC, event_index_in_block ASC
-                    LIMIT ${}
-                )
-                SELECT miniblocks.hash as "block_hash", miniblocks.l1_batch_number as "l1_batch_number", events_select.*
-                FROM events_select
-                LEFT JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
-                ORDER BY miniblock_number ASC, event_index_in_block ASC
-                "#,
-                where_sql, arg_index
-            );
+    /// Same as [`Self::get_logs()`], but enforces the specified query strategy. Filter conditions not used
+    /// by the strategy are wrapped into no-op expressions, so that Postgres cannot use indices for them.
+    pub async fn get_logs_with_strategy(
+        &mut self,
+        filter: GetLogsFilter,
+        limit: usize,
+        strategy: Option<LogsQueryStrategy>,
+    ) -> Result<Vec<Log>, SqlxError> {
+        {
+            let (where_sql, arg_index) =
+                self.build_get_logs_where_clause_with_strategy(&filter, strategy);
+            let query = Self::get_logs_query(&where_sql, arg_index);
 
             let mut query = sqlx::query_as(&query);
             if !filter.addresses.is_empty() {
@@ -104,6 +115,7 @@ impl EventsWeb3Dal<'_, '_> {
                 .report_latency()
                 .with_arg("filter", &filter)
                 .with_arg("limit", &limit)
+                .with_arg("strategy", &strategy)
                 .fetch_all(self.storage)
                 .await?;
             let logs = db_logs.into_iter().map(Into::into).collect();
@@ -111,19 +123,163 @@ impl EventsWeb3Dal<'_, '_> {
         }
     }
 
+    fn get_logs_query(where_sql: &str, arg_index: u8) -> String {
+        format!(
+            r#"
+            WITH events_select AS (
+                SELECT
+                    address, topic1, topic2, topic3, topic4, value,
+                    miniblock_number, tx_hash, tx_index_in_block,
+                    event_index_in_block, event_index_in_tx
+                FROM events
+                WHERE {}
+                ORDER BY miniblock_number ASC, event_index_in_block ASC
+                LIMIT ${}
+            )
+            SELECT miniblocks.hash as "block_hash", miniblocks.l1_batch_number as "l1_batch_number", events_select.*
+            FROM events_select
+            LEFT JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            ORDER BY miniblock_number ASC, event_index_in_block ASC
+            "#,
+            where_sql, arg_index
+        )
+    }
+
+    /// Loads statistics of the `events` table collected by Postgres. Returns `None` if the table
+    /// was not analyzed yet.
+    pub async fn get_events_statistics(&mut self) -> Result<Option<EventsStatistics>, SqlxError> {
+        let totals = sqlx::query(
+            r#"
+            SELECT
+                (
+                    SELECT
+                        reltuples::FLOAT8
+                    FROM
+                        pg_class
+                    WHERE
+                        oid = 'events'::regclass
+                ) AS total_events,
+                (
+                    SELECT
+                        (MAX(number) - MIN(number) + 1)::FLOAT8
+                    FROM
+                        miniblocks
+                ) AS total_miniblocks
+            "#,
+        )
+        .instrument("get_events_statistics#totals")
+        .fetch_optional(self.storage)
+        .await?;
+        let Some(totals) = totals else {
+            return Ok(None);
+        };
+        let total_events: Option<f64> = totals.get("total_events");
+        let total_miniblocks: Option<f64> = totals.get("total_miniblocks");
+        let (Some(total_events), Some(total_miniblocks)) = (total_events, total_miniblocks) else {
+            return Ok(None);
+        };
+        // `reltuples` is -1 or 0 for tables that were never analyzed.
+        if total_events <= 0.0 {
+            return Ok(None);
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                attname::TEXT AS column_name,
+                n_distinct::FLOAT8 AS n_distinct,
+                most_common_vals::TEXT::BYTEA[] AS most_common_vals,
+                most_common_freqs::FLOAT8[] AS most_common_freqs
+            FROM
+                pg_stats
+            WHERE
+                schemaname = CURRENT_SCHEMA()
+                AND tablename = 'events'
+                AND attname IN ('address', 'topic1', 'topic2', 'topic3', 'topic4')
+            "#,
+        )
+        .instrument("get_events_statistics#columns")
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut columns: HashMap<String, ColumnStatistics> = rows
+            .into_iter()
+            .map(|row| {
+                let n_distinct: f64 = row.get("n_distinct");
+                // Negative values mean the number of distinct values relative to the number of rows.
+                let distinct_values = if n_distinct < 0.0 {
+                    -n_distinct * total_events
+                } else {
+                    n_distinct
+                };
+                let values: Option<Vec<Vec<u8>>> = row.get("most_common_vals");
+                let frequencies: Option<Vec<f64>> = row.get("most_common_freqs");
+                let most_common_values = values
+                    .unwrap_or_default()
+                    .into_iter()
+                    .zip(frequencies.unwrap_or_default())
+                    .collect();
+                let stats = ColumnStatistics {
+                    distinct_values,
+                    most_common_values,
+                };
+                (row.get("column_name"), stats)
+            })
+            .collect();
+        let Some(address) = columns.remove("address") else {
+            return Ok(None);
+        };
+        let topics = ["topic1", "topic2", "topic3", "topic4"]
+            .map(|column| columns.remove(column).unwrap_or_default());
+        Ok(Some(EventsStatistics {
+            total_events,
+            total_miniblocks,
+            address,
+            topics,
+        }))
+    }
+
     fn build_get_logs_where_clause(&self, filter: &GetLogsFilter) -> (String, u8) {
+        self.build_get_logs_where_clause_with_strategy(filter, None)
+    }
+
+    /// Builds the `WHERE` clause for a logs query. Columns that should not be used by the query strategy
+    /// are wrapped into no-op expressions, which prevents Postgres from using indices on them.
+    pub fn build_get_logs_where_clause_with_strategy(
+        &self,
+        filter: &GetLogsFilter,
+        strategy: Option<LogsQueryStrategy>,
+    ) -> (String, u8) {
+        let (block_column, address_column) = match strategy {
+            None | Some(LogsQueryStrategy::AddressIndex) => ("miniblock_number", "address"),
+            Some(LogsQueryStrategy::BlockRange) => ("miniblock_number", "(address || ''::bytea)"),
+            // The block range condition is left indexable, so that Postgres can combine it with the topic index.
+            Some(LogsQueryStrategy::TopicIndex(_)) => {
+                ("miniblock_number", "(address || ''::bytea)")
+            }
+        };
         let mut arg_index = 1;
 
-        let mut where_sql = format!("(miniblock_number >= {})", filter.from_block.0 as i64);
+        let mut where_sql = format!("({block_column} >= {})", filter.from_block.0 as i64);
 
-        where_sql += &format!(" AND (miniblock_number <= {})", filter.to_block.0 as i64);
+        where_sql += &format!(" AND ({block_column} <= {})", filter.to_block.0 as i64);
 
         if !filter.addresses.is_empty() {
-            where_sql += &format!(" AND (address = ANY(${}))", arg_index);
+            where_sql += &format!(" AND ({address_column} = ANY(${}))", arg_index);
             arg_index += 1;
         }
         for (topic_index, _) in filter.topics.iter() {
-            where_sql += &format!(" AND (topic{} = ANY(${}))", topic_index, arg_index);
+            let is_indexed = match strategy {
+                None => true,
+                Some(LogsQueryStrategy::TopicIndex(index)) => index == *topic_index,
+                Some(_) => false,
+            };
+            let topic_column = if is_indexed {
+                format!("topic{topic_index}")
+            } else {
+                format!("(topic{topic_index} || ''::bytea)")
+            };
+            where_sql += &format!(" AND ({topic_column} = ANY(${}))", arg_index);
             arg_index += 1;
         }
 
@@ -193,10 +349,12 @@ impl EventsWeb3Dal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::{Address, H256};
+    use zksync_types::{
+        tx::IncludedTxLocation, Address, L1BatchNumber, ProtocolVersion, VmEvent, H256,
+    };
 
     use super::*;
-    use crate::connection::ConnectionPool;
+    use crate::{connection::ConnectionPool, tests::create_miniblock_header};
 
     #[tokio::test]
     async fn test_build_get_logs_where_clause() {
@@ -218,4 +376,110 @@ mod tests {
         assert_eq!(actual_sql, expected_sql);
         assert_eq!(actual_arg_index, expected_arg_index);
     }
+
+    #[tokio::test]
+    async fn where_clause_with_strategies() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let storage = &mut connection_pool.access_storage().await.unwrap();
+        let events_web3_dal = EventsWeb3Dal { storage };
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(100),
+            to_block: MiniblockNumber(200),
+            addresses: vec![Address::from_low_u64_be(123)],
+            topics: vec![(1, vec![H256::from_low_u64_be(456)])],
+        };
+
+        let (sql, _) = events_web3_dal.build_get_logs_where_clause_with_strategy(
+            &filter,
+            Some(LogsQueryStrategy::BlockRange),
+        );
+        assert_eq!(
+            sql,
+            "(miniblock_number >= 100) AND (miniblock_number <= 200) AND ((address || ''::bytea) = ANY($1)) \
+             AND ((topic1 || ''::bytea) = ANY($2))"
+        );
+        let (sql, _) = events_web3_dal.build_get_logs_where_clause_with_strategy(
+            &filter,
+            Some(LogsQueryStrategy::AddressIndex),
+        );
+        assert_eq!(
+            sql,
+            "(miniblock_number >= 100) AND (miniblock_number <= 200) AND (address = ANY($1)) \
+             AND ((topic1 || ''::bytea) = ANY($2))"
+        );
+        let (sql, _) = events_web3_dal.build_get_logs_where_clause_with_strategy(
+            &filter,
+            Some(LogsQueryStrategy::TopicIndex(1)),
+        );
+        assert_eq!(
+            sql,
+            "(miniblock_number >= 100) AND (miniblock_number <= 200) \
+             AND ((address || ''::bytea) = ANY($1)) AND (topic1 = ANY($2))"
+        );
+    }
+
+    fn create_vm_event(index: u8, topic_count: u8) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), u32::from(index)),
+            address: Address::repeat_byte(index),
+            indexed_topics: (0..topic_count).map(H256::repeat_byte).collect(),
+            value: vec![index],
+        }
+    }
+
+    #[tokio::test]
+    async fn getting_logs_with_strategies() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let location = IncludedTxLocation {
+            tx_hash: H256([1; 32]),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let events: Vec<_> = (0..5).map(|i| create_vm_event(i, i % 2 * 4)).collect();
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &[(location, events.iter().collect())])
+            .await;
+        sqlx::query("ANALYZE events")
+            .execute(conn.conn())
+            .await
+            .unwrap();
+
+        let stats = conn
+            .events_web3_dal()
+            .get_events_statistics()
+            .await
+            .unwrap()
+            .expect("no statistics");
+        assert!(stats.total_events > 0.0, "{stats:?}");
+
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(1),
+            addresses: vec![Address::repeat_byte(1), Address::repeat_byte(2)],
+            topics: vec![(1, vec![H256::repeat_byte(0)])],
+        };
+        let strategies = [
+            None,
+            Some(LogsQueryStrategy::BlockRange),
+            Some(LogsQueryStrategy::AddressIndex),
+            Some(LogsQueryStrategy::TopicIndex(1)),
+        ];
+        for strategy in strategies {
+            let logs = conn
+                .events_web3_dal()
+                .get_logs_with_strategy(filter.clone(), 100, strategy)
+                .await
+                .unwrap();
+            assert_eq!(logs.len(), 1, "{strategy:?}");
+            assert_eq!(logs[0].address, Address::repeat_byte(1));
+        }
+    }
 }
@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use zksync_types::MiniblockNumber;
+
+use crate::{instrument::InstrumentExt, time_utils::pg_interval_from_duration, StorageProcessor};
+
+/// State of a WebSocket subscription handed off between API server replicas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedSubscription {
+    pub id: String,
+    /// Subscription type and parameters; the format is defined by the API server.
+    pub params: serde_json::Value,
+    /// Last miniblock for which notifications were sent to the subscriber, if known.
+    pub last_miniblock: Option<MiniblockNumber>,
+}
+
+/// Shared store of WebSocket subscriptions exported by API server replicas on shutdown.
+#[derive(Debug)]
+pub struct ExportedSubscriptionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ExportedSubscriptionsDal<'_, '_> {
+    /// Saves the provided subscriptions, overwriting the state of subscriptions with the same IDs.
+    pub async fn insert_subscriptions(
+        &mut self,
+        subscriptions: &[ExportedSubscription],
+    ) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        for subscription in subscriptions {
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    exported_subscriptions (id, params, last_miniblock, exported_at)
+                VALUES
+                    ($1, $2, $3, NOW())
+                ON CONFLICT (id) DO
+                UPDATE
+                SET
+                    params = excluded.params,
+                    last_miniblock = excluded.last_miniblock,
+                    exported_at = excluded.exported_at
+                "#,
+                subscription.id,
+                subscription.params,
+                subscription
+                    .last_miniblock
+                    .map(|number| i64::from(number.0))
+            )
+            .instrument("insert_exported_subscription")
+            .with_arg("id", &subscription.id)
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await
+    }
+
+    /// Removes the subscription with the specified ID from the store and returns it. Subscriptions exported
+    /// longer than `ttl` ago are not returned.
+    pub async fn take_subscription(
+        &mut self,
+        id: &str,
+        ttl: Duration,
+    ) -> sqlx::Result<Option<ExportedSubscription>> {
+        let row = sqlx::query!(
+            r#"
+            DELETE FROM exported_subscriptions
+            WHERE
+                id = $1
+            RETURNING
+                id,
+                params,
+                last_miniblock,
+                exported_at >= NOW() - $2::INTERVAL AS "is_fresh!"
+            "#,
+            id,
+            &pg_interval_from_duration(ttl)
+        )
+        .instrument("take_exported_subscription")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row
+            .filter(|row| row.is_fresh)
+            .map(|row| ExportedSubscription {
+                id: row.id,
+                params: row.params,
+                last_miniblock: row
+                    .last_miniblock
+                    .map(|number| MiniblockNumber(number as u32)),
+            }))
+    }
+
+    /// Removes subscriptions exported longer than `ttl` ago. Returns the number of removed subscriptions.
+    pub async fn prune_subscriptions(&mut self, ttl: Duration) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM exported_subscriptions
+            WHERE
+                exported_at < NOW() - $1::INTERVAL
+            "#,
+            &pg_interval_from_duration(ttl)
+        )
+        .instrument("prune_exported_subscriptions")
+        .with_arg("ttl", &ttl)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn exporting_and_taking_subscriptions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        const TTL: Duration = Duration::from_secs(600);
+
+        let subscriptions = [
+            ExportedSubscription {
+                id: "0x01".to_owned(),
+                params: json!({ "type": "newHeads" }),
+                last_miniblock: Some(MiniblockNumber(5)),
+            },
+            ExportedSubscription {
+                id: "0x02".to_owned(),
+                params: json!({ "type": "newPendingTransactions" }),
+                last_miniblock: None,
+            },
+        ];
+        conn.exported_subscriptions_dal()
+            .insert_subscriptions(&subscriptions)
+            .await
+            .unwrap();
+
+        // Re-exporting a subscription overwrites its state.
+        let updated_subscription = ExportedSubscription {
+            last_miniblock: Some(MiniblockNumber(7)),
+            ..subscriptions[0].clone()
+        };
+        conn.exported_subscriptions_dal()
+            .insert_subscriptions(&[updated_subscription.clone()])
+            .await
+            .unwrap();
+
+        let taken = conn
+            .exported_subscriptions_dal()
+            .take_subscription("0x01", TTL)
+            .await
+            .unwrap();
+        assert_eq!(taken, Some(updated_subscription));
+        // A subscription can only be taken once.
+        let taken = conn
+            .exported_subscriptions_dal()
+            .take_subscription("0x01", TTL)
+            .await
+            .unwrap();
+        assert_eq!(taken, None);
+
+        // Stale subscriptions are not returned.
+        let taken = conn
+            .exported_subscriptions_dal()
+            .take_subscription("0x02", Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(taken, None);
+    }
+
+    #[tokio::test]
+    async fn pruning_exported_subscriptions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+
+        let subscription = ExportedSubscription {
+            id: "0x01".to_owned(),
+            params: json!({ "type": "newHeads" }),
+            last_miniblock: None,
+        };
+        conn.exported_subscriptions_dal()
+            .insert_subscriptions(&[subscription])
+            .await
+            .unwrap();
+
+        let pruned = conn
+            .exported_subscriptions_dal()
+            .prune_subscriptions(Duration::from_secs(600))
+            .await
+            .unwrap();
+        assert_eq!(pruned, 0);
+        let pruned = conn
+            .exported_subscriptions_dal()
+            .prune_subscriptions(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(pruned, 1);
+    }
+}
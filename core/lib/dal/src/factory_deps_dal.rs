@@ -1,8 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    ops,
+};
 
 use anyhow::Context as _;
 use zksync_contracts::{BaseSystemContracts, SystemContractCode};
-use zksync_types::{MiniblockNumber, H256, U256};
+use zksync_types::{MiniblockNumber, ACCOUNT_CODE_STORAGE_ADDRESS, H256, U256};
 use zksync_utils::{bytes_to_be_words, bytes_to_chunks};
 
 use crate::StorageProcessor;
@@ -69,6 +72,55 @@ impl FactoryDepsDal<'_, '_> {
         .map(|row| row.bytecode))
     }
 
+    /// Returns hashes of bytecodes deployed to contract addresses (i.e., stored in the account code storage)
+    /// in the specified miniblock range that have no preimage in the `factory_deps` table, together with
+    /// the first miniblock in the range writing each hash. The VM cannot load such bytecodes, so executing
+    /// transactions calling the corresponding contracts fails.
+    ///
+    /// The range bounds the number of scanned storage logs; scanning all of them is prohibitively slow for large DBs.
+    pub async fn get_missing_deployed_bytecodes(
+        &mut self,
+        miniblock_range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<(H256, MiniblockNumber)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                storage_logs.value AS bytecode_hash,
+                MIN(storage_logs.miniblock_number) AS "miniblock_number!"
+            FROM
+                storage_logs
+                LEFT JOIN factory_deps ON factory_deps.bytecode_hash = storage_logs.value
+            WHERE
+                storage_logs.miniblock_number BETWEEN $1 AND $2
+                AND storage_logs.address = $3
+                AND storage_logs.value != $4
+                -- Hashes of contracts being constructed are marked with a non-zero second byte
+                AND GET_BYTE(storage_logs.value, 1) = 0
+                AND factory_deps.bytecode_hash IS NULL
+            GROUP BY
+                storage_logs.value
+            ORDER BY
+                "miniblock_number!"
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64,
+            ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
+            H256::zero().as_bytes(),
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    H256::from_slice(&row.bytecode_hash),
+                    MiniblockNumber(row.miniblock_number as u32),
+                )
+            })
+            .collect())
+    }
+
     pub async fn get_base_system_contracts(
         &mut self,
         bootloader_hash: H256,
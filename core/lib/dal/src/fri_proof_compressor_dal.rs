@@ -185,6 +185,8 @@ impl FriProofCompressorDal<'_, '_> {
         .unwrap();
     }
 
+    /// Returns the earliest batch with a compressed proof that is not sent to the server yet. Batches
+    /// requeued for re-proving are ignored.
     pub async fn get_least_proven_block_number_not_sent_to_server(
         &mut self,
     ) -> Option<(L1BatchNumber, ProofCompressionJobStatus)> {
@@ -202,8 +204,17 @@ impl FriProofCompressorDal<'_, '_> {
                     FROM
                         proof_compression_jobs_fri
                     WHERE
-                        status = $1
-                        OR status = $2
+                        (
+                            status = $1
+                            OR status = $2
+                        )
+                        -- Proofs for re-proven batches are only used for validation and are not sent
+                        AND l1_batch_number NOT IN (
+                            SELECT
+                                l1_batch_number
+                            FROM
+                                fri_batch_reproofs
+                        )
                 )
             "#,
             ProofCompressionJobStatus::Successful.to_string(),
@@ -221,6 +232,27 @@ impl FriProofCompressorDal<'_, '_> {
         }
     }
 
+    pub async fn get_proof_compression_job_status(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> Option<ProofCompressionJobStatus> {
+        sqlx::query!(
+            r#"
+            SELECT
+                status
+            FROM
+                proof_compression_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            block_number.0 as i64,
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()
+        .map(|row| ProofCompressionJobStatus::from_str(&row.status).unwrap())
+    }
+
     pub async fn mark_proof_sent_to_server(&mut self, block_number: L1BatchNumber) {
         sqlx::query!(
             r#"
@@ -283,6 +315,40 @@ impl FriProofCompressorDal<'_, '_> {
         result
     }
 
+    /// Returns L1 batches with a number greater than `after` that have a finished (compressed) proof.
+    pub async fn get_proven_l1_batches_after(
+        &mut self,
+        after: L1BatchNumber,
+        limit: usize,
+    ) -> sqlx::Result<Vec<L1BatchNumber>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                proof_compression_jobs_fri
+            WHERE
+                l1_batch_number > $1
+                AND status IN ($2, $3)
+            ORDER BY
+                l1_batch_number ASC
+            LIMIT
+                $4
+            "#,
+            after.0 as i64,
+            ProofCompressionJobStatus::Successful.to_string(),
+            ProofCompressionJobStatus::SentToServer.to_string(),
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+            .collect())
+    }
+
     pub async fn requeue_stuck_jobs(
         &mut self,
         processing_timeout: Duration,
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, convert::TryFrom, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    time::Duration,
+};
 
 use zksync_types::{
     basic_fri_types::{AggregationRound, CircuitIdRoundTuple},
@@ -778,4 +782,230 @@ impl FriProverDal<'_, '_> {
         .ok()?
         .map(|row| row.id as u32)
     }
+
+    /// Returns object store keys of circuits and proofs for prover jobs of the L1 batches with numbers greater
+    /// than `last_l1_batch_to_keep`.
+    pub async fn get_blob_urls_for_l1_batches_after(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> sqlx::Result<Vec<(String, Option<String>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                circuit_blob_url,
+                proof_blob_url
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number > $1
+            "#,
+            i64::from(last_l1_batch_to_keep.0)
+        )
+        .instrument("get_blob_urls_for_l1_batches_after")
+        .with_arg("last_l1_batch_to_keep", &last_l1_batch_to_keep)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.circuit_blob_url, row.proof_blob_url))
+            .collect())
+    }
+
+    /// Returns the number of witness generation, proving and compression jobs for L1 batches with numbers greater
+    /// than `last_l1_batch_to_keep`, keyed by the table name.
+    pub async fn get_job_counts_for_l1_batches_after(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> sqlx::Result<BTreeMap<&'static str, u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        scheduler_dependency_tracker_fri
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "scheduler_dependency_tracker_fri!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        scheduler_witness_jobs_fri
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "scheduler_witness_jobs_fri!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        node_aggregation_witness_jobs_fri
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "node_aggregation_witness_jobs_fri!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        leaf_aggregation_witness_jobs_fri
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "leaf_aggregation_witness_jobs_fri!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        prover_jobs_fri
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "prover_jobs_fri!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        proof_compression_jobs_fri
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "proof_compression_jobs_fri!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        fri_batch_reproofs
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "fri_batch_reproofs!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        witness_inputs_fri
+                    WHERE
+                        l1_batch_number > $1
+                ) AS "witness_inputs_fri!"
+            "#,
+            i64::from(last_l1_batch_to_keep.0)
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        let counts = [
+            (
+                "scheduler_dependency_tracker_fri",
+                row.scheduler_dependency_tracker_fri,
+            ),
+            ("scheduler_witness_jobs_fri", row.scheduler_witness_jobs_fri),
+            (
+                "node_aggregation_witness_jobs_fri",
+                row.node_aggregation_witness_jobs_fri,
+            ),
+            (
+                "leaf_aggregation_witness_jobs_fri",
+                row.leaf_aggregation_witness_jobs_fri,
+            ),
+            ("prover_jobs_fri", row.prover_jobs_fri),
+            ("proof_compression_jobs_fri", row.proof_compression_jobs_fri),
+            ("fri_batch_reproofs", row.fri_batch_reproofs),
+            ("witness_inputs_fri", row.witness_inputs_fri),
+        ];
+        Ok(counts
+            .into_iter()
+            .map(|(table, count)| (table, count as u64))
+            .collect())
+    }
+
+    /// Removes all witness generation, proving and compression jobs for L1 batches with numbers greater
+    /// than `last_l1_batch_to_keep`. Returns the total number of removed jobs.
+    pub async fn delete_jobs_for_l1_batches_after(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> sqlx::Result<u64> {
+        let l1_batch_number = i64::from(last_l1_batch_to_keep.0);
+        let mut transaction = self.storage.start_transaction().await?;
+        // Dependency tracker rows reference prover jobs, so they are removed first.
+        let results = [
+            sqlx::query!(
+                r#"
+            DELETE FROM scheduler_dependency_tracker_fri
+            WHERE
+                l1_batch_number > $1
+            "#,
+                l1_batch_number
+            )
+            .execute(transaction.conn())
+            .await?,
+            sqlx::query!(
+                r#"
+            DELETE FROM scheduler_witness_jobs_fri
+            WHERE
+                l1_batch_number > $1
+            "#,
+                l1_batch_number
+            )
+            .execute(transaction.conn())
+            .await?,
+            sqlx::query!(
+                r#"
+            DELETE FROM node_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number > $1
+            "#,
+                l1_batch_number
+            )
+            .execute(transaction.conn())
+            .await?,
+            sqlx::query!(
+                r#"
+            DELETE FROM leaf_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number > $1
+            "#,
+                l1_batch_number
+            )
+            .execute(transaction.conn())
+            .await?,
+            sqlx::query!(
+                r#"
+            DELETE FROM prover_jobs_fri
+            WHERE
+                l1_batch_number > $1
+            "#,
+                l1_batch_number
+            )
+            .execute(transaction.conn())
+            .await?,
+            sqlx::query!(
+                r#"
+            DELETE FROM proof_compression_jobs_fri
+            WHERE
+                l1_batch_number > $1
+            "#,
+                l1_batch_number
+            )
+            .execute(transaction.conn())
+            .await?,
+            sqlx::query!(
+                r#"
+            DELETE FROM fri_batch_reproofs
+            WHERE
+                l1_batch_number > $1
+            "#,
+                l1_batch_number
+            )
+            .execute(transaction.conn())
+            .await?,
+            sqlx::query!(
+                r#"
+            DELETE FROM witness_inputs_fri
+            WHERE
+                l1_batch_number > $1
+            "#,
+                l1_batch_number
+            )
+            .execute(transaction.conn())
+            .await?,
+        ];
+        transaction.commit().await?;
+        Ok(results.iter().map(|result| result.rows_affected()).sum())
+    }
 }
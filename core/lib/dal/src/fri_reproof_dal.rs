@@ -0,0 +1,265 @@
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use zksync_types::{protocol_version::FriProtocolVersionId, L1BatchNumber};
+
+use crate::StorageProcessor;
+
+/// Batch requeued to be re-proven under a specific protocol version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchReproof {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: FriProtocolVersionId,
+    pub original_protocol_version: Option<FriProtocolVersionId>,
+    pub original_proof_blob_url: String,
+    pub original_circuit_counts: serde_json::Value,
+    pub report: Option<serde_json::Value>,
+}
+
+#[derive(Debug)]
+pub struct FriReproofDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl FriReproofDal<'_, '_> {
+    /// Returns the number of prover jobs per aggregation round for the specified batch.
+    pub async fn get_circuit_counts(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<BTreeMap<u8, u64>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                aggregation_round,
+                COUNT(*) AS "count!"
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            GROUP BY
+                aggregation_round
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.aggregation_round as u8, row.count as u64))
+            .collect())
+    }
+
+    /// Requeues the specified batch to be re-proven under `protocol_version`. All prover jobs for the batch
+    /// are removed, and basic witness generation for the batch is queued again. Returns the protocol version
+    /// the batch was originally proven with.
+    ///
+    /// If the batch was re-proven before, the original protocol version, proof URL and circuit counts recorded
+    /// for the first re-proof are preserved, so that re-proofs are always compared with the original proof.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`sqlx::Error::RowNotFound`] if witness inputs for the batch are not present.
+    pub async fn enqueue_reproof(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        protocol_version: FriProtocolVersionId,
+        original_proof_blob_url: &str,
+        original_circuit_counts: &serde_json::Value,
+    ) -> sqlx::Result<Option<FriProtocolVersionId>> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let witness_inputs = sqlx::query!(
+            r#"
+            SELECT
+                protocol_version
+            FROM
+                witness_inputs_fri
+            WHERE
+                l1_batch_number = $1
+            FOR UPDATE
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .fetch_optional(transaction.conn())
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+        let original_protocol_version = witness_inputs
+            .protocol_version
+            .map(|id| FriProtocolVersionId::try_from(id as u16).unwrap());
+
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                fri_batch_reproofs (
+                    l1_batch_number,
+                    protocol_version,
+                    original_protocol_version,
+                    original_proof_blob_url,
+                    original_circuit_counts,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                protocol_version = $2,
+                report = NULL,
+                updated_at = NOW()
+            RETURNING
+                original_protocol_version
+            "#,
+            l1_batch_number.0 as i64,
+            protocol_version as i32,
+            original_protocol_version.map(|id| id as i32),
+            original_proof_blob_url,
+            original_circuit_counts,
+        )
+        .fetch_one(transaction.conn())
+        .await?;
+        let original_protocol_version = row
+            .original_protocol_version
+            .map(|id| FriProtocolVersionId::try_from(id as u16).unwrap());
+
+        // Dependency tracker rows reference prover jobs, so they are removed first.
+        sqlx::query!(
+            r#"
+            DELETE FROM scheduler_dependency_tracker_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM scheduler_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM node_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM leaf_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM proof_compression_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE witness_inputs_fri
+            SET
+                status = 'queued',
+                protocol_version = $2,
+                attempts = 0,
+                error = NULL,
+                processing_started_at = NULL,
+                time_taken = NULL,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+            protocol_version as i32,
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+        Ok(original_protocol_version)
+    }
+
+    pub async fn get_reproof(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<BatchReproof>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                protocol_version,
+                original_protocol_version,
+                original_proof_blob_url,
+                original_circuit_counts,
+                report
+            FROM
+                fri_batch_reproofs
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(|row| BatchReproof {
+            l1_batch_number,
+            protocol_version: FriProtocolVersionId::try_from(row.protocol_version as u16).unwrap(),
+            original_protocol_version: row
+                .original_protocol_version
+                .map(|id| FriProtocolVersionId::try_from(id as u16).unwrap()),
+            original_proof_blob_url: row.original_proof_blob_url,
+            original_circuit_counts: row.original_circuit_counts,
+            report: row.report,
+        }))
+    }
+
+    pub async fn save_report(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        report: &serde_json::Value,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE fri_batch_reproofs
+            SET
+                report = $2,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+            report,
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+}
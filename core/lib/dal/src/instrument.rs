@@ -129,6 +129,11 @@ impl<'a> InstrumentedData<'a> {
             report_latency,
             slow_query_reporting_enabled,
         } = self;
+        #[cfg(feature = "chaos")]
+        zksync_utils::chaos::fault_point(&format!("dal.{name}"))
+            .await
+            .map_err(|err| sqlx::Error::Protocol(err.to_string()))?;
+
         let started_at = Instant::now();
         tokio::pin!(query_future);
 
@@ -232,6 +237,12 @@ where
         let (conn, tags) = storage.conn_and_tags();
         self.data.fetch(tags, self.query.fetch_optional(conn)).await
     }
+
+    /// Fetches all rows using this query.
+    pub async fn fetch_all(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<Vec<PgRow>> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_all(conn)).await
+    }
 }
 
 impl<'q, O, A> Instrumented<'_, QueryAs<'q, Postgres, O, A>>
@@ -4,19 +4,24 @@ pub use sqlx::{types::BigDecimal, Error as SqlxError};
 
 pub use crate::connection::{ConnectionPool, StorageProcessor};
 use crate::{
-    basic_witness_input_producer_dal::BasicWitnessInputProducerDal, blocks_dal::BlocksDal,
-    blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
-    contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
-    events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
+    audit_log_dal::AuditLogDal, basic_witness_input_producer_dal::BasicWitnessInputProducerDal,
+    blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal, chain_freeze_dal::ChainFreezeDal,
+    cold_storage_dal::ColdStorageDal, consensus_dal::ConsensusDal,
+    contract_verification_dal::ContractVerificationDal, data_availability_dal::DataAvailabilityDal,
+    eth_sender_dal::EthSenderDal, events_dal::EventsDal, events_web3_dal::EventsWeb3Dal,
+    exported_subscriptions_dal::ExportedSubscriptionsDal, factory_deps_dal::FactoryDepsDal,
     fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
     fri_proof_compressor_dal::FriProofCompressorDal,
     fri_protocol_versions_dal::FriProtocolVersionsDal, fri_prover_dal::FriProverDal,
+    fri_reproof_dal::FriReproofDal,
     fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDal,
-    fri_witness_generator_dal::FriWitnessGeneratorDal, proof_generation_dal::ProofGenerationDal,
-    protocol_versions_dal::ProtocolVersionsDal,
-    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal,
-    snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
-    snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
+    fri_witness_generator_dal::FriWitnessGeneratorDal,
+    object_store_retention_dal::ObjectStoreRetentionDal, priority_tree_dal::PriorityTreeDal,
+    proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
+    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, scheduled_txs_dal::ScheduledTxsDal,
+    shadow_sequencer_dal::ShadowSequencerDal, snapshot_recovery_dal::SnapshotRecoveryDal,
+    snapshots_creator_dal::SnapshotsCreatorDal, snapshots_dal::SnapshotsDal,
+    solvency_reports_dal::SolvencyReportsDal, storage_logs_dal::StorageLogsDal,
     storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
     sync_dal::SyncDal, system_dal::SystemDal, tokens_dal::TokensDal,
     tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
@@ -25,32 +30,44 @@ use crate::{
 
 #[macro_use]
 mod macro_utils;
+pub mod audit_log_dal;
 pub mod basic_witness_input_producer_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
+pub mod chain_freeze_dal;
+pub mod cold_storage_dal;
 pub mod connection;
 pub mod consensus_dal;
 pub mod contract_verification_dal;
+pub mod data_availability_dal;
 pub mod eth_sender_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
+pub mod exported_subscriptions_dal;
 pub mod factory_deps_dal;
 pub mod fri_gpu_prover_queue_dal;
 pub mod fri_proof_compressor_dal;
 pub mod fri_protocol_versions_dal;
 pub mod fri_prover_dal;
+pub mod fri_reproof_dal;
 pub mod fri_scheduler_dependency_tracker_dal;
 pub mod fri_witness_generator_dal;
 pub mod healthcheck;
 mod instrument;
+pub mod logs_query_planner;
 mod metrics;
 mod models;
+pub mod object_store_retention_dal;
+pub mod priority_tree_dal;
 pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
+pub mod scheduled_txs_dal;
+pub mod shadow_sequencer_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
+pub mod solvency_reports_dal;
 mod storage_dal;
 pub mod storage_logs_dal;
 pub mod storage_logs_dedup_dal;
@@ -179,6 +196,10 @@ impl<'a> StorageProcessor<'a> {
         FriProofCompressorDal { storage: self }
     }
 
+    pub fn fri_reproof_dal(&mut self) -> FriReproofDal<'_, 'a> {
+        FriReproofDal { storage: self }
+    }
+
     pub fn system_dal(&mut self) -> SystemDal<'_, 'a> {
         SystemDal { storage: self }
     }
@@ -194,4 +215,44 @@ impl<'a> StorageProcessor<'a> {
     pub fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a> {
         SnapshotRecoveryDal { storage: self }
     }
+
+    pub fn data_availability_dal(&mut self) -> DataAvailabilityDal<'_, 'a> {
+        DataAvailabilityDal { storage: self }
+    }
+
+    pub fn object_store_retention_dal(&mut self) -> ObjectStoreRetentionDal<'_, 'a> {
+        ObjectStoreRetentionDal { storage: self }
+    }
+
+    pub fn audit_log_dal(&mut self) -> AuditLogDal<'_, 'a> {
+        AuditLogDal { storage: self }
+    }
+
+    pub fn scheduled_txs_dal(&mut self) -> ScheduledTxsDal<'_, 'a> {
+        ScheduledTxsDal { storage: self }
+    }
+
+    pub fn solvency_reports_dal(&mut self) -> SolvencyReportsDal<'_, 'a> {
+        SolvencyReportsDal { storage: self }
+    }
+
+    pub fn exported_subscriptions_dal(&mut self) -> ExportedSubscriptionsDal<'_, 'a> {
+        ExportedSubscriptionsDal { storage: self }
+    }
+
+    pub fn chain_freeze_dal(&mut self) -> ChainFreezeDal<'_, 'a> {
+        ChainFreezeDal { storage: self }
+    }
+
+    pub fn cold_storage_dal(&mut self) -> ColdStorageDal<'_, 'a> {
+        ColdStorageDal { storage: self }
+    }
+
+    pub fn shadow_sequencer_dal(&mut self) -> ShadowSequencerDal<'_, 'a> {
+        ShadowSequencerDal { storage: self }
+    }
+
+    pub fn priority_tree_dal(&mut self) -> PriorityTreeDal<'_, 'a> {
+        PriorityTreeDal { storage: self }
+    }
 }
@@ -0,0 +1,228 @@
+//! Planning of logs queries.
+//!
+//! Postgres estimates the selectivity of log filters poorly if the filter targets a popular contract:
+//! it tends to use the address index even if a topic in the filter is rare, which leads to scanning
+//! millions of rows. The planner here estimates the number of rows scanned by each applicable index strategy
+//! based on the `events` table statistics and chooses the cheapest one. The chosen strategy is enforced
+//! by making address and topic conditions not used by it non-indexable (see [`EventsWeb3Dal::get_logs_with_strategy()`]).
+//! The block range condition is always left indexable.
+//!
+//! [`EventsWeb3Dal::get_logs_with_strategy()`]: crate::events_web3_dal::EventsWeb3Dal::get_logs_with_strategy()
+
+use std::collections::HashMap;
+
+use zksync_types::{
+    api::{GetLogsFilter, LogsQueryEstimate, LogsQueryStrategy},
+    Address, H256,
+};
+
+/// Statistics for a single column of the `events` table.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnStatistics {
+    /// Estimated number of distinct values in the column.
+    pub distinct_values: f64,
+    /// Most common values together with their frequencies (i.e., the fraction of rows with the value).
+    pub most_common_values: HashMap<Vec<u8>, f64>,
+}
+
+impl ColumnStatistics {
+    /// Estimates the fraction of rows having one of the specified values in the column.
+    pub fn selectivity<'a>(&self, values: impl Iterator<Item = &'a [u8]>) -> f64 {
+        let common_frequency: f64 = self.most_common_values.values().sum();
+        let other_distinct_values =
+            (self.distinct_values - self.most_common_values.len() as f64).max(1.0);
+        let other_frequency = ((1.0 - common_frequency) / other_distinct_values).max(0.0);
+        let selectivity: f64 = values
+            .map(|value| {
+                self.most_common_values
+                    .get(value)
+                    .copied()
+                    .unwrap_or(other_frequency)
+            })
+            .sum();
+        selectivity.min(1.0)
+    }
+}
+
+/// Statistics of the `events` table used to plan logs queries.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventsStatistics {
+    /// Estimated number of rows in the table.
+    pub total_events: f64,
+    /// Number of miniblocks in the storage.
+    pub total_miniblocks: f64,
+    pub address: ColumnStatistics,
+    /// Statistics for `topic1`..`topic4` columns.
+    pub topics: [ColumnStatistics; 4],
+}
+
+/// Plan for a logs query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogsQueryPlan {
+    /// Chosen strategy.
+    pub strategy: LogsQueryStrategy,
+    /// Estimates for all applicable strategies, including the chosen one.
+    pub estimates: Vec<LogsQueryEstimate>,
+}
+
+impl EventsStatistics {
+    /// Chooses the strategy scanning the least number of rows for the specified filter.
+    pub fn plan(&self, filter: &GetLogsFilter) -> LogsQueryPlan {
+        let block_count = filter.to_block.0.saturating_sub(filter.from_block.0) as f64 + 1.0;
+        let range_fraction = (block_count / self.total_miniblocks.max(1.0)).min(1.0);
+
+        let mut estimates = vec![Self::estimate(
+            LogsQueryStrategy::BlockRange,
+            self.total_events * range_fraction,
+        )];
+        if !filter.addresses.is_empty() {
+            let addresses = filter.addresses.iter().map(Address::as_bytes);
+            // The address index also covers miniblock numbers, so the block range limits the scan as well.
+            let selectivity = self.address.selectivity(addresses);
+            estimates.push(Self::estimate(
+                LogsQueryStrategy::AddressIndex,
+                self.total_events * selectivity * range_fraction,
+            ));
+        }
+        for (topic_index, topics) in &filter.topics {
+            let Some(stats) = topic_index
+                .checked_sub(1)
+                .and_then(|idx| self.topics.get(idx as usize))
+            else {
+                continue;
+            };
+            let selectivity = stats.selectivity(topics.iter().map(H256::as_bytes));
+            // Since the block range condition stays indexable, Postgres combines the topic index with the block range
+            // (e.g., via a bitmap AND), so only rows in the range are scanned.
+            estimates.push(Self::estimate(
+                LogsQueryStrategy::TopicIndex(*topic_index),
+                self.total_events * selectivity * range_fraction,
+            ));
+        }
+
+        let best_estimate = estimates
+            .iter()
+            .min_by(|x, y| x.estimated_rows.total_cmp(&y.estimated_rows))
+            .expect("no estimates");
+        LogsQueryPlan {
+            strategy: best_estimate.strategy,
+            estimates,
+        }
+    }
+
+    fn estimate(strategy: LogsQueryStrategy, estimated_rows: f64) -> LogsQueryEstimate {
+        LogsQueryEstimate {
+            strategy,
+            estimated_rows,
+            latency_ms: None,
+            log_count: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::MiniblockNumber;
+
+    use super::*;
+
+    fn popular_contract_stats() -> EventsStatistics {
+        let popular_address = Address::repeat_byte(1);
+        let popular_topic = H256::repeat_byte(2);
+        EventsStatistics {
+            total_events: 1_000_000.0,
+            total_miniblocks: 10_000.0,
+            address: ColumnStatistics {
+                distinct_values: 1_000.0,
+                most_common_values: HashMap::from([(popular_address.as_bytes().to_vec(), 0.5)]),
+            },
+            topics: [
+                ColumnStatistics {
+                    distinct_values: 100_000.0,
+                    most_common_values: HashMap::from([(popular_topic.as_bytes().to_vec(), 0.6)]),
+                },
+                ColumnStatistics::default(),
+                ColumnStatistics::default(),
+                ColumnStatistics::default(),
+            ],
+        }
+    }
+
+    #[test]
+    fn estimating_selectivity() {
+        let stats = popular_contract_stats();
+        let popular_address = Address::repeat_byte(1);
+        let selectivity = stats
+            .address
+            .selectivity([popular_address.as_bytes()].into_iter());
+        assert_eq!(selectivity, 0.5);
+        let other_address = Address::repeat_byte(3);
+        let selectivity = stats
+            .address
+            .selectivity([other_address.as_bytes()].into_iter());
+        assert!((selectivity - 0.5 / 999.0).abs() < 1e-9, "{selectivity}");
+    }
+
+    #[test]
+    fn planning_queries_for_popular_contract() {
+        let stats = popular_contract_stats();
+        let mut filter = GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(9_999),
+            addresses: vec![Address::repeat_byte(1)],
+            topics: vec![(1, vec![H256::repeat_byte(0xff)])],
+        };
+        // The topic is rare, so it's more efficient to use the topic index.
+        let plan = stats.plan(&filter);
+        assert_eq!(plan.strategy, LogsQueryStrategy::TopicIndex(1));
+        assert_eq!(plan.estimates.len(), 3);
+
+        // The topic is popular as well, so it's better to scan the address index.
+        filter.topics = vec![(1, vec![H256::repeat_byte(2)])];
+        let plan = stats.plan(&filter);
+        assert_eq!(plan.strategy, LogsQueryStrategy::AddressIndex);
+
+        // For a small block range, scanning all logs in the range is the cheapest option.
+        filter.from_block = MiniblockNumber(9_999);
+        filter.topics = vec![];
+        filter.addresses = vec![];
+        let plan = stats.plan(&filter);
+        assert_eq!(plan.strategy, LogsQueryStrategy::BlockRange);
+        assert_eq!(plan.estimates.len(), 1);
+    }
+
+    #[test]
+    fn topic_index_estimate_depends_on_block_range() {
+        let stats = popular_contract_stats();
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(9_999),
+            addresses: vec![],
+            topics: vec![(1, vec![H256::repeat_byte(2)])],
+        };
+        let full_range_plan = stats.plan(&filter);
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(9_000),
+            ..filter
+        };
+        let partial_range_plan = stats.plan(&filter);
+
+        let topic_estimate = |plan: &LogsQueryPlan| {
+            plan.estimates
+                .iter()
+                .find(|estimate| estimate.strategy == LogsQueryStrategy::TopicIndex(1))
+                .unwrap()
+                .estimated_rows
+        };
+        let full_range_estimate = topic_estimate(&full_range_plan);
+        let partial_range_estimate = topic_estimate(&partial_range_plan);
+        assert!(
+            (full_range_estimate - 600_000.0).abs() < 1e-6,
+            "{full_range_estimate}"
+        );
+        assert!(
+            (partial_range_estimate - 60_000.0).abs() < 1e-6,
+            "{partial_range_estimate}"
+        );
+    }
+}
@@ -3,7 +3,7 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics, Unit,
 };
 
@@ -92,6 +92,13 @@ pub(crate) struct ConnectionMetrics {
     /// Lifetime of a DB connection, tagged with the requester label.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["requester"])]
     pub lifetime: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of currently held DB connections, tagged with the requester label.
+    #[metrics(labels = ["requester"])]
+    pub active: LabeledFamily<&'static str, Gauge<usize>>,
+    /// Number of DB connections held for longer than the leak detection threshold, tagged with the requester label.
+    /// Untagged connections are reported with the `untagged` label.
+    #[metrics(labels = ["requester"])]
+    pub leaked: LabeledFamily<&'static str, Counter>,
 }
 
 #[vise::register]
@@ -31,6 +31,7 @@ pub struct Payload {
     pub operator_address: Address,
     pub transactions: Vec<Transaction>,
     pub last_in_batch: bool,
+    pub gas_limit: Option<u64>,
 }
 
 impl ProtoFmt for Payload {
@@ -63,6 +64,7 @@ impl ProtoFmt for Payload {
                 .context("operator_address")?,
             transactions,
             last_in_batch: *required(&message.last_in_batch).context("last_in_batch")?,
+            gas_limit: message.gas_limit,
         })
     }
 
@@ -84,6 +86,7 @@ impl ProtoFmt for Payload {
                 .map(proto::Transaction::build)
                 .collect(),
             last_in_batch: Some(self.last_in_batch),
+            gas_limit: self.gas_limit,
         }
     }
 }
@@ -223,6 +226,9 @@ impl ProtoRepr for proto::Transaction {
                                     .clone(),
                             }
                         },
+                        // Deadlines and arrival sequence numbers are only relevant for mempool transactions.
+                        valid_until: None,
+                        arrival_seq: None,
                     })
                 }
                 proto::transaction::CommonData::ProtocolUpgrade(common_data) => {
@@ -16,6 +16,7 @@ use zksync_types::{
     l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log, UserL2ToL1Log},
     Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H2048, H256,
 };
+use zksync_utils::bigdecimal_to_u256;
 
 #[derive(Debug, Error)]
 pub enum StorageL1BatchConvertError {
@@ -339,6 +340,7 @@ pub struct StorageBlockDetails {
     pub default_aa_code_hash: Option<Vec<u8>>,
     pub fee_account_address: Vec<u8>,
     pub protocol_version: Option<i32>,
+    pub seal_info: Option<serde_json::Value>,
 }
 
 impl From<StorageBlockDetails> for api::BlockDetails {
@@ -382,6 +384,9 @@ impl From<StorageBlockDetails> for api::BlockDetails {
                 details.bootloader_code_hash,
                 details.default_aa_code_hash,
             ),
+            seal_info: details
+                .seal_info
+                .map(|info| serde_json::from_value(info).expect("Incorrect seal info")),
         };
         api::BlockDetails {
             base,
@@ -412,6 +417,7 @@ pub struct StorageL1BatchDetails {
     pub l2_fair_gas_price: i64,
     pub bootloader_code_hash: Option<Vec<u8>>,
     pub default_aa_code_hash: Option<Vec<u8>>,
+    pub seal_info: Option<serde_json::Value>,
 }
 
 impl From<StorageL1BatchDetails> for api::L1BatchDetails {
@@ -455,6 +461,9 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
                 details.bootloader_code_hash,
                 details.default_aa_code_hash,
             ),
+            seal_info: details
+                .seal_info
+                .map(|info| serde_json::from_value(info).expect("Incorrect seal info")),
         };
         api::L1BatchDetails {
             base,
@@ -463,6 +472,44 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
     }
 }
 
+pub(crate) struct StorageGasPriceHistoryEntry {
+    pub number: i64,
+    pub l1_batch_number: Option<i64>,
+    pub timestamp: i64,
+    pub base_fee_per_gas: BigDecimal,
+    pub l1_gas_price: i64,
+    pub l2_fair_gas_price: i64,
+    pub fair_pubdata_price: Option<i64>,
+}
+
+impl From<StorageGasPriceHistoryEntry> for api::GasPriceHistoryEntry {
+    fn from(row: StorageGasPriceHistoryEntry) -> Self {
+        // Fair pubdata price is not persisted for miniblocks created before the pubdata-independent fee model
+        // was introduced; for such miniblocks, it's derived from the L1 gas price.
+        let fair_pubdata_price = row.fair_pubdata_price.map_or_else(
+            || {
+                BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
+                    fair_l2_gas_price: row.l2_fair_gas_price as u64,
+                    l1_gas_price: row.l1_gas_price as u64,
+                })
+                .fair_pubdata_price()
+            },
+            |price| price as u64,
+        );
+        Self {
+            number: MiniblockNumber(row.number as u32),
+            l1_batch_number: row
+                .l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            timestamp: row.timestamp as u64,
+            base_fee_per_gas: bigdecimal_to_u256(row.base_fee_per_gas),
+            l1_gas_price: (row.l1_gas_price as u64).into(),
+            fair_l2_gas_price: (row.l2_fair_gas_price as u64).into(),
+            fair_pubdata_price: fair_pubdata_price.into(),
+        }
+    }
+}
+
 pub struct StorageMiniblockHeader {
     pub number: i64,
     pub timestamp: i64,
@@ -488,6 +535,7 @@ pub struct StorageMiniblockHeader {
     // `min(virtual_blocks`, `miniblock_number - virtual_block_number`), i.e. making sure that virtual blocks
     // never go beyond the miniblock they are based on.
     pub virtual_blocks: i64,
+    pub gas_limit: Option<i64>,
 }
 
 impl From<StorageMiniblockHeader> for MiniblockHeader {
@@ -529,6 +577,7 @@ impl From<StorageMiniblockHeader> for MiniblockHeader {
             gas_per_pubdata_limit: row.gas_per_pubdata_limit as u64,
             protocol_version,
             virtual_blocks: row.virtual_blocks as u32,
+            gas_limit: row.gas_limit.map(|limit| limit as u64),
         }
     }
 }
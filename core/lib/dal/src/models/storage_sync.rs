@@ -26,6 +26,7 @@ pub(crate) struct StorageSyncBlock {
     pub protocol_version: i32,
     pub virtual_blocks: i64,
     pub hash: Vec<u8>,
+    pub gas_limit: Option<i64>,
 }
 
 pub(crate) struct SyncBlock {
@@ -41,6 +42,7 @@ pub(crate) struct SyncBlock {
     pub virtual_blocks: u32,
     pub hash: H256,
     pub protocol_version: ProtocolVersionId,
+    pub gas_limit: Option<u64>,
 }
 
 impl TryFrom<StorageSyncBlock> for SyncBlock {
@@ -88,6 +90,10 @@ impl TryFrom<StorageSyncBlock> for SyncBlock {
                 .context("protocol_version")?
                 .try_into()
                 .context("protocol_version")?,
+            gas_limit: block
+                .gas_limit
+                .map(|limit| limit.try_into().context("gas_limit"))
+                .transpose()?,
         })
     }
 }
@@ -108,6 +114,7 @@ impl SyncBlock {
             virtual_blocks: Some(self.virtual_blocks),
             hash: Some(self.hash),
             protocol_version: self.protocol_version,
+            gas_limit: self.gas_limit,
         }
     }
 
@@ -124,6 +131,7 @@ impl SyncBlock {
             operator_address: self.fee_account_address,
             transactions,
             last_in_batch: self.last_in_batch,
+            gas_limit: self.gas_limit,
         }
     }
 }
@@ -9,17 +9,18 @@ use sqlx::{
 };
 use zksync_types::{
     api,
-    api::{TransactionDetails, TransactionReceipt, TransactionStatus},
+    api::{RevertReason, TransactionDetails, TransactionReceipt, TransactionStatus},
     fee::Fee,
     l1::{OpProcessingType, PriorityQueueType},
-    l2::TransactionType,
+    l2::{TransactionType, ValidUntil, TX_EXPIRED_ERROR},
     protocol_version::ProtocolUpgradeTxCommonData,
     transaction_request::PaymasterParams,
     vm_trace::Call,
     web3::types::U64,
     Address, Bytes, Execute, ExecuteTransactionCommon, L1TxCommonData, L2ChainId, L2TxCommonData,
-    Nonce, PackedEthSignature, PriorityOpId, Transaction, EIP_1559_TX_TYPE, EIP_2930_TX_TYPE,
-    EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    MiniblockNumber, Nonce, PackedEthSignature, PriorityOpId, Transaction, EIP_1559_TX_TYPE,
+    EIP_2930_TX_TYPE, EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE,
+    PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
 use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
@@ -70,6 +71,34 @@ pub struct StorageTransaction {
 
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+
+    pub revert_data: Option<Vec<u8>>,
+    pub valid_until_timestamp: Option<i64>,
+    pub valid_until_block: Option<i64>,
+    pub arrival_seq: Option<i64>,
+}
+
+/// Restores the transaction deadline from the `valid_until_*` columns of the `transactions` table.
+pub(crate) fn valid_until_from_storage(
+    timestamp: Option<i64>,
+    block: Option<i64>,
+) -> Option<ValidUntil> {
+    match (timestamp, block) {
+        (Some(timestamp), _) => Some(ValidUntil::Timestamp(timestamp as u64)),
+        (None, Some(block)) => Some(ValidUntil::Block(MiniblockNumber(block as u32))),
+        (None, None) => None,
+    }
+}
+
+/// Splits the transaction deadline into the `valid_until_timestamp` and `valid_until_block` columns.
+pub(crate) fn valid_until_to_storage(
+    valid_until: Option<ValidUntil>,
+) -> (Option<i64>, Option<i64>) {
+    match valid_until {
+        Some(ValidUntil::Timestamp(timestamp)) => (Some(timestamp as i64), None),
+        Some(ValidUntil::Block(block)) => (None, Some(block.0.into())),
+        None => (None, None),
+    }
 }
 
 impl From<StorageTransaction> for L1TxCommonData {
@@ -228,6 +257,9 @@ impl From<StorageTransaction> for L2TxCommonData {
             signature,
             hash,
             input,
+            valid_until_timestamp,
+            valid_until_block,
+            arrival_seq,
             ..
         } = tx;
 
@@ -236,7 +268,7 @@ impl From<StorageTransaction> for L2TxCommonData {
             paymaster_input,
         };
 
-        L2TxCommonData::new(
+        let mut common_data = L2TxCommonData::new(
             nonce,
             fee,
             Address::from_slice(&initiator_address),
@@ -247,7 +279,11 @@ impl From<StorageTransaction> for L2TxCommonData {
             input.expect("input data is mandatory for l2 transactions"),
             H256::from_slice(&hash),
             paymaster_params,
-        )
+        );
+        common_data.valid_until =
+            valid_until_from_storage(valid_until_timestamp, valid_until_block);
+        common_data.arrival_seq = arrival_seq.map(|seq| seq as u64);
+        common_data
     }
 }
 
@@ -353,6 +389,7 @@ impl From<StorageTransactionReceipt> for TransactionReceipt {
             .map_or_else(Default::default, U64::from);
 
         let block_hash = H256::from_slice(&storage_receipt.block_hash);
+        let refunded_gas: U256 = storage_receipt.refunded_gas.into();
         TransactionReceipt {
             transaction_hash: H256::from_slice(&storage_receipt.tx_hash),
             transaction_index,
@@ -371,13 +408,10 @@ impl From<StorageTransactionReceipt> for TransactionReceipt {
                 // For better compatibility with various clients, we never return null.
                 .or_else(|| Some(Address::default())),
             cumulative_gas_used: Default::default(), // TODO: Should be actually calculated (SMA-1183).
-            gas_used: {
-                let refunded_gas: U256 = storage_receipt.refunded_gas.into();
-                storage_receipt.gas_limit.map(|val| {
-                    let gas_limit = bigdecimal_to_u256(val);
-                    gas_limit - refunded_gas
-                })
-            },
+            gas_used: storage_receipt.gas_limit.map(|val| {
+                let gas_limit = bigdecimal_to_u256(val);
+                gas_limit - refunded_gas
+            }),
             effective_gas_price: Some(
                 storage_receipt
                     .effective_gas_price
@@ -395,6 +429,7 @@ impl From<StorageTransactionReceipt> for TransactionReceipt {
             // Even though the Rust SDK recommends us to supply "None" for legacy transactions
             // we always supply some number anyway to have the same behavior as most popular RPCs
             transaction_type: Some(tx_type),
+            gas_refunded: Some(refunded_gas),
         }
     }
 }
@@ -490,6 +525,9 @@ pub struct StorageTransactionDetails {
     pub received_at: NaiveDateTime,
     pub miniblock_number: Option<i64>,
     pub error: Option<String>,
+    pub revert_data: Option<Vec<u8>>,
+    pub valid_until_timestamp: Option<i64>,
+    pub valid_until_block: Option<i64>,
     pub effective_gas_price: Option<BigDecimal>,
     pub refunded_gas: i64,
     pub eth_commit_tx_hash: Option<String>,
@@ -499,8 +537,12 @@ pub struct StorageTransactionDetails {
 
 impl StorageTransactionDetails {
     fn get_transaction_status(&self) -> TransactionStatus {
-        if self.error.is_some() {
-            TransactionStatus::Failed
+        if let Some(error) = &self.error {
+            if self.miniblock_number.is_none() && error.ends_with(TX_EXPIRED_ERROR) {
+                TransactionStatus::Expired
+            } else {
+                TransactionStatus::Failed
+            }
         } else if self.eth_execute_tx_hash.is_some() {
             TransactionStatus::Verified
         } else if self.miniblock_number.is_some() {
@@ -541,6 +583,11 @@ impl From<StorageTransactionDetails> for TransactionDetails {
         let eth_execute_tx_hash = tx_details
             .eth_execute_tx_hash
             .map(|hash| H256::from_str(&hash).unwrap());
+        let revert_reason = tx_details.revert_data.as_deref().map(RevertReason::decode);
+        let valid_until = valid_until_from_storage(
+            tx_details.valid_until_timestamp,
+            tx_details.valid_until_block,
+        );
 
         TransactionDetails {
             is_l1_originated: tx_details.is_priority,
@@ -552,6 +599,8 @@ impl From<StorageTransactionDetails> for TransactionDetails {
             eth_commit_tx_hash,
             eth_prove_tx_hash,
             eth_execute_tx_hash,
+            revert_reason,
+            valid_until,
         }
     }
 }
@@ -0,0 +1,97 @@
+use zksync_types::L1BatchNumber;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Tracks progress of removing expired objects from the object store.
+#[derive(Debug)]
+pub struct ObjectStoreRetentionDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ObjectStoreRetentionDal<'_, '_> {
+    /// Returns the last L1 batch for which objects covered by the specified retention policy were removed.
+    pub async fn get_last_removed_l1_batch(
+        &mut self,
+        policy: &str,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_removed_l1_batch
+            FROM
+                object_store_retention
+            WHERE
+                policy = $1
+            "#,
+            policy
+        )
+        .instrument("get_last_removed_l1_batch")
+        .with_arg("policy", &policy)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| L1BatchNumber(row.last_removed_l1_batch as u32)))
+    }
+
+    /// Sets the last L1 batch for which objects covered by the specified retention policy were removed.
+    pub async fn set_last_removed_l1_batch(
+        &mut self,
+        policy: &str,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                object_store_retention (policy, last_removed_l1_batch, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (policy) DO
+            UPDATE
+            SET
+                last_removed_l1_batch = excluded.last_removed_l1_batch,
+                updated_at = NOW()
+            "#,
+            policy,
+            l1_batch_number.0 as i64
+        )
+        .instrument("set_last_removed_l1_batch")
+        .with_arg("policy", &policy)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn tracking_object_store_retention_progress() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.object_store_retention_dal();
+
+        let last_removed = dal.get_last_removed_l1_batch("proofs").await.unwrap();
+        assert_eq!(last_removed, None);
+
+        dal.set_last_removed_l1_batch("proofs", L1BatchNumber(10))
+            .await
+            .unwrap();
+        dal.set_last_removed_l1_batch("witness_inputs", L1BatchNumber(5))
+            .await
+            .unwrap();
+        dal.set_last_removed_l1_batch("proofs", L1BatchNumber(20))
+            .await
+            .unwrap();
+
+        let last_removed = dal.get_last_removed_l1_batch("proofs").await.unwrap();
+        assert_eq!(last_removed, Some(L1BatchNumber(20)));
+        let last_removed = dal
+            .get_last_removed_l1_batch("witness_inputs")
+            .await
+            .unwrap();
+        assert_eq!(last_removed, Some(L1BatchNumber(5)));
+    }
+}
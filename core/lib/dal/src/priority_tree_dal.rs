@@ -0,0 +1,140 @@
+use std::ops;
+
+use zksync_types::{PriorityOpId, H256};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Persisted leaves of the priority operations Merkle tree. Leaves are canonical hashes of priority operations;
+/// they are appended by the L1 watcher when priority operations are received, and are used to restore the tree
+/// when generating priority operation proofs.
+#[derive(Debug)]
+pub struct PriorityTreeDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl PriorityTreeDal<'_, '_> {
+    /// Inserts leaves for priority operations with consecutive IDs starting from `first_op_id`.
+    /// Already present leaves are not overwritten.
+    pub async fn insert_leaves(
+        &mut self,
+        first_op_id: PriorityOpId,
+        leaves: &[H256],
+    ) -> sqlx::Result<()> {
+        let op_ids: Vec<_> = (0..leaves.len() as u64)
+            .map(|i| (first_op_id.0 + i) as i64)
+            .collect();
+        let leaves: Vec<_> = leaves.iter().map(H256::as_bytes).collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                priority_tree_leaves (priority_op_id, leaf_hash, created_at)
+            SELECT
+                u.priority_op_id,
+                u.leaf_hash,
+                NOW()
+            FROM
+                UNNEST($1::BIGINT[], $2::BYTEA[]) AS u (priority_op_id, leaf_hash)
+            ON CONFLICT (priority_op_id) DO NOTHING
+            "#,
+            &op_ids,
+            &leaves as &[&[u8]]
+        )
+        .instrument("insert_priority_tree_leaves")
+        .with_arg("first_op_id", &first_op_id)
+        .with_arg("leaves.len", &leaves.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns leaves for priority operations with IDs in the specified range, ordered by ID.
+    pub async fn get_leaves(&mut self, ids: ops::Range<PriorityOpId>) -> sqlx::Result<Vec<H256>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                leaf_hash
+            FROM
+                priority_tree_leaves
+            WHERE
+                priority_op_id >= $1
+                AND priority_op_id < $2
+            ORDER BY
+                priority_op_id
+            "#,
+            ids.start.0 as i64,
+            ids.end.0 as i64
+        )
+        .instrument("get_priority_tree_leaves")
+        .with_arg("ids", &ids)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.leaf_hash))
+            .collect())
+    }
+
+    /// Returns the ID of the next priority operation to be added to the tree, or `None` if the tree is empty.
+    pub async fn get_next_op_id(&mut self) -> sqlx::Result<Option<PriorityOpId>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(priority_op_id) AS "max_id"
+            FROM
+                priority_tree_leaves
+            "#
+        )
+        .instrument("get_priority_tree_next_op_id")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.max_id.map(|id| PriorityOpId(id as u64 + 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn persisting_priority_tree_leaves() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.priority_tree_dal();
+        assert_eq!(dal.get_next_op_id().await.unwrap(), None);
+
+        let leaves: Vec<_> = (1..=5).map(H256::repeat_byte).collect();
+        dal.insert_leaves(PriorityOpId(0), &leaves[..3])
+            .await
+            .unwrap();
+        dal.insert_leaves(PriorityOpId(3), &leaves[3..])
+            .await
+            .unwrap();
+        assert_eq!(dal.get_next_op_id().await.unwrap(), Some(PriorityOpId(5)));
+        assert_eq!(
+            dal.get_leaves(PriorityOpId(0)..PriorityOpId(5))
+                .await
+                .unwrap(),
+            leaves
+        );
+        assert_eq!(
+            dal.get_leaves(PriorityOpId(2)..PriorityOpId(4))
+                .await
+                .unwrap(),
+            leaves[2..4]
+        );
+
+        // Re-inserting leaves (e.g., after the L1 watcher is restarted) must not overwrite them.
+        dal.insert_leaves(PriorityOpId(4), &[H256::zero()])
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_leaves(PriorityOpId(4)..PriorityOpId(5))
+                .await
+                .unwrap(),
+            [leaves[4]]
+        );
+    }
+}
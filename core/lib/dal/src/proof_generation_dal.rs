@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use strum::{Display, EnumString};
-use zksync_types::L1BatchNumber;
+use zksync_types::{L1BatchNumber, H256};
 
 use crate::{time_utils::pg_interval_from_duration, SqlxError, StorageProcessor};
 
@@ -22,6 +22,17 @@ enum ProofGenerationJobStatus {
     Skipped,
 }
 
+/// Kind of a prover artifact stored in the object store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+pub enum ProverArtifact {
+    /// Witness input generated by the Merkle tree (`PrepareBasicCircuitsJob`).
+    #[strum(serialize = "witness_input")]
+    WitnessInput,
+    /// Final proof submitted by the prover (`L1BatchProofForL1`).
+    #[strum(serialize = "proof")]
+    Proof,
+}
+
 impl ProofGenerationDal<'_, '_> {
     pub async fn get_next_block_to_be_proven(
         &mut self,
@@ -93,6 +104,111 @@ impl ProofGenerationDal<'_, '_> {
         .ok_or(sqlx::Error::RowNotFound)
     }
 
+    /// Saves the fingerprint of the proof generated for the specified L1 batch, overwriting the previously
+    /// saved fingerprint (if any). Fingerprints are retained if the batch is reverted.
+    pub async fn save_proof_fingerprint(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        fingerprint: H256,
+        proof_blob_url: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batch_proof_fingerprints (l1_batch_number, fingerprint, proof_blob_url, created_at, updated_at)
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                fingerprint = $2,
+                proof_blob_url = $3,
+                updated_at = NOW()
+            "#,
+            l1_batch_number.0 as i64,
+            fingerprint.as_bytes(),
+            proof_blob_url,
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the fingerprint and the blob URL of the latest proof generated for the specified L1 batch.
+    pub async fn get_proof_fingerprint(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<(H256, String)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                fingerprint,
+                proof_blob_url
+            FROM
+                l1_batch_proof_fingerprints
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.map(|row| (H256::from_slice(&row.fingerprint), row.proof_blob_url)))
+    }
+
+    /// Saves the hash of the specified artifact for an L1 batch, overwriting the previously saved hash (if any).
+    /// Like proof fingerprints, hashes are retained if the batch is reverted.
+    pub async fn save_artifact_hash(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        artifact: ProverArtifact,
+        hash: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batch_artifact_hashes (l1_batch_number, artifact, hash, created_at, updated_at)
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (l1_batch_number, artifact) DO
+            UPDATE
+            SET
+                hash = $3,
+                updated_at = NOW()
+            "#,
+            l1_batch_number.0 as i64,
+            artifact.to_string(),
+            hash.as_bytes(),
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the hash of the specified artifact for an L1 batch, or `None` if the hash was not recorded.
+    pub async fn get_artifact_hash(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        artifact: ProverArtifact,
+    ) -> sqlx::Result<Option<H256>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                hash
+            FROM
+                l1_batch_artifact_hashes
+            WHERE
+                l1_batch_number = $1
+                AND artifact = $2
+            "#,
+            l1_batch_number.0 as i64,
+            artifact.to_string(),
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.map(|row| H256::from_slice(&row.hash)))
+    }
+
     pub async fn insert_proof_generation_details(
         &mut self,
         block_number: L1BatchNumber,
@@ -183,4 +299,122 @@ impl ProofGenerationDal<'_, '_> {
 
         result
     }
+
+    /// Returns L1 batches that are executed on L1, but for which proof generation is not recorded as finished.
+    /// Such batches indicate an inconsistency between the core and the prover subsystem.
+    pub async fn get_executed_l1_batches_without_proof(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<L1BatchNumber>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batches.number
+            FROM
+                l1_batches
+                JOIN eth_txs_history AS execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id)
+                JOIN proof_generation_details ON (proof_generation_details.l1_batch_number = l1_batches.number)
+            WHERE
+                execute_tx.confirmed_at IS NOT NULL
+                AND proof_generation_details.status NOT IN ('generated', 'skipped')
+            ORDER BY
+                l1_batches.number ASC
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.number as u32))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn saving_proof_fingerprints() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.proof_generation_dal();
+        let fingerprint = dal.get_proof_fingerprint(L1BatchNumber(1)).await.unwrap();
+        assert_eq!(fingerprint, None);
+
+        dal.save_proof_fingerprint(L1BatchNumber(1), H256::repeat_byte(1), "proof_1")
+            .await
+            .unwrap();
+        let fingerprint = dal.get_proof_fingerprint(L1BatchNumber(1)).await.unwrap();
+        assert_eq!(
+            fingerprint,
+            Some((H256::repeat_byte(1), "proof_1".to_owned()))
+        );
+
+        dal.save_proof_fingerprint(L1BatchNumber(1), H256::repeat_byte(2), "proof_1")
+            .await
+            .unwrap();
+        let fingerprint = dal.get_proof_fingerprint(L1BatchNumber(1)).await.unwrap();
+        assert_eq!(
+            fingerprint,
+            Some((H256::repeat_byte(2), "proof_1".to_owned()))
+        );
+        let fingerprint = dal.get_proof_fingerprint(L1BatchNumber(2)).await.unwrap();
+        assert_eq!(fingerprint, None);
+    }
+
+    #[tokio::test]
+    async fn saving_artifact_hashes() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.proof_generation_dal();
+        let hash = dal
+            .get_artifact_hash(L1BatchNumber(1), ProverArtifact::Proof)
+            .await
+            .unwrap();
+        assert_eq!(hash, None);
+
+        dal.save_artifact_hash(
+            L1BatchNumber(1),
+            ProverArtifact::WitnessInput,
+            H256::repeat_byte(1),
+        )
+        .await
+        .unwrap();
+        dal.save_artifact_hash(
+            L1BatchNumber(1),
+            ProverArtifact::Proof,
+            H256::repeat_byte(2),
+        )
+        .await
+        .unwrap();
+        dal.save_artifact_hash(
+            L1BatchNumber(1),
+            ProverArtifact::Proof,
+            H256::repeat_byte(3),
+        )
+        .await
+        .unwrap();
+
+        let hash = dal
+            .get_artifact_hash(L1BatchNumber(1), ProverArtifact::WitnessInput)
+            .await
+            .unwrap();
+        assert_eq!(hash, Some(H256::repeat_byte(1)));
+        let hash = dal
+            .get_artifact_hash(L1BatchNumber(1), ProverArtifact::Proof)
+            .await
+            .unwrap();
+        assert_eq!(hash, Some(H256::repeat_byte(3)));
+        let hash = dal
+            .get_artifact_hash(L1BatchNumber(2), ProverArtifact::WitnessInput)
+            .await
+            .unwrap();
+        assert_eq!(hash, None);
+    }
 }
@@ -0,0 +1,504 @@
+use std::time::Duration;
+
+use zksync_types::{Address, H256, U256};
+use zksync_utils::bigdecimal_to_u256;
+
+use crate::{instrument::InstrumentExt, time_utils::pg_interval_from_duration, StorageProcessor};
+
+/// Recurring system transaction scheduled by the operator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledTx {
+    /// Unique name of the scheduled transaction, e.g. `price_oracle_update`.
+    pub name: String,
+    pub contract_address: Address,
+    pub calldata: Vec<u8>,
+    pub gas_limit: u64,
+    pub interval: Duration,
+}
+
+/// Submitted scheduled transaction that is neither executed nor rejected yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingScheduledTx {
+    pub scheduled_tx: ScheduledTx,
+    pub tx_hash: H256,
+    pub nonce: u32,
+    pub max_fee_per_gas: U256,
+}
+
+/// Gas accounting for a scheduled transaction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScheduledTxStats {
+    /// Number of submitted transactions.
+    pub submitted_count: u64,
+    /// Number of submitted transactions included into miniblocks (including reverted ones).
+    pub executed_count: u64,
+    /// Number of submitted transactions reverted during execution.
+    pub reverted_count: u64,
+    /// Total gas used by executed transactions.
+    pub gas_used: U256,
+}
+
+#[derive(Debug)]
+pub struct ScheduledTxsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ScheduledTxsDal<'_, '_> {
+    /// Inserts a new scheduled transaction or updates the existing one with the same name. The inserted / updated
+    /// transaction is enabled and is due immediately.
+    pub async fn upsert_scheduled_tx(&mut self, tx: &ScheduledTx) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                scheduled_txs (
+                    name,
+                    contract_address,
+                    calldata,
+                    gas_limit,
+                    interval_sec,
+                    is_enabled,
+                    next_submission_at,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, TRUE, NOW(), NOW(), NOW())
+            ON CONFLICT (name) DO
+            UPDATE
+            SET
+                contract_address = excluded.contract_address,
+                calldata = excluded.calldata,
+                gas_limit = excluded.gas_limit,
+                interval_sec = excluded.interval_sec,
+                is_enabled = TRUE,
+                next_submission_at = NOW(),
+                updated_at = NOW()
+            "#,
+            tx.name,
+            tx.contract_address.as_bytes(),
+            tx.calldata,
+            tx.gas_limit as i64,
+            tx.interval.as_secs() as i64
+        )
+        .instrument("upsert_scheduled_tx")
+        .with_arg("name", &tx.name)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Enables or disables the scheduled transaction. Returns `false` if there is no transaction with the specified name.
+    pub async fn set_scheduled_tx_enabled(
+        &mut self,
+        name: &str,
+        is_enabled: bool,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE scheduled_txs
+            SET
+                is_enabled = $2,
+                updated_at = NOW()
+            WHERE
+                name = $1
+            "#,
+            name,
+            is_enabled
+        )
+        .instrument("set_scheduled_tx_enabled")
+        .with_arg("name", &name)
+        .with_arg("is_enabled", &is_enabled)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns enabled scheduled transactions that are due. Transactions with a previous submission
+    /// neither executed nor rejected yet are skipped, so that a transaction stuck in the mempool
+    /// doesn't lead to a pile-up of submissions.
+    pub async fn get_due_scheduled_txs(&mut self) -> sqlx::Result<Vec<ScheduledTx>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                name,
+                contract_address,
+                calldata,
+                gas_limit,
+                interval_sec
+            FROM
+                scheduled_txs
+            WHERE
+                is_enabled
+                AND next_submission_at <= NOW()
+                AND NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        scheduled_tx_submissions
+                        INNER JOIN transactions ON transactions.hash = scheduled_tx_submissions.tx_hash
+                    WHERE
+                        scheduled_tx_submissions.schedule_name = scheduled_txs.name
+                        AND transactions.miniblock_number IS NULL
+                        AND transactions.error IS NULL
+                )
+            ORDER BY
+                next_submission_at
+            "#
+        )
+        .instrument("get_due_scheduled_txs")
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ScheduledTx {
+                name: row.name,
+                contract_address: Address::from_slice(&row.contract_address),
+                calldata: row.calldata,
+                gas_limit: row.gas_limit as u64,
+                interval: Duration::from_secs(row.interval_sec as u64),
+            })
+            .collect())
+    }
+
+    /// Records a submitted transaction for the scheduled transaction and schedules the next submission.
+    /// Should be called in the same DB transaction as the transaction is inserted into the mempool.
+    pub async fn record_submission(
+        &mut self,
+        name: &str,
+        tx_hash: H256,
+        nonce: u32,
+    ) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                scheduled_tx_submissions (tx_hash, schedule_name, nonce, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            "#,
+            tx_hash.as_bytes(),
+            name,
+            i64::from(nonce)
+        )
+        .instrument("record_scheduled_tx_submission#insert")
+        .with_arg("name", &name)
+        .with_arg("tx_hash", &tx_hash)
+        .execute(&mut transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE scheduled_txs
+            SET
+                next_submission_at = NOW() + interval_sec * INTERVAL '1 second',
+                updated_at = NOW()
+            WHERE
+                name = $1
+            "#,
+            name
+        )
+        .instrument("record_scheduled_tx_submission#update")
+        .with_arg("name", &name)
+        .execute(&mut transaction)
+        .await?;
+
+        transaction.commit().await
+    }
+
+    /// Returns submitted transactions that are pending for at least `pending_for` since their submission
+    /// or the latest replacement, ordered by nonce.
+    pub async fn get_stuck_submissions(
+        &mut self,
+        pending_for: Duration,
+    ) -> sqlx::Result<Vec<PendingScheduledTx>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                scheduled_txs.name,
+                scheduled_txs.contract_address,
+                scheduled_txs.calldata,
+                scheduled_txs.gas_limit,
+                scheduled_txs.interval_sec,
+                scheduled_tx_submissions.tx_hash,
+                scheduled_tx_submissions.nonce,
+                transactions.max_fee_per_gas AS "max_fee_per_gas!"
+            FROM
+                scheduled_tx_submissions
+                INNER JOIN scheduled_txs ON scheduled_txs.name = scheduled_tx_submissions.schedule_name
+                INNER JOIN transactions ON transactions.hash = scheduled_tx_submissions.tx_hash
+            WHERE
+                transactions.miniblock_number IS NULL
+                AND transactions.error IS NULL
+                AND scheduled_tx_submissions.created_at <= NOW() - $1::INTERVAL
+            ORDER BY
+                scheduled_tx_submissions.nonce
+            "#,
+            &pg_interval_from_duration(pending_for)
+        )
+        .instrument("get_stuck_scheduled_tx_submissions")
+        .with_arg("pending_for", &pending_for)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PendingScheduledTx {
+                scheduled_tx: ScheduledTx {
+                    name: row.name,
+                    contract_address: Address::from_slice(&row.contract_address),
+                    calldata: row.calldata,
+                    gas_limit: row.gas_limit as u64,
+                    interval: Duration::from_secs(row.interval_sec as u64),
+                },
+                tx_hash: H256::from_slice(&row.tx_hash),
+                nonce: row.nonce as u32,
+                max_fee_per_gas: bigdecimal_to_u256(row.max_fee_per_gas),
+            })
+            .collect())
+    }
+
+    /// Records that the submitted transaction with `tx_hash` was replaced in the mempool by the transaction
+    /// with `new_tx_hash` and the same nonce (e.g., one with a bumped fee). The replacement is considered
+    /// pending since this call.
+    pub async fn record_replacement(
+        &mut self,
+        tx_hash: H256,
+        new_tx_hash: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE scheduled_tx_submissions
+            SET
+                tx_hash = $2,
+                created_at = NOW()
+            WHERE
+                tx_hash = $1
+            "#,
+            tx_hash.as_bytes(),
+            new_tx_hash.as_bytes()
+        )
+        .instrument("record_scheduled_tx_replacement")
+        .with_arg("tx_hash", &tx_hash)
+        .with_arg("new_tx_hash", &new_tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns gas accounting for the scheduled transaction with the specified name.
+    pub async fn get_stats(&mut self, name: &str) -> sqlx::Result<ScheduledTxStats> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "submitted_count!",
+                COUNT(transactions.miniblock_number) AS "executed_count!",
+                COUNT(*) FILTER (
+                    WHERE
+                        transactions.miniblock_number IS NOT NULL
+                        AND transactions.error IS NOT NULL
+                ) AS "reverted_count!",
+                SUM(transactions.gas_limit - transactions.refunded_gas) FILTER (
+                    WHERE
+                        transactions.miniblock_number IS NOT NULL
+                ) AS gas_used
+            FROM
+                scheduled_tx_submissions
+                LEFT JOIN transactions ON transactions.hash = scheduled_tx_submissions.tx_hash
+            WHERE
+                scheduled_tx_submissions.schedule_name = $1
+            "#,
+            name
+        )
+        .instrument("get_scheduled_tx_stats")
+        .with_arg("name", &name)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(ScheduledTxStats {
+            submitted_count: row.submitted_count as u64,
+            executed_count: row.executed_count as u64,
+            reverted_count: row.reverted_count as u64,
+            gas_used: row.gas_used.map_or_else(U256::zero, bigdecimal_to_u256),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        fee::TransactionExecutionMetrics, tx::tx_execution_info::TxExecutionStatus,
+        MiniblockNumber, ProtocolVersion,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    fn scheduled_tx(interval: Duration) -> ScheduledTx {
+        ScheduledTx {
+            name: "oracle".to_owned(),
+            contract_address: Address::repeat_byte(1),
+            calldata: vec![1, 2, 3],
+            gas_limit: 1_000_000,
+            interval,
+        }
+    }
+
+    #[tokio::test]
+    async fn scheduling_transactions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let tx = scheduled_tx(Duration::ZERO);
+        conn.scheduled_txs_dal()
+            .upsert_scheduled_tx(&tx)
+            .await
+            .unwrap();
+
+        let due_txs = conn
+            .scheduled_txs_dal()
+            .get_due_scheduled_txs()
+            .await
+            .unwrap();
+        assert_eq!(due_txs, [tx.clone()]);
+
+        // Pending submissions block new ones.
+        let l2_tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(l2_tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        conn.scheduled_txs_dal()
+            .record_submission(&tx.name, l2_tx.hash(), 0)
+            .await
+            .unwrap();
+        let due_txs = conn
+            .scheduled_txs_dal()
+            .get_due_scheduled_txs()
+            .await
+            .unwrap();
+        assert!(due_txs.is_empty(), "{due_txs:?}");
+
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let mut tx_result = mock_execution_result(l2_tx);
+        tx_result.execution_status = TxExecutionStatus::Failure;
+        tx_result.refunded_gas = 100;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], 1.into())
+            .await;
+        let due_txs = conn
+            .scheduled_txs_dal()
+            .get_due_scheduled_txs()
+            .await
+            .unwrap();
+        assert_eq!(due_txs, [tx.clone()]);
+
+        let stats = conn.scheduled_txs_dal().get_stats(&tx.name).await.unwrap();
+        assert_eq!(stats.submitted_count, 1);
+        assert_eq!(stats.executed_count, 1);
+        assert_eq!(stats.reverted_count, 1);
+        assert!(!stats.gas_used.is_zero());
+
+        let updated = conn
+            .scheduled_txs_dal()
+            .set_scheduled_tx_enabled(&tx.name, false)
+            .await
+            .unwrap();
+        assert!(updated);
+        let due_txs = conn
+            .scheduled_txs_dal()
+            .get_due_scheduled_txs()
+            .await
+            .unwrap();
+        assert!(due_txs.is_empty(), "{due_txs:?}");
+    }
+
+    #[tokio::test]
+    async fn scheduling_next_submission() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let tx = scheduled_tx(Duration::from_secs(3_600));
+        conn.scheduled_txs_dal()
+            .upsert_scheduled_tx(&tx)
+            .await
+            .unwrap();
+        conn.scheduled_txs_dal()
+            .record_submission(&tx.name, H256::repeat_byte(1), 0)
+            .await
+            .unwrap();
+
+        // The submitted transaction is not in the mempool; still, the next submission is only due in an hour.
+        let due_txs = conn
+            .scheduled_txs_dal()
+            .get_due_scheduled_txs()
+            .await
+            .unwrap();
+        assert!(due_txs.is_empty(), "{due_txs:?}");
+        let stats = conn.scheduled_txs_dal().get_stats(&tx.name).await.unwrap();
+        assert_eq!(stats.submitted_count, 1);
+        assert_eq!(stats.executed_count, 0);
+        assert_eq!(stats.gas_used, U256::zero());
+    }
+
+    #[tokio::test]
+    async fn getting_stuck_submissions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let tx = scheduled_tx(Duration::ZERO);
+        conn.scheduled_txs_dal()
+            .upsert_scheduled_tx(&tx)
+            .await
+            .unwrap();
+        let l2_tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(l2_tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        conn.scheduled_txs_dal()
+            .record_submission(&tx.name, l2_tx.hash(), 0)
+            .await
+            .unwrap();
+
+        let stuck_txs = conn
+            .scheduled_txs_dal()
+            .get_stuck_submissions(Duration::from_secs(3_600))
+            .await
+            .unwrap();
+        assert!(stuck_txs.is_empty(), "{stuck_txs:?}");
+        let stuck_txs = conn
+            .scheduled_txs_dal()
+            .get_stuck_submissions(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(
+            stuck_txs,
+            [PendingScheduledTx {
+                scheduled_tx: tx.clone(),
+                tx_hash: l2_tx.hash(),
+                nonce: 0,
+                max_fee_per_gas: l2_tx.common_data.fee.max_fee_per_gas,
+            }]
+        );
+
+        // The replacement transaction is not in the mempool, so it isn't reported as stuck.
+        conn.scheduled_txs_dal()
+            .record_replacement(l2_tx.hash(), H256::repeat_byte(1))
+            .await
+            .unwrap();
+        let stuck_txs = conn
+            .scheduled_txs_dal()
+            .get_stuck_submissions(Duration::ZERO)
+            .await
+            .unwrap();
+        assert!(stuck_txs.is_empty(), "{stuck_txs:?}");
+        let stats = conn.scheduled_txs_dal().get_stats(&tx.name).await.unwrap();
+        assert_eq!(stats.submitted_count, 1);
+    }
+}
@@ -0,0 +1,108 @@
+use zksync_types::{L1BatchNumber, ProtocolVersionId};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Results of executing L1 batches by the shadow sequencer.
+#[derive(Debug)]
+pub struct ShadowSequencerDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ShadowSequencerDal<'_, '_> {
+    /// Records the result of executing an L1 batch by the shadow sequencer. `protocol_version` is the candidate
+    /// protocol version the batch was executed with, or `None` if it was executed with its own protocol version.
+    pub async fn insert_l1_batch_result(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        protocol_version: Option<ProtocolVersionId>,
+        divergences: &[String],
+        error: Option<&str>,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                shadow_sequencer_l1_batches (
+                    l1_batch_number,
+                    protocol_version,
+                    divergences,
+                    error,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                protocol_version = excluded.protocol_version,
+                divergences = excluded.divergences,
+                error = excluded.error,
+                created_at = excluded.created_at
+            "#,
+            i64::from(l1_batch_number.0),
+            protocol_version.map(|version| version as i32),
+            divergences,
+            error
+        )
+        .instrument("insert_shadow_sequencer_l1_batch_result")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the last L1 batch processed by the shadow sequencer.
+    pub async fn get_last_processed_l1_batch(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number"
+            FROM
+                shadow_sequencer_l1_batches
+            "#
+        )
+        .instrument("get_last_shadow_sequencer_l1_batch")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn recording_shadow_sequencer_results() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.shadow_sequencer_dal();
+        assert_eq!(dal.get_last_processed_l1_batch().await.unwrap(), None);
+
+        dal.insert_l1_batch_result(L1BatchNumber(3), None, &[], None)
+            .await
+            .unwrap();
+        dal.insert_l1_batch_result(
+            L1BatchNumber(4),
+            Some(ProtocolVersionId::latest()),
+            &["tx_status".to_owned()],
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            dal.get_last_processed_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(4))
+        );
+
+        // Results can be overwritten, e.g. if the shadow sequencer was restarted in the middle of the batch.
+        dal.insert_l1_batch_result(L1BatchNumber(4), None, &[], Some("error"))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_last_processed_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(4))
+        );
+    }
+}
@@ -0,0 +1,459 @@
+use zksync_types::{Address, L1BatchNumber, H256, U256};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Cumulative bridge accounting for the base token after a certain L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolvencyReport {
+    pub l1_batch_number: L1BatchNumber,
+    /// Total value minted on L2 by L1 transactions.
+    pub total_deposited: U256,
+    /// Total value of withdrawals initiated on L2.
+    pub total_withdrawn: U256,
+    /// L2 total supply expected based on the genesis supply, deposits and withdrawals.
+    pub expected_supply: U256,
+    /// Actual L2 total supply after the L1 batch.
+    pub l2_total_supply: U256,
+    /// Base token balance of the L1 contracts holding bridged funds, observed after the L1 batch was sealed.
+    /// `None` if L1 balances weren't checked for the batch (they are only checked for the last batch
+    /// in each chunk processed by the reporter).
+    pub l1_bridge_balance: Option<U256>,
+}
+
+/// Cumulative bridge accounting for an ERC-20 token after a certain L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenSolvencyReport {
+    pub l2_token_address: Address,
+    /// L1 address of the token. `None` if the token is not known to the node.
+    pub l1_token_address: Option<Address>,
+    /// Total value deposited via the L2 ERC-20 bridge.
+    pub total_deposited: U256,
+    /// Total value of withdrawals initiated via the L2 ERC-20 bridge.
+    pub total_withdrawn: U256,
+    /// Token balance of the L1 contracts holding bridged funds. `None` if L1 balances weren't checked for the batch.
+    pub l1_bridge_balance: Option<U256>,
+}
+
+#[derive(Debug)]
+pub struct SolvencyReportsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl SolvencyReportsDal<'_, '_> {
+    pub async fn insert_report(&mut self, report: &SolvencyReport) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                solvency_reports (
+                    l1_batch_number,
+                    total_deposited,
+                    total_withdrawn,
+                    expected_supply,
+                    l2_total_supply,
+                    l1_bridge_balance,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, NOW())
+            "#,
+            report.l1_batch_number.0 as i64,
+            u256_to_big_decimal(report.total_deposited),
+            u256_to_big_decimal(report.total_withdrawn),
+            u256_to_big_decimal(report.expected_supply),
+            u256_to_big_decimal(report.l2_total_supply),
+            report.l1_bridge_balance.map(u256_to_big_decimal)
+        )
+        .instrument("insert_solvency_report")
+        .with_arg("l1_batch_number", &report.l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_report(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<SolvencyReport>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                total_deposited,
+                total_withdrawn,
+                expected_supply,
+                l2_total_supply,
+                l1_bridge_balance
+            FROM
+                solvency_reports
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_solvency_report")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| SolvencyReport {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            total_deposited: bigdecimal_to_u256(row.total_deposited),
+            total_withdrawn: bigdecimal_to_u256(row.total_withdrawn),
+            expected_supply: bigdecimal_to_u256(row.expected_supply),
+            l2_total_supply: bigdecimal_to_u256(row.l2_total_supply),
+            l1_bridge_balance: row.l1_bridge_balance.map(bigdecimal_to_u256),
+        }))
+    }
+
+    /// Sets the base token balance of L1 bridge contracts for an existing report.
+    pub async fn set_l1_bridge_balance(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        balance: U256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE solvency_reports
+            SET
+                l1_bridge_balance = $2
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64,
+            u256_to_big_decimal(balance)
+        )
+        .instrument("set_solvency_l1_bridge_balance")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Inserts or updates a report for an ERC-20 token. `l1_token_address` of the report is ignored.
+    pub async fn insert_token_report(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        report: &TokenSolvencyReport,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                solvency_token_reports (
+                    l1_batch_number,
+                    l2_token_address,
+                    total_deposited,
+                    total_withdrawn,
+                    l1_bridge_balance,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (l1_batch_number, l2_token_address) DO
+            UPDATE
+            SET
+                total_deposited = excluded.total_deposited,
+                total_withdrawn = excluded.total_withdrawn,
+                l1_bridge_balance = excluded.l1_bridge_balance
+            "#,
+            l1_batch_number.0 as i64,
+            report.l2_token_address.as_bytes(),
+            u256_to_big_decimal(report.total_deposited),
+            u256_to_big_decimal(report.total_withdrawn),
+            report.l1_bridge_balance.map(u256_to_big_decimal)
+        )
+        .instrument("insert_token_solvency_report")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("l2_token_address", &report.l2_token_address)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns reports for all ERC-20 tokens bridged up to and including the specified L1 batch. Since token reports
+    /// are only persisted for L1 batches changing the token accounting, the latest report for each token is returned.
+    pub async fn get_token_reports(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<TokenSolvencyReport>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT
+                ON (solvency_token_reports.l2_token_address) solvency_token_reports.l2_token_address,
+                tokens.l1_address AS "l1_token_address?",
+                solvency_token_reports.total_deposited,
+                solvency_token_reports.total_withdrawn,
+                CASE
+                    WHEN solvency_token_reports.l1_batch_number = $1 THEN solvency_token_reports.l1_bridge_balance
+                END AS l1_bridge_balance
+            FROM
+                solvency_token_reports
+                LEFT JOIN tokens ON tokens.l2_address = solvency_token_reports.l2_token_address
+            WHERE
+                solvency_token_reports.l1_batch_number <= $1
+            ORDER BY
+                solvency_token_reports.l2_token_address,
+                solvency_token_reports.l1_batch_number DESC
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_token_solvency_reports")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TokenSolvencyReport {
+                l2_token_address: Address::from_slice(&row.l2_token_address),
+                l1_token_address: row.l1_token_address.as_deref().map(Address::from_slice),
+                total_deposited: bigdecimal_to_u256(row.total_deposited),
+                total_withdrawn: bigdecimal_to_u256(row.total_withdrawn),
+                l1_bridge_balance: row.l1_bridge_balance.map(bigdecimal_to_u256),
+            })
+            .collect())
+    }
+
+    pub async fn get_last_report_l1_batch(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let number = sqlx::query_scalar!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number?"
+            FROM
+                solvency_reports
+            "#
+        )
+        .instrument("get_last_report_l1_batch")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Returns the total value minted by all transactions in the specified L1 batch.
+    pub async fn get_l1_batch_minted_value(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<U256> {
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COALESCE(SUM(l1_tx_mint), 0) AS "total!"
+            FROM
+                transactions
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_l1_batch_minted_value")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(bigdecimal_to_u256(total))
+    }
+
+    /// Returns the first and the fourth topics together with data of events with the specified address and
+    /// the first topic emitted in the specified L1 batch.
+    pub async fn get_l1_batch_indexed_events(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        address: Address,
+        topics: &[H256],
+    ) -> sqlx::Result<Vec<(H256, H256, Vec<u8>)>> {
+        let topics: Vec<_> = topics.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                events.topic1,
+                events.topic4,
+                events.value
+            FROM
+                events
+                INNER JOIN miniblocks ON miniblocks.number = events.miniblock_number
+            WHERE
+                miniblocks.l1_batch_number = $1
+                AND events.address = $2
+                AND events.topic1 = ANY ($3)
+            ORDER BY
+                events.miniblock_number,
+                events.event_index_in_block
+            "#,
+            l1_batch_number.0 as i64,
+            address.as_bytes(),
+            &topics as &[&[u8]]
+        )
+        .instrument("get_l1_batch_indexed_events")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("address", &address)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    H256::from_slice(&row.topic1),
+                    H256::from_slice(&row.topic4),
+                    row.value,
+                )
+            })
+            .collect())
+    }
+
+    /// Returns data of events with the specified address and the first topic emitted in the specified L1 batch.
+    pub async fn get_l1_batch_event_data(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        address: Address,
+        topics: &[H256],
+    ) -> sqlx::Result<Vec<Vec<u8>>> {
+        let topics: Vec<_> = topics.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                events.value
+            FROM
+                events
+                INNER JOIN miniblocks ON miniblocks.number = events.miniblock_number
+            WHERE
+                miniblocks.l1_batch_number = $1
+                AND events.address = $2
+                AND events.topic1 = ANY ($3)
+            ORDER BY
+                events.miniblock_number,
+                events.event_index_in_block
+            "#,
+            l1_batch_number.0 as i64,
+            address.as_bytes(),
+            &topics as &[&[u8]]
+        )
+        .instrument("get_l1_batch_event_data")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("address", &address)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.value).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn saving_and_loading_reports() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        assert_eq!(
+            conn.solvency_reports_dal()
+                .get_last_report_l1_batch()
+                .await
+                .unwrap(),
+            None
+        );
+
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        let report = SolvencyReport {
+            l1_batch_number: L1BatchNumber(1),
+            total_deposited: U256::from(10).pow(25.into()),
+            total_withdrawn: 123.into(),
+            expected_supply: U256::from(10).pow(25.into()) - 123,
+            l2_total_supply: U256::from(10).pow(25.into()) - 123,
+            l1_bridge_balance: None,
+        };
+        conn.solvency_reports_dal()
+            .insert_report(&report)
+            .await
+            .unwrap();
+
+        let loaded_report = conn
+            .solvency_reports_dal()
+            .get_report(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(loaded_report, Some(report.clone()));
+        let l1_balance = U256::from(10).pow(25.into());
+        conn.solvency_reports_dal()
+            .set_l1_bridge_balance(L1BatchNumber(1), l1_balance)
+            .await
+            .unwrap();
+        let loaded_report = conn
+            .solvency_reports_dal()
+            .get_report(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded_report.l1_bridge_balance, Some(l1_balance));
+
+        let mut token_report = TokenSolvencyReport {
+            l2_token_address: Address::repeat_byte(1),
+            l1_token_address: None,
+            total_deposited: 1_000.into(),
+            total_withdrawn: 100.into(),
+            l1_bridge_balance: None,
+        };
+        conn.solvency_reports_dal()
+            .insert_token_report(L1BatchNumber(1), &token_report)
+            .await
+            .unwrap();
+        token_report.l1_bridge_balance = Some(900.into());
+        conn.solvency_reports_dal()
+            .insert_token_report(L1BatchNumber(1), &token_report)
+            .await
+            .unwrap();
+        let token_reports = conn
+            .solvency_reports_dal()
+            .get_token_reports(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(token_reports, [token_report.clone()]);
+        let token_reports = conn
+            .solvency_reports_dal()
+            .get_token_reports(L1BatchNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(token_reports, []);
+
+        assert_eq!(
+            conn.solvency_reports_dal()
+                .get_last_report_l1_batch()
+                .await
+                .unwrap(),
+            Some(L1BatchNumber(1))
+        );
+
+        // Reports are removed together with L1 batches.
+        conn.blocks_dal()
+            .delete_l1_batches(L1BatchNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(
+            conn.solvency_reports_dal()
+                .get_report(L1BatchNumber(1))
+                .await
+                .unwrap(),
+            None
+        );
+        let token_reports = conn
+            .solvency_reports_dal()
+            .get_token_reports(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(token_reports, []);
+    }
+}
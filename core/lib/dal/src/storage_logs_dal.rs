@@ -2,9 +2,11 @@ use std::{collections::HashMap, ops, time::Instant};
 
 use sqlx::{types::chrono::Utc, Row};
 use zksync_types::{
-    get_code_key, snapshots::SnapshotStorageLog, AccountTreeId, Address, L1BatchNumber,
-    MiniblockNumber, StorageKey, StorageLog, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256,
+    get_code_key, snapshots::SnapshotStorageLog, writes::StateDiffRecord, AccountTreeId, Address,
+    L1BatchNumber, MiniblockNumber, StorageKey, StorageLog,
+    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256,
 };
+use zksync_utils::h256_to_u256;
 
 pub use crate::models::storage_log::{DbStorageLog, StorageRecoveryLogEntry};
 use crate::{instrument::InstrumentExt, StorageProcessor};
@@ -398,6 +400,56 @@ impl StorageLogsDal<'_, '_> {
         Ok(touched_slots.collect())
     }
 
+    /// Returns deduplicated state diffs for the specified L1 batch in the form they are published in the batch pubdata
+    /// (i.e., sorted by address and key; initial writes have zero enumeration index and initial value).
+    /// Slots whose value hasn't changed in the batch are not included.
+    ///
+    /// The L1 batch must be sealed; otherwise, this method will panic.
+    pub async fn get_l1_batch_state_diffs(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<StateDiffRecord>> {
+        let touched_slots = self.get_touched_slots_for_l1_batch(l1_batch_number).await?;
+        let touched_hashed_keys: Vec<_> =
+            touched_slots.keys().map(|key| key.hashed_key()).collect();
+        let previous_values = self
+            .get_previous_storage_values(&touched_hashed_keys, l1_batch_number)
+            .await?;
+        let l1_batches_for_initial_writes = self
+            .get_l1_batches_and_indices_for_initial_writes(&touched_hashed_keys)
+            .await?;
+
+        let mut state_diffs = Vec::new();
+        for (key, value) in touched_slots {
+            let hashed_key = key.hashed_key();
+            let prev_value = previous_values[&hashed_key].unwrap_or_default();
+            if prev_value == value {
+                continue;
+            }
+
+            let (initial_write_l1_batch_number, index) = l1_batches_for_initial_writes[&hashed_key];
+            assert!(
+                initial_write_l1_batch_number <= l1_batch_number,
+                "Slot {hashed_key:?} was changed in L1 batch {l1_batch_number} but in DB L1 batch of initial write is greater"
+            );
+            let is_initial_write = initial_write_l1_batch_number == l1_batch_number;
+            state_diffs.push(StateDiffRecord {
+                address: *key.address(),
+                key: h256_to_u256(*key.key()),
+                derived_key: StorageKey::raw_hashed_key(key.address(), key.key()),
+                enumeration_index: if is_initial_write { 0 } else { index },
+                initial_value: if is_initial_write {
+                    0.into()
+                } else {
+                    h256_to_u256(prev_value)
+                },
+                final_value: h256_to_u256(value),
+            });
+        }
+        state_diffs.sort_unstable_by_key(|rec| (rec.address, rec.key));
+        Ok(state_diffs)
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -1042,6 +1094,67 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn getting_l1_batch_state_diffs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let keys: Vec<_> = (0_u64..4)
+            .map(|i| StorageKey::new(account, H256::from_low_u64_be(i)))
+            .collect();
+        let logs: Vec<_> = keys[..3]
+            .iter()
+            .map(|&key| StorageLog::new_write_log(key, H256::repeat_byte(1)))
+            .collect();
+        insert_miniblock(&mut conn, 1, logs).await;
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(1), &keys[..3])
+            .await
+            .unwrap();
+
+        let new_logs = vec![
+            StorageLog::new_write_log(keys[1], H256::repeat_byte(2)),
+            // Value is not changed, so this write must not be included in state diffs
+            StorageLog::new_write_log(keys[2], H256::repeat_byte(1)),
+            StorageLog::new_write_log(keys[3], H256::repeat_byte(3)),
+        ];
+        insert_miniblock(&mut conn, 2, new_logs).await;
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(2), &keys[3..])
+            .await
+            .unwrap();
+
+        let state_diffs = conn
+            .storage_logs_dal()
+            .get_l1_batch_state_diffs(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(state_diffs.len(), 2);
+        let repeated_write = &state_diffs[0];
+        assert_eq!(repeated_write.key, 1.into());
+        assert_ne!(repeated_write.enumeration_index, 0);
+        assert_eq!(
+            repeated_write.initial_value,
+            h256_to_u256(H256::repeat_byte(1))
+        );
+        assert_eq!(
+            repeated_write.final_value,
+            h256_to_u256(H256::repeat_byte(2))
+        );
+        let initial_write = &state_diffs[1];
+        assert_eq!(initial_write.key, 3.into());
+        assert_eq!(initial_write.enumeration_index, 0);
+        assert_eq!(initial_write.initial_value, 0.into());
+        assert_eq!(
+            initial_write.final_value,
+            h256_to_u256(H256::repeat_byte(3))
+        );
+    }
+
     #[tokio::test]
     async fn reverting_keys_without_initial_write() {
         let pool = ConnectionPool::test_pool().await;
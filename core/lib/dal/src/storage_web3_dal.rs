@@ -227,6 +227,52 @@ impl StorageWeb3Dal<'_, '_> {
         .collect()
     }
 
+    /// Returns the last value written to each of the specified hashed storage keys in each miniblock
+    /// from the specified range. The returned writes are ordered by miniblock number.
+    pub async fn get_storage_writes_in_miniblocks(
+        &mut self,
+        miniblock_numbers: ops::RangeInclusive<MiniblockNumber>,
+        hashed_keys: &[H256],
+    ) -> sqlx::Result<Vec<(MiniblockNumber, H256, H256)>> {
+        let hashed_keys: Vec<_> = hashed_keys.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT
+                ON (miniblock_number, hashed_key) miniblock_number,
+                hashed_key,
+                value
+            FROM
+                storage_logs
+            WHERE
+                hashed_key = ANY ($1)
+                AND miniblock_number BETWEEN $2 AND $3
+            ORDER BY
+                miniblock_number,
+                hashed_key,
+                operation_number DESC
+            "#,
+            &hashed_keys as &[&[u8]],
+            miniblock_numbers.start().0 as i64,
+            miniblock_numbers.end().0 as i64
+        )
+        .instrument("get_storage_writes_in_miniblocks")
+        .with_arg("miniblock_numbers", &miniblock_numbers)
+        .with_arg("hashed_keys.len", &hashed_keys.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    MiniblockNumber(row.miniblock_number as u32),
+                    H256::from_slice(&row.hashed_key),
+                    H256::from_slice(&row.value),
+                )
+            })
+            .collect())
+    }
+
     /// This method doesn't check if block with number equals to `block_number`
     /// is present in the database. For such blocks `None` will be returned.
     pub async fn get_contract_code_unchecked(
@@ -55,7 +55,8 @@ impl SyncDal<'_, '_> {
                 miniblocks.virtual_blocks,
                 miniblocks.hash,
                 miniblocks.protocol_version AS "protocol_version!",
-                miniblocks.fee_account_address AS "fee_account_address!"
+                miniblocks.fee_account_address AS "fee_account_address!",
+                miniblocks.gas_limit
             FROM
                 miniblocks
             WHERE
@@ -159,6 +160,7 @@ mod tests {
         // Insert another block in the store.
         let miniblock_header = MiniblockHeader {
             fee_account_address: Address::repeat_byte(0x42),
+            gas_limit: Some(100_000_000),
             ..create_miniblock_header(1)
         };
         let tx = mock_l2_transaction();
@@ -204,6 +206,7 @@ mod tests {
             miniblock_header.batch_fee_input.fair_l2_gas_price()
         );
         assert_eq!(block.operator_address, miniblock_header.fee_account_address);
+        assert_eq!(block.gas_limit, Some(100_000_000));
         assert!(block.transactions.is_none());
 
         let block = conn
@@ -1,18 +1,20 @@
 use std::time::Duration;
 
+use assert_matches::assert_matches;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
+    api::TransactionStatus,
     block::{MiniblockHasher, MiniblockHeader},
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     helpers::unix_timestamp_ms,
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
-    l2::L2Tx,
+    l2::{L2Tx, ValidUntil},
     protocol_version::{ProtocolUpgradeTx, ProtocolUpgradeTxCommonData},
     snapshots::SnapshotRecoveryStatus,
     tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
-    Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
-    PriorityOpId, ProtocolVersionId, H160, H256, U256,
+    Address, Execute, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, L1TxCommonData,
+    L2ChainId, MiniblockNumber, PriorityOpId, ProtocolVersionId, Transaction, H160, H256, U256,
 };
 
 use crate::{
@@ -45,6 +47,7 @@ pub(crate) fn create_miniblock_header(number: u32) -> MiniblockHeader {
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
         protocol_version: Some(protocol_version),
         virtual_blocks: 1,
+        gas_limit: None,
     }
 }
 
@@ -146,6 +149,7 @@ pub(crate) fn mock_execution_result(transaction: L2Tx) -> TransactionExecutionRe
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        revert_data: None,
     }
 }
 
@@ -302,3 +306,76 @@ async fn remove_stuck_txs() {
 
     assert_eq!(receipts.len(), 1);
 }
+
+#[tokio::test]
+async fn mark_expired_txs() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let mut protocol_versions_dal = ProtocolVersionsDal { storage };
+    protocol_versions_dal
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+
+    let storage = protocol_versions_dal.storage;
+    let mut transactions_dal = TransactionsDal { storage };
+
+    let mut expired_by_timestamp_tx = mock_l2_transaction();
+    expired_by_timestamp_tx.common_data.valid_until = Some(ValidUntil::Timestamp(100));
+    let mut expired_by_block_tx = mock_l2_transaction();
+    expired_by_block_tx.common_data.valid_until = Some(ValidUntil::Block(MiniblockNumber(5)));
+    let mut valid_tx = mock_l2_transaction();
+    valid_tx.common_data.valid_until = Some(ValidUntil::Block(MiniblockNumber(7)));
+    let tx_without_deadline = mock_l2_transaction();
+    for tx in [
+        &expired_by_timestamp_tx,
+        &expired_by_block_tx,
+        &valid_tx,
+        &tx_without_deadline,
+    ] {
+        let result = transactions_dal
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await;
+        assert_eq!(result, L2TxSubmissionResult::Added);
+    }
+
+    let expired_count = transactions_dal
+        .mark_expired_txs(MiniblockNumber(5), 100)
+        .await
+        .unwrap();
+    assert_eq!(expired_count, 2);
+
+    let txs = transactions_dal
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    let mut tx_hashes: Vec<_> = txs.iter().map(Transaction::hash).collect();
+    tx_hashes.sort_unstable();
+    let mut expected_hashes = vec![valid_tx.hash(), tx_without_deadline.hash()];
+    expected_hashes.sort_unstable();
+    assert_eq!(tx_hashes, expected_hashes);
+    // Deadlines should be restored from the storage.
+    let restored_tx = txs.iter().find(|tx| tx.hash() == valid_tx.hash()).unwrap();
+    let ExecuteTransactionCommon::L2(common_data) = &restored_tx.common_data else {
+        panic!("unexpected transaction: {restored_tx:?}");
+    };
+    assert_eq!(common_data.valid_until, valid_tx.common_data.valid_until);
+
+    let storage = transactions_dal.storage;
+    let mut transactions_web3_dal = TransactionsWeb3Dal { storage };
+    let details = transactions_web3_dal
+        .get_transaction_details(expired_by_block_tx.hash())
+        .await
+        .unwrap()
+        .expect("no transaction details");
+    assert_matches!(details.status, TransactionStatus::Expired);
+    assert_eq!(
+        details.valid_until,
+        Some(ValidUntil::Block(MiniblockNumber(5)))
+    );
+    let details = transactions_web3_dal
+        .get_transaction_details(valid_tx.hash())
+        .await
+        .unwrap()
+        .expect("no transaction details");
+    assert_matches!(details.status, TransactionStatus::Pending);
+}
@@ -1,30 +1,33 @@
-use std::{collections::HashMap, fmt, time::Duration};
+use std::{collections::HashMap, fmt, ops, time::Duration};
 
 use anyhow::Context as _;
 use bigdecimal::BigDecimal;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use sqlx::{error, types::chrono::NaiveDateTime};
 use zksync_types::{
     block::MiniblockExecutionData,
     fee::TransactionExecutionMetrics,
+    fee_model::{BatchFeeInput, L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput},
     l1::L1Tx,
-    l2::L2Tx,
+    l2::{L2Tx, SignatureScheme, TX_EXPIRED_ERROR},
     protocol_version::ProtocolUpgradeTx,
     tx::{tx_execution_info::TxExecutionStatus, TransactionExecutionResult},
     vm_trace::Call,
     Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, PriorityOpId,
-    Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    ProtocolVersionId, Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
 use zksync_utils::u256_to_big_decimal;
 
 use crate::{
     instrument::InstrumentExt,
-    models::storage_transaction::{CallTrace, StorageTransaction},
+    models::storage_transaction::{valid_until_to_storage, CallTrace, StorageTransaction},
     time_utils::pg_interval_from_duration,
     StorageProcessor,
 };
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum L2TxSubmissionResult {
     Added,
     Replaced,
@@ -45,6 +48,28 @@ impl fmt::Display for L2TxSubmissionResult {
     }
 }
 
+/// Persisted outcome of executing a transaction by the state keeper.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TxExecutionOutcome {
+    pub hash: H256,
+    pub status: TxExecutionStatus,
+    pub refunded_gas: u64,
+}
+
+/// Fee-related data of an executed L2 transaction, used to simulate changes of the fee model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxFeeRecord {
+    pub miniblock_number: MiniblockNumber,
+    pub miniblock_timestamp: u64,
+    /// L1 batch the transaction is included into; `None` if the batch is not sealed yet.
+    pub l1_batch_number: Option<L1BatchNumber>,
+    pub protocol_version: Option<ProtocolVersionId>,
+    /// Fee input of the miniblock the transaction is included into.
+    pub batch_fee_input: BatchFeeInput,
+    pub computational_gas_used: u64,
+    pub pubdata_published: u64,
+}
+
 #[derive(Debug)]
 pub struct TransactionsDal<'c, 'a> {
     pub(crate) storage: &'c mut StorageProcessor<'a>,
@@ -288,6 +313,7 @@ impl TransactionsDal<'_, '_> {
             let gas_per_pubdata_limit =
                 u256_to_big_decimal(tx.common_data.fee.gas_per_pubdata_limit);
             let tx_format = tx.common_data.transaction_type as i32;
+            let signature_scheme = SignatureScheme::detect(&tx.common_data.signature);
             let signature = tx.common_data.signature;
             let nonce = tx.common_data.nonce.0 as i64;
             let input_data = tx.common_data.input.expect("Data is mandatory").data;
@@ -297,6 +323,8 @@ impl TransactionsDal<'_, '_> {
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
             let received_at = NaiveDateTime::from_timestamp_opt(secs, nanosecs).unwrap();
+            let (valid_until_timestamp, valid_until_block) =
+                valid_until_to_storage(tx.common_data.valid_until);
             // Besides just adding or updating(on conflict) the record, we want to extract some info
             // from the query below, to indicate what actually happened:
             // 1) transaction is added
@@ -329,6 +357,10 @@ impl TransactionsDal<'_, '_> {
                         paymaster_input,
                         execution_info,
                         received_at,
+                        valid_until_timestamp,
+                        valid_until_block,
+                        signature_scheme,
+                        arrival_seq,
                         created_at,
                         updated_at
                     )
@@ -352,6 +384,10 @@ impl TransactionsDal<'_, '_> {
                         $15,
                         JSONB_BUILD_OBJECT('gas_used', $16::BIGINT, 'storage_writes', $17::INT, 'contracts_used', $18::INT),
                         $19,
+                        $20,
+                        $21,
+                        $22,
+                        NEXTVAL('transactions_arrival_seq'),
                         NOW(),
                         NOW()
                     )
@@ -374,6 +410,10 @@ impl TransactionsDal<'_, '_> {
                     execution_info = JSONB_BUILD_OBJECT('gas_used', $16::BIGINT, 'storage_writes', $17::INT, 'contracts_used', $18::INT),
                     in_mempool = FALSE,
                     received_at = $19,
+                    valid_until_timestamp = $20,
+                    valid_until_block = $21,
+                    signature_scheme = $22,
+                    arrival_seq = NEXTVAL('transactions_arrival_seq'),
                     created_at = NOW(),
                     updated_at = NOW(),
                     error = NULL
@@ -409,7 +449,10 @@ impl TransactionsDal<'_, '_> {
                 exec_info.gas_used as i64,
                 (exec_info.initial_storage_writes + exec_info.repeated_storage_writes) as i32,
                 exec_info.contracts_used as i32,
-                received_at
+                received_at,
+                valid_until_timestamp,
+                valid_until_block,
+                signature_scheme.to_string()
             )
                 .fetch_optional(self.storage.conn())
                 .await
@@ -498,6 +541,7 @@ impl TransactionsDal<'_, '_> {
             let mut l1_hashes = Vec::with_capacity(transactions.len());
             let mut l1_indices_in_block = Vec::with_capacity(transactions.len());
             let mut l1_errors = Vec::with_capacity(transactions.len());
+            let mut l1_revert_data = Vec::with_capacity(transactions.len());
             let mut l1_execution_infos = Vec::with_capacity(transactions.len());
             let mut l1_refunded_gas = Vec::with_capacity(transactions.len());
             let mut l1_effective_gas_prices = Vec::with_capacity(transactions.len());
@@ -505,6 +549,7 @@ impl TransactionsDal<'_, '_> {
             let mut upgrade_hashes = Vec::new();
             let mut upgrade_indices_in_block = Vec::new();
             let mut upgrade_errors = Vec::new();
+            let mut upgrade_revert_data = Vec::new();
             let mut upgrade_execution_infos = Vec::new();
             let mut upgrade_refunded_gas = Vec::new();
             let mut upgrade_effective_gas_prices = Vec::new();
@@ -518,8 +563,10 @@ impl TransactionsDal<'_, '_> {
             let mut l2_initiators = Vec::with_capacity(transactions.len());
             let mut l2_nonces = Vec::with_capacity(transactions.len());
             let mut l2_signatures = Vec::with_capacity(transactions.len());
+            let mut l2_signature_schemes = Vec::with_capacity(transactions.len());
             let mut l2_tx_formats = Vec::with_capacity(transactions.len());
             let mut l2_errors = Vec::with_capacity(transactions.len());
+            let mut l2_revert_data = Vec::with_capacity(transactions.len());
             let mut l2_effective_gas_prices = Vec::with_capacity(transactions.len());
             let mut l2_execution_infos = Vec::with_capacity(transactions.len());
             let mut l2_inputs = Vec::with_capacity(transactions.len());
@@ -542,8 +589,10 @@ impl TransactionsDal<'_, '_> {
                         transaction,
                         execution_status,
                         refunded_gas,
+                        revert_data,
                         ..
                     } = tx_res;
+                    let revert_data = revert_data.clone().unwrap_or_default();
 
                     // Bootloader currently doesn't return detailed errors.
                     let error = match execution_status {
@@ -564,6 +613,7 @@ impl TransactionsDal<'_, '_> {
                             l1_hashes.push(hash.0.to_vec());
                             l1_indices_in_block.push(index_in_block as i32);
                             l1_errors.push(error.unwrap_or_default());
+                            l1_revert_data.push(revert_data);
                             l1_execution_infos.push(serde_json::to_value(execution_info).unwrap());
                             l1_refunded_gas.push(*refunded_gas as i64);
                             l1_effective_gas_prices
@@ -583,8 +633,11 @@ impl TransactionsDal<'_, '_> {
                             l2_initiators.push(transaction.initiator_account().0.to_vec());
                             l2_nonces.push(common_data.nonce.0 as i32);
                             l2_signatures.push(common_data.signature.clone());
+                            l2_signature_schemes
+                                .push(SignatureScheme::detect(&common_data.signature).to_string());
                             l2_tx_formats.push(common_data.transaction_type as i32);
                             l2_errors.push(error.unwrap_or_default());
+                            l2_revert_data.push(revert_data);
                             let l2_effective_gas_price = common_data
                                 .fee
                                 .get_effective_gas_price(block_base_fee_per_gas);
@@ -608,6 +661,7 @@ impl TransactionsDal<'_, '_> {
                             upgrade_hashes.push(hash.0.to_vec());
                             upgrade_indices_in_block.push(index_in_block as i32);
                             upgrade_errors.push(error.unwrap_or_default());
+                            upgrade_revert_data.push(revert_data);
                             upgrade_execution_infos
                                 .push(serde_json::to_value(execution_info).unwrap());
                             upgrade_refunded_gas.push(*refunded_gas as i64);
@@ -630,6 +684,7 @@ impl TransactionsDal<'_, '_> {
                     SET
                         hash = data_table.hash,
                         signature = data_table.signature,
+                        signature_scheme = data_table.signature_scheme,
                         gas_limit = data_table.gas_limit,
                         max_fee_per_gas = data_table.max_fee_per_gas,
                         max_priority_fee_per_gas = data_table.max_priority_fee_per_gas,
@@ -640,6 +695,7 @@ impl TransactionsDal<'_, '_> {
                         miniblock_number = $21,
                         index_in_block = data_table.index_in_block,
                         error = NULLIF(data_table.error, ''),
+                        revert_data = NULLIF(data_table.revert_data, ''),
                         effective_gas_price = data_table.effective_gas_price,
                         execution_info = data_table.new_execution_info,
                         refunded_gas = data_table.refunded_gas,
@@ -675,7 +731,9 @@ impl TransactionsDal<'_, '_> {
                                         UNNEST($17::NUMERIC[]) AS value,
                                         UNNEST($18::bytea[]) AS contract_address,
                                         UNNEST($19::bytea[]) AS paymaster,
-                                        UNNEST($20::bytea[]) AS paymaster_input
+                                        UNNEST($20::bytea[]) AS paymaster_input,
+                                        UNNEST($22::bytea[]) AS revert_data,
+                                        UNNEST($23::TEXT[]) AS signature_scheme
                                 ) AS data_table_temp
                                 JOIN transactions ON transactions.initiator_address = data_table_temp.initiator_address
                                 AND transactions.nonce = data_table_temp.nonce
@@ -707,6 +765,8 @@ impl TransactionsDal<'_, '_> {
                     &l2_paymaster,
                     &l2_paymaster_input,
                     miniblock_number.0 as i32,
+                    &l2_revert_data,
+                    &l2_signature_schemes,
                 )
                 .execute(transaction.conn())
                 .await
@@ -722,6 +782,7 @@ impl TransactionsDal<'_, '_> {
                         miniblock_number = $1,
                         index_in_block = data_table.index_in_block,
                         error = NULLIF(data_table.error, ''),
+                        revert_data = NULLIF(data_table.revert_data, ''),
                         in_mempool = FALSE,
                         execution_info = execution_info || data_table.new_execution_info,
                         refunded_gas = data_table.refunded_gas,
@@ -735,7 +796,8 @@ impl TransactionsDal<'_, '_> {
                                 UNNEST($4::VARCHAR[]) AS error,
                                 UNNEST($5::jsonb[]) AS new_execution_info,
                                 UNNEST($6::BIGINT[]) AS refunded_gas,
-                                UNNEST($7::NUMERIC[]) AS effective_gas_price
+                                UNNEST($7::NUMERIC[]) AS effective_gas_price,
+                                UNNEST($8::bytea[]) AS revert_data
                         ) AS data_table
                     WHERE
                         transactions.hash = data_table.hash
@@ -747,6 +809,7 @@ impl TransactionsDal<'_, '_> {
                     &l1_execution_infos,
                     &l1_refunded_gas,
                     &l1_effective_gas_prices,
+                    &l1_revert_data,
                 )
                 .execute(transaction.conn())
                 .await
@@ -761,6 +824,7 @@ impl TransactionsDal<'_, '_> {
                         miniblock_number = $1,
                         index_in_block = data_table.index_in_block,
                         error = NULLIF(data_table.error, ''),
+                        revert_data = NULLIF(data_table.revert_data, ''),
                         in_mempool = FALSE,
                         execution_info = execution_info || data_table.new_execution_info,
                         refunded_gas = data_table.refunded_gas,
@@ -774,7 +838,8 @@ impl TransactionsDal<'_, '_> {
                                 UNNEST($4::VARCHAR[]) AS error,
                                 UNNEST($5::jsonb[]) AS new_execution_info,
                                 UNNEST($6::BIGINT[]) AS refunded_gas,
-                                UNNEST($7::NUMERIC[]) AS effective_gas_price
+                                UNNEST($7::NUMERIC[]) AS effective_gas_price,
+                                UNNEST($8::bytea[]) AS revert_data
                         ) AS data_table
                     WHERE
                         transactions.hash = data_table.hash
@@ -786,6 +851,7 @@ impl TransactionsDal<'_, '_> {
                     &upgrade_execution_infos,
                     &upgrade_refunded_gas,
                     &upgrade_effective_gas_prices,
+                    &upgrade_revert_data,
                 )
                 .execute(transaction.conn())
                 .await
@@ -848,6 +914,7 @@ impl TransactionsDal<'_, '_> {
                     l1_batch_number = NULL,
                     miniblock_number = NULL,
                     error = NULL,
+                    revert_data = NULL,
                     index_in_block = NULL,
                     execution_info = '{}'
                 WHERE
@@ -898,6 +965,45 @@ impl TransactionsDal<'_, '_> {
         Ok(rows.len())
     }
 
+    /// Marks L2 transactions that cannot be included into the next miniblock because of their deadline as expired.
+    /// The next miniblock is assumed to have a number greater than `sealed_miniblock_number` and a timestamp
+    /// not less than `timestamp`.
+    ///
+    /// Transactions already loaded into the mempool are not affected; the state keeper rejects them
+    /// on its own once they are taken from the mempool.
+    pub async fn mark_expired_txs(
+        &mut self,
+        sealed_miniblock_number: MiniblockNumber,
+        timestamp: u64,
+    ) -> sqlx::Result<usize> {
+        let rows = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                error = $3,
+                updated_at = NOW()
+            WHERE
+                miniblock_number IS NULL
+                AND in_mempool = FALSE
+                AND is_priority = FALSE
+                AND error IS NULL
+                AND (
+                    valid_until_block <= $1
+                    OR valid_until_timestamp <= $2
+                )
+            RETURNING
+                hash
+            "#,
+            i64::from(sealed_miniblock_number.0) + 1,
+            timestamp as i64,
+            TX_EXPIRED_ERROR
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows.len())
+    }
+
     /// Fetches new updates for mempool. Returns new transactions and current nonces for related accounts;
     /// the latter are only used to bootstrap mempool for given account.
     pub async fn sync_mempool(
@@ -1074,6 +1180,193 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns the oldest priority operation that is not executed on L1 yet (i.e., is not included into
+    /// an L1 batch with a confirmed execute transaction), together with the L1 batch it's included into (if any)
+    /// and the time it was received by the server.
+    pub async fn get_oldest_unexecuted_priority_op(
+        &mut self,
+    ) -> sqlx::Result<Option<(PriorityOpId, Option<L1BatchNumber>, NaiveDateTime)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                priority_op_id AS "priority_op_id!",
+                l1_batch_number,
+                received_at
+            FROM
+                transactions
+            WHERE
+                priority_op_id IS NOT NULL
+                AND (
+                    l1_batch_number IS NULL
+                    OR l1_batch_number > COALESCE(
+                        (
+                            SELECT
+                                number
+                            FROM
+                                l1_batches
+                                LEFT JOIN eth_txs_history AS execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id)
+                            WHERE
+                                execute_tx.confirmed_at IS NOT NULL
+                            ORDER BY
+                                number DESC
+                            LIMIT
+                                1
+                        ),
+                        -1
+                    )
+                )
+            ORDER BY
+                priority_op_id
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_oldest_unexecuted_priority_op")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| {
+            (
+                PriorityOpId(row.priority_op_id as u64),
+                row.l1_batch_number
+                    .map(|number| L1BatchNumber(number as u32)),
+                row.received_at,
+            )
+        }))
+    }
+
+    /// Returns canonical hashes of priority operations with IDs in the specified range, ordered by the operation ID.
+    pub async fn get_priority_op_hashes(
+        &mut self,
+        ids: ops::Range<PriorityOpId>,
+    ) -> sqlx::Result<Vec<H256>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash
+            FROM
+                transactions
+            WHERE
+                priority_op_id >= $1
+                AND priority_op_id < $2
+            ORDER BY
+                priority_op_id
+            "#,
+            ids.start.0 as i64,
+            ids.end.0 as i64
+        )
+        .instrument("get_priority_op_hashes")
+        .with_arg("ids", &ids)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect())
+    }
+
+    /// Returns the range of IDs of priority operations included into the specified L1 batch, or `None`
+    /// if the batch doesn't contain priority operations.
+    pub async fn get_priority_op_ids_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<ops::RangeInclusive<PriorityOpId>>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MIN(priority_op_id) AS "first_id",
+                MAX(priority_op_id) AS "last_id"
+            FROM
+                transactions
+            WHERE
+                l1_batch_number = $1
+                AND priority_op_id IS NOT NULL
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_priority_op_ids_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.first_id.zip(row.last_id).map(|(first_id, last_id)| {
+            PriorityOpId(first_id as u64)..=PriorityOpId(last_id as u64)
+        }))
+    }
+
+    /// Returns fee-related data for all L2 transactions in the specified miniblock range, ordered by execution.
+    pub async fn get_tx_fee_records(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<TxFeeRecord>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblocks.number,
+                miniblocks.timestamp,
+                miniblocks.l1_batch_number,
+                miniblocks.protocol_version,
+                miniblocks.l1_gas_price,
+                miniblocks.l2_fair_gas_price,
+                miniblocks.fair_pubdata_price,
+                (transactions.execution_info ->> 'computational_gas_used')::BIGINT AS "computational_gas_used",
+                (transactions.execution_info ->> 'pubdata_published')::BIGINT AS "pubdata_published"
+            FROM
+                transactions
+                INNER JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE
+                miniblocks.number BETWEEN $1 AND $2
+                AND transactions.is_priority = FALSE
+            ORDER BY
+                miniblocks.number,
+                transactions.index_in_block
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
+        )
+        .instrument("get_tx_fee_records")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut records = Vec::with_capacity(rows.len());
+        for row in rows {
+            let protocol_version = row
+                .protocol_version
+                .map(|version| ProtocolVersionId::try_from(version as u16))
+                .transpose()
+                .map_err(|err| sqlx::Error::Decode(err.into()))?;
+            let is_pubdata_independent =
+                protocol_version.map_or(false, |version| version.is_post_1_4_1());
+            let batch_fee_input = match row.fair_pubdata_price {
+                Some(fair_pubdata_price) if is_pubdata_independent => {
+                    BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+                        l1_gas_price: row.l1_gas_price as u64,
+                        fair_l2_gas_price: row.l2_fair_gas_price as u64,
+                        fair_pubdata_price: fair_pubdata_price as u64,
+                    })
+                }
+                _ => BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
+                    l1_gas_price: row.l1_gas_price as u64,
+                    fair_l2_gas_price: row.l2_fair_gas_price as u64,
+                }),
+            };
+            records.push(TxFeeRecord {
+                miniblock_number: MiniblockNumber(row.number as u32),
+                miniblock_timestamp: row.timestamp as u64,
+                l1_batch_number: row
+                    .l1_batch_number
+                    .map(|number| L1BatchNumber(number as u32)),
+                protocol_version,
+                batch_fee_input,
+                computational_gas_used: row.computational_gas_used.unwrap_or(0) as u64,
+                pubdata_published: row.pubdata_published.unwrap_or(0) as u64,
+            });
+        }
+        Ok(records)
+    }
+
     /// Returns miniblocks with their transactions that state_keeper needs to re-execute on restart.
     /// These are the transactions that are included to some miniblock,
     /// but not included to L1 batch. The order of the transactions is the same as it was
@@ -1288,6 +1581,156 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns execution outcomes for all transactions in the specified L1 batch. Outcomes are ordered
+    /// in the same way as transactions returned by [`Self::get_miniblocks_to_execute_for_l1_batch()`].
+    pub async fn get_execution_outcomes_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<TxExecutionOutcome>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                error,
+                refunded_gas
+            FROM
+                transactions
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                miniblock_number,
+                index_in_block
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TxExecutionOutcome {
+                hash: H256::from_slice(&row.hash),
+                status: if row.error.is_some() {
+                    TxExecutionStatus::Failure
+                } else {
+                    TxExecutionStatus::Success
+                },
+                refunded_gas: row.refunded_gas as u64,
+            })
+            .collect())
+    }
+
+    /// Returns execution outcomes for all transactions in the specified miniblock ordered by their index
+    /// in the miniblock.
+    pub async fn get_execution_outcomes_for_miniblock(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<Vec<TxExecutionOutcome>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                error,
+                refunded_gas
+            FROM
+                transactions
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                index_in_block
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_execution_outcomes_for_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TxExecutionOutcome {
+                hash: H256::from_slice(&row.hash),
+                status: if row.error.is_some() {
+                    TxExecutionStatus::Failure
+                } else {
+                    TxExecutionStatus::Success
+                },
+                refunded_gas: row.refunded_gas as u64,
+            })
+            .collect())
+    }
+
+    /// Returns the specified miniblock with its transactions to be used in VM execution, or `None`
+    /// if the miniblock is not sealed. Unlike [`Self::get_miniblocks_to_execute_for_l1_batch()`], the miniblock
+    /// may belong to an unsealed L1 batch and may contain no transactions (e.g., be a fictive miniblock).
+    pub async fn get_miniblock_to_execute(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<Option<MiniblockExecutionData>> {
+        let prev_miniblock_number = miniblock_number
+            .0
+            .checked_sub(1)
+            .map(MiniblockNumber)
+            .context("genesis miniblock cannot be executed")?;
+        let Some(header) = self
+            .storage
+            .blocks_dal()
+            .get_miniblock_header(miniblock_number)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let prev_header = self
+            .storage
+            .blocks_dal()
+            .get_miniblock_header(prev_miniblock_number)
+            .await?;
+        let prev_block_hash = match prev_header {
+            Some(header) => header.hash,
+            None => {
+                // Can occur after snapshot recovery; the previous miniblock may not be present in the storage.
+                let snapshot_recovery = self
+                    .storage
+                    .snapshot_recovery_dal()
+                    .get_applied_snapshot_status()
+                    .await?
+                    .filter(|status| status.miniblock_number == prev_miniblock_number);
+                snapshot_recovery
+                    .with_context(|| {
+                        format!("miniblock #{prev_miniblock_number} is missing in the storage")
+                    })?
+                    .miniblock_hash
+            }
+        };
+
+        let transactions = sqlx::query_as!(
+            StorageTransaction,
+            r#"
+            SELECT
+                *
+            FROM
+                transactions
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                index_in_block
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_miniblock_to_execute")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(Some(MiniblockExecutionData {
+            number: miniblock_number,
+            timestamp: header.timestamp,
+            prev_block_hash,
+            virtual_blocks: header.virtual_blocks,
+            txs: transactions.into_iter().map(Transaction::from).collect(),
+        }))
+    }
+
     pub async fn get_call_trace(&mut self, tx_hash: H256) -> sqlx::Result<Option<Call>> {
         Ok(sqlx::query_as!(
             CallTrace,
@@ -1328,14 +1771,173 @@ impl TransactionsDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::ProtocolVersion;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{L1BatchHeader, MiniblockHeader},
+        ProtocolVersion,
+    };
 
     use super::*;
     use crate::{
-        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        tests::{
+            create_miniblock_header, mock_execution_result, mock_l1_execute, mock_l2_transaction,
+        },
         ConnectionPool,
     };
 
+    #[tokio::test]
+    async fn getting_oldest_unexecuted_priority_op() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        let op = conn
+            .transactions_dal()
+            .get_oldest_unexecuted_priority_op()
+            .await
+            .unwrap();
+        assert_eq!(op, None);
+
+        let mut tx = mock_l1_execute();
+        tx.received_timestamp_ms = 1_000_000;
+        conn.transactions_dal()
+            .insert_transaction_l1(tx, L1BlockNumber(1))
+            .await;
+        let (op_id, l1_batch_number, received_at) = conn
+            .transactions_dal()
+            .get_oldest_unexecuted_priority_op()
+            .await
+            .unwrap()
+            .expect("no priority op");
+        assert_eq!(op_id, PriorityOpId(1));
+        assert_eq!(l1_batch_number, None);
+        assert_eq!(received_at.timestamp_millis(), 1_000_000);
+    }
+
+    #[tokio::test]
+    async fn getting_priority_op_hashes() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        let mut txs = vec![];
+        for serial_id in 0..3 {
+            let mut tx = mock_l1_execute();
+            tx.common_data.serial_id = PriorityOpId(serial_id);
+            tx.common_data.canonical_tx_hash = H256::from_low_u64_be(serial_id + 1);
+            conn.transactions_dal()
+                .insert_transaction_l1(tx.clone(), L1BlockNumber(1))
+                .await;
+            txs.push(tx);
+        }
+        let hashes = conn
+            .transactions_dal()
+            .get_priority_op_hashes(PriorityOpId(1)..PriorityOpId(5))
+            .await
+            .unwrap();
+        assert_eq!(hashes, [txs[1].hash(), txs[2].hash()]);
+
+        let op_ids = conn
+            .transactions_dal()
+            .get_priority_op_ids_for_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(op_ids, None);
+
+        let tx_results: Vec<_> = txs[..2]
+            .iter()
+            .map(|tx| TransactionExecutionResult {
+                hash: tx.hash(),
+                ..mock_execution_result(mock_l2_transaction())
+            })
+            .collect();
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &tx_results)
+            .await;
+        let op_ids = conn
+            .transactions_dal()
+            .get_priority_op_ids_for_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(op_ids, Some(PriorityOpId(0)..=PriorityOpId(1)));
+    }
+
+    #[tokio::test]
+    async fn getting_tx_fee_records() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let batch_fee_input =
+            BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+                l1_gas_price: 1_000,
+                fair_l2_gas_price: 100,
+                fair_pubdata_price: 10_000,
+            });
+        let miniblock_header = MiniblockHeader {
+            timestamp: 100,
+            batch_fee_input,
+            ..create_miniblock_header(1)
+        };
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .unwrap();
+
+        let tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        let mut tx_result = mock_execution_result(tx);
+        tx_result.execution_info.computational_gas_used = 50_000;
+        tx_result.execution_info.pubdata_published = 300;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], 1.into())
+            .await;
+
+        let miniblocks = conn
+            .blocks_dal()
+            .get_miniblock_range_by_timestamps(0..=1_000)
+            .await
+            .unwrap();
+        assert_eq!(miniblocks, Some(MiniblockNumber(1)..=MiniblockNumber(1)));
+        let miniblocks = conn
+            .blocks_dal()
+            .get_miniblock_range_by_timestamps(101..=1_000)
+            .await
+            .unwrap();
+        assert_eq!(miniblocks, None);
+
+        let records = conn
+            .transactions_dal()
+            .get_tx_fee_records(MiniblockNumber(0)..=MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            records,
+            [TxFeeRecord {
+                miniblock_number: MiniblockNumber(1),
+                miniblock_timestamp: 100,
+                l1_batch_number: None,
+                protocol_version: miniblock_header.protocol_version,
+                batch_fee_input,
+                computational_gas_used: 50_000,
+                pubdata_published: 300,
+            }]
+        );
+    }
+
     #[tokio::test]
     async fn getting_call_trace_for_transaction() {
         let connection_pool = ConnectionPool::test_pool().await;
@@ -1373,4 +1975,75 @@ mod tests {
             .expect("no call trace");
         assert_eq!(call_trace, expected_call_trace);
     }
+
+    #[tokio::test]
+    async fn getting_miniblock_to_execute() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..=2 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        let mut tx_result = mock_execution_result(tx);
+        tx_result.refunded_gas = 1_000;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], 1.into())
+            .await;
+
+        let miniblock = conn
+            .transactions_dal()
+            .get_miniblock_to_execute(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .expect("no miniblock");
+        let prev_header = conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(miniblock.number, MiniblockNumber(1));
+        assert_eq!(miniblock.prev_block_hash, prev_header.hash);
+        let tx_hashes: Vec<_> = miniblock.txs.iter().map(Transaction::hash).collect();
+        assert_eq!(tx_hashes, [tx_hash]);
+        let outcomes = conn
+            .transactions_dal()
+            .get_execution_outcomes_for_miniblock(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            outcomes,
+            [TxExecutionOutcome {
+                hash: tx_hash,
+                status: TxExecutionStatus::Success,
+                refunded_gas: 1_000,
+            }]
+        );
+
+        // Miniblocks without transactions are returned as well.
+        let miniblock = conn
+            .transactions_dal()
+            .get_miniblock_to_execute(MiniblockNumber(2))
+            .await
+            .unwrap()
+            .expect("no miniblock");
+        assert!(miniblock.txs.is_empty());
+        let missing_miniblock = conn
+            .transactions_dal()
+            .get_miniblock_to_execute(MiniblockNumber(3))
+            .await
+            .unwrap();
+        assert!(missing_miniblock.is_none());
+    }
 }
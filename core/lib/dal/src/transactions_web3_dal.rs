@@ -1,8 +1,10 @@
-use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
 use zksync_types::{
-    api, api::TransactionReceipt, Address, L2ChainId, MiniblockNumber, Transaction,
-    ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
+    api, api::TransactionReceipt, l2::SignatureScheme, Address, L2ChainId, MiniblockNumber,
+    Transaction, ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256,
+    U256,
 };
+use zksync_utils::address_to_h256;
 
 use crate::{
     instrument::InstrumentExt,
@@ -16,6 +18,27 @@ use crate::{
     SqlxError, StorageProcessor,
 };
 
+/// Converts a cursor to the `(miniblock_number, index_in_block)` position in the transaction history.
+fn cursor_position(cursor: Option<api::TransactionsByAddressCursor>) -> (i64, i32) {
+    // `(i64::MAX, i32::MAX)` is greater than any real transaction position, i.e., it points to the history start.
+    cursor.map_or((i64::MAX, i32::MAX), |cursor| {
+        (
+            i64::from(cursor.block_number.0),
+            cursor.transaction_index.min(i32::MAX as u32) as i32,
+        )
+    })
+}
+
+/// Filter for [`TransactionsWeb3Dal::get_transactions_page()`]. Unset conditions are not checked.
+#[derive(Debug, Default)]
+struct TransactionsPageFilter {
+    initiator_address: Option<Address>,
+    contract_address: Option<Address>,
+    /// Sender or recipient of an ERC-20 `Transfer` event emitted by the transaction.
+    token_transfer_address: Option<Address>,
+    signature_scheme: Option<SignatureScheme>,
+}
+
 #[derive(Debug)]
 pub struct TransactionsWeb3Dal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -182,6 +205,9 @@ impl TransactionsWeb3Dal<'_, '_> {
                     transactions.received_at,
                     transactions.miniblock_number,
                     transactions.error,
+                    transactions.revert_data,
+                    transactions.valid_until_timestamp,
+                    transactions.valid_until_block,
                     transactions.effective_gas_price,
                     transactions.refunded_gas,
                     commit_tx.tx_hash AS "eth_commit_tx_hash?",
@@ -219,10 +245,158 @@ impl TransactionsWeb3Dal<'_, '_> {
         }
     }
 
-    /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
+    /// Returns executed transactions in which `address` has the specified `role`, ordered from newest to oldest.
+    /// If `cursor` is specified, only transactions strictly preceding it are returned.
+    pub async fn get_transactions_by_address(
+        &mut self,
+        address: Address,
+        role: api::TransactionAddressRole,
+        cursor: Option<api::TransactionsByAddressCursor>,
+        limit: usize,
+        chain_id: L2ChainId,
+    ) -> Result<Vec<api::Transaction>, SqlxError> {
+        let filter = match role {
+            api::TransactionAddressRole::Initiator => TransactionsPageFilter {
+                initiator_address: Some(address),
+                ..TransactionsPageFilter::default()
+            },
+            api::TransactionAddressRole::ContractCalled => TransactionsPageFilter {
+                contract_address: Some(address),
+                ..TransactionsPageFilter::default()
+            },
+            api::TransactionAddressRole::TokenTransferred => TransactionsPageFilter {
+                token_transfer_address: Some(address),
+                ..TransactionsPageFilter::default()
+            },
+        };
+        self.get_transactions_page(filter, cursor, limit, chain_id)
+            .await
+    }
+
+    /// Returns executed L2 transactions with the specified signature scheme, ordered from newest to oldest.
+    /// If `cursor` is specified, only transactions strictly preceding it are returned.
+    /// Transactions stored before signature schemes were recorded are not returned.
+    pub async fn get_transactions_by_signature_scheme(
+        &mut self,
+        scheme: SignatureScheme,
+        cursor: Option<api::TransactionsByAddressCursor>,
+        limit: usize,
+        chain_id: L2ChainId,
+    ) -> Result<Vec<api::Transaction>, SqlxError> {
+        let filter = TransactionsPageFilter {
+            signature_scheme: Some(scheme),
+            ..TransactionsPageFilter::default()
+        };
+        self.get_transactions_page(filter, cursor, limit, chain_id)
+            .await
+    }
+
+    /// Returns a page of executed transactions matching the `filter`, ordered from newest to oldest.
+    async fn get_transactions_page(
+        &mut self,
+        filter: TransactionsPageFilter,
+        cursor: Option<api::TransactionsByAddressCursor>,
+        limit: usize,
+        chain_id: L2ChainId,
+    ) -> Result<Vec<api::Transaction>, SqlxError> {
+        // The `Transfer` event topic is inlined since otherwise the planner may not use
+        // the partial `events_transfer_*` indexes.
+        let query = format!(
+            r#"
+            SELECT
+                {}
+            FROM
+                transactions
+                LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE
+                transactions.miniblock_number IS NOT NULL
+                AND (transactions.miniblock_number, transactions.index_in_block) < ($5, $6)
+                AND (
+                    $1::BYTEA IS NULL
+                    OR transactions.initiator_address = $1
+                )
+                AND (
+                    $2::BYTEA IS NULL
+                    OR transactions.contract_address = $2
+                )
+                AND (
+                    $3::BYTEA IS NULL
+                    OR (transactions.miniblock_number, transactions.index_in_block) IN (
+                        (
+                            SELECT
+                                miniblock_number,
+                                tx_index_in_block
+                            FROM
+                                events
+                            WHERE
+                                topic1 = '\xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'
+                                AND topic2 = $3
+                                AND (miniblock_number, tx_index_in_block) < ($5, $6)
+                            ORDER BY
+                                miniblock_number DESC,
+                                tx_index_in_block DESC
+                            LIMIT
+                                $7
+                        )
+                        UNION
+                        (
+                            SELECT
+                                miniblock_number,
+                                tx_index_in_block
+                            FROM
+                                events
+                            WHERE
+                                topic1 = '\xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'
+                                AND topic3 = $3
+                                AND (miniblock_number, tx_index_in_block) < ($5, $6)
+                            ORDER BY
+                                miniblock_number DESC,
+                                tx_index_in_block DESC
+                            LIMIT
+                                $7
+                        )
+                    )
+                )
+                AND (
+                    $4::TEXT IS NULL
+                    OR transactions.signature_scheme = $4
+                )
+            ORDER BY
+                transactions.miniblock_number DESC,
+                transactions.index_in_block DESC
+            LIMIT
+                $7
+            "#,
+            web3_transaction_select_sql()
+        );
+
+        let (cursor_block, cursor_index) = cursor_position(cursor);
+        let transactions = sqlx::query(&query)
+            .bind(filter.initiator_address.map(|address| address.0.to_vec()))
+            .bind(filter.contract_address.map(|address| address.0.to_vec()))
+            .bind(
+                filter
+                    .token_transfer_address
+                    .map(|address| address_to_h256(&address).0.to_vec()),
+            )
+            .bind(filter.signature_scheme.map(|scheme| scheme.to_string()))
+            .bind(cursor_block)
+            .bind(cursor_index)
+            .bind(limit as i64)
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| extract_web3_transaction(row, chain_id))
+            .collect();
+        Ok(transactions)
+    }
+
+    /// Returns hashes of transactions received after `from_timestamp` (exclusive) and, if specified,
+    /// no later than `to_timestamp`, together with the receiving timestamp of the last returned transaction.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
         from_timestamp: NaiveDateTime,
+        to_timestamp: Option<NaiveDateTime>,
         limit: Option<usize>,
     ) -> Result<(Vec<H256>, Option<NaiveDateTime>), SqlxError> {
         let records = sqlx::query!(
@@ -235,12 +409,17 @@ impl TransactionsWeb3Dal<'_, '_> {
                 LEFT JOIN miniblocks ON miniblocks.number = miniblock_number
             WHERE
                 received_at > $1
+                AND (
+                    $2::TIMESTAMP IS NULL
+                    OR received_at <= $2
+                )
             ORDER BY
                 received_at ASC
             LIMIT
-                $2
+                $3
             "#,
             from_timestamp,
+            to_timestamp,
             limit.map(|limit| limit as i64)
         )
         .fetch_all(self.storage.conn())
@@ -255,6 +434,9 @@ impl TransactionsWeb3Dal<'_, '_> {
     }
 
     /// `committed_next_nonce` should equal the nonce for `initiator_address` in the storage.
+    ///
+    /// For accounts with arbitrary nonce ordering, the returned nonce is the least nonce not used
+    /// by any non-rejected transaction, so it's always safe to use for a new transaction.
     pub async fn next_nonce_by_initiator_account(
         &mut self,
         initiator_address: Address,
@@ -329,6 +511,43 @@ impl TransactionsWeb3Dal<'_, '_> {
 
         Ok(rows.into_iter().map(Into::into).collect())
     }
+
+    /// Returns arrival information for transactions in the specified miniblock ordered by their index in the block.
+    pub async fn get_miniblock_ordering_info(
+        &mut self,
+        miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Vec<api::TransactionOrderingInfo>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                index_in_block,
+                arrival_seq,
+                received_at
+            FROM
+                transactions
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                index_in_block
+            "#,
+            miniblock.0 as i64
+        )
+        .instrument("get_miniblock_ordering_info")
+        .with_arg("miniblock", &miniblock)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::TransactionOrderingInfo {
+                hash: H256::from_slice(&row.hash),
+                index_in_block: row.index_in_block.unwrap_or_default() as u32,
+                arrival_seq: row.arrival_seq.map(|seq| seq as u64),
+                received_at: DateTime::<Utc>::from_naive_utc_and_offset(row.received_at, Utc),
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -336,8 +555,8 @@ mod tests {
     use std::collections::HashMap;
 
     use zksync_types::{
-        block::MiniblockHasher, fee::TransactionExecutionMetrics, l2::L2Tx, Nonce, ProtocolVersion,
-        ProtocolVersionId,
+        block::MiniblockHasher, event::TRANSFER_EVENT_SIGNATURE, fee::TransactionExecutionMetrics,
+        l2::L2Tx, Nonce, ProtocolVersion, ProtocolVersionId,
     };
 
     use super::*;
@@ -346,6 +565,15 @@ mod tests {
         ConnectionPool,
     };
 
+    #[test]
+    fn transfer_event_topic_is_inlined_correctly() {
+        // The topic is inlined into the `get_transactions_page()` query.
+        assert_eq!(
+            hex::encode(TRANSFER_EVENT_SIGNATURE.as_bytes()),
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+        );
+    }
+
     async fn prepare_transactions(conn: &mut StorageProcessor<'_>, txs: Vec<L2Tx>) {
         conn.blocks_dal()
             .delete_miniblocks(MiniblockNumber(0))
@@ -496,6 +724,34 @@ mod tests {
         assert_eq!(raw_txs[0].hash(), tx_hash);
     }
 
+    #[tokio::test]
+    async fn getting_miniblock_ordering_info() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let txs = vec![mock_l2_transaction(), mock_l2_transaction()];
+        let tx_hashes: Vec<_> = txs.iter().map(L2Tx::hash).collect();
+        prepare_transactions(&mut conn, txs).await;
+
+        let ordering_info = conn
+            .transactions_web3_dal()
+            .get_miniblock_ordering_info(MiniblockNumber(1))
+            .await
+            .unwrap();
+        let hashes: Vec<_> = ordering_info.iter().map(|tx| tx.hash).collect();
+        assert_eq!(hashes, tx_hashes);
+        let arrival_seqs: Vec<_> = ordering_info
+            .iter()
+            .map(|tx| tx.arrival_seq.unwrap())
+            .collect();
+        assert!(arrival_seqs[0] < arrival_seqs[1], "{arrival_seqs:?}");
+
+        let block_info = api::BlockOrderingInfo::new(MiniblockNumber(1), ordering_info);
+        assert_eq!(block_info.max_reordering, 0);
+    }
+
     #[tokio::test]
     async fn getting_next_nonce_by_initiator_account() {
         let connection_pool = ConnectionPool::test_pool().await;
@@ -593,4 +849,125 @@ mod tests {
             .unwrap();
         assert_eq!(next_nonce, 2.into());
     }
+
+    #[tokio::test]
+    async fn getting_transactions_by_address() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let contract = Address::repeat_byte(0x42);
+        let txs: Vec<_> = (0..3)
+            .map(|_| {
+                let mut tx = mock_l2_transaction();
+                tx.execute.contract_address = contract;
+                tx
+            })
+            .collect();
+        let tx_hashes: Vec<_> = txs.iter().map(L2Tx::hash).collect();
+        let initiator = txs[0].initiator_account();
+        prepare_transactions(&mut conn, txs).await;
+
+        let first_page = conn
+            .transactions_web3_dal()
+            .get_transactions_by_address(
+                contract,
+                api::TransactionAddressRole::ContractCalled,
+                None,
+                2,
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        let first_page_hashes: Vec<_> = first_page.iter().map(|tx| tx.hash).collect();
+        assert_eq!(first_page_hashes, [tx_hashes[2], tx_hashes[1]]);
+
+        let cursor = api::TransactionsByAddressCursor {
+            block_number: MiniblockNumber(1),
+            transaction_index: 1,
+        };
+        let second_page = conn
+            .transactions_web3_dal()
+            .get_transactions_by_address(
+                contract,
+                api::TransactionAddressRole::ContractCalled,
+                Some(cursor),
+                2,
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        let second_page_hashes: Vec<_> = second_page.iter().map(|tx| tx.hash).collect();
+        assert_eq!(second_page_hashes, [tx_hashes[0]]);
+
+        let initiated_txs = conn
+            .transactions_web3_dal()
+            .get_transactions_by_address(
+                initiator,
+                api::TransactionAddressRole::Initiator,
+                None,
+                10,
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        assert_eq!(initiated_txs.len(), 1);
+        assert_eq!(initiated_txs[0].hash, tx_hashes[0]);
+
+        let transferred_txs = conn
+            .transactions_web3_dal()
+            .get_transactions_by_address(
+                initiator,
+                api::TransactionAddressRole::TokenTransferred,
+                None,
+                10,
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        assert!(transferred_txs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn getting_transactions_by_signature_scheme() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let mut custom_tx = mock_l2_transaction();
+        custom_tx.common_data.signature = vec![1; 130];
+        let ecdsa_tx = mock_l2_transaction();
+        let (custom_tx_hash, ecdsa_tx_hash) = (custom_tx.hash(), ecdsa_tx.hash());
+        prepare_transactions(&mut conn, vec![custom_tx, ecdsa_tx]).await;
+
+        let custom_txs = conn
+            .transactions_web3_dal()
+            .get_transactions_by_signature_scheme(
+                SignatureScheme::Custom,
+                None,
+                10,
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        let custom_tx_hashes: Vec<_> = custom_txs.iter().map(|tx| tx.hash).collect();
+        assert_eq!(custom_tx_hashes, [custom_tx_hash]);
+
+        let ecdsa_txs = conn
+            .transactions_web3_dal()
+            .get_transactions_by_signature_scheme(
+                SignatureScheme::Ecdsa,
+                None,
+                10,
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        let ecdsa_tx_hashes: Vec<_> = ecdsa_txs.iter().map(|tx| tx.hash).collect();
+        assert_eq!(ecdsa_tx_hashes, [ecdsa_tx_hash]);
+    }
 }
@@ -50,6 +50,8 @@ impl FromEnv for MerkleTreeApiConfig {
 mod tests {
     use std::num::NonZeroU32;
 
+    use zksync_config::configs::api::MempoolFeedMode;
+
     use super::*;
     use crate::test_utils::{hash, EnvMutex};
 
@@ -64,6 +66,7 @@ mod tests {
                 ws_url: "ws://127.0.0.1:3051".into(),
                 req_entities_limit: Some(10000),
                 filters_disabled: false,
+                reject_txs_without_chain_id: true,
                 filters_limit: Some(10000),
                 subscriptions_limit: Some(10000),
                 pubsub_polling_interval: Some(200),
@@ -88,6 +91,31 @@ mod tests {
                 max_response_body_size_mb: Some(10),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
                 tree_api_url: None,
+                custom_errors_abi_path: Some("/etc/zksync/custom_errors.json".into()),
+                sponsorship_policy_path: Some("/etc/zksync/sponsorship_policy.json".into()),
+                shared_cache_url: Some("redis://127.0.0.1:6379".into()),
+                shutdown_delay_ms: Some(2_000),
+                shutdown_timeout_ms: Some(10_000),
+                validation_cache_capacity: Some(1_000),
+                vm_execution_time_budget_ms: Some(5_000),
+                debug_namespace_concurrency_limit: Some(16),
+                debug_namespace_requests_per_minute_limit: Some(NonZeroU32::new(600).unwrap()),
+                debug_trace_block_tx_limit: Some(1_000),
+                tx_forwarding_url: Some("https://127.0.0.1:3080".into()),
+                tx_forwarding_ca_cert_path: Some("/etc/zksync/tx_intake_ca.pem".into()),
+                tx_forwarding_auth_token: Some("token".into()),
+                tx_forwarding_queue_capacity: Some(500),
+                tx_intake_port: Some(3080),
+                tx_intake_bind_address: Some("10.0.0.1".parse().unwrap()),
+                tx_intake_tls_cert_path: Some("/etc/zksync/tx_intake_cert.pem".into()),
+                tx_intake_tls_key_path: Some("/etc/zksync/tx_intake_key.pem".into()),
+                call_cache_capacity: Some(5_000),
+                mempool_feed_mode: Some(MempoolFeedMode::Delayed),
+                mempool_feed_delay_ms: Some(5_000),
+                mempool_feed_rate_limit: Some(NonZeroU32::new(100).unwrap()),
+                subscription_handoff_ttl_sec: Some(300),
+                admin_port: Some(3090),
+                logs_query_planner_enabled: true,
             },
             contract_verification: ContractVerificationApiConfig {
                 port: 3070,
@@ -117,6 +145,7 @@ mod tests {
             API_WEB3_JSON_RPC_WS_URL="ws://127.0.0.1:3051"
             API_WEB3_JSON_RPC_REQ_ENTITIES_LIMIT=10000
             API_WEB3_JSON_RPC_FILTERS_DISABLED=false
+            API_WEB3_JSON_RPC_REJECT_TXS_WITHOUT_CHAIN_ID=true
             API_WEB3_JSON_RPC_FILTERS_LIMIT=10000
             API_WEB3_JSON_RPC_SUBSCRIPTIONS_LIMIT=10000
             API_WEB3_JSON_RPC_PUBSUB_POLLING_INTERVAL=200
@@ -135,6 +164,31 @@ mod tests {
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
+            API_WEB3_JSON_RPC_CUSTOM_ERRORS_ABI_PATH="/etc/zksync/custom_errors.json"
+            API_WEB3_JSON_RPC_SPONSORSHIP_POLICY_PATH="/etc/zksync/sponsorship_policy.json"
+            API_WEB3_JSON_RPC_SHARED_CACHE_URL="redis://127.0.0.1:6379"
+            API_WEB3_JSON_RPC_SHUTDOWN_DELAY_MS=2000
+            API_WEB3_JSON_RPC_SHUTDOWN_TIMEOUT_MS=10000
+            API_WEB3_JSON_RPC_VALIDATION_CACHE_CAPACITY=1000
+            API_WEB3_JSON_RPC_VM_EXECUTION_TIME_BUDGET_MS=5000
+            API_WEB3_JSON_RPC_DEBUG_NAMESPACE_CONCURRENCY_LIMIT=16
+            API_WEB3_JSON_RPC_DEBUG_NAMESPACE_REQUESTS_PER_MINUTE_LIMIT=600
+            API_WEB3_JSON_RPC_DEBUG_TRACE_BLOCK_TX_LIMIT=1000
+            API_WEB3_JSON_RPC_TX_FORWARDING_URL="https://127.0.0.1:3080"
+            API_WEB3_JSON_RPC_TX_FORWARDING_CA_CERT_PATH="/etc/zksync/tx_intake_ca.pem"
+            API_WEB3_JSON_RPC_TX_FORWARDING_AUTH_TOKEN="token"
+            API_WEB3_JSON_RPC_TX_FORWARDING_QUEUE_CAPACITY=500
+            API_WEB3_JSON_RPC_TX_INTAKE_PORT=3080
+            API_WEB3_JSON_RPC_TX_INTAKE_BIND_ADDRESS="10.0.0.1"
+            API_WEB3_JSON_RPC_TX_INTAKE_TLS_CERT_PATH="/etc/zksync/tx_intake_cert.pem"
+            API_WEB3_JSON_RPC_TX_INTAKE_TLS_KEY_PATH="/etc/zksync/tx_intake_key.pem"
+            API_WEB3_JSON_RPC_CALL_CACHE_CAPACITY=5000
+            API_WEB3_JSON_RPC_MEMPOOL_FEED_MODE="delayed"
+            API_WEB3_JSON_RPC_MEMPOOL_FEED_DELAY_MS=5000
+            API_WEB3_JSON_RPC_MEMPOOL_FEED_RATE_LIMIT=100
+            API_WEB3_JSON_RPC_SUBSCRIPTION_HANDOFF_TTL_SEC=300
+            API_WEB3_JSON_RPC_ADMIN_PORT=3090
+            API_WEB3_JSON_RPC_LOGS_QUERY_PLANNER_ENABLED=true
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
@@ -37,7 +37,7 @@ impl FromEnv for MempoolConfig {
 #[cfg(test)]
 mod tests {
     use zksync_basic_types::L2ChainId;
-    use zksync_config::configs::chain::FeeModelVersion;
+    use zksync_config::configs::chain::{FeeModelVersion, L1BatchCommitDataGeneratorMode};
 
     use super::*;
     use crate::test_utils::{addr, EnvMutex};
@@ -94,6 +94,21 @@ mod tests {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: Some(2_000),
+            tx_hook_url: Some("http://127.0.0.1:3070/".to_owned()),
+            tx_hook_timeout_ms: Some(500),
+            priority_tx_fee_premium: Some(0.25),
+            force_include_addresses: vec![
+                addr("0x6c10d9c1744f149d4b17660e14faa247964749c7"),
+                addr("0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
+            ],
+            shadow_protocol_version: Some(24),
+            max_factory_deps: Some(16),
+            max_tx_calldata_size: Some(500_000),
+            max_l1_timestamp_drift_sec: Some(1_800),
+            max_tree_lag_batches: Some(100),
+            max_commitment_lag_batches: Some(50),
+            miniblock_gas_limit: Some(100_000_000),
+            l1_batch_commit_data_generator_mode: L1BatchCommitDataGeneratorMode::Validium,
         }
     }
 
@@ -127,6 +142,18 @@ mod tests {
             CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_PER_MINIBLOCK="1"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_INTERVAL="1"
+            CHAIN_STATE_KEEPER_TX_HOOK_URL="http://127.0.0.1:3070/"
+            CHAIN_STATE_KEEPER_TX_HOOK_TIMEOUT_MS="500"
+            CHAIN_STATE_KEEPER_PRIORITY_TX_FEE_PREMIUM="0.25"
+            CHAIN_STATE_KEEPER_FORCE_INCLUDE_ADDRESSES="0x6c10d9c1744f149d4b17660e14faa247964749c7,0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+            CHAIN_STATE_KEEPER_SHADOW_PROTOCOL_VERSION="24"
+            CHAIN_STATE_KEEPER_MAX_FACTORY_DEPS="16"
+            CHAIN_STATE_KEEPER_MAX_TX_CALLDATA_SIZE="500000"
+            CHAIN_STATE_KEEPER_MAX_L1_TIMESTAMP_DRIFT_SEC="1800"
+            CHAIN_STATE_KEEPER_MAX_TREE_LAG_BATCHES="100"
+            CHAIN_STATE_KEEPER_MAX_COMMITMENT_LAG_BATCHES="50"
+            CHAIN_STATE_KEEPER_MINIBLOCK_GAS_LIMIT="100000000"
+            CHAIN_STATE_KEEPER_L1_BATCH_COMMIT_DATA_GENERATOR_MODE="Validium"
         "#;
         lock.set_env(config);
 
@@ -160,6 +187,7 @@ mod tests {
             stuck_tx_timeout: 10,
             remove_stuck_txs: true,
             delay_interval: 100,
+            fair_ordering_max_reordering: Some(5),
         }
     }
 
@@ -173,6 +201,7 @@ mod tests {
             CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
             CHAIN_MEMPOOL_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_CAPACITY="1000000"
+            CHAIN_MEMPOOL_FAIR_ORDERING_MAX_REORDERING="5"
         "#;
         lock.set_env(config);
 
@@ -186,6 +215,10 @@ mod tests {
             http_req_max_retry_number: 5,
             http_req_retry_interval_sec: 2,
             replication_lag_limit_sec: Some(10),
+            base_token_ratio_url: Some("http://127.0.0.1:3030/ratio".to_owned()),
+            base_token_ratio_max_change_percent: Some(5.0),
+            base_token_ratio_window_sec: None,
+            base_token_ratio_max_staleness_sec: Some(600),
         }
     }
 
@@ -197,6 +230,9 @@ mod tests {
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
             CHAIN_CIRCUIT_BREAKER_REPLICATION_LAG_LIMIT_SEC="10"
+            CHAIN_CIRCUIT_BREAKER_BASE_TOKEN_RATIO_URL="http://127.0.0.1:3030/ratio"
+            CHAIN_CIRCUIT_BREAKER_BASE_TOKEN_RATIO_MAX_CHANGE_PERCENT="5"
+            CHAIN_CIRCUIT_BREAKER_BASE_TOKEN_RATIO_MAX_STALENESS_SEC="600"
         "#;
         lock.set_env(config);
 
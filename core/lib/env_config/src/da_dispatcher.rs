@@ -0,0 +1,157 @@
+use zksync_config::configs::{AvailConfig, CelestiaConfig, DADispatcherConfig, EigenDAConfig};
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for DADispatcherConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("da_dispatcher", "DA_DISPATCHER_")
+    }
+}
+
+impl FromEnv for AvailConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("da_dispatcher_avail", "DA_DISPATCHER_AVAIL_")
+    }
+}
+
+impl FromEnv for EigenDAConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("da_dispatcher_eigen_da", "DA_DISPATCHER_EIGEN_DA_")
+    }
+}
+
+impl FromEnv for CelestiaConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("da_dispatcher_celestia", "DA_DISPATCHER_CELESTIA_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::DAClientConfig;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    fn expected_config() -> DADispatcherConfig {
+        DADispatcherConfig {
+            da_client_url: "http://localhost:4242/".to_owned(),
+            polling_interval_ms: 2_000,
+            max_rows_to_dispatch: 10,
+            max_retries: 3,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DA_DISPATCHER_DA_CLIENT_URL="http://localhost:4242/"
+            DA_DISPATCHER_POLLING_INTERVAL_MS=2000
+            DA_DISPATCHER_MAX_ROWS_TO_DISPATCH=10
+            DA_DISPATCHER_MAX_RETRIES=3
+        "#;
+        lock.set_env(config);
+
+        let actual = DADispatcherConfig::from_env().unwrap();
+        assert_eq!(actual, expected_config());
+    }
+
+    #[test]
+    fn avail_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DA_DISPATCHER_AVAIL_APP_ID=42
+            DA_DISPATCHER_AVAIL_API_KEY="secret"
+            DA_DISPATCHER_AVAIL_API_NODE_URL="https://turing-rpc.avail.so/rpc"
+            DA_DISPATCHER_AVAIL_BRIDGE_API_URL="https://turing-bridge-api.avail.so"
+        "#;
+        lock.set_env(config);
+
+        let actual = AvailConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            AvailConfig {
+                app_id: 42,
+                api_key: "secret".to_owned(),
+                api_node_url: "https://turing-rpc.avail.so/rpc".to_owned(),
+                bridge_api_url: Some("https://turing-bridge-api.avail.so".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn eigen_da_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DA_DISPATCHER_EIGEN_DA_ACCOUNT_ID="0x0123"
+            DA_DISPATCHER_EIGEN_DA_CUSTOM_QUORUM_NUMBERS="2,3"
+            DA_DISPATCHER_EIGEN_DA_WAIT_FOR_FINALIZATION="true"
+        "#;
+        lock.set_env(config);
+
+        let actual = EigenDAConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            EigenDAConfig {
+                account_id: "0x0123".to_owned(),
+                custom_quorum_numbers: vec![2, 3],
+                wait_for_finalization: true,
+            }
+        );
+    }
+
+    #[test]
+    fn selecting_da_client() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DA_DISPATCHER_AVAIL_APP_ID=42
+            DA_DISPATCHER_AVAIL_API_KEY="secret"
+            DA_DISPATCHER_AVAIL_API_NODE_URL="https://turing-rpc.avail.so/rpc"
+            DA_DISPATCHER_CELESTIA_NAMESPACE="00000000000000000a0b"
+            DA_DISPATCHER_CELESTIA_AUTH_TOKEN="token"
+        "#;
+        lock.set_env(config);
+
+        let celestia_config = CelestiaConfig::from_env().unwrap();
+        let selected =
+            DAClientConfig::select(None, None, Some(celestia_config.clone()), None).unwrap();
+        assert_eq!(
+            selected,
+            Some(DAClientConfig::Celestia(celestia_config.clone()))
+        );
+        let err = DAClientConfig::select(
+            Some(AvailConfig::from_env().unwrap()),
+            None,
+            Some(celestia_config),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Avail and Celestia"), "{err}");
+        assert_eq!(
+            DAClientConfig::select(None, None, None, None).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn celestia_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DA_DISPATCHER_CELESTIA_NAMESPACE="00000000000000000a0b"
+            DA_DISPATCHER_CELESTIA_AUTH_TOKEN="token"
+        "#;
+        lock.set_env(config);
+
+        let actual = CelestiaConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            CelestiaConfig {
+                namespace: "00000000000000000a0b".to_owned(),
+                auth_token: "token".to_owned(),
+            }
+        );
+    }
+}
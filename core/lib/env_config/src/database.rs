@@ -43,6 +43,8 @@ impl FromEnv for PostgresConfig {
         let long_connection_threshold_ms =
             parse_optional_var("DATABASE_LONG_CONNECTION_THRESHOLD_MS")?;
         let slow_query_threshold_ms = parse_optional_var("DATABASE_SLOW_QUERY_THRESHOLD_MS")?;
+        let leak_detection_threshold_ms =
+            parse_optional_var("DATABASE_LEAK_DETECTION_THRESHOLD_MS")?;
 
         Ok(Self {
             master_url,
@@ -53,6 +55,7 @@ impl FromEnv for PostgresConfig {
             statement_timeout_sec,
             long_connection_threshold_ms,
             slow_query_threshold_ms,
+            leak_detection_threshold_ms,
         })
     }
 }
@@ -145,6 +148,7 @@ mod tests {
             DATABASE_STATEMENT_TIMEOUT_SEC=300
             DATABASE_LONG_CONNECTION_THRESHOLD_MS=3000
             DATABASE_SLOW_QUERY_THRESHOLD_MS=150
+            DATABASE_LEAK_DETECTION_THRESHOLD_MS=60000
         "#;
         lock.set_env(config);
 
@@ -170,5 +174,9 @@ mod tests {
             postgres_config.slow_query_threshold(),
             Some(Duration::from_millis(150))
         );
+        assert_eq!(
+            postgres_config.leak_detection_threshold(),
+            Some(Duration::from_secs(60))
+        );
     }
 }
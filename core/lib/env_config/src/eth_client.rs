@@ -17,6 +17,8 @@ impl FromEnv for ETHClientConfig {
 
 #[cfg(test)]
 mod tests {
+    use zksync_config::configs::eth_client::L1FinalityMode;
+
     use super::*;
     use crate::test_utils::EnvMutex;
 
@@ -26,6 +28,8 @@ mod tests {
         ETHClientConfig {
             chain_id: 9,
             web3_url: "http://127.0.0.1:8545".into(),
+            finality_mode: Some(L1FinalityMode::Hybrid),
+            finality_confirmations: Some(10),
         }
     }
 
@@ -35,6 +39,8 @@ mod tests {
         let config = r#"
             ETH_CLIENT_CHAIN_ID="9"
             ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545"
+            ETH_CLIENT_FINALITY_MODE="hybrid"
+            ETH_CLIENT_FINALITY_CONFIRMATIONS="10"
         "#;
         lock.set_env(config);
 
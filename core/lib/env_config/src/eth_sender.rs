@@ -27,11 +27,11 @@ impl FromEnv for GasAdjusterConfig {
 #[cfg(test)]
 mod tests {
     use zksync_config::configs::eth_sender::{
-        ProofLoadingMode, ProofSendingMode, PubdataSendingMode,
+        ProofLoadingMode, ProofSendingMode, PubdataSendingMode, SignerBackend,
     };
 
     use super::*;
-    use crate::test_utils::{hash, EnvMutex};
+    use crate::test_utils::{addr, hash, EnvMutex};
 
     static MUTEX: EnvMutex = EnvMutex::new();
 
@@ -57,6 +57,26 @@ mod tests {
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
                 pubdata_sending_mode: PubdataSendingMode::Calldata,
+                operator_balance_alert_threshold_gwei: Some(1_000_000_000),
+                operator_runway_alert_threshold_hours: Some(24),
+                base_token_addr: Some(addr("0x7f5ea2bd3b4b7f1da1f3d0b4d0b5f53e3c8e8a61")),
+                operator_base_token_balance_alert_threshold: Some(10_000),
+                execution_time_windows: vec!["08:00-12:00".to_owned(), "22:00-02:00".to_owned()],
+                execution_approval_contract_addr: Some(addr(
+                    "0x6c10d9c1744f149d4b17660e14faa247964749c7",
+                )),
+                disabled_publish_criteria: vec!["timestamp".to_owned()],
+                dry_run: true,
+                shutdown_drain_timeout_sec: Some(120),
+                priority_op_deadline_sec: Some(86_400),
+                force_execute_on_priority_op_deadline: true,
+                signer_backend: SignerBackend::AwsKms,
+                signer_url: None,
+                signer_region: Some("eu-central-1".to_owned()),
+                operator_signer_key_id: Some("alias/operator".to_owned()),
+                blobs_operator_signer_key_id: Some("alias/blobs-operator".to_owned()),
+                max_blobs_per_eth_tx: 9,
+                auto_select_pubdata_da: true,
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -70,6 +90,8 @@ mod tests {
                 num_samples_for_blob_base_fee_estimate: 10,
                 internal_pubdata_pricing_multiplier: 1.0,
                 max_blob_base_fee: None,
+                max_l1_staleness_sec: Some(120),
+                internal_enforced_pubdata_price: Some(1000),
             },
         }
     }
@@ -93,6 +115,8 @@ mod tests {
             ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE="100000000"
             ETH_SENDER_GAS_ADJUSTER_MAX_BLOB_BASE_FEE_SAMPLES="10"
             ETH_SENDER_GAS_ADJUSTER_INTERNAL_PUBDATA_PRICING_MULTIPLIER="1.0"
+            ETH_SENDER_GAS_ADJUSTER_MAX_L1_STALENESS_SEC="120"
+            ETH_SENDER_GAS_ADJUSTER_INTERNAL_ENFORCED_PUBDATA_PRICE="1000"
             ETH_SENDER_WAIT_FOR_PROOFS="false"
             ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
             ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
@@ -107,6 +131,23 @@ mod tests {
             ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             ETH_SENDER_SENDER_PROOF_LOADING_MODE="OldProofFromDb"
             ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Calldata"
+            ETH_SENDER_SENDER_OPERATOR_BALANCE_ALERT_THRESHOLD_GWEI="1000000000"
+            ETH_SENDER_SENDER_OPERATOR_RUNWAY_ALERT_THRESHOLD_HOURS="24"
+            ETH_SENDER_SENDER_BASE_TOKEN_ADDR="0x7f5ea2bd3b4b7f1da1f3d0b4d0b5f53e3c8e8a61"
+            ETH_SENDER_SENDER_OPERATOR_BASE_TOKEN_BALANCE_ALERT_THRESHOLD="10000"
+            ETH_SENDER_SENDER_EXECUTION_TIME_WINDOWS="08:00-12:00,22:00-02:00"
+            ETH_SENDER_SENDER_EXECUTION_APPROVAL_CONTRACT_ADDR="0x6c10d9c1744f149d4b17660e14faa247964749c7"
+            ETH_SENDER_SENDER_DISABLED_PUBLISH_CRITERIA="timestamp"
+            ETH_SENDER_SENDER_DRY_RUN="true"
+            ETH_SENDER_SENDER_SHUTDOWN_DRAIN_TIMEOUT_SEC="120"
+            ETH_SENDER_SENDER_PRIORITY_OP_DEADLINE_SEC="86400"
+            ETH_SENDER_SENDER_FORCE_EXECUTE_ON_PRIORITY_OP_DEADLINE="true"
+            ETH_SENDER_SENDER_SIGNER_BACKEND="AwsKms"
+            ETH_SENDER_SENDER_SIGNER_REGION="eu-central-1"
+            ETH_SENDER_SENDER_OPERATOR_SIGNER_KEY_ID="alias/operator"
+            ETH_SENDER_SENDER_BLOBS_OPERATOR_SIGNER_KEY_ID="alias/blobs-operator"
+            ETH_SENDER_SENDER_MAX_BLOBS_PER_ETH_TX=9
+            ETH_SENDER_SENDER_AUTO_SELECT_PUBDATA_DA="true"
         "#;
         lock.set_env(config);
 
@@ -30,6 +30,14 @@ mod tests {
             fri_prover_stats_reporting_interval_ms: 30_000,
             fri_proof_compressor_job_retrying_interval_ms: 30_000,
             fri_proof_compressor_stats_reporting_interval_ms: 30_000,
+            witness_inputs_retention_days: Some(30),
+            proofs_retention_days: None,
+            artifacts_retention_interval_ms: 600_000,
+            artifacts_retention_dry_run: true,
+            prover_db_consistency_check_interval_ms: 300_000,
+            audit_log_export_interval_ms: Some(60_000),
+            cold_storage_threshold_days: Some(90),
+            cold_storage_interval_ms: 3_600_000,
         }
     }
 
@@ -50,6 +58,12 @@ mod tests {
             HOUSE_KEEPER_FRI_PROVER_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_JOB_RETRYING_INTERVAL_MS="30000"
+            HOUSE_KEEPER_WITNESS_INPUTS_RETENTION_DAYS="30"
+            HOUSE_KEEPER_ARTIFACTS_RETENTION_INTERVAL_MS="600000"
+            HOUSE_KEEPER_ARTIFACTS_RETENTION_DRY_RUN="true"
+            HOUSE_KEEPER_PROVER_DB_CONSISTENCY_CHECK_INTERVAL_MS="300000"
+            HOUSE_KEEPER_AUDIT_LOG_EXPORT_INTERVAL_MS="60000"
+            HOUSE_KEEPER_COLD_STORAGE_THRESHOLD_DAYS="90"
         "#;
         lock.set_env(config);
 
@@ -17,7 +17,7 @@ mod tests {
 
     fn expected_config() -> KzgConfig {
         KzgConfig {
-            trusted_setup_path: "dir/file.json".to_owned(),
+            trusted_setup_path: Some("dir/file.json".to_owned()),
         }
     }
 
@@ -6,6 +6,7 @@ mod api;
 mod chain;
 mod contract_verifier;
 mod contracts;
+mod da_dispatcher;
 mod database;
 mod eth_client;
 mod eth_sender;
@@ -21,6 +22,7 @@ mod kzg;
 pub mod object_store;
 mod observability;
 mod proof_data_handler;
+mod remote_config;
 mod snapshots_creator;
 mod utils;
 mod witness_generator;
@@ -40,6 +40,18 @@ impl FromEnv for SnapshotsObjectStoreConfig {
     }
 }
 
+/// Wrapper for `ObjectStoreConfig` that allows loading the config of the object store used as a DA layer
+/// by the DA dispatcher.
+#[derive(Debug)]
+pub struct DAObjectStoreConfig(pub ObjectStoreConfig);
+
+impl FromEnv for DAObjectStoreConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        let config = envy_load("da_dispatcher_object_store", "DA_DISPATCHER_OBJECT_STORE_")?;
+        Ok(Self(config))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
@@ -56,6 +68,8 @@ mod tests {
                 gcs_credential_file_path: "/path/to/credentials.json".to_owned(),
             },
             max_retries: 5,
+            compression_level: Some(3),
+            replica_bucket_base_url: Some("/replica/url".to_owned()),
         }
     }
 
@@ -67,6 +81,8 @@ mod tests {
             OBJECT_STORE_MODE="GCSWithCredentialFile"
             OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
             OBJECT_STORE_MAX_RETRIES="5"
+            OBJECT_STORE_COMPRESSION_LEVEL="3"
+            OBJECT_STORE_REPLICA_BUCKET_BASE_URL="/replica/url"
         "#;
         lock.set_env(config);
         let actual = ObjectStoreConfig::from_env().unwrap();
@@ -117,6 +133,7 @@ mod tests {
             PROVER_OBJECT_STORE_MODE="GCSWithCredentialFile"
             PROVER_OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
             PROVER_OBJECT_STORE_MAX_RETRIES="5"
+            PROVER_OBJECT_STORE_COMPRESSION_LEVEL="3"
         "#;
         lock.set_env(config);
         let actual = ProverObjectStoreConfig::from_env().unwrap().0;
@@ -140,4 +157,23 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn da_bucket_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DA_DISPATCHER_OBJECT_STORE_MODE="FileBacked"
+            DA_DISPATCHER_OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+            DA_DISPATCHER_OBJECT_STORE_MAX_RETRIES="3"
+        "#;
+        lock.set_env(config);
+        let actual = DAObjectStoreConfig::from_env().unwrap().0;
+        assert_eq!(actual.max_retries, 3);
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::FileBacked {
+                file_backed_base_path: "artifacts".to_owned(),
+            }
+        );
+    }
 }
@@ -0,0 +1,40 @@
+use zksync_config::configs::RemoteConfigServiceConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for RemoteConfigServiceConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("remote_config", "REMOTE_CONFIG_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{addr, EnvMutex};
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    fn expected_config() -> RemoteConfigServiceConfig {
+        RemoteConfigServiceConfig {
+            url: "http://127.0.0.1:8500/v1/kv/zksync/main_node?raw".to_owned(),
+            signer_address: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
+            poll_interval_secs: Some(30),
+            required: true,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            REMOTE_CONFIG_URL="http://127.0.0.1:8500/v1/kv/zksync/main_node?raw"
+            REMOTE_CONFIG_SIGNER_ADDRESS="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+            REMOTE_CONFIG_POLL_INTERVAL_SECS="30"
+            REMOTE_CONFIG_REQUIRED="true"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = RemoteConfigServiceConfig::from_env().unwrap();
+        assert_eq!(actual, expected_config());
+    }
+}
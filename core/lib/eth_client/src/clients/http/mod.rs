@@ -6,7 +6,7 @@ use vise::{
 
 pub use self::{
     query::QueryClient,
-    signing::{PKSigningClient, SigningClient},
+    signing::{OperatorSigningClient, PKSigningClient, SigningClient},
 };
 
 mod query;
@@ -17,7 +17,7 @@ use zksync_types::web3::{
 use crate::{
     clients::http::{Method, COUNTERS, LATENCIES},
     types::{Error, ExecutedTxStatus, FailureInfo, RawTokens},
-    Block, ContractCall, EthInterface, RawTransactionBytes,
+    Block, ContractCall, EthInterface, RawTransactionBytes, RevertData,
 };
 
 /// An "anonymous" Ethereum client that can invoke read-only methods that aren't
@@ -43,6 +43,23 @@ impl QueryClient {
     }
 }
 
+/// Fault point for chaos testing named `eth_client.{method}`. Injected errors are reported as transport errors,
+/// i.e., the same way as network errors. No-op unless the `chaos` feature is enabled.
+#[cfg(feature = "chaos")]
+async fn fault_point(method: &str) -> Result<(), Error> {
+    zksync_utils::chaos::fault_point(&format!("eth_client.{method}"))
+        .await
+        .map_err(|err| {
+            let err = web3::error::TransportError::Message(err.to_string());
+            Error::EthereumGateway(web3::Error::Transport(err))
+        })
+}
+
+#[cfg(not(feature = "chaos"))]
+async fn fault_point(_method: &str) -> Result<(), Error> {
+    Ok(())
+}
+
 #[async_trait]
 impl EthInterface for QueryClient {
     async fn nonce_at_for_account(
@@ -52,6 +69,7 @@ impl EthInterface for QueryClient {
         component: &'static str,
     ) -> Result<U256, Error> {
         COUNTERS.call[&(Method::NonceAtForAccount, component)].inc();
+        fault_point("nonce_at_for_account").await?;
         let latency = LATENCIES.direct[&Method::NonceAtForAccount].start();
         let nonce = self
             .web3
@@ -64,6 +82,7 @@ impl EthInterface for QueryClient {
 
     async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
         COUNTERS.call[&(Method::BlockNumber, component)].inc();
+        fault_point("block_number").await?;
         let latency = LATENCIES.direct[&Method::BlockNumber].start();
         let block_number = self.web3.eth().block_number().await?;
         latency.observe();
@@ -72,6 +91,7 @@ impl EthInterface for QueryClient {
 
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
         COUNTERS.call[&(Method::GetGasPrice, component)].inc();
+        fault_point("get_gas_price").await?;
         let latency = LATENCIES.direct[&Method::GetGasPrice].start();
         let network_gas_price = self.web3.eth().gas_price().await?;
         latency.observe();
@@ -79,6 +99,7 @@ impl EthInterface for QueryClient {
     }
 
     async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+        fault_point("send_raw_tx").await?;
         let latency = LATENCIES.direct[&Method::SendRawTx].start();
         let tx = self.web3.eth().send_raw_transaction(Bytes(tx.0)).await?;
         latency.observe();
@@ -94,6 +115,7 @@ impl EthInterface for QueryClient {
         const MAX_REQUEST_CHUNK: usize = 1024;
 
         COUNTERS.call[&(Method::BaseFeeHistory, component)].inc();
+        fault_point("base_fee_history").await?;
         let latency = LATENCIES.direct[&Method::BaseFeeHistory].start();
         let mut history = Vec::with_capacity(block_count);
         let from_block = upto_block.saturating_sub(block_count);
@@ -123,6 +145,7 @@ impl EthInterface for QueryClient {
         component: &'static str,
     ) -> Result<U256, Error> {
         COUNTERS.call[&(Method::PendingBlockBaseFee, component)].inc();
+        fault_point("get_pending_block_base_fee_per_gas").await?;
         let latency = LATENCIES.direct[&Method::PendingBlockBaseFee].start();
 
         let block = self
@@ -164,6 +187,7 @@ impl EthInterface for QueryClient {
     }
 
     async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
+        fault_point("failure_reason").await?;
         let latency = LATENCIES.direct[&Method::FailureReason].start();
         let transaction = self.web3.eth().transaction(tx_hash.into()).await?;
         let receipt = self.web3.eth().transaction_receipt(tx_hash).await?;
@@ -196,8 +220,9 @@ impl EthInterface for QueryClient {
                 let failure_info = match call_error {
                     Some(web3::Error::Rpc(rpc_error)) => {
                         let revert_code = rpc_error.code.code();
-                        let message_len = "execution reverted: ".len().min(rpc_error.message.len());
-                        let revert_reason = rpc_error.message[message_len..].to_string();
+                        let revert_reason = RevertData::from_rpc(&rpc_error)
+                            .reason
+                            .unwrap_or(rpc_error.message);
 
                         Ok(Some(FailureInfo {
                             revert_code,
@@ -223,6 +248,7 @@ impl EthInterface for QueryClient {
         component: &'static str,
     ) -> Result<Option<Transaction>, Error> {
         COUNTERS.call[&(Method::GetTx, component)].inc();
+        fault_point("get_tx").await?;
         let tx = self
             .web3
             .eth()
@@ -235,6 +261,7 @@ impl EthInterface for QueryClient {
         &self,
         call: ContractCall,
     ) -> Result<Vec<ethabi::Token>, Error> {
+        fault_point("call_contract_function").await?;
         let latency = LATENCIES.direct[&Method::CallContractFunction].start();
         let contract = Contract::new(self.web3.eth(), call.contract_address, call.contract_abi);
         let RawTokens(res) = contract
@@ -256,6 +283,7 @@ impl EthInterface for QueryClient {
         component: &'static str,
     ) -> Result<Option<TransactionReceipt>, Error> {
         COUNTERS.call[&(Method::TxReceipt, component)].inc();
+        fault_point("tx_receipt").await?;
         let latency = LATENCIES.direct[&Method::TxReceipt].start();
         let receipt = self.web3.eth().transaction_receipt(tx_hash).await?;
         latency.observe();
@@ -264,6 +292,7 @@ impl EthInterface for QueryClient {
 
     async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error> {
         COUNTERS.call[&(Method::EthBalance, component)].inc();
+        fault_point("eth_balance").await?;
         let latency = LATENCIES.direct[&Method::EthBalance].start();
         let balance = self.web3.eth().balance(address, None).await?;
         latency.observe();
@@ -272,6 +301,7 @@ impl EthInterface for QueryClient {
 
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
         COUNTERS.call[&(Method::Logs, component)].inc();
+        fault_point("logs").await?;
         let latency = LATENCIES.direct[&Method::Logs].start();
         let logs = self.web3.eth().logs(filter).await?;
         latency.observe();
@@ -284,6 +314,7 @@ impl EthInterface for QueryClient {
         component: &'static str,
     ) -> Result<Option<Block<H256>>, Error> {
         COUNTERS.call[&(Method::Block, component)].inc();
+        fault_point("block").await?;
         let latency = LATENCIES.direct[&Method::Block].start();
         // Copy of `web3::block` implementation. It's required to deserialize response as `crate::types::Block`
         // that has EIP-4844 fields.
@@ -1,9 +1,17 @@
 use std::{fmt, sync::Arc};
 
 use async_trait::async_trait;
-use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_config::{
+    configs::eth_sender::{SenderConfig, SignerBackend},
+    ContractsConfig, ETHClientConfig, ETHSenderConfig,
+};
 use zksync_contracts::zksync_contract;
-use zksync_eth_signer::{raw_ethereum_tx::TransactionParameters, EthereumSigner, PrivateKeySigner};
+use zksync_eth_signer::{
+    error::SignerError,
+    raw_ethereum_tx::TransactionParameters,
+    remote::{AwsKmsBackend, GcpKmsBackend, Web3SignerBackend},
+    EthereumSigner, OperatorSigner, PrivateKeySigner,
+};
 use zksync_types::{
     web3::{
         self,
@@ -96,6 +104,110 @@ impl PKSigningClient {
     }
 }
 
+/// HTTP-based Ethereum client signing transactions with the backend specified in the sender config,
+/// e.g. a remote signer or a KMS.
+pub type OperatorSigningClient = SigningClient<OperatorSigner>;
+
+/// Operator account signing L1 transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OperatorAccount {
+    Main,
+    Blobs,
+}
+
+impl OperatorSigningClient {
+    /// Creates a client for the main operator account.
+    pub async fn from_config(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> Result<Self, SignerError> {
+        let signer = Self::create_signer(&eth_sender.sender, OperatorAccount::Main)
+            .await?
+            .ok_or(SignerError::MissingEthSigner)?;
+        Ok(Self::with_signer(
+            eth_sender,
+            contracts_config,
+            eth_client,
+            signer,
+        ))
+    }
+
+    /// Creates a client for the blobs operator account, if one is configured.
+    pub async fn from_config_blobs(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> Result<Option<Self>, SignerError> {
+        let signer = Self::create_signer(&eth_sender.sender, OperatorAccount::Blobs).await?;
+        Ok(
+            signer
+                .map(|signer| Self::with_signer(eth_sender, contracts_config, eth_client, signer)),
+        )
+    }
+
+    async fn create_signer(
+        config: &SenderConfig,
+        account: OperatorAccount,
+    ) -> Result<Option<OperatorSigner>, SignerError> {
+        let key_id = match account {
+            OperatorAccount::Main => config.operator_signer_key_id.as_deref(),
+            OperatorAccount::Blobs => config.blobs_operator_signer_key_id.as_deref(),
+        };
+        let url = config.signer_url.as_deref();
+        let signer = match (config.signer_backend, key_id) {
+            (SignerBackend::PrivateKey, _) => {
+                let private_key = match account {
+                    OperatorAccount::Main => config.private_key(),
+                    OperatorAccount::Blobs => config.private_key_blobs(),
+                };
+                let Some(private_key) = private_key else {
+                    return Ok(None);
+                };
+                OperatorSigner::new(PrivateKeySigner::new(private_key)).await?
+            }
+            (_, None) => return Ok(None),
+            (SignerBackend::Web3Signer, Some(key_id)) => {
+                let url = url.ok_or_else(|| {
+                    SignerError::CustomError("web3signer URL is not specified".to_owned())
+                })?;
+                OperatorSigner::new(Web3SignerBackend::new(url, key_id)?).await?
+            }
+            (SignerBackend::AwsKms, Some(key_id)) => {
+                let region = config.signer_region.as_deref().ok_or_else(|| {
+                    SignerError::CustomError("AWS KMS region is not specified".to_owned())
+                })?;
+                OperatorSigner::new(AwsKmsBackend::new(region, key_id, url).await?).await?
+            }
+            (SignerBackend::GcpKms, Some(key_id)) => {
+                OperatorSigner::new(GcpKmsBackend::new(key_id, url).await?).await?
+            }
+        };
+        Ok(Some(signer))
+    }
+
+    fn with_signer(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+        signer: OperatorSigner,
+    ) -> Self {
+        let transport = Http::new(&eth_client.web3_url).expect("Failed to create transport");
+        let operator_address = signer.address();
+        tracing::info!("Operator address: {operator_address:?}");
+
+        SigningClient::new(
+            transport,
+            zksync_contract(),
+            operator_address,
+            signer,
+            contracts_config.diamond_proxy_addr,
+            eth_sender.gas_adjuster.default_priority_fee_per_gas.into(),
+            L1ChainId(eth_client.chain_id),
+        )
+    }
+}
+
 /// Gas limit value to be used in transaction if for some reason
 /// gas limit was not set for it.
 ///
@@ -371,4 +483,9 @@ impl<S: EthereumSigner> SigningClient<S> {
             query_client: transport.into(),
         }
     }
+
+    /// Returns the signer used by this client.
+    pub fn signer(&self) -> &S {
+        &self.inner.eth_signer
+    }
 }
@@ -123,6 +123,8 @@ pub struct MockEthereum {
     /// This is useful for testing the cases when the transactions are executed out of order.
     non_ordering_confirmations: bool,
     multicall_address: Address,
+    /// Number of blocks by which the block with the `finalized` tag lags behind the latest block.
+    finalization_lag: u64,
     inner: RwLock<MockEthereumInner>,
 }
 
@@ -135,6 +137,7 @@ impl Default for MockEthereum {
             excess_blob_gas_history: vec![],
             non_ordering_confirmations: false,
             multicall_address: Address::default(),
+            finalization_lag: 0,
             inner: RwLock::default(),
         }
     }
@@ -204,6 +207,21 @@ impl MockEthereum {
         inner.block_number
     }
 
+    /// Simulates an L1 reorg replacing the last `depth` blocks with empty ones: transactions mined in these blocks
+    /// become unmined, and the sender nonce is rolled back accordingly. The block number doesn't change.
+    pub fn reorg(&self, depth: u64) {
+        let mut inner = self.inner.write().unwrap();
+        let fork_block_number = inner.block_number.saturating_sub(depth);
+        inner.tx_statuses.retain(|_, status| {
+            let block_number = status.receipt.block_number.unwrap_or_default();
+            block_number.as_u64() <= fork_block_number
+        });
+        inner
+            .nonces
+            .retain(|&block_number, _| block_number <= fork_block_number);
+        inner.current_nonce = inner.nonces.values().next_back().copied().unwrap_or(0);
+    }
+
     pub fn with_fee_history(self, history: Vec<u64>) -> Self {
         Self {
             base_fee_history: history,
@@ -231,6 +249,13 @@ impl MockEthereum {
             ..self
         }
     }
+
+    pub fn with_finalization_lag(self, finalization_lag: u64) -> Self {
+        Self {
+            finalization_lag,
+            ..self
+        }
+    }
 }
 
 #[async_trait]
@@ -254,8 +279,15 @@ impl EthInterface for MockEthereum {
 
         if mock_tx.nonce < inner.current_nonce {
             return Err(Error::EthereumGateway(Web3Error::Rpc(RpcError {
-                message: "transaction with the same nonce already processed".to_string(),
-                code: 101.into(),
+                message: "nonce too low".to_string(),
+                code: (-32000).into(),
+                data: None,
+            })));
+        }
+        if inner.sent_txs.contains_key(&mock_tx_hash) {
+            return Err(Error::EthereumGateway(Web3Error::Rpc(RpcError {
+                message: "already known".to_string(),
+                code: (-32000).into(),
                 data: None,
             })));
         }
@@ -389,6 +421,14 @@ impl EthInterface for MockEthereum {
                     ..Default::default()
                 }))
             }
+            BlockId::Number(BlockNumber::Finalized) => {
+                let block_number = self.inner.read().unwrap().block_number;
+                let number = block_number.saturating_sub(self.finalization_lag);
+                Ok(Some(Block {
+                    number: Some(number.into()),
+                    ..Default::default()
+                }))
+            }
             _ => unimplemented!("Not needed right now"),
         }
     }
@@ -508,4 +548,55 @@ mod tests {
         assert_eq!(tx_status.tx_hash, tx_hash);
         assert_eq!(tx_status.receipt.block_number, Some(2.into()));
     }
+
+    #[tokio::test]
+    async fn reorging_transactions() {
+        let client = MockEthereum::default();
+        let mut tx_hashes = vec![];
+        for nonce in 0..2 {
+            let signed_tx = client
+                .sign_prepared_tx(
+                    vec![nonce],
+                    Options {
+                        nonce: Some(nonce.into()),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            let tx_hash = client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+            client.execute_tx(tx_hash, true, 2);
+            tx_hashes.push(tx_hash);
+        }
+        // Transactions are mined in blocks #0 and #2; the current block is #4.
+        assert_eq!(client.current_nonce("test").await.unwrap(), 2.into());
+
+        client.reorg(3);
+        assert_eq!(client.block_number("test").await.unwrap(), 4.into());
+        assert_eq!(client.current_nonce("test").await.unwrap(), 1.into());
+        let nonce = client
+            .nonce_at(BlockNumber::Number(4.into()), "test")
+            .await
+            .unwrap();
+        assert_eq!(nonce, 1.into());
+        assert!(client
+            .get_tx_status(tx_hashes[0], "test")
+            .await
+            .unwrap()
+            .is_some());
+        assert!(client
+            .get_tx_status(tx_hashes[1], "test")
+            .await
+            .unwrap()
+            .is_none());
+
+        // The reorged transaction can be re-executed.
+        client.execute_tx(tx_hashes[1], true, 1);
+        let tx_status = client
+            .get_tx_status(tx_hashes[1], "test")
+            .await
+            .unwrap()
+            .expect("no transaction status");
+        assert_eq!(tx_status.receipt.block_number, Some(4.into()));
+        assert_eq!(client.current_nonce("test").await.unwrap(), 2.into());
+    }
 }
@@ -0,0 +1,292 @@
+//! Classification of L1 client errors used to drive retry policies.
+
+use zksync_types::web3::{
+    self,
+    contract::Error as ContractError,
+    error::TransportError,
+    ethabi::{self, ParamType, Token},
+};
+
+use crate::types::Error;
+
+/// Selector of the `Error(string)` error emitted by `require` / `revert` with a message.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the `Panic(uint256)` error emitted by failed assertions, arithmetic overflows etc.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+/// JSON-RPC error code used by Geth-compatible nodes for reverted calls.
+const REVERT_ERROR_CODE: i64 = 3;
+/// JSON-RPC error code for exceeded request limits (<https://eips.ethereum.org/EIPS/eip-1474#error-codes>).
+const LIMIT_EXCEEDED_ERROR_CODE: i64 = -32005;
+/// JSON-RPC error code for internal errors.
+const INTERNAL_ERROR_CODE: i64 = -32603;
+
+/// Kind of [`Error`] that determines how the failed request should be handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Network or server-side error, e.g. a connection error, a timeout or an internal server error.
+    /// The request can be retried.
+    Transient,
+    /// The request was rate-limited by the L1 provider. The request can be retried after a backoff.
+    RateLimited,
+    /// The response is too large, e.g. `eth_getLogs` matches too many logs. The request should be split.
+    ResponseTooLarge,
+    /// The transaction nonce is too low, i.e. a transaction with the same nonce is already mined.
+    NonceTooLow,
+    /// A transaction with the same nonce is in the mempool, and the new transaction doesn't pay enough to replace it.
+    ReplacementUnderpriced,
+    /// The transaction is already in the mempool of the L1 node.
+    AlreadyKnown,
+    /// A call or gas estimation reverted. Revert data can be obtained with [`Error::revert_data()`].
+    Reverted,
+    /// Any other error, e.g. a malformed request or response, or a signing error. Retrying the request
+    /// won't help.
+    Fatal,
+}
+
+impl ErrorKind {
+    /// Returns whether a request failed with this kind of error can be retried as-is.
+    pub fn is_retriable(self) -> bool {
+        matches!(self, Self::Transient | Self::RateLimited)
+    }
+
+    fn from_web3(err: &web3::Error) -> Self {
+        match err {
+            web3::Error::Transport(TransportError::Code(429)) => Self::RateLimited,
+            web3::Error::Transport(TransportError::Code(code)) if *code >= 500 => Self::Transient,
+            web3::Error::Transport(TransportError::Code(_)) => Self::Fatal,
+            web3::Error::Transport(TransportError::Message(_))
+            | web3::Error::Io(_)
+            | web3::Error::Unreachable
+            | web3::Error::InvalidResponse(_) => Self::Transient,
+            web3::Error::Rpc(err) => Self::from_rpc(err.code.code(), &err.message),
+            _ => Self::Fatal,
+        }
+    }
+
+    /// Classifies a JSON-RPC error. Since error codes are not standardized among L1 node implementations
+    /// and providers, error messages need to be inspected as well. Reverts are checked first since a revert reason
+    /// is arbitrary and may contain any of the other matched phrases.
+    fn from_rpc(code: i64, message: &str) -> Self {
+        let message = message.to_lowercase();
+        if code == REVERT_ERROR_CODE || message.starts_with("execution reverted") {
+            Self::Reverted
+        } else if message.contains("query returned more than")
+            || message.contains("response size exceeded")
+        {
+            // Infura and Alchemy messages, respectively.
+            Self::ResponseTooLarge
+        } else if code == 429
+            || code == LIMIT_EXCEEDED_ERROR_CODE
+            || message.contains("rate limit")
+            || message.contains("too many requests")
+        {
+            Self::RateLimited
+        } else if message.contains("nonce too low") {
+            Self::NonceTooLow
+        } else if message.contains("replacement transaction underpriced")
+            || message.contains("replacement fee too low")
+        {
+            Self::ReplacementUnderpriced
+        } else if message.contains("already known") || message.contains("known transaction") {
+            Self::AlreadyKnown
+        } else if code == INTERNAL_ERROR_CODE
+            || message.contains("timed out")
+            || message.contains("timeout")
+            || message.contains("failed")
+        {
+            Self::Transient
+        } else {
+            Self::Fatal
+        }
+    }
+}
+
+/// Data returned for a reverted call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevertData {
+    /// Raw revert data; empty if the node didn't return it.
+    pub data: Vec<u8>,
+    /// Human-readable revert reason, if it can be decoded from the revert data or the error message.
+    pub reason: Option<String>,
+}
+
+impl RevertData {
+    pub(crate) fn from_rpc(err: &web3::error::RPCError) -> Self {
+        let data = err
+            .data
+            .as_ref()
+            .and_then(|data| data.as_str())
+            .and_then(|data| hex::decode(data.strip_prefix("0x").unwrap_or(data)).ok())
+            .unwrap_or_default();
+        let message_reason = err
+            .message
+            .strip_prefix("execution reverted")
+            .map(|reason| reason.trim_start_matches(':').trim())
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_owned);
+        Self {
+            reason: Self::decode_reason(&data).or(message_reason),
+            data,
+        }
+    }
+
+    /// Decodes a standard Solidity revert reason (`Error(string)` or `Panic(uint256)`).
+    fn decode_reason(data: &[u8]) -> Option<String> {
+        if data.len() < 4 {
+            return None;
+        }
+        let (selector, payload) = data.split_at(4);
+        if selector == ERROR_SELECTOR {
+            let tokens = ethabi::decode(&[ParamType::String], payload).ok()?;
+            match tokens.into_iter().next()? {
+                Token::String(reason) => Some(reason),
+                _ => None,
+            }
+        } else if selector == PANIC_SELECTOR {
+            let tokens = ethabi::decode(&[ParamType::Uint(256)], payload).ok()?;
+            match tokens.into_iter().next()? {
+                Token::Uint(code) => Some(format!("panic code {code:#x}")),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+}
+
+impl Error {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::EthereumGateway(err) | Self::Contract(ContractError::Api(err)) => {
+                ErrorKind::from_web3(err)
+            }
+            Self::Contract(_)
+            | Self::Signer(_)
+            | Self::Decode(_)
+            | Self::WrongFeeProvided(..)
+            | Self::Eip4844MissingMaxFeePerBlobGas
+            | Self::Eip4844MissingBlobVersionedHashes => ErrorKind::Fatal,
+        }
+    }
+
+    /// Returns revert data if this error corresponds to a reverted call or gas estimation.
+    pub fn revert_data(&self) -> Option<RevertData> {
+        let (Self::EthereumGateway(web3::Error::Rpc(err))
+        | Self::Contract(ContractError::Api(web3::Error::Rpc(err)))) = self
+        else {
+            return None;
+        };
+        (ErrorKind::from_rpc(err.code.code(), &err.message) == ErrorKind::Reverted)
+            .then(|| RevertData::from_rpc(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpc_core::ErrorCode;
+
+    use super::*;
+
+    fn rpc_error(code: i64, message: &str) -> web3::error::RPCError {
+        web3::error::RPCError {
+            code: ErrorCode::ServerError(code),
+            message: message.to_owned(),
+            data: None,
+        }
+    }
+
+    #[test]
+    fn classifying_errors() {
+        let transport_errors = [
+            (TransportError::Code(429), ErrorKind::RateLimited),
+            (TransportError::Code(503), ErrorKind::Transient),
+            (TransportError::Code(401), ErrorKind::Fatal),
+            (
+                TransportError::Message("connection refused".into()),
+                ErrorKind::Transient,
+            ),
+        ];
+        for (err, expected_kind) in transport_errors {
+            let err = Error::EthereumGateway(web3::Error::Transport(err));
+            assert_eq!(err.kind(), expected_kind, "{err}");
+        }
+
+        let rpc_errors = [
+            (
+                -32005,
+                "query returned more than 10000 results",
+                ErrorKind::ResponseTooLarge,
+            ),
+            (
+                -32005,
+                "daily request count exceeded",
+                ErrorKind::RateLimited,
+            ),
+            (429, "Too Many Requests", ErrorKind::RateLimited),
+            (-32000, "nonce too low", ErrorKind::NonceTooLow),
+            (
+                -32000,
+                "replacement transaction underpriced",
+                ErrorKind::ReplacementUnderpriced,
+            ),
+            (-32000, "already known", ErrorKind::AlreadyKnown),
+            (3, "execution reverted: oops", ErrorKind::Reverted),
+            (3, "execution reverted: rate limit", ErrorKind::Reverted),
+            (
+                -32000,
+                "execution reverted: token already known",
+                ErrorKind::Reverted,
+            ),
+            (-32603, "internal error", ErrorKind::Transient),
+            (-32000, "request timed out", ErrorKind::Transient),
+            (-32602, "invalid argument 0", ErrorKind::Fatal),
+        ];
+        for (code, message, expected_kind) in rpc_errors {
+            let err = Error::EthereumGateway(web3::Error::Rpc(rpc_error(code, message)));
+            assert_eq!(err.kind(), expected_kind, "{err}");
+            let err = Error::Contract(ContractError::Api(web3::Error::Rpc(rpc_error(
+                code, message,
+            ))));
+            assert_eq!(err.kind(), expected_kind, "{err}");
+        }
+
+        let err = Error::WrongFeeProvided(1.into(), 2.into());
+        assert_eq!(err.kind(), ErrorKind::Fatal);
+        assert!(!err.kind().is_retriable());
+    }
+
+    #[test]
+    fn decoding_revert_data() {
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend(ethabi::encode(&[Token::String("not enough funds".into())]));
+        let mut err = rpc_error(3, "execution reverted");
+        err.data = Some(format!("0x{}", hex::encode(&data)).into());
+        let revert_data = Error::EthereumGateway(web3::Error::Rpc(err))
+            .revert_data()
+            .unwrap();
+        assert_eq!(revert_data.data, data);
+        assert_eq!(revert_data.reason.as_deref(), Some("not enough funds"));
+
+        let mut panic_data = PANIC_SELECTOR.to_vec();
+        panic_data.extend(ethabi::encode(&[Token::Uint(0x11.into())]));
+        assert_eq!(
+            RevertData::decode_reason(&panic_data).as_deref(),
+            Some("panic code 0x11")
+        );
+
+        // Reason is taken from the message if there's no revert data.
+        let err = rpc_error(3, "execution reverted: oops");
+        let revert_data = Error::EthereumGateway(web3::Error::Rpc(err))
+            .revert_data()
+            .unwrap();
+        assert!(revert_data.data.is_empty());
+        assert_eq!(revert_data.reason.as_deref(), Some("oops"));
+
+        let err = rpc_error(-32000, "nonce too low");
+        assert_eq!(
+            Error::EthereumGateway(web3::Error::Rpc(err)).revert_data(),
+            None
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Resolution of the latest reorg-safe L1 block.
+
+use zksync_config::configs::eth_client::L1FinalitySource;
+use zksync_types::web3::{
+    self,
+    types::{BlockId, BlockNumber, U64},
+};
+
+use crate::{Error, EthInterface};
+
+/// Returns the number of the latest L1 block considered reorg-safe according to the `source`.
+pub async fn finalized_block_number(
+    client: &dyn EthInterface,
+    source: L1FinalitySource,
+    component: &'static str,
+) -> Result<U64, Error> {
+    Ok(match source {
+        L1FinalitySource::Confirmations(confirmations) => {
+            latest_block_number(client, confirmations, component).await?
+        }
+        L1FinalitySource::Finalized => tagged_finalized_block_number(client, component).await?,
+        L1FinalitySource::Hybrid { confirmations } => {
+            let by_confirmations = latest_block_number(client, confirmations, component).await?;
+            let finalized = tagged_finalized_block_number(client, component).await?;
+            by_confirmations.min(finalized)
+        }
+    })
+}
+
+async fn latest_block_number(
+    client: &dyn EthInterface,
+    confirmations: u64,
+    component: &'static str,
+) -> Result<U64, Error> {
+    let latest_block_number = client.block_number(component).await?.as_u64();
+    Ok(latest_block_number.saturating_sub(confirmations).into())
+}
+
+async fn tagged_finalized_block_number(
+    client: &dyn EthInterface,
+    component: &'static str,
+) -> Result<U64, Error> {
+    let block = client
+        .block(BlockId::Number(BlockNumber::Finalized), component)
+        .await?;
+    block.and_then(|block| block.number).ok_or_else(|| {
+        let message = "L1 node returned no number for the finalized block".to_owned();
+        web3::Error::InvalidResponse(message).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::MockEthereum;
+
+    #[tokio::test]
+    async fn resolving_finalized_block_number() {
+        let client = MockEthereum::default().with_finalization_lag(10);
+        client.advance_block_number(100);
+
+        let source = L1FinalitySource::Confirmations(5);
+        let number = finalized_block_number(&client, source, "test")
+            .await
+            .unwrap();
+        assert_eq!(number, 95.into());
+        let source = L1FinalitySource::Confirmations(500);
+        let number = finalized_block_number(&client, source, "test")
+            .await
+            .unwrap();
+        assert_eq!(number, 0.into());
+
+        let source = L1FinalitySource::Finalized;
+        let number = finalized_block_number(&client, source, "test")
+            .await
+            .unwrap();
+        assert_eq!(number, 90.into());
+
+        let source = L1FinalitySource::Hybrid { confirmations: 5 };
+        let number = finalized_block_number(&client, source, "test")
+            .await
+            .unwrap();
+        assert_eq!(number, 90.into());
+        let source = L1FinalitySource::Hybrid { confirmations: 20 };
+        let number = finalized_block_number(&client, source, "test")
+            .await
+            .unwrap();
+        assert_eq!(number, 80.into());
+    }
+}
@@ -13,12 +13,18 @@ use zksync_types::{
     L1ChainId,
 };
 
-pub use crate::types::{
-    encode_blob_tx_with_sidecar, Block, CallFunctionArgs, ContractCall, Error, ExecutedTxStatus,
-    FailureInfo, RawTransactionBytes, SignedCallResult,
+pub use crate::{
+    errors::{ErrorKind, RevertData},
+    finality::finalized_block_number,
+    types::{
+        encode_blob_tx_with_sidecar, Block, CallFunctionArgs, ContractCall, Error,
+        ExecutedTxStatus, FailureInfo, RawTransactionBytes, SignedCallResult,
+    },
 };
 
 pub mod clients;
+mod errors;
+mod finality;
 mod types;
 
 /// Contract Call/Query Options
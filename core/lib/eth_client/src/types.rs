@@ -316,6 +316,9 @@ pub struct Block<TX> {
     /// Excess blob gas
     #[serde(rename = "excessBlobGas")]
     pub excess_blob_gas: Option<U64>,
+    /// Blob gas used
+    #[serde(rename = "blobGasUsed")]
+    pub blob_gas_used: Option<U64>,
 }
 
 fn null_to_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -596,6 +599,7 @@ mod tests {
         }"#;
         let block: Block<H256> = serde_json::from_str(post_dencun).unwrap();
         assert_eq!(block.excess_blob_gas, Some(U64::from(0x4b40000)));
+        assert_eq!(block.blob_gas_used, Some(U64::from(0xc0000)));
 
         let pre_dencun = r#"
         {
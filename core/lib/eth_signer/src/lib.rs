@@ -1,17 +1,21 @@
 use async_trait::async_trait;
 use error::SignerError;
 pub use json_rpc_signer::JsonRpcSigner;
+pub use operator_signer::{OperatorSigner, SigningBackend};
 pub use pk_signer::PrivateKeySigner;
 use zksync_types::{
     tx::primitives::PackedEthSignature, Address, EIP712TypedStructure, Eip712Domain,
 };
 
-pub use crate::raw_ethereum_tx::TransactionParameters;
+pub use crate::{metrics::SigningBackendKind, raw_ethereum_tx::TransactionParameters};
 
 pub mod error;
 pub mod json_rpc_signer;
+mod metrics;
+pub mod operator_signer;
 pub mod pk_signer;
 pub mod raw_ethereum_tx;
+pub mod remote;
 
 #[async_trait]
 pub trait EthereumSigner: 'static + Send + Sync + Clone {
@@ -0,0 +1,37 @@
+//! Metrics for signing backends.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelValue, Histogram, LabeledFamily, Metrics};
+
+/// Kind of a [`SigningBackend`](crate::SigningBackend).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub enum SigningBackendKind {
+    PrivateKey,
+    Web3Signer,
+    AwsKms,
+    GcpKms,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(crate) enum SigningOperation {
+    SignTransaction,
+    SignTypedData,
+    HealthCheck,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "eth_signer")]
+pub(crate) struct SignerMetrics {
+    /// Latency of signing backend operations.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["backend", "operation"])]
+    pub latency: LabeledFamily<(SigningBackendKind, SigningOperation), Histogram<Duration>, 2>,
+    /// Number of failed signing backend operations.
+    #[metrics(labels = ["backend", "operation"])]
+    pub errors: LabeledFamily<(SigningBackendKind, SigningOperation), Counter, 2>,
+}
+
+#[vise::register]
+pub(crate) static SIGNER_METRICS: vise::Global<SignerMetrics> = vise::Global::new();
@@ -0,0 +1,272 @@
+//! Ethereum signer backed by a pluggable signing backend, such as a local private key or a remote signer.
+
+use std::{fmt, sync::Arc, time::Instant};
+
+use async_trait::async_trait;
+use zksync_types::{
+    tx::primitives::PackedEthSignature, web3::signing::keccak256, Address, EIP712TypedStructure,
+    Eip712Domain, H256,
+};
+
+use crate::{
+    error::SignerError,
+    metrics::{SigningBackendKind, SigningOperation, SIGNER_METRICS},
+    raw_ethereum_tx::{Transaction, TransactionParameters},
+    EthereumSigner, PrivateKeySigner,
+};
+
+/// Backend producing ECDSA secp256k1 signatures for a single account, e.g. a remote signing service or an HSM.
+#[async_trait]
+pub trait SigningBackend: fmt::Debug + Send + Sync + 'static {
+    /// Returns the kind of this backend used in logs and metrics.
+    fn kind(&self) -> SigningBackendKind;
+
+    /// Returns the address of the account.
+    async fn address(&self) -> Result<Address, SignerError>;
+
+    /// Signs the Keccak-256 hash of the provided message. The returned signature must be normalized
+    /// (i.e., have the lower `s` value), but is not required to have a correct recovery ID.
+    async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError>;
+
+    /// Checks that the backend is reachable and can sign with the account key. The default implementation
+    /// checks the account address.
+    async fn check_health(&self) -> Result<(), SignerError> {
+        self.address().await.map(drop)
+    }
+}
+
+#[async_trait]
+impl SigningBackend for PrivateKeySigner {
+    fn kind(&self) -> SigningBackendKind {
+        SigningBackendKind::PrivateKey
+    }
+
+    async fn address(&self) -> Result<Address, SignerError> {
+        self.get_address().await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+        let hash = H256(keccak256(message));
+        PackedEthSignature::sign_raw(self.private_key(), &hash)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))
+    }
+}
+
+/// [`EthereumSigner`] delegating to a [`SigningBackend`]. Reports latency of backend operations and verifies
+/// that all produced signatures recover to the expected account address.
+#[derive(Debug, Clone)]
+pub struct OperatorSigner {
+    backend: Arc<dyn SigningBackend>,
+    address: Address,
+}
+
+impl OperatorSigner {
+    /// Creates a signer for the account managed by the provided backend.
+    pub async fn new(backend: impl SigningBackend) -> Result<Self, SignerError> {
+        let backend: Arc<dyn SigningBackend> = Arc::new(backend);
+        let address = backend.address().await?;
+        tracing::info!(
+            "Using {:?} signing backend for account {address:?}",
+            backend.kind()
+        );
+        Ok(Self { backend, address })
+    }
+
+    /// Returns the address of the account.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the kind of the underlying backend.
+    pub fn backend_kind(&self) -> SigningBackendKind {
+        self.backend.kind()
+    }
+
+    /// Checks that the underlying backend is reachable and can sign with the account key.
+    pub async fn check_health(&self) -> Result<(), SignerError> {
+        self.observe(SigningOperation::HealthCheck, self.backend.check_health())
+            .await
+    }
+
+    async fn observe<T>(
+        &self,
+        operation: SigningOperation,
+        action: impl std::future::Future<Output = Result<T, SignerError>>,
+    ) -> Result<T, SignerError> {
+        let labels = (self.backend.kind(), operation);
+        let started_at = Instant::now();
+        let result = action.await;
+        SIGNER_METRICS.latency[&labels].observe(started_at.elapsed());
+        if let Err(err) = &result {
+            tracing::warn!(
+                "{operation:?} failed for {:?} signing backend: {err}",
+                self.backend.kind()
+            );
+            SIGNER_METRICS.errors[&labels].inc();
+        }
+        result
+    }
+
+    /// Signs the Keccak-256 hash of the message and fixes the recovery ID of the signature.
+    async fn sign(
+        &self,
+        operation: SigningOperation,
+        message: &[u8],
+    ) -> Result<PackedEthSignature, SignerError> {
+        let signature = self
+            .observe(operation, self.backend.sign_message(message))
+            .await?;
+        let hash = H256(keccak256(message));
+        let r = H256::from_slice(signature.r());
+        let s = H256::from_slice(signature.s());
+        for recovery_id in 0..2 {
+            let signature = PackedEthSignature::from_rsv(&r, &s, recovery_id);
+            if signature.signature_recover_signer(&hash).ok() == Some(self.address) {
+                return Ok(signature);
+            }
+        }
+        Err(SignerError::SigningFailed(format!(
+            "{:?} signing backend returned signature not matching account {:?}",
+            self.backend.kind(),
+            self.address
+        )))
+    }
+}
+
+#[async_trait]
+impl EthereumSigner for OperatorSigner {
+    async fn sign_typed_data<S: EIP712TypedStructure + Sync>(
+        &self,
+        domain: &Eip712Domain,
+        typed_struct: &S,
+    ) -> Result<PackedEthSignature, SignerError> {
+        let mut message = b"\x19\x01".to_vec();
+        message.extend_from_slice(domain.hash_struct().as_bytes());
+        message.extend_from_slice(typed_struct.hash_struct().as_bytes());
+        self.sign(SigningOperation::SignTypedData, &message).await
+    }
+
+    async fn sign_transaction(
+        &self,
+        raw_tx: TransactionParameters,
+    ) -> Result<Vec<u8>, SignerError> {
+        let chain_id = raw_tx.chain_id;
+        let tx = Transaction::from(raw_tx);
+        let payload = tx.signing_payload(chain_id);
+        let signature = self
+            .sign(SigningOperation::SignTransaction, &payload)
+            .await?;
+        let r = H256::from_slice(signature.r());
+        let s = H256::from_slice(signature.s());
+        let signed = tx.into_signed(chain_id, r, s, signature.v());
+        Ok(signed.raw_transaction.0)
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        Ok(self.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{H160, U256, U64};
+
+    use super::*;
+
+    /// Backend returning signatures with a flipped recovery ID.
+    #[derive(Debug)]
+    struct FlippedRecoveryId(PrivateKeySigner);
+
+    #[async_trait]
+    impl SigningBackend for FlippedRecoveryId {
+        fn kind(&self) -> SigningBackendKind {
+            SigningBackendKind::Web3Signer
+        }
+
+        async fn address(&self) -> Result<Address, SignerError> {
+            self.0.get_address().await
+        }
+
+        async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+            let signature = self.0.sign_message(message).await?;
+            Ok(PackedEthSignature::from_rsv(
+                &H256::from_slice(signature.r()),
+                &H256::from_slice(signature.s()),
+                1 - signature.v(),
+            ))
+        }
+    }
+
+    fn test_transactions() -> impl Iterator<Item = TransactionParameters> {
+        let base_tx = TransactionParameters {
+            nonce: U256::from(1u32),
+            to: Some(H160::default()),
+            gas: 100_000.into(),
+            gas_price: Some(U256::from(2u32)),
+            max_fee_per_gas: U256::from(2u32),
+            max_priority_fee_per_gas: U256::from(1u32),
+            value: Default::default(),
+            data: vec![1, 2, 3],
+            chain_id: 270,
+            transaction_type: None,
+            access_list: None,
+            blob_versioned_hashes: None,
+            max_fee_per_blob_gas: None,
+        };
+        vec![None, Some(1_u64), Some(2)]
+            .into_iter()
+            .map(move |transaction_type| TransactionParameters {
+                transaction_type: transaction_type.map(U64::from),
+                ..base_tx.clone()
+            })
+    }
+
+    #[tokio::test]
+    async fn operator_signer_matches_private_key_signer() {
+        let pk_signer = PrivateKeySigner::new(H256::repeat_byte(5));
+        let operator_signer = OperatorSigner::new(pk_signer.clone()).await.unwrap();
+        let flipped_signer = OperatorSigner::new(FlippedRecoveryId(pk_signer.clone()))
+            .await
+            .unwrap();
+        assert_eq!(
+            operator_signer.address(),
+            pk_signer.get_address().await.unwrap()
+        );
+
+        for tx in test_transactions() {
+            let expected = pk_signer.sign_transaction(tx.clone()).await.unwrap();
+            let actual = operator_signer.sign_transaction(tx.clone()).await.unwrap();
+            assert_eq!(actual, expected, "{tx:?}");
+            let actual = flipped_signer.sign_transaction(tx.clone()).await.unwrap();
+            assert_eq!(actual, expected, "{tx:?}");
+        }
+    }
+
+    #[derive(Debug)]
+    struct WrongKey(PrivateKeySigner);
+
+    #[async_trait]
+    impl SigningBackend for WrongKey {
+        fn kind(&self) -> SigningBackendKind {
+            SigningBackendKind::AwsKms
+        }
+
+        async fn address(&self) -> Result<Address, SignerError> {
+            Ok(Address::repeat_byte(1))
+        }
+
+        async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+            self.0.sign_message(message).await
+        }
+    }
+
+    #[tokio::test]
+    async fn operator_signer_rejects_signatures_for_wrong_account() {
+        let backend = WrongKey(PrivateKeySigner::new(H256::repeat_byte(5)));
+        let signer = OperatorSigner::new(backend).await.unwrap();
+        signer.check_health().await.unwrap();
+        let tx = test_transactions().next().unwrap();
+        let err = signer.sign_transaction(tx).await.unwrap_err();
+        assert!(matches!(err, SignerError::SigningFailed(_)), "{err}");
+    }
+}
@@ -73,6 +73,26 @@ pub struct Transaction {
     pub blob_versioned_hashes: Option<Vec<H256>>,
 }
 
+impl From<TransactionParameters> for Transaction {
+    fn from(raw_tx: TransactionParameters) -> Self {
+        // According to the code in web3 <https://docs.rs/web3/latest/src/web3/api/accounts.rs.html#86>
+        // We should use `max_fee_per_gas` as `gas_price` if we use EIP1559
+        Self {
+            to: raw_tx.to,
+            nonce: raw_tx.nonce,
+            gas: raw_tx.gas,
+            gas_price: raw_tx.max_fee_per_gas,
+            value: raw_tx.value,
+            data: raw_tx.data,
+            transaction_type: raw_tx.transaction_type,
+            access_list: raw_tx.access_list.unwrap_or_default(),
+            max_priority_fee_per_gas: raw_tx.max_priority_fee_per_gas,
+            max_fee_per_blob_gas: raw_tx.max_fee_per_blob_gas,
+            blob_versioned_hashes: raw_tx.blob_versioned_hashes,
+        }
+    }
+}
+
 impl Transaction {
     fn rlp_append_legacy(&self, stream: &mut RlpStream) {
         stream.append(&self.nonce);
@@ -243,6 +263,44 @@ impl Transaction {
         }
     }
 
+    /// Returns the payload to be signed, i.e., the message whose Keccak-256 hash is signed.
+    pub fn signing_payload(&self, chain_id: u64) -> Vec<u8> {
+        self.encode(chain_id, None)
+    }
+
+    /// Returns a raw signed transaction given a signature of the [`Self::signing_payload()`] hash
+    /// with the specified recovery ID (0 or 1).
+    pub fn into_signed(
+        self,
+        chain_id: u64,
+        r: H256,
+        s: H256,
+        recovery_id: u8,
+    ) -> SignedTransaction {
+        let is_legacy = matches!(
+            self.transaction_type.map(|t| t.as_u64()),
+            Some(LEGACY_TX_ID) | None
+        );
+        let v = if is_legacy {
+            u64::from(recovery_id) + 35 + chain_id * 2
+        } else {
+            recovery_id.into()
+        };
+        let signature = Signature { v, r, s };
+
+        let hash = signing::keccak256(&self.signing_payload(chain_id));
+        let signed = self.encode(chain_id, Some(&signature));
+        let transaction_hash = signing::keccak256(signed.as_ref()).into();
+        SignedTransaction {
+            message_hash: hash.into(),
+            v: signature.v,
+            r: signature.r,
+            s: signature.s,
+            raw_transaction: signed.into(),
+            transaction_hash,
+        }
+    }
+
     /// Sign and return a raw signed transaction.
     pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
         let adjust_v_value = matches!(
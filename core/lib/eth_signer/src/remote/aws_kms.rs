@@ -0,0 +1,327 @@
+//! Backend using asymmetric secp256k1 keys managed by AWS KMS.
+
+use std::{collections::BTreeMap, fmt};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use hmac::{Hmac, Mac};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use zksync_types::{tx::primitives::PackedEthSignature, web3::signing::keccak256, Address};
+
+use super::{
+    address_from_spki, http_client, network_error, signature_from_der, SecretDecryptor, Zeroizing,
+};
+use crate::{error::SignerError, metrics::SigningBackendKind, SigningBackend};
+
+const SERVICE: &str = "kms";
+
+/// AWS credentials loaded from the standard env variables.
+struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AwsCredentials {
+    fn from_env() -> Result<Self, SignerError> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .map_err(|_| SignerError::CustomError(format!("`{name}` env variable is not set")))
+        };
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+/// Computes the `Authorization` header value for a request according to AWS Signature Version 4.
+/// `headers` must contain all headers to be signed (including `host` and `x-amz-date`) with lowercase names.
+fn authorization_header(
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    method: &str,
+    headers: &BTreeMap<&str, String>,
+    payload: &[u8],
+) -> String {
+    fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+        mac.update(data.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    let amz_date = &headers["x-amz-date"];
+    let date = &amz_date[..8];
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect();
+    let signed_headers = headers.keys().copied().collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "{method}\n/\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex::encode(Sha256::digest(payload))
+    );
+
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let secret = format!("AWS4{}", credentials.secret_access_key);
+    let key = hmac(secret.as_bytes(), date);
+    let key = hmac(&key, region);
+    let key = hmac(&key, service);
+    let key = hmac(&key, "aws4_request");
+    let signature = hex::encode(hmac(&key, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id
+    )
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignResponse {
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetPublicKeyResponse {
+    public_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DecryptResponse {
+    plaintext: String,
+}
+
+/// Client for the AWS KMS JSON API. Credentials are read from the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`
+/// and (optionally) `AWS_SESSION_TOKEN` env variables.
+#[derive(Debug)]
+struct AwsKmsClient {
+    client: reqwest::Client,
+    endpoint: reqwest::Url,
+    region: String,
+    credentials: AwsCredentials,
+}
+
+impl AwsKmsClient {
+    fn new(region: &str, endpoint: Option<&str>) -> Result<Self, SignerError> {
+        let endpoint = endpoint
+            .map_or_else(
+                || format!("https://kms.{region}.amazonaws.com/"),
+                str::to_owned,
+            )
+            .parse::<reqwest::Url>()
+            .map_err(|err| SignerError::CustomError(format!("invalid KMS endpoint: {err}")))?;
+        Ok(Self {
+            client: http_client()?,
+            endpoint,
+            region: region.to_owned(),
+            credentials: AwsCredentials::from_env()?,
+        })
+    }
+
+    async fn call<R: DeserializeOwned>(
+        &self,
+        action: &str,
+        body: serde_json::Value,
+    ) -> Result<R, SignerError> {
+        let payload = serde_json::to_vec(&body).expect("failed serializing request");
+        let host = self.endpoint.host_str().unwrap_or_default();
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_owned(),
+        };
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let mut headers = BTreeMap::from([
+            ("content-type", "application/x-amz-json-1.1".to_owned()),
+            ("host", host),
+            ("x-amz-date", amz_date),
+            ("x-amz-target", format!("TrentService.{action}")),
+        ]);
+        if let Some(token) = &self.credentials.session_token {
+            headers.insert("x-amz-security-token", token.clone());
+        }
+        let authorization = authorization_header(
+            &self.credentials,
+            &self.region,
+            SERVICE,
+            "POST",
+            &headers,
+            &payload,
+        );
+
+        let mut request = self
+            .client
+            .post(self.endpoint.clone())
+            .header("authorization", authorization);
+        for (name, value) in headers {
+            if name != "host" {
+                request = request.header(name, value);
+            }
+        }
+        let response = request.body(payload).send().await.map_err(network_error)?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(SignerError::SigningFailed(format!(
+                "AWS KMS `{action}` responded with {status}: {body}"
+            )));
+        }
+        response.json().await.map_err(network_error)
+    }
+}
+
+/// Signing backend using an `ECC_SECG_P256K1` key managed by AWS KMS. Credentials are read from
+/// the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and (optionally) `AWS_SESSION_TOKEN` env variables.
+#[derive(Debug)]
+pub struct AwsKmsBackend {
+    client: AwsKmsClient,
+    key_id: String,
+    address: Address,
+}
+
+impl AwsKmsBackend {
+    /// Creates a backend for the specified key (a key ID, ARN or alias). If `endpoint` is not specified,
+    /// the default regional KMS endpoint is used.
+    pub async fn new(
+        region: &str,
+        key_id: &str,
+        endpoint: Option<&str>,
+    ) -> Result<Self, SignerError> {
+        let mut this = Self {
+            client: AwsKmsClient::new(region, endpoint)?,
+            key_id: key_id.to_owned(),
+            address: Address::zero(),
+        };
+        this.address = this.fetch_address().await?;
+        Ok(this)
+    }
+
+    async fn fetch_address(&self) -> Result<Address, SignerError> {
+        let response: GetPublicKeyResponse = self
+            .client
+            .call("GetPublicKey", json!({ "KeyId": self.key_id }))
+            .await?;
+        let public_key = BASE64
+            .decode(response.public_key)
+            .map_err(|err| SignerError::CustomError(format!("malformed public key: {err}")))?;
+        address_from_spki(&public_key)
+    }
+}
+
+#[async_trait]
+impl SigningBackend for AwsKmsBackend {
+    fn kind(&self) -> SigningBackendKind {
+        SigningBackendKind::AwsKms
+    }
+
+    async fn address(&self) -> Result<Address, SignerError> {
+        Ok(self.address)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+        let request = json!({
+            "KeyId": self.key_id,
+            "Message": BASE64.encode(keccak256(message)),
+            "MessageType": "DIGEST",
+            "SigningAlgorithm": "ECDSA_SHA_256",
+        });
+        let response: SignResponse = self.client.call("Sign", request).await?;
+        let signature = BASE64
+            .decode(response.signature)
+            .map_err(|err| SignerError::SigningFailed(format!("malformed signature: {err}")))?;
+        signature_from_der(&signature)
+    }
+
+    async fn check_health(&self) -> Result<(), SignerError> {
+        let address = self.fetch_address().await?;
+        if address == self.address {
+            Ok(())
+        } else {
+            Err(SignerError::CustomError(format!(
+                "KMS key {} changed its address from {:?} to {address:?}",
+                self.key_id, self.address
+            )))
+        }
+    }
+}
+
+/// Decryptor using a symmetric key managed by AWS KMS. Credentials are read in the same way as for [`AwsKmsBackend`].
+#[derive(Debug)]
+pub struct AwsKmsDecryptor {
+    client: AwsKmsClient,
+    key_id: String,
+}
+
+impl AwsKmsDecryptor {
+    /// Creates a decryptor for the specified key (a key ID, ARN or alias). If `endpoint` is not specified,
+    /// the default regional KMS endpoint is used.
+    pub fn new(region: &str, key_id: &str, endpoint: Option<&str>) -> Result<Self, SignerError> {
+        Ok(Self {
+            client: AwsKmsClient::new(region, endpoint)?,
+            key_id: key_id.to_owned(),
+        })
+    }
+}
+
+#[async_trait]
+impl SecretDecryptor for AwsKmsDecryptor {
+    async fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, SignerError> {
+        let request = json!({
+            "KeyId": self.key_id,
+            "CiphertextBlob": BASE64.encode(ciphertext),
+        });
+        let response: DecryptResponse = self.client.call("Decrypt", request).await?;
+        let plaintext = Zeroizing::new(response.plaintext);
+        BASE64
+            .decode(plaintext.as_bytes())
+            .map(Zeroizing::new)
+            .map_err(|err| SignerError::CustomError(format!("malformed plaintext: {err}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vector from the `get-vanilla` case of the AWS Signature Version 4 test suite.
+    #[test]
+    fn signing_request() {
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_owned(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_owned(),
+            session_token: None,
+        };
+        let headers = BTreeMap::from([
+            ("host", "example.amazonaws.com".to_owned()),
+            ("x-amz-date", "20150830T123600Z".to_owned()),
+        ]);
+        let authorization =
+            authorization_header(&credentials, "us-east-1", "service", "GET", &headers, b"");
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+}
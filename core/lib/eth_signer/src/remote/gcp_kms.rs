@@ -0,0 +1,215 @@
+//! Backend using asymmetric secp256k1 keys managed by GCP Cloud KMS.
+
+use std::fmt;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use google_cloud_auth::{
+    project::{create_token_source, Config},
+    token_source::TokenSource,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use zksync_types::{tx::primitives::PackedEthSignature, web3::signing::keccak256, Address};
+
+use super::{
+    address_from_spki, http_client, network_error, signature_from_der, SecretDecryptor, Zeroizing,
+};
+use crate::{error::SignerError, metrics::SigningBackendKind, SigningBackend};
+
+const DEFAULT_ENDPOINT: &str = "https://cloudkms.googleapis.com/v1/";
+const SCOPES: [&str; 1] = ["https://www.googleapis.com/auth/cloudkms"];
+
+#[derive(Debug, Deserialize)]
+struct AsymmetricSignResponse {
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublicKeyResponse {
+    pem: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DecryptResponse {
+    plaintext: String,
+}
+
+/// Client for the Cloud KMS REST API using application default credentials.
+struct GcpKmsClient {
+    client: reqwest::Client,
+    token_source: Box<dyn TokenSource>,
+    endpoint: String,
+}
+
+impl fmt::Debug for GcpKmsClient {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("GcpKmsClient")
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GcpKmsClient {
+    async fn new(endpoint: Option<&str>) -> Result<Self, SignerError> {
+        let config = Config {
+            scopes: Some(&SCOPES),
+            ..Config::default()
+        };
+        let token_source = create_token_source(config).await.map_err(|err| {
+            SignerError::CustomError(format!("failed initializing GCP credentials: {err}"))
+        })?;
+        let endpoint = endpoint.unwrap_or(DEFAULT_ENDPOINT);
+        Ok(Self {
+            client: http_client()?,
+            token_source,
+            endpoint: format!("{}/", endpoint.trim_end_matches('/')),
+        })
+    }
+
+    async fn get<R: DeserializeOwned>(&self, path: &str) -> Result<R, SignerError> {
+        let url = format!("{}{path}", self.endpoint);
+        self.call(self.client.get(url)).await
+    }
+
+    async fn post<R: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<R, SignerError> {
+        let url = format!("{}{path}", self.endpoint);
+        self.call(self.client.post(url).json(body)).await
+    }
+
+    async fn call<R: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<R, SignerError> {
+        let token = self.token_source.token().await.map_err(|err| {
+            SignerError::SigningFailed(format!("failed getting GCP access token: {err}"))
+        })?;
+        let response = request
+            .bearer_auth(token.access_token)
+            .send()
+            .await
+            .map_err(network_error)?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(SignerError::SigningFailed(format!(
+                "GCP KMS responded with {status}: {body}"
+            )));
+        }
+        response.json().await.map_err(network_error)
+    }
+}
+
+/// Signing backend using an `EC_SIGN_SECP256K1_SHA256` key managed by GCP Cloud KMS. Uses application
+/// default credentials (e.g., the `GOOGLE_APPLICATION_CREDENTIALS` env variable or the metadata server).
+#[derive(Debug)]
+pub struct GcpKmsBackend {
+    client: GcpKmsClient,
+    /// Resource name of the crypto key version, e.g.
+    /// `projects/{project}/locations/{location}/keyRings/{ring}/cryptoKeys/{key}/cryptoKeyVersions/{version}`.
+    key_version: String,
+    address: Address,
+}
+
+impl GcpKmsBackend {
+    /// Creates a backend for the specified crypto key version. If `endpoint` is not specified,
+    /// the default Cloud KMS endpoint is used.
+    pub async fn new(key_version: &str, endpoint: Option<&str>) -> Result<Self, SignerError> {
+        let mut this = Self {
+            client: GcpKmsClient::new(endpoint).await?,
+            key_version: key_version.to_owned(),
+            address: Address::zero(),
+        };
+        this.address = this.fetch_address().await?;
+        Ok(this)
+    }
+
+    async fn fetch_address(&self) -> Result<Address, SignerError> {
+        let path = format!("{}/publicKey", self.key_version);
+        let response: PublicKeyResponse = self.client.get(&path).await?;
+        let public_key: String = response
+            .pem
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        let public_key = BASE64
+            .decode(public_key)
+            .map_err(|err| SignerError::CustomError(format!("malformed public key: {err}")))?;
+        address_from_spki(&public_key)
+    }
+}
+
+#[async_trait]
+impl SigningBackend for GcpKmsBackend {
+    fn kind(&self) -> SigningBackendKind {
+        SigningBackendKind::GcpKms
+    }
+
+    async fn address(&self) -> Result<Address, SignerError> {
+        Ok(self.address)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+        let path = format!("{}:asymmetricSign", self.key_version);
+        // KMS signs the provided digest as is, so it's fine to supply a Keccak-256 digest.
+        let request = json!({
+            "digest": { "sha256": BASE64.encode(keccak256(message)) },
+        });
+        let response: AsymmetricSignResponse = self.client.post(&path, &request).await?;
+        let signature = BASE64
+            .decode(response.signature)
+            .map_err(|err| SignerError::SigningFailed(format!("malformed signature: {err}")))?;
+        signature_from_der(&signature)
+    }
+
+    async fn check_health(&self) -> Result<(), SignerError> {
+        let address = self.fetch_address().await?;
+        if address == self.address {
+            Ok(())
+        } else {
+            Err(SignerError::CustomError(format!(
+                "KMS key {} changed its address from {:?} to {address:?}",
+                self.key_version, self.address
+            )))
+        }
+    }
+}
+
+/// Decryptor using a symmetric key managed by GCP Cloud KMS. Uses application default credentials
+/// in the same way as [`GcpKmsBackend`].
+#[derive(Debug)]
+pub struct GcpKmsDecryptor {
+    client: GcpKmsClient,
+    /// Resource name of the crypto key, e.g. `projects/{project}/locations/{location}/keyRings/{ring}/cryptoKeys/{key}`.
+    key_name: String,
+}
+
+impl GcpKmsDecryptor {
+    /// Creates a decryptor for the specified crypto key. If `endpoint` is not specified,
+    /// the default Cloud KMS endpoint is used.
+    pub async fn new(key_name: &str, endpoint: Option<&str>) -> Result<Self, SignerError> {
+        Ok(Self {
+            client: GcpKmsClient::new(endpoint).await?,
+            key_name: key_name.to_owned(),
+        })
+    }
+}
+
+#[async_trait]
+impl SecretDecryptor for GcpKmsDecryptor {
+    async fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, SignerError> {
+        let path = format!("{}:decrypt", self.key_name);
+        let request = json!({ "ciphertext": BASE64.encode(ciphertext) });
+        let response: DecryptResponse = self.client.post(&path, &request).await?;
+        let plaintext = Zeroizing::new(response.plaintext);
+        BASE64
+            .decode(plaintext.as_bytes())
+            .map(Zeroizing::new)
+            .map_err(|err| SignerError::CustomError(format!("malformed plaintext: {err}")))
+    }
+}
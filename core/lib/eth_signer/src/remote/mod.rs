@@ -0,0 +1,125 @@
+//! Remote signing backends.
+
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+pub use zeroize::Zeroizing;
+use zksync_types::{tx::primitives::PackedEthSignature, web3::signing::keccak256, Address, H256};
+
+pub use self::{
+    aws_kms::{AwsKmsBackend, AwsKmsDecryptor},
+    gcp_kms::{GcpKmsBackend, GcpKmsDecryptor},
+    web3signer::Web3SignerBackend,
+};
+use crate::error::SignerError;
+
+mod aws_kms;
+mod gcp_kms;
+mod web3signer;
+
+/// Decrypts secrets protected by envelope encryption with a symmetric key managed by a remote service.
+/// Used for secrets that cannot be handled by a [`SigningBackend`](crate::SigningBackend), such as consensus keys.
+///
+/// Unlike signing backends, decryption puts the secret into the process memory, so it's only a substitute
+/// for remote signing where the latter isn't possible. For consensus keys, it isn't: validator keys (bn254)
+/// and node keys (ed25519) use schemes that neither AWS nor GCP KMS can sign with, and the consensus executor
+/// signs messages itself given secret keys, without a hook for an external signer. Envelope encryption still
+/// ensures that keys never exist on the host at rest (in configs, env files or backups), that every decryption
+/// is authorized and audited by KMS, and that revoking KMS access disables the keys on restart. The plaintext
+/// is zeroed out as soon as the key is decoded. Operator keys should use a [`SigningBackend`](crate::SigningBackend)
+/// instead, so that they never leave KMS.
+#[async_trait]
+pub trait SecretDecryptor: fmt::Debug + Send + Sync {
+    /// Decrypts the provided ciphertext. The plaintext is zeroed out on drop.
+    async fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, SignerError>;
+}
+
+/// Timeout for requests to remote signers.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn http_client() -> Result<reqwest::Client, SignerError> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|err| SignerError::CustomError(format!("failed creating HTTP client: {err}")))
+}
+
+fn network_error(err: reqwest::Error) -> SignerError {
+    SignerError::SigningFailed(format!("signer request failed: {err}"))
+}
+
+/// Computes an Ethereum address from an uncompressed secp256k1 public key, either prefixed with 0x04 or not.
+fn address_from_public_key(public_key: &[u8]) -> Result<Address, SignerError> {
+    let public_key = match public_key {
+        [0x04, rest @ ..] if rest.len() == 64 => rest,
+        _ if public_key.len() == 64 => public_key,
+        _ => return Err(SignerError::DefineAddress),
+    };
+    secp256k1::PublicKey::from_slice(&[&[0x04][..], public_key].concat())
+        .map_err(|_| SignerError::DefineAddress)?;
+    Ok(Address::from_slice(&keccak256(public_key)[12..]))
+}
+
+/// Extracts an Ethereum address from a DER-encoded `SubjectPublicKeyInfo` of a secp256k1 key, as returned by KMS.
+fn address_from_spki(spki: &[u8]) -> Result<Address, SignerError> {
+    // The uncompressed public key is the last component of the SPKI structure, encoded as a bit string.
+    let point_start = spki
+        .len()
+        .checked_sub(65)
+        .ok_or(SignerError::DefineAddress)?;
+    address_from_public_key(&spki[point_start..])
+}
+
+/// Converts a DER-encoded ECDSA signature of a 32-byte digest, as returned by KMS, to a normalized signature.
+/// The recovery ID is fixed by [`OperatorSigner`](crate::OperatorSigner).
+fn signature_from_der(der: &[u8]) -> Result<PackedEthSignature, SignerError> {
+    let mut signature = secp256k1::ecdsa::Signature::from_der(der)
+        .map_err(|err| SignerError::SigningFailed(format!("malformed DER signature: {err}")))?;
+    signature.normalize_s();
+    let compact = signature.serialize_compact();
+    let (r, s) = compact.split_at(32);
+    Ok(PackedEthSignature::from_rsv(
+        &H256::from_slice(r),
+        &H256::from_slice(s),
+        0,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+    use super::*;
+
+    #[test]
+    fn converting_kms_signature() {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[5; 32]).unwrap();
+        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+        let expected_address =
+            PackedEthSignature::address_from_private_key(&H256::repeat_byte(5)).unwrap();
+
+        let address = address_from_public_key(&public_key.serialize_uncompressed()).unwrap();
+        assert_eq!(address, expected_address);
+        // SPKI header for secp256k1 public keys.
+        let spki_header = hex::decode("3056301006072a8648ce3d020106052b8104000a034200").unwrap();
+        let spki = [spki_header, public_key.serialize_uncompressed().to_vec()].concat();
+        assert_eq!(address_from_spki(&spki).unwrap(), expected_address);
+        address_from_public_key(&[1; 65]).unwrap_err();
+
+        let digest = H256::repeat_byte(0x42);
+        let message = Message::from_slice(digest.as_bytes()).unwrap();
+        let signature = secp.sign_ecdsa(&message, &secret_key);
+        let converted = signature_from_der(&signature.serialize_der()).unwrap();
+        assert_eq!(converted.r(), &signature.serialize_compact()[..32]);
+        let recovered = (0..2).any(|v| {
+            let signature = PackedEthSignature::from_rsv(
+                &H256::from_slice(converted.r()),
+                &H256::from_slice(converted.s()),
+                v,
+            );
+            signature.signature_recover_signer(&digest).ok() == Some(expected_address)
+        });
+        assert!(recovered);
+    }
+}
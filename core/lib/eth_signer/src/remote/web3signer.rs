@@ -0,0 +1,117 @@
+//! Backend using the [web3signer] Eth1 signing API.
+//!
+//! [web3signer]: https://docs.web3signer.consensys.io/
+
+use async_trait::async_trait;
+use serde::Serialize;
+use zksync_types::{tx::primitives::PackedEthSignature, Address};
+
+use super::{address_from_public_key, http_client, network_error};
+use crate::{error::SignerError, metrics::SigningBackendKind, SigningBackend};
+
+#[derive(Debug, Serialize)]
+struct SignRequest {
+    data: String,
+}
+
+/// Signing backend delegating to a web3signer instance. web3signer can keep keys in a variety of key stores,
+/// including HSMs accessed via PKCS#11, so that private keys never leave the HSM.
+#[derive(Debug)]
+pub struct Web3SignerBackend {
+    client: reqwest::Client,
+    base_url: String,
+    /// Hex-encoded 64-byte public key used by web3signer to identify the key.
+    identifier: String,
+    address: Address,
+}
+
+impl Web3SignerBackend {
+    /// Creates a backend for the key with the specified hex-encoded uncompressed secp256k1 public key.
+    pub fn new(base_url: &str, public_key: &str) -> Result<Self, SignerError> {
+        let public_key = hex::decode(public_key.strip_prefix("0x").unwrap_or(public_key))
+            .map_err(|err| SignerError::CustomError(format!("invalid public key: {err}")))?;
+        let address = address_from_public_key(&public_key)?;
+        let public_key = public_key.strip_prefix(&[0x04]).unwrap_or(&public_key);
+        Ok(Self {
+            client: http_client()?,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            identifier: format!("0x{}", hex::encode(public_key)),
+            address,
+        })
+    }
+
+    async fn checked_response(
+        response: Result<reqwest::Response, reqwest::Error>,
+    ) -> Result<reqwest::Response, SignerError> {
+        let response = response.map_err(network_error)?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(SignerError::SigningFailed(format!(
+            "web3signer responded with {status}: {body}"
+        )))
+    }
+}
+
+#[async_trait]
+impl SigningBackend for Web3SignerBackend {
+    fn kind(&self) -> SigningBackendKind {
+        SigningBackendKind::Web3Signer
+    }
+
+    async fn address(&self) -> Result<Address, SignerError> {
+        Ok(self.address)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+        let url = format!("{}/api/v1/eth1/sign/{}", self.base_url, self.identifier);
+        let request = SignRequest {
+            data: format!("0x{}", hex::encode(message)),
+        };
+        let response = self.client.post(url).json(&request).send().await;
+        let signature = Self::checked_response(response)
+            .await?
+            .text()
+            .await
+            .map_err(network_error)?;
+
+        let signature = signature.trim();
+        let signature = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+            .map_err(|err| SignerError::SigningFailed(format!("malformed signature: {err}")))?;
+        PackedEthSignature::deserialize_packed(&signature)
+            .map_err(|err| SignerError::SigningFailed(format!("malformed signature: {err}")))
+    }
+
+    async fn check_health(&self) -> Result<(), SignerError> {
+        let response = self
+            .client
+            .get(format!("{}/upcheck", self.base_url))
+            .send()
+            .await;
+        Self::checked_response(response).await?;
+
+        let response = self
+            .client
+            .get(format!("{}/api/v1/eth1/publicKeys", self.base_url))
+            .send()
+            .await;
+        let public_keys: Vec<String> = Self::checked_response(response)
+            .await?
+            .json()
+            .await
+            .map_err(network_error)?;
+        let has_key = public_keys
+            .iter()
+            .any(|key| key.eq_ignore_ascii_case(&self.identifier));
+        if has_key {
+            Ok(())
+        } else {
+            Err(SignerError::CustomError(format!(
+                "web3signer does not manage key {}",
+                self.identifier
+            )))
+        }
+    }
+}
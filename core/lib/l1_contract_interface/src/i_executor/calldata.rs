@@ -0,0 +1,543 @@
+//! Decoding of raw calldata for `commit`, `prove` and `execute` methods defined in `IExecutor.sol`.
+//!
+//! Calldata is usually obtained from L1 transactions and is thus untrusted; decoding must not panic on any input.
+
+use std::convert::TryInto;
+
+use zksync_types::{
+    ethabi::{self, ParamType, Token},
+    web3::contract::Error as Web3ContractError,
+    U256,
+};
+
+use crate::{
+    i_executor::structures::{CommitBatchInfo, PriorityOpsBatchInfo, StoredBatchInfo},
+    Tokenizable,
+};
+
+/// Errors that can occur when decoding [`ExecutorCalldata`].
+#[derive(Debug, thiserror::Error)]
+pub enum CalldataDecodeError {
+    #[error("calldata ({0} bytes) is too short to contain a function selector")]
+    TooShort(usize),
+    #[error("unknown function selector: 0x{}", hex_selector(.0))]
+    UnknownSelector([u8; 4]),
+    #[error("failed ABI-decoding arguments of `{method}`: {source}")]
+    Abi {
+        method: &'static str,
+        #[source]
+        source: ethabi::Error,
+    },
+    #[error("invalid arguments of `{method}`: {source}")]
+    InvalidArgs {
+        method: &'static str,
+        #[source]
+        source: Web3ContractError,
+    },
+}
+
+fn hex_selector(selector: &[u8; 4]) -> String {
+    selector.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Decoded arguments of a `commitBatches` call (or its pre-Boojum / shared bridge variant).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitBatchesCalldata {
+    /// Chain ID; only present in shared bridge calls.
+    pub chain_id: Option<U256>,
+    pub last_committed_batch: StoredBatchInfo,
+    pub new_batches: Vec<CommitBatchInfo>,
+}
+
+/// Decoded arguments of a `proveBatches` call (or its pre-Boojum / shared bridge variant).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProveBatchesCalldata {
+    /// Chain ID; only present in shared bridge calls.
+    pub chain_id: Option<U256>,
+    pub prev_batch: StoredBatchInfo,
+    pub committed_batches: Vec<StoredBatchInfo>,
+    pub recursive_aggregation_input: Vec<U256>,
+    pub serialized_proof: Vec<U256>,
+}
+
+/// Decoded arguments of an `executeBatches` call (or its pre-Boojum / shared bridge variant).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecuteBatchesCalldata {
+    /// Chain ID; only present in shared bridge calls.
+    pub chain_id: Option<U256>,
+    pub batches: Vec<StoredBatchInfo>,
+    /// Proofs of priority operations; only present if the L1 contract uses the priority tree.
+    pub priority_ops_proofs: Option<Vec<PriorityOpsBatchInfo>>,
+}
+
+/// Decoded calldata of an `IExecutor.sol` method.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutorCalldata {
+    Commit(CommitBatchesCalldata),
+    Prove(ProveBatchesCalldata),
+    Execute(ExecuteBatchesCalldata),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MethodKind {
+    Commit { is_pre_boojum: bool },
+    Prove,
+    Execute { with_priority_ops: bool },
+}
+
+/// Signature of a supported `IExecutor.sol` method.
+#[derive(Debug)]
+struct Method {
+    name: &'static str,
+    kind: MethodKind,
+    is_shared_bridge: bool,
+}
+
+impl Method {
+    const ALL: &'static [Self] = &[
+        Self::new(
+            "commitBlocks",
+            MethodKind::Commit {
+                is_pre_boojum: true,
+            },
+            false,
+        ),
+        Self::new(
+            "commitBatches",
+            MethodKind::Commit {
+                is_pre_boojum: false,
+            },
+            false,
+        ),
+        Self::new(
+            "commitBatchesSharedBridge",
+            MethodKind::Commit {
+                is_pre_boojum: false,
+            },
+            true,
+        ),
+        Self::new("proveBlocks", MethodKind::Prove, false),
+        Self::new("proveBatches", MethodKind::Prove, false),
+        Self::new("proveBatchesSharedBridge", MethodKind::Prove, true),
+        Self::new(
+            "executeBlocks",
+            MethodKind::Execute {
+                with_priority_ops: false,
+            },
+            false,
+        ),
+        Self::new(
+            "executeBatches",
+            MethodKind::Execute {
+                with_priority_ops: false,
+            },
+            false,
+        ),
+        Self::new(
+            "executeBatches",
+            MethodKind::Execute {
+                with_priority_ops: true,
+            },
+            false,
+        ),
+        Self::new(
+            "executeBatchesSharedBridge",
+            MethodKind::Execute {
+                with_priority_ops: false,
+            },
+            true,
+        ),
+        Self::new(
+            "executeBatchesSharedBridge",
+            MethodKind::Execute {
+                with_priority_ops: true,
+            },
+            true,
+        ),
+    ];
+
+    const fn new(name: &'static str, kind: MethodKind, is_shared_bridge: bool) -> Self {
+        Self {
+            name,
+            kind,
+            is_shared_bridge,
+        }
+    }
+
+    fn params(&self) -> Vec<ParamType> {
+        let mut params = vec![];
+        if self.is_shared_bridge {
+            params.push(ParamType::Uint(256));
+        }
+        match self.kind {
+            MethodKind::Commit { is_pre_boojum } => {
+                let commit_schema = if is_pre_boojum {
+                    CommitBatchInfo::pre_boojum_schema()
+                } else {
+                    CommitBatchInfo::schema()
+                };
+                params.extend([
+                    StoredBatchInfo::schema(),
+                    ParamType::Array(Box::new(commit_schema)),
+                ]);
+            }
+            MethodKind::Prove => {
+                let uints = ParamType::Array(Box::new(ParamType::Uint(256)));
+                params.extend([
+                    StoredBatchInfo::schema(),
+                    ParamType::Array(Box::new(StoredBatchInfo::schema())),
+                    ParamType::Tuple(vec![uints.clone(), uints]),
+                ]);
+            }
+            MethodKind::Execute { with_priority_ops } => {
+                params.push(ParamType::Array(Box::new(StoredBatchInfo::schema())));
+                if with_priority_ops {
+                    params.push(ParamType::Array(Box::new(PriorityOpsBatchInfo::schema())));
+                }
+            }
+        }
+        params
+    }
+
+    fn decode_args(&self, args: &[u8]) -> Result<ExecutorCalldata, CalldataDecodeError> {
+        let tokens =
+            ethabi::decode(&self.params(), args).map_err(|source| CalldataDecodeError::Abi {
+                method: self.name,
+                source,
+            })?;
+        self.decode_tokens(tokens)
+            .map_err(|source| CalldataDecodeError::InvalidArgs {
+                method: self.name,
+                source,
+            })
+    }
+
+    /// Tokens are guaranteed to correspond to [`Self::params()`] since they are produced by `ethabi::decode()`.
+    fn decode_tokens(&self, tokens: Vec<Token>) -> Result<ExecutorCalldata, Web3ContractError> {
+        let decode_array = |token: Token| {
+            token
+                .into_array()
+                .unwrap_or_default()
+                .into_iter()
+                .map(StoredBatchInfo::from_token)
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let mut tokens = tokens.into_iter();
+        let chain_id = if self.is_shared_bridge {
+            tokens.next().and_then(Token::into_uint)
+        } else {
+            None
+        };
+        let mut next = || {
+            tokens.next().ok_or_else(|| {
+                Web3ContractError::InvalidOutputType("missing method argument".to_owned())
+            })
+        };
+
+        Ok(match self.kind {
+            MethodKind::Commit { .. } => {
+                let last_committed_batch = StoredBatchInfo::from_token(next()?)?;
+                let new_batches = next()?
+                    .into_array()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(CommitBatchInfo::from_token)
+                    .collect::<Result<_, _>>()?;
+                ExecutorCalldata::Commit(CommitBatchesCalldata {
+                    chain_id,
+                    last_committed_batch,
+                    new_batches,
+                })
+            }
+            MethodKind::Prove => {
+                let prev_batch = StoredBatchInfo::from_token(next()?)?;
+                let committed_batches = decode_array(next()?)?;
+                let proof_input = next()?.into_tuple().unwrap_or_default();
+                let mut proof_input = proof_input.into_iter().map(|token| {
+                    token
+                        .into_array()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(Token::into_uint)
+                        .collect::<Vec<_>>()
+                });
+                ExecutorCalldata::Prove(ProveBatchesCalldata {
+                    chain_id,
+                    prev_batch,
+                    committed_batches,
+                    recursive_aggregation_input: proof_input.next().unwrap_or_default(),
+                    serialized_proof: proof_input.next().unwrap_or_default(),
+                })
+            }
+            MethodKind::Execute { with_priority_ops } => {
+                let batches = decode_array(next()?)?;
+                let priority_ops_proofs = if with_priority_ops {
+                    let proofs = next()?
+                        .into_array()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(PriorityOpsBatchInfo::from_token)
+                        .collect::<Result<_, _>>()?;
+                    Some(proofs)
+                } else {
+                    None
+                };
+                ExecutorCalldata::Execute(ExecuteBatchesCalldata {
+                    chain_id,
+                    batches,
+                    priority_ops_proofs,
+                })
+            }
+        })
+    }
+}
+
+impl ExecutorCalldata {
+    /// Decodes calldata (including the 4-byte function selector) of a `commit*`, `prove*` or `execute*` method,
+    /// including pre-Boojum and shared bridge variants.
+    pub fn decode(calldata: &[u8]) -> Result<Self, CalldataDecodeError> {
+        let Some((selector, args)) = calldata.get(..4).map(|selector| (selector, &calldata[4..]))
+        else {
+            return Err(CalldataDecodeError::TooShort(calldata.len()));
+        };
+        let method = Method::ALL
+            .iter()
+            .find(|method| ethabi::short_signature(method.name, &method.params()) == selector);
+        let Some(method) = method else {
+            let selector = selector.try_into().unwrap(); // the slice length is 4
+            return Err(CalldataDecodeError::UnknownSelector(selector));
+        };
+        method.decode_args(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{L1BatchNumber, H256};
+
+    use super::*;
+    use crate::i_executor::structures::BatchCommitments;
+
+    fn mock_stored_batch_info(number: u32) -> StoredBatchInfo {
+        StoredBatchInfo {
+            batch_number: L1BatchNumber(number),
+            batch_hash: H256::repeat_byte(1),
+            index_repeated_storage_changes: 100,
+            number_of_layer1_txs: 3.into(),
+            priority_operations_hash: H256::repeat_byte(2),
+            l2_logs_tree_root: H256::repeat_byte(3),
+            timestamp: 1_700_000_000.into(),
+            commitment: H256::repeat_byte(4),
+        }
+    }
+
+    fn mock_commit_batch_info(number: u32) -> CommitBatchInfo {
+        CommitBatchInfo {
+            batch_number: L1BatchNumber(number),
+            timestamp: 1_700_000_000,
+            index_repeated_storage_changes: 100,
+            new_state_root: H256::repeat_byte(1),
+            number_of_layer1_txs: 3.into(),
+            priority_operations_hash: H256::repeat_byte(2),
+            commitments: BatchCommitments::PostBoojum {
+                bootloader_heap_initial_contents_hash: H256::repeat_byte(3),
+                events_queue_state_hash: H256::repeat_byte(4),
+                system_logs: vec![5; 88],
+            },
+            pubdata: Some(vec![0, 1, 2, 3]),
+        }
+    }
+
+    fn encode_calldata(name: &str, params: &[ParamType], tokens: &[Token]) -> Vec<u8> {
+        let mut calldata = ethabi::short_signature(name, params).to_vec();
+        calldata.extend(ethabi::encode(tokens));
+        calldata
+    }
+
+    #[test]
+    fn decoding_commit_calldata() {
+        let expected = CommitBatchesCalldata {
+            chain_id: None,
+            last_committed_batch: mock_stored_batch_info(1),
+            new_batches: vec![mock_commit_batch_info(2), mock_commit_batch_info(3)],
+        };
+        let tokens = [
+            expected.last_committed_batch.clone().into_token(),
+            Token::Array(
+                expected
+                    .new_batches
+                    .iter()
+                    .cloned()
+                    .map(Tokenizable::into_token)
+                    .collect(),
+            ),
+        ];
+        let params = [
+            StoredBatchInfo::schema(),
+            ParamType::Array(Box::new(CommitBatchInfo::schema())),
+        ];
+        let calldata = encode_calldata("commitBatches", &params, &tokens);
+        let decoded = ExecutorCalldata::decode(&calldata).unwrap();
+        assert_eq!(decoded, ExecutorCalldata::Commit(expected.clone()));
+
+        let mut shared_bridge_params = vec![ParamType::Uint(256)];
+        shared_bridge_params.extend(params);
+        let mut shared_bridge_tokens = vec![Token::Uint(270.into())];
+        shared_bridge_tokens.extend(tokens);
+        let calldata = encode_calldata(
+            "commitBatchesSharedBridge",
+            &shared_bridge_params,
+            &shared_bridge_tokens,
+        );
+        let decoded = ExecutorCalldata::decode(&calldata).unwrap();
+        let expected = CommitBatchesCalldata {
+            chain_id: Some(270.into()),
+            ..expected
+        };
+        assert_eq!(decoded, ExecutorCalldata::Commit(expected));
+    }
+
+    #[test]
+    fn decoding_prove_calldata() {
+        let expected = ProveBatchesCalldata {
+            chain_id: None,
+            prev_batch: mock_stored_batch_info(1),
+            committed_batches: vec![mock_stored_batch_info(2)],
+            recursive_aggregation_input: vec![],
+            serialized_proof: vec![1.into(), 2.into(), 3.into()],
+        };
+        let uints = ParamType::Array(Box::new(ParamType::Uint(256)));
+        let params = [
+            StoredBatchInfo::schema(),
+            ParamType::Array(Box::new(StoredBatchInfo::schema())),
+            ParamType::Tuple(vec![uints.clone(), uints]),
+        ];
+        let tokens = [
+            expected.prev_batch.clone().into_token(),
+            Token::Array(vec![expected.committed_batches[0].clone().into_token()]),
+            Token::Tuple(vec![
+                Token::Array(vec![]),
+                Token::Array(
+                    expected
+                        .serialized_proof
+                        .iter()
+                        .copied()
+                        .map(Token::Uint)
+                        .collect(),
+                ),
+            ]),
+        ];
+        let calldata = encode_calldata("proveBatches", &params, &tokens);
+        let decoded = ExecutorCalldata::decode(&calldata).unwrap();
+        assert_eq!(decoded, ExecutorCalldata::Prove(expected));
+    }
+
+    #[test]
+    fn decoding_execute_calldata() {
+        let batches = vec![mock_stored_batch_info(1), mock_stored_batch_info(2)];
+        let batches_token = Token::Array(
+            batches
+                .iter()
+                .cloned()
+                .map(Tokenizable::into_token)
+                .collect(),
+        );
+        let batches_param = ParamType::Array(Box::new(StoredBatchInfo::schema()));
+
+        let calldata = encode_calldata(
+            "executeBatches",
+            &[batches_param.clone()],
+            &[batches_token.clone()],
+        );
+        let decoded = ExecutorCalldata::decode(&calldata).unwrap();
+        let expected = ExecuteBatchesCalldata {
+            chain_id: None,
+            batches: batches.clone(),
+            priority_ops_proofs: None,
+        };
+        assert_eq!(decoded, ExecutorCalldata::Execute(expected));
+
+        let priority_ops_proofs = vec![
+            PriorityOpsBatchInfo::default(),
+            PriorityOpsBatchInfo {
+                left_path: vec![H256::repeat_byte(1)],
+                right_path: vec![H256::repeat_byte(2)],
+                item_hashes: vec![H256::repeat_byte(3), H256::repeat_byte(4)],
+            },
+        ];
+        let proofs_token = Token::Array(
+            priority_ops_proofs
+                .iter()
+                .cloned()
+                .map(Tokenizable::into_token)
+                .collect(),
+        );
+        let proofs_param = ParamType::Array(Box::new(PriorityOpsBatchInfo::schema()));
+        let calldata = encode_calldata(
+            "executeBatches",
+            &[batches_param, proofs_param],
+            &[batches_token, proofs_token],
+        );
+        let decoded = ExecutorCalldata::decode(&calldata).unwrap();
+        let expected = ExecuteBatchesCalldata {
+            chain_id: None,
+            batches,
+            priority_ops_proofs: Some(priority_ops_proofs),
+        };
+        assert_eq!(decoded, ExecutorCalldata::Execute(expected));
+    }
+
+    #[test]
+    fn decoding_invalid_calldata() {
+        let err = ExecutorCalldata::decode(&[1, 2]).unwrap_err();
+        assert!(matches!(err, CalldataDecodeError::TooShort(2)), "{err}");
+
+        let err = ExecutorCalldata::decode(&[0xde, 0xad, 0xbe, 0xef]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                CalldataDecodeError::UnknownSelector([0xde, 0xad, 0xbe, 0xef])
+            ),
+            "{err}"
+        );
+
+        let params = [ParamType::Array(Box::new(StoredBatchInfo::schema()))];
+        let mut calldata = ethabi::short_signature("executeBatches", &params).to_vec();
+        calldata.extend([0xff; 40]);
+        let err = ExecutorCalldata::decode(&calldata).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                CalldataDecodeError::Abi {
+                    method: "executeBatches",
+                    ..
+                }
+            ),
+            "{err}"
+        );
+
+        // `batchNumber` is out of range
+        let mut batch_token = mock_stored_batch_info(1).into_token();
+        let Token::Tuple(fields) = &mut batch_token else {
+            unreachable!();
+        };
+        fields[0] = Token::Uint(U256::MAX);
+        let calldata = encode_calldata(
+            "executeBatches",
+            &params,
+            &[Token::Array(vec![batch_token])],
+        );
+        let err = ExecutorCalldata::decode(&calldata).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                CalldataDecodeError::InvalidArgs {
+                    method: "executeBatches",
+                    ..
+                }
+            ),
+            "{err}"
+        );
+    }
+}
@@ -3,36 +3,49 @@
 
 use std::convert::TryInto;
 
+pub use kzg::KzgSettings;
+use kzg::{compute_commitment, compute_proof, compute_proof_poly};
 use sha2::Sha256;
 use sha3::{Digest, Keccak256};
-use zkevm_test_harness_1_3_3::ff::{PrimeField, PrimeFieldRepr};
-pub use zkevm_test_harness_1_4_2::kzg::KzgSettings;
-use zkevm_test_harness_1_4_2::{
-    kzg::{compute_commitment, compute_proof, compute_proof_poly},
-    zkevm_circuits::{
-        boojum::pairing::{
-            bls12_381::{Fr, FrRepr, G1Affine},
-            CurveAffine,
-        },
-        eip_4844::{
-            bitreverse, fft,
-            input::{BLOB_CHUNK_SIZE, ELEMENTS_PER_4844_BLOCK},
-            zksync_pubdata_into_ethereum_4844_data, zksync_pubdata_into_monomial_form_poly,
-        },
+use zkevm_circuits::{
+    boojum::pairing::{
+        bls12_381::{Fr, FrRepr, G1Affine},
+        CurveAffine,
+    },
+    eip_4844::{
+        bitreverse, ethereum_4844_data_into_zksync_pubdata, fft,
+        input::{BLOB_CHUNK_SIZE, ELEMENTS_PER_4844_BLOCK},
+        zksync_pubdata_into_ethereum_4844_data, zksync_pubdata_into_monomial_form_poly,
     },
 };
+use zkevm_test_harness_1_3_3::ff::{PrimeField, PrimeFieldRepr};
 use zksync_types::H256;
 
+pub use self::trusted_setup::{KzgSettingsProvider, KzgSetupError, KzgTrustedSetupSource};
+
+mod trusted_setup;
+
 pub const ZK_SYNC_BYTES_PER_BLOB: usize = BLOB_CHUNK_SIZE * ELEMENTS_PER_4844_BLOCK;
-const EIP_4844_BYTES_PER_BLOB: usize = 32 * ELEMENTS_PER_4844_BLOCK;
+pub const EIP_4844_BYTES_PER_BLOB: usize = 32 * ELEMENTS_PER_4844_BLOCK;
 
 /// Packed pubdata commitments.
 /// Format: opening point (16 bytes) || claimed value (32 bytes) || commitment (48 bytes)
 ///         || opening proof (48 bytes)) = 144 bytes
-const BYTES_PER_PUBDATA_COMMITMENT: usize = 144;
+pub const BYTES_PER_PUBDATA_COMMITMENT: usize = 144;
 
 const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
 
+/// Errors that can occur when decoding pubdata from a blob using [`decode_blob()`].
+#[derive(Debug, thiserror::Error)]
+pub enum BlobDecodeError {
+    #[error("blob has unexpected length {0} bytes, expected {EIP_4844_BYTES_PER_BLOB} bytes")]
+    InvalidLength(usize),
+    #[error("blob element #{0} is not a canonical BLS12-381 scalar")]
+    NonCanonicalElement(usize),
+    #[error("blob doesn't match the KZG commitment")]
+    CommitmentMismatch,
+}
+
 /// All the info needed for both the network transaction and by our L1 contracts. As part of the network transaction we
 /// need to encode the sidecar which contains the: blob, `kzg` commitment, and the blob proof. The transaction payload
 /// will utilize the versioned hash. The info needed for `commitBatches` is the `kzg` commitment, opening point,
@@ -57,9 +70,15 @@ pub struct KzgInfo {
 
 /// Given a KZG commitment, calculate the versioned hash.
 fn commitment_to_versioned_hash(kzg_commitment: G1Affine) -> [u8; 32] {
+    kzg_commitment_to_versioned_hash(kzg_commitment.into_compressed().as_ref())
+}
+
+/// Calculates the versioned hash of a KZG commitment in the compressed form, e.g. as included
+/// into a pubdata commitment.
+pub fn kzg_commitment_to_versioned_hash(kzg_commitment: &[u8]) -> [u8; 32] {
     let mut versioned_hash = [0u8; 32];
 
-    let mut versioned_hash_bytes = Sha256::digest(kzg_commitment.into_compressed());
+    let mut versioned_hash_bytes = Sha256::digest(kzg_commitment);
     versioned_hash_bytes[0] = VERSIONED_HASH_VERSION_KZG;
 
     versioned_hash.copy_from_slice(&versioned_hash_bytes);
@@ -87,7 +106,7 @@ impl KzgInfo {
     /// Here we use the size of the blob expected for 4844 (4096 elements * 32 bytes per element) and not
     /// `BYTES_PER_BLOB_ZK_SYNC` which is (4096 elements * 31 bytes per element)
     /// The zksync interpretation of the blob uses 31 byte fields so we can ensure they fit into a field element.
-    const SERIALIZED_SIZE: usize = EIP_4844_BYTES_PER_BLOB + 48 + 32 + 32 + 48 + 32 + 48;
+    pub const SERIALIZED_SIZE: usize = EIP_4844_BYTES_PER_BLOB + 48 + 32 + 32 + 48 + 32 + 48;
 
     /// Returns the bytes necessary for pubdata commitment part of batch commitments when blobs are used.
     /// Return format: opening point (16 bytes) || claimed value (32 bytes) || commitment (48 bytes)
@@ -264,18 +283,77 @@ impl KzgInfo {
     }
 }
 
+/// Decodes zkSync pubdata from a 4844 blob (e.g., one retrieved from a beacon node), checking that the blob
+/// matches the provided KZG commitment (e.g., one from the pubdata commitment published in `commitBatches` calldata).
+/// This is the inverse of [`KzgInfo::new()`]; the returned pubdata is right-padded with zeros
+/// to [`ZK_SYNC_BYTES_PER_BLOB`].
+pub fn decode_blob(
+    kzg_settings: &KzgSettings,
+    blob: &[u8],
+    kzg_commitment: &[u8; 48],
+) -> Result<Vec<u8>, BlobDecodeError> {
+    if blob.len() != EIP_4844_BYTES_PER_BLOB {
+        return Err(BlobDecodeError::InvalidLength(blob.len()));
+    }
+    // Conversion below panics on non-canonical field elements, so we check them beforehand.
+    for (i, element) in blob.chunks(32).enumerate() {
+        let mut repr = FrRepr::default();
+        repr.read_be(element).unwrap(); // the element has the correct length
+        if Fr::from_repr(repr).is_err() {
+            return Err(BlobDecodeError::NonCanonicalElement(i));
+        }
+    }
+
+    let pubdata = ethereum_4844_data_into_zksync_pubdata(blob);
+    // Re-encoding the pubdata both authenticates the blob and checks that decoding is correct.
+    let kzg_info = KzgInfo::new(kzg_settings, &pubdata);
+    if kzg_info.kzg_commitment != *kzg_commitment || kzg_info.blob[..] != *blob {
+        return Err(BlobDecodeError::CommitmentMismatch);
+    }
+    Ok(pubdata)
+}
+
 pub fn pubdata_to_blob_commitments(pubdata_input: &[u8], kzg_settings: &KzgSettings) -> [H256; 2] {
     assert!(
         pubdata_input.len() <= 2 * ZK_SYNC_BYTES_PER_BLOB,
         "Pubdata length exceeds size of 2 blobs"
     );
+    blob_commitments(&pubdata_to_kzg_info(pubdata_input, kzg_settings))
+}
+
+/// Computes KZG info for each blob of the pubdata. Blobs are processed in parallel on separate threads.
+pub fn pubdata_to_kzg_info(pubdata_input: &[u8], kzg_settings: &KzgSettings) -> Vec<KzgInfo> {
+    let blobs: Vec<_> = pubdata_input.chunks(ZK_SYNC_BYTES_PER_BLOB).collect();
+    if blobs.len() <= 1 {
+        return blobs
+            .into_iter()
+            .map(|blob| KzgInfo::new(kzg_settings, blob))
+            .collect();
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = blobs
+            .into_iter()
+            .map(|blob| scope.spawn(move || KzgInfo::new(kzg_settings, blob)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("KZG info computation panicked"))
+            .collect()
+    })
+}
+
+/// Returns blob commitments included into the L1 batch commitment given KZG info for pubdata blobs
+/// (e.g., computed with [`pubdata_to_kzg_info()`]).
+pub fn blob_commitments(kzg_info: &[KzgInfo]) -> [H256; 2] {
+    assert!(
+        kzg_info.len() <= 2,
+        "Pubdata length exceeds size of 2 blobs"
+    );
 
-    let blob_commitments = pubdata_input
-        .chunks(ZK_SYNC_BYTES_PER_BLOB)
-        .map(|blob| {
-            let kzg_info = KzgInfo::new(kzg_settings, blob);
-            H256(kzg_info.to_blob_commitment())
-        })
+    let blob_commitments = kzg_info
+        .iter()
+        .map(|kzg_info| H256(kzg_info.to_blob_commitment()))
         .collect::<Vec<_>>();
 
     // If length of `pubdata_input` is less than or equal to `ZK_SYNC_BYTES_PER_BLOB` (126976)
@@ -288,21 +366,24 @@ pub fn pubdata_to_blob_commitments(pubdata_input: &[u8], kzg_settings: &KzgSetti
 
 #[cfg(test)]
 mod tests {
+    use kzg::{verify_kzg_proof, verify_proof_poly};
     use serde::{Deserialize, Serialize};
     use serde_with::{self, serde_as};
-    use zkevm_test_harness_1_4_2::{
+    use zkevm_circuits::{
         boojum::pairing::{
             bls12_381::{Fr, FrRepr, G1Affine, G1Compressed},
             EncodedPoint,
         },
-        kzg::{verify_kzg_proof, verify_proof_poly, KzgSettings},
-        zkevm_circuits::eip_4844::{
+        eip_4844::{
             bitreverse, ethereum_4844_data_into_zksync_pubdata, fft,
             zksync_pubdata_into_monomial_form_poly,
         },
     };
 
-    use super::{KzgInfo, PrimeField};
+    use super::{
+        blob_commitments, decode_blob, pubdata_to_blob_commitments, pubdata_to_kzg_info,
+        BlobDecodeError, KzgInfo, KzgSettingsProvider, PrimeField, H256, ZK_SYNC_BYTES_PER_BLOB,
+    };
 
     #[serde_as]
     #[derive(Debug, Serialize, Deserialize)]
@@ -359,8 +440,7 @@ mod tests {
         let contents = std::fs::read_to_string(path).unwrap();
         let kzg_test: KzgTest = serde_json::from_str(&contents).unwrap();
 
-        let path = std::path::Path::new(&zksync_home).join("trusted_setup.json");
-        let kzg_settings = KzgSettings::new(path.to_str().unwrap());
+        let kzg_settings = KzgSettingsProvider::embedded().get().unwrap();
 
         let kzg_info = KzgInfo::new(&kzg_settings, &kzg_test.pubdata);
 
@@ -385,6 +465,10 @@ mod tests {
             hex::encode(kzg_info.versioned_hash),
             hex::encode(kzg_test.expected_outputs.versioned_hash)
         );
+        assert_eq!(
+            kzg_commitment_to_versioned_hash(&kzg_info.kzg_commitment),
+            kzg_info.versioned_hash
+        );
         assert_eq!(
             hex::encode(kzg_info.blob_proof),
             hex::encode(kzg_test.expected_outputs.blob_proof)
@@ -429,8 +513,7 @@ mod tests {
         let contents = std::fs::read_to_string(path).unwrap();
         let kzg_test: KzgTest = serde_json::from_str(&contents).unwrap();
 
-        let path = std::path::Path::new(&zksync_home).join("trusted_setup.json");
-        let kzg_settings = KzgSettings::new(path.to_str().unwrap());
+        let kzg_settings = KzgSettingsProvider::embedded().get().unwrap();
 
         let kzg_info = KzgInfo::new(&kzg_settings, &kzg_test.pubdata);
 
@@ -441,4 +524,59 @@ mod tests {
 
         assert_eq!(kzg_info, decoded_kzg_info);
     }
+
+    #[test]
+    fn computing_kzg_info_for_multiple_blobs() {
+        let kzg_settings = KzgSettingsProvider::embedded().get().unwrap();
+
+        let pubdata: Vec<u8> = (0..ZK_SYNC_BYTES_PER_BLOB * 3 / 2)
+            .map(|i| i as u8)
+            .collect();
+        let kzg_info = pubdata_to_kzg_info(&pubdata, &kzg_settings);
+        let expected_kzg_info: Vec<_> = pubdata
+            .chunks(ZK_SYNC_BYTES_PER_BLOB)
+            .map(|blob| KzgInfo::new(&kzg_settings, blob))
+            .collect();
+        assert_eq!(kzg_info, expected_kzg_info);
+
+        let commitments = blob_commitments(&kzg_info);
+        assert_eq!(
+            commitments,
+            pubdata_to_blob_commitments(&pubdata, &kzg_settings)
+        );
+        assert_eq!(
+            commitments[0],
+            H256(expected_kzg_info[0].to_blob_commitment())
+        );
+        assert_eq!(
+            commitments[1],
+            H256(expected_kzg_info[1].to_blob_commitment())
+        );
+    }
+
+    #[test]
+    fn decoding_blob() {
+        let kzg_settings = KzgSettingsProvider::embedded().get().unwrap();
+
+        let pubdata: Vec<u8> = (0..10_000).map(|i| (i * 7) as u8).collect();
+        let kzg_info = KzgInfo::new(&kzg_settings, &pubdata);
+        let decoded = decode_blob(&kzg_settings, &kzg_info.blob, &kzg_info.kzg_commitment).unwrap();
+        assert_eq!(decoded.len(), ZK_SYNC_BYTES_PER_BLOB);
+        assert_eq!(decoded[..pubdata.len()], pubdata);
+        assert!(decoded[pubdata.len()..].iter().all(|&byte| byte == 0));
+
+        let other_info = KzgInfo::new(&kzg_settings, &pubdata[1..]);
+        let err =
+            decode_blob(&kzg_settings, &kzg_info.blob, &other_info.kzg_commitment).unwrap_err();
+        assert!(matches!(err, BlobDecodeError::CommitmentMismatch), "{err}");
+
+        let mut non_canonical_blob = kzg_info.blob;
+        non_canonical_blob[32..64].copy_from_slice(&[0xff; 32]);
+        let err =
+            decode_blob(&kzg_settings, &non_canonical_blob, &kzg_info.kzg_commitment).unwrap_err();
+        assert!(
+            matches!(err, BlobDecodeError::NonCanonicalElement(1)),
+            "{err}"
+        );
+    }
 }
@@ -0,0 +1,285 @@
+//! Loading of the KZG trusted setup at runtime.
+
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+use zkevm_circuits::eip_4844::input::ELEMENTS_PER_4844_BLOCK;
+
+use super::KzgSettings;
+
+/// Trusted setup from the Ethereum KZG ceremony embedded into the binary. This is the same file that is used
+/// as the default `trusted_setup_path`, so there is a single copy of the setup in the repository.
+const EMBEDDED_TRUSTED_SETUP: &str = include_str!("../../../../../../../trusted_setup.json");
+/// Number of hex digits in a compressed G1 point.
+const G1_POINT_HEX_LEN: usize = 96;
+
+/// Settings loaded in this process, shared among all [`KzgSettingsProvider`]s with the same source.
+static LOADED_SETTINGS: Mutex<Vec<(KzgTrustedSetupSource, Arc<KzgSettings>)>> =
+    Mutex::new(Vec::new());
+
+/// Errors that can occur when loading the KZG trusted setup.
+#[derive(Debug, thiserror::Error)]
+pub enum KzgSetupError {
+    #[error("I/O error accessing KZG trusted setup file `{}`: {1}", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("malformed KZG trusted setup: {0}")]
+    Malformed(String),
+}
+
+/// Source of the KZG trusted setup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KzgTrustedSetupSource {
+    /// Setup embedded into the binary.
+    Embedded,
+    /// File with the setup in one of standard ceremony formats: JSON used by Ethereum consensus specs
+    /// (only `g1_lagrange` points are used), or the text format used by `c-kzg`.
+    File(PathBuf),
+}
+
+impl KzgTrustedSetupSource {
+    fn load(&self) -> Result<KzgSettings, KzgSetupError> {
+        let (contents, path) = match self {
+            Self::Embedded => (Cow::Borrowed(EMBEDDED_TRUSTED_SETUP), None),
+            Self::File(path) => {
+                let contents =
+                    fs::read_to_string(path).map_err(|err| KzgSetupError::Io(path.clone(), err))?;
+                (Cow::Owned(contents), Some(path))
+            }
+        };
+        let setup = TrustedSetup::parse(&contents)?;
+
+        // `KzgSettings` can only be loaded from a JSON file; other setups are converted to this format
+        // in a temporary file.
+        match path.and_then(|path| path.to_str()) {
+            Some(path) if setup.is_json => Ok(KzgSettings::new(path)),
+            _ => setup.load_via_temp_file(),
+        }
+    }
+}
+
+/// Parsed trusted setup.
+#[derive(Debug, Serialize, Deserialize)]
+struct TrustedSetup {
+    /// G1 points in the Lagrange form, each as a `0x`-prefixed hex string.
+    g1_lagrange: Vec<String>,
+    #[serde(skip)]
+    is_json: bool,
+}
+
+impl TrustedSetup {
+    fn parse(contents: &str) -> Result<Self, KzgSetupError> {
+        let mut setup = if contents.trim_start().starts_with('{') {
+            let mut setup: Self = serde_json::from_str(contents)
+                .map_err(|err| KzgSetupError::Malformed(err.to_string()))?;
+            setup.is_json = true;
+            setup
+        } else {
+            Self::parse_text(contents)?
+        };
+
+        if setup.g1_lagrange.len() != ELEMENTS_PER_4844_BLOCK {
+            return Err(KzgSetupError::Malformed(format!(
+                "expected {ELEMENTS_PER_4844_BLOCK} G1 points, got {}",
+                setup.g1_lagrange.len()
+            )));
+        }
+        for (i, point) in setup.g1_lagrange.iter_mut().enumerate() {
+            let digits = point.strip_prefix("0x").unwrap_or(point.as_str());
+            let is_valid = digits.len() == G1_POINT_HEX_LEN
+                && digits.bytes().all(|byte| byte.is_ascii_hexdigit());
+            if !is_valid {
+                return Err(KzgSetupError::Malformed(format!(
+                    "G1 point #{i} is not a hex-encoded compressed point"
+                )));
+            }
+            if !point.starts_with("0x") {
+                *point = format!("0x{point}");
+            }
+        }
+        Ok(setup)
+    }
+
+    /// Parses the setup in the `c-kzg` text format: number of G1 points, number of G2 points, G1 points
+    /// in the Lagrange form, G2 points in the monomial form (the latter are not used).
+    fn parse_text(contents: &str) -> Result<Self, KzgSetupError> {
+        let mut lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        let mut parse_count = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.parse::<usize>().ok())
+                .ok_or_else(|| KzgSetupError::Malformed(format!("missing number of {name} points")))
+        };
+        let g1_count = parse_count("G1")?;
+        parse_count("G2")?;
+
+        let g1_lagrange: Vec<_> = lines.take(g1_count).map(str::to_owned).collect();
+        Ok(Self {
+            g1_lagrange,
+            is_json: false,
+        })
+    }
+
+    fn load_via_temp_file(&self) -> Result<KzgSettings, KzgSetupError> {
+        static FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let file_name = format!(
+            "zksync_kzg_trusted_setup_{}_{}.json",
+            std::process::id(),
+            FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = std::env::temp_dir().join(file_name);
+        let contents = serde_json::to_string(self).expect("failed serializing trusted setup");
+        fs::write(&path, contents).map_err(|err| KzgSetupError::Io(path.clone(), err))?;
+
+        let path_str = path.to_str().ok_or_else(|| {
+            KzgSetupError::Malformed(format!("non-UTF-8 temporary path: {}", path.display()))
+        });
+        let settings = path_str.map(KzgSettings::new);
+        fs::remove_file(&path).ok();
+        settings
+    }
+}
+
+/// Provider of [`KzgSettings`] loading the trusted setup lazily, on the first request. Loaded settings are shared
+/// in the entire process, so the setup is loaded at most once, and only if some component actually needs it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KzgSettingsProvider {
+    source: KzgTrustedSetupSource,
+}
+
+impl KzgSettingsProvider {
+    pub fn new(source: KzgTrustedSetupSource) -> Self {
+        Self { source }
+    }
+
+    /// Creates a provider loading the trusted setup from the specified file, or using the embedded setup
+    /// if the path is not specified.
+    pub fn from_path(trusted_setup_path: Option<impl AsRef<Path>>) -> Self {
+        Self::new(match trusted_setup_path {
+            Some(path) => KzgTrustedSetupSource::File(path.as_ref().to_owned()),
+            None => KzgTrustedSetupSource::Embedded,
+        })
+    }
+
+    /// Creates a provider using the embedded trusted setup.
+    pub fn embedded() -> Self {
+        Self::new(KzgTrustedSetupSource::Embedded)
+    }
+
+    pub fn source(&self) -> &KzgTrustedSetupSource {
+        &self.source
+    }
+
+    /// Returns KZG settings, loading them if necessary. Loading is CPU-heavy and blocks the current thread.
+    pub fn get(&self) -> Result<Arc<KzgSettings>, KzgSetupError> {
+        // The lock is held while loading, so that the same setup is never loaded concurrently.
+        let mut loaded = LOADED_SETTINGS
+            .lock()
+            .expect("loaded KZG settings are poisoned");
+        let existing = loaded.iter().find(|(source, _)| *source == self.source);
+        if let Some((_, settings)) = existing {
+            return Ok(settings.clone());
+        }
+
+        let settings = Arc::new(self.source.load()?);
+        loaded.push((self.source.clone(), settings.clone()));
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i_executor::commit::kzg::KzgInfo;
+
+    fn trusted_setup_path() -> PathBuf {
+        let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
+        Path::new(&zksync_home).join("trusted_setup.json")
+    }
+
+    fn assert_same_settings(settings: &KzgSettings, expected: &KzgSettings) {
+        let pubdata: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+        assert_eq!(
+            KzgInfo::new(settings, &pubdata),
+            KzgInfo::new(expected, &pubdata)
+        );
+    }
+
+    #[test]
+    fn settings_are_shared() {
+        let provider = KzgSettingsProvider::from_path(Some(trusted_setup_path()));
+        let settings = provider.get().unwrap();
+        let other_settings = provider.clone().get().unwrap();
+        assert!(Arc::ptr_eq(&settings, &other_settings));
+
+        let embedded = KzgSettingsProvider::from_path(None::<&str>);
+        assert_eq!(embedded, KzgSettingsProvider::embedded());
+        let embedded_settings = embedded.get().unwrap();
+        assert!(!Arc::ptr_eq(&settings, &embedded_settings));
+        assert!(Arc::ptr_eq(
+            &embedded_settings,
+            &KzgSettingsProvider::embedded().get().unwrap()
+        ));
+    }
+
+    #[test]
+    fn loading_trusted_setup_in_text_format() {
+        let setup = TrustedSetup::parse(EMBEDDED_TRUSTED_SETUP).unwrap();
+        let mut text = format!("{ELEMENTS_PER_4844_BLOCK}\n65\n");
+        for point in &setup.g1_lagrange {
+            text += &point[2..];
+            text.push('\n');
+        }
+        // G2 points are ignored
+        for _ in 0..65 {
+            text += &"00".repeat(96);
+            text.push('\n');
+        }
+        let parsed = TrustedSetup::parse(&text).unwrap();
+        assert!(!parsed.is_json);
+        assert_eq!(parsed.g1_lagrange, setup.g1_lagrange);
+
+        let path = std::env::temp_dir().join(format!(
+            "zksync_trusted_setup_test_{}.txt",
+            std::process::id()
+        ));
+        fs::write(&path, text).unwrap();
+        let settings = KzgSettingsProvider::from_path(Some(&path)).get();
+        fs::remove_file(&path).ok();
+        let expected = KzgSettingsProvider::embedded().get().unwrap();
+        assert_same_settings(&settings.unwrap(), &expected);
+    }
+
+    #[test]
+    fn loading_malformed_trusted_setup() {
+        let err = TrustedSetup::parse("{}").unwrap_err();
+        assert!(matches!(err, KzgSetupError::Malformed(_)), "{err}");
+
+        let err = TrustedSetup::parse("4096").unwrap_err();
+        assert!(err.to_string().contains("number of G2 points"), "{err}");
+
+        let err = TrustedSetup::parse("4096\n65\n0x01").unwrap_err();
+        assert!(err.to_string().contains("G1 points"), "{err}");
+
+        let mut setup = TrustedSetup::parse(EMBEDDED_TRUSTED_SETUP).unwrap();
+        setup.g1_lagrange[1] = "0xzz".to_owned();
+        let json = serde_json::to_string(&setup).unwrap();
+        let err = TrustedSetup::parse(&json).unwrap_err();
+        assert!(err.to_string().contains("G1 point #1"), "{err}");
+
+        let provider = KzgSettingsProvider::from_path(Some("/non/existing/setup.json"));
+        let err = provider.get().unwrap_err();
+        assert!(matches!(err, KzgSetupError::Io(..)), "{err}");
+    }
+}
@@ -1 +1,2 @@
 pub mod kzg;
+pub mod pubdata;
@@ -0,0 +1,562 @@
+//! Decoding of the L1 batch pubdata published on L1 (either in calldata or in blobs).
+//!
+//! Decoded pubdata is sufficient to reconstruct the state of the chain from L1 alone: state diffs
+//! of each batch are applied on top of the state after the previous batch.
+
+use std::convert::TryInto;
+
+use zksync_types::{
+    commitment::SerializeCommitment,
+    l2_to_l1_log::L2ToL1Log,
+    pubdata_da::PubdataDA,
+    writes::{compression::COMPRESSION_VERSION_NUMBER, BYTES_PER_DERIVED_KEY},
+    ProtocolVersionId, H256, U256,
+};
+
+use crate::i_executor::{
+    encoding::EncodingVersion,
+    structures::{CommitBatchInfo, PubdataPayload, PubdataPayloadError},
+};
+
+/// Errors that can occur when decoding pubdata.
+#[derive(Debug, thiserror::Error)]
+pub enum PubdataDecodeError {
+    #[error("unexpected end of pubdata at offset {offset}: expected {expected} more bytes")]
+    UnexpectedEnd { offset: usize, expected: usize },
+    #[error("unsupported state diffs compression version: {0}")]
+    UnsupportedCompressionVersion(u8),
+    #[error("unsupported enumeration index size: {0}")]
+    UnsupportedEnumerationIndexSize(u8),
+    #[error("invalid compressed value metadata byte {0:#04x}")]
+    InvalidValueMetadata(u8),
+    #[error("{0} trailing bytes after the end of pubdata")]
+    TrailingBytes(usize),
+    #[error("pubdata is not published in pre-Boojum batches")]
+    PreBoojum,
+    #[error("pubdata is not published in calldata (pubdata DA: {0:?})")]
+    NotInCalldata(PubdataDA),
+    #[error("invalid pubdata payload: {0}")]
+    Payload(#[from] PubdataPayloadError),
+}
+
+/// Key of a storage slot updated by a state diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateDiffKey {
+    /// Slot written for the first time, identified by its derived key (`blake2s(address, key)`).
+    Initial(H256),
+    /// Slot written previously, identified by its enumeration index in the Merkle tree.
+    Repeated(u64),
+}
+
+/// Compressed update of a storage slot value. The final value can only be restored given the previous value
+/// of the slot (which is zero for initial writes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueUpdate {
+    /// Value is replaced with the specified one.
+    Transform(U256),
+    /// Value is incremented by the specified amount (wrapping).
+    Add(U256),
+    /// Value is decremented by the specified amount (wrapping).
+    Sub(U256),
+}
+
+impl ValueUpdate {
+    /// Applies this update to the previous value of the slot.
+    pub fn apply(self, prev_value: U256) -> U256 {
+        match self {
+            Self::Transform(value) => value,
+            Self::Add(diff) => prev_value.overflowing_add(diff).0,
+            Self::Sub(diff) => prev_value.overflowing_sub(diff).0,
+        }
+    }
+}
+
+/// Single decoded state diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateDiff {
+    pub key: StateDiffKey,
+    pub update: ValueUpdate,
+}
+
+/// Pubdata of an L1 batch decoded into its components.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecodedPubdata {
+    pub user_l2_to_l1_logs: Vec<L2ToL1Log>,
+    pub l2_to_l1_messages: Vec<Vec<u8>>,
+    pub published_bytecodes: Vec<Vec<u8>>,
+    /// State diffs in the order they are published: initial writes followed by repeated writes.
+    pub state_diffs: Vec<StateDiff>,
+}
+
+#[derive(Debug)]
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read(&mut self, len: usize) -> Result<&'a [u8], PubdataDecodeError> {
+        let remaining = self.data.len() - self.offset;
+        if remaining < len {
+            return Err(PubdataDecodeError::UnexpectedEnd {
+                offset: self.offset,
+                expected: len - remaining,
+            });
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, PubdataDecodeError> {
+        Ok(self.read(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, PubdataDecodeError> {
+        Ok(u16::from_be_bytes(self.read(2)?.try_into().unwrap()))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, PubdataDecodeError> {
+        Ok(u32::from_be_bytes(self.read(4)?.try_into().unwrap()))
+    }
+
+    fn read_be_uint(&mut self, len: usize) -> Result<u64, PubdataDecodeError> {
+        let bytes = self.read(len)?;
+        let mut buffer = [0_u8; 8];
+        buffer[8 - len..].copy_from_slice(bytes);
+        Ok(u64::from_be_bytes(buffer))
+    }
+
+    fn read_length_prefixed_items(&mut self) -> Result<Vec<Vec<u8>>, PubdataDecodeError> {
+        let count = self.read_u32()?;
+        (0..count)
+            .map(|_| {
+                let len = self.read_u32()? as usize;
+                Ok(self.read(len)?.to_vec())
+            })
+            .collect()
+    }
+
+    fn read_value_update(&mut self) -> Result<ValueUpdate, PubdataDecodeError> {
+        let metadata = self.read_u8()?;
+        let (len, operation_id) = ((metadata >> 3) as usize, metadata & 7);
+        if operation_id == 0 {
+            // Uncompressed value; its length is not encoded in the metadata byte.
+            if len != 0 {
+                return Err(PubdataDecodeError::InvalidValueMetadata(metadata));
+            }
+            return Ok(ValueUpdate::Transform(U256::from_big_endian(
+                self.read(32)?,
+            )));
+        }
+
+        if len > 32 {
+            return Err(PubdataDecodeError::InvalidValueMetadata(metadata));
+        }
+        let value = U256::from_big_endian(self.read(len)?);
+        Ok(match operation_id {
+            1 => ValueUpdate::Add(value),
+            2 => ValueUpdate::Sub(value),
+            3 => ValueUpdate::Transform(value),
+            _ => return Err(PubdataDecodeError::InvalidValueMetadata(metadata)),
+        })
+    }
+
+    fn read_state_diffs(&mut self) -> Result<Vec<StateDiff>, PubdataDecodeError> {
+        let version = self.read_u8()?;
+        if version != COMPRESSION_VERSION_NUMBER {
+            return Err(PubdataDecodeError::UnsupportedCompressionVersion(version));
+        }
+        let compressed_len = self.read_be_uint(3)? as usize;
+        let enumeration_index_size = self.read_u8()?;
+        if !(1..=8).contains(&enumeration_index_size) {
+            return Err(PubdataDecodeError::UnsupportedEnumerationIndexSize(
+                enumeration_index_size,
+            ));
+        }
+
+        let mut diffs_reader = Reader {
+            data: self.read(compressed_len)?,
+            offset: 0,
+        };
+        let initial_writes_count = diffs_reader.read_u16()?;
+        let mut state_diffs = Vec::with_capacity(initial_writes_count.into());
+        for _ in 0..initial_writes_count {
+            let derived_key = diffs_reader.read(BYTES_PER_DERIVED_KEY.into())?;
+            state_diffs.push(StateDiff {
+                key: StateDiffKey::Initial(H256::from_slice(derived_key)),
+                update: diffs_reader.read_value_update()?,
+            });
+        }
+        while !diffs_reader.is_finished() {
+            let index = diffs_reader.read_be_uint(enumeration_index_size.into())?;
+            state_diffs.push(StateDiff {
+                key: StateDiffKey::Repeated(index),
+                update: diffs_reader.read_value_update()?,
+            });
+        }
+        Ok(state_diffs)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.offset == self.data.len()
+    }
+}
+
+/// Decodes pubdata of a post-boojum L1 batch, i.e., `totalL2ToL1Pubdata` without the pubdata source byte
+/// (and without the blob commitment for calldata DA). For blob DA, use [`decode_blobs_pubdata()`].
+pub fn decode_pubdata(pubdata: &[u8]) -> Result<DecodedPubdata, PubdataDecodeError> {
+    decode_pubdata_inner(pubdata, false)
+}
+
+/// Decodes pubdata of a post-boojum L1 batch published in blobs. `pubdata` is the concatenation of pubdata
+/// decoded from all blobs of the batch (e.g., using [`decode_blob()`](super::kzg::decode_blob())); unlike
+/// [`decode_pubdata()`], trailing zero padding is allowed.
+pub fn decode_blobs_pubdata(pubdata: &[u8]) -> Result<DecodedPubdata, PubdataDecodeError> {
+    decode_pubdata_inner(pubdata, true)
+}
+
+fn decode_pubdata_inner(
+    pubdata: &[u8],
+    allow_padding: bool,
+) -> Result<DecodedPubdata, PubdataDecodeError> {
+    let mut reader = Reader {
+        data: pubdata,
+        offset: 0,
+    };
+
+    let logs_count = reader.read_u32()?;
+    let user_l2_to_l1_logs = (0..logs_count)
+        .map(|_| {
+            Ok(L2ToL1Log::from_slice(
+                reader.read(L2ToL1Log::SERIALIZED_SIZE)?,
+            ))
+        })
+        .collect::<Result<_, PubdataDecodeError>>()?;
+    let l2_to_l1_messages = reader.read_length_prefixed_items()?;
+    let published_bytecodes = reader.read_length_prefixed_items()?;
+    let state_diffs = reader.read_state_diffs()?;
+
+    let remaining = &pubdata[reader.offset..];
+    let is_padding = allow_padding && remaining.iter().all(|&byte| byte == 0);
+    if !reader.is_finished() && !is_padding {
+        return Err(PubdataDecodeError::TrailingBytes(
+            pubdata.len() - reader.offset,
+        ));
+    }
+    Ok(DecodedPubdata {
+        user_l2_to_l1_logs,
+        l2_to_l1_messages,
+        published_bytecodes,
+        state_diffs,
+    })
+}
+
+/// Decodes pubdata published in calldata of the `commitBatches` call for an L1 batch with the specified
+/// protocol version. Pubdata published in blobs or in an external DA layer is not contained in the calldata;
+/// it must be retrieved separately and decoded using [`decode_pubdata()`].
+pub fn decode_commit_pubdata(
+    info: &CommitBatchInfo,
+    protocol_version: ProtocolVersionId,
+) -> Result<DecodedPubdata, PubdataDecodeError> {
+    match EncodingVersion::from(protocol_version) {
+        EncodingVersion::PreBoojum => Err(PubdataDecodeError::PreBoojum),
+        // Pubdata is published as is, without the pubdata source byte
+        EncodingVersion::Boojum => {
+            let pubdata = info
+                .pubdata
+                .as_deref()
+                .ok_or(PubdataDecodeError::PreBoojum)?;
+            decode_pubdata(pubdata)
+        }
+        EncodingVersion::Post1_4_2 => match info.pubdata_payload()? {
+            PubdataPayload::Calldata { pubdata, .. } => decode_pubdata(pubdata),
+            PubdataPayload::Empty(pubdata_da) => Err(PubdataDecodeError::NotInCalldata(pubdata_da)),
+            PubdataPayload::Blobs(_) => Err(PubdataDecodeError::NotInCalldata(PubdataDA::Blobs)),
+            PubdataPayload::Custom { .. } => {
+                Err(PubdataDecodeError::NotInCalldata(PubdataDA::Custom))
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use zksync_types::{
+        commitment::L1BatchWithMetadata,
+        l2_to_l1_log::UserL2ToL1Log,
+        writes::{compress_state_diffs, StateDiffRecord},
+        Address, L1BatchNumber,
+    };
+
+    use super::*;
+    use crate::i_executor::{structures::BatchCommitments, testonly::mock_l1_batch};
+
+    fn encode_pubdata(
+        logs: &[L2ToL1Log],
+        messages: &[Vec<u8>],
+        bytecodes: &[Vec<u8>],
+        state_diffs: Vec<StateDiffRecord>,
+    ) -> Vec<u8> {
+        let mut pubdata = (logs.len() as u32).to_be_bytes().to_vec();
+        for log in logs {
+            pubdata.extend(log.to_bytes());
+        }
+        for items in [messages, bytecodes] {
+            pubdata.extend((items.len() as u32).to_be_bytes());
+            for item in items {
+                pubdata.extend((item.len() as u32).to_be_bytes());
+                pubdata.extend(item);
+            }
+        }
+        pubdata.extend(compress_state_diffs(state_diffs));
+        pubdata
+    }
+
+    #[test]
+    fn decoding_pubdata() {
+        let log = L2ToL1Log {
+            shard_id: 0,
+            is_service: true,
+            tx_number_in_block: 3,
+            sender: Address::repeat_byte(1),
+            key: H256::repeat_byte(2),
+            value: H256::repeat_byte(3),
+        };
+        let state_diffs = vec![
+            StateDiffRecord {
+                address: Address::repeat_byte(1),
+                key: 1.into(),
+                derived_key: [4; 32],
+                enumeration_index: 0,
+                initial_value: 0.into(),
+                final_value: U256::MAX,
+            },
+            StateDiffRecord {
+                address: Address::repeat_byte(2),
+                key: 2.into(),
+                derived_key: [5; 32],
+                enumeration_index: 10,
+                initial_value: 1_000.into(),
+                final_value: 1_001.into(),
+            },
+            StateDiffRecord {
+                address: Address::repeat_byte(3),
+                key: 3.into(),
+                derived_key: [6; 32],
+                enumeration_index: 20,
+                initial_value: 1_000_000.into(),
+                final_value: 1.into(),
+            },
+        ];
+        let pubdata = encode_pubdata(
+            &[log.clone()],
+            &[vec![1, 2, 3]],
+            &[vec![0; 64]],
+            state_diffs.clone(),
+        );
+
+        let decoded = decode_pubdata(&pubdata).unwrap();
+        assert_eq!(decoded.user_l2_to_l1_logs, [log]);
+        assert_eq!(decoded.l2_to_l1_messages, [vec![1, 2, 3]]);
+        assert_eq!(decoded.published_bytecodes, [vec![0; 64]]);
+        assert_eq!(decoded.state_diffs.len(), state_diffs.len());
+        assert_eq!(
+            decoded.state_diffs[0].key,
+            StateDiffKey::Initial(H256::repeat_byte(4))
+        );
+        assert_eq!(decoded.state_diffs[1].key, StateDiffKey::Repeated(10));
+        for (diff, record) in decoded.state_diffs.iter().zip(&state_diffs) {
+            assert_eq!(diff.update.apply(record.initial_value), record.final_value);
+        }
+
+        let err = decode_pubdata(&pubdata[..pubdata.len() - 1]).unwrap_err();
+        assert!(
+            matches!(err, PubdataDecodeError::UnexpectedEnd { .. }),
+            "{err}"
+        );
+        let mut pubdata_with_trailing_bytes = pubdata;
+        pubdata_with_trailing_bytes.push(0);
+        let err = decode_pubdata(&pubdata_with_trailing_bytes).unwrap_err();
+        assert!(matches!(err, PubdataDecodeError::TrailingBytes(1)), "{err}");
+        let decoded_with_padding = decode_blobs_pubdata(&pubdata_with_trailing_bytes).unwrap();
+        assert_eq!(decoded_with_padding, decoded);
+
+        pubdata_with_trailing_bytes.push(1);
+        let err = decode_blobs_pubdata(&pubdata_with_trailing_bytes).unwrap_err();
+        assert!(matches!(err, PubdataDecodeError::TrailingBytes(2)), "{err}");
+    }
+
+    fn gen_bytes(rng: &mut impl Rng, len: usize) -> Vec<u8> {
+        (0..len).map(|_| rng.gen()).collect()
+    }
+
+    fn gen_state_diff(rng: &mut impl Rng) -> StateDiffRecord {
+        let enumeration_index = if rng.gen() {
+            0
+        } else {
+            rng.gen_range(1..=u64::from(u32::MAX))
+        };
+        let initial_value = if enumeration_index == 0 {
+            U256::zero()
+        } else {
+            U256(rng.gen())
+        };
+        // Small differences are compressed as additions or subtractions
+        let final_value = match rng.gen_range(0..3) {
+            0 => U256(rng.gen()),
+            1 => initial_value.overflowing_add(rng.gen::<u32>().into()).0,
+            _ => initial_value.overflowing_sub(rng.gen::<u32>().into()).0,
+        };
+        StateDiffRecord {
+            address: Address::from(rng.gen::<[u8; 20]>()),
+            key: U256(rng.gen()),
+            derived_key: rng.gen(),
+            enumeration_index,
+            initial_value,
+            final_value,
+        }
+    }
+
+    /// Generates an L1 batch with random pubdata components.
+    fn gen_l1_batch(rng: &mut impl Rng) -> (L1BatchWithMetadata, Vec<StateDiffRecord>) {
+        let mut l1_batch = mock_l1_batch(1, ProtocolVersionId::latest());
+        l1_batch.header.pubdata_input = None;
+        l1_batch.header.l2_to_l1_logs = (0..rng.gen_range(0..5))
+            .map(|_| {
+                UserL2ToL1Log(L2ToL1Log {
+                    shard_id: rng.gen(),
+                    is_service: rng.gen(),
+                    tx_number_in_block: rng.gen(),
+                    sender: Address::from(rng.gen::<[u8; 20]>()),
+                    key: H256(rng.gen()),
+                    value: H256(rng.gen()),
+                })
+            })
+            .collect();
+        l1_batch.header.l2_to_l1_messages = (0..rng.gen_range(0..5))
+            .map(|_| {
+                let len = rng.gen_range(0..200);
+                gen_bytes(rng, len)
+            })
+            .collect();
+        l1_batch.raw_published_factory_deps = (0..rng.gen_range(0..3))
+            .map(|_| {
+                let len = rng.gen_range(1..10) * 32;
+                gen_bytes(rng, len)
+            })
+            .collect();
+
+        let state_diffs: Vec<_> = (0..rng.gen_range(0..20))
+            .map(|_| gen_state_diff(rng))
+            .collect();
+        l1_batch.metadata.state_diffs_compressed = compress_state_diffs(state_diffs.clone());
+        (l1_batch, state_diffs)
+    }
+
+    fn assert_decoded_pubdata(
+        decoded: &DecodedPubdata,
+        l1_batch: &L1BatchWithMetadata,
+        mut state_diffs: Vec<StateDiffRecord>,
+    ) {
+        let logs: Vec<_> = l1_batch
+            .header
+            .l2_to_l1_logs
+            .iter()
+            .map(|log| log.0.clone())
+            .collect();
+        assert_eq!(decoded.user_l2_to_l1_logs, logs);
+        assert_eq!(decoded.l2_to_l1_messages, l1_batch.header.l2_to_l1_messages);
+        assert_eq!(
+            decoded.published_bytecodes,
+            l1_batch.raw_published_factory_deps
+        );
+
+        // State diffs are published sorted by the slot, with initial writes going first.
+        state_diffs.sort_by_key(|diff| (diff.address, diff.key));
+        state_diffs.sort_by_key(|diff| diff.enumeration_index != 0);
+        assert_eq!(decoded.state_diffs.len(), state_diffs.len());
+        for (diff, record) in decoded.state_diffs.iter().zip(&state_diffs) {
+            let expected_key = if record.enumeration_index == 0 {
+                StateDiffKey::Initial(H256(record.derived_key))
+            } else {
+                StateDiffKey::Repeated(record.enumeration_index)
+            };
+            assert_eq!(diff.key, expected_key);
+            assert_eq!(diff.update.apply(record.initial_value), record.final_value);
+        }
+    }
+
+    #[test]
+    fn decoding_constructed_pubdata() {
+        let rng = &mut StdRng::seed_from_u64(123);
+        for _ in 0..100 {
+            let (l1_batch, state_diffs) = gen_l1_batch(rng);
+            let pubdata = l1_batch.construct_pubdata();
+            let decoded = decode_pubdata(&pubdata).unwrap();
+            assert_decoded_pubdata(&decoded, &l1_batch, state_diffs);
+        }
+    }
+
+    fn commit_info_with_pubdata(pubdata: Option<Vec<u8>>) -> CommitBatchInfo {
+        CommitBatchInfo {
+            batch_number: L1BatchNumber(1),
+            timestamp: 1_700_000_000,
+            index_repeated_storage_changes: 10,
+            new_state_root: H256::zero(),
+            number_of_layer1_txs: 0.into(),
+            priority_operations_hash: H256::zero(),
+            commitments: BatchCommitments::PostBoojum {
+                bootloader_heap_initial_contents_hash: H256::zero(),
+                events_queue_state_hash: H256::zero(),
+                system_logs: vec![],
+            },
+            pubdata,
+        }
+    }
+
+    #[test]
+    fn decoding_commit_pubdata_for_different_versions() {
+        let rng = &mut StdRng::seed_from_u64(456);
+        let (l1_batch, state_diffs) = gen_l1_batch(rng);
+        let pubdata = l1_batch.construct_pubdata();
+
+        let info = commit_info_with_pubdata(Some(pubdata.clone()));
+        let decoded = decode_commit_pubdata(&info, ProtocolVersionId::Version20).unwrap();
+        assert_decoded_pubdata(&decoded, &l1_batch, state_diffs.clone());
+
+        let calldata_pubdata = std::iter::once(0)
+            .chain(pubdata)
+            .chain([0; 32]) // blob commitment
+            .collect();
+        let info = commit_info_with_pubdata(Some(calldata_pubdata));
+        let decoded = decode_commit_pubdata(&info, ProtocolVersionId::Version21).unwrap();
+        assert_decoded_pubdata(&decoded, &l1_batch, state_diffs);
+
+        let err = decode_commit_pubdata(&info, ProtocolVersionId::Version17).unwrap_err();
+        assert!(matches!(err, PubdataDecodeError::PreBoojum), "{err}");
+
+        let info = commit_info_with_pubdata(Some(vec![1; 145]));
+        let err = decode_commit_pubdata(&info, ProtocolVersionId::Version21).unwrap_err();
+        assert!(
+            matches!(err, PubdataDecodeError::NotInCalldata(PubdataDA::Blobs)),
+            "{err}"
+        );
+        let info = commit_info_with_pubdata(Some(vec![0]));
+        let err = decode_commit_pubdata(&info, ProtocolVersionId::Version21).unwrap_err();
+        assert!(
+            matches!(err, PubdataDecodeError::NotInCalldata(PubdataDA::Calldata)),
+            "{err}"
+        );
+        let info = commit_info_with_pubdata(Some(vec![]));
+        let err = decode_commit_pubdata(&info, ProtocolVersionId::Version21).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                PubdataDecodeError::Payload(PubdataPayloadError::MissingSource)
+            ),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Encoding for Boojum protocol versions before 1.4.2.
+
+use zksync_types::{
+    commitment::{serialize_commitments, L1BatchCommitmentMode, L1BatchWithMetadata},
+    pubdata_da::PubdataDA,
+};
+
+use super::{EncodingVersion, ExecutorEncoder};
+use crate::i_executor::structures::{
+    BatchCommitments, CommitBatchInfoBuilder, CommitBatchInfoError,
+};
+
+/// Encoder for [`EncodingVersion::Boojum`]. Pubdata (`totalL2ToL1Pubdata`) is published in calldata as is;
+/// neither blobs nor custom DA are supported.
+#[derive(Debug)]
+pub struct BoojumEncoder;
+
+/// Prepares commitments shared by all post-Boojum encodings.
+pub(super) fn post_boojum_commitments(
+    l1_batch: &L1BatchWithMetadata,
+) -> Result<BatchCommitments, CommitBatchInfoError> {
+    let number = l1_batch.header.number;
+    let metadata = &l1_batch.metadata;
+    Ok(BatchCommitments::PostBoojum {
+        bootloader_heap_initial_contents_hash: metadata
+            .bootloader_initial_content_commitment
+            .ok_or(CommitBatchInfoError::MissingBootloaderInitialContentCommitment(number))?,
+        events_queue_state_hash: metadata
+            .events_queue_commitment
+            .ok_or(CommitBatchInfoError::MissingEventsQueueCommitment(number))?,
+        system_logs: serialize_commitments(&l1_batch.header.system_logs),
+    })
+}
+
+impl ExecutorEncoder for BoojumEncoder {
+    fn version(&self) -> EncodingVersion {
+        EncodingVersion::Boojum
+    }
+
+    fn batch_commitments(
+        &self,
+        l1_batch: &L1BatchWithMetadata,
+    ) -> Result<BatchCommitments, CommitBatchInfoError> {
+        post_boojum_commitments(l1_batch)
+    }
+
+    fn validate_pubdata(
+        &self,
+        input: &CommitBatchInfoBuilder<'_>,
+    ) -> Result<(), CommitBatchInfoError> {
+        if input.pubdata_da() == PubdataDA::Custom {
+            return Err(CommitBatchInfoError::CustomDAUnsupported(
+                input.l1_batch_number(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn encode_pubdata(
+        &self,
+        input: &CommitBatchInfoBuilder<'_>,
+    ) -> Result<Option<Vec<u8>>, CommitBatchInfoError> {
+        self.validate_pubdata(input)?;
+        Ok(Some(match input.commitment_mode() {
+            // Validium commitments contain no pubdata
+            L1BatchCommitmentMode::Validium => vec![],
+            // `totalL2ToL1Pubdata` without pubdata source byte
+            L1BatchCommitmentMode::Rollup => input.pubdata().into_owned(),
+        }))
+    }
+}
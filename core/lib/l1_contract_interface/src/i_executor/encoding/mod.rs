@@ -0,0 +1,116 @@
+//! Protocol-version-dependent encoding of `IExecutor.sol` structures.
+//!
+//! Each range of protocol versions with a distinct encoding has a dedicated [`ExecutorEncoder`] implementation.
+//! The encoder for an L1 batch is selected based on its protocol version via [`EncodingVersion`]. Encodings
+//! for supported versions are pinned by fixture tests, so a new protocol upgrade should add a new encoder
+//! rather than change an existing one.
+
+use std::fmt;
+
+use zksync_types::{
+    commitment::L1BatchWithMetadata,
+    ethabi::{ParamType, Token},
+    ProtocolVersionId,
+};
+
+pub use self::{boojum::BoojumEncoder, post_1_4_2::Post1_4_2Encoder, pre_boojum::PreBoojumEncoder};
+use crate::i_executor::structures::{
+    BatchCommitments, CommitBatchInfo, CommitBatchInfoBuilder, CommitBatchInfoError,
+    StoredBatchInfo,
+};
+
+mod boojum;
+mod post_1_4_2;
+mod pre_boojum;
+#[cfg(test)]
+mod tests;
+
+/// Range of protocol versions sharing the same encoding of `IExecutor.sol` structures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EncodingVersion {
+    /// Protocol versions before Boojum (up to 17).
+    PreBoojum,
+    /// Boojum protocol versions before 1.4.2 (18 to 20). Pubdata is published in calldata
+    /// without the pubdata source byte.
+    Boojum,
+    /// Protocol versions starting from 1.4.2 (21 and later). Pubdata is prefixed with the pubdata source byte
+    /// and can be published in calldata, in blobs or in an external DA layer.
+    Post1_4_2,
+}
+
+impl EncodingVersion {
+    /// All encoding versions in the ascending order.
+    pub const ALL: [Self; 3] = [Self::PreBoojum, Self::Boojum, Self::Post1_4_2];
+
+    /// Returns the encoding version for the L1 batch. L1 batches without a protocol version are treated
+    /// as having [the last potentially undefined version](ProtocolVersionId::last_potentially_undefined()).
+    pub fn for_l1_batch(l1_batch: &L1BatchWithMetadata) -> Self {
+        let protocol_version = l1_batch
+            .header
+            .protocol_version
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        protocol_version.into()
+    }
+
+    /// Returns the encoder for this version.
+    pub fn encoder(self) -> &'static dyn ExecutorEncoder {
+        match self {
+            Self::PreBoojum => &PreBoojumEncoder,
+            Self::Boojum => &BoojumEncoder,
+            Self::Post1_4_2 => &Post1_4_2Encoder,
+        }
+    }
+}
+
+impl From<ProtocolVersionId> for EncodingVersion {
+    fn from(protocol_version: ProtocolVersionId) -> Self {
+        if protocol_version.is_pre_boojum() {
+            Self::PreBoojum
+        } else if protocol_version.is_pre_1_4_2() {
+            Self::Boojum
+        } else {
+            Self::Post1_4_2
+        }
+    }
+}
+
+/// Encoding of `IExecutor.sol` structures for a specific range of protocol versions (see [`EncodingVersion`]).
+/// Used for all executor methods: commit, prove and execute.
+pub trait ExecutorEncoder: fmt::Debug + Send + Sync + 'static {
+    /// Returns the encoding version implemented by this encoder.
+    fn version(&self) -> EncodingVersion;
+
+    /// Returns the ABI type of `CommitBatchInfo`.
+    fn commit_batch_info_schema(&self) -> ParamType {
+        CommitBatchInfo::schema()
+    }
+
+    /// Prepares commitments to the L1 batch data included into `CommitBatchInfo`.
+    fn batch_commitments(
+        &self,
+        l1_batch: &L1BatchWithMetadata,
+    ) -> Result<BatchCommitments, CommitBatchInfoError>;
+
+    /// Checks that the batch pubdata can be encoded with the builder parameters, without computing KZG info.
+    fn validate_pubdata(
+        &self,
+        input: &CommitBatchInfoBuilder<'_>,
+    ) -> Result<(), CommitBatchInfoError>;
+
+    /// Encodes the pubdata part of `CommitBatchInfo`, or returns `None` if the struct has no such part.
+    fn encode_pubdata(
+        &self,
+        input: &CommitBatchInfoBuilder<'_>,
+    ) -> Result<Option<Vec<u8>>, CommitBatchInfoError>;
+
+    /// Encodes `StoredBatchInfo` for the L1 batch. The struct is used by all executor methods.
+    fn stored_batch_info(&self, l1_batch: &L1BatchWithMetadata) -> StoredBatchInfo {
+        StoredBatchInfo::from(l1_batch)
+    }
+
+    /// Encodes `recursiveAggregationInput` of `ProofInput` for `proveBatches` given the aggregation result
+    /// coordinates of the proof.
+    fn aggregation_result_coords(&self, _coords: &[[u8; 32]; 4]) -> Token {
+        Token::Array(vec![])
+    }
+}
@@ -0,0 +1,88 @@
+//! Encoding for protocol versions starting from 1.4.2.
+
+use zksync_types::{
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    pubdata_da::PubdataDA,
+};
+
+use super::{boojum::post_boojum_commitments, EncodingVersion, ExecutorEncoder};
+use crate::i_executor::{
+    commit::kzg::ZK_SYNC_BYTES_PER_BLOB,
+    structures::{BatchCommitments, CommitBatchInfoBuilder, CommitBatchInfoError},
+};
+
+/// Encoder for [`EncodingVersion::Post1_4_2`]. Pubdata is prefixed with the pubdata source byte. Calldata pubdata
+/// is followed by the blob commitment, blob pubdata is replaced with pubdata commitments for each blob, and
+/// custom DA pubdata is replaced with the DA inclusion data.
+#[derive(Debug)]
+pub struct Post1_4_2Encoder;
+
+impl ExecutorEncoder for Post1_4_2Encoder {
+    fn version(&self) -> EncodingVersion {
+        EncodingVersion::Post1_4_2
+    }
+
+    fn batch_commitments(
+        &self,
+        l1_batch: &L1BatchWithMetadata,
+    ) -> Result<BatchCommitments, CommitBatchInfoError> {
+        post_boojum_commitments(l1_batch)
+    }
+
+    fn validate_pubdata(
+        &self,
+        input: &CommitBatchInfoBuilder<'_>,
+    ) -> Result<(), CommitBatchInfoError> {
+        if input.pubdata_da() == PubdataDA::Custom {
+            input.da_inclusion_data()?;
+        } else if input.commitment_mode() == L1BatchCommitmentMode::Rollup {
+            input.check_kzg_info(&input.pubdata())?;
+        }
+        Ok(())
+    }
+
+    fn encode_pubdata(
+        &self,
+        input: &CommitBatchInfoBuilder<'_>,
+    ) -> Result<Option<Vec<u8>>, CommitBatchInfoError> {
+        let pubdata_source = input.pubdata_source();
+        if input.pubdata_da() == PubdataDA::Custom {
+            // Pubdata is published in the DA layer, so only the inclusion data is sent to L1 (regardless
+            // of the commitment mode).
+            let inclusion_data = input.da_inclusion_data()?;
+            return Ok(Some(
+                std::iter::once(pubdata_source)
+                    .chain(inclusion_data.iter().copied())
+                    .collect(),
+            ));
+        }
+        if input.commitment_mode() == L1BatchCommitmentMode::Validium {
+            // Validium commitments only contain the pubdata source byte
+            return Ok(Some(vec![pubdata_source]));
+        }
+
+        let pubdata = input.pubdata();
+        input.check_kzg_info(&pubdata)?;
+        Ok(Some(match input.pubdata_da() {
+            PubdataDA::Calldata => {
+                // We compute and add the blob commitment to the pubdata payload so that we can verify the proof
+                // even if we are not using blobs.
+                let blob_commitment = input.kzg_info(0, &pubdata).to_blob_commitment();
+                std::iter::once(pubdata_source)
+                    .chain(pubdata.iter().copied())
+                    .chain(blob_commitment)
+                    .collect()
+            }
+            PubdataDA::Blobs => {
+                let pubdata_commitments = pubdata
+                    .chunks(ZK_SYNC_BYTES_PER_BLOB)
+                    .enumerate()
+                    .flat_map(|(i, blob)| input.kzg_info(i, blob).to_pubdata_commitment().to_vec());
+                std::iter::once(pubdata_source)
+                    .chain(pubdata_commitments)
+                    .collect()
+            }
+            PubdataDA::Custom => unreachable!("custom DA is handled above"),
+        }))
+    }
+}
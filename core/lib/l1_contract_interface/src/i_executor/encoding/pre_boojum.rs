@@ -0,0 +1,71 @@
+//! Encoding for pre-Boojum protocol versions.
+
+use zksync_types::{
+    commitment::{pre_boojum_serialize_commitments, L1BatchWithMetadata},
+    ethabi::{ParamType, Token},
+    U256,
+};
+
+use super::{EncodingVersion, ExecutorEncoder};
+use crate::i_executor::structures::{
+    BatchCommitments, CommitBatchInfo, CommitBatchInfoBuilder, CommitBatchInfoError,
+};
+
+/// Encoder for [`EncodingVersion::PreBoojum`]. `CommitBlockInfo` contains compressed storage writes, L2 logs,
+/// messages and published bytecodes instead of pubdata, and proofs include the aggregation result.
+#[derive(Debug)]
+pub struct PreBoojumEncoder;
+
+impl ExecutorEncoder for PreBoojumEncoder {
+    fn version(&self) -> EncodingVersion {
+        EncodingVersion::PreBoojum
+    }
+
+    fn commit_batch_info_schema(&self) -> ParamType {
+        CommitBatchInfo::pre_boojum_schema()
+    }
+
+    fn batch_commitments(
+        &self,
+        l1_batch: &L1BatchWithMetadata,
+    ) -> Result<BatchCommitments, CommitBatchInfoError> {
+        let number = l1_batch.header.number;
+        let metadata = &l1_batch.metadata;
+        Ok(BatchCommitments::PreBoojum {
+            l2_logs_tree_root: metadata.l2_l1_merkle_root,
+            initial_writes_compressed: metadata
+                .initial_writes_compressed
+                .clone()
+                .ok_or(CommitBatchInfoError::MissingInitialWritesCompressed(number))?,
+            repeated_writes_compressed: metadata.repeated_writes_compressed.clone().ok_or(
+                CommitBatchInfoError::MissingRepeatedWritesCompressed(number),
+            )?,
+            l2_logs: pre_boojum_serialize_commitments(&l1_batch.header.l2_to_l1_logs),
+            l2_arbitrary_length_messages: l1_batch.header.l2_to_l1_messages.clone(),
+            factory_deps: l1_batch.raw_published_factory_deps.clone(),
+        })
+    }
+
+    fn validate_pubdata(
+        &self,
+        _input: &CommitBatchInfoBuilder<'_>,
+    ) -> Result<(), CommitBatchInfoError> {
+        Ok(())
+    }
+
+    fn encode_pubdata(
+        &self,
+        _input: &CommitBatchInfoBuilder<'_>,
+    ) -> Result<Option<Vec<u8>>, CommitBatchInfoError> {
+        Ok(None)
+    }
+
+    fn aggregation_result_coords(&self, coords: &[[u8; 32]; 4]) -> Token {
+        Token::Array(
+            coords
+                .iter()
+                .map(|bytes| Token::Uint(U256::from_big_endian(bytes)))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,257 @@
+//! Fixture tests pinning the ABI encoding of `IExecutor.sol` structures for each supported encoding version.
+
+use std::collections::HashMap;
+
+use zksync_types::{
+    commitment::L1BatchCommitmentMode, ethabi, pubdata_da::PubdataDA, L1BatchNumber, U256,
+};
+
+use super::*;
+use crate::{
+    i_executor::{
+        commit::kzg::{KzgInfo, ZK_SYNC_BYTES_PER_BLOB},
+        methods::{CommitBatches, ExecuteBatches},
+        structures::PubdataPayload,
+        testonly::mock_l1_batch,
+    },
+    Tokenizable, Tokenize,
+};
+
+/// Creates KZG info with recognizable values, so that tests don't need KZG settings.
+fn mock_kzg_info() -> KzgInfo {
+    const BLOB_SIZE: usize = KzgInfo::SERIALIZED_SIZE - 48 - 32 - 32 - 48 - 32 - 48;
+
+    let mut bytes = vec![0; BLOB_SIZE];
+    bytes.extend([0xc1; 48]); // KZG commitment
+    bytes.extend([0xc2; 32]); // opening point
+    bytes.extend([0xc3; 32]); // opening value
+    bytes.extend([0xc4; 48]); // opening proof
+    bytes.extend([0xc5; 32]); // versioned hash
+    bytes.extend([0; 48]); // blob proof
+    KzgInfo::from_slice(&bytes)
+}
+
+fn assert_fixture(encoded: &[u8], fixture: &str) {
+    let expected: String = fixture.split_whitespace().collect();
+    let expected = hex::decode(expected).unwrap();
+    assert!(
+        encoded == expected,
+        "encoding doesn't match the fixture; actual encoding: {}",
+        hex::encode(encoded)
+    );
+}
+
+/// Checks the ABI encoding of `CommitBatchInfo` against the fixture, and that it can be decoded back
+/// using the encoder schema.
+fn assert_commit_batch_info_fixture(
+    encoder: &dyn ExecutorEncoder,
+    builder: CommitBatchInfoBuilder<'_>,
+    fixture: &str,
+) {
+    let info = builder.build().unwrap();
+    let encoded = ethabi::encode(&[info.clone().into_token()]);
+    assert_fixture(&encoded, fixture);
+
+    let decoded = ethabi::decode(&[encoder.commit_batch_info_schema()], &encoded).unwrap();
+    let decoded = CommitBatchInfo::from_token(decoded.into_iter().next().unwrap()).unwrap();
+    assert_eq!(decoded, info);
+}
+
+#[test]
+fn selecting_encoding_version() {
+    let expected_versions = [
+        (ProtocolVersionId::Version0, EncodingVersion::PreBoojum),
+        (ProtocolVersionId::Version17, EncodingVersion::PreBoojum),
+        (ProtocolVersionId::Version18, EncodingVersion::Boojum),
+        (ProtocolVersionId::Version20, EncodingVersion::Boojum),
+        (ProtocolVersionId::Version21, EncodingVersion::Post1_4_2),
+        (ProtocolVersionId::next(), EncodingVersion::Post1_4_2),
+    ];
+    for (protocol_version, expected) in expected_versions {
+        assert_eq!(
+            EncodingVersion::from(protocol_version),
+            expected,
+            "{protocol_version:?}"
+        );
+    }
+
+    let mut l1_batch = mock_l1_batch(1, ProtocolVersionId::latest());
+    l1_batch.header.protocol_version = None;
+    assert_eq!(
+        EncodingVersion::for_l1_batch(&l1_batch),
+        EncodingVersion::PreBoojum
+    );
+
+    for version in EncodingVersion::ALL {
+        assert_eq!(version.encoder().version(), version);
+    }
+}
+
+#[test]
+fn pre_boojum_commit_batch_info_fixture() {
+    let l1_batch = mock_l1_batch(2, ProtocolVersionId::Version17);
+    let encoder = EncodingVersion::for_l1_batch(&l1_batch).encoder();
+    assert_eq!(encoder.version(), EncodingVersion::PreBoojum);
+
+    let builder = CommitBatchInfo::builder(&l1_batch, PubdataDA::Calldata);
+    assert_commit_batch_info_fixture(
+        encoder,
+        builder,
+        include_str!("testdata/commit_batch_info_pre_boojum.hex"),
+    );
+}
+
+#[test]
+fn boojum_commit_batch_info_fixture() {
+    let l1_batch = mock_l1_batch(2, ProtocolVersionId::Version20);
+    let encoder = EncodingVersion::for_l1_batch(&l1_batch).encoder();
+    assert_eq!(encoder.version(), EncodingVersion::Boojum);
+
+    let builder = CommitBatchInfo::builder(&l1_batch, PubdataDA::Calldata);
+    assert_commit_batch_info_fixture(
+        encoder,
+        builder,
+        include_str!("testdata/commit_batch_info_boojum.hex"),
+    );
+
+    let err = CommitBatchInfo::builder(&l1_batch, PubdataDA::Custom)
+        .with_da_inclusion_data(Some(&[0xda; 40]))
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(err, CommitBatchInfoError::CustomDAUnsupported(_)),
+        "{err}"
+    );
+}
+
+#[test]
+fn post_1_4_2_commit_batch_info_fixtures() {
+    let l1_batch = mock_l1_batch(2, ProtocolVersionId::Version21);
+    let encoder = EncodingVersion::for_l1_batch(&l1_batch).encoder();
+    assert_eq!(encoder.version(), EncodingVersion::Post1_4_2);
+    let kzg_info = [mock_kzg_info()];
+
+    let builder = CommitBatchInfo::builder(&l1_batch, PubdataDA::Calldata)
+        .with_precomputed_kzg_info(Some(&kzg_info));
+    assert_commit_batch_info_fixture(
+        encoder,
+        builder,
+        include_str!("testdata/commit_batch_info_1_4_2_calldata.hex"),
+    );
+
+    let builder = CommitBatchInfo::builder(&l1_batch, PubdataDA::Blobs)
+        .with_precomputed_kzg_info(Some(&kzg_info));
+    assert_commit_batch_info_fixture(
+        encoder,
+        builder,
+        include_str!("testdata/commit_batch_info_1_4_2_blobs.hex"),
+    );
+
+    let builder = CommitBatchInfo::builder(&l1_batch, PubdataDA::Custom)
+        .with_da_inclusion_data(Some(&[0xda; 40]));
+    assert_commit_batch_info_fixture(
+        encoder,
+        builder,
+        include_str!("testdata/commit_batch_info_1_4_2_custom.hex"),
+    );
+
+    let builder = CommitBatchInfo::builder(&l1_batch, PubdataDA::Calldata)
+        .with_commitment_mode(L1BatchCommitmentMode::Validium);
+    assert_commit_batch_info_fixture(
+        encoder,
+        builder,
+        include_str!("testdata/commit_batch_info_1_4_2_validium.hex"),
+    );
+}
+
+#[test]
+fn limiting_blob_count_of_commit_batch_info() {
+    let mut l1_batch = mock_l1_batch(2, ProtocolVersionId::Version21);
+    l1_batch.header.pubdata_input = Some(vec![0xab; 2 * ZK_SYNC_BYTES_PER_BLOB + 1]);
+    let kzg_info = vec![mock_kzg_info(); 3];
+
+    let err = CommitBatchInfo::builder(&l1_batch, PubdataDA::Blobs)
+        .with_precomputed_kzg_info(Some(&kzg_info))
+        .with_max_blobs(2)
+        .validate()
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            CommitBatchInfoError::TooManyBlobs {
+                l1_batch_number: L1BatchNumber(2),
+                blob_count: 3,
+                max_blobs: 2,
+            }
+        ),
+        "{err}"
+    );
+
+    let info = CommitBatchInfo::builder(&l1_batch, PubdataDA::Blobs)
+        .with_precomputed_kzg_info(Some(&kzg_info))
+        .with_max_blobs(3)
+        .build()
+        .unwrap();
+    let Ok(PubdataPayload::Blobs(commitments)) = info.pubdata_payload() else {
+        panic!("unexpected pubdata payload");
+    };
+    assert_eq!(commitments.len(), 3);
+
+    // The limit doesn't apply to pubdata not published in blobs.
+    CommitBatchInfo::builder(&l1_batch, PubdataDA::Custom)
+        .with_da_inclusion_data(Some(&[0xda; 40]))
+        .with_max_blobs(0)
+        .validate()
+        .unwrap();
+}
+
+#[test]
+fn commit_batches_fixture() {
+    let l1_batch = mock_l1_batch(2, ProtocolVersionId::Version21);
+    let commit_batches = CommitBatches {
+        last_committed_l1_batch: mock_l1_batch(1, ProtocolVersionId::Version21),
+        l1_batches: vec![l1_batch],
+        pubdata_da: PubdataDA::Blobs,
+        commitment_mode: L1BatchCommitmentMode::Rollup,
+        kzg_settings: None,
+        precomputed_kzg_info: HashMap::from([(L1BatchNumber(2), vec![mock_kzg_info()])]),
+        da_inclusion_data: HashMap::new(),
+    };
+    assert_fixture(
+        &ethabi::encode(&commit_batches.into_tokens()),
+        include_str!("testdata/commit_batches_1_4_2_blobs.hex"),
+    );
+}
+
+#[test]
+fn execute_batches_fixture() {
+    let execute_batches = ExecuteBatches {
+        l1_batches: vec![
+            mock_l1_batch(2, ProtocolVersionId::Version20),
+            mock_l1_batch(3, ProtocolVersionId::Version21),
+        ],
+        priority_ops_proofs: None,
+    };
+    assert_fixture(
+        &ethabi::encode(&execute_batches.into_tokens()),
+        include_str!("testdata/execute_batches.hex"),
+    );
+}
+
+#[test]
+fn encoding_aggregation_result_coords() {
+    let coords = [[1; 32], [2; 32], [3; 32], [4; 32]];
+    let token = PreBoojumEncoder.aggregation_result_coords(&coords);
+    let expected_coords = coords
+        .iter()
+        .map(|bytes| Token::Uint(U256::from_big_endian(bytes)))
+        .collect();
+    assert_eq!(token, Token::Array(expected_coords));
+
+    for encoder in [&BoojumEncoder as &dyn ExecutorEncoder, &Post1_4_2Encoder] {
+        assert_eq!(
+            encoder.aggregation_result_coords(&coords),
+            Token::Array(vec![])
+        );
+    }
+}
@@ -1,30 +1,59 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use zkevm_test_harness_1_4_2::kzg::KzgSettings;
-use zksync_types::{commitment::L1BatchWithMetadata, ethabi::Token, pubdata_da::PubdataDA};
+use zksync_types::{
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    ethabi::Token,
+    pubdata_da::PubdataDA,
+    L1BatchNumber,
+};
 
 use crate::{
-    i_executor::structures::{CommitBatchInfo, StoredBatchInfo},
+    i_executor::{
+        commit::kzg::{KzgInfo, KzgSettings},
+        encoding::EncodingVersion,
+        structures::CommitBatchInfo,
+    },
     Tokenizable, Tokenize,
 };
 
-/// Input required to encode `commitBatches` call.
+/// Input required to encode `commitBatches` call. All `l1_batches` must be validated beforehand
+/// using [`CommitBatchInfoBuilder::validate()`](crate::i_executor::structures::CommitBatchInfoBuilder::validate()).
 #[derive(Debug, Clone)]
 pub struct CommitBatches {
     pub last_committed_l1_batch: L1BatchWithMetadata,
     pub l1_batches: Vec<L1BatchWithMetadata>,
     pub pubdata_da: PubdataDA,
+    pub commitment_mode: L1BatchCommitmentMode,
     pub kzg_settings: Option<Arc<KzgSettings>>,
+    /// KZG info precomputed after batches were sealed, keyed by the batch number.
+    pub precomputed_kzg_info: HashMap<L1BatchNumber, Vec<KzgInfo>>,
+    /// Proofs of pubdata inclusion in an external DA layer keyed by the batch number. Required for all `l1_batches`
+    /// if `pubdata_da` is [`PubdataDA::Custom`].
+    pub da_inclusion_data: HashMap<L1BatchNumber, Vec<u8>>,
 }
 
 impl Tokenize for CommitBatches {
     fn into_tokens(self) -> Vec<Token> {
-        let stored_batch_info = StoredBatchInfo(&self.last_committed_l1_batch).into_token();
+        let last_committed_l1_batch = &self.last_committed_l1_batch;
+        let stored_batch_info = EncodingVersion::for_l1_batch(last_committed_l1_batch)
+            .encoder()
+            .stored_batch_info(last_committed_l1_batch)
+            .into_token();
         let l1_batches_to_commit = self
             .l1_batches
             .iter()
             .map(|batch| {
-                CommitBatchInfo::new(batch, self.pubdata_da, self.kzg_settings.clone()).into_token()
+                let kzg_info = self.precomputed_kzg_info.get(&batch.header.number);
+                let da_inclusion_data = self.da_inclusion_data.get(&batch.header.number);
+                CommitBatchInfo::builder(batch, self.pubdata_da)
+                    .with_commitment_mode(self.commitment_mode)
+                    .with_kzg_settings(self.kzg_settings.clone())
+                    .with_precomputed_kzg_info(kzg_info.map(Vec::as_slice))
+                    .with_da_inclusion_data(da_inclusion_data.map(Vec::as_slice))
+                    .build()
+                    // Batches are validated when this operation is created
+                    .unwrap_or_else(|err| panic!("Cannot encode commit data: {err}"))
+                    .into_token()
             })
             .collect();
 
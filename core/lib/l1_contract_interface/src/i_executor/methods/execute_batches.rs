@@ -2,21 +2,44 @@ use zksync_types::{
     commitment::L1BatchWithMetadata, ethabi::Token, web3::contract::tokens::Tokenizable,
 };
 
-use crate::{i_executor::structures::StoredBatchInfo, Tokenize};
+use crate::{
+    i_executor::{encoding::EncodingVersion, structures::PriorityOpsBatchInfo},
+    Tokenize,
+};
 
 /// Input required to encode `executeBatches` call.
 #[derive(Debug, Clone)]
 pub struct ExecuteBatches {
     pub l1_batches: Vec<L1BatchWithMetadata>,
+    /// Proofs of priority operations for each of `l1_batches`. Must be set iff the L1 contract uses the priority tree
+    /// (in this case, the call is encoded with an additional `PriorityOpsBatchInfo[]` argument). For batches
+    /// processed via the legacy priority queue, the corresponding proof must be empty.
+    pub priority_ops_proofs: Option<Vec<PriorityOpsBatchInfo>>,
 }
 
 impl Tokenize for ExecuteBatches {
     fn into_tokens(self) -> Vec<Token> {
-        vec![Token::Array(
+        let stored_batch_infos = Token::Array(
             self.l1_batches
                 .iter()
-                .map(|batch| StoredBatchInfo(batch).into_token())
+                .map(|batch| {
+                    let encoder = EncodingVersion::for_l1_batch(batch).encoder();
+                    encoder.stored_batch_info(batch).into_token()
+                })
                 .collect(),
-        )]
+        );
+        let Some(priority_ops_proofs) = self.priority_ops_proofs else {
+            return vec![stored_batch_infos];
+        };
+        assert_eq!(
+            priority_ops_proofs.len(),
+            self.l1_batches.len(),
+            "Mismatch between number of L1 batches and priority ops proofs"
+        );
+        let priority_ops_proofs = priority_ops_proofs
+            .into_iter()
+            .map(Tokenizable::into_token)
+            .collect();
+        vec![stored_batch_infos, Token::Array(priority_ops_proofs)]
     }
 }
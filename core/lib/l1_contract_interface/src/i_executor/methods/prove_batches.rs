@@ -4,9 +4,15 @@ use zksync_types::{
     commitment::L1BatchWithMetadata, ethabi::Token, web3::contract::tokens::Tokenizable, U256,
 };
 
-use crate::{i_executor::structures::StoredBatchInfo, Tokenize};
+use crate::{i_executor::encoding::EncodingVersion, Tokenize};
 
 /// Input required to encode `proveBatches` call.
+///
+/// Proofs are always submitted to the diamond proxy of the chain they belong to. Submitting them through
+/// a state transition manager-level aggregator batching proofs of several hyperchains is declined: the state
+/// transition manager contracts don't expose such an entry point, so the aggregated public input layout and
+/// the aggregate submission status would have nothing on L1 to be checked against. This should be revisited
+/// once the contracts define the aggregator interface.
 #[derive(Debug, Clone)]
 pub struct ProveBatches {
     pub prev_l1_batch: L1BatchWithMetadata,
@@ -17,12 +23,12 @@ pub struct ProveBatches {
 
 impl Tokenize for ProveBatches {
     fn into_tokens(self) -> Vec<Token> {
-        let prev_l1_batch = StoredBatchInfo(&self.prev_l1_batch).into_token();
-        let batches_arg = self
-            .l1_batches
-            .iter()
-            .map(|batch| StoredBatchInfo(batch).into_token())
-            .collect();
+        let stored_batch_info = |batch: &L1BatchWithMetadata| {
+            let encoder = EncodingVersion::for_l1_batch(batch).encoder();
+            encoder.stored_batch_info(batch).into_token()
+        };
+        let prev_l1_batch = stored_batch_info(&self.prev_l1_batch);
+        let batches_arg = self.l1_batches.iter().map(stored_batch_info).collect();
         let batches_arg = Token::Array(batches_arg);
 
         if self.should_verify {
@@ -37,21 +43,9 @@ impl Tokenize for ProveBatches {
 
             let (_, proof) = serialize_proof(scheduler_proof);
 
-            let aggregation_result_coords = if self.l1_batches[0]
-                .header
-                .protocol_version
-                .unwrap()
-                .is_pre_boojum()
-            {
-                Token::Array(
-                    aggregation_result_coords
-                        .iter()
-                        .map(|bytes| Token::Uint(U256::from_big_endian(bytes)))
-                        .collect(),
-                )
-            } else {
-                Token::Array(Vec::new())
-            };
+            let aggregation_result_coords = EncodingVersion::for_l1_batch(&self.l1_batches[0])
+                .encoder()
+                .aggregation_result_coords(aggregation_result_coords);
             let proof_input = Token::Tuple(vec![
                 aggregation_result_coords,
                 Token::Array(proof.into_iter().map(Token::Uint).collect()),
@@ -1,5 +1,9 @@
 //! Different interfaces exposed by the `IExecutor.sol`.
 
+pub mod calldata;
 pub mod commit;
+pub mod encoding;
 pub mod methods;
 pub mod structures;
+#[cfg(test)]
+mod testonly;
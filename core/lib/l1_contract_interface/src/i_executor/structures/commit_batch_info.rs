@@ -1,245 +1,551 @@
-use std::sync::Arc;
+use std::{borrow::Cow, convert::TryInto, sync::Arc};
 
-use zkevm_test_harness_1_4_2::kzg::KzgSettings;
 use zksync_types::{
-    commitment::{pre_boojum_serialize_commitments, serialize_commitments, L1BatchWithMetadata},
-    ethabi::Token,
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    ethabi::{ParamType, Token},
     pubdata_da::PubdataDA,
-    web3::{contract::Error as Web3ContractError, error::Error as Web3ApiError},
-    ProtocolVersionId, U256,
+    web3::contract::Error as Web3ContractError,
+    L1BatchNumber, H256, U256,
 };
 
+use super::tokens;
 use crate::{
-    i_executor::commit::kzg::{KzgInfo, ZK_SYNC_BYTES_PER_BLOB},
+    i_executor::{
+        commit::kzg::{KzgInfo, KzgSettings, BYTES_PER_PUBDATA_COMMITMENT, ZK_SYNC_BYTES_PER_BLOB},
+        encoding::{EncodingVersion, ExecutorEncoder},
+    },
     Tokenizable,
 };
 
 /// These are used by the L1 Contracts to indicate what DA layer is used for pubdata
 const PUBDATA_SOURCE_CALLDATA: u8 = 0;
 const PUBDATA_SOURCE_BLOBS: u8 = 1;
+const PUBDATA_SOURCE_CUSTOM: u8 = 2;
 
-/// Encoding for `CommitBatchInfo` from `IExecutor.sol`
+/// Errors that can occur when preparing [`CommitBatchInfo`] for an L1 batch.
+#[derive(Debug, thiserror::Error)]
+pub enum CommitBatchInfoError {
+    #[error("L1 batch #{0} has no compressed initial writes")]
+    MissingInitialWritesCompressed(L1BatchNumber),
+    #[error("L1 batch #{0} has no compressed repeated writes")]
+    MissingRepeatedWritesCompressed(L1BatchNumber),
+    #[error("L1 batch #{0} has no bootloader initial content commitment")]
+    MissingBootloaderInitialContentCommitment(L1BatchNumber),
+    #[error("L1 batch #{0} has no events queue commitment")]
+    MissingEventsQueueCommitment(L1BatchNumber),
+    #[error("KZG settings are required to encode pubdata of L1 batch #{0} using {1:?} DA")]
+    MissingKzgSettings(L1BatchNumber, PubdataDA),
+    #[error(
+        "pubdata of L1 batch #{l1_batch_number} ({size} bytes) doesn't fit into a single blob, \
+         which is required for calldata DA"
+    )]
+    PubdataTooLarge {
+        l1_batch_number: L1BatchNumber,
+        size: usize,
+    },
+    #[error(
+        "pubdata of L1 batch #{l1_batch_number} occupies {blob_count} blobs, while at most {max_blobs} blobs \
+         can be attached to a commit transaction"
+    )]
+    TooManyBlobs {
+        l1_batch_number: L1BatchNumber,
+        blob_count: usize,
+        max_blobs: usize,
+    },
+    #[error("L1 batch #{0} has no DA inclusion data, which is required for custom DA")]
+    MissingDAInclusionData(L1BatchNumber),
+    #[error(
+        "custom DA is not supported for L1 batch #{0} since its protocol version predates 1.4.2"
+    )]
+    CustomDAUnsupported(L1BatchNumber),
+}
+
+/// Builder of [`CommitBatchInfo`] that checks that the L1 batch has all data required for encoding.
 #[derive(Debug)]
-pub struct CommitBatchInfo<'a> {
-    pub l1_batch_with_metadata: &'a L1BatchWithMetadata,
-    pub pubdata_da: PubdataDA,
-    pub kzg_settings: Option<Arc<KzgSettings>>,
+pub struct CommitBatchInfoBuilder<'a> {
+    l1_batch_with_metadata: &'a L1BatchWithMetadata,
+    pubdata_da: PubdataDA,
+    commitment_mode: L1BatchCommitmentMode,
+    kzg_settings: Option<Arc<KzgSettings>>,
+    precomputed_kzg_info: Option<&'a [KzgInfo]>,
+    da_inclusion_data: Option<&'a [u8]>,
+    max_blobs: Option<usize>,
 }
 
-impl<'a> CommitBatchInfo<'a> {
-    pub fn new(
-        l1_batch_with_metadata: &'a L1BatchWithMetadata,
-        pubdata_da: PubdataDA,
-        kzg_settings: Option<Arc<KzgSettings>>,
-    ) -> Self {
+impl<'a> CommitBatchInfoBuilder<'a> {
+    pub fn new(l1_batch_with_metadata: &'a L1BatchWithMetadata, pubdata_da: PubdataDA) -> Self {
         Self {
             l1_batch_with_metadata,
             pubdata_da,
-            kzg_settings,
+            commitment_mode: L1BatchCommitmentMode::Rollup,
+            kzg_settings: None,
+            precomputed_kzg_info: None,
+            da_inclusion_data: None,
+            max_blobs: None,
+        }
+    }
+
+    /// Sets the commitment mode of the chain. In the validium mode, pubdata is not included into the encoded data,
+    /// so neither KZG settings nor precomputed KZG info are required. The default mode is rollup.
+    pub fn with_commitment_mode(mut self, commitment_mode: L1BatchCommitmentMode) -> Self {
+        self.commitment_mode = commitment_mode;
+        self
+    }
+
+    /// Sets KZG settings used to compute KZG info for the batch pubdata.
+    pub fn with_kzg_settings(mut self, kzg_settings: Option<Arc<KzgSettings>>) -> Self {
+        self.kzg_settings = kzg_settings;
+        self
+    }
+
+    /// Sets KZG info for each blob of the batch pubdata computed in advance. If not set,
+    /// KZG info is computed using KZG settings.
+    pub fn with_precomputed_kzg_info(mut self, kzg_info: Option<&'a [KzgInfo]>) -> Self {
+        self.precomputed_kzg_info = kzg_info;
+        self
+    }
+
+    /// Sets the proof of the batch pubdata inclusion in an external DA layer obtained by the DA dispatcher.
+    /// Required for [`PubdataDA::Custom`]; the inclusion data is published on L1 instead of the pubdata.
+    pub fn with_da_inclusion_data(mut self, inclusion_data: Option<&'a [u8]>) -> Self {
+        self.da_inclusion_data = inclusion_data;
+        self
+    }
+
+    /// Sets the maximum number of blobs the batch pubdata can occupy if it's published in blobs (e.g., the number
+    /// of blobs that can be attached to a commit transaction). By default, the number of blobs is not limited.
+    pub fn with_max_blobs(mut self, max_blobs: usize) -> Self {
+        self.max_blobs = Some(max_blobs);
+        self
+    }
+
+    pub(crate) fn l1_batch_number(&self) -> L1BatchNumber {
+        self.l1_batch_with_metadata.header.number
+    }
+
+    pub(crate) fn pubdata_da(&self) -> PubdataDA {
+        self.pubdata_da
+    }
+
+    pub(crate) fn commitment_mode(&self) -> L1BatchCommitmentMode {
+        self.commitment_mode
+    }
+
+    fn encoder(&self) -> &'static dyn ExecutorEncoder {
+        EncodingVersion::for_l1_batch(self.l1_batch_with_metadata).encoder()
+    }
+
+    pub(crate) fn pubdata(&self) -> Cow<'a, [u8]> {
+        let l1_batch: &'a L1BatchWithMetadata = self.l1_batch_with_metadata;
+        match &l1_batch.header.pubdata_input {
+            Some(pubdata) => Cow::Borrowed(pubdata),
+            None => Cow::Owned(l1_batch.construct_pubdata()),
+        }
+    }
+
+    pub(crate) fn pubdata_source(&self) -> u8 {
+        match self.pubdata_da {
+            PubdataDA::Calldata => PUBDATA_SOURCE_CALLDATA,
+            PubdataDA::Blobs => PUBDATA_SOURCE_BLOBS,
+            PubdataDA::Custom => PUBDATA_SOURCE_CUSTOM,
+        }
+    }
+
+    /// Returns DA inclusion data required for [`PubdataDA::Custom`].
+    pub(crate) fn da_inclusion_data(&self) -> Result<&'a [u8], CommitBatchInfoError> {
+        self.da_inclusion_data
+            .ok_or(CommitBatchInfoError::MissingDAInclusionData(
+                self.l1_batch_number(),
+            ))
+    }
+
+    fn precomputed_kzg_info(&self, blob_index: usize) -> Option<&'a KzgInfo> {
+        self.precomputed_kzg_info?.get(blob_index)
+    }
+
+    /// Checks that KZG info can be obtained for all blobs of the pubdata.
+    pub(crate) fn check_kzg_info(&self, pubdata: &[u8]) -> Result<(), CommitBatchInfoError> {
+        let blob_count = match self.pubdata_da {
+            PubdataDA::Calldata => {
+                if pubdata.len() > ZK_SYNC_BYTES_PER_BLOB {
+                    return Err(CommitBatchInfoError::PubdataTooLarge {
+                        l1_batch_number: self.l1_batch_number(),
+                        size: pubdata.len(),
+                    });
+                }
+                1
+            }
+            PubdataDA::Blobs => {
+                let blob_count = pubdata.chunks(ZK_SYNC_BYTES_PER_BLOB).count();
+                match self.max_blobs {
+                    Some(max_blobs) if blob_count > max_blobs => {
+                        return Err(CommitBatchInfoError::TooManyBlobs {
+                            l1_batch_number: self.l1_batch_number(),
+                            blob_count,
+                            max_blobs,
+                        });
+                    }
+                    _ => blob_count,
+                }
+            }
+            // Pubdata is not published on L1, so no KZG info is required.
+            PubdataDA::Custom => return Ok(()),
+        };
+        let has_precomputed_info = (0..blob_count).all(|i| self.precomputed_kzg_info(i).is_some());
+        if self.kzg_settings.is_none() && !has_precomputed_info {
+            return Err(CommitBatchInfoError::MissingKzgSettings(
+                self.l1_batch_number(),
+                self.pubdata_da,
+            ));
         }
+        Ok(())
     }
 
-    fn base_tokens(&self) -> Vec<Token> {
-        if self
-            .l1_batch_with_metadata
-            .header
-            .protocol_version
-            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined)
-            .is_pre_boojum()
-        {
-            vec![
-                Token::Uint(U256::from(self.l1_batch_with_metadata.header.number.0)),
-                Token::Uint(U256::from(self.l1_batch_with_metadata.header.timestamp)),
-                Token::Uint(U256::from(
-                    self.l1_batch_with_metadata.metadata.rollup_last_leaf_index,
-                )),
-                Token::FixedBytes(
-                    self.l1_batch_with_metadata
-                        .metadata
-                        .merkle_root_hash
-                        .as_bytes()
-                        .to_vec(),
-                ),
-                Token::Uint(U256::from(self.l1_batch_with_metadata.header.l1_tx_count)),
-                Token::FixedBytes(
-                    self.l1_batch_with_metadata
-                        .metadata
-                        .l2_l1_merkle_root
-                        .as_bytes()
-                        .to_vec(),
-                ),
-                Token::FixedBytes(
-                    self.l1_batch_with_metadata
-                        .header
-                        .priority_ops_onchain_data_hash()
-                        .as_bytes()
-                        .to_vec(),
-                ),
-                Token::Bytes(
-                    self.l1_batch_with_metadata
-                        .metadata
-                        .initial_writes_compressed
-                        .clone()
-                        .unwrap(),
-                ),
-                Token::Bytes(
-                    self.l1_batch_with_metadata
-                        .metadata
-                        .repeated_writes_compressed
-                        .clone()
-                        .unwrap(),
-                ),
-                Token::Bytes(pre_boojum_serialize_commitments(
-                    &self.l1_batch_with_metadata.header.l2_to_l1_logs,
-                )),
-                Token::Array(
-                    self.l1_batch_with_metadata
-                        .header
-                        .l2_to_l1_messages
-                        .iter()
-                        .map(|message| Token::Bytes(message.to_vec()))
-                        .collect(),
-                ),
-                Token::Array(
-                    self.l1_batch_with_metadata
-                        .raw_published_factory_deps
-                        .iter()
-                        .map(|bytecode| Token::Bytes(bytecode.to_vec()))
-                        .collect(),
-                ),
-            ]
-        } else {
-            vec![
-                // `batchNumber`
-                Token::Uint(U256::from(self.l1_batch_with_metadata.header.number.0)),
-                // `timestamp`
-                Token::Uint(U256::from(self.l1_batch_with_metadata.header.timestamp)),
-                // `indexRepeatedStorageChanges`
-                Token::Uint(U256::from(
-                    self.l1_batch_with_metadata.metadata.rollup_last_leaf_index,
-                )),
-                // `newStateRoot`
-                Token::FixedBytes(
-                    self.l1_batch_with_metadata
-                        .metadata
-                        .merkle_root_hash
-                        .as_bytes()
-                        .to_vec(),
-                ),
-                // `numberOfLayer1Txs`
-                Token::Uint(U256::from(self.l1_batch_with_metadata.header.l1_tx_count)),
-                // `priorityOperationsHash`
-                Token::FixedBytes(
-                    self.l1_batch_with_metadata
-                        .header
-                        .priority_ops_onchain_data_hash()
-                        .as_bytes()
-                        .to_vec(),
-                ),
-                // `bootloaderHeapInitialContentsHash`
-                Token::FixedBytes(
-                    self.l1_batch_with_metadata
-                        .metadata
-                        .bootloader_initial_content_commitment
-                        .unwrap()
-                        .as_bytes()
-                        .to_vec(),
-                ),
-                // `eventsQueueStateHash`
-                Token::FixedBytes(
-                    self.l1_batch_with_metadata
-                        .metadata
-                        .events_queue_commitment
-                        .unwrap()
-                        .as_bytes()
-                        .to_vec(),
-                ),
-                // `systemLogs`
-                Token::Bytes(serialize_commitments(
-                    &self.l1_batch_with_metadata.header.system_logs,
-                )),
-            ]
+    /// Returns KZG info for the blob with the specified index. Must be called after [`Self::check_kzg_info()`].
+    pub(crate) fn kzg_info(&self, blob_index: usize, blob: &[u8]) -> KzgInfo {
+        if let Some(kzg_info) = self.precomputed_kzg_info(blob_index) {
+            return kzg_info.clone();
         }
+        let kzg_settings = self.kzg_settings.as_ref();
+        KzgInfo::new(kzg_settings.expect("checked in `check_kzg_info()`"), blob)
+    }
+
+    /// Checks that the L1 batch has all data required for encoding without computing KZG info.
+    pub fn validate(&self) -> Result<(), CommitBatchInfoError> {
+        let encoder = self.encoder();
+        encoder.batch_commitments(self.l1_batch_with_metadata)?;
+        encoder.validate_pubdata(self)
+    }
+
+    /// Validates the L1 batch data and prepares it for encoding, computing KZG info if necessary.
+    pub fn build(self) -> Result<CommitBatchInfo, CommitBatchInfoError> {
+        let encoder = self.encoder();
+        let l1_batch = self.l1_batch_with_metadata;
+        let commitments = encoder.batch_commitments(l1_batch)?;
+        let pubdata = encoder.encode_pubdata(&self)?;
+        Ok(CommitBatchInfo {
+            batch_number: l1_batch.header.number,
+            timestamp: l1_batch.header.timestamp,
+            index_repeated_storage_changes: l1_batch.metadata.rollup_last_leaf_index,
+            new_state_root: l1_batch.metadata.merkle_root_hash,
+            number_of_layer1_txs: l1_batch.header.l1_tx_count.into(),
+            priority_operations_hash: l1_batch.header.priority_ops_onchain_data_hash(),
+            commitments,
+            pubdata,
+        })
     }
 }
 
-impl<'a> Tokenizable for CommitBatchInfo<'a> {
-    fn from_token(_token: Token) -> Result<Self, Web3ContractError>
-    where
-        Self: Sized,
-    {
-        // Currently there is no need to decode this struct.
-        // We still want to implement `Tokenizable` trait for it, so that *once* it's needed
-        // the implementation is provided here and not in some other inconsistent way.
-        Err(Web3ContractError::Api(Web3ApiError::Decoder(
-            "Not implemented".to_string(),
-        )))
+/// Commitments to the batch data which differ between pre- and post-Boojum batches.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchCommitments {
+    PreBoojum {
+        l2_logs_tree_root: H256,
+        initial_writes_compressed: Vec<u8>,
+        repeated_writes_compressed: Vec<u8>,
+        l2_logs: Vec<u8>,
+        l2_arbitrary_length_messages: Vec<Vec<u8>>,
+        factory_deps: Vec<Vec<u8>>,
+    },
+    PostBoojum {
+        bootloader_heap_initial_contents_hash: H256,
+        events_queue_state_hash: H256,
+        /// Serialized system logs.
+        system_logs: Vec<u8>,
+    },
+}
+
+/// Pubdata part of the `CommitBatchInfo` for a post-1.4.2 batch (i.e., `totalL2ToL1Pubdata` prefixed
+/// with the pubdata source byte). Returned by [`CommitBatchInfo::pubdata_payload()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PubdataPayload<'a> {
+    /// Pubdata is not published on L1 (i.e., the chain is a validium); the payload only contains
+    /// the pubdata source byte.
+    Empty(PubdataDA),
+    /// Pubdata is published in calldata, followed by the commitment to the blob containing it.
+    Calldata {
+        pubdata: &'a [u8],
+        blob_commitment: H256,
+    },
+    /// Pubdata is published in blobs. Contains a pubdata commitment for each blob: opening point (16 bytes) ||
+    /// claimed value (32 bytes) || KZG commitment (48 bytes) || opening proof (48 bytes).
+    Blobs(Vec<&'a [u8; BYTES_PER_PUBDATA_COMMITMENT]>),
+    /// Pubdata is published in an external DA layer. Contains the DA inclusion data, the format of which
+    /// is specific to the DA layer.
+    Custom { inclusion_data: &'a [u8] },
+}
+
+/// Errors that can occur when parsing [`PubdataPayload`].
+#[derive(Debug, thiserror::Error)]
+pub enum PubdataPayloadError {
+    #[error("pubdata is not present in pre-Boojum batches")]
+    PreBoojum,
+    #[error("pubdata doesn't contain the pubdata source byte")]
+    MissingSource,
+    #[error("unknown pubdata source: {0}")]
+    UnknownSource(u8),
+    #[error("calldata pubdata ({0} bytes) is too short to contain the blob commitment")]
+    MissingBlobCommitment(usize),
+    #[error(
+        "blob pubdata commitments length ({0} bytes) is not a multiple of the pubdata commitment size"
+    )]
+    InvalidPubdataCommitments(usize),
+}
+
+/// Encoding for `CommitBatchInfo` from `IExecutor.sol`. Can be created for a local L1 batch using
+/// [`CommitBatchInfoBuilder`], or decoded from a token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitBatchInfo {
+    pub batch_number: L1BatchNumber,
+    pub timestamp: u64,
+    pub index_repeated_storage_changes: u64,
+    pub new_state_root: H256,
+    pub number_of_layer1_txs: U256,
+    pub priority_operations_hash: H256,
+    pub commitments: BatchCommitments,
+    /// Encoded pubdata (`totalL2ToL1Pubdata`); `None` for pre-Boojum batches.
+    pub pubdata: Option<Vec<u8>>,
+}
+
+impl CommitBatchInfo {
+    pub fn builder(
+        l1_batch_with_metadata: &L1BatchWithMetadata,
+        pubdata_da: PubdataDA,
+    ) -> CommitBatchInfoBuilder<'_> {
+        CommitBatchInfoBuilder::new(l1_batch_with_metadata, pubdata_da)
     }
 
-    fn into_token(self) -> Token {
-        let mut tokens = self.base_tokens();
+    /// Returns the ABI type of the struct for post-Boojum batches.
+    pub fn schema() -> ParamType {
+        ParamType::Tuple(vec![
+            ParamType::Uint(64),
+            ParamType::Uint(64),
+            ParamType::Uint(64),
+            ParamType::FixedBytes(32),
+            ParamType::Uint(256),
+            ParamType::FixedBytes(32),
+            ParamType::FixedBytes(32),
+            ParamType::FixedBytes(32),
+            ParamType::Bytes,
+            ParamType::Bytes,
+        ])
+    }
 
-        let protocol_version = self
-            .l1_batch_with_metadata
-            .header
-            .protocol_version
-            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+    /// Returns the ABI type of the struct (`CommitBlockInfo` in legacy contracts) for pre-Boojum batches.
+    pub fn pre_boojum_schema() -> ParamType {
+        ParamType::Tuple(vec![
+            ParamType::Uint(64),
+            ParamType::Uint(64),
+            ParamType::Uint(64),
+            ParamType::FixedBytes(32),
+            ParamType::Uint(256),
+            ParamType::FixedBytes(32),
+            ParamType::FixedBytes(32),
+            ParamType::Bytes,
+            ParamType::Bytes,
+            ParamType::Bytes,
+            ParamType::Array(Box::new(ParamType::Bytes)),
+            ParamType::Array(Box::new(ParamType::Bytes)),
+        ])
+    }
 
-        if protocol_version.is_pre_boojum() {
-            return Token::Tuple(tokens);
+    /// Parses pubdata of a post-1.4.2 batch. Post-Boojum batches with earlier protocol versions
+    /// don't have the pubdata source byte, so the caller must ensure that the batch version is appropriate.
+    /// Pubdata itself can be decoded using [`decode_pubdata()`](crate::i_executor::commit::pubdata::decode_pubdata()).
+    pub fn pubdata_payload(&self) -> Result<PubdataPayload<'_>, PubdataPayloadError> {
+        let pubdata = self
+            .pubdata
+            .as_deref()
+            .ok_or(PubdataPayloadError::PreBoojum)?;
+        let (&source, payload) = pubdata
+            .split_first()
+            .ok_or(PubdataPayloadError::MissingSource)?;
+        let pubdata_da = match source {
+            PUBDATA_SOURCE_CALLDATA => PubdataDA::Calldata,
+            PUBDATA_SOURCE_BLOBS => PubdataDA::Blobs,
+            PUBDATA_SOURCE_CUSTOM => {
+                return Ok(PubdataPayload::Custom {
+                    inclusion_data: payload,
+                })
+            }
+            _ => return Err(PubdataPayloadError::UnknownSource(source)),
+        };
+        if payload.is_empty() {
+            return Ok(PubdataPayload::Empty(pubdata_da));
         }
 
-        if protocol_version.is_pre_1_4_2() {
-            tokens.push(
-                // `totalL2ToL1Pubdata` without pubdata source byte
-                Token::Bytes(
-                    self.l1_batch_with_metadata
-                        .header
-                        .pubdata_input
-                        .clone()
-                        .unwrap_or(self.l1_batch_with_metadata.construct_pubdata()),
-                ),
-            );
-        } else {
-            let pubdata = self
-                .l1_batch_with_metadata
-                .header
-                .pubdata_input
-                .clone()
-                .unwrap_or(self.l1_batch_with_metadata.construct_pubdata());
-            match self.pubdata_da {
-                PubdataDA::Calldata => {
-                    // We compute and add the blob commitment to the pubdata payload so that we can verify the proof
-                    // even if we are not using blobs.
-                    let blob_commitment =
-                        KzgInfo::new(self.kzg_settings.as_ref().unwrap(), &pubdata)
-                            .to_blob_commitment();
-
-                    let result = std::iter::once(PUBDATA_SOURCE_CALLDATA)
-                        .chain(pubdata)
-                        .chain(blob_commitment)
-                        .collect();
-
-                    tokens.push(Token::Bytes(result));
+        Ok(match pubdata_da {
+            PubdataDA::Calldata => {
+                let Some(split_at) = payload.len().checked_sub(32) else {
+                    return Err(PubdataPayloadError::MissingBlobCommitment(payload.len()));
+                };
+                let (pubdata, blob_commitment) = payload.split_at(split_at);
+                PubdataPayload::Calldata {
+                    pubdata,
+                    blob_commitment: H256::from_slice(blob_commitment),
                 }
-                PubdataDA::Blobs => {
-                    let pubdata_commitments = pubdata
-                        .chunks(ZK_SYNC_BYTES_PER_BLOB)
-                        .flat_map(|blob| {
-                            let kzg_info = KzgInfo::new(self.kzg_settings.as_ref().unwrap(), blob);
-                            kzg_info.to_pubdata_commitment().to_vec()
-                        })
-                        .collect::<Vec<u8>>();
-
-                    let result = std::iter::once(PUBDATA_SOURCE_BLOBS)
-                        .chain(pubdata_commitments)
-                        .collect();
-
-                    tokens.push(Token::Bytes(result));
+            }
+            PubdataDA::Blobs => {
+                if payload.len() % BYTES_PER_PUBDATA_COMMITMENT != 0 {
+                    return Err(PubdataPayloadError::InvalidPubdataCommitments(
+                        payload.len(),
+                    ));
                 }
+                let commitments = payload
+                    .chunks_exact(BYTES_PER_PUBDATA_COMMITMENT)
+                    .map(|chunk| chunk.try_into().unwrap()) // chunk size is checked above
+                    .collect();
+                PubdataPayload::Blobs(commitments)
             }
+            PubdataDA::Custom => unreachable!("custom DA is handled above"),
+        })
+    }
+
+    fn decode_pre_boojum(fields: Vec<Token>) -> Result<Self, Web3ContractError> {
+        let decode_byte_arrays = |token: Token, field: &str| {
+            tokens::array(token, field)?
+                .into_iter()
+                .map(|item| tokens::bytes(item, field))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let mut fields = fields.into_iter();
+        let mut next = || fields.next().unwrap(); // length is checked by the caller
+        let batch_number = L1BatchNumber(tokens::uint32(next(), "blockNumber")?);
+        let timestamp = tokens::uint64(next(), "timestamp")?;
+        let index_repeated_storage_changes = tokens::uint64(next(), "indexRepeatedStorageChanges")?;
+        let new_state_root = tokens::bytes32(next(), "newStateRoot")?;
+        let number_of_layer1_txs = tokens::uint(next(), "numberOfLayer1Txs")?;
+        let l2_logs_tree_root = tokens::bytes32(next(), "l2LogsTreeRoot")?;
+        let priority_operations_hash = tokens::bytes32(next(), "priorityOperationsHash")?;
+        let commitments = BatchCommitments::PreBoojum {
+            l2_logs_tree_root,
+            initial_writes_compressed: tokens::bytes(next(), "initialStorageChanges")?,
+            repeated_writes_compressed: tokens::bytes(next(), "repeatedStorageChanges")?,
+            l2_logs: tokens::bytes(next(), "l2Logs")?,
+            l2_arbitrary_length_messages: decode_byte_arrays(next(), "l2ArbitraryLengthMessages")?,
+            factory_deps: decode_byte_arrays(next(), "factoryDeps")?,
+        };
+        Ok(Self {
+            batch_number,
+            timestamp,
+            index_repeated_storage_changes,
+            new_state_root,
+            number_of_layer1_txs,
+            priority_operations_hash,
+            commitments,
+            pubdata: None,
+        })
+    }
+
+    fn decode_post_boojum(fields: Vec<Token>) -> Result<Self, Web3ContractError> {
+        let mut fields = fields.into_iter();
+        // All fields except for the pubdata are present; the length is checked by the caller.
+        let mut next = || fields.next().unwrap();
+        let batch_number = L1BatchNumber(tokens::uint32(next(), "batchNumber")?);
+        let timestamp = tokens::uint64(next(), "timestamp")?;
+        let index_repeated_storage_changes = tokens::uint64(next(), "indexRepeatedStorageChanges")?;
+        let new_state_root = tokens::bytes32(next(), "newStateRoot")?;
+        let number_of_layer1_txs = tokens::uint(next(), "numberOfLayer1Txs")?;
+        let priority_operations_hash = tokens::bytes32(next(), "priorityOperationsHash")?;
+        let commitments = BatchCommitments::PostBoojum {
+            bootloader_heap_initial_contents_hash: tokens::bytes32(
+                next(),
+                "bootloaderHeapInitialContentsHash",
+            )?,
+            events_queue_state_hash: tokens::bytes32(next(), "eventsQueueStateHash")?,
+            system_logs: tokens::bytes(next(), "systemLogs")?,
+        };
+        let pubdata = fields
+            .next()
+            .map(|token| tokens::bytes(token, "pubdataCommitments"))
+            .transpose()?;
+        Ok(Self {
+            batch_number,
+            timestamp,
+            index_repeated_storage_changes,
+            new_state_root,
+            number_of_layer1_txs,
+            priority_operations_hash,
+            commitments,
+            pubdata,
+        })
+    }
+}
+
+impl Tokenizable for CommitBatchInfo {
+    fn from_token(token: Token) -> Result<Self, Web3ContractError>
+    where
+        Self: Sized,
+    {
+        let Token::Tuple(fields) = token else {
+            return Err(tokens::invalid_token(format!(
+                "expected `CommitBatchInfo` tuple, got {token:?}"
+            )));
+        };
+        match fields.len() {
+            12 => Self::decode_pre_boojum(fields),
+            // Post-Boojum batches are always encoded with pubdata, but we allow it to be missing for consistency
+            // with `into_token()`.
+            9 | 10 => Self::decode_post_boojum(fields),
+            len => Err(tokens::invalid_token(format!(
+                "unexpected number of fields in `CommitBatchInfo`: {len}"
+            ))),
         }
+    }
 
+    fn into_token(self) -> Token {
+        let mut tokens = vec![
+            Token::Uint(U256::from(self.batch_number.0)),
+            Token::Uint(U256::from(self.timestamp)),
+            Token::Uint(U256::from(self.index_repeated_storage_changes)),
+            Token::FixedBytes(self.new_state_root.as_bytes().to_vec()),
+            Token::Uint(self.number_of_layer1_txs),
+        ];
+        match self.commitments {
+            BatchCommitments::PreBoojum {
+                l2_logs_tree_root,
+                initial_writes_compressed,
+                repeated_writes_compressed,
+                l2_logs,
+                l2_arbitrary_length_messages,
+                factory_deps,
+            } => {
+                tokens.extend([
+                    Token::FixedBytes(l2_logs_tree_root.as_bytes().to_vec()),
+                    Token::FixedBytes(self.priority_operations_hash.as_bytes().to_vec()),
+                    Token::Bytes(initial_writes_compressed),
+                    Token::Bytes(repeated_writes_compressed),
+                    Token::Bytes(l2_logs),
+                    Token::Array(
+                        l2_arbitrary_length_messages
+                            .into_iter()
+                            .map(Token::Bytes)
+                            .collect(),
+                    ),
+                    Token::Array(factory_deps.into_iter().map(Token::Bytes).collect()),
+                ]);
+            }
+            BatchCommitments::PostBoojum {
+                bootloader_heap_initial_contents_hash,
+                events_queue_state_hash,
+                system_logs,
+            } => {
+                tokens.extend([
+                    // `priorityOperationsHash`
+                    Token::FixedBytes(self.priority_operations_hash.as_bytes().to_vec()),
+                    // `bootloaderHeapInitialContentsHash`
+                    Token::FixedBytes(bootloader_heap_initial_contents_hash.as_bytes().to_vec()),
+                    // `eventsQueueStateHash`
+                    Token::FixedBytes(events_queue_state_hash.as_bytes().to_vec()),
+                    // `systemLogs`
+                    Token::Bytes(system_logs),
+                ]);
+            }
+        }
+        if let Some(pubdata) = self.pubdata {
+            tokens.push(Token::Bytes(pubdata));
+        }
         Token::Tuple(tokens)
     }
 }
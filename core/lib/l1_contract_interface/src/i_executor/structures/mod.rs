@@ -1,6 +1,17 @@
 //! Structures exposed by the `IExecutor.sol`.
 
 mod commit_batch_info;
+mod priority_ops_batch_info;
 mod stored_batch_info;
+#[cfg(test)]
+mod tests;
+mod tokens;
 
-pub use self::{commit_batch_info::CommitBatchInfo, stored_batch_info::StoredBatchInfo};
+pub use self::{
+    commit_batch_info::{
+        BatchCommitments, CommitBatchInfo, CommitBatchInfoBuilder, CommitBatchInfoError,
+        PubdataPayload, PubdataPayloadError,
+    },
+    priority_ops_batch_info::PriorityOpsBatchInfo,
+    stored_batch_info::StoredBatchInfo,
+};
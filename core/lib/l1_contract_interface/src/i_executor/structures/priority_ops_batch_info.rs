@@ -0,0 +1,71 @@
+use zksync_types::{
+    ethabi::{ParamType, Token},
+    web3::contract::Error as Web3ContractError,
+    H256,
+};
+
+use super::tokens;
+use crate::Tokenizable;
+
+/// Encoding for `PriorityOpsBatchInfo` from `IExecutor.sol`: the proof of inclusion of the priority operations
+/// processed in a batch into the priority tree.
+///
+/// The default value corresponds to a batch without priority operations, or to a batch which priority operations
+/// are processed via the legacy priority queue.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriorityOpsBatchInfo {
+    /// Merkle path for the first priority operation in the batch.
+    pub left_path: Vec<H256>,
+    /// Merkle path for the last priority operation in the batch.
+    pub right_path: Vec<H256>,
+    /// Canonical hashes of all priority operations in the batch.
+    pub item_hashes: Vec<H256>,
+}
+
+impl PriorityOpsBatchInfo {
+    /// Returns the ABI type of the struct.
+    pub fn schema() -> ParamType {
+        let hashes = ParamType::Array(Box::new(ParamType::FixedBytes(32)));
+        ParamType::Tuple(vec![hashes.clone(), hashes.clone(), hashes])
+    }
+}
+
+impl Tokenizable for PriorityOpsBatchInfo {
+    fn from_token(token: Token) -> Result<Self, Web3ContractError>
+    where
+        Self: Sized,
+    {
+        let decode_hashes = |token: Token, field: &str| {
+            tokens::array(token, field)?
+                .into_iter()
+                .map(|hash| tokens::bytes32(hash, field))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let mut fields = tokens::tuple_fields(token, "PriorityOpsBatchInfo", 3)?.into_iter();
+        let mut next = || fields.next().unwrap(); // length is checked above
+        Ok(Self {
+            left_path: decode_hashes(next(), "leftPath")?,
+            right_path: decode_hashes(next(), "rightPath")?,
+            item_hashes: decode_hashes(next(), "itemHashes")?,
+        })
+    }
+
+    fn into_token(self) -> Token {
+        let encode_hashes = |hashes: Vec<H256>| {
+            Token::Array(
+                hashes
+                    .into_iter()
+                    .map(|hash| Token::FixedBytes(hash.as_bytes().to_vec()))
+                    .collect(),
+            )
+        };
+        Token::Tuple(vec![
+            // `leftPath`
+            encode_hashes(self.left_path),
+            // `rightPath`
+            encode_hashes(self.right_path),
+            // `itemHashes`
+            encode_hashes(self.item_hashes),
+        ])
+    }
+}
@@ -1,53 +1,94 @@
 use zksync_types::{
     commitment::L1BatchWithMetadata,
-    ethabi::Token,
-    web3::{contract::Error as Web3ContractError, error::Error as Web3ApiError},
-    U256,
+    ethabi::{ParamType, Token},
+    web3::contract::Error as Web3ContractError,
+    L1BatchNumber, H256, U256,
 };
 
+use super::tokens;
 use crate::Tokenizable;
 
 /// Encoding for `StoredBatchInfo` from `IExecutor.sol`
-#[derive(Debug)]
-pub struct StoredBatchInfo<'a>(pub &'a L1BatchWithMetadata);
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredBatchInfo {
+    pub batch_number: L1BatchNumber,
+    pub batch_hash: H256,
+    pub index_repeated_storage_changes: u64,
+    pub number_of_layer1_txs: U256,
+    pub priority_operations_hash: H256,
+    pub l2_logs_tree_root: H256,
+    pub timestamp: U256,
+    pub commitment: H256,
+}
+
+impl StoredBatchInfo {
+    /// Returns the ABI type of the struct.
+    pub fn schema() -> ParamType {
+        ParamType::Tuple(vec![
+            ParamType::Uint(64),
+            ParamType::FixedBytes(32),
+            ParamType::Uint(64),
+            ParamType::Uint(256),
+            ParamType::FixedBytes(32),
+            ParamType::FixedBytes(32),
+            ParamType::Uint(256),
+            ParamType::FixedBytes(32),
+        ])
+    }
+}
+
+impl From<&L1BatchWithMetadata> for StoredBatchInfo {
+    fn from(l1_batch: &L1BatchWithMetadata) -> Self {
+        Self {
+            batch_number: l1_batch.header.number,
+            batch_hash: l1_batch.metadata.root_hash,
+            index_repeated_storage_changes: l1_batch.metadata.rollup_last_leaf_index,
+            number_of_layer1_txs: l1_batch.header.l1_tx_count.into(),
+            priority_operations_hash: l1_batch.header.priority_ops_onchain_data_hash(),
+            l2_logs_tree_root: l1_batch.metadata.l2_l1_merkle_root,
+            timestamp: l1_batch.header.timestamp.into(),
+            commitment: l1_batch.metadata.commitment,
+        }
+    }
+}
 
-impl<'a> Tokenizable for StoredBatchInfo<'a> {
-    fn from_token(_token: Token) -> Result<Self, zksync_types::web3::contract::Error>
+impl Tokenizable for StoredBatchInfo {
+    fn from_token(token: Token) -> Result<Self, Web3ContractError>
     where
         Self: Sized,
     {
-        // Currently there is no need to decode this struct.
-        // We still want to implement `Tokenizable` trait for it, so that *once* it's needed
-        // the implementation is provided here and not in some other inconsistent way.
-        Err(Web3ContractError::Api(Web3ApiError::Decoder(
-            "Not implemented".to_string(),
-        )))
+        let mut fields = tokens::tuple_fields(token, "StoredBatchInfo", 8)?.into_iter();
+        let mut next = || fields.next().unwrap(); // length is checked above
+        Ok(Self {
+            batch_number: L1BatchNumber(tokens::uint32(next(), "batchNumber")?),
+            batch_hash: tokens::bytes32(next(), "batchHash")?,
+            index_repeated_storage_changes: tokens::uint64(next(), "indexRepeatedStorageChanges")?,
+            number_of_layer1_txs: tokens::uint(next(), "numberOfLayer1Txs")?,
+            priority_operations_hash: tokens::bytes32(next(), "priorityOperationsHash")?,
+            l2_logs_tree_root: tokens::bytes32(next(), "l2LogsTreeRoot")?,
+            timestamp: tokens::uint(next(), "timestamp")?,
+            commitment: tokens::bytes32(next(), "commitment")?,
+        })
     }
 
     fn into_token(self) -> Token {
         Token::Tuple(vec![
             // `batchNumber`
-            Token::Uint(U256::from(self.0.header.number.0)),
+            Token::Uint(U256::from(self.batch_number.0)),
             // `batchHash`
-            Token::FixedBytes(self.0.metadata.root_hash.as_bytes().to_vec()),
+            Token::FixedBytes(self.batch_hash.as_bytes().to_vec()),
             // `indexRepeatedStorageChanges`
-            Token::Uint(U256::from(self.0.metadata.rollup_last_leaf_index)),
+            Token::Uint(U256::from(self.index_repeated_storage_changes)),
             // `numberOfLayer1Txs`
-            Token::Uint(U256::from(self.0.header.l1_tx_count)),
+            Token::Uint(self.number_of_layer1_txs),
             // `priorityOperationsHash`
-            Token::FixedBytes(
-                self.0
-                    .header
-                    .priority_ops_onchain_data_hash()
-                    .as_bytes()
-                    .to_vec(),
-            ),
+            Token::FixedBytes(self.priority_operations_hash.as_bytes().to_vec()),
             // `l2LogsTreeRoot`
-            Token::FixedBytes(self.0.metadata.l2_l1_merkle_root.as_bytes().to_vec()),
+            Token::FixedBytes(self.l2_logs_tree_root.as_bytes().to_vec()),
             // timestamp
-            Token::Uint(U256::from(self.0.header.timestamp)),
+            Token::Uint(self.timestamp),
             // commitment
-            Token::FixedBytes(self.0.metadata.commitment.as_bytes().to_vec()),
+            Token::FixedBytes(self.commitment.as_bytes().to_vec()),
         ])
     }
 }
@@ -0,0 +1,217 @@
+//! Round-trip tests for `IExecutor.sol` structures.
+
+use zksync_types::{ethabi, pubdata_da::PubdataDA, L1BatchNumber, H256, U256};
+
+use super::*;
+use crate::{i_executor::commit::kzg::BYTES_PER_PUBDATA_COMMITMENT, Tokenizable};
+
+fn post_boojum_commit_info(pubdata: Option<Vec<u8>>) -> CommitBatchInfo {
+    CommitBatchInfo {
+        batch_number: L1BatchNumber(42),
+        timestamp: 1_700_000_000,
+        index_repeated_storage_changes: 1_000,
+        new_state_root: H256::repeat_byte(1),
+        number_of_layer1_txs: 5.into(),
+        priority_operations_hash: H256::repeat_byte(2),
+        commitments: BatchCommitments::PostBoojum {
+            bootloader_heap_initial_contents_hash: H256::repeat_byte(3),
+            events_queue_state_hash: H256::repeat_byte(4),
+            system_logs: vec![5; 88 * 3],
+        },
+        pubdata,
+    }
+}
+
+/// Checks that the struct survives `into_token()` -> ABI encoding -> ABI decoding -> `from_token()`.
+fn assert_round_trip<T: Tokenizable + Clone + PartialEq + std::fmt::Debug>(
+    value: &T,
+    schema: ethabi::ParamType,
+) {
+    let token = value.clone().into_token();
+    assert_eq!(T::from_token(token.clone()).unwrap(), *value);
+
+    let encoded = ethabi::encode(&[token]);
+    let decoded = ethabi::decode(&[schema], &encoded).unwrap();
+    let decoded = T::from_token(decoded.into_iter().next().unwrap()).unwrap();
+    assert_eq!(decoded, *value);
+}
+
+#[test]
+fn stored_batch_info_round_trip() {
+    let info = StoredBatchInfo {
+        batch_number: L1BatchNumber(42),
+        batch_hash: H256::repeat_byte(1),
+        index_repeated_storage_changes: 1_000,
+        number_of_layer1_txs: 5.into(),
+        priority_operations_hash: H256::repeat_byte(2),
+        l2_logs_tree_root: H256::repeat_byte(3),
+        timestamp: 1_700_000_000.into(),
+        commitment: H256::repeat_byte(4),
+    };
+    assert_round_trip(&info, StoredBatchInfo::schema());
+}
+
+#[test]
+fn pre_boojum_commit_batch_info_round_trip() {
+    let info = CommitBatchInfo {
+        batch_number: L1BatchNumber(1),
+        timestamp: 1_700_000_000,
+        index_repeated_storage_changes: 10,
+        new_state_root: H256::repeat_byte(1),
+        number_of_layer1_txs: 0.into(),
+        priority_operations_hash: H256::repeat_byte(2),
+        commitments: BatchCommitments::PreBoojum {
+            l2_logs_tree_root: H256::repeat_byte(3),
+            initial_writes_compressed: vec![4; 64],
+            repeated_writes_compressed: vec![5; 40],
+            l2_logs: vec![6; 88],
+            l2_arbitrary_length_messages: vec![vec![7; 10], vec![]],
+            factory_deps: vec![vec![8; 32]],
+        },
+        pubdata: None,
+    };
+    assert_round_trip(&info, CommitBatchInfo::pre_boojum_schema());
+}
+
+#[test]
+fn post_boojum_commit_batch_info_round_trip() {
+    // Pre-1.4.2 batch: pubdata without the source byte
+    let info = post_boojum_commit_info(Some(vec![9; 100]));
+    assert_round_trip(&info, CommitBatchInfo::schema());
+
+    // Calldata pubdata followed by the blob commitment
+    let calldata_pubdata = std::iter::once(0).chain([9; 100]).chain([10; 32]).collect();
+    let info = post_boojum_commit_info(Some(calldata_pubdata));
+    assert_round_trip(&info, CommitBatchInfo::schema());
+
+    // Blob pubdata commitments
+    let blob_pubdata = std::iter::once(1)
+        .chain([11; BYTES_PER_PUBDATA_COMMITMENT * 2])
+        .collect();
+    let info = post_boojum_commit_info(Some(blob_pubdata));
+    assert_round_trip(&info, CommitBatchInfo::schema());
+
+    // Validium batch
+    let info = post_boojum_commit_info(Some(vec![0]));
+    assert_round_trip(&info, CommitBatchInfo::schema());
+}
+
+#[test]
+fn post_boojum_commit_batch_info_without_pubdata() {
+    let info = post_boojum_commit_info(None);
+    let token = info.clone().into_token();
+    assert_eq!(CommitBatchInfo::from_token(token).unwrap(), info);
+}
+
+#[test]
+fn priority_ops_batch_info_round_trip() {
+    assert_round_trip(
+        &PriorityOpsBatchInfo::default(),
+        PriorityOpsBatchInfo::schema(),
+    );
+    let info = PriorityOpsBatchInfo {
+        left_path: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+        right_path: vec![H256::repeat_byte(3)],
+        item_hashes: vec![H256::repeat_byte(4); 3],
+    };
+    assert_round_trip(&info, PriorityOpsBatchInfo::schema());
+}
+
+#[test]
+fn decoding_malformed_tokens() {
+    let err = StoredBatchInfo::from_token(ethabi::Token::Bool(true)).unwrap_err();
+    assert!(err.to_string().contains("StoredBatchInfo"), "{err}");
+
+    let mut token = post_boojum_commit_info(None).into_token();
+    let ethabi::Token::Tuple(fields) = &mut token else {
+        unreachable!();
+    };
+    fields[1] = ethabi::Token::Uint(U256::MAX);
+    let err = CommitBatchInfo::from_token(token).unwrap_err();
+    assert!(err.to_string().contains("timestamp"), "{err}");
+}
+
+#[test]
+fn decoding_tokens_with_unexpected_number_of_fields() {
+    let mut token = post_boojum_commit_info(None).into_token();
+    let ethabi::Token::Tuple(fields) = &mut token else {
+        unreachable!();
+    };
+    fields.truncate(5);
+    CommitBatchInfo::from_token(token).unwrap_err();
+}
+
+#[test]
+fn parsing_pubdata_payload() {
+    let calldata_pubdata: Vec<_> = std::iter::once(0).chain([9; 100]).chain([10; 32]).collect();
+    let info = post_boojum_commit_info(Some(calldata_pubdata));
+    assert_eq!(
+        info.pubdata_payload().unwrap(),
+        PubdataPayload::Calldata {
+            pubdata: &[9; 100],
+            blob_commitment: H256::repeat_byte(10),
+        }
+    );
+
+    let blob_pubdata: Vec<_> = std::iter::once(1)
+        .chain([11; BYTES_PER_PUBDATA_COMMITMENT])
+        .chain([12; BYTES_PER_PUBDATA_COMMITMENT])
+        .collect();
+    let info = post_boojum_commit_info(Some(blob_pubdata));
+    assert_eq!(
+        info.pubdata_payload().unwrap(),
+        PubdataPayload::Blobs(vec![
+            &[11; BYTES_PER_PUBDATA_COMMITMENT],
+            &[12; BYTES_PER_PUBDATA_COMMITMENT],
+        ])
+    );
+
+    let info = post_boojum_commit_info(Some(vec![1]));
+    assert_eq!(
+        info.pubdata_payload().unwrap(),
+        PubdataPayload::Empty(PubdataDA::Blobs)
+    );
+
+    let info = post_boojum_commit_info(Some(vec![2, 13, 14, 15]));
+    assert_eq!(
+        info.pubdata_payload().unwrap(),
+        PubdataPayload::Custom {
+            inclusion_data: &[13, 14, 15]
+        }
+    );
+}
+
+#[test]
+fn parsing_invalid_pubdata_payload() {
+    let err = post_boojum_commit_info(None).pubdata_payload().unwrap_err();
+    assert!(matches!(err, PubdataPayloadError::PreBoojum), "{err}");
+
+    let err = post_boojum_commit_info(Some(vec![]))
+        .pubdata_payload()
+        .unwrap_err();
+    assert!(matches!(err, PubdataPayloadError::MissingSource), "{err}");
+
+    let err = post_boojum_commit_info(Some(vec![3, 0]))
+        .pubdata_payload()
+        .unwrap_err();
+    assert!(
+        matches!(err, PubdataPayloadError::UnknownSource(3)),
+        "{err}"
+    );
+
+    let err = post_boojum_commit_info(Some(vec![0; 10]))
+        .pubdata_payload()
+        .unwrap_err();
+    assert!(
+        matches!(err, PubdataPayloadError::MissingBlobCommitment(9)),
+        "{err}"
+    );
+
+    let err = post_boojum_commit_info(Some(vec![1; 100]))
+        .pubdata_payload()
+        .unwrap_err();
+    assert!(
+        matches!(err, PubdataPayloadError::InvalidPubdataCommitments(99)),
+        "{err}"
+    );
+}
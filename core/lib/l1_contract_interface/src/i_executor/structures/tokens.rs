@@ -0,0 +1,69 @@
+//! Helpers for decoding tokens of `IExecutor.sol` structures.
+
+use std::convert::TryFrom;
+
+use zksync_types::{ethabi::Token, web3::contract::Error as Web3ContractError, H256, U256};
+
+pub(super) fn invalid_token(message: impl Into<String>) -> Web3ContractError {
+    Web3ContractError::InvalidOutputType(message.into())
+}
+
+/// Splits a tuple token into its fields, checking the number of fields.
+pub(super) fn tuple_fields(
+    token: Token,
+    struct_name: &str,
+    expected_len: usize,
+) -> Result<Vec<Token>, Web3ContractError> {
+    let Token::Tuple(fields) = token else {
+        return Err(invalid_token(format!(
+            "expected `{struct_name}` tuple, got {token:?}"
+        )));
+    };
+    if fields.len() != expected_len {
+        return Err(invalid_token(format!(
+            "expected {expected_len} fields in `{struct_name}`, got {}",
+            fields.len()
+        )));
+    }
+    Ok(fields)
+}
+
+pub(super) fn uint(token: Token, field: &str) -> Result<U256, Web3ContractError> {
+    token
+        .into_uint()
+        .ok_or_else(|| invalid_token(format!("`{field}` is not an integer")))
+}
+
+pub(super) fn uint64(token: Token, field: &str) -> Result<u64, Web3ContractError> {
+    let value = uint(token, field)?;
+    if value > U256::from(u64::MAX) {
+        return Err(invalid_token(format!(
+            "`{field}` doesn't fit into `uint64`: {value}"
+        )));
+    }
+    Ok(value.as_u64())
+}
+
+pub(super) fn uint32(token: Token, field: &str) -> Result<u32, Web3ContractError> {
+    let value = uint64(token, field)?;
+    u32::try_from(value).map_err(|_| invalid_token(format!("`{field}` is out of range: {value}")))
+}
+
+pub(super) fn bytes32(token: Token, field: &str) -> Result<H256, Web3ContractError> {
+    match token {
+        Token::FixedBytes(bytes) if bytes.len() == 32 => Ok(H256::from_slice(&bytes)),
+        _ => Err(invalid_token(format!("`{field}` is not `bytes32`"))),
+    }
+}
+
+pub(super) fn bytes(token: Token, field: &str) -> Result<Vec<u8>, Web3ContractError> {
+    token
+        .into_bytes()
+        .ok_or_else(|| invalid_token(format!("`{field}` is not `bytes`")))
+}
+
+pub(super) fn array(token: Token, field: &str) -> Result<Vec<Token>, Web3ContractError> {
+    token
+        .into_array()
+        .ok_or_else(|| invalid_token(format!("`{field}` is not an array")))
+}
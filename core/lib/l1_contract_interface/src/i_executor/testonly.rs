@@ -0,0 +1,49 @@
+//! Test utils shared by `IExecutor.sol` encoding tests.
+
+use zksync_types::{
+    block::L1BatchHeader,
+    commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
+    L1BatchNumber, ProtocolVersionId, H256,
+};
+
+/// Creates an L1 batch with deterministic data. Changing the returned data will break encoding fixtures.
+pub(crate) fn mock_l1_batch(
+    number: u32,
+    protocol_version: ProtocolVersionId,
+) -> L1BatchWithMetadata {
+    let mut header = L1BatchHeader::new(
+        L1BatchNumber(number),
+        1_700_000_000 + u64::from(number),
+        Default::default(),
+        protocol_version,
+    );
+    header.l1_tx_count = 3;
+    header.l2_to_l1_messages = vec![vec![1, 2, 3]];
+    header.pubdata_input = Some(vec![0xab; 100]);
+
+    let metadata = L1BatchMetadata {
+        root_hash: H256::repeat_byte(1),
+        rollup_last_leaf_index: 100 + u64::from(number),
+        merkle_root_hash: H256::repeat_byte(2),
+        initial_writes_compressed: Some(vec![3; 10]),
+        repeated_writes_compressed: Some(vec![4; 5]),
+        commitment: H256::repeat_byte(5),
+        l2_l1_merkle_root: H256::repeat_byte(6),
+        block_meta_params: L1BatchMetaParameters {
+            zkporter_is_available: false,
+            bootloader_code_hash: H256::zero(),
+            default_aa_code_hash: H256::zero(),
+        },
+        aux_data_hash: H256::zero(),
+        meta_parameters_hash: H256::zero(),
+        pass_through_data_hash: H256::zero(),
+        events_queue_commitment: Some(H256::repeat_byte(7)),
+        bootloader_initial_content_commitment: Some(H256::repeat_byte(8)),
+        state_diffs_compressed: vec![],
+    };
+    L1BatchWithMetadata {
+        header,
+        metadata,
+        raw_published_factory_deps: vec![vec![9; 32]],
+    }
+}
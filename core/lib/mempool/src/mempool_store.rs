@@ -1,7 +1,8 @@
 use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, AccountNonceOrdering, Address, ExecuteTransactionCommon, Nonce,
+    PriorityOpId, Transaction, U256,
 };
 
 use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
@@ -27,6 +28,12 @@ pub struct MempoolStore {
     l2_transactions_per_account: HashMap<Address, AccountTransactions>,
     /// Global priority queue for L2 transactions. Used for scoring
     l2_priority_queue: BTreeSet<MempoolScore>,
+    /// Operator accounts whose transactions are force-included, i.e. served ahead of other L2 transactions.
+    force_include_accounts: HashSet<Address>,
+    /// Priority queue for transactions of `force_include_accounts`.
+    force_include_queue: BTreeSet<MempoolScore>,
+    /// If set, L2 transactions are prioritized by their arrival sequence numbers.
+    fair_ordering: bool,
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
@@ -41,6 +48,9 @@ impl MempoolStore {
             l1_transactions: HashMap::new(),
             l2_transactions_per_account: HashMap::new(),
             l2_priority_queue: BTreeSet::new(),
+            force_include_accounts: HashSet::new(),
+            force_include_queue: BTreeSet::new(),
+            fair_ordering: false,
             next_priority_id,
             stashed_accounts: vec![],
             size: 0,
@@ -48,6 +58,51 @@ impl MempoolStore {
         }
     }
 
+    /// Sets operator accounts whose L2 transactions are force-included: they are returned by
+    /// [`Self::next_transaction()`] ahead of transactions from other accounts regardless of their score,
+    /// and are never stashed or purged. Should be called before any transactions are inserted.
+    pub fn set_force_include_accounts(&mut self, accounts: HashSet<Address>) {
+        assert!(
+            self.l2_transactions_per_account.is_empty(),
+            "force-include accounts must be set for an empty mempool"
+        );
+        self.force_include_accounts = accounts;
+    }
+
+    /// Enables fair ordering: L2 transactions are returned by [`Self::next_transaction()`] in the order
+    /// of their arrival sequence numbers rather than received timestamps. Should be called before any transactions
+    /// are inserted.
+    pub fn set_fair_ordering(&mut self, fair_ordering: bool) {
+        assert!(
+            self.l2_transactions_per_account.is_empty(),
+            "fair ordering must be set for an empty mempool"
+        );
+        self.fair_ordering = fair_ordering;
+    }
+
+    /// Changes the capacity of the mempool. If the mempool is over capacity, excess transactions are purged
+    /// on the next [`Self::get_mempool_info()`] call.
+    pub fn set_capacity(&mut self, capacity: u64) {
+        self.capacity = capacity;
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Checks whether transactions of the specified account are force-included.
+    pub fn is_force_include_account(&self, account: &Address) -> bool {
+        self.force_include_accounts.contains(account)
+    }
+
+    fn queue_mut(&mut self, account: &Address) -> &mut BTreeSet<MempoolScore> {
+        if self.force_include_accounts.contains(account) {
+            &mut self.force_include_queue
+        } else {
+            &mut self.l2_priority_queue
+        }
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -56,6 +111,18 @@ impl MempoolStore {
         &mut self,
         transactions: Vec<Transaction>,
         initial_nonces: HashMap<Address, Nonce>,
+    ) {
+        self.insert_with_nonce_orderings(transactions, initial_nonces, &HashMap::new());
+    }
+
+    /// Same as [`Self::insert()`], but additionally provides nonce orderings of transaction initiators.
+    /// Accounts missing from `nonce_orderings` are assumed to have sequential nonce ordering if they are
+    /// not present in mempool yet.
+    pub fn insert_with_nonce_orderings(
+        &mut self,
+        transactions: Vec<Transaction>,
+        initial_nonces: HashMap<Address, Nonce>,
+        nonce_orderings: &HashMap<Address, AccountNonceOrdering>,
     ) {
         for transaction in transactions {
             let Transaction {
@@ -86,6 +153,7 @@ impl MempoolStore {
                             raw_bytes,
                         },
                         &initial_nonces,
+                        nonce_orderings,
                     );
                 }
                 ExecuteTransactionCommon::ProtocolUpgrade(_) => {
@@ -99,23 +167,39 @@ impl MempoolStore {
         &mut self,
         transaction: L2Tx,
         initial_nonces: &HashMap<Address, Nonce>,
+        nonce_orderings: &HashMap<Address, AccountNonceOrdering>,
     ) {
         let account = transaction.initiator_account();
+        let nonce_ordering = nonce_orderings.get(&account).copied();
 
         let metadata = match self.l2_transactions_per_account.entry(account) {
-            hash_map::Entry::Occupied(mut txs) => txs.get_mut().insert(transaction),
+            hash_map::Entry::Occupied(mut txs) => {
+                let txs = txs.get_mut();
+                let ready_scores = nonce_ordering
+                    .map(|nonce_ordering| txs.set_nonce_ordering(nonce_ordering))
+                    .unwrap_or_default();
+                let metadata = txs.insert(transaction);
+                self.queue_mut(&account).extend(ready_scores);
+                metadata
+            }
             hash_map::Entry::Vacant(entry) => {
                 let account_nonce = initial_nonces.get(&account).cloned().unwrap_or(Nonce(0));
+                let nonce_ordering = nonce_ordering.unwrap_or_default();
                 entry
-                    .insert(AccountTransactions::new(account_nonce))
+                    .insert(AccountTransactions::new(
+                        account_nonce,
+                        nonce_ordering,
+                        self.fair_ordering,
+                    ))
                     .insert(transaction)
             }
         };
+        let queue = self.queue_mut(&account);
         if let Some(score) = metadata.previous_score {
-            self.l2_priority_queue.remove(&score);
+            queue.remove(&score);
         }
         if let Some(score) = metadata.new_score {
-            self.l2_priority_queue.insert(score);
+            queue.insert(score);
         }
         if metadata.is_new {
             self.size += 1;
@@ -125,6 +209,10 @@ impl MempoolStore {
     /// Returns `true` if there is a transaction in the mempool satisfying the filter.
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
         self.l1_transactions.get(&self.next_priority_id).is_some()
+            || self
+                .force_include_queue
+                .iter()
+                .any(|el| el.matches_filter(filter))
             || self
                 .l2_priority_queue
                 .iter()
@@ -138,6 +226,9 @@ impl MempoolStore {
             self.next_priority_id += 1;
             return Some(transaction.into());
         }
+        if let Some(transaction) = self.next_force_included_transaction(filter) {
+            return Some(transaction.into());
+        }
 
         let mut removed = 0;
         // We want to fetch the next transaction that would match the fee requirements.
@@ -154,12 +245,24 @@ impl MempoolStore {
             .into_iter()
             .skip(1)
         {
-            removed += self
+            if stashed_pointer.account == tx_pointer.account {
+                // Accounts with arbitrary nonce ordering can have multiple transactions in the queue.
+                // We don't want to stash the account we're getting the transaction from.
+                self.l2_priority_queue.insert(stashed_pointer);
+                continue;
+            }
+            // The account may have already been stashed if it has multiple transactions in the queue.
+            let Some(account_transactions) = self
                 .l2_transactions_per_account
                 .remove(&stashed_pointer.account)
-                .expect("mempool: dangling pointer in priority queue")
-                .len();
-
+            else {
+                continue;
+            };
+            // Remove the remaining account transactions from the queue to not leave dangling pointers.
+            for score in account_transactions.queued_scores() {
+                self.l2_priority_queue.remove(&score);
+            }
+            removed += account_transactions.len();
             self.stashed_accounts.push(stashed_pointer.account);
         }
         // insert pointer to the next transaction if it exists
@@ -167,7 +270,7 @@ impl MempoolStore {
             .l2_transactions_per_account
             .get_mut(&tx_pointer.account)
             .expect("mempool: dangling pointer in priority queue")
-            .next();
+            .next(&tx_pointer);
 
         if let Some(score) = score {
             self.l2_priority_queue.insert(score);
@@ -179,6 +282,30 @@ impl MempoolStore {
         Some(transaction.into())
     }
 
+    fn next_force_included_transaction(&mut self, filter: &L2TxFilter) -> Option<L2Tx> {
+        // Transactions not matching the filter are not stashed; they will be returned once the fee requirements drop.
+        let tx_pointer = self
+            .force_include_queue
+            .iter()
+            .rfind(|el| el.matches_filter(filter))?
+            .clone();
+        self.force_include_queue.remove(&tx_pointer);
+        let (transaction, score) = self
+            .l2_transactions_per_account
+            .get_mut(&tx_pointer.account)
+            .expect("mempool: dangling pointer in force-include queue")
+            .next(&tx_pointer);
+
+        if let Some(score) = score {
+            self.force_include_queue.insert(score);
+        }
+        self.size = self
+            .size
+            .checked_sub(1)
+            .expect("mempool size can't be negative");
+        Some(transaction)
+    }
+
     /// When a state_keeper starts the block over after a rejected transaction,
     /// we have to rollback the nonces/ids in the mempool and
     /// reinsert the transactions from the block back into mempool.
@@ -190,13 +317,14 @@ impl MempoolStore {
                 self.next_priority_id = self.next_priority_id.min(data.serial_id);
             }
             ExecuteTransactionCommon::L2(_) => {
+                let account = tx.initiator_account();
                 if let Some(score) = self
                     .l2_transactions_per_account
-                    .get_mut(&tx.initiator_account())
+                    .get_mut(&account)
                     .expect("account is not available in mempool")
                     .reset(tx)
                 {
-                    self.l2_priority_queue.remove(&score);
+                    self.queue_mut(&account).remove(&score);
                 }
             }
             ExecuteTransactionCommon::ProtocolUpgrade(_) => {
@@ -212,6 +340,14 @@ impl MempoolStore {
         }
     }
 
+    /// Returns the minimum `max_fee_per_gas` among pending L1 transactions, or `None` if there are none.
+    pub fn min_l1_tx_fee_per_gas(&self) -> Option<U256> {
+        self.l1_transactions
+            .values()
+            .map(|tx| tx.common_data.max_fee_per_gas)
+            .min()
+    }
+
     pub fn stats(&self) -> MempoolStats {
         MempoolStats {
             l1_transaction_count: self.l1_transactions.len(),
@@ -226,6 +362,7 @@ impl MempoolStore {
                 .l2_priority_queue
                 .iter()
                 .map(|pointer| pointer.account)
+                .chain(self.force_include_accounts.iter().copied())
                 .collect();
             let transactions = std::mem::take(&mut self.l2_transactions_per_account);
             let (kept, drained) = transactions
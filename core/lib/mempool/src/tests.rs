@@ -8,8 +8,8 @@ use zksync_types::{
     helpers::unix_timestamp_ms,
     l1::{OpProcessingType, PriorityQueueType},
     l2::L2Tx,
-    Address, Execute, ExecuteTransactionCommon, L1TxCommonData, Nonce, PriorityOpId, Transaction,
-    H256, U256,
+    AccountNonceOrdering, Address, Execute, ExecuteTransactionCommon, L1TxCommonData, Nonce,
+    PriorityOpId, Transaction, H256, U256,
 };
 
 use crate::{mempool_store::MempoolStore, types::L2TxFilter};
@@ -365,6 +365,240 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn arrival_sequence_takes_precedence_over_timestamp() {
+    for fair_ordering in [false, true] {
+        let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+        mempool.set_fair_ordering(fair_ordering);
+        let account0 = Address::random();
+        let account1 = Address::random();
+        let now = unix_timestamp_ms();
+        let mut tx0 = gen_l2_tx_with_timestamp(account0, Nonce(0), now);
+        let mut tx1 = gen_l2_tx_with_timestamp(account1, Nonce(0), now + 1);
+        for (tx, arrival_seq) in [(&mut tx0, 2), (&mut tx1, 1)] {
+            let ExecuteTransactionCommon::L2(data) = &mut tx.common_data else {
+                unreachable!();
+            };
+            data.arrival_seq = Some(arrival_seq);
+        }
+        mempool.insert(vec![tx0, tx1], HashMap::new());
+
+        let accounts: Vec<_> = (0..2)
+            .map(|_| {
+                mempool
+                    .next_transaction(&L2TxFilter::default())
+                    .unwrap()
+                    .initiator_account()
+            })
+            .collect();
+        // Without fair ordering, arrival sequence numbers are ignored.
+        let expected_accounts = if fair_ordering {
+            [account1, account0]
+        } else {
+            [account0, account1]
+        };
+        assert_eq!(accounts, expected_accounts, "fair_ordering={fair_ordering}");
+    }
+}
+
+#[test]
+fn arbitrary_nonce_ordering() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account = Address::random();
+    let now = unix_timestamp_ms();
+    let transactions = vec![
+        gen_l2_tx_with_timestamp(account, Nonce(7), now),
+        gen_l2_tx_with_timestamp(account, Nonce(3), now + 1),
+        gen_l2_tx_with_timestamp(account, Nonce(1), now + 2),
+    ];
+    let nonce_orderings = HashMap::from([(account, AccountNonceOrdering::Arbitrary)]);
+    mempool.insert_with_nonce_orderings(
+        transactions,
+        HashMap::from([(account, Nonce(1))]),
+        &nonce_orderings,
+    );
+    assert_eq!(mempool.stats().l2_priority_queue_size, 3);
+
+    // Transactions are returned in the order of arrival rather than in the nonce order.
+    for expected_nonce in [7, 3, 1] {
+        assert_eq!(
+            view(mempool.next_transaction(&L2TxFilter::default())),
+            (account, expected_nonce)
+        );
+    }
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+
+    // Rejection of a transaction doesn't block other account transactions.
+    let transactions = vec![
+        gen_l2_tx_with_timestamp(account, Nonce(5), now + 3),
+        gen_l2_tx_with_timestamp(account, Nonce(2), now + 4),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    let rejected = mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    mempool.rollback(&rejected);
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 2)
+    );
+    // Transactions with nonces below the committed nonce are ignored.
+    mempool.insert(vec![gen_l2_tx(account, Nonce(0))], HashMap::new());
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+}
+
+#[test]
+fn switching_to_arbitrary_nonce_ordering() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account = Address::random();
+    mempool.insert(
+        vec![gen_l2_tx(account, Nonce(0)), gen_l2_tx(account, Nonce(2))],
+        HashMap::new(),
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 0)
+    );
+    // Nonce 1 is missing, so the account is blocked.
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+
+    let nonce_orderings = HashMap::from([(account, AccountNonceOrdering::Arbitrary)]);
+    mempool.insert_with_nonce_orderings(
+        vec![gen_l2_tx(account, Nonce(4))],
+        HashMap::new(),
+        &nonce_orderings,
+    );
+    let mut nonces: Vec<_> = (0..2)
+        .map(|_| view(mempool.next_transaction(&L2TxFilter::default())).1)
+        .collect();
+    nonces.sort_unstable();
+    assert_eq!(nonces, [2, 4]);
+    assert_eq!(mempool.stats().l2_transaction_count, 0);
+}
+
+#[test]
+fn stashing_account_with_arbitrary_nonce_ordering() {
+    let filter_non_zero = L2TxFilter {
+        fee_input: Default::default(),
+        fee_per_gas: 0u64,
+        gas_per_pubdata: 1u32,
+    };
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let now = unix_timestamp_ms();
+    let nonce_orderings = HashMap::from([(account0, AccountNonceOrdering::Arbitrary)]);
+    mempool.insert_with_nonce_orderings(
+        gen_transactions_for_filtering(vec![
+            (account0, Nonce(0), now, 0),
+            (account1, Nonce(0), now + 10, 1),
+            (account0, Nonce(1), now + 20, 0),
+        ]),
+        HashMap::new(),
+        &nonce_orderings,
+    );
+
+    assert_eq!(
+        view(mempool.next_transaction(&filter_non_zero)),
+        (account1, 0)
+    );
+    // All `account0` transactions must be stashed, including ones with lower priority.
+    assert_eq!(mempool.get_mempool_info().stashed_accounts, vec![account0]);
+    let stats = mempool.stats();
+    assert_eq!(stats.l2_transaction_count, 0);
+    assert_eq!(stats.l2_priority_queue_size, 0);
+}
+
+#[test]
+fn force_included_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let operator = Address::random();
+    let account = Address::random();
+    mempool.set_force_include_accounts(HashSet::from([operator]));
+    assert!(mempool.is_force_include_account(&operator));
+    assert!(!mempool.is_force_include_account(&account));
+
+    let now = unix_timestamp_ms();
+    mempool.insert(
+        vec![
+            gen_l2_tx_with_timestamp(account, Nonce(0), now),
+            gen_l2_tx_with_timestamp(operator, Nonce(0), now + 10),
+            gen_l2_tx_with_timestamp(operator, Nonce(1), now + 20),
+            gen_l1_tx(PriorityOpId(0)),
+        ],
+        HashMap::new(),
+    );
+
+    // L1 transactions are still processed first.
+    assert!(mempool
+        .next_transaction(&L2TxFilter::default())
+        .unwrap()
+        .is_l1());
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (operator, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (operator, 1)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 0)
+    );
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+
+    // Force-included transactions not matching the filter should not be stashed.
+    let filter_non_zero = L2TxFilter {
+        fee_input: Default::default(),
+        fee_per_gas: 0u64,
+        gas_per_pubdata: 1u32,
+    };
+    mempool.insert(
+        gen_transactions_for_filtering(vec![
+            (operator, Nonce(2), now + 30, 0),
+            (account, Nonce(1), now + 40, 1),
+        ]),
+        HashMap::new(),
+    );
+    assert!(mempool.has_next(&filter_non_zero));
+    assert_eq!(
+        view(mempool.next_transaction(&filter_non_zero)),
+        (account, 1)
+    );
+    assert_eq!(mempool.next_transaction(&filter_non_zero), None);
+    assert!(mempool.get_mempool_info().stashed_accounts.is_empty());
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (operator, 2)
+    );
+    assert_eq!(mempool.stats().l2_transaction_count, 0);
+}
+
+#[test]
+fn min_l1_tx_fee_per_gas() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    assert_eq!(mempool.min_l1_tx_fee_per_gas(), None);
+
+    let transactions = [300_u64, 100, 200]
+        .into_iter()
+        .zip(0..)
+        .map(|(max_fee_per_gas, id)| {
+            let mut tx = gen_l1_tx(PriorityOpId(id));
+            let ExecuteTransactionCommon::L1(data) = &mut tx.common_data else {
+                unreachable!();
+            };
+            data.max_fee_per_gas = max_fee_per_gas.into();
+            tx
+        })
+        .collect();
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.min_l1_tx_fee_per_gas(), Some(100.into()));
+
+    // Once the cheapest transaction is processed, the minimum should be updated.
+    mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    assert_eq!(mempool.min_l1_tx_fee_per_gas(), Some(200.into()));
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -1,7 +1,8 @@
 use std::{cmp::Ordering, collections::HashMap};
 
 use zksync_types::{
-    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, U256,
+    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, AccountNonceOrdering, Address, Nonce,
+    Transaction, U256,
 };
 
 /// Pending mempool transactions of account
@@ -10,15 +11,22 @@ pub(crate) struct AccountTransactions {
     /// transactions that belong to given account keyed by transaction nonce
     transactions: HashMap<Nonce, L2Tx>,
     /// account nonce in mempool
-    /// equals to committed nonce in db + number of transactions sent to state keeper
+    /// equals to committed nonce in db + number of transactions sent to state keeper.
+    /// For accounts with arbitrary nonce ordering, it's the committed nonce in db; transactions with lesser nonces
+    /// are guaranteed to fail.
     nonce: Nonce,
+    nonce_ordering: AccountNonceOrdering,
+    /// Whether transaction scores take arrival sequence numbers into account.
+    fair_ordering: bool,
 }
 
 impl AccountTransactions {
-    pub fn new(nonce: Nonce) -> Self {
+    pub fn new(nonce: Nonce, nonce_ordering: AccountNonceOrdering, fair_ordering: bool) -> Self {
         Self {
             transactions: HashMap::new(),
             nonce,
+            nonce_ordering,
+            fair_ordering,
         }
     }
 
@@ -30,22 +38,51 @@ impl AccountTransactions {
         if nonce < self.nonce {
             return metadata;
         }
-        let new_score = Self::score_for_transaction(&transaction);
+        let new_score = self.score_for_transaction(&transaction);
         let previous_score = self
             .transactions
             .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+            .map(|tx| self.score_for_transaction(&tx));
         metadata.is_new = previous_score.is_none();
-        if nonce == self.nonce {
+        // With arbitrary nonce ordering, all account transactions are ready for execution at the same time.
+        if nonce == self.nonce || self.nonce_ordering == AccountNonceOrdering::Arbitrary {
             metadata.new_score = Some(new_score);
             metadata.previous_score = previous_score;
         }
         metadata
     }
 
+    /// Updates the nonce ordering for the account. Returns scores of transactions that became ready for execution.
+    /// Accounts can only switch from sequential to arbitrary nonce ordering.
+    pub fn set_nonce_ordering(
+        &mut self,
+        nonce_ordering: AccountNonceOrdering,
+    ) -> Vec<MempoolScore> {
+        if self.nonce_ordering == nonce_ordering
+            || nonce_ordering == AccountNonceOrdering::Sequential
+        {
+            return vec![];
+        }
+        self.nonce_ordering = nonce_ordering;
+        self.transactions
+            .iter()
+            .filter(|(&nonce, _)| nonce != self.nonce)
+            .map(|(_, tx)| self.score_for_transaction(tx))
+            .collect()
+    }
+
     /// Returns next transaction to be included in block and optional score of its successor
     /// Panics if no such transaction exists
-    pub fn next(&mut self) -> (L2Tx, Option<MempoolScore>) {
+    pub fn next(&mut self, score: &MempoolScore) -> (L2Tx, Option<MempoolScore>) {
+        if self.nonce_ordering == AccountNonceOrdering::Arbitrary {
+            // All other transactions of the account are already in the priority queue.
+            let transaction = self
+                .transactions
+                .remove(&score.nonce)
+                .expect("missing transaction in mempool");
+            return (transaction, None);
+        }
+
         let transaction = self
             .transactions
             .remove(&self.nonce)
@@ -54,12 +91,17 @@ impl AccountTransactions {
         let score = self
             .transactions
             .get(&self.nonce)
-            .map(Self::score_for_transaction);
+            .map(|tx| self.score_for_transaction(tx));
         (transaction, score)
     }
 
     /// Handles transaction rejection. Returns optional score of its successor
     pub fn reset(&mut self, transaction: &Transaction) -> Option<MempoolScore> {
+        if self.nonce_ordering == AccountNonceOrdering::Arbitrary {
+            // Transactions are independent, so the rejection doesn't influence other account transactions.
+            return None;
+        }
+
         // current nonce for the group needs to be reset
         let tx_nonce = transaction
             .nonce()
@@ -67,28 +109,58 @@ impl AccountTransactions {
         self.nonce = self.nonce.min(tx_nonce);
         self.transactions
             .get(&(tx_nonce + 1))
-            .map(Self::score_for_transaction)
+            .map(|tx| self.score_for_transaction(tx))
+    }
+
+    /// Returns scores of all account transactions that can be present in the priority queue.
+    pub fn queued_scores(&self) -> Vec<MempoolScore> {
+        match self.nonce_ordering {
+            AccountNonceOrdering::Sequential => self
+                .transactions
+                .get(&self.nonce)
+                .map(|tx| self.score_for_transaction(tx))
+                .into_iter()
+                .collect(),
+            AccountNonceOrdering::Arbitrary => self
+                .transactions
+                .values()
+                .map(|tx| self.score_for_transaction(tx))
+                .collect(),
+        }
     }
 
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
-    fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
+    fn score_for_transaction(&self, transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
+            nonce: transaction.common_data.nonce,
             received_at_ms: transaction.received_timestamp_ms,
+            arrival_seq: transaction
+                .common_data
+                .arrival_seq
+                .filter(|_| self.fair_ordering),
             fee_data: transaction.common_data.fee.clone(),
         }
     }
 }
 
 /// Mempool score of transaction. Used to prioritize L2 transactions in mempool
-/// Currently trivial ordering is used based on received at timestamp
+/// Currently trivial ordering is used based on the arrival sequence number (only with fair ordering)
+/// and received at timestamp
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct MempoolScore {
     pub account: Address,
+    /// Transaction nonce. Used to distinguish transactions of accounts with arbitrary nonce ordering,
+    /// several of which can be present in the priority queue at the same time.
+    pub nonce: Nonce,
     pub received_at_ms: u64,
+    /// Arrival sequence number assigned to the transaction when it was persisted. Only set if the mempool
+    /// uses fair ordering. Transactions without one (i.e., persisted before sequence numbers were introduced)
+    /// are prioritized.
+    pub arrival_seq: Option<u64>,
     // Not used for actual scoring, but state keeper would request
     // transactions that have acceptable fee values (so transactions
     // with fee too low would be ignored until prices go down).
@@ -105,11 +177,19 @@ impl MempoolScore {
 
 impl Ord for MempoolScore {
     fn cmp(&self, other: &MempoolScore) -> Ordering {
+        match self.arrival_seq.cmp(&other.arrival_seq).reverse() {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
         match self.received_at_ms.cmp(&other.received_at_ms).reverse() {
             Ordering::Equal => {}
             ordering => return ordering,
         }
-        self.account.cmp(&other.account)
+        match self.account.cmp(&other.account) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+        self.nonce.cmp(&other.nonce).reverse()
     }
 }
 
@@ -159,7 +239,9 @@ mod tests {
 
         let score = MempoolScore {
             account: Address::random(),
+            nonce: Nonce(0),                    // Not important
             received_at_ms: Default::default(), // Not important
+            arrival_seq: None,                  // Not important
             fee_data: Fee {
                 gas_limit: Default::default(), // Not important
                 max_fee_per_gas: U256::from(MAX_FEE_PER_GAS),
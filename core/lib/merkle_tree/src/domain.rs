@@ -430,6 +430,15 @@ impl ZkSyncTreeReader {
         self.0.latest_root().leaf_count()
     }
 
+    /// Returns the root hash and the number of leaves in the tree after processing the specified L1 batch,
+    /// or `None` if the tree has no version for the batch.
+    pub fn root_info(&self, l1_batch_number: L1BatchNumber) -> Option<(ValueHash, u64)> {
+        let version = u64::from(l1_batch_number.0);
+        let root_hash = self.0.root_hash(version)?;
+        let leaf_count = self.0.root(version)?.leaf_count();
+        Some((root_hash, leaf_count))
+    }
+
     /// Reads entries together with Merkle proofs with the specified keys from the tree. The entries are returned
     /// in the same order as requested.
     ///
@@ -172,6 +172,12 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         Some(node.hash(&mut HasherWithStats::new(&self.hasher), 0))
     }
 
+    /// Returns the number of leaves in the tree at the specified `version`, or `None` if the version
+    /// was not written yet.
+    pub fn leaf_count(&self, version: u64) -> Option<u64> {
+        Some(self.root(version)?.leaf_count())
+    }
+
     pub(crate) fn root(&self, version: u64) -> Option<Root> {
         self.db.root(version)
     }
@@ -108,6 +108,14 @@ fn basic_workflow_multiblock() {
     let tree = ZkSyncTree::new_lightweight(db.into());
     assert_eq!(tree.root_hash(), expected_root_hash);
     assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(12));
+
+    let reader = tree.reader();
+    let (root_hash, leaf_count) = reader.root_info(L1BatchNumber(11)).unwrap();
+    assert_eq!(root_hash, expected_root_hash);
+    assert_eq!(leaf_count, 100);
+    let (_, leaf_count) = reader.root_info(L1BatchNumber(0)).unwrap();
+    assert_eq!(leaf_count, 9);
+    assert!(reader.root_info(L1BatchNumber(12)).is_none());
 }
 
 #[test]
@@ -0,0 +1,157 @@
+//! Incremental Merkle tree mirroring `DynamicIncrementalMerkle` from L1 contracts.
+
+use std::ops;
+
+use zksync_basic_types::H256;
+use zksync_crypto::hasher::{keccak::KeccakHasher, Hasher};
+
+use crate::MAX_TREE_DEPTH;
+
+/// Append-only Merkle tree with 32-byte leaves that grows in depth as leaves are added.
+///
+/// Unlike [`MiniMerkleTree`](crate::MiniMerkleTree), leaves are not hashed before being inserted into the tree,
+/// and the tree depth is always the minimum depth fitting all leaves (i.e., `ceil(log2(len))`), with missing leaves
+/// set to the `zero_leaf` supplied on creation. As a consequence, the root hash of a tree with a single leaf
+/// is the leaf itself. The root hash of an empty tree is defined to be zero.
+#[derive(Debug, Clone)]
+pub struct IncrementalMerkleTree<H = KeccakHasher> {
+    hasher: H,
+    /// Hashes of empty subtrees; `zeros[0]` is the zero leaf.
+    zeros: Vec<H256>,
+    leaves: Vec<H256>,
+}
+
+/// Merkle paths for a contiguous range of leaves in an [`IncrementalMerkleTree`]. Together with the range leaves,
+/// the paths are sufficient to restore the tree root hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMerklePaths {
+    /// Merkle path for the first leaf in the range, starting from the leaf level.
+    pub left_path: Vec<H256>,
+    /// Merkle path for the last leaf in the range, starting from the leaf level.
+    pub right_path: Vec<H256>,
+}
+
+impl IncrementalMerkleTree {
+    /// Creates an empty tree with the specified hash of an empty leaf. The hash function used is keccak-256.
+    pub fn new(zero_leaf: H256) -> Self {
+        Self::with_hasher(KeccakHasher, zero_leaf)
+    }
+}
+
+impl<H: Hasher<Hash = H256>> IncrementalMerkleTree<H> {
+    /// Creates an empty tree with the specified hasher and hash of an empty leaf.
+    pub fn with_hasher(hasher: H, zero_leaf: H256) -> Self {
+        let mut zeros = Vec::with_capacity(MAX_TREE_DEPTH + 1);
+        zeros.push(zero_leaf);
+        for depth in 0..MAX_TREE_DEPTH {
+            let zero = hasher.compress(&zeros[depth], &zeros[depth]);
+            zeros.push(zero);
+        }
+        Self {
+            hasher,
+            zeros,
+            leaves: vec![],
+        }
+    }
+
+    /// Returns the number of leaves in this tree.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Checks whether this tree is empty.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Returns the leaves in this tree.
+    pub fn leaves(&self) -> &[H256] {
+        &self.leaves
+    }
+
+    /// Appends a leaf to the tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is full (i.e., contains `2^32` leaves).
+    pub fn push(&mut self, leaf: H256) {
+        assert!(
+            self.leaves.len() < 1 << MAX_TREE_DEPTH,
+            "Tree contains more than {} items; this is not supported",
+            1_u64 << MAX_TREE_DEPTH
+        );
+        self.leaves.push(leaf);
+    }
+
+    /// Returns the current depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.leaves.len().next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// Returns the root hash of this tree.
+    pub fn merkle_root(&self) -> H256 {
+        if self.leaves.is_empty() {
+            return H256::zero();
+        }
+        self.compute_root_and_paths(0, 0, None)
+    }
+
+    /// Returns the root hash of this tree together with Merkle paths for the first and last leaves
+    /// in the specified range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty or is out of bounds.
+    pub fn merkle_root_and_paths_for_range(
+        &self,
+        range: ops::Range<usize>,
+    ) -> (H256, RangeMerklePaths) {
+        assert!(!range.is_empty(), "range is empty");
+        assert!(range.end <= self.leaves.len(), "range is out of bounds");
+
+        let mut paths = RangeMerklePaths {
+            left_path: Vec::with_capacity(self.depth()),
+            right_path: Vec::with_capacity(self.depth()),
+        };
+        let root = self.compute_root_and_paths(range.start, range.end - 1, Some(&mut paths));
+        (root, paths)
+    }
+
+    fn compute_root_and_paths(
+        &self,
+        mut left_index: usize,
+        mut right_index: usize,
+        mut paths: Option<&mut RangeMerklePaths>,
+    ) -> H256 {
+        let mut hashes = self.leaves.clone();
+        let mut level_len = hashes.len();
+        for level in 0..self.depth() {
+            let zero = self.zeros[level];
+            if let Some(paths) = paths.as_deref_mut() {
+                let sibling = |idx: usize| hashes.get(idx ^ 1).copied().unwrap_or(zero);
+                paths.left_path.push(sibling(left_index));
+                paths.right_path.push(sibling(right_index));
+            }
+
+            for i in 0..(level_len / 2) {
+                hashes[i] = self.hasher.compress(&hashes[2 * i], &hashes[2 * i + 1]);
+            }
+            if level_len % 2 == 1 {
+                hashes[level_len / 2] = self.hasher.compress(&hashes[level_len - 1], &zero);
+            }
+            level_len = level_len / 2 + level_len % 2;
+            hashes.truncate(level_len);
+            left_index /= 2;
+            right_index /= 2;
+        }
+        hashes[0]
+    }
+}
+
+impl<H: Hasher<Hash = H256>> Extend<H256> for IncrementalMerkleTree<H> {
+    fn extend<I: IntoIterator<Item = H256>>(&mut self, iter: I) {
+        for leaf in iter {
+            self.push(leaf);
+        }
+    }
+}
@@ -9,12 +9,15 @@ use std::iter;
 
 use once_cell::sync::Lazy;
 
+mod incremental;
 #[cfg(test)]
 mod tests;
 
 use zksync_basic_types::H256;
 use zksync_crypto::hasher::{keccak::KeccakHasher, Hasher};
 
+pub use self::incremental::{IncrementalMerkleTree, RangeMerklePaths};
+
 /// Maximum supported depth of the tree. 32 corresponds to `2^32` elements in the tree, which
 /// we unlikely to ever hit.
 const MAX_TREE_DEPTH: usize = 32;
@@ -217,3 +217,107 @@ fn merkle_proofs_are_valid_in_very_small_trees() {
         }
     }
 }
+
+/// Restores the root hash of an `IncrementalMerkleTree` from the specified range of leaves and Merkle paths
+/// for the range boundaries, similarly to `Merkle.calculateRootPaths()` in L1 contracts.
+fn restore_root_from_range(start_index: usize, items: &[H256], paths: &RangeMerklePaths) -> H256 {
+    assert_eq!(paths.left_path.len(), paths.right_path.len());
+    let mut level = items.to_vec();
+    let mut start_index = start_index;
+    for (left_sibling, right_sibling) in paths.left_path.iter().zip(&paths.right_path) {
+        let end_index = start_index + level.len() - 1;
+        let mut padded_level = vec![];
+        if start_index % 2 == 1 {
+            padded_level.push(*left_sibling);
+        }
+        padded_level.extend_from_slice(&level);
+        if end_index % 2 == 0 {
+            padded_level.push(*right_sibling);
+        }
+        level = padded_level
+            .chunks(2)
+            .map(|pair| KeccakHasher.compress(&pair[0], &pair[1]))
+            .collect();
+        start_index /= 2;
+    }
+    assert_eq!(level.len(), 1);
+    level[0]
+}
+
+fn incremental_tree_leaves(count: u64) -> impl Iterator<Item = H256> {
+    (1..=count).map(H256::from_low_u64_be)
+}
+
+#[test]
+fn incremental_tree_root_basics() {
+    let zero_leaf = KeccakHasher.hash_bytes(&[]);
+    let mut tree = IncrementalMerkleTree::new(zero_leaf);
+    assert!(tree.is_empty());
+    assert_eq!(tree.merkle_root(), H256::zero());
+
+    let leaf = H256::repeat_byte(1);
+    tree.push(leaf);
+    assert_eq!(tree.depth(), 0);
+    assert_eq!(tree.merkle_root(), leaf);
+
+    let other_leaf = H256::repeat_byte(2);
+    tree.push(other_leaf);
+    assert_eq!(tree.depth(), 1);
+    assert_eq!(
+        tree.merkle_root(),
+        KeccakHasher.compress(&leaf, &other_leaf)
+    );
+
+    tree.push(leaf);
+    assert_eq!(tree.depth(), 2);
+    let expected_root = KeccakHasher.compress(
+        &KeccakHasher.compress(&leaf, &other_leaf),
+        &KeccakHasher.compress(&leaf, &zero_leaf),
+    );
+    assert_eq!(tree.merkle_root(), expected_root);
+}
+
+#[test]
+fn incremental_tree_root_agrees_with_mini_merkle_tree() {
+    // `MiniMerkleTree` hashes leaves, so we hash them manually for the incremental tree.
+    let zero_leaf = KeccakHasher.hash_bytes(&[0_u8; 88]);
+    let mut tree = IncrementalMerkleTree::new(zero_leaf);
+    for len in 1_u8..=40 {
+        tree.push(KeccakHasher.hash_bytes(&[len; 88]));
+        let mini_tree = MiniMerkleTree::new((1..=len).map(|byte| [byte; 88]), None);
+        assert_eq!(tree.merkle_root(), mini_tree.merkle_root(), "len={len}");
+    }
+}
+
+#[test]
+fn incremental_tree_range_paths_are_valid() {
+    let zero_leaf = KeccakHasher.hash_bytes(&[]);
+    for len in [1, 2, 3, 5, 8, 13, 32, 50] {
+        let mut tree = IncrementalMerkleTree::new(zero_leaf);
+        tree.extend(incremental_tree_leaves(len));
+        let expected_root = tree.merkle_root();
+        let len = usize::try_from(len).unwrap();
+
+        for start in 0..len {
+            for end in (start + 1)..=len {
+                let (root, paths) = tree.merkle_root_and_paths_for_range(start..end);
+                assert_eq!(root, expected_root);
+                assert_eq!(paths.left_path.len(), tree.depth());
+                let items = &tree.leaves()[start..end];
+                let restored_root = restore_root_from_range(start, items, &paths);
+                assert_eq!(
+                    restored_root, expected_root,
+                    "len={len}, range={start}..{end}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "range is out of bounds")]
+fn incremental_tree_range_out_of_bounds() {
+    let mut tree = IncrementalMerkleTree::new(H256::zero());
+    tree.extend(incremental_tree_leaves(3));
+    tree.merkle_root_and_paths_for_range(1..4);
+}
@@ -46,6 +46,7 @@ impl GlueFrom<crate::vm_m5::vm_instance::VmBlockResult> for crate::interface::Fi
                     gas_remaining: value.full_result.gas_remaining,
                     pubdata_published: 0,
                     circuit_statistic: Default::default(),
+                    operation_statistic: Default::default(),
                 },
                 refunds: Refunds::default(),
             },
@@ -108,6 +109,7 @@ impl GlueFrom<crate::vm_m6::vm_instance::VmBlockResult> for crate::interface::Fi
                     gas_remaining: value.full_result.gas_remaining,
                     pubdata_published: 0,
                     circuit_statistic: Default::default(),
+                    operation_statistic: Default::default(),
                 },
                 refunds: Refunds::default(),
             },
@@ -168,6 +170,7 @@ impl GlueFrom<crate::vm_1_3_2::vm_instance::VmBlockResult> for crate::interface:
                     gas_remaining: value.full_result.gas_remaining,
                     pubdata_published: 0,
                     circuit_statistic: Default::default(),
+                    operation_statistic: Default::default(),
                 },
                 refunds: Refunds::default(),
             },
@@ -242,6 +245,7 @@ impl GlueFrom<crate::vm_1_3_2::vm_instance::VmBlockResult>
                 gas_remaining: value.full_result.gas_remaining,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                operation_statistic: Default::default(),
             },
             refunds: Refunds::default(),
         }
@@ -274,6 +278,7 @@ impl GlueFrom<crate::vm_m5::vm_instance::VmBlockResult>
                 gas_remaining: value.full_result.gas_remaining,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                operation_statistic: Default::default(),
             },
             refunds: Refunds::default(),
         }
@@ -322,6 +327,7 @@ impl GlueFrom<crate::vm_m6::vm_instance::VmBlockResult>
                 gas_remaining: value.full_result.gas_remaining,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                operation_statistic: Default::default(),
             },
             refunds: Refunds::default(),
         }
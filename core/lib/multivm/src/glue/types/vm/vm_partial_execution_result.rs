@@ -17,6 +17,7 @@ impl GlueFrom<crate::vm_m5::vm_instance::VmPartialExecutionResult>
                 computational_gas_used: 0,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                operation_statistic: Default::default(),
             },
             refunds: crate::interface::Refunds {
                 gas_refunded: 0,
@@ -43,6 +44,7 @@ impl GlueFrom<crate::vm_m6::vm_instance::VmPartialExecutionResult>
                 gas_remaining: 0,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                operation_statistic: Default::default(),
             },
             refunds: crate::interface::Refunds {
                 gas_refunded: 0,
@@ -69,6 +71,7 @@ impl GlueFrom<crate::vm_1_3_2::vm_instance::VmPartialExecutionResult>
                 gas_remaining: 0,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                operation_statistic: Default::default(),
             },
             refunds: crate::interface::Refunds {
                 gas_refunded: 0,
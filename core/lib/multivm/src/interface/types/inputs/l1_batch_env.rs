@@ -7,6 +7,12 @@ use super::L2BlockEnv;
 /// Eventually, most of these parameters (`l1_gas_price`, `fair_l2_gas_price`, `fee_account`,
 /// `enforced_base_fee`) will be moved to [`L2BlockEnv`]. For now, the VM doesn't support changing
 /// them in the middle of execution; that's why these params are specified here.
+///
+/// There is intentionally no per-batch randomness value (e.g., derived from L1 `prevrandao` at the commit anchor)
+/// among these params. Exposing one to contracts is declined: the bootloader has no memory slot for it and no
+/// system contract provides a call to read it, and the L1 executor doesn't check it against the L1 block it was
+/// derived from, so including it into batch commitments wouldn't make it verifiable. Supporting it requires
+/// a protocol upgrade changing the system contracts and the L1 contracts first.
 #[derive(Debug, Clone)]
 pub struct L1BatchEnv {
     // If previous batch hash is None, then this is the first batch
@@ -1,4 +1,4 @@
-use zksync_types::circuit::CircuitStatistic;
+use zksync_types::circuit::{CircuitStatistic, VmOperationStatistic};
 
 /// Statistics of the tx execution.
 #[derive(Debug, Default, Clone)]
@@ -17,6 +17,8 @@ pub struct VmExecutionStatistics {
     pub total_log_queries: usize,
     pub pubdata_published: u32,
     pub circuit_statistic: CircuitStatistic,
+    /// Operations performed by the VM during the tx execution. Only collected by the latest VM version.
+    pub operation_statistic: VmOperationStatistic,
 }
 
 /// Oracle metrics of the VM.
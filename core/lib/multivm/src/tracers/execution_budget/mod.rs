@@ -0,0 +1,97 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{glue::tracers::IntoOldVmTracer, interface::Halt};
+
+pub mod vm_1_4_1;
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Number of VM cycles between consecutive checks of the elapsed time. Cancellation is checked every cycle
+/// since it's just an atomic load.
+const TIME_CHECK_INTERVAL: u32 = 1_024;
+
+#[derive(Debug)]
+struct BudgetState {
+    limit: Duration,
+    deadline: Instant,
+    cancelled: AtomicBool,
+    exceeded: AtomicBool,
+}
+
+/// Tracer stopping VM execution once the time budget is spent or the budget is cancelled.
+///
+/// All clones of a budget share the deadline, so a single budget can be used for multiple VM runs
+/// (e.g., gas estimation iterations); the deadline counts from the budget creation. Cancellation is cooperative:
+/// [`Self::cancel()`] can be called from any thread, and the VM stops on the next cycle.
+/// The old VM versions are not supported: executing them with a budget will not stop execution.
+#[derive(Debug, Clone)]
+pub struct ExecutionBudget {
+    state: Arc<BudgetState>,
+    cycles: u32,
+}
+
+impl ExecutionBudget {
+    pub fn new(limit: Duration) -> Self {
+        Self {
+            state: Arc::new(BudgetState {
+                limit,
+                deadline: Instant::now() + limit,
+                cancelled: AtomicBool::new(false),
+                exceeded: AtomicBool::new(false),
+            }),
+            cycles: 0,
+        }
+    }
+
+    pub fn limit(&self) -> Duration {
+        self.state.limit
+    }
+
+    /// Cancels execution for all clones of this budget.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Checks whether execution was stopped because the time budget is exceeded.
+    pub fn is_exceeded(&self) -> bool {
+        self.state.exceeded.load(Ordering::Relaxed)
+    }
+
+    /// Called on each VM cycle. Returns the halt reason if execution should be stopped.
+    fn check(&mut self) -> Option<Halt> {
+        if self.is_cancelled() {
+            return Some(Halt::TracerCustom("Execution cancelled".to_string()));
+        }
+        if self.is_exceeded() {
+            return Some(self.exceeded_halt());
+        }
+
+        self.cycles = self.cycles.wrapping_add(1);
+        if self.cycles % TIME_CHECK_INTERVAL == 0 && Instant::now() >= self.state.deadline {
+            self.state.exceeded.store(true, Ordering::Relaxed);
+            return Some(self.exceeded_halt());
+        }
+        None
+    }
+
+    fn exceeded_halt(&self) -> Halt {
+        Halt::TracerCustom(format!(
+            "Execution time budget of {:?} exceeded",
+            self.state.limit
+        ))
+    }
+}
+
+impl IntoOldVmTracer for ExecutionBudget {}
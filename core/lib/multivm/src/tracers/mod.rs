@@ -1,9 +1,11 @@
 pub mod call_tracer;
+pub mod execution_budget;
 mod multivm_dispatcher;
 pub mod old_tracers;
 pub mod storage_invocation;
 pub mod validator;
 
 pub use call_tracer::CallTracer;
+pub use execution_budget::ExecutionBudget;
 pub use multivm_dispatcher::TracerDispatcher;
 pub use storage_invocation::StorageInvocations;
@@ -92,11 +92,11 @@ pub fn adjust_pubdata_price_for_tx(
             // `gasPerPubdata <= 17 * l1gasprice / fair_l2_gas_price + 1`
             // `fair_l2_gas_price(gasPerPubdata - 1) / 17 <= l1gasprice`
             let new_l1_gas_price = U256::from(fee_input.fair_l2_gas_price)
-                * (tx_gas_per_pubdata_limit - U256::from(1u32))
+                * tx_gas_per_pubdata_limit.saturating_sub(U256::from(1u32))
                 / U256::from(17);
 
             BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
-                l1_gas_price: new_l1_gas_price.as_u64(),
+                l1_gas_price: new_l1_gas_price.min(fee_input.l1_gas_price.into()).as_u64(),
                 ..fee_input
             })
         }
@@ -105,10 +105,12 @@ pub fn adjust_pubdata_price_for_tx(
             // `gasPerPubdata <= fair_pubdata_price / fair_l2_gas_price + 1`
             // `fair_l2_gas_price(gasPerPubdata - 1) <= fair_pubdata_price`
             let new_fair_pubdata_price = U256::from(fee_input.fair_l2_gas_price)
-                * (tx_gas_per_pubdata_limit - U256::from(1u32));
+                * tx_gas_per_pubdata_limit.saturating_sub(U256::from(1u32));
 
             BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
-                fair_pubdata_price: new_fair_pubdata_price.as_u64(),
+                fair_pubdata_price: new_fair_pubdata_price
+                    .min(fee_input.fair_pubdata_price.into())
+                    .as_u64(),
                 ..fee_input
             })
         }
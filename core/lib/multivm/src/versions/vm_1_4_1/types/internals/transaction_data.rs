@@ -282,6 +282,8 @@ impl TryInto<L2Tx> for TransactionData {
                 paymaster: self.paymaster,
                 paymaster_input: self.paymaster_input,
             },
+            valid_until: None,
+            arrival_seq: None,
         };
         let factory_deps = (!self.factory_deps.is_empty()).then_some(self.factory_deps);
         let execute = Execute {
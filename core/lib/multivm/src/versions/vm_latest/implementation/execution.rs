@@ -88,6 +88,7 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
             pubdata_published,
             logs.total_log_queries_count,
             circuit_statistic_from_cycles(tx_tracer.circuits_tracer.statistics),
+            tx_tracer.circuits_tracer.operations,
         );
         let result = tx_tracer.result_tracer.into_result();
 
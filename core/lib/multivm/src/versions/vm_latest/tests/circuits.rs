@@ -66,4 +66,17 @@ fn test_circuits() {
             );
         }
     }
+
+    // Check `operation_statistic`. A transfer verifies an ECDSA signature, computes transaction hashes,
+    // calls system contracts and updates balances, but doesn't use SHA256.
+    let ops = res.statistics.operation_statistic;
+    assert_eq!(ops.ecrecover_calls, 1);
+    assert!(ops.keccak256_calls > 0, "{ops:?}");
+    assert_eq!(ops.sha256_calls, 0);
+    assert!(ops.far_calls > 0, "{ops:?}");
+    assert!(ops.returns > 0, "{ops:?}");
+    assert!(ops.storage_writes > 0, "{ops:?}");
+    assert!(ops.decommitments > 0, "{ops:?}");
+    assert!(ops.decommitted_words >= ops.decommitments, "{ops:?}");
+    assert!(ops.arithmetic_opcodes > 0, "{ops:?}");
 }
@@ -6,7 +6,7 @@ use zk_evm_1_4_1::{
     zkevm_opcode_defs::{LogOpcode, Opcode, UMAOpcode},
 };
 use zksync_state::{StoragePtr, WriteStorage};
-use zksync_types::circuit::CircuitCycleStatistic;
+use zksync_types::circuit::{CircuitCycleStatistic, VmOperationStatistic};
 
 use super::circuits_capacity::*;
 use crate::{
@@ -19,10 +19,11 @@ use crate::{
     },
 };
 
-/// Tracer responsible for collecting information about refunds.
+/// Tracer responsible for collecting information about circuit cycles and VM operations.
 #[derive(Debug)]
 pub(crate) struct CircuitsTracer<S, H> {
     pub(crate) statistics: CircuitCycleStatistic,
+    pub(crate) operations: VmOperationStatistic,
     last_decommitment_history_entry_checked: Option<usize>,
     last_written_keys_history_entry_checked: Option<usize>,
     last_read_keys_history_entry_checked: Option<usize>,
@@ -40,6 +41,7 @@ impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for Circuits
     ) {
         self.statistics.main_vm_cycles += 1;
 
+        self.trace_opcode_family(data.opcode.variant.opcode);
         match data.opcode.variant.opcode {
             Opcode::Nop(_)
             | Opcode::Add(_)
@@ -135,6 +137,7 @@ impl<S: WriteStorage, H: HistoryMode> CircuitsTracer<S, H> {
     pub(crate) fn new() -> Self {
         Self {
             statistics: CircuitCycleStatistic::new(),
+            operations: VmOperationStatistic::default(),
             last_decommitment_history_entry_checked: None,
             last_written_keys_history_entry_checked: None,
             last_read_keys_history_entry_checked: None,
@@ -143,6 +146,38 @@ impl<S: WriteStorage, H: HistoryMode> CircuitsTracer<S, H> {
         }
     }
 
+    fn trace_opcode_family(&mut self, opcode: Opcode) {
+        let counter = match opcode {
+            Opcode::Nop(_)
+            | Opcode::Add(_)
+            | Opcode::Sub(_)
+            | Opcode::Mul(_)
+            | Opcode::Div(_)
+            | Opcode::Binop(_)
+            | Opcode::Shift(_) => &mut self.operations.arithmetic_opcodes,
+            Opcode::Jump(_) => &mut self.operations.jump_opcodes,
+            Opcode::Ptr(_) => &mut self.operations.pointer_opcodes,
+            Opcode::Context(_) => &mut self.operations.context_opcodes,
+            Opcode::NearCall(_) => &mut self.operations.near_calls,
+            Opcode::FarCall(_) => &mut self.operations.far_calls,
+            Opcode::Ret(_) => &mut self.operations.returns,
+            Opcode::Log(LogOpcode::StorageRead) => &mut self.operations.storage_reads,
+            Opcode::Log(LogOpcode::StorageWrite) => &mut self.operations.storage_writes,
+            Opcode::Log(LogOpcode::Event) => &mut self.operations.events,
+            Opcode::Log(LogOpcode::ToL1Message) => &mut self.operations.l2_to_l1_messages,
+            // Precompile calls are counted by type in `trace_precompile_calls()`.
+            Opcode::Log(LogOpcode::PrecompileCall) => return,
+            Opcode::UMA(UMAOpcode::AuxHeapWrite | UMAOpcode::HeapWrite) => {
+                &mut self.operations.heap_writes
+            }
+            Opcode::UMA(
+                UMAOpcode::AuxHeapRead | UMAOpcode::HeapRead | UMAOpcode::FatPointerRead,
+            ) => &mut self.operations.heap_reads,
+            Opcode::Invalid(_) => unreachable!(), // invalid opcodes are never executed
+        };
+        *counter += 1;
+    }
+
     fn trace_decommitments(&mut self, state: &ZkSyncVmState<S, H>) {
         let last_decommitment_history_entry_checked = self
             .last_decommitment_history_entry_checked
@@ -166,6 +201,8 @@ impl<S: WriteStorage, H: HistoryMode> CircuitsTracer<S, H> {
             // If the number of words in bytecode is odd, then number of cycles must be rounded up.
             let decommitter_cycles_used = (bytecode_len + 1) / 2;
             self.statistics.code_decommitter_cycles += decommitter_cycles_used as u32;
+            self.operations.decommitments += 1;
+            self.operations.decommitted_words += bytecode_len as u32;
         }
         self.last_decommitment_history_entry_checked = Some(history.len());
     }
@@ -180,6 +217,7 @@ impl<S: WriteStorage, H: HistoryMode> CircuitsTracer<S, H> {
             assert!(history_event.value.is_none());
 
             self.statistics.storage_application_cycles += STORAGE_WRITE_STORAGE_APPLICATION_CYCLES;
+            self.operations.unique_storage_writes += 1;
         }
         self.last_written_keys_history_entry_checked = Some(history.len());
     }
@@ -192,6 +230,7 @@ impl<S: WriteStorage, H: HistoryMode> CircuitsTracer<S, H> {
         for (_, history_event) in &history[last_reads_history_entry_checked..] {
             // We assume that only insertions may happen during a single VM inspection.
             assert!(history_event.value.is_none());
+            self.operations.unique_storage_reads += 1;
 
             // If the slot is already written to, then we've already taken 2 cycles into account.
             if !state
@@ -219,12 +258,15 @@ impl<S: WriteStorage, H: HistoryMode> CircuitsTracer<S, H> {
             match precompile {
                 PrecompileAddress::Ecrecover => {
                     self.statistics.ecrecover_cycles += *cycles as u32;
+                    self.operations.ecrecover_calls += 1;
                 }
                 PrecompileAddress::SHA256 => {
                     self.statistics.sha256_cycles += *cycles as u32;
+                    self.operations.sha256_calls += 1;
                 }
                 PrecompileAddress::Keccak256 => {
                     self.statistics.keccak256_cycles += *cycles as u32;
+                    self.operations.keccak256_calls += 1;
                 }
             };
         }
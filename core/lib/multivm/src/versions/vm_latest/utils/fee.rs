@@ -22,6 +22,10 @@ pub(crate) fn derive_base_fee_and_gas_per_pubdata(
     );
 
     let gas_per_pubdata = ceil_div(fair_pubdata_price, base_fee);
+    debug_assert!(
+        gas_per_pubdata <= MAX_GAS_PER_PUBDATA_BYTE,
+        "Gas per pubdata {gas_per_pubdata} exceeds the limit for fee input {fee_input:?}"
+    );
 
     (base_fee, gas_per_pubdata)
 }
@@ -42,6 +42,7 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
             // This field will be populated by the `RefundTracer`
             pubdata_published: 0,
             circuit_statistic: Default::default(),
+            operation_statistic: Default::default(),
         }
     }
 
@@ -0,0 +1,85 @@
+//! Fault points for chaos testing.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use zksync_utils::chaos::{fault_point, InjectedFault};
+
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+
+impl From<InjectedFault> for ObjectStoreError {
+    fn from(err: InjectedFault) -> Self {
+        Self::Other(err.into())
+    }
+}
+
+/// [`ObjectStore`] wrapper placing fault points before each operation. Fault points are named
+/// `object_store.{get|put|remove}.{bucket}`, e.g. `object_store.put.proof_fri`.
+#[derive(Debug)]
+pub(crate) struct ChaosStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl ChaosStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ChaosStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        fault_point(&format!("object_store.get.{bucket}")).await?;
+        self.inner.get_raw(bucket, key).await
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        fault_point(&format!("object_store.put.{bucket}")).await?;
+        self.inner.put_raw(bucket, key, value).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        fault_point(&format!("object_store.remove.{bucket}")).await?;
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_utils::chaos::{self, Fault};
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    #[tokio::test]
+    async fn injecting_faults_into_store() {
+        let store = ChaosStore::new(Arc::new(MockStore::default()));
+        store
+            .put_raw(Bucket::ProofsFri, "test", vec![1, 2, 3])
+            .await
+            .unwrap();
+
+        let point = format!("object_store.get.{}", Bucket::ProofsFri);
+        chaos::inject(
+            point.clone(),
+            Fault::Error {
+                message: "boom".to_owned(),
+            },
+        );
+        let err = store.get_raw(Bucket::ProofsFri, "test").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Other(_)), "{err}");
+        chaos::remove(&point);
+
+        let value = store.get_raw(Bucket::ProofsFri, "test").await.unwrap();
+        assert_eq!(value, [1, 2, 3]);
+    }
+}
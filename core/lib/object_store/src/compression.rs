@@ -0,0 +1,232 @@
+//! Transparent zstd compression for stored objects.
+
+use async_trait::async_trait;
+
+use crate::{
+    metrics::COMPRESSION_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Suffix appended to keys of compressed objects. It serves as an explicit compression flag, so that
+/// the object contents are never inspected to decide whether an object is compressed.
+const COMPRESSED_KEY_SUFFIX: &str = ".zst";
+
+/// [`ObjectStore`] wrapper that compresses stored objects using zstd and decompresses them on retrieval.
+///
+/// Compression is flagged per object: compressed objects are stored under the original key with
+/// the [`COMPRESSED_KEY_SUFFIX`], and uncompressed objects under the original key. On retrieval, both keys
+/// are checked (the key matching the store configuration first). Thus, the store can read objects written
+/// both with and without compression, and compression can be enabled without migrating existing objects.
+#[derive(Debug)]
+pub(crate) struct CompressingStore<S> {
+    inner: S,
+    level: Option<i32>,
+}
+
+impl<S: ObjectStore> CompressingStore<S> {
+    /// Creates a new store. If `level` is `None`, objects are stored uncompressed, but compressed
+    /// objects are still decompressed on retrieval.
+    pub fn new(inner: S, level: Option<u8>) -> Self {
+        Self {
+            inner,
+            level: level.map(i32::from),
+        }
+    }
+}
+
+fn compressed_key(key: &str) -> String {
+    format!("{key}{COMPRESSED_KEY_SUFFIX}")
+}
+
+fn compress(level: i32, value: &[u8]) -> Result<Vec<u8>, ObjectStoreError> {
+    zstd::bulk::compress(value, level).map_err(|err| ObjectStoreError::Serialization(err.into()))
+}
+
+fn decompress(value: &[u8]) -> Result<Vec<u8>, ObjectStoreError> {
+    zstd::stream::decode_all(value).map_err(|err| ObjectStoreError::Serialization(err.into()))
+}
+
+#[allow(clippy::cast_precision_loss)] // precision loss is acceptable for metrics
+fn compression_ratio(original_len: usize, compressed_len: usize) -> f64 {
+    compressed_len as f64 / original_len as f64
+}
+
+impl Bucket {
+    /// Checks whether objects in this bucket should be compressed. Snapshot chunks are already compressed
+    /// using gzip and are read by external nodes of older versions; DA blobs and audit log exports
+    /// are consumed by external parties.
+    fn is_compressible(self) -> bool {
+        !matches!(
+            self,
+            Self::StorageSnapshot | Self::DataAvailability | Self::AuditLog
+        )
+    }
+}
+
+#[async_trait]
+impl<S: ObjectStore> ObjectStore for CompressingStore<S> {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        if !bucket.is_compressible() {
+            return self.inner.get_raw(bucket, key).await;
+        }
+
+        let compressed_key = compressed_key(key);
+        let mut candidates = [(key, false), (compressed_key.as_str(), true)];
+        if self.level.is_some() {
+            candidates.reverse();
+        }
+        let [(first_key, first_compressed), (second_key, second_compressed)] = candidates;
+        let (value, is_compressed) = match self.inner.get_raw(bucket, first_key).await {
+            Ok(value) => (value, first_compressed),
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                (self.inner.get_raw(bucket, second_key).await?, second_compressed)
+            }
+            Err(err) => return Err(err),
+        };
+        if !is_compressed {
+            return Ok(value);
+        }
+
+        let latency = COMPRESSION_METRICS.decompression_time[&bucket.as_str()].start();
+        let decompressed = tokio::task::spawn_blocking(move || decompress(&value))
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))??;
+        latency.observe();
+        Ok(decompressed)
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let Some(level) = self.level.filter(|_| bucket.is_compressible()) else {
+            return self.inner.put_raw(bucket, key, value).await;
+        };
+
+        let latency = COMPRESSION_METRICS.compression_time[&bucket.as_str()].start();
+        let original_len = value.len();
+        let compressed = tokio::task::spawn_blocking(move || compress(level, &value))
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))??;
+        latency.observe();
+        if original_len > 0 {
+            let ratio = compression_ratio(original_len, compressed.len());
+            COMPRESSION_METRICS.compression_ratio[&bucket.as_str()].observe(ratio);
+        }
+        self.inner
+            .put_raw(bucket, &compressed_key(key), compressed)
+            .await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        if !bucket.is_compressible() {
+            return self.inner.remove_raw(bucket, key).await;
+        }
+        // The object may be stored under either key; it's only an error if neither is removed.
+        let plain_result = self.inner.remove_raw(bucket, key).await;
+        let compressed_result = self.inner.remove_raw(bucket, &compressed_key(key)).await;
+        match (plain_result, compressed_result) {
+            (Ok(()), _) | (_, Ok(())) => Ok(()),
+            (Err(err), _) => Err(err),
+        }
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    #[tokio::test]
+    async fn compressing_objects() {
+        let inner = Arc::new(MockStore::default());
+        let store = CompressingStore::new(inner.clone(), Some(3));
+        let value = b"test".repeat(1_000);
+        store
+            .put_raw(Bucket::WitnessInput, "test", value.clone())
+            .await
+            .unwrap();
+
+        let err = inner
+            .get_raw(Bucket::WitnessInput, "test")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        let raw_value = inner
+            .get_raw(Bucket::WitnessInput, "test.zst")
+            .await
+            .unwrap();
+        assert!(raw_value.len() < value.len());
+        let decompressed = store.get_raw(Bucket::WitnessInput, "test").await.unwrap();
+        assert_eq!(decompressed, value);
+
+        // Snapshot chunks must not be compressed.
+        store
+            .put_raw(Bucket::StorageSnapshot, "test", value.clone())
+            .await
+            .unwrap();
+        let raw_value = inner
+            .get_raw(Bucket::StorageSnapshot, "test")
+            .await
+            .unwrap();
+        assert_eq!(raw_value, value);
+    }
+
+    #[tokio::test]
+    async fn reading_objects_with_and_without_compression() {
+        let inner = Arc::new(MockStore::default());
+        let compressing_store = CompressingStore::new(inner.clone(), Some(3));
+        let store = CompressingStore::new(inner.clone(), None);
+        let value = b"test".repeat(1_000);
+
+        store
+            .put_raw(Bucket::ProofsFri, "uncompressed", value.clone())
+            .await
+            .unwrap();
+        compressing_store
+            .put_raw(Bucket::ProofsFri, "compressed", value.clone())
+            .await
+            .unwrap();
+
+        let raw_value = inner
+            .get_raw(Bucket::ProofsFri, "uncompressed")
+            .await
+            .unwrap();
+        assert_eq!(raw_value, value);
+        for store in [&store, &compressing_store] {
+            for key in ["uncompressed", "compressed"] {
+                let retrieved = store.get_raw(Bucket::ProofsFri, key).await.unwrap();
+                assert_eq!(retrieved, value);
+            }
+        }
+
+        // Uncompressed objects that happen to look like zstd frames must be returned as is.
+        let zstd_like_value = zstd::bulk::compress(&value, 3).unwrap();
+        store
+            .put_raw(Bucket::ProofsFri, "zstd_like", zstd_like_value.clone())
+            .await
+            .unwrap();
+        for store in [&store, &compressing_store] {
+            let retrieved = store.get_raw(Bucket::ProofsFri, "zstd_like").await.unwrap();
+            assert_eq!(retrieved, zstd_like_value);
+        }
+
+        compressing_store
+            .remove_raw(Bucket::ProofsFri, "compressed")
+            .await
+            .unwrap();
+        let err = store
+            .get_raw(Bucket::ProofsFri, "compressed")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
+}
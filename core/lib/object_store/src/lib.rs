@@ -4,6 +4,10 @@
 //! - File-based storage saving blobs as separate files in the local filesystem
 //! - GCS-based storage
 //!
+//! Stored objects can be transparently compressed using zstd (see `compression_level`
+//! in the store configuration), and replicated to a secondary bucket (see `replica_bucket_base_url`
+//! in the store configuration).
+//!
 //! These implementations are not exposed externally. Instead, a store trait object
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
 //! The configuration can be provided explicitly (see [`ObjectStoreFactory::new()`])
@@ -23,12 +27,17 @@
     clippy::doc_markdown
 )]
 
+#[cfg(feature = "chaos")]
+mod chaos;
+mod compression;
 mod file;
 mod gcs;
 mod metrics;
 mod mock;
 mod objects;
 mod raw;
+mod replication;
+mod retention;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
@@ -41,4 +50,5 @@ pub mod _reexports {
 pub use self::{
     objects::StoredObject,
     raw::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory},
+    retention::RetentionPolicy,
 };
@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, Histogram, LabeledFamily, LatencyObserver, Metrics};
+use vise::{Buckets, Counter, Histogram, LabeledFamily, LatencyObserver, Metrics};
 
 use crate::Bucket;
 
@@ -29,3 +29,44 @@ impl GcsMetrics {
 
 #[vise::register]
 pub(crate) static GCS_METRICS: vise::Global<GcsMetrics> = vise::Global::new();
+
+const RATIO_BUCKETS: Buckets =
+    Buckets::values(&[0.05, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.8, 1.0, 1.2]);
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_object_store")]
+pub(crate) struct CompressionMetrics {
+    /// Latency to compress an object before storing it.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
+    pub compression_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency to decompress a retrieved object.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
+    pub decompression_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Ratio of the compressed object size to the original size.
+    #[metrics(buckets = RATIO_BUCKETS, labels = ["bucket"])]
+    pub compression_ratio: LabeledFamily<&'static str, Histogram<f64>>,
+}
+
+#[vise::register]
+pub(crate) static COMPRESSION_METRICS: vise::Global<CompressionMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_object_store_replication")]
+pub(crate) struct ReplicationMetrics {
+    /// Number of failed writes to the primary or replica store.
+    #[metrics(labels = ["bucket", "store"])]
+    pub write_failures: LabeledFamily<(&'static str, &'static str), Counter, 2>,
+    /// Number of reads served by the replica because the primary store has failed.
+    #[metrics(labels = ["bucket"])]
+    pub fallback_reads: LabeledFamily<&'static str, Counter>,
+    /// Number of objects copied between stores by the verifier because they were missing or differed.
+    #[metrics(labels = ["bucket"])]
+    pub repaired_objects: LabeledFamily<&'static str, Counter>,
+    /// Number of objects that couldn't be verified after all attempts, or were not queued for verification
+    /// because the verification queue was full.
+    #[metrics(labels = ["bucket"])]
+    pub unverified_objects: LabeledFamily<&'static str, Counter>,
+}
+
+#[vise::register]
+pub(crate) static REPLICATION_METRICS: vise::Global<ReplicationMetrics> = vise::Global::new();
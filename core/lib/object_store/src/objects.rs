@@ -7,11 +7,14 @@ use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
+    cold_storage::L1BatchColdArchive,
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotManifest, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     storage::witness_block_state::WitnessBlockState,
-    L1BatchNumber,
+    web3::signing::keccak256,
+    L1BatchNumber, H256,
 };
 
 use crate::raw::{BoxedError, Bucket, ObjectStore, ObjectStoreError};
@@ -61,6 +64,29 @@ macro_rules! serialize_using_bincode {
     };
 }
 
+fn serialize_gzipped_proto<T: ProtoFmt>(value: &T) -> Result<Vec<u8>, BoxedError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let encoded_bytes = value.build().encode_to_vec();
+    encoder.write_all(&encoded_bytes)?;
+    encoder.finish().map_err(From::from)
+}
+
+fn deserialize_gzipped_proto<T: ProtoFmt>(bytes: &[u8]) -> Result<T, BoxedError> {
+    let mut decoder = GzDecoder::new(bytes);
+    let mut decompressed_bytes = Vec::new();
+    decoder
+        .read_to_end(&mut decompressed_bytes)
+        .map_err(BoxedError::from)?;
+    decode(&decompressed_bytes[..])
+        .with_context(|| {
+            format!(
+                "deserialization of Message to {}",
+                std::any::type_name::<T>()
+            )
+        })
+        .map_err(From::from)
+}
+
 impl StoredObject for SnapshotFactoryDependencies {
     const BUCKET: Bucket = Bucket::StorageSnapshot;
     type Key<'a> = L1BatchNumber;
@@ -70,21 +96,11 @@ impl StoredObject for SnapshotFactoryDependencies {
     }
 
     fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        let encoded_bytes = self.build().encode_to_vec();
-        encoder.write_all(&encoded_bytes)?;
-        encoder.finish().map_err(From::from)
+        serialize_gzipped_proto(self)
     }
 
     fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
-        let mut decoder = GzDecoder::new(&bytes[..]);
-        let mut decompressed_bytes = Vec::new();
-        decoder
-            .read_to_end(&mut decompressed_bytes)
-            .map_err(BoxedError::from)?;
-        decode(&decompressed_bytes[..])
-            .context("deserialization of Message to SnapshotFactoryDependencies")
-            .map_err(From::from)
+        deserialize_gzipped_proto(&bytes)
     }
 }
 
@@ -100,21 +116,28 @@ impl StoredObject for SnapshotStorageLogsChunk {
     }
 
     fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-        let encoded_bytes = self.build().encode_to_vec();
-        encoder.write_all(&encoded_bytes)?;
-        encoder.finish().map_err(From::from)
+        serialize_gzipped_proto(self)
     }
 
     fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
-        let mut decoder = GzDecoder::new(&bytes[..]);
-        let mut decompressed_bytes = Vec::new();
-        decoder
-            .read_to_end(&mut decompressed_bytes)
-            .map_err(BoxedError::from)?;
-        decode(&decompressed_bytes[..])
-            .context("deserialization of Message to SnapshotStorageLogsChunk")
-            .map_err(From::from)
+        deserialize_gzipped_proto(&bytes)
+    }
+}
+
+impl StoredObject for SnapshotManifest {
+    const BUCKET: Bucket = Bucket::StorageSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_manifest.proto.gzip")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serialize_gzipped_proto(self)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        deserialize_gzipped_proto(&bytes)
     }
 }
 
@@ -129,6 +152,25 @@ impl StoredObject for WitnessBlockState {
     serialize_using_bincode!();
 }
 
+impl StoredObject for L1BatchColdArchive {
+    const BUCKET: Bucket = Bucket::ColdStorage;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("l1_batch_{key}_traces_and_events.bin.gzip")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        bincode::serialize_into(&mut encoder, self)?;
+        encoder.finish().map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        bincode::deserialize_from(GzDecoder::new(&bytes[..])).map_err(From::from)
+    }
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
@@ -159,6 +201,42 @@ impl dyn ObjectStore + '_ {
         Ok(key)
     }
 
+    /// Same as [`Self::get()`], but additionally returns the hash of the serialized value.
+    /// Comparing it with the hash returned by [`Self::put_with_hash()`] allows detecting substitution of the object
+    /// in the store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object with the `key` does not exist, cannot be accessed,
+    /// or cannot be deserialized.
+    pub async fn get_with_hash<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+    ) -> Result<(V, H256), ObjectStoreError> {
+        let key = V::encode_key(key);
+        let bytes = self.get_raw(V::BUCKET, &key).await?;
+        let hash = H256(keccak256(&bytes));
+        let value = V::deserialize(bytes).map_err(ObjectStoreError::Serialization)?;
+        Ok((value, hash))
+    }
+
+    /// Same as [`Self::put()`], but additionally returns the hash of the serialized value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or the insertion / replacement operation fails.
+    pub async fn put_with_hash<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+        value: &V,
+    ) -> Result<(String, H256), ObjectStoreError> {
+        let key = V::encode_key(key);
+        let bytes = value.serialize().map_err(ObjectStoreError::Serialization)?;
+        let hash = H256(keccak256(&bytes));
+        self.put_raw(V::BUCKET, &key, bytes).await?;
+        Ok((key, hash))
+    }
+
     pub fn get_storage_prefix<V: StoredObject>(&self) -> String {
         self.storage_prefix_raw(V::BUCKET)
     }
@@ -168,7 +246,7 @@ impl dyn ObjectStore + '_ {
 mod tests {
     use zksync_types::{
         snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
-        AccountTreeId, Bytes, StorageKey, H160, H256,
+        AccountTreeId, Bytes, StorageKey, H160,
     };
 
     use super::*;
@@ -248,4 +326,32 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn hashing_stored_objects() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(1);
+        let factory_deps = SnapshotFactoryDependencies {
+            factory_deps: vec![SnapshotFactoryDependency {
+                bytecode: Bytes(vec![1, 2, 3]),
+            }],
+        };
+        let (_, hash) = store.put_with_hash(key, &factory_deps).await.unwrap();
+        let (loaded_factory_deps, loaded_hash) = store
+            .get_with_hash::<SnapshotFactoryDependencies>(key)
+            .await
+            .unwrap();
+        assert_eq!(loaded_factory_deps, factory_deps);
+        assert_eq!(loaded_hash, hash);
+
+        let substituted_factory_deps = SnapshotFactoryDependencies {
+            factory_deps: vec![],
+        };
+        store.put(key, &substituted_factory_deps).await.unwrap();
+        let (_, loaded_hash) = store
+            .get_with_hash::<SnapshotFactoryDependencies>(key)
+            .await
+            .unwrap();
+        assert_ne!(loaded_hash, hash);
+    }
 }
@@ -4,9 +4,11 @@ use async_trait::async_trait;
 use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
 
 use crate::{
+    compression::CompressingStore,
     file::FileBackedObjectStore,
     gcs::{GoogleCloudStorage, GoogleCloudStorageAuthMode},
     mock::MockStore,
+    replication::ReplicatingStore,
 };
 
 /// Bucket for [`ObjectStore`] in which objects can be placed.
@@ -24,6 +26,9 @@ pub enum Bucket {
     SchedulerWitnessJobsFri,
     ProofsFri,
     StorageSnapshot,
+    DataAvailability,
+    AuditLog,
+    ColdStorage,
 }
 
 impl Bucket {
@@ -40,6 +45,9 @@ impl Bucket {
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
             Self::StorageSnapshot => "storage_logs_snapshots",
+            Self::DataAvailability => "data_availability",
+            Self::AuditLog => "audit_log",
+            Self::ColdStorage => "cold_storage",
         }
     }
 }
@@ -183,13 +191,40 @@ impl ObjectStoreFactory {
 
     /// Creates an [`ObjectStore`].
     pub async fn create_store(&self) -> Arc<dyn ObjectStore> {
-        match &self.origin {
+        let store: Arc<dyn ObjectStore> = match &self.origin {
             ObjectStoreOrigin::Config(config) => Self::create_from_config(config).await,
             ObjectStoreOrigin::Mock(store) => Arc::new(Arc::clone(store)),
-        }
+        };
+        Self::with_fault_points(store)
+    }
+
+    #[cfg(feature = "chaos")]
+    fn with_fault_points(store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        Arc::new(crate::chaos::ChaosStore::new(store))
+    }
+
+    #[cfg(not(feature = "chaos"))]
+    fn with_fault_points(store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        store
     }
 
     async fn create_from_config(config: &ObjectStoreConfig) -> Arc<dyn ObjectStore> {
+        let mut store = Self::create_raw_store(config).await;
+        if let Some(replica_url) = &config.replica_bucket_base_url {
+            let replica_config = ObjectStoreConfig {
+                mode: replica_mode(&config.mode, replica_url),
+                ..config.clone()
+            };
+            tracing::info!("Replicating objects to `{replica_url}`");
+            let replica = Self::create_raw_store(&replica_config).await;
+            store = Arc::new(ReplicatingStore::new(store, replica));
+        }
+        // The store is always wrapped, so that compressed objects are decompressed even if compression
+        // is not enabled for this store.
+        Arc::new(CompressingStore::new(store, config.compression_level))
+    }
+
+    async fn create_raw_store(config: &ObjectStoreConfig) -> Arc<dyn ObjectStore> {
         match &config.mode {
             ObjectStoreMode::GCS { bucket_base_url } => {
                 tracing::trace!(
@@ -238,3 +273,24 @@ impl ObjectStoreFactory {
         }
     }
 }
+
+/// Returns the mode for the replica store, which uses the same mode as the primary store, but a different bucket.
+fn replica_mode(mode: &ObjectStoreMode, replica_url: &str) -> ObjectStoreMode {
+    let bucket_base_url = replica_url.to_owned();
+    match mode {
+        ObjectStoreMode::GCS { .. } => ObjectStoreMode::GCS { bucket_base_url },
+        ObjectStoreMode::GCSAnonymousReadOnly { .. } => {
+            ObjectStoreMode::GCSAnonymousReadOnly { bucket_base_url }
+        }
+        ObjectStoreMode::GCSWithCredentialFile {
+            gcs_credential_file_path,
+            ..
+        } => ObjectStoreMode::GCSWithCredentialFile {
+            bucket_base_url,
+            gcs_credential_file_path: gcs_credential_file_path.clone(),
+        },
+        ObjectStoreMode::FileBacked { .. } => ObjectStoreMode::FileBacked {
+            file_backed_base_path: bucket_base_url,
+        },
+    }
+}
@@ -0,0 +1,390 @@
+//! Write-through replication of objects to a secondary store (e.g., a bucket in another region).
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::{
+    metrics::REPLICATION_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Maximum number of objects queued for verification. If the queue is full, new objects are not verified.
+const VERIFICATION_QUEUE_CAPACITY: usize = 10_000;
+/// Maximum number of attempts to verify an object.
+const MAX_VERIFICATION_ATTEMPTS: u32 = 10;
+/// Delay before the first retry of a failed verification. The delay is doubled for each subsequent retry.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+struct VerificationTask {
+    bucket: Bucket,
+    key: String,
+    attempt: u32,
+}
+
+/// [`ObjectStore`] writing objects to both the primary and the replica store.
+///
+/// - Writes succeed if at least one of the stores has accepted the object.
+/// - Reads are served by the primary store and fall back to the replica if the primary store fails.
+/// - After each write, the object is asynchronously verified: if it is missing from one of the stores
+///   or the copies differ, it is copied from the other store (the primary store takes precedence).
+///
+/// Thus, an outage of one of the stores doesn't stall components relying on the store, and the stores
+/// converge after the outage ends.
+#[derive(Debug)]
+pub(crate) struct ReplicatingStore {
+    primary: Arc<dyn ObjectStore>,
+    replica: Arc<dyn ObjectStore>,
+    verification_sender: mpsc::Sender<VerificationTask>,
+}
+
+impl ReplicatingStore {
+    /// Creates a new store and spawns a verification task on the current Tokio runtime. The task
+    /// terminates once the store is dropped.
+    pub fn new(primary: Arc<dyn ObjectStore>, replica: Arc<dyn ObjectStore>) -> Self {
+        let (verification_sender, verification_receiver) =
+            mpsc::channel(VERIFICATION_QUEUE_CAPACITY);
+        let verifier = Verifier {
+            primary: primary.clone(),
+            replica: replica.clone(),
+            retry_sender: verification_sender.downgrade(),
+        };
+        tokio::spawn(verifier.run(verification_receiver));
+        Self {
+            primary,
+            replica,
+            verification_sender,
+        }
+    }
+
+    fn schedule_verification(&self, bucket: Bucket, key: &str) {
+        let task = VerificationTask {
+            bucket,
+            key: key.to_owned(),
+            attempt: 0,
+        };
+        if self.verification_sender.try_send(task).is_err() {
+            tracing::warn!(
+                "Verification queue is full; `{key}` in bucket `{bucket}` won't be verified"
+            );
+            REPLICATION_METRICS.unverified_objects[&bucket.as_str()].inc();
+        }
+    }
+}
+
+fn report_write_failure(bucket: Bucket, key: &str, store: &'static str, err: &ObjectStoreError) {
+    tracing::warn!(
+        "Failed writing `{key}` to the {store} store in bucket `{bucket}`: {err}; \
+         the object will be copied once the store recovers"
+    );
+    REPLICATION_METRICS.write_failures[&(bucket.as_str(), store)].inc();
+}
+
+#[async_trait]
+impl ObjectStore for ReplicatingStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let primary_err = match self.primary.get_raw(bucket, key).await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        match self.replica.get_raw(bucket, key).await {
+            Ok(value) => {
+                tracing::info!(
+                    "Failed reading `{key}` from primary store ({primary_err}); served by the replica"
+                );
+                REPLICATION_METRICS.fallback_reads[&bucket.as_str()].inc();
+                Ok(value)
+            }
+            Err(_) => Err(primary_err),
+        }
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let (primary_result, replica_result) = tokio::join!(
+            self.primary.put_raw(bucket, key, value.clone()),
+            self.replica.put_raw(bucket, key, value)
+        );
+        match (primary_result, replica_result) {
+            (Ok(()), Ok(())) => { /* do nothing */ }
+            (Err(primary_err), Err(replica_err)) => {
+                tracing::warn!(
+                    "Failed writing `{key}` to the replica store in bucket `{bucket}`: {replica_err}"
+                );
+                for store in ["primary", "replica"] {
+                    REPLICATION_METRICS.write_failures[&(bucket.as_str(), store)].inc();
+                }
+                return Err(primary_err);
+            }
+            (Err(err), Ok(())) => report_write_failure(bucket, key, "primary", &err),
+            (Ok(()), Err(err)) => report_write_failure(bucket, key, "replica", &err),
+        }
+        self.schedule_verification(bucket, key);
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let (primary_result, replica_result) = tokio::join!(
+            self.primary.remove_raw(bucket, key),
+            self.replica.remove_raw(bucket, key)
+        );
+        if let Err(err) = replica_result {
+            tracing::warn!(
+                "Failed removing `{key}` from the replica store in bucket `{bucket}`: {err}"
+            );
+        }
+        primary_result
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.primary.storage_prefix_raw(bucket)
+    }
+}
+
+/// Outcome of a single verification attempt.
+#[derive(Debug, PartialEq)]
+enum Verification {
+    /// Both stores contain the same object.
+    Matches,
+    /// The object was missing from one of the stores or differed, and was copied.
+    Repaired,
+    /// The object was removed from both stores (e.g., by a retention policy) after being written.
+    Removed,
+}
+
+#[derive(Debug)]
+struct Verifier {
+    primary: Arc<dyn ObjectStore>,
+    replica: Arc<dyn ObjectStore>,
+    retry_sender: mpsc::WeakSender<VerificationTask>,
+}
+
+impl Verifier {
+    async fn run(self, mut receiver: mpsc::Receiver<VerificationTask>) {
+        while let Some(task) = receiver.recv().await {
+            self.process(task);
+        }
+        tracing::debug!("Object store is dropped; stopping replication verifier");
+    }
+
+    fn process(&self, mut task: VerificationTask) {
+        let primary = self.primary.clone();
+        let replica = self.replica.clone();
+        let retry_sender = self.retry_sender.clone();
+        // Verification is performed concurrently, so that a slow or failing store doesn't block the queue.
+        tokio::spawn(async move {
+            let (bucket, key) = (task.bucket, &task.key);
+            match verify(&*primary, &*replica, bucket, key).await {
+                Ok(Verification::Repaired) => {
+                    tracing::info!("Repaired replication of `{key}` in bucket `{bucket}`");
+                    REPLICATION_METRICS.repaired_objects[&bucket.as_str()].inc();
+                }
+                Ok(Verification::Matches | Verification::Removed) => { /* do nothing */ }
+                Err(err) => {
+                    task.attempt += 1;
+                    if task.attempt >= MAX_VERIFICATION_ATTEMPTS {
+                        tracing::error!(
+                            "Failed verifying replication of `{key}` in bucket `{bucket}` after {} attempts: {err}",
+                            task.attempt
+                        );
+                        REPLICATION_METRICS.unverified_objects[&bucket.as_str()].inc();
+                        return;
+                    }
+                    tracing::info!(
+                        "Failed verifying replication of `{key}` in bucket `{bucket}` (attempt {}): {err}",
+                        task.attempt
+                    );
+                    tokio::time::sleep(INITIAL_RETRY_DELAY * 2_u32.pow(task.attempt - 1)).await;
+                    // If the store is dropped, the sender cannot be upgraded, and the task is abandoned.
+                    if let Some(sender) = retry_sender.upgrade() {
+                        sender.send(task).await.ok();
+                    }
+                }
+            }
+        });
+    }
+}
+
+async fn verify(
+    primary: &dyn ObjectStore,
+    replica: &dyn ObjectStore,
+    bucket: Bucket,
+    key: &str,
+) -> Result<Verification, ObjectStoreError> {
+    let (primary_value, replica_value) =
+        tokio::join!(primary.get_raw(bucket, key), replica.get_raw(bucket, key));
+    match (primary_value, replica_value) {
+        (Ok(primary_value), Ok(replica_value)) if primary_value == replica_value => {
+            Ok(Verification::Matches)
+        }
+        (Ok(value), Ok(_) | Err(ObjectStoreError::KeyNotFound(_))) => {
+            replica.put_raw(bucket, key, value).await?;
+            Ok(Verification::Repaired)
+        }
+        (Err(ObjectStoreError::KeyNotFound(_)), Ok(value)) => {
+            primary.put_raw(bucket, key, value).await?;
+            Ok(Verification::Repaired)
+        }
+        (Err(ObjectStoreError::KeyNotFound(_)), Err(ObjectStoreError::KeyNotFound(_))) => {
+            Ok(Verification::Removed)
+        }
+        (Err(err), _) | (_, Err(err)) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    /// Store that fails all operations while it's unavailable.
+    #[derive(Debug, Default)]
+    struct FlakyStore {
+        inner: MockStore,
+        unavailable: AtomicBool,
+    }
+
+    impl FlakyStore {
+        fn check(&self) -> Result<(), ObjectStoreError> {
+            if self.unavailable.load(Ordering::SeqCst) {
+                Err(ObjectStoreError::Other("store is unavailable".into()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyStore {
+        async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+            self.check()?;
+            self.inner.get_raw(bucket, key).await
+        }
+
+        async fn put_raw(
+            &self,
+            bucket: Bucket,
+            key: &str,
+            value: Vec<u8>,
+        ) -> Result<(), ObjectStoreError> {
+            self.check()?;
+            self.inner.put_raw(bucket, key, value).await
+        }
+
+        async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+            self.check()?;
+            self.inner.remove_raw(bucket, key).await
+        }
+
+        fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+            self.inner.storage_prefix_raw(bucket)
+        }
+    }
+
+    #[tokio::test]
+    async fn replicating_objects() {
+        let primary = Arc::new(FlakyStore::default());
+        let replica = Arc::new(FlakyStore::default());
+        let store = ReplicatingStore::new(primary.clone(), replica.clone());
+
+        store
+            .put_raw(Bucket::ProofsFri, "proof", b"proof".to_vec())
+            .await
+            .unwrap();
+        let value = replica.get_raw(Bucket::ProofsFri, "proof").await.unwrap();
+        assert_eq!(value, b"proof");
+
+        // Primary store outage shouldn't affect reads and writes.
+        primary.unavailable.store(true, Ordering::SeqCst);
+        let value = store.get_raw(Bucket::ProofsFri, "proof").await.unwrap();
+        assert_eq!(value, b"proof");
+        store
+            .put_raw(Bucket::StorageSnapshot, "chunk", b"chunk".to_vec())
+            .await
+            .unwrap();
+        let value = store
+            .get_raw(Bucket::StorageSnapshot, "chunk")
+            .await
+            .unwrap();
+        assert_eq!(value, b"chunk");
+
+        replica.unavailable.store(true, Ordering::SeqCst);
+        store
+            .put_raw(Bucket::ProofsFri, "other", b"other".to_vec())
+            .await
+            .unwrap_err();
+        store.get_raw(Bucket::ProofsFri, "proof").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn verifying_replicated_objects() {
+        let primary = Arc::new(MockStore::default());
+        let replica = Arc::new(MockStore::default());
+
+        primary
+            .put_raw(Bucket::ProofsFri, "proof", b"proof".to_vec())
+            .await
+            .unwrap();
+        let verification = verify(&*primary, &*replica, Bucket::ProofsFri, "proof").await;
+        assert_eq!(verification.unwrap(), Verification::Repaired);
+        let value = replica.get_raw(Bucket::ProofsFri, "proof").await.unwrap();
+        assert_eq!(value, b"proof");
+        let verification = verify(&*primary, &*replica, Bucket::ProofsFri, "proof").await;
+        assert_eq!(verification.unwrap(), Verification::Matches);
+
+        // Objects present only in the replica should be copied to the primary store.
+        replica
+            .put_raw(Bucket::ProofsFri, "other", b"other".to_vec())
+            .await
+            .unwrap();
+        let verification = verify(&*primary, &*replica, Bucket::ProofsFri, "other").await;
+        assert_eq!(verification.unwrap(), Verification::Repaired);
+        let value = primary.get_raw(Bucket::ProofsFri, "other").await.unwrap();
+        assert_eq!(value, b"other");
+
+        // The primary store takes precedence if the copies differ.
+        replica
+            .put_raw(Bucket::ProofsFri, "proof", b"corrupted".to_vec())
+            .await
+            .unwrap();
+        let verification = verify(&*primary, &*replica, Bucket::ProofsFri, "proof").await;
+        assert_eq!(verification.unwrap(), Verification::Repaired);
+        let value = replica.get_raw(Bucket::ProofsFri, "proof").await.unwrap();
+        assert_eq!(value, b"proof");
+
+        let verification = verify(&*primary, &*replica, Bucket::ProofsFri, "missing").await;
+        assert_eq!(verification.unwrap(), Verification::Removed);
+    }
+
+    #[tokio::test]
+    async fn repairing_objects_after_outage() {
+        let primary = Arc::new(MockStore::default());
+        let replica = Arc::new(FlakyStore::default());
+        replica.unavailable.store(true, Ordering::SeqCst);
+        let store = ReplicatingStore::new(primary.clone(), replica.clone());
+        store
+            .put_raw(Bucket::ProofsFri, "proof", b"proof".to_vec())
+            .await
+            .unwrap();
+
+        tokio::time::sleep(INITIAL_RETRY_DELAY / 2).await;
+        replica.unavailable.store(false, Ordering::SeqCst);
+        // The verifier should copy the object to the replica on retry.
+        let started_at = tokio::time::Instant::now();
+        while replica.get_raw(Bucket::ProofsFri, "proof").await.is_err() {
+            assert!(
+                started_at.elapsed() < INITIAL_RETRY_DELAY * 10,
+                "object is not repaired"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
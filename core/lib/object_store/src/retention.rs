@@ -0,0 +1,130 @@
+//! Retention policies for objects associated with L1 batches.
+
+use std::{fmt, time::Duration};
+
+use zksync_types::L1BatchNumber;
+
+use crate::{
+    objects::StoredObject,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Policy specifying for how long objects of a certain type are retained after the L1 batch they belong to
+/// is executed on L1.
+#[derive(Clone)]
+pub struct RetentionPolicy {
+    name: &'static str,
+    bucket: Bucket,
+    encode_key: fn(L1BatchNumber) -> String,
+    retention: Duration,
+}
+
+impl fmt::Debug for RetentionPolicy {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RetentionPolicy")
+            .field("name", &self.name)
+            .field("bucket", &self.bucket)
+            .field("retention", &self.retention)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetentionPolicy {
+    /// Creates a policy for objects of type `V`. The `name` must uniquely identify the policy; it is used to track
+    /// the policy progress.
+    pub fn new<V>(name: &'static str, retention: Duration) -> Self
+    where
+        V: StoredObject<Key<'static> = L1BatchNumber>,
+    {
+        Self {
+            name,
+            bucket: V::BUCKET,
+            encode_key: encode_key::<V>,
+            retention,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn bucket(&self) -> Bucket {
+        self.bucket
+    }
+
+    /// Returns the duration for which objects are retained after their L1 batch is executed.
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    /// Returns the key of the object covered by this policy for the specified L1 batch.
+    pub fn key(&self, l1_batch_number: L1BatchNumber) -> String {
+        (self.encode_key)(l1_batch_number)
+    }
+}
+
+fn encode_key<V>(l1_batch_number: L1BatchNumber) -> String
+where
+    V: StoredObject<Key<'static> = L1BatchNumber>,
+{
+    V::encode_key(l1_batch_number)
+}
+
+impl dyn ObjectStore + '_ {
+    /// Removes the object covered by the retention `policy` for the specified L1 batch. Returns `false`
+    /// if the object didn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns I/O errors when accessing the store.
+    pub async fn remove_expired(
+        &self,
+        policy: &RetentionPolicy,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<bool, ObjectStoreError> {
+        let key = policy.key(l1_batch_number);
+        match self.remove_raw(policy.bucket, &key).await {
+            Ok(()) => Ok(true),
+            Err(ObjectStoreError::KeyNotFound(_)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::witness_block_state::WitnessBlockState;
+
+    use super::*;
+    use crate::ObjectStoreFactory;
+
+    #[tokio::test]
+    async fn removing_expired_objects() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        store
+            .put(L1BatchNumber(1), &WitnessBlockState::default())
+            .await
+            .unwrap();
+
+        let policy = RetentionPolicy::new::<WitnessBlockState>(
+            "witness_inputs",
+            Duration::from_secs(86_400),
+        );
+        assert_eq!(policy.bucket(), Bucket::WitnessInput);
+        assert_eq!(
+            policy.key(L1BatchNumber(1)),
+            "witness_block_state_for_l1_batch_1.bin"
+        );
+
+        assert!(store
+            .remove_expired(&policy, L1BatchNumber(1))
+            .await
+            .unwrap());
+        let err = store
+            .get::<WitnessBlockState>(L1BatchNumber(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
+}
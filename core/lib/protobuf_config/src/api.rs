@@ -7,6 +7,26 @@ use zksync_protobuf::{
 
 use crate::{parse_h256, proto};
 
+impl proto::MempoolFeedMode {
+    fn new(mode: &api::MempoolFeedMode) -> Self {
+        use api::MempoolFeedMode as From;
+        match mode {
+            From::Private => Self::Private,
+            From::Delayed => Self::Delayed,
+            From::Public => Self::Public,
+        }
+    }
+
+    fn parse(&self) -> api::MempoolFeedMode {
+        use api::MempoolFeedMode as To;
+        match self {
+            Self::Private => To::Private,
+            Self::Delayed => To::Delayed,
+            Self::Public => To::Public,
+        }
+    }
+}
+
 impl ProtoRepr for proto::Api {
     type Type = ApiConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -45,6 +65,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             ws_url: required(&self.ws_url).context("ws_url")?.clone(),
             req_entities_limit: self.req_entities_limit,
             filters_disabled: self.filters_disabled.unwrap_or(false),
+            reject_txs_without_chain_id: self.reject_txs_without_chain_id.unwrap_or(false),
             filters_limit: self.filters_limit,
             subscriptions_limit: self.subscriptions_limit,
             pubsub_polling_interval: self.pubsub_polling_interval,
@@ -120,6 +141,76 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .transpose()
                 .context("websocket_requests_per_minute_limit")?,
             tree_api_url: self.tree_api_url.clone(),
+            custom_errors_abi_path: self.custom_errors_abi_path.clone(),
+            sponsorship_policy_path: self.sponsorship_policy_path.clone(),
+            shared_cache_url: self.shared_cache_url.clone(),
+            shutdown_delay_ms: self.shutdown_delay_ms,
+            shutdown_timeout_ms: self.shutdown_timeout_ms,
+            validation_cache_capacity: self
+                .validation_cache_capacity
+                .map(|x| x.try_into())
+                .transpose()
+                .context("validation_cache_capacity")?,
+            vm_execution_time_budget_ms: self.vm_execution_time_budget_ms,
+            debug_namespace_concurrency_limit: self
+                .debug_namespace_concurrency_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("debug_namespace_concurrency_limit")?,
+            debug_namespace_requests_per_minute_limit: self
+                .debug_namespace_requests_per_minute_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("debug_namespace_requests_per_minute_limit")?,
+            debug_trace_block_tx_limit: self
+                .debug_trace_block_tx_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("debug_trace_block_tx_limit")?,
+            tx_forwarding_url: self.tx_forwarding_url.clone(),
+            tx_forwarding_ca_cert_path: self.tx_forwarding_ca_cert_path.clone(),
+            tx_forwarding_auth_token: self.tx_forwarding_auth_token.clone(),
+            tx_forwarding_queue_capacity: self
+                .tx_forwarding_queue_capacity
+                .map(|x| x.try_into())
+                .transpose()
+                .context("tx_forwarding_queue_capacity")?,
+            tx_intake_port: self
+                .tx_intake_port
+                .map(|x| x.try_into())
+                .transpose()
+                .context("tx_intake_port")?,
+            tx_intake_bind_address: self
+                .tx_intake_bind_address
+                .as_ref()
+                .map(|x| x.parse())
+                .transpose()
+                .context("tx_intake_bind_address")?,
+            tx_intake_tls_cert_path: self.tx_intake_tls_cert_path.clone(),
+            tx_intake_tls_key_path: self.tx_intake_tls_key_path.clone(),
+            call_cache_capacity: self
+                .call_cache_capacity
+                .map(|x| x.try_into())
+                .transpose()
+                .context("call_cache_capacity")?,
+            mempool_feed_mode: self
+                .mempool_feed_mode
+                .map(|x| Ok::<_, anyhow::Error>(proto::MempoolFeedMode::try_from(x)?.parse()))
+                .transpose()
+                .context("mempool_feed_mode")?,
+            mempool_feed_delay_ms: self.mempool_feed_delay_ms,
+            mempool_feed_rate_limit: self
+                .mempool_feed_rate_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("mempool_feed_rate_limit")?,
+            subscription_handoff_ttl_sec: self.subscription_handoff_ttl_sec,
+            admin_port: self
+                .admin_port
+                .map(|x| x.try_into())
+                .transpose()
+                .context("admin_port")?,
+            logs_query_planner_enabled: self.logs_query_planner_enabled.unwrap_or(false),
         })
     }
     fn build(this: &Self::Type) -> Self {
@@ -130,6 +221,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             ws_url: Some(this.ws_url.clone()),
             req_entities_limit: this.req_entities_limit,
             filters_disabled: Some(this.filters_disabled),
+            reject_txs_without_chain_id: Some(this.reject_txs_without_chain_id),
             filters_limit: this.filters_limit,
             subscriptions_limit: this.subscriptions_limit,
             pubsub_polling_interval: this.pubsub_polling_interval,
@@ -169,6 +261,43 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
             tree_api_url: this.tree_api_url.clone(),
+            custom_errors_abi_path: this.custom_errors_abi_path.clone(),
+            sponsorship_policy_path: this.sponsorship_policy_path.clone(),
+            shared_cache_url: this.shared_cache_url.clone(),
+            shutdown_delay_ms: this.shutdown_delay_ms,
+            shutdown_timeout_ms: this.shutdown_timeout_ms,
+            validation_cache_capacity: this
+                .validation_cache_capacity
+                .map(|x| x.try_into().unwrap()),
+            vm_execution_time_budget_ms: this.vm_execution_time_budget_ms,
+            debug_namespace_concurrency_limit: this
+                .debug_namespace_concurrency_limit
+                .map(|x| x.try_into().unwrap()),
+            debug_namespace_requests_per_minute_limit: this
+                .debug_namespace_requests_per_minute_limit
+                .map(|x| x.into()),
+            debug_trace_block_tx_limit: this
+                .debug_trace_block_tx_limit
+                .map(|x| x.try_into().unwrap()),
+            tx_forwarding_url: this.tx_forwarding_url.clone(),
+            tx_forwarding_ca_cert_path: this.tx_forwarding_ca_cert_path.clone(),
+            tx_forwarding_auth_token: this.tx_forwarding_auth_token.clone(),
+            tx_forwarding_queue_capacity: this
+                .tx_forwarding_queue_capacity
+                .map(|x| x.try_into().unwrap()),
+            tx_intake_port: this.tx_intake_port.map(|x| x.into()),
+            tx_intake_bind_address: this.tx_intake_bind_address.map(|x| x.to_string()),
+            tx_intake_tls_cert_path: this.tx_intake_tls_cert_path.clone(),
+            tx_intake_tls_key_path: this.tx_intake_tls_key_path.clone(),
+            call_cache_capacity: this.call_cache_capacity.map(|x| x.try_into().unwrap()),
+            mempool_feed_mode: this
+                .mempool_feed_mode
+                .map(|x| proto::MempoolFeedMode::new(&x).into()),
+            mempool_feed_delay_ms: this.mempool_feed_delay_ms,
+            mempool_feed_rate_limit: this.mempool_feed_rate_limit.map(|x| x.into()),
+            subscription_handoff_ttl_sec: this.subscription_handoff_ttl_sec,
+            admin_port: this.admin_port.map(|x| x.into()),
+            logs_query_planner_enabled: Some(this.logs_query_planner_enabled),
         }
     }
 }
@@ -51,6 +51,24 @@ impl proto::FeeModelVersion {
     }
 }
 
+impl proto::L1BatchCommitDataGeneratorMode {
+    fn new(n: &configs::chain::L1BatchCommitDataGeneratorMode) -> Self {
+        use configs::chain::L1BatchCommitDataGeneratorMode as From;
+        match n {
+            From::Rollup => Self::Rollup,
+            From::Validium => Self::Validium,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::L1BatchCommitDataGeneratorMode {
+        use configs::chain::L1BatchCommitDataGeneratorMode as To;
+        match self {
+            Self::Rollup => To::Rollup,
+            Self::Validium => To::Validium,
+        }
+    }
+}
+
 impl ProtoRepr for proto::EthNetwork {
     type Type = configs::chain::NetworkConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -140,6 +158,44 @@ impl ProtoRepr for proto::StateKeeper {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("enum_index_migration_chunk_size")?,
+            tx_hook_url: self.tx_hook_url.clone(),
+            tx_hook_timeout_ms: self.tx_hook_timeout_ms,
+            priority_tx_fee_premium: self.priority_tx_fee_premium,
+            force_include_addresses: self
+                .force_include_addresses
+                .iter()
+                .map(|a| parse_h160(a))
+                .collect::<Result<_, _>>()
+                .context("force_include_addresses")?,
+            shadow_protocol_version: self
+                .shadow_protocol_version
+                .map(u16::try_from)
+                .transpose()
+                .context("shadow_protocol_version")?,
+            max_factory_deps: self
+                .max_factory_deps
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_factory_deps")?,
+            max_tx_calldata_size: self
+                .max_tx_calldata_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_tx_calldata_size")?,
+            max_l1_timestamp_drift_sec: self.max_l1_timestamp_drift_sec,
+            max_tree_lag_batches: self.max_tree_lag_batches,
+            max_commitment_lag_batches: self.max_commitment_lag_batches,
+            miniblock_gas_limit: self.miniblock_gas_limit,
+            l1_batch_commit_data_generator_mode: self
+                .l1_batch_commit_data_generator_mode
+                .map(|x| {
+                    Ok::<_, anyhow::Error>(
+                        proto::L1BatchCommitDataGeneratorMode::try_from(x)?.parse(),
+                    )
+                })
+                .transpose()
+                .context("l1_batch_commit_data_generator_mode")?
+                .unwrap_or_default(),
         })
     }
 
@@ -176,6 +232,27 @@ impl ProtoRepr for proto::StateKeeper {
                 .enum_index_migration_chunk_size
                 .as_ref()
                 .map(|x| (*x).try_into().unwrap()),
+            tx_hook_url: this.tx_hook_url.clone(),
+            tx_hook_timeout_ms: this.tx_hook_timeout_ms,
+            priority_tx_fee_premium: this.priority_tx_fee_premium,
+            force_include_addresses: this
+                .force_include_addresses
+                .iter()
+                .map(|a| a.as_bytes().into())
+                .collect(),
+            shadow_protocol_version: this.shadow_protocol_version.map(Into::into),
+            max_factory_deps: this.max_factory_deps.map(|x| x.try_into().unwrap()),
+            max_tx_calldata_size: this.max_tx_calldata_size.map(|x| x.try_into().unwrap()),
+            max_l1_timestamp_drift_sec: this.max_l1_timestamp_drift_sec,
+            max_tree_lag_batches: this.max_tree_lag_batches,
+            max_commitment_lag_batches: this.max_commitment_lag_batches,
+            miniblock_gas_limit: this.miniblock_gas_limit,
+            l1_batch_commit_data_generator_mode: Some(
+                proto::L1BatchCommitDataGeneratorMode::new(
+                    &this.l1_batch_commit_data_generator_mode,
+                )
+                .into(),
+            ),
         }
     }
 }
@@ -185,6 +262,7 @@ impl ProtoRepr for proto::OperationsManager {
     fn read(&self) -> anyhow::Result<Self::Type> {
         Ok(Self::Type {
             delay_interval: *required(&self.delay_interval).context("delay_interval")?,
+            fair_ordering_max_reordering: self.fair_ordering_max_reordering,
         })
     }
 
@@ -218,6 +296,7 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: Some(this.stuck_tx_timeout),
             remove_stuck_txs: Some(this.remove_stuck_txs),
             delay_interval: Some(this.delay_interval),
+            fair_ordering_max_reordering: this.fair_ordering_max_reordering,
         }
     }
 }
@@ -234,6 +313,10 @@ impl ProtoRepr for proto::CircuitBreaker {
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("http_req_retry_interval_sec")?,
             replication_lag_limit_sec: self.replication_lag_limit_sec,
+            base_token_ratio_url: self.base_token_ratio_url.clone(),
+            base_token_ratio_max_change_percent: self.base_token_ratio_max_change_percent,
+            base_token_ratio_window_sec: self.base_token_ratio_window_sec,
+            base_token_ratio_max_staleness_sec: self.base_token_ratio_max_staleness_sec,
         })
     }
 
@@ -243,6 +326,10 @@ impl ProtoRepr for proto::CircuitBreaker {
             http_req_max_retry_number: Some(this.http_req_max_retry_number.try_into().unwrap()),
             http_req_retry_interval_sec: Some(this.http_req_retry_interval_sec.into()),
             replication_lag_limit_sec: this.replication_lag_limit_sec,
+            base_token_ratio_url: this.base_token_ratio_url.clone(),
+            base_token_ratio_max_change_percent: this.base_token_ratio_max_change_percent,
+            base_token_ratio_window_sec: this.base_token_ratio_window_sec,
+            base_token_ratio_max_staleness_sec: this.base_token_ratio_max_staleness_sec,
         }
     }
 }
@@ -152,6 +152,18 @@ impl ProtoRepr for proto::Contracts {
                 .map(|x| parse_h160(x))
                 .transpose()
                 .context("transparent_proxy_admin_addr")?,
+            l1_shared_bridge_proxy_addr: self
+                .l1_shared_bridge_proxy_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("l1_shared_bridge_proxy_addr")?,
+            genesis_root: self
+                .genesis_root
+                .as_ref()
+                .map(|x| parse_h256(x))
+                .transpose()
+                .context("genesis_root")?,
         })
     }
 
@@ -224,6 +236,11 @@ impl ProtoRepr for proto::Contracts {
                 .transparent_proxy_admin_addr
                 .as_ref()
                 .map(|x| x.as_bytes().into()),
+            l1_shared_bridge_proxy_addr: this
+                .l1_shared_bridge_proxy_addr
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            genesis_root: this.genesis_root.as_ref().map(|x| x.as_bytes().into()),
         }
     }
 }
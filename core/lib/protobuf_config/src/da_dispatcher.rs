@@ -0,0 +1,124 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::{repr::ProtoRepr, required};
+
+use crate::proto;
+
+impl ProtoRepr for proto::DaDispatcher {
+    type Type = configs::DADispatcherConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            da_client_url: required(&self.da_client_url)
+                .context("da_client_url")?
+                .clone(),
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+            max_rows_to_dispatch: *required(&self.max_rows_to_dispatch)
+                .context("max_rows_to_dispatch")?,
+            max_retries: required(&self.max_retries)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("max_retries")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            da_client_url: Some(this.da_client_url.clone()),
+            polling_interval_ms: Some(this.polling_interval_ms),
+            max_rows_to_dispatch: Some(this.max_rows_to_dispatch),
+            max_retries: Some(this.max_retries.into()),
+        }
+    }
+}
+
+impl ProtoRepr for proto::Avail {
+    type Type = configs::AvailConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            app_id: *required(&self.app_id).context("app_id")?,
+            api_key: required(&self.api_key).context("api_key")?.clone(),
+            api_node_url: required(&self.api_node_url)
+                .context("api_node_url")?
+                .clone(),
+            bridge_api_url: self.bridge_api_url.clone(),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            app_id: Some(this.app_id),
+            api_key: Some(this.api_key.clone()),
+            api_node_url: Some(this.api_node_url.clone()),
+            bridge_api_url: this.bridge_api_url.clone(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::EigenDa {
+    type Type = configs::EigenDAConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            account_id: required(&self.account_id).context("account_id")?.clone(),
+            custom_quorum_numbers: self.custom_quorum_numbers.clone(),
+            wait_for_finalization: *required(&self.wait_for_finalization)
+                .context("wait_for_finalization")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            account_id: Some(this.account_id.clone()),
+            custom_quorum_numbers: this.custom_quorum_numbers.clone(),
+            wait_for_finalization: Some(this.wait_for_finalization),
+        }
+    }
+}
+
+impl ProtoRepr for proto::Celestia {
+    type Type = configs::CelestiaConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            namespace: required(&self.namespace).context("namespace")?.clone(),
+            auth_token: required(&self.auth_token).context("auth_token")?.clone(),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            namespace: Some(this.namespace.clone()),
+            auth_token: Some(this.auth_token.clone()),
+        }
+    }
+}
+
+impl ProtoRepr for proto::DaClient {
+    type Type = configs::DAClientConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        use proto::da_client::Client;
+
+        Ok(match required(&self.client).context("client")? {
+            Client::Avail(config) => Self::Type::Avail(config.read().context("avail")?),
+            Client::EigenDa(config) => Self::Type::EigenDA(config.read().context("eigen_da")?),
+            Client::Celestia(config) => Self::Type::Celestia(config.read().context("celestia")?),
+            Client::ObjectStore(config) => {
+                Self::Type::ObjectStore(config.read().context("object_store")?)
+            }
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        use proto::da_client::Client;
+
+        let client = match this {
+            configs::DAClientConfig::Avail(config) => Client::Avail(ProtoRepr::build(config)),
+            configs::DAClientConfig::EigenDA(config) => Client::EigenDa(ProtoRepr::build(config)),
+            configs::DAClientConfig::Celestia(config) => Client::Celestia(ProtoRepr::build(config)),
+            configs::DAClientConfig::ObjectStore(config) => {
+                Client::ObjectStore(ProtoRepr::build(config))
+            }
+        };
+        Self {
+            client: Some(client),
+        }
+    }
+}
@@ -96,6 +96,7 @@ impl ProtoRepr for proto::Postgres {
             statement_timeout_sec: self.statement_timeout_sec,
             long_connection_threshold_ms: self.long_connection_threshold_ms,
             slow_query_threshold_ms: self.slow_query_threshold_ms,
+            leak_detection_threshold_ms: self.leak_detection_threshold_ms,
         })
     }
 
@@ -109,6 +110,7 @@ impl ProtoRepr for proto::Postgres {
             statement_timeout_sec: this.statement_timeout_sec,
             long_connection_threshold_ms: this.long_connection_threshold_ms,
             slow_query_threshold_ms: this.slow_query_threshold_ms,
+            leak_detection_threshold_ms: this.leak_detection_threshold_ms,
         }
     }
 }
@@ -4,12 +4,39 @@ use zksync_protobuf::{repr::ProtoRepr, required};
 
 use crate::proto;
 
+impl proto::L1FinalityMode {
+    fn new(x: &configs::eth_client::L1FinalityMode) -> Self {
+        use configs::eth_client::L1FinalityMode as From;
+        match x {
+            From::Confirmations => Self::Confirmations,
+            From::Finalized => Self::Finalized,
+            From::Hybrid => Self::Hybrid,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_client::L1FinalityMode {
+        use configs::eth_client::L1FinalityMode as To;
+        match self {
+            Self::Confirmations => To::Confirmations,
+            Self::Finalized => To::Finalized,
+            Self::Hybrid => To::Hybrid,
+        }
+    }
+}
+
 impl ProtoRepr for proto::EthClient {
     type Type = configs::ETHClientConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
         Ok(Self::Type {
             chain_id: *required(&self.chain_id).context("chain_id")?,
             web3_url: required(&self.web3_url).context("web3_url")?.clone(),
+            finality_mode: self
+                .finality_mode
+                .map(proto::L1FinalityMode::try_from)
+                .transpose()
+                .context("finality_mode")?
+                .map(|mode| mode.parse()),
+            finality_confirmations: self.finality_confirmations,
         })
     }
 
@@ -17,6 +44,11 @@ impl ProtoRepr for proto::EthClient {
         Self {
             chain_id: Some(this.chain_id),
             web3_url: Some(this.web3_url.clone()),
+            finality_mode: this
+                .finality_mode
+                .as_ref()
+                .map(|mode| proto::L1FinalityMode::new(mode).into()),
+            finality_confirmations: this.finality_confirmations,
         }
     }
 }
@@ -2,7 +2,7 @@ use anyhow::Context as _;
 use zksync_config::configs::{self};
 use zksync_protobuf::{read_required_repr, required, ProtoRepr};
 
-use crate::proto;
+use crate::{parse_h160, proto};
 
 impl proto::ProofSendingMode {
     fn new(x: &configs::eth_sender::ProofSendingMode) -> Self {
@@ -48,6 +48,7 @@ impl proto::PubdataSendingMode {
         match x {
             From::Calldata => Self::Calldata,
             From::Blobs => Self::Blobs,
+            From::Custom => Self::Custom,
         }
     }
 
@@ -56,6 +57,29 @@ impl proto::PubdataSendingMode {
         match self {
             Self::Calldata => To::Calldata,
             Self::Blobs => To::Blobs,
+            Self::Custom => To::Custom,
+        }
+    }
+}
+
+impl proto::SignerBackend {
+    fn new(x: &configs::eth_sender::SignerBackend) -> Self {
+        use configs::eth_sender::SignerBackend as From;
+        match x {
+            From::PrivateKey => Self::PrivateKey,
+            From::Web3Signer => Self::Web3Signer,
+            From::AwsKms => Self::AwsKms,
+            From::GcpKms => Self::GcpKms,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::SignerBackend {
+        use configs::eth_sender::SignerBackend as To;
+        match self {
+            Self::PrivateKey => To::PrivateKey,
+            Self::Web3Signer => To::Web3Signer,
+            Self::AwsKms => To::AwsKms,
+            Self::GcpKms => To::GcpKms,
         }
     }
 }
@@ -128,6 +152,44 @@ impl ProtoRepr for proto::Sender {
                 .and_then(|x| Ok(proto::PubdataSendingMode::try_from(*x)?))
                 .context("pubdata_sending_mode")?
                 .parse(),
+            operator_balance_alert_threshold_gwei: self.operator_balance_alert_threshold_gwei,
+            operator_runway_alert_threshold_hours: self.operator_runway_alert_threshold_hours,
+            base_token_addr: self
+                .base_token_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("base_token_addr")?,
+            operator_base_token_balance_alert_threshold: self
+                .operator_base_token_balance_alert_threshold,
+            execution_time_windows: self.execution_time_windows.clone(),
+            execution_approval_contract_addr: self
+                .execution_approval_contract_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("execution_approval_contract_addr")?,
+            disabled_publish_criteria: self.disabled_publish_criteria.clone(),
+            dry_run: self.dry_run.unwrap_or(false),
+            shutdown_drain_timeout_sec: self.shutdown_drain_timeout_sec,
+            priority_op_deadline_sec: self.priority_op_deadline_sec,
+            force_execute_on_priority_op_deadline: self
+                .force_execute_on_priority_op_deadline
+                .unwrap_or(false),
+            signer_backend: self
+                .signer_backend
+                .map(proto::SignerBackend::try_from)
+                .transpose()
+                .context("signer_backend")?
+                .map_or_else(Default::default, |x| x.parse()),
+            signer_url: self.signer_url.clone(),
+            signer_region: self.signer_region.clone(),
+            operator_signer_key_id: self.operator_signer_key_id.clone(),
+            blobs_operator_signer_key_id: self.blobs_operator_signer_key_id.clone(),
+            max_blobs_per_eth_tx: self
+                .max_blobs_per_eth_tx
+                .unwrap_or_else(configs::eth_sender::SenderConfig::default_max_blobs_per_eth_tx),
+            auto_select_pubdata_da: self.auto_select_pubdata_da.unwrap_or(false),
         })
     }
 
@@ -159,6 +221,28 @@ impl ProtoRepr for proto::Sender {
             pubdata_sending_mode: Some(
                 proto::PubdataSendingMode::new(&this.pubdata_sending_mode).into(),
             ),
+            operator_balance_alert_threshold_gwei: this.operator_balance_alert_threshold_gwei,
+            operator_runway_alert_threshold_hours: this.operator_runway_alert_threshold_hours,
+            base_token_addr: this.base_token_addr.as_ref().map(|x| x.as_bytes().into()),
+            operator_base_token_balance_alert_threshold: this
+                .operator_base_token_balance_alert_threshold,
+            execution_time_windows: this.execution_time_windows.clone(),
+            execution_approval_contract_addr: this
+                .execution_approval_contract_addr
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            disabled_publish_criteria: this.disabled_publish_criteria.clone(),
+            dry_run: Some(this.dry_run),
+            shutdown_drain_timeout_sec: this.shutdown_drain_timeout_sec,
+            priority_op_deadline_sec: this.priority_op_deadline_sec,
+            force_execute_on_priority_op_deadline: Some(this.force_execute_on_priority_op_deadline),
+            signer_backend: Some(proto::SignerBackend::new(&this.signer_backend).into()),
+            signer_url: this.signer_url.clone(),
+            signer_region: this.signer_region.clone(),
+            operator_signer_key_id: this.operator_signer_key_id.clone(),
+            blobs_operator_signer_key_id: this.blobs_operator_signer_key_id.clone(),
+            max_blobs_per_eth_tx: Some(this.max_blobs_per_eth_tx),
+            auto_select_pubdata_da: Some(this.auto_select_pubdata_da),
         }
     }
 }
@@ -191,6 +275,8 @@ impl ProtoRepr for proto::GasAdjuster {
             )
             .context("internal_pubdata_pricing_multiplier")?,
             max_blob_base_fee: self.max_blob_base_fee,
+            max_l1_staleness_sec: self.max_l1_staleness_sec,
+            internal_enforced_pubdata_price: self.internal_enforced_pubdata_price,
         })
     }
 
@@ -211,6 +297,8 @@ impl ProtoRepr for proto::GasAdjuster {
             ),
             internal_pubdata_pricing_multiplier: Some(this.internal_pubdata_pricing_multiplier),
             max_blob_base_fee: this.max_blob_base_fee,
+            max_l1_staleness_sec: this.max_l1_staleness_sec,
+            internal_enforced_pubdata_price: this.internal_enforced_pubdata_price,
         }
     }
 }
@@ -50,6 +50,22 @@ impl ProtoRepr for proto::HouseKeeper {
                 &self.fri_proof_compressor_stats_reporting_interval_ms,
             )
             .context("fri_proof_compressor_stats_reporting_interval_ms")?,
+            witness_inputs_retention_days: self.witness_inputs_retention_days,
+            proofs_retention_days: self.proofs_retention_days,
+            artifacts_retention_interval_ms: self.artifacts_retention_interval_ms.unwrap_or_else(
+                configs::house_keeper::HouseKeeperConfig::default_artifacts_retention_interval_ms,
+            ),
+            artifacts_retention_dry_run: self.artifacts_retention_dry_run.unwrap_or(false),
+            prover_db_consistency_check_interval_ms: self
+                .prover_db_consistency_check_interval_ms
+                .unwrap_or_else(
+                    configs::house_keeper::HouseKeeperConfig::default_prover_db_consistency_check_interval_ms,
+                ),
+            audit_log_export_interval_ms: self.audit_log_export_interval_ms,
+            cold_storage_threshold_days: self.cold_storage_threshold_days,
+            cold_storage_interval_ms: self.cold_storage_interval_ms.unwrap_or_else(
+                configs::house_keeper::HouseKeeperConfig::default_cold_storage_interval_ms,
+            ),
         })
     }
 
@@ -82,6 +98,16 @@ impl ProtoRepr for proto::HouseKeeper {
             fri_proof_compressor_stats_reporting_interval_ms: Some(
                 this.fri_proof_compressor_stats_reporting_interval_ms,
             ),
+            witness_inputs_retention_days: this.witness_inputs_retention_days,
+            proofs_retention_days: this.proofs_retention_days,
+            artifacts_retention_interval_ms: Some(this.artifacts_retention_interval_ms),
+            artifacts_retention_dry_run: Some(this.artifacts_retention_dry_run),
+            prover_db_consistency_check_interval_ms: Some(
+                this.prover_db_consistency_check_interval_ms,
+            ),
+            audit_log_export_interval_ms: this.audit_log_export_interval_ms,
+            cold_storage_threshold_days: this.cold_storage_threshold_days,
+            cold_storage_interval_ms: Some(this.cold_storage_interval_ms),
         }
     }
 }
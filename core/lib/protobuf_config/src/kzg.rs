@@ -1,6 +1,5 @@
-use anyhow::Context as _;
 use zksync_config::configs;
-use zksync_protobuf::{required, ProtoRepr};
+use zksync_protobuf::ProtoRepr;
 
 use crate::proto;
 
@@ -8,15 +7,13 @@ impl ProtoRepr for proto::Kzg {
     type Type = configs::KzgConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
         Ok(Self::Type {
-            trusted_setup_path: required(&self.trusted_setup_path)
-                .context("trusted_setup_path")?
-                .clone(),
+            trusted_setup_path: self.trusted_setup_path.clone(),
         })
     }
 
     fn build(this: &Self::Type) -> Self {
         Self {
-            trusted_setup_path: Some(this.trusted_setup_path.clone()),
+            trusted_setup_path: this.trusted_setup_path.clone(),
         }
     }
 }
@@ -9,6 +9,7 @@ mod api;
 mod chain;
 mod contract_verifier;
 mod contracts;
+mod da_dispatcher;
 mod database;
 mod eth_client;
 mod eth_sender;
@@ -28,6 +29,7 @@ mod snapshots_creator;
 mod witness_generator;
 
 pub mod proto;
+pub mod schema;
 #[cfg(test)]
 mod tests;
 mod utils;
@@ -44,6 +44,12 @@ impl ProtoRepr for proto::ObjectStore {
             max_retries: required(&self.max_retries)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_retries")?,
+            compression_level: self
+                .compression_level
+                .map(|x| x.try_into())
+                .transpose()
+                .context("compression_level")?,
+            replica_bucket_base_url: self.replica_bucket_base_url.clone(),
         })
     }
 
@@ -80,6 +86,8 @@ impl ProtoRepr for proto::ObjectStore {
         Self {
             mode: Some(mode),
             max_retries: Some(this.max_retries.into()),
+            compression_level: this.compression_level.map(Into::into),
+            replica_bucket_base_url: this.replica_bucket_base_url.clone(),
         }
     }
 }
@@ -0,0 +1,356 @@
+//! JSON schema of the node configuration and offline validation of config bundles.
+//!
+//! A config bundle is a JSON object mapping section names (e.g., `api` or `eth_sender`) to configs
+//! in the protobuf JSON format. The schema is derived from protobuf descriptors, so it's always in sync
+//! with the configs the node accepts. Validation goes further than the schema: it runs the same conversions
+//! as the node on startup, and thus catches missing required fields and invalid values (e.g., malformed addresses).
+//! Constraints spanning several sections (e.g., the genesis parameters assembled from `chain_eth`, `eth_client`
+//! and `contracts`) are checked as well.
+
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use prost_reflect::{FieldDescriptor, Kind, MessageDescriptor, ReflectMessage};
+use serde_json::{json, Map, Value};
+use zksync_basic_types::network::Network;
+use zksync_config::configs::contracts::ProverAtGenesis;
+use zksync_protobuf::{repr::ProtoRepr, ProtoFmt};
+use zksync_types::H256;
+
+use crate::proto;
+
+/// Top-level section of a config bundle.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigSection {
+    /// Key of the section in the bundle.
+    pub name: &'static str,
+    /// Prefix of env variables the section is loaded from. `None` if env variable names
+    /// cannot be derived from the field names.
+    pub env_prefix: Option<&'static str>,
+    descriptor: fn() -> MessageDescriptor,
+    validate: fn(&Value) -> anyhow::Result<()>,
+}
+
+impl ConfigSection {
+    fn new<P: ProtoRepr>(name: &'static str, env_prefix: Option<&'static str>) -> Self {
+        Self {
+            name,
+            env_prefix,
+            descriptor: || P::default().descriptor(),
+            validate: validate_section::<P>,
+        }
+    }
+
+    /// Returns the protobuf descriptor of the section config.
+    pub fn descriptor(&self) -> MessageDescriptor {
+        (self.descriptor)()
+    }
+
+    /// Creates a section for a config defined outside this crate (e.g., the consensus config).
+    pub fn with_proto_fmt<T: ProtoFmt>(
+        name: &'static str,
+        env_prefix: Option<&'static str>,
+    ) -> Self {
+        Self {
+            name,
+            env_prefix,
+            descriptor: || T::Proto::default().descriptor(),
+            validate: validate_proto_fmt_section::<T>,
+        }
+    }
+
+    /// Checks that the section config can be parsed and converted to the Rust config.
+    pub fn validate(&self, config: &Value) -> anyhow::Result<()> {
+        (self.validate)(config)
+    }
+}
+
+fn validate_section<P: ProtoRepr>(config: &Value) -> anyhow::Result<()> {
+    let config: P =
+        zksync_protobuf::serde::deserialize_proto(config).context("malformed config")?;
+    P::read(&config)?;
+    Ok(())
+}
+
+fn validate_proto_fmt_section<T: ProtoFmt>(config: &Value) -> anyhow::Result<()> {
+    zksync_protobuf::serde::deserialize::<T, _>(config).context("malformed config")?;
+    Ok(())
+}
+
+/// Returns all sections of a config bundle defined in this crate. Configs defined in other crates
+/// (e.g., the consensus config) can be added using [`ConfigSection::with_proto_fmt()`].
+pub fn config_sections() -> Vec<ConfigSection> {
+    vec![
+        ConfigSection::new::<proto::Alerts>("alerts", Some("ALERTS_")),
+        ConfigSection::new::<proto::Api>("api", Some("API_")),
+        ConfigSection::new::<proto::EthNetwork>("chain_eth", Some("CHAIN_ETH_")),
+        ConfigSection::new::<proto::StateKeeper>("chain_state_keeper", Some("CHAIN_STATE_KEEPER_")),
+        ConfigSection::new::<proto::OperationsManager>(
+            "chain_operations_manager",
+            Some("CHAIN_OPERATIONS_MANAGER_"),
+        ),
+        ConfigSection::new::<proto::Mempool>("chain_mempool", Some("CHAIN_MEMPOOL_")),
+        ConfigSection::new::<proto::CircuitBreaker>(
+            "chain_circuit_breaker",
+            Some("CHAIN_CIRCUIT_BREAKER_"),
+        ),
+        ConfigSection::new::<proto::ContractVerifier>(
+            "contract_verifier",
+            Some("CONTRACT_VERIFIER_"),
+        ),
+        ConfigSection::new::<proto::Contracts>("contracts", Some("CONTRACTS_")),
+        ConfigSection::new::<proto::DaDispatcher>("da_dispatcher", Some("DA_DISPATCHER_")),
+        // DA client configs are loaded from `DA_DISPATCHER_AVAIL_*`, `DA_DISPATCHER_CELESTIA_*` etc.
+        ConfigSection::new::<proto::DaClient>("da_client", None),
+        ConfigSection::new::<proto::Db>("db", Some("DATABASE_")),
+        // Postgres config is loaded from `DATABASE_URL`, `DATABASE_POOL_SIZE` etc., which don't match field names.
+        ConfigSection::new::<proto::Postgres>("postgres", None),
+        ConfigSection::new::<proto::EthClient>("eth_client", Some("ETH_CLIENT_")),
+        ConfigSection::new::<proto::EthSender>("eth_sender", Some("ETH_SENDER_")),
+        ConfigSection::new::<proto::EthWatch>("eth_watch", Some("ETH_WATCH_")),
+        ConfigSection::new::<proto::FriProofCompressor>(
+            "fri_proof_compressor",
+            Some("FRI_PROOF_COMPRESSOR_"),
+        ),
+        ConfigSection::new::<proto::FriProver>("fri_prover", Some("FRI_PROVER_")),
+        ConfigSection::new::<proto::FriProverGateway>(
+            "fri_prover_gateway",
+            Some("FRI_PROVER_GATEWAY_"),
+        ),
+        ConfigSection::new::<proto::FriProverGroup>("fri_prover_group", None),
+        ConfigSection::new::<proto::FriWitnessGenerator>("fri_witness", Some("FRI_WITNESS_")),
+        ConfigSection::new::<proto::FriWitnessVectorGenerator>(
+            "fri_witness_vector_generator",
+            Some("FRI_WITNESS_VECTOR_GENERATOR_"),
+        ),
+        ConfigSection::new::<proto::HouseKeeper>("house_keeper", Some("HOUSE_KEEPER_")),
+        ConfigSection::new::<proto::Kzg>("kzg", Some("KZG_")),
+        ConfigSection::new::<proto::ObjectStore>("object_store", Some("OBJECT_STORE_")),
+        // Observability config is assembled from several `MISC_*` variables.
+        ConfigSection::new::<proto::Observability>("observability", None),
+        ConfigSection::new::<proto::ProofDataHandler>(
+            "proof_data_handler",
+            Some("PROOF_DATA_HANDLER_"),
+        ),
+        ConfigSection::new::<proto::SnapshotsCreator>(
+            "snapshots_creator",
+            Some("SNAPSHOTS_CREATOR_"),
+        ),
+        ConfigSection::new::<proto::WitnessGenerator>("witness", Some("WITNESS_")),
+    ]
+}
+
+/// Builds the JSON schema for config bundles. Each scalar field that can be set via env has
+/// the `x-env` annotation with the env variable name. If `env_defaults` (e.g., parsed from an `.env` file)
+/// contains a value for this variable, it's used as the field default.
+pub fn config_schema(sections: &[ConfigSection], env_defaults: &HashMap<String, String>) -> Value {
+    let properties: Map<_, _> = sections
+        .iter()
+        .map(|section| {
+            let schema = message_schema(&section.descriptor(), section.env_prefix, env_defaults);
+            (section.name.to_owned(), schema)
+        })
+        .collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "zkSync node config bundle",
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    })
+}
+
+fn message_schema(
+    descriptor: &MessageDescriptor,
+    env_prefix: Option<&str>,
+    env_defaults: &HashMap<String, String>,
+) -> Value {
+    let mut properties = Map::new();
+    let mut property_names = vec![];
+    for field in descriptor.fields() {
+        // Variants of `oneof`s are not represented in env variables directly.
+        let env_var = env_prefix
+            .filter(|_| field.containing_oneof().is_none())
+            .map(|prefix| format!("{prefix}{}", field.name().to_uppercase()));
+        let schema = field_schema(&field, env_var.as_deref(), env_defaults);
+        properties.insert(field.name().to_owned(), schema);
+        // The protobuf JSON format accepts both original and camel-cased field names.
+        property_names.push(field.name().to_owned());
+        if field.json_name() != field.name() {
+            property_names.push(field.json_name().to_owned());
+        }
+    }
+    json!({
+        "title": descriptor.full_name(),
+        "type": "object",
+        "properties": properties,
+        "propertyNames": { "enum": property_names },
+    })
+}
+
+fn field_schema(
+    field: &FieldDescriptor,
+    env_var: Option<&str>,
+    env_defaults: &HashMap<String, String>,
+) -> Value {
+    let kind = field.kind();
+    if field.is_map() {
+        let Kind::Message(entry) = kind else {
+            unreachable!("map field {} is not a message", field.full_name());
+        };
+        let value_schema = match entry.map_entry_value_field().kind() {
+            Kind::Message(message) => message_schema(&message, None, env_defaults),
+            kind => scalar_schema(&kind),
+        };
+        return json!({ "type": "object", "additionalProperties": value_schema });
+    }
+
+    if let Kind::Message(message) = &kind {
+        let prefix = env_var.map(|var| format!("{var}_"));
+        let schema = message_schema(message, prefix.as_deref(), env_defaults);
+        return if field.is_list() {
+            json!({ "type": "array", "items": schema })
+        } else {
+            schema
+        };
+    }
+
+    let mut schema = scalar_schema(&kind);
+    if field.is_list() {
+        // Lists are represented as comma-separated values in env variables.
+        schema = json!({ "type": "array", "items": schema });
+    }
+    if let Some(env_var) = env_var {
+        schema["x-env"] = env_var.into();
+        if let Some(raw_value) = env_defaults.get(env_var) {
+            schema["default"] = if field.is_list() {
+                raw_value
+                    .split(',')
+                    .map(|item| parse_env_value(&kind, item.trim()))
+                    .collect()
+            } else {
+                parse_env_value(&kind, raw_value)
+            };
+        }
+    }
+    schema
+}
+
+fn scalar_schema(kind: &Kind) -> Value {
+    match kind {
+        Kind::Double | Kind::Float => json!({ "type": "number" }),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => {
+            json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX })
+        }
+        Kind::Uint32 | Kind::Fixed32 => {
+            json!({ "type": "integer", "minimum": 0, "maximum": u32::MAX })
+        }
+        // 64-bit integers are encoded as strings in the protobuf JSON format, but numbers are accepted as well.
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => {
+            json!({ "type": ["integer", "string"], "format": "int64" })
+        }
+        Kind::Uint64 | Kind::Fixed64 => {
+            json!({ "type": ["integer", "string"], "format": "uint64" })
+        }
+        Kind::Bool => json!({ "type": "boolean" }),
+        Kind::String => json!({ "type": "string" }),
+        Kind::Bytes => json!({ "type": "string", "contentEncoding": "base64" }),
+        Kind::Enum(descriptor) => {
+            let variants: Vec<_> = descriptor
+                .values()
+                .map(|value| value.name().to_owned())
+                .collect();
+            json!({ "type": "string", "enum": variants })
+        }
+        Kind::Message(descriptor) => unreachable!("{} is not a scalar", descriptor.full_name()),
+    }
+}
+
+fn parse_env_value(kind: &Kind, raw_value: &str) -> Value {
+    let parsed = match kind {
+        Kind::Double | Kind::Float => raw_value.parse::<f64>().ok().map(Value::from),
+        Kind::Int32
+        | Kind::Sint32
+        | Kind::Sfixed32
+        | Kind::Int64
+        | Kind::Sint64
+        | Kind::Sfixed64 => raw_value.parse::<i64>().ok().map(Value::from),
+        Kind::Uint32 | Kind::Fixed32 | Kind::Uint64 | Kind::Fixed64 => {
+            raw_value.parse::<u64>().ok().map(Value::from)
+        }
+        Kind::Bool => raw_value.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| raw_value.into())
+}
+
+/// Validates a config bundle. Returns a list of errors, which is empty if the bundle is valid.
+pub fn validate_bundle(sections: &[ConfigSection], bundle: &Value) -> Vec<String> {
+    let Some(bundle) = bundle.as_object() else {
+        return vec!["config bundle must be a JSON object".to_owned()];
+    };
+    let mut errors = vec![];
+    for (name, config) in bundle {
+        let Some(section) = sections.iter().find(|section| section.name == name) else {
+            errors.push(format!("{name}: unknown config section"));
+            continue;
+        };
+        if let Err(err) = section.validate(config) {
+            errors.push(format!("{name}: {err:#}"));
+        }
+    }
+    errors.extend(validate_cross_section(bundle));
+    errors
+}
+
+/// Parses a section from the bundle. Returns `None` if the section is missing or invalid; errors
+/// for invalid sections are reported by per-section validation.
+fn parse_section<P: ProtoRepr>(bundle: &Map<String, Value>, name: &str) -> Option<P::Type> {
+    let config: P = zksync_protobuf::serde::deserialize_proto(bundle.get(name)?).ok()?;
+    P::read(&config).ok()
+}
+
+/// Checks constraints spanning several sections, which the node otherwise only detects on startup or genesis.
+fn validate_cross_section(bundle: &Map<String, Value>) -> Vec<String> {
+    let mut errors = vec![];
+    let network = parse_section::<proto::EthNetwork>(bundle, "chain_eth");
+    let eth_client = parse_section::<proto::EthClient>(bundle, "eth_client");
+    if let (Some(network), Some(eth_client)) = (network, eth_client) {
+        if !matches!(network.network, Network::Unknown | Network::Test)
+            && network.network.chain_id().0 != eth_client.chain_id
+        {
+            errors.push(format!(
+                "chain_eth, eth_client: L1 network `{}` has chain ID {}, but `eth_client.chain_id` is {}",
+                network.network,
+                network.network.chain_id().0,
+                eth_client.chain_id
+            ));
+        }
+    }
+
+    // The L1 verifier config used in genesis is assembled from verification key hashes corresponding
+    // to `prover_at_genesis`; the node only detects missing hashes when checking them against L1.
+    if let Some(contracts) = parse_section::<proto::Contracts>(bundle, "contracts") {
+        if matches!(contracts.prover_at_genesis, ProverAtGenesis::Fri) {
+            let vk_hashes = [
+                (
+                    "fri_recursion_node_level_vk_hash",
+                    contracts.fri_recursion_node_level_vk_hash,
+                ),
+                (
+                    "fri_recursion_leaf_level_vk_hash",
+                    contracts.fri_recursion_leaf_level_vk_hash,
+                ),
+                ("snark_wrapper_vk_hash", contracts.snark_wrapper_vk_hash),
+            ];
+            for (field, hash) in vk_hashes {
+                if hash == H256::zero() {
+                    errors.push(format!(
+                        "genesis: `contracts.{field}` must be set if `contracts.prover_at_genesis` is `fri`"
+                    ));
+                }
+            }
+        }
+    }
+    errors
+}
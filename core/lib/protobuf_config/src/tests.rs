@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+
 use pretty_assertions::assert_eq;
 use rand::Rng;
-use zksync_config::testonly;
+use zksync_basic_types::network::Network;
+use zksync_config::{configs::contracts::ProverAtGenesis, testonly};
 use zksync_protobuf::repr::ProtoRepr;
+use zksync_types::H256;
 
-use crate::proto;
+use crate::{proto, schema};
 
 fn encode<P: ProtoRepr>(msg: &P::Type) -> Vec<u8> {
     let msg = P::build(msg);
@@ -69,6 +73,11 @@ fn test_encoding() {
     encode_decode::<proto::CircuitBreaker>(rng);
     encode_decode::<proto::ContractVerifier>(rng);
     encode_decode::<proto::Contracts>(rng);
+    encode_decode::<proto::DaDispatcher>(rng);
+    encode_decode::<proto::Avail>(rng);
+    encode_decode::<proto::EigenDa>(rng);
+    encode_decode::<proto::Celestia>(rng);
+    encode_decode::<proto::DaClient>(rng);
     encode_decode::<proto::MerkleTree>(rng);
     encode_decode::<proto::Db>(rng);
     encode_decode::<proto::Postgres>(rng);
@@ -91,3 +100,117 @@ fn test_encoding() {
     encode_decode::<proto::SnapshotsCreator>(rng);
     encode_decode::<proto::WitnessGenerator>(rng);
 }
+
+#[test]
+fn config_schema_has_env_mapping_and_defaults() {
+    let env_defaults =
+        HashMap::from([("API_WEB3_JSON_RPC_HTTP_PORT".to_owned(), "3050".to_owned())]);
+    let sections = schema::config_sections();
+    let schema = schema::config_schema(&sections, &env_defaults);
+    for section in &sections {
+        assert!(
+            schema["properties"][section.name].is_object(),
+            "{}",
+            section.name
+        );
+    }
+
+    let http_port =
+        &schema["properties"]["api"]["properties"]["web3_json_rpc"]["properties"]["http_port"];
+    assert_eq!(http_port["type"], "integer");
+    assert_eq!(http_port["x-env"], "API_WEB3_JSON_RPC_HTTP_PORT");
+    assert_eq!(http_port["default"], 3050);
+    let postgres_url = &schema["properties"]["postgres"]["properties"]["master_url"];
+    assert_eq!(postgres_url["type"], "string");
+    assert!(postgres_url.get("x-env").is_none());
+}
+
+#[test]
+fn validating_config_bundle() {
+    let rng = &mut rand::thread_rng();
+    let api: <proto::Api as ProtoRepr>::Type = testonly::Gen {
+        rng,
+        required_only: false,
+        decimal_fractions: true,
+    }
+    .gen();
+    let eth_watch: <proto::EthWatch as ProtoRepr>::Type = testonly::Gen {
+        rng,
+        required_only: true,
+        decimal_fractions: true,
+    }
+    .gen();
+    let mut bundle = serde_json::json!({
+        "api": serde_json::from_str::<serde_json::Value>(&encode_json::<proto::Api>(&api)).unwrap(),
+        "eth_watch": serde_json::from_str::<serde_json::Value>(&encode_json::<proto::EthWatch>(&eth_watch)).unwrap(),
+    });
+    let sections = schema::config_sections();
+    let errors = schema::validate_bundle(&sections, &bundle);
+    assert!(errors.is_empty(), "{errors:?}");
+
+    bundle["unknown"] = serde_json::json!({});
+    bundle["eth_watch"]
+        .as_object_mut()
+        .unwrap()
+        .remove("ethNodePollInterval");
+    bundle["api"]["web3JsonRpc"]["httpPortt"] = 3050.into();
+    let mut errors = schema::validate_bundle(&sections, &bundle);
+    errors.sort();
+    assert_eq!(errors.len(), 3, "{errors:?}");
+    assert!(errors[0].starts_with("api: "), "{errors:?}");
+    assert!(errors[1].starts_with("eth_watch: "), "{errors:?}");
+    assert!(errors[2].starts_with("unknown: "), "{errors:?}");
+}
+
+#[test]
+fn validating_cross_section_constraints() {
+    let rng = &mut rand::thread_rng();
+    let mut network: <proto::EthNetwork as ProtoRepr>::Type = testonly::Gen {
+        rng,
+        required_only: true,
+        decimal_fractions: true,
+    }
+    .gen();
+    network.network = Network::Mainnet;
+    let mut eth_client: <proto::EthClient as ProtoRepr>::Type = testonly::Gen {
+        rng,
+        required_only: true,
+        decimal_fractions: true,
+    }
+    .gen();
+    eth_client.chain_id = 1;
+    let mut contracts: <proto::Contracts as ProtoRepr>::Type = testonly::Gen {
+        rng,
+        required_only: true,
+        decimal_fractions: true,
+    }
+    .gen();
+    contracts.prover_at_genesis = ProverAtGenesis::Fri;
+
+    let mut bundle = serde_json::json!({
+        "chain_eth": serde_json::from_str::<serde_json::Value>(&encode_json::<proto::EthNetwork>(&network)).unwrap(),
+        "eth_client": serde_json::from_str::<serde_json::Value>(&encode_json::<proto::EthClient>(&eth_client)).unwrap(),
+        "contracts": serde_json::from_str::<serde_json::Value>(&encode_json::<proto::Contracts>(&contracts)).unwrap(),
+    });
+    let sections = schema::config_sections();
+    let errors = schema::validate_bundle(&sections, &bundle);
+    assert!(errors.is_empty(), "{errors:?}");
+
+    eth_client.chain_id = 5;
+    contracts.snark_wrapper_vk_hash = H256::zero();
+    bundle["eth_client"] =
+        serde_json::from_str(&encode_json::<proto::EthClient>(&eth_client)).unwrap();
+    bundle["contracts"] =
+        serde_json::from_str(&encode_json::<proto::Contracts>(&contracts)).unwrap();
+    let mut errors = schema::validate_bundle(&sections, &bundle);
+    errors.sort();
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    assert!(
+        errors[0].starts_with("chain_eth, eth_client: "),
+        "{errors:?}"
+    );
+    assert!(
+        errors[1].starts_with("genesis: `contracts.snark_wrapper_vk_hash`"),
+        "{errors:?}"
+    );
+}
@@ -0,0 +1,490 @@
+//! Construction of protocol upgrade payloads.
+//!
+//! An upgrade is described by a declarative [`UpgradeManifest`] listing the changed base system contracts,
+//! verifier parameters and contracts force-deployed on L2. The manifest is turned into an [`UpgradeSpec`]
+//! (the manifest with loaded bytecodes), from which the full [`UpgradePayload`] is built: the L2 upgrade
+//! transaction, the diamond cut data for the L1 upgrade and the governance calldata scheduling and executing it.
+//!
+//! The payload is decoded back using the same code the node uses to parse upgrades from L1, so the result
+//! is guaranteed to be interpreted by the node as intended. The payload can also be validated against
+//! the current [`ChainState`] read from L1.
+//!
+//! Facet cuts are not supported; the produced diamond cut only calls the upgrade initializer.
+
+use std::collections::HashSet;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    ethabi::{self, encode, ParamType, Token},
+    protocol_version::{self, Call, ProtocolUpgrade, VerifierParams},
+    web3::signing::keccak256,
+    Address, Bytes, ProtocolVersion, ProtocolVersionId, CONTRACT_DEPLOYER_ADDRESS,
+    CONTRACT_FORCE_DEPLOYER_ADDRESS, H256, MAX_NEW_FACTORY_DEPS, PROTOCOL_UPGRADE_TX_TYPE,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
+};
+use zksync_utils::{
+    address_to_u256,
+    bytecode::{hash_bytecode, validate_bytecode},
+    h256_to_u256,
+};
+
+pub use crate::manifest::{ForcedDeploymentManifest, GovernanceParams, UpgradeManifest};
+
+mod manifest;
+#[cfg(test)]
+mod tests;
+
+/// Parameters of the L2 upgrade transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpgradeTxParams {
+    pub gas_limit: u64,
+    pub gas_per_pubdata_limit: u64,
+}
+
+impl Default for UpgradeTxParams {
+    fn default() -> Self {
+        Self {
+            gas_limit: 72_000_000,
+            gas_per_pubdata_limit: REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE,
+        }
+    }
+}
+
+/// Contract force-deployed by the L2 upgrade transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForcedDeployment {
+    pub address: Address,
+    pub bytecode: Vec<u8>,
+    pub call_constructor: bool,
+    pub value: U256,
+    pub input: Vec<u8>,
+}
+
+/// Upgrade manifest with loaded bytecodes.
+#[derive(Debug, Clone)]
+pub struct UpgradeSpec {
+    pub protocol_version: ProtocolVersionId,
+    pub timestamp: u64,
+    pub diamond_proxy: Address,
+    pub upgrade_contract: Address,
+    pub bootloader: Option<Vec<u8>>,
+    pub default_account: Option<Vec<u8>>,
+    pub verifier_address: Option<Address>,
+    pub verifier_params: Option<VerifierParams>,
+    pub forced_deployments: Vec<ForcedDeployment>,
+    pub upgrade_tx: UpgradeTxParams,
+    pub governance: GovernanceParams,
+}
+
+/// Current state of the chain an upgrade is validated against. L1 is the source of truth for it;
+/// the protocol version persisted by the node may lag behind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainState {
+    pub protocol_version: u16,
+    /// Timestamp of the current protocol version. Not exposed by the diamond proxy, so it's taken from
+    /// the node storage if available.
+    pub timestamp: Option<u64>,
+    pub bootloader_code_hash: H256,
+    pub default_account_code_hash: H256,
+    pub verifier_address: Address,
+    pub verifier_params: VerifierParams,
+}
+
+impl From<&ProtocolVersion> for ChainState {
+    fn from(version: &ProtocolVersion) -> Self {
+        Self {
+            protocol_version: version.id as u16,
+            timestamp: Some(version.timestamp),
+            bootloader_code_hash: version.base_system_contracts_hashes.bootloader,
+            default_account_code_hash: version.base_system_contracts_hashes.default_aa,
+            verifier_address: version.verifier_address,
+            verifier_params: version.l1_verifier_config.params,
+        }
+    }
+}
+
+/// Governance calldata executing the upgrade.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GovernanceCalldata {
+    /// Calldata of `scheduleTransparent(operation, delay)`.
+    pub schedule: Bytes,
+    /// Calldata of `execute(operation)`.
+    pub execute: Bytes,
+}
+
+/// Complete protocol upgrade payload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpgradePayload {
+    pub protocol_version: u16,
+    pub timestamp: u64,
+    pub bootloader_code_hash: Option<H256>,
+    pub default_account_code_hash: Option<H256>,
+    pub verifier_address: Option<Address>,
+    pub verifier_params: Option<VerifierParams>,
+    /// Canonical hash of the L2 upgrade transaction. `None` if the upgrade has no L2 transaction.
+    pub upgrade_tx_hash: Option<H256>,
+    /// Hashes of factory dependencies published with the upgrade.
+    pub factory_deps: Vec<H256>,
+    /// Diamond proxy the upgrade is executed on.
+    pub diamond_proxy: Address,
+    /// ABI-encoded `DiamondCutData`.
+    pub diamond_cut_data: Bytes,
+    /// Calldata of `executeUpgrade(diamondCut)` called on the diamond proxy by the governance.
+    pub execute_upgrade_calldata: Bytes,
+    pub governance: GovernanceCalldata,
+}
+
+fn calldata(name: &str, param_types: &[ParamType], tokens: &[Token]) -> Vec<u8> {
+    let mut calldata = ethabi::short_signature(name, param_types).to_vec();
+    calldata.extend(encode(tokens));
+    calldata
+}
+
+fn verifier_params_param_type() -> ParamType {
+    ParamType::Tuple(vec![ParamType::FixedBytes(32); 3])
+}
+
+fn proposed_upgrade_param_type() -> ParamType {
+    ParamType::Tuple(vec![
+        protocol_version::get_transaction_param_type(), // L2 upgrade transaction
+        ParamType::Array(Box::new(ParamType::Bytes)),   // factory deps
+        ParamType::FixedBytes(32),                      // bootloader code hash
+        ParamType::FixedBytes(32),                      // default account code hash
+        ParamType::Address,                             // verifier address
+        verifier_params_param_type(),                   // verifier params
+        ParamType::Bytes,                               // L1 custom data
+        ParamType::Bytes,                               // L1 post-upgrade custom data
+        ParamType::Uint(256),                           // timestamp
+        ParamType::Uint(256),                           // version ID
+        ParamType::Address,                             // allow list address
+    ])
+}
+
+fn diamond_cut_param_type() -> ParamType {
+    let facet_cut_param_type = ParamType::Tuple(vec![
+        ParamType::Address,
+        ParamType::Uint(8),
+        ParamType::Bool,
+        ParamType::Array(Box::new(ParamType::FixedBytes(4))),
+    ]);
+    ParamType::Tuple(vec![
+        ParamType::Array(Box::new(facet_cut_param_type)),
+        ParamType::Address,
+        ParamType::Bytes,
+    ])
+}
+
+fn governance_operation_param_type() -> ParamType {
+    let call_param_type = ParamType::Tuple(vec![
+        ParamType::Address,
+        ParamType::Uint(256),
+        ParamType::Bytes,
+    ]);
+    ParamType::Tuple(vec![
+        ParamType::Array(Box::new(call_param_type)),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+    ])
+}
+
+fn force_deploy_calldata(deployments: &[(H256, &ForcedDeployment)]) -> Vec<u8> {
+    let deployment_param_type = ParamType::Tuple(vec![
+        ParamType::FixedBytes(32),
+        ParamType::Address,
+        ParamType::Bool,
+        ParamType::Uint(256),
+        ParamType::Bytes,
+    ]);
+    let deployments = deployments
+        .iter()
+        .map(|(bytecode_hash, deployment)| {
+            Token::Tuple(vec![
+                Token::FixedBytes(bytecode_hash.0.to_vec()),
+                Token::Address(deployment.address),
+                Token::Bool(deployment.call_constructor),
+                Token::Uint(deployment.value),
+                Token::Bytes(deployment.input.clone()),
+            ])
+        })
+        .collect();
+    calldata(
+        "forceDeployOnAddresses",
+        &[ParamType::Array(Box::new(deployment_param_type))],
+        &[Token::Array(deployments)],
+    )
+}
+
+fn checked_bytecode_hash(name: &str, bytecode: &[u8]) -> anyhow::Result<H256> {
+    validate_bytecode(bytecode).with_context(|| format!("invalid {name} bytecode"))?;
+    Ok(hash_bytecode(bytecode))
+}
+
+fn u256_token(value: impl Into<U256>) -> Token {
+    Token::Uint(value.into())
+}
+
+impl UpgradeSpec {
+    /// Builds the upgrade payload.
+    pub fn build(&self) -> anyhow::Result<UpgradePayload> {
+        let bootloader_code_hash = self
+            .bootloader
+            .as_deref()
+            .map(|code| checked_bytecode_hash("bootloader", code))
+            .transpose()?;
+        let default_account_code_hash = self
+            .default_account
+            .as_deref()
+            .map(|code| checked_bytecode_hash("default account", code))
+            .transpose()?;
+
+        // Base system contracts are loaded by the node from factory deps by their hashes, so their bytecodes
+        // must be published by the upgrade transaction together with the force-deployed contracts.
+        let mut factory_deps: Vec<(H256, Vec<u8>)> = vec![];
+        let system_contracts = [
+            (bootloader_code_hash, &self.bootloader),
+            (default_account_code_hash, &self.default_account),
+        ];
+        for (hash, bytecode) in system_contracts {
+            if let (Some(hash), Some(bytecode)) = (hash, bytecode) {
+                if !factory_deps.iter().any(|(dep_hash, _)| *dep_hash == hash) {
+                    factory_deps.push((hash, bytecode.clone()));
+                }
+            }
+        }
+
+        let mut deployed_addresses = HashSet::new();
+        let mut deployments = Vec::with_capacity(self.forced_deployments.len());
+        for deployment in &self.forced_deployments {
+            let address = deployment.address;
+            anyhow::ensure!(
+                deployed_addresses.insert(address),
+                "multiple forced deployments to {address:?}"
+            );
+            let name = format!("{address:?} contract");
+            let bytecode_hash = checked_bytecode_hash(&name, &deployment.bytecode)?;
+            if !factory_deps.iter().any(|(hash, _)| *hash == bytecode_hash) {
+                factory_deps.push((bytecode_hash, deployment.bytecode.clone()));
+            }
+            deployments.push((bytecode_hash, deployment));
+        }
+
+        // The upgrade transaction is required whenever there are bytecodes to publish, even if
+        // nothing is force-deployed (e.g., if only the bootloader changes).
+        let has_upgrade_tx = !factory_deps.is_empty();
+        let transaction = if has_upgrade_tx {
+            anyhow::ensure!(
+                self.upgrade_tx.gas_limit > 0,
+                "upgrade transaction gas limit must be positive"
+            );
+            self.upgrade_transaction(&deployments, &factory_deps)
+        } else {
+            Self::empty_transaction()
+        };
+        let upgrade_tx_hash =
+            has_upgrade_tx.then(|| H256(keccak256(&encode(&[transaction.clone()]))));
+
+        let verifier_params = self.verifier_params.unwrap_or_default();
+        let proposed_upgrade = Token::Tuple(vec![
+            transaction,
+            Token::Array(
+                factory_deps
+                    .iter()
+                    .map(|(_, bytecode)| Token::Bytes(bytecode.clone()))
+                    .collect(),
+            ),
+            Token::FixedBytes(bootloader_code_hash.unwrap_or_default().0.to_vec()),
+            Token::FixedBytes(default_account_code_hash.unwrap_or_default().0.to_vec()),
+            Token::Address(self.verifier_address.unwrap_or_default()),
+            Token::Tuple(vec![
+                Token::FixedBytes(verifier_params.recursion_node_level_vk_hash.0.to_vec()),
+                Token::FixedBytes(verifier_params.recursion_leaf_level_vk_hash.0.to_vec()),
+                Token::FixedBytes(verifier_params.recursion_circuits_set_vks_hash.0.to_vec()),
+            ]),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+            u256_token(self.timestamp),
+            u256_token(self.protocol_version as u16),
+            Token::Address(Address::zero()),
+        ]);
+        let init_calldata = calldata(
+            "upgrade",
+            &[proposed_upgrade_param_type()],
+            &[proposed_upgrade],
+        );
+
+        let diamond_cut = Token::Tuple(vec![
+            Token::Array(vec![]),
+            Token::Address(self.upgrade_contract),
+            Token::Bytes(init_calldata),
+        ]);
+        let diamond_cut_data = encode(&[diamond_cut.clone()]);
+        let execute_upgrade_calldata = calldata(
+            "executeUpgrade",
+            &[diamond_cut_param_type()],
+            &[diamond_cut],
+        );
+
+        let operation = Token::Tuple(vec![
+            Token::Array(vec![Token::Tuple(vec![
+                Token::Address(self.diamond_proxy),
+                u256_token(0),
+                Token::Bytes(execute_upgrade_calldata.clone()),
+            ])]),
+            Token::FixedBytes(self.governance.predecessor.0.to_vec()),
+            Token::FixedBytes(self.governance.salt.0.to_vec()),
+        ]);
+        let schedule = calldata(
+            "scheduleTransparent",
+            &[governance_operation_param_type(), ParamType::Uint(256)],
+            &[operation.clone(), u256_token(self.governance.delay)],
+        );
+        let execute = calldata(
+            "execute",
+            &[governance_operation_param_type()],
+            &[operation],
+        );
+
+        Ok(UpgradePayload {
+            protocol_version: self.protocol_version as u16,
+            timestamp: self.timestamp,
+            bootloader_code_hash,
+            default_account_code_hash,
+            verifier_address: self.verifier_address,
+            verifier_params: self.verifier_params,
+            upgrade_tx_hash,
+            factory_deps: factory_deps.into_iter().map(|(hash, _)| hash).collect(),
+            diamond_proxy: self.diamond_proxy,
+            diamond_cut_data: diamond_cut_data.into(),
+            execute_upgrade_calldata: execute_upgrade_calldata.into(),
+            governance: GovernanceCalldata {
+                schedule: schedule.into(),
+                execute: execute.into(),
+            },
+        })
+    }
+
+    /// Returns the transaction with zero type, which signals that there's no L2 upgrade transaction.
+    fn empty_transaction() -> Token {
+        let mut tokens = vec![u256_token(0); 10];
+        tokens.extend([
+            Token::FixedArray(vec![u256_token(0); 4]),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+            Token::Array(vec![]),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+        ]);
+        Token::Tuple(tokens)
+    }
+
+    fn upgrade_transaction(
+        &self,
+        deployments: &[(H256, &ForcedDeployment)],
+        factory_deps: &[(H256, Vec<u8>)],
+    ) -> Token {
+        let factory_deps = factory_deps
+            .iter()
+            .map(|(hash, _)| Token::Uint(h256_to_u256(*hash)))
+            .collect();
+        Token::Tuple(vec![
+            u256_token(PROTOCOL_UPGRADE_TX_TYPE),
+            Token::Uint(address_to_u256(&CONTRACT_FORCE_DEPLOYER_ADDRESS)),
+            Token::Uint(address_to_u256(&CONTRACT_DEPLOYER_ADDRESS)),
+            u256_token(self.upgrade_tx.gas_limit),
+            u256_token(self.upgrade_tx.gas_per_pubdata_limit),
+            u256_token(0),                             // `maxFeePerGas`
+            u256_token(0),                             // `maxPriorityFeePerGas`
+            u256_token(0),                             // paymaster
+            u256_token(self.protocol_version as u16),  // nonce (upgrade ID)
+            u256_token(0),                             // value
+            Token::FixedArray(vec![u256_token(0); 4]), // reserved (to mint, refund recipient, ...)
+            Token::Bytes(force_deploy_calldata(deployments)),
+            Token::Bytes(vec![]), // signature
+            Token::Array(factory_deps),
+            Token::Bytes(vec![]), // paymaster input
+            Token::Bytes(vec![]), // `reservedDynamic`
+        ])
+    }
+}
+
+impl UpgradePayload {
+    /// Decodes the payload in the same way the node decodes upgrades executed on L1.
+    pub fn to_protocol_upgrade(&self) -> anyhow::Result<ProtocolUpgrade> {
+        let call = Call {
+            target: self.diamond_proxy,
+            value: U256::zero(),
+            data: self.execute_upgrade_calldata.0.clone(),
+            eth_hash: H256::zero(),
+            eth_block: 0,
+        };
+        ProtocolUpgrade::try_from(call).context("failed decoding upgrade")
+    }
+
+    /// Validates the upgrade against the current state of the chain. Returns a list of errors,
+    /// which is empty if the upgrade is valid.
+    pub fn validate(&self, current: &ChainState) -> Vec<String> {
+        let mut errors = vec![];
+        let current_id = current.protocol_version;
+        if self.protocol_version <= current_id {
+            errors.push(format!(
+                "protocol version {} is not greater than the current version {current_id}",
+                self.protocol_version
+            ));
+        }
+        if let Some(current_timestamp) = current.timestamp {
+            if self.timestamp < current_timestamp {
+                errors.push(format!(
+                    "upgrade timestamp {} precedes the timestamp of the current version ({current_timestamp})",
+                    self.timestamp
+                ));
+            }
+        }
+
+        if self.bootloader_code_hash == Some(current.bootloader_code_hash) {
+            errors.push("bootloader is the same as in the current version".to_owned());
+        }
+        if self.default_account_code_hash == Some(current.default_account_code_hash) {
+            errors.push("default account is the same as in the current version".to_owned());
+        }
+        if self.verifier_address == Some(current.verifier_address) {
+            errors.push("verifier address is the same as in the current version".to_owned());
+        }
+        if self.verifier_params == Some(current.verifier_params) {
+            errors.push("verifier params are the same as in the current version".to_owned());
+        }
+        if self.factory_deps.len() > MAX_NEW_FACTORY_DEPS {
+            errors.push(format!(
+                "upgrade transaction has {} factory deps, while at most {MAX_NEW_FACTORY_DEPS} are allowed",
+                self.factory_deps.len()
+            ));
+        }
+
+        let changes_anything = self.bootloader_code_hash.is_some()
+            || self.default_account_code_hash.is_some()
+            || self.verifier_address.is_some()
+            || self.verifier_params.is_some()
+            || self.upgrade_tx_hash.is_some();
+        if !changes_anything {
+            errors.push("upgrade doesn't change anything".to_owned());
+        }
+
+        match self.to_protocol_upgrade() {
+            Ok(upgrade) => {
+                let upgrade_tx_hash = upgrade
+                    .tx
+                    .as_ref()
+                    .map(|tx| tx.common_data.canonical_tx_hash);
+                if upgrade_tx_hash != self.upgrade_tx_hash {
+                    errors.push(format!(
+                        "decoded upgrade transaction hash {upgrade_tx_hash:?} differs from the expected {:?}",
+                        self.upgrade_tx_hash
+                    ));
+                }
+            }
+            Err(err) => errors.push(format!("{err:#}")),
+        }
+        errors
+    }
+}
@@ -0,0 +1,148 @@
+//! Declarative upgrade manifest.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    protocol_version::VerifierParams, Address, Bytes, ProtocolVersionId, H256, U256,
+};
+
+use crate::{ForcedDeployment, UpgradeSpec, UpgradeTxParams};
+
+/// Upgrade manifest as stored in a JSON file. Bytecodes are referenced by paths relative to the manifest file;
+/// a path can point either to a compiled artifact (a JSON file with the `bytecode` field) or to a raw
+/// `.zbin` bytecode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpgradeManifest {
+    /// New protocol version ID.
+    pub protocol_version: u16,
+    /// Timestamp after which the upgrade can be executed.
+    pub timestamp: u64,
+    /// Address of the diamond proxy on L1.
+    pub diamond_proxy: Address,
+    /// Address of the L1 upgrade contract (e.g., `DefaultUpgrade`) used as the diamond cut initializer.
+    pub upgrade_contract: Address,
+    /// New bootloader bytecode.
+    #[serde(default)]
+    pub bootloader: Option<PathBuf>,
+    /// New default account bytecode.
+    #[serde(default)]
+    pub default_account: Option<PathBuf>,
+    /// New verifier address on L1.
+    #[serde(default)]
+    pub verifier_address: Option<Address>,
+    /// New recursion verification keys.
+    #[serde(default)]
+    pub verifier_params: Option<VerifierParams>,
+    /// Contracts force-deployed by the L2 upgrade transaction.
+    #[serde(default)]
+    pub forced_deployments: Vec<ForcedDeploymentManifest>,
+    #[serde(default)]
+    pub upgrade_tx: UpgradeTxParams,
+    #[serde(default)]
+    pub governance: GovernanceParams,
+}
+
+/// Contract force-deployed by the upgrade transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForcedDeploymentManifest {
+    /// L2 address to deploy the contract to.
+    pub address: Address,
+    /// Contract bytecode.
+    pub bytecode: PathBuf,
+    #[serde(default)]
+    pub call_constructor: bool,
+    #[serde(default)]
+    pub value: U256,
+    /// Constructor calldata.
+    #[serde(default)]
+    pub input: Bytes,
+}
+
+/// Parameters of the governance operation executing the upgrade.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GovernanceParams {
+    /// Delay (in seconds) between scheduling and executing the operation.
+    #[serde(default)]
+    pub delay: u64,
+    #[serde(default)]
+    pub predecessor: H256,
+    #[serde(default)]
+    pub salt: H256,
+}
+
+impl UpgradeManifest {
+    /// Loads a manifest from a JSON file.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("failed reading {path:?}"))?;
+        serde_json::from_str(&contents).with_context(|| format!("malformed manifest {path:?}"))
+    }
+
+    /// Loads all referenced bytecodes. Relative paths are resolved against `base_dir`.
+    pub fn load(self, base_dir: &Path) -> anyhow::Result<UpgradeSpec> {
+        let protocol_version =
+            ProtocolVersionId::try_from(self.protocol_version).map_err(|_| {
+                anyhow::anyhow!("unsupported protocol version {}", self.protocol_version)
+            })?;
+        let bootloader = self
+            .bootloader
+            .map(|path| read_bytecode(&base_dir.join(path)))
+            .transpose()?;
+        let default_account = self
+            .default_account
+            .map(|path| read_bytecode(&base_dir.join(path)))
+            .transpose()?;
+        let forced_deployments = self
+            .forced_deployments
+            .into_iter()
+            .map(|deployment| {
+                Ok(ForcedDeployment {
+                    address: deployment.address,
+                    bytecode: read_bytecode(&base_dir.join(&deployment.bytecode))?,
+                    call_constructor: deployment.call_constructor,
+                    value: deployment.value,
+                    input: deployment.input.0,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(UpgradeSpec {
+            protocol_version,
+            timestamp: self.timestamp,
+            diamond_proxy: self.diamond_proxy,
+            upgrade_contract: self.upgrade_contract,
+            bootloader,
+            default_account,
+            verifier_address: self.verifier_address,
+            verifier_params: self.verifier_params,
+            forced_deployments,
+            upgrade_tx: self.upgrade_tx,
+            governance: self.governance,
+        })
+    }
+}
+
+fn read_bytecode(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let is_artifact = path.extension().map_or(false, |ext| ext == "json");
+    if !is_artifact {
+        return fs::read(path).with_context(|| format!("failed reading bytecode {path:?}"));
+    }
+
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed reading artifact {path:?}"))?;
+    let artifact: serde_json::Value =
+        serde_json::from_str(&contents).with_context(|| format!("malformed artifact {path:?}"))?;
+    let bytecode = artifact["bytecode"]
+        .as_str()
+        .with_context(|| format!("no bytecode in artifact {path:?}"))?;
+    let bytecode = bytecode.strip_prefix("0x").unwrap_or(bytecode);
+    hex::decode(bytecode).with_context(|| format!("invalid bytecode hex in artifact {path:?}"))
+}
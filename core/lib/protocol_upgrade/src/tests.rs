@@ -0,0 +1,269 @@
+//! Tests for protocol upgrade construction.
+
+use std::fs;
+
+use zksync_types::{protocol_version::L1VerifierConfig, Execute};
+use zksync_utils::bytecode::hash_bytecode;
+
+use super::*;
+
+fn mock_bytecode(byte: u8) -> Vec<u8> {
+    // Valid bytecodes consist of an odd number of 32-byte words.
+    vec![byte; 96]
+}
+
+fn mock_spec() -> UpgradeSpec {
+    UpgradeSpec {
+        protocol_version: ProtocolVersionId::Version22,
+        timestamp: 1_700_000_000,
+        diamond_proxy: Address::repeat_byte(1),
+        upgrade_contract: Address::repeat_byte(2),
+        bootloader: Some(mock_bytecode(3)),
+        default_account: None,
+        verifier_address: None,
+        verifier_params: Some(VerifierParams {
+            recursion_node_level_vk_hash: H256::repeat_byte(4),
+            recursion_leaf_level_vk_hash: H256::repeat_byte(5),
+            recursion_circuits_set_vks_hash: H256::repeat_byte(6),
+        }),
+        forced_deployments: vec![
+            ForcedDeployment {
+                address: Address::from_low_u64_be(0x8008),
+                bytecode: mock_bytecode(7),
+                call_constructor: false,
+                value: U256::zero(),
+                input: vec![],
+            },
+            ForcedDeployment {
+                address: Address::from_low_u64_be(0x8009),
+                bytecode: mock_bytecode(7),
+                call_constructor: true,
+                value: U256::zero(),
+                input: vec![1, 2, 3],
+            },
+        ],
+        upgrade_tx: UpgradeTxParams::default(),
+        governance: GovernanceParams::default(),
+    }
+}
+
+#[test]
+fn building_upgrade_payload() {
+    let spec = mock_spec();
+    let payload = spec.build().unwrap();
+    assert_eq!(
+        payload.bootloader_code_hash,
+        Some(hash_bytecode(&mock_bytecode(3)))
+    );
+    assert_eq!(payload.default_account_code_hash, None);
+    // The bootloader must be published; identical bytecodes should be deduplicated.
+    assert_eq!(
+        payload.factory_deps,
+        [
+            hash_bytecode(&mock_bytecode(3)),
+            hash_bytecode(&mock_bytecode(7))
+        ]
+    );
+
+    let upgrade = payload.to_protocol_upgrade().unwrap();
+    assert_eq!(upgrade.id, ProtocolVersionId::Version22);
+    assert_eq!(upgrade.timestamp, spec.timestamp);
+    assert_eq!(upgrade.bootloader_code_hash, payload.bootloader_code_hash);
+    assert_eq!(upgrade.default_account_code_hash, None);
+    assert_eq!(upgrade.verifier_address, None);
+    assert_eq!(upgrade.verifier_params, spec.verifier_params);
+
+    let tx = upgrade.tx.expect("no upgrade tx");
+    assert_eq!(
+        Some(tx.common_data.canonical_tx_hash),
+        payload.upgrade_tx_hash
+    );
+    assert_eq!(tx.common_data.sender, CONTRACT_FORCE_DEPLOYER_ADDRESS);
+    assert_eq!(tx.common_data.upgrade_id, ProtocolVersionId::Version22);
+    assert_eq!(tx.common_data.gas_limit, spec.upgrade_tx.gas_limit.into());
+    let Execute {
+        contract_address,
+        calldata,
+        factory_deps,
+        ..
+    } = tx.execute;
+    assert_eq!(contract_address, CONTRACT_DEPLOYER_ADDRESS);
+    assert_eq!(
+        factory_deps,
+        Some(vec![mock_bytecode(3), mock_bytecode(7)])
+    );
+    let deployments: Vec<_> = spec
+        .forced_deployments
+        .iter()
+        .map(|deployment| (hash_bytecode(&deployment.bytecode), deployment))
+        .collect();
+    assert_eq!(calldata, force_deploy_calldata(&deployments));
+}
+
+#[test]
+fn building_upgrade_changing_only_system_contracts() {
+    let spec = UpgradeSpec {
+        default_account: Some(mock_bytecode(8)),
+        forced_deployments: vec![],
+        ..mock_spec()
+    };
+    let payload = spec.build().unwrap();
+    assert_eq!(
+        payload.factory_deps,
+        [
+            hash_bytecode(&mock_bytecode(3)),
+            hash_bytecode(&mock_bytecode(8))
+        ]
+    );
+
+    let upgrade = payload.to_protocol_upgrade().unwrap();
+    assert_eq!(upgrade.bootloader_code_hash, payload.bootloader_code_hash);
+    let tx = upgrade.tx.expect("no upgrade tx");
+    assert_eq!(
+        Some(tx.common_data.canonical_tx_hash),
+        payload.upgrade_tx_hash
+    );
+    assert_eq!(
+        tx.execute.factory_deps,
+        Some(vec![mock_bytecode(3), mock_bytecode(8)])
+    );
+    assert_eq!(tx.execute.calldata, force_deploy_calldata(&[]));
+}
+
+#[test]
+fn building_upgrade_without_l2_transaction() {
+    let spec = UpgradeSpec {
+        bootloader: None,
+        forced_deployments: vec![],
+        ..mock_spec()
+    };
+    let payload = spec.build().unwrap();
+    assert_eq!(payload.upgrade_tx_hash, None);
+    assert!(payload.factory_deps.is_empty());
+
+    let upgrade = payload.to_protocol_upgrade().unwrap();
+    assert!(upgrade.tx.is_none());
+    assert_eq!(upgrade.verifier_params, spec.verifier_params);
+}
+
+#[test]
+fn invalid_bytecodes_are_rejected() {
+    let spec = UpgradeSpec {
+        bootloader: Some(vec![0; 64]),
+        ..mock_spec()
+    };
+    let err = spec.build().unwrap_err().to_string();
+    assert!(err.contains("bootloader"), "{err}");
+
+    let mut spec = mock_spec();
+    spec.forced_deployments[1].address = spec.forced_deployments[0].address;
+    let err = spec.build().unwrap_err().to_string();
+    assert!(err.contains("multiple forced deployments"), "{err}");
+}
+
+#[test]
+fn validating_upgrade_against_current_version() {
+    let payload = mock_spec().build().unwrap();
+    let version = ProtocolVersion {
+        id: ProtocolVersionId::Version21,
+        timestamp: 1_600_000_000,
+        l1_verifier_config: L1VerifierConfig::default(),
+        ..ProtocolVersion::default()
+    };
+    let mut current = ChainState::from(&version);
+    assert_eq!(payload.validate(&current), Vec::<String>::new());
+
+    current.protocol_version = ProtocolVersionId::Version22 as u16;
+    current.bootloader_code_hash = payload.bootloader_code_hash.unwrap();
+    let errors = payload.validate(&current);
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    assert!(errors[0].contains("not greater than"), "{errors:?}");
+    assert!(errors[1].contains("bootloader"), "{errors:?}");
+
+    let noop_payload = UpgradeSpec {
+        bootloader: None,
+        verifier_params: None,
+        forced_deployments: vec![],
+        ..mock_spec()
+    }
+    .build()
+    .unwrap();
+    current.protocol_version = ProtocolVersionId::Version21 as u16;
+    let errors = noop_payload.validate(&current);
+    assert_eq!(errors, ["upgrade doesn't change anything"]);
+}
+
+#[test]
+fn validating_factory_deps_limit() {
+    let forced_deployments = (0..MAX_NEW_FACTORY_DEPS as u64)
+        .map(|i| {
+            let mut bytecode = mock_bytecode(7);
+            bytecode[..8].copy_from_slice(&i.to_be_bytes());
+            ForcedDeployment {
+                address: Address::from_low_u64_be(0x10000 + i),
+                bytecode,
+                call_constructor: false,
+                value: U256::zero(),
+                input: vec![],
+            }
+        })
+        .collect();
+    let payload = UpgradeSpec {
+        forced_deployments,
+        ..mock_spec()
+    }
+    .build()
+    .unwrap();
+    // The bootloader is published as well.
+    assert_eq!(payload.factory_deps.len(), MAX_NEW_FACTORY_DEPS + 1);
+
+    let current = ChainState::from(&ProtocolVersion {
+        id: ProtocolVersionId::Version21,
+        timestamp: 1_600_000_000,
+        ..ProtocolVersion::default()
+    });
+    let errors = payload.validate(&current);
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(errors[0].contains("factory deps"), "{errors:?}");
+}
+
+#[test]
+fn loading_manifest() {
+    let dir = tempfile::TempDir::new().unwrap();
+    fs::write(dir.path().join("bootloader.yul.zbin"), mock_bytecode(3)).unwrap();
+    let artifact = serde_json::json!({
+        "contractName": "Test",
+        "bytecode": format!("0x{}", hex::encode(mock_bytecode(7))),
+    });
+    fs::write(dir.path().join("Test.json"), artifact.to_string()).unwrap();
+
+    let manifest = serde_json::json!({
+        "protocol_version": 22,
+        "timestamp": 1_700_000_000,
+        "diamond_proxy": Address::repeat_byte(1),
+        "upgrade_contract": Address::repeat_byte(2),
+        "bootloader": "bootloader.yul.zbin",
+        "forced_deployments": [{
+            "address": Address::from_low_u64_be(0x8008),
+            "bytecode": "Test.json",
+        }],
+        "upgrade_tx": { "gas_limit": 10_000_000 },
+    });
+    let manifest_path = dir.path().join("manifest.json");
+    fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+    let spec = UpgradeManifest::from_file(&manifest_path)
+        .unwrap()
+        .load(dir.path())
+        .unwrap();
+    assert_eq!(spec.protocol_version, ProtocolVersionId::Version22);
+    assert_eq!(spec.bootloader, Some(mock_bytecode(3)));
+    assert_eq!(spec.forced_deployments.len(), 1);
+    assert_eq!(spec.forced_deployments[0].bytecode, mock_bytecode(7));
+    assert_eq!(spec.upgrade_tx.gas_limit, 10_000_000);
+    assert_eq!(
+        spec.upgrade_tx.gas_per_pubdata_limit,
+        REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE
+    );
+    spec.build().unwrap();
+}
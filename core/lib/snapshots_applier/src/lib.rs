@@ -12,8 +12,9 @@ use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_types::{
     api::en::SyncBlock,
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotHeader, SnapshotRecoveryStatus, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotHeader, SnapshotManifest, SnapshotRecoveryStatus,
+        SnapshotStorageLog, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotVersion,
     },
     tokens::TokenInfo,
     web3::futures,
@@ -248,6 +249,7 @@ impl<'a> SnapshotsApplier<'a> {
     async fn prepare_applied_snapshot_status(
         storage: &mut StorageProcessor<'_>,
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
+        blob_store: &dyn ObjectStore,
     ) -> Result<(SnapshotRecoveryStatus, bool), SnapshotsApplierError> {
         let latency =
             METRICS.initial_stage_duration[&InitialStage::FetchMetadataFromMainNode].start();
@@ -282,7 +284,8 @@ impl<'a> SnapshotsApplier<'a> {
             }
 
             let recovery_status =
-                SnapshotsApplier::create_fresh_recovery_status(main_node_client).await?;
+                SnapshotsApplier::create_fresh_recovery_status(main_node_client, blob_store)
+                    .await?;
 
             let storage_logs_count = storage
                 .snapshots_creator_dal()
@@ -322,8 +325,12 @@ impl<'a> SnapshotsApplier<'a> {
         })?;
 
         let (applied_snapshot_status, created_from_scratch) =
-            Self::prepare_applied_snapshot_status(&mut storage_transaction, main_node_client)
-                .await?;
+            Self::prepare_applied_snapshot_status(
+                &mut storage_transaction,
+                main_node_client,
+                blob_store,
+            )
+            .await?;
 
         let mut this = Self {
             connection_pool,
@@ -373,6 +380,7 @@ impl<'a> SnapshotsApplier<'a> {
 
     async fn create_fresh_recovery_status(
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
+        blob_store: &dyn ObjectStore,
     ) -> Result<SnapshotRecoveryStatus, SnapshotsApplierError> {
         let snapshot_response = main_node_client.fetch_newest_snapshot().await?;
 
@@ -380,10 +388,23 @@ impl<'a> SnapshotsApplier<'a> {
             .context("no snapshots on main node; snapshot recovery is impossible")?;
         let l1_batch_number = snapshot.l1_batch_number;
         let miniblock_number = snapshot.miniblock_number;
+        let version = SnapshotVersion::try_from(snapshot.version).with_context(|| {
+            format!(
+                "snapshot for L1 batch #{l1_batch_number} has an unsupported version; \
+                 update the node to recover from it"
+            )
+        })?;
         tracing::info!(
-            "Found snapshot with data up to L1 batch #{l1_batch_number}, storage_logs are divided into {} chunk(s)",
+            "Found snapshot (version {version:?}) with data up to L1 batch #{l1_batch_number}, \
+             storage_logs are divided into {} chunk(s)",
             snapshot.storage_logs_chunks.len()
         );
+        match version {
+            SnapshotVersion::Version1 => { /* no manifest for the snapshot */ }
+            SnapshotVersion::Version2 => {
+                Self::check_snapshot_manifest(&snapshot, blob_store).await?;
+            }
+        }
 
         let miniblock = main_node_client
             .fetch_l2_block(miniblock_number)
@@ -409,6 +430,52 @@ impl<'a> SnapshotsApplier<'a> {
         })
     }
 
+    /// Checks that the snapshot manifest persisted in the object store matches the snapshot header
+    /// returned by the main node.
+    async fn check_snapshot_manifest(
+        snapshot: &SnapshotHeader,
+        blob_store: &dyn ObjectStore,
+    ) -> Result<(), SnapshotsApplierError> {
+        let l1_batch_number = snapshot.l1_batch_number;
+        let manifest: SnapshotManifest = blob_store.get(l1_batch_number).await.map_err(|err| {
+            let context =
+                format!("failed fetching manifest for snapshot at L1 batch #{l1_batch_number}");
+            SnapshotsApplierError::object_store(err, context)
+        })?;
+        Self::validate_snapshot_manifest(snapshot, &manifest)?;
+        Ok(())
+    }
+
+    fn validate_snapshot_manifest(
+        snapshot: &SnapshotHeader,
+        manifest: &SnapshotManifest,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = snapshot.l1_batch_number;
+        let header_version = SnapshotVersion::try_from(snapshot.version)?;
+        anyhow::ensure!(
+            manifest.version == header_version,
+            "snapshot manifest has version {:?}, while the main node reports version {header_version:?}",
+            manifest.version
+        );
+        anyhow::ensure!(
+            manifest.l1_batch_number == l1_batch_number
+                && manifest.miniblock_number == snapshot.miniblock_number,
+            "snapshot manifest is for L1 batch #{} / miniblock #{}, while the main node reports \
+             L1 batch #{l1_batch_number} / miniblock #{}",
+            manifest.l1_batch_number,
+            manifest.miniblock_number,
+            snapshot.miniblock_number
+        );
+        anyhow::ensure!(
+            manifest.storage_logs_chunks == snapshot.storage_logs_chunks,
+            "storage log chunks in the snapshot manifest ({} chunks) differ from the ones reported \
+             by the main node ({} chunks)",
+            manifest.storage_logs_chunks.len(),
+            snapshot.storage_logs_chunks.len()
+        );
+        Ok(())
+    }
+
     fn update_health(&self) {
         let details = SnapshotsApplierHealthDetails {
             snapshot_miniblock: self.applied_snapshot_status.miniblock_number,
@@ -3,7 +3,7 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use test_casing::test_casing;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{ObjectStoreFactory, StoredObject};
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     get_code_key, Address, L1BatchNumber, ProtocolVersion, ProtocolVersionId,
@@ -119,6 +119,7 @@ async fn applier_errors_after_genesis() {
         base_system_contracts_hashes: Default::default(),
         protocol_version: Some(ProtocolVersionId::latest()),
         virtual_blocks: 0,
+        gas_limit: None,
     };
     storage
         .blocks_dal()
@@ -152,6 +153,69 @@ async fn applier_errors_after_genesis() {
         .unwrap_err();
 }
 
+#[tokio::test]
+async fn recovering_from_legacy_snapshot_without_manifest() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let manifest_key = SnapshotManifest::encode_key(expected_status.l1_batch_number);
+    object_store
+        .remove_raw(SnapshotManifest::BUCKET, &manifest_key)
+        .await
+        .unwrap();
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.version = SnapshotVersion::Version1.into();
+
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let current_db_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(current_db_status.unwrap(), expected_status);
+}
+
+#[tokio::test]
+async fn applier_errors_on_unsupported_snapshot_version() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.version = 1_000;
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("unsupported snapshot version"),
+        "{err:#}"
+    );
+}
+
+#[tokio::test]
+async fn applier_errors_on_snapshot_manifest_mismatch() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.storage_logs_chunks.pop();
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("snapshot manifest"), "{err:#}");
+}
+
 #[tokio::test]
 async fn applier_errors_without_snapshots() {
     let pool = ConnectionPool::test_pool().await;
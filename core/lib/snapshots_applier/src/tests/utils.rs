@@ -9,9 +9,9 @@ use zksync_types::{
     block::L1BatchHeader,
     commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader,
+        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader, SnapshotManifest,
         SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
-        SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey,
+        SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     tokens::{TokenInfo, TokenMetadata},
     AccountTreeId, Address, Bytes, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey,
@@ -118,6 +118,7 @@ fn miniblock_metadata(
         virtual_blocks: None,
         hash: Some(hash),
         protocol_version: Default::default(),
+        gas_limit: None,
     }
 }
 
@@ -244,6 +245,7 @@ pub(super) async fn prepare_clients(
     }
 
     let snapshot_header = SnapshotHeader {
+        version: SnapshotVersion::LATEST.into(),
         l1_batch_number: status.l1_batch_number,
         miniblock_number: status.miniblock_number,
         last_l1_batch_with_metadata: l1_block_metadata(
@@ -262,6 +264,17 @@ pub(super) async fn prepare_clients(
         ],
         factory_deps_filepath: "some_filepath".to_string(),
     };
+    let manifest = SnapshotManifest {
+        version: SnapshotVersion::LATEST,
+        l1_batch_number: snapshot_header.l1_batch_number,
+        miniblock_number: snapshot_header.miniblock_number,
+        factory_deps_filepath: snapshot_header.factory_deps_filepath.clone(),
+        storage_logs_chunks: snapshot_header.storage_logs_chunks.clone(),
+    };
+    object_store
+        .put(status.l1_batch_number, &manifest)
+        .await
+        .unwrap();
     client.fetch_newest_snapshot_response = Some(snapshot_header);
     client.fetch_l2_block_responses.insert(
         status.miniblock_number,
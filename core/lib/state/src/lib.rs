@@ -29,7 +29,7 @@ mod witness;
 
 pub use self::{
     in_memory::{InMemoryStorage, IN_MEMORY_STORAGE_DEFAULT_NETWORK_ID},
-    postgres::{PostgresStorage, PostgresStorageCaches},
+    postgres::{PostgresStorage, PostgresStorageCaches, RedisCache, SharedCache},
     rocksdb::{RocksbStorageBuilder, RocksdbStorage},
     shadow_storage::ShadowStorage,
     storage_view::{StorageView, StorageViewMetrics},
@@ -11,6 +11,14 @@ pub(super) enum ValuesUpdateStage {
     RemoveStaleKeys,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum SharedCacheRequest {
+    Hit,
+    Miss,
+    Error,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_state_cache")]
 pub(super) struct ValuesCacheMetrics {
@@ -29,6 +37,8 @@ pub(super) struct ValuesCacheMetrics {
     /// Number of times the negative initial writes cache was successfully used. This is distinct
     /// from cache hits (we can hit the cache, but the cached value may be outdated).
     pub effective_values: Counter,
+    /// Number of requests to the shared cache grouped by the result.
+    pub shared_cache: Family<SharedCacheRequest, Counter>,
 }
 
 #[vise::register]
@@ -9,12 +9,14 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::{L1BatchNumber, MiniblockNumber, StorageKey, StorageValue, H256};
 
 use self::metrics::{Method, ValuesUpdateStage, CACHE_METRICS, STORAGE_METRICS};
+pub use self::shared_cache::{RedisCache, SharedCache};
 use crate::{
     cache::{Cache, CacheValue},
     ReadStorage,
 };
 
 mod metrics;
+mod shared_cache;
 #[cfg(test)]
 mod tests;
 
@@ -221,6 +223,10 @@ struct ValuesCacheAndUpdater {
 /// - Cache for L1 batch numbers of initial writes for storage keys (never invalidated, except after
 ///   reverting L1 batch execution)
 /// - Cache of the VM storage snapshot corresponding to the latest sealed miniblock
+///
+/// Bytecode and initial write caches can optionally be backed by a [`SharedCache`] (e.g., a Redis instance
+/// used by all API server replicas), which is queried on local cache misses before accessing Postgres.
+/// The storage values cache is always local since it is tied to a specific miniblock.
 #[derive(Debug, Clone)]
 pub struct PostgresStorageCaches {
     factory_deps: FactoryDepsCache,
@@ -234,10 +240,13 @@ pub struct PostgresStorageCaches {
     // it wasn't written to at the point that interests us.
     negative_initial_writes: InitialWritesCache,
     values: Option<ValuesCacheAndUpdater>,
+    shared: Option<Arc<dyn SharedCache>>,
 }
 
 impl PostgresStorageCaches {
     const NEG_INITIAL_WRITES_NAME: &'static str = "negative_initial_writes_cache";
+    const SHARED_FACTORY_DEPS_PREFIX: &'static [u8] = b"fd:";
+    const SHARED_INITIAL_WRITES_PREFIX: &'static [u8] = b"iw:";
 
     /// Creates caches with the specified capacities measured in bytes.
     pub fn new(factory_deps_capacity: u64, initial_writes_capacity: u64) -> Self {
@@ -257,7 +266,68 @@ impl PostgresStorageCaches {
                 initial_writes_capacity / 2,
             ),
             values: None,
+            shared: None,
+        }
+    }
+
+    /// Backs factory dependency and initial write caches with the provided shared cache.
+    #[must_use]
+    pub fn with_shared_cache(mut self, shared: Arc<dyn SharedCache>) -> Self {
+        tracing::debug!("Using shared cache {shared:?} for VM execution");
+        self.shared = Some(shared);
+        self
+    }
+
+    fn shared_key(prefix: &[u8], hash: H256) -> Vec<u8> {
+        let mut key = prefix.to_vec();
+        key.extend_from_slice(hash.as_bytes());
+        key
+    }
+
+    fn get_factory_dep(&self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(dep) = self.factory_deps.get(&hash) {
+            return Some(dep);
+        }
+        let shared = self.shared.as_ref()?;
+        let dep = shared.get(&Self::shared_key(Self::SHARED_FACTORY_DEPS_PREFIX, hash))?;
+        self.factory_deps.insert(hash, dep.clone());
+        Some(dep)
+    }
+
+    fn insert_factory_dep(&self, hash: H256, dep: Vec<u8>) {
+        if let Some(shared) = &self.shared {
+            shared.set(
+                &Self::shared_key(Self::SHARED_FACTORY_DEPS_PREFIX, hash),
+                &dep,
+            );
+        }
+        self.factory_deps.insert(hash, dep);
+    }
+
+    fn get_initial_write(&self, key: &StorageKey) -> Option<L1BatchNumber> {
+        if let Some(l1_batch_number) = self.initial_writes.get(key) {
+            return Some(l1_batch_number);
+        }
+        let shared = self.shared.as_ref()?;
+        let shared_key = Self::shared_key(Self::SHARED_INITIAL_WRITES_PREFIX, key.hashed_key());
+        let value = shared.get(&shared_key)?;
+        let Ok(bytes) = <[u8; 4]>::try_from(value.as_slice()) else {
+            tracing::warn!(
+                "Invalid initial write value in shared cache for key {key:?}: {value:?}"
+            );
+            return None;
+        };
+        let l1_batch_number = L1BatchNumber(u32::from_be_bytes(bytes));
+        self.initial_writes.insert(*key, l1_batch_number);
+        Some(l1_batch_number)
+    }
+
+    fn insert_initial_write(&self, key: &StorageKey, l1_batch_number: L1BatchNumber) {
+        if let Some(shared) = &self.shared {
+            let shared_key = Self::shared_key(Self::SHARED_INITIAL_WRITES_PREFIX, key.hashed_key());
+            shared.set(&shared_key, &l1_batch_number.0.to_be_bytes());
         }
+        self.initial_writes.insert(*key, l1_batch_number);
     }
 
     /// Configures the VM storage values cache. The returned closure is the background task that will update
@@ -444,7 +514,7 @@ impl ReadStorage for PostgresStorage<'_> {
     fn is_write_initial(&mut self, key: &StorageKey) -> bool {
         let latency = STORAGE_METRICS.storage[&Method::IsWriteInitial].start();
         let caches = self.caches.as_ref();
-        let cached_value = caches.and_then(|caches| caches.initial_writes.get(key));
+        let cached_value = caches.and_then(|caches| caches.get_initial_write(key));
 
         if cached_value.is_none() {
             // Write is absent in positive cache, check whether it's present in the negative cache.
@@ -471,7 +541,7 @@ impl ReadStorage for PostgresStorage<'_> {
             if let Some(caches) = &self.caches {
                 if let Some(l1_batch_number) = value {
                     caches.negative_initial_writes.remove(key);
-                    caches.initial_writes.insert(*key, l1_batch_number);
+                    caches.insert_initial_write(key, l1_batch_number);
                 } else {
                     caches
                         .negative_initial_writes
@@ -496,7 +566,7 @@ impl ReadStorage for PostgresStorage<'_> {
         let cached_value = self
             .caches
             .as_ref()
-            .and_then(|caches| caches.factory_deps.get(&hash));
+            .and_then(|caches| caches.get_factory_dep(hash));
 
         let result = cached_value.or_else(|| {
             let mut dal = self.connection.storage_web3_dal();
@@ -508,7 +578,7 @@ impl ReadStorage for PostgresStorage<'_> {
             if let Some(caches) = &self.caches {
                 // If we receive None, we won't cache it.
                 if let Some(dep) = value.clone() {
-                    caches.factory_deps.insert(hash, dep);
+                    caches.insert_factory_dep(hash, dep);
                 }
             };
 
@@ -0,0 +1,301 @@
+//! Shared caches that can be used by multiple processes (e.g., API server replicas) as a second level
+//! behind local in-memory caches.
+
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+
+use super::metrics::{SharedCacheRequest, CACHE_METRICS};
+
+/// Shared cache of immutable (or rarely changing) storage data. Entries are expected to be retained
+/// by the cache for a limited time only.
+///
+/// Cache failures must not influence VM execution, so implementations are expected to handle errors
+/// internally (e.g., by logging them and treating failed reads as cache misses).
+pub trait SharedCache: fmt::Debug + Send + Sync {
+    /// Gets an entry from the cache.
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>>;
+    /// Inserts an entry into the cache.
+    fn set(&self, key: &[u8], value: &[u8]);
+}
+
+/// Connection to a Redis server.
+#[derive(Debug)]
+struct RedisConnection {
+    reader: BufReader<TcpStream>,
+}
+
+impl RedisConnection {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            reader: BufReader::new(stream),
+        }
+    }
+
+    /// Sends a command encoded as an array of bulk strings.
+    fn send_command(&mut self, args: &[&[u8]]) -> io::Result<()> {
+        let mut command = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            command.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            command.extend_from_slice(arg);
+            command.extend_from_slice(b"\r\n");
+        }
+        self.reader.get_mut().write_all(&command)
+    }
+
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        if !line.ends_with("\r\n") {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unterminated Redis response",
+            ));
+        }
+        line.truncate(line.len() - 2);
+        Ok(line)
+    }
+
+    /// Reads a reply that is either a simple string or a (possibly null) bulk string.
+    fn read_reply(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let line = self.read_line()?;
+        let mut chars = line.chars();
+        let prefix = chars.next();
+        let rest = chars.as_str();
+        match prefix {
+            Some('+') => Ok(Some(rest.as_bytes().to_vec())),
+            Some('-') => Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("Redis error: {rest}"),
+            )),
+            Some('$') => {
+                let len: i64 = rest.parse().map_err(|_| invalid_reply(&line))?;
+                let Ok(len) = usize::try_from(len) else {
+                    return Ok(None); // null bulk string
+                };
+                let mut value = vec![0_u8; len + 2];
+                io::Read::read_exact(&mut self.reader, &mut value)?;
+                if !value.ends_with(b"\r\n") {
+                    return Err(invalid_reply(&line));
+                }
+                value.truncate(len);
+                Ok(Some(value))
+            }
+            _ => Err(invalid_reply(&line)),
+        }
+    }
+}
+
+fn invalid_reply(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected Redis reply: {line:?}"),
+    )
+}
+
+/// [`SharedCache`] backed by a Redis server. Uses a small pool of synchronous connections, since caches
+/// are accessed from the (blocking) VM execution threads.
+///
+/// If a Redis request fails, the cache is bypassed for [`Self::BACKOFF`] so that an unavailable server
+/// doesn't slow down VM execution.
+pub struct RedisCache {
+    address: String,
+    key_prefix: String,
+    ttl: Duration,
+    timeout: Duration,
+    connections: Mutex<Vec<RedisConnection>>,
+    disabled_until: Mutex<Option<Instant>>,
+}
+
+impl fmt::Debug for RedisCache {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RedisCache")
+            .field("address", &self.address)
+            .field("key_prefix", &self.key_prefix)
+            .field("ttl", &self.ttl)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisCache {
+    /// Time during which the cache is bypassed after a failed request.
+    pub const BACKOFF: Duration = Duration::from_secs(5);
+    const MAX_IDLE_CONNECTIONS: usize = 16;
+
+    /// Creates a cache for the specified Redis URL (e.g., `redis://redis.local:6379`). Entries are stored
+    /// with the provided key prefix, which allows to share a Redis instance among multiple networks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid. The server is not contacted in this method.
+    pub fn new(url: &str, key_prefix: String, ttl: Duration) -> anyhow::Result<Self> {
+        let address = url
+            .strip_prefix("redis://")
+            .with_context(|| {
+                format!("unsupported shared cache URL `{url}`; expected `redis://host:port`")
+            })?
+            .trim_end_matches('/');
+        anyhow::ensure!(
+            !address.is_empty() && !address.contains('/'),
+            "invalid Redis URL `{url}`; expected `redis://host:port`"
+        );
+        Ok(Self {
+            address: address.to_owned(),
+            key_prefix,
+            ttl,
+            timeout: Duration::from_millis(100),
+            connections: Mutex::default(),
+            disabled_until: Mutex::default(),
+        })
+    }
+
+    fn connect(&self) -> io::Result<RedisConnection> {
+        let mut last_err = None;
+        for address in self.address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, self.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    stream.set_nodelay(true)?;
+                    return Ok(RedisConnection::new(stream));
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "Redis address not resolved")
+        }))
+    }
+
+    fn is_disabled(&self) -> bool {
+        let mut disabled_until = self.disabled_until.lock().unwrap();
+        match *disabled_until {
+            Some(until) if Instant::now() < until => true,
+            Some(_) => {
+                *disabled_until = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Executes a command on a pooled connection. Returns `None` if the cache is disabled or the command has failed.
+    fn execute(&self, args: &[&[u8]]) -> Option<Option<Vec<u8>>> {
+        if self.is_disabled() {
+            return None;
+        }
+
+        let connection = self.connections.lock().unwrap().pop();
+        let result = connection
+            .map_or_else(|| self.connect(), Ok)
+            .and_then(|mut connection| {
+                connection.send_command(args)?;
+                let reply = connection.read_reply()?;
+                Ok((connection, reply))
+            });
+        match result {
+            Ok((connection, reply)) => {
+                let mut connections = self.connections.lock().unwrap();
+                if connections.len() < Self::MAX_IDLE_CONNECTIONS {
+                    connections.push(connection);
+                }
+                Some(reply)
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Request to shared cache at {} failed: {err}; bypassing cache for {:?}",
+                    self.address,
+                    Self::BACKOFF
+                );
+                CACHE_METRICS.shared_cache[&SharedCacheRequest::Error].inc();
+                *self.disabled_until.lock().unwrap() = Some(Instant::now() + Self::BACKOFF);
+                None
+            }
+        }
+    }
+
+    fn prefixed_key(&self, key: &[u8]) -> Vec<u8> {
+        let mut prefixed_key = self.key_prefix.as_bytes().to_vec();
+        prefixed_key.extend_from_slice(key);
+        prefixed_key
+    }
+}
+
+impl SharedCache for RedisCache {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let key = self.prefixed_key(key);
+        let value = self.execute(&[b"GET", &key])?;
+        let request = if value.is_some() {
+            SharedCacheRequest::Hit
+        } else {
+            SharedCacheRequest::Miss
+        };
+        CACHE_METRICS.shared_cache[&request].inc();
+        value
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) {
+        let key = self.prefixed_key(key);
+        let ttl_ms = self.ttl.as_millis().to_string();
+        self.execute(&[b"SET", &key, value, b"PX", ttl_ms.as_bytes()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener, thread};
+
+    use super::*;
+
+    #[test]
+    fn parsing_redis_url() {
+        let cache =
+            RedisCache::new("redis://localhost:6379/", "test:".into(), Duration::ZERO).unwrap();
+        assert_eq!(cache.address, "localhost:6379");
+        RedisCache::new("http://localhost:6379", "test:".into(), Duration::ZERO).unwrap_err();
+        RedisCache::new("redis://localhost:6379/0", "test:".into(), Duration::ZERO).unwrap_err();
+    }
+
+    #[test]
+    fn redis_cache_basics() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0_u8; 256];
+            // `GET test:key` for a missing key
+            let len = stream.read(&mut buffer).unwrap();
+            assert_eq!(&buffer[..len], b"*2\r\n$3\r\nGET\r\n$8\r\ntest:key\r\n");
+            stream.write_all(b"$-1\r\n").unwrap();
+            // `SET test:key value PX 1000`
+            let len = stream.read(&mut buffer).unwrap();
+            assert!(
+                buffer[..len].starts_with(b"*5\r\n$3\r\nSET\r\n$8\r\ntest:key\r\n$5\r\nvalue\r\n")
+            );
+            stream.write_all(b"+OK\r\n").unwrap();
+            // `GET test:key` for an existing key
+            let len = stream.read(&mut buffer).unwrap();
+            assert!(buffer[..len].starts_with(b"*2\r\n$3\r\nGET\r\n"));
+            stream.write_all(b"$5\r\nvalue\r\n").unwrap();
+        });
+
+        let url = format!("redis://{address}");
+        let cache = RedisCache::new(&url, "test:".into(), Duration::from_secs(1)).unwrap();
+        assert_eq!(cache.get(b"key"), None);
+        cache.set(b"key", b"value");
+        assert_eq!(cache.get(b"key").unwrap(), b"value");
+        server.join().unwrap();
+
+        // The server has shut down, so the cache should be bypassed.
+        assert_eq!(cache.get(b"key"), None);
+        assert!(cache.is_disabled());
+    }
+}
@@ -255,6 +255,92 @@ async fn using_factory_deps_cache() {
         .unwrap();
 }
 
+#[derive(Debug, Default)]
+struct MockSharedCache(std::sync::Mutex<HashMap<Vec<u8>, Vec<u8>>>);
+
+impl SharedCache for MockSharedCache {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.0.lock().unwrap().get(key).cloned()
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) {
+        self.0.lock().unwrap().insert(key.to_vec(), value.to_vec());
+    }
+}
+
+fn test_shared_cache(pool: &ConnectionPool, rt_handle: Handle) {
+    let mut connection = rt_handle.block_on(pool.access_storage()).unwrap();
+    rt_handle.block_on(prepare_postgres(&mut connection));
+    let logs = gen_storage_logs(100..120);
+    rt_handle.block_on(create_miniblock(
+        &mut connection,
+        MiniblockNumber(1),
+        logs.clone(),
+    ));
+    rt_handle.block_on(create_l1_batch(&mut connection, L1BatchNumber(1), &logs));
+    let mut contracts = HashMap::new();
+    contracts.insert(H256::repeat_byte(1), vec![1, 2, 3]);
+    rt_handle
+        .block_on(
+            connection
+                .factory_deps_dal()
+                .insert_factory_deps(MiniblockNumber(1), &contracts),
+        )
+        .unwrap();
+
+    let shared_cache = Arc::new(MockSharedCache::default());
+    let caches = PostgresStorageCaches::new(1_024, 1_024).with_shared_cache(shared_cache.clone());
+    let mut storage = PostgresStorage::new(rt_handle.clone(), connection, MiniblockNumber(1), true)
+        .with_caches(caches);
+    assert_eq!(
+        storage.load_factory_dep(H256::repeat_byte(1)),
+        Some(vec![1, 2, 3])
+    );
+    assert!(!storage.is_write_initial(&logs[0].key));
+    assert_eq!(shared_cache.0.lock().unwrap().len(), 2);
+
+    // Storage with fresh local caches (e.g., on another API server replica) should use the shared cache
+    // without accessing Postgres.
+    let mut connection = storage.connection;
+    rt_handle
+        .block_on(
+            connection
+                .blocks_dal()
+                .delete_initial_writes(L1BatchNumber(0)),
+        )
+        .unwrap();
+    rt_handle
+        .block_on(
+            connection
+                .factory_deps_dal()
+                .rollback_factory_deps(MiniblockNumber(0)),
+        )
+        .unwrap();
+    let caches = PostgresStorageCaches::new(1_024, 1_024).with_shared_cache(shared_cache);
+    let mut storage = PostgresStorage::new(rt_handle, connection, MiniblockNumber(1), true)
+        .with_caches(caches.clone());
+    assert_eq!(
+        storage.load_factory_dep(H256::repeat_byte(1)),
+        Some(vec![1, 2, 3])
+    );
+    assert!(!storage.is_write_initial(&logs[0].key));
+    assert_eq!(
+        caches.initial_writes.get(&logs[0].key),
+        Some(L1BatchNumber(1))
+    );
+    // This key is not in the shared cache, so it's checked in Postgres (which doesn't have initial writes anymore).
+    assert!(storage.is_write_initial(&logs[1].key));
+}
+
+#[tokio::test]
+async fn using_shared_cache() {
+    let pool = ConnectionPool::test_pool().await;
+    let handle = Handle::current();
+    tokio::task::spawn_blocking(move || test_shared_cache(&pool, handle))
+        .await
+        .unwrap();
+}
+
 fn test_initial_writes_cache(pool: &ConnectionPool, rt_handle: Handle) {
     let connection = rt_handle.block_on(pool.access_storage()).unwrap();
     let caches = PostgresStorageCaches::new(1_024, 4 * 1_024 * 1_024);
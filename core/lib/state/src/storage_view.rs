@@ -60,6 +60,16 @@ impl<S> StorageView<S> {
             is_write_initial: self.initial_writes_cache.clone(),
         }
     }
+
+    /// Same as [`Self::witness_block_state()`], but moves the state out of the in-memory cache instead of cloning it.
+    /// This is useful for large L1 batches, for which the state can occupy a lot of RAM. The view must not be used
+    /// to execute transactions afterwards.
+    pub fn take_witness_block_state(&mut self) -> WitnessBlockState {
+        WitnessBlockState {
+            read_storage_key: mem::take(&mut self.read_storage_keys),
+            is_write_initial: mem::take(&mut self.initial_writes_cache),
+        }
+    }
 }
 
 impl<S> ReadStorage for Box<S>
@@ -251,4 +261,37 @@ mod test {
         assert_eq!(metrics.get_value_storage_invocations, 3);
         assert_eq!(metrics.set_value_storage_invocations, 2);
     }
+
+    #[test]
+    fn taking_witness_block_state() {
+        let account = AccountTreeId::new(Address::from([0xfe; 20]));
+        let key = StorageKey::new(account, H256::from_low_u64_be(61));
+        let new_key = StorageKey::new(account, H256::from_low_u64_be(62));
+        let mut raw_storage = InMemoryStorage::default();
+        raw_storage.set_value(key, H256::from_low_u64_be(73));
+
+        let mut storage_view = StorageView::new(&raw_storage);
+        storage_view.read_value(&key);
+        storage_view.set_value(key, H256::from_low_u64_be(74));
+        storage_view.set_value(new_key, H256::from_low_u64_be(75));
+        assert!(!storage_view.is_write_initial(&key));
+        assert!(storage_view.is_write_initial(&new_key));
+
+        let cloned_state = storage_view.witness_block_state();
+        let state = storage_view.take_witness_block_state();
+        assert_eq!(state.read_storage_key, cloned_state.read_storage_key);
+        assert_eq!(state.is_write_initial, cloned_state.is_write_initial);
+        assert_eq!(
+            state.read_storage_key,
+            HashMap::from([(key, H256::from_low_u64_be(73)), (new_key, H256::zero())])
+        );
+        assert_eq!(
+            state.is_write_initial,
+            HashMap::from([(key, false), (new_key, true)])
+        );
+
+        let state = storage_view.witness_block_state();
+        assert!(state.read_storage_key.is_empty());
+        assert!(state.is_write_initial.is_empty());
+    }
 }
@@ -43,4 +43,6 @@ pub struct SyncBlock {
     pub hash: Option<H256>,
     /// Version of the protocol used for this block.
     pub protocol_version: ProtocolVersionId,
+    /// Gas limit of the L2 block, if it was enforced by the main node.
+    pub gas_limit: Option<u64>,
 }
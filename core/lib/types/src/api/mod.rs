@@ -7,17 +7,23 @@ use zksync_basic_types::{
 };
 use zksync_contracts::BaseSystemContractsHashes;
 
-pub use crate::transaction_request::{
-    Eip712Meta, SerializationTransactionError, TransactionRequest,
-};
+pub use self::signing::{SignedResponse, SigningToken};
 use crate::{
+    block::{MiniblockHashScheme, SealInfo},
+    commitment::BlobCommitment,
     protocol_version::L1VerifierConfig,
+    pubdata_da::PubdataDA,
     vm_trace::{Call, CallType},
     web3::types::{AccessList, Index, H2048},
     Address, MiniblockNumber, ProtocolVersionId,
 };
+pub use crate::{
+    l2::ValidUntil,
+    transaction_request::{Eip712Meta, SerializationTransactionError, TransactionRequest},
+};
 
 pub mod en;
+mod signing;
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq, Display)]
@@ -199,6 +205,293 @@ pub struct BridgeAddresses {
     pub l2_weth_bridge: Option<Address>,
 }
 
+/// Addresses of the contracts relevant for interacting with the chain, together with the chain IDs.
+/// Addresses that are not deployed or not known to the node are set to `None`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractAddresses {
+    pub l1_chain_id: U64,
+    pub l2_chain_id: U64,
+    /// Address of the main (diamond proxy) contract of the chain on L1.
+    pub main_contract: Address,
+    pub bridgehub: Option<Address>,
+    pub shared_bridge: Option<Address>,
+    pub state_transition_manager: Option<Address>,
+    pub validator_timelock: Option<Address>,
+    pub governance: Option<Address>,
+    pub bridges: BridgeAddresses,
+    pub testnet_paymaster: Option<Address>,
+}
+
+/// Per-chain limits for L2 transactions, which can be used by clients to validate transactions before submitting them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxLimits {
+    /// Max size of an encoded L2 transaction in bytes.
+    pub max_encoded_tx_size: U64,
+    /// Max number of factory dependencies in an L2 transaction.
+    pub max_factory_deps: U64,
+    /// Max size of L2 transaction calldata in bytes.
+    pub max_calldata_size: U64,
+}
+
+/// Limits on the L1 batch contents; a batch is sealed once any of these limits is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSealLimits {
+    /// Max number of transactions in a batch.
+    pub transaction_slots: U64,
+    /// Max amount of gas that can be used by a batch.
+    pub max_gas_per_batch: U64,
+    /// Max amount of pubdata (in bytes) that can be produced by a batch.
+    pub max_pubdata_per_batch: U64,
+}
+
+/// Version of the fee model used by the chain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeeModelVersion {
+    /// Pubdata price is pegged to the L1 gas price.
+    #[default]
+    V1,
+    /// Pubdata price may be independent from the L1 gas price.
+    V2,
+}
+
+/// Static and semi-static parameters of the chain, allowing clients to discover the chain configuration in a single call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainParameters {
+    pub l2_chain_id: U64,
+    /// L1 address of the base token of the chain. The zero address corresponds to ETH.
+    pub base_token_address: Address,
+    pub fee_model_version: FeeModelVersion,
+    /// Data availability mode used to publish pubdata of L1 batches.
+    pub pubdata_da: PubdataDA,
+    pub batch_seal_limits: BatchSealLimits,
+    pub tx_limits: TxLimits,
+    /// Latest protocol version known to the node.
+    pub protocol_version: u16,
+    /// Hashes of base system contracts for the latest protocol version.
+    pub base_system_contracts: BaseSystemContractsHashes,
+}
+
+/// Information about the node build and configuration, allowing to audit deployments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfo {
+    /// Git commit the node is built from, if known.
+    pub git_commit: Option<String>,
+    /// Cargo profile the node is built with.
+    pub build_profile: String,
+    /// Components run by the node.
+    pub components: Vec<String>,
+    /// API namespaces enabled on the server serving the request.
+    pub api_namespaces: Vec<String>,
+    /// Protocol versions supported by the VM bundled with the node.
+    pub supported_protocol_versions: Vec<u16>,
+}
+
+/// API capability depending on the protocol version of the chain and / or on the API level declared by the client.
+/// Capabilities allow rolling out encoding changes without breaking older SDKs: a capability changing
+/// the encoding of existing responses is only enabled for clients declaring a sufficient API level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiCapability {
+    /// L1 batches may publish pubdata in EIP-4844 blobs; blob commitments are returned
+    /// by `zks_getL1BatchPublicInputs`.
+    BlobCommitments,
+    /// Transaction receipts contain the `gasRefunded` field.
+    ReceiptGasRefunded,
+    /// JSON-RPC errors for transaction submission and execution contain an object with a stable error code
+    /// in the `data` field (e.g., `{ "code": "nonce_too_low" }`). Otherwise, `data` contains hex-encoded revert data,
+    /// which is empty for errors other than reverts.
+    TxErrorCodes,
+}
+
+impl ApiCapability {
+    /// All known capabilities.
+    pub const ALL: [Self; 3] = [
+        Self::BlobCommitments,
+        Self::ReceiptGasRefunded,
+        Self::TxErrorCodes,
+    ];
+
+    /// Maximum API level supported by the server. Clients declaring a greater level are treated
+    /// as declaring this level.
+    pub const MAX_CLIENT_LEVEL: u32 = 1;
+
+    /// Returns the minimum protocol version for which the capability is enabled, or `None`
+    /// if the capability doesn't depend on the protocol version.
+    pub fn min_protocol_version(self) -> Option<ProtocolVersionId> {
+        match self {
+            Self::BlobCommitments => Some(ProtocolVersionId::Version21),
+            Self::ReceiptGasRefunded | Self::TxErrorCodes => None,
+        }
+    }
+
+    /// Returns the minimum API level the client needs to declare for the capability to be enabled.
+    pub fn min_client_level(self) -> u32 {
+        match self {
+            Self::BlobCommitments => 0,
+            Self::ReceiptGasRefunded | Self::TxErrorCodes => 1,
+        }
+    }
+
+    /// Checks whether the capability is enabled for the specified protocol version and client API level.
+    pub fn is_enabled(self, protocol_version: ProtocolVersionId, client_level: u32) -> bool {
+        let protocol_version_matches = self
+            .min_protocol_version()
+            .map_or(true, |min_version| protocol_version >= min_version);
+        protocol_version_matches && client_level >= self.min_client_level()
+    }
+}
+
+/// Information about a single [`ApiCapability`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiCapabilityInfo {
+    pub capability: ApiCapability,
+    pub min_protocol_version: Option<u16>,
+    pub min_client_level: u32,
+    /// Whether the capability is enabled for the current protocol version and the declared client API level.
+    pub enabled: bool,
+}
+
+/// Capabilities of the API server returned by `zks_getApiCapabilities`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiCapabilities {
+    /// Latest protocol version known to the node.
+    pub protocol_version: u16,
+    /// API level declared by the client (after capping by `max_client_level`).
+    pub client_level: u32,
+    /// Maximum API level supported by the server.
+    pub max_client_level: u32,
+    pub capabilities: Vec<ApiCapabilityInfo>,
+}
+
+/// Fee inputs of a single miniblock returned by `zks_gasPriceHistory`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasPriceHistoryEntry {
+    pub number: MiniblockNumber,
+    /// Number of the L1 batch the miniblock belongs to; `None` if the batch is not sealed yet.
+    pub l1_batch_number: Option<L1BatchNumber>,
+    pub timestamp: u64,
+    pub base_fee_per_gas: U256,
+    pub l1_gas_price: U64,
+    pub fair_l2_gas_price: U64,
+    /// Fair price for a byte of pubdata. For miniblocks using the L1-pegged fee model,
+    /// this price is derived from the L1 gas price.
+    pub fair_pubdata_price: U64,
+}
+
+/// System context values observed by the VM when executing a miniblock, returned by `zks_getSystemContext`.
+/// The values are restored from fee inputs and other block data persisted when the miniblock was sealed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemContext {
+    pub chain_id: U64,
+    /// Number of the L1 batch the miniblock belongs to. If the batch is not sealed yet,
+    /// this is the number of the pending batch.
+    pub l1_batch_number: L1BatchNumber,
+    pub l1_batch_sealed: bool,
+    pub l1_batch_timestamp: u64,
+    pub miniblock_number: MiniblockNumber,
+    pub miniblock_timestamp: u64,
+    /// Hash of the previous miniblock; `None` for the genesis miniblock.
+    pub prev_miniblock_hash: Option<H256>,
+    pub max_virtual_blocks_to_create: u32,
+    /// Value of `block.coinbase` observed by contracts, i.e. the bootloader formal address.
+    pub coinbase: Address,
+    pub protocol_version: Option<ProtocolVersionId>,
+    pub base_fee_per_gas: U256,
+    /// Gas per pubdata byte derived from the fee input for the miniblock protocol version.
+    pub gas_per_pubdata: U256,
+    pub gas_per_pubdata_limit: U64,
+    pub l1_gas_price: U64,
+    pub fair_l2_gas_price: U64,
+    pub fair_pubdata_price: U64,
+}
+
+/// Bridge accounting after a certain L1 batch returned by `zks_getSolvencyReport`.
+/// All values are cumulative and are denominated in wei (or the smallest token units for ERC-20 tokens).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolvencyReport {
+    pub l1_batch_number: L1BatchNumber,
+    /// Total value bridged to L2 by L1 transactions.
+    pub total_deposited: U256,
+    /// Total value of withdrawals initiated on L2.
+    pub total_withdrawn: U256,
+    /// Total value of withdrawals in L1 batches executed on L1, i.e. withdrawals that can be finalized on L1.
+    pub finalized_withdrawn: U256,
+    /// L2 total supply expected based on the supply when reporting started, deposits and withdrawals.
+    pub expected_supply: U256,
+    pub l2_total_supply: U256,
+    /// Whether the actual L2 total supply matches the expected one.
+    pub is_balanced: bool,
+    /// Total base token balance of the L1 contracts holding bridged funds, observed after the L1 batch was sealed.
+    /// `None` if the balance wasn't checked for this L1 batch.
+    pub l1_bridge_balance: Option<U256>,
+    /// Whether the L1 bridge balance covers the value bridged to L2. `None` if the balance wasn't checked.
+    pub is_solvent: Option<bool>,
+    /// Accounting for ERC-20 tokens bridged via the L2 ERC-20 bridge.
+    pub tokens: Vec<TokenSolvencyReport>,
+}
+
+/// ERC-20 token bridge accounting, part of [`SolvencyReport`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenSolvencyReport {
+    /// L1 address of the token. `None` if the token is not known to the node.
+    pub l1_address: Option<Address>,
+    pub l2_address: Address,
+    pub total_deposited: U256,
+    pub total_withdrawn: U256,
+    /// Token value outstanding on L2, i.e. the value that can be withdrawn to L1.
+    pub l2_outstanding: U256,
+    /// Total token balance of the L1 contracts holding bridged funds. `None` if the balance wasn't checked
+    /// for this L1 batch.
+    pub l1_bridge_balance: Option<U256>,
+    /// Whether the L1 bridge balance covers the value outstanding on L2. `None` if the balance wasn't checked.
+    pub is_solvent: Option<bool>,
+}
+
+/// Utilization of blobs by recent commit transactions returned by `zks_getBlobUtilizationReport`.
+///
+/// The report doesn't project savings from packing more L1 batches into a commit transaction. Such packing is
+/// declined: the executor contracts of the supported protocol versions reject commits of several L1 batches
+/// publishing pubdata in blobs, so the commit aggregation is left to the existing publish criteria.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobUtilizationReport {
+    pub commit_tx_count: u32,
+    pub l1_batch_count: u32,
+    pub blob_count: u32,
+    /// Total size of the published pubdata in bytes.
+    pub pubdata_bytes: u64,
+    /// Total size of blob space not filled with pubdata in bytes.
+    pub wasted_bytes: u64,
+    /// Share of blob space filled with pubdata, from 0 to 1.
+    pub utilization: f64,
+    /// Per-transaction utilization, newest transactions first.
+    pub commits: Vec<CommitBlobUtilization>,
+}
+
+/// Utilization of blobs by a single commit transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitBlobUtilization {
+    pub first_l1_batch_number: L1BatchNumber,
+    pub last_l1_batch_number: L1BatchNumber,
+    pub blob_count: u32,
+    pub pubdata_bytes: u64,
+    pub wasted_bytes: u64,
+    /// DA cost per committed L1 batch in wei; `None` if the L1 costs of the transaction are not known yet.
+    pub fee_per_l1_batch: Option<U256>,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TransactionReceipt {
     /// Transaction hash.
@@ -258,6 +551,14 @@ pub struct TransactionReceipt {
     /// Effective gas price
     #[serde(rename = "effectiveGasPrice")]
     pub effective_gas_price: Option<U256>,
+    /// Gas refunded to the transaction initiator. Only returned to clients declaring
+    /// the [`ApiCapability::ReceiptGasRefunded`] capability level.
+    #[serde(
+        rename = "gasRefunded",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas_refunded: Option<U256>,
 }
 
 /// The block type returned from RPC calls.
@@ -530,6 +831,8 @@ pub enum TransactionStatus {
     Included,
     Verified,
     Failed,
+    /// Transaction was dropped from the mempool because its deadline has passed.
+    Expired,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -544,6 +847,103 @@ pub struct TransactionDetails {
     pub eth_commit_tx_hash: Option<H256>,
     pub eth_prove_tx_hash: Option<H256>,
     pub eth_execute_tx_hash: Option<H256>,
+    /// Decoded revert reason for failed transactions. Only set if the transaction reverted with data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<RevertReason>,
+    /// Inclusion deadline of the transaction specified on submission, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<ValidUntil>,
+}
+
+/// Revert reason of a failed transaction decoded from the data it reverted with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RevertReason {
+    /// `Error(string)` revert, e.g. produced by `require(condition, "message")`.
+    Error { message: String },
+    /// `Panic(uint256)` revert produced by the Solidity compiler for failed assertions, arithmetic overflows etc.
+    Panic {
+        code: U256,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+    /// Custom error decoded using an ABI supplied by the node operator.
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        signature: String,
+        /// Decoded error arguments formatted as strings.
+        args: Vec<String>,
+    },
+    /// Revert data that couldn't be decoded.
+    Unknown { data: Bytes },
+}
+
+impl RevertReason {
+    const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+    const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+    /// Decodes standard `Error(string)` and `Panic(uint256)` reverts. Other data is returned as [`Self::Unknown`].
+    pub fn decode(data: &[u8]) -> Self {
+        use crate::ethabi::{decode, ParamType, Token};
+
+        let unknown = || Self::Unknown {
+            data: Bytes(data.to_vec()),
+        };
+        if data.len() < 4 {
+            return unknown();
+        }
+        let (selector, encoded_args) = data.split_at(4);
+        if selector == Self::ERROR_SELECTOR {
+            match decode(&[ParamType::String], encoded_args).as_deref() {
+                Ok([Token::String(message)]) => Self::Error {
+                    message: message.clone(),
+                },
+                _ => unknown(),
+            }
+        } else if selector == Self::PANIC_SELECTOR {
+            match decode(&[ParamType::Uint(256)], encoded_args).as_deref() {
+                Ok([Token::Uint(code)]) => Self::Panic {
+                    code: *code,
+                    description: Self::panic_description(*code).map(str::to_owned),
+                },
+                _ => unknown(),
+            }
+        } else {
+            unknown()
+        }
+    }
+
+    /// Returns the selector of unknown revert data, if any.
+    pub fn unknown_selector(&self) -> Option<[u8; 4]> {
+        match self {
+            Self::Unknown { data } if data.0.len() >= 4 => {
+                let mut selector = [0_u8; 4];
+                selector.copy_from_slice(&data.0[..4]);
+                Some(selector)
+            }
+            _ => None,
+        }
+    }
+
+    /// See [the Solidity docs](https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require).
+    fn panic_description(code: U256) -> Option<&'static str> {
+        if code > U256::from(u8::MAX) {
+            return None;
+        }
+        Some(match code.as_u32() {
+            0x00 => "generic compiler panic",
+            0x01 => "assertion failed",
+            0x11 => "arithmetic overflow or underflow",
+            0x12 => "division or modulo by zero",
+            0x21 => "invalid enum value",
+            0x22 => "invalid storage byte array encoding",
+            0x31 => "pop() on an empty array",
+            0x32 => "array index out of bounds",
+            0x41 => "too much memory allocated",
+            0x51 => "call to a zero-initialized internal function",
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -554,6 +954,382 @@ pub struct GetLogsFilter {
     pub topics: Vec<(u32, Vec<H256>)>,
 }
 
+impl GetLogsFilter {
+    /// Checks whether the log matches this filter. Unlike the Postgres query, this method can be used
+    /// for logs that are not stored in Postgres (e.g., ones moved to cold storage).
+    pub fn matches(&self, log: &Log) -> bool {
+        let Some(block_number) = log.block_number else {
+            return false;
+        };
+        let block_number = block_number.as_u32();
+        if block_number < self.from_block.0 || block_number > self.to_block.0 {
+            return false;
+        }
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        // Topic indices are 1-based, similar to the topic columns in Postgres.
+        self.topics.iter().all(|(topic_index, expected_topics)| {
+            let actual_topic = log.topics.get(*topic_index as usize - 1);
+            actual_topic.map_or(false, |topic| expected_topics.contains(topic))
+        })
+    }
+}
+
+/// Index strategy used to retrieve logs from the storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogsQueryStrategy {
+    /// Scan all logs in the requested block range.
+    BlockRange,
+    /// Use the index on log addresses (restricted to the requested block range).
+    AddressIndex,
+    /// Use the index on the specified topic (1-based).
+    TopicIndex(u32),
+}
+
+/// Estimated cost of a logs query with a certain strategy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsQueryEstimate {
+    pub strategy: LogsQueryStrategy,
+    /// Estimated number of rows scanned by the query.
+    pub estimated_rows: f64,
+    /// Actual query latency in milliseconds. Only set in the benchmark mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+    /// Actual number of returned logs. Only set in the benchmark mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_count: Option<usize>,
+}
+
+/// Explanation of how logs matching a filter are retrieved returned by `debug_explainLogsQuery`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsQueryExplanation {
+    /// Whether `eth_getLogs` queries use the strategy chosen by the planner. If not, the strategy is always
+    /// left to the Postgres planner, and the remaining fields show what the planner would choose.
+    pub planner_enabled: bool,
+    /// Chosen strategy. `None` if the table statistics are not available, in which case the strategy
+    /// is left to the Postgres planner.
+    pub strategy: Option<LogsQueryStrategy>,
+    /// Estimates for all applicable strategies.
+    pub estimates: Vec<LogsQueryEstimate>,
+    /// `WHERE` clause of the query with the chosen strategy.
+    pub where_clause: String,
+}
+
+/// Role of an address in a transaction used to filter the address history returned by
+/// `zks_getTransactionsByAddress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionAddressRole {
+    /// Address is the transaction initiator (i.e., the `from` field).
+    Initiator,
+    /// Address is the contract called by the transaction (i.e., the `to` field).
+    ContractCalled,
+    /// Address is the sender or the recipient of an ERC-20 `Transfer` event emitted by the transaction.
+    TokenTransferred,
+}
+
+/// Position in the address history. Transactions are returned from newest to oldest; the cursor
+/// is exclusive, i.e. the transaction it points to is not included in the returned page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsByAddressCursor {
+    pub block_number: MiniblockNumber,
+    pub transaction_index: u32,
+}
+
+/// Page of transactions returned by `zks_getTransactionsByAddress`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsByAddressPage {
+    pub transactions: Vec<Transaction>,
+    /// Cursor to fetch the next page with. `None` if there are no more transactions.
+    pub next_cursor: Option<TransactionsByAddressCursor>,
+}
+
+/// Additional parameters of a priority operation simulated by `zks_simulateL1Transaction`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1TransactionSimulationOptions {
+    /// Whether the operation is initiated by an L1 contract, in which case the L1 mailbox aliases the sender address.
+    #[serde(default)]
+    pub alias_sender: bool,
+    /// Value minted on L2 for the operation. Defaults to `value + maxFeePerGas * gasLimit`.
+    pub to_mint: Option<U256>,
+    /// Recipient of the refund for unused gas. Defaults to the L2 sender of the operation.
+    pub refund_recipient: Option<Address>,
+}
+
+/// Result of a priority operation simulated by `zks_simulateL1Transaction`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1TransactionSimulation {
+    /// L2 sender of the operation, i.e. the aliased L1 sender if aliasing was requested.
+    pub sender: Address,
+    pub refund_recipient: Address,
+    /// Value minted on L2 for the operation.
+    pub to_mint: U256,
+    /// L2 gas limit the operation was executed with.
+    pub gas_limit: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_per_pubdata_limit: U256,
+    pub success: bool,
+    /// Data returned by the operation, or the revert data if it has failed.
+    pub output: Bytes,
+    pub revert_reason: Option<String>,
+    pub gas_used: U256,
+    /// L2 gas limit required for the operation to succeed; the same value is returned by `zks_estimateGasL1ToL2`.
+    /// `None` if the operation fails regardless of the gas limit.
+    pub required_gas_limit: Option<U256>,
+}
+
+/// Deduplicated change of a single storage slot in an L1 batch, as published in the batch pubdata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSlotDiff {
+    pub address: Address,
+    pub key: H256,
+    /// Hashed key of the slot used in the Merkle tree.
+    pub derived_key: H256,
+    /// Enumeration index of the slot in the Merkle tree. Only present for repeated writes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enumeration_index: Option<u64>,
+    /// Slot value before the batch. Only present if values were requested; always zero for initial writes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_value: Option<H256>,
+    /// Slot value after the batch. Only present if values were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_value: Option<H256>,
+}
+
+/// State diffs of an L1 batch returned by `zks_getBatchStateDiffs`. Both initial and repeated writes
+/// are ordered in the same way as in the batch pubdata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchStateDiffs {
+    pub number: L1BatchNumber,
+    pub initial_writes: Vec<StorageSlotDiff>,
+    pub repeated_writes: Vec<StorageSlotDiff>,
+}
+
+/// Public inputs of the proof for an L1 batch returned by `zks_getL1BatchPublicInputs`. Allows to validate
+/// batch proofs without re-deriving their inputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchPublicInputs {
+    pub number: L1BatchNumber,
+    /// Root hash of the state tree after the previous L1 batch.
+    pub prev_root_hash: H256,
+    /// Root hash of the state tree after this L1 batch.
+    pub root_hash: H256,
+    pub prev_commitment: H256,
+    pub commitment: H256,
+    /// Rolling hash of priority operations processed in the batch.
+    pub priority_operations_hash: H256,
+    /// Commitments to the pubdata blobs. Only present for batches produced with protocol version 1.4.2 or newer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_commitments: Option<Vec<BlobCommitment>>,
+    /// Public input of the proof as computed by the verifier contract, i.e. the hash of the previous
+    /// and current commitments shifted right by 32 bits.
+    pub public_input: U256,
+}
+
+/// `StoredBatchInfo` of a committed L1 batch returned by `zks_getL1BatchStoredInfo`. Allows contracts and
+/// off-chain verifiers to recompute the stored batch hash without reimplementing the encoding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchStoredInfo {
+    pub number: L1BatchNumber,
+    /// ABI-encoded `StoredBatchInfo` of the batch, exactly as stored by the chain contract on L1.
+    pub stored_batch_info: Bytes,
+    /// `keccak256` hash of `storedBatchInfo`. Equals `storedBatchHash(number)` of the chain contract on L1.
+    pub stored_batch_hash: H256,
+    /// Hash of the L1 transaction committing the batch.
+    pub commit_tx_hash: H256,
+}
+
+/// Information about a pubdata blob published by the commit transaction of an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommittedBlobInfo {
+    /// Versioned hash of the blob as referenced by the commit transaction.
+    pub versioned_hash: H256,
+    /// KZG commitment to the blob (48 bytes).
+    pub kzg_commitment: Bytes,
+}
+
+/// Information on how an L1 batch was committed on L1 returned by `zks_getL1BatchCommitmentDetails`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchCommitmentDetails {
+    pub number: L1BatchNumber,
+    /// DA mode used to publish the batch pubdata. `None` if the mode is not known to the node.
+    pub pubdata_da: Option<PubdataDA>,
+    /// ABI-encoded `CommitBatchInfo` of the batch, exactly as sent to L1 in the commit transaction. Only available
+    /// on nodes that have sent the commit transaction.
+    pub commit_batch_info: Option<Bytes>,
+    /// Blobs published for the batch. Empty if pubdata is not published in blobs, or if `commitBatchInfo`
+    /// is not available.
+    pub blobs: Vec<CommittedBlobInfo>,
+    pub commit_tx_hash: H256,
+    pub prove_tx_hash: Option<H256>,
+    pub execute_tx_hash: Option<H256>,
+}
+
+/// Actual L1 costs of an L1 transaction (commit, prove or execute) covering an L1 batch returned by
+/// `zks_getL1BatchL1Costs`. A single L1 transaction may cover several L1 batches, so costs are not amortized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchL1Cost {
+    /// Type of the L1 transaction: `CommitBlocks`, `PublishProofBlocksOnchain` or `ExecuteBlocks`.
+    pub tx_type: String,
+    pub tx_hash: H256,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    pub l1_block_number: U64,
+    /// Total fee paid for the transaction in wei.
+    pub total_fee: U256,
+}
+
+/// Arrival information of a transaction included into a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionOrderingInfo {
+    pub hash: H256,
+    pub index_in_block: u32,
+    /// Sequence number assigned to the transaction when it was accepted by the node. `None` for L1 transactions
+    /// and transactions accepted before sequence numbers were introduced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arrival_seq: Option<u64>,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Information returned by `zks_getBlockOrderingInfo` that allows to audit fair (first-come-first-served)
+/// ordering of transactions in a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOrderingInfo {
+    pub number: MiniblockNumber,
+    /// Transactions in the block ordered by their index in the block.
+    pub transactions: Vec<TransactionOrderingInfo>,
+    /// Maximum number of positions in the arrival sequence by which a transaction was preceded
+    /// by a later-arriving one in the block. Zero if transactions are included strictly in the arrival order.
+    pub max_reordering: u64,
+}
+
+impl BlockOrderingInfo {
+    pub fn new(number: MiniblockNumber, transactions: Vec<TransactionOrderingInfo>) -> Self {
+        let mut max_arrival_seq = None::<u64>;
+        let mut max_reordering = 0;
+        for arrival_seq in transactions.iter().filter_map(|tx| tx.arrival_seq) {
+            if let Some(max_seq) = max_arrival_seq {
+                max_reordering = max_reordering.max(max_seq.saturating_sub(arrival_seq));
+            }
+            max_arrival_seq = Some(max_arrival_seq.map_or(arrival_seq, |seq| seq.max(arrival_seq)));
+        }
+        Self {
+            number,
+            transactions,
+            max_reordering,
+        }
+    }
+}
+
+/// Header fields from which the hash of a block is derived, returned by `zks_getBlockHashPreimage`.
+/// Allows to verify block hashes (and thus the chain of blocks via parent hashes) without trusting the API server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHashPreimage {
+    pub number: MiniblockNumber,
+    pub hash: H256,
+    pub hash_scheme: MiniblockHashScheme,
+    pub timestamp: u64,
+    pub parent_hash: H256,
+    /// Hashes of transactions in the block in the order of their execution.
+    pub transactions: Vec<H256>,
+    /// Rolling hash of `transactions`.
+    pub txs_rolling_hash: H256,
+}
+
+/// Header fields of a block used to link the block hash to the hash of the previous block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHashLink {
+    pub number: MiniblockNumber,
+    pub timestamp: u64,
+    pub txs_rolling_hash: H256,
+    pub hash: H256,
+}
+
+/// Proof linking a block to the commitment of its L1 batch, returned by `zks_getBlockCommitmentProof`.
+///
+/// A light client can verify the proof as follows:
+///
+/// 1. Starting from the block hash, derive hashes of `subsequentBlocks` (see [`MiniblockHashScheme::Header`]).
+///    The last derived hash is the hash of the last block in the batch.
+/// 2. Check that `systemContextProof` proves the `SystemContext` storage slots holding the packed number and timestamp
+///    of the last block in the batch, its transactions rolling hash, and the hash of the preceding block
+///    against `rootHash`, and that the last block hash derived from these slots matches the one from step 1.
+/// 3. Check that `commitment` equals `keccak256(passThroughDataHash ++ metaParametersHash ++ auxDataHash)`, where
+///    `passThroughDataHash = keccak256(u64_be(rollupLastLeafIndex) ++ rootHash ++ u64_be(0) ++ bytes32(0))`,
+///    and that it matches the commitment of the batch stored on L1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockCommitmentProof {
+    pub number: MiniblockNumber,
+    pub hash: H256,
+    pub l1_batch_number: L1BatchNumber,
+    /// Blocks following the block up to and including the last block in the L1 batch.
+    pub subsequent_blocks: Vec<BlockHashLink>,
+    /// Proofs for `SystemContext` storage slots against `root_hash`.
+    pub system_context_proof: Vec<StorageProof>,
+    /// Root hash of the state tree after the L1 batch.
+    pub root_hash: H256,
+    pub rollup_last_leaf_index: u64,
+    pub meta_parameters_hash: H256,
+    pub aux_data_hash: H256,
+    pub commitment: H256,
+}
+
+/// Proof of a storage slot value written in an L1 batch executed on L1, returned by `zks_getStorageExitProof`. Allows
+/// bridges to verify L2 state (e.g., for exits) without access to the full state tree. The proof only uses keccak256,
+/// so it can be verified on-chain.
+///
+/// The proof can be verified as follows:
+///
+/// 1. Check that the state diff at `stateDiffIndex` in `stateDiffs` has the expected address, key and final value.
+///    State diffs are packed as in the batch commitment, i.e., each diff takes 272 bytes.
+/// 2. Check that `keccak256(stateDiffs)` equals the second 32-byte word of `auxOutput`.
+/// 3. Check that `keccak256(passThroughDataHash ++ metaParametersHash ++ keccak256(auxOutput))` equals
+///    the `commitment` field of `storedBatchInfo`.
+/// 4. Check that `keccak256(storedBatchInfo)` equals `storedBatchHash(l1BatchNumber)` of the chain contract on L1.
+///
+/// `calldata` packages all the above data for on-chain verification; it is the ABI encoding of
+/// `(StoredBatchInfo batch, bytes32 passThroughDataHash, bytes32 metaParametersHash, bytes auxOutput,
+/// bytes stateDiffs, uint256 stateDiffIndex)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageExitProof {
+    pub address: Address,
+    pub key: H256,
+    /// Value of the slot after the batch.
+    pub value: H256,
+    pub l1_batch_number: L1BatchNumber,
+    /// Index of the slot diff among state diffs of the batch.
+    pub state_diff_index: u32,
+    /// ABI-encoded `StoredBatchInfo` of the batch, as stored by the chain contract on L1.
+    pub stored_batch_info: Bytes,
+    pub commitment: H256,
+    /// Hash of the L1 transaction committing the batch.
+    pub commit_tx_hash: H256,
+    /// Hash of the L1 transaction executing the batch.
+    pub execute_tx_hash: H256,
+    pub calldata: Bytes,
+}
+
 /// Result of debugging block
 /// For some reasons geth returns result as {result: DebugCall}
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -666,6 +1442,9 @@ pub struct BlockDetailsBase {
     pub l1_gas_price: u64,
     pub l2_fair_gas_price: u64,
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
+    /// Seal reason and capacity statistics. `None` for blocks sealed before this information was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seal_info: Option<SealInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,380 @@
+//! Signing of API responses returned by `zks_getSigned*` methods.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{Address, L1BatchNumber, L2ChainId, MiniblockNumber, H256, U256};
+use zksync_contracts::BaseSystemContractsHashes;
+
+use super::{BlockDetailsBase, BlockStatus, L1BatchDetails, L2ToL1LogProof, Proof, StorageProof};
+use crate::{
+    block::SealInfo,
+    ethabi::{self, Token},
+    tx::primitives::ecdsa_signature::Error as ParityCryptoError,
+    web3::signing::keccak256,
+    PackedEthSignature,
+};
+
+/// Canonical ABI representation of a value covered by a [`SignedResponse`] signature. Unlike the JSON
+/// representation, it doesn't depend on serialization details (field order, number formatting etc.), so it can be
+/// reproduced by consumers in any language.
+///
+/// Encoding rules:
+///
+/// - Integers are encoded as `uint256`, hashes as `bytes32`, addresses as `address`, strings as `string`.
+/// - `Option<T>` is encoded as `(bool is_some, T value)`; `None` uses the zero value of `T`.
+/// - Sequences are encoded as dynamic arrays; structs are encoded as tuples of their fields in declaration order.
+/// - Timestamps are encoded as `(int256 seconds, uint256 subsec_nanos)` since the Unix epoch.
+/// - Floating-point numbers (capacity percentages) are encoded as `uint256` fixed-point values with 6 decimal places,
+///   so that the encoding is stable under JSON round-trips.
+pub trait SigningToken {
+    fn signing_token(&self) -> Token;
+
+    /// Token used in place of `None` for `Option<Self>`.
+    fn zero_token() -> Token
+    where
+        Self: Sized;
+}
+
+impl SigningToken for u64 {
+    fn signing_token(&self) -> Token {
+        Token::Uint((*self).into())
+    }
+
+    fn zero_token() -> Token {
+        Token::Uint(U256::zero())
+    }
+}
+
+impl SigningToken for u32 {
+    fn signing_token(&self) -> Token {
+        Token::Uint((*self).into())
+    }
+
+    fn zero_token() -> Token {
+        Token::Uint(U256::zero())
+    }
+}
+
+impl SigningToken for usize {
+    fn signing_token(&self) -> Token {
+        Token::Uint((*self).into())
+    }
+
+    fn zero_token() -> Token {
+        Token::Uint(U256::zero())
+    }
+}
+
+impl SigningToken for f64 {
+    fn signing_token(&self) -> Token {
+        // Float-to-int casts saturate, so negative and NaN values are encoded as 0.
+        let fixed_point = (self * 1_000_000.0).round() as u64;
+        Token::Uint(fixed_point.into())
+    }
+
+    fn zero_token() -> Token {
+        Token::Uint(U256::zero())
+    }
+}
+
+impl SigningToken for H256 {
+    fn signing_token(&self) -> Token {
+        Token::FixedBytes(self.as_bytes().to_vec())
+    }
+
+    fn zero_token() -> Token {
+        Token::FixedBytes(vec![0; 32])
+    }
+}
+
+impl SigningToken for Address {
+    fn signing_token(&self) -> Token {
+        Token::Address(*self)
+    }
+
+    fn zero_token() -> Token {
+        Token::Address(Address::zero())
+    }
+}
+
+impl SigningToken for String {
+    fn signing_token(&self) -> Token {
+        Token::String(self.clone())
+    }
+
+    fn zero_token() -> Token {
+        Token::String(String::new())
+    }
+}
+
+impl SigningToken for L1BatchNumber {
+    fn signing_token(&self) -> Token {
+        self.0.signing_token()
+    }
+
+    fn zero_token() -> Token {
+        u32::zero_token()
+    }
+}
+
+impl SigningToken for MiniblockNumber {
+    fn signing_token(&self) -> Token {
+        self.0.signing_token()
+    }
+
+    fn zero_token() -> Token {
+        u32::zero_token()
+    }
+}
+
+impl SigningToken for DateTime<Utc> {
+    fn signing_token(&self) -> Token {
+        let seconds = self.timestamp();
+        let abs_seconds = U256::from(seconds.unsigned_abs());
+        let seconds = if seconds < 0 {
+            // Two's complement representation of a negative `int256`.
+            (!abs_seconds).overflowing_add(U256::one()).0
+        } else {
+            abs_seconds
+        };
+        Token::Tuple(vec![
+            Token::Int(seconds),
+            self.timestamp_subsec_nanos().signing_token(),
+        ])
+    }
+
+    fn zero_token() -> Token {
+        Token::Tuple(vec![Token::Int(U256::zero()), u32::zero_token()])
+    }
+}
+
+impl<T: SigningToken> SigningToken for Option<T> {
+    fn signing_token(&self) -> Token {
+        match self {
+            Some(value) => Token::Tuple(vec![Token::Bool(true), value.signing_token()]),
+            None => Self::zero_token(),
+        }
+    }
+
+    fn zero_token() -> Token {
+        Token::Tuple(vec![Token::Bool(false), T::zero_token()])
+    }
+}
+
+impl<T: SigningToken> SigningToken for Vec<T> {
+    fn signing_token(&self) -> Token {
+        Token::Array(self.iter().map(T::signing_token).collect())
+    }
+
+    fn zero_token() -> Token {
+        Token::Array(vec![])
+    }
+}
+
+/// Implements [`SigningToken`] for a struct by encoding the listed fields as a tuple.
+macro_rules! impl_signing_token_for_struct {
+    ($ty:ty { $($field:ident),+ $(,)? }) => {
+        impl SigningToken for $ty {
+            fn signing_token(&self) -> Token {
+                Token::Tuple(vec![$(self.$field.signing_token()),+])
+            }
+
+            fn zero_token() -> Token {
+                unreachable!(concat!("`", stringify!($ty), "` is never encoded as an optional value"))
+            }
+        }
+    };
+}
+
+impl SigningToken for BlockStatus {
+    fn signing_token(&self) -> Token {
+        let discriminant: u32 = match self {
+            Self::Sealed => 0,
+            Self::Verified => 1,
+        };
+        discriminant.signing_token()
+    }
+
+    fn zero_token() -> Token {
+        u32::zero_token()
+    }
+}
+
+impl SigningToken for BaseSystemContractsHashes {
+    fn signing_token(&self) -> Token {
+        Token::Tuple(vec![
+            self.bootloader.signing_token(),
+            self.default_aa.signing_token(),
+        ])
+    }
+
+    fn zero_token() -> Token {
+        Token::Tuple(vec![H256::zero_token(), H256::zero_token()])
+    }
+}
+
+impl SigningToken for SealInfo {
+    fn signing_token(&self) -> Token {
+        let capacity_filled = self
+            .capacity_filled
+            .iter()
+            .map(|(criterion, filled)| {
+                Token::Tuple(vec![criterion.signing_token(), filled.signing_token()])
+            })
+            .collect();
+        Token::Tuple(vec![
+            self.reason.signing_token(),
+            Token::Array(capacity_filled),
+        ])
+    }
+
+    fn zero_token() -> Token {
+        Token::Tuple(vec![Option::<String>::zero_token(), Token::Array(vec![])])
+    }
+}
+
+impl_signing_token_for_struct!(BlockDetailsBase {
+    timestamp,
+    l1_tx_count,
+    l2_tx_count,
+    root_hash,
+    status,
+    commit_tx_hash,
+    committed_at,
+    prove_tx_hash,
+    proven_at,
+    execute_tx_hash,
+    executed_at,
+    l1_gas_price,
+    l2_fair_gas_price,
+    base_system_contracts_hashes,
+    seal_info,
+});
+impl_signing_token_for_struct!(L1BatchDetails { number, base });
+impl_signing_token_for_struct!(L2ToL1LogProof { proof, id, root });
+impl_signing_token_for_struct!(StorageProof {
+    key,
+    proof,
+    value,
+    index
+});
+impl_signing_token_for_struct!(Proof {
+    address,
+    storage_proof
+});
+
+/// Response of a critical API method (e.g., a Merkle proof) signed by the node operator, so that consumers
+/// can verify provenance of the response when fetching it through untrusted intermediaries.
+///
+/// The signature covers the digest returned by [`Self::digest()`], which binds the response to the method,
+/// the chain and the request params. The signature is produced without an EIP-191 prefix.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedResponse<T> {
+    pub response: T,
+    pub chain_id: L2ChainId,
+    pub signer: Address,
+    pub signature: PackedEthSignature,
+}
+
+impl<T: SigningToken> SignedResponse<T> {
+    /// Computes the signed digest for a response of the specified method:
+    /// `keccak256(abi.encode(method, chain_id, params, response))`, where `method` is the full method name
+    /// (e.g., `zks_getSignedL1BatchDetails`), `params` is the tuple of request params and `response` is
+    /// the canonical encoding of the response, both encoded according to [`SigningToken`] rules.
+    pub fn digest(method: &str, chain_id: L2ChainId, params: &[Token], response: &T) -> H256 {
+        let encoded = ethabi::encode(&[
+            Token::String(method.to_owned()),
+            chain_id.as_u64().signing_token(),
+            Token::Tuple(params.to_vec()),
+            response.signing_token(),
+        ]);
+        H256(keccak256(&encoded))
+    }
+
+    /// Signs a response of the specified method called with the specified params.
+    pub fn sign(
+        method: &str,
+        chain_id: L2ChainId,
+        params: &[Token],
+        response: T,
+        private_key: &H256,
+    ) -> Result<Self, ParityCryptoError> {
+        let digest = Self::digest(method, chain_id, params, &response);
+        let signature = PackedEthSignature::sign_raw(private_key, &digest)?;
+        Ok(Self {
+            response,
+            chain_id,
+            signer: PackedEthSignature::address_from_private_key(private_key)?,
+            signature,
+        })
+    }
+
+    /// Checks that the response of the specified method called with the specified params is signed
+    /// by the declared signer.
+    pub fn verify(&self, method: &str, params: &[Token]) -> Result<bool, ParityCryptoError> {
+        let digest = Self::digest(method, self.chain_id, params, &self.response);
+        let recovered_signer = self.signature.signature_recover_signer(&digest)?;
+        Ok(recovered_signer == self.signer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn signing_tokens_for_options_have_fixed_shape() {
+        let some_hash = Some(H256::repeat_byte(1)).signing_token();
+        let none_hash = Option::<H256>::None.signing_token();
+        let (Token::Tuple(some_hash), Token::Tuple(none_hash)) = (some_hash, none_hash) else {
+            panic!("unexpected encoding of options");
+        };
+        assert_eq!(some_hash[0], Token::Bool(true));
+        assert_eq!(
+            none_hash,
+            [Token::Bool(false), Token::FixedBytes(vec![0; 32])]
+        );
+    }
+
+    #[test]
+    fn negative_timestamps_are_encoded_in_twos_complement() {
+        let timestamp = Utc.timestamp_opt(-1, 5).unwrap();
+        assert_eq!(
+            timestamp.signing_token(),
+            Token::Tuple(vec![Token::Int(U256::MAX), Token::Uint(5.into())])
+        );
+    }
+
+    #[test]
+    fn signed_response_is_bound_to_params() {
+        let private_key = H256::repeat_byte(0x11);
+        let proof = L2ToL1LogProof {
+            proof: vec![H256::repeat_byte(2)],
+            id: 3,
+            root: H256::repeat_byte(4),
+        };
+        let method = "zks_getSignedL2ToL1LogProof";
+        let params = [
+            H256::repeat_byte(5).signing_token(),
+            Some(0_usize).signing_token(),
+        ];
+        let signed =
+            SignedResponse::sign(method, L2ChainId::default(), &params, proof, &private_key)
+                .unwrap();
+        assert!(signed.verify(method, &params).unwrap());
+
+        let other_params = [
+            H256::repeat_byte(5).signing_token(),
+            Some(1_usize).signing_token(),
+        ];
+        assert!(!signed.verify(method, &other_params).unwrap());
+        assert!(!signed.verify("zks_getL2ToL1LogProof", &params).unwrap());
+
+        let mut tampered = signed;
+        tampered.response.id = 4;
+        assert!(!tampered.verify(method, &params).unwrap());
+    }
+}
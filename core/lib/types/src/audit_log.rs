@@ -0,0 +1,76 @@
+//! Types related to the audit log of privileged operations.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Privileged action recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Revert of L1 batches in the node storage or on L1.
+    BlockRevert,
+    /// Removal of failed L1 transactions sent by the operator.
+    ClearFailedL1Transactions,
+    /// Runtime update of the node configuration.
+    ConfigReload,
+    /// Call of an admin RPC method.
+    AdminRpcCall,
+    /// Manual requeue of a failed or stuck job (e.g., a prover or witness generator job).
+    JobRequeue,
+    /// Submission of a recurring system transaction scheduled by the operator.
+    ScheduledTxSubmission,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BlockRevert => "block_revert",
+            Self::ClearFailedL1Transactions => "clear_failed_l1_transactions",
+            Self::ConfigReload => "config_reload",
+            Self::AdminRpcCall => "admin_rpc_call",
+            Self::JobRequeue => "job_requeue",
+            Self::ScheduledTxSubmission => "scheduled_tx_submission",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block_revert" => Ok(Self::BlockRevert),
+            "clear_failed_l1_transactions" => Ok(Self::ClearFailedL1Transactions),
+            "config_reload" => Ok(Self::ConfigReload),
+            "admin_rpc_call" => Ok(Self::AdminRpcCall),
+            "job_requeue" => Ok(Self::JobRequeue),
+            "scheduled_tx_submission" => Ok(Self::ScheduledTxSubmission),
+            _ => Err(
+                "Incorrect audit action; expected one of `block_revert`, `clear_failed_l1_transactions`, \
+                 `config_reload`, `admin_rpc_call`, `job_requeue`, `scheduled_tx_submission`",
+            ),
+        }
+    }
+}
+
+/// Entry of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    /// Sequential ID of the entry.
+    pub id: u64,
+    pub action: AuditAction,
+    /// Identity of the actor that has performed the action, e.g. an OS user for CLI tools
+    /// or the signer address for remote config updates.
+    pub actor: String,
+    /// Action-specific parameters.
+    pub params: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
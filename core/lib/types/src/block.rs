@@ -1,4 +1,4 @@
-use std::{fmt, ops};
+use std::{collections::BTreeMap, fmt, ops};
 
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::{Address, H2048, H256, U256};
@@ -76,6 +76,9 @@ pub struct MiniblockHeader {
     pub protocol_version: Option<ProtocolVersionId>,
     /// The maximal number of virtual blocks to be created in the miniblock.
     pub virtual_blocks: u32,
+    /// Max amount of gas that can be used by transactions in the miniblock. `None` for miniblocks
+    /// produced without an enforced limit.
+    pub gas_limit: Option<u64>,
 }
 
 /// Data needed to execute a miniblock in the VM.
@@ -88,6 +91,19 @@ pub struct MiniblockExecutionData {
     pub txs: Vec<Transaction>,
 }
 
+/// Information on why an L1 batch or a miniblock was sealed and how much of its capacity was used.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SealInfo {
+    /// Name of the criterion that has triggered sealing (e.g., `slots` or `no_txs_timeout`). `None` if the reason
+    /// is not known to the node that has sealed the block (e.g., for blocks synced by the external node).
+    pub reason: Option<String>,
+    /// Percentage of the L1 batch capacity filled according to each capacity-limiting criterion, keyed
+    /// by the criterion name. For miniblocks, this is the capacity of the containing L1 batch filled as of sealing
+    /// the miniblock, plus the share of the miniblock gas limit used (if the limit is enforced).
+    pub capacity_filled: BTreeMap<String, f64>,
+}
+
 impl L1BatchHeader {
     pub fn new(
         number: L1BatchNumber,
@@ -176,6 +192,75 @@ impl ops::AddAssign for BlockGasCount {
     }
 }
 
+/// Scheme used to derive a miniblock hash from the miniblock header fields. The scheme is determined
+/// by the protocol version of the miniblock and must match the one used by the `SystemContext` system contract,
+/// which verifies miniblock hashes during execution (and returns them from `blockhash`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MiniblockHashScheme {
+    /// `keccak256(u32_be(number))`. Used for miniblocks before protocol version 13,
+    /// i.e., before miniblock hashes were stored on L2.
+    Legacy,
+    /// Header-based scheme used starting from protocol version 13:
+    ///
+    /// ```text
+    /// keccak256(u256_be(number) ++ u256_be(timestamp) ++ prev_miniblock_hash ++ txs_rolling_hash)
+    /// ```
+    ///
+    /// This is equivalent to `keccak256(abi.encode(number, timestamp, prevBlockHash, txsRollingHash))` in Solidity.
+    /// `txs_rolling_hash` is *the rolling hash* of miniblock transactions, calculated the following way:
+    ///
+    /// - If the miniblock has 0 transactions, then `txs_rolling_hash` is equal to `H256::zero()`.
+    /// - If the miniblock has i transactions, then `txs_rolling_hash` is equal to `H(H_{i-1}, H(tx_i))`, where
+    ///   `H_{i-1}` is the `txs_rolling_hash` of the first i-1 transactions.
+    Header,
+}
+
+impl MiniblockHashScheme {
+    /// Returns the scheme used for miniblocks with the specified protocol version.
+    pub fn for_protocol_version(protocol_version: ProtocolVersionId) -> Self {
+        if protocol_version >= ProtocolVersionId::Version13 {
+            Self::Header
+        } else {
+            Self::Legacy
+        }
+    }
+
+    /// Returns the scheme used for the specified miniblock. Same as [`Self::for_protocol_version()`],
+    /// except for the genesis miniblock, which always has the legacy hash.
+    pub fn for_miniblock(number: MiniblockNumber, protocol_version: ProtocolVersionId) -> Self {
+        if number == MiniblockNumber(0) {
+            Self::Legacy
+        } else {
+            Self::for_protocol_version(protocol_version)
+        }
+    }
+
+    /// Detects the scheme for a miniblock with an unknown protocol version (e.g., a miniblock
+    /// synced by an external node that didn't persist protocol versions) by recomputing its hash.
+    /// Returns `None` if the hash cannot be produced by any known scheme.
+    pub fn detect(hasher: &MiniblockHasher, hash: H256) -> Option<Self> {
+        [Self::Header, Self::Legacy]
+            .into_iter()
+            .find(|&scheme| hasher.hash_with_scheme(scheme) == hash)
+    }
+}
+
+/// Error returned by [`MiniblockHasher::verify()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "hash {actual:?} of miniblock #{number} doesn't match hash {expected:?} according to {scheme:?} scheme \
+     (hash is produced by scheme: {detected_scheme:?})"
+)]
+pub struct MiniblockHashMismatch {
+    pub number: MiniblockNumber,
+    pub scheme: MiniblockHashScheme,
+    pub expected: H256,
+    pub actual: H256,
+    /// Scheme producing the actual hash, if any.
+    pub detected_scheme: Option<MiniblockHashScheme>,
+}
+
 /// Hasher of miniblock contents used by the VM.
 #[derive(Debug)]
 pub struct MiniblockHasher {
@@ -209,30 +294,55 @@ impl MiniblockHasher {
         self.txs_rolling_hash = concat_and_hash(self.txs_rolling_hash, tx_hash);
     }
 
-    /// Returns the hash of the miniblock.
-    ///
-    /// For newer protocol versions, the hash is computed as
-    ///
-    /// ```text
-    /// keccak256(u256_be(number) ++ u256_be(timestamp) ++ prev_miniblock_hash ++ txs_rolling_hash)
-    /// ```
-    ///
-    /// Here, `u256_be` is the big-endian 256-bit serialization of a number, and `txs_rolling_hash`
-    /// is *the rolling hash* of miniblock transactions. `txs_rolling_hash` is calculated the following way:
-    ///
-    /// - If the miniblock has 0 transactions, then `txs_rolling_hash` is equal to `H256::zero()`.
-    /// - If the miniblock has i transactions, then `txs_rolling_hash` is equal to `H(H_{i-1}, H(tx_i))`, where
-    ///   `H_{i-1}` is the `txs_rolling_hash` of the first i-1 transactions.
+    /// Returns the rolling hash of the transactions pushed to this hasher so far.
+    pub fn txs_rolling_hash(&self) -> H256 {
+        self.txs_rolling_hash
+    }
+
+    /// Returns the hash of the miniblock using the scheme defined by the protocol version;
+    /// see [`MiniblockHashScheme`] for details.
     pub fn finalize(self, protocol_version: ProtocolVersionId) -> H256 {
-        if protocol_version >= ProtocolVersionId::Version13 {
-            let mut digest = [0_u8; 128];
-            U256::from(self.number.0).to_big_endian(&mut digest[0..32]);
-            U256::from(self.timestamp).to_big_endian(&mut digest[32..64]);
-            digest[64..96].copy_from_slice(self.prev_miniblock_hash.as_bytes());
-            digest[96..128].copy_from_slice(self.txs_rolling_hash.as_bytes());
-            H256(keccak256(&digest))
+        self.hash_with_scheme(MiniblockHashScheme::for_protocol_version(protocol_version))
+    }
+
+    /// Verifies that `hash` is produced by the scheme selected for the miniblock by [`MiniblockHashScheme::for_miniblock()`].
+    /// If the protocol version is unknown, the scheme is detected instead. Returns the scheme producing the hash.
+    pub fn verify(
+        &self,
+        protocol_version: Option<ProtocolVersionId>,
+        hash: H256,
+    ) -> Result<MiniblockHashScheme, MiniblockHashMismatch> {
+        let detected_scheme = MiniblockHashScheme::detect(self, hash);
+        let scheme = match protocol_version {
+            Some(version) => MiniblockHashScheme::for_miniblock(self.number, version),
+            None => detected_scheme.unwrap_or(MiniblockHashScheme::Header),
+        };
+        let expected = self.hash_with_scheme(scheme);
+        if expected == hash {
+            Ok(scheme)
         } else {
-            Self::legacy_hash(self.number)
+            Err(MiniblockHashMismatch {
+                number: self.number,
+                scheme,
+                expected,
+                actual: hash,
+                detected_scheme,
+            })
+        }
+    }
+
+    /// Returns the hash of the miniblock using the specified scheme.
+    pub fn hash_with_scheme(&self, scheme: MiniblockHashScheme) -> H256 {
+        match scheme {
+            MiniblockHashScheme::Legacy => Self::legacy_hash(self.number),
+            MiniblockHashScheme::Header => {
+                let mut digest = [0_u8; 128];
+                U256::from(self.number.0).to_big_endian(&mut digest[0..32]);
+                U256::from(self.timestamp).to_big_endian(&mut digest[32..64]);
+                digest[64..96].copy_from_slice(self.prev_miniblock_hash.as_bytes());
+                digest[96..128].copy_from_slice(self.txs_rolling_hash.as_bytes());
+                H256(keccak256(&digest))
+            }
         }
     }
 }
@@ -298,6 +408,67 @@ mod tests {
         )
     }
 
+    #[test]
+    fn detecting_miniblock_hash_scheme() {
+        assert_eq!(
+            MiniblockHashScheme::for_protocol_version(ProtocolVersionId::Version12),
+            MiniblockHashScheme::Legacy
+        );
+        assert_eq!(
+            MiniblockHashScheme::for_protocol_version(ProtocolVersionId::latest()),
+            MiniblockHashScheme::Header
+        );
+
+        let mut hasher = MiniblockHasher::new(MiniblockNumber(5), 100, H256::repeat_byte(1));
+        hasher.push_tx_hash(H256::repeat_byte(2));
+        let legacy_hash = hasher.hash_with_scheme(MiniblockHashScheme::Legacy);
+        assert_eq!(
+            legacy_hash,
+            MiniblockHasher::legacy_hash(MiniblockNumber(5))
+        );
+        let header_hash = hasher.hash_with_scheme(MiniblockHashScheme::Header);
+        assert_eq!(
+            MiniblockHashScheme::detect(&hasher, legacy_hash),
+            Some(MiniblockHashScheme::Legacy)
+        );
+        assert_eq!(
+            MiniblockHashScheme::detect(&hasher, header_hash),
+            Some(MiniblockHashScheme::Header)
+        );
+        assert_eq!(MiniblockHashScheme::detect(&hasher, H256::zero()), None);
+    }
+
+    #[test]
+    fn verifying_miniblock_hash() {
+        let mut hasher = MiniblockHasher::new(MiniblockNumber(5), 100, H256::repeat_byte(1));
+        hasher.push_tx_hash(H256::repeat_byte(2));
+        let header_hash = hasher.hash_with_scheme(MiniblockHashScheme::Header);
+        let legacy_hash = hasher.hash_with_scheme(MiniblockHashScheme::Legacy);
+
+        let version = ProtocolVersionId::latest();
+        assert_eq!(
+            hasher.verify(Some(version), header_hash),
+            Ok(MiniblockHashScheme::Header)
+        );
+        assert_eq!(
+            hasher.verify(None, legacy_hash),
+            Ok(MiniblockHashScheme::Legacy)
+        );
+        let err = hasher.verify(Some(version), legacy_hash).unwrap_err();
+        assert_eq!(err.scheme, MiniblockHashScheme::Header);
+        assert_eq!(err.detected_scheme, Some(MiniblockHashScheme::Legacy));
+        let err = hasher.verify(None, H256::zero()).unwrap_err();
+        assert_eq!(err.detected_scheme, None);
+
+        // The genesis miniblock always uses the legacy scheme.
+        let genesis_hasher = MiniblockHasher::new(MiniblockNumber(0), 0, H256::zero());
+        let genesis_hash = MiniblockHasher::legacy_hash(MiniblockNumber(0));
+        assert_eq!(
+            genesis_hasher.verify(Some(version), genesis_hash),
+            Ok(MiniblockHashScheme::Legacy)
+        );
+    }
+
     #[test]
     fn test_block_packing() {
         let block_number = 101;
@@ -0,0 +1,61 @@
+//! Types related to the chain freeze (emergency) mode.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::H256;
+
+/// Source of a chain freeze state change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainFreezeSource {
+    /// Call of `admin_freezeChain` / `admin_unfreezeChain` RPC methods.
+    AdminRpc,
+    /// `Freeze` / `Unfreeze` event emitted by the diamond proxy on L1.
+    L1,
+}
+
+impl ChainFreezeSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::AdminRpc => "admin_rpc",
+            Self::L1 => "l1",
+        }
+    }
+}
+
+impl fmt::Display for ChainFreezeSource {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ChainFreezeSource {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin_rpc" => Ok(Self::AdminRpc),
+            "l1" => Ok(Self::L1),
+            _ => Err("Incorrect chain freeze source; expected one of `admin_rpc`, `l1`"),
+        }
+    }
+}
+
+/// Recorded change of the chain freeze state. The latest event defines whether the chain is currently frozen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainFreezeEvent {
+    /// Sequential ID of the event.
+    pub id: u64,
+    /// Whether the chain is frozen after this event.
+    pub is_frozen: bool,
+    pub source: ChainFreezeSource,
+    /// Human-readable reason provided by the operator.
+    pub reason: Option<String>,
+    /// Hash of the L1 transaction that has emitted the corresponding event. Only set for [`ChainFreezeSource::L1`].
+    pub l1_tx_hash: Option<H256>,
+    pub created_at: DateTime<Utc>,
+}
@@ -91,3 +91,96 @@ impl Add for CircuitStatistic {
         }
     }
 }
+
+/// Holds information about operations performed by the VM: executed opcodes grouped by family, precompile calls,
+/// bytecode decommitments and storage accesses. Unlike [`CircuitStatistic`], these numbers describe the workload
+/// itself rather than its proving cost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmOperationStatistic {
+    /// Arithmetic, bitwise and shift opcodes, plus `nop`s.
+    pub arithmetic_opcodes: u32,
+    pub jump_opcodes: u32,
+    pub pointer_opcodes: u32,
+    pub context_opcodes: u32,
+    pub near_calls: u32,
+    pub far_calls: u32,
+    pub returns: u32,
+    pub heap_reads: u32,
+    pub heap_writes: u32,
+    pub storage_reads: u32,
+    pub storage_writes: u32,
+    /// Number of `event` opcodes. Since each opcode emits at most 2 topics or data words, this is generally
+    /// greater than the number of emitted events.
+    pub events: u32,
+    pub l2_to_l1_messages: u32,
+    pub keccak256_calls: u32,
+    pub sha256_calls: u32,
+    pub ecrecover_calls: u32,
+    /// Number of bytecodes decommitted for the first time.
+    pub decommitments: u32,
+    /// Total length of decommitted bytecodes in 32-byte words.
+    pub decommitted_words: u32,
+    /// Number of storage slots read for the first time.
+    pub unique_storage_reads: u32,
+    /// Number of storage slots written to for the first time.
+    pub unique_storage_writes: u32,
+}
+
+impl VmOperationStatistic {
+    /// Returns the total number of executed opcodes.
+    pub fn total_opcodes(&self) -> u64 {
+        [
+            self.arithmetic_opcodes,
+            self.jump_opcodes,
+            self.pointer_opcodes,
+            self.context_opcodes,
+            self.near_calls,
+            self.far_calls,
+            self.returns,
+            self.heap_reads,
+            self.heap_writes,
+            self.storage_reads,
+            self.storage_writes,
+            self.events,
+            self.l2_to_l1_messages,
+            self.precompile_calls(),
+        ]
+        .into_iter()
+        .map(u64::from)
+        .sum()
+    }
+
+    /// Returns the total number of precompile calls.
+    pub fn precompile_calls(&self) -> u32 {
+        self.keccak256_calls + self.sha256_calls + self.ecrecover_calls
+    }
+}
+
+impl Add for VmOperationStatistic {
+    type Output = VmOperationStatistic;
+
+    fn add(self, other: VmOperationStatistic) -> VmOperationStatistic {
+        VmOperationStatistic {
+            arithmetic_opcodes: self.arithmetic_opcodes + other.arithmetic_opcodes,
+            jump_opcodes: self.jump_opcodes + other.jump_opcodes,
+            pointer_opcodes: self.pointer_opcodes + other.pointer_opcodes,
+            context_opcodes: self.context_opcodes + other.context_opcodes,
+            near_calls: self.near_calls + other.near_calls,
+            far_calls: self.far_calls + other.far_calls,
+            returns: self.returns + other.returns,
+            heap_reads: self.heap_reads + other.heap_reads,
+            heap_writes: self.heap_writes + other.heap_writes,
+            storage_reads: self.storage_reads + other.storage_reads,
+            storage_writes: self.storage_writes + other.storage_writes,
+            events: self.events + other.events,
+            l2_to_l1_messages: self.l2_to_l1_messages + other.l2_to_l1_messages,
+            keccak256_calls: self.keccak256_calls + other.keccak256_calls,
+            sha256_calls: self.sha256_calls + other.sha256_calls,
+            ecrecover_calls: self.ecrecover_calls + other.ecrecover_calls,
+            decommitments: self.decommitments + other.decommitments,
+            decommitted_words: self.decommitted_words + other.decommitted_words,
+            unique_storage_reads: self.unique_storage_reads + other.unique_storage_reads,
+            unique_storage_writes: self.unique_storage_writes + other.unique_storage_writes,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Types related to the cold storage of call traces and events, i.e. data for old L1 batches moved
+//! from Postgres to the object store.
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{L1BatchNumber, MiniblockNumber, H256};
+
+use crate::{api, vm_trace::Call};
+
+/// Call trace of a transaction stored in an [`L1BatchColdArchive`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedCallTrace {
+    pub tx_hash: H256,
+    pub miniblock_number: MiniblockNumber,
+    pub call_trace: Call,
+}
+
+/// Call traces and events of a single L1 batch moved to the object store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct L1BatchColdArchive {
+    pub l1_batch_number: L1BatchNumber,
+    /// Call traces ordered by the transaction position in the batch.
+    pub call_traces: Vec<ArchivedCallTrace>,
+    /// Events ordered by the miniblock number and the event index in the miniblock.
+    pub events: Vec<api::Log>,
+}
+
+impl L1BatchColdArchive {
+    /// Returns call traces for all transactions in the specified miniblock in the order of their execution.
+    pub fn traces_for_miniblock(&self, miniblock_number: MiniblockNumber) -> Vec<Call> {
+        self.call_traces
+            .iter()
+            .filter(|trace| trace.miniblock_number == miniblock_number)
+            .map(|trace| trace.call_trace.clone())
+            .collect()
+    }
+
+    pub fn trace_for_tx(&self, tx_hash: H256) -> Option<Call> {
+        self.call_traces
+            .iter()
+            .find(|trace| trace.tx_hash == tx_hash)
+            .map(|trace| trace.call_trace.clone())
+    }
+
+    /// Returns events matching the provided filter.
+    pub fn logs<'a>(
+        &'a self,
+        filter: &'a api::GetLogsFilter,
+    ) -> impl Iterator<Item = &'a api::Log> + 'a {
+        self.events.iter().filter(move |log| filter.matches(log))
+    }
+}
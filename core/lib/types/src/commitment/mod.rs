@@ -9,6 +9,7 @@
 use std::{collections::HashMap, convert::TryFrom};
 
 use serde::{Deserialize, Serialize};
+use zksync_config::configs::chain::L1BatchCommitDataGeneratorMode;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_system_constants::{
@@ -26,12 +27,24 @@ use crate::{
         compress_state_diffs, InitialStorageWrite, RepeatedStorageWrite, StateDiffRecord,
         PADDED_ENCODED_STORAGE_DIFF_LEN_BYTES,
     },
-    ProtocolVersionId, H256,
+    ProtocolVersionId, H256, U256,
 };
 
 #[cfg(test)]
 mod tests;
 
+/// Number of bits the public input of the batch proof is shifted by, so that it fits into the scalar field
+/// of the proof system. Corresponds to `PUBLIC_INPUT_SHIFT` in the `Executor` contract.
+const PUBLIC_INPUT_SHIFT: usize = 32;
+
+/// Computes the public input of the proof for an L1 batch the same way as the `Executor` contract:
+/// `uint256(keccak256(abi.encodePacked(prevBatchCommitment, currentBatchCommitment))) >> PUBLIC_INPUT_SHIFT`.
+pub fn proof_public_input(prev_commitment: H256, commitment: H256) -> U256 {
+    let mut bytes = prev_commitment.as_bytes().to_vec();
+    bytes.extend_from_slice(commitment.as_bytes());
+    U256::from_big_endian(&keccak256(&bytes)) >> PUBLIC_INPUT_SHIFT
+}
+
 /// Type that can be serialized for commitment.
 pub trait SerializeCommitment {
     /// Size of the structure in bytes.
@@ -68,6 +81,25 @@ pub fn serialize_commitments<I: SerializeCommitment>(values: &[I]) -> Vec<u8> {
     input
 }
 
+/// Mode in which L1 batches are committed on L1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum L1BatchCommitmentMode {
+    /// Pubdata of L1 batches is published on L1 (either in calldata or in blobs) as a part of the commit transaction.
+    #[default]
+    Rollup,
+    /// Pubdata of L1 batches is not published on L1; commit transactions only contain the pubdata source marker.
+    Validium,
+}
+
+impl From<L1BatchCommitDataGeneratorMode> for L1BatchCommitmentMode {
+    fn from(mode: L1BatchCommitDataGeneratorMode) -> Self {
+        match mode {
+            L1BatchCommitDataGeneratorMode::Rollup => Self::Rollup,
+            L1BatchCommitDataGeneratorMode::Validium => Self::Validium,
+        }
+    }
+}
+
 /// Precalculated data for the L1 batch that was used in commitment and L1 transaction.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct L1BatchMetadata {
@@ -255,6 +287,38 @@ impl SerializeCommitment for StateDiffRecord {
     }
 }
 
+/// Serializes the auxiliary output of a post-Boojum L1 batch commitment, i.e., the preimage of
+/// [`L1BatchMetadata::aux_data_hash`]. Missing `blobs` are treated as zero commitments.
+pub fn post_boojum_aux_output_bytes(
+    protocol_version: ProtocolVersionId,
+    system_logs_linear_hash: H256,
+    state_diffs_hash: H256,
+    aux_commitments: AuxCommitments,
+    blobs: &[BlobCommitment],
+) -> Vec<u8> {
+    let mut result = Vec::with_capacity(32 * 8);
+    result.extend(system_logs_linear_hash.as_bytes());
+    result.extend(state_diffs_hash.as_bytes());
+    result.extend(
+        aux_commitments
+            .bootloader_initial_content_commitment
+            .as_bytes(),
+    );
+    result.extend(aux_commitments.events_queue_commitment.as_bytes());
+
+    if protocol_version.is_1_4_1() {
+        // We are using zeroes as commitments to the KZG pubdata as per convention.
+        result.extend([0_u8; 32 * 4]);
+    } else if protocol_version.is_post_1_4_2() {
+        for i in 0..2 {
+            let blob = blobs.get(i).copied().unwrap_or_default();
+            result.extend(blob.linear_hash.as_bytes());
+            result.extend(blob.commitment.as_bytes());
+        }
+    }
+    result
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(test, derive(Serialize, Deserialize))]
 struct L1BatchAuxiliaryCommonOutput {
@@ -444,27 +508,17 @@ impl L1BatchAuxiliaryOutput {
                 blob_commitments,
                 ..
             } => {
-                result.extend(system_logs_linear_hash.as_bytes());
-                result.extend(state_diffs_hash.as_bytes());
-                result.extend(
-                    aux_commitments
-                        .bootloader_initial_content_commitment
-                        .as_bytes(),
+                let blobs = [0, 1].map(|i| BlobCommitment {
+                    linear_hash: blob_linear_hashes[i],
+                    commitment: blob_commitments[i],
+                });
+                result = post_boojum_aux_output_bytes(
+                    common.protocol_version,
+                    *system_logs_linear_hash,
+                    *state_diffs_hash,
+                    *aux_commitments,
+                    &blobs,
                 );
-                result.extend(aux_commitments.events_queue_commitment.as_bytes());
-
-                if common.protocol_version.is_1_4_1() {
-                    // We are using zeroes as commitments to the KZG pubdata as per convention.
-                    result.extend(H256::zero().as_bytes());
-                    result.extend(H256::zero().as_bytes());
-                    result.extend(H256::zero().as_bytes());
-                    result.extend(H256::zero().as_bytes());
-                } else if common.protocol_version.is_post_1_4_2() {
-                    result.extend(blob_linear_hashes[0].as_bytes());
-                    result.extend(blob_commitments[0].as_bytes());
-                    result.extend(blob_linear_hashes[1].as_bytes());
-                    result.extend(blob_commitments[1].as_bytes());
-                }
             }
         }
 
@@ -593,6 +647,28 @@ impl L1BatchCommitment {
         self.auxiliary_output.common().l2_l1_logs_merkle_root
     }
 
+    /// Returns commitments to the pubdata blobs for post-1.4.2 batches.
+    pub fn blob_commitments(&self) -> Option<Vec<BlobCommitment>> {
+        match &self.auxiliary_output {
+            L1BatchAuxiliaryOutput::PostBoojum {
+                common,
+                blob_linear_hashes,
+                blob_commitments,
+                ..
+            } if common.protocol_version.is_post_1_4_2() => Some(
+                blob_linear_hashes
+                    .iter()
+                    .zip(blob_commitments)
+                    .map(|(&linear_hash, &commitment)| BlobCommitment {
+                        linear_hash,
+                        commitment,
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
     pub fn aux_commitments(&self) -> Option<AuxCommitments> {
         match &self.auxiliary_output {
             L1BatchAuxiliaryOutput::PostBoojum {
@@ -644,12 +720,24 @@ impl L1BatchCommitment {
             compressed_state_diffs,
             zkporter_is_available: self.meta_parameters.zkporter_is_available,
             aux_commitments: self.aux_commitments(),
+            blob_commitments: self.blob_commitments(),
             compressed_initial_writes,
             compressed_repeated_writes,
         }
     }
 }
 
+/// Commitment to a single pubdata blob included into the auxiliary output of an L1 batch commitment.
+/// Unused blobs have both hashes set to zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobCommitment {
+    /// Linear hash of the blob published by the `PubdataChunkPublisher` system contract.
+    pub linear_hash: H256,
+    /// Commitment to the blob: keccak hash of its versioned hash, KZG opening point and opening value.
+    pub commitment: H256,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(test, derive(Serialize, Deserialize))]
 pub struct AuxCommitments {
@@ -668,6 +756,13 @@ pub struct CommitmentCommonInput {
     pub protocol_version: ProtocolVersionId,
 }
 
+/// Input for computing the L1 batch commitment.
+///
+/// Operator-defined per-batch metadata (e.g., DA receipts or oracle roots) is intentionally not a part of the input.
+/// Committing its hash is declined: the commitment is checked against the scheduler circuit output when the batch
+/// is proven on L1, and neither the circuits nor the L1 executor reserve a slot for such a hash, so including it
+/// on the server alone would make batches unprovable. Chains can anchor app-specific data per batch by sending
+/// L2->L1 messages, which are already committed via the L2->L1 logs tree.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(test, derive(Serialize, Deserialize))]
 pub enum CommitmentInput {
@@ -737,4 +832,5 @@ pub struct L1BatchCommitmentArtifacts {
     pub compressed_repeated_writes: Option<Vec<u8>>,
     pub zkporter_is_available: bool,
     pub aux_commitments: Option<AuxCommitments>,
+    pub blob_commitments: Option<Vec<BlobCommitment>>,
 }
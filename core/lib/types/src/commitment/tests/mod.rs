@@ -45,3 +45,14 @@ fn post_boojum_1_4_1() {
 fn post_boojum_1_4_2() {
     run_test("post_boojum_1_4_2_test");
 }
+
+#[test]
+fn computing_proof_public_input() {
+    let public_input = proof_public_input(H256::repeat_byte(1), H256::repeat_byte(2));
+    let expected = U256::from_str_radix(
+        "346d8c96a2454213fcc0daff3c96ad0398148181b9fa6488f7ae2c0a",
+        16,
+    )
+    .unwrap();
+    assert_eq!(public_input, expected);
+}
@@ -1,6 +1,11 @@
+use std::ops;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{aggregated_operations::AggregatedActionType, Address, Nonce, H256};
+use crate::{
+    aggregated_operations::AggregatedActionType, Address, L1BatchNumber, L1BlockNumber, Nonce,
+    H256, U256,
+};
 
 /// A forward-compatible `enum` describing a EIP4844 sidecar
 ///
@@ -89,3 +94,39 @@ pub struct TxHistoryToSend {
     pub signed_raw_tx: Vec<u8>,
     pub nonce: Nonce,
 }
+
+/// Actual L1 costs of a confirmed ETH transaction, as reported by its L1 receipt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EthTxL1Costs {
+    pub tx_hash: H256,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    pub l1_block_number: L1BlockNumber,
+}
+
+impl EthTxL1Costs {
+    /// Returns the total fee paid for the transaction in wei.
+    pub fn total_fee(&self) -> U256 {
+        self.gas_used * self.effective_gas_price
+    }
+}
+
+/// Utilization of blobs by a commit transaction publishing pubdata in blobs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitBlobUtilization {
+    pub eth_tx_id: u32,
+    pub l1_batches: ops::RangeInclusive<L1BatchNumber>,
+    pub blob_count: u32,
+    /// Total size of the published pubdata in bytes.
+    pub pubdata_bytes: u64,
+    /// Fee paid for the transaction in wei. `None` if the transaction is not confirmed or its L1 costs
+    /// are not recorded yet.
+    pub total_fee: Option<U256>,
+}
+
+impl CommitBlobUtilization {
+    /// Returns the number of L1 batches committed by the transaction.
+    pub fn l1_batch_count(&self) -> u32 {
+        self.l1_batches.end().0 - self.l1_batches.start().0 + 1
+    }
+}
@@ -44,6 +44,18 @@ pub static DEPLOY_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     )
 });
 
+/// Signature of the ERC-20 `Transfer(address,address,uint256)` event; also emitted by the L2 ETH token.
+pub static TRANSFER_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "Transfer",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+        ],
+    )
+});
+
 static L1_MESSAGE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     ethabi::long_signature(
         "L1MessageSent",
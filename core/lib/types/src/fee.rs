@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use zksync_utils::ceil_div;
 
-use crate::{circuit::CircuitStatistic, U256};
+use crate::{circuit::CircuitStatistic, Address, U256};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "result")]
@@ -79,6 +79,59 @@ impl Fee {
     }
 }
 
+/// Fee estimate returned by `zks_estimateFee`, optionally accompanied by a breakdown of the estimated gas limit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    #[serde(flatten)]
+    pub fee: Fee,
+    /// Breakdown of the estimated gas limit. Only returned if explicitly requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<FeeBreakdown>,
+}
+
+/// Breakdown of the gas limit estimated for a transaction. `execution_gas`, `pubdata_gas` and `overhead_gas`
+/// sum up to the estimated gas limit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeBreakdown {
+    /// Gas limit for executing the transaction body, including the gas estimation scale factor.
+    pub execution_gas: U256,
+    /// Gas for publishing bytecodes of factory dependencies. Always zero for L1 transactions.
+    pub pubdata_gas: U256,
+    /// Batch overhead charged for the transaction.
+    pub overhead_gas: U256,
+    /// Gas per pubdata byte used for the estimation.
+    pub gas_per_pubdata: U256,
+    /// Lower bound of gas expected to be refunded, assuming the transaction behaves the same way as during estimation.
+    pub min_expected_refund: U256,
+    /// Upper bound of gas expected to be refunded, assuming the transaction behaves the same way as during estimation.
+    pub max_expected_refund: U256,
+}
+
+/// Fee estimate for a transaction together with the fee sponsorship the transaction is eligible for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeReport {
+    #[serde(flatten)]
+    pub fee: Fee,
+    /// Sponsorship of the transaction fee by the operator, if the transaction matches the operator sponsorship policy.
+    pub sponsorship: Option<FeeSponsorship>,
+    /// Maximum fee paid by the transaction initiator. Zero for sponsored transactions.
+    pub max_user_fee: U256,
+}
+
+/// Sponsorship of a transaction fee by the operator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeSponsorship {
+    /// Operator-funded paymaster that must be specified in the transaction (using the general paymaster flow)
+    /// for the sponsorship to apply.
+    pub paymaster: Address,
+    /// Maximum fee absorbed by the operator.
+    pub max_sponsored_fee: U256,
+    /// Maximum gas limit of a sponsored transaction.
+    pub max_gas_limit: U256,
+    /// Maximum number of pubdata bytes a sponsored transaction may publish.
+    pub max_pubdata: u32,
+}
+
 /// Returns how many slots would ABI-encoding of the transaction with such parameters take
 pub fn encoding_len(
     data_len: u64,
@@ -101,3 +154,47 @@ pub fn encoding_len(
 
     BASE_LEN + dynamic_len as usize
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_estimate_without_breakdown_is_serialized_as_fee() {
+        let fee = Fee {
+            gas_limit: 1_000_000.into(),
+            max_fee_per_gas: 250_000_000.into(),
+            max_priority_fee_per_gas: 0.into(),
+            gas_per_pubdata_limit: 800.into(),
+        };
+        let estimate = FeeEstimate {
+            fee: fee.clone(),
+            breakdown: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&estimate).unwrap(),
+            serde_json::to_value(&fee).unwrap()
+        );
+
+        let estimate = FeeEstimate {
+            fee: fee.clone(),
+            breakdown: Some(FeeBreakdown {
+                execution_gas: 900_000.into(),
+                pubdata_gas: 0.into(),
+                overhead_gas: 100_000.into(),
+                gas_per_pubdata: 800.into(),
+                min_expected_refund: 100_000.into(),
+                max_expected_refund: 150_000.into(),
+            }),
+        };
+        let json = serde_json::to_value(&estimate).unwrap();
+        assert_eq!(json["breakdown"]["overhead_gas"], "0x186a0");
+        let restored: FeeEstimate = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, estimate);
+        // Clients expecting a plain fee must be able to parse the extended response.
+        let restored_fee: Fee = serde_json::to_value(&estimate)
+            .and_then(serde_json::from_value)
+            .unwrap();
+        assert_eq!(restored_fee, fee);
+    }
+}
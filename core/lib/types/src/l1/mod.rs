@@ -7,7 +7,7 @@ use zksync_basic_types::{
     ethabi::{decode, ParamType, Token},
     Address, L1BlockNumber, Log, PriorityOpId, H160, H256, U256,
 };
-use zksync_utils::u256_to_account_address;
+use zksync_utils::{address_to_u256, u256_to_account_address};
 
 use super::Transaction;
 use crate::{
@@ -21,6 +21,19 @@ use crate::{
 
 pub mod error;
 
+/// Offset added by the L1 mailbox to the address of an L1 contract initiating a priority operation. Aliasing prevents
+/// L1 contracts from impersonating L2 accounts with the same address.
+pub const L1_TO_L2_ALIAS_OFFSET: Address = H160([
+    0x11, 0x11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x11, 0x11,
+]);
+
+/// Returns the L2 address used as the sender of priority operations initiated by the specified L1 contract.
+pub fn apply_l1_to_l2_alias(address: Address) -> Address {
+    let aliased = address_to_u256(&address) + address_to_u256(&L1_TO_L2_ALIAS_OFFSET);
+    // Truncates the sum to 160 bits, i.e., the addition wraps around.
+    u256_to_account_address(&aliased)
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
 #[repr(u8)]
 pub enum OpProcessingType {
@@ -351,3 +364,28 @@ impl TryFrom<Log> for L1Tx {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applying_l1_to_l2_alias() {
+        assert_eq!(apply_l1_to_l2_alias(Address::zero()), L1_TO_L2_ALIAS_OFFSET);
+        let address: Address = "0x1000000000000000000000000000000000000001"
+            .parse()
+            .unwrap();
+        let expected: Address = "0x2111000000000000000000000000000000001112"
+            .parse()
+            .unwrap();
+        assert_eq!(apply_l1_to_l2_alias(address), expected);
+
+        let address: Address = "0xffffffffffffffffffffffffffffffffffffffff"
+            .parse()
+            .unwrap();
+        let expected: Address = "0x1111000000000000000000000000000000001110"
+            .parse()
+            .unwrap();
+        assert_eq!(apply_l1_to_l2_alias(address), expected);
+    }
+}
@@ -15,9 +15,9 @@ use crate::{
     tx::{primitives::PackedEthSignature, Execute},
     web3::types::U64,
     Address, Bytes, EIP712TypedStructure, Eip712Domain, ExecuteTransactionCommon, InputData,
-    L2ChainId, Nonce, StructBuilder, Transaction, EIP_1559_TX_TYPE, EIP_2930_TX_TYPE,
-    EIP_712_TX_TYPE, H256, LEGACY_TX_TYPE, PRIORITY_OPERATION_L2_TX_TYPE, PROTOCOL_UPGRADE_TX_TYPE,
-    U256,
+    L2ChainId, MiniblockNumber, Nonce, StructBuilder, Transaction, EIP_1559_TX_TYPE,
+    EIP_2930_TX_TYPE, EIP_712_TX_TYPE, H256, LEGACY_TX_TYPE, PRIORITY_OPERATION_L2_TX_TYPE,
+    PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
 
 pub mod error;
@@ -48,6 +48,31 @@ impl TransactionType {
     }
 }
 
+/// Signature scheme of an L2 transaction detected from its signature. Custom accounts may use arbitrary
+/// signature formats, so the scheme is determined on a best-effort basis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(strum::Display, strum::EnumString)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum SignatureScheme {
+    /// 65-byte ECDSA signature, used by the default account.
+    Ecdsa,
+    /// Any other signature format. There is no standard signature layout for custom accounts,
+    /// so such signatures are not classified further.
+    Custom,
+}
+
+impl SignatureScheme {
+    /// Detects the scheme of the provided transaction signature.
+    pub fn detect(signature: &[u8]) -> Self {
+        if signature.len() == 65 {
+            Self::Ecdsa
+        } else {
+            Self::Custom
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct L2TxCommonData {
@@ -63,6 +88,37 @@ pub struct L2TxCommonData {
     pub input: Option<InputData>,
 
     pub paymaster_params: PaymasterParams,
+    /// Deadline after which the transaction must not be included into a miniblock. Not a part of the signed
+    /// transaction data; it's specified by the submitter when sending the transaction to the API server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<ValidUntil>,
+    /// Sequence number assigned to the transaction when it's persisted in the mempool. Reflects the order
+    /// in which transactions arrived to the node and is used for first-come-first-served ordering.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arrival_seq: Option<u64>,
+}
+
+/// Reason used when rejecting transactions with an expired deadline.
+pub const TX_EXPIRED_ERROR: &str = "transaction deadline has passed";
+
+/// Inclusion deadline of an L2 transaction.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidUntil {
+    /// The transaction may only be included into miniblocks with a timestamp less than this UNIX timestamp (in seconds).
+    Timestamp(u64),
+    /// The transaction may only be included into miniblocks with a number less than this one.
+    Block(MiniblockNumber),
+}
+
+impl ValidUntil {
+    /// Checks whether the deadline has passed for a miniblock with the specified number and timestamp.
+    pub fn is_expired(&self, miniblock_number: MiniblockNumber, timestamp: u64) -> bool {
+        match *self {
+            Self::Timestamp(valid_until) => valid_until <= timestamp,
+            Self::Block(valid_until) => valid_until <= miniblock_number,
+        }
+    }
 }
 
 impl L2TxCommonData {
@@ -86,6 +142,8 @@ impl L2TxCommonData {
             transaction_type,
             input,
             paymaster_params,
+            valid_until: None,
+            arrival_seq: None,
         }
     }
 
@@ -137,6 +195,8 @@ impl Default for L2TxCommonData {
             transaction_type: TransactionType::EIP712Transaction,
             input: Default::default(),
             paymaster_params: Default::default(),
+            valid_until: None,
+            arrival_seq: None,
         }
     }
 }
@@ -176,6 +236,8 @@ impl L2Tx {
                 transaction_type: TransactionType::EIP712Transaction,
                 input: None,
                 paymaster_params,
+                valid_until: None,
+                arrival_seq: None,
             },
             received_timestamp_ms: unix_timestamp_ms(),
             raw_bytes: None,
@@ -473,14 +535,27 @@ impl EIP712TypedStructure for L2Tx {
 
 #[cfg(test)]
 mod tests {
-    use zksync_basic_types::{Nonce, U256};
+    use zksync_basic_types::{MiniblockNumber, Nonce, U256};
 
-    use super::{L2Tx, TransactionType};
+    use super::{L2Tx, SignatureScheme, TransactionType, ValidUntil};
     use crate::{
         api::TransactionRequest, fee::Fee, transaction_request::PaymasterParams, Execute,
         L2TxCommonData,
     };
 
+    #[test]
+    fn checking_deadline_expiration() {
+        let deadline = ValidUntil::Block(MiniblockNumber(5));
+        assert!(!deadline.is_expired(MiniblockNumber(4), u64::MAX));
+        assert!(deadline.is_expired(MiniblockNumber(5), 0));
+        assert!(deadline.is_expired(MiniblockNumber(6), 0));
+
+        let deadline = ValidUntil::Timestamp(100);
+        assert!(!deadline.is_expired(MiniblockNumber(u32::MAX), 99));
+        assert!(deadline.is_expired(MiniblockNumber(0), 100));
+        assert!(deadline.is_expired(MiniblockNumber(0), 101));
+    }
+
     #[test]
     fn test_correct_l2_tx_transaction_request_conversion() {
         // It is a random valid signature
@@ -500,6 +575,8 @@ mod tests {
                 transaction_type: TransactionType::LegacyTransaction,
                 input: None,
                 paymaster_params: PaymasterParams::default(),
+                valid_until: None,
+                arrival_seq: None,
             },
             received_timestamp_ms: Default::default(),
             raw_bytes: None,
@@ -511,4 +588,17 @@ mod tests {
             signature[64] as u32
         );
     }
+
+    #[test]
+    fn detecting_signature_scheme() {
+        assert_eq!(SignatureScheme::detect(&[1; 65]), SignatureScheme::Ecdsa);
+        assert_eq!(SignatureScheme::detect(&[]), SignatureScheme::Custom);
+        assert_eq!(SignatureScheme::detect(&[1; 130]), SignatureScheme::Custom);
+
+        assert_eq!(SignatureScheme::Custom.to_string(), "custom");
+        assert_eq!(
+            "ecdsa".parse::<SignatureScheme>().unwrap(),
+            SignatureScheme::Ecdsa
+        );
+    }
 }
@@ -24,8 +24,11 @@ pub use crate::{Nonce, H256, U256, U64};
 pub type SerialId = u64;
 
 pub mod aggregated_operations;
+pub mod audit_log;
 pub mod block;
+pub mod chain_freeze;
 pub mod circuit;
+pub mod cold_storage;
 pub mod commitment;
 pub mod contract_verification_api;
 pub mod event;
@@ -115,6 +118,14 @@ impl Transaction {
         matches!(self.common_data, ExecuteTransactionCommon::L1(_))
     }
 
+    /// Returns the inclusion deadline of the transaction. Only L2 transactions can have a deadline.
+    pub fn valid_until(&self) -> Option<l2::ValidUntil> {
+        match &self.common_data {
+            ExecuteTransactionCommon::L2(data) => data.valid_until,
+            _ => None,
+        }
+    }
+
     pub fn tx_format(&self) -> TransactionType {
         match &self.common_data {
             ExecuteTransactionCommon::L1(tx) => tx.tx_format(),
@@ -118,6 +118,17 @@ impl ProtocolVersionId {
     pub fn is_pre_1_4_2(&self) -> bool {
         self < &ProtocolVersionId::Version21
     }
+
+    /// Returns whether the state keeper enforces the configured gas limit for each miniblock.
+    pub fn supports_miniblock_gas_limit(&self) -> bool {
+        self >= &ProtocolVersionId::Version22
+    }
+
+    /// Returns whether priority operations are processed on L1 via the priority Merkle tree (rather than via
+    /// the legacy priority queue with a rolling hash), which requires proving priority operations for `executeBatches`.
+    pub fn supports_priority_tree(&self) -> bool {
+        self >= &ProtocolVersionId::Version22
+    }
 }
 
 impl Default for ProtocolVersionId {
@@ -299,7 +310,8 @@ pub struct ProtocolUpgrade {
     pub tx: Option<ProtocolUpgradeTx>,
 }
 
-fn get_transaction_param_type() -> ParamType {
+/// Returns the ABI type of `L2CanonicalTransaction` used in protocol upgrades.
+pub fn get_transaction_param_type() -> ParamType {
     ParamType::Tuple(vec![
         ParamType::Uint(256),                                     // `txType`
         ParamType::Uint(256),                                     // sender
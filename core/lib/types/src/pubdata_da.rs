@@ -1,7 +1,10 @@
+use chrono::NaiveDateTime;
 use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 use zksync_config::configs::eth_sender::PubdataSendingMode;
 
+use crate::L1BatchNumber;
+
 /// Enum holding the current values used for DA Layers.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
@@ -9,6 +12,8 @@ use zksync_config::configs::eth_sender::PubdataSendingMode;
 pub enum PubdataDA {
     Calldata = 0,
     Blobs,
+    /// Pubdata is published to an external DA layer; L1 only receives the DA inclusion data.
+    Custom,
 }
 
 impl From<PubdataSendingMode> for PubdataDA {
@@ -16,6 +21,18 @@ impl From<PubdataSendingMode> for PubdataDA {
         match value {
             PubdataSendingMode::Calldata => PubdataDA::Calldata,
             PubdataSendingMode::Blobs => PubdataDA::Blobs,
+            PubdataSendingMode::Custom => PubdataDA::Custom,
         }
     }
 }
+
+/// Pubdata of an L1 batch dispatched to an external DA layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataAvailabilityBlob {
+    pub l1_batch_number: L1BatchNumber,
+    /// Identifier of the blob returned by the DA layer.
+    pub blob_id: String,
+    /// Proof of the blob inclusion in the DA layer. `None` if the blob is not included yet.
+    pub inclusion_data: Option<Vec<u8>>,
+    pub sent_at: NaiveDateTime,
+}
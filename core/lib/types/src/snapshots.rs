@@ -19,9 +19,49 @@ pub struct AllSnapshots {
     pub snapshots_l1_batch_numbers: Vec<L1BatchNumber>,
 }
 
+/// Version of the snapshot format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SnapshotVersion {
+    /// Initial snapshot format. Snapshot objects are described only by the [`SnapshotHeader`] returned
+    /// by the main node API.
+    Version1 = 1,
+    /// In addition to version 1 objects, a [`SnapshotManifest`] is persisted in the object store.
+    Version2 = 2,
+}
+
+impl SnapshotVersion {
+    /// Latest snapshot version, which is used for newly created snapshots.
+    pub const LATEST: Self = Self::Version2;
+
+    fn default_for_header() -> u16 {
+        Self::Version1.into()
+    }
+}
+
+impl From<SnapshotVersion> for u16 {
+    fn from(version: SnapshotVersion) -> Self {
+        version as u16
+    }
+}
+
+impl TryFrom<u16> for SnapshotVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Version1),
+            2 => Ok(Self::Version2),
+            _ => Err(anyhow::anyhow!("unsupported snapshot version: {value}")),
+        }
+    }
+}
+
 /// Storage snapshot metadata. Used in DAL to fetch certain snapshot data.
 #[derive(Debug, Clone)]
 pub struct SnapshotMetadata {
+    /// Version of the snapshot format.
+    pub version: SnapshotVersion,
     /// L1 batch for the snapshot. The data in the snapshot captures node storage at the end of this batch.
     pub l1_batch_number: L1BatchNumber,
     /// Path to the factory dependencies blob.
@@ -43,6 +83,11 @@ impl SnapshotMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotHeader {
+    /// Version of the snapshot format. Not converted to [`SnapshotVersion`] so that the header can be deserialized
+    /// even if the version is not supported by the reader. Headers returned by older main nodes don't have
+    /// this field; they correspond to [`SnapshotVersion::Version1`].
+    #[serde(default = "SnapshotVersion::default_for_header")]
+    pub version: u16,
     pub l1_batch_number: L1BatchNumber,
     pub miniblock_number: MiniblockNumber,
     /// Ordered by chunk IDs.
@@ -59,6 +104,18 @@ pub struct SnapshotStorageLogsChunkMetadata {
     pub filepath: String,
 }
 
+/// Manifest describing all objects of a snapshot. Persisted in the object store starting from
+/// [`SnapshotVersion::Version2`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotManifest {
+    pub version: SnapshotVersion,
+    pub l1_batch_number: L1BatchNumber,
+    pub miniblock_number: MiniblockNumber,
+    pub factory_deps_filepath: String,
+    /// Ordered by chunk IDs.
+    pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotStorageLogsStorageKey {
@@ -190,6 +247,68 @@ impl ProtoFmt for SnapshotStorageLogsChunk {
     }
 }
 
+impl ProtoFmt for SnapshotStorageLogsChunkMetadata {
+    type Proto = crate::proto::SnapshotStorageLogsChunkMetadata;
+
+    fn read(r: &Self::Proto) -> anyhow::Result<Self> {
+        Ok(Self {
+            chunk_id: *required(&r.chunk_id).context("chunk_id")?,
+            filepath: required(&r.filepath).context("filepath")?.clone(),
+        })
+    }
+
+    fn build(&self) -> Self::Proto {
+        Self::Proto {
+            chunk_id: Some(self.chunk_id),
+            filepath: Some(self.filepath.clone()),
+        }
+    }
+}
+
+impl ProtoFmt for SnapshotManifest {
+    type Proto = crate::proto::SnapshotManifest;
+
+    fn read(r: &Self::Proto) -> anyhow::Result<Self> {
+        let version = required(&r.version)
+            .and_then(|&version| SnapshotVersion::try_from(u16::try_from(version)?))
+            .context("version")?;
+        let mut storage_logs_chunks = Vec::with_capacity(r.storage_logs_chunks.len());
+        for (i, chunk) in r.storage_logs_chunks.iter().enumerate() {
+            storage_logs_chunks.push(
+                SnapshotStorageLogsChunkMetadata::read(chunk)
+                    .with_context(|| format!("storage_logs_chunks[{i}]"))?,
+            );
+        }
+        Ok(Self {
+            version,
+            l1_batch_number: L1BatchNumber(
+                *required(&r.l1_batch_number).context("l1_batch_number")?,
+            ),
+            miniblock_number: MiniblockNumber(
+                *required(&r.miniblock_number).context("miniblock_number")?,
+            ),
+            factory_deps_filepath: required(&r.factory_deps_filepath)
+                .context("factory_deps_filepath")?
+                .clone(),
+            storage_logs_chunks,
+        })
+    }
+
+    fn build(&self) -> Self::Proto {
+        Self::Proto {
+            version: Some(u16::from(self.version).into()),
+            l1_batch_number: Some(self.l1_batch_number.0),
+            miniblock_number: Some(self.miniblock_number.0),
+            factory_deps_filepath: Some(self.factory_deps_filepath.clone()),
+            storage_logs_chunks: self
+                .storage_logs_chunks
+                .iter()
+                .map(SnapshotStorageLogsChunkMetadata::build)
+                .collect(),
+        }
+    }
+}
+
 /// Status of snapshot recovery process stored in Postgres.
 #[derive(Debug, PartialEq)]
 pub struct SnapshotRecoveryStatus {
@@ -253,6 +372,32 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn snapshot_manifest_roundtrip() {
+        let manifest = SnapshotManifest {
+            version: SnapshotVersion::Version2,
+            l1_batch_number: L1BatchNumber(42),
+            miniblock_number: MiniblockNumber(100),
+            factory_deps_filepath: "factory_deps.proto.gzip".to_owned(),
+            storage_logs_chunks: (0..3)
+                .map(|chunk_id| SnapshotStorageLogsChunkMetadata {
+                    chunk_id,
+                    filepath: format!("chunk_{chunk_id}.proto.gzip"),
+                })
+                .collect(),
+        };
+        let mut proto = manifest.build();
+        let decoded = SnapshotManifest::read(&proto).unwrap();
+        assert_eq!(decoded, manifest);
+
+        proto.version = Some(1_000);
+        let err = SnapshotManifest::read(&proto).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unsupported snapshot version"),
+            "{err:#}"
+        );
+    }
+
     #[test]
     fn chunking_is_correct() {
         for chunks_count in (2..10).chain([42, 256, 500, 1_001, 12_345]) {
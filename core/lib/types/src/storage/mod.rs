@@ -98,6 +98,27 @@ pub fn get_is_account_key(account: &Address) -> StorageKey {
     StorageKey::new(deployer, key)
 }
 
+/// Nonce ordering of an account as stored in the `AccountInfo` struct of the deployer system contract.
+/// Accounts with arbitrary nonce ordering may have transactions with any unused nonce included in any order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+pub enum AccountNonceOrdering {
+    #[default]
+    Sequential,
+    Arbitrary,
+}
+
+impl AccountNonceOrdering {
+    /// Parses nonce ordering from the account info stored under [`get_is_account_key()`]. The info is packed
+    /// as `(supportedAAVersion: uint8, nonceOrdering: uint8)` in the lowest-order bytes of the slot.
+    pub fn from_account_info(account_info: H256) -> Self {
+        match account_info.as_bytes()[30] {
+            1 => Self::Arbitrary,
+            _ => Self::Sequential,
+        }
+    }
+}
+
 pub type StorageValue = H256;
 
 pub fn get_system_context_init_logs(chain_id: L2ChainId) -> Vec<StorageLog> {
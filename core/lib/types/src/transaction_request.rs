@@ -175,6 +175,9 @@ pub enum SerializationTransactionError {
     MalformedSignature,
     #[error("wrong chain id {}", .0.unwrap_or_default())]
     WrongChainId(Option<u64>),
+    /// Returned for legacy transactions without an EIP-155 chain ID if the node enforces replay protection.
+    #[error("only replay-protected (EIP-155) transactions are allowed")]
+    MissingChainId,
     #[error("malformed paymaster params")]
     MalforedPaymasterParams,
     #[error("factory dependency #{0} is invalid: {1}")]
@@ -28,6 +28,8 @@ pub struct TransactionExecutionResult {
     pub compressed_bytecodes: Vec<CompressedBytecodeInfo>,
     pub call_traces: Vec<Call>,
     pub revert_reason: Option<String>,
+    /// Raw data returned by the reverted transaction. Only set for transactions reverted by a contract.
+    pub revert_data: Option<Vec<u8>>,
 }
 
 impl TransactionExecutionResult {
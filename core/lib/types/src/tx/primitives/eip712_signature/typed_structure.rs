@@ -147,6 +147,14 @@ pub trait EIP712TypedStructure: Serialize {
     }
 }
 
+/// EIP-712 signing domain of L2 transactions.
+///
+/// Signed payloads of different chains are separated by the chain ID only. Adding a genesis-level chain salt to
+/// the domain (and to L2->L1 message hashing) to tell forked or cloned chains with the same chain ID apart is
+/// declined: the domain is checked by the bootloader and default account system contracts, and L2->L1 messages
+/// are hashed by the L1 messenger and proven against the L1 mailbox, none of which know about a salt. A salt
+/// applied by the server alone would only make valid transactions fail verification. Forks are expected to use
+/// a distinct chain ID, which the shared bridge already requires for every registered chain.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Eip712Domain {
@@ -1,7 +1,7 @@
 use std::ops::{Add, AddAssign};
 
 use crate::{
-    circuit::CircuitStatistic,
+    circuit::{CircuitStatistic, VmOperationStatistic},
     commitment::SerializeCommitment,
     fee::TransactionExecutionMetrics,
     l2_to_l1_log::L2ToL1Log,
@@ -71,6 +71,7 @@ pub struct ExecutionMetrics {
     pub computational_gas_used: u32,
     pub pubdata_published: u32,
     pub circuit_statistic: CircuitStatistic,
+    pub operation_statistic: VmOperationStatistic,
 }
 
 impl ExecutionMetrics {
@@ -89,6 +90,7 @@ impl ExecutionMetrics {
             computational_gas_used: tx_metrics.computational_gas_used,
             pubdata_published: tx_metrics.pubdata_published,
             circuit_statistic: tx_metrics.circuit_statistic,
+            operation_statistic: VmOperationStatistic::default(),
         }
     }
 
@@ -123,6 +125,7 @@ impl Add for ExecutionMetrics {
             computational_gas_used: self.computational_gas_used + other.computational_gas_used,
             pubdata_published: self.pubdata_published + other.pubdata_published,
             circuit_statistic: self.circuit_statistic + other.circuit_statistic,
+            operation_statistic: self.operation_statistic + other.operation_statistic,
         }
     }
 }
@@ -0,0 +1,366 @@
+//! Fault injection for chaos testing.
+//!
+//! Components call [`fault_point()`] at the places where faults can be injected (e.g., DAL queries,
+//! object store and L1 client calls). Faults are registered for a certain point using [`inject()`] or
+//! by running a [`ChaosScenario`]; a fault may add latency to the call, make it fail, or crash the calling task.
+//! This module and fault points are only compiled in if the `chaos` feature is enabled for the corresponding crate,
+//! so that production builds are not affected. The following fault points are available:
+//!
+//! - `dal.connection` when acquiring a DB connection, and `dal.{query_name}` for each instrumented DAL query
+//! - `object_store.{get|put|remove}.{bucket}` for object store operations
+//! - `eth_client.{method}` for L1 client calls, e.g. `eth_client.send_raw_tx`
+//!
+//! For the server, a scenario can be supplied as a JSON file via the `CHAOS_SCENARIO_PATH` env variable
+//! if the server is built with the `chaos` feature.
+//!
+//! The fault registry is global for the process. Hence, tests injecting faults should use point names
+//! not used by other tests in the same test binary, or run sequentially.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Fault that can be injected at a fault point.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Fault {
+    /// Delays the call by the specified number of milliseconds.
+    Latency { delay_ms: u64 },
+    /// Makes the call fail with an [`InjectedFault`] error.
+    Error { message: String },
+    /// Panics in the calling task.
+    Crash,
+}
+
+/// Fault together with the conditions of its triggering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultSpec {
+    #[serde(flatten)]
+    pub fault: Fault,
+    /// Number of hits of the fault point let through before the fault is triggered.
+    #[serde(default)]
+    pub skip: u32,
+    /// Max number of times the fault is triggered. If not set, the fault is triggered on each hit.
+    #[serde(default)]
+    pub times: Option<u32>,
+}
+
+impl From<Fault> for FaultSpec {
+    fn from(fault: Fault) -> Self {
+        Self {
+            fault,
+            skip: 0,
+            times: None,
+        }
+    }
+}
+
+/// Error returned from a fault point with an injected [`Fault::Error`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("injected fault at `{point}`: {message}")]
+pub struct InjectedFault {
+    pub point: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+struct ActiveFault {
+    spec: FaultSpec,
+    hits: u32,
+    triggered: u32,
+}
+
+impl ActiveFault {
+    /// Registers a hit and returns the fault if it should be triggered.
+    fn hit(&mut self) -> Option<Fault> {
+        self.hits += 1;
+        if self.hits <= self.spec.skip {
+            return None;
+        }
+        if self
+            .spec
+            .times
+            .map_or(false, |times| self.triggered >= times)
+        {
+            return None;
+        }
+        self.triggered += 1;
+        Some(self.spec.fault.clone())
+    }
+}
+
+fn registry() -> &'static Mutex<BTreeMap<String, ActiveFault>> {
+    static REGISTRY: OnceLock<Mutex<BTreeMap<String, ActiveFault>>> = OnceLock::new();
+    REGISTRY.get_or_init(Mutex::default)
+}
+
+/// Checks whether a registered point pattern matches the `point`. Patterns ending with `*` match all points
+/// with the specified prefix, e.g. `dal.*` matches all DAL queries.
+fn matches(pattern: &str, point: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => point.starts_with(prefix),
+        None => pattern == point,
+    }
+}
+
+/// Injects a fault at the specified point, replacing the previously injected fault (if any).
+pub fn inject(point: impl Into<String>, spec: impl Into<FaultSpec>) {
+    let point = point.into();
+    let spec = spec.into();
+    tracing::info!("Injecting fault {spec:?} at `{point}`");
+    let active_fault = ActiveFault {
+        spec,
+        hits: 0,
+        triggered: 0,
+    };
+    registry().lock().unwrap().insert(point, active_fault);
+}
+
+/// Removes the fault injected at the specified point. Returns `false` if there was no such fault.
+pub fn remove(point: &str) -> bool {
+    let removed = registry().lock().unwrap().remove(point).is_some();
+    if removed {
+        tracing::info!("Removed fault at `{point}`");
+    }
+    removed
+}
+
+/// Removes all injected faults.
+pub fn clear() {
+    registry().lock().unwrap().clear();
+}
+
+/// Returns the number of times the fault injected at the specified point was triggered.
+pub fn triggered_count(point: &str) -> u32 {
+    registry()
+        .lock()
+        .unwrap()
+        .get(point)
+        .map_or(0, |fault| fault.triggered)
+}
+
+/// Fault point. Applies a fault injected for `point` (if any). If several injected patterns match the point,
+/// the lexicographically smallest one is used.
+///
+/// # Errors
+///
+/// Returns an error if a [`Fault::Error`] is triggered.
+///
+/// # Panics
+///
+/// Panics if a [`Fault::Crash`] is triggered.
+pub async fn fault_point(point: &str) -> Result<(), InjectedFault> {
+    let fault = {
+        let mut registry = registry().lock().unwrap();
+        registry
+            .iter_mut()
+            .find(|(pattern, _)| matches(pattern, point))
+            .and_then(|(_, fault)| fault.hit())
+    };
+    let Some(fault) = fault else {
+        return Ok(());
+    };
+
+    tracing::info!("Triggered fault {fault:?} at `{point}`");
+    match fault {
+        Fault::Latency { delay_ms } => {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            Ok(())
+        }
+        Fault::Error { message } => Err(InjectedFault {
+            point: point.to_owned(),
+            message,
+        }),
+        Fault::Crash => panic!("injected crash at `{point}`"),
+    }
+}
+
+/// Step of a [`ChaosScenario`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChaosStep {
+    /// Delay since the scenario start after which the fault is injected, in milliseconds.
+    pub at_ms: u64,
+    /// Fault point (or a pattern ending with `*`) to inject the fault at.
+    pub point: String,
+    #[serde(flatten)]
+    pub spec: FaultSpec,
+    /// Time after which the fault is removed, in milliseconds. If not set, the fault remains injected.
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+/// Scenario injecting and removing faults on a timeline, e.g. "L1 is unavailable for 30 seconds,
+/// then the DB becomes slow". Can be deserialized from JSON:
+///
+/// ```json
+/// {
+///   "steps": [
+///     { "at_ms": 0, "point": "eth_client.send_raw_tx", "kind": "error", "message": "boom", "duration_ms": 30000 },
+///     { "at_ms": 30000, "point": "dal.*", "kind": "latency", "delay_ms": 500, "times": 100 }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChaosScenario {
+    pub steps: Vec<ChaosStep>,
+}
+
+impl ChaosScenario {
+    /// Runs the scenario, returning after all faults are injected and all faults with a duration are removed.
+    pub async fn run(self) {
+        enum Action {
+            Inject(FaultSpec),
+            Remove,
+        }
+
+        let mut events = Vec::with_capacity(self.steps.len() * 2);
+        for step in self.steps {
+            events.push((step.at_ms, step.point.clone(), Action::Inject(step.spec)));
+            if let Some(duration_ms) = step.duration_ms {
+                events.push((step.at_ms + duration_ms, step.point, Action::Remove));
+            }
+        }
+        // The sort is stable, so a removal scheduled at the same time as the injection goes after it.
+        events.sort_by_key(|(at_ms, ..)| *at_ms);
+
+        let started_at = tokio::time::Instant::now();
+        for (at_ms, point, action) in events {
+            tokio::time::sleep_until(started_at + Duration::from_millis(at_ms)).await;
+            match action {
+                Action::Inject(spec) => inject(point, spec),
+                Action::Remove => {
+                    remove(&point);
+                }
+            }
+        }
+        tracing::info!("Chaos scenario has finished");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn injecting_faults() {
+        fault_point("test.errors").await.unwrap();
+
+        let spec = FaultSpec {
+            fault: Fault::Error {
+                message: "boom".to_owned(),
+            },
+            skip: 1,
+            times: Some(2),
+        };
+        inject("test.errors", spec);
+        fault_point("test.errors").await.unwrap();
+        let err = fault_point("test.errors").await.unwrap_err();
+        assert_eq!(err.point, "test.errors");
+        assert_eq!(err.message, "boom");
+        fault_point("test.errors").await.unwrap_err();
+        fault_point("test.errors").await.unwrap();
+        assert_eq!(triggered_count("test.errors"), 2);
+
+        assert!(remove("test.errors"));
+        assert!(!remove("test.errors"));
+        assert_eq!(triggered_count("test.errors"), 0);
+    }
+
+    #[tokio::test]
+    async fn injecting_faults_by_pattern() {
+        inject(
+            "test.pattern.*",
+            Fault::Error {
+                message: "boom".to_owned(),
+            },
+        );
+        fault_point("test.pattern.first").await.unwrap_err();
+        fault_point("test.pattern.second").await.unwrap_err();
+        fault_point("test.other").await.unwrap();
+        assert_eq!(triggered_count("test.pattern.*"), 2);
+        remove("test.pattern.*");
+    }
+
+    #[tokio::test]
+    async fn selecting_fault_among_matching_patterns() {
+        inject("test.order.point", Fault::Latency { delay_ms: 0 });
+        inject(
+            "test.order.*",
+            Fault::Error {
+                message: "boom".to_owned(),
+            },
+        );
+        for _ in 0..3 {
+            fault_point("test.order.point").await.unwrap_err();
+        }
+        assert_eq!(triggered_count("test.order.*"), 3);
+        assert_eq!(triggered_count("test.order.point"), 0);
+        remove("test.order.*");
+        remove("test.order.point");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "injected crash at `test.crash`")]
+    async fn injecting_crash() {
+        inject("test.crash", Fault::Crash);
+        fault_point("test.crash").await.ok();
+    }
+
+    #[test]
+    fn deserializing_scenario() {
+        let scenario = r#"{
+            "steps": [
+                { "at_ms": 0, "point": "dal.*", "kind": "latency", "delay_ms": 500, "times": 10 },
+                { "at_ms": 100, "point": "object_store.put", "kind": "crash", "duration_ms": 1000 }
+            ]
+        }"#;
+        let scenario: ChaosScenario = serde_json::from_str(scenario).unwrap();
+        assert_eq!(
+            scenario.steps,
+            [
+                ChaosStep {
+                    at_ms: 0,
+                    point: "dal.*".to_owned(),
+                    spec: FaultSpec {
+                        fault: Fault::Latency { delay_ms: 500 },
+                        skip: 0,
+                        times: Some(10),
+                    },
+                    duration_ms: None,
+                },
+                ChaosStep {
+                    at_ms: 100,
+                    point: "object_store.put".to_owned(),
+                    spec: Fault::Crash.into(),
+                    duration_ms: Some(1_000),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_scenario() {
+        let scenario = ChaosScenario {
+            steps: vec![ChaosStep {
+                at_ms: 1_000,
+                point: "test.scenario".to_owned(),
+                spec: Fault::Error {
+                    message: "boom".to_owned(),
+                }
+                .into(),
+                duration_ms: Some(1_000),
+            }],
+        };
+        let scenario_task = tokio::spawn(scenario.run());
+
+        fault_point("test.scenario").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1_500)).await;
+        fault_point("test.scenario").await.unwrap_err();
+        scenario_task.await.unwrap();
+        fault_point("test.scenario").await.unwrap();
+    }
+}
@@ -1,6 +1,8 @@
 //! Various helpers used in the zkSync stack.
 
 pub mod bytecode;
+#[cfg(any(test, feature = "chaos"))]
+pub mod chaos;
 mod convert;
 pub mod http_with_retries;
 pub mod misc;
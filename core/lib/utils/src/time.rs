@@ -1,4 +1,12 @@
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
+#[cfg(any(test, feature = "testonly"))]
+use std::{cell::Cell, marker::PhantomData};
+
+#[cfg(any(test, feature = "testonly"))]
+thread_local! {
+    /// Current virtual time (as a duration since the UNIX epoch) if a [`VirtualClock`] is active on this thread.
+    static VIRTUAL_TIME: Cell<Option<Duration>> = Cell::new(None);
+}
 
 pub fn seconds_since_epoch() -> u64 {
     duration_since_epoch().as_secs()
@@ -13,7 +21,93 @@ pub fn millis_since_epoch() -> u128 {
 }
 
 fn duration_since_epoch() -> Duration {
+    #[cfg(any(test, feature = "testonly"))]
+    if let Some(virtual_time) = VIRTUAL_TIME.with(Cell::get) {
+        return virtual_time;
+    }
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("Incorrect system time")
 }
+
+/// Virtual clock for deterministic simulation tests.
+///
+/// While the clock is alive, functions in this module return virtual time instead of the system time, which only
+/// changes when the clock is explicitly advanced. The clock is thread-local and cannot be sent to other threads,
+/// so it should be used with a single-threaded runtime (e.g., the default one for `#[tokio::test]`); code running
+/// on other threads (e.g., in `spawn_blocking()`) still observes the system time.
+///
+/// Only available with the `testonly` feature.
+#[cfg(any(test, feature = "testonly"))]
+#[derive(Debug)]
+pub struct VirtualClock {
+    _not_send: PhantomData<*const ()>,
+}
+
+#[cfg(any(test, feature = "testonly"))]
+impl VirtualClock {
+    /// Starts a virtual clock at the specified time since the UNIX epoch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another virtual clock is active on the current thread.
+    pub fn new(start: Duration) -> Self {
+        VIRTUAL_TIME.with(|time| {
+            assert!(
+                time.get().is_none(),
+                "virtual clock is already active on this thread"
+            );
+            time.set(Some(start));
+        });
+        Self {
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Starts a virtual clock at the current system time.
+    pub fn from_system_time() -> Self {
+        Self::new(duration_since_epoch())
+    }
+
+    /// Returns the current virtual time since the UNIX epoch.
+    pub fn now(&self) -> Duration {
+        VIRTUAL_TIME
+            .with(Cell::get)
+            .expect("virtual clock is active")
+    }
+
+    /// Advances the clock by the specified duration.
+    pub fn advance(&self, duration: Duration) {
+        VIRTUAL_TIME.with(|time| time.set(Some(self.now() + duration)));
+    }
+}
+
+#[cfg(any(test, feature = "testonly"))]
+impl Drop for VirtualClock {
+    fn drop(&mut self) {
+        VIRTUAL_TIME.with(|time| time.set(None));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn virtual_clock_basics() {
+        let clock = VirtualClock::new(Duration::from_secs(1_000));
+        assert_eq!(seconds_since_epoch(), 1_000);
+        assert_eq!(millis_since(999), 1_000);
+
+        clock.advance(Duration::from_millis(1_500));
+        assert_eq!(millis_since_epoch(), 1_001_500);
+        assert_eq!(clock.now(), Duration::from_millis(1_001_500));
+
+        // Other threads observe the system time.
+        let system_time = std::thread::spawn(seconds_since_epoch).join().unwrap();
+        assert!(system_time > 1_000_000);
+
+        drop(clock);
+        assert!(seconds_since_epoch() > 1_000_000);
+    }
+}
@@ -1,14 +1,18 @@
+use std::time::Duration;
+
 use futures::{future, Future};
-use tokio::task::JoinHandle;
+use tokio::task::{JoinError, JoinHandle};
 
 use crate::panic_extractor::try_extract_panic_message;
 
-pub async fn wait_for_tasks<Fut>(
-    task_futures: Vec<JoinHandle<anyhow::Result<()>>>,
+pub async fn wait_for_tasks<I, Fut>(
+    task_futures: I,
     particular_crypto_alerts: Option<Vec<String>>,
     graceful_shutdown: Option<Fut>,
     tasks_allowed_to_finish: bool,
 ) where
+    I: IntoIterator,
+    I::Item: Future<Output = Result<anyhow::Result<()>, JoinError>> + Unpin,
     Fut: Future<Output = ()>,
 {
     match future::select_all(task_futures).await.0 {
@@ -64,3 +68,31 @@ pub async fn wait_for_tasks<Fut>(
         }
     }
 }
+
+/// Waits until all tasks that are still running terminate, but no longer than the specified timeout.
+/// Returns `false` if the timeout has elapsed before all tasks have terminated.
+pub async fn wait_for_tasks_termination(
+    task_handles: &mut [JoinHandle<anyhow::Result<()>>],
+    timeout: Duration,
+) -> bool {
+    let running_tasks = task_handles
+        .iter_mut()
+        .filter(|handle| !handle.is_finished());
+    let all_tasks = future::join_all(running_tasks);
+    match tokio::time::timeout(timeout, all_tasks).await {
+        Ok(results) => {
+            for result in results {
+                match result {
+                    Ok(Ok(())) => { /* task has terminated successfully */ }
+                    Ok(Err(err)) => tracing::warn!("Task terminated with error: {err:#}"),
+                    Err(err) => {
+                        let panic_message = try_extract_panic_message(err);
+                        tracing::warn!("Task panicked during shutdown: {panic_message}");
+                    }
+                }
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,390 @@
+//! Conformance checks for the VM: re-executing historical L1 batches under the VM version that originally produced
+//! them and comparing outputs with the ones persisted in Postgres. This allows detecting regressions in `multivm`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    ops::RangeInclusive,
+};
+
+use anyhow::Context as _;
+use multivm::interface::{FinishedL1Batch, L2BlockEnv, VmInterface};
+use tokio::runtime::Handle;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    zk_evm_types::LogQuery, Address, L1BatchNumber, L2ChainId, ProtocolVersionId, U256,
+};
+
+use crate::{create_vm, execute_tx};
+
+/// Mismatch between the re-executed and persisted final value of a storage slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageDiffMismatch {
+    pub address: Address,
+    pub key: U256,
+    /// Final slot value persisted in Postgres, or `None` if the slot wasn't changed in the batch.
+    pub expected: Option<U256>,
+    /// Final slot value after re-execution, or `None` if the slot wasn't changed.
+    pub actual: Option<U256>,
+}
+
+/// Mismatch between the re-executed and persisted events queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventsQueueMismatch {
+    /// Index of the first differing event in the queue.
+    pub index: usize,
+    pub expected_len: usize,
+    pub actual_len: usize,
+}
+
+/// Result of a conformance check for a single L1 batch.
+#[derive(Debug, Clone)]
+pub struct L1BatchConformanceReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: Option<ProtocolVersionId>,
+    pub storage_diff_mismatches: Vec<StorageDiffMismatch>,
+    /// `None` if the events queue matches.
+    pub events_queue_mismatch: Option<EventsQueueMismatch>,
+    /// Whether the events queue was checked; it may be missing in Postgres (e.g., for pre-boojum batches).
+    pub events_queue_checked: bool,
+}
+
+impl L1BatchConformanceReport {
+    pub fn is_conformant(&self) -> bool {
+        self.storage_diff_mismatches.is_empty() && self.events_queue_mismatch.is_none()
+    }
+}
+
+impl fmt::Display for L1BatchConformanceReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "L1 batch #{} (protocol version: {:?}): ",
+            self.l1_batch_number, self.protocol_version
+        )?;
+        if self.is_conformant() {
+            return formatter.write_str("OK");
+        }
+        if !self.storage_diff_mismatches.is_empty() {
+            write!(
+                formatter,
+                "{} storage diff mismatch(es), first: {:?}; ",
+                self.storage_diff_mismatches.len(),
+                self.storage_diff_mismatches[0]
+            )?;
+        }
+        if let Some(mismatch) = &self.events_queue_mismatch {
+            write!(formatter, "events queue mismatch: {mismatch:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Selects up to `sample_size` L1 batches evenly spaced across `range` (including both its ends if `sample_size > 1`).
+/// The selection is deterministic, so that a failing check can be reproduced.
+pub fn sample_l1_batches(
+    range: RangeInclusive<L1BatchNumber>,
+    sample_size: usize,
+) -> Vec<L1BatchNumber> {
+    let (start, end) = (u64::from(range.start().0), u64::from(range.end().0));
+    if start > end || sample_size == 0 {
+        return vec![];
+    }
+    let range_len = end - start + 1;
+    let sample_size = (sample_size as u64).min(range_len);
+    if sample_size == 1 {
+        return vec![*range.start()];
+    }
+
+    (0..sample_size)
+        .map(|i| {
+            let number = start + i * (range_len - 1) / (sample_size - 1);
+            L1BatchNumber(number as u32)
+        })
+        .collect()
+}
+
+/// Re-executes the specified L1 batch using transactions and batch params persisted in Postgres. The VM version
+/// is selected based on the protocol version of the batch.
+///
+/// This is a blocking function; it should be run in a blocking context (e.g., using `spawn_blocking()`).
+pub fn re_execute_l1_batch(
+    rt_handle: Handle,
+    l1_batch_number: L1BatchNumber,
+    mut connection: StorageProcessor<'_>,
+    l2_chain_id: L2ChainId,
+) -> anyhow::Result<FinishedL1Batch> {
+    let miniblocks_execution_data = rt_handle
+        .block_on(
+            connection
+                .transactions_dal()
+                .get_miniblocks_to_execute_for_l1_batch(l1_batch_number),
+        )
+        .with_context(|| format!("failed loading miniblocks for L1 batch #{l1_batch_number}"))?;
+    let (mut vm, _) = create_vm(rt_handle, l1_batch_number, connection, l2_chain_id)
+        .with_context(|| format!("failed creating VM for L1 batch #{l1_batch_number}"))?;
+
+    let next_miniblocks_data = miniblocks_execution_data
+        .iter()
+        .skip(1)
+        .map(Some)
+        .chain([None]);
+    let miniblocks_data = miniblocks_execution_data.iter().zip(next_miniblocks_data);
+    for (miniblock_data, next_miniblock_data) in miniblocks_data {
+        for tx in &miniblock_data.txs {
+            execute_tx(tx, &mut vm).with_context(|| {
+                format!(
+                    "failed executing transaction {:?} in miniblock #{}",
+                    tx.hash(),
+                    miniblock_data.number
+                )
+            })?;
+        }
+        if let Some(next_miniblock_data) = next_miniblock_data {
+            vm.start_new_l2_block(L2BlockEnv::from_miniblock_data(next_miniblock_data));
+        }
+    }
+    Ok(vm.finish_batch())
+}
+
+/// Re-executes the specified L1 batch and compares its storage diffs and events queue with the ones
+/// persisted in Postgres.
+pub async fn check_l1_batch_conformance(
+    pool: &ConnectionPool,
+    l1_batch_number: L1BatchNumber,
+    l2_chain_id: L2ChainId,
+) -> anyhow::Result<L1BatchConformanceReport> {
+    let mut storage = pool.access_storage_tagged("vm_conformance").await?;
+    let protocol_version = storage
+        .blocks_dal()
+        .get_batch_protocol_version_id(l1_batch_number)
+        .await?;
+    let expected_state_diffs = storage
+        .storage_logs_dal()
+        .get_l1_batch_state_diffs(l1_batch_number)
+        .await?;
+    let expected_state_diffs: StorageDiffs = expected_state_diffs
+        .into_iter()
+        .map(|diff| ((diff.address, diff.key), diff.final_value))
+        .collect();
+    let expected_events_queue = storage
+        .blocks_dal()
+        .get_events_queue(l1_batch_number)
+        .await?;
+    drop(storage);
+
+    let pool = pool.clone();
+    let finished_batch = tokio::task::spawn_blocking(move || {
+        let rt_handle = Handle::current();
+        let connection = rt_handle.block_on(pool.access_storage_tagged("vm_conformance"))?;
+        re_execute_l1_batch(rt_handle, l1_batch_number, connection, l2_chain_id)
+    })
+    .await
+    .context("L1 batch re-execution panicked")??;
+
+    let execution_state = &finished_batch.final_execution_state;
+    let actual_state_diffs =
+        storage_diffs_from_logs(&execution_state.deduplicated_storage_log_queries);
+    let events_queue_mismatch = expected_events_queue.as_ref().and_then(|expected| {
+        compare_events_queues(expected, &execution_state.deduplicated_events_logs)
+    });
+    Ok(L1BatchConformanceReport {
+        l1_batch_number,
+        protocol_version,
+        storage_diff_mismatches: compare_storage_diffs(&expected_state_diffs, &actual_state_diffs),
+        events_queue_mismatch,
+        events_queue_checked: expected_events_queue.is_some(),
+    })
+}
+
+type StorageDiffs = BTreeMap<(Address, U256), U256>;
+
+/// Extracts final values of slots changed in the batch from deduplicated storage logs.
+fn storage_diffs_from_logs(deduplicated_logs: &[LogQuery]) -> StorageDiffs {
+    deduplicated_logs
+        .iter()
+        .filter(|log| log.rw_flag && log.read_value != log.written_value)
+        .map(|log| ((log.address, log.key), log.written_value))
+        .collect()
+}
+
+fn compare_storage_diffs(
+    expected: &StorageDiffs,
+    actual: &StorageDiffs,
+) -> Vec<StorageDiffMismatch> {
+    let all_keys: BTreeSet<_> = expected.keys().chain(actual.keys()).collect();
+    all_keys
+        .into_iter()
+        .filter_map(|&(address, key)| {
+            let expected = expected.get(&(address, key)).copied();
+            let actual = actual.get(&(address, key)).copied();
+            (expected != actual).then_some(StorageDiffMismatch {
+                address,
+                key,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+fn compare_events_queues(
+    expected: &[LogQuery],
+    actual: &[LogQuery],
+) -> Option<EventsQueueMismatch> {
+    let first_mismatch = expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual);
+    let index = first_mismatch
+        .or_else(|| (expected.len() != actual.len()).then_some(expected.len().min(actual.len())))?;
+    Some(EventsQueueMismatch {
+        index,
+        expected_len: expected.len(),
+        actual_len: actual.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::zk_evm_types::Timestamp;
+
+    use super::*;
+
+    fn write_log(address: Address, key: u64, read_value: u64, written_value: u64) -> LogQuery {
+        LogQuery {
+            timestamp: Timestamp(0),
+            tx_number_in_block: 0,
+            aux_byte: 0,
+            shard_id: 0,
+            address,
+            key: key.into(),
+            read_value: read_value.into(),
+            written_value: written_value.into(),
+            rw_flag: true,
+            rollback: false,
+            is_service: false,
+        }
+    }
+
+    #[test]
+    fn sampling_l1_batches() {
+        let range = L1BatchNumber(1)..=L1BatchNumber(100);
+        assert!(sample_l1_batches(range.clone(), 0).is_empty());
+        assert_eq!(sample_l1_batches(range.clone(), 1), [L1BatchNumber(1)]);
+        assert_eq!(
+            sample_l1_batches(range.clone(), 2),
+            [L1BatchNumber(1), L1BatchNumber(100)]
+        );
+        let sample = sample_l1_batches(range.clone(), 4);
+        assert_eq!(sample, [1, 34, 67, 100].map(L1BatchNumber), "{sample:?}");
+
+        let sample = sample_l1_batches(L1BatchNumber(5)..=L1BatchNumber(7), 10);
+        assert_eq!(sample, [5, 6, 7].map(L1BatchNumber));
+        assert!(sample_l1_batches(L1BatchNumber(5)..=L1BatchNumber(4), 10).is_empty());
+    }
+
+    #[test]
+    fn comparing_storage_diffs() {
+        let address = Address::repeat_byte(1);
+        let logs = [
+            write_log(address, 0, 0, 1),
+            // No-op write; should be ignored
+            write_log(address, 1, 5, 5),
+            write_log(address, 2, 3, 4),
+            LogQuery {
+                rw_flag: false,
+                ..write_log(address, 3, 1, 2)
+            },
+        ];
+        let actual = storage_diffs_from_logs(&logs);
+        assert_eq!(actual.len(), 2);
+
+        let mut expected = actual.clone();
+        assert!(compare_storage_diffs(&expected, &actual).is_empty());
+
+        expected.insert((address, 2.into()), 5.into());
+        expected.insert((address, 10.into()), 1.into());
+        let mismatches = compare_storage_diffs(&expected, &actual);
+        assert_eq!(
+            mismatches,
+            [
+                StorageDiffMismatch {
+                    address,
+                    key: 2.into(),
+                    expected: Some(5.into()),
+                    actual: Some(4.into()),
+                },
+                StorageDiffMismatch {
+                    address,
+                    key: 10.into(),
+                    expected: Some(1.into()),
+                    actual: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn comparing_events_queues() {
+        let address = Address::repeat_byte(1);
+        let events: Vec<_> = (0..5).map(|i| write_log(address, i, 0, i)).collect();
+        assert_eq!(compare_events_queues(&events, &events), None);
+
+        let mismatch = compare_events_queues(&events, &events[..3]).unwrap();
+        assert_eq!(
+            mismatch,
+            EventsQueueMismatch {
+                index: 3,
+                expected_len: 5,
+                actual_len: 3,
+            }
+        );
+
+        let mut modified_events = events.clone();
+        modified_events[1].written_value = 100.into();
+        let mismatch = compare_events_queues(&events, &modified_events).unwrap();
+        assert_eq!(mismatch.index, 1);
+    }
+
+    /// Long-running check against a Postgres instance with historical L1 batches (e.g., an archive node snapshot).
+    /// Run with `VM_CONFORMANCE_DATABASE_URL=... cargo test -p vm_utils -- --ignored`; the sample size
+    /// and chain ID can be overridden with `VM_CONFORMANCE_SAMPLE_SIZE` and `VM_CONFORMANCE_L2_CHAIN_ID`.
+    #[tokio::test]
+    #[ignore]
+    async fn historical_l1_batches_are_conformant() {
+        let database_url = std::env::var("VM_CONFORMANCE_DATABASE_URL")
+            .expect("VM_CONFORMANCE_DATABASE_URL must be set");
+        let env_or = |name: &str, default: u64| {
+            std::env::var(name).map_or(default, |value| value.parse().expect(name))
+        };
+        let sample_size = env_or("VM_CONFORMANCE_SAMPLE_SIZE", 10) as usize;
+        let l2_chain_id = L2ChainId::try_from(env_or("VM_CONFORMANCE_L2_CHAIN_ID", 270)).unwrap();
+
+        let pool = ConnectionPool::builder(&database_url, 2)
+            .build()
+            .await
+            .unwrap();
+        let mut storage = pool.access_storage().await.unwrap();
+        let earliest_batch = storage
+            .blocks_dal()
+            .get_earliest_l1_batch_number()
+            .await
+            .unwrap()
+            .expect("no L1 batches");
+        let latest_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap()
+            .expect("no L1 batches");
+        drop(storage);
+
+        for l1_batch_number in sample_l1_batches(earliest_batch + 1..=latest_batch, sample_size) {
+            let report = check_l1_batch_conformance(&pool, l1_batch_number, l2_chain_id)
+                .await
+                .unwrap();
+            assert!(report.is_conformant(), "{report}");
+        }
+    }
+}
@@ -1,15 +1,16 @@
+pub mod conformance;
 pub mod storage;
 
 use anyhow::{anyhow, Context};
 use multivm::{
-    interface::{VmInterface, VmInterfaceHistoryEnabled},
+    interface::{VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled},
     vm_latest::HistoryEnabled,
     VmInstance,
 };
 use tokio::runtime::Handle;
 use zksync_dal::StorageProcessor;
 use zksync_state::{PostgresStorage, StoragePtr, StorageView, WriteStorage};
-use zksync_types::{L1BatchNumber, L2ChainId, Transaction};
+use zksync_types::{L1BatchNumber, L2ChainId, ProtocolVersionId, Transaction};
 
 use crate::storage::L1BatchParamsProvider;
 
@@ -19,10 +20,39 @@ pub type VmAndStorage<'a> = (
 );
 
 pub fn create_vm(
+    rt_handle: Handle,
+    l1_batch_number: L1BatchNumber,
+    connection: StorageProcessor<'_>,
+    l2_chain_id: L2ChainId,
+) -> anyhow::Result<VmAndStorage> {
+    create_vm_inner(rt_handle, l1_batch_number, connection, l2_chain_id, None)
+}
+
+/// Same as [`create_vm()`], but the VM executes the batch with the specified protocol version (i.e., with the VM
+/// implementation and base system contracts corresponding to this version) rather than the version
+/// the batch was originally executed with. The protocol version must be persisted in Postgres.
+pub fn create_vm_with_protocol_version(
+    rt_handle: Handle,
+    l1_batch_number: L1BatchNumber,
+    connection: StorageProcessor<'_>,
+    l2_chain_id: L2ChainId,
+    protocol_version: ProtocolVersionId,
+) -> anyhow::Result<VmAndStorage> {
+    create_vm_inner(
+        rt_handle,
+        l1_batch_number,
+        connection,
+        l2_chain_id,
+        Some(protocol_version),
+    )
+}
+
+fn create_vm_inner(
     rt_handle: Handle,
     l1_batch_number: L1BatchNumber,
     mut connection: StorageProcessor<'_>,
     l2_chain_id: L2ChainId,
+    protocol_version: Option<ProtocolVersionId>,
 ) -> anyhow::Result<VmAndStorage> {
     let l1_batch_params_provider = rt_handle
         .block_on(L1BatchParamsProvider::new(&mut connection))
@@ -40,7 +70,7 @@ pub fn create_vm(
     // This means we don't want to reject any execution, therefore we're using MAX as an allow all.
     let validation_computational_gas_limit = u32::MAX;
 
-    let (system_env, l1_batch_env) = rt_handle
+    let (mut system_env, l1_batch_env) = rt_handle
         .block_on(l1_batch_params_provider.load_l1_batch_params(
             &mut connection,
             &first_miniblock_in_batch,
@@ -49,6 +79,18 @@ pub fn create_vm(
         ))
         .context("expected miniblock to be executed and sealed")?;
 
+    if let Some(protocol_version) = protocol_version {
+        let base_system_contracts = rt_handle
+            .block_on(
+                connection
+                    .protocol_versions_dal()
+                    .load_base_system_contracts_by_version_id(protocol_version as u16),
+            )?
+            .with_context(|| format!("protocol version {protocol_version:?} is not persisted"))?;
+        system_env.version = protocol_version;
+        system_env.base_system_smart_contracts = base_system_contracts;
+    }
+
     let storage_miniblock_number = first_miniblock_in_batch.number() - 1;
     let pg_storage = PostgresStorage::new(
         rt_handle.clone(),
@@ -62,29 +104,26 @@ pub fn create_vm(
     Ok((vm, storage_view))
 }
 
+/// Executes a transaction in the VM and returns the execution result.
 pub fn execute_tx<S: WriteStorage>(
     tx: &Transaction,
     vm: &mut VmInstance<S, HistoryEnabled>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<VmExecutionResultAndLogs> {
     // Attempt to run VM with bytecode compression on.
     vm.make_snapshot();
-    if vm
-        .execute_transaction_with_bytecode_compression(tx.clone(), true)
-        .0
-        .is_ok()
-    {
+    let (compression_result, result) =
+        vm.execute_transaction_with_bytecode_compression(tx.clone(), true);
+    if compression_result.is_ok() {
         vm.pop_snapshot_no_rollback();
-        return Ok(());
+        return Ok(result);
     }
 
     // If failed with bytecode compression, attempt to run without bytecode compression.
     vm.rollback_to_the_latest_snapshot();
-    if vm
-        .execute_transaction_with_bytecode_compression(tx.clone(), false)
-        .0
-        .is_err()
-    {
+    let (compression_result, result) =
+        vm.execute_transaction_with_bytecode_compression(tx.clone(), false);
+    if compression_result.is_err() {
         return Err(anyhow!("compression can't fail if we don't apply it"));
     }
-    Ok(())
+    Ok(result)
 }
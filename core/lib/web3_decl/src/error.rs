@@ -13,8 +13,9 @@ use std::{
 
 use jsonrpsee::core::ClientError;
 use pin_project_lite::pin_project;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use zksync_types::{api::SerializationTransactionError, L1BatchNumber, MiniblockNumber};
+use zksync_types::{api::SerializationTransactionError, Bytes, L1BatchNumber, MiniblockNumber};
 
 #[derive(Debug, Error)]
 pub enum Web3Error {
@@ -35,7 +36,7 @@ pub enum Web3Error {
     #[error("Invalid transaction data: {0}")]
     InvalidTransactionData(#[from] zksync_types::ethabi::Error),
     #[error("{0}")]
-    SubmitTransactionError(String, Vec<u8>),
+    SubmitTransactionError(String, Option<TxErrorData>),
     #[error("Failed to serialize transaction: {0}")]
     SerializationError(#[from] SerializationTransactionError),
     #[error("Invalid fee parameters: {0}")]
@@ -54,6 +55,130 @@ pub enum Web3Error {
     InvalidFilterBlockHash,
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error("Response signing is not enabled on this node")]
+    ResponseSigningDisabled,
+    #[error("Too many requests")]
+    TooManyRequests,
+    #[error(
+        "Block contains {0} transactions, which exceeds the tracing limit of {1} transactions"
+    )]
+    TooManyTracedTransactions(usize, usize),
+}
+
+/// Stable code of a transaction submission or execution error. Returned in the `data` field of JSON-RPC errors
+/// (see [`TxErrorData`]), so that clients can handle errors without parsing error messages.
+///
+/// Codes are serialized in `snake_case`. Codes are never renamed or removed; new codes may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxErrorCode {
+    NonceTooHigh,
+    NonceTooLow,
+    /// The transaction is malformed (e.g., has an invalid signature or exceeds field limits).
+    IncorrectTx,
+    InsufficientBalanceForFee,
+    InsufficientFundsForTransfer,
+    ExecutionReverted,
+    /// The transaction halted during execution in a way not covered by other codes.
+    Unexecutable,
+    GasLimitTooBig,
+    IntrinsicGasTooLow,
+    MaxFeePerGasTooLow,
+    MaxPriorityFeeGreaterThanMaxFee,
+    FeePerGasTooHigh,
+    FeePerPubdataByteTooHigh,
+    UnrealisticPubdataPriceLimit,
+    TooManyFactoryDeps,
+    TxTooLarge,
+    FromIsNotAnAccount,
+    AccountValidationFailed,
+    FailedToChargeFee,
+    PaymasterValidationFailed,
+    PrePaymasterPreparationFailed,
+    BootloaderFailure,
+    FailedToPublishCompressedBytecodes,
+    UnexpectedVmBehavior,
+    DeadlinePassed,
+    NotSponsored,
+    ExecutionTimeout,
+    RateLimitExceeded,
+    ServerShuttingDown,
+    /// The node doesn't accept transactions (e.g., it runs in the read-only forensics mode).
+    SubmissionDisabled,
+    /// The chain is frozen (emergency mode) and doesn't accept transactions.
+    ChainFrozen,
+    /// L1 data used to price transactions is stale, so transactions are temporarily not accepted.
+    L1DataStale,
+}
+
+/// Data payload of a transaction submission or execution error returned in the `data` field of the JSON-RPC error.
+///
+/// For [`TxErrorCode::ExecutionReverted`], the payload is the hex-encoded revert data (e.g., `"0x08c379a0..."`),
+/// for compatibility with Ethereum tooling. For other codes, the payload is an object with the error code,
+/// e.g. `{ "code": "nonce_too_low" }`. Clients that haven't opted into error codes receive the legacy payload
+/// (see [`Self::to_legacy_json()`]) instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxErrorData {
+    pub code: TxErrorCode,
+    /// Revert data; only non-empty for [`TxErrorCode::ExecutionReverted`].
+    pub revert_data: Vec<u8>,
+}
+
+impl From<TxErrorCode> for TxErrorData {
+    fn from(code: TxErrorCode) -> Self {
+        Self {
+            code,
+            revert_data: Vec::new(),
+        }
+    }
+}
+
+impl TxErrorData {
+    /// Creates data for a reverted execution.
+    pub fn revert(revert_data: Vec<u8>) -> Self {
+        Self {
+            code: TxErrorCode::ExecutionReverted,
+            revert_data,
+        }
+    }
+
+    /// Converts this data to the JSON payload.
+    pub fn to_json(&self) -> serde_json::Value {
+        #[derive(Serialize)]
+        struct CodeObject {
+            code: TxErrorCode,
+        }
+
+        let value = if self.code == TxErrorCode::ExecutionReverted {
+            serde_json::to_value(Bytes(self.revert_data.clone()))
+        } else {
+            serde_json::to_value(CodeObject { code: self.code })
+        };
+        value.expect("failed serializing error data")
+    }
+
+    /// Converts this data to the legacy JSON payload: hex-encoded revert data, which is empty (`"0x"`)
+    /// for errors other than reverts.
+    pub fn to_legacy_json(&self) -> serde_json::Value {
+        serde_json::to_value(Bytes(self.revert_data.clone()))
+            .expect("failed serializing error data")
+    }
+
+    /// Parses data from the JSON payload. Returns `None` if the payload has an unexpected format.
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct CodeObject {
+            code: TxErrorCode,
+        }
+
+        if value.is_string() {
+            let Bytes(revert_data) = serde_json::from_value(value.clone()).ok()?;
+            Some(Self::revert(revert_data))
+        } else {
+            let object: CodeObject = serde_json::from_value(value.clone()).ok()?;
+            Some(object.code.into())
+        }
+    }
 }
 
 /// Client RPC error with additional details: the method name and arguments of the called method.
@@ -204,3 +329,35 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tx_error_data_serialization() {
+        let data = TxErrorData::from(TxErrorCode::NonceTooLow);
+        let json = data.to_json();
+        assert_eq!(json, serde_json::json!({ "code": "nonce_too_low" }));
+        assert_eq!(TxErrorData::from_json(&json), Some(data));
+
+        let data = TxErrorData::revert(vec![0x08, 0xc3, 0x79, 0xa0]);
+        let json = data.to_json();
+        assert_eq!(json, serde_json::json!("0x08c379a0"));
+        assert_eq!(TxErrorData::from_json(&json), Some(data));
+
+        let data = TxErrorData::revert(vec![]);
+        assert_eq!(TxErrorData::from_json(&data.to_json()), Some(data));
+
+        let data = TxErrorData::from(TxErrorCode::NonceTooLow);
+        assert_eq!(data.to_legacy_json(), serde_json::json!("0x"));
+        let data = TxErrorData::revert(vec![0x08, 0xc3, 0x79, 0xa0]);
+        assert_eq!(data.to_legacy_json(), serde_json::json!("0x08c379a0"));
+
+        assert_eq!(
+            TxErrorData::from_json(&serde_json::json!({ "code": "unknown" })),
+            None
+        );
+        assert_eq!(TxErrorData::from_json(&serde_json::json!(42)), None);
+    }
+}
@@ -0,0 +1,28 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::chain_freeze::ChainFreezeEvent;
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "admin")
+)]
+pub trait AdminNamespace {
+    /// Freezes the chain: transactions are no longer accepted, and batch sealing stops after the current L1 batch.
+    #[method(name = "freezeChain")]
+    async fn freeze_chain(&self, reason: Option<String>) -> RpcResult<ChainFreezeEvent>;
+
+    /// Unfreezes the chain, resuming transaction intake and batch sealing.
+    #[method(name = "unfreezeChain")]
+    async fn unfreeze_chain(&self, reason: Option<String>) -> RpcResult<ChainFreezeEvent>;
+
+    /// Returns the latest chain freeze state change, or `null` if the chain has never been frozen.
+    #[method(name = "getChainFreezeStatus")]
+    async fn get_chain_freeze_status(&self) -> RpcResult<Option<ChainFreezeEvent>>;
+}
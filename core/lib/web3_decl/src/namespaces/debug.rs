@@ -1,10 +1,10 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, LogsQueryExplanation, ResultDebugCall, TracerConfig},
     transaction_request::CallRequest,
 };
 
-use crate::types::H256;
+use crate::types::{Filter, H256};
 
 #[cfg_attr(
     all(feature = "client", feature = "server"),
@@ -44,4 +44,12 @@ pub trait DebugNamespace {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> RpcResult<Option<DebugCall>>;
+    /// Explains how logs matching the filter are retrieved from the storage. If `benchmark` is set,
+    /// additionally runs the query with each applicable strategy and reports the actual latencies.
+    #[method(name = "explainLogsQuery")]
+    async fn explain_logs_query(
+        &self,
+        filter: Filter,
+        benchmark: Option<bool>,
+    ) -> RpcResult<LogsQueryExplanation>;
 }
@@ -1,18 +1,27 @@
 use std::collections::HashMap;
 
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        ApiCapabilities, BlobUtilizationReport, BlockCommitmentProof, BlockDetails,
+        BlockHashPreimage, BlockOrderingInfo, BridgeAddresses, ChainParameters, ContractAddresses,
+        GasPriceHistoryEntry, L1BatchCommitmentDetails, L1BatchDetails, L1BatchL1Cost,
+        L1BatchPublicInputs, L1BatchStateDiffs, L1BatchStoredInfo, L1TransactionSimulation,
+        L1TransactionSimulationOptions, L2ToL1LogProof, NodeInfo, Proof, ProtocolVersion,
+        SignedResponse, SolvencyReport, StorageExitProof, SystemContext, TransactionAddressRole,
+        TransactionDetails, TransactionsByAddressCursor, TransactionsByAddressPage, ValidUntil,
     },
-    fee::Fee,
+    fee::{FeeEstimate, FeeReport},
     fee_model::FeeParams,
+    l2::SignatureScheme,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
 
-use crate::types::Token;
+use crate::types::{PubSubResult, StorageSlot, Token};
 
 #[cfg_attr(
     all(feature = "client", feature = "server"),
@@ -27,12 +36,30 @@ use crate::types::Token;
     rpc(server, namespace = "zks")
 )]
 pub trait ZksNamespace {
+    /// Estimates the fee for a transaction. If `include_breakdown` is set, the response additionally contains
+    /// a breakdown of the estimated gas limit.
     #[method(name = "estimateFee")]
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
+    async fn estimate_fee(
+        &self,
+        req: CallRequest,
+        include_breakdown: Option<bool>,
+    ) -> RpcResult<FeeEstimate>;
+
+    #[method(name = "getFeeReport")]
+    async fn get_fee_report(&self, req: CallRequest) -> RpcResult<FeeReport>;
 
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
 
+    /// Simulates a priority operation (an L1 -> L2 transaction) as if it was requested on L1, without submitting it.
+    /// Returns the execution result and the L2 gas limit required for the operation to succeed.
+    #[method(name = "simulateL1Transaction")]
+    async fn simulate_l1_transaction(
+        &self,
+        req: CallRequest,
+        options: Option<L1TransactionSimulationOptions>,
+    ) -> RpcResult<L1TransactionSimulation>;
+
     #[method(name = "getBridgehubContract")]
     async fn get_bridgehub_contract(&self) -> RpcResult<Option<Address>>;
 
@@ -48,6 +75,20 @@ pub trait ZksNamespace {
     #[method(name = "L1ChainId")]
     async fn l1_chain_id(&self) -> RpcResult<U64>;
 
+    #[method(name = "getContractAddresses")]
+    async fn get_contract_addresses(&self) -> RpcResult<ContractAddresses>;
+
+    #[method(name = "getChainParameters")]
+    async fn get_chain_parameters(&self) -> RpcResult<ChainParameters>;
+
+    #[method(name = "getNodeInfo")]
+    async fn get_node_info(&self) -> RpcResult<NodeInfo>;
+
+    /// Returns API capabilities for the latest protocol version and the API level declared by the client
+    /// via the `zksync-api-level` HTTP header.
+    #[method(name = "getApiCapabilities")]
+    async fn get_api_capabilities(&self) -> RpcResult<ApiCapabilities>;
+
     #[method(name = "getConfirmedTokens")]
     async fn get_confirmed_tokens(&self, from: u32, limit: u8) -> RpcResult<Vec<Token>>;
 
@@ -92,10 +133,101 @@ pub trait ZksNamespace {
         block_number: MiniblockNumber,
     ) -> RpcResult<Vec<zksync_types::Transaction>>;
 
+    #[method(name = "getTransactionsByAddress")]
+    async fn get_transactions_by_address(
+        &self,
+        address: Address,
+        role: TransactionAddressRole,
+        cursor: Option<TransactionsByAddressCursor>,
+    ) -> RpcResult<TransactionsByAddressPage>;
+
+    /// Returns executed L2 transactions with the specified signature scheme, ordered from newest to oldest.
+    #[method(name = "getTransactionsBySignatureScheme")]
+    async fn get_transactions_by_signature_scheme(
+        &self,
+        scheme: SignatureScheme,
+        cursor: Option<TransactionsByAddressCursor>,
+    ) -> RpcResult<TransactionsByAddressPage>;
+
     #[method(name = "getL1BatchDetails")]
     async fn get_l1_batch_details(&self, batch: L1BatchNumber)
         -> RpcResult<Option<L1BatchDetails>>;
 
+    /// Returns the base token solvency report for the specified L1 batch, or for the last reported batch
+    /// if the batch is not specified. Reports are produced by the solvency reporter on the main node.
+    #[method(name = "getSolvencyReport")]
+    async fn get_solvency_report(
+        &self,
+        batch: Option<L1BatchNumber>,
+    ) -> RpcResult<Option<SolvencyReport>>;
+
+    /// Returns the blob utilization report for up to `limit` latest commit transactions publishing pubdata
+    /// in blobs.
+    #[method(name = "getBlobUtilizationReport")]
+    async fn get_blob_utilization_report(
+        &self,
+        limit: Option<usize>,
+    ) -> RpcResult<BlobUtilizationReport>;
+
+    #[method(name = "getBatchStateDiffs")]
+    async fn get_batch_state_diffs(
+        &self,
+        batch: L1BatchNumber,
+        include_values: Option<bool>,
+    ) -> RpcResult<Option<L1BatchStateDiffs>>;
+
+    /// Returns public inputs of the proof for the specified L1 batch, or `None` if the batch doesn't have
+    /// a commitment yet.
+    #[method(name = "getL1BatchPublicInputs")]
+    async fn get_l1_batch_public_inputs(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchPublicInputs>>;
+
+    /// Returns the ABI-encoded `StoredBatchInfo` for the specified L1 batch as stored by the chain contract on L1,
+    /// or `None` if the batch is not committed on L1 yet.
+    #[method(name = "getL1BatchStoredInfo")]
+    async fn get_l1_batch_stored_info(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchStoredInfo>>;
+
+    /// Returns information on how the specified L1 batch was committed on L1 (pubdata DA mode, published blobs,
+    /// encoded commit data and L1 transaction hashes), or `None` if the batch is not committed on L1 yet.
+    #[method(name = "getL1BatchCommitmentDetails")]
+    async fn get_l1_batch_commitment_details(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchCommitmentDetails>>;
+
+    /// Returns actual L1 costs of confirmed commit, prove and execute transactions for the specified L1 batch,
+    /// as reported by their L1 receipts. Transactions with costs not yet recorded are omitted.
+    #[method(name = "getL1BatchL1Costs")]
+    async fn get_l1_batch_l1_costs(&self, batch: L1BatchNumber) -> RpcResult<Vec<L1BatchL1Cost>>;
+
+    /// Returns arrival information for transactions in the specified block, which allows to audit
+    /// fair ordering of transactions.
+    #[method(name = "getBlockOrderingInfo")]
+    async fn get_block_ordering_info(
+        &self,
+        block: MiniblockNumber,
+    ) -> RpcResult<Option<BlockOrderingInfo>>;
+
+    /// Returns header fields from which the hash of the specified block is derived.
+    #[method(name = "getBlockHashPreimage")]
+    async fn get_block_hash_preimage(
+        &self,
+        block: MiniblockNumber,
+    ) -> RpcResult<Option<BlockHashPreimage>>;
+
+    /// Returns a proof linking the specified block to the commitment of its L1 batch, or `None` if the batch
+    /// doesn't have a commitment yet.
+    #[method(name = "getBlockCommitmentProof")]
+    async fn get_block_commitment_proof(
+        &self,
+        block: MiniblockNumber,
+    ) -> RpcResult<Option<BlockCommitmentProof>>;
+
     #[method(name = "getBytecodeByHash")]
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>>;
 
@@ -105,6 +237,20 @@ pub trait ZksNamespace {
     #[method(name = "getFeeParams")]
     async fn get_fee_params(&self) -> RpcResult<FeeParams>;
 
+    /// Returns fee inputs for at most `block_count` miniblocks starting from `from_block` (inclusive).
+    /// `block_count` is capped by the server, similarly to `eth_feeHistory`.
+    #[method(name = "gasPriceHistory")]
+    async fn gas_price_history(
+        &self,
+        from_block: MiniblockNumber,
+        block_count: U64,
+    ) -> RpcResult<Vec<GasPriceHistoryEntry>>;
+
+    /// Returns system context values (block and batch numbers, timestamps, fee inputs etc.) that the VM
+    /// observed when executing the specified miniblock. Returns `None` if the miniblock is not sealed yet.
+    #[method(name = "getSystemContext")]
+    async fn get_system_context(&self, block: MiniblockNumber) -> RpcResult<Option<SystemContext>>;
+
     #[method(name = "getProtocolVersion")]
     async fn get_protocol_version(
         &self,
@@ -118,4 +264,81 @@ pub trait ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Proof>;
+
+    /// Same as `getL1BatchDetails`, but the response is signed by the node operator. The signature covers
+    /// the method name, chain ID, request params and the canonical encoding of the response
+    /// (see `SignedResponse::digest()`). Returns an error if response signing is not enabled on the node.
+    #[method(name = "getSignedL1BatchDetails")]
+    async fn get_signed_l1_batch_details(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<SignedResponse<L1BatchDetails>>>;
+
+    /// Same as `getL2ToL1MsgProof`, but the response is signed by the node operator.
+    #[method(name = "getSignedL2ToL1MsgProof")]
+    async fn get_signed_l2_to_l1_msg_proof(
+        &self,
+        block: MiniblockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> RpcResult<Option<SignedResponse<L2ToL1LogProof>>>;
+
+    /// Same as `getL2ToL1LogProof`, but the response is signed by the node operator.
+    #[method(name = "getSignedL2ToL1LogProof")]
+    async fn get_signed_l2_to_l1_log_proof(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> RpcResult<Option<SignedResponse<L2ToL1LogProof>>>;
+
+    /// Same as `getProof`, but the response is signed by the node operator.
+    #[method(name = "getSignedProof")]
+    async fn get_signed_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<SignedResponse<Proof>>;
+
+    /// Returns a proof of the storage slot value in the specified L1 batch together with data linking it
+    /// to the batch stored on L1. Returns `None` if the batch is not executed on L1 yet, or if the slot
+    /// is not modified in the batch.
+    #[method(name = "getStorageExitProof")]
+    async fn get_storage_exit_proof(
+        &self,
+        address: Address,
+        key: H256,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<StorageExitProof>>;
+
+    /// Same as `eth_sendRawTransaction`, but the transaction is dropped from the mempool
+    /// if it's not included into a miniblock until the specified deadline.
+    #[method(name = "sendRawTransactionWithDeadline")]
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: ValidUntil,
+    ) -> RpcResult<H256>;
+}
+
+#[rpc(server, namespace = "zks")]
+pub trait ZksPubSub {
+    /// Subscribes to changes of the specified storage slots. A notification is sent for each miniblock
+    /// in which a slot value has changed.
+    #[subscription(
+        name = "subscribeStorage" => "storageSubscription",
+        unsubscribe = "unsubscribeStorage",
+        item = PubSubResult
+    )]
+    async fn subscribe_storage(&self, slots: Vec<StorageSlot>) -> SubscriptionResult;
+
+    /// Resumes a subscription exported by another API server replica on shutdown. Notifications for miniblocks
+    /// sealed since the subscription was exported are replayed before live notifications.
+    #[subscription(
+        name = "resumeSubscription" => "subscription",
+        unsubscribe = "unsubscribeResumed",
+        item = PubSubResult
+    )]
+    async fn resume_subscription(&self, subscription_id: String) -> SubscriptionResult;
 }
@@ -257,27 +257,37 @@ pub struct PubSubFilter {
 }
 
 impl PubSubFilter {
+    /// Checks whether the log matches this filter according to the Ethereum spec: addresses are OR-ed, and each
+    /// topic position is AND-ed with alternatives at the same position OR-ed. `null` or an empty array at
+    /// a certain position (or an empty address list) matches any value.
     pub fn matches(&self, log: &Log) -> bool {
         if let Some(addresses) = &self.address {
-            if !addresses.0.contains(&log.address) {
+            if !addresses.0.is_empty() && !addresses.0.contains(&log.address) {
                 return false;
             }
         }
         if let Some(all_topics) = &self.topics {
             for (idx, expected_topics) in all_topics.iter().enumerate() {
-                if let Some(expected_topics) = expected_topics {
-                    if let Some(actual_topic) = log.topics.get(idx) {
-                        if !expected_topics.0.contains(actual_topic) {
-                            return false;
-                        }
-                    } else {
-                        return false;
-                    }
+                let Some(expected_topics) = expected_topics else {
+                    continue;
+                };
+                if expected_topics.0.is_empty() {
+                    continue;
+                }
+                match log.topics.get(idx) {
+                    Some(actual_topic) if expected_topics.0.contains(actual_topic) => {}
+                    _ => return false,
                 }
             }
         }
         true
     }
+
+    /// Returns the addresses this filter is restricted to, or `None` if it matches logs from any address.
+    pub fn addresses(&self) -> Option<&[H160]> {
+        let addresses = &self.address.as_ref()?.0;
+        (!addresses.is_empty()).then_some(addresses.as_slice())
+    }
 }
 
 #[derive(Default, Clone)]
@@ -337,12 +347,41 @@ fn topic_to_option<T>(topic: ethabi::Topic<T>) -> Option<Vec<T>> {
     }
 }
 
+/// Storage slot watched by a `zks_subscribeStorage` subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StorageSlot {
+    pub address: Address,
+    pub slot: H256,
+}
+
+/// Notification about a changed value of a watched storage slot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageChange {
+    pub address: Address,
+    pub slot: H256,
+    /// Value of the slot after the miniblock.
+    pub value: H256,
+    /// Number of the miniblock in which the slot has changed.
+    pub block_number: U64,
+}
+
+impl StorageChange {
+    pub fn storage_slot(&self) -> StorageSlot {
+        StorageSlot {
+            address: self.address,
+            slot: self.slot,
+        }
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PubSubResult {
     Header(BlockHeader),
     Log(Log),
+    StorageChange(StorageChange),
     TxHash(H256),
     Syncing(bool),
 }
@@ -441,4 +480,54 @@ mod tests {
         let restored_value: ValueOrArray<Address> = serde_json::from_value(json).unwrap();
         assert_eq!(restored_value, value);
     }
+
+    #[test]
+    fn matching_logs_with_pub_sub_filter() {
+        let log = Log {
+            address: Address::repeat_byte(1),
+            topics: vec![H256::repeat_byte(0xa), H256::repeat_byte(0xb)],
+            data: Bytes::default(),
+            block_hash: None,
+            block_number: None,
+            l1_batch_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        };
+        let filter_json =
+            |json: serde_json::Value| -> PubSubFilter { serde_json::from_value(json).unwrap() };
+        let address = "0x0101010101010101010101010101010101010101";
+        let other_address = "0x0202020202020202020202020202020202020202";
+        let topic_a = "0x0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a";
+        let topic_b = "0x0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+        let other_topic = "0x0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c";
+
+        let matching_filters = [
+            serde_json::json!({}),
+            serde_json::json!({ "address": address }),
+            serde_json::json!({ "address": [other_address, address] }),
+            serde_json::json!({ "address": [] }),
+            serde_json::json!({ "topics": [topic_a] }),
+            serde_json::json!({ "topics": [null, topic_b] }),
+            serde_json::json!({ "topics": [[], [other_topic, topic_b]] }),
+            serde_json::json!({ "address": address, "topics": [[topic_a, other_topic], topic_b] }),
+        ];
+        for filter in matching_filters {
+            assert!(filter_json(filter.clone()).matches(&log), "{filter}");
+        }
+
+        let non_matching_filters = [
+            serde_json::json!({ "address": other_address }),
+            serde_json::json!({ "topics": [topic_b] }),
+            serde_json::json!({ "topics": [null, [other_topic]] }),
+            serde_json::json!({ "topics": [topic_a, topic_b, null, other_topic] }),
+            serde_json::json!({ "address": other_address, "topics": [topic_a] }),
+        ];
+        for filter in non_matching_filters {
+            assert!(!filter_json(filter.clone()).matches(&log), "{filter}");
+        }
+    }
 }
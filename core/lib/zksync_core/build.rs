@@ -1,4 +1,48 @@
-//! Generates rust code from protobufs.
+//! Generates rust code from protobufs and embeds build information.
+
+use std::{env, path::Path, process::Command};
+
+fn git_output(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Reruns the build script if the checked-out commit changes. Watching `HEAD` only catches branch switches,
+/// so the ref file of the current branch (and `packed-refs`, in case the ref is packed) is watched as well.
+fn watch_git_head() {
+    let mut watched_files = vec!["HEAD".to_owned(), "packed-refs".to_owned()];
+    if let Some(head_ref) = git_output(&["symbolic-ref", "-q", "HEAD"]) {
+        watched_files.push(head_ref);
+    }
+    for file in watched_files {
+        let Some(path) = git_output(&["rev-parse", "--git-path", &file]) else {
+            continue;
+        };
+        // Cargo always reruns the build script if a watched file doesn't exist.
+        if Path::new(&path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+}
+
+/// Embeds the git commit and the build profile into the build. The commit can be overridden with
+/// the `ZKSYNC_GIT_COMMIT` env var (e.g., for builds from source archives); no other build-specific data
+/// (like timestamps) is embedded, so builds remain reproducible.
+fn embed_build_info() {
+    println!("cargo:rerun-if-env-changed=ZKSYNC_GIT_COMMIT");
+    if env::var_os("ZKSYNC_GIT_COMMIT").is_none() {
+        if let Some(commit) = git_output(&["rev-parse", "HEAD"]) {
+            println!("cargo:rustc-env=ZKSYNC_GIT_COMMIT={commit}");
+        }
+        watch_git_head();
+    }
+    let profile = env::var("PROFILE").unwrap_or_else(|_| "unknown".to_owned());
+    println!("cargo:rustc-env=ZKSYNC_BUILD_PROFILE={profile}");
+}
+
 fn main() {
     zksync_protobuf_build::Config {
         input_root: "src/consensus/proto".into(),
@@ -9,4 +53,5 @@ fn main() {
     }
     .generate()
     .unwrap();
+    embed_build_info();
 }
@@ -297,6 +297,35 @@ pub(super) fn apply_vm_in_sandbox<T>(
         &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
         Transaction,
     ) -> T,
+) -> anyhow::Result<T> {
+    apply_vm_in_sandbox_with_storage(
+        vm_permit,
+        shared_args,
+        adjust_pubdata_price,
+        execution_args,
+        connection_pool,
+        tx,
+        block_args,
+        |vm, tx, _| apply(vm, tx),
+    )
+}
+
+/// Same as [`apply_vm_in_sandbox()`], but additionally provides the storage view used by the VM to the `apply` closure,
+/// e.g. to inspect storage slots accessed during execution.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn apply_vm_in_sandbox_with_storage<T>(
+    vm_permit: VmPermit,
+    shared_args: TxSharedArgs,
+    adjust_pubdata_price: bool,
+    execution_args: &TxExecutionArgs,
+    connection_pool: &ConnectionPool,
+    tx: Transaction,
+    block_args: BlockArgs,
+    apply: impl FnOnce(
+        &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
+        Transaction,
+        &StoragePtr<StorageView<PostgresStorage<'_>>>,
+    ) -> T,
 ) -> anyhow::Result<T> {
     let stage_started_at = Instant::now();
     let span = tracing::debug_span!("initialization").entered();
@@ -328,7 +357,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
         tx.nonce().unwrap_or(Nonce(0))
     );
     let execution_latency = SANDBOX_METRICS.sandbox[&SandboxStage::Execution].start();
-    let result = apply(&mut vm, tx);
+    let result = apply(&mut vm, tx, &storage_view);
     let vm_execution_took = execution_latency.observe();
 
     let memory_metrics = vm.record_vm_memory_metrics();
@@ -0,0 +1,96 @@
+//! Cache of `eth_call` results for calls pinned to a block hash.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use zksync_types::{transaction_request::CallRequest, web3::signing::keccak256, H256};
+
+use super::vm_metrics::{CallCacheLookup, EXECUTION_METRICS};
+
+/// Key of a cached call: the hash of the block the call is executed on, and the digest of the call request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct CallCacheKey {
+    block_hash: H256,
+    call_digest: H256,
+}
+
+impl CallCacheKey {
+    pub fn new(block_hash: H256, request: &CallRequest) -> Self {
+        // The serialization is deterministic, so identical requests have the same digest.
+        let request_bytes = serde_json::to_vec(request).expect("failed serializing call request");
+        Self {
+            block_hash,
+            call_digest: H256(keccak256(&request_bytes)),
+        }
+    }
+}
+
+/// LRU cache of successful `eth_call` outputs.
+///
+/// Execution of a call on a block identified by its hash is deterministic: the block state and the block fee input
+/// are fixed, and the block hash commits to the block contents, so cached outputs never become stale. Calls on blocks
+/// identified by number or tag are never cached since the block they resolve to may change (e.g., `latest`,
+/// or any block after a revert).
+#[derive(Debug, Clone)]
+pub(crate) struct CallCache(Arc<Mutex<LruCache<CallCacheKey, Vec<u8>>>>);
+
+impl CallCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    pub fn get(&self, key: &CallCacheKey) -> Option<Vec<u8>> {
+        let output = self.0.lock().unwrap().get(key).cloned();
+        let lookup = if output.is_some() {
+            CallCacheLookup::Hit
+        } else {
+            CallCacheLookup::Miss
+        };
+        EXECUTION_METRICS.call_cache[&lookup].inc();
+        output
+    }
+
+    pub fn insert(&self, key: CallCacheKey, output: Vec<u8>) {
+        self.0.lock().unwrap().put(key, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::Address;
+
+    use super::*;
+
+    #[test]
+    fn call_cache_basics() {
+        let cache = CallCache::new(NonZeroUsize::new(2).unwrap());
+        let request = CallRequest {
+            to: Some(Address::repeat_byte(1)),
+            data: Some(vec![1, 2, 3].into()),
+            ..CallRequest::default()
+        };
+        let key = CallCacheKey::new(H256::repeat_byte(1), &request);
+        assert_eq!(key, CallCacheKey::new(H256::repeat_byte(1), &request));
+        assert!(cache.get(&key).is_none());
+        cache.insert(key, vec![42]);
+        assert_eq!(cache.get(&key), Some(vec![42]));
+
+        let other_block_key = CallCacheKey::new(H256::repeat_byte(2), &request);
+        assert!(cache.get(&other_block_key).is_none());
+        let other_request = CallRequest {
+            data: Some(vec![1, 2].into()),
+            ..request
+        };
+        let other_request_key = CallCacheKey::new(H256::repeat_byte(1), &other_request);
+        assert!(cache.get(&other_request_key).is_none());
+
+        // Check eviction.
+        cache.insert(other_block_key, vec![1]);
+        cache.insert(other_request_key, vec![2]);
+        assert!(cache.get(&key).is_none());
+        assert_eq!(cache.get(&other_request_key), Some(vec![2]));
+    }
+}
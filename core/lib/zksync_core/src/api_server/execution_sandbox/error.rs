@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use multivm::interface::{Halt, TxRevertReason};
 use thiserror::Error;
 
@@ -28,6 +30,11 @@ pub(crate) enum SandboxExecutionError {
     UnexpectedVMBehavior(String),
 }
 
+/// Error returned if VM execution for an API request has exceeded its time budget.
+#[derive(Debug, Error)]
+#[error("execution time budget of {0:?} exceeded")]
+pub(crate) struct ExecutionTimeoutError(pub Duration);
+
 impl From<Halt> for SandboxExecutionError {
     fn from(value: Halt) -> Self {
         match value {
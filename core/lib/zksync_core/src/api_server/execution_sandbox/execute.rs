@@ -16,7 +16,10 @@ use zksync_types::{
 
 #[cfg(test)]
 use super::testonly::MockTransactionExecutor;
-use super::{apply, vm_metrics, ApiTracer, BlockArgs, TxSharedArgs, VmPermit};
+use super::{
+    apply, vm_metrics, ApiTracer, BlockArgs, ExecutionTimeoutError, TxSharedArgs, VmPermit,
+    SANDBOX_METRICS,
+};
 
 #[derive(Debug)]
 pub(crate) struct TxExecutionArgs {
@@ -123,6 +126,11 @@ impl TransactionExecutor {
             .factory_deps
             .as_ref()
             .map_or(0, |deps| deps.len() as u16);
+        let execution_budgets: Vec<_> = custom_tracers
+            .iter()
+            .filter_map(ApiTracer::execution_budget)
+            .cloned()
+            .collect();
 
         let (published_bytecodes, execution_result) = tokio::task::spawn_blocking(move || {
             let span = span!(Level::DEBUG, "execute_in_sandbox").entered();
@@ -155,6 +163,11 @@ impl TransactionExecutor {
         .await
         .context("transaction execution panicked")??;
 
+        if let Some(budget) = execution_budgets.iter().find(|budget| budget.is_exceeded()) {
+            SANDBOX_METRICS.execution_timeouts.inc();
+            return Err(ExecutionTimeoutError(budget.limit()).into());
+        }
+
         let metrics =
             vm_metrics::collect_tx_execution_metrics(total_factory_deps, &execution_result);
         Ok(TransactionExecutionOutput {
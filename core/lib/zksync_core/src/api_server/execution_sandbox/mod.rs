@@ -12,16 +12,19 @@ use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 
 use self::vm_metrics::SandboxStage;
 pub(super) use self::{
-    error::SandboxExecutionError,
+    call_cache::{CallCache, CallCacheKey},
+    error::{ExecutionTimeoutError, SandboxExecutionError},
     execute::{TransactionExecutor, TxExecutionArgs},
-    tracers::ApiTracer,
+    tracers::{ApiTracer, ExecutionBudgetGuard},
     validate::ValidationError,
+    validation_cache::ValidationCache,
     vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
 };
 use super::tx_sender::MultiVMBaseSystemContracts;
 
 // Note: keep the modules private, and instead re-export functions that make public interface.
 mod apply;
+mod call_cache;
 mod error;
 mod execute;
 #[cfg(test)]
@@ -30,6 +33,7 @@ pub(super) mod testonly;
 mod tests;
 mod tracers;
 mod validate;
+mod validation_cache;
 mod vm_metrics;
 
 /// Permit to invoke VM code.
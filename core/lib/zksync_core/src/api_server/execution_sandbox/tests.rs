@@ -196,3 +196,35 @@ async fn test_instantiating_vm(pool: ConnectionPool, block_args: BlockArgs) {
     .expect("VM instantiation panicked")
     .expect("VM instantiation errored");
 }
+
+#[tokio::test]
+async fn exceeding_execution_time_budget() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let block_args = BlockArgs::pending(&mut storage).await.unwrap();
+    drop(storage);
+
+    let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(1);
+    let vm_permit = vm_concurrency_limiter.acquire().await.unwrap();
+    let transaction = create_l2_transaction(10, 100).into();
+    let budget = ExecutionBudgetGuard::new(Duration::ZERO);
+
+    let err = TransactionExecutor::Real
+        .execute_tx_in_sandbox(
+            vm_permit,
+            TxSharedArgs::mock(ApiContracts::load_from_disk().estimate_gas, pool.clone()),
+            true,
+            TxExecutionArgs::for_gas_estimate(None, &transaction, 123),
+            pool,
+            transaction,
+            block_args,
+            vec![budget.tracer()],
+        )
+        .await
+        .unwrap_err();
+    let err = err.downcast::<ExecutionTimeoutError>().unwrap();
+    assert_eq!(err.0, Duration::ZERO);
+}
@@ -1,6 +1,10 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use multivm::{tracers::CallTracer, vm_latest::HistoryMode, MultiVMTracer, MultiVmTracerPointer};
+use multivm::{
+    tracers::{CallTracer, ExecutionBudget},
+    vm_latest::HistoryMode,
+    MultiVMTracer, MultiVmTracerPointer,
+};
 use once_cell::sync::OnceCell;
 use zksync_state::WriteStorage;
 use zksync_types::vm_trace::Call;
@@ -9,6 +13,7 @@ use zksync_types::vm_trace::Call;
 #[derive(Debug)]
 pub(crate) enum ApiTracer {
     CallTracer(Arc<OnceCell<Vec<Call>>>),
+    ExecutionBudget(ExecutionBudget),
 }
 
 impl ApiTracer {
@@ -20,6 +25,36 @@ impl ApiTracer {
     ) -> MultiVmTracerPointer<S, H> {
         match self {
             ApiTracer::CallTracer(tracer) => CallTracer::new(tracer.clone()).into_tracer_pointer(),
+            ApiTracer::ExecutionBudget(budget) => budget.into_tracer_pointer(),
         }
     }
+
+    pub(super) fn execution_budget(&self) -> Option<&ExecutionBudget> {
+        match self {
+            ApiTracer::ExecutionBudget(budget) => Some(budget),
+            _ => None,
+        }
+    }
+}
+
+/// Execution time budget for an API request. The budget is cancelled when the guard is dropped, so that the VM
+/// stops executing once the request future is dropped (e.g., if the client has disconnected).
+#[derive(Debug)]
+pub(crate) struct ExecutionBudgetGuard(ExecutionBudget);
+
+impl ExecutionBudgetGuard {
+    pub fn new(limit: Duration) -> Self {
+        Self(ExecutionBudget::new(limit))
+    }
+
+    /// Returns a tracer enforcing this budget. All returned tracers share the budget.
+    pub fn tracer(&self) -> ApiTracer {
+        ApiTracer::ExecutionBudget(self.0.clone())
+    }
+}
+
+impl Drop for ExecutionBudgetGuard {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
 }
@@ -16,6 +16,7 @@ use zksync_types::{l2::L2Tx, Transaction, TRUSTED_ADDRESS_SLOTS, TRUSTED_TOKEN_S
 use super::{
     apply,
     execute::TransactionExecutor,
+    validation_cache::ValidationCache,
     vm_metrics::{SandboxStage, EXECUTION_METRICS, SANDBOX_METRICS},
     BlockArgs, TxExecutionArgs, TxSharedArgs, VmPermit,
 };
@@ -31,6 +32,9 @@ pub(crate) enum ValidationError {
 }
 
 impl TransactionExecutor {
+    /// Validates a transaction. If `validation_cache` is provided, successful validation results are cached in it,
+    /// and the VM run is skipped for transactions with a cached result that is still valid.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn validate_tx_in_sandbox(
         &self,
         connection_pool: ConnectionPool,
@@ -39,6 +43,7 @@ impl TransactionExecutor {
         shared_args: TxSharedArgs,
         block_args: BlockArgs,
         computational_gas_limit: u32,
+        validation_cache: Option<ValidationCache>,
     ) -> Result<(), ValidationError> {
         #[cfg(test)]
         if let Self::Mock(mock) = self {
@@ -50,6 +55,20 @@ impl TransactionExecutor {
             .access_storage_tagged("api")
             .await
             .context("failed acquiring DB connection")?;
+        let initiator = tx.initiator_account();
+        let tx_hash = tx.hash();
+        let fee_input = shared_args.fee_input;
+        if let Some(cache) = &validation_cache {
+            if cache
+                .contains(&mut connection, initiator, tx_hash, fee_input)
+                .await?
+            {
+                tracing::debug!("Using cached validation result for transaction {tx_hash:?}");
+                stage_latency.observe();
+                return Ok(());
+            }
+        }
+
         let validation_params =
             get_validation_params(&mut connection, &tx, computational_gas_limit)
                 .await
@@ -61,7 +80,7 @@ impl TransactionExecutor {
 
         let validation_result = tokio::task::spawn_blocking(move || {
             let span = tracing::debug_span!("validate_in_sandbox").entered();
-            let result = apply::apply_vm_in_sandbox(
+            let result = apply::apply_vm_in_sandbox_with_storage(
                 vm_permit,
                 shared_args,
                 true,
@@ -69,7 +88,7 @@ impl TransactionExecutor {
                 &connection_pool,
                 tx,
                 block_args,
-                |vm, tx| {
+                |vm, tx, storage_view| {
                     let stage_latency = SANDBOX_METRICS.sandbox[&SandboxStage::Validation].start();
                     let span = tracing::debug_span!("validation").entered();
                     vm.push_transaction(tx);
@@ -96,6 +115,11 @@ impl TransactionExecutor {
                         }
                         (_, None) => Ok(()),
                     };
+                    if let (Ok(()), Some(cache)) = (&result, &validation_cache) {
+                        let read_slots =
+                            storage_view.borrow().witness_block_state().read_storage_key;
+                        cache.insert(initiator, tx_hash, fee_input, read_slots);
+                    }
 
                     stage_latency.observe();
                     span.exit();
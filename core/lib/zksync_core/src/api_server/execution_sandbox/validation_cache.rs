@@ -0,0 +1,174 @@
+//! Cache of successful transaction validation results.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use lru::LruCache;
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    fee_model::BatchFeeInput, Address, StorageKey, StorageValue, H256, SYSTEM_CONTEXT_ADDRESS,
+};
+
+use super::vm_metrics::{ValidationCacheLookup, EXECUTION_METRICS};
+
+/// Storage state observed during a successful transaction validation.
+#[derive(Debug, Clone)]
+struct ValidatedState {
+    fee_input: BatchFeeInput,
+    read_slots: Vec<(StorageKey, StorageValue)>,
+}
+
+/// LRU cache of successful validation results keyed by the transaction initiator and hash (which covers
+/// the transaction signature and calldata).
+///
+/// A cached result is only reused if the fee input and the values of all storage slots read during validation
+/// are unchanged, so any storage write to a validation-touched slot (e.g., a nonce increment, a balance change
+/// or a change of a session key stored by the account) invalidates the entry. Slots of the system context contract
+/// are not tracked, since they store block info that the validation rules prohibit accessing, and that changes
+/// with every block.
+#[derive(Debug, Clone)]
+pub(crate) struct ValidationCache(Arc<Mutex<LruCache<(Address, H256), ValidatedState>>>);
+
+impl ValidationCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    /// Checks whether the transaction was successfully validated against the current storage state.
+    pub async fn contains(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        initiator: Address,
+        tx_hash: H256,
+        fee_input: BatchFeeInput,
+    ) -> anyhow::Result<bool> {
+        let state = self.0.lock().unwrap().get(&(initiator, tx_hash)).cloned();
+        let Some(state) = state.filter(|state| state.fee_input == fee_input) else {
+            EXECUTION_METRICS.validation_cache[&ValidationCacheLookup::Miss].inc();
+            return Ok(false);
+        };
+
+        let hashed_keys: Vec<_> = state
+            .read_slots
+            .iter()
+            .map(|(key, _)| key.hashed_key())
+            .collect();
+        let current_values = connection
+            .storage_web3_dal()
+            .get_values(&hashed_keys)
+            .await
+            .context("failed getting values of slots read during validation")?;
+        let is_valid = state.read_slots.iter().all(|(key, value)| {
+            current_values
+                .get(&key.hashed_key())
+                .copied()
+                .unwrap_or_default()
+                == *value
+        });
+
+        if is_valid {
+            EXECUTION_METRICS.validation_cache[&ValidationCacheLookup::Hit].inc();
+        } else {
+            EXECUTION_METRICS.validation_cache[&ValidationCacheLookup::Stale].inc();
+            self.0.lock().unwrap().pop(&(initiator, tx_hash));
+        }
+        Ok(is_valid)
+    }
+
+    /// Records a successful validation of a transaction.
+    pub fn insert(
+        &self,
+        initiator: Address,
+        tx_hash: H256,
+        fee_input: BatchFeeInput,
+        read_slots: impl IntoIterator<Item = (StorageKey, StorageValue)>,
+    ) {
+        let read_slots = read_slots
+            .into_iter()
+            .filter(|(key, _)| *key.address() != SYSTEM_CONTEXT_ADDRESS)
+            .collect();
+        let state = ValidatedState {
+            fee_input,
+            read_slots,
+        };
+        self.0.lock().unwrap().put((initiator, tx_hash), state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::ConnectionPool;
+    use zksync_types::{
+        get_nonce_key, get_system_context_key, StorageLog, SYSTEM_CONTEXT_CHAIN_ID_POSITION,
+    };
+
+    use super::*;
+    use crate::{
+        genesis::{ensure_genesis_state, GenesisParams},
+        utils::testonly::create_miniblock,
+    };
+
+    #[tokio::test]
+    async fn validation_cache_is_invalidated_on_storage_writes() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, Default::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+
+        let cache = ValidationCache::new(NonZeroUsize::new(10).unwrap());
+        let initiator = Address::repeat_byte(1);
+        let tx_hash = H256::repeat_byte(2);
+        let fee_input = BatchFeeInput::l1_pegged(100, 1);
+        let nonce_key = get_nonce_key(&initiator);
+        assert!(!cache
+            .contains(&mut storage, initiator, tx_hash, fee_input)
+            .await
+            .unwrap());
+
+        let read_slots = [
+            (nonce_key, H256::zero()),
+            // Should be ignored.
+            (
+                get_system_context_key(SYSTEM_CONTEXT_CHAIN_ID_POSITION),
+                H256::repeat_byte(0xff),
+            ),
+        ];
+        cache.insert(initiator, tx_hash, fee_input, read_slots);
+        assert!(cache
+            .contains(&mut storage, initiator, tx_hash, fee_input)
+            .await
+            .unwrap());
+        assert!(!cache
+            .contains(&mut storage, Address::repeat_byte(3), tx_hash, fee_input)
+            .await
+            .unwrap());
+        let changed_fee_input = BatchFeeInput::l1_pegged(200, 1);
+        assert!(!cache
+            .contains(&mut storage, initiator, tx_hash, changed_fee_input)
+            .await
+            .unwrap());
+
+        let miniblock = create_miniblock(1);
+        storage
+            .blocks_dal()
+            .insert_miniblock(&miniblock)
+            .await
+            .unwrap();
+        let nonce_log = StorageLog::new_write_log(nonce_key, H256::from_low_u64_be(1));
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(miniblock.number, &[(H256::zero(), vec![nonce_log])])
+            .await
+            .unwrap();
+        assert!(!cache
+            .contains(&mut storage, initiator, tx_hash, fee_input)
+            .await
+            .unwrap());
+        // The stale entry should be evicted.
+        assert!(cache.0.lock().unwrap().is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use multivm::interface::{VmExecutionResultAndLogs, VmMemoryMetrics};
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
 use zksync_state::StorageViewMetrics;
 use zksync_types::{
     event::{extract_long_l2_to_l1_messages, extract_published_bytecodes},
@@ -107,12 +107,32 @@ pub(in crate::api_server) struct SandboxMetrics {
     pub submit_tx: Family<SubmitTxStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::linear(0.0..=30.0, 3.0))]
     pub estimate_gas_binary_search_iterations: Histogram<usize>,
+    /// Number of VM executions stopped because of an exceeded execution time budget.
+    pub(super) execution_timeouts: Counter,
 }
 
 #[vise::register]
 pub(in crate::api_server) static SANDBOX_METRICS: vise::Global<SandboxMetrics> =
     vise::Global::new();
 
+/// Outcome of looking up a transaction in the validation cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum ValidationCacheLookup {
+    Hit,
+    Miss,
+    /// The transaction was cached, but some storage slots read during validation have changed since.
+    Stale,
+}
+
+/// Outcome of looking up a call in the `eth_call` cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum CallCacheLookup {
+    Hit,
+    Miss,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "api_execution")]
 pub(super) struct ExecutionMetrics {
@@ -120,6 +140,10 @@ pub(super) struct ExecutionMetrics {
     pub trusted_address_slots_amount: Gauge<usize>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub get_validation_params: Histogram<Duration>,
+    /// Lookups in the validation cache.
+    pub validation_cache: Family<ValidationCacheLookup, Counter>,
+    /// Lookups in the `eth_call` cache.
+    pub call_cache: Family<CallCacheLookup, Counter>,
 }
 
 #[vise::register]
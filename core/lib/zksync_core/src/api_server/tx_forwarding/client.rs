@@ -0,0 +1,265 @@
+//! Replica side of transaction forwarding.
+
+use std::{
+    collections::HashSet,
+    mem,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    oneshot,
+};
+use zksync_dal::transactions_dal::L2TxSubmissionResult;
+use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx, Bytes, H256};
+
+use super::{
+    metrics::{ForwardingResult, METRICS},
+    ForwardTxsRequest, ForwardTxsResponse, ForwardedTxResult, TRANSACTIONS_PATH,
+};
+use crate::{
+    api_server::tx_sender::{tx_sink::TxSink, SubmitTxError},
+    metrics::{TxStage, APP_METRICS},
+};
+
+type ForwardingResponse = Result<ForwardedTxResult, String>;
+
+#[derive(Debug)]
+struct ForwardingRequest {
+    raw_tx: Bytes,
+    response_sender: oneshot::Sender<ForwardingResponse>,
+}
+
+/// Removes a transaction hash from the in-flight set on drop, so that the hash is removed even if
+/// the submission future is dropped.
+#[derive(Debug)]
+struct InFlightGuard<'a> {
+    in_flight: &'a Mutex<HashSet<H256>>,
+    tx_hash: H256,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.in_flight.lock().unwrap().remove(&self.tx_hash);
+    }
+}
+
+/// [`TxSink`] forwarding transactions to the intake server running next to the sequencer.
+///
+/// Transactions are put to a bounded queue processed by [`TxForwarderTask`]; if the queue is full, submission fails
+/// with [`SubmitTxError::RateLimitExceeded`]. Transactions already being forwarded are deduplicated by hash.
+/// Only raw transaction bytes are forwarded; the sequencer decodes and executes transactions anew, so neither the decoded
+/// transaction nor execution metrics obtained by the replica are trusted.
+#[derive(Debug)]
+pub struct TxForwarder {
+    sender: mpsc::Sender<ForwardingRequest>,
+    in_flight: Mutex<HashSet<H256>>,
+}
+
+impl TxForwarder {
+    /// Creates a forwarder together with the task sending transactions to the intake server at `url`.
+    /// The task terminates once the forwarder is dropped.
+    ///
+    /// `url` must use the `https` scheme. `ca_cert_pem` is an optional PEM-encoded root certificate trusted
+    /// in addition to the system ones (e.g., a certificate of an internal CA signing the intake server certificate).
+    pub fn new(
+        url: &str,
+        ca_cert_pem: Option<&[u8]>,
+        auth_token: String,
+        queue_capacity: usize,
+    ) -> anyhow::Result<(Self, TxForwarderTask)> {
+        let parsed_url =
+            reqwest::Url::parse(url).context("invalid transaction intake server URL")?;
+        anyhow::ensure!(
+            parsed_url.scheme() == "https",
+            "transaction intake server URL must use the `https` scheme, got {url}"
+        );
+        let mut client = reqwest::Client::builder().https_only(true);
+        if let Some(ca_cert_pem) = ca_cert_pem {
+            let ca_cert = reqwest::Certificate::from_pem(ca_cert_pem)
+                .context("invalid CA certificate for transaction intake server")?;
+            client = client.add_root_certificate(ca_cert);
+        }
+        let client = client
+            .build()
+            .context("failed building transaction forwarding HTTP client")?;
+
+        let (sender, receiver) = mpsc::channel(queue_capacity);
+        let this = Self {
+            sender,
+            in_flight: Mutex::default(),
+        };
+        let task = TxForwarderTask {
+            client,
+            url: format!("{}{TRANSACTIONS_PATH}", url.trim_end_matches('/')),
+            auth_token: Arc::from(auth_token),
+            receiver,
+        };
+        Ok((this, task))
+    }
+}
+
+#[async_trait::async_trait]
+impl TxSink for TxForwarder {
+    async fn submit_tx(
+        &self,
+        tx: L2Tx,
+        _execution_metrics: TransactionExecutionMetrics,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        let Some(raw_tx) = tx.common_data.input_data() else {
+            // Transactions submitted via the API always retain their raw bytes, so this should never happen.
+            return Err(anyhow::anyhow!("transaction has no raw bytes to forward").into());
+        };
+        let raw_tx = Bytes(raw_tx.to_vec());
+        let tx_hash = tx.hash();
+        if !self.in_flight.lock().unwrap().insert(tx_hash) {
+            METRICS.transactions[&ForwardingResult::DuplicateInFlight].inc();
+            return Ok(L2TxSubmissionResult::Duplicate);
+        }
+        let _guard = InFlightGuard {
+            in_flight: &self.in_flight,
+            tx_hash,
+        };
+
+        let (response_sender, response_receiver) = oneshot::channel();
+        let request = ForwardingRequest {
+            raw_tx,
+            response_sender,
+        };
+        self.sender.try_send(request).map_err(|err| match err {
+            TrySendError::Full(_) => {
+                METRICS.transactions[&ForwardingResult::QueueFull].inc();
+                SubmitTxError::RateLimitExceeded
+            }
+            TrySendError::Closed(_) => SubmitTxError::ServerShuttingDown,
+        })?;
+
+        let response = response_receiver
+            .await
+            .map_err(|_| SubmitTxError::ServerShuttingDown)?;
+        match response {
+            Ok(ForwardedTxResult::Submitted(result)) => {
+                METRICS.transactions[&ForwardingResult::Forwarded].inc();
+                APP_METRICS.processed_txs[&TxStage::Forwarded].inc();
+                Ok(result)
+            }
+            Ok(ForwardedTxResult::Rejected(reason)) => {
+                METRICS.transactions[&ForwardingResult::Rejected].inc();
+                Err(SubmitTxError::Unexecutable(reason))
+            }
+            Err(err) => {
+                METRICS.transactions[&ForwardingResult::Failed].inc();
+                Err(anyhow::anyhow!(err)
+                    .context(format!("failed forwarding transaction {tx_hash:?}"))
+                    .into())
+            }
+        }
+    }
+}
+
+/// Task sending transactions queued by [`TxForwarder`] to the intake server in batches.
+#[derive(Debug)]
+pub struct TxForwarderTask {
+    client: reqwest::Client,
+    url: String,
+    auth_token: Arc<str>,
+    receiver: mpsc::Receiver<ForwardingRequest>,
+}
+
+impl TxForwarderTask {
+    const MAX_BATCH_SIZE: usize = 100;
+    const MAX_ATTEMPTS: usize = 3;
+    const RETRY_INTERVAL: Duration = Duration::from_millis(200);
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Runs the task until the corresponding [`TxForwarder`] is dropped. Batches are forwarded sequentially,
+    /// so that the transaction order is preserved, and a slow intake server applies backpressure to the forwarder queue.
+    pub async fn run(mut self) {
+        let mut batch = Vec::with_capacity(Self::MAX_BATCH_SIZE);
+        while let Some(request) = self.receiver.recv().await {
+            batch.push(request);
+            while batch.len() < Self::MAX_BATCH_SIZE {
+                let Ok(request) = self.receiver.try_recv() else {
+                    break;
+                };
+                batch.push(request);
+            }
+            self.forward_batch(mem::take(&mut batch)).await;
+        }
+        tracing::info!("Transaction forwarder is stopped since all senders are dropped");
+    }
+
+    async fn forward_batch(&self, batch: Vec<ForwardingRequest>) {
+        METRICS.batch_size.observe(batch.len());
+        let latency = METRICS.batch_latency.start();
+        let (transactions, response_senders): (Vec<_>, Vec<_>) = batch
+            .into_iter()
+            .map(|request| (request.raw_tx, request.response_sender))
+            .unzip();
+        let request = ForwardTxsRequest { transactions };
+
+        let mut attempt = 1;
+        let responses: Vec<ForwardingResponse> = loop {
+            match self.send_request(&request).await {
+                Ok(response) if response.results.len() == response_senders.len() => {
+                    break response.results.into_iter().map(Ok).collect();
+                }
+                Ok(response) => {
+                    let err = format!(
+                        "intake server returned {} results for {} transactions",
+                        response.results.len(),
+                        response_senders.len()
+                    );
+                    tracing::error!("{err}");
+                    break vec![Err(err); response_senders.len()];
+                }
+                Err(err) if attempt < Self::MAX_ATTEMPTS => {
+                    // Retrying is safe: transactions already inserted to the mempool are reported as duplicates.
+                    tracing::warn!(
+                        "Failed forwarding {} transactions (attempt {attempt}/{}): {err:#}",
+                        response_senders.len(),
+                        Self::MAX_ATTEMPTS
+                    );
+                    attempt += 1;
+                    tokio::time::sleep(Self::RETRY_INTERVAL).await;
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Failed forwarding {} transactions: {err:#}",
+                        response_senders.len()
+                    );
+                    break vec![Err(format!("{err:#}")); response_senders.len()];
+                }
+            }
+        };
+        latency.observe();
+
+        for (sender, response) in response_senders.into_iter().zip(responses) {
+            sender.send(response).ok(); // The submitter may be gone, e.g. if the API request was cancelled
+        }
+    }
+
+    async fn send_request(
+        &self,
+        request: &ForwardTxsRequest,
+    ) -> anyhow::Result<ForwardTxsResponse> {
+        let response = self
+            .client
+            .post(&self.url)
+            .bearer_auth(&self.auth_token)
+            .timeout(Self::REQUEST_TIMEOUT)
+            .json(request)
+            .send()
+            .await
+            .context("failed sending transactions to intake server")?;
+        let response = response
+            .error_for_status()
+            .context("intake server returned non-OK response")?;
+        response
+            .json()
+            .await
+            .context("failed deserializing intake server response")
+    }
+}
@@ -0,0 +1,40 @@
+//! Metrics for transaction forwarding.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics, Unit};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum ForwardingResult {
+    /// Transaction was accepted by the intake server.
+    Forwarded,
+    /// Transaction was rejected by the sequencer after re-validation.
+    Rejected,
+    /// Transaction with the same hash is already being forwarded.
+    DuplicateInFlight,
+    /// Forwarding queue is full.
+    QueueFull,
+    /// Forwarding failed (e.g., the intake server is unavailable).
+    Failed,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_tx_forwarding")]
+pub(super) struct TxForwardingMetrics {
+    /// Number of transactions processed by the forwarder, grouped by the outcome.
+    pub transactions: Family<ForwardingResult, Counter>,
+    /// Number of transactions in a forwarded batch.
+    #[metrics(buckets = Buckets::exponential(1.0..=128.0, 2.0))]
+    pub batch_size: Histogram<usize>,
+    /// Latency of forwarding a batch of transactions, including retries.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub batch_latency: Histogram<Duration>,
+    /// Number of requests rejected by the intake server because of invalid authentication.
+    pub unauthorized_requests: Counter,
+    /// Number of forwarded transactions rejected by the intake server after re-validation.
+    pub rejected_transactions: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<TxForwardingMetrics> = vise::Global::new();
@@ -0,0 +1,47 @@
+//! Propagation of transactions from API-only replicas to the sequencer.
+//!
+//! A replica validates transactions locally as usual, and then, instead of writing them to the master DB,
+//! forwards them in batches to the intake server running next to the sequencer over TLS. Transactions are forwarded
+//! as raw RLP-encoded bytes, exactly as they were submitted by the user. The intake server authenticates replicas
+//! using a shared token, decodes received transactions and validates and executes them anew (results of the replica
+//! decoding and execution are not trusted), and inserts them to the mempool.
+
+use serde::{Deserialize, Serialize};
+use zksync_dal::transactions_dal::L2TxSubmissionResult;
+use zksync_types::Bytes;
+
+pub use self::{
+    client::{TxForwarder, TxForwarderTask},
+    server::{run_intake_server, IntakeTlsConfig},
+};
+
+mod client;
+mod metrics;
+mod server;
+#[cfg(test)]
+mod tests;
+
+/// Path of the intake server endpoint accepting forwarded transactions.
+const TRANSACTIONS_PATH: &str = "/transactions";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ForwardTxsRequest {
+    /// Raw RLP-encoded transactions in the same format as accepted by `eth_sendRawTransaction`.
+    transactions: Vec<Bytes>,
+}
+
+/// Outcome of processing a single forwarded transaction by the intake server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ForwardedTxResult {
+    /// Transaction was validated by the sequencer and submitted to the mempool.
+    Submitted(L2TxSubmissionResult),
+    /// Transaction was rejected by the sequencer; contains the rejection reason.
+    Rejected(String),
+}
+
+/// Results for each transaction in the same order as transactions in the request.
+#[derive(Debug, Serialize, Deserialize)]
+struct ForwardTxsResponse {
+    results: Vec<ForwardedTxResult>,
+}
@@ -0,0 +1,309 @@
+//! Sequencer side of transaction forwarding.
+
+use std::{
+    fmt, future::Future, io, net::SocketAddr, path::Path, pin::Pin, sync::Arc, time::Duration,
+};
+
+use anyhow::Context as _;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    routing, Json, Router,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, watch},
+};
+use tokio_rustls::{
+    rustls::{self, ServerConfig},
+    server::TlsStream,
+    TlsAcceptor,
+};
+use zksync_types::{api, l2::L2Tx, L2ChainId};
+
+use super::{
+    metrics::METRICS, ForwardTxsRequest, ForwardTxsResponse, ForwardedTxResult, TRANSACTIONS_PATH,
+};
+use crate::api_server::tx_sender::TxSender;
+
+/// TLS configuration of the transaction intake server.
+#[derive(Clone)]
+pub struct IntakeTlsConfig(Arc<ServerConfig>);
+
+impl fmt::Debug for IntakeTlsConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("IntakeTlsConfig")
+            .finish_non_exhaustive()
+    }
+}
+
+impl IntakeTlsConfig {
+    /// Creates a config from a PEM-encoded certificate chain and a PEM-encoded private key
+    /// (PKCS #8, PKCS #1 or SEC1).
+    pub fn from_pem(cert_chain: &[u8], private_key: &[u8]) -> anyhow::Result<Self> {
+        let cert_chain = rustls_pemfile::certs(&mut &*cert_chain)
+            .context("failed parsing TLS certificate chain")?;
+        anyhow::ensure!(!cert_chain.is_empty(), "TLS certificate chain is empty");
+        let cert_chain = cert_chain.into_iter().map(rustls::Certificate).collect();
+
+        let private_key = rustls_pemfile::read_all(&mut &*private_key)
+            .context("failed parsing TLS private key")?
+            .into_iter()
+            .find_map(|item| match item {
+                rustls_pemfile::Item::PKCS8Key(key)
+                | rustls_pemfile::Item::RSAKey(key)
+                | rustls_pemfile::Item::ECKey(key) => Some(rustls::PrivateKey(key)),
+                _ => None,
+            })
+            .context("no TLS private key found")?;
+
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(cert_chain, private_key)
+            .context("invalid TLS certificate or private key")?;
+        Ok(Self(Arc::new(config)))
+    }
+
+    /// Loads a config from PEM files with the certificate chain and the private key.
+    pub fn load(cert_chain_path: &Path, private_key_path: &Path) -> anyhow::Result<Self> {
+        let cert_chain = std::fs::read(cert_chain_path).with_context(|| {
+            format!("failed reading TLS certificate chain from {cert_chain_path:?}")
+        })?;
+        let private_key = std::fs::read(private_key_path)
+            .with_context(|| format!("failed reading TLS private key from {private_key_path:?}"))?;
+        Self::from_pem(&cert_chain, &private_key)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IntakeState {
+    tx_sender: TxSender,
+    chain_id: L2ChainId,
+    max_tx_size: usize,
+    expected_authorization: Arc<str>,
+}
+
+impl IntakeState {
+    fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        let Some(authorization) = headers.get(header::AUTHORIZATION) else {
+            return false;
+        };
+        let expected = self.expected_authorization.as_bytes();
+        let actual = authorization.as_bytes();
+        // Constant-time comparison so that the token cannot be guessed based on response timings.
+        expected.len() == actual.len()
+            && expected
+                .iter()
+                .zip(actual)
+                .fold(0, |acc, (&x, &y)| acc | (x ^ y))
+                == 0
+    }
+
+    /// Decodes a raw forwarded transaction in the same way as `eth_sendRawTransaction` does.
+    fn decode_transaction(&self, raw_tx: Vec<u8>) -> anyhow::Result<L2Tx> {
+        let (tx_request, hash) = api::TransactionRequest::from_bytes(&raw_tx, self.chain_id)?;
+        let mut tx = L2Tx::from_request(tx_request, self.max_tx_size)?;
+        tx.set_input(raw_tx, hash);
+        Ok(tx)
+    }
+
+    async fn transactions_handler(
+        State(this): State<Self>,
+        headers: HeaderMap,
+        Json(request): Json<ForwardTxsRequest>,
+    ) -> Result<Json<ForwardTxsResponse>, StatusCode> {
+        if !this.is_authorized(&headers) {
+            METRICS.unauthorized_requests.inc();
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
+        // Transactions are decoded, validated and executed anew rather than trusting the replica, so that a compromised
+        // or outdated replica cannot insert invalid transactions to the mempool.
+        let mut results = Vec::with_capacity(request.transactions.len());
+        for raw_tx in request.transactions {
+            let tx = match this.decode_transaction(raw_tx.0) {
+                Ok(tx) => tx,
+                Err(err) => {
+                    tracing::info!("Failed decoding forwarded transaction: {err}");
+                    METRICS.rejected_transactions.inc();
+                    results.push(ForwardedTxResult::Rejected(err.to_string()));
+                    continue;
+                }
+            };
+            let tx_hash = tx.hash();
+            let result = match this.tx_sender.submit_tx(tx).await {
+                Ok(result) => ForwardedTxResult::Submitted(result),
+                Err(err) => {
+                    tracing::info!("Forwarded transaction {tx_hash:?} was rejected: {err}");
+                    METRICS.rejected_transactions.inc();
+                    ForwardedTxResult::Rejected(err.to_string())
+                }
+            };
+            results.push(result);
+        }
+        Ok(Json(ForwardTxsResponse { results }))
+    }
+}
+
+pub(super) struct IntakeServer {
+    local_addr: SocketAddr,
+    server_future: Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>,
+}
+
+impl fmt::Debug for IntakeServer {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("IntakeServer")
+            .field("local_addr", &self.local_addr)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IntakeServer {
+    /// Maximum duration of a TLS handshake with a client.
+    const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub async fn new(
+        bind_address: &SocketAddr,
+        tls_config: IntakeTlsConfig,
+        auth_token: &str,
+        tx_sender: TxSender,
+        chain_id: L2ChainId,
+        max_tx_size: usize,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Self> {
+        tracing::debug!("Starting transaction intake server on {bind_address}");
+
+        let state = IntakeState {
+            tx_sender,
+            chain_id,
+            max_tx_size,
+            expected_authorization: format!("Bearer {auth_token}").into(),
+        };
+        let app = Router::new()
+            .route(
+                TRANSACTIONS_PATH,
+                routing::post(IntakeState::transactions_handler),
+            )
+            .with_state(state);
+
+        let listener = TcpListener::bind(bind_address).await.with_context(|| {
+            format!("Failed binding transaction intake server to {bind_address}")
+        })?;
+        let local_addr = listener
+            .local_addr()
+            .context("Failed getting local address for transaction intake server")?;
+        // TLS handshakes are performed in separate tasks, so that a slow client cannot block accepting connections.
+        let (tls_streams_sender, tls_streams_receiver) = mpsc::channel(16);
+        tokio::spawn(Self::accept_connections(
+            listener,
+            TlsAcceptor::from(tls_config.0),
+            tls_streams_sender,
+        ));
+        let incoming = futures::stream::unfold(tls_streams_receiver, |mut receiver| async move {
+            let stream = receiver.recv().await?;
+            Some((Ok::<_, io::Error>(stream), receiver))
+        });
+
+        let server = axum::Server::builder(hyper::server::accept::from_stream(incoming))
+            .serve(app.into_make_service());
+        let server_future = async move {
+            server
+                .with_graceful_shutdown(async move {
+                    if stop_receiver.changed().await.is_err() {
+                        tracing::warn!(
+                            "Stop signal sender for transaction intake server was dropped without sending a signal"
+                        );
+                    }
+                    tracing::info!(
+                        "Stop signal received, transaction intake server is shutting down"
+                    );
+                })
+                .await
+                .context("Transaction intake server failed")?;
+
+            tracing::info!("Transaction intake server shut down");
+            Ok(())
+        };
+
+        Ok(Self {
+            local_addr,
+            server_future: Box::pin(server_future),
+        })
+    }
+
+    /// Accepts TCP connections and performs TLS handshakes until the server is dropped.
+    async fn accept_connections(
+        listener: TcpListener,
+        acceptor: TlsAcceptor,
+        tls_streams_sender: mpsc::Sender<TlsStream<TcpStream>>,
+    ) {
+        loop {
+            let (stream, peer_addr) = tokio::select! {
+                res = listener.accept() => match res {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        tracing::warn!("Failed accepting connection to transaction intake server: {err}");
+                        continue;
+                    }
+                },
+                () = tls_streams_sender.closed() => return,
+            };
+
+            let acceptor = acceptor.clone();
+            let tls_streams_sender = tls_streams_sender.clone();
+            tokio::spawn(async move {
+                let handshake =
+                    tokio::time::timeout(Self::TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream));
+                match handshake.await {
+                    Ok(Ok(tls_stream)) => {
+                        tls_streams_sender.send(tls_stream).await.ok();
+                    }
+                    Ok(Err(err)) => {
+                        tracing::info!("TLS handshake with {peer_addr} failed: {err}");
+                    }
+                    Err(_) => {
+                        tracing::info!("TLS handshake with {peer_addr} timed out");
+                    }
+                }
+            });
+        }
+    }
+
+    #[cfg(test)]
+    pub fn local_addr(&self) -> &SocketAddr {
+        &self.local_addr
+    }
+
+    pub async fn run(self) -> anyhow::Result<()> {
+        self.server_future.await
+    }
+}
+
+/// Runs the server accepting transactions forwarded by API replicas over TLS. Transactions are decoded
+/// for `chain_id` with the `max_tx_size` limit, and validated and executed by `tx_sender` before being
+/// inserted to the mempool. Requests must be authenticated with the bearer `auth_token` shared with the replicas.
+pub async fn run_intake_server(
+    bind_address: SocketAddr,
+    tls_config: IntakeTlsConfig,
+    auth_token: String,
+    tx_sender: TxSender,
+    chain_id: L2ChainId,
+    max_tx_size: usize,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    IntakeServer::new(
+        &bind_address,
+        tls_config,
+        &auth_token,
+        tx_sender,
+        chain_id,
+        max_tx_size,
+        stop_receiver,
+    )
+    .await?
+    .run()
+    .await
+}
@@ -0,0 +1,220 @@
+//! Tests for transaction forwarding.
+
+use std::net::Ipv4Addr;
+
+use assert_matches::assert_matches;
+use multivm::interface::{ExecutionResult, VmRevertReason};
+use tokio::sync::watch;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api, fee::TransactionExecutionMetrics, get_intrinsic_constants, l2::L2Tx, Address, L2ChainId,
+    MiniblockNumber, PackedEthSignature, StorageLog, H256, U256,
+};
+use zksync_utils::u256_to_h256;
+
+use super::{server::IntakeServer, *};
+use crate::{
+    api_server::{
+        execution_sandbox::testonly::MockTransactionExecutor,
+        tx_sender::{tests::create_test_tx_sender, tx_sink::TxSink, SubmitTxError},
+    },
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::create_l2_transaction,
+};
+
+const AUTH_TOKEN: &str = "test-token";
+const CA_CERT: &[u8] = include_bytes!("testdata/ca.pem");
+
+const FUNDED_PRIVATE_KEY: H256 = H256::repeat_byte(11);
+
+fn create_signed_transaction(private_key: &H256, nonce: u32) -> L2Tx {
+    let address = PackedEthSignature::address_from_private_key(private_key).unwrap();
+    let tx_request = api::TransactionRequest {
+        chain_id: Some(L2ChainId::default().as_u64()),
+        nonce: nonce.into(),
+        from: Some(address),
+        to: Some(Address::repeat_byte(2)),
+        value: 123_456.into(),
+        gas: (get_intrinsic_constants().l2_tx_intrinsic_gas * 2).into(),
+        gas_price: StateKeeperConfig::for_tests().minimal_l2_gas_price.into(),
+        input: vec![1, 2, 3, 4].into(),
+        ..api::TransactionRequest::default()
+    };
+    let mut rlp = Default::default();
+    tx_request.rlp(&mut rlp, L2ChainId::default().as_u64(), None);
+    let signed_message = PackedEthSignature::message_to_signed_bytes(&rlp.out());
+    let signature = PackedEthSignature::sign_raw(private_key, &signed_message).unwrap();
+
+    let mut rlp = Default::default();
+    tx_request.rlp(&mut rlp, L2ChainId::default().as_u64(), Some(&signature));
+    let (tx_request, _) =
+        api::TransactionRequest::from_bytes(&rlp.out(), L2ChainId::default()).unwrap();
+    L2Tx::from_request(tx_request, usize::MAX).unwrap()
+}
+
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    // Manually set sufficient balance for the transaction account.
+    let address = PackedEthSignature::address_from_private_key(&FUNDED_PRIVATE_KEY).unwrap();
+    let balance_key = zksync_types::utils::storage_key_for_eth_balance(&address);
+    let balance_log = StorageLog::new_write_log(balance_key, u256_to_h256(U256::one() << 64));
+    storage
+        .storage_logs_dal()
+        .append_storage_logs(MiniblockNumber(0), &[(H256::zero(), vec![balance_log])])
+        .await
+        .unwrap();
+}
+
+async fn start_intake_server(
+    pool: ConnectionPool,
+    tx_executor: MockTransactionExecutor,
+) -> (String, watch::Sender<bool>) {
+    let (tx_sender, _) =
+        create_test_tx_sender(pool, L2ChainId::default(), tx_executor.into()).await;
+    let tls_config = IntakeTlsConfig::from_pem(
+        include_bytes!("testdata/server.pem"),
+        include_bytes!("testdata/server.key.pem"),
+    )
+    .unwrap();
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let bind_address = (Ipv4Addr::LOCALHOST, 0).into();
+    let server = IntakeServer::new(
+        &bind_address,
+        tls_config,
+        AUTH_TOKEN,
+        tx_sender,
+        L2ChainId::default(),
+        usize::MAX,
+        stop_receiver,
+    )
+    .await
+    .unwrap();
+    let url = format!("https://{}", server.local_addr());
+    tokio::spawn(server.run());
+    (url, stop_sender)
+}
+
+fn successful_executor() -> MockTransactionExecutor {
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_tx_responses(|_, _| ExecutionResult::Success { output: vec![] });
+    tx_executor
+}
+
+async fn is_tx_stored(pool: &ConnectionPool, tx_hash: H256) -> bool {
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .transactions_web3_dal()
+        .get_transaction_details(tx_hash)
+        .await
+        .unwrap()
+        .is_some()
+}
+
+#[tokio::test]
+async fn forwarding_transactions() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let (url, _stop_sender) = start_intake_server(pool.clone(), successful_executor()).await;
+    let (forwarder, task) =
+        TxForwarder::new(&url, Some(CA_CERT), AUTH_TOKEN.to_owned(), 10).unwrap();
+    tokio::spawn(task.run());
+
+    let tx = create_signed_transaction(&FUNDED_PRIVATE_KEY, 0);
+    let result = forwarder
+        .submit_tx(tx.clone(), TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Added);
+    assert!(is_tx_stored(&pool, tx.hash()).await);
+
+    // Re-submitting the transaction should be deduplicated by the sequencer.
+    let result = forwarder
+        .submit_tx(tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Duplicate);
+}
+
+#[tokio::test]
+async fn forwarding_with_invalid_auth_token() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let (url, _stop_sender) = start_intake_server(pool.clone(), successful_executor()).await;
+    let (forwarder, task) =
+        TxForwarder::new(&url, Some(CA_CERT), "wrong-token".to_owned(), 10).unwrap();
+    tokio::spawn(task.run());
+
+    let tx = create_signed_transaction(&FUNDED_PRIVATE_KEY, 0);
+    let err = forwarder
+        .submit_tx(tx.clone(), TransactionExecutionMetrics::default())
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::Internal(_));
+    assert!(!is_tx_stored(&pool, tx.hash()).await);
+}
+
+#[tokio::test]
+async fn forwarded_transactions_are_executed_by_sequencer() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_tx_responses(|_, _| ExecutionResult::Revert {
+        output: VmRevertReason::General {
+            msg: "oops".to_owned(),
+            data: vec![],
+        },
+    });
+    let (url, _stop_sender) = start_intake_server(pool.clone(), tx_executor).await;
+    let (forwarder, task) =
+        TxForwarder::new(&url, Some(CA_CERT), AUTH_TOKEN.to_owned(), 10).unwrap();
+    tokio::spawn(task.run());
+
+    // The replica may claim that the transaction is executable, but the sequencer shouldn't trust it.
+    let tx = create_signed_transaction(&FUNDED_PRIVATE_KEY, 0);
+    let err = forwarder
+        .submit_tx(tx.clone(), TransactionExecutionMetrics::default())
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::Unexecutable(_));
+    assert!(!is_tx_stored(&pool, tx.hash()).await);
+
+    // Transactions failing validation (here, because of the insufficient balance) are rejected as well.
+    let tx = create_signed_transaction(&H256::repeat_byte(12), 0);
+    let err = forwarder
+        .submit_tx(tx.clone(), TransactionExecutionMetrics::default())
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::Unexecutable(_));
+    assert!(!is_tx_stored(&pool, tx.hash()).await);
+}
+
+#[tokio::test]
+async fn forwarded_transactions_are_decoded_by_sequencer() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let (url, _stop_sender) = start_intake_server(pool.clone(), successful_executor()).await;
+    let (forwarder, task) =
+        TxForwarder::new(&url, Some(CA_CERT), AUTH_TOKEN.to_owned(), 10).unwrap();
+    tokio::spawn(task.run());
+
+    // Raw bytes of this transaction are garbage, so the sequencer should reject it regardless of the decoded fields.
+    let tx = create_l2_transaction(10, 100);
+    let err = forwarder
+        .submit_tx(tx.clone(), TransactionExecutionMetrics::default())
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::Unexecutable(_));
+    assert!(!is_tx_stored(&pool, tx.hash()).await);
+}
+
+#[test]
+fn forwarding_requires_https() {
+    let err =
+        TxForwarder::new("http://127.0.0.1:3080", None, AUTH_TOKEN.to_owned(), 10).unwrap_err();
+    assert!(err.to_string().contains("https"), "{err}");
+}
@@ -1,50 +1,61 @@
 //! Helper module to submit transactions into the zkSync Network.
 
-use std::{cmp, sync::Arc, time::Instant};
+use std::{
+    cmp,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use multivm::{
-    interface::VmExecutionResultAndLogs,
+    interface::{ExecutionResult, VmExecutionResultAndLogs},
     utils::{adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead},
     vm_latest::constants::BLOCK_GAS_LIMIT,
 };
+use tokio::sync::watch;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
 use zksync_state::PostgresStorageCaches;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
-    fee::{Fee, TransactionExecutionMetrics},
+    api::L1TransactionSimulation,
+    fee::{Fee, FeeBreakdown, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     get_code_key, get_intrinsic_constants,
-    l1::is_l1_tx_type,
+    l1::{is_l1_tx_type, L1Tx},
     l2::{error::TxCheckError::TxDuplication, L2Tx},
     utils::storage_key_for_eth_balance,
     AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, MiniblockNumber, Nonce,
     PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
-    MAX_NEW_FACTORY_DEPS, U256,
+    U256,
 };
-use zksync_utils::h256_to_u256;
+use zksync_utils::{h256_to_u256, time::seconds_since_epoch};
 
 pub(super) use self::result::SubmitTxError;
-use self::tx_sink::TxSink;
+use self::{sponsorship::SponsorshipPolicy, tx_sink::TxSink};
 use crate::{
     api_server::{
         execution_sandbox::{
-            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, SubmitTxStage,
-            TransactionExecutor, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
-            SANDBOX_METRICS,
+            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, CallCache, CallCacheKey,
+            ExecutionBudgetGuard, SubmitTxStage, TransactionExecutor, TxExecutionArgs,
+            TxSharedArgs, ValidationCache, VmConcurrencyLimiter, VmPermit, SANDBOX_METRICS,
         },
         tx_sender::result::ApiCallResult,
     },
     fee_model::BatchFeeModelInputProvider,
-    state_keeper::seal_criteria::{ConditionalSealer, NoopSealer, SealData},
+    state_keeper::{
+        seal_criteria::{ConditionalSealer, NoopSealer, SealData},
+        TxSizeLimits,
+    },
     utils::pending_protocol_version,
 };
 
 pub mod master_pool_sink;
 pub mod proxy;
 mod result;
+pub mod sponsorship;
 #[cfg(test)]
 pub(crate) mod tests;
 pub mod tx_sink;
@@ -151,6 +162,10 @@ pub struct TxSenderBuilder {
     tx_sink: Arc<dyn TxSink>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Option<Arc<dyn ConditionalSealer>>,
+    /// Operator policy for fee sponsorship.
+    sponsorship_policy: Option<SponsorshipPolicy>,
+    /// Receiver of the chain freeze state.
+    chain_freeze: Option<watch::Receiver<bool>>,
 }
 
 impl TxSenderBuilder {
@@ -164,6 +179,8 @@ impl TxSenderBuilder {
             replica_connection_pool,
             tx_sink,
             sealer: None,
+            sponsorship_policy: None,
+            chain_freeze: None,
         }
     }
 
@@ -172,6 +189,17 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_sponsorship_policy(mut self, policy: Option<SponsorshipPolicy>) -> Self {
+        self.sponsorship_policy = policy;
+        self
+    }
+
+    /// Makes the sender reject transactions while the chain is frozen, as reported by the provided receiver.
+    pub fn with_chain_freeze(mut self, receiver: watch::Receiver<bool>) -> Self {
+        self.chain_freeze = Some(receiver);
+        self
+    }
+
     pub async fn build(
         self,
         batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -181,6 +209,16 @@ impl TxSenderBuilder {
     ) -> TxSender {
         // Use noop sealer if no sealer was explicitly provided.
         let sealer = self.sealer.unwrap_or_else(|| Arc::new(NoopSealer));
+        let validation_cache = self
+            .config
+            .validation_cache_capacity
+            .and_then(NonZeroUsize::new)
+            .map(ValidationCache::new);
+        let call_cache = self
+            .config
+            .call_cache_capacity
+            .and_then(NonZeroUsize::new)
+            .map(CallCache::new);
 
         TxSender(Arc::new(TxSenderInner {
             sender_config: self.config,
@@ -191,6 +229,10 @@ impl TxSenderBuilder {
             vm_concurrency_limiter,
             storage_caches,
             sealer,
+            sponsorship_policy: self.sponsorship_policy,
+            chain_freeze: self.chain_freeze,
+            validation_cache,
+            call_cache,
             executor: TransactionExecutor::Real,
         }))
     }
@@ -211,6 +253,17 @@ pub struct TxSenderConfig {
     pub l1_to_l2_transactions_compatibility_mode: bool,
     pub chain_id: L2ChainId,
     pub max_pubdata_per_batch: u64,
+    /// Multiplier applied to the base fee per gas when estimating fees for L1->L2 transactions.
+    pub priority_tx_fee_multiplier: f64,
+    /// Capacity of the cache for successful transaction validation results. If not set or 0, results are not cached.
+    pub validation_cache_capacity: Option<usize>,
+    /// Capacity of the cache for results of `eth_call`s pinned to a block hash. If not set or 0, results are not cached.
+    pub call_cache_capacity: Option<usize>,
+    /// Time budget for VM executions performed for a single API request. If exceeded, execution is stopped,
+    /// and the request fails with [`SubmitTxError::ExecutionTimeout`].
+    pub vm_execution_time_budget: Option<Duration>,
+    /// Size limits for submitted transactions; the same limits are enforced by the state keeper.
+    pub tx_size_limits: TxSizeLimits,
 }
 
 impl TxSenderConfig {
@@ -231,6 +284,14 @@ impl TxSenderConfig {
                 .l1_to_l2_transactions_compatibility_mode,
             chain_id,
             max_pubdata_per_batch: state_keeper_config.max_pubdata_per_batch,
+            priority_tx_fee_multiplier: state_keeper_config.priority_tx_fee_multiplier(),
+            validation_cache_capacity: web3_json_config.validation_cache_capacity,
+            call_cache_capacity: web3_json_config.call_cache_capacity,
+            vm_execution_time_budget: web3_json_config.vm_execution_time_budget(),
+            tx_size_limits: TxSizeLimits::new(
+                state_keeper_config,
+                Some(web3_json_config.max_tx_size),
+            ),
         }
     }
 }
@@ -250,6 +311,14 @@ pub struct TxSenderInner {
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
     pub(super) executor: TransactionExecutor,
+    /// Operator policy for fee sponsorship. If not set, no transactions are sponsored.
+    pub(super) sponsorship_policy: Option<SponsorshipPolicy>,
+    /// Receiver of the chain freeze state. If not set, the chain is never considered frozen.
+    chain_freeze: Option<watch::Receiver<bool>>,
+    /// Cache for successful transaction validation results.
+    validation_cache: Option<ValidationCache>,
+    /// Cache for results of `eth_call`s pinned to a block hash.
+    call_cache: Option<CallCache>,
 }
 
 #[derive(Clone)]
@@ -270,6 +339,25 @@ impl TxSender {
         self.0.storage_caches.clone()
     }
 
+    pub(crate) fn sponsorship_policy(&self) -> Option<&SponsorshipPolicy> {
+        self.0.sponsorship_policy.as_ref()
+    }
+
+    fn is_chain_frozen(&self) -> bool {
+        self.0
+            .chain_freeze
+            .as_ref()
+            .map_or(false, |receiver| *receiver.borrow())
+    }
+
+    /// Returns the execution time budget for a single API request, if one is configured.
+    pub(crate) fn execution_budget(&self) -> Option<ExecutionBudgetGuard> {
+        self.0
+            .sender_config
+            .vm_execution_time_budget
+            .map(ExecutionBudgetGuard::new)
+    }
+
     async fn acquire_replica_connection(&self) -> anyhow::Result<StorageProcessor<'_>> {
         self.0
             .replica_connection_pool
@@ -280,8 +368,18 @@ impl TxSender {
 
     #[tracing::instrument(skip(self, tx))]
     pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        if self.is_chain_frozen() {
+            return Err(SubmitTxError::ChainFrozen);
+        }
+        if self.0.batch_fee_input_provider.is_l1_data_stale() {
+            return Err(SubmitTxError::L1DataStale);
+        }
+
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::Validate].start();
         self.validate_tx(&tx).await?;
+        if let Some(policy) = &self.0.sponsorship_policy {
+            policy.check_tx(&tx).map_err(SubmitTxError::NotSponsored)?;
+        }
         stage_latency.observe();
 
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::DryRun].start();
@@ -292,6 +390,13 @@ impl TxSender {
         let block_args = BlockArgs::pending(&mut connection).await?;
         drop(connection);
 
+        if let Some(valid_until) = tx.common_data.valid_until {
+            if valid_until.is_expired(block_args.resolved_block_number(), seconds_since_epoch()) {
+                return Err(SubmitTxError::DeadlinePassed);
+            }
+        }
+
+        let execution_budget = self.execution_budget();
         let execution_output = self
             .0
             .executor
@@ -303,7 +408,10 @@ impl TxSender {
                 self.0.replica_connection_pool.clone(),
                 tx.clone().into(),
                 block_args,
-                vec![],
+                execution_budget
+                    .iter()
+                    .map(ExecutionBudgetGuard::tracer)
+                    .collect(),
             )
             .await?;
 
@@ -326,6 +434,7 @@ impl TxSender {
                 shared_args,
                 block_args,
                 computational_gas_limit,
+                self.0.validation_cache.clone(),
             )
             .await;
         stage_latency.observe();
@@ -336,6 +445,11 @@ impl TxSender {
         if !execution_output.are_published_bytecodes_ok {
             return Err(SubmitTxError::FailedToPublishCompressedBytecodes);
         }
+        if let Some(policy) = &self.0.sponsorship_policy {
+            policy
+                .check_execution(&tx, &execution_output.metrics)
+                .map_err(SubmitTxError::NotSponsored)?;
+        }
 
         let stage_started_at = Instant::now();
         self.ensure_tx_executable(tx.clone().into(), &execution_output.metrics, true)?;
@@ -427,12 +541,10 @@ impl TxSender {
             );
             return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee);
         }
-        if tx.execute.factory_deps_length() > MAX_NEW_FACTORY_DEPS {
-            return Err(SubmitTxError::TooManyFactoryDependencies(
-                tx.execute.factory_deps_length(),
-                MAX_NEW_FACTORY_DEPS,
-            ));
-        }
+        self.0
+            .sender_config
+            .tx_size_limits
+            .check(&tx.execute, tx.raw_bytes.as_ref())?;
 
         let intrinsic_consts = get_intrinsic_constants();
         assert!(
@@ -464,6 +576,9 @@ impl TxSender {
                 )
             })?;
 
+        // The same range applies to accounts with arbitrary nonce ordering, so that such an account
+        // cannot occupy an unbounded number of mempool slots. Reuse of nonces by these accounts is checked
+        // by the `NonceHolder` system contract during validation.
         if tx.common_data.nonce.0 < expected_nonce {
             Err(SubmitTxError::NonceIsTooLow(
                 expected_nonce,
@@ -560,6 +675,7 @@ impl TxSender {
         block_args: BlockArgs,
         base_fee: u64,
         vm_version: VmVersion,
+        execution_budget: Option<&ExecutionBudgetGuard>,
     ) -> anyhow::Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics)> {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
@@ -605,7 +721,10 @@ impl TxSender {
                 self.0.replica_connection_pool.clone(),
                 tx.clone(),
                 block_args,
-                vec![],
+                execution_budget
+                    .map(ExecutionBudgetGuard::tracer)
+                    .into_iter()
+                    .collect(),
             )
             .await?;
         Ok((execution_output.vm, execution_output.metrics))
@@ -627,10 +746,27 @@ impl TxSender {
 
     pub async fn get_txs_fee_in_wei(
         &self,
-        mut tx: Transaction,
+        tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
     ) -> Result<Fee, SubmitTxError> {
+        let (fee, _) = self
+            .get_txs_fee_in_wei_with_breakdown(
+                tx,
+                estimated_fee_scale_factor,
+                acceptable_overestimation,
+            )
+            .await?;
+        Ok(fee)
+    }
+
+    /// Same as [`Self::get_txs_fee_in_wei()`], but additionally returns a breakdown of the estimated gas limit.
+    pub async fn get_txs_fee_in_wei_with_breakdown(
+        &self,
+        mut tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+    ) -> Result<(Fee, FeeBreakdown), SubmitTxError> {
         let estimation_started_at = Instant::now();
 
         let mut connection = self.acquire_replica_connection().await?;
@@ -665,7 +801,9 @@ impl TxSender {
                 common_data.fee.max_priority_fee_per_gas = base_fee.into();
             }
             ExecuteTransactionCommon::L1(common_data) => {
-                common_data.max_fee_per_gas = base_fee.into();
+                // Priority transactions are expected to pay the operator-configured premium on top of the base fee.
+                let fee_per_gas = base_fee as f64 * self.0.sender_config.priority_tx_fee_multiplier;
+                common_data.max_fee_per_gas = (fee_per_gas as u64).into();
             }
             ExecuteTransactionCommon::ProtocolUpgrade(common_data) => {
                 common_data.max_fee_per_gas = base_fee.into();
@@ -714,6 +852,8 @@ impl TxSender {
         // Acquire the vm token for the whole duration of the binary search.
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+        // The budget is shared by all steps of the binary search.
+        let execution_budget = self.execution_budget();
 
         // We already know how many gas is needed to cover for the publishing of the bytecodes.
         // For L1->L2 transactions all the bytecodes have been made available on L1, so no funds need to be
@@ -770,6 +910,7 @@ impl TxSender {
                     block_args,
                     base_fee,
                     protocol_version.into(),
+                    execution_budget.as_ref(),
                 )
                 .await
                 .context("estimate_gas step failed")?;
@@ -810,10 +951,12 @@ impl TxSender {
                 block_args,
                 base_fee,
                 protocol_version.into(),
+                execution_budget.as_ref(),
             )
             .await
             .context("final estimate_gas step failed")?;
 
+        let gas_refunded = result.refunds.gas_refunded;
         result.into_api_call_result()?;
         self.ensure_tx_executable(tx.clone(), &tx_metrics, false)?;
 
@@ -852,24 +995,162 @@ impl TxSender {
                 }
             };
 
-        Ok(Fee {
-            max_fee_per_gas: base_fee.into(),
+        let fee = Fee {
+            // Equals to `base_fee` for L2 transactions; L1 transactions may include the fee premium.
+            max_fee_per_gas: tx.max_fee_per_gas(),
             max_priority_fee_per_gas: 0u32.into(),
             gas_limit: full_gas_limit.into(),
             gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+        };
+        // The part of the execution gas limit added by the scale factor is expected to be refunded if the transaction
+        // behaves the same way as during estimation; the upper bound is the refund observed in the final estimation step.
+        let min_expected_refund = tx_body_gas_limit.saturating_sub(upper_bound);
+        let max_expected_refund = cmp::max(min_expected_refund, gas_refunded);
+        let breakdown = FeeBreakdown {
+            execution_gas: tx_body_gas_limit.into(),
+            pubdata_gas: gas_for_bytecodes_pubdata.into(),
+            overhead_gas: overhead.into(),
+            gas_per_pubdata: gas_per_pubdata_byte.into(),
+            min_expected_refund: min_expected_refund.into(),
+            max_expected_refund: max_expected_refund.into(),
+        };
+        Ok((fee, breakdown))
+    }
+
+    /// Simulates a priority operation with the gas params specified in it, and estimates the L2 gas limit required
+    /// for it to succeed. Unspecified gas params are filled in the same way as during fee estimation.
+    pub async fn simulate_l1_tx(
+        &self,
+        mut tx: L1Tx,
+        to_mint: Option<U256>,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+    ) -> Result<L1TransactionSimulation, SubmitTxError> {
+        // Estimation is performed with the zero refund recipient, consistently with `zks_estimateGasL1ToL2`.
+        let mut tx_for_estimation = tx.clone();
+        tx_for_estimation.common_data.refund_recipient = Address::zero();
+        let required_fee = match self
+            .get_txs_fee_in_wei_with_breakdown(
+                tx_for_estimation.into(),
+                estimated_fee_scale_factor,
+                acceptable_overestimation,
+            )
+            .await
+        {
+            Ok((fee, _)) => Some(fee),
+            Err(SubmitTxError::ExecutionReverted(..)) => None,
+            Err(err) => return Err(err),
+        };
+
+        let mut connection = self.acquire_replica_connection().await?;
+        let block_args = BlockArgs::pending(&mut connection).await?;
+        let protocol_version = pending_protocol_version(&mut connection)
+            .await
+            .context("failed getting pending protocol version")?;
+        drop(connection);
+
+        let common_data = &mut tx.common_data;
+        if common_data.gas_limit.is_zero() {
+            common_data.gas_limit = required_fee
+                .as_ref()
+                .map_or(MAX_L2_TX_GAS_LIMIT.into(), |fee| fee.gas_limit);
+        }
+        let fee_input = self
+            .0
+            .batch_fee_input_provider
+            .get_batch_fee_input_scaled(
+                self.0.sender_config.gas_price_scale_factor,
+                self.0.sender_config.gas_price_scale_factor,
+            )
+            .await;
+        let fee_input = adjust_pubdata_price_for_tx(
+            fee_input,
+            common_data.gas_per_pubdata_limit,
+            protocol_version.into(),
+        );
+        let (base_fee, _) = derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+        if common_data.max_fee_per_gas.is_zero() {
+            let fee_per_gas = base_fee as f64 * self.0.sender_config.priority_tx_fee_multiplier;
+            common_data.max_fee_per_gas = (fee_per_gas as u64).into();
+        }
+        common_data.to_mint = to_mint.unwrap_or_else(|| {
+            tx.execute.value + common_data.max_fee_per_gas * common_data.gas_limit
+        });
+
+        let tx = Transaction::from(tx);
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+        let execution_budget = self.execution_budget();
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let execution_args =
+            TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
+        let execution_output = self
+            .0
+            .executor
+            .execute_tx_in_sandbox(
+                vm_permit,
+                self.shared_args_for_gas_estimate(fee_input),
+                true,
+                execution_args,
+                self.0.replica_connection_pool.clone(),
+                tx.clone(),
+                block_args,
+                execution_budget
+                    .iter()
+                    .map(ExecutionBudgetGuard::tracer)
+                    .collect(),
+            )
+            .await?;
+
+        let (success, output, revert_reason) = match execution_output.vm.result {
+            ExecutionResult::Success { output } => (true, output, None),
+            ExecutionResult::Revert { output } => (
+                false,
+                output.encoded_data(),
+                Some(output.to_user_friendly_string()),
+            ),
+            ExecutionResult::Halt { reason } => (false, vec![], Some(reason.to_string())),
+        };
+        let ExecuteTransactionCommon::L1(common_data) = tx.common_data else {
+            unreachable!("transaction was converted from an L1 transaction");
+        };
+        Ok(L1TransactionSimulation {
+            sender: common_data.sender,
+            refund_recipient: common_data.refund_recipient,
+            to_mint: common_data.to_mint,
+            gas_limit: common_data.gas_limit,
+            max_fee_per_gas: common_data.max_fee_per_gas,
+            gas_per_pubdata_limit: common_data.gas_per_pubdata_limit,
+            success,
+            output: output.into(),
+            revert_reason,
+            gas_used: execution_output.vm.statistics.gas_used.into(),
+            required_gas_limit: required_fee.map(|fee| fee.gas_limit),
         })
     }
 
+    /// Executes a call. If `cache_key` is provided (i.e., the call is deterministic), the output is looked up in
+    /// and saved to the call cache, provided that the cache is enabled.
     pub(super) async fn eth_call(
         &self,
         block_args: BlockArgs,
         tx: L2Tx,
+        cache_key: Option<CallCacheKey>,
     ) -> Result<Vec<u8>, SubmitTxError> {
+        let cache_and_key = self.0.call_cache.as_ref().zip(cache_key);
+        if let Some((cache, key)) = &cache_and_key {
+            if let Some(output) = cache.get(key) {
+                return Ok(output);
+            }
+        }
+
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
 
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        self.0
+        let execution_budget = self.execution_budget();
+        let output = self
+            .0
             .executor
             .execute_tx_eth_call(
                 vm_permit,
@@ -878,10 +1159,18 @@ impl TxSender {
                 tx,
                 block_args,
                 vm_execution_cache_misses_limit,
-                vec![],
+                execution_budget
+                    .iter()
+                    .map(ExecutionBudgetGuard::tracer)
+                    .collect(),
             )
             .await?
-            .into_api_call_result()
+            .into_api_call_result()?;
+        // Only successful outputs are cached; errors may be caused by transient conditions (e.g., execution timeouts).
+        if let Some((cache, key)) = cache_and_key {
+            cache.insert(key, output.clone());
+        }
+        Ok(output)
     }
 
     pub async fn gas_price(&self) -> anyhow::Result<u64> {
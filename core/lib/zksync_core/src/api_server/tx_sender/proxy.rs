@@ -128,6 +128,14 @@ impl TxProxy {
         let raw_tx = zksync_types::Bytes(input_data.to_vec());
         let tx_hash = tx.hash();
         tracing::info!("Proxying tx {tx_hash:?}");
+        if let Some(valid_until) = tx.common_data.valid_until {
+            return self
+                .client
+                .send_raw_transaction_with_deadline(raw_tx, valid_until)
+                .rpc_context("send_raw_transaction_with_deadline")
+                .with_arg("tx_hash", &tx_hash)
+                .await;
+        }
         self.client
             .send_raw_transaction(raw_tx)
             .rpc_context("send_raw_transaction")
@@ -1,9 +1,16 @@
+use std::time::Duration;
+
 use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
 use thiserror::Error;
 use zksync_types::{l2::error::TxCheckError, U256};
-use zksync_web3_decl::error::EnrichedClientError;
+use zksync_web3_decl::error::{EnrichedClientError, TxErrorCode, TxErrorData};
 
-use crate::api_server::execution_sandbox::{SandboxExecutionError, ValidationError};
+use crate::{
+    api_server::execution_sandbox::{
+        ExecutionTimeoutError, SandboxExecutionError, ValidationError,
+    },
+    state_keeper::TxSizeLimitError,
+};
 
 /// Errors that con occur submitting a transaction or estimating gas for its execution.
 #[derive(Debug, Error)]
@@ -53,6 +60,9 @@ pub enum SubmitTxError {
         "too many factory dependencies in the transaction. {0} provided, while only {1} allowed"
     )]
     TooManyFactoryDependencies(usize, usize),
+    /// The transaction exceeds one of the per-chain size limits.
+    #[error("transaction is too large: {0}")]
+    TxTooLarge(TxSizeLimitError),
     #[error("max fee per gas higher than 2^32")]
     FeePerGasTooHigh,
     #[error("max fee per pubdata byte higher than 2^32")]
@@ -70,9 +80,27 @@ pub enum SubmitTxError {
     ProxyError(#[from] EnrichedClientError),
     #[error("not enough gas to publish compressed bytecodes")]
     FailedToPublishCompressedBytecodes,
+    /// The transaction cannot be included into the next miniblock because of its deadline.
+    #[error("transaction deadline has passed")]
+    DeadlinePassed,
+    /// The transaction uses the sponsoring paymaster, but doesn't satisfy the operator sponsorship policy.
+    #[error("transaction is not eligible for sponsorship: {0}")]
+    NotSponsored(String),
+    /// VM execution for the request has exceeded the configured time budget.
+    #[error("execution timed out: exceeded time budget of {}ms", .0.as_millis())]
+    ExecutionTimeout(Duration),
+    /// The node doesn't accept transactions, e.g. because it runs in the read-only forensics mode.
+    #[error("transaction submission is disabled on this node")]
+    SubmissionDisabled,
+    /// The chain is frozen (emergency mode); transactions are not accepted until it's unfrozen.
+    #[error("chain is frozen; transactions are not accepted")]
+    ChainFrozen,
+    /// L1 data used to price transactions is stale; transactions are not accepted until fresh data is received.
+    #[error("L1 data is stale; transactions are temporarily not accepted")]
+    L1DataStale,
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
-    Internal(#[from] anyhow::Error),
+    Internal(anyhow::Error),
 }
 
 impl SubmitTxError {
@@ -98,21 +126,82 @@ impl SubmitTxError {
             Self::UnexpectedVMBehavior(_) => "unexpected-vm-behavior",
             Self::UnrealisticPubdataPriceLimit => "unrealistic-pubdata-price-limit",
             Self::TooManyFactoryDependencies(_, _) => "too-many-factory-dependencies",
+            Self::TxTooLarge(_) => "tx-too-large",
             Self::FeePerGasTooHigh => "gas-price-limit-too-high",
             Self::FeePerPubdataByteTooHigh => "pubdata-price-limit-too-high",
             Self::InsufficientFundsForTransfer => "insufficient-funds-for-transfer",
             Self::IntrinsicGas => "intrinsic-gas",
             Self::ProxyError(_) => "proxy-error",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
+            Self::DeadlinePassed => "deadline-passed",
+            Self::NotSponsored(_) => "not-sponsored",
+            Self::ExecutionTimeout(_) => "execution-timeout",
+            Self::SubmissionDisabled => "submission-disabled",
+            Self::ChainFrozen => "chain-frozen",
+            Self::L1DataStale => "l1-data-stale",
             Self::Internal(_) => "internal",
         }
     }
 
-    pub fn data(&self) -> Vec<u8> {
+    /// Returns the stable error code returned to API clients. Returns `None` for internal and proxied errors.
+    pub fn code(&self) -> Option<TxErrorCode> {
+        Some(match self {
+            Self::NonceIsTooHigh(..) => TxErrorCode::NonceTooHigh,
+            Self::NonceIsTooLow(..) => TxErrorCode::NonceTooLow,
+            Self::IncorrectTx(_) => TxErrorCode::IncorrectTx,
+            Self::NotEnoughBalanceForFeeValue(..) => TxErrorCode::InsufficientBalanceForFee,
+            Self::ExecutionReverted(..) => TxErrorCode::ExecutionReverted,
+            Self::GasLimitIsTooBig => TxErrorCode::GasLimitTooBig,
+            Self::Unexecutable(_) => TxErrorCode::Unexecutable,
+            Self::RateLimitExceeded => TxErrorCode::RateLimitExceeded,
+            Self::ServerShuttingDown => TxErrorCode::ServerShuttingDown,
+            Self::BootloaderFailure(_) => TxErrorCode::BootloaderFailure,
+            Self::ValidationFailed(_) => TxErrorCode::AccountValidationFailed,
+            Self::FailedToChargeFee(_) => TxErrorCode::FailedToChargeFee,
+            Self::PaymasterValidationFailed(_) => TxErrorCode::PaymasterValidationFailed,
+            Self::PrePaymasterPreparationFailed(_) => TxErrorCode::PrePaymasterPreparationFailed,
+            Self::FromIsNotAnAccount => TxErrorCode::FromIsNotAnAccount,
+            Self::MaxFeePerGasTooLow => TxErrorCode::MaxFeePerGasTooLow,
+            Self::MaxPriorityFeeGreaterThanMaxFee => TxErrorCode::MaxPriorityFeeGreaterThanMaxFee,
+            Self::UnexpectedVMBehavior(_) => TxErrorCode::UnexpectedVmBehavior,
+            Self::UnrealisticPubdataPriceLimit => TxErrorCode::UnrealisticPubdataPriceLimit,
+            Self::TooManyFactoryDependencies(..) => TxErrorCode::TooManyFactoryDeps,
+            Self::TxTooLarge(_) => TxErrorCode::TxTooLarge,
+            Self::FeePerGasTooHigh => TxErrorCode::FeePerGasTooHigh,
+            Self::FeePerPubdataByteTooHigh => TxErrorCode::FeePerPubdataByteTooHigh,
+            Self::InsufficientFundsForTransfer => TxErrorCode::InsufficientFundsForTransfer,
+            Self::IntrinsicGas => TxErrorCode::IntrinsicGasTooLow,
+            Self::FailedToPublishCompressedBytecodes => {
+                TxErrorCode::FailedToPublishCompressedBytecodes
+            }
+            Self::DeadlinePassed => TxErrorCode::DeadlinePassed,
+            Self::NotSponsored(_) => TxErrorCode::NotSponsored,
+            Self::ExecutionTimeout(_) => TxErrorCode::ExecutionTimeout,
+            Self::SubmissionDisabled => TxErrorCode::SubmissionDisabled,
+            Self::ChainFrozen => TxErrorCode::ChainFrozen,
+            Self::L1DataStale => TxErrorCode::L1DataStale,
+            Self::ProxyError(_) | Self::Internal(_) => return None,
+        })
+    }
+
+    /// Returns the data payload returned to API clients. Returns `None` for internal and proxied errors.
+    pub fn data(&self) -> Option<TxErrorData> {
         if let Self::ExecutionReverted(_, data) = self {
-            data.clone()
+            Some(TxErrorData::revert(data.clone()))
         } else {
-            Vec::new()
+            self.code().map(TxErrorData::from)
+        }
+    }
+}
+
+impl From<TxSizeLimitError> for SubmitTxError {
+    fn from(err: TxSizeLimitError) -> Self {
+        match err {
+            // Preserved for backward compatibility.
+            TxSizeLimitError::FactoryDeps(actual, max) => {
+                Self::TooManyFactoryDependencies(actual, max)
+            }
+            _ => Self::TxTooLarge(err),
         }
     }
 }
@@ -146,10 +235,20 @@ impl From<SandboxExecutionError> for SubmitTxError {
     }
 }
 
+impl From<anyhow::Error> for SubmitTxError {
+    fn from(err: anyhow::Error) -> Self {
+        // Execution timeouts are surfaced as is, even if they are wrapped with context.
+        match err.downcast_ref::<ExecutionTimeoutError>() {
+            Some(ExecutionTimeoutError(limit)) => Self::ExecutionTimeout(*limit),
+            None => Self::Internal(err),
+        }
+    }
+}
+
 impl From<ValidationError> for SubmitTxError {
     fn from(err: ValidationError) -> Self {
         match err {
-            ValidationError::Internal(err) => Self::Internal(err),
+            ValidationError::Internal(err) => err.into(),
             ValidationError::Vm(err) => Self::ValidationFailed(err.to_string()),
         }
     }
@@ -0,0 +1,301 @@
+//! Operator-configured sponsorship of transaction fees.
+//!
+//! The policy lists contracts (and, optionally, methods) whose callers don't pay fees. Fees for such transactions
+//! are paid by an operator-funded paymaster, which is expected to approve any transaction (see `sponsoring-paymaster.sol`
+//! in test contracts for a reference implementation). The allowlist and the gas / pubdata limits are enforced
+//! by the API server when the transaction is submitted, and by the state keeper when the transaction is executed,
+//! so that transactions reaching the mempool by other routes cannot bypass the policy. L1 priority transactions
+//! cannot specify a paymaster, so they are never sponsored.
+
+use std::path::Path;
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_types::{
+    fee::{Fee, FeeSponsorship, TransactionExecutionMetrics},
+    l2::L2Tx,
+    web3::types::Bytes,
+    Address, Execute, ExecuteTransactionCommon, L2TxCommonData, Transaction, U256,
+};
+
+/// Rule describing transactions sponsored by the operator.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SponsorshipRule {
+    /// Contract called by sponsored transactions.
+    pub contract: Address,
+    /// 4-byte selectors of sponsored contract methods. If empty, all calls to the contract are sponsored.
+    #[serde(default)]
+    pub selectors: Vec<Bytes>,
+    /// Maximum gas limit of a sponsored transaction.
+    pub max_gas_limit: u64,
+    /// Maximum number of pubdata bytes a sponsored transaction may publish.
+    pub max_pubdata: u32,
+}
+
+impl SponsorshipRule {
+    fn matches(&self, contract: Address, calldata: &[u8]) -> bool {
+        if contract != self.contract {
+            return false;
+        }
+        self.selectors.is_empty()
+            || self
+                .selectors
+                .iter()
+                .any(|selector| calldata.starts_with(&selector.0))
+    }
+}
+
+/// Declarative fee sponsorship policy loaded from a JSON file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SponsorshipPolicy {
+    /// Operator-funded paymaster paying for sponsored transactions.
+    pub paymaster: Address,
+    /// Sponsorship rules. A transaction is sponsored if it matches any of the rules.
+    pub rules: Vec<SponsorshipRule>,
+}
+
+impl SponsorshipPolicy {
+    /// Loads the policy from a JSON file.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let json = std::fs::read(path)
+            .with_context(|| format!("failed reading sponsorship policy from {path:?}"))?;
+        Self::from_json(&json)
+            .with_context(|| format!("failed parsing sponsorship policy from {path:?}"))
+    }
+
+    fn from_json(json: &[u8]) -> anyhow::Result<Self> {
+        let policy: Self = serde_json::from_slice(json)?;
+        for rule in &policy.rules {
+            if let Some(selector) = rule.selectors.iter().find(|selector| selector.0.len() != 4) {
+                anyhow::bail!(
+                    "selector {selector:?} for contract {:?} is not 4 bytes long",
+                    rule.contract
+                );
+            }
+        }
+        Ok(policy)
+    }
+
+    fn matching_rule(&self, contract: Address, calldata: &[u8]) -> Option<&SponsorshipRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(contract, calldata))
+    }
+
+    /// Returns the sponsorship the transaction with the specified `fee` estimate is eligible for.
+    pub(crate) fn sponsorship(
+        &self,
+        contract: Address,
+        calldata: &[u8],
+        fee: &Fee,
+    ) -> Option<FeeSponsorship> {
+        let rule = self.matching_rule(contract, calldata)?;
+        let max_gas_limit = U256::from(rule.max_gas_limit);
+        if fee.gas_limit > max_gas_limit {
+            return None;
+        }
+        Some(FeeSponsorship {
+            paymaster: self.paymaster,
+            max_sponsored_fee: fee.max_total_fee(),
+            max_gas_limit,
+            max_pubdata: rule.max_pubdata,
+        })
+    }
+
+    /// Checks a submitted transaction against the policy before its execution. Transactions not using
+    /// the sponsoring paymaster are not affected by the policy.
+    pub(super) fn check_tx(&self, tx: &L2Tx) -> Result<(), String> {
+        self.check_l2_tx(&tx.common_data, &tx.execute)
+    }
+
+    /// Checks pubdata published by a sponsored transaction during its execution in the sandbox.
+    pub(super) fn check_execution(
+        &self,
+        tx: &L2Tx,
+        metrics: &TransactionExecutionMetrics,
+    ) -> Result<(), String> {
+        self.check_l2_tx_pubdata(&tx.common_data, &tx.execute, metrics.pubdata_published)
+    }
+
+    /// Same as [`Self::check_tx()`], but for a transaction executed by the state keeper.
+    pub(crate) fn check_transaction(&self, tx: &Transaction) -> Result<(), String> {
+        match &tx.common_data {
+            ExecuteTransactionCommon::L2(data) => self.check_l2_tx(data, &tx.execute),
+            _ => Ok(()),
+        }
+    }
+
+    /// Same as [`Self::check_execution()`], but for a transaction executed by the state keeper.
+    pub(crate) fn check_transaction_pubdata(
+        &self,
+        tx: &Transaction,
+        pubdata_published: u32,
+    ) -> Result<(), String> {
+        match &tx.common_data {
+            ExecuteTransactionCommon::L2(data) => {
+                self.check_l2_tx_pubdata(data, &tx.execute, pubdata_published)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_l2_tx(&self, data: &L2TxCommonData, execute: &Execute) -> Result<(), String> {
+        if data.paymaster_params.paymaster != self.paymaster {
+            return Ok(());
+        }
+        let contract = execute.contract_address;
+        let Some(rule) = self.matching_rule(contract, &execute.calldata) else {
+            return Err(format!("calls to {contract:?} are not sponsored"));
+        };
+        if data.fee.gas_limit > rule.max_gas_limit.into() {
+            return Err(format!(
+                "gas limit {} exceeds sponsored limit {}",
+                data.fee.gas_limit, rule.max_gas_limit
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_l2_tx_pubdata(
+        &self,
+        data: &L2TxCommonData,
+        execute: &Execute,
+        pubdata_published: u32,
+    ) -> Result<(), String> {
+        if data.paymaster_params.paymaster != self.paymaster {
+            return Ok(());
+        }
+        let Some(rule) = self.matching_rule(execute.contract_address, &execute.calldata) else {
+            return Ok(()); // Checked in `check_l2_tx()`
+        };
+        if pubdata_published > rule.max_pubdata {
+            return Err(format!(
+                "transaction publishes {pubdata_published} bytes of pubdata, while only {} bytes are sponsored",
+                rule.max_pubdata
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{transaction_request::PaymasterParams, Nonce};
+
+    use super::*;
+
+    const POLICY: &str = r#"{
+        "paymaster": "0x000000000000000000000000000000000000beef",
+        "rules": [
+            {
+                "contract": "0x0000000000000000000000000000000000000001",
+                "selectors": ["0xa9059cbb"],
+                "max_gas_limit": 1000000,
+                "max_pubdata": 200
+            },
+            {
+                "contract": "0x0000000000000000000000000000000000000002",
+                "max_gas_limit": 5000000,
+                "max_pubdata": 1000
+            }
+        ]
+    }"#;
+
+    fn sponsored_tx(contract: Address, calldata: Vec<u8>, gas_limit: u64) -> L2Tx {
+        let mut tx = L2Tx::new(
+            contract,
+            calldata,
+            Nonce(0),
+            Fee {
+                gas_limit: gas_limit.into(),
+                max_fee_per_gas: 100.into(),
+                max_priority_fee_per_gas: 0.into(),
+                gas_per_pubdata_limit: 800.into(),
+            },
+            Address::repeat_byte(0xaa),
+            U256::zero(),
+            None,
+            PaymasterParams::default(),
+        );
+        tx.common_data.paymaster_params.paymaster = Address::from_low_u64_be(0xbeef);
+        tx
+    }
+
+    #[test]
+    fn parsing_policy() {
+        let policy = SponsorshipPolicy::from_json(POLICY.as_bytes()).unwrap();
+        assert_eq!(policy.rules.len(), 2);
+        assert_eq!(policy.rules[0].selectors[0].0, [0xa9, 0x05, 0x9c, 0xbb]);
+
+        let invalid_policy = POLICY.replace("0xa9059cbb", "0xa9059c");
+        let err = SponsorshipPolicy::from_json(invalid_policy.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("not 4 bytes long"), "{err}");
+    }
+
+    #[test]
+    fn matching_transactions() {
+        let policy = SponsorshipPolicy::from_json(POLICY.as_bytes()).unwrap();
+        let contract = Address::from_low_u64_be(1);
+        let transfer_calldata = [0xa9, 0x05, 0x9c, 0xbb, 0, 0, 0, 1];
+
+        let tx = sponsored_tx(contract, transfer_calldata.to_vec(), 500_000);
+        policy.check_tx(&tx).unwrap();
+        let tx = sponsored_tx(contract, vec![1, 2, 3, 4], 500_000);
+        policy.check_tx(&tx).unwrap_err();
+        let tx = sponsored_tx(contract, transfer_calldata.to_vec(), 2_000_000);
+        policy.check_tx(&tx).unwrap_err();
+        // All methods of the second contract are sponsored.
+        let tx = sponsored_tx(Address::from_low_u64_be(2), vec![], 2_000_000);
+        policy.check_tx(&tx).unwrap();
+        // Unknown contract is not sponsored, but this only matters if the sponsoring paymaster is used.
+        let mut tx = sponsored_tx(Address::from_low_u64_be(3), vec![], 500_000);
+        policy.check_tx(&tx).unwrap_err();
+        tx.common_data.paymaster_params.paymaster = Address::zero();
+        policy.check_tx(&tx).unwrap();
+
+        let tx = sponsored_tx(contract, transfer_calldata.to_vec(), 500_000);
+        let mut metrics = TransactionExecutionMetrics {
+            pubdata_published: 100,
+            ..TransactionExecutionMetrics::default()
+        };
+        policy.check_execution(&tx, &metrics).unwrap();
+        metrics.pubdata_published = 300;
+        policy.check_execution(&tx, &metrics).unwrap_err();
+
+        // The same checks are applied to transactions executed by the state keeper.
+        let tx = Transaction::from(tx);
+        policy.check_transaction(&tx).unwrap();
+        policy.check_transaction_pubdata(&tx, 100).unwrap();
+        policy.check_transaction_pubdata(&tx, 300).unwrap_err();
+        let tx = Transaction::from(sponsored_tx(contract, vec![1, 2, 3, 4], 500_000));
+        policy.check_transaction(&tx).unwrap_err();
+    }
+
+    #[test]
+    fn reporting_sponsorship() {
+        let policy = SponsorshipPolicy::from_json(POLICY.as_bytes()).unwrap();
+        let contract = Address::from_low_u64_be(2);
+        let fee = Fee {
+            gas_limit: 1_000_000.into(),
+            max_fee_per_gas: 100.into(),
+            max_priority_fee_per_gas: 0.into(),
+            gas_per_pubdata_limit: 800.into(),
+        };
+        let sponsorship = policy.sponsorship(contract, &[], &fee).unwrap();
+        assert_eq!(sponsorship.paymaster, policy.paymaster);
+        assert_eq!(sponsorship.max_sponsored_fee, 100_000_000.into());
+        assert_eq!(sponsorship.max_pubdata, 1_000);
+
+        let expensive_fee = Fee {
+            gas_limit: 10_000_000.into(),
+            ..fee.clone()
+        };
+        assert_eq!(policy.sponsorship(contract, &[], &expensive_fee), None);
+        assert_eq!(
+            policy.sponsorship(Address::from_low_u64_be(3), &[], &fee),
+            None
+        );
+    }
+}
@@ -1,12 +1,25 @@
 //! Tests for the transaction sender.
 
-use zksync_types::{get_nonce_key, L1BatchNumber, StorageLog};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use assert_matches::assert_matches;
+use multivm::interface::ExecutionResult;
+use zksync_types::{
+    api, fee_model::FeeParams, get_is_account_key, get_nonce_key, transaction_request::CallRequest,
+    L1BatchNumber, StorageLog,
+};
 
 use super::*;
 use crate::{
-    api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
+    api_server::{
+        execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
+        web3::backend_jsonrpsee::into_jsrpc_error,
+    },
     genesis::{ensure_genesis_state, GenesisParams},
-    utils::testonly::{create_miniblock, prepare_recovery_snapshot, MockBatchFeeParamsProvider},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+        MockBatchFeeParamsProvider,
+    },
 };
 
 pub(crate) async fn create_test_tx_sender(
@@ -32,11 +45,13 @@ pub(crate) async fn create_test_tx_sender(
         &web3_config,
         &state_keeper_config,
         pool.clone(),
-        pool,
+        Some(pool),
         batch_fee_model_input_provider,
         storage_caches,
+        watch::channel(false).1,
     )
-    .await;
+    .await
+    .unwrap();
 
     Arc::get_mut(&mut tx_sender.0).unwrap().executor = tx_executor;
     (tx_sender, vm_barrier)
@@ -146,3 +161,167 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
     let nonce = tx_sender.get_expected_nonce(missing_address).await.unwrap();
     assert_eq!(nonce, Nonce(0));
 }
+
+#[tokio::test]
+async fn caching_calls_pinned_to_block_hash() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let l2_chain_id = L2ChainId::default();
+    ensure_genesis_state(&mut storage, l2_chain_id, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let genesis_hash = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(0))
+        .await
+        .unwrap()
+        .unwrap()
+        .hash;
+
+    let call_count = Arc::new(AtomicUsize::new(0));
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_call_responses({
+        let call_count = call_count.clone();
+        move |_, _| {
+            call_count.fetch_add(1, Ordering::Relaxed);
+            ExecutionResult::Success {
+                output: b"output".to_vec(),
+            }
+        }
+    });
+    let (mut tx_sender, _) =
+        create_test_tx_sender(pool.clone(), l2_chain_id, tx_executor.into()).await;
+    Arc::get_mut(&mut tx_sender.0).unwrap().call_cache =
+        Some(CallCache::new(NonZeroUsize::new(10).unwrap()));
+
+    let block_id = api::BlockId::Hash(genesis_hash);
+    let start_info = BlockStartInfo::new(&mut storage).await.unwrap();
+    let block_args = BlockArgs::new(&mut storage, block_id, start_info)
+        .await
+        .unwrap();
+    let request = CallRequest {
+        to: Some(Address::repeat_byte(2)),
+        data: Some(b"call".to_vec().into()),
+        ..CallRequest::default()
+    };
+    let cache_key = CallCacheKey::new(genesis_hash, &request);
+    for _ in 0..3 {
+        let tx = L2Tx::from_request(request.clone().into(), usize::MAX).unwrap();
+        let output = tx_sender
+            .eth_call(block_args, tx, Some(cache_key))
+            .await
+            .unwrap();
+        assert_eq!(output, b"output");
+    }
+    assert_eq!(call_count.load(Ordering::Relaxed), 1);
+
+    // Calls without a cache key are always executed.
+    let tx = L2Tx::from_request(request.into(), usize::MAX).unwrap();
+    tx_sender.eth_call(block_args, tx, None).await.unwrap();
+    assert_eq!(call_count.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn nonce_limits_for_account_with_arbitrary_ordering() {
+    let pool = ConnectionPool::test_pool().await;
+    let l2_chain_id = L2ChainId::default();
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, l2_chain_id, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let mut tx = create_l2_transaction(10, 100);
+    let account = tx.initiator_account();
+    // Mark the account as using arbitrary nonce ordering (`nonceOrdering` is stored in byte 30 of the account info).
+    let mut account_info = H256::zero();
+    account_info.0[30] = 1;
+    let account_info_log = StorageLog::new_write_log(get_is_account_key(&account), account_info);
+    let nonce_log = StorageLog::new_write_log(get_nonce_key(&account), H256::from_low_u64_be(5));
+    storage
+        .storage_logs_dal()
+        .append_storage_logs(
+            MiniblockNumber(0),
+            &[(H256::default(), vec![account_info_log, nonce_log])],
+        )
+        .await
+        .unwrap();
+
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (tx_sender, _) = create_test_tx_sender(pool.clone(), l2_chain_id, tx_executor).await;
+    let max_nonce_ahead = tx_sender.0.sender_config.max_nonce_ahead;
+
+    tx.common_data.nonce = Nonce(5 + max_nonce_ahead);
+    tx_sender.validate_account_nonce(&tx).await.unwrap();
+    tx.common_data.nonce = Nonce(5 + max_nonce_ahead + 1);
+    let err = tx_sender.validate_account_nonce(&tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::NonceIsTooHigh(5, max, _) if max == 5 + max_nonce_ahead);
+    tx.common_data.nonce = Nonce(4);
+    let err = tx_sender.validate_account_nonce(&tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::NonceIsTooLow(5, ..));
+}
+
+#[tokio::test]
+async fn rejecting_transactions_when_chain_is_frozen() {
+    let pool = ConnectionPool::test_pool().await;
+    let l2_chain_id = L2ChainId::default();
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) = create_test_tx_sender(pool, l2_chain_id, tx_executor).await;
+    let (_freeze_sender, freeze_receiver) = watch::channel(true);
+    Arc::get_mut(&mut tx_sender.0).unwrap().chain_freeze = Some(freeze_receiver);
+
+    let tx = create_l2_transaction(10, 100);
+    let err = tx_sender.submit_tx(tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::ChainFrozen);
+}
+
+#[derive(Debug)]
+struct StaleL1DataProvider;
+
+impl BatchFeeModelInputProvider for StaleL1DataProvider {
+    fn get_fee_model_params(&self) -> FeeParams {
+        FeeParams::sensible_v1_default()
+    }
+
+    fn is_l1_data_stale(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn rejecting_transactions_when_l1_data_is_stale() {
+    let pool = ConnectionPool::test_pool().await;
+    let l2_chain_id = L2ChainId::default();
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) = create_test_tx_sender(pool, l2_chain_id, tx_executor).await;
+    Arc::get_mut(&mut tx_sender.0)
+        .unwrap()
+        .batch_fee_input_provider = Arc::new(StaleL1DataProvider);
+
+    let tx = create_l2_transaction(10, 100);
+    let err = tx_sender.submit_tx(tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::L1DataStale);
+}
+
+#[test]
+fn converting_submit_errors_to_rpc_errors() {
+    let err = SubmitTxError::NonceIsTooLow(5, 10, 3).into_web3_error("test");
+    let err = into_jsrpc_error(err);
+    assert_eq!(err.code(), 3);
+    assert!(err.message().starts_with("nonce too low"), "{err:?}");
+    let data: serde_json::Value = serde_json::from_str(err.data().unwrap().get()).unwrap();
+    assert_eq!(data, serde_json::json!({ "code": "nonce_too_low" }));
+
+    let err = SubmitTxError::ExecutionReverted("oops".to_owned(), vec![1, 2, 3]);
+    let err = into_jsrpc_error(err.into_web3_error("test"));
+    assert_eq!(err.code(), 3);
+    let data: serde_json::Value = serde_json::from_str(err.data().unwrap().get()).unwrap();
+    assert_eq!(data, serde_json::json!("0x010203"));
+
+    let err = into_jsrpc_error(SubmitTxError::SubmissionDisabled.into_web3_error("test"));
+    let data: serde_json::Value = serde_json::from_str(err.data().unwrap().get()).unwrap();
+    assert_eq!(data, serde_json::json!({ "code": "submission_disabled" }));
+
+    let err = SubmitTxError::Internal(anyhow::anyhow!("database is down"));
+    let err = into_jsrpc_error(err.into_web3_error("test"));
+    assert!(err.data().is_none());
+}
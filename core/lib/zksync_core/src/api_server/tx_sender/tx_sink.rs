@@ -61,3 +61,18 @@ pub trait TxSink: std::fmt::Debug + Send + Sync + 'static {
         Ok(None)
     }
 }
+
+/// Sink rejecting all transactions. Used by nodes that must never modify the database, e.g. in the forensics mode.
+#[derive(Debug)]
+pub struct ReadOnlySink;
+
+#[async_trait::async_trait]
+impl TxSink for ReadOnlySink {
+    async fn submit_tx(
+        &self,
+        _tx: L2Tx,
+        _execution_metrics: TransactionExecutionMetrics,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        Err(SubmitTxError::SubmissionDisabled)
+    }
+}
@@ -0,0 +1,75 @@
+//! Internal JSON-RPC server exposing the `admin` namespace.
+
+use std::net::SocketAddr;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_web3_decl::{
+    jsonrpsee::server::{ServerBuilder, ServerHandle},
+    namespaces::AdminNamespaceServer,
+};
+
+use super::namespaces::AdminNamespace;
+
+/// HTTP JSON-RPC server serving privileged operator methods (e.g., freezing the chain). The server is separate
+/// from the public API servers and is bound to the loopback interface, so that admin methods are never reachable
+/// by API clients.
+#[derive(Debug)]
+pub struct AdminServer {
+    local_addr: SocketAddr,
+    server_handle: ServerHandle,
+    stop_receiver: watch::Receiver<bool>,
+}
+
+impl AdminServer {
+    /// Binds the server to `127.0.0.1:{port}` and starts serving requests. Admin methods modify the node state,
+    /// so `master_pool` must be connected to the master database.
+    pub async fn new(
+        port: u16,
+        master_pool: ConnectionPool,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Self> {
+        let bind_address = SocketAddr::from(([127, 0, 0, 1], port));
+        let server = ServerBuilder::default()
+            .http_only()
+            .build(bind_address)
+            .await
+            .with_context(|| format!("Failed binding admin JSON-RPC server to {bind_address}"))?;
+        let local_addr = server
+            .local_addr()
+            .context("Failed getting local address for admin JSON-RPC server")?;
+        let server_handle = server.start(AdminNamespace::new(master_pool).into_rpc());
+        tracing::info!("Initialized admin API on {local_addr:?}");
+
+        Ok(Self {
+            local_addr,
+            server_handle,
+            stop_receiver,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        tokio::select! {
+            res = self.stop_receiver.changed() => {
+                if res.is_err() {
+                    tracing::warn!(
+                        "Stop signal sender for admin JSON-RPC server was dropped without sending a signal"
+                    );
+                }
+                tracing::info!("Stop signal received, admin JSON-RPC server is shutting down");
+                self.server_handle.stop().ok();
+                self.server_handle.stopped().await;
+            }
+            () = self.server_handle.clone().stopped() => {
+                anyhow::bail!("Admin JSON-RPC server stopped unexpectedly");
+            }
+        }
+        tracing::info!("Admin JSON-RPC server stopped");
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+//! Negotiation of the client API level. Clients declare the level they support via the [`API_LEVEL_HEADER`]
+//! HTTP header; the declared level determines which [`ApiCapability`]s changing response encoding
+//! are enabled for the client.
+//!
+//! [`ApiCapability`]: zksync_types::api::ApiCapability
+
+use std::task::{Context, Poll};
+
+use axum::http::{HeaderMap, Request};
+use tokio::task::futures::TaskLocalFuture;
+use zksync_types::api::ApiCapability;
+
+/// Name of the HTTP header used by clients to declare their API level.
+pub(crate) const API_LEVEL_HEADER: &str = "zksync-api-level";
+
+tokio::task_local! {
+    static CLIENT_API_LEVEL: u32;
+}
+
+/// Returns the API level declared by the client for the currently processed request. Returns 0 (i.e., the baseline level)
+/// if the level is not declared, e.g. for WS requests, or for HTTP requests without [`API_LEVEL_HEADER`].
+pub(crate) fn client_api_level() -> u32 {
+    CLIENT_API_LEVEL.try_with(|level| *level).unwrap_or(0)
+}
+
+fn parse_api_level(headers: &HeaderMap) -> u32 {
+    let Some(value) = headers.get(API_LEVEL_HEADER) else {
+        return 0;
+    };
+    let level = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.trim().parse().ok());
+    // Malformed levels are treated as the baseline level so that responses have the most compatible encoding.
+    level.map_or(0, |level: u32| level.min(ApiCapability::MAX_CLIENT_LEVEL))
+}
+
+/// Tower layer extracting the client API level from HTTP request headers. Only has effect for HTTP transport;
+/// WS requests are processed outside the scope of the HTTP request and always use the baseline level.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ApiLevelLayer;
+
+impl<S> tower::Layer<S> for ApiLevelLayer {
+    type Service = ApiLevelService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiLevelService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ApiLevelService<S> {
+    inner: S,
+}
+
+impl<S, B> tower::Service<Request<B>> for ApiLevelService<S>
+where
+    S: tower::Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<u32, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let level = parse_api_level(request.headers());
+        CLIENT_API_LEVEL.scope(level, self.inner.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use zksync_web3_decl::error::{TxErrorCode, Web3Error};
+
+    use super::*;
+    use crate::api_server::web3::backend_jsonrpsee::into_jsrpc_error;
+
+    #[test]
+    fn parsing_api_level() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_api_level(&headers), 0);
+        headers.insert(API_LEVEL_HEADER, HeaderValue::from_static("1"));
+        assert_eq!(parse_api_level(&headers), 1);
+        headers.insert(API_LEVEL_HEADER, HeaderValue::from_static("100"));
+        assert_eq!(parse_api_level(&headers), ApiCapability::MAX_CLIENT_LEVEL);
+        headers.insert(API_LEVEL_HEADER, HeaderValue::from_static("what"));
+        assert_eq!(parse_api_level(&headers), 0);
+    }
+
+    #[tokio::test]
+    async fn api_level_is_scoped_to_request() {
+        assert_eq!(client_api_level(), 0);
+        let level = CLIENT_API_LEVEL
+            .scope(1, async { client_api_level() })
+            .await;
+        assert_eq!(level, 1);
+        assert_eq!(client_api_level(), 0);
+    }
+
+    #[tokio::test]
+    async fn tx_error_data_depends_on_api_level() {
+        let error_data = |level: u32| {
+            CLIENT_API_LEVEL.scope(level, async {
+                let err = Web3Error::SubmitTransactionError(
+                    "nonce too low".to_owned(),
+                    Some(TxErrorCode::NonceTooLow.into()),
+                );
+                let err = into_jsrpc_error(err);
+                serde_json::from_str::<serde_json::Value>(err.data().unwrap().get()).unwrap()
+            })
+        };
+
+        assert_eq!(error_data(0).await, serde_json::json!("0x"));
+        assert_eq!(
+            error_data(1).await,
+            serde_json::json!({ "code": "nonce_too_low" })
+        );
+    }
+}
@@ -4,19 +4,34 @@
 
 use std::fmt;
 
+use zksync_types::api::ApiCapability;
 use zksync_web3_decl::{
-    error::Web3Error,
-    jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned},
+    error::{TxErrorData, Web3Error},
+    jsonrpsee::{
+        core::ClientError,
+        types::{error::ErrorCode, ErrorObjectOwned},
+    },
 };
 
-use crate::api_server::{tx_sender::SubmitTxError, web3::metrics::API_METRICS};
+use crate::api_server::{
+    tx_sender::SubmitTxError,
+    web3::{api_level::client_api_level, metrics::API_METRICS},
+};
 
 pub mod batch_limiter_middleware;
 pub mod namespaces;
 
 pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
     let data = match &err {
-        Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
+        Web3Error::SubmitTransactionError(_, data) => {
+            // Error codes change the `data` format, so they are only returned to clients that opted into them.
+            if client_api_level() >= ApiCapability::TxErrorCodes.min_client_level() {
+                data.as_ref().map(TxErrorData::to_json)
+            } else {
+                let data = data.clone().unwrap_or_else(|| TxErrorData::revert(vec![]));
+                Some(data.to_legacy_json())
+            }
+        }
         _ => None,
     };
     ErrorObjectOwned::owned(
@@ -32,11 +47,17 @@ pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
+            | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::TooManyTracedTransactions(_, _) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
             Web3Error::TreeApiUnavailable => 6,
+            Web3Error::ResponseSigningDisabled => 7,
+            Web3Error::TooManyRequests => {
+                ErrorCode::ServerError(reqwest::StatusCode::TOO_MANY_REQUESTS.as_u16().into())
+                    .code()
+            }
         },
         match err {
             Web3Error::SubmitTransactionError(message, _) => message,
@@ -55,7 +76,17 @@ impl SubmitTxError {
             Self::ProxyError(ref err) => {
                 // Strip internal error details that should not be exposed to the caller.
                 tracing::warn!("Error proxying call to main node in method {method_name}: {err}");
-                Web3Error::SubmitTransactionError(err.as_ref().to_string(), self.data())
+                match err.as_ref() {
+                    // Forward the message and data returned by the main node, so that the error code is preserved.
+                    ClientError::Call(err) => {
+                        let data = err
+                            .data()
+                            .and_then(|data| serde_json::from_str(data.get()).ok())
+                            .and_then(|data| TxErrorData::from_json(&data));
+                        Web3Error::SubmitTransactionError(err.message().to_owned(), data)
+                    }
+                    err => Web3Error::SubmitTransactionError(err.to_string(), None),
+                }
             }
             _ => Web3Error::SubmitTransactionError(self.to_string(), self.data()),
         }
@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use zksync_types::chain_freeze::ChainFreezeEvent;
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::AdminNamespaceServer};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::AdminNamespace};
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    async fn freeze_chain(&self, reason: Option<String>) -> RpcResult<ChainFreezeEvent> {
+        self.freeze_chain_impl(reason)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn unfreeze_chain(&self, reason: Option<String>) -> RpcResult<ChainFreezeEvent> {
+        self.unfreeze_chain_impl(reason)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_chain_freeze_status(&self) -> RpcResult<Option<ChainFreezeEvent>> {
+        self.get_chain_freeze_status_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+}
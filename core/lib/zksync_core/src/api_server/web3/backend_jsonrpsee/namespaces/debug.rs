@@ -1,11 +1,12 @@
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, LogsQueryExplanation, ResultDebugCall, TracerConfig},
     transaction_request::CallRequest,
     H256,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
     namespaces::debug::DebugNamespaceServer,
+    types::Filter,
 };
 
 use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::DebugNamespace};
@@ -52,4 +53,14 @@ impl DebugNamespaceServer for DebugNamespace {
             .await
             .map_err(into_jsrpc_error)
     }
+
+    async fn explain_logs_query(
+        &self,
+        filter: Filter,
+        benchmark: Option<bool>,
+    ) -> RpcResult<LogsQueryExplanation> {
+        self.debug_explain_logs_query_impl(filter, benchmark.unwrap_or(false))
+            .await
+            .map_err(into_jsrpc_error)
+    }
 }
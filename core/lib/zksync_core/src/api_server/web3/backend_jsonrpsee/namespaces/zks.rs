@@ -2,13 +2,19 @@ use std::collections::HashMap;
 
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        ApiCapabilities, BlobUtilizationReport, BlockCommitmentProof, BlockDetails,
+        BlockHashPreimage, BlockOrderingInfo, BridgeAddresses, ChainParameters, ContractAddresses,
+        GasPriceHistoryEntry, L1BatchCommitmentDetails, L1BatchDetails, L1BatchL1Cost,
+        L1BatchPublicInputs, L1BatchStateDiffs, L1BatchStoredInfo, L1TransactionSimulation,
+        L1TransactionSimulationOptions, L2ToL1LogProof, NodeInfo, Proof, ProtocolVersion,
+        SignedResponse, SolvencyReport, StorageExitProof, SystemContext, TransactionAddressRole,
+        TransactionDetails, TransactionsByAddressCursor, TransactionsByAddressPage, ValidUntil,
     },
-    fee::Fee,
+    fee::{FeeEstimate, FeeReport},
     fee_model::FeeParams,
+    l2::SignatureScheme,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -20,8 +26,20 @@ use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, ZksNamespace}
 
 #[async_trait]
 impl ZksNamespaceServer for ZksNamespace {
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee> {
-        self.estimate_fee_impl(req).await.map_err(into_jsrpc_error)
+    async fn estimate_fee(
+        &self,
+        req: CallRequest,
+        include_breakdown: Option<bool>,
+    ) -> RpcResult<FeeEstimate> {
+        self.estimate_fee_impl(req, include_breakdown.unwrap_or(false))
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_fee_report(&self, req: CallRequest) -> RpcResult<FeeReport> {
+        self.get_fee_report_impl(req)
+            .await
+            .map_err(into_jsrpc_error)
     }
 
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256> {
@@ -30,6 +48,16 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn simulate_l1_transaction(
+        &self,
+        req: CallRequest,
+        options: Option<L1TransactionSimulationOptions>,
+    ) -> RpcResult<L1TransactionSimulation> {
+        self.simulate_l1_transaction_impl(req, options.unwrap_or_default())
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_bridgehub_contract(&self) -> RpcResult<Option<Address>> {
         Ok(self.get_bridgehub_contract_impl())
     }
@@ -50,6 +78,26 @@ impl ZksNamespaceServer for ZksNamespace {
         Ok(self.l1_chain_id_impl())
     }
 
+    async fn get_contract_addresses(&self) -> RpcResult<ContractAddresses> {
+        Ok(self.get_contract_addresses_impl())
+    }
+
+    async fn get_chain_parameters(&self) -> RpcResult<ChainParameters> {
+        self.get_chain_parameters_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_node_info(&self) -> RpcResult<NodeInfo> {
+        Ok(self.get_node_info_impl())
+    }
+
+    async fn get_api_capabilities(&self) -> RpcResult<ApiCapabilities> {
+        self.get_api_capabilities_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_confirmed_tokens(&self, from: u32, limit: u8) -> RpcResult<Vec<Token>> {
         self.get_confirmed_tokens_impl(from, limit)
             .await
@@ -123,6 +171,27 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_transactions_by_address(
+        &self,
+        address: Address,
+        role: TransactionAddressRole,
+        cursor: Option<TransactionsByAddressCursor>,
+    ) -> RpcResult<TransactionsByAddressPage> {
+        self.get_transactions_by_address_impl(address, role, cursor)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_transactions_by_signature_scheme(
+        &self,
+        scheme: SignatureScheme,
+        cursor: Option<TransactionsByAddressCursor>,
+    ) -> RpcResult<TransactionsByAddressPage> {
+        self.get_transactions_by_signature_scheme_impl(scheme, cursor)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_l1_batch_details(
         &self,
         batch_number: L1BatchNumber,
@@ -132,6 +201,94 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_solvency_report(
+        &self,
+        batch_number: Option<L1BatchNumber>,
+    ) -> RpcResult<Option<SolvencyReport>> {
+        self.get_solvency_report_impl(batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_blob_utilization_report(
+        &self,
+        limit: Option<usize>,
+    ) -> RpcResult<BlobUtilizationReport> {
+        self.get_blob_utilization_report_impl(limit)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_batch_state_diffs(
+        &self,
+        batch: L1BatchNumber,
+        include_values: Option<bool>,
+    ) -> RpcResult<Option<L1BatchStateDiffs>> {
+        self.get_batch_state_diffs_impl(batch, include_values.unwrap_or(false))
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_l1_batch_public_inputs(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchPublicInputs>> {
+        self.get_l1_batch_public_inputs_impl(batch)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_l1_batch_stored_info(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchStoredInfo>> {
+        self.get_l1_batch_stored_info_impl(batch)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_l1_batch_commitment_details(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchCommitmentDetails>> {
+        self.get_l1_batch_commitment_details_impl(batch)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_l1_batch_l1_costs(&self, batch: L1BatchNumber) -> RpcResult<Vec<L1BatchL1Cost>> {
+        self.get_l1_batch_l1_costs_impl(batch)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_block_ordering_info(
+        &self,
+        block: MiniblockNumber,
+    ) -> RpcResult<Option<BlockOrderingInfo>> {
+        self.get_block_ordering_info_impl(block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_block_hash_preimage(
+        &self,
+        block: MiniblockNumber,
+    ) -> RpcResult<Option<BlockHashPreimage>> {
+        self.get_block_hash_preimage_impl(block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_block_commitment_proof(
+        &self,
+        block: MiniblockNumber,
+    ) -> RpcResult<Option<BlockCommitmentProof>> {
+        self.get_block_commitment_proof_impl(block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>> {
         self.get_bytecode_by_hash_impl(hash)
             .await
@@ -142,6 +299,22 @@ impl ZksNamespaceServer for ZksNamespace {
         Ok(self.get_l1_gas_price_impl().await)
     }
 
+    async fn gas_price_history(
+        &self,
+        from_block: MiniblockNumber,
+        block_count: U64,
+    ) -> RpcResult<Vec<GasPriceHistoryEntry>> {
+        self.gas_price_history_impl(from_block, block_count)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_system_context(&self, block: MiniblockNumber) -> RpcResult<Option<SystemContext>> {
+        self.get_system_context_impl(block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_fee_params(&self) -> RpcResult<FeeParams> {
         Ok(self.get_fee_params_impl())
     }
@@ -165,4 +338,67 @@ impl ZksNamespaceServer for ZksNamespace {
             .await
             .map_err(into_jsrpc_error)
     }
+
+    async fn get_signed_l1_batch_details(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<SignedResponse<L1BatchDetails>>> {
+        self.get_signed_l1_batch_details_impl(batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_signed_l2_to_l1_msg_proof(
+        &self,
+        block: MiniblockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> RpcResult<Option<SignedResponse<L2ToL1LogProof>>> {
+        self.get_signed_l2_to_l1_msg_proof_impl(block, sender, msg, l2_log_position)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_signed_l2_to_l1_log_proof(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> RpcResult<Option<SignedResponse<L2ToL1LogProof>>> {
+        self.get_signed_l2_to_l1_log_proof_impl(tx_hash, index)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_signed_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<SignedResponse<Proof>> {
+        self.get_signed_proofs_impl(address, keys, l1_batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_storage_exit_proof(
+        &self,
+        address: Address,
+        key: H256,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<StorageExitProof>> {
+        self.get_storage_exit_proof_impl(address, key, l1_batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: ValidUntil,
+    ) -> RpcResult<H256> {
+        self.send_raw_transaction_with_deadline_impl(tx_bytes, valid_until)
+            .await
+            .map_err(into_jsrpc_error)
+    }
 }
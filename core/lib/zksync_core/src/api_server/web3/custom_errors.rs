@@ -0,0 +1,205 @@
+//! Decoding of custom Solidity errors in revert reasons of failed transactions.
+
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Context as _;
+use zksync_types::{
+    api::RevertReason,
+    ethabi::{self, short_signature, AbiError, ParamType, Token},
+    U256,
+};
+
+#[derive(Debug)]
+struct CustomError {
+    signature: String,
+    abi: AbiError,
+}
+
+/// Registry of custom errors supplied by the node operator as a JSON ABI (e.g., an ABI of a contract
+/// or a standalone ABI containing only error definitions). Used to decode revert reasons that are not
+/// standard `Error(string)` / `Panic(uint256)` reverts.
+#[derive(Debug, Default)]
+pub struct CustomErrorsRegistry {
+    errors: HashMap<[u8; 4], CustomError>,
+}
+
+impl CustomErrorsRegistry {
+    /// Loads the registry from a JSON ABI file.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let abi = std::fs::read(path)
+            .with_context(|| format!("failed reading custom errors ABI from {path:?}"))?;
+        Self::from_abi_json(&abi)
+            .with_context(|| format!("failed parsing custom errors ABI from {path:?}"))
+    }
+
+    fn from_abi_json(abi: &[u8]) -> anyhow::Result<Self> {
+        let contract = ethabi::Contract::load(abi)?;
+        let mut errors = HashMap::new();
+        for abi in contract.errors() {
+            let param_types: Vec<ParamType> =
+                abi.inputs.iter().map(|param| param.kind.clone()).collect();
+            let selector = short_signature(&abi.name, &param_types);
+            let param_types: Vec<_> = param_types.iter().map(ParamType::to_string).collect();
+            let signature = format!("{}({})", abi.name, param_types.join(","));
+            let error = CustomError {
+                signature: signature.clone(),
+                abi: abi.clone(),
+            };
+            if let Some(prev) = errors.insert(selector, error) {
+                anyhow::bail!(
+                    "custom errors `{}` and `{signature}` have the same selector",
+                    prev.signature
+                );
+            }
+        }
+        Ok(Self { errors })
+    }
+
+    /// Decodes the custom error in the provided revert reason. Returns the reason as-is if it's not an unknown error,
+    /// or if it cannot be decoded using the registry.
+    pub(super) fn decode(&self, reason: RevertReason) -> RevertReason {
+        let Some(selector) = reason.unknown_selector() else {
+            return reason;
+        };
+        let Some(error) = self.errors.get(&selector) else {
+            return reason;
+        };
+        let RevertReason::Unknown { data } = &reason else {
+            unreachable!("selector is only returned for unknown errors");
+        };
+        match error.abi.decode(&data.0[4..]) {
+            Ok(tokens) => RevertReason::Custom {
+                signature: error.signature.clone(),
+                args: tokens.iter().map(format_token).collect(),
+            },
+            Err(err) => {
+                tracing::debug!(
+                    "Failed decoding custom error `{}` from revert data: {err}",
+                    error.signature
+                );
+                reason
+            }
+        }
+    }
+}
+
+/// Formats a decoded error argument similarly to Solidity literals: integers are decimal, while addresses and byte
+/// arrays are `0x`-prefixed hex. (`Token`'s `Display` implementation uses bare hex for all of these.)
+fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => format!("{address:?}"),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) if value.bit(255) => {
+            // Two's complement representation of a negative number
+            let abs_value = (!*value).overflowing_add(U256::one()).0;
+            format!("-{abs_value}")
+        }
+        Token::Int(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => format!("{value:?}"),
+        Token::FixedArray(tokens) | Token::Array(tokens) => {
+            let tokens: Vec<_> = tokens.iter().map(format_token).collect();
+            format!("[{}]", tokens.join(", "))
+        }
+        Token::Tuple(tokens) => {
+            let tokens: Vec<_> = tokens.iter().map(format_token).collect();
+            format!("({})", tokens.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{web3::types::Bytes, Address};
+
+    use super::*;
+
+    const ABI: &str = r#"[{
+        "type": "error",
+        "name": "InsufficientBalance",
+        "inputs": [
+            { "name": "available", "type": "uint256" },
+            { "name": "required", "type": "uint256" }
+        ]
+    }]"#;
+
+    #[test]
+    fn decoding_revert_reasons() {
+        let registry = CustomErrorsRegistry::from_abi_json(ABI.as_bytes()).unwrap();
+
+        let selector = short_signature(
+            "InsufficientBalance",
+            &[ParamType::Uint(256), ParamType::Uint(256)],
+        );
+        let mut data = selector.to_vec();
+        data.extend(ethabi::encode(&[
+            Token::Uint(100.into()),
+            Token::Uint(1_000.into()),
+        ]));
+        let reason = registry.decode(RevertReason::decode(&data));
+        assert_eq!(
+            reason,
+            RevertReason::Custom {
+                signature: "InsufficientBalance(uint256,uint256)".to_owned(),
+                args: vec!["100".to_owned(), "1000".to_owned()],
+            }
+        );
+
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend(ethabi::encode(&[Token::String("not enough".to_owned())]));
+        let reason = registry.decode(RevertReason::decode(&data));
+        assert_eq!(
+            reason,
+            RevertReason::Error {
+                message: "not enough".to_owned()
+            }
+        );
+
+        let mut data = vec![0x4e, 0x48, 0x7b, 0x71];
+        data.extend(ethabi::encode(&[Token::Uint(0x11.into())]));
+        let reason = registry.decode(RevertReason::decode(&data));
+        assert_eq!(
+            reason,
+            RevertReason::Panic {
+                code: U256::from(0x11),
+                description: Some("arithmetic overflow or underflow".to_owned()),
+            }
+        );
+
+        let data = vec![1, 2, 3, 4, 5];
+        let reason = registry.decode(RevertReason::decode(&data));
+        assert_eq!(reason, RevertReason::Unknown { data: Bytes(data) });
+    }
+
+    #[test]
+    fn formatting_error_args() {
+        let address = Address::repeat_byte(0x12);
+        let tokens = [
+            Token::Address(address),
+            Token::Uint(1_000.into()),
+            Token::Int(U256::MAX),
+            Token::Int(5.into()),
+            Token::Bool(true),
+            Token::String("test".to_owned()),
+            Token::FixedBytes(vec![0xab; 4]),
+            Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+            Token::Tuple(vec![Token::Bytes(vec![]), Token::Int(U256::MAX - 1)]),
+        ];
+        let formatted: Vec<_> = tokens.iter().map(format_token).collect();
+        assert_eq!(
+            formatted,
+            [
+                "0x1212121212121212121212121212121212121212",
+                "1000",
+                "-1",
+                "5",
+                "true",
+                "\"test\"",
+                "0xabababab",
+                "[1, 2]",
+                "(0x, -2)",
+            ]
+        );
+    }
+}
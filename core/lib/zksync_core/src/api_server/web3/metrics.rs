@@ -143,10 +143,17 @@ pub(super) struct ApiMetrics {
     /// Number of transaction submission errors for a specific submission error reason.
     #[metrics(labels = ["reason"])]
     pub submit_tx_error: LabeledFamily<&'static str, Counter>,
+    /// Number of submitted legacy transactions without an EIP-155 chain ID, split by whether they were accepted
+    /// or rejected.
+    #[metrics(labels = ["action"])]
+    pub txs_without_chain_id: LabeledFamily<&'static str, Counter>,
     #[metrics(buckets = Buckets::exponential(1.0..=128.0, 2.0))]
     pub web3_in_flight_requests: Family<ApiTransportLabel, Histogram<usize>>,
     /// Number of currently open WebSocket sessions.
     pub ws_open_sessions: Gauge,
+    /// Latency of loading a call traces and events archive from the cold storage.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub cold_storage_load_latency: Histogram<Duration>,
 }
 
 impl ApiMetrics {
@@ -179,6 +186,7 @@ pub(super) enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    Storage,
 }
 
 #[derive(Debug, Metrics)]
@@ -205,6 +213,8 @@ pub(super) struct PubSubMetrics {
     pub skipped_broadcast_messages: Family<SubscriptionType, Histogram<u64>>,
     /// Number of subscribers dropped because of a send timeout.
     pub subscriber_send_timeouts: Family<SubscriptionType, Counter>,
+    /// Number of events not sent to subscribers because of per-subscriber rate limits.
+    pub rate_limited_notifications: Family<SubscriptionType, Counter>,
 }
 
 #[vise::register]
@@ -5,12 +5,13 @@ use chrono::NaiveDateTime;
 use futures::future;
 use serde::Deserialize;
 use tokio::{
-    sync::{mpsc, oneshot, watch, Mutex},
+    sync::{broadcast, mpsc, oneshot, watch, Mutex},
     task::JoinHandle,
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 use zksync_dal::ConnectionPool;
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_object_store::ObjectStore;
 use zksync_types::MiniblockNumber;
 use zksync_web3_decl::{
     jsonrpsee::{
@@ -20,31 +21,38 @@ use zksync_web3_decl::{
     namespaces::{
         DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
         NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        ZksPubSubServer,
     },
     types::Filter,
 };
 
 use self::{
+    api_level::{ApiLevelLayer, API_LEVEL_HEADER},
+    custom_errors::CustomErrorsRegistry,
     metrics::API_METRICS,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        DebugNamespace, DebugNamespaceLimits, EnNamespace, EthNamespace, NetNamespace,
+        SnapshotsNamespace, Web3Namespace, ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
-    state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
+    state::{Filters, InternalApiConfig, ResponseSigner, RpcState, SealedMiniblockNumber},
 };
 use crate::{
     api_server::{
-        execution_sandbox::{BlockStartInfo, VmConcurrencyBarrier},
+        execution_sandbox::{BlockStartInfo, VmConcurrencyBarrier, VmConcurrencyLimiter},
         tree::TreeApiHttpClient,
         tx_sender::TxSender,
         web3::backend_jsonrpsee::batch_limiter_middleware::LimitMiddleware,
     },
+    state_keeper::MiniblockWriteSet,
     sync_layer::SyncState,
     utils::wait_for_l1_batch,
 };
 
+pub mod admin_server;
+mod api_level;
 pub mod backend_jsonrpsee;
+pub mod custom_errors;
 mod metrics;
 pub mod namespaces;
 mod pubsub;
@@ -52,7 +60,7 @@ pub mod state;
 #[cfg(test)]
 pub(crate) mod tests;
 
-/// Timeout for graceful shutdown logic within API servers.
+/// Default timeout for graceful shutdown logic within API servers.
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Represents all kinds of `Filter`.
@@ -94,6 +102,19 @@ impl Namespace {
         Self::En,
         Self::Pubsub,
     ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eth => "eth",
+            Self::Net => "net",
+            Self::Web3 => "web3",
+            Self::Debug => "debug",
+            Self::Zks => "zks",
+            Self::En => "en",
+            Self::Pubsub => "pubsub",
+            Self::Snapshots => "snapshots",
+        }
+    }
 }
 
 /// Handles to the initialized API server.
@@ -111,11 +132,21 @@ struct OptionalApiParams {
     sync_state: Option<SyncState>,
     filters_limit: Option<usize>,
     subscriptions_limit: Option<usize>,
+    subscription_handoff: Option<(ConnectionPool, Duration)>,
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<usize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tree_api_url: Option<String>,
+    custom_errors: Option<CustomErrorsRegistry>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    storage_write_sets: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
+    shutdown_delay: Duration,
+    shutdown_timeout: Option<Duration>,
+    debug_namespace_concurrency_limit: Option<usize>,
+    debug_namespace_requests_per_minute_limit: Option<NonZeroU32>,
+    debug_trace_block_tx_limit: Option<usize>,
+    response_signer: Option<ResponseSigner>,
+    cold_storage: Option<Arc<dyn ObjectStore>>,
 }
 
 /// Full API server parameters.
@@ -200,6 +231,30 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables exporting WebSocket subscriptions to Postgres on shutdown, so that clients can resume them
+    /// on another API server replica using `zks_resumeSubscription` within the specified TTL.
+    /// Exported subscriptions are written to and consumed from the database using the provided `pool`,
+    /// so it must be connected to the master database.
+    pub fn with_subscription_handoff(
+        mut self,
+        pool: ConnectionPool,
+        ttl: Option<Duration>,
+    ) -> Self {
+        self.optional.subscription_handoff = ttl.map(|ttl| (pool, ttl));
+        self
+    }
+
+    /// Makes storage subscriptions consume miniblock write sets published by the state keeper
+    /// (see [`MiniblockSealer::with_write_sets()`](crate::state_keeper::MiniblockSealer::with_write_sets()))
+    /// instead of polling storage logs from Postgres.
+    pub fn with_storage_write_sets(
+        mut self,
+        write_sets_sender: broadcast::Sender<Arc<MiniblockWriteSet>>,
+    ) -> Self {
+        self.optional.storage_write_sets = Some(write_sets_sender);
+        self
+    }
+
     pub fn with_batch_request_size_limit(mut self, batch_request_size_limit: usize) -> Self {
         self.optional.batch_request_size_limit = Some(batch_request_size_limit);
         self
@@ -239,6 +294,63 @@ impl ApiBuilder {
         self
     }
 
+    /// Sets the registry of custom errors used to decode revert reasons of failed transactions.
+    pub fn with_custom_errors(mut self, custom_errors: Option<CustomErrorsRegistry>) -> Self {
+        self.optional.custom_errors = custom_errors;
+        self
+    }
+
+    /// Sets the delay between receiving a stop signal and stopping to accept new requests. During this delay,
+    /// the server reports the "shutting down" health status, so that load balancers can redirect traffic
+    /// to other replicas.
+    pub fn with_shutdown_delay(mut self, shutdown_delay: Duration) -> Self {
+        self.optional.shutdown_delay = shutdown_delay;
+        self
+    }
+
+    /// Sets the timeout for in-flight requests and VM executions to complete after the server
+    /// has stopped accepting new requests. If not set, a default timeout of 5 seconds is used.
+    pub fn with_shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+        self.optional.shutdown_timeout = Some(shutdown_timeout);
+        self
+    }
+
+    /// Sets the maximum number of concurrently processed `debug_` requests. If set, `debug_` methods are processed
+    /// by a dedicated bounded worker pool, so that heavy tracing load doesn't compete for VM permits with other namespaces.
+    /// The pool is specific to this server.
+    pub fn with_debug_namespace_concurrency_limit(mut self, limit: Option<usize>) -> Self {
+        self.optional.debug_namespace_concurrency_limit = limit;
+        self
+    }
+
+    /// Sets the maximum number of `debug_` requests per minute. The limit is shared among all connections
+    /// to this server; requests above the limit are rejected.
+    pub fn with_debug_namespace_requests_per_minute_limit(
+        mut self,
+        limit: Option<NonZeroU32>,
+    ) -> Self {
+        self.optional.debug_namespace_requests_per_minute_limit = limit;
+        self
+    }
+
+    /// Sets the maximum number of transactions in a block traced by `debug_traceBlock*` methods.
+    pub fn with_debug_trace_block_tx_limit(mut self, limit: Option<usize>) -> Self {
+        self.optional.debug_trace_block_tx_limit = limit;
+        self
+    }
+
+    /// Enables `zks_getSigned*` methods signing responses with the specified signer.
+    pub fn with_response_signer(mut self, signer: ResponseSigner) -> Self {
+        self.optional.response_signer = Some(signer);
+        self
+    }
+
+    /// Enables loading call traces and events moved from Postgres to the specified object store.
+    pub fn with_cold_storage(mut self, object_store: Arc<dyn ObjectStore>) -> Self {
+        self.optional.cold_storage = Some(object_store);
+        self
+    }
+
     #[cfg(test)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
         self.optional.pub_sub_events_sender = Some(sender);
@@ -303,6 +415,11 @@ impl FullApiParams {
                 .optional
                 .tree_api_url
                 .map(|url| TreeApiHttpClient::new(url.as_str())),
+            custom_errors: Arc::new(self.optional.custom_errors.unwrap_or_default()),
+            namespaces: self.namespaces,
+            events_statistics: Arc::default(),
+            response_signer: self.optional.response_signer,
+            cold_storage: self.optional.cold_storage,
         })
     }
 
@@ -310,6 +427,7 @@ impl FullApiParams {
         self,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
+        debug_limits: DebugNamespaceLimits,
     ) -> anyhow::Result<RpcModule<()>> {
         let namespaces = self.namespaces.clone();
         let zksync_network_id = self.config.l2_chain_id;
@@ -318,8 +436,10 @@ impl FullApiParams {
         // Collect all the methods into a single RPC module.
         let mut rpc = RpcModule::new(());
         if let Some(pub_sub) = pub_sub {
-            rpc.merge(pub_sub.into_rpc())
+            rpc.merge(EthPubSubServer::into_rpc(pub_sub.clone()))
                 .expect("Can't merge eth pubsub namespace");
+            rpc.merge(ZksPubSubServer::into_rpc(pub_sub))
+                .expect("Can't merge zks pubsub namespace");
         }
 
         if namespaces.contains(&Namespace::Eth) {
@@ -343,7 +463,8 @@ impl FullApiParams {
                 .expect("Can't merge en namespace");
         }
         if namespaces.contains(&Namespace::Debug) {
-            rpc.merge(DebugNamespace::new(rpc_state.clone()).await.into_rpc())
+            let debug_namespace = DebugNamespace::new(rpc_state.clone(), debug_limits).await;
+            rpc.merge(debug_namespace.into_rpc())
                 .expect("Can't merge debug namespace");
         }
         if namespaces.contains(&Namespace::Snapshots) {
@@ -390,12 +511,11 @@ impl FullApiParams {
         self.build_jsonrpsee(stop_receiver).await
     }
 
-    async fn wait_for_vm(vm_barrier: VmConcurrencyBarrier, transport: &str) {
-        let wait_for_vm =
-            tokio::time::timeout(GRACEFUL_SHUTDOWN_TIMEOUT, vm_barrier.wait_until_stopped());
+    async fn wait_for_vm(vm_barrier: VmConcurrencyBarrier, transport: &str, timeout: Duration) {
+        let wait_for_vm = tokio::time::timeout(timeout, vm_barrier.wait_until_stopped());
         if wait_for_vm.await.is_err() {
             tracing::warn!(
-                "VM execution on {transport} JSON-RPC server didn't stop after {GRACEFUL_SHUTDOWN_TIMEOUT:?}; \
+                "VM execution on {transport} JSON-RPC server didn't stop after {timeout:?}; \
                  forcing shutdown anyway"
             );
         } else {
@@ -430,10 +550,16 @@ impl FullApiParams {
         let pub_sub = if matches!(transport, ApiTransport::WebSocket(_))
             && self.namespaces.contains(&Namespace::Pubsub)
         {
-            let mut pub_sub = EthSubscribe::new();
+            let mut pub_sub = EthSubscribe::new(self.config.mempool_feed);
             if let Some(sender) = &self.optional.pub_sub_events_sender {
                 pub_sub.set_events_sender(sender.clone());
             }
+            if let Some((handoff_pool, ttl)) = self.optional.subscription_handoff.clone() {
+                pub_sub.set_handoff(handoff_pool, ttl);
+            }
+            if let Some(sender) = &self.optional.storage_write_sets {
+                pub_sub.set_storage_write_sets(sender.clone());
+            }
 
             tasks.extend(pub_sub.spawn_notifiers(
                 self.pool.clone(),
@@ -508,9 +634,28 @@ impl FullApiParams {
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
         let subscriptions_limit = self.optional.subscriptions_limit;
         let vm_barrier = self.vm_barrier.clone();
+        let (debug_worker_pool, debug_barrier) =
+            match self.optional.debug_namespace_concurrency_limit {
+                Some(limit) if self.namespaces.contains(&Namespace::Debug) => {
+                    let (limiter, barrier) = VmConcurrencyLimiter::new(limit);
+                    (Some(Arc::new(limiter)), Some(barrier))
+                }
+                _ => (None, None),
+            };
+        let debug_limits = DebugNamespaceLimits::new(
+            debug_worker_pool,
+            self.optional.debug_namespace_requests_per_minute_limit,
+            self.optional.debug_trace_block_tx_limit,
+        );
+        let shutdown_delay = self.optional.shutdown_delay;
+        let shutdown_timeout = self
+            .optional
+            .shutdown_timeout
+            .unwrap_or(GRACEFUL_SHUTDOWN_TIMEOUT);
 
+        let exported_pub_sub = pub_sub.clone().filter(EthSubscribe::has_handoff);
         let rpc = self
-            .build_rpc_module(pub_sub, last_sealed_miniblock)
+            .build_rpc_module(pub_sub, last_sealed_miniblock, debug_limits)
             .await?;
 
         // Setup CORS.
@@ -520,7 +665,10 @@ impl FullApiParams {
                 .allow_methods([reqwest::Method::POST])
                 // Allow requests from any origin
                 .allow_origin(tower_http::cors::Any)
-                .allow_headers([reqwest::header::CONTENT_TYPE])
+                .allow_headers([
+                    reqwest::header::CONTENT_TYPE,
+                    reqwest::header::HeaderName::from_static(API_LEVEL_HEADER),
+                ])
         });
         // Setup metrics for the number of in-flight requests.
         let (in_flight_requests, counter) = InFlightRequestsLayer::pair();
@@ -533,7 +681,8 @@ impl FullApiParams {
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .layer(ApiLevelLayer);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -577,6 +726,7 @@ impl FullApiParams {
         // Hence, we monitor `stop_receiver` on a separate Tokio task.
         let close_handle = server_handle.clone();
         let closing_vm_barrier = vm_barrier.clone();
+        let closing_debug_barrier = debug_barrier.clone();
         let health_updater = Arc::new(health_updater);
         // We use `Weak` reference to the health updater in order to not prevent its drop if the server stops on its own.
         let closing_health_updater = Arc::downgrade(&health_updater);
@@ -590,17 +740,35 @@ impl FullApiParams {
             if let Some(health_updater) = closing_health_updater.upgrade() {
                 health_updater.update(HealthStatus::ShuttingDown.into());
             }
+            if !shutdown_delay.is_zero() {
+                tracing::info!(
+                    "Stop signal received, {transport_str} JSON-RPC server will stop accepting requests \
+                     in {shutdown_delay:?}"
+                );
+                tokio::time::sleep(shutdown_delay).await;
+            }
+            if let Some(pub_sub) = &exported_pub_sub {
+                if let Err(err) = pub_sub.export_subscriptions().await {
+                    tracing::warn!("Failed exporting subscriptions on {transport_str} JSON-RPC server: {err:#}");
+                }
+            }
             tracing::info!(
                 "Stop signal received, {transport_str} JSON-RPC server is shutting down"
             );
             closing_vm_barrier.close();
+            if let Some(barrier) = &closing_debug_barrier {
+                barrier.close();
+            }
             close_handle.stop().ok();
         });
 
         server_handle.stopped().await;
         drop(health_updater);
         tracing::info!("{transport_str} JSON-RPC server stopped");
-        Self::wait_for_vm(vm_barrier, transport_str).await;
+        Self::wait_for_vm(vm_barrier, transport_str, shutdown_timeout).await;
+        if let Some(barrier) = debug_barrier {
+            Self::wait_for_vm(barrier, transport_str, shutdown_timeout).await;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,111 @@
+use serde_json::json;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    audit_log::AuditAction,
+    chain_freeze::{ChainFreezeEvent, ChainFreezeSource},
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::{
+    api_server::web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS},
+    audit_log::AuditLogger,
+};
+
+/// Privileged operator methods. Uses a separate (master) connection pool since the methods modify the node state.
+#[derive(Debug, Clone)]
+pub struct AdminNamespace {
+    pool: ConnectionPool,
+    audit_log: AuditLogger,
+}
+
+impl AdminNamespace {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self {
+            audit_log: AuditLogger::new(pool.clone(), "admin_rpc"),
+            pool,
+        }
+    }
+
+    async fn set_chain_freeze_state(
+        &self,
+        method_name: &'static str,
+        is_frozen: bool,
+        reason: Option<String>,
+    ) -> Result<ChainFreezeEvent, Web3Error> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let event = storage
+            .chain_freeze_dal()
+            .insert_event(
+                is_frozen,
+                ChainFreezeSource::AdminRpc,
+                reason.as_deref(),
+                None,
+            )
+            .await
+            .map_err(|err| internal_error(method_name, err))?
+            .ok_or_else(|| internal_error(method_name, "chain freeze event was not recorded"))?;
+        drop(storage);
+
+        let params = json!({
+            "method": method_name,
+            "reason": reason,
+            "event_id": event.id,
+        });
+        self.audit_log
+            .record(AuditAction::AdminRpcCall, params)
+            .await
+            .map_err(|err| internal_error(method_name, format!("{err:#}")))?;
+        tracing::warn!(
+            "Chain was {} via admin RPC (reason: {reason:?})",
+            if is_frozen { "frozen" } else { "unfrozen" }
+        );
+        Ok(event)
+    }
+
+    pub async fn freeze_chain_impl(
+        &self,
+        reason: Option<String>,
+    ) -> Result<ChainFreezeEvent, Web3Error> {
+        let method_name = "freeze_chain";
+        let method_latency = API_METRICS.start_call(method_name);
+        let response = self.set_chain_freeze_state(method_name, true, reason).await;
+        method_latency.observe();
+        response
+    }
+
+    pub async fn unfreeze_chain_impl(
+        &self,
+        reason: Option<String>,
+    ) -> Result<ChainFreezeEvent, Web3Error> {
+        let method_name = "unfreeze_chain";
+        let method_latency = API_METRICS.start_call(method_name);
+        let response = self
+            .set_chain_freeze_state(method_name, false, reason)
+            .await;
+        method_latency.observe();
+        response
+    }
+
+    pub async fn get_chain_freeze_status_impl(
+        &self,
+    ) -> Result<Option<ChainFreezeEvent>, Web3Error> {
+        let method_name = "get_chain_freeze_status";
+        let method_latency = API_METRICS.start_call(method_name);
+        let mut storage = self
+            .pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let response = storage
+            .chain_freeze_dal()
+            .get_latest_event()
+            .await
+            .map_err(|err| internal_error(method_name, err));
+        method_latency.observe();
+        response
+    }
+}
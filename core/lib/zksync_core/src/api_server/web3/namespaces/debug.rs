@@ -1,33 +1,88 @@
-use std::sync::Arc;
+use std::{fmt, num::NonZeroU32, sync::Arc, time::Instant};
 
+use governor::{
+    clock::DefaultClock,
+    middleware::NoOpMiddleware,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
 use multivm::{interface::ExecutionResult, vm_latest::constants::BLOCK_GAS_LIMIT};
 use once_cell::sync::OnceCell;
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, LogsQueryExplanation, ResultDebugCall, TracerConfig},
     fee_model::BatchFeeInput,
     l2::L2Tx,
     transaction_request::CallRequest,
     vm_trace::Call,
     AccountTreeId, H256,
 };
-use zksync_web3_decl::error::Web3Error;
+use zksync_web3_decl::{error::Web3Error, types::Filter};
 
 use crate::api_server::{
-    execution_sandbox::{ApiTracer, TxSharedArgs},
-    tx_sender::{ApiContracts, TxSenderConfig},
+    execution_sandbox::{
+        ApiTracer, ExecutionBudgetGuard, SandboxExecutionError, TxSharedArgs, VmConcurrencyLimiter,
+        VmPermit,
+    },
+    tx_sender::{ApiContracts, SubmitTxError, TxSenderConfig},
     web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState},
 };
 
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+
+/// Limits applied to `debug_` requests. The limits are shared among all connections to the API server.
+#[derive(Clone, Default)]
+pub(crate) struct DebugNamespaceLimits {
+    /// Dedicated pool bounding the number of concurrently processed requests. If not set, VM executions
+    /// share permits with other namespaces.
+    worker_pool: Option<Arc<VmConcurrencyLimiter>>,
+    rate_limiter: Option<Arc<DirectRateLimiter>>,
+    trace_block_tx_limit: Option<usize>,
+}
+
+impl fmt::Debug for DebugNamespaceLimits {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("DebugNamespaceLimits")
+            .field("worker_pool", &self.worker_pool)
+            .field("rate_limited", &self.rate_limiter.is_some())
+            .field("trace_block_tx_limit", &self.trace_block_tx_limit)
+            .finish()
+    }
+}
+
+impl DebugNamespaceLimits {
+    pub fn new(
+        worker_pool: Option<Arc<VmConcurrencyLimiter>>,
+        requests_per_minute_limit: Option<NonZeroU32>,
+        trace_block_tx_limit: Option<usize>,
+    ) -> Self {
+        Self {
+            worker_pool,
+            rate_limiter: requests_per_minute_limit
+                .map(|limit| Arc::new(RateLimiter::direct(Quota::per_minute(limit)))),
+            trace_block_tx_limit,
+        }
+    }
+
+    pub(crate) fn check_rate_limit(&self) -> Result<(), Web3Error> {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return Ok(());
+        };
+        rate_limiter.check().map_err(|_| Web3Error::TooManyRequests)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DebugNamespace {
     batch_fee_input: BatchFeeInput,
     state: RpcState,
     api_contracts: ApiContracts,
+    limits: DebugNamespaceLimits,
 }
 
 impl DebugNamespace {
-    pub async fn new(state: RpcState) -> Self {
+    pub(crate) async fn new(state: RpcState, limits: DebugNamespaceLimits) -> Self {
         let api_contracts = ApiContracts::load_from_disk();
         Self {
             // For now, the same scaling is used for both the L1 gas price and the pubdata price
@@ -42,9 +97,21 @@ impl DebugNamespace {
                 .await,
             state,
             api_contracts,
+            limits,
         }
     }
 
+    /// Checks the rate limit and acquires a permit from the dedicated worker pool, if one is configured.
+    /// Should be called at the very start of each method so that the heavy `debug_` load is bounded.
+    async fn acquire_worker_permit(&self) -> Result<Option<VmPermit>, Web3Error> {
+        self.limits.check_rate_limit()?;
+        let Some(worker_pool) = &self.limits.worker_pool else {
+            return Ok(None);
+        };
+        let permit = worker_pool.acquire().await;
+        permit.map(Some).ok_or(Web3Error::InternalError)
+    }
+
     fn sender_config(&self) -> &TxSenderConfig {
         &self.state.tx_sender.0.sender_config
     }
@@ -57,6 +124,7 @@ impl DebugNamespace {
     ) -> Result<Vec<ResultDebugCall>, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_block";
 
+        let _permit = self.acquire_worker_permit().await?;
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
         let only_top_call = options
             .map(|options| options.tracer_config.only_top_call)
@@ -71,11 +139,42 @@ impl DebugNamespace {
             .state
             .resolve_block(&mut connection, block_id, METHOD_NAME)
             .await?;
-        let call_traces = connection
+        if let Some(tx_limit) = self.limits.trace_block_tx_limit {
+            let header = connection
+                .blocks_dal()
+                .get_miniblock_header(block_number)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            let tx_count = header.map_or(0, |header| {
+                usize::from(header.l1_tx_count) + usize::from(header.l2_tx_count)
+            });
+            if tx_count > tx_limit {
+                return Err(Web3Error::TooManyTracedTransactions(tx_count, tx_limit));
+            }
+        }
+        let mut call_traces = connection
             .blocks_web3_dal()
             .get_traces_for_miniblock(block_number)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if call_traces.is_empty() {
+            // Traces may be moved to the cold storage.
+            let l1_batch_number = connection
+                .blocks_web3_dal()
+                .get_l1_batch_number_of_miniblock(block_number)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            if let Some(l1_batch_number) = l1_batch_number {
+                let archive = self
+                    .state
+                    .load_cold_archive(&mut connection, l1_batch_number)
+                    .await
+                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+                if let Some(archive) = archive {
+                    call_traces = archive.traces_for_miniblock(block_number);
+                }
+            }
+        }
         let call_trace = call_traces
             .into_iter()
             .map(|call_trace| {
@@ -100,6 +199,7 @@ impl DebugNamespace {
     ) -> Result<Option<DebugCall>, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_transaction";
 
+        let _permit = self.acquire_worker_permit().await?;
         let only_top_call = options
             .map(|options| options.tracer_config.only_top_call)
             .unwrap_or(false);
@@ -109,11 +209,27 @@ impl DebugNamespace {
             .access_storage_tagged("api")
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
-        let call_trace = connection
+        let mut call_trace = connection
             .transactions_dal()
             .get_call_trace(tx_hash)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if call_trace.is_none() {
+            // The trace may be moved to the cold storage.
+            let l1_batch_info = connection
+                .blocks_web3_dal()
+                .get_l1_batch_info_for_tx(tx_hash)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            if let Some((l1_batch_number, _)) = l1_batch_info {
+                let archive = self
+                    .state
+                    .load_cold_archive(&mut connection, l1_batch_number)
+                    .await
+                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+                call_trace = archive.and_then(|archive| archive.trace_for_tx(tx_hash));
+            }
+        }
         Ok(call_trace.map(|call_trace| {
             let mut result: DebugCall = call_trace.into();
             if only_top_call {
@@ -123,6 +239,65 @@ impl DebugNamespace {
         }))
     }
 
+    #[tracing::instrument(skip(self, filter))]
+    pub async fn debug_explain_logs_query_impl(
+        &self,
+        mut filter: Filter,
+        benchmark: bool,
+    ) -> Result<LogsQueryExplanation, Web3Error> {
+        const METHOD_NAME: &str = "debug_explain_logs_query";
+
+        self.limits.check_rate_limit()?;
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.resolve_filter_block_hash(&mut filter).await?;
+        let from_block = self
+            .state
+            .resolve_filter_block_number(filter.from_block)
+            .await?;
+        let get_logs_filter = self.state.get_logs_filter(&filter, from_block).await?;
+        let limit = self.state.api_config.req_entities_limit;
+
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        // Unlike for actual logs queries, statistics are not cached so that the output reflects the current state.
+        let stats = connection
+            .events_web3_dal()
+            .get_events_statistics()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let plan = stats.map(|stats| stats.plan(&get_logs_filter));
+        let strategy = plan.as_ref().map(|plan| plan.strategy);
+        let mut estimates = plan.map(|plan| plan.estimates).unwrap_or_default();
+
+        if benchmark {
+            for estimate in &mut estimates {
+                let started_at = Instant::now();
+                let logs = connection
+                    .events_web3_dal()
+                    .get_logs_with_strategy(get_logs_filter.clone(), limit, Some(estimate.strategy))
+                    .await
+                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+                estimate.latency_ms = Some(started_at.elapsed().as_secs_f64() * 1_000.0);
+                estimate.log_count = Some(logs.len());
+            }
+        }
+
+        let (where_clause, _) = connection
+            .events_web3_dal()
+            .build_get_logs_where_clause_with_strategy(&get_logs_filter, strategy);
+        method_latency.observe();
+        Ok(LogsQueryExplanation {
+            planner_enabled: self.state.api_config.logs_query_planner_enabled,
+            strategy,
+            estimates,
+            where_clause,
+        })
+    }
+
     #[tracing::instrument(skip(self, request, block_id))]
     pub async fn debug_trace_call_impl(
         &self,
@@ -132,6 +307,7 @@ impl DebugNamespace {
     ) -> Result<DebugCall, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_call";
 
+        let worker_permit = self.acquire_worker_permit().await?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
         let only_top_call = options
@@ -153,21 +329,27 @@ impl DebugNamespace {
         let tx = L2Tx::from_request(request.into(), MAX_ENCODED_TX_SIZE)?;
 
         let shared_args = self.shared_args();
-        let vm_permit = self
-            .state
-            .tx_sender
-            .vm_concurrency_limiter()
-            .acquire()
-            .await;
-        let vm_permit = vm_permit.ok_or(Web3Error::InternalError)?;
+        let vm_permit = if let Some(permit) = worker_permit {
+            permit
+        } else {
+            let vm_permit = self
+                .state
+                .tx_sender
+                .vm_concurrency_limiter()
+                .acquire()
+                .await;
+            vm_permit.ok_or(Web3Error::InternalError)?
+        };
 
         // We don't need properly trace if we only need top call
         let call_tracer_result = Arc::new(OnceCell::default());
-        let custom_tracers = if only_top_call {
+        let mut custom_tracers = if only_top_call {
             vec![]
         } else {
             vec![ApiTracer::CallTracer(call_tracer_result.clone())]
         };
+        let execution_budget = self.state.tx_sender.execution_budget();
+        custom_tracers.extend(execution_budget.as_ref().map(ExecutionBudgetGuard::tracer));
 
         let executor = &self.state.tx_sender.0.executor;
         let result = executor
@@ -181,16 +363,14 @@ impl DebugNamespace {
                 custom_tracers,
             )
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
+            .map_err(|err| SubmitTxError::from(err).into_web3_error(METHOD_NAME))?;
 
         let (output, revert_reason) = match result.result {
             ExecutionResult::Success { output, .. } => (output, None),
             ExecutionResult::Revert { output } => (vec![], Some(output.to_string())),
             ExecutionResult::Halt { reason } => {
-                return Err(Web3Error::SubmitTransactionError(
-                    reason.to_string(),
-                    vec![],
-                ))
+                let err = SubmitTxError::from(SandboxExecutionError::from(reason));
+                return Err(err.into_web3_error(METHOD_NAME));
             }
         };
 
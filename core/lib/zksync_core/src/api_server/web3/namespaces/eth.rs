@@ -1,7 +1,7 @@
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
+        ApiCapability, BlockId, BlockNumber, Transaction, TransactionId, TransactionReceipt,
         TransactionVariant,
     },
     l2::{L2Tx, TransactionType},
@@ -19,16 +19,27 @@ use zksync_web3_decl::{
     types::{Address, Block, Filter, FilterChanges, Log, U64},
 };
 
-use crate::api_server::web3::{
-    backend_jsonrpsee::internal_error,
-    metrics::{BlockCallObserver, API_METRICS},
-    state::RpcState,
-    TypedFilter,
+use crate::api_server::{
+    execution_sandbox::CallCacheKey,
+    web3::{
+        api_level::client_api_level,
+        backend_jsonrpsee::internal_error,
+        metrics::{BlockCallObserver, API_METRICS},
+        state::RpcState,
+        TypedFilter,
+    },
 };
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
 pub const PROTOCOL_VERSION: &str = "zks/1";
 
+/// Removes receipt fields not supported by the API level declared by the client.
+fn adapt_receipt_to_client(receipt: &mut TransactionReceipt, client_level: u32) {
+    if client_level < ApiCapability::ReceiptGasRefunded.min_client_level() {
+        receipt.gas_refunded = None;
+    }
+}
+
 #[derive(Debug)]
 pub struct EthNamespace {
     state: RpcState,
@@ -84,9 +95,18 @@ impl EthNamespace {
 
         drop(connection);
 
+        // Calls pinned to a block hash are deterministic, so their results can be cached.
+        let cache_key = match block_id {
+            BlockId::Hash(block_hash) => Some(CallCacheKey::new(block_hash, &request)),
+            BlockId::Number(_) => None,
+        };
         let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
 
-        let call_result = self.state.tx_sender.eth_call(block_args, tx).await;
+        let call_result = self
+            .state
+            .tx_sender
+            .eth_call(block_args, tx, cache_key)
+            .await;
         let res_bytes = call_result.map_err(|err| err.into_web3_error(METHOD_NAME))?;
 
         let block_diff = self
@@ -357,18 +377,28 @@ impl EthNamespace {
             })
             .collect();
 
-        let mut receipts = self
+        let mut storage = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut receipts = storage
             .transactions_web3_dal()
             .get_transaction_receipts(&hashes)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        self.state
+            .fill_cold_receipt_logs(&mut storage, &mut receipts)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
 
         receipts.sort_unstable_by_key(|receipt| receipt.transaction_index);
+        let client_level = client_api_level();
+        for receipt in &mut receipts {
+            adapt_receipt_to_client(receipt, client_level);
+        }
 
         if let Some(block) = block {
             self.report_latency_with_block_id(method_latency, block.number.as_u32().into());
@@ -550,20 +580,30 @@ impl EthNamespace {
         const METHOD_NAME: &str = "get_transaction_receipt";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let receipts = self
+        let mut storage = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .unwrap()
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut receipts = storage
             .transactions_web3_dal()
             .get_transaction_receipts(&[hash])
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        self.state
+            .fill_cold_receipt_logs(&mut storage, &mut receipts)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
 
         method_latency.observe();
 
-        Ok(receipts.into_iter().next())
+        let client_level = client_api_level();
+        Ok(receipts.into_iter().next().map(|mut receipt| {
+            adapt_receipt_to_client(&mut receipt, client_level);
+            receipt
+        }))
     }
 
     #[tracing::instrument(skip(self))]
@@ -629,6 +669,9 @@ impl EthNamespace {
     pub async fn new_pending_transaction_filter_impl(&self) -> Result<U256, Web3Error> {
         const METHOD_NAME: &str = "new_pending_transaction_filter";
 
+        if self.state.api_config.mempool_feed.is_private() {
+            return Err(Web3Error::NotImplemented);
+        }
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let installed_filters = self
             .state
@@ -838,6 +881,7 @@ impl EthNamespace {
                     .transactions_web3_dal()
                     .get_pending_txs_hashes_after(
                         *from_timestamp_excluded,
+                        self.state.api_config.mempool_feed.published_until(),
                         Some(self.state.api_config.req_entities_limit),
                     )
                     .await
@@ -849,42 +893,8 @@ impl EthNamespace {
             }
 
             TypedFilter::Events(filter, from_block) => {
-                let addresses = if let Some(addresses) = &filter.address {
-                    addresses.0.clone()
-                } else {
-                    vec![]
-                };
-                let topics = if let Some(topics) = &filter.topics {
-                    if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
-                        return Err(Web3Error::TooManyTopics);
-                    }
-                    let topics_by_idx = topics.iter().enumerate().filter_map(|(idx, topics)| {
-                        Some((idx as u32 + 1, topics.as_ref()?.0.clone()))
-                    });
-                    topics_by_idx.collect::<Vec<_>>()
-                } else {
-                    vec![]
-                };
-
-                let mut to_block = self
-                    .state
-                    .resolve_filter_block_number(filter.to_block)
-                    .await?;
-
-                if matches!(filter.to_block, Some(BlockNumber::Number(_))) {
-                    to_block = to_block.min(
-                        self.state
-                            .resolve_filter_block_number(Some(BlockNumber::Latest))
-                            .await?,
-                    );
-                }
-
-                let get_logs_filter = GetLogsFilter {
-                    from_block: *from_block,
-                    to_block,
-                    addresses,
-                    topics,
-                };
+                let mut get_logs_filter = self.state.get_logs_filter(filter, *from_block).await?;
+                let to_block = get_logs_filter.to_block;
 
                 let mut storage = self
                     .state
@@ -893,14 +903,41 @@ impl EthNamespace {
                     .await
                     .map_err(|err| internal_error(METHOD_NAME, err))?;
 
+                // Logs for old miniblocks may be moved to the cold storage; they are filtered in memory.
+                let mut cold_logs = vec![];
+                if let Some((logs, last_cold_miniblock)) = self
+                    .state
+                    .get_cold_logs(&mut storage, &get_logs_filter)
+                    .await?
+                {
+                    cold_logs = logs;
+                    get_logs_filter.from_block = last_cold_miniblock + 1;
+                    if get_logs_filter.from_block > to_block {
+                        *from_block = to_block + 1;
+                        return Ok(FilterChanges::Logs(cold_logs));
+                    }
+                }
+
+                let strategy = self
+                    .state
+                    .plan_logs_query(&mut storage, &get_logs_filter)
+                    .await
+                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
                 if *from_block != to_block {
+                    let remaining_limit = self
+                        .state
+                        .api_config
+                        .req_entities_limit
+                        .saturating_sub(cold_logs.len());
                     if let Some(miniblock_number) = storage
                         .events_web3_dal()
-                        .get_log_block_number(
+                        .get_log_block_number_with_strategy(
                             &get_logs_filter,
-                            self.state.api_config.req_entities_limit,
+                            remaining_limit,
+                            strategy,
                         )
                         .await
                         .map_err(|err| internal_error(METHOD_NAME, err))?
@@ -915,11 +952,12 @@ impl EthNamespace {
 
                 let logs = storage
                     .events_web3_dal()
-                    .get_logs(get_logs_filter, i32::MAX as usize)
+                    .get_logs_with_strategy(get_logs_filter, i32::MAX as usize, strategy)
                     .await
                     .map_err(|err| internal_error(METHOD_NAME, err))?;
                 *from_block = to_block + 1;
-                FilterChanges::Logs(logs)
+                cold_logs.extend(logs);
+                FilterChanges::Logs(cold_logs)
             }
         };
 
@@ -1,6 +1,7 @@
 //! Actual implementation of Web3 API namespaces logic, not tied to the backend
 //! used to create a JSON RPC server.
 
+mod admin;
 mod debug;
 mod en;
 pub(crate) mod eth;
@@ -9,7 +10,8 @@ mod snapshots;
 mod web3;
 mod zks;
 
+pub(crate) use self::debug::DebugNamespaceLimits;
 pub use self::{
-    debug::DebugNamespace, en::EnNamespace, eth::EthNamespace, net::NetNamespace,
-    snapshots::SnapshotsNamespace, web3::Web3Namespace, zks::ZksNamespace,
+    admin::AdminNamespace, debug::DebugNamespace, en::EnNamespace, eth::EthNamespace,
+    net::NetNamespace, snapshots::SnapshotsNamespace, web3::Web3Namespace, zks::ZksNamespace,
 };
@@ -98,6 +98,7 @@ impl SnapshotsNamespace {
 
         method_latency.observe();
         Ok(Some(SnapshotHeader {
+            version: snapshot_metadata.version.into(),
             l1_batch_number: snapshot_metadata.l1_batch_number,
             miniblock_number,
             last_l1_batch_with_metadata: l1_batch_with_metadata,
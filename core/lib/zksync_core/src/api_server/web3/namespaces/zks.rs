@@ -1,35 +1,77 @@
 use std::{collections::HashMap, convert::TryInto};
 
+use anyhow::Context as _;
+use multivm::utils::derive_base_fee_and_gas_per_pubdata;
 use zksync_dal::StorageProcessor;
+use zksync_l1_contract_interface::{
+    i_executor::{
+        calldata::ExecutorCalldata,
+        commit::kzg::{kzg_commitment_to_versioned_hash, ZK_SYNC_BYTES_PER_BLOB},
+        encoding::EncodingVersion,
+        structures::{CommitBatchInfo, PubdataPayload, StoredBatchInfo},
+    },
+    Tokenizable,
+};
 use zksync_mini_merkle_tree::MiniMerkleTree;
-use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
+use zksync_system_constants::{
+    DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE, SYSTEM_CONTEXT_ADDRESS,
+    SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+    SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION, SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES,
+};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        self, ApiCapabilities, ApiCapability, ApiCapabilityInfo, BlobUtilizationReport,
+        BlockCommitmentProof, BlockDetails, BlockHashLink, BlockHashPreimage, BlockOrderingInfo,
+        BridgeAddresses, ChainParameters, CommittedBlobInfo, ContractAddresses,
+        GasPriceHistoryEntry, GetLogsFilter, L1BatchCommitmentDetails, L1BatchDetails,
+        L1BatchL1Cost, L1BatchPublicInputs, L1BatchStateDiffs, L1BatchStoredInfo,
+        L1TransactionSimulation, L1TransactionSimulationOptions, L2ToL1LogProof, NodeInfo, Proof,
+        ProtocolVersion, SignedResponse, SigningToken, SolvencyReport, StorageExitProof,
+        StorageProof, StorageSlotDiff, SystemContext, TokenSolvencyReport, TransactionAddressRole,
+        TransactionDetails, TransactionsByAddressCursor, TransactionsByAddressPage, ValidUntil,
+    },
+    block::{MiniblockHashScheme, MiniblockHasher},
+    commitment::{
+        post_boojum_aux_output_bytes, proof_public_input, serialize_commitments, AuxCommitments,
     },
-    fee::Fee,
+    eth_sender, ethabi,
+    fee::{Fee, FeeBreakdown, FeeEstimate, FeeReport},
     fee_model::FeeParams,
-    l1::L1Tx,
-    l2::L2Tx,
+    l1::{apply_l1_to_l2_alias, L1Tx},
+    l2::{L2Tx, SignatureScheme},
     l2_to_l1_log::{l2_to_l1_logs_tree_size, L2ToL1Log},
+    pubdata_da::PubdataDA,
     tokens::ETHEREUM_ADDRESS,
-    transaction_request::CallRequest,
+    transaction_request::{CallRequest, Eip712Meta},
     utils::storage_key_for_standard_token_balance,
-    AccountTreeId, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, Transaction,
-    L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
+    web3::signing::keccak256,
+    AccountTreeId, Bytes, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey,
+    Transaction, BOOTLOADER_ADDRESS, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
-use zksync_utils::{address_to_h256, h256_to_u256};
+use zksync_utils::{address_to_h256, h256_to_u256, u256_to_h256};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Token, H256},
 };
 
-use crate::api_server::{
-    tree::TreeApiClient,
-    web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, RpcState},
+use crate::{
+    api_server::{
+        tree::TreeApiClient,
+        web3::{
+            api_level::client_api_level, backend_jsonrpsee::internal_error, metrics::API_METRICS,
+            RpcState,
+        },
+    },
+    utils::build_info,
 };
 
+/// Maximum number of transactions returned in a single `zks_getTransactionsByAddress` page.
+const TRANSACTIONS_BY_ADDRESS_PAGE_SIZE: usize = 100;
+/// Default and maximum number of commit transactions covered by `zks_getBlobUtilizationReport`.
+const BLOB_UTILIZATION_DEFAULT_LIMIT: usize = 100;
+const BLOB_UTILIZATION_MAX_LIMIT: usize = 1_000;
+
 #[derive(Debug)]
 pub struct ZksNamespace {
     pub state: RpcState,
@@ -52,10 +94,52 @@ impl ZksNamespace {
     }
 
     #[tracing::instrument(skip(self, request))]
-    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
+    pub async fn estimate_fee_impl(
+        &self,
+        request: CallRequest,
+        include_breakdown: bool,
+    ) -> Result<FeeEstimate, Web3Error> {
         const METHOD_NAME: &str = "estimate_fee";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let tx = self.l2_tx_for_fee_estimation(request).await?;
+        let (fee, breakdown) = self.estimate_fee(tx.into(), METHOD_NAME).await?;
+        method_latency.observe();
+        Ok(FeeEstimate {
+            fee,
+            breakdown: include_breakdown.then_some(breakdown),
+        })
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    pub async fn get_fee_report_impl(&self, request: CallRequest) -> Result<FeeReport, Web3Error> {
+        const METHOD_NAME: &str = "get_fee_report";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let tx = self.l2_tx_for_fee_estimation(request).await?;
+        let contract_address = tx.execute.contract_address;
+        let calldata = tx.execute.calldata.clone();
+        let (fee, _) = self.estimate_fee(tx.into(), METHOD_NAME).await?;
+
+        let sponsorship = self
+            .state
+            .tx_sender
+            .sponsorship_policy()
+            .and_then(|policy| policy.sponsorship(contract_address, &calldata, &fee));
+        let max_user_fee = if sponsorship.is_some() {
+            U256::zero()
+        } else {
+            fee.max_total_fee()
+        };
+        method_latency.observe();
+        Ok(FeeReport {
+            fee,
+            sponsorship,
+            max_user_fee,
+        })
+    }
+
+    async fn l2_tx_for_fee_estimation(&self, request: CallRequest) -> Result<L2Tx, Web3Error> {
         let mut request_with_gas_per_pubdata_overridden = request;
 
         self.state
@@ -75,10 +159,7 @@ impl ZksNamespace {
         // not consider provided ones.
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
-
-        let fee = self.estimate_fee(tx.into(), METHOD_NAME).await?;
-        method_latency.observe();
-        Ok(fee)
+        Ok(tx)
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -89,6 +170,13 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "estimate_gas_l1_to_l2";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let tx = Self::l1_tx_for_fee_estimation(request)?;
+        let (fee, _) = self.estimate_fee(tx.into(), METHOD_NAME).await?;
+        method_latency.observe();
+        Ok(fee.gas_limit)
+    }
+
+    fn l1_tx_for_fee_estimation(request: CallRequest) -> Result<L1Tx, Web3Error> {
         let mut request_with_gas_per_pubdata_overridden = request;
         // When we're estimating fee, we are trying to deduce values related to fee, so we should
         // not consider provided ones.
@@ -98,27 +186,63 @@ impl ZksNamespace {
             }
         }
 
-        let tx: L1Tx = request_with_gas_per_pubdata_overridden
+        request_with_gas_per_pubdata_overridden
             .try_into()
-            .map_err(Web3Error::SerializationError)?;
+            .map_err(Web3Error::SerializationError)
+    }
+
+    /// Unlike fee estimation, simulation keeps the user-provided gas per pubdata limit; the required L1-to-L2 value
+    /// is only used if the limit is not specified.
+    fn l1_tx_for_simulation(mut request: CallRequest) -> Result<L1Tx, Web3Error> {
+        let eip712_meta = request.eip712_meta.get_or_insert_with(Eip712Meta::default);
+        if eip712_meta.gas_per_pubdata == U256::zero() {
+            eip712_meta.gas_per_pubdata = REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE.into();
+        }
+        request.try_into().map_err(Web3Error::SerializationError)
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    pub async fn simulate_l1_transaction_impl(
+        &self,
+        request: CallRequest,
+        options: L1TransactionSimulationOptions,
+    ) -> Result<L1TransactionSimulation, Web3Error> {
+        const METHOD_NAME: &str = "simulate_l1_transaction";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut tx = Self::l1_tx_for_simulation(request)?;
+        if options.alias_sender {
+            tx.common_data.sender = apply_l1_to_l2_alias(tx.common_data.sender);
+        }
+        tx.common_data.refund_recipient = options.refund_recipient.unwrap_or(tx.common_data.sender);
 
-        let fee = self.estimate_fee(tx.into(), METHOD_NAME).await?;
+        let simulation = self
+            .state
+            .tx_sender
+            .simulate_l1_tx(
+                tx,
+                options.to_mint,
+                self.state.api_config.estimate_gas_scale_factor,
+                self.state.api_config.estimate_gas_acceptable_overestimation,
+            )
+            .await
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
         method_latency.observe();
-        Ok(fee.gas_limit)
+        Ok(simulation)
     }
 
     async fn estimate_fee(
         &self,
         tx: Transaction,
         method_name: &'static str,
-    ) -> Result<Fee, Web3Error> {
+    ) -> Result<(Fee, FeeBreakdown), Web3Error> {
         let scale_factor = self.state.api_config.estimate_gas_scale_factor;
         let acceptable_overestimation =
             self.state.api_config.estimate_gas_acceptable_overestimation;
 
         self.state
             .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei_with_breakdown(tx, scale_factor, acceptable_overestimation)
             .await
             .map_err(|err| err.into_web3_error(method_name))
     }
@@ -148,6 +272,105 @@ impl ZksNamespace {
         U64::from(*self.state.api_config.l1_chain_id)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn get_contract_addresses_impl(&self) -> ContractAddresses {
+        let api_config = &self.state.api_config;
+        ContractAddresses {
+            l1_chain_id: U64::from(*api_config.l1_chain_id),
+            l2_chain_id: U64::from(api_config.l2_chain_id.as_u64()),
+            main_contract: api_config.diamond_proxy_addr,
+            bridgehub: api_config.bridgehub_proxy_addr,
+            shared_bridge: api_config.l1_shared_bridge_proxy_addr,
+            state_transition_manager: api_config.state_transition_proxy_addr,
+            validator_timelock: api_config.validator_timelock_addr,
+            governance: api_config.governance_addr,
+            bridges: api_config.bridge_addresses.clone(),
+            testnet_paymaster: api_config.l2_testnet_paymaster_addr,
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_chain_parameters_impl(&self) -> Result<ChainParameters, Web3Error> {
+        const METHOD_NAME: &str = "get_chain_parameters";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let protocol_version = storage
+            .protocol_versions_web3_dal()
+            .get_latest_protocol_version()
+            .await;
+        drop(storage);
+
+        let api_config = &self.state.api_config;
+        let params = ChainParameters {
+            l2_chain_id: U64::from(api_config.l2_chain_id.as_u64()),
+            // Custom base tokens are not supported yet, so the base token is always ETH.
+            base_token_address: ETHEREUM_ADDRESS,
+            fee_model_version: api_config.fee_model_version,
+            pubdata_da: api_config.pubdata_da,
+            batch_seal_limits: api_config.batch_seal_limits,
+            tx_limits: api_config.tx_size_limits.to_api_limits(),
+            protocol_version: protocol_version.version_id,
+            base_system_contracts: protocol_version.base_system_contracts,
+        };
+        method_latency.observe();
+        Ok(params)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_node_info_impl(&self) -> NodeInfo {
+        let latest_version = ProtocolVersionId::next() as u16;
+        let supported_protocol_versions = (0..=latest_version)
+            .filter(|&id| ProtocolVersionId::try_from(id).is_ok())
+            .collect();
+        NodeInfo {
+            git_commit: build_info::GIT_COMMIT.map(str::to_owned),
+            build_profile: build_info::BUILD_PROFILE.to_owned(),
+            components: self.state.api_config.components.clone(),
+            api_namespaces: self
+                .state
+                .namespaces
+                .iter()
+                .map(|namespace| namespace.as_str().to_owned())
+                .collect(),
+            supported_protocol_versions,
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_api_capabilities_impl(&self) -> Result<ApiCapabilities, Web3Error> {
+        const METHOD_NAME: &str = "get_api_capabilities";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let protocol_version = storage
+            .protocol_versions_web3_dal()
+            .get_latest_protocol_version()
+            .await
+            .version_id;
+        drop(storage);
+        let protocol_version_id = ProtocolVersionId::try_from(protocol_version)
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let client_level = client_api_level();
+        let capabilities = ApiCapability::ALL
+            .into_iter()
+            .map(|capability| ApiCapabilityInfo {
+                capability,
+                min_protocol_version: capability.min_protocol_version().map(|id| id as u16),
+                min_client_level: capability.min_client_level(),
+                enabled: capability.is_enabled(protocol_version_id, client_level),
+            })
+            .collect();
+        method_latency.observe();
+        Ok(ApiCapabilities {
+            protocol_version,
+            client_level,
+            max_client_level: ApiCapability::MAX_CLIENT_LEVEL,
+            capabilities,
+        })
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_confirmed_tokens_impl(
         &self,
@@ -484,11 +707,92 @@ impl ZksNamespace {
                 .lookup_tx_details(METHOD_NAME, hash)
                 .await;
         }
+        if let Ok(Some(details)) = &mut tx_details {
+            details.revert_reason = details
+                .revert_reason
+                .take()
+                .map(|reason| self.state.custom_errors.decode(reason));
+        }
 
         method_latency.observe();
         tx_details
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transactions_by_address_impl(
+        &self,
+        address: Address,
+        role: TransactionAddressRole,
+        cursor: Option<TransactionsByAddressCursor>,
+    ) -> Result<TransactionsByAddressPage, Web3Error> {
+        const METHOD_NAME: &str = "get_transactions_by_address";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let page_size =
+            TRANSACTIONS_BY_ADDRESS_PAGE_SIZE.min(self.state.api_config.req_entities_limit);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_transactions_by_address(
+                address,
+                role,
+                cursor,
+                page_size,
+                self.state.api_config.l2_chain_id,
+            )
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(Self::transactions_page(transactions, page_size))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transactions_by_signature_scheme_impl(
+        &self,
+        scheme: SignatureScheme,
+        cursor: Option<TransactionsByAddressCursor>,
+    ) -> Result<TransactionsByAddressPage, Web3Error> {
+        const METHOD_NAME: &str = "get_transactions_by_signature_scheme";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let page_size =
+            TRANSACTIONS_BY_ADDRESS_PAGE_SIZE.min(self.state.api_config.req_entities_limit);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_transactions_by_signature_scheme(
+                scheme,
+                cursor,
+                page_size,
+                self.state.api_config.l2_chain_id,
+            )
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(Self::transactions_page(transactions, page_size))
+    }
+
+    fn transactions_page(
+        transactions: Vec<api::Transaction>,
+        page_size: usize,
+    ) -> TransactionsByAddressPage {
+        // A full page may be followed by more transactions; a partial one is the end of the history.
+        let next_cursor = if transactions.len() == page_size {
+            transactions.last().and_then(|tx| {
+                Some(TransactionsByAddressCursor {
+                    block_number: MiniblockNumber(tx.block_number?.as_u32()),
+                    transaction_index: tx.transaction_index?.as_u32(),
+                })
+            })
+        } else {
+            None
+        };
+        TransactionsByAddressPage {
+            transactions,
+            next_cursor,
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_l1_batch_details_impl(
         &self,
@@ -510,121 +814,1177 @@ impl ZksNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn get_bytecode_by_hash_impl(
+    pub async fn get_solvency_report_impl(
         &self,
-        hash: H256,
-    ) -> Result<Option<Vec<u8>>, Web3Error> {
-        const METHOD_NAME: &str = "get_bytecode_by_hash";
+        batch_number: Option<L1BatchNumber>,
+    ) -> Result<Option<SolvencyReport>, Web3Error> {
+        const METHOD_NAME: &str = "get_solvency_report";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let mut storage = self.access_storage(METHOD_NAME).await?;
-        let bytecode = storage
-            .factory_deps_dal()
-            .get_factory_dep(hash)
+        let batch_number = match batch_number {
+            Some(number) => Some(number),
+            None => storage
+                .solvency_reports_dal()
+                .get_last_report_l1_batch()
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?,
+        };
+        let Some(batch_number) = batch_number else {
+            method_latency.observe();
+            return Ok(None);
+        };
+        let Some(report) = storage
+            .solvency_reports_dal()
+            .get_report(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+        else {
+            method_latency.observe();
+            return Ok(None);
+        };
+
+        let last_executed_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let finalized_withdrawn = match last_executed_batch {
+            Some(executed) if executed >= batch_number => report.total_withdrawn,
+            Some(executed) => storage
+                .solvency_reports_dal()
+                .get_report(executed)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+                .map_or_else(U256::zero, |report| report.total_withdrawn),
+            None => U256::zero(),
+        };
+        let tokens = storage
+            .solvency_reports_dal()
+            .get_token_reports(batch_number)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let tokens = tokens
+            .into_iter()
+            .map(|token| {
+                let l2_outstanding = token.total_deposited.saturating_sub(token.total_withdrawn);
+                TokenSolvencyReport {
+                    l1_address: token.l1_token_address,
+                    l2_address: token.l2_token_address,
+                    total_deposited: token.total_deposited,
+                    total_withdrawn: token.total_withdrawn,
+                    l2_outstanding,
+                    l1_bridge_balance: token.l1_bridge_balance,
+                    is_solvent: token
+                        .l1_bridge_balance
+                        .map(|balance| balance >= l2_outstanding),
+                }
+            })
+            .collect();
+
         method_latency.observe();
-        Ok(bytecode)
+        Ok(Some(SolvencyReport {
+            l1_batch_number: report.l1_batch_number,
+            total_deposited: report.total_deposited,
+            total_withdrawn: report.total_withdrawn,
+            finalized_withdrawn,
+            expected_supply: report.expected_supply,
+            l2_total_supply: report.l2_total_supply,
+            is_balanced: report.expected_supply == report.l2_total_supply,
+            l1_bridge_balance: report.l1_bridge_balance,
+            is_solvent: report
+                .l1_bridge_balance
+                .map(|balance| balance + report.total_withdrawn >= report.total_deposited),
+            tokens,
+        }))
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn get_l1_gas_price_impl(&self) -> U64 {
-        const METHOD_NAME: &str = "get_l1_gas_price";
+    pub async fn get_blob_utilization_report_impl(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<BlobUtilizationReport, Web3Error> {
+        const METHOD_NAME: &str = "get_blob_utilization_report";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let gas_price = self
-            .state
-            .tx_sender
-            .0
-            .batch_fee_input_provider
-            .get_batch_fee_input()
+        let limit = limit
+            .unwrap_or(BLOB_UTILIZATION_DEFAULT_LIMIT)
+            .min(BLOB_UTILIZATION_MAX_LIMIT);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let commit_txs = storage
+            .eth_sender_dal()
+            .get_blob_utilization(limit)
             .await
-            .l1_gas_price();
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
 
+        let report = blob_utilization_report(&commit_txs);
         method_latency.observe();
-        gas_price.into()
+        Ok(report)
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn get_fee_params_impl(&self) -> FeeParams {
-        const METHOD_NAME: &str = "get_fee_params";
+    pub async fn get_batch_state_diffs_impl(
+        &self,
+        batch_number: L1BatchNumber,
+        include_values: bool,
+    ) -> Result<Option<L1BatchStateDiffs>, Web3Error> {
+        const METHOD_NAME: &str = "get_batch_state_diffs";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let fee_model_params = self
-            .state
-            .tx_sender
-            .0
-            .batch_fee_input_provider
-            .get_fee_model_params();
+        self.state.start_info.ensure_not_pruned(batch_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let miniblock_range = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if miniblock_range.is_none() {
+            // The batch is not sealed yet, so its state diffs are not final.
+            method_latency.observe();
+            return Ok(None);
+        }
 
-        method_latency.observe();
+        let state_diffs = storage
+            .storage_logs_dal()
+            .get_l1_batch_state_diffs(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut initial_writes = vec![];
+        let mut repeated_writes = vec![];
+        for record in state_diffs {
+            let is_initial_write = record.enumeration_index == 0;
+            let diff = StorageSlotDiff {
+                address: record.address,
+                key: u256_to_h256(record.key),
+                derived_key: H256(record.derived_key),
+                enumeration_index: (!is_initial_write).then_some(record.enumeration_index),
+                initial_value: include_values.then(|| u256_to_h256(record.initial_value)),
+                final_value: include_values.then(|| u256_to_h256(record.final_value)),
+            };
+            if is_initial_write {
+                initial_writes.push(diff);
+            } else {
+                repeated_writes.push(diff);
+            }
+        }
 
-        fee_model_params
+        method_latency.observe();
+        Ok(Some(L1BatchStateDiffs {
+            number: batch_number,
+            initial_writes,
+            repeated_writes,
+        }))
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn get_protocol_version_impl(
+    pub async fn get_l1_batch_public_inputs_impl(
         &self,
-        version_id: Option<u16>,
-    ) -> Result<Option<ProtocolVersion>, Web3Error> {
-        const METHOD_NAME: &str = "get_protocol_version";
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchPublicInputs>, Web3Error> {
+        const METHOD_NAME: &str = "get_l1_batch_public_inputs";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(batch_number)?;
+        let Some(prev_batch_number) = batch_number.0.checked_sub(1) else {
+            // The genesis batch isn't proven.
+            method_latency.observe();
+            return Ok(None);
+        };
         let mut storage = self.access_storage(METHOD_NAME).await?;
-        let protocol_version = match version_id {
-            Some(id) => {
-                storage
-                    .protocol_versions_web3_dal()
-                    .get_protocol_version_by_id(id)
-                    .await
-            }
-            None => Some(
-                storage
-                    .protocol_versions_web3_dal()
-                    .get_latest_protocol_version()
-                    .await,
-            ),
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let prev_l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(prev_batch_number))
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let (Some(l1_batch), Some(prev_l1_batch)) = (l1_batch, prev_l1_batch) else {
+            method_latency.observe();
+            return Ok(None);
         };
+        let blob_commitments = storage
+            .blocks_dal()
+            .get_l1_batch_blob_commitments(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
 
+        let prev_commitment = prev_l1_batch.metadata.commitment;
+        let commitment = l1_batch.metadata.commitment;
         method_latency.observe();
-        Ok(protocol_version)
+        Ok(Some(L1BatchPublicInputs {
+            number: batch_number,
+            prev_root_hash: prev_l1_batch.metadata.root_hash,
+            root_hash: l1_batch.metadata.root_hash,
+            prev_commitment,
+            commitment,
+            priority_operations_hash: l1_batch.header.priority_ops_onchain_data_hash(),
+            blob_commitments,
+            public_input: proof_public_input(prev_commitment, commitment),
+        }))
     }
 
-    #[tracing::instrument(skip_all)]
-    pub async fn get_proofs_impl(
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l1_batch_stored_info_impl(
         &self,
-        address: Address,
-        keys: Vec<H256>,
-        l1_batch_number: L1BatchNumber,
-    ) -> Result<Proof, Web3Error> {
-        const METHOD_NAME: &str = "get_proofs";
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchStoredInfo>, Web3Error> {
+        const METHOD_NAME: &str = "get_l1_batch_stored_info";
 
-        self.state.start_info.ensure_not_pruned(l1_batch_number)?;
-        let hashed_keys = keys
-            .iter()
-            .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
-            .collect();
-        let storage_proof = self
-            .state
-            .tree_api
-            .as_ref()
-            .ok_or(Web3Error::TreeApiUnavailable)?
-            .get_proofs(l1_batch_number, hashed_keys)
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(batch_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let details = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(commit_tx_hash) = details.and_then(|details| details.base.commit_tx_hash) else {
+            // The batch doesn't exist or is not committed on L1 yet.
+            method_latency.observe();
+            return Ok(None);
+        };
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(batch_number)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?
-            .into_iter()
-            .zip(keys)
-            .map(|(proof, key)| StorageProof {
-                key,
-                proof: proof.merkle_path,
-                value: proof.value,
-                index: proof.index,
+            .with_context(|| format!("committed L1 batch #{batch_number} has no metadata"))
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
+
+        let stored_batch_info = ethabi::encode(&[StoredBatchInfo::from(&l1_batch).into_token()]);
+        let stored_batch_hash = H256(keccak256(&stored_batch_info));
+        method_latency.observe();
+        Ok(Some(L1BatchStoredInfo {
+            number: batch_number,
+            stored_batch_info: stored_batch_info.into(),
+            stored_batch_hash,
+            commit_tx_hash,
+        }))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l1_batch_commitment_details_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchCommitmentDetails>, Web3Error> {
+        const METHOD_NAME: &str = "get_l1_batch_commitment_details";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(batch_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let details = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(details) = details else {
+            method_latency.observe();
+            return Ok(None);
+        };
+        let Some(commit_tx_hash) = details.base.commit_tx_hash else {
+            // The batch is not committed on L1 yet.
+            method_latency.observe();
+            return Ok(None);
+        };
+
+        let protocol_version = storage
+            .blocks_dal()
+            .get_l1_batch_header(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .and_then(|header| header.protocol_version)
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        let commit_batch_info = Self::load_commit_batch_info(&mut storage, batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let recorded_pubdata_da = storage
+            .blocks_dal()
+            .get_commit_pubdata_da(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
+
+        let (pubdata_da, blobs) = match &commit_batch_info {
+            Some(info) => committed_pubdata(info, protocol_version),
+            None => (recorded_pubdata_da, vec![]),
+        };
+        let commit_batch_info =
+            commit_batch_info.map(|info| ethabi::encode(&[info.into_token()]).into());
+        method_latency.observe();
+        Ok(Some(L1BatchCommitmentDetails {
+            number: batch_number,
+            pubdata_da,
+            commit_batch_info,
+            blobs,
+            commit_tx_hash,
+            prove_tx_hash: details.base.prove_tx_hash,
+            execute_tx_hash: details.base.execute_tx_hash,
+        }))
+    }
+
+    /// Loads `CommitBatchInfo` for the L1 batch from the calldata of the commit transaction sent by this node.
+    async fn load_commit_batch_info(
+        storage: &mut StorageProcessor<'_>,
+        batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<CommitBatchInfo>> {
+        let Some(eth_tx_id) = storage
+            .blocks_dal()
+            .get_eth_commit_tx_id(batch_number)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let eth_tx_id = u32::try_from(eth_tx_id).context("eth_tx_id overflow")?;
+        let Some(eth_tx) = storage.eth_sender_dal().get_eth_tx(eth_tx_id).await? else {
+            return Ok(None);
+        };
+        let calldata = match ExecutorCalldata::decode(&eth_tx.raw_tx) {
+            Ok(ExecutorCalldata::Commit(calldata)) => calldata,
+            Ok(_) => anyhow::bail!(
+                "Ethereum tx #{eth_tx_id} committing L1 batch #{batch_number} doesn't call \
+                 a commit method"
+            ),
+            Err(err) => {
+                // E.g., the transaction was inserted by the external node and has no calldata.
+                tracing::debug!(
+                    "Cannot decode calldata of Ethereum tx #{eth_tx_id} committing L1 batch \
+                     #{batch_number}: {err}"
+                );
+                return Ok(None);
+            }
+        };
+        Ok(calldata
+            .new_batches
+            .into_iter()
+            .find(|info| info.batch_number == batch_number))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l1_batch_l1_costs_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Vec<L1BatchL1Cost>, Web3Error> {
+        const METHOD_NAME: &str = "get_l1_batch_l1_costs";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(batch_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let costs = storage
+            .eth_sender_dal()
+            .get_l1_batch_l1_costs(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(costs
+            .into_iter()
+            .map(|(tx_type, costs)| L1BatchL1Cost {
+                tx_type: tx_type.to_string(),
+                tx_hash: costs.tx_hash,
+                gas_used: costs.gas_used,
+                effective_gas_price: costs.effective_gas_price,
+                l1_block_number: costs.l1_block_number.0.into(),
+                total_fee: costs.total_fee(),
             })
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_ordering_info_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<BlockOrderingInfo>, Web3Error> {
+        const METHOD_NAME: &str = "get_block_ordering_info";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(block_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let sealed_miniblock_number = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if sealed_miniblock_number.map_or(true, |number| block_number > number) {
+            method_latency.observe();
+            return Ok(None);
+        }
+
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_miniblock_ordering_info(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(Some(BlockOrderingInfo::new(block_number, transactions)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_hash_preimage_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<BlockHashPreimage>, Web3Error> {
+        const METHOD_NAME: &str = "get_block_hash_preimage";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(block_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let Some(header) = storage
+            .blocks_dal()
+            .get_miniblock_header(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+        else {
+            method_latency.observe();
+            return Ok(None);
+        };
+
+        let parent_hash = if block_number == MiniblockNumber(0) {
+            H256::zero()
+        } else {
+            Self::get_parent_hash(&mut storage, block_number - 1)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+        };
+        let transactions: Vec<_> = storage
+            .transactions_web3_dal()
+            .get_miniblock_ordering_info(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .into_iter()
+            .map(|tx| tx.hash)
             .collect();
 
+        let mut hasher = MiniblockHasher::new(block_number, header.timestamp, parent_hash);
+        for &tx_hash in &transactions {
+            hasher.push_tx_hash(tx_hash);
+        }
+        // The protocol version may be unknown for old miniblocks on external nodes; in this case,
+        // the scheme is detected.
+        let hash_scheme = hasher
+            .verify(header.protocol_version, header.hash)
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(Some(BlockHashPreimage {
+            number: block_number,
+            hash: header.hash,
+            hash_scheme,
+            timestamp: header.timestamp,
+            parent_hash,
+            transactions,
+            txs_rolling_hash: hasher.txs_rolling_hash(),
+        }))
+    }
+
+    async fn get_parent_hash(
+        storage: &mut StorageProcessor<'_>,
+        parent_number: MiniblockNumber,
+    ) -> anyhow::Result<H256> {
+        let parent_hash = storage
+            .blocks_web3_dal()
+            .get_miniblock_hash(parent_number)
+            .await?;
+        if let Some(hash) = parent_hash {
+            return Ok(hash);
+        }
+        // The parent miniblock may be missing if the node was recovered from a snapshot.
+        let snapshot_recovery = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        snapshot_recovery
+            .filter(|recovery| recovery.miniblock_number == parent_number)
+            .map(|recovery| recovery.miniblock_hash)
+            .with_context(|| format!("parent miniblock #{parent_number} is missing"))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_commitment_proof_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<BlockCommitmentProof>, Web3Error> {
+        const METHOD_NAME: &str = "get_block_commitment_proof";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(block_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let Some(l1_batch_number) = storage
+            .blocks_web3_dal()
+            .get_l1_batch_number_of_miniblock(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+        else {
+            method_latency.observe();
+            return Ok(None);
+        };
+        let Some(l1_batch) = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+        else {
+            // The batch is not sealed, or its commitment is not computed yet.
+            method_latency.observe();
+            return Ok(None);
+        };
+        // Miniblock hashes are only stored in `SystemContext` storage for the header-based hashing scheme.
+        let protocol_version = l1_batch
+            .header
+            .protocol_version
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        let hash_scheme = MiniblockHashScheme::for_protocol_version(protocol_version);
+        if l1_batch_number == L1BatchNumber(0) || hash_scheme != MiniblockHashScheme::Header {
+            method_latency.observe();
+            return Ok(None);
+        }
+        let (_, last_block_number) = storage
+            .blocks_web3_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .with_context(|| format!("no miniblocks for L1 batch #{l1_batch_number}"))
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let hash = storage
+            .blocks_web3_dal()
+            .get_miniblock_hash(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .with_context(|| format!("miniblock #{block_number} disappeared from storage"))
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut subsequent_blocks = vec![];
+        for number in (block_number.0 + 1)..=last_block_number.0 {
+            let link = Self::get_block_hash_link(&mut storage, MiniblockNumber(number))
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            subsequent_blocks.push(link);
+        }
+        drop(storage);
+
+        let prev_block_hash_position =
+            h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+                + U256::from((last_block_number.0 - 1) % SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES);
+        let keys = vec![
+            SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+            SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+            u256_to_h256(prev_block_hash_position),
+        ];
+        let system_context_proof = self
+            .get_storage_proofs(METHOD_NAME, SYSTEM_CONTEXT_ADDRESS, keys, l1_batch_number)
+            .await?;
+
+        let metadata = l1_batch.metadata;
+        method_latency.observe();
+        Ok(Some(BlockCommitmentProof {
+            number: block_number,
+            hash,
+            l1_batch_number,
+            subsequent_blocks,
+            system_context_proof,
+            root_hash: metadata.root_hash,
+            rollup_last_leaf_index: metadata.rollup_last_leaf_index,
+            meta_parameters_hash: metadata.meta_parameters_hash,
+            aux_data_hash: metadata.aux_data_hash,
+            commitment: metadata.commitment,
+        }))
+    }
+
+    async fn get_block_hash_link(
+        storage: &mut StorageProcessor<'_>,
+        number: MiniblockNumber,
+    ) -> anyhow::Result<BlockHashLink> {
+        let header = storage
+            .blocks_dal()
+            .get_miniblock_header(number)
+            .await?
+            .with_context(|| format!("miniblock #{number} disappeared from storage"))?;
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_miniblock_ordering_info(number)
+            .await?;
+        // Parent hash doesn't influence the rolling hash of transactions.
+        let mut hasher = MiniblockHasher::new(number, header.timestamp, H256::zero());
+        for tx in &transactions {
+            hasher.push_tx_hash(tx.hash);
+        }
+        Ok(BlockHashLink {
+            number,
+            timestamp: header.timestamp,
+            txs_rolling_hash: hasher.txs_rolling_hash(),
+            hash: header.hash,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_bytecode_by_hash_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<Vec<u8>>, Web3Error> {
+        const METHOD_NAME: &str = "get_bytecode_by_hash";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let bytecode = storage
+            .factory_deps_dal()
+            .get_factory_dep(hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(bytecode)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l1_gas_price_impl(&self) -> U64 {
+        const METHOD_NAME: &str = "get_l1_gas_price";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let gas_price = self
+            .state
+            .tx_sender
+            .0
+            .batch_fee_input_provider
+            .get_batch_fee_input()
+            .await
+            .l1_gas_price();
+
+        method_latency.observe();
+        gas_price.into()
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn gas_price_history_impl(
+        &self,
+        from_block: MiniblockNumber,
+        block_count: U64,
+    ) -> Result<Vec<GasPriceHistoryEntry>, Web3Error> {
+        const METHOD_NAME: &str = "gas_price_history";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(from_block)?;
+        let block_count = block_count
+            .as_u64()
+            .min(self.state.api_config.fee_history_limit);
+
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let history = storage
+            .blocks_web3_dal()
+            .get_gas_price_history(from_block, block_count)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(history)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_system_context_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<SystemContext>, Web3Error> {
+        const METHOD_NAME: &str = "get_system_context";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(block_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let Some(header) = storage
+            .blocks_dal()
+            .get_miniblock_header(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+        else {
+            return Ok(None);
+        };
+
+        let prev_miniblock_hash = if let Some(prev_number) = block_number.0.checked_sub(1) {
+            let prev_header = storage
+                .blocks_dal()
+                .get_miniblock_header(MiniblockNumber(prev_number))
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            // The previous miniblock may be missing if the node was recovered from a snapshot.
+            prev_header.map(|header| header.hash)
+        } else {
+            None
+        };
+
+        let resolved_l1_batch = storage
+            .storage_web3_dal()
+            .resolve_l1_batch_number_of_miniblock(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let l1_batch_timestamp = storage
+            .blocks_web3_dal()
+            .get_expected_l1_batch_timestamp(&resolved_l1_batch)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .ok_or_else(|| {
+                let err = format!("no timestamp for L1 batch of miniblock #{block_number}");
+                internal_error(METHOD_NAME, err)
+            })?;
+
+        // Blocks without version specified are considered to be of `Version9`, same as in the VM sandbox.
+        let vm_version = header
+            .protocol_version
+            .unwrap_or(ProtocolVersionId::last_potentially_undefined())
+            .into();
+        let fee_input = header.batch_fee_input;
+        let (_, gas_per_pubdata) = derive_base_fee_and_gas_per_pubdata(fee_input, vm_version);
+
+        method_latency.observe();
+        Ok(Some(SystemContext {
+            chain_id: U64::from(self.state.api_config.l2_chain_id.as_u64()),
+            l1_batch_number: resolved_l1_batch.expected_l1_batch(),
+            l1_batch_sealed: resolved_l1_batch.miniblock_l1_batch.is_some(),
+            l1_batch_timestamp,
+            miniblock_number: header.number,
+            miniblock_timestamp: header.timestamp,
+            prev_miniblock_hash,
+            max_virtual_blocks_to_create: header.virtual_blocks,
+            // `block.coinbase` in the VM is the bootloader formal address, not the fee account.
+            coinbase: BOOTLOADER_ADDRESS,
+            protocol_version: header.protocol_version,
+            base_fee_per_gas: header.base_fee_per_gas.into(),
+            gas_per_pubdata: gas_per_pubdata.into(),
+            gas_per_pubdata_limit: header.gas_per_pubdata_limit.into(),
+            l1_gas_price: fee_input.l1_gas_price().into(),
+            fair_l2_gas_price: fee_input.fair_l2_gas_price().into(),
+            fair_pubdata_price: fee_input.fair_pubdata_price().into(),
+        }))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_fee_params_impl(&self) -> FeeParams {
+        const METHOD_NAME: &str = "get_fee_params";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let fee_model_params = self
+            .state
+            .tx_sender
+            .0
+            .batch_fee_input_provider
+            .get_fee_model_params();
+
+        method_latency.observe();
+
+        fee_model_params
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_protocol_version_impl(
+        &self,
+        version_id: Option<u16>,
+    ) -> Result<Option<ProtocolVersion>, Web3Error> {
+        const METHOD_NAME: &str = "get_protocol_version";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let protocol_version = match version_id {
+            Some(id) => {
+                storage
+                    .protocol_versions_web3_dal()
+                    .get_protocol_version_by_id(id)
+                    .await
+            }
+            None => Some(
+                storage
+                    .protocol_versions_web3_dal()
+                    .get_latest_protocol_version()
+                    .await,
+            ),
+        };
+
+        method_latency.observe();
+        Ok(protocol_version)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn get_proofs_impl(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Proof, Web3Error> {
+        const METHOD_NAME: &str = "get_proofs";
+
+        self.state.start_info.ensure_not_pruned(l1_batch_number)?;
+        let storage_proof = self
+            .get_storage_proofs(METHOD_NAME, address, keys, l1_batch_number)
+            .await?;
         Ok(Proof {
             address,
             storage_proof,
         })
     }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_storage_exit_proof_impl(
+        &self,
+        address: Address,
+        key: H256,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<StorageExitProof>, Web3Error> {
+        const METHOD_NAME: &str = "get_storage_exit_proof";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(l1_batch_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let details = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some((commit_tx_hash, execute_tx_hash)) = details.and_then(|details| {
+            Some((details.base.commit_tx_hash?, details.base.execute_tx_hash?))
+        }) else {
+            // The batch doesn't exist or is not executed on L1 yet.
+            method_latency.observe();
+            return Ok(None);
+        };
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .with_context(|| format!("executed L1 batch #{l1_batch_number} has no metadata"))
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let protocol_version = l1_batch
+            .header
+            .protocol_version
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        if protocol_version.is_pre_boojum() {
+            // Pre-Boojum batches don't commit to state diffs.
+            method_latency.observe();
+            return Ok(None);
+        }
+
+        let state_diffs = storage
+            .storage_logs_dal()
+            .get_l1_batch_state_diffs(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let key_u256 = h256_to_u256(key);
+        let Some(state_diff_index) = state_diffs
+            .iter()
+            .position(|diff| diff.address == address && diff.key == key_u256)
+        else {
+            // The slot is not modified in the batch.
+            method_latency.observe();
+            return Ok(None);
+        };
+        let blobs = storage
+            .blocks_dal()
+            .get_l1_batch_blob_commitments(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .unwrap_or_default();
+        drop(storage);
+
+        let aux_commitments = l1_batch
+            .metadata
+            .events_queue_commitment
+            .zip(l1_batch.metadata.bootloader_initial_content_commitment)
+            .map(
+                |(events_queue_commitment, bootloader_initial_content_commitment)| AuxCommitments {
+                    events_queue_commitment,
+                    bootloader_initial_content_commitment,
+                },
+            )
+            .with_context(|| format!("L1 batch #{l1_batch_number} has no aux commitments"))
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let state_diffs_packed = serialize_commitments(&state_diffs);
+        let system_logs_linear_hash = H256(keccak256(&serialize_commitments(
+            &l1_batch.header.system_logs,
+        )));
+        let aux_output = post_boojum_aux_output_bytes(
+            protocol_version,
+            system_logs_linear_hash,
+            H256(keccak256(&state_diffs_packed)),
+            aux_commitments,
+            &blobs,
+        );
+        if H256(keccak256(&aux_output)) != l1_batch.metadata.aux_data_hash {
+            let err = anyhow::anyhow!(
+                "auxiliary output for L1 batch #{l1_batch_number} doesn't match its `aux_data_hash`"
+            );
+            return Err(internal_error(METHOD_NAME, err));
+        }
+
+        let value = u256_to_h256(state_diffs[state_diff_index].final_value);
+        let stored_batch_info = StoredBatchInfo::from(&l1_batch).into_token();
+        let calldata = ethabi::encode(&[
+            stored_batch_info.clone(),
+            ethabi::Token::FixedBytes(l1_batch.metadata.pass_through_data_hash.0.to_vec()),
+            ethabi::Token::FixedBytes(l1_batch.metadata.meta_parameters_hash.0.to_vec()),
+            ethabi::Token::Bytes(aux_output),
+            ethabi::Token::Bytes(state_diffs_packed),
+            ethabi::Token::Uint(state_diff_index.into()),
+        ]);
+
+        method_latency.observe();
+        Ok(Some(StorageExitProof {
+            address,
+            key,
+            value,
+            l1_batch_number,
+            state_diff_index: state_diff_index as u32,
+            stored_batch_info: ethabi::encode(&[stored_batch_info]).into(),
+            commitment: l1_batch.metadata.commitment,
+            commit_tx_hash,
+            execute_tx_hash,
+            calldata: calldata.into(),
+        }))
+    }
+
+    async fn get_storage_proofs(
+        &self,
+        method_name: &'static str,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Vec<StorageProof>, Web3Error> {
+        let hashed_keys = keys
+            .iter()
+            .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
+            .collect();
+        let storage_proof = self
+            .state
+            .tree_api
+            .as_ref()
+            .ok_or(Web3Error::TreeApiUnavailable)?
+            .get_proofs(l1_batch_number, hashed_keys)
+            .await
+            .map_err(|err| internal_error(method_name, err))?
+            .into_iter()
+            .zip(keys)
+            .map(|(proof, key)| StorageProof {
+                key,
+                proof: proof.merkle_path,
+                value: proof.value,
+                index: proof.index,
+            })
+            .collect();
+        Ok(storage_proof)
+    }
+
+    /// Signs the response of the specified RPC method called with `params`, or returns an error if response signing
+    /// is disabled.
+    fn sign_response<T: SigningToken>(
+        &self,
+        method_name: &'static str,
+        rpc_method: &str,
+        params: &[ethabi::Token],
+        response: T,
+    ) -> Result<SignedResponse<T>, Web3Error> {
+        let signer = self
+            .state
+            .response_signer
+            .as_ref()
+            .ok_or(Web3Error::ResponseSigningDisabled)?;
+        signer
+            .sign(
+                rpc_method,
+                self.state.api_config.l2_chain_id,
+                params,
+                response,
+            )
+            .map_err(|err| internal_error(method_name, err))
+    }
+
+    fn ensure_response_signing(&self) -> Result<(), Web3Error> {
+        if self.state.response_signer.is_none() {
+            return Err(Web3Error::ResponseSigningDisabled);
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_signed_l1_batch_details_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<SignedResponse<L1BatchDetails>>, Web3Error> {
+        const METHOD_NAME: &str = "get_signed_l1_batch_details";
+
+        self.ensure_response_signing()?;
+        let params = [batch_number.signing_token()];
+        let details = self.get_l1_batch_details_impl(batch_number).await?;
+        details
+            .map(|details| {
+                self.sign_response(METHOD_NAME, "zks_getSignedL1BatchDetails", &params, details)
+            })
+            .transpose()
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_signed_l2_to_l1_msg_proof_impl(
+        &self,
+        block_number: MiniblockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> Result<Option<SignedResponse<L2ToL1LogProof>>, Web3Error> {
+        const METHOD_NAME: &str = "get_signed_l2_to_l1_msg_proof";
+
+        self.ensure_response_signing()?;
+        let params = [
+            block_number.signing_token(),
+            sender.signing_token(),
+            msg.signing_token(),
+            l2_log_position.signing_token(),
+        ];
+        let proof = self
+            .get_l2_to_l1_msg_proof_impl(block_number, sender, msg, l2_log_position)
+            .await?;
+        proof
+            .map(|proof| {
+                self.sign_response(METHOD_NAME, "zks_getSignedL2ToL1MsgProof", &params, proof)
+            })
+            .transpose()
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_signed_l2_to_l1_log_proof_impl(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> Result<Option<SignedResponse<L2ToL1LogProof>>, Web3Error> {
+        const METHOD_NAME: &str = "get_signed_l2_to_l1_log_proof";
+
+        self.ensure_response_signing()?;
+        let params = [tx_hash.signing_token(), index.signing_token()];
+        let proof = self.get_l2_to_l1_log_proof_impl(tx_hash, index).await?;
+        proof
+            .map(|proof| {
+                self.sign_response(METHOD_NAME, "zks_getSignedL2ToL1LogProof", &params, proof)
+            })
+            .transpose()
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_signed_proofs_impl(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<SignedResponse<Proof>, Web3Error> {
+        const METHOD_NAME: &str = "get_signed_proofs";
+
+        self.ensure_response_signing()?;
+        let params = [
+            address.signing_token(),
+            keys.signing_token(),
+            l1_batch_number.signing_token(),
+        ];
+        let proof = self.get_proofs_impl(address, keys, l1_batch_number).await?;
+        self.sign_response(METHOD_NAME, "zks_getSignedProof", &params, proof)
+    }
+
+    #[tracing::instrument(skip(self, tx_bytes))]
+    pub async fn send_raw_transaction_with_deadline_impl(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: ValidUntil,
+    ) -> Result<H256, Web3Error> {
+        const METHOD_NAME: &str = "send_raw_transaction_with_deadline";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        tx.set_input(tx_bytes.0, hash);
+        tx.common_data.valid_until = Some(valid_until);
+
+        let submit_result = self.state.tx_sender.submit_tx(tx).await;
+        let submit_result = submit_result.map(|_| hash).map_err(|err| {
+            tracing::debug!("Send raw transaction with deadline error: {err}");
+            API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
+            err.into_web3_error(METHOD_NAME)
+        });
+
+        method_latency.observe();
+        submit_result
+    }
+}
+
+/// Returns the DA mode and published blobs for the committed L1 batch based on its `CommitBatchInfo`.
+fn committed_pubdata(
+    info: &CommitBatchInfo,
+    protocol_version: ProtocolVersionId,
+) -> (Option<PubdataDA>, Vec<CommittedBlobInfo>) {
+    if EncodingVersion::from(protocol_version) != EncodingVersion::Post1_4_2 {
+        // Older protocol versions only support publishing pubdata in calldata.
+        return (Some(PubdataDA::Calldata), vec![]);
+    }
+
+    match info.pubdata_payload() {
+        Ok(PubdataPayload::Empty(pubdata_da)) => (Some(pubdata_da), vec![]),
+        Ok(PubdataPayload::Calldata { .. }) => (Some(PubdataDA::Calldata), vec![]),
+        Ok(PubdataPayload::Custom { .. }) => (Some(PubdataDA::Custom), vec![]),
+        Ok(PubdataPayload::Blobs(pubdata_commitments)) => {
+            let blobs = pubdata_commitments
+                .into_iter()
+                .map(|pubdata_commitment| {
+                    // Pubdata commitment format: opening point (16 bytes) || claimed value (32 bytes) ||
+                    // KZG commitment (48 bytes) || opening proof (48 bytes)
+                    let kzg_commitment = &pubdata_commitment[48..96];
+                    CommittedBlobInfo {
+                        versioned_hash: H256(kzg_commitment_to_versioned_hash(kzg_commitment)),
+                        kzg_commitment: kzg_commitment.to_vec().into(),
+                    }
+                })
+                .collect();
+            (Some(PubdataDA::Blobs), blobs)
+        }
+        Err(err) => {
+            tracing::warn!(
+                "Cannot parse pubdata of committed L1 batch #{}: {err}",
+                info.batch_number
+            );
+            (None, vec![])
+        }
+    }
+}
+
+/// Builds a blob utilization report from the per-transaction utilization ordered from newest to oldest transactions.
+fn blob_utilization_report(
+    commit_txs: &[eth_sender::CommitBlobUtilization],
+) -> BlobUtilizationReport {
+    let blob_capacity = |blob_count: u32| u64::from(blob_count) * ZK_SYNC_BYTES_PER_BLOB as u64;
+    let l1_batch_count = commit_txs.iter().map(|tx| tx.l1_batch_count()).sum::<u32>();
+    let commits: Vec<_> = commit_txs
+        .iter()
+        .map(|commit| api::CommitBlobUtilization {
+            first_l1_batch_number: *commit.l1_batches.start(),
+            last_l1_batch_number: *commit.l1_batches.end(),
+            blob_count: commit.blob_count,
+            pubdata_bytes: commit.pubdata_bytes,
+            wasted_bytes: blob_capacity(commit.blob_count).saturating_sub(commit.pubdata_bytes),
+            fee_per_l1_batch: commit.total_fee.map(|fee| fee / commit.l1_batch_count()),
+        })
+        .collect();
+
+    let blob_count = commits.iter().map(|commit| commit.blob_count).sum::<u32>();
+    let pubdata_bytes = commits
+        .iter()
+        .map(|commit| commit.pubdata_bytes)
+        .sum::<u64>();
+    let wasted_bytes = commits
+        .iter()
+        .map(|commit| commit.wasted_bytes)
+        .sum::<u64>();
+    let utilization = match blob_capacity(blob_count) {
+        0 => 0.0,
+        capacity => pubdata_bytes as f64 / capacity as f64,
+    };
+
+    BlobUtilizationReport {
+        commit_tx_count: commits.len() as u32,
+        l1_batch_count,
+        blob_count,
+        pubdata_bytes,
+        wasted_bytes,
+        utilization,
+        commits,
+    }
 }
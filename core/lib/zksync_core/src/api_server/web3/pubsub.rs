@@ -1,14 +1,30 @@
 //! (Largely) backend-agnostic logic for dealing with Web3 subscriptions.
 
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
 use anyhow::Context as _;
 use futures::FutureExt;
+use governor::{
+    clock::DefaultClock,
+    middleware::NoOpMiddleware,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use serde::{Deserialize, Serialize};
 use tokio::{
     sync::{broadcast, mpsc, watch},
     task::JoinHandle,
     time::{interval, Duration},
 };
-use zksync_dal::ConnectionPool;
-use zksync_types::{MiniblockNumber, H128, H256};
+use zksync_dal::{
+    exported_subscriptions_dal::ExportedSubscription, ConnectionPool, StorageProcessor,
+};
+use zksync_types::{
+    api::GetLogsFilter, AccountTreeId, Address, MiniblockNumber, StorageKey, H128, H256, U64,
+};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::{server::SubscriptionMessage, SubscriptionResult},
@@ -16,18 +32,26 @@ use zksync_web3_decl::{
         types::{error::ErrorCode, ErrorObject, SubscriptionId},
         PendingSubscriptionSink, SendTimeoutError, SubscriptionSink,
     },
-    namespaces::EthPubSubServer,
-    types::{BlockHeader, Log, PubSubFilter, PubSubResult},
+    namespaces::{EthPubSubServer, ZksPubSubServer},
+    types::{BlockHeader, Log, PubSubFilter, PubSubResult, StorageChange, StorageSlot},
 };
 
 use super::{
     metrics::{SubscriptionType, PUB_SUB_METRICS},
     namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
+    state::MempoolFeedConfig,
 };
-use crate::api_server::execution_sandbox::BlockStartInfo;
+use crate::{api_server::execution_sandbox::BlockStartInfo, state_keeper::MiniblockWriteSet};
 
 const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
 const SUBSCRIPTION_SINK_SEND_TIMEOUT: Duration = Duration::from_secs(1);
+/// Maximum number of storage slots watched by a single storage subscription.
+const STORAGE_SUBSCRIPTION_SLOTS_LIMIT: usize = 100;
+/// Maximum number of miniblocks replayed when resuming an exported subscription. Subscriptions lagging further behind
+/// cannot be resumed.
+const MAX_REPLAYED_MINIBLOCKS: u32 = 1_000;
+
+type SubscriberRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
 
 #[derive(Debug, Clone, Copy)]
 pub struct EthSubscriptionIdProvider;
@@ -39,6 +63,13 @@ impl IdProvider for EthSubscriptionIdProvider {
     }
 }
 
+fn subscription_id_to_string(id: SubscriptionId<'_>) -> String {
+    match id {
+        SubscriptionId::Num(id) => id.to_string(),
+        SubscriptionId::Str(id) => id.into_owned(),
+    }
+}
+
 /// Events emitted by the subscription logic. Only used in WebSocket server tests so far.
 #[derive(Debug)]
 pub(super) enum PubSubEvent {
@@ -47,10 +78,213 @@ pub(super) enum PubSubEvent {
     MiniblockAdvanced(SubscriptionType, MiniblockNumber),
 }
 
+/// Addresses of logs that should be loaded by the logs notifier.
+#[derive(Debug, PartialEq)]
+enum LogAddresses {
+    /// There are no log subscriptions, so logs don't need to be loaded at all.
+    None,
+    /// Logs emitted by any address are needed.
+    Any,
+    Only(Vec<Address>),
+}
+
+/// Filters of active subscriptions of a certain type. Used by notifiers to only load data that matches at least one
+/// of the subscriptions.
+#[derive(Debug)]
+struct SubscriptionFilters<F> {
+    next_id: u64,
+    filters: HashMap<u64, F>,
+}
+
+impl<F> Default for SubscriptionFilters<F> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            filters: HashMap::new(),
+        }
+    }
+}
+
+impl<F> SubscriptionFilters<F> {
+    fn insert(&mut self, filter: F) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.filters.insert(id, filter);
+        id
+    }
+}
+
+type LogFilters = SubscriptionFilters<PubSubFilter>;
+
+impl LogFilters {
+    fn addresses(&self) -> LogAddresses {
+        if self.filters.is_empty() {
+            return LogAddresses::None;
+        }
+        let mut addresses = HashSet::new();
+        for filter in self.filters.values() {
+            let Some(filter_addresses) = filter.addresses() else {
+                return LogAddresses::Any;
+            };
+            addresses.extend(filter_addresses.iter().copied());
+        }
+        let mut addresses: Vec<_> = addresses.into_iter().collect();
+        addresses.sort_unstable();
+        LogAddresses::Only(addresses)
+    }
+}
+
+type StorageFilters = SubscriptionFilters<HashSet<StorageSlot>>;
+
+impl StorageFilters {
+    /// Returns all watched storage slots keyed by their hashed storage key.
+    fn slots(&self) -> HashMap<H256, StorageSlot> {
+        let slots = self.filters.values().flatten();
+        slots
+            .map(|slot| {
+                let key = StorageKey::new(AccountTreeId::new(slot.address), slot.slot);
+                (key.hashed_key(), *slot)
+            })
+            .collect()
+    }
+}
+
+/// Removes a subscription filter once the subscription is dropped.
+#[derive(Debug)]
+struct FilterGuard<F> {
+    filters: Arc<Mutex<SubscriptionFilters<F>>>,
+    id: u64,
+}
+
+impl<F> FilterGuard<F> {
+    fn new(filters: &Arc<Mutex<SubscriptionFilters<F>>>, filter: F) -> Self {
+        let id = filters.lock().unwrap().insert(filter);
+        Self {
+            filters: filters.clone(),
+            id,
+        }
+    }
+}
+
+impl<F> Drop for FilterGuard<F> {
+    fn drop(&mut self) {
+        self.filters.lock().unwrap().filters.remove(&self.id);
+    }
+}
+
+/// Parameters of a subscription that can be exported to Postgres and resumed on another API server replica.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(super) enum ExportedSubscriptionParams {
+    NewHeads,
+    NewPendingTransactions,
+    Logs { filter: PubSubFilter },
+    Storage { slots: Vec<StorageSlot> },
+}
+
+/// Subscription tracked for export.
+#[derive(Debug)]
+struct ActiveSubscription {
+    params: ExportedSubscriptionParams,
+    /// Last miniblock fully processed by the subscriber.
+    last_miniblock: Option<MiniblockNumber>,
+}
+
+type ActiveSubscriptions = Arc<Mutex<HashMap<String, ActiveSubscription>>>;
+
+/// Updates the cursor of a tracked subscription and removes the subscription once it's dropped.
+#[derive(Debug)]
+struct SubscriptionCursor {
+    subscriptions: ActiveSubscriptions,
+    id: String,
+}
+
+impl SubscriptionCursor {
+    fn new(
+        subscriptions: &ActiveSubscriptions,
+        id: String,
+        params: ExportedSubscriptionParams,
+        last_miniblock: Option<MiniblockNumber>,
+    ) -> Self {
+        let subscription = ActiveSubscription {
+            params,
+            last_miniblock,
+        };
+        subscriptions
+            .lock()
+            .unwrap()
+            .insert(id.clone(), subscription);
+        Self {
+            subscriptions: subscriptions.clone(),
+            id,
+        }
+    }
+
+    fn advance(&self, miniblock_number: MiniblockNumber) {
+        if let Some(subscription) = self.subscriptions.lock().unwrap().get_mut(&self.id) {
+            subscription.last_miniblock = Some(miniblock_number);
+        }
+    }
+}
+
+impl Drop for SubscriptionCursor {
+    fn drop(&mut self) {
+        self.subscriptions.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Configuration of subscription handoff between API server replicas.
+#[derive(Debug, Clone)]
+struct SubscriptionHandoff {
+    connection_pool: ConnectionPool,
+    ttl: Duration,
+    active_subscriptions: ActiveSubscriptions,
+}
+
+/// Batch of notifications broadcast by a notifier.
+#[derive(Debug, Clone)]
+struct PubSubBatch {
+    items: Vec<PubSubResult>,
+    /// Last miniblock covered by this batch. `None` for notifications not tied to miniblocks (i.e., pending transactions).
+    last_miniblock: Option<MiniblockNumber>,
+}
+
+impl PubSubBatch {
+    /// Returns the miniblock an item belongs to, if any.
+    fn item_miniblock(item: &PubSubResult) -> Option<MiniblockNumber> {
+        let number = match item {
+            PubSubResult::Header(header) => header.number?,
+            PubSubResult::Log(log) => log.block_number?,
+            PubSubResult::StorageChange(change) => change.block_number,
+            PubSubResult::TxHash(_) | PubSubResult::Syncing(_) => return None,
+        };
+        Some(MiniblockNumber(number.as_u32()))
+    }
+}
+
+/// Filter for notifications sent to a single subscriber.
+#[derive(Debug)]
+enum SubscriberFilter {
+    Logs(PubSubFilter, FilterGuard<PubSubFilter>),
+    Storage(HashSet<StorageSlot>, FilterGuard<HashSet<StorageSlot>>),
+}
+
+impl SubscriberFilter {
+    fn matches(&self, item: &PubSubResult) -> bool {
+        match (self, item) {
+            (Self::Logs(filter, _), PubSubResult::Log(log)) => filter.matches(log),
+            (Self::Storage(slots, _), PubSubResult::StorageChange(change)) => {
+                slots.contains(&change.storage_slot())
+            }
+            _ => true,
+        }
+    }
+}
+
 /// Manager of notifications for a certain type of subscriptions.
 #[derive(Debug)]
 struct PubSubNotifier {
-    sender: broadcast::Sender<Vec<PubSubResult>>,
+    sender: broadcast::Sender<PubSubBatch>,
     connection_pool: ConnectionPool,
     polling_interval: Duration,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
@@ -103,7 +337,11 @@ impl PubSubNotifier {
             if let Some(last_block) = new_blocks.last() {
                 last_block_number = MiniblockNumber(last_block.number.unwrap().as_u32());
                 let new_blocks = new_blocks.into_iter().map(PubSubResult::Header).collect();
-                self.send_pub_sub_results(new_blocks, SubscriptionType::Blocks);
+                let batch = PubSubBatch {
+                    items: new_blocks,
+                    last_miniblock: Some(last_block_number),
+                };
+                self.send_pub_sub_results(batch, SubscriptionType::Blocks);
                 self.emit_event(PubSubEvent::MiniblockAdvanced(
                     SubscriptionType::Blocks,
                     last_block_number,
@@ -116,9 +354,9 @@ impl PubSubNotifier {
         Ok(())
     }
 
-    fn send_pub_sub_results(&self, results: Vec<PubSubResult>, sub_type: SubscriptionType) {
+    fn send_pub_sub_results(&self, batch: PubSubBatch, sub_type: SubscriptionType) {
         // Errors only on 0 receivers, but we want to go on if we have 0 subscribers so ignore the error.
-        self.sender.send(results).ok();
+        self.sender.send(batch).ok();
         PUB_SUB_METRICS.broadcast_channel_len[&sub_type].set(self.sender.len());
     }
 
@@ -126,17 +364,19 @@ impl PubSubNotifier {
         &self,
         last_block_number: MiniblockNumber,
     ) -> anyhow::Result<Vec<BlockHeader>> {
-        self.connection_pool
+        let mut storage = self
+            .connection_pool
             .access_storage_tagged("api")
             .await
-            .context("access_storage_tagged")?
-            .blocks_web3_dal()
-            .get_block_headers_after(last_block_number)
-            .await
-            .with_context(|| format!("get_block_headers_after({last_block_number})"))
+            .context("access_storage_tagged")?;
+        load_block_headers(&mut storage, last_block_number).await
     }
 
-    async fn notify_txs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+    async fn notify_txs(
+        self,
+        mempool_feed: MempoolFeedConfig,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
         let mut last_time = chrono::Utc::now().naive_utc();
         let mut timer = interval(self.polling_interval);
         loop {
@@ -147,13 +387,19 @@ impl PubSubNotifier {
             timer.tick().await;
 
             let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Txs].start();
-            let (new_txs, new_last_time) = self.new_txs(last_time).await?;
+            let (new_txs, new_last_time) = self
+                .new_txs(last_time, mempool_feed.published_until())
+                .await?;
             db_latency.observe();
 
             if let Some(new_last_time) = new_last_time {
                 last_time = new_last_time;
                 let new_txs = new_txs.into_iter().map(PubSubResult::TxHash).collect();
-                self.send_pub_sub_results(new_txs, SubscriptionType::Txs);
+                let batch = PubSubBatch {
+                    items: new_txs,
+                    last_miniblock: None,
+                };
+                self.send_pub_sub_results(batch, SubscriptionType::Txs);
             }
             self.emit_event(PubSubEvent::NotifyIterationFinished(SubscriptionType::Txs));
         }
@@ -163,18 +409,23 @@ impl PubSubNotifier {
     async fn new_txs(
         &self,
         last_time: chrono::NaiveDateTime,
+        published_until: Option<chrono::NaiveDateTime>,
     ) -> anyhow::Result<(Vec<H256>, Option<chrono::NaiveDateTime>)> {
         self.connection_pool
             .access_storage_tagged("api")
             .await
             .context("access_storage_tagged")?
             .transactions_web3_dal()
-            .get_pending_txs_hashes_after(last_time, None)
+            .get_pending_txs_hashes_after(last_time, published_until, None)
             .await
             .context("get_pending_txs_hashes_after()")
     }
 
-    async fn notify_logs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+    async fn notify_logs(
+        self,
+        log_filters: Arc<Mutex<LogFilters>>,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
         let mut last_block_number = self.get_starting_miniblock_number().await?;
 
         let mut timer = interval(self.polling_interval);
@@ -186,13 +437,17 @@ impl PubSubNotifier {
             timer.tick().await;
 
             let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Logs].start();
-            let new_logs = self.new_logs(last_block_number).await?;
+            let new_logs = self.new_logs(last_block_number, &log_filters).await?;
             db_latency.observe();
 
-            if let Some(last_log) = new_logs.last() {
-                last_block_number = MiniblockNumber(last_log.block_number.unwrap().as_u32());
-                let new_logs = new_logs.into_iter().map(PubSubResult::Log).collect();
-                self.send_pub_sub_results(new_logs, SubscriptionType::Logs);
+            if let Some((new_logs, new_last_block_number)) = new_logs {
+                last_block_number = new_last_block_number;
+                // Batches are sent even if there are no new logs, so that subscribers can advance their cursors.
+                let batch = PubSubBatch {
+                    items: new_logs.into_iter().map(PubSubResult::Log).collect(),
+                    last_miniblock: Some(last_block_number),
+                };
+                self.send_pub_sub_results(batch, SubscriptionType::Logs);
                 self.emit_event(PubSubEvent::MiniblockAdvanced(
                     SubscriptionType::Logs,
                     last_block_number,
@@ -203,37 +458,345 @@ impl PubSubNotifier {
         Ok(())
     }
 
-    async fn new_logs(&self, last_block_number: MiniblockNumber) -> anyhow::Result<Vec<Log>> {
-        self.connection_pool
+    /// Loads logs from miniblocks sealed after `last_block_number` that match at least one of the active
+    /// subscriptions. Returns `None` if there are no new miniblocks.
+    async fn new_logs(
+        &self,
+        last_block_number: MiniblockNumber,
+        log_filters: &Mutex<LogFilters>,
+    ) -> anyhow::Result<Option<(Vec<Log>, MiniblockNumber)>> {
+        let mut storage = self
+            .connection_pool
             .access_storage_tagged("api")
             .await
-            .context("access_storage_tagged")?
-            .events_web3_dal()
-            .get_all_logs(last_block_number)
+            .context("access_storage_tagged")?;
+        let sealed_miniblock_number = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("get_sealed_miniblock_number()")?;
+        let Some(sealed_miniblock_number) = sealed_miniblock_number else {
+            return Ok(None);
+        };
+        if sealed_miniblock_number <= last_block_number {
+            return Ok(None);
+        }
+
+        // Filters must be read *after* the sealed miniblock number. Otherwise, a subscription created
+        // after the filters are read may miss logs from a miniblock sealed after the subscription was created.
+        let addresses = log_filters.lock().unwrap().addresses();
+        let addresses = match addresses {
+            LogAddresses::None => return Ok(Some((vec![], sealed_miniblock_number))),
+            LogAddresses::Any => vec![],
+            LogAddresses::Only(addresses) => addresses,
+        };
+        let logs = load_logs(
+            &mut storage,
+            addresses,
+            last_block_number,
+            sealed_miniblock_number,
+        )
+        .await?;
+        Ok(Some((logs, sealed_miniblock_number)))
+    }
+}
+
+impl PubSubNotifier {
+    /// Notifies storage subscribers based on miniblock write sets published by the state keeper if `write_sets`
+    /// are provided. Falls back to polling Postgres if there are no write sets (e.g., if the state keeper
+    /// runs in another process) or once the write sets feed is closed.
+    async fn notify_storage(
+        self,
+        storage_filters: Arc<Mutex<StorageFilters>>,
+        write_sets: Option<broadcast::Receiver<Arc<MiniblockWriteSet>>>,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut last_block_number = self.get_starting_miniblock_number().await?;
+        if let Some(write_sets) = write_sets {
+            last_block_number = self
+                .notify_storage_from_write_sets(
+                    last_block_number,
+                    &storage_filters,
+                    write_sets,
+                    stop_receiver.clone(),
+                )
+                .await?;
+        }
+
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, pubsub_storage_notifier is shutting down");
+                break;
+            }
+            timer.tick().await;
+
+            let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Storage].start();
+            let new_changes = self
+                .new_storage_changes(last_block_number, &storage_filters)
+                .await?;
+            db_latency.observe();
+
+            if let Some((new_changes, new_last_block_number)) = new_changes {
+                last_block_number = new_last_block_number;
+                self.send_storage_changes(new_changes, last_block_number);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::Storage,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Processes write sets until the stop signal is received or the write sets feed is closed.
+    /// Returns the last processed miniblock number.
+    async fn notify_storage_from_write_sets(
+        &self,
+        mut last_block_number: MiniblockNumber,
+        storage_filters: &Mutex<StorageFilters>,
+        mut write_sets: broadcast::Receiver<Arc<MiniblockWriteSet>>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<MiniblockNumber> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            // Unlike with polling, an iteration is finished once the notifier is ready to process the next write set.
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::Storage,
+            ));
+            let write_set = tokio::select! {
+                write_set = write_sets.recv() => write_set,
+                _ = stop_receiver.changed() => break,
+            };
+            let write_set = match write_set {
+                Ok(write_set) => write_set,
+                Err(broadcast::error::RecvError::Lagged(message_count)) => {
+                    // Skipped miniblocks will be loaded from Postgres once the next write set is received.
+                    tracing::warn!(
+                        "pubsub_storage_notifier lagged behind by {message_count} miniblock write sets"
+                    );
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    tracing::info!(
+                        "Miniblock write sets feed is closed; pubsub_storage_notifier falls back to polling Postgres"
+                    );
+                    break;
+                }
+            };
+            if write_set.number <= last_block_number {
+                // The miniblock was sealed before the notifier has started.
+                continue;
+            }
+
+            if write_set.number > last_block_number + 1 {
+                // Some write sets were skipped; load changes in the corresponding miniblocks from Postgres.
+                // Write sets are published after miniblocks are persisted, so the data is guaranteed to be there.
+                let to_block = write_set.number - 1;
+                let mut storage = self
+                    .connection_pool
+                    .access_storage_tagged("api")
+                    .await
+                    .context("access_storage_tagged")?;
+                let slots = storage_filters.lock().unwrap().slots();
+                let changes =
+                    load_storage_changes(&mut storage, &slots, last_block_number, to_block).await?;
+                drop(storage);
+                last_block_number = to_block;
+                self.send_storage_changes(changes, last_block_number);
+            }
+
+            // Filters must be read *after* the write set is received; see `Self::new_logs()` for details.
+            let slots = storage_filters.lock().unwrap().slots();
+            let changes = storage_changes_from_write_set(&slots, &write_set);
+            last_block_number = write_set.number;
+            self.send_storage_changes(changes, last_block_number);
+        }
+        Ok(last_block_number)
+    }
+
+    fn send_storage_changes(
+        &self,
+        changes: Vec<StorageChange>,
+        last_block_number: MiniblockNumber,
+    ) {
+        let batch = PubSubBatch {
+            items: changes
+                .into_iter()
+                .map(PubSubResult::StorageChange)
+                .collect(),
+            last_miniblock: Some(last_block_number),
+        };
+        self.send_pub_sub_results(batch, SubscriptionType::Storage);
+        self.emit_event(PubSubEvent::MiniblockAdvanced(
+            SubscriptionType::Storage,
+            last_block_number,
+        ));
+    }
+
+    /// Loads changes of watched storage slots from write sets of miniblocks sealed after `last_block_number`.
+    /// Writes that don't change the slot value are skipped. Returns `None` if there are no new miniblocks.
+    async fn new_storage_changes(
+        &self,
+        last_block_number: MiniblockNumber,
+        storage_filters: &Mutex<StorageFilters>,
+    ) -> anyhow::Result<Option<(Vec<StorageChange>, MiniblockNumber)>> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let sealed_miniblock_number = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
             .await
-            .context("events_web3_dal().get_all_logs()")
+            .context("get_sealed_miniblock_number()")?;
+        let Some(sealed_miniblock_number) = sealed_miniblock_number else {
+            return Ok(None);
+        };
+        if sealed_miniblock_number <= last_block_number {
+            return Ok(None);
+        }
+
+        // Filters must be read *after* the sealed miniblock number; see `Self::new_logs()` for details.
+        let slots = storage_filters.lock().unwrap().slots();
+        let changes = load_storage_changes(
+            &mut storage,
+            &slots,
+            last_block_number,
+            sealed_miniblock_number,
+        )
+        .await?;
+        Ok(Some((changes, sealed_miniblock_number)))
     }
 }
 
+async fn load_block_headers(
+    storage: &mut StorageProcessor<'_>,
+    last_block_number: MiniblockNumber,
+) -> anyhow::Result<Vec<BlockHeader>> {
+    storage
+        .blocks_web3_dal()
+        .get_block_headers_after(last_block_number)
+        .await
+        .with_context(|| format!("get_block_headers_after({last_block_number})"))
+}
+
+/// Loads logs emitted by the specified `addresses` (or by any address if `addresses` are empty)
+/// in miniblocks `last_block_number + 1..=to_block`.
+async fn load_logs(
+    storage: &mut StorageProcessor<'_>,
+    addresses: Vec<Address>,
+    last_block_number: MiniblockNumber,
+    to_block: MiniblockNumber,
+) -> anyhow::Result<Vec<Log>> {
+    let filter = GetLogsFilter {
+        from_block: last_block_number + 1,
+        to_block,
+        addresses,
+        topics: vec![],
+    };
+    storage
+        .events_web3_dal()
+        .get_logs(filter, i32::MAX as usize)
+        .await
+        .context("events_web3_dal().get_logs()")
+}
+
+/// Loads changes of the specified storage `slots` (keyed by the hashed storage key) in miniblocks
+/// `last_block_number + 1..=to_block`.
+async fn load_storage_changes(
+    storage: &mut StorageProcessor<'_>,
+    slots: &HashMap<H256, StorageSlot>,
+    last_block_number: MiniblockNumber,
+    to_block: MiniblockNumber,
+) -> anyhow::Result<Vec<StorageChange>> {
+    if slots.is_empty() {
+        return Ok(vec![]);
+    }
+    let hashed_keys: Vec<_> = slots.keys().copied().collect();
+    let mut values = storage
+        .storage_logs_dal()
+        .get_storage_values(&hashed_keys, last_block_number)
+        .await
+        .context("get_storage_values()")?;
+    let writes = storage
+        .storage_web3_dal()
+        .get_storage_writes_in_miniblocks(last_block_number + 1..=to_block, &hashed_keys)
+        .await
+        .context("get_storage_writes_in_miniblocks()")?;
+
+    let mut changes = vec![];
+    for (miniblock_number, hashed_key, value) in writes {
+        let prev_value = values.insert(hashed_key, Some(value)).flatten();
+        if prev_value.unwrap_or_default() == value {
+            continue;
+        }
+        let slot = slots[&hashed_key];
+        changes.push(StorageChange {
+            address: slot.address,
+            slot: slot.slot,
+            value,
+            block_number: U64::from(miniblock_number.0),
+        });
+    }
+    Ok(changes)
+}
+
+/// Extracts changes of the specified storage `slots` (keyed by the hashed storage key) from a miniblock `write_set`.
+fn storage_changes_from_write_set(
+    slots: &HashMap<H256, StorageSlot>,
+    write_set: &MiniblockWriteSet,
+) -> Vec<StorageChange> {
+    slots
+        .values()
+        .filter_map(|slot| {
+            let key = StorageKey::new(AccountTreeId::new(slot.address), slot.slot);
+            let value = *write_set.writes.get(&key)?;
+            Some(StorageChange {
+                address: slot.address,
+                slot: slot.slot,
+                value,
+                block_number: U64::from(write_set.number.0),
+            })
+        })
+        .collect()
+}
+
 /// Subscription support for Web3 APIs.
+#[derive(Clone)]
 pub(super) struct EthSubscribe {
-    blocks: broadcast::Sender<Vec<PubSubResult>>,
-    transactions: broadcast::Sender<Vec<PubSubResult>>,
-    logs: broadcast::Sender<Vec<PubSubResult>>,
+    blocks: broadcast::Sender<PubSubBatch>,
+    transactions: broadcast::Sender<PubSubBatch>,
+    logs: broadcast::Sender<PubSubBatch>,
+    storage_changes: broadcast::Sender<PubSubBatch>,
+    log_filters: Arc<Mutex<LogFilters>>,
+    storage_filters: Arc<Mutex<StorageFilters>>,
+    storage_write_sets: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
+    mempool_feed: MempoolFeedConfig,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    handoff: Option<SubscriptionHandoff>,
 }
 
 impl EthSubscribe {
-    pub fn new() -> Self {
+    pub fn new(mempool_feed: MempoolFeedConfig) -> Self {
         let (blocks, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (logs, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (storage_changes, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
 
         Self {
             blocks,
             transactions,
             logs,
+            storage_changes,
+            log_filters: Arc::default(),
+            storage_filters: Arc::default(),
+            storage_write_sets: None,
+            mempool_feed,
             events_sender: None,
+            handoff: None,
         }
     }
 
@@ -241,6 +804,48 @@ impl EthSubscribe {
         self.events_sender = Some(sender);
     }
 
+    /// Makes storage subscriptions use miniblock write sets published by the state keeper.
+    pub fn set_storage_write_sets(&mut self, sender: broadcast::Sender<Arc<MiniblockWriteSet>>) {
+        self.storage_write_sets = Some(sender);
+    }
+
+    /// Enables tracking active subscriptions so that they can be exported on shutdown
+    /// and resumed on another replica within the specified `ttl`.
+    pub fn set_handoff(&mut self, connection_pool: ConnectionPool, ttl: Duration) {
+        self.handoff = Some(SubscriptionHandoff {
+            connection_pool,
+            ttl,
+            active_subscriptions: ActiveSubscriptions::default(),
+        });
+    }
+
+    pub fn has_handoff(&self) -> bool {
+        self.handoff.is_some()
+    }
+
+    /// Starts tracking a subscription for export if subscription handoff is enabled.
+    fn track(
+        &self,
+        sink: &SubscriptionSink,
+        params: ExportedSubscriptionParams,
+        last_miniblock: Option<MiniblockNumber>,
+    ) -> Option<SubscriptionCursor> {
+        let handoff = self.handoff.as_ref()?;
+        let id = subscription_id_to_string(sink.subscription_id());
+        Some(SubscriptionCursor::new(
+            &handoff.active_subscriptions,
+            id,
+            params,
+            last_miniblock,
+        ))
+    }
+
+    fn emit_subscribed(&self, sub_type: SubscriptionType) {
+        if let Some(sender) = &self.events_sender {
+            sender.send(PubSubEvent::Subscribed(sub_type)).ok();
+        }
+    }
+
     async fn reject(sink: PendingSubscriptionSink) {
         sink.reject(ErrorObject::borrowed(
             ErrorCode::InvalidParams.code(),
@@ -250,11 +855,16 @@ impl EthSubscribe {
         .await;
     }
 
+    /// Forwards notifications from `receiver` to the subscriber. Notifications for miniblocks up to and including
+    /// `replay_until` are skipped since they were already sent when resuming an exported subscription.
     async fn run_subscriber(
         sink: SubscriptionSink,
         subscription_type: SubscriptionType,
-        mut receiver: broadcast::Receiver<Vec<PubSubResult>>,
-        filter: Option<PubSubFilter>,
+        mut receiver: broadcast::Receiver<PubSubBatch>,
+        filter: Option<SubscriberFilter>,
+        rate_limiter: Option<SubscriberRateLimiter>,
+        cursor: Option<SubscriptionCursor>,
+        replay_until: Option<MiniblockNumber>,
     ) {
         let _guard = PUB_SUB_METRICS.active_subscribers[&subscription_type].inc_guard(1);
         let lifetime_latency = PUB_SUB_METRICS.subscriber_lifetime[&subscription_type].start();
@@ -264,8 +874,8 @@ impl EthSubscribe {
         loop {
             tokio::select! {
                 new_items_result = receiver.recv() => {
-                    let new_items = match new_items_result {
-                        Ok(items) => items,
+                    let mut batch = match new_items_result {
+                        Ok(batch) => batch,
                         Err(broadcast::error::RecvError::Closed) => {
                             // The broadcast channel has closed because the notifier task is shut down.
                             // This is fine; we should just stop this task.
@@ -279,16 +889,27 @@ impl EthSubscribe {
                         }
                     };
 
-                    let handle_result = Self::handle_new_items(
-                        &sink,
-                        subscription_type,
-                        new_items,
-                        filter.as_ref()
-                    )
-                    .await;
-                    if handle_result.is_err() {
-                        PUB_SUB_METRICS.subscriber_send_timeouts[&subscription_type].inc();
-                        break;
+                    if let Some(replay_until) = replay_until {
+                        batch.items.retain(|item| {
+                            PubSubBatch::item_miniblock(item).map_or(true, |number| number > replay_until)
+                        });
+                    }
+                    if !batch.items.is_empty() {
+                        let handle_result = Self::handle_new_items(
+                            &sink,
+                            subscription_type,
+                            batch.items,
+                            filter.as_ref(),
+                            rate_limiter.as_ref(),
+                        )
+                        .await;
+                        if handle_result.is_err() {
+                            PUB_SUB_METRICS.subscriber_send_timeouts[&subscription_type].inc();
+                            break;
+                        }
+                    }
+                    if let (Some(cursor), Some(number)) = (&cursor, batch.last_miniblock) {
+                        cursor.advance(number);
                     }
                 }
                 _ = &mut closed => {
@@ -303,15 +924,20 @@ impl EthSubscribe {
         sink: &SubscriptionSink,
         subscription_type: SubscriptionType,
         new_items: Vec<PubSubResult>,
-        filter: Option<&PubSubFilter>,
+        filter: Option<&SubscriberFilter>,
+        rate_limiter: Option<&SubscriberRateLimiter>,
     ) -> Result<(), SendTimeoutError> {
         let notify_latency = PUB_SUB_METRICS.notify_subscribers_latency[&subscription_type].start();
         for item in new_items {
-            if let PubSubResult::Log(log) = &item {
-                if let Some(filter) = &filter {
-                    if !filter.matches(log) {
-                        continue;
-                    }
+            if let Some(filter) = filter {
+                if !filter.matches(&item) {
+                    continue;
+                }
+            }
+            if let Some(rate_limiter) = rate_limiter {
+                if rate_limiter.check().is_err() {
+                    PUB_SUB_METRICS.rate_limited_notifications[&subscription_type].inc();
+                    continue;
                 }
             }
 
@@ -342,25 +968,47 @@ impl EthSubscribe {
                     return;
                 };
                 let blocks_rx = self.blocks.subscribe();
+                let cursor = self.track(&sink, ExportedSubscriptionParams::NewHeads, None);
                 tokio::spawn(Self::run_subscriber(
                     sink,
                     SubscriptionType::Blocks,
                     blocks_rx,
                     None,
+                    None,
+                    cursor,
+                    None,
                 ));
 
                 Some(SubscriptionType::Blocks)
             }
+            "newPendingTransactions" if self.mempool_feed.is_private() => {
+                pending_sink
+                    .reject(ErrorObject::borrowed(
+                        ErrorCode::MethodNotFound.code(),
+                        "Rejecting subscription - pending transactions are private.",
+                        None,
+                    ))
+                    .await;
+                None
+            }
             "newPendingTransactions" => {
                 let Ok(sink) = pending_sink.accept().await else {
                     return;
                 };
                 let transactions_rx = self.transactions.subscribe();
+                let cursor = self.track(
+                    &sink,
+                    ExportedSubscriptionParams::NewPendingTransactions,
+                    None,
+                );
                 tokio::spawn(Self::run_subscriber(
                     sink,
                     SubscriptionType::Txs,
                     transactions_rx,
                     None,
+                    self.txs_rate_limiter(),
+                    cursor,
+                    None,
                 ));
                 Some(SubscriptionType::Txs)
             }
@@ -376,11 +1024,19 @@ impl EthSubscribe {
                         return;
                     };
                     let logs_rx = self.logs.subscribe();
+                    let filter_guard = FilterGuard::new(&self.log_filters, filter.clone());
+                    let params = ExportedSubscriptionParams::Logs {
+                        filter: filter.clone(),
+                    };
+                    let cursor = self.track(&sink, params, None);
                     tokio::spawn(Self::run_subscriber(
                         sink,
                         SubscriptionType::Logs,
                         logs_rx,
-                        Some(filter),
+                        Some(SubscriberFilter::Logs(filter, filter_guard)),
+                        None,
+                        cursor,
+                        None,
                     ));
                     Some(SubscriptionType::Logs)
                 }
@@ -406,12 +1062,300 @@ impl EthSubscribe {
         };
 
         if let Some(sub_type) = sub_type {
-            if let Some(sender) = &self.events_sender {
-                sender.send(PubSubEvent::Subscribed(sub_type)).ok();
+            self.emit_subscribed(sub_type);
+        }
+    }
+
+    fn txs_rate_limiter(&self) -> Option<SubscriberRateLimiter> {
+        self.mempool_feed
+            .rate_limit
+            .map(|limit| RateLimiter::direct(Quota::per_second(limit)))
+    }
+
+    #[tracing::instrument(skip(self, pending_sink))]
+    pub async fn sub_storage(
+        &self,
+        pending_sink: PendingSubscriptionSink,
+        slots: Vec<StorageSlot>,
+    ) {
+        let slots: HashSet<_> = slots.into_iter().collect();
+        if slots.is_empty() || slots.len() > STORAGE_SUBSCRIPTION_SLOTS_LIMIT {
+            Self::reject(pending_sink).await;
+            return;
+        }
+        let Ok(sink) = pending_sink.accept().await else {
+            return;
+        };
+        let storage_rx = self.storage_changes.subscribe();
+        let filter_guard = FilterGuard::new(&self.storage_filters, slots.clone());
+        let params = ExportedSubscriptionParams::Storage {
+            slots: slots.iter().copied().collect(),
+        };
+        let cursor = self.track(&sink, params, None);
+        tokio::spawn(Self::run_subscriber(
+            sink,
+            SubscriptionType::Storage,
+            storage_rx,
+            Some(SubscriberFilter::Storage(slots, filter_guard)),
+            None,
+            cursor,
+            None,
+        ));
+        self.emit_subscribed(SubscriptionType::Storage);
+    }
+
+    /// Exports all active subscriptions to Postgres so that they can be resumed on another replica. Also prunes
+    /// exported subscriptions older than the handoff TTL. No-op if subscription handoff is disabled.
+    pub async fn export_subscriptions(&self) -> anyhow::Result<()> {
+        let Some(handoff) = &self.handoff else {
+            return Ok(());
+        };
+        let subscriptions: Vec<_> = handoff
+            .active_subscriptions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, subscription)| ExportedSubscription {
+                id: id.clone(),
+                params: serde_json::to_value(&subscription.params)
+                    .expect("subscription params are always serializable"),
+                last_miniblock: subscription.last_miniblock,
+            })
+            .collect();
+
+        let mut storage = handoff
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        storage
+            .exported_subscriptions_dal()
+            .insert_subscriptions(&subscriptions)
+            .await
+            .context("insert_subscriptions()")?;
+        let pruned_count = storage
+            .exported_subscriptions_dal()
+            .prune_subscriptions(handoff.ttl)
+            .await
+            .context("prune_subscriptions()")?;
+        tracing::info!(
+            "Exported {} subscriptions; pruned {pruned_count} expired exported subscriptions",
+            subscriptions.len()
+        );
+        Ok(())
+    }
+
+    /// Resumes a subscription exported by another replica, replaying notifications for miniblocks sealed
+    /// since the subscription was exported.
+    #[tracing::instrument(skip(self, pending_sink))]
+    pub async fn resume(&self, pending_sink: PendingSubscriptionSink, subscription_id: String) {
+        let Some(handoff) = &self.handoff else {
+            pending_sink
+                .reject(ErrorObject::borrowed(
+                    ErrorCode::MethodNotFound.code(),
+                    "Rejecting subscription - subscription handoff is disabled.",
+                    None,
+                ))
+                .await;
+            return;
+        };
+
+        match self
+            .resume_inner(handoff, pending_sink, &subscription_id)
+            .await
+        {
+            Ok(Some(sub_type)) => self.emit_subscribed(sub_type),
+            Ok(None) => { /* Subscription was rejected or the client has disconnected */ }
+            Err(err) => {
+                tracing::warn!("Failed resuming subscription {subscription_id}: {err:#}");
             }
         }
     }
 
+    async fn resume_inner(
+        &self,
+        handoff: &SubscriptionHandoff,
+        pending_sink: PendingSubscriptionSink,
+        subscription_id: &str,
+    ) -> anyhow::Result<Option<SubscriptionType>> {
+        let mut storage = handoff
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let exported = storage
+            .exported_subscriptions_dal()
+            .take_subscription(subscription_id, handoff.ttl)
+            .await
+            .context("take_subscription()")?;
+        let params = exported.and_then(|exported| {
+            let params = serde_json::from_value(exported.params).ok()?;
+            Some((params, exported.last_miniblock))
+        });
+        let Some((params, last_miniblock)) = params else {
+            pending_sink
+                .reject(ErrorObject::borrowed(
+                    ErrorCode::InvalidParams.code(),
+                    "Rejecting subscription - unknown or expired subscription ID.",
+                    None,
+                ))
+                .await;
+            return Ok(None);
+        };
+
+        let (sub_type, receiver, filter, rate_limiter) = match &params {
+            ExportedSubscriptionParams::NewHeads => (
+                SubscriptionType::Blocks,
+                self.blocks.subscribe(),
+                None,
+                None,
+            ),
+            ExportedSubscriptionParams::NewPendingTransactions
+                if self.mempool_feed.is_private() =>
+            {
+                Self::reject(pending_sink).await;
+                return Ok(None);
+            }
+            ExportedSubscriptionParams::NewPendingTransactions => (
+                SubscriptionType::Txs,
+                self.transactions.subscribe(),
+                None,
+                self.txs_rate_limiter(),
+            ),
+            ExportedSubscriptionParams::Logs { filter } => {
+                let filter_guard = FilterGuard::new(&self.log_filters, filter.clone());
+                let filter = SubscriberFilter::Logs(filter.clone(), filter_guard);
+                (
+                    SubscriptionType::Logs,
+                    self.logs.subscribe(),
+                    Some(filter),
+                    None,
+                )
+            }
+            ExportedSubscriptionParams::Storage { slots } => {
+                let slots: HashSet<_> = slots.iter().copied().collect();
+                let filter_guard = FilterGuard::new(&self.storage_filters, slots.clone());
+                let filter = SubscriberFilter::Storage(slots, filter_guard);
+                (
+                    SubscriptionType::Storage,
+                    self.storage_changes.subscribe(),
+                    Some(filter),
+                    None,
+                )
+            }
+        };
+
+        // The sealed miniblock number must be read *after* subscribing to notifications, so that all miniblocks
+        // after it are guaranteed to be broadcast to the subscriber.
+        let (replayed_items, replay_until) = match last_miniblock {
+            Some(last_miniblock) => {
+                let sealed_miniblock_number = storage
+                    .blocks_dal()
+                    .get_sealed_miniblock_number()
+                    .await
+                    .context("get_sealed_miniblock_number()")?
+                    .unwrap_or(last_miniblock);
+                let lag = sealed_miniblock_number.0.saturating_sub(last_miniblock.0);
+                if lag > MAX_REPLAYED_MINIBLOCKS {
+                    pending_sink
+                        .reject(ErrorObject::owned(
+                            ErrorCode::InvalidParams.code(),
+                            format!(
+                                "Rejecting subscription - subscription is {lag} miniblocks behind; \
+                                 at most {MAX_REPLAYED_MINIBLOCKS} miniblocks can be replayed"
+                            ),
+                            None::<()>,
+                        ))
+                        .await;
+                    return Ok(None);
+                }
+                let items = Self::load_replayed_items(
+                    &mut storage,
+                    &params,
+                    last_miniblock,
+                    sealed_miniblock_number,
+                )
+                .await?;
+                (items, Some(sealed_miniblock_number.max(last_miniblock)))
+            }
+            None => (vec![], None),
+        };
+        drop(storage);
+
+        let Ok(sink) = pending_sink.accept().await else {
+            return Ok(None);
+        };
+        let cursor = self.track(&sink, params, replay_until.or(last_miniblock));
+        tokio::spawn(async move {
+            if !replayed_items.is_empty() {
+                let replay_result =
+                    Self::handle_new_items(&sink, sub_type, replayed_items, filter.as_ref(), None)
+                        .await;
+                if replay_result.is_err() {
+                    PUB_SUB_METRICS.subscriber_send_timeouts[&sub_type].inc();
+                    return;
+                }
+            }
+            Self::run_subscriber(
+                sink,
+                sub_type,
+                receiver,
+                filter,
+                rate_limiter,
+                cursor,
+                replay_until,
+            )
+            .await;
+        });
+        Ok(Some(sub_type))
+    }
+
+    /// Loads notifications for a resumed subscription in miniblocks `last_miniblock + 1..=to_block`.
+    async fn load_replayed_items(
+        storage: &mut StorageProcessor<'_>,
+        params: &ExportedSubscriptionParams,
+        last_miniblock: MiniblockNumber,
+        to_block: MiniblockNumber,
+    ) -> anyhow::Result<Vec<PubSubResult>> {
+        if to_block <= last_miniblock {
+            return Ok(vec![]);
+        }
+        Ok(match params {
+            ExportedSubscriptionParams::NewHeads => {
+                let headers = load_block_headers(storage, last_miniblock).await?;
+                headers
+                    .into_iter()
+                    .map(PubSubResult::Header)
+                    .filter(|item| {
+                        PubSubBatch::item_miniblock(item).map_or(false, |number| number <= to_block)
+                    })
+                    .collect()
+            }
+            // Pending transactions are not replayed.
+            ExportedSubscriptionParams::NewPendingTransactions => vec![],
+            ExportedSubscriptionParams::Logs { filter } => {
+                let addresses = filter.addresses().map(<[_]>::to_vec).unwrap_or_default();
+                let logs = load_logs(storage, addresses, last_miniblock, to_block).await?;
+                logs.into_iter().map(PubSubResult::Log).collect()
+            }
+            ExportedSubscriptionParams::Storage { slots } => {
+                let slots = slots
+                    .iter()
+                    .map(|slot| {
+                        let key = StorageKey::new(AccountTreeId::new(slot.address), slot.slot);
+                        (key.hashed_key(), *slot)
+                    })
+                    .collect();
+                let changes =
+                    load_storage_changes(storage, &slots, last_miniblock, to_block).await?;
+                changes
+                    .into_iter()
+                    .map(PubSubResult::StorageChange)
+                    .collect()
+            }
+        })
+    }
+
     /// Spawns notifier tasks. This should be called once per instance.
     pub fn spawn_notifiers(
         &self,
@@ -419,7 +1363,7 @@ impl EthSubscribe {
         polling_interval: Duration,
         stop_receiver: watch::Receiver<bool>,
     ) -> Vec<JoinHandle<anyhow::Result<()>>> {
-        let mut notifier_tasks = Vec::with_capacity(3);
+        let mut notifier_tasks = Vec::with_capacity(4);
 
         let notifier = PubSubNotifier {
             sender: self.blocks.clone(),
@@ -430,23 +1374,45 @@ impl EthSubscribe {
         let notifier_task = tokio::spawn(notifier.notify_blocks(stop_receiver.clone()));
         notifier_tasks.push(notifier_task);
 
+        // Pending transactions are never published in the private mode, so there's no need to poll them.
+        if !self.mempool_feed.is_private() {
+            let notifier = PubSubNotifier {
+                sender: self.transactions.clone(),
+                connection_pool: connection_pool.clone(),
+                polling_interval,
+                events_sender: self.events_sender.clone(),
+            };
+            let notifier_task =
+                tokio::spawn(notifier.notify_txs(self.mempool_feed, stop_receiver.clone()));
+            notifier_tasks.push(notifier_task);
+        }
+
         let notifier = PubSubNotifier {
-            sender: self.transactions.clone(),
+            sender: self.logs.clone(),
             connection_pool: connection_pool.clone(),
             polling_interval,
             events_sender: self.events_sender.clone(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_txs(stop_receiver.clone()));
+        let notifier_task =
+            tokio::spawn(notifier.notify_logs(self.log_filters.clone(), stop_receiver.clone()));
         notifier_tasks.push(notifier_task);
 
         let notifier = PubSubNotifier {
-            sender: self.logs.clone(),
+            sender: self.storage_changes.clone(),
             connection_pool,
             polling_interval,
             events_sender: self.events_sender.clone(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver));
-
+        // Subscribe to write sets before the notifier determines its starting miniblock, so that no write sets are missed.
+        let write_sets = self
+            .storage_write_sets
+            .as_ref()
+            .map(broadcast::Sender::subscribe);
+        let notifier_task = tokio::spawn(notifier.notify_storage(
+            self.storage_filters.clone(),
+            write_sets,
+            stop_receiver,
+        ));
         notifier_tasks.push(notifier_task);
         notifier_tasks
     }
@@ -464,3 +1430,24 @@ impl EthPubSubServer for EthSubscribe {
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl ZksPubSubServer for EthSubscribe {
+    async fn subscribe_storage(
+        &self,
+        pending: PendingSubscriptionSink,
+        slots: Vec<StorageSlot>,
+    ) -> SubscriptionResult {
+        self.sub_storage(pending, slots).await;
+        Ok(())
+    }
+
+    async fn resume_subscription(
+        &self,
+        pending: PendingSubscriptionSink,
+        subscription_id: String,
+    ) -> SubscriptionResult {
+        self.resume(pending, subscription_id).await;
+        Ok(())
+    }
+}
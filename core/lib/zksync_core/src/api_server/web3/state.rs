@@ -1,5 +1,8 @@
 use std::{
+    collections::{hash_map, HashMap},
+    fmt,
     future::Future,
+    num::NonZeroU32,
     sync::{
         atomic::{AtomicU32, Ordering},
         Arc,
@@ -7,25 +10,43 @@ use std::{
     time::{Duration, Instant},
 };
 
+use anyhow::Context as _;
 use lru::LruCache;
 use tokio::sync::{watch, Mutex};
 use vise::GaugeGuard;
-use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
-use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_config::configs::{
+    api::{MempoolFeedMode, Web3JsonRpcConfig},
+    chain::NetworkConfig,
+    ContractsConfig,
+};
+use zksync_dal::{
+    logs_query_planner::EventsStatistics, ConnectionPool, SqlxError, StorageProcessor,
+};
+use zksync_object_store::ObjectStore;
 use zksync_types::{
-    api, l2::L2Tx, transaction_request::CallRequest, Address, L1BatchNumber, L1ChainId, L2ChainId,
-    MiniblockNumber, H256, U256, U64,
+    api::{self, GetLogsFilter, LogsQueryStrategy, SerializationTransactionError},
+    cold_storage::L1BatchColdArchive,
+    ethabi,
+    l2::L2Tx,
+    pubdata_da::PubdataDA,
+    transaction_request::CallRequest,
+    Address, L1BatchNumber, L1ChainId, L2ChainId, MiniblockNumber, PackedEthSignature, H256, U256,
+    U64,
 };
 use zksync_web3_decl::{error::Web3Error, types::Filter};
 
-use super::metrics::{FilterType, FILTER_METRICS};
+use super::metrics::{FilterType, API_METRICS, FILTER_METRICS};
 use crate::{
     api_server::{
         execution_sandbox::{BlockArgs, BlockArgsError, BlockStartInfo},
         tree::TreeApiHttpClient,
         tx_sender::TxSender,
-        web3::{backend_jsonrpsee::internal_error, TypedFilter},
+        web3::{
+            backend_jsonrpsee::internal_error, custom_errors::CustomErrorsRegistry,
+            namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT, Namespace, TypedFilter,
+        },
     },
+    state_keeper::TxSizeLimits,
     sync_layer::SyncState,
 };
 
@@ -69,6 +90,46 @@ impl BlockStartInfo {
     }
 }
 
+/// Configuration of the pending transactions feed exposed via `newPendingTransactions` subscriptions
+/// and pending transaction filters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MempoolFeedConfig {
+    pub mode: MempoolFeedMode,
+    /// Delay after which pending transactions are published in [`MempoolFeedMode::Delayed`].
+    pub delay: Duration,
+    /// Maximum number of transaction hashes sent to a single subscriber per second.
+    pub rate_limit: Option<NonZeroU32>,
+}
+
+impl MempoolFeedConfig {
+    pub fn new(web3_config: &Web3JsonRpcConfig) -> Self {
+        Self {
+            mode: web3_config.mempool_feed_mode(),
+            delay: web3_config.mempool_feed_delay(),
+            rate_limit: web3_config.mempool_feed_rate_limit,
+        }
+    }
+
+    pub(super) fn is_private(&self) -> bool {
+        self.mode == MempoolFeedMode::Private
+    }
+
+    /// Returns the latest receiving timestamp of transactions that can be published at the moment,
+    /// or `None` if there's no such limit.
+    pub(super) fn published_until(&self) -> Option<chrono::NaiveDateTime> {
+        if self.mode != MempoolFeedMode::Delayed {
+            return None;
+        }
+        let delay = chrono::Duration::from_std(self.delay)
+            .unwrap_or_else(|_| chrono::Duration::max_value());
+        let now = chrono::Utc::now().naive_utc();
+        Some(
+            now.checked_sub_signed(delay)
+                .unwrap_or(chrono::NaiveDateTime::MIN),
+        )
+    }
+}
+
 /// Configuration values for the API.
 /// This structure is detached from `ZkSyncConfig`, since different node types (main, external, etc)
 /// may require different configuration layouts.
@@ -82,11 +143,26 @@ pub struct InternalApiConfig {
     pub estimate_gas_acceptable_overestimation: u32,
     pub bridge_addresses: api::BridgeAddresses,
     pub bridgehub_proxy_addr: Option<Address>,
+    pub l1_shared_bridge_proxy_addr: Option<Address>,
+    pub state_transition_proxy_addr: Option<Address>,
+    pub validator_timelock_addr: Option<Address>,
+    pub governance_addr: Option<Address>,
     pub diamond_proxy_addr: Address,
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub req_entities_limit: usize,
     pub fee_history_limit: u64,
     pub filters_disabled: bool,
+    pub reject_txs_without_chain_id: bool,
+    /// Size limits for L2 transactions reported via `zks_getChainParameters`.
+    pub tx_size_limits: TxSizeLimits,
+    pub fee_model_version: api::FeeModelVersion,
+    pub pubdata_da: PubdataDA,
+    pub batch_seal_limits: api::BatchSealLimits,
+    /// Components run by the node reported via `zks_getNodeInfo`.
+    pub components: Vec<String>,
+    pub mempool_feed: MempoolFeedConfig,
+    /// Whether logs queries use the index strategy chosen based on the `events` table statistics.
+    pub logs_query_planner_enabled: bool,
 }
 
 impl InternalApiConfig {
@@ -108,12 +184,32 @@ impl InternalApiConfig {
                 l1_weth_bridge: contracts_config.l1_weth_bridge_proxy_addr,
                 l2_weth_bridge: contracts_config.l2_weth_bridge_addr,
             },
-            bridgehub_proxy_addr: contracts_config.bridgehub_proxy_addr,
+            // Zero addresses are used as placeholders for contracts that are not deployed yet.
+            bridgehub_proxy_addr: contracts_config
+                .bridgehub_proxy_addr
+                .filter(|addr| !addr.is_zero()),
+            l1_shared_bridge_proxy_addr: contracts_config
+                .l1_shared_bridge_proxy_addr
+                .filter(|addr| !addr.is_zero()),
+            state_transition_proxy_addr: contracts_config
+                .state_transition_proxy_addr
+                .filter(|addr| !addr.is_zero()),
+            validator_timelock_addr: Some(contracts_config.validator_timelock_addr)
+                .filter(|addr| !addr.is_zero()),
+            governance_addr: Some(contracts_config.governance_addr).filter(|addr| !addr.is_zero()),
             diamond_proxy_addr: contracts_config.diamond_proxy_addr,
             l2_testnet_paymaster_addr: contracts_config.l2_testnet_paymaster_addr,
             req_entities_limit: web3_config.req_entities_limit(),
             fee_history_limit: web3_config.fee_history_limit(),
             filters_disabled: web3_config.filters_disabled,
+            reject_txs_without_chain_id: web3_config.reject_txs_without_chain_id,
+            tx_size_limits: TxSizeLimits::default(),
+            fee_model_version: api::FeeModelVersion::default(),
+            pubdata_da: PubdataDA::Calldata,
+            batch_seal_limits: api::BatchSealLimits::default(),
+            components: vec![],
+            mempool_feed: MempoolFeedConfig::new(web3_config),
+            logs_query_planner_enabled: web3_config.logs_query_planner_enabled,
         }
     }
 }
@@ -193,6 +289,73 @@ impl SealedMiniblockNumber {
     }
 }
 
+/// Cached statistics of the `events` table used to plan logs queries. Statistics are refreshed
+/// by Postgres on auto-analyze, so there's no point in loading them on each request.
+#[derive(Debug, Default)]
+pub(crate) struct EventsStatisticsCache(Mutex<Option<(Instant, Option<EventsStatistics>)>>);
+
+impl EventsStatisticsCache {
+    const TTL: Duration = Duration::from_secs(300);
+
+    async fn get(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<Option<EventsStatistics>, SqlxError> {
+        let mut cached = self.0.lock().await;
+        if let Some((loaded_at, stats)) = &*cached {
+            if loaded_at.elapsed() < Self::TTL {
+                return Ok(stats.clone());
+            }
+        }
+        let stats = storage.events_web3_dal().get_events_statistics().await?;
+        *cached = Some((Instant::now(), stats.clone()));
+        Ok(stats)
+    }
+}
+
+/// Signer of responses returned by `zks_getSigned*` methods. The private key is not exposed in the `Debug` output.
+#[derive(Clone)]
+pub struct ResponseSigner {
+    private_key: H256,
+    address: Address,
+}
+
+impl fmt::Debug for ResponseSigner {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ResponseSigner")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResponseSigner {
+    pub fn new(private_key: H256) -> anyhow::Result<Self> {
+        let address = PackedEthSignature::address_from_private_key(&private_key)
+            .context("invalid response signing key")?;
+        Ok(Self {
+            private_key,
+            address,
+        })
+    }
+
+    /// Returns the address of the signer.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub(super) fn sign<T: api::SigningToken>(
+        &self,
+        method: &str,
+        chain_id: L2ChainId,
+        params: &[ethabi::Token],
+        response: T,
+    ) -> anyhow::Result<api::SignedResponse<T>> {
+        api::SignedResponse::sign(method, chain_id, params, response, &self.private_key)
+            .map_err(|err| anyhow::anyhow!("failed signing response: {err}"))
+    }
+}
+
 /// Holder for the data required for the API to be functional.
 #[derive(Debug, Clone)]
 pub struct RpcState {
@@ -206,12 +369,32 @@ pub struct RpcState {
     /// from a snapshot.
     pub(super) start_info: BlockStartInfo,
     pub(super) last_sealed_miniblock: SealedMiniblockNumber,
+    pub(super) custom_errors: Arc<CustomErrorsRegistry>,
+    /// Namespaces enabled on the server.
+    pub(super) namespaces: Vec<Namespace>,
+    pub(super) events_statistics: Arc<EventsStatisticsCache>,
+    /// Signer for `zks_getSigned*` methods; if not set, these methods return an error.
+    pub(super) response_signer: Option<ResponseSigner>,
+    /// Object store with call traces and events moved out of Postgres for old L1 batches. If not set,
+    /// this data is only served from Postgres.
+    pub(super) cold_storage: Option<Arc<dyn ObjectStore>>,
 }
 
 impl RpcState {
     pub fn parse_transaction_bytes(&self, bytes: &[u8]) -> Result<(L2Tx, H256), Web3Error> {
         let chain_id = self.api_config.l2_chain_id;
         let (tx_request, hash) = api::TransactionRequest::from_bytes(bytes, chain_id)?;
+        if tx_request.is_legacy_tx() && tx_request.chain_id.is_none() {
+            let rejected = self.api_config.reject_txs_without_chain_id;
+            let action = if rejected { "rejected" } else { "accepted" };
+            API_METRICS.txs_without_chain_id[&action].inc();
+            tracing::debug!(
+                "Received legacy transaction {hash:?} without EIP-155 chain ID; {action}"
+            );
+            if rejected {
+                return Err(SerializationTransactionError::MissingChainId.into());
+            }
+        }
 
         Ok((
             L2Tx::from_request(tx_request, self.api_config.max_tx_size)?,
@@ -289,6 +472,241 @@ impl RpcState {
         Ok((from_block, to_block))
     }
 
+    /// Converts a logs filter into the DAL representation, resolving its upper block bound.
+    pub async fn get_logs_filter(
+        &self,
+        filter: &Filter,
+        from_block: MiniblockNumber,
+    ) -> Result<GetLogsFilter, Web3Error> {
+        let addresses = if let Some(addresses) = &filter.address {
+            addresses.0.clone()
+        } else {
+            vec![]
+        };
+        let topics = if let Some(topics) = &filter.topics {
+            if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
+                return Err(Web3Error::TooManyTopics);
+            }
+            let topics_by_idx = topics
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, topics)| Some((idx as u32 + 1, topics.as_ref()?.0.clone())));
+            topics_by_idx.collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+
+        let mut to_block = self.resolve_filter_block_number(filter.to_block).await?;
+        if matches!(filter.to_block, Some(api::BlockNumber::Number(_))) {
+            to_block = to_block.min(
+                self.resolve_filter_block_number(Some(api::BlockNumber::Latest))
+                    .await?,
+            );
+        }
+
+        Ok(GetLogsFilter {
+            from_block,
+            to_block,
+            addresses,
+            topics,
+        })
+    }
+
+    /// Chooses the strategy for a logs query based on the `events` table statistics. Returns `None`
+    /// (i.e., leaves the choice to Postgres) if the planner is disabled or statistics are not available.
+    pub async fn plan_logs_query(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        filter: &GetLogsFilter,
+    ) -> Result<Option<LogsQueryStrategy>, SqlxError> {
+        if !self.api_config.logs_query_planner_enabled {
+            return Ok(None);
+        }
+        let stats = self.events_statistics.get(storage).await?;
+        Ok(stats.map(|stats| stats.plan(filter).strategy))
+    }
+
+    /// Loads call traces and events for the specified L1 batch from the cold storage. Returns `None` if the cold storage
+    /// is not configured, or if the data for the batch is not moved to the cold storage.
+    pub(super) async fn load_cold_archive(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<L1BatchColdArchive>> {
+        let Some(cold_storage) = &self.cold_storage else {
+            return Ok(None);
+        };
+        let object_key = storage
+            .cold_storage_dal()
+            .get_archive_key(l1_batch_number)
+            .await?;
+        let Some(object_key) = object_key else {
+            return Ok(None);
+        };
+
+        let latency = API_METRICS.cold_storage_load_latency.start();
+        let archive = cold_storage
+            .get(l1_batch_number)
+            .await
+            .with_context(|| format!("failed loading cold storage archive `{object_key}`"))?;
+        latency.observe();
+        Ok(Some(archive))
+    }
+
+    /// Fills logs of receipts for transactions whose events were moved to the cold storage. Receipts are loaded
+    /// from Postgres, so logs of such transactions are missing.
+    pub(super) async fn fill_cold_receipt_logs(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        receipts: &mut [api::TransactionReceipt],
+    ) -> anyhow::Result<()> {
+        if self.cold_storage.is_none() {
+            return Ok(());
+        }
+        let Some(last_archived_l1_batch) = storage
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let mut archives = HashMap::new();
+        for receipt in receipts {
+            let Some(l1_batch_number) = receipt.l1_batch_number else {
+                continue;
+            };
+            let l1_batch_number = L1BatchNumber(l1_batch_number.as_u32());
+            if !receipt.logs.is_empty() || l1_batch_number > last_archived_l1_batch {
+                continue;
+            }
+
+            let archive = match archives.entry(l1_batch_number) {
+                hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                hash_map::Entry::Vacant(entry) => {
+                    let archive = self.load_cold_archive(storage, l1_batch_number).await?;
+                    entry.insert(archive)
+                }
+            };
+            let Some(archive) = archive else {
+                continue;
+            };
+            let tx_hash = Some(receipt.transaction_hash);
+            receipt.logs = archive
+                .events
+                .iter()
+                .filter(|log| log.transaction_hash == tx_hash)
+                .map(|log| api::Log {
+                    block_hash: Some(receipt.block_hash),
+                    l1_batch_number: receipt.l1_batch_number,
+                    ..log.clone()
+                })
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// Returns logs matching the filter from L1 batches moved to the cold storage, together with the last miniblock
+    /// covered by the loaded archives. Returns `None` if the filter doesn't cover any archived miniblocks.
+    pub(super) async fn get_cold_logs(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        filter: &GetLogsFilter,
+    ) -> Result<Option<(Vec<api::Log>, MiniblockNumber)>, Web3Error> {
+        const METHOD_NAME: &str = "get_cold_logs";
+        /// Maximum number of archives loaded to serve a single logs request.
+        const MAX_ARCHIVES_PER_REQUEST: u32 = 10;
+
+        if self.cold_storage.is_none() {
+            return Ok(None);
+        }
+        let last_archived_l1_batch = storage
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(last_archived_l1_batch) = last_archived_l1_batch else {
+            return Ok(None);
+        };
+        let miniblock_range = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_archived_l1_batch)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some((_, last_archived_miniblock)) = miniblock_range else {
+            return Ok(None);
+        };
+        if filter.from_block > last_archived_miniblock {
+            return Ok(None);
+        }
+
+        let to_block = filter.to_block.min(last_archived_miniblock);
+        let mut l1_batch_numbers = [L1BatchNumber(0); 2];
+        for (l1_batch_number, miniblock) in l1_batch_numbers
+            .iter_mut()
+            .zip([filter.from_block, to_block])
+        {
+            *l1_batch_number = storage
+                .blocks_web3_dal()
+                .get_l1_batch_number_of_miniblock(miniblock)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+                .ok_or_else(|| {
+                    internal_error(
+                        METHOD_NAME,
+                        format!("no L1 batch for archived miniblock #{miniblock}"),
+                    )
+                })?;
+        }
+        let [first_l1_batch, last_l1_batch] = l1_batch_numbers;
+        if last_l1_batch.0 - first_l1_batch.0 >= MAX_ARCHIVES_PER_REQUEST {
+            let last_allowed_l1_batch = first_l1_batch + MAX_ARCHIVES_PER_REQUEST - 1;
+            let (_, last_allowed_miniblock) = storage
+                .blocks_dal()
+                .get_miniblock_range_of_l1_batch(last_allowed_l1_batch)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+                .ok_or_else(|| {
+                    internal_error(
+                        METHOD_NAME,
+                        format!("no miniblocks for L1 batch #{last_allowed_l1_batch}"),
+                    )
+                })?;
+            return Err(Web3Error::LogsLimitExceeded(
+                self.api_config.req_entities_limit,
+                filter.from_block.0,
+                last_allowed_miniblock.0,
+            ));
+        }
+
+        let filter = GetLogsFilter {
+            to_block,
+            ..filter.clone()
+        };
+        let mut logs = vec![];
+        for l1_batch_number in first_l1_batch.0..=last_l1_batch.0 {
+            let archive = self
+                .load_cold_archive(storage, L1BatchNumber(l1_batch_number))
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            // The archive may be missing, e.g. for L1 batches not present locally after snapshot recovery.
+            if let Some(archive) = archive {
+                logs.extend(archive.logs(&filter).cloned());
+            }
+        }
+
+        let limit = self.api_config.req_entities_limit;
+        if filter.from_block != filter.to_block && logs.len() > limit {
+            let block_number = logs[limit].block_number.map_or(0, |number| number.as_u32());
+            return Err(Web3Error::LogsLimitExceeded(
+                limit,
+                filter.from_block.0,
+                block_number.saturating_sub(1),
+            ));
+        }
+        Ok(Some((logs, to_block)))
+    }
+
     /// If filter has `block_hash` then it resolves block number by hash and sets it to `from_block` and `to_block`.
     pub async fn resolve_filter_block_hash(&self, filter: &mut Filter) -> Result<(), Web3Error> {
         match (filter.block_hash, filter.from_block, filter.to_block) {
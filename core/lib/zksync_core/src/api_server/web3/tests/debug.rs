@@ -1,7 +1,7 @@
 //! Tests for the `debug` Web3 namespace.
 
 use zksync_types::{tx::TransactionExecutionResult, vm_trace::Call, BOOTLOADER_ADDRESS};
-use zksync_web3_decl::namespaces::DebugNamespaceClient;
+use zksync_web3_decl::{error::Web3Error, namespaces::DebugNamespaceClient};
 
 use super::*;
 
@@ -163,3 +163,66 @@ impl HttpTest for TraceBlockTestWithSnapshotRecovery {
 async fn tracing_block_after_snapshot_recovery() {
     test_http_server(TraceBlockTestWithSnapshotRecovery).await;
 }
+
+#[derive(Debug)]
+struct ExplainLogsQueryTest;
+
+#[async_trait]
+impl HttpTest for ExplainLogsQueryTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Number(1.into())),
+            address: Some(Address::repeat_byte(23).into()),
+            topics: Some(vec![Some(H256::repeat_byte(42).into())]),
+            ..Filter::default()
+        };
+        let explanation = client.explain_logs_query(filter.clone(), None).await?;
+        assert!(!explanation.planner_enabled);
+        assert!(
+            explanation.where_clause.contains("miniblock_number"),
+            "{explanation:?}"
+        );
+        if let Some(strategy) = explanation.strategy {
+            assert!(explanation
+                .estimates
+                .iter()
+                .any(|estimate| estimate.strategy == strategy));
+        }
+        assert!(explanation
+            .estimates
+            .iter()
+            .all(|estimate| estimate.latency_ms.is_none()));
+
+        let explanation = client.explain_logs_query(filter, Some(true)).await?;
+        for estimate in &explanation.estimates {
+            assert!(estimate.latency_ms.is_some(), "{estimate:?}");
+            assert_eq!(estimate.log_count, Some(1), "{estimate:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn explaining_logs_query() {
+    test_http_server(ExplainLogsQueryTest).await;
+}
+
+#[test]
+fn rate_limiting_debug_requests() {
+    let limits = DebugNamespaceLimits::new(None, Some(NonZeroU32::new(2).unwrap()), None);
+    limits.check_rate_limit().unwrap();
+    // The limiter is shared among clones, i.e., among all connections to the server.
+    limits.clone().check_rate_limit().unwrap();
+    let err = limits.check_rate_limit().unwrap_err();
+    assert_matches!(err, Web3Error::TooManyRequests);
+
+    let unlimited = DebugNamespaceLimits::default();
+    for _ in 0..10 {
+        unlimited.check_rate_limit().unwrap();
+    }
+}
@@ -303,3 +303,25 @@ impl HttpTest for DisableFiltersTest {
 async fn disable_filters() {
     test_http_server(DisableFiltersTest).await;
 }
+
+#[derive(Debug)]
+struct PrivateMempoolFeedTest;
+
+#[async_trait]
+impl HttpTest for PrivateMempoolFeedTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        assert_not_implemented(client.new_pending_transaction_filter().await);
+        // Other filters should still work.
+        client.new_block_filter().await?;
+        Ok(())
+    }
+
+    fn mempool_feed_mode(&self) -> MempoolFeedMode {
+        MempoolFeedMode::Private
+    }
+}
+
+#[tokio::test]
+async fn pending_transaction_filters_in_private_mempool_feed_mode() {
+    test_http_server(PrivateMempoolFeedTest).await;
+}
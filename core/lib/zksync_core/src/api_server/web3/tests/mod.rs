@@ -4,20 +4,38 @@ use assert_matches::assert_matches;
 use async_trait::async_trait;
 use jsonrpsee::core::ClientError;
 use multivm::zk_evm_latest::ethereum_types::U256;
+use rlp::RlpStream;
 use tokio::sync::watch;
 use zksync_config::configs::{
-    api::Web3JsonRpcConfig,
+    api::{MempoolFeedMode, Web3JsonRpcConfig},
     chain::{NetworkConfig, StateKeeperConfig},
     ContractsConfig,
 };
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
+use zksync_dal::{
+    solvency_reports_dal::SolvencyReport, transactions_dal::L2TxSubmissionResult, ConnectionPool,
+    StorageProcessor,
+};
 use zksync_health_check::CheckHealth;
+use zksync_l1_contract_interface::{
+    i_executor::{
+        commit::kzg::{kzg_commitment_to_versioned_hash, ZK_SYNC_BYTES_PER_BLOB},
+        structures::{BatchCommitments, CommitBatchInfo, StoredBatchInfo},
+    },
+    Tokenizable,
+};
 use zksync_types::{
-    api,
-    block::MiniblockHeader,
+    aggregated_operations::AggregatedActionType,
+    api::{self, SigningToken},
+    audit_log::AuditAction,
+    block::{MiniblockHashScheme, MiniblockHasher, MiniblockHeader},
+    chain_freeze::ChainFreezeSource,
+    eth_sender::EthTxL1Costs,
+    ethabi,
     fee::TransactionExecutionMetrics,
+    fee_model::BatchFeeInput,
     get_nonce_key,
     l2::L2Tx,
+    pubdata_da::PubdataDA,
     storage::get_code_key,
     tokens::{TokenInfo, TokenMetadata},
     tx::{
@@ -25,21 +43,27 @@ use zksync_types::{
         TransactionExecutionResult,
     },
     utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
-    AccountTreeId, Address, L1BatchNumber, Nonce, StorageKey, StorageLog, VmEvent, H256, U64,
+    web3::signing::keccak256,
+    AccountTreeId, Address, L1BatchNumber, L1BlockNumber, Nonce, PackedEthSignature,
+    ProtocolVersionId, StorageKey, StorageLog, VmEvent, BOOTLOADER_ADDRESS, H256, U64,
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::{
-    jsonrpsee::{http_client::HttpClient, types::error::ErrorCode},
-    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+    jsonrpsee::{
+        http_client::{HeaderMap, HeaderValue, HttpClient},
+        types::error::ErrorCode,
+    },
+    namespaces::{AdminNamespaceClient, EthNamespaceClient, ZksNamespaceClient},
 };
 
-use super::{metrics::ApiTransportLabel, *};
+use super::{admin_server::AdminServer, metrics::ApiTransportLabel, *};
 use crate::{
     api_server::{
         execution_sandbox::testonly::MockTransactionExecutor,
         tx_sender::tests::create_test_tx_sender,
     },
     genesis::{ensure_genesis_state, GenesisParams},
+    state_keeper::TxSizeLimits,
     utils::testonly::{
         create_l1_batch, create_l1_batch_metadata, create_l2_transaction, create_miniblock,
         l1_batch_metadata_to_commitment_artifacts, prepare_recovery_snapshot,
@@ -54,6 +78,7 @@ mod ws;
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(10);
 const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const RESPONSE_SIGNING_KEY: H256 = H256::repeat_byte(0x11);
 
 impl ApiServerHandles {
     /// Waits until the server health check reports the ready state. Must be called once per server instance.
@@ -110,6 +135,7 @@ pub(crate) async fn spawn_http_server(
         api_config,
         pool,
         None,
+        None,
         tx_executor,
         stop_receiver,
     )
@@ -122,12 +148,14 @@ async fn spawn_ws_server(
     pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    storage_write_sets: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     spawn_server(
         ApiTransportLabel::Ws,
         api_config,
         pool,
         websocket_requests_per_minute_limit,
+        storage_write_sets,
         MockTransactionExecutor::default(),
         stop_receiver,
     )
@@ -139,6 +167,7 @@ async fn spawn_server(
     api_config: InternalApiConfig,
     pool: ConnectionPool,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    storage_write_sets: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
     tx_executor: MockTransactionExecutor,
     stop_receiver: watch::Receiver<bool>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
@@ -149,16 +178,21 @@ async fn spawn_server(
     let mut namespaces = Namespace::DEFAULT.to_vec();
     namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
 
+    let handoff_pool = pool.clone();
     let server_builder = match transport {
         ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
         ApiTransportLabel::Ws => {
             let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
                 .ws(0)
-                .with_subscriptions_limit(100);
+                .with_subscriptions_limit(100)
+                .with_subscription_handoff(handoff_pool, Some(Duration::from_secs(60)));
             if let Some(websocket_requests_per_minute_limit) = websocket_requests_per_minute_limit {
                 builder = builder
                     .with_websocket_requests_per_minute_limit(websocket_requests_per_minute_limit);
             }
+            if let Some(storage_write_sets) = storage_write_sets {
+                builder = builder.with_storage_write_sets(storage_write_sets);
+            }
             builder
         }
     };
@@ -166,6 +200,7 @@ async fn spawn_server(
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender, vm_barrier)
         .with_pub_sub_events(pub_sub_events_sender)
+        .with_response_signer(ResponseSigner::new(RESPONSE_SIGNING_KEY).unwrap())
         .enable_api_namespaces(namespaces)
         .build(stop_receiver)
         .await
@@ -190,6 +225,21 @@ trait HttpTest: Send + Sync {
     fn filters_disabled(&self) -> bool {
         false
     }
+
+    /// Overrides the `reject_txs_without_chain_id` configuration parameter for HTTP server startup
+    fn reject_txs_without_chain_id(&self) -> bool {
+        false
+    }
+
+    /// Overrides the `mempool_feed_mode` configuration parameter for HTTP server startup.
+    fn mempool_feed_mode(&self) -> MempoolFeedMode {
+        MempoolFeedMode::Public
+    }
+
+    /// API level declared by the client via the `zksync-api-level` HTTP header.
+    fn client_api_level(&self) -> Option<u32> {
+        None
+    }
 }
 
 /// Storage initialization strategy.
@@ -266,6 +316,8 @@ async fn test_http_server(test: impl HttpTest) {
     let web3_config = Web3JsonRpcConfig::for_tests();
     let mut api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
     api_config.filters_disabled = test.filters_disabled();
+    api_config.reject_txs_without_chain_id = test.reject_txs_without_chain_id();
+    api_config.mempool_feed.mode = test.mempool_feed_mode();
     let mut server_handles = spawn_http_server(
         api_config,
         pool.clone(),
@@ -275,7 +327,12 @@ async fn test_http_server(test: impl HttpTest) {
     .await;
 
     let local_addr = server_handles.wait_until_ready().await;
+    let mut headers = HeaderMap::new();
+    if let Some(level) = test.client_api_level() {
+        headers.insert(api_level::API_LEVEL_HEADER, HeaderValue::from(level));
+    }
     let client = <HttpClient>::builder()
+        .set_headers(headers)
         .build(format!("http://{local_addr}/"))
         .unwrap();
     test.test(&client, &pool).await.unwrap();
@@ -317,6 +374,7 @@ fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionResult {
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        revert_data: None,
     }
 }
 
@@ -325,6 +383,15 @@ async fn store_miniblock(
     storage: &mut StorageProcessor<'_>,
     number: MiniblockNumber,
     transaction_results: &[TransactionExecutionResult],
+) -> anyhow::Result<MiniblockHeader> {
+    let new_miniblock = create_miniblock(number.0);
+    store_miniblock_with_header(storage, new_miniblock, transaction_results).await
+}
+
+async fn store_miniblock_with_header(
+    storage: &mut StorageProcessor<'_>,
+    new_miniblock: MiniblockHeader,
+    transaction_results: &[TransactionExecutionResult],
 ) -> anyhow::Result<MiniblockHeader> {
     for result in transaction_results {
         let l2_tx = result.transaction.clone().try_into().unwrap();
@@ -335,7 +402,6 @@ async fn store_miniblock(
         assert_matches!(tx_submission_result, L2TxSubmissionResult::Added);
     }
 
-    let new_miniblock = create_miniblock(number.0);
     storage
         .blocks_dal()
         .insert_miniblock(&new_miniblock)
@@ -454,6 +520,123 @@ async fn http_server_basics() {
     test_http_server(HttpServerBasicsTest).await;
 }
 
+#[derive(Debug)]
+struct ContractAddressesTest;
+
+#[async_trait]
+impl HttpTest for ContractAddressesTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let contracts_config = ContractsConfig::for_tests();
+        let addresses = client.get_contract_addresses().await?;
+        assert_eq!(addresses.l1_chain_id, client.l1_chain_id().await?);
+        assert_eq!(addresses.main_contract, contracts_config.diamond_proxy_addr);
+        assert_eq!(addresses.bridgehub, contracts_config.bridgehub_proxy_addr);
+        assert_eq!(addresses.bridgehub, client.get_bridgehub_contract().await?);
+        assert_eq!(
+            addresses.shared_bridge,
+            contracts_config.l1_shared_bridge_proxy_addr
+        );
+        assert_eq!(
+            addresses.state_transition_manager,
+            contracts_config.state_transition_proxy_addr
+        );
+        assert_eq!(
+            addresses.validator_timelock,
+            Some(contracts_config.validator_timelock_addr)
+        );
+        assert_eq!(
+            addresses.bridges.l1_erc20_default_bridge,
+            contracts_config.l1_erc20_bridge_proxy_addr
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_contract_addresses() {
+    test_http_server(ContractAddressesTest).await;
+}
+
+#[test]
+fn zero_contract_addresses_are_not_reported() {
+    let contracts_config = ContractsConfig {
+        bridgehub_proxy_addr: Some(Address::zero()),
+        l1_shared_bridge_proxy_addr: Some(Address::zero()),
+        state_transition_proxy_addr: Some(Address::zero()),
+        validator_timelock_addr: Address::zero(),
+        governance_addr: Address::zero(),
+        ..ContractsConfig::for_tests()
+    };
+    let api_config = InternalApiConfig::new(
+        &NetworkConfig::for_tests(),
+        &Web3JsonRpcConfig::for_tests(),
+        &contracts_config,
+    );
+    assert_eq!(api_config.bridgehub_proxy_addr, None);
+    assert_eq!(api_config.l1_shared_bridge_proxy_addr, None);
+    assert_eq!(api_config.state_transition_proxy_addr, None);
+    assert_eq!(api_config.validator_timelock_addr, None);
+    assert_eq!(api_config.governance_addr, None);
+}
+
+#[derive(Debug)]
+struct ChainParametersTest;
+
+#[async_trait]
+impl HttpTest for ChainParametersTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let params = client.get_chain_parameters().await?;
+        assert_eq!(params.l2_chain_id, client.chain_id().await?);
+        assert_eq!(params.base_token_address, Address::zero());
+        assert_eq!(
+            TxSizeLimits::from_api_limits(&params.tx_limits),
+            TxSizeLimits::default()
+        );
+
+        let protocol_version = client
+            .get_protocol_version(None)
+            .await?
+            .context("no protocol version")?;
+        assert_eq!(params.protocol_version, protocol_version.version_id);
+        assert_eq!(
+            params.base_system_contracts,
+            protocol_version.base_system_contracts
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_chain_parameters() {
+    test_http_server(ChainParametersTest).await;
+}
+
+#[derive(Debug)]
+struct NodeInfoTest;
+
+#[async_trait]
+impl HttpTest for NodeInfoTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let info = client.get_node_info().await?;
+        assert!(!info.build_profile.is_empty());
+        for namespace in Namespace::DEFAULT {
+            assert!(
+                info.api_namespaces.contains(&namespace.as_str().to_owned()),
+                "{info:?}"
+            );
+        }
+        assert!(info
+            .supported_protocol_versions
+            .contains(&(ProtocolVersionId::latest() as u16)));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_node_info() {
+    test_http_server(NodeInfoTest).await;
+}
+
 #[derive(Debug)]
 struct BlockMethodsWithSnapshotRecovery;
 
@@ -864,6 +1047,768 @@ async fn transaction_receipts() {
     test_http_server(TransactionReceiptsTest).await;
 }
 
+#[derive(Debug)]
+struct GasPriceHistoryTest;
+
+#[async_trait]
+impl HttpTest for GasPriceHistoryTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let mut headers = vec![];
+        for number in 1..=2 {
+            let header = MiniblockHeader {
+                batch_fee_input: BatchFeeInput::pubdata_independent(100, 200, 300 + number),
+                ..create_miniblock(number as u32)
+            };
+            headers.push(store_miniblock_with_header(&mut storage, header, &[]).await?);
+        }
+
+        let history = client
+            .gas_price_history(MiniblockNumber(1), 100.into())
+            .await?;
+        assert_eq!(history.len(), 2);
+        for (entry, header) in history.iter().zip(&headers) {
+            assert_eq!(entry.number, header.number);
+            assert_eq!(entry.timestamp, header.timestamp);
+            assert_eq!(entry.base_fee_per_gas, header.base_fee_per_gas.into());
+            let fee_input = header.batch_fee_input;
+            assert_eq!(entry.l1_gas_price, fee_input.l1_gas_price().into());
+            assert_eq!(
+                entry.fair_l2_gas_price,
+                fee_input.fair_l2_gas_price().into()
+            );
+            assert_eq!(
+                entry.fair_pubdata_price,
+                fee_input.fair_pubdata_price().into()
+            );
+        }
+
+        let history = client
+            .gas_price_history(MiniblockNumber(0), 1.into())
+            .await?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].number, MiniblockNumber(0));
+        assert_eq!(history[0].l1_batch_number, Some(L1BatchNumber(0)));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_gas_price_history() {
+    test_http_server(GasPriceHistoryTest).await;
+}
+
+#[derive(Debug)]
+struct SystemContextTest;
+
+#[async_trait]
+impl HttpTest for SystemContextTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let system_context = client.get_system_context(MiniblockNumber(1)).await?;
+        assert_eq!(system_context, None);
+
+        let mut storage = pool.access_storage().await?;
+        let genesis_header = storage
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(0))
+            .await?
+            .context("no genesis miniblock")?;
+        let header = MiniblockHeader {
+            timestamp: 10,
+            fee_account_address: Address::repeat_byte(0x23),
+            batch_fee_input: BatchFeeInput::pubdata_independent(100, 200, 300),
+            ..create_miniblock(1)
+        };
+        let header = store_miniblock_with_header(&mut storage, header, &[]).await?;
+
+        let system_context = client
+            .get_system_context(MiniblockNumber(1))
+            .await?
+            .context("no system context")?;
+        assert_eq!(system_context.chain_id, client.chain_id().await?);
+        assert_eq!(system_context.l1_batch_number, L1BatchNumber(1));
+        assert!(!system_context.l1_batch_sealed);
+        assert_eq!(system_context.l1_batch_timestamp, header.timestamp);
+        assert_eq!(system_context.miniblock_number, header.number);
+        assert_eq!(system_context.miniblock_timestamp, header.timestamp);
+        assert_eq!(
+            system_context.prev_miniblock_hash,
+            Some(genesis_header.hash)
+        );
+        assert_eq!(system_context.coinbase, BOOTLOADER_ADDRESS);
+        assert_eq!(system_context.protocol_version, header.protocol_version);
+        assert_eq!(
+            system_context.base_fee_per_gas,
+            header.base_fee_per_gas.into()
+        );
+        let (_, expected_gas_per_pubdata) = multivm::utils::derive_base_fee_and_gas_per_pubdata(
+            header.batch_fee_input,
+            ProtocolVersionId::latest().into(),
+        );
+        assert_eq!(
+            system_context.gas_per_pubdata,
+            expected_gas_per_pubdata.into()
+        );
+        assert_eq!(system_context.l1_gas_price, 100.into());
+        assert_eq!(system_context.fair_l2_gas_price, 200.into());
+        assert_eq!(system_context.fair_pubdata_price, 300.into());
+
+        let genesis_context = client
+            .get_system_context(MiniblockNumber(0))
+            .await?
+            .context("no system context for genesis")?;
+        assert_eq!(genesis_context.l1_batch_number, L1BatchNumber(0));
+        assert!(genesis_context.l1_batch_sealed);
+        assert_eq!(genesis_context.prev_miniblock_hash, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_system_context() {
+    test_http_server(SystemContextTest).await;
+}
+
+#[derive(Debug)]
+struct SolvencyReportTest;
+
+#[async_trait]
+impl HttpTest for SolvencyReportTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let report = client.get_solvency_report(None).await?;
+        assert_eq!(report, None);
+
+        let mut storage = pool.access_storage().await?;
+        let report = SolvencyReport {
+            l1_batch_number: L1BatchNumber(0),
+            total_deposited: 1_000.into(),
+            total_withdrawn: 100.into(),
+            expected_supply: 900.into(),
+            l2_total_supply: 900.into(),
+            l1_bridge_balance: None,
+        };
+        storage
+            .solvency_reports_dal()
+            .insert_report(&report)
+            .await?;
+        storage
+            .solvency_reports_dal()
+            .set_l1_bridge_balance(L1BatchNumber(0), 850.into())
+            .await?;
+
+        for batch in [None, Some(L1BatchNumber(0))] {
+            let api_report = client
+                .get_solvency_report(batch)
+                .await?
+                .context("no solvency report")?;
+            assert_eq!(api_report.l1_batch_number, L1BatchNumber(0));
+            assert_eq!(api_report.total_deposited, report.total_deposited);
+            assert_eq!(api_report.total_withdrawn, report.total_withdrawn);
+            // The genesis batch is not executed on L1.
+            assert_eq!(api_report.finalized_withdrawn, 0.into());
+            assert!(api_report.is_balanced);
+            assert_eq!(api_report.l1_bridge_balance, Some(850.into()));
+            assert_eq!(api_report.is_solvent, Some(false));
+            assert!(api_report.tokens.is_empty());
+        }
+        let report = client.get_solvency_report(Some(L1BatchNumber(1))).await?;
+        assert_eq!(report, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_solvency_report() {
+    test_http_server(SolvencyReportTest).await;
+}
+
+#[derive(Debug)]
+struct SignedResponsesTest;
+
+#[async_trait]
+impl HttpTest for SignedResponsesTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let signed_details = client
+            .get_signed_l1_batch_details(L1BatchNumber(0))
+            .await?
+            .context("no genesis L1 batch")?;
+        let expected_signer =
+            PackedEthSignature::address_from_private_key(&RESPONSE_SIGNING_KEY).unwrap();
+        assert_eq!(signed_details.signer, expected_signer);
+        assert_eq!(
+            U64::from(signed_details.chain_id.as_u64()),
+            client.chain_id().await?
+        );
+        let params = [L1BatchNumber(0).signing_token()];
+        assert!(signed_details
+            .verify("zks_getSignedL1BatchDetails", &params)
+            .unwrap());
+        // The signature is bound to the method name and the request params.
+        assert!(!signed_details
+            .verify("zks_getL1BatchDetails", &params)
+            .unwrap());
+        let other_params = [L1BatchNumber(1).signing_token()];
+        assert!(!signed_details
+            .verify("zks_getSignedL1BatchDetails", &other_params)
+            .unwrap());
+
+        let details = client
+            .get_l1_batch_details(L1BatchNumber(0))
+            .await?
+            .context("no genesis L1 batch")?;
+        assert_eq!(
+            serde_json::to_value(&signed_details.response)?,
+            serde_json::to_value(&details)?
+        );
+
+        let mut tampered_details = signed_details;
+        tampered_details.response.base.root_hash = Some(H256::repeat_byte(0xff));
+        assert!(!tampered_details
+            .verify("zks_getSignedL1BatchDetails", &params)
+            .unwrap());
+
+        let missing_details = client.get_signed_l1_batch_details(L1BatchNumber(1)).await?;
+        assert!(missing_details.is_none());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_signed_responses() {
+    test_http_server(SignedResponsesTest).await;
+}
+
+#[derive(Debug)]
+struct BlobUtilizationReportTest;
+
+#[async_trait]
+impl HttpTest for BlobUtilizationReportTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let report = client.get_blob_utilization_report(None).await?;
+        assert_eq!(report.commit_tx_count, 0);
+        assert_eq!(report.utilization, 0.0);
+        assert!(report.commits.is_empty());
+
+        let mut storage = pool.access_storage().await?;
+        for tx_hash in [H256::repeat_byte(1), H256::repeat_byte(2)] {
+            storage
+                .eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(0),
+                    AggregatedActionType::Commit,
+                    tx_hash,
+                    chrono::Utc::now(),
+                )
+                .await?;
+        }
+        let eth_txs = storage
+            .eth_sender_dal()
+            .get_confirmed_txs_without_l1_costs(10)
+            .await?;
+        let (first_tx_id, second_tx_id) = (eth_txs[0].0, eth_txs[1].0);
+        storage
+            .eth_sender_dal()
+            .save_blob_utilization(first_tx_id, L1BatchNumber(1)..=L1BatchNumber(1), 1, 100_000)
+            .await?;
+        storage
+            .eth_sender_dal()
+            .save_blob_utilization(
+                second_tx_id,
+                L1BatchNumber(2)..=L1BatchNumber(3),
+                2,
+                200_000,
+            )
+            .await?;
+        let costs = EthTxL1Costs {
+            tx_hash: H256::repeat_byte(1),
+            gas_used: 150_000.into(),
+            effective_gas_price: 10.into(),
+            l1_block_number: L1BlockNumber(42),
+        };
+        storage
+            .eth_sender_dal()
+            .save_l1_costs(first_tx_id, &costs)
+            .await?;
+
+        let report = client.get_blob_utilization_report(None).await?;
+        let bytes_per_blob = ZK_SYNC_BYTES_PER_BLOB as u64;
+        assert_eq!(report.commit_tx_count, 2);
+        assert_eq!(report.l1_batch_count, 3);
+        assert_eq!(report.blob_count, 3);
+        assert_eq!(report.pubdata_bytes, 300_000);
+        assert_eq!(report.wasted_bytes, 3 * bytes_per_blob - 300_000);
+
+        let [newest_commit, oldest_commit] = report.commits.as_slice() else {
+            panic!("unexpected commits: {:?}", report.commits);
+        };
+        assert_eq!(newest_commit.first_l1_batch_number, L1BatchNumber(2));
+        assert_eq!(newest_commit.last_l1_batch_number, L1BatchNumber(3));
+        assert_eq!(newest_commit.wasted_bytes, 2 * bytes_per_blob - 200_000);
+        assert_eq!(newest_commit.fee_per_l1_batch, None);
+        assert_eq!(oldest_commit.wasted_bytes, bytes_per_blob - 100_000);
+        assert_eq!(oldest_commit.fee_per_l1_batch, Some(costs.total_fee()));
+
+        let report = client.get_blob_utilization_report(Some(1)).await?;
+        assert_eq!(report.commit_tx_count, 1);
+        assert_eq!(report.commits[0].last_l1_batch_number, L1BatchNumber(3));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_blob_utilization_report() {
+    test_http_server(BlobUtilizationReportTest).await;
+}
+
+#[derive(Debug)]
+struct L1BatchL1CostsTest;
+
+#[async_trait]
+impl HttpTest for L1BatchL1CostsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let costs = client.get_l1_batch_l1_costs(L1BatchNumber(0)).await?;
+        assert!(costs.is_empty());
+
+        let mut storage = pool.access_storage().await?;
+        let tx_hash = H256::repeat_byte(1);
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(0),
+                AggregatedActionType::Commit,
+                tx_hash,
+                chrono::Utc::now(),
+            )
+            .await?;
+        // Costs are not recorded yet.
+        let costs = client.get_l1_batch_l1_costs(L1BatchNumber(0)).await?;
+        assert!(costs.is_empty());
+
+        let eth_txs = storage
+            .eth_sender_dal()
+            .get_confirmed_txs_without_l1_costs(10)
+            .await?;
+        let l1_costs = EthTxL1Costs {
+            tx_hash,
+            gas_used: 150_000.into(),
+            effective_gas_price: 10.into(),
+            l1_block_number: L1BlockNumber(42),
+        };
+        storage
+            .eth_sender_dal()
+            .save_l1_costs(eth_txs[0].0, &l1_costs)
+            .await?;
+
+        let costs = client.get_l1_batch_l1_costs(L1BatchNumber(0)).await?;
+        let [cost] = costs.as_slice() else {
+            panic!("unexpected costs: {costs:?}");
+        };
+        assert_eq!(cost.tx_type, "CommitBlocks");
+        assert_eq!(cost.tx_hash, tx_hash);
+        assert_eq!(cost.gas_used, 150_000.into());
+        assert_eq!(cost.l1_block_number, 42.into());
+        assert_eq!(cost.total_fee, l1_costs.total_fee());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_l1_batch_l1_costs() {
+    test_http_server(L1BatchL1CostsTest).await;
+}
+
+#[derive(Debug)]
+struct ApiCapabilitiesTest {
+    client_api_level: Option<u32>,
+}
+
+#[async_trait]
+impl HttpTest for ApiCapabilitiesTest {
+    fn client_api_level(&self) -> Option<u32> {
+        self.client_api_level
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let mut tx_result = execute_l2_transaction(create_l2_transaction(10, 200));
+        tx_result.refunded_gas = 1_000;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[tx_result.clone()]).await?;
+
+        let expected_level = self.client_api_level.unwrap_or(0);
+        let capabilities = client.get_api_capabilities().await?;
+        assert_eq!(capabilities.client_level, expected_level);
+        assert_eq!(
+            capabilities.max_client_level,
+            api::ApiCapability::MAX_CLIENT_LEVEL
+        );
+        assert_eq!(
+            capabilities.capabilities.len(),
+            api::ApiCapability::ALL.len()
+        );
+        let receipt_capability = capabilities
+            .capabilities
+            .iter()
+            .find(|info| info.capability == api::ApiCapability::ReceiptGasRefunded)
+            .context("no receipt capability")?;
+        assert_eq!(receipt_capability.enabled, expected_level >= 1);
+
+        let receipt = client
+            .get_transaction_receipt(tx_result.hash)
+            .await?
+            .context("no receipt")?;
+        let expected_gas_refunded = (expected_level >= 1).then(|| 1_000.into());
+        assert_eq!(receipt.gas_refunded, expected_gas_refunded);
+        let receipts = client
+            .get_block_receipts(api::BlockId::Number(1.into()))
+            .await?;
+        assert_eq!(receipts, [receipt]);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn api_capabilities_for_baseline_client() {
+    test_http_server(ApiCapabilitiesTest {
+        client_api_level: None,
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn api_capabilities_for_upgraded_client() {
+    test_http_server(ApiCapabilitiesTest {
+        client_api_level: Some(1),
+    })
+    .await;
+}
+
+#[derive(Debug)]
+struct BlockHashPreimageTest;
+
+#[async_trait]
+impl HttpTest for BlockHashPreimageTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let genesis_preimage = client
+            .get_block_hash_preimage(MiniblockNumber(0))
+            .await?
+            .context("no genesis block")?;
+        // The genesis block always has the legacy hash.
+        assert_eq!(genesis_preimage.hash_scheme, MiniblockHashScheme::Legacy);
+        assert_eq!(
+            genesis_preimage.hash,
+            MiniblockHasher::legacy_hash(MiniblockNumber(0))
+        );
+        assert_eq!(genesis_preimage.parent_hash, H256::zero());
+
+        let tx_results = [
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+        ];
+        let mut new_miniblock = create_miniblock(1);
+        let mut hasher = MiniblockHasher::new(
+            new_miniblock.number,
+            new_miniblock.timestamp,
+            genesis_preimage.hash,
+        );
+        for result in &tx_results {
+            hasher.push_tx_hash(result.hash);
+        }
+        new_miniblock.hash = hasher.finalize(ProtocolVersionId::latest());
+        let mut storage = pool.access_storage().await?;
+        store_miniblock_with_header(&mut storage, new_miniblock.clone(), &tx_results).await?;
+
+        let preimage = client
+            .get_block_hash_preimage(MiniblockNumber(1))
+            .await?
+            .context("no block #1")?;
+        assert_eq!(preimage.hash, new_miniblock.hash);
+        assert_eq!(preimage.hash_scheme, MiniblockHashScheme::Header);
+        assert_eq!(preimage.timestamp, new_miniblock.timestamp);
+        assert_eq!(preimage.parent_hash, genesis_preimage.hash);
+        let tx_hashes: Vec<_> = tx_results.iter().map(|result| result.hash).collect();
+        assert_eq!(preimage.transactions, tx_hashes);
+        assert_eq!(preimage.txs_rolling_hash, hasher.txs_rolling_hash());
+
+        let preimage = client.get_block_hash_preimage(MiniblockNumber(2)).await?;
+        assert_eq!(preimage, None);
+        // Block with a hash that cannot be reproduced from its header.
+        store_miniblock(&mut storage, MiniblockNumber(2), &[]).await?;
+        let err = client
+            .get_block_hash_preimage(MiniblockNumber(2))
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(err) if err.code() == ErrorCode::InternalError.code());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_block_hash_preimage() {
+    test_http_server(BlockHashPreimageTest).await;
+}
+
+#[derive(Debug)]
+struct BlockCommitmentProofTest;
+
+#[async_trait]
+impl HttpTest for BlockCommitmentProofTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        // Miniblock hashes are not stored in the genesis state.
+        let proof = client
+            .get_block_commitment_proof(MiniblockNumber(0))
+            .await?;
+        assert!(proof.is_none());
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        // The miniblock is not included into an L1 batch yet.
+        let proof = client
+            .get_block_commitment_proof(MiniblockNumber(1))
+            .await?;
+        assert!(proof.is_none());
+
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        // The test server has no Merkle tree API, so storage proofs cannot be obtained.
+        let err = client
+            .get_block_commitment_proof(MiniblockNumber(1))
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(_));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_block_commitment_proof() {
+    test_http_server(BlockCommitmentProofTest).await;
+}
+
+#[derive(Debug)]
+struct StorageExitProofTest;
+
+#[async_trait]
+impl HttpTest for StorageExitProofTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let address = Address::repeat_byte(1);
+        let key = H256::repeat_byte(2);
+        let proof = client
+            .get_storage_exit_proof(address, key, L1BatchNumber(1))
+            .await?;
+        assert!(proof.is_none());
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        // The batch is not executed on L1 yet.
+        let proof = client
+            .get_storage_exit_proof(address, key, L1BatchNumber(1))
+            .await?;
+        assert!(proof.is_none());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_storage_exit_proof() {
+    test_http_server(StorageExitProofTest).await;
+}
+
+#[derive(Debug)]
+struct L1BatchStoredInfoTest;
+
+#[async_trait]
+impl HttpTest for L1BatchStoredInfoTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let info = client.get_l1_batch_stored_info(L1BatchNumber(1)).await?;
+        assert_eq!(info, None);
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        // The batch is not committed on L1 yet.
+        let info = client.get_l1_batch_stored_info(L1BatchNumber(1)).await?;
+        assert_eq!(info, None);
+
+        let commit_tx_hash = H256::repeat_byte(0x42);
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Commit,
+                commit_tx_hash,
+                chrono::Utc::now(),
+            )
+            .await?;
+        let info = client
+            .get_l1_batch_stored_info(L1BatchNumber(1))
+            .await?
+            .context("no stored info for committed batch")?;
+        assert_eq!(info.number, L1BatchNumber(1));
+        assert_eq!(info.commit_tx_hash, commit_tx_hash);
+
+        // `StoredBatchInfo` for the batch created by `seal_l1_batch()`, ABI-encoded as a static tuple.
+        let expected_words = [
+            H256::from_low_u64_be(1),  // batch number
+            H256::from_low_u64_be(1),  // batch hash
+            H256::from_low_u64_be(21), // index of repeated storage changes
+            H256::zero(),              // number of L1 txs
+            // Priority operations hash for a batch without priority ops, i.e. `keccak256("")`
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+                .parse()
+                .unwrap(),
+            H256::from_low_u64_be(1), // L2 logs tree root
+            H256::from_low_u64_be(1), // timestamp
+            H256::from_low_u64_be(1), // commitment
+        ];
+        let expected_info: Vec<u8> = expected_words.iter().flat_map(|word| word.0).collect();
+        assert_eq!(info.stored_batch_info.0, expected_info);
+        assert_eq!(info.stored_batch_hash, H256(keccak256(&expected_info)));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_l1_batch_stored_info() {
+    test_http_server(L1BatchStoredInfoTest).await;
+}
+
+#[derive(Debug)]
+struct L1BatchCommitmentDetailsTest;
+
+impl L1BatchCommitmentDetailsTest {
+    fn commit_calldata(prev_l1_batch: &StoredBatchInfo, info: &CommitBatchInfo) -> Vec<u8> {
+        let params = [
+            StoredBatchInfo::schema(),
+            ethabi::ParamType::Array(Box::new(CommitBatchInfo::schema())),
+        ];
+        let args = [
+            prev_l1_batch.clone().into_token(),
+            ethabi::Token::Array(vec![info.clone().into_token()]),
+        ];
+        let mut calldata = ethabi::short_signature("commitBatches", &params).to_vec();
+        calldata.extend(ethabi::encode(&args));
+        calldata
+    }
+}
+
+#[async_trait]
+impl HttpTest for L1BatchCommitmentDetailsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let details = client
+            .get_l1_batch_commitment_details(L1BatchNumber(1))
+            .await?;
+        assert_eq!(details, None);
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        // The batch is not committed on L1 yet.
+        let details = client
+            .get_l1_batch_commitment_details(L1BatchNumber(1))
+            .await?;
+        assert_eq!(details, None);
+
+        // Commit transaction without calldata, e.g. one inserted by the external node.
+        let commit_tx_hash = H256::repeat_byte(0x42);
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Commit,
+                commit_tx_hash,
+                chrono::Utc::now(),
+            )
+            .await?;
+        let details = client
+            .get_l1_batch_commitment_details(L1BatchNumber(1))
+            .await?
+            .context("no details for committed batch")?;
+        assert_eq!(details.commit_tx_hash, commit_tx_hash);
+        assert_eq!(details.commit_batch_info, None);
+        assert_eq!(details.pubdata_da, None);
+        assert!(details.blobs.is_empty());
+        assert_eq!(details.prove_tx_hash, None);
+
+        // Batch committed with blobs by this node.
+        store_miniblock(&mut storage, MiniblockNumber(2), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(2)).await?;
+        let prev_l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(1))
+            .await?
+            .context("no batch metadata")?;
+        let kzg_commitment = [3; 48];
+        let pubdata_commitment = [&[1; 16][..], &[2; 32], &kzg_commitment, &[4; 48]].concat();
+        let info = CommitBatchInfo {
+            batch_number: L1BatchNumber(2),
+            timestamp: 2,
+            index_repeated_storage_changes: 10,
+            new_state_root: H256::repeat_byte(1),
+            number_of_layer1_txs: 0.into(),
+            priority_operations_hash: H256::repeat_byte(2),
+            commitments: BatchCommitments::PostBoojum {
+                bootloader_heap_initial_contents_hash: H256::repeat_byte(3),
+                events_queue_state_hash: H256::repeat_byte(4),
+                system_logs: vec![],
+            },
+            pubdata: Some([&[1][..], &pubdata_commitment].concat()),
+        };
+        let calldata = Self::commit_calldata(&StoredBatchInfo::from(&prev_l1_batch), &info);
+
+        let eth_tx = storage
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                calldata,
+                AggregatedActionType::Commit,
+                Address::zero(),
+                0,
+                None,
+                None,
+            )
+            .await?;
+        storage
+            .blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(2)..=L1BatchNumber(2),
+                eth_tx.id,
+                AggregatedActionType::Commit,
+            )
+            .await?;
+        let commit_tx_hash = H256::repeat_byte(0x43);
+        storage
+            .eth_sender_dal()
+            .insert_tx_history(eth_tx.id, 0, 0, None, commit_tx_hash, &[])
+            .await?;
+        storage
+            .eth_sender_dal()
+            .confirm_tx(commit_tx_hash, 0.into())
+            .await?;
+
+        let details = client
+            .get_l1_batch_commitment_details(L1BatchNumber(2))
+            .await?
+            .context("no details for committed batch")?;
+        assert_eq!(details.number, L1BatchNumber(2));
+        assert_eq!(details.commit_tx_hash, commit_tx_hash);
+        assert_eq!(details.pubdata_da, Some(PubdataDA::Blobs));
+        let expected_info = ethabi::encode(&[info.into_token()]);
+        assert_eq!(details.commit_batch_info.unwrap().0, expected_info);
+        let expected_blob = api::CommittedBlobInfo {
+            versioned_hash: H256(kzg_commitment_to_versioned_hash(&kzg_commitment)),
+            kzg_commitment: kzg_commitment.to_vec().into(),
+        };
+        assert_eq!(details.blobs, [expected_blob]);
+        assert_eq!(details.blobs[0].versioned_hash.as_bytes()[0], 1);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_l1_batch_commitment_details() {
+    test_http_server(L1BatchCommitmentDetailsTest).await;
+}
+
 #[derive(Debug)]
 struct AllAccountBalancesTest;
 
@@ -933,3 +1878,91 @@ impl HttpTest for AllAccountBalancesTest {
 async fn getting_all_account_balances() {
     test_http_server(AllAccountBalancesTest).await;
 }
+
+#[derive(Debug)]
+struct LegacyTxWithoutChainIdTest;
+
+#[async_trait]
+impl HttpTest for LegacyTxWithoutChainIdTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let private_key = H256::repeat_byte(0x11);
+        let tx = api::TransactionRequest {
+            nonce: 0.into(),
+            to: Some(Address::repeat_byte(1)),
+            value: 1.into(),
+            gas_price: 250_000_000.into(),
+            gas: 1_000_000.into(),
+            ..api::TransactionRequest::default()
+        };
+        // Chain ID of 0 means that the transaction is signed without EIP-155 replay protection.
+        let mut rlp = RlpStream::new();
+        tx.rlp(&mut rlp, 0, None);
+        let message = PackedEthSignature::message_to_signed_bytes(&rlp.out());
+        let signature = PackedEthSignature::sign_raw(&private_key, &message).unwrap();
+        let mut rlp = RlpStream::new();
+        tx.rlp(&mut rlp, 0, Some(&signature));
+        let raw_tx = rlp.out().to_vec();
+
+        let err = client
+            .send_raw_transaction(raw_tx.into())
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.message().contains("replay-protected")
+        );
+        Ok(())
+    }
+
+    fn reject_txs_without_chain_id(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn rejecting_legacy_tx_without_chain_id() {
+    test_http_server(LegacyTxWithoutChainIdTest).await;
+}
+
+#[tokio::test]
+async fn freezing_chain_via_admin_server() {
+    let pool = ConnectionPool::test_pool().await;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let admin_server = AdminServer::new(0, pool.clone(), stop_receiver)
+        .await
+        .unwrap();
+    let local_addr = admin_server.local_addr();
+    assert!(local_addr.ip().is_loopback(), "{local_addr:?}");
+    let server_task = tokio::spawn(admin_server.run());
+
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    let status = client.get_chain_freeze_status().await.unwrap();
+    assert!(status.is_none(), "{status:?}");
+
+    let event = client
+        .freeze_chain(Some("incident".to_owned()))
+        .await
+        .unwrap();
+    assert!(event.is_frozen);
+    assert_eq!(event.source, ChainFreezeSource::AdminRpc);
+    assert_eq!(event.reason.as_deref(), Some("incident"));
+    let status = client.get_chain_freeze_status().await.unwrap();
+    assert_eq!(status, Some(event.clone()));
+
+    let mut storage = pool.access_storage().await.unwrap();
+    assert!(storage.chain_freeze_dal().is_chain_frozen().await.unwrap());
+    let audit_entries = storage.audit_log_dal().get_entries(0, 10).await.unwrap();
+    assert_eq!(audit_entries.len(), 1);
+    assert_eq!(audit_entries[0].action, AuditAction::AdminRpcCall);
+    assert_eq!(audit_entries[0].params["method"], "freeze_chain");
+    assert_eq!(audit_entries[0].params["event_id"], event.id);
+
+    let event = client.unfreeze_chain(None).await.unwrap();
+    assert!(!event.is_frozen);
+    assert!(!storage.chain_freeze_dal().is_chain_frozen().await.unwrap());
+
+    stop_sender.send_replace(true);
+    server_task.await.unwrap().unwrap();
+}
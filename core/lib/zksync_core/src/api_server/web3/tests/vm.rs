@@ -4,7 +4,12 @@ use std::sync::atomic::{AtomicU32, Ordering};
 
 use multivm::interface::{ExecutionResult, VmRevertReason};
 use zksync_types::{
-    get_intrinsic_constants, transaction_request::CallRequest, L2ChainId, PackedEthSignature, U256,
+    api::L1TransactionSimulationOptions,
+    get_intrinsic_constants,
+    l1::apply_l1_to_l2_alias,
+    transaction_request::{CallRequest, Eip712Meta},
+    ExecuteTransactionCommon, L2ChainId, PackedEthSignature,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::namespaces::DebugNamespaceClient;
@@ -487,3 +492,106 @@ async fn estimate_gas_basics() {
 async fn estimate_gas_after_snapshot_recovery() {
     test_http_server(EstimateGasTest::new(true)).await;
 }
+
+#[derive(Debug)]
+struct SimulateL1TransactionTest;
+
+impl SimulateL1TransactionTest {
+    const GAS_LIMIT_THRESHOLD: u32 = 100_000;
+
+    fn call_request() -> CallRequest {
+        CallRequest {
+            from: Some(Address::repeat_byte(1)),
+            to: Some(Address::repeat_byte(2)),
+            value: Some(1_000.into()),
+            ..CallRequest::default()
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTest for SimulateL1TransactionTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_tx_responses(|tx, _| {
+            let ExecuteTransactionCommon::L1(common_data) = &tx.common_data else {
+                panic!("Unexpected transaction: {tx:?}");
+            };
+            assert_eq!(
+                common_data.sender,
+                apply_l1_to_l2_alias(Address::repeat_byte(1))
+            );
+            assert!(common_data.to_mint >= tx.execute.value);
+
+            if common_data.gas_limit >= Self::GAS_LIMIT_THRESHOLD.into() {
+                ExecutionResult::Success {
+                    output: b"output".to_vec(),
+                }
+            } else {
+                ExecutionResult::Revert {
+                    output: VmRevertReason::General {
+                        msg: "out of gas".to_owned(),
+                        data: vec![],
+                    },
+                }
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let options = L1TransactionSimulationOptions {
+            alias_sender: true,
+            ..L1TransactionSimulationOptions::default()
+        };
+        let simulation = client
+            .simulate_l1_transaction(Self::call_request(), Some(options.clone()))
+            .await?;
+        let aliased_sender = apply_l1_to_l2_alias(Address::repeat_byte(1));
+        assert_eq!(simulation.sender, aliased_sender);
+        assert_eq!(simulation.refund_recipient, aliased_sender);
+        assert!(simulation.success, "{simulation:?}");
+        assert_eq!(simulation.output.0, b"output");
+        let required_gas_limit = simulation.required_gas_limit.unwrap();
+        assert!(required_gas_limit >= Self::GAS_LIMIT_THRESHOLD.into());
+        // If the gas limit is not specified, the operation is simulated with the required gas limit.
+        assert_eq!(simulation.gas_limit, required_gas_limit);
+        assert_eq!(
+            simulation.to_mint,
+            U256::from(1_000) + simulation.max_fee_per_gas * simulation.gas_limit
+        );
+        assert_eq!(
+            simulation.gas_per_pubdata_limit,
+            REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE.into()
+        );
+
+        let mut call_request = Self::call_request();
+        call_request.gas = Some(1_000.into());
+        call_request.eip712_meta = Some(Eip712Meta {
+            gas_per_pubdata: 1_234.into(),
+            ..Eip712Meta::default()
+        });
+        let options = L1TransactionSimulationOptions {
+            to_mint: Some(1_000_000.into()),
+            refund_recipient: Some(Address::repeat_byte(3)),
+            ..options
+        };
+        let simulation = client
+            .simulate_l1_transaction(call_request, Some(options))
+            .await?;
+        assert!(!simulation.success, "{simulation:?}");
+        assert_eq!(simulation.revert_reason.as_deref(), Some("out of gas"));
+        assert_eq!(simulation.gas_limit, 1_000.into());
+        assert_eq!(simulation.to_mint, 1_000_000.into());
+        assert_eq!(simulation.refund_recipient, Address::repeat_byte(3));
+        // The user-provided gas per pubdata limit must be respected.
+        assert_eq!(simulation.gas_per_pubdata_limit, 1_234.into());
+        assert!(simulation.required_gas_limit.is_some());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn simulating_l1_transaction() {
+    test_http_server(SimulateL1TransactionTest).await;
+}
@@ -7,7 +7,7 @@ use jsonrpsee::core::{client::ClientT, params::BatchRequestBuilder, ClientError}
 use reqwest::StatusCode;
 use tokio::sync::watch;
 use zksync_config::configs::chain::NetworkConfig;
-use zksync_dal::ConnectionPool;
+use zksync_dal::{exported_subscriptions_dal::ExportedSubscription, ConnectionPool};
 use zksync_types::{api, Address, L1BatchNumber, H256, U64};
 use zksync_web3_decl::{
     jsonrpsee::{
@@ -16,11 +16,11 @@ use zksync_web3_decl::{
         ws_client::{WsClient, WsClientBuilder},
     },
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
-    types::{BlockHeader, PubSubFilter},
+    types::{BlockHeader, PubSubFilter, StorageChange, StorageSlot},
 };
 
 use super::*;
-use crate::api_server::web3::metrics::SubscriptionType;
+use crate::api_server::web3::{metrics::SubscriptionType, state::MempoolFeedConfig};
 
 #[allow(clippy::needless_pass_by_ref_mut)] // false positive
 async fn wait_for_subscription(
@@ -108,7 +108,11 @@ async fn notifiers_start_after_snapshot_recovery() {
 
     let (stop_sender, stop_receiver) = watch::channel(false);
     let (events_sender, mut events_receiver) = mpsc::unbounded_channel();
-    let mut subscribe_logic = EthSubscribe::new();
+    let mempool_feed = MempoolFeedConfig {
+        mode: MempoolFeedMode::Public,
+        ..MempoolFeedConfig::default()
+    };
+    let mut subscribe_logic = EthSubscribe::new(mempool_feed);
     subscribe_logic.set_events_sender(events_sender);
     let notifier_handles =
         subscribe_logic.spawn_notifiers(pool.clone(), POLL_INTERVAL, stop_receiver);
@@ -159,6 +163,20 @@ trait WsTest: Send + Sync {
     fn websocket_requests_per_minute_limit(&self) -> Option<NonZeroU32> {
         None
     }
+
+    /// Miniblock write sets consumed by storage subscriptions. If not specified, storage changes are polled from Postgres.
+    fn storage_write_sets(&self) -> Option<broadcast::Sender<Arc<MiniblockWriteSet>>> {
+        None
+    }
+
+    /// Overrides the pending transactions feed configuration. Unlike in the config, the default is the public mode,
+    /// since most tests use `newPendingTransactions` subscriptions.
+    fn mempool_feed(&self) -> MempoolFeedConfig {
+        MempoolFeedConfig {
+            mode: MempoolFeedMode::Public,
+            ..MempoolFeedConfig::default()
+        }
+    }
 }
 
 async fn test_ws_server(test: impl WsTest) {
@@ -166,7 +184,8 @@ async fn test_ws_server(test: impl WsTest) {
     let network_config = NetworkConfig::for_tests();
     let contracts_config = ContractsConfig::for_tests();
     let web3_config = Web3JsonRpcConfig::for_tests();
-    let api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
+    let mut api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
+    api_config.mempool_feed = test.mempool_feed();
     let mut storage = pool.access_storage().await.unwrap();
     test.storage_initialization()
         .prepare_storage(&network_config, &mut storage)
@@ -180,6 +199,7 @@ async fn test_ws_server(test: impl WsTest) {
         pool.clone(),
         stop_receiver,
         test.websocket_requests_per_minute_limit(),
+        test.storage_write_sets(),
     )
     .await;
 
@@ -316,6 +336,87 @@ async fn basic_subscriptions_after_snapshot_recovery() {
     .await;
 }
 
+#[derive(Debug)]
+struct PrivateMempoolFeedTest;
+
+#[async_trait]
+impl WsTest for PrivateMempoolFeedTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        _pool: &ConnectionPool,
+        _pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        let params = rpc_params!["newPendingTransactions"];
+        let err = client
+            .subscribe::<H256, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(err) if err.message().contains("private"));
+        Ok(())
+    }
+
+    fn mempool_feed(&self) -> MempoolFeedConfig {
+        MempoolFeedConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn pending_transactions_subscription_in_private_mempool_feed_mode() {
+    test_ws_server(PrivateMempoolFeedTest).await;
+}
+
+#[derive(Debug)]
+struct RateLimitedMempoolFeedTest;
+
+#[async_trait]
+impl WsTest for RateLimitedMempoolFeedTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Txs]).await;
+        let params = rpc_params!["newPendingTransactions"];
+        let mut txs_subscription = client
+            .subscribe::<H256, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::Txs).await;
+
+        let tx_results: Vec<_> = (0..3)
+            .map(|_| execute_l2_transaction(create_l2_transaction(1, 2)))
+            .collect();
+        let tx_hashes: Vec<_> = tx_results.iter().map(|tx| tx.hash).collect();
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let received_tx_hash = tokio::time::timeout(TEST_TIMEOUT, txs_subscription.next())
+            .await
+            .context("Timed out waiting for new tx hash")?
+            .context("Pending txs subscription terminated")??;
+        assert!(tx_hashes.contains(&received_tx_hash));
+        // Other hashes should be dropped by the rate limiter.
+        let next_item = tokio::time::timeout(POLL_INTERVAL * 5, txs_subscription.next()).await;
+        assert!(next_item.is_err(), "{next_item:?}");
+        Ok(())
+    }
+
+    fn mempool_feed(&self) -> MempoolFeedConfig {
+        MempoolFeedConfig {
+            mode: MempoolFeedMode::Public,
+            delay: Duration::ZERO,
+            rate_limit: Some(NonZeroU32::new(1).unwrap()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn rate_limited_pending_transactions_subscription() {
+    test_ws_server(RateLimitedMempoolFeedTest).await;
+}
+
 #[derive(Debug)]
 struct LogSubscriptionsTest {
     snapshot_recovery: bool,
@@ -627,6 +728,292 @@ async fn log_subscriptions_with_delay() {
     test_ws_server(LogSubscriptionsWithDelayTest).await;
 }
 
+#[derive(Debug)]
+struct StorageSubscriptionTest;
+
+impl StorageSubscriptionTest {
+    const WATCHED_SLOT: StorageSlot = StorageSlot {
+        address: Address::repeat_byte(0x23),
+        slot: H256::repeat_byte(1),
+    };
+
+    async fn store_storage_writes(
+        storage: &mut StorageProcessor<'_>,
+        number: u32,
+        writes: &[(StorageSlot, H256)],
+    ) -> anyhow::Result<()> {
+        let number = MiniblockNumber(number);
+        store_miniblock(storage, number, &[]).await?;
+        let logs = writes
+            .iter()
+            .map(|(slot, value)| {
+                let key = StorageKey::new(AccountTreeId::new(slot.address), slot.slot);
+                StorageLog::new_write_log(key, *value)
+            })
+            .collect();
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(number, &[(H256::zero(), logs)])
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WsTest for StorageSubscriptionTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Storage]).await;
+
+        let err = client
+            .subscribe::<StorageChange, _>(
+                "zks_subscribeStorage",
+                rpc_params![Vec::<StorageSlot>::new()],
+                "zks_unsubscribeStorage",
+            )
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(_));
+
+        let params = rpc_params![[Self::WATCHED_SLOT]];
+        let mut subscription = client
+            .subscribe::<StorageChange, _>("zks_subscribeStorage", params, "zks_unsubscribeStorage")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::Storage).await;
+
+        let other_slot = StorageSlot {
+            address: Address::repeat_byte(0x23),
+            slot: H256::repeat_byte(2),
+        };
+        let mut storage = pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+        let writes = [
+            (Self::WATCHED_SLOT, H256::repeat_byte(0xaa)),
+            (other_slot, H256::repeat_byte(0xbb)),
+        ];
+        Self::store_storage_writes(&mut transaction, 1, &writes).await?;
+        // The value of the watched slot isn't changed, so there should be no notification.
+        let writes = [(Self::WATCHED_SLOT, H256::repeat_byte(0xaa))];
+        Self::store_storage_writes(&mut transaction, 2, &writes).await?;
+        let writes = [(Self::WATCHED_SLOT, H256::repeat_byte(0xcc))];
+        Self::store_storage_writes(&mut transaction, 3, &writes).await?;
+        transaction.commit().await?;
+        drop(storage);
+
+        let mut changes = vec![];
+        for _ in 0..2 {
+            let change = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+                .await
+                .context("Timed out waiting for storage change")?
+                .context("Storage subscription terminated")??;
+            changes.push(change);
+        }
+        assert_eq!(changes[0].storage_slot(), Self::WATCHED_SLOT);
+        assert_eq!(changes[0].value, H256::repeat_byte(0xaa));
+        assert_eq!(changes[0].block_number, 1.into());
+        assert_eq!(changes[1].storage_slot(), Self::WATCHED_SLOT);
+        assert_eq!(changes[1].value, H256::repeat_byte(0xcc));
+        assert_eq!(changes[1].block_number, 3.into());
+
+        wait_for_notifier_miniblock(
+            &mut pub_sub_events,
+            SubscriptionType::Storage,
+            MiniblockNumber(3),
+        )
+        .await;
+        tokio::time::timeout(POLL_INTERVAL, subscription.next())
+            .await
+            .unwrap_err();
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn storage_subscription() {
+    test_ws_server(StorageSubscriptionTest).await;
+}
+
+#[derive(Debug)]
+struct StorageSubscriptionWithWriteSetsTest {
+    write_sets: broadcast::Sender<Arc<MiniblockWriteSet>>,
+}
+
+impl StorageSubscriptionWithWriteSetsTest {
+    fn new() -> Self {
+        Self {
+            write_sets: broadcast::channel(16).0,
+        }
+    }
+
+    fn send_write_set(&self, number: u32, writes: &[(StorageSlot, H256)]) {
+        let writes = writes
+            .iter()
+            .map(|(slot, value)| {
+                let key = StorageKey::new(AccountTreeId::new(slot.address), slot.slot);
+                (key, *value)
+            })
+            .collect();
+        let write_set = MiniblockWriteSet {
+            number: MiniblockNumber(number),
+            writes,
+        };
+        self.write_sets.send(Arc::new(write_set)).unwrap();
+    }
+}
+
+#[async_trait]
+impl WsTest for StorageSubscriptionWithWriteSetsTest {
+    fn storage_write_sets(&self) -> Option<broadcast::Sender<Arc<MiniblockWriteSet>>> {
+        Some(self.write_sets.clone())
+    }
+
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        const WATCHED_SLOT: StorageSlot = StorageSubscriptionTest::WATCHED_SLOT;
+
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Storage]).await;
+        let params = rpc_params![[WATCHED_SLOT]];
+        let mut subscription = client
+            .subscribe::<StorageChange, _>("zks_subscribeStorage", params, "zks_unsubscribeStorage")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::Storage).await;
+
+        let other_slot = StorageSlot {
+            address: Address::repeat_byte(0x23),
+            slot: H256::repeat_byte(2),
+        };
+        let mut storage = pool.access_storage().await?;
+        let writes = [(WATCHED_SLOT, H256::repeat_byte(0xaa))];
+        StorageSubscriptionTest::store_storage_writes(&mut storage, 1, &writes).await?;
+        let writes = [
+            (WATCHED_SLOT, H256::repeat_byte(0xcc)),
+            (other_slot, H256::repeat_byte(0xbb)),
+        ];
+        StorageSubscriptionTest::store_storage_writes(&mut storage, 2, &writes).await?;
+        let writes = [(other_slot, H256::repeat_byte(0xdd))];
+        StorageSubscriptionTest::store_storage_writes(&mut storage, 3, &writes).await?;
+        drop(storage);
+
+        // The write set for miniblock #1 is skipped, so the notifier should load it from Postgres.
+        self.send_write_set(
+            2,
+            &[
+                (WATCHED_SLOT, H256::repeat_byte(0xcc)),
+                (other_slot, H256::repeat_byte(0xbb)),
+            ],
+        );
+        self.send_write_set(3, &[(other_slot, H256::repeat_byte(0xdd))]);
+
+        let mut changes = vec![];
+        for _ in 0..2 {
+            let change = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+                .await
+                .context("Timed out waiting for storage change")?
+                .context("Storage subscription terminated")??;
+            changes.push(change);
+        }
+        assert_eq!(changes[0].storage_slot(), WATCHED_SLOT);
+        assert_eq!(changes[0].value, H256::repeat_byte(0xaa));
+        assert_eq!(changes[0].block_number, 1.into());
+        assert_eq!(changes[1].storage_slot(), WATCHED_SLOT);
+        assert_eq!(changes[1].value, H256::repeat_byte(0xcc));
+        assert_eq!(changes[1].block_number, 2.into());
+
+        wait_for_notifier_miniblock(
+            &mut pub_sub_events,
+            SubscriptionType::Storage,
+            MiniblockNumber(3),
+        )
+        .await;
+        tokio::time::timeout(POLL_INTERVAL, subscription.next())
+            .await
+            .unwrap_err();
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn storage_subscription_with_write_sets() {
+    test_ws_server(StorageSubscriptionWithWriteSetsTest::new()).await;
+}
+
+#[derive(Debug)]
+struct ResumedSubscriptionTest;
+
+#[async_trait]
+impl WsTest for ResumedSubscriptionTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        // Emulate a subscription exported by another replica after processing the genesis miniblock.
+        let exported_subscription = ExportedSubscription {
+            id: "0x01".to_owned(),
+            params: serde_json::json!({ "type": "newHeads" }),
+            last_miniblock: Some(MiniblockNumber(0)),
+        };
+        let mut storage = pool.access_storage().await?;
+        storage
+            .exported_subscriptions_dal()
+            .insert_subscriptions(&[exported_subscription])
+            .await?;
+        let missed_miniblock = store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        drop(storage);
+
+        let params = rpc_params!["0x01"];
+        let mut subscription = client
+            .subscribe::<BlockHeader, _>("zks_resumeSubscription", params, "zks_unsubscribeResumed")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::Blocks).await;
+
+        let replayed_header = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for replayed block header")?
+            .context("Resumed subscription terminated")??;
+        assert_eq!(replayed_header.number, Some(1.into()));
+        assert_eq!(replayed_header.hash, Some(missed_miniblock.hash));
+
+        let mut storage = pool.access_storage().await?;
+        let new_miniblock = store_miniblock(&mut storage, MiniblockNumber(2), &[]).await?;
+        drop(storage);
+        let new_header = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for new block header")?
+            .context("Resumed subscription terminated")??;
+        assert_eq!(new_header.number, Some(2.into()));
+        assert_eq!(new_header.hash, Some(new_miniblock.hash));
+
+        // An exported subscription can only be resumed once.
+        let params = rpc_params!["0x01"];
+        let err = client
+            .subscribe::<BlockHeader, _>("zks_resumeSubscription", params, "zks_unsubscribeResumed")
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+        );
+
+        subscription.unsubscribe().await?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn resumed_subscription() {
+    test_ws_server(ResumedSubscriptionTest).await;
+}
+
 #[derive(Debug)]
 struct RateLimitingTest;
 
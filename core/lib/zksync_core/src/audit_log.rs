@@ -0,0 +1,53 @@
+//! Recording of privileged operations (block reverts, config reloads etc.) in the append-only audit log.
+
+use anyhow::Context as _;
+use serde::Serialize;
+use zksync_dal::ConnectionPool;
+use zksync_types::audit_log::AuditAction;
+
+/// Records privileged actions performed by a specific actor in the audit log.
+#[derive(Debug, Clone)]
+pub struct AuditLogger {
+    pool: ConnectionPool,
+    actor: String,
+}
+
+impl AuditLogger {
+    pub fn new(pool: ConnectionPool, actor: impl Into<String>) -> Self {
+        Self {
+            pool,
+            actor: actor.into(),
+        }
+    }
+
+    /// Returns the identity of the OS user running the current process, to be used as an actor for CLI tools.
+    pub fn local_actor() -> String {
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "unknown".to_owned());
+        match std::env::var("HOSTNAME") {
+            Ok(host) => format!("{user}@{host}"),
+            Err(_) => user,
+        }
+    }
+
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    /// Appends an entry for the specified action to the audit log.
+    pub async fn record(&self, action: AuditAction, params: impl Serialize) -> anyhow::Result<()> {
+        let params = serde_json::to_value(params).context("failed serializing params")?;
+        let mut storage = self.pool.access_storage_tagged("audit_log").await?;
+        let id = storage
+            .audit_log_dal()
+            .insert_entry(action, &self.actor, &params)
+            .await
+            .context("failed inserting audit log entry")?;
+        tracing::info!(
+            "Recorded audit log entry #{id}: `{action}` by `{}` with params {params}",
+            self.actor
+        );
+        Ok(())
+    }
+}
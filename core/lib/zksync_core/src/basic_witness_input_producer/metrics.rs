@@ -10,6 +10,11 @@ pub(crate) struct BasicWitnessInputProducerMetrics {
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub process_batch_time: Histogram<Duration>,
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub serialize_input_time: Histogram<Duration>,
+    /// Size of the serialized witness input.
+    #[metrics(buckets = Buckets::exponential(1_048_576.0..=68_719_476_736.0, 4.0), unit = Unit::Bytes)]
+    pub input_size: Histogram<usize>,
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub upload_input_time: Histogram<Duration>,
     pub block_number_processed: Gauge,
 }
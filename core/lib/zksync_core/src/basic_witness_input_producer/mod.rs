@@ -8,16 +8,21 @@ use vm_utils::{create_vm, execute_tx};
 use zksync_dal::{basic_witness_input_producer_dal::JOB_MAX_ATTEMPT, ConnectionPool};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
-use zksync_types::{witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId};
+use zksync_types::{L1BatchNumber, L2ChainId};
 
 use self::metrics::METRICS;
+pub use self::witness_input_file::WitnessInputFile;
 
 mod metrics;
+mod witness_input_file;
+
 /// Component that extracts all data (from DB) necessary to run a Basic Witness Generator.
 /// Does this by rerunning an entire L1Batch and extracting information from both the VM run and DB.
 /// This component will upload Witness Inputs to the object store.
 /// This allows Witness Generator workflow (that needs only Basic Witness Generator Inputs)
 /// to be run only using the object store information, having no other external dependency.
+/// To keep the memory footprint manageable for large L1 batches, inputs are serialized to temporary files
+/// rather than in memory.
 #[derive(Debug)]
 pub struct BasicWitnessInputProducer {
     connection_pool: ConnectionPool,
@@ -44,7 +49,7 @@ impl BasicWitnessInputProducer {
         started_at: Instant,
         connection_pool: ConnectionPool,
         l2_chain_id: L2ChainId,
-    ) -> anyhow::Result<WitnessBlockState> {
+    ) -> anyhow::Result<WitnessInputFile> {
         let mut connection = rt_handle
             .block_on(connection_pool.access_storage())
             .context("failed to get connection for BasicWitnessInputProducer")?;
@@ -99,8 +104,20 @@ impl BasicWitnessInputProducer {
             l1_batch_number.0
         );
 
-        let witness_block_state = (*storage_view).borrow().witness_block_state();
-        Ok(witness_block_state)
+        // Move the state out of the storage view and drop the VM so that only one copy of the state is kept in memory.
+        let witness_block_state = (*storage_view).borrow_mut().take_witness_block_state();
+        drop(vm);
+        drop(storage_view);
+
+        let serialization_started_at = Instant::now();
+        let witness_input = WitnessInputFile::new(&witness_block_state)
+            .context("failed serializing witness input for BasicWitnessInputProducer")?;
+        drop(witness_block_state);
+        METRICS
+            .serialize_input_time
+            .observe(serialization_started_at.elapsed());
+        METRICS.input_size.observe(witness_input.len() as usize);
+        Ok(witness_input)
     }
 }
 
@@ -108,7 +125,7 @@ impl BasicWitnessInputProducer {
 impl JobProcessor for BasicWitnessInputProducer {
     type Job = L1BatchNumber;
     type JobId = L1BatchNumber;
-    type JobArtifacts = WitnessBlockState;
+    type JobArtifacts = WitnessInputFile;
     const SERVICE_NAME: &'static str = "basic_witness_input_producer";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
@@ -164,9 +181,8 @@ impl JobProcessor for BasicWitnessInputProducer {
         artifacts: Self::JobArtifacts,
     ) -> anyhow::Result<()> {
         let upload_started_at = Instant::now();
-        let object_path = self
-            .object_store
-            .put(job_id, &artifacts)
+        let object_path = artifacts
+            .upload(&*self.object_store, job_id)
             .await
             .context("failed to upload artifacts for BasicWitnessInputProducer")?;
         METRICS
@@ -0,0 +1,102 @@
+//! Temporary on-disk storage for serialized witness inputs.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Seek, SeekFrom},
+};
+
+use anyhow::Context as _;
+use zksync_object_store::{bincode, ObjectStore, StoredObject};
+use zksync_types::{witness_block_state::WitnessBlockState, L1BatchNumber};
+
+/// Witness input serialized to an anonymous temporary file.
+///
+/// The input is serialized directly to disk instead of a `Vec<u8>`, so that the in-memory [`WitnessBlockState`]
+/// (and the VM that has produced it) can be dropped before the input is uploaded. This way, the input is never held
+/// in RAM in both deserialized and serialized forms. The file is created in the OS temporary directory
+/// (configurable via the `TMPDIR` env variable) and is removed once the value is dropped.
+#[derive(Debug)]
+pub struct WitnessInputFile {
+    file: File,
+    len: u64,
+}
+
+impl WitnessInputFile {
+    /// Serializes the state to a new temporary file. The serialization is streamed, i.e., the serialized state
+    /// is never buffered in memory as a whole.
+    pub(super) fn new(state: &WitnessBlockState) -> anyhow::Result<Self> {
+        let file = tempfile::tempfile().context("failed creating temporary file")?;
+        let mut writer = BufWriter::new(file);
+        bincode::serialize_into(&mut writer, state).context("failed serializing witness input")?;
+        let mut file = writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)
+            .context("failed flushing witness input to temporary file")?;
+        let len = file
+            .stream_position()
+            .context("failed getting temporary file length")?;
+        Ok(Self { file, len })
+    }
+
+    /// Returns the length of the serialized input in bytes.
+    pub(super) fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn read(mut self) -> io::Result<Vec<u8>> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut buffer = Vec::with_capacity(self.len as usize);
+        self.file.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Uploads the input to the object store and returns the object key. The uploaded object has the same format
+    /// as one produced by `ObjectStore::put()` for [`WitnessBlockState`].
+    pub(super) async fn upload(
+        self,
+        object_store: &dyn ObjectStore,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<String> {
+        let bytes = tokio::task::spawn_blocking(move || self.read())
+            .await
+            .context("panicked reading witness input from temporary file")?
+            .context("failed reading witness input from temporary file")?;
+        let key = WitnessBlockState::encode_key(l1_batch_number);
+        object_store
+            .put_raw(WitnessBlockState::BUCKET, &key, bytes)
+            .await?;
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::{AccountTreeId, Address, StorageKey, H256};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn uploading_witness_input_file() {
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let mut state = WitnessBlockState::default();
+        for i in 0..1_000 {
+            let key = StorageKey::new(account, H256::from_low_u64_be(i));
+            state
+                .read_storage_key
+                .insert(key, H256::from_low_u64_be(i + 1));
+            state.is_write_initial.insert(key, i % 2 == 0);
+        }
+
+        let file = WitnessInputFile::new(&state).unwrap();
+        assert_eq!(file.len(), bincode::serialized_size(&state).unwrap());
+
+        let object_store = ObjectStoreFactory::mock().create_store().await;
+        let key = file.upload(&*object_store, L1BatchNumber(1)).await.unwrap();
+        assert_eq!(key, WitnessBlockState::encode_key(L1BatchNumber(1)));
+
+        let loaded: WitnessBlockState = object_store.get(L1BatchNumber(1)).await.unwrap();
+        assert_eq!(loaded.read_storage_key, state.read_storage_key);
+        assert_eq!(loaded.is_write_initial, state.is_write_initial);
+    }
+}
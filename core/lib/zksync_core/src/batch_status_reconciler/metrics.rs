@@ -0,0 +1,41 @@
+//! Metrics for the batch status reconciler.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use zksync_types::aggregated_operations::AggregatedActionType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(super) enum BatchStage {
+    Committed,
+    Proven,
+    Executed,
+}
+
+impl From<AggregatedActionType> for BatchStage {
+    fn from(ty: AggregatedActionType) -> Self {
+        match ty {
+            AggregatedActionType::Commit => Self::Committed,
+            AggregatedActionType::PublishProofOnchain => Self::Proven,
+            AggregatedActionType::Execute => Self::Executed,
+        }
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_batch_status_reconciler")]
+pub(super) struct BatchStatusReconcilerMetrics {
+    /// Latency of a single reconciliation iteration.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub iteration_latency: Histogram<Duration>,
+    /// Number of L1 batch statuses corrected according to L1.
+    pub corrections: Family<BatchStage, Counter>,
+    /// Number of L1 batches marked locally with a status that is not confirmed by L1.
+    pub unconfirmed_local_statuses: Family<BatchStage, Gauge<u64>>,
+    /// Last L1 block processed by the reconciler.
+    pub last_checked_l1_block: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<BatchStatusReconcilerMetrics> = vise::Global::new();
@@ -0,0 +1,523 @@
+//! Reconciliation of L1 batch statuses (committed / proven / executed) against L1.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, ops,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use tokio::sync::watch;
+use zksync_contracts::zksync_contract;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::{Error as EthClientError, EthInterface};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    ethabi::{self, ParamType},
+    web3::types::{BlockId, BlockNumber, FilterBuilder, Log},
+    Address, L1BatchNumber, H256, U256,
+};
+
+use self::metrics::{BatchStage, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+const COMPONENT: &str = "batch_status_reconciler";
+
+const ACTIONS: [AggregatedActionType; 3] = [
+    AggregatedActionType::Commit,
+    AggregatedActionType::PublishProofOnchain,
+    AggregatedActionType::Execute,
+];
+
+fn action_str(action: AggregatedActionType) -> &'static str {
+    match action {
+        AggregatedActionType::Commit => "committed",
+        AggregatedActionType::PublishProofOnchain => "proven",
+        AggregatedActionType::Execute => "executed",
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum ReconcilerError {
+    #[error("Error communicating with L1")]
+    EthClient(#[from] EthClientError),
+    #[error("Internal error")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<zksync_dal::SqlxError> for ReconcilerError {
+    fn from(err: zksync_dal::SqlxError) -> Self {
+        Self::Internal(err.into())
+    }
+}
+
+/// Signatures of the diamond proxy events changing L1 batch statuses.
+#[derive(Debug, Clone)]
+struct EventSignatures {
+    commit: H256,
+    verification: H256,
+    execution: H256,
+    revert: H256,
+}
+
+impl EventSignatures {
+    fn new() -> Self {
+        let contract = zksync_contract();
+        let signature = |name: &str| {
+            contract
+                .event(name)
+                .unwrap_or_else(|_| panic!("{name} event is missing in abi"))
+                .signature()
+        };
+        Self {
+            commit: signature("BlockCommit"),
+            verification: signature("BlocksVerification"),
+            execution: signature("BlockExecution"),
+            revert: signature("BlocksRevert"),
+        }
+    }
+
+    fn topics(&self) -> Vec<H256> {
+        vec![self.commit, self.verification, self.execution, self.revert]
+    }
+}
+
+/// L1 client used by the reconciler.
+#[async_trait]
+trait L1BatchEventsClient: fmt::Debug + Send + Sync {
+    /// Returns the number of the last L1 block that has enough confirmations for its events to be trusted.
+    async fn confirmed_block_number(&self) -> Result<u64, EthClientError>;
+
+    /// Returns diamond proxy events with the specified topics emitted in the inclusive L1 block range.
+    async fn batch_events(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        topics: Vec<H256>,
+    ) -> Result<Vec<Log>, EthClientError>;
+
+    /// Returns the timestamp of the specified L1 block, or `None` if the block is not known.
+    async fn block_timestamp(&self, block_number: u64) -> Result<Option<u64>, EthClientError>;
+}
+
+#[derive(Debug)]
+struct L1Client {
+    eth_client: Arc<dyn EthInterface>,
+    diamond_proxy_addr: Address,
+    confirmations: u64,
+}
+
+#[async_trait]
+impl L1BatchEventsClient for L1Client {
+    async fn confirmed_block_number(&self) -> Result<u64, EthClientError> {
+        let latest_block = self.eth_client.block_number(COMPONENT).await?.as_u64();
+        Ok(latest_block.saturating_sub(self.confirmations))
+    }
+
+    async fn batch_events(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        topics: Vec<H256>,
+    ) -> Result<Vec<Log>, EthClientError> {
+        let filter = FilterBuilder::default()
+            .address(vec![self.diamond_proxy_addr])
+            .from_block(BlockNumber::Number(from_block.into()))
+            .to_block(BlockNumber::Number(to_block.into()))
+            .topics(Some(topics), None, None, None)
+            .build();
+        self.eth_client.logs(filter, COMPONENT).await
+    }
+
+    async fn block_timestamp(&self, block_number: u64) -> Result<Option<u64>, EthClientError> {
+        let block_id = BlockId::Number(BlockNumber::Number(block_number.into()));
+        let block = self.eth_client.block(block_id, COMPONENT).await?;
+        Ok(block.map(|block| block.timestamp.as_u64()))
+    }
+}
+
+/// L1 transaction that has changed the status of an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct L1Action {
+    tx_hash: H256,
+    l1_block_number: u64,
+}
+
+/// Statuses of L1 batches for a single stage (e.g., commitment) derived from L1 events.
+#[derive(Debug, Default)]
+struct StageStatuses {
+    actions: BTreeMap<L1BatchNumber, L1Action>,
+    /// Range of L1 batches mentioned in events for this stage, including reverted batches.
+    observed: Option<ops::RangeInclusive<L1BatchNumber>>,
+}
+
+impl StageStatuses {
+    fn insert(&mut self, number: L1BatchNumber, action: L1Action) {
+        self.actions.insert(number, action);
+        self.observed = Some(match self.observed.take() {
+            Some(range) => (*range.start()).min(number)..=(*range.end()).max(number),
+            None => number..=number,
+        });
+    }
+
+    fn revert(&mut self, last_retained: L1BatchNumber) {
+        self.actions.retain(|&number, _| number <= last_retained);
+    }
+}
+
+/// Statuses of recent L1 batches derived purely from L1 events.
+#[derive(Debug, Default)]
+struct L1BatchStatuses {
+    commit: StageStatuses,
+    prove: StageStatuses,
+    execute: StageStatuses,
+}
+
+impl L1BatchStatuses {
+    fn stage(&self, action: AggregatedActionType) -> &StageStatuses {
+        match action {
+            AggregatedActionType::Commit => &self.commit,
+            AggregatedActionType::PublishProofOnchain => &self.prove,
+            AggregatedActionType::Execute => &self.execute,
+        }
+    }
+
+    /// Returns the range of all L1 batches mentioned in the processed events.
+    fn observed_range(&self) -> Option<ops::RangeInclusive<L1BatchNumber>> {
+        ACTIONS
+            .into_iter()
+            .filter_map(|action| self.stage(action).observed.clone())
+            .reduce(|acc, range| {
+                (*acc.start()).min(*range.start())..=(*acc.end()).max(*range.end())
+            })
+    }
+
+    /// Applies events to the statuses. Events must be newer than all events processed previously.
+    fn extend(&mut self, signatures: &EventSignatures, mut logs: Vec<Log>) -> anyhow::Result<()> {
+        logs.retain(|log| log.removed != Some(true));
+        logs.sort_unstable_by_key(|log| (log.block_number, log.log_index));
+        for log in &logs {
+            self.apply_log(signatures, log)
+                .with_context(|| format!("failed processing log {log:?}"))?;
+        }
+        Ok(())
+    }
+
+    fn apply_log(&mut self, signatures: &EventSignatures, log: &Log) -> anyhow::Result<()> {
+        let signature = *log.topics.first().context("log has no topics")?;
+        let action = L1Action {
+            tx_hash: log.transaction_hash.context("log has no tx hash")?,
+            l1_block_number: log
+                .block_number
+                .context("log has no block number")?
+                .as_u64(),
+        };
+
+        if signature == signatures.commit {
+            self.commit.insert(l1_batch_from_topic(log, 1)?, action);
+        } else if signature == signatures.verification {
+            let previous_last_verified = l1_batch_from_topic(log, 1)?;
+            let current_last_verified = l1_batch_from_topic(log, 2)?;
+            for number in previous_last_verified.0 + 1..=current_last_verified.0 {
+                self.prove.insert(L1BatchNumber(number), action);
+            }
+        } else if signature == signatures.execution {
+            self.execute.insert(l1_batch_from_topic(log, 1)?, action);
+        } else if signature == signatures.revert {
+            let tokens = ethabi::decode(&[ParamType::Uint(256); 3], &log.data.0)
+                .context("failed decoding `BlocksRevert` event")?;
+            let [total_committed, total_verified, _] = tokens.as_slice() else {
+                unreachable!("decoded 3 tokens");
+            };
+            let total_committed = total_committed.clone().into_uint().context("not a uint")?;
+            let total_verified = total_verified.clone().into_uint().context("not a uint")?;
+            self.commit.revert(u256_to_l1_batch(total_committed)?);
+            self.prove.revert(u256_to_l1_batch(total_verified)?);
+        }
+        Ok(())
+    }
+}
+
+fn l1_batch_from_topic(log: &Log, index: usize) -> anyhow::Result<L1BatchNumber> {
+    let topic = log
+        .topics
+        .get(index)
+        .with_context(|| format!("log has no topic #{index}"))?;
+    u256_to_l1_batch(U256::from_big_endian(topic.as_bytes()))
+}
+
+fn u256_to_l1_batch(value: U256) -> anyhow::Result<L1BatchNumber> {
+    anyhow::ensure!(
+        value <= U256::from(u32::MAX),
+        "L1 batch number {value} is out of range"
+    );
+    Ok(L1BatchNumber(value.as_u32()))
+}
+
+/// Correction of the local status of an L1 batch according to L1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StatusCorrection {
+    number: L1BatchNumber,
+    action: AggregatedActionType,
+    local_tx_hash: Option<H256>,
+    l1_action: L1Action,
+}
+
+/// Outcome of a single reconciliation iteration.
+#[derive(Debug, Default)]
+struct Reconciliation {
+    corrections: Vec<StatusCorrection>,
+    /// L1 batches which have a local status that is not confirmed by L1 (e.g., because the batches were reverted
+    /// on L1). Such statuses are only reported since fixing them requires reverting the node state.
+    unconfirmed: Vec<(L1BatchNumber, AggregatedActionType)>,
+}
+
+/// Component periodically re-deriving commit / prove / execute statuses of recent L1 batches purely from
+/// the diamond proxy events on L1 and reconciling the local statuses with them.
+///
+/// If the local status has drifted (e.g., after a manual intervention into `eth_txs`), the status
+/// is corrected by tying the L1 batch to a confirmed L1 transaction with the hash observed on L1, similarly
+/// to how the external node persists statuses fetched from the main node. Every correction is logged.
+/// Local statuses that are not confirmed by L1 at all are only reported via logs and metrics.
+#[derive(Debug)]
+pub struct BatchStatusReconciler {
+    client: Box<dyn L1BatchEventsClient>,
+    signatures: EventSignatures,
+    pool: ConnectionPool,
+    poll_interval: Duration,
+    lookback_l1_blocks: u64,
+}
+
+impl BatchStatusReconciler {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(300);
+    /// Approximately 1 week of Ethereum blocks.
+    const DEFAULT_LOOKBACK_L1_BLOCKS: u64 = 50_000;
+    /// Maximum L1 block range queried for events in a single request.
+    const MAX_BLOCK_RANGE: u64 = 10_000;
+
+    pub fn new(
+        eth_client: Arc<dyn EthInterface>,
+        diamond_proxy_addr: Address,
+        confirmations: u64,
+        pool: ConnectionPool,
+    ) -> Self {
+        let client = L1Client {
+            eth_client,
+            diamond_proxy_addr,
+            confirmations,
+        };
+        Self::from_parts(
+            Box::new(client),
+            pool,
+            Self::DEFAULT_POLL_INTERVAL,
+            Self::DEFAULT_LOOKBACK_L1_BLOCKS,
+        )
+    }
+
+    fn from_parts(
+        client: Box<dyn L1BatchEventsClient>,
+        pool: ConnectionPool,
+        poll_interval: Duration,
+        lookback_l1_blocks: u64,
+    ) -> Self {
+        Self {
+            client,
+            signatures: EventSignatures::new(),
+            pool,
+            poll_interval,
+            lookback_l1_blocks,
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, batch status reconciler is shutting down");
+                break;
+            }
+
+            match self.run_iteration().await {
+                Ok(_) => { /* everything went smoothly */ }
+                Err(ReconcilerError::EthClient(err)) => {
+                    tracing::warn!("Failed reconciling L1 batch statuses: {err}");
+                }
+                Err(ReconcilerError::Internal(err)) => return Err(err),
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+
+    async fn run_iteration(&self) -> Result<Reconciliation, ReconcilerError> {
+        let latency = METRICS.iteration_latency.start();
+        let (l1_statuses, last_l1_block) = self.l1_statuses().await?;
+        let reconciliation = self.reconcile(&l1_statuses).await?;
+
+        for action in ACTIONS {
+            let unconfirmed_count = reconciliation
+                .unconfirmed
+                .iter()
+                .filter(|(_, unconfirmed_action)| *unconfirmed_action == action)
+                .count();
+            METRICS.unconfirmed_local_statuses[&action.into()].set(unconfirmed_count as u64);
+        }
+        for (number, action) in &reconciliation.unconfirmed {
+            tracing::warn!(
+                "L1 batch #{number} is locally marked as {}, but this is not confirmed by L1 events; \
+                 the batch may have been reverted on L1",
+                action_str(*action)
+            );
+        }
+
+        self.apply_corrections(&reconciliation.corrections).await?;
+        METRICS.last_checked_l1_block.set(last_l1_block);
+        latency.observe();
+        Ok(reconciliation)
+    }
+
+    /// Derives L1 batch statuses from events in the recent L1 blocks. Returns the statuses together with
+    /// the last checked L1 block.
+    async fn l1_statuses(&self) -> Result<(L1BatchStatuses, u64), ReconcilerError> {
+        let to_block = self.client.confirmed_block_number().await?;
+        let from_block = to_block.saturating_sub(self.lookback_l1_blocks);
+
+        let mut statuses = L1BatchStatuses::default();
+        let mut chunk_start = from_block;
+        while chunk_start <= to_block {
+            let chunk_end = (chunk_start + Self::MAX_BLOCK_RANGE - 1).min(to_block);
+            let logs = self
+                .client
+                .batch_events(chunk_start, chunk_end, self.signatures.topics())
+                .await?;
+            statuses.extend(&self.signatures, logs)?;
+            chunk_start = chunk_end + 1;
+        }
+        Ok((statuses, to_block))
+    }
+
+    async fn reconcile(
+        &self,
+        l1_statuses: &L1BatchStatuses,
+    ) -> Result<Reconciliation, ReconcilerError> {
+        let Some(numbers) = l1_statuses.observed_range() else {
+            return Ok(Reconciliation::default());
+        };
+        let mut storage = self.pool.access_storage_tagged(COMPONENT).await?;
+        let local_hashes = storage
+            .blocks_dal()
+            .get_l1_batch_eth_tx_hashes(numbers)
+            .await?;
+
+        let mut reconciliation = Reconciliation::default();
+        for action in ACTIONS {
+            let stage = l1_statuses.stage(action);
+            for (&number, &l1_action) in &stage.actions {
+                // L1 batches missing locally (e.g., because the node is still syncing) are skipped.
+                let Some(local_hashes) = local_hashes.get(&number) else {
+                    continue;
+                };
+                let local_tx_hash = local_hashes.get(action);
+                if local_tx_hash == Some(l1_action.tx_hash) {
+                    continue;
+                }
+                let is_pending = storage
+                    .eth_sender_dal()
+                    .is_tx_pending_confirmation(l1_action.tx_hash)
+                    .await?;
+                if is_pending {
+                    // The transaction was sent by this node and will be confirmed by `eth_tx_manager`.
+                    continue;
+                }
+                reconciliation.corrections.push(StatusCorrection {
+                    number,
+                    action,
+                    local_tx_hash,
+                    l1_action,
+                });
+            }
+
+            let Some(observed) = &stage.observed else {
+                continue;
+            };
+            for number in observed.start().0..=observed.end().0 {
+                let number = L1BatchNumber(number);
+                let has_local_status = local_hashes
+                    .get(&number)
+                    .map_or(false, |hashes| hashes.get(action).is_some());
+                if has_local_status && !stage.actions.contains_key(&number) {
+                    reconciliation.unconfirmed.push((number, action));
+                }
+            }
+        }
+        Ok(reconciliation)
+    }
+
+    async fn apply_corrections(
+        &self,
+        corrections: &[StatusCorrection],
+    ) -> Result<(), ReconcilerError> {
+        if corrections.is_empty() {
+            return Ok(());
+        }
+
+        // Fetch L1 block timestamps in advance, so that no L1 requests are made while holding a DB transaction.
+        let mut timestamps = HashMap::new();
+        for correction in corrections {
+            let block_number = correction.l1_action.l1_block_number;
+            if timestamps.contains_key(&block_number) {
+                continue;
+            }
+            let timestamp = self
+                .client
+                .block_timestamp(block_number)
+                .await?
+                .with_context(|| format!("L1 block #{block_number} is missing"))?;
+            let timestamp = Utc
+                .timestamp_opt(timestamp as i64, 0)
+                .single()
+                .with_context(|| {
+                    format!("timestamp of L1 block #{block_number} is out of range")
+                })?;
+            timestamps.insert(block_number, timestamp);
+        }
+
+        let mut storage = self.pool.access_storage_tagged(COMPONENT).await?;
+        let mut transaction = storage.start_transaction().await?;
+        for correction in corrections {
+            let StatusCorrection {
+                number,
+                action,
+                local_tx_hash,
+                l1_action,
+            } = *correction;
+            tracing::warn!(
+                "Correcting {} status of L1 batch #{number} according to L1: local tx {local_tx_hash:?}, \
+                 L1 tx {:?} (L1 block #{})",
+                action_str(action),
+                l1_action.tx_hash,
+                l1_action.l1_block_number
+            );
+            transaction
+                .eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    number,
+                    action,
+                    l1_action.tx_hash,
+                    timestamps[&l1_action.l1_block_number],
+                )
+                .await?;
+            METRICS.corrections[&BatchStage::from(action)].inc();
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+//! Tests for the batch status reconciler.
+
+use zksync_dal::StorageProcessor;
+use zksync_types::{ethabi::Token, ProtocolVersion};
+
+use super::*;
+use crate::utils::testonly::create_l1_batch;
+
+const L1_BLOCK_TIME: u64 = 12;
+
+#[derive(Debug, Default)]
+struct MockL1Client {
+    logs: Vec<Log>,
+    last_block: u64,
+}
+
+impl MockL1Client {
+    fn push_log(&mut self, signature: H256, topics: &[H256], data: Vec<u8>, tx_hash: H256) {
+        self.last_block += 1;
+        self.logs.push(Log {
+            address: Address::repeat_byte(0x1),
+            topics: [signature]
+                .into_iter()
+                .chain(topics.iter().copied())
+                .collect(),
+            data: data.into(),
+            block_hash: Some(H256::repeat_byte(0x11)),
+            block_number: Some(self.last_block.into()),
+            transaction_hash: Some(tx_hash),
+            transaction_index: Some(0_u64.into()),
+            log_index: Some(0_u64.into()),
+            transaction_log_index: Some(0_u64.into()),
+            log_type: None,
+            removed: None,
+        });
+    }
+
+    fn commit(&mut self, signatures: &EventSignatures, number: u32, tx_hash: H256) {
+        let topics = [batch_topic(number), H256::zero(), H256::zero()];
+        self.push_log(signatures.commit, &topics, vec![], tx_hash);
+    }
+
+    fn prove(&mut self, signatures: &EventSignatures, numbers: ops::Range<u32>, tx_hash: H256) {
+        let topics = [batch_topic(numbers.start), batch_topic(numbers.end)];
+        self.push_log(signatures.verification, &topics, vec![], tx_hash);
+    }
+
+    fn execute(&mut self, signatures: &EventSignatures, number: u32, tx_hash: H256) {
+        let topics = [batch_topic(number), H256::zero(), H256::zero()];
+        self.push_log(signatures.execution, &topics, vec![], tx_hash);
+    }
+
+    fn revert(&mut self, signatures: &EventSignatures, committed: u32, verified: u32) {
+        let data = ethabi::encode(&[
+            Token::Uint(committed.into()),
+            Token::Uint(verified.into()),
+            Token::Uint(0.into()),
+        ]);
+        self.push_log(signatures.revert, &[], data, H256::random());
+    }
+}
+
+#[async_trait]
+impl L1BatchEventsClient for MockL1Client {
+    async fn confirmed_block_number(&self) -> Result<u64, EthClientError> {
+        Ok(self.last_block)
+    }
+
+    async fn batch_events(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        topics: Vec<H256>,
+    ) -> Result<Vec<Log>, EthClientError> {
+        let logs = self.logs.iter().filter(|log| {
+            let block_number = log.block_number.unwrap().as_u64();
+            (from_block..=to_block).contains(&block_number) && topics.contains(&log.topics[0])
+        });
+        Ok(logs.cloned().collect())
+    }
+
+    async fn block_timestamp(&self, block_number: u64) -> Result<Option<u64>, EthClientError> {
+        Ok((block_number <= self.last_block).then_some(block_number * L1_BLOCK_TIME))
+    }
+}
+
+fn batch_topic(number: u32) -> H256 {
+    H256::from_low_u64_be(number.into())
+}
+
+fn tx_hash(action: AggregatedActionType, number: u32) -> H256 {
+    let action_byte = match action {
+        AggregatedActionType::Commit => 1,
+        AggregatedActionType::PublishProofOnchain => 2,
+        AggregatedActionType::Execute => 3,
+    };
+    let mut hash = H256::from_low_u64_be(number.into());
+    hash.0[0] = action_byte;
+    hash
+}
+
+async fn seal_l1_batches(storage: &mut StorageProcessor<'_>, count: u32) {
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    for number in 1..=count {
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number))
+            .await
+            .unwrap();
+    }
+}
+
+async fn set_local_status(
+    storage: &mut StorageProcessor<'_>,
+    number: u32,
+    action: AggregatedActionType,
+    tx_hash: H256,
+) {
+    storage
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(L1BatchNumber(number), action, tx_hash, Utc::now())
+        .await
+        .unwrap();
+}
+
+#[test]
+fn deriving_statuses_from_events() {
+    let signatures = EventSignatures::new();
+    let mut client = MockL1Client::default();
+    for number in 1..=3 {
+        client.commit(
+            &signatures,
+            number,
+            tx_hash(AggregatedActionType::Commit, number),
+        );
+    }
+    client.prove(&signatures, 0..2, H256::repeat_byte(2));
+    client.execute(&signatures, 1, H256::repeat_byte(3));
+    client.revert(&signatures, 2, 2);
+    client.commit(&signatures, 3, H256::repeat_byte(0xff));
+
+    let mut statuses = L1BatchStatuses::default();
+    // Logs in a chunk are not necessarily sorted.
+    statuses
+        .extend(&signatures, client.logs.into_iter().rev().collect())
+        .unwrap();
+
+    let committed: Vec<_> = statuses
+        .commit
+        .actions
+        .keys()
+        .map(|number| number.0)
+        .collect();
+    assert_eq!(committed, [1, 2, 3]);
+    assert_eq!(
+        statuses.commit.actions[&L1BatchNumber(3)].tx_hash,
+        H256::repeat_byte(0xff)
+    );
+    let proven: Vec<_> = statuses
+        .prove
+        .actions
+        .keys()
+        .map(|number| number.0)
+        .collect();
+    assert_eq!(proven, [1, 2]);
+    assert_eq!(statuses.prove.actions[&L1BatchNumber(2)].l1_block_number, 4);
+    let executed: Vec<_> = statuses
+        .execute
+        .actions
+        .keys()
+        .map(|number| number.0)
+        .collect();
+    assert_eq!(executed, [1]);
+    assert_eq!(
+        statuses.observed_range(),
+        Some(L1BatchNumber(1)..=L1BatchNumber(3))
+    );
+}
+
+#[tokio::test]
+async fn correcting_drifted_statuses() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    seal_l1_batches(&mut storage, 3).await;
+    set_local_status(
+        &mut storage,
+        1,
+        AggregatedActionType::Commit,
+        tx_hash(AggregatedActionType::Commit, 1),
+    )
+    .await;
+    // Status that has drifted, e.g. because of a manual intervention.
+    set_local_status(
+        &mut storage,
+        2,
+        AggregatedActionType::Commit,
+        H256::repeat_byte(0xff),
+    )
+    .await;
+
+    let signatures = EventSignatures::new();
+    let mut client = MockL1Client::default();
+    for number in 1..=3 {
+        client.commit(
+            &signatures,
+            number,
+            tx_hash(AggregatedActionType::Commit, number),
+        );
+    }
+    client.prove(
+        &signatures,
+        0..2,
+        tx_hash(AggregatedActionType::PublishProofOnchain, 2),
+    );
+    client.execute(&signatures, 1, tx_hash(AggregatedActionType::Execute, 1));
+
+    let reconciler =
+        BatchStatusReconciler::from_parts(Box::new(client), pool.clone(), Duration::ZERO, 1_000);
+    let reconciliation = reconciler.run_iteration().await.unwrap();
+    let corrected: Vec<_> = reconciliation
+        .corrections
+        .iter()
+        .map(|correction| (correction.number.0, correction.action))
+        .collect();
+    assert_eq!(
+        corrected,
+        [
+            (2, AggregatedActionType::Commit),
+            (3, AggregatedActionType::Commit),
+            (1, AggregatedActionType::PublishProofOnchain),
+            (2, AggregatedActionType::PublishProofOnchain),
+            (1, AggregatedActionType::Execute),
+        ]
+    );
+    assert_eq!(
+        reconciliation.corrections[0].local_tx_hash,
+        Some(H256::repeat_byte(0xff))
+    );
+    assert!(reconciliation.unconfirmed.is_empty());
+
+    let local_hashes = storage
+        .blocks_dal()
+        .get_l1_batch_eth_tx_hashes(L1BatchNumber(1)..=L1BatchNumber(3))
+        .await
+        .unwrap();
+    for number in 1..=3 {
+        let hashes = local_hashes[&L1BatchNumber(number)];
+        assert_eq!(
+            hashes.commit_tx_hash,
+            Some(tx_hash(AggregatedActionType::Commit, number))
+        );
+        let expected_prove_tx_hash =
+            (number <= 2).then(|| tx_hash(AggregatedActionType::PublishProofOnchain, 2));
+        assert_eq!(hashes.prove_tx_hash, expected_prove_tx_hash);
+        let expected_execute_tx_hash =
+            (number == 1).then(|| tx_hash(AggregatedActionType::Execute, 1));
+        assert_eq!(hashes.execute_tx_hash, expected_execute_tx_hash);
+    }
+
+    // Statuses are reconciled, so the repeated iteration should be a no-op.
+    let reconciliation = reconciler.run_iteration().await.unwrap();
+    assert!(reconciliation.corrections.is_empty());
+}
+
+#[tokio::test]
+async fn reporting_statuses_unconfirmed_by_l1() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    seal_l1_batches(&mut storage, 3).await;
+    for number in 1..=3 {
+        set_local_status(
+            &mut storage,
+            number,
+            AggregatedActionType::Commit,
+            tx_hash(AggregatedActionType::Commit, number),
+        )
+        .await;
+    }
+
+    let signatures = EventSignatures::new();
+    let mut client = MockL1Client::default();
+    for number in 1..=3 {
+        client.commit(
+            &signatures,
+            number,
+            tx_hash(AggregatedActionType::Commit, number),
+        );
+    }
+    client.revert(&signatures, 2, 0);
+
+    let reconciler =
+        BatchStatusReconciler::from_parts(Box::new(client), pool.clone(), Duration::ZERO, 1_000);
+    let reconciliation = reconciler.run_iteration().await.unwrap();
+    assert!(reconciliation.corrections.is_empty());
+    assert_eq!(
+        reconciliation.unconfirmed,
+        [(L1BatchNumber(3), AggregatedActionType::Commit)]
+    );
+
+    // The unconfirmed status must not be touched.
+    let local_hashes = storage
+        .blocks_dal()
+        .get_l1_batch_eth_tx_hashes(L1BatchNumber(3)..=L1BatchNumber(3))
+        .await
+        .unwrap();
+    assert_eq!(
+        local_hashes[&L1BatchNumber(3)].commit_tx_hash,
+        Some(tx_hash(AggregatedActionType::Commit, 3))
+    );
+}
@@ -1,17 +1,21 @@
-use std::{path::Path, time::Duration};
+use std::{collections::BTreeMap, path::Path, sync::Arc, time::Duration};
 
 use bitflags::bitflags;
 use serde::Serialize;
+use serde_json::json;
 use tokio::time::sleep;
 use zksync_config::{ContractsConfig, ETHSenderConfig};
 use zksync_contracts::zksync_contract;
 use zksync_dal::ConnectionPool;
 use zksync_eth_signer::{EthereumSigner, PrivateKeySigner, TransactionParameters};
 use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, RetentionPolicy};
+use zksync_prover_interface::{inputs::PrepareBasicCircuitsJob, outputs::L1BatchProofForL1};
 use zksync_state::RocksdbStorage;
 use zksync_storage::RocksDB;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
+    audit_log::AuditAction,
     ethabi::Token,
     web3::{
         contract::{Contract, Options},
@@ -19,14 +23,45 @@ use zksync_types::{
         types::{BlockId, BlockNumber},
         Web3,
     },
+    witness_block_state::WitnessBlockState,
     L1BatchNumber, PackedEthSignature, H160, H256, U256,
 };
 
+use crate::audit_log::AuditLogger;
+
 bitflags! {
     pub struct BlockReverterFlags: u32 {
         const POSTGRES = 0b_0001;
         const TREE = 0b_0010;
         const SK_CACHE = 0b_0100;
+        const OBJECT_STORE = 0b_1000;
+        const PROVER_JOBS = 0b_0001_0000;
+        const ETH_TXS = 0b_0010_0000;
+        /// Storage managed by the node itself: Postgres, the Merkle tree and the state keeper cache.
+        const NODE_STORAGE = Self::POSTGRES.bits | Self::TREE.bits | Self::SK_CACHE.bits;
+    }
+}
+
+/// Returns policies for object store artifacts keyed by the L1 batch number, which are removed
+/// when reverting the object store. The retention period is irrelevant for reverts.
+fn l1_batch_artifacts() -> [RetentionPolicy; 3] {
+    [
+        RetentionPolicy::new::<WitnessBlockState>("witness_inputs", Duration::ZERO),
+        RetentionPolicy::new::<PrepareBasicCircuitsJob>("merkle_paths", Duration::ZERO),
+        RetentionPolicy::new::<L1BatchProofForL1>("proofs", Duration::ZERO),
+    ]
+}
+
+/// Removes an object from the store. Returns `false` if the object didn't exist.
+async fn remove_object(
+    object_store: &dyn ObjectStore,
+    bucket: Bucket,
+    key: &str,
+) -> Result<bool, ObjectStoreError> {
+    match object_store.remove_raw(bucket, key).await {
+        Ok(()) => Ok(true),
+        Err(ObjectStoreError::KeyNotFound(_)) => Ok(false),
+        Err(err) => Err(err),
     }
 }
 
@@ -84,7 +119,16 @@ impl BlockReverterEthConfig {
 /// - State of the Postgres database
 /// - State of the merkle tree
 /// - State of the state_keeper cache
+/// - Object store artifacts (witness inputs, circuits and proofs)
+/// - Prover jobs
+/// - Unconfirmed L1 transactions
 /// - State of the Ethereum contract (if the block was committed)
+///
+/// Postgres is always reverted last, and reverting each component is idempotent, so an interrupted revert
+/// can be resumed by running it again with the same params. Before reverting, the impact of the revert
+/// can be estimated with [`Self::impact_report()`].
+///
+/// All reverts are recorded in the audit log.
 #[derive(Debug)]
 pub struct BlockReverter {
     state_keeper_cache_path: String,
@@ -92,6 +136,10 @@ pub struct BlockReverter {
     eth_config: Option<BlockReverterEthConfig>,
     connection_pool: ConnectionPool,
     executed_batches_revert_mode: L1ExecutedBatchesRevert,
+    audit_log: AuditLogger,
+    object_store: Option<Arc<dyn ObjectStore>>,
+    prover_connection_pool: Option<ConnectionPool>,
+    prover_object_store: Option<Arc<dyn ObjectStore>>,
 }
 
 impl BlockReverter {
@@ -106,20 +154,58 @@ impl BlockReverter {
             state_keeper_cache_path,
             merkle_tree_path,
             eth_config,
+            audit_log: AuditLogger::new(connection_pool.clone(), AuditLogger::local_actor()),
             connection_pool,
             executed_batches_revert_mode,
+            object_store: None,
+            prover_connection_pool: None,
+            prover_object_store: None,
         }
     }
 
+    /// Sets the object store with artifacts for L1 batches. Required to revert [`BlockReverterFlags::OBJECT_STORE`].
+    pub fn with_object_store(mut self, object_store: Arc<dyn ObjectStore>) -> Self {
+        self.object_store = Some(object_store);
+        self
+    }
+
+    /// Sets the connection pool for the prover DB. Required to revert [`BlockReverterFlags::PROVER_JOBS`].
+    pub fn with_prover_connection_pool(mut self, pool: ConnectionPool) -> Self {
+        self.prover_connection_pool = Some(pool);
+        self
+    }
+
+    /// Sets the object store with prover artifacts. If set together with the prover DB connection pool,
+    /// circuits and proofs of prover jobs are removed when reverting [`BlockReverterFlags::OBJECT_STORE`].
+    pub fn with_prover_object_store(mut self, object_store: Arc<dyn ObjectStore>) -> Self {
+        self.prover_object_store = Some(object_store);
+        self
+    }
+
+    /// Sets the actor recorded in the audit log for the performed reverts. By default, the OS user
+    /// running the process is used.
+    pub fn with_audit_actor(mut self, actor: impl Into<String>) -> Self {
+        self.audit_log = AuditLogger::new(self.connection_pool.clone(), actor);
+        self
+    }
+
     /// Rolls back DBs (Postgres + RocksDB) to a previous state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`BlockReverterFlags::ETH_TXS`] are reverted while some of the affected L1 transactions
+    /// are in flight (i.e., were sent and may still be mined).
     pub async fn rollback_db(
         &self,
         last_l1_batch_to_keep: L1BatchNumber,
         flags: BlockReverterFlags,
-    ) {
+    ) -> anyhow::Result<()> {
         let rollback_tree = flags.contains(BlockReverterFlags::TREE);
         let rollback_postgres = flags.contains(BlockReverterFlags::POSTGRES);
         let rollback_sk_cache = flags.contains(BlockReverterFlags::SK_CACHE);
+        let rollback_object_store = flags.contains(BlockReverterFlags::OBJECT_STORE);
+        let rollback_prover_jobs = flags.contains(BlockReverterFlags::PROVER_JOBS);
+        let rollback_eth_txs = flags.contains(BlockReverterFlags::ETH_TXS);
 
         if matches!(
             self.executed_batches_revert_mode,
@@ -138,12 +224,55 @@ impl BlockReverter {
             );
         }
 
+        if rollback_eth_txs {
+            let mut storage = self.connection_pool.access_storage().await?;
+            let (_, inflight_count) = storage
+                .eth_sender_dal()
+                .get_unconfirmed_txs_count_for_l1_batches_after(last_l1_batch_to_keep)
+                .await?;
+            anyhow::ensure!(
+                inflight_count == 0,
+                "Attempt to revert L1 batches with {inflight_count} L1 transactions in flight; wait until \
+                 these transactions are confirmed or fail"
+            );
+        }
+
+        let params = json!({
+            "target": "storage",
+            "last_l1_batch_to_keep": last_l1_batch_to_keep,
+            "postgres": rollback_postgres,
+            "tree": rollback_tree,
+            "state_keeper_cache": rollback_sk_cache,
+            "object_store": rollback_object_store,
+            "prover_jobs": rollback_prover_jobs,
+            "eth_txs": rollback_eth_txs,
+            "allow_executed_batches": matches!(
+                self.executed_batches_revert_mode,
+                L1ExecutedBatchesRevert::Allowed
+            ),
+        });
+        self.audit_log
+            .record(AuditAction::BlockRevert, params)
+            .await
+            .expect("failed recording revert in audit log");
+
         // Tree needs to be reverted first to keep state recoverable
         self.rollback_rocks_dbs(last_l1_batch_to_keep, rollback_tree, rollback_sk_cache)
             .await;
+        // Object keys are loaded from Postgres and the prover DB, so objects are removed before these DBs are reverted.
+        if rollback_object_store {
+            self.rollback_object_store(last_l1_batch_to_keep).await;
+        }
+        if rollback_prover_jobs {
+            self.rollback_prover_jobs(last_l1_batch_to_keep).await;
+        }
+        if rollback_eth_txs {
+            self.rollback_eth_txs(last_l1_batch_to_keep).await;
+        }
         if rollback_postgres {
             self.rollback_postgres(last_l1_batch_to_keep).await;
         }
+        Ok(())
     }
 
     async fn rollback_rocks_dbs(
@@ -223,6 +352,206 @@ impl BlockReverter {
         }
     }
 
+    /// Removes object store artifacts for the reverted L1 batches.
+    async fn rollback_object_store(&self, last_l1_batch_to_keep: L1BatchNumber) {
+        let object_store = self
+            .object_store
+            .as_deref()
+            .expect("object store is not provided");
+        let last_sealed_l1_batch = self
+            .connection_pool
+            .access_storage()
+            .await
+            .unwrap()
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap()
+            .unwrap_or(last_l1_batch_to_keep);
+
+        tracing::info!("removing object store artifacts for reverted L1 batches...");
+        let mut removed_count = 0_u64;
+        for number in (last_l1_batch_to_keep.0 + 1)..=last_sealed_l1_batch.0 {
+            for artifact in l1_batch_artifacts() {
+                let key = artifact.key(L1BatchNumber(number));
+                let removed = remove_object(object_store, artifact.bucket(), &key)
+                    .await
+                    .unwrap_or_else(|err| panic!("failed removing `{key}`: {err}"));
+                removed_count += u64::from(removed);
+            }
+        }
+
+        if let (Some(prover_pool), Some(prover_object_store)) = (
+            &self.prover_connection_pool,
+            self.prover_object_store.as_deref(),
+        ) {
+            let blob_urls = prover_pool
+                .access_storage()
+                .await
+                .unwrap()
+                .fri_prover_jobs_dal()
+                .get_blob_urls_for_l1_batches_after(last_l1_batch_to_keep)
+                .await
+                .unwrap();
+            for (circuit_blob_url, proof_blob_url) in blob_urls {
+                let objects = [
+                    Some((Bucket::ProverJobsFri, circuit_blob_url)),
+                    proof_blob_url.map(|url| (Bucket::ProofsFri, url)),
+                ];
+                for (bucket, key) in objects.into_iter().flatten() {
+                    let removed = remove_object(prover_object_store, bucket, &key)
+                        .await
+                        .unwrap_or_else(|err| panic!("failed removing `{key}`: {err}"));
+                    removed_count += u64::from(removed);
+                }
+            }
+        }
+        tracing::info!("removed {removed_count} objects from object store");
+    }
+
+    /// Removes prover jobs for the reverted L1 batches.
+    async fn rollback_prover_jobs(&self, last_l1_batch_to_keep: L1BatchNumber) {
+        tracing::info!("rolling back prover jobs...");
+        let removed_count = self
+            .prover_connection_pool
+            .as_ref()
+            .expect("prover connection pool is not provided")
+            .access_storage()
+            .await
+            .unwrap()
+            .fri_prover_jobs_dal()
+            .delete_jobs_for_l1_batches_after(last_l1_batch_to_keep)
+            .await
+            .unwrap();
+        tracing::info!("removed {removed_count} prover jobs");
+    }
+
+    /// Removes unconfirmed L1 transactions for the reverted L1 batches, so that the retained batches
+    /// are aggregated anew.
+    async fn rollback_eth_txs(&self, last_l1_batch_to_keep: L1BatchNumber) {
+        tracing::info!("rolling back unconfirmed L1 transactions...");
+        let removed_count = self
+            .connection_pool
+            .access_storage()
+            .await
+            .unwrap()
+            .eth_sender_dal()
+            .delete_unconfirmed_txs_for_l1_batches_after(last_l1_batch_to_keep)
+            .await
+            .unwrap();
+        tracing::info!("removed {removed_count} unconfirmed L1 transactions");
+    }
+
+    /// Estimates the impact of reverting the specified components to `last_l1_batch_to_keep` without
+    /// changing any data.
+    pub async fn impact_report(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        flags: BlockReverterFlags,
+    ) -> RevertImpactReport {
+        let mut storage = self.connection_pool.access_storage().await.unwrap();
+        let mut blocks_dal = storage.blocks_dal();
+        let last_sealed_l1_batch = blocks_dal.get_sealed_l1_batch_number().await.unwrap();
+        let reverted_count = |last: Option<L1BatchNumber>| {
+            last.map_or(0, |last| last.0.saturating_sub(last_l1_batch_to_keep.0))
+        };
+        let reverted_l1_batches = reverted_count(last_sealed_l1_batch);
+        let reverted_committed_l1_batches = reverted_count(
+            blocks_dal
+                .get_number_of_last_l1_batch_committed_on_eth()
+                .await
+                .unwrap(),
+        );
+        let reverted_proven_l1_batches = reverted_count(
+            blocks_dal
+                .get_number_of_last_l1_batch_proven_on_eth()
+                .await
+                .unwrap(),
+        );
+        let reverted_executed_l1_batches = reverted_count(
+            blocks_dal
+                .get_number_of_last_l1_batch_executed_on_eth()
+                .await
+                .unwrap(),
+        );
+
+        let last_miniblock_to_keep = blocks_dal
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .await
+            .unwrap()
+            .map(|(_, last)| last);
+        let last_sealed_miniblock = blocks_dal.get_sealed_miniblock_number().await.unwrap();
+        let reverted_miniblocks = match (last_miniblock_to_keep, last_sealed_miniblock) {
+            (Some(last_to_keep), Some(last_sealed)) => last_sealed.0.saturating_sub(last_to_keep.0),
+            _ => 0,
+        };
+
+        let tree_next_l1_batch = if flags.contains(BlockReverterFlags::TREE)
+            && Path::new(&self.merkle_tree_path).exists()
+        {
+            let db = RocksDB::new(Path::new(&self.merkle_tree_path))
+                .expect("Failed initializing RocksDB for Merkle tree");
+            Some(ZkSyncTree::new_lightweight(db.into()).next_l1_batch_number())
+        } else {
+            None
+        };
+        let sk_cache_l1_batch = if flags.contains(BlockReverterFlags::SK_CACHE)
+            && Path::new(&self.state_keeper_cache_path).exists()
+        {
+            let sk_cache = RocksdbStorage::builder(self.state_keeper_cache_path.as_ref())
+                .await
+                .expect("Failed initializing state keeper cache");
+            sk_cache.l1_batch_number().await
+        } else {
+            None
+        };
+
+        let (unconfirmed_eth_txs, inflight_eth_txs) = if flags.contains(BlockReverterFlags::ETH_TXS)
+        {
+            let (unconfirmed, inflight) = storage
+                .eth_sender_dal()
+                .get_unconfirmed_txs_count_for_l1_batches_after(last_l1_batch_to_keep)
+                .await
+                .unwrap();
+            (Some(unconfirmed), Some(inflight))
+        } else {
+            (None, None)
+        };
+        drop(storage);
+
+        let prover_jobs = match &self.prover_connection_pool {
+            Some(pool) if flags.contains(BlockReverterFlags::PROVER_JOBS) => Some(
+                pool.access_storage()
+                    .await
+                    .unwrap()
+                    .fri_prover_jobs_dal()
+                    .get_job_counts_for_l1_batches_after(last_l1_batch_to_keep)
+                    .await
+                    .unwrap(),
+            ),
+            _ => None,
+        };
+        let max_object_store_artifacts = flags
+            .contains(BlockReverterFlags::OBJECT_STORE)
+            .then(|| u64::from(reverted_l1_batches) * l1_batch_artifacts().len() as u64);
+
+        RevertImpactReport {
+            last_l1_batch_to_keep,
+            last_sealed_l1_batch,
+            reverted_l1_batches,
+            reverted_committed_l1_batches,
+            reverted_proven_l1_batches,
+            reverted_executed_l1_batches,
+            reverted_miniblocks,
+            tree_next_l1_batch,
+            sk_cache_l1_batch,
+            max_object_store_artifacts,
+            prover_jobs,
+            unconfirmed_eth_txs,
+            inflight_eth_txs,
+        }
+    }
+
     /// Reverts data in the Postgres database.
     async fn rollback_postgres(&self, last_l1_batch_to_keep: L1BatchNumber) {
         tracing::info!("rolling back postgres data...");
@@ -309,6 +638,17 @@ impl BlockReverter {
             .as_ref()
             .expect("eth_config is not provided");
 
+        let params = json!({
+            "target": "l1",
+            "last_l1_batch_to_keep": last_l1_batch_to_keep,
+            "nonce": nonce,
+            "priority_fee_per_gas": priority_fee_per_gas,
+        });
+        self.audit_log
+            .record(AuditAction::BlockRevert, params)
+            .await
+            .expect("failed recording revert in audit log");
+
         let web3 = Web3::new(Http::new(&eth_config.eth_client_url).unwrap());
         let contract = zksync_contract();
         let signer = PrivateKeySigner::new(eth_config.reverter_private_key);
@@ -430,6 +770,10 @@ impl BlockReverter {
     /// Clears failed L1 transactions
     pub async fn clear_failed_l1_transactions(&self) {
         tracing::info!("clearing failed L1 transactions...");
+        self.audit_log
+            .record(AuditAction::ClearFailedL1Transactions, json!({}))
+            .await
+            .expect("failed recording clearing transactions in audit log");
         self.connection_pool
             .access_storage()
             .await
@@ -454,3 +798,30 @@ pub struct SuggestedRollbackValues {
     pub nonce: u64,
     pub priority_fee: u64,
 }
+
+/// Pre-flight report on the impact of reverting L1 batches returned by [`BlockReverter::impact_report()`].
+/// Fields for components not selected for the revert are set to `None`.
+#[derive(Debug, Serialize)]
+pub struct RevertImpactReport {
+    pub last_l1_batch_to_keep: L1BatchNumber,
+    pub last_sealed_l1_batch: Option<L1BatchNumber>,
+    pub reverted_l1_batches: u32,
+    /// Number of reverted L1 batches committed on L1. If non-zero, the revert must also be performed on L1.
+    pub reverted_committed_l1_batches: u32,
+    pub reverted_proven_l1_batches: u32,
+    pub reverted_executed_l1_batches: u32,
+    pub reverted_miniblocks: u32,
+    /// Next L1 batch of the Merkle tree; `None` if the tree doesn't exist.
+    pub tree_next_l1_batch: Option<L1BatchNumber>,
+    /// L1 batch of the state keeper cache; `None` if the cache doesn't exist.
+    pub sk_cache_l1_batch: Option<L1BatchNumber>,
+    /// Upper bound on the number of L1 batch artifacts removed from the object store, excluding circuits
+    /// and proofs of prover jobs.
+    pub max_object_store_artifacts: Option<u64>,
+    /// Number of removed prover jobs, keyed by the job table.
+    pub prover_jobs: Option<BTreeMap<&'static str, u64>>,
+    pub unconfirmed_eth_txs: Option<u64>,
+    /// Number of unconfirmed L1 transactions that were sent and may still be mined. The revert is refused
+    /// if there are such transactions.
+    pub inflight_eth_txs: Option<u64>,
+}
@@ -0,0 +1,342 @@
+//! Repair tool backfilling bytecode preimages missing from Postgres.
+//!
+//! A node missing preimages of deployed bytecodes (e.g., because of a faulty snapshot or a bug in an earlier
+//! node version) fails re-executing transactions calling the corresponding contracts with an "unknown bytecode hash"
+//! error. The tool finds such bytecodes and fetches their preimages from peer nodes or from the pubdata published
+//! in L1 commit transactions. All fetched preimages are checked against the expected bytecode hash,
+//! so neither source needs to be trusted.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_eth_client::{clients::QueryClient, EthInterface};
+use zksync_types::{web3::ethabi, L1BatchNumber, MiniblockNumber, H256};
+use zksync_utils::bytecode::{decompress_bytecode, hash_bytecode, validate_bytecode};
+use zksync_web3_decl::{
+    error::{ClientRpcContext, EnrichedClientResult},
+    jsonrpsee::http_client::HttpClient,
+    namespaces::ZksNamespaceClient,
+};
+
+use crate::consistency_checker::ConsistencyChecker;
+
+#[cfg(test)]
+mod tests;
+
+/// Node providing bytecode preimages, e.g. the main node.
+#[async_trait]
+pub trait BytecodePeerClient: fmt::Debug + Send + Sync {
+    async fn fetch_bytecode(&self, hash: H256) -> EnrichedClientResult<Option<Vec<u8>>>;
+}
+
+#[async_trait]
+impl BytecodePeerClient for HttpClient {
+    async fn fetch_bytecode(&self, hash: H256) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.get_bytecode_by_hash(hash)
+            .rpc_context("get_bytecode_by_hash")
+            .with_arg("hash", &hash)
+            .await
+    }
+}
+
+/// Source of bytecode preimages published on L1 as a part of the L1 batch pubdata.
+#[derive(Debug)]
+struct L1CommitBytecodes {
+    l1_client: Box<dyn EthInterface>,
+    commit_function: ethabi::Function,
+    /// Bytecodes published in the last processed L1 batch. Missing bytecodes are processed in the order
+    /// of miniblocks they first appear in, so caching a single batch is sufficient.
+    cached_batch: Option<(L1BatchNumber, HashMap<H256, Vec<u8>>)>,
+}
+
+impl L1CommitBytecodes {
+    async fn fetch_bytecode(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        hash: H256,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let Some(l1_batch_number) = storage
+            .storage_web3_dal()
+            .resolve_l1_batch_number_of_miniblock(miniblock_number)
+            .await?
+            .miniblock_l1_batch
+        else {
+            return Ok(None);
+        };
+
+        if !matches!(&self.cached_batch, Some((number, _)) if *number == l1_batch_number) {
+            let bytecodes = self
+                .published_bytecodes(storage, l1_batch_number)
+                .await
+                .with_context(|| {
+                    format!("failed getting bytecodes published in L1 batch #{l1_batch_number}")
+                })?;
+            self.cached_batch = Some((l1_batch_number, bytecodes));
+        }
+        let (_, bytecodes) = self.cached_batch.as_ref().unwrap();
+        Ok(bytecodes.get(&hash).cloned())
+    }
+
+    async fn published_bytecodes(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<HashMap<H256, Vec<u8>>> {
+        let commit_tx_hash = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(l1_batch_number)
+            .await?
+            .and_then(|details| details.base.commit_tx_hash);
+        let Some(commit_tx_hash) = commit_tx_hash else {
+            tracing::info!("L1 batch #{l1_batch_number} is not committed yet");
+            return Ok(HashMap::new());
+        };
+
+        let commit_tx = self
+            .l1_client
+            .get_tx(commit_tx_hash, "bytecode_repair")
+            .await?
+            .with_context(|| format!("commit tx {commit_tx_hash:?} not found on L1"))?;
+        let commitment = ConsistencyChecker::extract_commit_data(
+            &commit_tx.input.0,
+            &self.commit_function,
+            l1_batch_number,
+        )?;
+        let ethabi::Token::Tuple(fields) = commitment else {
+            anyhow::bail!("unexpected signature for L1 commit function");
+        };
+        let Some(ethabi::Token::Bytes(pubdata)) = fields.last() else {
+            anyhow::bail!("unexpected signature for L1 commit function");
+        };
+        Ok(extract_published_bytecodes(pubdata))
+    }
+}
+
+/// Size of the L2-to-L1 log in the pubdata.
+const L2_TO_L1_LOG_SIZE: usize = 88;
+/// Size of the blob commitment appended to the pubdata published in calldata.
+const BLOB_COMMITMENT_SIZE: usize = 32;
+const PUBDATA_SOURCE_CALLDATA: u8 = 0;
+
+/// Extracts bytecodes published in the L1 batch pubdata: both uncompressed bytecodes and compressed ones,
+/// which are published as L2-to-L1 messages. Pubdata may be specified either in the raw form, or as pubdata
+/// commitments for the calldata pubdata source.
+fn extract_published_bytecodes(pubdata: &[u8]) -> HashMap<H256, Vec<u8>> {
+    let mut bytecodes = HashMap::new();
+    let mut candidates = vec![pubdata];
+    if let Some((&PUBDATA_SOURCE_CALLDATA, data)) = pubdata.split_first() {
+        if let Some(len) = data.len().checked_sub(BLOB_COMMITMENT_SIZE) {
+            candidates.push(&data[..len]);
+        }
+    }
+
+    for pubdata in candidates {
+        let Some((messages, published_bytecodes)) = split_pubdata(pubdata) else {
+            continue;
+        };
+        let compressed_bytecodes = messages.into_iter().filter_map(decompress_bytecode);
+        let all_bytecodes = published_bytecodes
+            .into_iter()
+            .map(<[u8]>::to_vec)
+            .chain(compressed_bytecodes);
+        for bytecode in all_bytecodes {
+            if validate_bytecode(&bytecode).is_ok() {
+                bytecodes.insert(hash_bytecode(&bytecode), bytecode);
+            }
+        }
+    }
+    bytecodes
+}
+
+/// Splits the raw pubdata into L2-to-L1 messages and published bytecodes. The pubdata consists of L2-to-L1 logs,
+/// messages, bytecodes and state diffs; each section except for state diffs starts from the 4-byte number of
+/// entries, and each message or bytecode is prefixed by its 4-byte length.
+fn split_pubdata(pubdata: &[u8]) -> Option<(Vec<&[u8]>, Vec<&[u8]>)> {
+    fn read_u32(data: &mut &[u8]) -> Option<usize> {
+        let (bytes, rest) = (data.get(..4)?, data.get(4..)?);
+        *data = rest;
+        Some(u32::from_be_bytes(bytes.try_into().unwrap()) as usize)
+    }
+
+    fn read_entries<'a>(data: &mut &'a [u8]) -> Option<Vec<&'a [u8]>> {
+        let count = read_u32(data)?;
+        let mut entries = Vec::with_capacity(count.min(data.len() / 4));
+        for _ in 0..count {
+            let len = read_u32(data)?;
+            let (entry, rest) = (data.get(..len)?, data.get(len..)?);
+            entries.push(entry);
+            *data = rest;
+        }
+        Some(entries)
+    }
+
+    let mut data = pubdata;
+    let log_count = read_u32(&mut data)?;
+    data = data.get(log_count.checked_mul(L2_TO_L1_LOG_SIZE)?..)?;
+    let messages = read_entries(&mut data)?;
+    let bytecodes = read_entries(&mut data)?;
+    Some((messages, bytecodes))
+}
+
+/// Number of miniblocks scanned for missing bytecodes in a single DB query.
+const MINIBLOCK_CHUNK_SIZE: u32 = 10_000;
+
+/// Outcome of a [`BytecodeRepair`] run.
+#[derive(Debug, Default)]
+pub struct BytecodeRepairReport {
+    /// Number of bytecode preimages missing from Postgres.
+    pub missing_count: usize,
+    /// Number of backfilled bytecode preimages.
+    pub repaired_count: usize,
+    /// Hashes of bytecodes that weren't found in any source.
+    pub unresolved: Vec<H256>,
+}
+
+/// Backfills bytecode preimages missing from Postgres. Peers are queried first in the order they were added;
+/// if no peer has a preimage, it is looked up in the pubdata of the L1 batch where the bytecode was deployed.
+#[derive(Debug)]
+pub struct BytecodeRepair {
+    pool: ConnectionPool,
+    peers: Vec<Box<dyn BytecodePeerClient>>,
+    l1_source: Option<L1CommitBytecodes>,
+}
+
+impl BytecodeRepair {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self {
+            pool,
+            peers: vec![],
+            l1_source: None,
+        }
+    }
+
+    /// Adds a peer node (e.g., the main node) to fetch bytecode preimages from.
+    pub fn with_peer(mut self, peer: Box<dyn BytecodePeerClient>) -> Self {
+        self.peers.push(peer);
+        self
+    }
+
+    /// Enables fetching bytecode preimages from L1 commit calldata.
+    pub fn with_l1_client(mut self, l1_client_url: &str) -> anyhow::Result<Self> {
+        let l1_client = QueryClient::new(l1_client_url).context("failed creating L1 client")?;
+        let commit_function = zksync_contracts::zksync_contract()
+            .function("commitBatches")
+            .context("L1 contract does not have `commitBatches` function")?
+            .clone();
+        self.l1_source = Some(L1CommitBytecodes {
+            l1_client: Box::new(l1_client),
+            commit_function,
+            cached_batch: None,
+        });
+        Ok(self)
+    }
+
+    async fn fetch_from_peers(&self, hash: H256) -> Option<Vec<u8>> {
+        for (i, peer) in self.peers.iter().enumerate() {
+            match peer.fetch_bytecode(hash).await {
+                Ok(Some(bytecode)) => {
+                    if validate_bytecode(&bytecode).is_ok() && hash_bytecode(&bytecode) == hash {
+                        return Some(bytecode);
+                    }
+                    tracing::warn!("Peer #{i} returned invalid preimage for bytecode {hash:?}");
+                }
+                Ok(None) => { /* Try the next peer */ }
+                Err(err) => {
+                    tracing::warn!("Failed fetching bytecode {hash:?} from peer #{i}: {err}");
+                }
+            }
+        }
+        None
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<BytecodeRepairReport> {
+        let mut storage = self.pool.access_storage_tagged("bytecode_repair").await?;
+        let Some(last_miniblock) = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("failed getting sealed miniblock number")?
+        else {
+            return Ok(BytecodeRepairReport::default());
+        };
+
+        let mut report = BytecodeRepairReport::default();
+        // Unresolved bytecodes remain missing, so they are returned again if they are deployed in later chunks.
+        let mut unresolved = HashSet::new();
+        for chunk_start in (0..=last_miniblock.0).step_by(MINIBLOCK_CHUNK_SIZE as usize) {
+            let chunk_end = chunk_start
+                .saturating_add(MINIBLOCK_CHUNK_SIZE - 1)
+                .min(last_miniblock.0);
+            let missing_bytecodes = storage
+                .factory_deps_dal()
+                .get_missing_deployed_bytecodes(
+                    MiniblockNumber(chunk_start)..=MiniblockNumber(chunk_end),
+                )
+                .await
+                .with_context(|| {
+                    format!("failed getting missing bytecodes in miniblocks #{chunk_start}..=#{chunk_end}")
+                })?;
+
+            for (hash, miniblock_number) in missing_bytecodes {
+                if unresolved.contains(&hash) {
+                    continue;
+                }
+                report.missing_count += 1;
+                if self
+                    .repair_bytecode(&mut storage, hash, miniblock_number)
+                    .await?
+                {
+                    report.repaired_count += 1;
+                } else {
+                    unresolved.insert(hash);
+                    report.unresolved.push(hash);
+                }
+            }
+        }
+        tracing::info!(
+            "Found {} missing bytecode preimages, backfilled {}",
+            report.missing_count,
+            report.repaired_count
+        );
+        Ok(report)
+    }
+
+    /// Returns `false` if the bytecode preimage is not found in any source.
+    async fn repair_bytecode(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        hash: H256,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<bool> {
+        let mut bytecode = self.fetch_from_peers(hash).await;
+        if bytecode.is_none() {
+            if let Some(l1_source) = &mut self.l1_source {
+                bytecode = l1_source
+                    .fetch_bytecode(storage, hash, miniblock_number)
+                    .await?;
+            }
+        }
+
+        let Some(bytecode) = bytecode else {
+            tracing::warn!(
+                "Preimage for bytecode {hash:?} deployed in miniblock #{miniblock_number} is not found"
+            );
+            return Ok(false);
+        };
+        storage
+            .factory_deps_dal()
+            .insert_factory_deps(miniblock_number, &HashMap::from([(hash, bytecode)]))
+            .await
+            .with_context(|| format!("failed inserting preimage for bytecode {hash:?}"))?;
+        tracing::info!(
+            "Backfilled preimage for bytecode {hash:?} deployed in miniblock #{miniblock_number}"
+        );
+        Ok(true)
+    }
+}
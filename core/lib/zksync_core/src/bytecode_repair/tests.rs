@@ -0,0 +1,161 @@
+//! Tests for the bytecode repair tool.
+
+use zksync_dal::ConnectionPool;
+use zksync_types::{get_code_key, Address, L2ChainId, StorageLog};
+use zksync_utils::bytecode::compress_bytecode;
+use zksync_web3_decl::error::EnrichedClientError;
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::create_miniblock,
+};
+
+fn encode_entries(entries: &[&[u8]]) -> Vec<u8> {
+    let mut encoded = (entries.len() as u32).to_be_bytes().to_vec();
+    for entry in entries {
+        encoded.extend_from_slice(&(entry.len() as u32).to_be_bytes());
+        encoded.extend_from_slice(entry);
+    }
+    encoded
+}
+
+fn mock_bytecode(word: u8) -> Vec<u8> {
+    [[word; 32], [0; 32], [word; 32]].concat()
+}
+
+#[test]
+fn extracting_published_bytecodes() {
+    let compressed_bytecode = mock_bytecode(1);
+    let published_bytecode = mock_bytecode(2);
+    let compressed = compress_bytecode(&compressed_bytecode).unwrap();
+
+    let mut pubdata = 1_u32.to_be_bytes().to_vec();
+    pubdata.extend_from_slice(&[0; L2_TO_L1_LOG_SIZE]);
+    pubdata.extend(encode_entries(&[&compressed, b"not a bytecode"]));
+    pubdata.extend(encode_entries(&[&published_bytecode, &[1; 33]]));
+    // State diffs are not parsed.
+    pubdata.extend_from_slice(&[0xff; 64]);
+
+    let bytecodes = extract_published_bytecodes(&pubdata);
+    let expected_bytecodes = HashMap::from([
+        (hash_bytecode(&compressed_bytecode), compressed_bytecode),
+        (hash_bytecode(&published_bytecode), published_bytecode),
+    ]);
+    assert_eq!(bytecodes, expected_bytecodes);
+
+    let mut commitments = vec![PUBDATA_SOURCE_CALLDATA];
+    commitments.extend_from_slice(&pubdata);
+    commitments.extend_from_slice(&[0; BLOB_COMMITMENT_SIZE]);
+    assert_eq!(
+        extract_published_bytecodes(&commitments),
+        expected_bytecodes
+    );
+
+    assert!(extract_published_bytecodes(&pubdata[..100]).is_empty());
+    assert!(extract_published_bytecodes(&[]).is_empty());
+}
+
+#[derive(Debug, Default)]
+struct MockPeer(HashMap<H256, Vec<u8>>);
+
+#[async_trait]
+impl BytecodePeerClient for MockPeer {
+    async fn fetch_bytecode(&self, hash: H256) -> EnrichedClientResult<Option<Vec<u8>>> {
+        Ok(self.0.get(&hash).cloned())
+    }
+}
+
+#[derive(Debug)]
+struct FailingPeer;
+
+#[async_trait]
+impl BytecodePeerClient for FailingPeer {
+    async fn fetch_bytecode(&self, _hash: H256) -> EnrichedClientResult<Option<Vec<u8>>> {
+        Err(EnrichedClientError::custom(
+            "unavailable",
+            "get_bytecode_by_hash",
+        ))
+    }
+}
+
+async fn deploy_unknown_bytecodes(storage: &mut StorageProcessor<'_>, hashes: &[H256]) {
+    ensure_genesis_state(storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let miniblock = create_miniblock(1);
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+
+    let logs = hashes.iter().enumerate().map(|(i, &hash)| {
+        let address = Address::from_low_u64_be(0x1_0000 + i as u64);
+        StorageLog::new_write_log(get_code_key(&address), hash)
+    });
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(miniblock.number, &[(H256::zero(), logs.collect())])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn repairing_bytecodes_from_peers() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let bytecode = mock_bytecode(1);
+    let bytecode_hash = hash_bytecode(&bytecode);
+    let unknown_hash = hash_bytecode(&mock_bytecode(2));
+    deploy_unknown_bytecodes(&mut storage, &[bytecode_hash, unknown_hash]).await;
+
+    let missing_bytecodes = storage
+        .factory_deps_dal()
+        .get_missing_deployed_bytecodes(MiniblockNumber(2)..=MiniblockNumber(5))
+        .await
+        .unwrap();
+    assert!(missing_bytecodes.is_empty(), "{missing_bytecodes:?}");
+    let mut missing_bytecodes = storage
+        .factory_deps_dal()
+        .get_missing_deployed_bytecodes(MiniblockNumber(0)..=MiniblockNumber(1))
+        .await
+        .unwrap();
+    missing_bytecodes.sort_unstable_by_key(|(hash, _)| *hash);
+    let mut expected_missing_bytecodes = vec![
+        (bytecode_hash, MiniblockNumber(1)),
+        (unknown_hash, MiniblockNumber(1)),
+    ];
+    expected_missing_bytecodes.sort_unstable_by_key(|(hash, _)| *hash);
+    assert_eq!(missing_bytecodes, expected_missing_bytecodes);
+
+    // The malicious peer returns a bogus preimage that must be ignored.
+    let malicious_peer = MockPeer(HashMap::from([
+        (bytecode_hash, mock_bytecode(3)),
+        (unknown_hash, vec![1; 32]),
+    ]));
+    let honest_peer = MockPeer(HashMap::from([(bytecode_hash, bytecode.clone())]));
+    let report = BytecodeRepair::new(pool.clone())
+        .with_peer(Box::new(FailingPeer))
+        .with_peer(Box::new(malicious_peer))
+        .with_peer(Box::new(honest_peer))
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(report.missing_count, 2);
+    assert_eq!(report.repaired_count, 1);
+    assert_eq!(report.unresolved, [unknown_hash]);
+
+    let stored_bytecode = storage
+        .factory_deps_dal()
+        .get_factory_dep(bytecode_hash)
+        .await
+        .unwrap();
+    assert_eq!(stored_bytecode, Some(bytecode));
+    let missing_bytecodes = storage
+        .factory_deps_dal()
+        .get_missing_deployed_bytecodes(MiniblockNumber(0)..=MiniblockNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(missing_bytecodes, [(unknown_hash, MiniblockNumber(1))]);
+}
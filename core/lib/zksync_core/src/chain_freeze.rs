@@ -0,0 +1,139 @@
+//! Chain freeze (emergency) mode. When the chain is frozen, API servers stop accepting transactions,
+//! and the state keeper seals the current L1 batch and doesn't start new ones; the read API keeps serving.
+//! The chain can be frozen via the admin RPC or by the `Freeze` event of the diamond proxy on L1.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+
+/// Polls the chain freeze state from Postgres and notifies subscribers about its changes.
+#[derive(Debug)]
+pub struct ChainFreezeWatcher {
+    pool: ConnectionPool,
+    poll_interval: Duration,
+    sender: watch::Sender<bool>,
+}
+
+impl ChainFreezeWatcher {
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Creates a new watcher together with a receiver of the chain freeze state. The initial state is loaded
+    /// from Postgres, so that components never start serving while the chain is frozen.
+    pub async fn new(
+        pool: ConnectionPool,
+        poll_interval: Duration,
+    ) -> anyhow::Result<(Self, watch::Receiver<bool>)> {
+        let is_frozen = Self::is_chain_frozen(&pool)
+            .await
+            .context("failed loading initial chain freeze state")?;
+        if is_frozen {
+            tracing::warn!("Chain is frozen");
+        }
+        let (sender, receiver) = watch::channel(is_frozen);
+        let this = Self {
+            pool,
+            poll_interval,
+            sender,
+        };
+        Ok((this, receiver))
+    }
+
+    async fn is_chain_frozen(pool: &ConnectionPool) -> anyhow::Result<bool> {
+        let mut storage = pool.access_storage_tagged("chain_freeze").await?;
+        storage
+            .chain_freeze_dal()
+            .is_chain_frozen()
+            .await
+            .context("is_chain_frozen()")
+    }
+
+    /// Runs the watcher until a stop signal is received or all state receivers are dropped. Errors loading
+    /// the state are logged; the last known state is retained in this case.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, chain freeze watcher is shutting down");
+                break;
+            }
+
+            match Self::is_chain_frozen(&self.pool).await {
+                Ok(is_frozen) => {
+                    let was_frozen = self.sender.send_replace(is_frozen);
+                    if is_frozen && !was_frozen {
+                        tracing::warn!("Chain is frozen");
+                    } else if !is_frozen && was_frozen {
+                        tracing::warn!("Chain is unfrozen");
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Failed loading chain freeze state: {err:#}");
+                }
+            }
+
+            tokio::select! {
+                res = tokio::time::timeout(self.poll_interval, stop_receiver.changed()) => {
+                    if res.is_ok() {
+                        break;
+                    }
+                }
+                () = self.sender.closed() => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::chain_freeze::ChainFreezeSource;
+
+    use super::*;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    #[tokio::test]
+    async fn watcher_loads_initial_freeze_state() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .chain_freeze_dal()
+            .insert_event(true, ChainFreezeSource::AdminRpc, None, None)
+            .await
+            .unwrap();
+
+        let (_watcher, freeze_receiver) = ChainFreezeWatcher::new(pool.clone(), POLL_INTERVAL)
+            .await
+            .unwrap();
+        assert!(*freeze_receiver.borrow());
+    }
+
+    #[tokio::test]
+    async fn watcher_reacts_to_freeze_state_changes() {
+        let pool = ConnectionPool::test_pool().await;
+        let (watcher, mut freeze_receiver) = ChainFreezeWatcher::new(pool.clone(), POLL_INTERVAL)
+            .await
+            .unwrap();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let watcher_task = tokio::spawn(watcher.run(stop_receiver));
+
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .chain_freeze_dal()
+            .insert_event(true, ChainFreezeSource::AdminRpc, None, None)
+            .await
+            .unwrap();
+        freeze_receiver.wait_for(|frozen| *frozen).await.unwrap();
+
+        storage
+            .chain_freeze_dal()
+            .insert_event(false, ChainFreezeSource::AdminRpc, None, None)
+            .await
+            .unwrap();
+        freeze_receiver.wait_for(|frozen| !*frozen).await.unwrap();
+
+        stop_sender.send_replace(true);
+        watcher_task.await.unwrap().unwrap();
+    }
+}
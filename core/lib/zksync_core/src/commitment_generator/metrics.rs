@@ -23,6 +23,9 @@ pub(super) struct CommitmentGeneratorMetrics {
     /// Latency of generating events queue commitment.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub events_queue_commitment_latency: Histogram<Duration>,
+    /// Latency of computing KZG info for pubdata blobs.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub kzg_info_latency: Histogram<Duration>,
 }
 
 #[vise::register]
@@ -3,20 +3,18 @@ use std::time::Duration;
 use anyhow::Context;
 use itertools::Itertools;
 use metrics::{CommitmentStage, METRICS};
-use multivm::zk_evm_latest::ethereum_types::U256;
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_commitment_utils::{bootloader_initial_content_commitment, events_queue_commitment};
 use zksync_dal::ConnectionPool;
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::i_executor::commit::kzg::{
-    pubdata_to_blob_commitments, KzgSettings,
+    blob_commitments, pubdata_to_kzg_info, KzgInfo, KzgSettingsProvider,
 };
 use zksync_types::{
     commitment::{AuxCommitments, CommitmentCommonInput, CommitmentInput, L1BatchCommitment},
-    writes::{InitialStorageWrite, RepeatedStorageWrite, StateDiffRecord},
-    L1BatchNumber, ProtocolVersionId, StorageKey, H256,
+    writes::{InitialStorageWrite, RepeatedStorageWrite},
+    L1BatchNumber, ProtocolVersionId, H256,
 };
-use zksync_utils::h256_to_u256;
 
 mod metrics;
 
@@ -26,18 +24,31 @@ const SLEEP_INTERVAL: Duration = Duration::from_millis(100);
 pub struct CommitmentGenerator {
     connection_pool: ConnectionPool,
     health_updater: HealthUpdater,
-    kzg_settings: KzgSettings,
+    kzg_settings: KzgSettingsProvider,
+    persist_kzg_info: bool,
 }
 
 impl CommitmentGenerator {
-    pub fn new(connection_pool: ConnectionPool, kzg_trusted_setup_path: &str) -> Self {
+    /// Creates a new generator. The generator doesn't load KZG settings by itself until it encounters an L1 batch
+    /// with pubdata that requires KZG info; callers should load them on startup via [`KzgSettingsProvider::get()`]
+    /// to detect a misconfigured trusted setup early. Loaded settings are shared in the entire process.
+    pub fn new(connection_pool: ConnectionPool, kzg_settings: KzgSettingsProvider) -> Self {
         Self {
             connection_pool,
             health_updater: ReactiveHealthCheck::new("commitment_generator").1,
-            kzg_settings: KzgSettings::new(kzg_trusted_setup_path),
+            kzg_settings,
+            persist_kzg_info: false,
         }
     }
 
+    /// Persists KZG info computed for pubdata blobs of each L1 batch, so that `eth_sender` doesn't need to recompute it
+    /// when encoding commit data. Should only be enabled on the main node, where persisted KZG info is pruned
+    /// after L1 batches are committed.
+    pub fn with_kzg_info_persistence(mut self) -> Self {
+        self.persist_kzg_info = true;
+        self
+    }
+
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
@@ -105,10 +116,12 @@ impl CommitmentGenerator {
         })
     }
 
+    /// Returns the commitment input together with KZG info for the pubdata blobs (empty for batches
+    /// not using blobs).
     async fn prepare_input(
         &self,
         l1_batch_number: L1BatchNumber,
-    ) -> anyhow::Result<CommitmentInput> {
+    ) -> anyhow::Result<(CommitmentInput, Vec<KzgInfo>)> {
         let mut connection = self
             .connection_pool
             .access_storage_tagged("commitment_generator")
@@ -136,23 +149,24 @@ impl CommitmentGenerator {
             default_aa_code_hash: header.base_system_contracts_hashes.default_aa,
             protocol_version,
         };
-        let touched_slots = connection
-            .storage_logs_dal()
-            .get_touched_slots_for_l1_batch(l1_batch_number)
-            .await?;
-        let touched_hashed_keys: Vec<_> =
-            touched_slots.keys().map(|key| key.hashed_key()).collect();
-        let previous_values = connection
-            .storage_logs_dal()
-            .get_previous_storage_values(&touched_hashed_keys, l1_batch_number)
-            .await?;
-        let l1_batches_for_initial_writes = connection
-            .storage_logs_dal()
-            .get_l1_batches_and_indices_for_initial_writes(&touched_hashed_keys)
-            .await?;
-        drop(connection);
-
+        let mut kzg_info = vec![];
         let input = if protocol_version.is_pre_boojum() {
+            let touched_slots = connection
+                .storage_logs_dal()
+                .get_touched_slots_for_l1_batch(l1_batch_number)
+                .await?;
+            let touched_hashed_keys: Vec<_> =
+                touched_slots.keys().map(|key| key.hashed_key()).collect();
+            let previous_values = connection
+                .storage_logs_dal()
+                .get_previous_storage_values(&touched_hashed_keys, l1_batch_number)
+                .await?;
+            let l1_batches_for_initial_writes = connection
+                .storage_logs_dal()
+                .get_l1_batches_and_indices_for_initial_writes(&touched_hashed_keys)
+                .await?;
+            drop(connection);
+
             let mut initial_writes = Vec::new();
             let mut repeated_writes = Vec::new();
             for (key, value) in touched_slots.into_iter().sorted_by_key(|(key, _)| *key) {
@@ -182,50 +196,23 @@ impl CommitmentGenerator {
                 repeated_writes,
             }
         } else {
+            let state_diffs = connection
+                .storage_logs_dal()
+                .get_l1_batch_state_diffs(l1_batch_number)
+                .await?;
+            drop(connection);
+
             let aux_commitments = self
                 .calculate_aux_commitments(header.number, protocol_version)
                 .await?;
 
-            let mut state_diffs = Vec::new();
-            for (key, value) in touched_slots {
-                let hashed_key = key.hashed_key();
-                let prev_value = previous_values[&hashed_key].unwrap_or_default();
-                if prev_value != value {
-                    let (initial_write_l1_batch_number, index) =
-                        l1_batches_for_initial_writes[&hashed_key];
-                    assert!(
-                        initial_write_l1_batch_number <= l1_batch_number,
-                        "Slot {hashed_key:?} was changed in L1 batch {l1_batch_number} but in DB L1 batch of initial write is greater"
-                    );
-                    if initial_write_l1_batch_number == l1_batch_number {
-                        state_diffs.push(StateDiffRecord {
-                            address: *key.address(),
-                            key: h256_to_u256(*key.key()),
-                            derived_key: StorageKey::raw_hashed_key(key.address(), key.key()),
-                            enumeration_index: 0u64,
-                            initial_value: U256::default(),
-                            final_value: h256_to_u256(value),
-                        });
-                    } else {
-                        state_diffs.push(StateDiffRecord {
-                            address: *key.address(),
-                            key: h256_to_u256(*key.key()),
-                            derived_key: StorageKey::raw_hashed_key(key.address(), key.key()),
-                            enumeration_index: index,
-                            initial_value: h256_to_u256(prev_value),
-                            final_value: h256_to_u256(value),
-                        });
-                    }
-                }
-            }
-            state_diffs.sort_unstable_by_key(|rec| (rec.address, rec.key));
-
             let blob_commitments = if protocol_version.is_post_1_4_2() {
                 let pubdata_input = header.pubdata_input.with_context(|| {
                     format!("`pubdata_input` is missing for L1 batch #{l1_batch_number}")
                 })?;
 
-                pubdata_to_blob_commitments(&pubdata_input, &self.kzg_settings)
+                kzg_info = self.calculate_kzg_info(pubdata_input).await?;
+                blob_commitments(&kzg_info)
             } else {
                 [H256::zero(), H256::zero()]
             };
@@ -239,13 +226,26 @@ impl CommitmentGenerator {
             }
         };
 
-        Ok(input)
+        Ok((input, kzg_info))
+    }
+
+    async fn calculate_kzg_info(&self, pubdata_input: Vec<u8>) -> anyhow::Result<Vec<KzgInfo>> {
+        let kzg_settings = self.kzg_settings.clone();
+        tokio::task::spawn_blocking(move || {
+            let kzg_settings = kzg_settings.get().context("failed loading KZG settings")?;
+            let latency = METRICS.kzg_info_latency.start();
+            let kzg_info = pubdata_to_kzg_info(&pubdata_input, &kzg_settings);
+            latency.observe();
+            Ok(kzg_info)
+        })
+        .await
+        .context("KZG info computation panicked")?
     }
 
     async fn step(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
         let latency =
             METRICS.generate_commitment_latency_stage[&CommitmentStage::PrepareInput].start();
-        let input = self.prepare_input(l1_batch_number).await?;
+        let (input, kzg_info) = self.prepare_input(l1_batch_number).await?;
         let latency = latency.observe();
         tracing::debug!("Prepared commitment input for L1 batch #{l1_batch_number} in {latency:?}");
 
@@ -260,12 +260,27 @@ impl CommitmentGenerator {
 
         let latency =
             METRICS.generate_commitment_latency_stage[&CommitmentStage::SaveResults].start();
-        self.connection_pool
+        let mut connection = self
+            .connection_pool
             .access_storage_tagged("commitment_generator")
-            .await?
+            .await?;
+        let mut transaction = connection.start_transaction().await?;
+        transaction
             .blocks_dal()
             .save_l1_batch_commitment_artifacts(l1_batch_number, &artifacts)
             .await?;
+        if self.persist_kzg_info && !kzg_info.is_empty() {
+            let serialized_kzg_info: Vec<_> = kzg_info
+                .iter()
+                .map(|kzg_info| kzg_info.to_bytes().to_vec())
+                .collect();
+            transaction
+                .blocks_dal()
+                .save_l1_batch_kzg_info(l1_batch_number, &serialized_kzg_info)
+                .await?;
+        }
+        transaction.commit().await?;
+        drop(connection);
         let latency = latency.observe();
         tracing::debug!(
             "Stored commitment artifacts for L1 batch #{l1_batch_number} in {latency:?}"
@@ -2,9 +2,12 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use anyhow::Context as _;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use zksync_config::configs::eth_sender::SignerBackend;
 use zksync_consensus_crypto::{read_required_text, Text, TextFmt};
 use zksync_consensus_executor as executor;
 use zksync_consensus_roles::{node, validator};
+use zksync_eth_signer::remote::{AwsKmsDecryptor, GcpKmsDecryptor, SecretDecryptor, Zeroizing};
 use zksync_protobuf::{required, ProtoFmt};
 
 use crate::consensus::proto;
@@ -18,14 +21,92 @@ pub fn decode_json<T: ProtoFmt>(json: &str) -> anyhow::Result<T> {
 }
 
 /// Decodes a secret of type T from an env var with name `var_name`.
-/// It makes sure that the error message doesn't contain the secret.
+/// It makes sure that the error message doesn't contain the secret, and zeroes out the raw secret after decoding.
 pub fn read_secret<T: TextFmt>(var_name: &str) -> anyhow::Result<T> {
     let raw = std::env::var(var_name).map_err(|_| anyhow::anyhow!("{var_name} not set"))?;
+    let raw = Zeroizing::new(raw);
     Text::new(&raw)
         .decode()
         .map_err(|_| anyhow::anyhow!("{var_name} has invalid format"))
 }
 
+/// Reader of consensus secrets. Secrets are either supplied in plaintext, or are encrypted with a symmetric
+/// KMS key, so that they are only present in the node memory and aren't stored on the host.
+///
+/// Consensus keys cannot be managed by remote signers directly; see [`SecretDecryptor`] docs for why envelope
+/// encryption is used instead.
+#[derive(Debug, Default)]
+pub struct SecretReader {
+    decryptor: Option<Box<dyn SecretDecryptor>>,
+}
+
+impl SecretReader {
+    /// Creates a reader decrypting secrets with the provided decryptor.
+    pub fn new(decryptor: Box<dyn SecretDecryptor>) -> Self {
+        Self {
+            decryptor: Some(decryptor),
+        }
+    }
+
+    /// Configures the reader from env vars:
+    ///
+    /// - `{prefix}SECRETS_BACKEND`: `PrivateKey` (plaintext secrets; the default), `AwsKms` or `GcpKms`.
+    /// - `{prefix}SECRETS_KMS_KEY_ID`: ID, ARN or alias of the AWS KMS key, or resource name of the GCP KMS key.
+    /// - `{prefix}SECRETS_KMS_REGION`: AWS region of the key.
+    /// - `{prefix}SECRETS_KMS_URL`: optional KMS endpoint overriding the default one.
+    pub async fn from_env(prefix: &str) -> anyhow::Result<Self> {
+        let var = |name: &str| std::env::var(format!("{prefix}SECRETS_{name}")).ok();
+        let required_var =
+            |name: &str| var(name).with_context(|| format!("{prefix}SECRETS_{name} not set"));
+
+        let backend = match var("BACKEND") {
+            Some(raw) => serde_json::from_value(raw.clone().into())
+                .with_context(|| format!("{prefix}SECRETS_BACKEND has invalid value {raw:?}"))?,
+            None => SignerBackend::PrivateKey,
+        };
+        let endpoint = var("KMS_URL");
+        let decryptor: Box<dyn SecretDecryptor> = match backend {
+            SignerBackend::PrivateKey => return Ok(Self::default()),
+            SignerBackend::Web3Signer => {
+                anyhow::bail!("web3signer cannot protect consensus secrets; use AwsKms or GcpKms")
+            }
+            SignerBackend::AwsKms => Box::new(AwsKmsDecryptor::new(
+                &required_var("KMS_REGION")?,
+                &required_var("KMS_KEY_ID")?,
+                endpoint.as_deref(),
+            )?),
+            SignerBackend::GcpKms => Box::new(
+                GcpKmsDecryptor::new(&required_var("KMS_KEY_ID")?, endpoint.as_deref()).await?,
+            ),
+        };
+        tracing::info!("Consensus secrets are decrypted using {backend:?}");
+        Ok(Self::new(decryptor))
+    }
+
+    /// Decodes a secret of type T from an env var with name `var_name`. If the reader has a decryptor,
+    /// the env var must contain base64-encoded ciphertext of the secret in its text format.
+    /// Like [`read_secret()`], it makes sure that the error message doesn't contain the secret; decrypted plaintext
+    /// is zeroed out after decoding.
+    pub async fn read<T: TextFmt>(&self, var_name: &str) -> anyhow::Result<T> {
+        let Some(decryptor) = &self.decryptor else {
+            return read_secret(var_name);
+        };
+        let raw = std::env::var(var_name).map_err(|_| anyhow::anyhow!("{var_name} not set"))?;
+        let ciphertext = BASE64
+            .decode(raw.trim())
+            .map_err(|_| anyhow::anyhow!("{var_name} is not valid base64"))?;
+        let plaintext = decryptor
+            .decrypt(&ciphertext)
+            .await
+            .with_context(|| format!("failed decrypting {var_name}"))?;
+        let plaintext = std::str::from_utf8(&plaintext)
+            .map_err(|_| anyhow::anyhow!("{var_name} has invalid format"))?;
+        Text::new(plaintext.trim())
+            .decode()
+            .map_err(|_| anyhow::anyhow!("{var_name} has invalid format"))
+    }
+}
+
 /// Config (shared between main node and external node).
 #[derive(Clone, Debug, PartialEq)]
 pub struct Config {
@@ -173,6 +173,7 @@ impl Cursor {
             virtual_blocks: payload.virtual_blocks,
             operator_address: payload.operator_address,
             transactions: payload.transactions,
+            gas_limit: payload.gas_limit,
         };
         self.actions.push_actions(self.inner.advance(block)).await;
         Ok(())
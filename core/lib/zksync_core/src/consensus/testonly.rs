@@ -83,6 +83,7 @@ impl MockMainNodeClient {
             virtual_blocks: Some(0),
             hash: Some(snapshot.miniblock_hash),
             protocol_version: ProtocolVersionId::latest(),
+            gas_limit: None,
         };
 
         Self {
@@ -140,6 +141,7 @@ impl MockMainNodeClient {
                 virtual_blocks: Some(!is_fictive as u32),
                 hash: Some(miniblock_hash),
                 protocol_version: ProtocolVersionId::latest(),
+                gas_limit: None,
             }
         });
 
@@ -310,6 +312,7 @@ impl StateKeeper {
                 operator_address: GenesisParams::mock().first_validator,
                 protocol_version: ProtocolVersionId::latest(),
                 first_miniblock_info: (self.last_block, 1),
+                first_miniblock_gas_limit: None,
             }
         } else {
             self.last_block += 1;
@@ -318,6 +321,7 @@ impl StateKeeper {
                 number: self.last_block,
                 timestamp: self.last_timestamp,
                 virtual_blocks: 0,
+                gas_limit: None,
             }
         }
     }
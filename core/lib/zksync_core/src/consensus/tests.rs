@@ -323,3 +323,58 @@ fn test_schema_encoding() {
     let rng = &mut ctx.rng();
     test_encode_random::<config::Config>(rng);
 }
+
+/// Decryptor XORing ciphertext with a fixed byte.
+#[derive(Debug)]
+struct XorDecryptor(u8);
+
+#[async_trait::async_trait]
+impl zksync_eth_signer::remote::SecretDecryptor for XorDecryptor {
+    async fn decrypt(
+        &self,
+        ciphertext: &[u8],
+    ) -> Result<zksync_eth_signer::remote::Zeroizing<Vec<u8>>, zksync_eth_signer::error::SignerError>
+    {
+        Ok(ciphertext
+            .iter()
+            .map(|byte| byte ^ self.0)
+            .collect::<Vec<_>>()
+            .into())
+    }
+}
+
+#[tokio::test]
+async fn reading_encrypted_secrets() {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+    use rand::Rng as _;
+    use zksync_consensus_crypto::TextFmt as _;
+    use zksync_consensus_roles::node;
+
+    let ctx = ctx::test_root(&ctx::RealClock);
+    let rng = &mut ctx.rng();
+    let key: node::SecretKey = rng.gen();
+    let encrypted: Vec<_> = key.encode().bytes().map(|byte| byte ^ 0x5a).collect();
+    std::env::set_var(
+        "TEST_ENCRYPTED_CONSENSUS_NODE_KEY",
+        BASE64.encode(encrypted),
+    );
+    std::env::set_var("TEST_PLAIN_CONSENSUS_NODE_KEY", key.encode());
+
+    let reader = config::SecretReader::new(Box::new(XorDecryptor(0x5a)));
+    let decrypted: node::SecretKey = reader
+        .read("TEST_ENCRYPTED_CONSENSUS_NODE_KEY")
+        .await
+        .unwrap();
+    assert_eq!(decrypted.public(), key.public());
+    let err = reader
+        .read::<node::SecretKey>("TEST_PLAIN_CONSENSUS_NODE_KEY")
+        .await
+        .unwrap_err();
+    assert!(!err.to_string().contains(&key.encode()), "{err}");
+
+    let plain: node::SecretKey = config::SecretReader::default()
+        .read("TEST_PLAIN_CONSENSUS_NODE_KEY")
+        .await
+        .unwrap();
+    assert_eq!(plain.public(), key.public());
+}
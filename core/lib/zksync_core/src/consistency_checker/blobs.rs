@@ -0,0 +1,234 @@
+//! Fetching and verifying EIP-4844 blobs published by commit transactions.
+
+use std::fmt;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::sync::OnceCell;
+use zksync_l1_contract_interface::i_executor::commit::kzg::{
+    kzg_commitment_to_versioned_hash, KzgInfo, KzgSettings, BYTES_PER_PUBDATA_COMMITMENT,
+    ZK_SYNC_BYTES_PER_BLOB,
+};
+use zksync_types::{Bytes, H256};
+
+/// Blob included into an L1 block together with its KZG commitment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobSidecar {
+    /// Index of the blob in the block.
+    pub index: u64,
+    pub blob: Vec<u8>,
+    pub kzg_commitment: Vec<u8>,
+}
+
+impl BlobSidecar {
+    fn versioned_hash(&self) -> H256 {
+        H256(kzg_commitment_to_versioned_hash(&self.kzg_commitment))
+    }
+}
+
+/// Client fetching blobs published on L1, e.g. from a beacon node.
+#[async_trait]
+pub trait BlobClient: fmt::Debug + Send + Sync {
+    /// Returns sidecars for all blobs included into the L1 block with the specified number and timestamp,
+    /// or `None` if blobs for the block are not available (e.g., have been pruned by the beacon node).
+    async fn blob_sidecars(
+        &self,
+        block_number: u64,
+        block_timestamp: u64,
+    ) -> anyhow::Result<Option<Vec<BlobSidecar>>>;
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconResponse<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconGenesis {
+    genesis_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconSpec {
+    #[serde(rename = "SECONDS_PER_SLOT")]
+    seconds_per_slot: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlobSidecar {
+    index: String,
+    blob: Bytes,
+    kzg_commitment: Bytes,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BeaconChainTiming {
+    genesis_time: u64,
+    seconds_per_slot: u64,
+}
+
+impl BeaconChainTiming {
+    fn slot(&self, block_timestamp: u64) -> anyhow::Result<u64> {
+        let since_genesis = block_timestamp
+            .checked_sub(self.genesis_time)
+            .with_context(|| {
+                format!(
+                    "L1 block timestamp {block_timestamp} precedes beacon chain genesis ({})",
+                    self.genesis_time
+                )
+            })?;
+        Ok(since_genesis / self.seconds_per_slot)
+    }
+}
+
+/// [`BlobClient`] implementation using the Ethereum beacon node API. Can also be pointed to a blob archive
+/// exposing the same `blob_sidecars` endpoint, which is useful to check blobs already pruned by beacon nodes.
+#[derive(Debug)]
+pub struct BeaconBlobClient {
+    inner: reqwest::Client,
+    url_base: String,
+    timing: OnceCell<BeaconChainTiming>,
+}
+
+impl BeaconBlobClient {
+    pub fn new(url_base: &str) -> Self {
+        Self {
+            inner: reqwest::Client::new(),
+            url_base: url_base.trim_end_matches('/').to_owned(),
+            timing: OnceCell::new(),
+        }
+    }
+
+    /// Returns `Ok(None)` if the requested resource is not found.
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<Option<T>> {
+        let url = format!("{}{path}", self.url_base);
+        let response = self
+            .inner
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed requesting `{path}` from beacon node"))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status().with_context(|| {
+            format!("Requesting `{path}` from beacon node returned non-OK response")
+        })?;
+        let response: BeaconResponse<T> = response
+            .json()
+            .await
+            .with_context(|| format!("Failed deserializing `{path}` response from beacon node"))?;
+        Ok(Some(response.data))
+    }
+
+    async fn fetch_timing(&self) -> anyhow::Result<BeaconChainTiming> {
+        let genesis: BeaconGenesis = self
+            .get("/eth/v1/beacon/genesis")
+            .await?
+            .context("beacon node doesn't know genesis")?;
+        let spec: BeaconSpec = self
+            .get("/eth/v1/config/spec")
+            .await?
+            .context("beacon node doesn't return chain spec")?;
+        let timing = BeaconChainTiming {
+            genesis_time: genesis
+                .genesis_time
+                .parse()
+                .context("invalid beacon chain genesis time")?,
+            seconds_per_slot: spec
+                .seconds_per_slot
+                .parse()
+                .context("invalid `SECONDS_PER_SLOT` in beacon chain spec")?,
+        };
+        anyhow::ensure!(
+            timing.seconds_per_slot > 0,
+            "`SECONDS_PER_SLOT` in beacon chain spec is zero"
+        );
+        Ok(timing)
+    }
+}
+
+#[async_trait]
+impl BlobClient for BeaconBlobClient {
+    async fn blob_sidecars(
+        &self,
+        block_number: u64,
+        block_timestamp: u64,
+    ) -> anyhow::Result<Option<Vec<BlobSidecar>>> {
+        let timing = self.timing.get_or_try_init(|| self.fetch_timing()).await?;
+        let slot = timing.slot(block_timestamp)?;
+        tracing::debug!("Fetching blob sidecars for L1 block #{block_number} (slot {slot})");
+
+        let path = format!("/eth/v1/beacon/blob_sidecars/{slot}");
+        let Some(sidecars) = self.get::<Vec<BeaconBlobSidecar>>(&path).await? else {
+            return Ok(None);
+        };
+        let sidecars = sidecars
+            .into_iter()
+            .map(|sidecar| {
+                Ok(BlobSidecar {
+                    index: sidecar
+                        .index
+                        .parse()
+                        .context("invalid blob sidecar index")?,
+                    blob: sidecar.blob.0,
+                    kzg_commitment: sidecar.kzg_commitment.0,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        // Beacon nodes return an empty list for blocks with pruned blobs.
+        Ok((!sidecars.is_empty()).then_some(sidecars))
+    }
+}
+
+/// Mismatch between blobs published on L1 and local pubdata.
+#[derive(Debug, thiserror::Error)]
+pub(super) enum BlobMismatch {
+    #[error(
+        "commit transaction references {l1} blob(s), while local pubdata occupies {local} blob(s)"
+    )]
+    BlobCount { l1: usize, local: usize },
+    #[error("blob #{0} with versioned hash {1:?} is not included into the L1 block")]
+    MissingBlob(usize, H256),
+    #[error("contents of blob #{0} differ from local pubdata")]
+    Contents(usize),
+    #[error("KZG commitment of blob #{0} differs from the one computed for local pubdata")]
+    KzgCommitment(usize),
+}
+
+/// Checks blobs published on L1 against local pubdata. Blobs are found among `sidecars` using versioned hashes
+/// of KZG commitments in `pubdata_commitments` (i.e., the ones that are verified by L1 contracts).
+pub(super) fn verify_blobs(
+    pubdata_commitments: &[&[u8; BYTES_PER_PUBDATA_COMMITMENT]],
+    sidecars: &[BlobSidecar],
+    local_pubdata: &[u8],
+    kzg_settings: &KzgSettings,
+) -> Result<(), BlobMismatch> {
+    let local_blobs: Vec<_> = local_pubdata.chunks(ZK_SYNC_BYTES_PER_BLOB).collect();
+    if local_blobs.len() != pubdata_commitments.len() {
+        return Err(BlobMismatch::BlobCount {
+            l1: pubdata_commitments.len(),
+            local: local_blobs.len(),
+        });
+    }
+
+    for (i, (commitment, local_blob)) in pubdata_commitments.iter().zip(local_blobs).enumerate() {
+        // Pubdata commitment format: opening point (16 bytes) || claimed value (32 bytes)
+        // || KZG commitment (48 bytes) || opening proof (48 bytes)
+        let versioned_hash = H256(kzg_commitment_to_versioned_hash(&commitment[48..96]));
+        let sidecar = sidecars
+            .iter()
+            .find(|sidecar| sidecar.versioned_hash() == versioned_hash)
+            .ok_or(BlobMismatch::MissingBlob(i, versioned_hash))?;
+
+        let local_info = KzgInfo::new(kzg_settings, local_blob);
+        if sidecar.blob != local_info.blob {
+            return Err(BlobMismatch::Contents(i));
+        }
+        if sidecar.kzg_commitment != local_info.kzg_commitment {
+            return Err(BlobMismatch::KzgCommitment(i));
+        }
+    }
+    Ok(())
+}
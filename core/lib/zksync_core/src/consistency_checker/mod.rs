@@ -3,21 +3,37 @@ use std::{fmt, sync::Arc, time::Duration};
 use anyhow::Context as _;
 use serde::Serialize;
 use tokio::sync::watch;
+use zksync_config::configs::eth_client::L1FinalitySource;
 use zksync_contracts::PRE_BOOJUM_COMMIT_FUNCTION;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_eth_client::{clients::QueryClient, Error as L1ClientError, EthInterface};
+use zksync_eth_client::{
+    clients::QueryClient, finalized_block_number, Error as L1ClientError, EthInterface,
+};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::{
-    i_executor::{commit::kzg::KzgSettings, structures::CommitBatchInfo},
+    i_executor::{
+        commit::kzg::KzgSettings,
+        structures::{CommitBatchInfo, PubdataPayload},
+    },
     Tokenizable,
 };
-use zksync_types::{pubdata_da::PubdataDA, web3::ethabi, L1BatchNumber, H256};
+use zksync_types::{
+    commitment::L1BatchCommitmentMode,
+    pubdata_da::PubdataDA,
+    web3::{
+        ethabi,
+        types::{BlockId, BlockNumber},
+    },
+    L1BatchNumber, H256, U64,
+};
 
+pub use self::blobs::{BeaconBlobClient, BlobClient, BlobSidecar};
 use crate::{
     metrics::{CheckerComponent, EN_METRICS},
     utils::wait_for_l1_batch_with_metadata,
 };
 
+mod blobs;
 #[cfg(test)]
 mod tests;
 
@@ -25,6 +41,8 @@ mod tests;
 enum CheckError {
     #[error("Web3 error communicating with L1")]
     Web3(#[from] L1ClientError),
+    #[error("Error fetching blobs from L1")]
+    Blobs(#[source] anyhow::Error),
     #[error("Internal error")]
     Internal(#[from] anyhow::Error),
 }
@@ -129,6 +147,8 @@ struct LocalL1BatchCommitData {
     /// Vector of possible encodings of L1 commit data.
     l1_commit_data_variants: Vec<ethabi::Token>,
     commit_tx_hash: H256,
+    /// Pubdata of a post-Boojum batch; used to check blobs published by the commit transaction.
+    pubdata: Option<Vec<u8>>,
 }
 
 impl LocalL1BatchCommitData {
@@ -137,6 +157,7 @@ impl LocalL1BatchCommitData {
     async fn new(
         storage: &mut StorageProcessor<'_>,
         batch_number: L1BatchNumber,
+        commitment_mode: L1BatchCommitmentMode,
         kzg_settings: Option<Arc<KzgSettings>>,
     ) -> anyhow::Result<Option<Self>> {
         let Some(storage_l1_batch) = storage
@@ -183,21 +204,59 @@ impl LocalL1BatchCommitData {
             return Ok(None);
         }
 
-        // Encoding data using `PubdataDA::Blobs` or `PubdataDA::Blobs` never panics because we check
-        // protocol version in `CommitBatchInfo`.
-        let variants = vec![PubdataDA::Calldata, PubdataDA::Blobs];
+        // If the DA mode used by the commit transaction is recorded (i.e., on the main node), only this mode is checked.
+        let variants = match storage
+            .blocks_dal()
+            .get_commit_pubdata_da(batch_number)
+            .await?
+        {
+            Some(pubdata_da) => vec![pubdata_da],
+            None => vec![PubdataDA::Calldata, PubdataDA::Blobs, PubdataDA::Custom],
+        };
+        // DA inclusion data is only available if the DA dispatcher runs on this node.
+        let da_inclusion_data = storage
+            .data_availability_dal()
+            .get_da_blob(batch_number)
+            .await?
+            .and_then(|blob| blob.inclusion_data);
 
-        // Iterate over possible `PubdataDA` used for encoding `CommitBatchInfo`.
-        let l1_commit_data_variants = variants
+        // Iterate over possible `PubdataDA` used for encoding `CommitBatchInfo`. Some variants may be
+        // inapplicable to the batch (e.g., calldata DA for pubdata that doesn't fit into a single blob).
+        // For validium chains, pubdata is not published on L1, so that only the pubdata source byte is checked.
+        let l1_commit_data_variants: Vec<_> = variants
             .into_iter()
-            .map(|pubdata_da| {
-                CommitBatchInfo::new(&l1_batch, pubdata_da, kzg_settings.clone()).into_token()
+            .filter_map(|pubdata_da| {
+                let info = CommitBatchInfo::builder(&l1_batch, pubdata_da)
+                    .with_commitment_mode(commitment_mode)
+                    .with_kzg_settings(kzg_settings.clone())
+                    .with_da_inclusion_data(da_inclusion_data.as_deref())
+                    .build();
+                match info {
+                    Ok(info) => Some(info.into_token()),
+                    Err(err) => {
+                        tracing::debug!("Cannot encode commit data using {pubdata_da:?}: {err}");
+                        None
+                    }
+                }
             })
             .collect();
+        anyhow::ensure!(
+            !l1_commit_data_variants.is_empty(),
+            "cannot encode commit data for L1 batch #{}",
+            l1_batch.header.number
+        );
+        let pubdata = (!is_pre_boojum).then(|| {
+            l1_batch
+                .header
+                .pubdata_input
+                .clone()
+                .unwrap_or_else(|| l1_batch.construct_pubdata())
+        });
         Ok(Some(Self {
             is_pre_boojum,
             l1_commit_data_variants,
             commit_tx_hash,
+            pubdata,
         }))
     }
 }
@@ -214,7 +273,12 @@ pub struct ConsistencyChecker {
     l1_data_mismatch_behavior: L1DataMismatchBehavior,
     pool: ConnectionPool,
     health_check: ReactiveHealthCheck,
+    commitment_mode: L1BatchCommitmentMode,
     kzg_settings: Option<Arc<KzgSettings>>,
+    /// If set, batches are only checked after their commit transaction is reorg-safe.
+    finality_source: Option<L1FinalitySource>,
+    /// If set, blobs published by commit transactions are fetched and checked against local pubdata.
+    blob_client: Option<Box<dyn BlobClient>>,
 }
 
 impl ConsistencyChecker {
@@ -224,6 +288,7 @@ impl ConsistencyChecker {
         web3_url: &str,
         max_batches_to_recheck: u32,
         pool: ConnectionPool,
+        commitment_mode: L1BatchCommitmentMode,
         kzg_settings: Option<Arc<KzgSettings>>,
     ) -> Self {
         let web3 = QueryClient::new(web3_url).unwrap();
@@ -237,15 +302,60 @@ impl ConsistencyChecker {
             l1_data_mismatch_behavior: L1DataMismatchBehavior::Log,
             pool,
             health_check,
+            commitment_mode,
             kzg_settings,
+            finality_source: None,
+            blob_client: None,
         }
     }
 
+    /// Sets the L1 finality source. If set, the checker waits until a commit transaction is reorg-safe
+    /// before checking the corresponding L1 batch; otherwise, batches are checked as soon as they are committed.
+    pub fn with_finality_source(mut self, finality_source: Option<L1FinalitySource>) -> Self {
+        self.finality_source = finality_source;
+        self
+    }
+
+    /// Sets the client used to fetch blobs published by commit transactions. If not set, only commit calldata
+    /// is checked for batches that publish pubdata in blobs.
+    pub fn with_blob_client(mut self, blob_client: Option<Box<dyn BlobClient>>) -> Self {
+        self.blob_client = blob_client;
+        self
+    }
+
     /// Returns health check associated with this checker.
     pub fn health_check(&self) -> &ReactiveHealthCheck {
         &self.health_check
     }
 
+    /// Checks whether the commit transaction for an L1 batch is reorg-safe according to the finality source.
+    async fn is_commit_tx_finalized(
+        &self,
+        local: &LocalL1BatchCommitData,
+    ) -> Result<bool, CheckError> {
+        let Some(finality_source) = self.finality_source else {
+            return Ok(true);
+        };
+        let commit_tx_hash = local.commit_tx_hash;
+        let commit_tx_status = self
+            .l1_client
+            .get_tx_status(commit_tx_hash, "consistency_checker")
+            .await?;
+        let Some(commit_block_number) =
+            commit_tx_status.and_then(|status| status.receipt.block_number)
+        else {
+            // The transaction is not mined from the point of view of the L1 node yet (e.g., because of an L1 reorg).
+            return Ok(false);
+        };
+        let finalized_block_number = finalized_block_number(
+            self.l1_client.as_ref(),
+            finality_source,
+            "consistency_checker",
+        )
+        .await?;
+        Ok(commit_block_number <= finalized_block_number)
+    }
+
     async fn check_commitments(
         &self,
         batch_number: L1BatchNumber,
@@ -259,6 +369,7 @@ impl ConsistencyChecker {
             .get_tx_status(commit_tx_hash, "consistency_checker")
             .await?
             .with_context(|| format!("Receipt for tx {commit_tx_hash:?} not found on L1"))?;
+        let commit_block_number = commit_tx_status.receipt.block_number;
         if !commit_tx_status.success {
             let err = anyhow::anyhow!("Main node gave us a failed commit tx");
             return Err(err.into());
@@ -286,16 +397,108 @@ impl ConsistencyChecker {
                     format!("Failed extracting commit data for transaction {commit_tx_hash:?}")
                 })?;
 
-        Ok(local.l1_commit_data_variants.contains(&commitment))
+        if !local.l1_commit_data_variants.contains(&commitment) {
+            return Ok(false);
+        }
+        let Some(blob_client) = &self.blob_client else {
+            return Ok(true);
+        };
+        let commit_block_number = commit_block_number.with_context(|| {
+            format!("Commit tx {commit_tx_hash:?} is not included into a block")
+        })?;
+        self.check_blobs(
+            blob_client.as_ref(),
+            batch_number,
+            local,
+            commitment,
+            commit_block_number,
+        )
+        .await
     }
 
-    fn extract_commit_data(
+    /// Checks blobs published by the commit transaction (if any) against local pubdata.
+    async fn check_blobs(
+        &self,
+        blob_client: &dyn BlobClient,
+        batch_number: L1BatchNumber,
+        local: &LocalL1BatchCommitData,
+        commitment: ethabi::Token,
+        commit_block_number: U64,
+    ) -> Result<bool, CheckError> {
+        let Some(local_pubdata) = &local.pubdata else {
+            return Ok(true); // Pre-Boojum batches cannot use blobs
+        };
+        let commit_info =
+            CommitBatchInfo::from_token(commitment).context("Failed decoding L1 commit data")?;
+        let Ok(PubdataPayload::Blobs(pubdata_commitments)) = commit_info.pubdata_payload() else {
+            return Ok(true); // Pubdata is not published in blobs; it's fully checked by comparing calldata
+        };
+
+        let block = self
+            .l1_client
+            .block(
+                BlockId::Number(BlockNumber::Number(commit_block_number)),
+                "consistency_checker",
+            )
+            .await?
+            .with_context(|| format!("L1 block #{commit_block_number} not found"))?;
+        let block_timestamp = u64::try_from(block.timestamp)
+            .map_err(|err| anyhow::anyhow!("L1 block timestamp overflow: {err}"))?;
+        let sidecars = blob_client
+            .blob_sidecars(commit_block_number.as_u64(), block_timestamp)
+            .await
+            .map_err(CheckError::Blobs)?;
+        let Some(sidecars) = sidecars else {
+            tracing::warn!(
+                "Blobs for L1 block #{commit_block_number} are not available; skipping blob checks \
+                 for L1 batch #{batch_number}"
+            );
+            return Ok(true);
+        };
+
+        let kzg_settings = self
+            .kzg_settings
+            .clone()
+            .context("KZG settings are required to check blobs")?;
+        let local_pubdata = local_pubdata.clone();
+        let pubdata_commitments: Vec<_> = pubdata_commitments.into_iter().copied().collect();
+        // Computing KZG info is CPU-heavy, so we don't want to block the async runtime.
+        let verification_result = tokio::task::spawn_blocking(move || {
+            let pubdata_commitments: Vec<_> = pubdata_commitments.iter().collect();
+            blobs::verify_blobs(
+                &pubdata_commitments,
+                &sidecars,
+                &local_pubdata,
+                &kzg_settings,
+            )
+        })
+        .await
+        .context("blob verification panicked")?;
+
+        if let Err(err) = verification_result {
+            tracing::error!(
+                "Blobs published by commit tx {:?} for L1 batch #{batch_number} don't match \
+                 local pubdata: {err}",
+                local.commit_tx_hash
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Extracts commitment data for the specified L1 batch from the calldata of a commit transaction.
+    /// Public to be usable in fuzz targets; calldata is untrusted, so this method must not panic on any input.
+    #[doc(hidden)]
+    pub fn extract_commit_data(
         commit_tx_input_data: &[u8],
         commit_function: &ethabi::Function,
         batch_number: L1BatchNumber,
     ) -> anyhow::Result<ethabi::Token> {
+        let commit_tx_args = commit_tx_input_data
+            .get(4..)
+            .context("L1 commit calldata is too short to contain function selector")?;
         let mut commit_input_tokens = commit_function
-            .decode_input(&commit_tx_input_data[4..])
+            .decode_input(commit_tx_args)
             .with_context(|| format!("Failed decoding calldata for L1 commit function"))?;
         let mut commitments = commit_input_tokens
             .pop()
@@ -328,7 +531,8 @@ impl ConsistencyChecker {
                 (offset < commitments.len()).then(|| commitments.swap_remove(offset))
             });
         commitment.with_context(|| {
-            let actual_range = first_batch_number..(first_batch_number + commitments.len());
+            let actual_range =
+                first_batch_number..first_batch_number.saturating_add(commitments.len());
             format!(
                 "Malformed commitment data; it should prove L1 batch #{batch_number}, \
                  but it actually proves batches #{actual_range:?}"
@@ -388,15 +592,37 @@ impl ConsistencyChecker {
             // The batch might be already committed but not yet processed by the external node's tree
             // OR the batch might be processed by the external node's tree but not yet committed.
             // We need both.
-            let Some(local) =
-                LocalL1BatchCommitData::new(&mut storage, batch_number, self.kzg_settings.clone())
-                    .await?
+            let Some(local) = LocalL1BatchCommitData::new(
+                &mut storage,
+                batch_number,
+                self.commitment_mode,
+                self.kzg_settings.clone(),
+            )
+            .await?
             else {
                 tokio::time::sleep(self.sleep_interval).await;
                 continue;
             };
             drop(storage);
 
+            match self.is_commit_tx_finalized(&local).await {
+                Ok(true) => { /* continue checking the batch */ }
+                Ok(false) => {
+                    tracing::debug!(
+                        "Commit tx {:?} for L1 batch #{batch_number} is not finalized on L1 yet",
+                        local.commit_tx_hash
+                    );
+                    tokio::time::sleep(self.sleep_interval).await;
+                    continue;
+                }
+                Err(CheckError::Web3(err)) => {
+                    tracing::warn!("Error accessing L1; will retry after a delay: {err}");
+                    tokio::time::sleep(self.sleep_interval).await;
+                    continue;
+                }
+                Err(CheckError::Blobs(err) | CheckError::Internal(err)) => return Err(err),
+            }
+
             match self.check_commitments(batch_number, &local).await {
                 Ok(true) => {
                     self.event_handler.update_checked_batch(batch_number);
@@ -418,6 +644,10 @@ impl ConsistencyChecker {
                     tracing::warn!("Error accessing L1; will retry after a delay: {err}");
                     tokio::time::sleep(self.sleep_interval).await;
                 }
+                Err(CheckError::Blobs(err)) => {
+                    tracing::warn!("Error fetching blobs; will retry after a delay: {err:#}");
+                    tokio::time::sleep(self.sleep_interval).await;
+                }
                 Err(CheckError::Internal(err)) => {
                     let context =
                         format!("Failed verifying consistency of L1 batch #{batch_number}");
@@ -3,12 +3,16 @@
 use std::{collections::HashMap, slice};
 
 use assert_matches::assert_matches;
+use async_trait::async_trait;
 use test_casing::{test_casing, Product};
 use tokio::sync::mpsc;
 use zksync_config::configs::KzgConfig;
 use zksync_dal::StorageProcessor;
 use zksync_eth_client::{clients::MockEthereum, Options};
-use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
+use zksync_l1_contract_interface::i_executor::{
+    commit::kzg::{pubdata_to_kzg_info, KzgSettingsProvider},
+    structures::StoredBatchInfo,
+};
 use zksync_types::{
     aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata, L2ChainId,
     ProtocolVersion, ProtocolVersionId, H256,
@@ -45,12 +49,37 @@ fn create_pre_boojum_l1_batch_with_metadata(number: u32) -> L1BatchWithMetadata
     l1_batch
 }
 
+fn test_kzg_settings() -> Arc<KzgSettings> {
+    KzgSettingsProvider::from_path(KzgConfig::for_tests().trusted_setup_path)
+        .get()
+        .unwrap()
+}
+
 fn build_commit_tx_input_data(
     batches: &[L1BatchWithMetadata],
     kzg_settings: Arc<KzgSettings>,
+) -> Vec<u8> {
+    build_commit_tx_input_data_with_mode(
+        batches,
+        L1BatchCommitmentMode::Rollup,
+        PubdataDA::Calldata,
+        kzg_settings,
+    )
+}
+
+fn build_commit_tx_input_data_with_mode(
+    batches: &[L1BatchWithMetadata],
+    commitment_mode: L1BatchCommitmentMode,
+    pubdata_da: PubdataDA,
+    kzg_settings: Arc<KzgSettings>,
 ) -> Vec<u8> {
     let commit_tokens = batches.iter().map(|batch| {
-        CommitBatchInfo::new(batch, PubdataDA::Calldata, Some(kzg_settings.clone())).into_token()
+        CommitBatchInfo::builder(batch, pubdata_da)
+            .with_commitment_mode(commitment_mode)
+            .with_kzg_settings(Some(kzg_settings.clone()))
+            .build()
+            .unwrap()
+            .into_token()
     });
     let commit_tokens = ethabi::Token::Array(commit_tokens.collect());
 
@@ -60,7 +89,7 @@ fn build_commit_tx_input_data(
     // Mock an additional argument used in real `commitBlocks` / `commitBatches`. In real transactions,
     // it's taken from the L1 batch previous to `batches[0]`, but since this argument is not checked,
     // it's OK to use `batches[0]`.
-    let prev_header_tokens = StoredBatchInfo(&batches[0]).into_token();
+    let prev_header_tokens = StoredBatchInfo::from(&batches[0]).into_token();
     encoded.extend_from_slice(&ethabi::encode(&[prev_header_tokens, commit_tokens]));
     encoded
 }
@@ -76,9 +105,10 @@ fn create_mock_checker(client: MockEthereum, pool: ConnectionPool) -> Consistenc
         l1_data_mismatch_behavior: L1DataMismatchBehavior::Bail,
         pool,
         health_check,
-        kzg_settings: Some(Arc::new(KzgSettings::new(
-            &KzgConfig::for_tests().trusted_setup_path,
-        ))),
+        commitment_mode: L1BatchCommitmentMode::Rollup,
+        kzg_settings: Some(test_kzg_settings()),
+        finality_source: None,
+        blob_client: None,
     }
 }
 
@@ -109,7 +139,7 @@ fn build_commit_tx_input_data_is_correct() {
         create_l1_batch_with_metadata(2),
     ];
 
-    let kzg_settings = Arc::new(KzgSettings::new(&KzgConfig::for_tests().trusted_setup_path));
+    let kzg_settings = test_kzg_settings();
     let commit_tx_input_data = build_commit_tx_input_data(&batches, kzg_settings.clone());
 
     for batch in &batches {
@@ -121,7 +151,10 @@ fn build_commit_tx_input_data_is_correct() {
         .unwrap();
         assert_eq!(
             commit_data,
-            CommitBatchInfo::new(batch, PubdataDA::Calldata, Some(kzg_settings.clone()))
+            CommitBatchInfo::builder(batch, PubdataDA::Calldata)
+                .with_kzg_settings(Some(kzg_settings.clone()))
+                .build()
+                .unwrap()
                 .into_token()
         );
     }
@@ -189,6 +222,24 @@ fn extracting_commit_data_for_multiple_batches() {
     }
 }
 
+#[test]
+fn extracting_commit_data_from_malformed_calldata() {
+    let contract = zksync_contracts::zksync_contract();
+    let commit_function = contract.function("commitBatches").unwrap();
+    let commit_tx_input_data = include_bytes!("commit_l1_batch_4470_testnet_sepolia.calldata");
+
+    let malformed_inputs = [
+        &[][..],
+        &commit_tx_input_data[..3],
+        &commit_tx_input_data[..4],
+        &commit_tx_input_data[..commit_tx_input_data.len() / 2],
+    ];
+    for input in malformed_inputs {
+        ConsistencyChecker::extract_commit_data(input, commit_function, L1BatchNumber(4_470))
+            .unwrap_err();
+    }
+}
+
 #[test]
 fn extracting_commit_data_for_pre_boojum_batch() {
     // Calldata taken from the commit transaction for `https://goerli.explorer.zksync.io/batch/200000`;
@@ -330,7 +381,7 @@ async fn normal_checker_function(
     let l1_batches: Vec<_> = (1..=10).map(create_l1_batch_with_metadata).collect();
     let mut commit_tx_hash_by_l1_batch = HashMap::with_capacity(l1_batches.len());
     let client = MockEthereum::default();
-    let kzg_settings = Arc::new(KzgSettings::new(&KzgConfig::for_tests().trusted_setup_path));
+    let kzg_settings = test_kzg_settings();
 
     for (i, l1_batches) in l1_batches.chunks(batches_per_transaction).enumerate() {
         let input_data = build_commit_tx_input_data(l1_batches, kzg_settings.clone());
@@ -409,7 +460,7 @@ async fn checker_processes_pre_boojum_batches(
         .collect();
     let mut commit_tx_hash_by_l1_batch = HashMap::with_capacity(l1_batches.len());
     let client = MockEthereum::default();
-    let kzg_settings = Arc::new(KzgSettings::new(&KzgConfig::for_tests().trusted_setup_path));
+    let kzg_settings = test_kzg_settings();
 
     for (i, l1_batch) in l1_batches.iter().enumerate() {
         let input_data =
@@ -469,7 +520,7 @@ async fn checker_functions_after_snapshot_recovery(delay_batch_insertion: bool)
         .await;
 
     let l1_batch = create_l1_batch_with_metadata(99);
-    let kzg_settings = Arc::new(KzgSettings::new(&KzgConfig::for_tests().trusted_setup_path));
+    let kzg_settings = test_kzg_settings();
 
     let commit_tx_input_data = build_commit_tx_input_data(slice::from_ref(&l1_batch), kzg_settings);
     let client = MockEthereum::default();
@@ -546,7 +597,7 @@ impl IncorrectDataKind {
     ];
 
     async fn apply(self, client: &MockEthereum, l1_batch: &L1BatchWithMetadata) -> H256 {
-        let kzg_settings = Arc::new(KzgSettings::new(&KzgConfig::for_tests().trusted_setup_path));
+        let kzg_settings = test_kzg_settings();
         let (commit_tx_input_data, successful_status) = match self {
             Self::MissingStatus => {
                 return H256::zero(); // Do not execute the transaction
@@ -645,3 +696,314 @@ async fn checker_detects_incorrect_tx_data(kind: IncorrectDataKind, snapshot_rec
 async fn checker_detects_incorrect_tx_data_after_snapshot_recovery() {
     checker_detects_incorrect_tx_data(IncorrectDataKind::CommitDataForAnotherBatch, true).await;
 }
+
+async fn commit_l1_batches_with_mode(
+    client: &MockEthereum,
+    l1_batches: &[L1BatchWithMetadata],
+    commitment_mode: L1BatchCommitmentMode,
+) -> H256 {
+    let kzg_settings = test_kzg_settings();
+    let input_data = build_commit_tx_input_data_with_mode(
+        l1_batches,
+        commitment_mode,
+        PubdataDA::Calldata,
+        kzg_settings,
+    );
+    let signed_tx = client.sign_prepared_tx(
+        input_data,
+        Options {
+            nonce: Some(0.into()),
+            ..Options::default()
+        },
+    );
+    let signed_tx = signed_tx.unwrap();
+    client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    client.execute_tx(signed_tx.hash, true, 1);
+    signed_tx.hash
+}
+
+#[tokio::test]
+async fn checker_processes_validium_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batches: Vec<_> = (1..=3).map(create_l1_batch_with_metadata).collect();
+    let client = MockEthereum::default();
+    let commit_tx_hash =
+        commit_l1_batches_with_mode(&client, &l1_batches, L1BatchCommitmentMode::Validium).await;
+    let commit_tx_hash_by_l1_batch: HashMap<_, _> = l1_batches
+        .iter()
+        .map(|batch| (batch.header.number, commit_tx_hash))
+        .collect();
+    for save_action in SAVE_ACTION_MAPPERS[0].1(&l1_batches) {
+        save_action
+            .apply(&mut storage, &commit_tx_hash_by_l1_batch)
+            .await;
+    }
+    drop(storage);
+
+    let (l1_batch_updates_sender, mut l1_batch_updates_receiver) = mpsc::unbounded_channel();
+    let checker = ConsistencyChecker {
+        event_handler: Box::new(l1_batch_updates_sender),
+        commitment_mode: L1BatchCommitmentMode::Validium,
+        // KZG settings are not required for validium chains.
+        kzg_settings: None,
+        ..create_mock_checker(client, pool)
+    };
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    loop {
+        let checked_batch = l1_batch_updates_receiver.recv().await.unwrap();
+        if checked_batch == l1_batches.last().unwrap().header.number {
+            break;
+        }
+    }
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+}
+
+#[test_casing(2, [L1BatchCommitmentMode::Rollup, L1BatchCommitmentMode::Validium])]
+#[tokio::test]
+async fn checker_detects_commitment_mode_mismatch(commitment_mode: L1BatchCommitmentMode) {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batch = create_l1_batch_with_metadata(1);
+    let client = MockEthereum::default();
+    let commit_tx_hash =
+        commit_l1_batches_with_mode(&client, slice::from_ref(&l1_batch), commitment_mode).await;
+    let commit_tx_hash_by_l1_batch = HashMap::from([(l1_batch.header.number, commit_tx_hash)]);
+    for save_action in SAVE_ACTION_MAPPERS[0].1(slice::from_ref(&l1_batch)) {
+        save_action
+            .apply(&mut storage, &commit_tx_hash_by_l1_batch)
+            .await;
+    }
+    drop(storage);
+
+    let checker_commitment_mode = match commitment_mode {
+        L1BatchCommitmentMode::Rollup => L1BatchCommitmentMode::Validium,
+        L1BatchCommitmentMode::Validium => L1BatchCommitmentMode::Rollup,
+    };
+    let checker = ConsistencyChecker {
+        commitment_mode: checker_commitment_mode,
+        ..create_mock_checker(client, pool)
+    };
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    // The checker must stop with an error.
+    tokio::time::timeout(Duration::from_secs(30), checker.run(stop_receiver))
+        .await
+        .expect("Timed out waiting for checker to stop")
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn checker_waits_for_commit_tx_finality() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batches: Vec<_> = (1..=3).map(create_l1_batch_with_metadata).collect();
+    let client = Arc::new(MockEthereum::default());
+    client.advance_block_number(10);
+    let commit_tx_hash =
+        commit_l1_batches_with_mode(&client, &l1_batches, L1BatchCommitmentMode::Rollup).await;
+    let commit_tx_hash_by_l1_batch: HashMap<_, _> = l1_batches
+        .iter()
+        .map(|batch| (batch.header.number, commit_tx_hash))
+        .collect();
+    for save_action in SAVE_ACTION_MAPPERS[0].1(&l1_batches) {
+        save_action
+            .apply(&mut storage, &commit_tx_hash_by_l1_batch)
+            .await;
+    }
+    drop(storage);
+
+    let (l1_batch_updates_sender, mut l1_batch_updates_receiver) = mpsc::unbounded_channel();
+    let checker = ConsistencyChecker {
+        event_handler: Box::new(l1_batch_updates_sender),
+        l1_client: Box::new(client.clone()),
+        finality_source: Some(L1FinalitySource::Confirmations(5)),
+        ..create_mock_checker(MockEthereum::default(), pool)
+    };
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    // The commit tx is mined in block #10, while the finalized block is #6.
+    let recv_result =
+        tokio::time::timeout(Duration::from_millis(100), l1_batch_updates_receiver.recv()).await;
+    assert!(recv_result.is_err(), "{recv_result:?}");
+
+    client.advance_block_number(10);
+    loop {
+        let checked_batch = l1_batch_updates_receiver.recv().await.unwrap();
+        if checked_batch == l1_batches.last().unwrap().header.number {
+            break;
+        }
+    }
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+}
+
+/// Mock [`BlobClient`] returning blob sidecars by L1 block number.
+#[derive(Debug, Default)]
+struct MockBlobClient(HashMap<u64, Vec<BlobSidecar>>);
+
+#[async_trait]
+impl BlobClient for MockBlobClient {
+    async fn blob_sidecars(
+        &self,
+        block_number: u64,
+        _block_timestamp: u64,
+    ) -> anyhow::Result<Option<Vec<BlobSidecar>>> {
+        Ok(self.0.get(&block_number).cloned())
+    }
+}
+
+fn create_blob_sidecars(l1_batch: &L1BatchWithMetadata) -> Vec<BlobSidecar> {
+    let kzg_settings = test_kzg_settings();
+    let pubdata = l1_batch.construct_pubdata();
+    pubdata_to_kzg_info(&pubdata, &kzg_settings)
+        .into_iter()
+        .enumerate()
+        .map(|(i, kzg_info)| BlobSidecar {
+            index: i as u64,
+            blob: kzg_info.blob.to_vec(),
+            kzg_commitment: kzg_info.kzg_commitment.to_vec(),
+        })
+        .collect()
+}
+
+/// Commits an L1 batch with pubdata published in blobs. Returns the commit tx hash and the number
+/// of the L1 block the transaction is included in.
+async fn commit_l1_batch_with_blobs(
+    client: &MockEthereum,
+    l1_batch: &L1BatchWithMetadata,
+) -> (H256, u64) {
+    let kzg_settings = test_kzg_settings();
+    let input_data = build_commit_tx_input_data_with_mode(
+        slice::from_ref(l1_batch),
+        L1BatchCommitmentMode::Rollup,
+        PubdataDA::Blobs,
+        kzg_settings,
+    );
+    let signed_tx = client.sign_prepared_tx(
+        input_data,
+        Options {
+            nonce: Some(0.into()),
+            ..Options::default()
+        },
+    );
+    let signed_tx = signed_tx.unwrap();
+    client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    let block_number = client.block_number("test").await.unwrap().as_u64();
+    client.execute_tx(signed_tx.hash, true, 1);
+    (signed_tx.hash, block_number)
+}
+
+async fn prepare_storage_for_blob_checks(
+    pool: &ConnectionPool,
+    l1_batch: &L1BatchWithMetadata,
+    commit_tx_hash: H256,
+) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let commit_tx_hash_by_l1_batch = HashMap::from([(l1_batch.header.number, commit_tx_hash)]);
+    for save_action in SAVE_ACTION_MAPPERS[0].1(slice::from_ref(l1_batch)) {
+        save_action
+            .apply(&mut storage, &commit_tx_hash_by_l1_batch)
+            .await;
+    }
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn checker_checks_blobs(blobs_available: bool) {
+    let pool = ConnectionPool::test_pool().await;
+    let l1_batch = create_l1_batch_with_metadata(1);
+    let client = MockEthereum::default();
+    let (commit_tx_hash, block_number) = commit_l1_batch_with_blobs(&client, &l1_batch).await;
+    prepare_storage_for_blob_checks(&pool, &l1_batch, commit_tx_hash).await;
+
+    let mut blob_client = MockBlobClient::default();
+    if blobs_available {
+        let sidecars = create_blob_sidecars(&l1_batch);
+        assert!(!sidecars.is_empty());
+        blob_client.0.insert(block_number, sidecars);
+    }
+    // If blobs are unavailable, the checker should fall back to checking calldata.
+
+    let (l1_batch_updates_sender, mut l1_batch_updates_receiver) = mpsc::unbounded_channel();
+    let checker = ConsistencyChecker {
+        event_handler: Box::new(l1_batch_updates_sender),
+        ..create_mock_checker(client, pool)
+    }
+    .with_blob_client(Some(Box::new(blob_client)));
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    let checked_batch = l1_batch_updates_receiver.recv().await.unwrap();
+    assert_eq!(checked_batch, l1_batch.header.number);
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BlobMismatchKind {
+    Contents,
+    KzgCommitment,
+    MissingBlob,
+}
+
+impl BlobMismatchKind {
+    const ALL: [Self; 3] = [Self::Contents, Self::KzgCommitment, Self::MissingBlob];
+
+    fn apply(self, sidecars: &mut Vec<BlobSidecar>) {
+        match self {
+            Self::Contents => {
+                sidecars[0].blob[1] ^= 1;
+            }
+            Self::KzgCommitment => {
+                // Blob is not included into the block since the versioned hash changes.
+                sidecars[0].kzg_commitment[47] ^= 1;
+            }
+            Self::MissingBlob => {
+                // Blobs from another transaction in the same block
+                let other_l1_batch = create_l1_batch_with_metadata(2);
+                *sidecars = create_blob_sidecars(&other_l1_batch);
+            }
+        }
+    }
+}
+
+#[test_casing(3, BlobMismatchKind::ALL)]
+#[tokio::test]
+async fn checker_detects_blob_mismatch(kind: BlobMismatchKind) {
+    let pool = ConnectionPool::test_pool().await;
+    let l1_batch = create_l1_batch_with_metadata(1);
+    let client = MockEthereum::default();
+    let (commit_tx_hash, block_number) = commit_l1_batch_with_blobs(&client, &l1_batch).await;
+    prepare_storage_for_blob_checks(&pool, &l1_batch, commit_tx_hash).await;
+
+    let mut sidecars = create_blob_sidecars(&l1_batch);
+    kind.apply(&mut sidecars);
+    let blob_client = MockBlobClient(HashMap::from([(block_number, sidecars)]));
+
+    let checker = create_mock_checker(client, pool).with_blob_client(Some(Box::new(blob_client)));
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    // The checker must stop with an error.
+    tokio::time::timeout(Duration::from_secs(30), checker.run(stop_receiver))
+        .await
+        .expect("Timed out waiting for checker to stop")
+        .unwrap_err();
+}
@@ -0,0 +1,366 @@
+//! Client for the Avail DA layer.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use zksync_config::configs::AvailConfig;
+use zksync_types::{ethabi, web3::types::Bytes, L1BatchNumber, H256, U256};
+
+use super::client::{DataAvailabilityClient, DispatchResponse, InclusionData, InclusionStatus};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize)]
+struct SubmitRequest {
+    data: Bytes,
+    app_id: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubmitResponse {
+    submission_id: String,
+}
+
+/// Status of a submission returned by the gas relay API. Block hash and extrinsic index are only set
+/// once the submission is included in a finalized Avail block.
+#[derive(Debug, Deserialize)]
+struct SubmissionInfo {
+    block_hash: Option<H256>,
+    extrinsic_index: Option<u32>,
+}
+
+/// Merkle proof of a blob attested by the Avail bridge on L1.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BridgeProof {
+    data_root_proof: Vec<H256>,
+    leaf_proof: Vec<H256>,
+    range_hash: H256,
+    data_root_index: u64,
+    blob_root: H256,
+    bridge_root: H256,
+    leaf: H256,
+    leaf_index: u64,
+}
+
+impl BridgeProof {
+    /// ABI-encodes the proof as the `MerkleProofInput` struct expected by the Avail attestation contract on L1.
+    fn encode(&self) -> Vec<u8> {
+        let hashes = |hashes: &[H256]| {
+            ethabi::Token::Array(
+                hashes
+                    .iter()
+                    .map(|hash| ethabi::Token::FixedBytes(hash.as_bytes().to_vec()))
+                    .collect(),
+            )
+        };
+        ethabi::encode(&[ethabi::Token::Tuple(vec![
+            hashes(&self.data_root_proof),
+            hashes(&self.leaf_proof),
+            ethabi::Token::FixedBytes(self.range_hash.as_bytes().to_vec()),
+            ethabi::Token::Uint(U256::from(self.data_root_index)),
+            ethabi::Token::FixedBytes(self.blob_root.as_bytes().to_vec()),
+            ethabi::Token::FixedBytes(self.bridge_root.as_bytes().to_vec()),
+            ethabi::Token::FixedBytes(self.leaf.as_bytes().to_vec()),
+            ethabi::Token::Uint(U256::from(self.leaf_index)),
+        ])])
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataProofRoots {
+    data_root: H256,
+    blob_root: H256,
+    bridge_root: H256,
+}
+
+/// Kate data proof of a blob returned by the `kate_queryDataProof` RPC method of an Avail node.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataProof {
+    roots: DataProofRoots,
+    proof: Vec<H256>,
+    number_of_leaves: u32,
+    leaf_index: u32,
+    leaf: H256,
+}
+
+impl DataProof {
+    fn encode(&self) -> Vec<u8> {
+        ethabi::encode(&[ethabi::Token::Tuple(vec![
+            ethabi::Token::FixedBytes(self.roots.data_root.as_bytes().to_vec()),
+            ethabi::Token::FixedBytes(self.roots.blob_root.as_bytes().to_vec()),
+            ethabi::Token::FixedBytes(self.roots.bridge_root.as_bytes().to_vec()),
+            ethabi::Token::Array(
+                self.proof
+                    .iter()
+                    .map(|hash| ethabi::Token::FixedBytes(hash.as_bytes().to_vec()))
+                    .collect(),
+            ),
+            ethabi::Token::Uint(self.number_of_leaves.into()),
+            ethabi::Token::Uint(self.leaf_index.into()),
+            ethabi::Token::FixedBytes(self.leaf.as_bytes().to_vec()),
+        ])])
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataProofResponse {
+    data_proof: DataProof,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+/// Client for the Avail DA layer. Pubdata is submitted under the configured application ID via the gas relay API,
+/// which signs and pays for the `submit_data` extrinsics. Once a submission is included in a finalized block,
+/// its inclusion proof is obtained either from the Avail bridge (if configured) or as a Kate data proof from an Avail node.
+#[derive(Debug)]
+pub struct AvailClient {
+    client: reqwest::Client,
+    gas_relay_api_url: String,
+    config: AvailConfig,
+}
+
+impl AvailClient {
+    pub fn new(gas_relay_api_url: &str, config: AvailConfig) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("failed building HTTP client")?;
+        Ok(Self {
+            client,
+            gas_relay_api_url: gas_relay_api_url.trim_end_matches('/').to_owned(),
+            config,
+        })
+    }
+
+    async fn get_submission_info(&self, submission_id: &str) -> anyhow::Result<SubmissionInfo> {
+        self.client
+            .get(format!(
+                "{}/user/get_submission_info",
+                self.gas_relay_api_url
+            ))
+            .query(&[("submission_id", submission_id)])
+            .bearer_auth(&self.config.api_key)
+            .send()
+            .await
+            .context("failed requesting submission info from gas relay")?
+            .error_for_status()
+            .context("gas relay returned an error")?
+            .json()
+            .await
+            .context("failed deserializing submission info")
+    }
+
+    /// Returns `None` if the block with the blob is not attested by the bridge yet.
+    async fn get_bridge_proof(
+        &self,
+        bridge_api_url: &str,
+        block_hash: H256,
+        extrinsic_index: u32,
+    ) -> anyhow::Result<Option<BridgeProof>> {
+        let url = format!(
+            "{}/eth/proof/{block_hash:?}",
+            bridge_api_url.trim_end_matches('/')
+        );
+        let response = self
+            .client
+            .get(url)
+            .query(&[("index", extrinsic_index)])
+            .send()
+            .await
+            .context("failed requesting proof from Avail bridge")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let proof = response
+            .error_for_status()
+            .context("Avail bridge returned an error")?
+            .json()
+            .await
+            .context("failed deserializing Avail bridge proof")?;
+        Ok(Some(proof))
+    }
+
+    async fn get_data_proof(
+        &self,
+        block_hash: H256,
+        extrinsic_index: u32,
+    ) -> anyhow::Result<DataProof> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "kate_queryDataProof",
+            "params": [extrinsic_index, block_hash],
+        });
+        let response: JsonRpcResponse<DataProofResponse> = self
+            .client
+            .post(&self.config.api_node_url)
+            .json(&request)
+            .send()
+            .await
+            .context("failed requesting data proof from Avail node")?
+            .error_for_status()
+            .context("Avail node returned an error")?
+            .json()
+            .await
+            .context("failed deserializing Avail node response")?;
+        if let Some(err) = response.error {
+            anyhow::bail!(
+                "`kate_queryDataProof` returned an error ({}): {}",
+                err.code,
+                err.message
+            );
+        }
+        let response = response
+            .result
+            .context("`kate_queryDataProof` returned no result")?;
+        Ok(response.data_proof)
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for AvailClient {
+    async fn dispatch_blob(
+        &self,
+        _l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let request = SubmitRequest {
+            data: Bytes(data),
+            app_id: self.config.app_id,
+        };
+        let response: SubmitResponse = self
+            .client
+            .post(format!("{}/user/submit_raw_data", self.gas_relay_api_url))
+            .bearer_auth(&self.config.api_key)
+            .json(&request)
+            .send()
+            .await
+            .context("failed submitting blob to gas relay")?
+            .error_for_status()
+            .context("gas relay returned an error")?
+            .json()
+            .await
+            .context("failed deserializing gas relay response")?;
+        Ok(DispatchResponse {
+            blob_id: response.submission_id,
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<InclusionStatus> {
+        let info = self.get_submission_info(blob_id).await?;
+        let (Some(block_hash), Some(extrinsic_index)) = (info.block_hash, info.extrinsic_index)
+        else {
+            return Ok(InclusionStatus::Pending);
+        };
+
+        let data = if let Some(bridge_api_url) = &self.config.bridge_api_url {
+            let Some(proof) = self
+                .get_bridge_proof(bridge_api_url, block_hash, extrinsic_index)
+                .await?
+            else {
+                return Ok(InclusionStatus::Pending);
+            };
+            proof.encode()
+        } else {
+            self.get_data_proof(block_hash, extrinsic_index)
+                .await?
+                .encode()
+        };
+        Ok(InclusionStatus::Included(InclusionData {
+            data: Bytes(data),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoding_and_encoding_bridge_proof() {
+        let json = serde_json::json!({
+            "blobRoot": H256::repeat_byte(1),
+            "blockHash": H256::repeat_byte(0xff),
+            "bridgeRoot": H256::repeat_byte(2),
+            "dataRoot": H256::repeat_byte(3),
+            "dataRootCommitment": H256::repeat_byte(4),
+            "dataRootIndex": 5,
+            "dataRootProof": [H256::repeat_byte(6), H256::repeat_byte(7)],
+            "leaf": H256::repeat_byte(8),
+            "leafIndex": 9,
+            "leafProof": [H256::repeat_byte(10)],
+            "message": null,
+            "rangeHash": H256::repeat_byte(11),
+        });
+        let proof: BridgeProof = serde_json::from_value(json).unwrap();
+        assert_eq!(proof.data_root_index, 5);
+        assert_eq!(proof.leaf_proof, [H256::repeat_byte(10)]);
+
+        let encoded = proof.encode();
+        let param_types = [ethabi::ParamType::Tuple(vec![
+            ethabi::ParamType::Array(Box::new(ethabi::ParamType::FixedBytes(32))),
+            ethabi::ParamType::Array(Box::new(ethabi::ParamType::FixedBytes(32))),
+            ethabi::ParamType::FixedBytes(32),
+            ethabi::ParamType::Uint(256),
+            ethabi::ParamType::FixedBytes(32),
+            ethabi::ParamType::FixedBytes(32),
+            ethabi::ParamType::FixedBytes(32),
+            ethabi::ParamType::Uint(256),
+        ])];
+        let tokens = ethabi::decode(&param_types, &encoded).unwrap();
+        let [ethabi::Token::Tuple(fields)] = tokens.as_slice() else {
+            panic!("unexpected tokens: {tokens:?}");
+        };
+        assert_eq!(fields[3], ethabi::Token::Uint(5.into()));
+        assert_eq!(
+            fields[6],
+            ethabi::Token::FixedBytes(H256::repeat_byte(8).as_bytes().to_vec())
+        );
+        assert_eq!(fields[7], ethabi::Token::Uint(9.into()));
+    }
+
+    #[test]
+    fn decoding_data_proof_response() {
+        let json = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "dataProof": {
+                    "roots": {
+                        "dataRoot": H256::repeat_byte(1),
+                        "blobRoot": H256::repeat_byte(2),
+                        "bridgeRoot": H256::repeat_byte(3),
+                    },
+                    "proof": [H256::repeat_byte(4)],
+                    "numberOfLeaves": 2,
+                    "leafIndex": 1,
+                    "leaf": H256::repeat_byte(5),
+                },
+                "message": null,
+            },
+        });
+        let response: JsonRpcResponse<DataProofResponse> = serde_json::from_value(json).unwrap();
+        assert!(response.error.is_none());
+        let proof = response.result.unwrap().data_proof;
+        assert_eq!(proof.roots.data_root, H256::repeat_byte(1));
+        assert_eq!(proof.number_of_leaves, 2);
+        assert_eq!(proof.leaf_index, 1);
+        assert_eq!(proof.encode().len(), 32 * 10);
+    }
+}
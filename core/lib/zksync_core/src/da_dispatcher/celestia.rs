@@ -0,0 +1,286 @@
+//! Client for the Celestia DA layer.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zksync_config::configs::CelestiaConfig;
+use zksync_types::{ethabi, web3::types::Bytes, L1BatchNumber, U256};
+
+use super::client::{DataAvailabilityClient, DispatchResponse, InclusionData, InclusionStatus};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// Size of a Celestia namespace: version byte + 28-byte ID.
+const NAMESPACE_SIZE: usize = 29;
+/// Max size of a user-defined ID of a version 0 namespace; the remaining ID bytes must be zero.
+const NAMESPACE_V0_ID_SIZE: usize = 10;
+
+/// Parses a hex-encoded user-defined ID into a version 0 namespace.
+fn parse_namespace(namespace_id: &str) -> anyhow::Result<[u8; NAMESPACE_SIZE]> {
+    let id = hex::decode(namespace_id.strip_prefix("0x").unwrap_or(namespace_id))
+        .context("namespace ID is not a valid hex string")?;
+    anyhow::ensure!(
+        !id.is_empty() && id.len() <= NAMESPACE_V0_ID_SIZE,
+        "namespace ID must have 1 to {NAMESPACE_V0_ID_SIZE} bytes, got {}",
+        id.len()
+    );
+    let mut namespace = [0_u8; NAMESPACE_SIZE];
+    namespace[NAMESPACE_SIZE - id.len()..].copy_from_slice(&id);
+    Ok(namespace)
+}
+
+/// Identifies a blob in Celestia: the height of the block containing it and its share commitment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CelestiaBlobId {
+    pub height: u64,
+    pub commitment: Vec<u8>,
+}
+
+impl fmt::Display for CelestiaBlobId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{}-{}",
+            self.height,
+            hex::encode(&self.commitment)
+        )
+    }
+}
+
+impl FromStr for CelestiaBlobId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (height, commitment) = s
+            .split_once('-')
+            .context("blob ID must have `{height}-{commitment}` format")?;
+        Ok(Self {
+            height: height.parse().context("invalid block height")?,
+            commitment: hex::decode(commitment).context("invalid share commitment")?,
+        })
+    }
+}
+
+/// Blob as represented in the node API; binary fields are base64-encoded.
+#[derive(Debug, Serialize, Deserialize)]
+struct Blob {
+    namespace: String,
+    data: String,
+    share_version: u32,
+    #[serde(default)]
+    commitment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+/// Client for Celestia. Pubdata is submitted as blobs to the configured namespace via the JSON-RPC API
+/// of a Celestia node; the node returns once the blob is included in a block. The blob ID consists of the inclusion
+/// height and the share commitment of the blob, which are ABI-encoded (together with the namespace)
+/// as inclusion data once the inclusion is verified by the node.
+#[derive(Debug)]
+pub struct CelestiaClient {
+    client: reqwest::Client,
+    node_url: String,
+    auth_token: String,
+    namespace: [u8; NAMESPACE_SIZE],
+}
+
+impl CelestiaClient {
+    pub fn new(node_url: &str, config: CelestiaConfig) -> anyhow::Result<Self> {
+        let namespace = parse_namespace(&config.namespace).context("invalid namespace")?;
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("failed building HTTP client")?;
+        Ok(Self {
+            client,
+            node_url: node_url.to_owned(),
+            auth_token: config.auth_token,
+            namespace,
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> anyhow::Result<T> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: JsonRpcResponse<T> = self
+            .client
+            .post(&self.node_url)
+            .bearer_auth(&self.auth_token)
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("failed calling `{method}`"))?
+            .error_for_status()
+            .context("Celestia node returned an error")?
+            .json()
+            .await
+            .with_context(|| format!("failed deserializing `{method}` response"))?;
+        if let Some(err) = response.error {
+            anyhow::bail!(
+                "`{method}` returned an error ({}): {}",
+                err.code,
+                err.message
+            );
+        }
+        response
+            .result
+            .with_context(|| format!("`{method}` returned no result"))
+    }
+
+    /// Returns the share commitment of a blob with the specified data at the specified height, if any.
+    async fn find_commitment(&self, height: u64, data: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let namespace = BASE64.encode(self.namespace);
+        let blobs: Option<Vec<Blob>> = self
+            .call("blob.GetAll", serde_json::json!([height, [namespace]]))
+            .await?;
+        let Some(blob) = blobs.into_iter().flatten().find(|blob| blob.data == data) else {
+            return Ok(None);
+        };
+        let commitment = blob
+            .commitment
+            .context("node returned blob without commitment")?;
+        let commitment = BASE64
+            .decode(commitment)
+            .context("invalid share commitment")?;
+        Ok(Some(commitment))
+    }
+
+    /// Checks with the Celestia node that a blob with the specified share commitment is included
+    /// in the block at the specified height under the configured namespace. The node verifies
+    /// the inclusion proof against the data root of the block, so for a trust-minimized check
+    /// the client should be connected to a light node.
+    pub async fn verify_commitment(&self, blob_id: &CelestiaBlobId) -> anyhow::Result<bool> {
+        let namespace = BASE64.encode(self.namespace);
+        let commitment = BASE64.encode(&blob_id.commitment);
+        let proof: serde_json::Value = self
+            .call(
+                "blob.GetProof",
+                serde_json::json!([blob_id.height, namespace, commitment]),
+            )
+            .await?;
+        self.call(
+            "blob.Included",
+            serde_json::json!([blob_id.height, namespace, proof, commitment]),
+        )
+        .await
+    }
+
+    fn encode_inclusion_data(&self, blob_id: &CelestiaBlobId) -> Vec<u8> {
+        ethabi::encode(&[ethabi::Token::Tuple(vec![
+            ethabi::Token::Uint(U256::from(blob_id.height)),
+            ethabi::Token::Bytes(self.namespace.to_vec()),
+            ethabi::Token::Bytes(blob_id.commitment.clone()),
+        ])])
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for CelestiaClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let blob = Blob {
+            namespace: BASE64.encode(self.namespace),
+            data: BASE64.encode(data),
+            share_version: 0,
+            commitment: None,
+        };
+        // Default submit options: gas price and limit are estimated by the node.
+        let height: u64 = self
+            .call(
+                "blob.Submit",
+                serde_json::json!([[&blob], serde_json::Value::Null]),
+            )
+            .await?;
+        // The commitment is computed by the node, so it's queried from the block with the blob.
+        let commitment = self
+            .find_commitment(height, &blob.data)
+            .await?
+            .with_context(|| {
+                format!("blob for L1 batch #{l1_batch_number} is not found at height {height}")
+            })?;
+        let blob_id = CelestiaBlobId { height, commitment };
+        Ok(DispatchResponse {
+            blob_id: blob_id.to_string(),
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<InclusionStatus> {
+        let blob_id: CelestiaBlobId = blob_id.parse()?;
+        if !self.verify_commitment(&blob_id).await? {
+            return Ok(InclusionStatus::Expired);
+        }
+        let data = self.encode_inclusion_data(&blob_id);
+        Ok(InclusionStatus::Included(InclusionData {
+            data: Bytes(data),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_namespace() {
+        let namespace = parse_namespace("0a0b").unwrap();
+        assert_eq!(namespace[..NAMESPACE_SIZE - 2], [0; NAMESPACE_SIZE - 2]);
+        assert_eq!(namespace[NAMESPACE_SIZE - 2..], [0x0a, 0x0b]);
+        assert_eq!(parse_namespace("0x0a0b").unwrap(), namespace);
+
+        parse_namespace("").unwrap_err();
+        parse_namespace("not hex").unwrap_err();
+        parse_namespace(&"00".repeat(NAMESPACE_V0_ID_SIZE + 1)).unwrap_err();
+    }
+
+    #[test]
+    fn blob_id_roundtrip() {
+        let blob_id = CelestiaBlobId {
+            height: 123,
+            commitment: vec![0xab; 32],
+        };
+        let serialized = blob_id.to_string();
+        assert_eq!(serialized, format!("123-{}", "ab".repeat(32)));
+        assert_eq!(serialized.parse::<CelestiaBlobId>().unwrap(), blob_id);
+
+        "123".parse::<CelestiaBlobId>().unwrap_err();
+        "abc-00".parse::<CelestiaBlobId>().unwrap_err();
+    }
+
+    #[test]
+    fn deserializing_blob() {
+        let json = serde_json::json!({
+            "namespace": BASE64.encode([0; NAMESPACE_SIZE]),
+            "data": BASE64.encode([1, 2, 3]),
+            "share_version": 0,
+            "commitment": BASE64.encode([4; 32]),
+            "index": 3,
+        });
+        let blob: Blob = serde_json::from_value(json).unwrap();
+        assert_eq!(BASE64.decode(blob.data).unwrap(), [1, 2, 3]);
+        assert_eq!(BASE64.decode(blob.commitment.unwrap()).unwrap(), [4; 32]);
+    }
+}
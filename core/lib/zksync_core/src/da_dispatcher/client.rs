@@ -0,0 +1,127 @@
+//! Clients for external data availability (DA) layers.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use zksync_types::{web3::types::Bytes, L1BatchNumber};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Response of the DA layer to dispatching a blob.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchResponse {
+    /// Identifier of the blob used to query its inclusion proof.
+    pub blob_id: String,
+}
+
+/// Proof of a blob inclusion in the DA layer. The proof format is specific to the DA layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InclusionData {
+    pub data: Bytes,
+}
+
+/// Inclusion status of a dispatched blob.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InclusionStatus {
+    /// Blob is not included in the DA layer yet.
+    Pending,
+    /// Blob is included in the DA layer.
+    Included(InclusionData),
+    /// Blob will never be included (e.g., the DA layer dropped or failed processing it), so it must be dispatched again.
+    Expired,
+}
+
+/// Client of an external DA layer.
+#[async_trait]
+pub trait DataAvailabilityClient: fmt::Debug + Send + Sync {
+    /// Dispatches the pubdata of the specified L1 batch to the DA layer.
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse>;
+
+    /// Fetches the inclusion status (including the inclusion proof once it's available) of a previously dispatched blob.
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<InclusionStatus>;
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DispatchRequest {
+    l1_batch_number: L1BatchNumber,
+    data: Bytes,
+}
+
+/// DA layer client communicating with a DA layer sidecar via a simple HTTP API:
+///
+/// - `POST {url}/blobs` with a JSON `{ "l1BatchNumber": _, "data": "0x.." }` body dispatches a blob
+///   and returns `{ "blobId": _ }`.
+/// - `GET {url}/blobs/{blob_id}/inclusion` returns `{ "data": "0x.." }` with the inclusion proof,
+///   or 404 if the blob is not included yet, or 410 if the blob will never be included and must be re-dispatched.
+#[derive(Debug)]
+pub struct HttpDataAvailabilityClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpDataAvailabilityClient {
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("failed building HTTP client")?;
+        Ok(Self {
+            client,
+            url: url.trim_end_matches('/').to_owned(),
+        })
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for HttpDataAvailabilityClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let request = DispatchRequest {
+            l1_batch_number,
+            data: Bytes(data),
+        };
+        self.client
+            .post(format!("{}/blobs", self.url))
+            .json(&request)
+            .send()
+            .await
+            .context("failed sending blob to DA layer")?
+            .error_for_status()
+            .context("DA layer returned an error")?
+            .json()
+            .await
+            .context("failed deserializing DA layer response")
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<InclusionStatus> {
+        let response = self
+            .client
+            .get(format!("{}/blobs/{blob_id}/inclusion", self.url))
+            .send()
+            .await
+            .context("failed requesting inclusion data from DA layer")?;
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => return Ok(InclusionStatus::Pending),
+            reqwest::StatusCode::GONE => return Ok(InclusionStatus::Expired),
+            _ => {}
+        }
+        let inclusion_data = response
+            .error_for_status()
+            .context("DA layer returned an error")?
+            .json()
+            .await
+            .context("failed deserializing inclusion data")?;
+        Ok(InclusionStatus::Included(inclusion_data))
+    }
+}
@@ -0,0 +1,303 @@
+//! Client for the EigenDA DA layer.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use prost::Message;
+use zksync_config::configs::EigenDAConfig;
+use zksync_types::{web3::types::Bytes, L1BatchNumber};
+
+use super::{
+    client::{DataAvailabilityClient, DispatchResponse, InclusionData, InclusionStatus},
+    metrics::EIGEN_DA_METRICS,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// gRPC service of the EigenDA disperser.
+const DISPERSER_SERVICE: &str = "disperser.Disperser";
+/// Number of payload bytes in a single 32-byte field element of an encoded blob.
+const BYTES_PER_FIELD_ELEMENT: usize = 31;
+
+// Subset of the disperser API messages used by the client. Field tags must match the EigenDA `disperser.proto`.
+
+#[derive(Clone, PartialEq, Message)]
+struct DisperseBlobRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    data: Vec<u8>,
+    #[prost(uint32, repeated, tag = "2")]
+    custom_quorum_numbers: Vec<u32>,
+    #[prost(string, tag = "3")]
+    account_id: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct DisperseBlobReply {
+    #[prost(enumeration = "BlobStatus", tag = "1")]
+    result: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    request_id: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct BlobStatusRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    request_id: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct BlobStatusReply {
+    #[prost(enumeration = "BlobStatus", tag = "1")]
+    status: i32,
+    /// `BlobInfo` message (the blob header and its verification proof, i.e. the DA certificate of the blob).
+    /// It's kept serialized since it's only persisted and forwarded to L1.
+    #[prost(bytes = "vec", tag = "2")]
+    info: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+enum BlobStatus {
+    Unknown = 0,
+    Processing = 1,
+    Confirmed = 2,
+    Failed = 3,
+    Finalized = 4,
+    InsufficientSignatures = 5,
+    Dispersing = 6,
+}
+
+/// Encodes data as an EigenDA blob. Each 32-byte field element of the blob must be a valid BN254 scalar,
+/// which is ensured by prepending a zero byte to each 31-byte chunk of the data.
+fn encode_blob(data: &[u8]) -> Vec<u8> {
+    let chunk_count = (data.len() + BYTES_PER_FIELD_ELEMENT - 1) / BYTES_PER_FIELD_ELEMENT;
+    let mut blob = Vec::with_capacity(chunk_count * (BYTES_PER_FIELD_ELEMENT + 1));
+    for chunk in data.chunks(BYTES_PER_FIELD_ELEMENT) {
+        blob.push(0);
+        blob.extend_from_slice(chunk);
+    }
+    blob
+}
+
+/// Wraps a message into a gRPC frame (uncompressed).
+fn encode_grpc_frame(message: &impl Message) -> Vec<u8> {
+    let len = message.encoded_len();
+    let mut frame = Vec::with_capacity(len + 5);
+    frame.push(0);
+    frame.extend_from_slice(&(len as u32).to_be_bytes());
+    message
+        .encode(&mut frame)
+        .expect("buffer has sufficient capacity");
+    frame
+}
+
+fn decode_grpc_frame<M: Message + Default>(frame: &[u8]) -> anyhow::Result<M> {
+    anyhow::ensure!(frame.len() >= 5, "gRPC frame is too short");
+    anyhow::ensure!(frame[0] == 0, "compressed gRPC messages are not supported");
+    let len = u32::from_be_bytes(frame[1..5].try_into().unwrap()) as usize;
+    let payload = frame
+        .get(5..5 + len)
+        .context("gRPC frame is shorter than its declared length")?;
+    M::decode(payload).context("failed decoding gRPC message")
+}
+
+fn inclusion_status(reply: BlobStatusReply, wait_for_finalization: bool) -> InclusionStatus {
+    let included = InclusionStatus::Included(InclusionData {
+        data: Bytes(reply.info),
+    });
+    match BlobStatus::try_from(reply.status).unwrap_or(BlobStatus::Unknown) {
+        // `Unknown` may be returned transiently (e.g., before the disperser has processed the request), so blobs
+        // with this status are polled again rather than dispersed anew.
+        BlobStatus::Processing | BlobStatus::Dispersing | BlobStatus::Unknown => {
+            InclusionStatus::Pending
+        }
+        BlobStatus::Confirmed if wait_for_finalization => InclusionStatus::Pending,
+        BlobStatus::Confirmed | BlobStatus::Finalized => included,
+        BlobStatus::Failed | BlobStatus::InsufficientSignatures => InclusionStatus::Expired,
+    }
+}
+
+/// Client for EigenDA. Blobs are dispersed via the disperser gRPC API; the blob ID is the hex-encoded
+/// dispersal request ID, which is used to poll the blob status. Once the blob is confirmed (or finalized,
+/// depending on the config), its `BlobInfo` certificate is used as inclusion data. Blobs that failed dispersal
+/// are reported as expired, so that they are dispersed again.
+#[derive(Debug)]
+pub struct EigenDAClient {
+    client: reqwest::Client,
+    disperser_url: String,
+    config: EigenDAConfig,
+}
+
+impl EigenDAClient {
+    pub fn new(disperser_url: &str, config: EigenDAConfig) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .http2_prior_knowledge()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("failed building HTTP client")?;
+        Ok(Self {
+            client,
+            disperser_url: disperser_url.trim_end_matches('/').to_owned(),
+            config,
+        })
+    }
+
+    async fn call<R: Message + Default>(
+        &self,
+        method: &str,
+        request: &impl Message,
+    ) -> anyhow::Result<R> {
+        let response = self
+            .client
+            .post(format!(
+                "{}/{DISPERSER_SERVICE}/{method}",
+                self.disperser_url
+            ))
+            .header(reqwest::header::CONTENT_TYPE, "application/grpc")
+            .header("te", "trailers")
+            .body(encode_grpc_frame(request))
+            .send()
+            .await
+            .with_context(|| format!("failed calling `{method}`"))?
+            .error_for_status()
+            .with_context(|| format!("disperser returned an error for `{method}`"))?;
+
+        // Errors are returned in trailers-only responses, i.e., with the status in the headers.
+        let headers = response.headers();
+        if let Some(status) = headers.get("grpc-status") {
+            if status != "0" {
+                let message = headers
+                    .get("grpc-message")
+                    .and_then(|message| message.to_str().ok())
+                    .unwrap_or_default();
+                anyhow::bail!("`{method}` failed with gRPC status {status:?}: {message}");
+            }
+        }
+        let body = response
+            .bytes()
+            .await
+            .with_context(|| format!("failed reading `{method}` response"))?;
+        decode_grpc_frame(&body).with_context(|| format!("invalid `{method}` response"))
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for EigenDAClient {
+    async fn dispatch_blob(
+        &self,
+        _l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let request = DisperseBlobRequest {
+            data: encode_blob(&data),
+            custom_quorum_numbers: self.config.custom_quorum_numbers.clone(),
+            account_id: self.config.account_id.clone(),
+        };
+        let latency = EIGEN_DA_METRICS.dispersal_latency.start();
+        let reply: DisperseBlobReply = self.call("DisperseBlob", &request).await?;
+        latency.observe();
+
+        let status = BlobStatus::try_from(reply.result).unwrap_or(BlobStatus::Unknown);
+        anyhow::ensure!(
+            !matches!(
+                status,
+                BlobStatus::Failed | BlobStatus::InsufficientSignatures
+            ),
+            "blob dispersal failed with status {status:?}"
+        );
+        Ok(DispatchResponse {
+            blob_id: hex::encode(reply.request_id),
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<InclusionStatus> {
+        let request = BlobStatusRequest {
+            request_id: hex::decode(blob_id).context("invalid blob ID")?,
+        };
+        let latency = EIGEN_DA_METRICS.status_query_latency.start();
+        let reply: BlobStatusReply = self.call("GetBlobStatus", &request).await?;
+        latency.observe();
+        Ok(inclusion_status(reply, self.config.wait_for_finalization))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_blob() {
+        let data: Vec<u8> = (0..70).collect();
+        let blob = encode_blob(&data);
+        assert_eq!(blob.len(), 32 * 2 + 9);
+        for chunk in blob.chunks(32) {
+            assert_eq!(chunk[0], 0);
+        }
+        let restored: Vec<u8> = blob
+            .chunks(32)
+            .flat_map(|chunk| chunk[1..].iter().copied())
+            .collect();
+        assert_eq!(restored, data);
+    }
+
+    #[test]
+    fn grpc_frame_roundtrip() {
+        let request = DisperseBlobRequest {
+            data: vec![0, 1, 2],
+            custom_quorum_numbers: vec![2],
+            account_id: "0x0123".to_owned(),
+        };
+        let frame = encode_grpc_frame(&request);
+        assert_eq!(frame[0], 0);
+        assert_eq!(
+            u32::from_be_bytes(frame[1..5].try_into().unwrap()) as usize,
+            frame.len() - 5
+        );
+        let decoded: DisperseBlobRequest = decode_grpc_frame(&frame).unwrap();
+        assert_eq!(decoded, request);
+
+        decode_grpc_frame::<DisperseBlobRequest>(&frame[..frame.len() - 1]).unwrap_err();
+    }
+
+    #[test]
+    fn mapping_blob_status() {
+        let reply = |status: BlobStatus| BlobStatusReply {
+            status: status as i32,
+            info: vec![1, 2, 3],
+        };
+        let included = InclusionStatus::Included(InclusionData {
+            data: Bytes(vec![1, 2, 3]),
+        });
+
+        for wait_for_finalization in [false, true] {
+            for status in [
+                BlobStatus::Processing,
+                BlobStatus::Dispersing,
+                BlobStatus::Unknown,
+            ] {
+                assert_eq!(
+                    inclusion_status(reply(status), wait_for_finalization),
+                    InclusionStatus::Pending
+                );
+            }
+            for status in [BlobStatus::Failed, BlobStatus::InsufficientSignatures] {
+                assert_eq!(
+                    inclusion_status(reply(status), wait_for_finalization),
+                    InclusionStatus::Expired
+                );
+            }
+            assert_eq!(
+                inclusion_status(reply(BlobStatus::Finalized), wait_for_finalization),
+                included
+            );
+        }
+        assert_eq!(
+            inclusion_status(reply(BlobStatus::Confirmed), false),
+            included
+        );
+        assert_eq!(
+            inclusion_status(reply(BlobStatus::Confirmed), true),
+            InclusionStatus::Pending
+        );
+    }
+}
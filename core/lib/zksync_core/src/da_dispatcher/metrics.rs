@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "request", rename_all = "snake_case")]
+pub(super) enum DataAvailabilityRequest {
+    DispatchBlob,
+    GetInclusionData,
+}
+
+/// Metrics for the data availability dispatcher.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_da_dispatcher")]
+pub(super) struct DataAvailabilityDispatcherMetrics {
+    /// Latency of dispatching a blob to the DA layer, including retries.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub blob_dispatch_latency: Histogram<Duration>,
+    /// Time elapsed between dispatching a blob and obtaining its inclusion proof.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub inclusion_latency: Histogram<Duration>,
+    /// Size of the dispatched blobs in bytes.
+    #[metrics(buckets = Buckets::exponential(1_024.0..=16_777_216.0, 2.0))]
+    pub blob_size: Histogram<usize>,
+    /// Number of blobs that have expired in the DA layer and were scheduled for re-dispatching.
+    pub expired_blobs: Counter,
+    /// Number of retried requests to the DA layer.
+    pub retries: Family<DataAvailabilityRequest, Counter>,
+    /// Number of the last L1 batch dispatched to the DA layer.
+    pub last_dispatched_l1_batch: Gauge<u64>,
+    /// Number of the last L1 batch with an inclusion proof.
+    pub last_included_l1_batch: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DataAvailabilityDispatcherMetrics> = vise::Global::new();
+
+/// Metrics for the EigenDA client.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_da_dispatcher_eigen_da")]
+pub(super) struct EigenDAMetrics {
+    /// Latency of a single blob dispersal request to the EigenDA disperser.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub dispersal_latency: Histogram<Duration>,
+    /// Latency of a single blob status request to the EigenDA disperser.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub status_query_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static EIGEN_DA_METRICS: vise::Global<EigenDAMetrics> = vise::Global::new();
@@ -0,0 +1,219 @@
+//! Dispatching of L1 batch pubdata to an external data availability (DA) layer.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use chrono::Utc;
+use tokio::sync::watch;
+use zksync_config::configs::DADispatcherConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
+
+use self::metrics::{DataAvailabilityRequest, METRICS};
+pub use self::{
+    avail::AvailClient,
+    celestia::{CelestiaBlobId, CelestiaClient},
+    client::{
+        DataAvailabilityClient, DispatchResponse, HttpDataAvailabilityClient, InclusionData,
+        InclusionStatus,
+    },
+    eigen_da::EigenDAClient,
+    object_store::{ObjectStoreDAClient, StoredPubdataBlob},
+};
+
+mod avail;
+mod celestia;
+mod client;
+mod eigen_da;
+mod metrics;
+mod object_store;
+#[cfg(test)]
+mod tests;
+
+/// Submits the pubdata of sealed L1 batches to an external DA layer and polls the layer for inclusion proofs
+/// of the dispatched blobs. Inclusion proofs are persisted in Postgres; if DA inclusion gating is enabled
+/// in `eth_sender`, an L1 batch is only committed once its inclusion proof is available.
+#[derive(Debug)]
+pub struct DataAvailabilityDispatcher {
+    pool: ConnectionPool,
+    config: DADispatcherConfig,
+    client: Arc<dyn DataAvailabilityClient>,
+    retry_backoff: Duration,
+}
+
+impl DataAvailabilityDispatcher {
+    pub fn new(
+        pool: ConnectionPool,
+        config: DADispatcherConfig,
+        client: Arc<dyn DataAvailabilityClient>,
+    ) -> Self {
+        Self {
+            pool,
+            config,
+            client,
+            retry_backoff: Duration::from_secs(1),
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, DA dispatcher is shutting down");
+                break;
+            }
+
+            self.dispatch().await?;
+            self.poll_for_inclusion().await?;
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.config.polling_interval(), stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// Dispatches pubdata of L1 batches that were not dispatched yet.
+    async fn dispatch(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+        let l1_batches = storage
+            .data_availability_dal()
+            .get_ready_for_da_dispatch_l1_batches(self.config.max_rows_to_dispatch as usize)
+            .await
+            .context("get_ready_for_da_dispatch_l1_batches()")?;
+        drop(storage);
+
+        for (l1_batch_number, pubdata) in l1_batches {
+            let pubdata_len = pubdata.len();
+            let latency = METRICS.blob_dispatch_latency.start();
+            let response = self
+                .retry(
+                    DataAvailabilityRequest::DispatchBlob,
+                    l1_batch_number,
+                    || self.client.dispatch_blob(l1_batch_number, pubdata.clone()),
+                )
+                .await
+                .with_context(|| format!("failed dispatching L1 batch #{l1_batch_number}"))?;
+            latency.observe();
+            let sent_at = Utc::now().naive_utc();
+
+            let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+            storage
+                .data_availability_dal()
+                .insert_l1_batch_da(l1_batch_number, &response.blob_id, sent_at)
+                .await
+                .context("insert_l1_batch_da()")?;
+            drop(storage);
+
+            METRICS.blob_size.observe(pubdata_len);
+            METRICS
+                .last_dispatched_l1_batch
+                .set(l1_batch_number.0.into());
+            tracing::info!(
+                "Dispatched pubdata for L1 batch #{l1_batch_number} to DA layer; blob ID: {}",
+                response.blob_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Polls the DA layer for inclusion proofs of dispatched blobs in the order of L1 batches.
+    /// Stops at the first blob that is not included yet. Expired blobs are scheduled for re-dispatching.
+    async fn poll_for_inclusion(&self) -> anyhow::Result<()> {
+        for _ in 0..self.config.max_rows_to_dispatch {
+            let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+            let blob = storage
+                .data_availability_dal()
+                .get_first_da_blob_awaiting_inclusion()
+                .await
+                .context("get_first_da_blob_awaiting_inclusion()")?;
+            drop(storage);
+            let Some(blob) = blob else {
+                return Ok(());
+            };
+
+            let l1_batch_number = blob.l1_batch_number;
+            let inclusion_data = self
+                .retry(
+                    DataAvailabilityRequest::GetInclusionData,
+                    l1_batch_number,
+                    || self.client.get_inclusion_data(&blob.blob_id),
+                )
+                .await
+                .with_context(|| {
+                    format!("failed getting inclusion data for L1 batch #{l1_batch_number}")
+                })?;
+            let inclusion_data = match inclusion_data {
+                InclusionStatus::Included(data) => data,
+                InclusionStatus::Pending => {
+                    tracing::debug!(
+                        "Blob {} for L1 batch #{l1_batch_number} is not included in DA layer yet",
+                        blob.blob_id
+                    );
+                    return Ok(());
+                }
+                InclusionStatus::Expired => {
+                    // Removing the blob makes the L1 batch eligible for dispatching again.
+                    let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+                    storage
+                        .data_availability_dal()
+                        .remove_l1_batch_da(l1_batch_number)
+                        .await
+                        .context("remove_l1_batch_da()")?;
+                    METRICS.expired_blobs.inc();
+                    tracing::warn!(
+                        "Blob {} for L1 batch #{l1_batch_number} has expired; it will be re-dispatched",
+                        blob.blob_id
+                    );
+                    return Ok(());
+                }
+            };
+
+            let mut storage = self.pool.access_storage_tagged("da_dispatcher").await?;
+            storage
+                .data_availability_dal()
+                .save_l1_batch_inclusion_data(l1_batch_number, &inclusion_data.data.0)
+                .await
+                .context("save_l1_batch_inclusion_data()")?;
+            drop(storage);
+
+            let inclusion_latency = Utc::now().naive_utc() - blob.sent_at;
+            if let Ok(latency) = inclusion_latency.to_std() {
+                METRICS.inclusion_latency.observe(latency);
+            }
+            METRICS.last_included_l1_batch.set(l1_batch_number.0.into());
+            tracing::info!("Received inclusion data for L1 batch #{l1_batch_number}");
+        }
+        Ok(())
+    }
+
+    /// Retries the DA layer request with exponential backoff up to `max_retries` times.
+    async fn retry<T, Fut>(
+        &self,
+        request: DataAvailabilityRequest,
+        l1_batch_number: L1BatchNumber,
+        mut action: impl FnMut() -> Fut,
+    ) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut backoff = self.retry_backoff;
+        let mut retries = 0;
+        loop {
+            match action().await {
+                Ok(value) => return Ok(value),
+                Err(err) if retries < self.config.max_retries => {
+                    retries += 1;
+                    METRICS.retries[&request].inc();
+                    tracing::warn!(
+                        "DA layer request {request:?} for L1 batch #{l1_batch_number} failed \
+                         (retry {retries}/{}), retrying in {backoff:?}: {err:#}",
+                        self.config.max_retries
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! DA client persisting pubdata in an object store.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use zksync_object_store::{
+    serialize_using_bincode, Bucket, ObjectStore, ObjectStoreError, StoredObject,
+};
+use zksync_types::{
+    web3::{signing::keccak256, types::Bytes},
+    L1BatchNumber, H256,
+};
+
+use super::client::{DataAvailabilityClient, DispatchResponse, InclusionData, InclusionStatus};
+
+/// Pubdata blob persisted in the object store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredPubdataBlob {
+    pub l1_batch_number: L1BatchNumber,
+    pub data: Vec<u8>,
+}
+
+impl StoredObject for StoredPubdataBlob {
+    const BUCKET: Bucket = Bucket::DataAvailability;
+    /// Keccak256 hash of the blob data.
+    type Key<'a> = H256;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("pubdata_blob_{}.bin", hex::encode(key))
+    }
+
+    serialize_using_bincode!();
+}
+
+/// DA client that doesn't use an external DA layer, but rather persists pubdata blobs in an object store
+/// (e.g., GCS or a local directory) keyed by the blob hash. This is useful for private chains that need to retain
+/// pubdata off-chain, and for testing the DA dispatcher without external networks. The blob ID is the hex-encoded
+/// Keccak256 hash of the blob data; the same hash is used as inclusion data, since there's no inclusion proof
+/// in this case. If a blob disappears from the store before it's reported as included, it's dispatched again.
+#[derive(Debug)]
+pub struct ObjectStoreDAClient {
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreDAClient {
+    pub fn new(object_store: Arc<dyn ObjectStore>) -> Self {
+        Self { object_store }
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for ObjectStoreDAClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let hash = H256(keccak256(&data));
+        let blob = StoredPubdataBlob {
+            l1_batch_number,
+            data,
+        };
+        self.object_store
+            .put(hash, &blob)
+            .await
+            .context("failed persisting blob in object store")?;
+        Ok(DispatchResponse {
+            blob_id: hex::encode(hash),
+        })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<InclusionStatus> {
+        let hash_bytes = hex::decode(blob_id).context("invalid blob ID")?;
+        anyhow::ensure!(hash_bytes.len() == 32, "invalid blob ID length");
+        let hash = H256::from_slice(&hash_bytes);
+
+        let blob: StoredPubdataBlob = match self.object_store.get(hash).await {
+            Ok(blob) => blob,
+            Err(ObjectStoreError::KeyNotFound(_)) => return Ok(InclusionStatus::Expired),
+            Err(err) => return Err(anyhow::Error::from(err).context("failed loading blob")),
+        };
+        anyhow::ensure!(
+            H256(keccak256(&blob.data)) == hash,
+            "blob {blob_id} stored for L1 batch #{} has mismatched hash",
+            blob.l1_batch_number
+        );
+        Ok(InclusionStatus::Included(InclusionData {
+            data: Bytes(hash_bytes),
+        }))
+    }
+}
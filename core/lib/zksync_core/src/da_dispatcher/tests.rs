@@ -0,0 +1,263 @@
+//! Tests for the DA dispatcher.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::{web3::types::Bytes, H256};
+
+use super::*;
+use crate::utils::testonly::create_l1_batch;
+
+#[derive(Debug, Default)]
+struct MockClientState {
+    /// Number of the remaining failures for each request.
+    failures_left: usize,
+    dispatched: Vec<(L1BatchNumber, Vec<u8>)>,
+    included: HashMap<String, Vec<u8>>,
+    expired: HashSet<String>,
+}
+
+#[derive(Debug, Default)]
+struct MockDataAvailabilityClient(Mutex<MockClientState>);
+
+impl MockDataAvailabilityClient {
+    fn fail_next_requests(&self, count: usize) {
+        self.0.lock().unwrap().failures_left = count;
+    }
+
+    fn include_blob(&self, blob_id: &str, inclusion_data: Vec<u8>) {
+        let mut state = self.0.lock().unwrap();
+        state.included.insert(blob_id.to_owned(), inclusion_data);
+    }
+
+    fn expire_blob(&self, blob_id: &str) {
+        self.0.lock().unwrap().expired.insert(blob_id.to_owned());
+    }
+
+    fn dispatched(&self) -> Vec<(L1BatchNumber, Vec<u8>)> {
+        self.0.lock().unwrap().dispatched.clone()
+    }
+
+    fn check_failure(state: &mut MockClientState) -> anyhow::Result<()> {
+        if state.failures_left > 0 {
+            state.failures_left -= 1;
+            anyhow::bail!("DA layer is unavailable");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DataAvailabilityClient for MockDataAvailabilityClient {
+    async fn dispatch_blob(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        data: Vec<u8>,
+    ) -> anyhow::Result<DispatchResponse> {
+        let mut state = self.0.lock().unwrap();
+        Self::check_failure(&mut state)?;
+        let attempt = state
+            .dispatched
+            .iter()
+            .filter(|(number, _)| *number == l1_batch_number)
+            .count();
+        state.dispatched.push((l1_batch_number, data));
+        let blob_id = match attempt {
+            0 => format!("blob-{l1_batch_number}"),
+            _ => format!("blob-{l1_batch_number}-{attempt}"),
+        };
+        Ok(DispatchResponse { blob_id })
+    }
+
+    async fn get_inclusion_data(&self, blob_id: &str) -> anyhow::Result<InclusionStatus> {
+        let mut state = self.0.lock().unwrap();
+        Self::check_failure(&mut state)?;
+        if state.expired.contains(blob_id) {
+            return Ok(InclusionStatus::Expired);
+        }
+        Ok(match state.included.get(blob_id) {
+            Some(data) => InclusionStatus::Included(InclusionData {
+                data: Bytes(data.clone()),
+            }),
+            None => InclusionStatus::Pending,
+        })
+    }
+}
+
+async fn prepare_storage(pool: &ConnectionPool, l1_batch_count: u32) {
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+    for number in 1..=l1_batch_count {
+        let mut header = create_l1_batch(number);
+        header.pubdata_input = Some(vec![number as u8; 64]);
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+    }
+}
+
+fn create_dispatcher(
+    pool: ConnectionPool,
+    client: Arc<MockDataAvailabilityClient>,
+    max_retries: u16,
+) -> DataAvailabilityDispatcher {
+    let config = DADispatcherConfig {
+        max_retries,
+        ..DADispatcherConfig::for_tests()
+    };
+    let mut dispatcher = DataAvailabilityDispatcher::new(pool, config, client);
+    dispatcher.retry_backoff = Duration::from_millis(1);
+    dispatcher
+}
+
+#[tokio::test]
+async fn dispatching_blobs_and_polling_inclusion() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 2).await;
+    let client = Arc::<MockDataAvailabilityClient>::default();
+    let dispatcher = create_dispatcher(pool.clone(), client.clone(), 0);
+
+    dispatcher.dispatch().await.unwrap();
+    assert_eq!(
+        client.dispatched(),
+        [
+            (L1BatchNumber(1), vec![1; 64]),
+            (L1BatchNumber(2), vec![2; 64])
+        ]
+    );
+    // Repeated dispatching is a no-op.
+    dispatcher.dispatch().await.unwrap();
+    assert_eq!(client.dispatched().len(), 2);
+
+    // Only the second blob is included; the first one should block polling.
+    client.include_blob("blob-2", vec![2; 16]);
+    dispatcher.poll_for_inclusion().await.unwrap();
+    let mut storage = pool.access_storage().await.unwrap();
+    let awaiting = storage
+        .data_availability_dal()
+        .get_first_da_blob_awaiting_inclusion()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(awaiting.l1_batch_number, L1BatchNumber(1));
+
+    client.include_blob("blob-1", vec![1; 16]);
+    dispatcher.poll_for_inclusion().await.unwrap();
+    for number in [1, 2] {
+        let blob = storage
+            .data_availability_dal()
+            .get_da_blob(L1BatchNumber(number))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(blob.blob_id, format!("blob-{number}"));
+        assert_eq!(blob.inclusion_data, Some(vec![number as u8; 16]));
+    }
+}
+
+#[tokio::test]
+async fn retrying_da_layer_requests() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 1).await;
+    let client = Arc::<MockDataAvailabilityClient>::default();
+    let dispatcher = create_dispatcher(pool.clone(), client.clone(), 2);
+
+    client.fail_next_requests(2);
+    dispatcher.dispatch().await.unwrap();
+    assert_eq!(client.dispatched(), [(L1BatchNumber(1), vec![1; 64])]);
+
+    client.include_blob("blob-1", vec![1; 16]);
+    client.fail_next_requests(3);
+    let err = dispatcher.poll_for_inclusion().await.unwrap_err();
+    assert!(
+        format!("{err:#}").contains("DA layer is unavailable"),
+        "{err:#}"
+    );
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let blob = storage
+        .data_availability_dal()
+        .get_da_blob(L1BatchNumber(1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(blob.inclusion_data, None);
+}
+
+#[tokio::test]
+async fn redispatching_expired_blobs() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 1).await;
+    let client = Arc::<MockDataAvailabilityClient>::default();
+    let dispatcher = create_dispatcher(pool.clone(), client.clone(), 0);
+
+    dispatcher.dispatch().await.unwrap();
+    client.expire_blob("blob-1");
+    dispatcher.poll_for_inclusion().await.unwrap();
+    let mut storage = pool.access_storage().await.unwrap();
+    let blob = storage
+        .data_availability_dal()
+        .get_da_blob(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(blob, None);
+
+    dispatcher.dispatch().await.unwrap();
+    assert_eq!(
+        client.dispatched(),
+        [
+            (L1BatchNumber(1), vec![1; 64]),
+            (L1BatchNumber(1), vec![1; 64])
+        ]
+    );
+    client.include_blob("blob-1-1", vec![1; 16]);
+    dispatcher.poll_for_inclusion().await.unwrap();
+    let blob = storage
+        .data_availability_dal()
+        .get_da_blob(L1BatchNumber(1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(blob.blob_id, "blob-1-1");
+    assert_eq!(blob.inclusion_data, Some(vec![1; 16]));
+}
+
+#[tokio::test]
+async fn dispatching_blobs_to_object_store() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 2).await;
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let client = Arc::new(ObjectStoreDAClient::new(object_store.clone()));
+    let config = DADispatcherConfig {
+        max_retries: 0,
+        ..DADispatcherConfig::for_tests()
+    };
+    let dispatcher = DataAvailabilityDispatcher::new(pool.clone(), config, client);
+
+    dispatcher.dispatch().await.unwrap();
+    dispatcher.poll_for_inclusion().await.unwrap();
+    let mut storage = pool.access_storage().await.unwrap();
+    for number in [1, 2] {
+        let blob = storage
+            .data_availability_dal()
+            .get_da_blob(L1BatchNumber(number))
+            .await
+            .unwrap()
+            .unwrap();
+        let hash = H256::from_slice(blob.inclusion_data.as_deref().unwrap());
+        assert_eq!(blob.blob_id, hex::encode(hash));
+
+        let stored: StoredPubdataBlob = object_store.get(hash).await.unwrap();
+        assert_eq!(stored.l1_batch_number, L1BatchNumber(number));
+        assert_eq!(stored.data, vec![number as u8; 64]);
+    }
+}
@@ -1,33 +1,56 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
+use anyhow::Context as _;
+use multivm::vm_latest::constants::MAX_BLOBS_PER_BATCH;
+use serde::Serialize;
 use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode, SenderConfig};
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::StorageProcessor;
+use zksync_dal::{proof_generation_dal::ProverArtifact, StorageProcessor};
+use zksync_eth_client::EthInterface;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::i_executor::{
-    commit::kzg::KzgSettings,
+    commit::kzg::{KzgSettings, ZK_SYNC_BYTES_PER_BLOB},
     methods::{CommitBatches, ExecuteBatches, ProveBatches},
+    structures::{CommitBatchInfo, PriorityOpsBatchInfo},
 };
 use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_prover_interface::outputs::L1BatchProofForL1;
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata,
-    helpers::unix_timestamp_ms, protocol_version::L1VerifierConfig, pubdata_da::PubdataDA,
-    L1BatchNumber, ProtocolVersionId,
+    aggregated_operations::AggregatedActionType,
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    helpers::unix_timestamp_ms,
+    protocol_version::L1VerifierConfig,
+    pubdata_da::PubdataDA,
+    Address, L1BatchNumber, PriorityOpId, ProtocolVersionId, H256,
 };
 
 use super::{
     aggregated_operations::AggregatedOperation,
+    execution_policy::{
+        ExecutionHold, ExecutionPolicy, FixedDelayPolicy, HoldReason, L1ApprovalPolicy,
+        TimeWindowsPolicy,
+    },
+    kzg_precomputer::load_precomputed_kzg_info,
+    metrics::METRICS,
+    priority_tree::PriorityOpsTree,
+    pubdata_da_selection::PubdataDASelector,
     publish_criterion::{
-        DataSizeCriterion, GasCriterion, L1BatchPublishCriterion, NumberCriterion,
-        TimestampDeadlineCriterion,
+        BlobLimitCriterion, DataSizeCriterion, GasCriterion, L1BatchPublishCriterion,
+        NumberCriterion, PriorityOpDeadlineCriterion, PublishCriteria, TimestampDeadlineCriterion,
     },
 };
+use crate::l1_gas_price::L1TxParamsProvider;
 
 #[derive(Debug)]
 pub struct Aggregator {
-    commit_criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
-    proof_criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
-    execute_criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
+    commit_criteria: PublishCriteria,
+    proof_criteria: PublishCriteria,
+    execute_criteria: PublishCriteria,
+    execution_policies: Vec<Box<dyn ExecutionPolicy>>,
+    /// Reports the L1 batch held by execution policies (if any).
+    execution_policy_health: HealthUpdater,
+    /// Reports the L1 batch which proof doesn't match the hash recorded when it was saved (if any).
+    proof_integrity_health: HealthUpdater,
     config: SenderConfig,
     blob_store: Arc<dyn ObjectStore>,
     /// If we are operating in 4844 mode we need to wait for commit transaction
@@ -37,7 +60,13 @@ pub struct Aggregator {
     /// transactions.
     operate_4844_mode: bool,
     pubdata_da: PubdataDA,
+    commitment_mode: L1BatchCommitmentMode,
     kzg_settings: Option<Arc<KzgSettings>>,
+    /// If set, DA mode of each commit operation is selected based on current L1 fees instead of using `pubdata_da`.
+    pubdata_da_selector: Option<PubdataDASelector>,
+    /// Priority operations tree used to prove priority operations on execution. Initialized lazily once L1 contracts
+    /// start using the priority tree.
+    priority_tree: Option<PriorityOpsTree>,
 }
 
 impl Aggregator {
@@ -46,31 +75,68 @@ impl Aggregator {
         blob_store: Arc<dyn ObjectStore>,
         operate_4844_mode: bool,
         pubdata_da: PubdataDA,
+        commitment_mode: L1BatchCommitmentMode,
         kzg_settings: Option<Arc<KzgSettings>>,
-    ) -> Self {
-        Self {
-            commit_criteria: vec![
-                Box::from(NumberCriterion {
-                    op: AggregatedActionType::Commit,
-                    limit: config.max_aggregated_blocks_to_commit,
-                }),
-                Box::from(GasCriterion::new(
-                    AggregatedActionType::Commit,
-                    config.max_aggregated_tx_gas,
-                )),
-                Box::from(DataSizeCriterion {
-                    op: AggregatedActionType::Commit,
-                    data_limit: config.max_eth_tx_data_size,
-                    pubdata_da,
-                    kzg_settings: kzg_settings.clone(),
-                }),
-                Box::from(TimestampDeadlineCriterion {
-                    op: AggregatedActionType::Commit,
-                    deadline_seconds: config.aggregated_block_commit_deadline,
-                    max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
-                }),
-            ],
-            proof_criteria: vec![
+    ) -> anyhow::Result<Self> {
+        let mut execution_policies: Vec<Box<dyn ExecutionPolicy>> = vec![];
+        if let Some(age) = config.l1_batch_min_age_before_execute_seconds {
+            execution_policies.push(Box::new(FixedDelayPolicy {
+                delay: Duration::from_secs(age),
+            }));
+        }
+        if !config.execution_time_windows.is_empty() {
+            let policy = TimeWindowsPolicy::new(&config.execution_time_windows)
+                .context("execution_time_windows")?;
+            execution_policies.push(Box::new(policy));
+        }
+
+        PublishCriteria::validate_disabled(&config.disabled_publish_criteria)
+            .context("disabled_publish_criteria")?;
+        let max_blobs_per_eth_tx = config.max_blobs_per_eth_tx;
+        anyhow::ensure!(
+            max_blobs_per_eth_tx > 0 || pubdata_da != PubdataDA::Blobs,
+            "`max_blobs_per_eth_tx` must be positive if pubdata is published in blobs"
+        );
+        anyhow::ensure!(
+            commitment_mode == L1BatchCommitmentMode::Rollup || pubdata_da != PubdataDA::Blobs,
+            "pubdata cannot be published in blobs in validium mode"
+        );
+        let disabled_criteria = &config.disabled_publish_criteria;
+        let mut commit_criteria: Vec<Box<dyn L1BatchPublishCriterion>> = vec![
+            Box::from(NumberCriterion {
+                op: AggregatedActionType::Commit,
+                limit: config.max_aggregated_blocks_to_commit,
+            }),
+            Box::from(GasCriterion::new(
+                AggregatedActionType::Commit,
+                config.max_aggregated_tx_gas,
+            )),
+            Box::from(DataSizeCriterion {
+                op: AggregatedActionType::Commit,
+                data_limit: config.max_eth_tx_data_size,
+                pubdata_da,
+                commitment_mode,
+                kzg_settings: kzg_settings.clone(),
+            }),
+            Box::from(TimestampDeadlineCriterion {
+                op: AggregatedActionType::Commit,
+                deadline_seconds: config.aggregated_block_commit_deadline,
+                max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
+            }),
+        ];
+        if pubdata_da == PubdataDA::Blobs {
+            commit_criteria.push(Box::new(BlobLimitCriterion {
+                max_blobs_per_eth_tx,
+            }));
+        }
+        let commit_criteria = PublishCriteria::new(
+            AggregatedActionType::Commit,
+            commit_criteria,
+            disabled_criteria,
+        );
+        let proof_criteria = PublishCriteria::new(
+            AggregatedActionType::PublishProofOnchain,
+            vec![
                 Box::from(NumberCriterion {
                     op: AggregatedActionType::PublishProofOnchain,
                     limit: *config.aggregated_proof_sizes.iter().max().unwrap() as u32,
@@ -88,7 +154,11 @@ impl Aggregator {
                     max_allowed_lag: None,
                 }),
             ],
-            execute_criteria: vec![
+            disabled_criteria,
+        );
+        let execute_criteria = PublishCriteria::new(
+            AggregatedActionType::Execute,
+            vec![
                 Box::from(NumberCriterion {
                     op: AggregatedActionType::Execute,
                     limit: config.max_aggregated_blocks_to_execute,
@@ -103,12 +173,122 @@ impl Aggregator {
                     max_allowed_lag: Some(config.timestamp_criteria_max_allowed_lag),
                 }),
             ],
+            disabled_criteria,
+        );
+        // Proof integrity is only checked when proofs are loaded, so the check is healthy until a mismatch is found.
+        let proof_integrity_health = ReactiveHealthCheck::new("eth_proof_integrity").1;
+        proof_integrity_health.update(Health::from(HealthStatus::Ready));
+
+        Ok(Self {
+            commit_criteria,
+            proof_criteria,
+            execute_criteria,
+            execution_policies,
+            execution_policy_health: ReactiveHealthCheck::new("eth_execution_policy").1,
+            proof_integrity_health,
             config,
             blob_store,
             operate_4844_mode,
             pubdata_da,
+            commitment_mode,
             kzg_settings,
+            pubdata_da_selector: None,
+            priority_tree: None,
+        })
+    }
+
+    /// Adds a custom publish criterion for the specified aggregated operation.
+    pub fn with_publish_criterion(
+        mut self,
+        op: AggregatedActionType,
+        criterion: Box<dyn L1BatchPublishCriterion>,
+    ) -> Self {
+        let criteria = match op {
+            AggregatedActionType::Commit => &mut self.commit_criteria,
+            AggregatedActionType::PublishProofOnchain => &mut self.proof_criteria,
+            AggregatedActionType::Execute => &mut self.execute_criteria,
+        };
+        criteria.push(criterion);
+        self
+    }
+
+    /// Forces executing L1 batches once the oldest unexecuted priority operation in them comes close
+    /// to the specified processing deadline, regardless of other execute criteria. Execution policies
+    /// (time windows, L1 approval etc.) still apply.
+    pub fn with_priority_op_deadline_enforcement(self, deadline: Duration) -> Self {
+        self.with_publish_criterion(
+            AggregatedActionType::Execute,
+            Box::new(PriorityOpDeadlineCriterion { deadline }),
+        )
+    }
+
+    /// Checks that pubdata of any L1 batch sealed by the state keeper with the specified `max_pubdata_per_batch` limit
+    /// fits into `max_blobs_per_eth_tx` blobs; otherwise, such a batch could never be committed. No-op unless pubdata
+    /// is published in blobs.
+    pub fn validate_max_pubdata_per_batch(&self, max_pubdata_per_batch: u64) -> anyhow::Result<()> {
+        if self.pubdata_da != PubdataDA::Blobs {
+            return Ok(());
+        }
+
+        let max_blobs_per_eth_tx = self.config.max_blobs_per_eth_tx;
+        let max_blobs_per_batch = (max_pubdata_per_batch as usize)
+            .div_ceil(ZK_SYNC_BYTES_PER_BLOB)
+            .min(MAX_BLOBS_PER_BATCH);
+        anyhow::ensure!(
+            max_blobs_per_eth_tx as usize >= max_blobs_per_batch,
+            "`max_blobs_per_eth_tx` ({max_blobs_per_eth_tx}) is less than the number of blobs required for an L1 batch \
+             with `max_pubdata_per_batch` ({max_pubdata_per_batch} bytes, up to {max_blobs_per_batch} blobs)"
+        );
+
+        let max_blobs_per_commit =
+            max_blobs_per_batch * self.config.max_aggregated_blocks_to_commit as usize;
+        if max_blobs_per_eth_tx as usize > max_blobs_per_commit {
+            tracing::warn!(
+                "`max_blobs_per_eth_tx` ({max_blobs_per_eth_tx}) has no effect: commit transactions include at most \
+                 {} L1 batches with up to {max_blobs_per_batch} blobs each",
+                self.config.max_aggregated_blocks_to_commit
+            );
         }
+        Ok(())
+    }
+
+    /// Selects between calldata and blobs for each commit operation depending on which is cheaper given the current
+    /// L1 fees. Must only be used if pubdata is published in blobs, since the commit operator must be able
+    /// to send blob transactions.
+    pub fn with_pubdata_da_selection(mut self, gas_adjuster: Arc<dyn L1TxParamsProvider>) -> Self {
+        assert_eq!(
+            self.pubdata_da,
+            PubdataDA::Blobs,
+            "Pubdata DA selection requires blobs DA"
+        );
+        self.pubdata_da_selector = Some(PubdataDASelector::new(
+            gas_adjuster,
+            self.kzg_settings.clone(),
+            self.config.max_eth_tx_data_size,
+        ));
+        self
+    }
+
+    /// Adds a policy holding L1 batches until their execution is approved in the specified L1 contract.
+    pub fn with_l1_approval_policy(
+        mut self,
+        eth_client: Arc<dyn EthInterface>,
+        contract_address: Address,
+    ) -> Self {
+        let policy = L1ApprovalPolicy::new(eth_client, contract_address);
+        self.execution_policies.push(Box::new(policy));
+        self
+    }
+
+    /// Returns a health check reporting why execution of L1 batches is held, if it is.
+    pub fn execution_policy_health_check(&self) -> ReactiveHealthCheck {
+        self.execution_policy_health.subscribe()
+    }
+
+    /// Returns a health check reporting a proof that doesn't match the hash recorded when it was saved.
+    /// Proving is stalled until the proof is regenerated, so the health check must be monitored.
+    pub fn proof_integrity_health_check(&self) -> ReactiveHealthCheck {
+        self.proof_integrity_health.subscribe()
     }
 
     pub async fn get_next_ready_operation(
@@ -117,6 +297,7 @@ impl Aggregator {
         base_system_contracts_hashes: BaseSystemContractsHashes,
         protocol_version_id: ProtocolVersionId,
         l1_verifier_config: L1VerifierConfig,
+        priority_tree_start_index: Option<PriorityOpId>,
     ) -> Option<AggregatedOperation> {
         let Some(last_sealed_l1_batch_number) = storage
             .blocks_dal()
@@ -132,6 +313,7 @@ impl Aggregator {
                 storage,
                 self.config.max_aggregated_blocks_to_execute as usize,
                 last_sealed_l1_batch_number,
+                priority_tree_start_index,
             )
             .await
         {
@@ -164,25 +346,109 @@ impl Aggregator {
         storage: &mut StorageProcessor<'_>,
         limit: usize,
         last_sealed_l1_batch: L1BatchNumber,
+        priority_tree_start_index: Option<PriorityOpId>,
     ) -> Option<ExecuteBatches> {
-        let max_l1_batch_timestamp_millis = self
-            .config
-            .l1_batch_min_age_before_execute_seconds
-            .map(|age| unix_timestamp_ms() - age * 1_000);
-        let ready_for_execute_batches = storage
+        let mut ready_for_execute_batches = storage
             .blocks_dal()
-            .get_ready_for_execute_l1_batches(limit, max_l1_batch_timestamp_millis)
+            .get_ready_for_execute_l1_batches(limit, None)
             .await
             .unwrap();
-        let l1_batches = extract_ready_subrange(
-            storage,
-            &mut self.execute_criteria,
-            ready_for_execute_batches,
-            last_sealed_l1_batch,
-        )
-        .await;
+        self.apply_execution_policies(&mut ready_for_execute_batches)
+            .await;
+        let l1_batches = self
+            .execute_criteria
+            .extract_ready_subrange(storage, ready_for_execute_batches, last_sealed_l1_batch)
+            .await;
+
+        let l1_batches = l1_batches?;
 
-        l1_batches.map(|l1_batches| ExecuteBatches { l1_batches })
+        let priority_ops_proofs = if let Some(start_index) = priority_tree_start_index {
+            match self
+                .load_priority_ops_proofs(storage, start_index, &l1_batches)
+                .await
+            {
+                Ok(proofs) => Some(proofs),
+                Err(err) => {
+                    tracing::error!("Failed generating priority ops proofs for execution: {err:#}");
+                    return None;
+                }
+            }
+        } else {
+            None
+        };
+        Some(ExecuteBatches {
+            l1_batches,
+            priority_ops_proofs,
+        })
+    }
+
+    /// Generates proofs for priority operations in the executed L1 batches. Batches with priority operations
+    /// enqueued before the priority tree was introduced on L1 (i.e., with IDs less than `start_index`)
+    /// get empty proofs, since their operations are processed via the legacy priority queue.
+    async fn load_priority_ops_proofs(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        start_index: PriorityOpId,
+        l1_batches: &[L1BatchWithMetadata],
+    ) -> anyhow::Result<Vec<PriorityOpsBatchInfo>> {
+        let tree_is_outdated = self
+            .priority_tree
+            .as_ref()
+            .map_or(true, |tree| tree.start_index() != start_index);
+        if tree_is_outdated {
+            self.priority_tree = Some(PriorityOpsTree::new(start_index));
+        }
+        let tree = self.priority_tree.as_mut().unwrap();
+
+        let mut proofs = Vec::with_capacity(l1_batches.len());
+        for l1_batch in l1_batches {
+            let proof = tree.batch_info(storage, l1_batch.header.number).await?;
+            proofs.push(proof);
+        }
+        Ok(proofs)
+    }
+
+    /// Truncates `l1_batches` at the first batch held by any execution policy and updates the policy health check.
+    async fn apply_execution_policies(&self, l1_batches: &mut Vec<L1BatchWithMetadata>) {
+        let now_secs = unix_timestamp_ms() / 1_000;
+        let mut hold = None;
+        'batches: for (i, l1_batch) in l1_batches.iter().enumerate() {
+            for policy in &self.execution_policies {
+                let reason = match policy.check(&l1_batch.header, now_secs).await {
+                    Ok(None) => continue,
+                    Ok(Some(reason)) => reason,
+                    Err(err) => {
+                        tracing::warn!(
+                            "Execution policy `{}` failed for L1 batch #{}: {err:#}",
+                            policy.name(),
+                            l1_batch.header.number
+                        );
+                        HoldReason {
+                            reason: format!("policy check failed: {err}"),
+                            until: None,
+                        }
+                    }
+                };
+                hold = Some((
+                    i,
+                    ExecutionHold {
+                        policy: policy.name(),
+                        l1_batch_number: l1_batch.header.number,
+                        reason,
+                    },
+                ));
+                break 'batches;
+            }
+        }
+
+        let health = if let Some((i, hold)) = hold {
+            tracing::debug!("Execution of L1 batches is held: {hold:?}");
+            l1_batches.truncate(i);
+            Health::from(HealthStatus::Affected).with_details(hold)
+        } else {
+            Health::from(HealthStatus::Ready)
+        };
+        self.execution_policy_health.update(health);
     }
 
     async fn get_commit_operation(
@@ -199,7 +465,7 @@ impl Aggregator {
             .await
             .unwrap()?;
 
-        let ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
+        let mut ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
             blocks_dal
                 .pre_boojum_get_ready_for_commit_l1_batches(
                     limit,
@@ -232,19 +498,74 @@ impl Aggregator {
                 }
             });
 
-        let batches = extract_ready_subrange(
-            storage,
-            &mut self.commit_criteria,
-            ready_for_commit_l1_batches,
-            last_sealed_batch,
-        )
-        .await;
+        let mut da_inclusion_data = HashMap::new();
+        if self.pubdata_da == PubdataDA::Custom {
+            // Only commit L1 batches up to the first one without the DA inclusion proof, which is persisted
+            // by the DA dispatcher.
+            let mut included_count = 0;
+            for batch in &ready_for_commit_l1_batches {
+                let da_blob = storage
+                    .data_availability_dal()
+                    .get_da_blob(batch.header.number)
+                    .await
+                    .unwrap();
+                let Some(inclusion_data) = da_blob.and_then(|blob| blob.inclusion_data) else {
+                    tracing::debug!(
+                        "L1 batch #{} is held until its pubdata is included in DA layer",
+                        batch.header.number
+                    );
+                    break;
+                };
+                da_inclusion_data.insert(batch.header.number, inclusion_data);
+                included_count += 1;
+            }
+            ready_for_commit_l1_batches.truncate(included_count);
+        }
 
-        batches.map(|batches| CommitBatches {
+        // Only commit L1 batches up to the first one that cannot be encoded (e.g., its pubdata doesn't fit
+        // into the blobs of a single commit transaction).
+        let invalid_batch_index = ready_for_commit_l1_batches.iter().position(|batch| {
+            let inclusion_data = da_inclusion_data.get(&batch.header.number);
+            let validation_result = CommitBatchInfo::builder(batch, self.pubdata_da)
+                .with_commitment_mode(self.commitment_mode)
+                .with_kzg_settings(self.kzg_settings.clone())
+                .with_da_inclusion_data(inclusion_data.map(Vec::as_slice))
+                .with_max_blobs(self.config.max_blobs_per_eth_tx as usize)
+                .validate();
+            if let Err(err) = &validation_result {
+                tracing::error!("L1 batch cannot be committed: {err}");
+            }
+            validation_result.is_err()
+        });
+        if let Some(index) = invalid_batch_index {
+            ready_for_commit_l1_batches.truncate(index);
+        }
+
+        let batches = self
+            .commit_criteria
+            .extract_ready_subrange(storage, ready_for_commit_l1_batches, last_sealed_batch)
+            .await;
+
+        let batches = batches?;
+        let mut precomputed_kzg_info = HashMap::new();
+        for batch in &batches {
+            let number = batch.header.number;
+            if let Some(kzg_info) = load_precomputed_kzg_info(storage, number).await {
+                precomputed_kzg_info.insert(number, kzg_info);
+            }
+        }
+        let pubdata_da = match &self.pubdata_da_selector {
+            Some(selector) => selector.select(&batches, &precomputed_kzg_info),
+            None => self.pubdata_da,
+        };
+        Some(CommitBatches {
             last_committed_l1_batch,
             l1_batches: batches,
-            pubdata_da: self.pubdata_da,
+            pubdata_da,
+            commitment_mode: self.commitment_mode,
             kzg_settings: self.kzg_settings.clone(),
+            precomputed_kzg_info,
+            da_inclusion_data,
         })
     }
 
@@ -253,6 +574,7 @@ impl Aggregator {
         l1_verifier_config: L1VerifierConfig,
         proof_loading_mode: &ProofLoadingMode,
         blob_store: &dyn ObjectStore,
+        proof_integrity_health: &HealthUpdater,
         is_4844_mode: bool,
     ) -> Option<ProveBatches> {
         let previous_proven_batch_number = storage
@@ -300,7 +622,14 @@ impl Aggregator {
                 unreachable!("OldProofFromDb is not supported anymore")
             }
             ProofLoadingMode::FriProofFromGcs => {
-                load_wrapped_fri_proofs_for_range(batch_to_prove, batch_to_prove, blob_store).await
+                load_wrapped_fri_proofs_for_range(
+                    storage,
+                    batch_to_prove,
+                    batch_to_prove,
+                    blob_store,
+                    proof_integrity_health,
+                )
+                .await
             }
         };
         if proofs.is_empty() {
@@ -347,13 +676,10 @@ impl Aggregator {
         ready_for_proof_l1_batches: Vec<L1BatchWithMetadata>,
         last_sealed_l1_batch: L1BatchNumber,
     ) -> Option<ProveBatches> {
-        let batches = extract_ready_subrange(
-            storage,
-            &mut self.proof_criteria,
-            ready_for_proof_l1_batches,
-            last_sealed_l1_batch,
-        )
-        .await?;
+        let batches = self
+            .proof_criteria
+            .extract_ready_subrange(storage, ready_for_proof_l1_batches, last_sealed_l1_batch)
+            .await?;
 
         let prev_l1_batch_number = batches.first().map(|batch| batch.header.number - 1)?;
         let prev_batch = storage
@@ -384,6 +710,7 @@ impl Aggregator {
                     l1_verifier_config,
                     &self.config.proof_loading_mode,
                     &*self.blob_store,
+                    &self.proof_integrity_health,
                     self.operate_4844_mode,
                 )
                 .await
@@ -410,6 +737,7 @@ impl Aggregator {
                     l1_verifier_config,
                     &self.config.proof_loading_mode,
                     &*self.blob_store,
+                    &self.proof_integrity_health,
                     self.operate_4844_mode,
                 )
                 .await
@@ -437,41 +765,53 @@ impl Aggregator {
     }
 }
 
-async fn extract_ready_subrange(
-    storage: &mut StorageProcessor<'_>,
-    publish_criteria: &mut [Box<dyn L1BatchPublishCriterion>],
-    unpublished_l1_batches: Vec<L1BatchWithMetadata>,
-    last_sealed_l1_batch: L1BatchNumber,
-) -> Option<Vec<L1BatchWithMetadata>> {
-    let mut last_l1_batch: Option<L1BatchNumber> = None;
-    for criterion in publish_criteria {
-        let l1_batch_by_criterion = criterion
-            .last_l1_batch_to_publish(storage, &unpublished_l1_batches, last_sealed_l1_batch)
-            .await;
-        if let Some(l1_batch) = l1_batch_by_criterion {
-            last_l1_batch = Some(last_l1_batch.map_or(l1_batch, |number| number.min(l1_batch)));
-        }
-    }
-
-    let last_l1_batch = last_l1_batch?;
-    Some(
-        unpublished_l1_batches
-            .into_iter()
-            .take_while(|l1_batch| l1_batch.header.number <= last_l1_batch)
-            .collect(),
-    )
+/// Details of the proof integrity health check.
+#[derive(Debug, Serialize)]
+struct ProofHashMismatch {
+    l1_batch_number: L1BatchNumber,
+    expected_hash: H256,
+    actual_hash: H256,
 }
 
+/// Loads proofs for the specified range of L1 batches. Proofs not matching the hash recorded when they were saved
+/// are not returned, i.e., they are treated as not generated yet; such proofs are reported via `integrity_health`
+/// with the [`HealthStatus::Affected`] status until a valid proof is loaded.
 pub async fn load_wrapped_fri_proofs_for_range(
+    storage: &mut StorageProcessor<'_>,
     from: L1BatchNumber,
     to: L1BatchNumber,
     blob_store: &dyn ObjectStore,
+    integrity_health: &HealthUpdater,
 ) -> Vec<L1BatchProofForL1> {
     let mut proofs = Vec::new();
     for l1_batch_number in from.0..=to.0 {
         let l1_batch_number = L1BatchNumber(l1_batch_number);
-        match blob_store.get(l1_batch_number).await {
-            Ok(proof) => proofs.push(proof),
+        match blob_store.get_with_hash(l1_batch_number).await {
+            Ok((proof, hash)) => {
+                let expected_hash = storage
+                    .proof_generation_dal()
+                    .get_artifact_hash(l1_batch_number, ProverArtifact::Proof)
+                    .await
+                    .unwrap();
+                if let Some(expected_hash) = expected_hash.filter(|expected| *expected != hash) {
+                    tracing::error!(
+                        "Proof for L1 batch #{l1_batch_number} doesn't match the recorded hash; \
+                         it may have been substituted in the object store. Proving is stalled until the proof \
+                         is regenerated"
+                    );
+                    METRICS.proof_hash_mismatches.inc();
+                    let details = ProofHashMismatch {
+                        l1_batch_number,
+                        expected_hash,
+                        actual_hash: hash,
+                    };
+                    integrity_health
+                        .update(Health::from(HealthStatus::Affected).with_details(details));
+                    continue;
+                }
+                integrity_health.update(Health::from(HealthStatus::Ready));
+                proofs.push(proof);
+            }
             Err(ObjectStoreError::KeyNotFound(_)) => (), // do nothing, proof is not ready yet
             Err(err) => panic!(
                 "Failed to load proof for batch {}: {}",
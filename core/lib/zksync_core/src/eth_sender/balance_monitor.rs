@@ -0,0 +1,360 @@
+//! Monitor of operator account balances on L1.
+
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_eth_client::{CallFunctionArgs, EthInterface};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{ethabi, ethabi::Token, Address, U256};
+
+use super::metrics::BALANCE_METRICS;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Window over which the spend rate of an account is estimated.
+const SPEND_RATE_WINDOW: Duration = Duration::from_secs(6 * 3_600);
+const WEI_IN_ETH: f64 = 1e18;
+const WEI_IN_GWEI: u64 = 1_000_000_000;
+
+/// Thresholds below which an operator account is considered to have low balance.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BalanceThresholds {
+    pub min_balance: Option<U256>,
+    pub min_runway: Option<Duration>,
+}
+
+impl BalanceThresholds {
+    pub fn new(min_balance_gwei: Option<u64>, min_runway_hours: Option<u64>) -> Self {
+        Self {
+            min_balance: min_balance_gwei.map(|gwei| U256::from(gwei) * WEI_IN_GWEI),
+            min_runway: min_runway_hours.map(|hours| Duration::from_secs(hours * 3_600)),
+        }
+    }
+}
+
+const ERC20_ABI: &str = r#"[{
+    "type": "function",
+    "name": "balanceOf",
+    "inputs": [{ "name": "account", "type": "address" }],
+    "outputs": [{ "name": "", "type": "uint256" }],
+    "stateMutability": "view"
+}, {
+    "type": "function",
+    "name": "decimals",
+    "inputs": [],
+    "outputs": [{ "name": "", "type": "uint8" }],
+    "stateMutability": "view"
+}]"#;
+
+/// ERC-20 base token of a chain with a custom base token.
+#[derive(Debug)]
+struct BaseToken {
+    address: Address,
+    contract: ethabi::Contract,
+    /// Balance (in whole tokens) below which an operator account is considered to have low base token balance.
+    min_balance: Option<u64>,
+    /// Number of decimals of the token; fetched on the first check.
+    decimals: Option<u8>,
+}
+
+impl BaseToken {
+    fn new(address: Address, min_balance: Option<u64>) -> Self {
+        let contract = ethabi::Contract::load(ERC20_ABI.as_bytes()).expect("ERC-20 ABI is invalid");
+        Self {
+            address,
+            contract,
+            min_balance,
+            decimals: None,
+        }
+    }
+
+    async fn decimals(&mut self, eth_client: &dyn EthInterface) -> anyhow::Result<u8> {
+        if let Some(decimals) = self.decimals {
+            return Ok(decimals);
+        }
+        let args =
+            CallFunctionArgs::new("decimals", ()).for_contract(self.address, self.contract.clone());
+        let output = eth_client.call_contract_function(args).await?;
+        let decimals = match output.as_slice() {
+            [Token::Uint(decimals)] if *decimals <= U256::from(u8::MAX) => decimals.as_u32() as u8,
+            _ => anyhow::bail!("unexpected output of `decimals`: {output:?}"),
+        };
+        self.decimals = Some(decimals);
+        Ok(decimals)
+    }
+
+    async fn balance(
+        &self,
+        eth_client: &dyn EthInterface,
+        account: Address,
+    ) -> anyhow::Result<U256> {
+        let args = CallFunctionArgs::new("balanceOf", (account,))
+            .for_contract(self.address, self.contract.clone());
+        let output = eth_client.call_contract_function(args).await?;
+        match output.as_slice() {
+            [Token::Uint(balance)] => Ok(*balance),
+            _ => anyhow::bail!("unexpected output of `balanceOf`: {output:?}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MonitoredAccount {
+    /// Role of the account, e.g. `operator` or `blobs_operator`.
+    role: &'static str,
+    address: Address,
+    samples: VecDeque<(Instant, U256)>,
+}
+
+impl MonitoredAccount {
+    fn new(role: &'static str, address: Address) -> Self {
+        Self {
+            role,
+            address,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Records a balance sample and returns the current spend rate in wei per second, if it can be estimated.
+    fn record_balance(&mut self, now: Instant, balance: U256) -> Option<f64> {
+        self.samples.push_back((now, balance));
+        while let Some(&(timestamp, _)) = self.samples.front() {
+            if now.duration_since(timestamp) <= SPEND_RATE_WINDOW {
+                break;
+            }
+            self.samples.pop_front();
+        }
+
+        let (first_timestamp, _) = *self.samples.front()?;
+        let elapsed = now.duration_since(first_timestamp).as_secs_f64();
+        if elapsed == 0.0 {
+            return None;
+        }
+        // Only balance decreases are counted as spending; top-ups are ignored.
+        let spent = self
+            .samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|((_, prev), (_, next))| prev.saturating_sub(*next))
+            .fold(U256::zero(), |acc, spent| acc + spent);
+        Some(u256_to_f64(spent) / elapsed)
+    }
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    // Precision loss is acceptable for reporting purposes.
+    value.to_string().parse().unwrap_or(f64::MAX)
+}
+
+fn to_whole_tokens(amount: U256, decimals: u8) -> f64 {
+    u256_to_f64(amount) / 10_f64.powi(decimals.into())
+}
+
+#[derive(Debug, Serialize)]
+struct AccountBalanceDetails {
+    role: &'static str,
+    address: Address,
+    balance: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    runway_hours: Option<f64>,
+    /// Balance in the smallest units of the base token for chains with a custom base token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_token_balance: Option<U256>,
+    is_low: bool,
+}
+
+/// Tracks balances of operator accounts on L1 (i.e., accounts sending commit / prove / execute transactions,
+/// and the blobs account if it's used), estimates their runway from recent spend rates and reports it
+/// via metrics. For chains with a custom base token, balances in the base token are tracked as well
+/// (see [`Self::with_base_token()`]). The component health is set to [`HealthStatus::Affected`]
+/// if any balance is below the thresholds.
+#[derive(Debug)]
+pub struct OperatorBalanceMonitor {
+    eth_client: Arc<dyn EthInterface>,
+    accounts: Vec<MonitoredAccount>,
+    thresholds: BalanceThresholds,
+    base_token: Option<BaseToken>,
+    health_updater: HealthUpdater,
+}
+
+impl OperatorBalanceMonitor {
+    pub fn new(
+        eth_client: Arc<dyn EthInterface>,
+        operator_address: Address,
+        blobs_operator_address: Option<Address>,
+        thresholds: BalanceThresholds,
+    ) -> Self {
+        let mut accounts = vec![MonitoredAccount::new("operator", operator_address)];
+        if let Some(address) = blobs_operator_address {
+            accounts.push(MonitoredAccount::new("blobs_operator", address));
+        }
+        Self {
+            eth_client,
+            accounts,
+            thresholds,
+            base_token: None,
+            health_updater: ReactiveHealthCheck::new("operator_balance_monitor").1,
+        }
+    }
+
+    /// Monitors balances of operator accounts in the ERC-20 base token at `address` in addition to ETH balances.
+    /// An account is considered to have low balance if its base token balance is below `min_balance`
+    /// (in whole tokens).
+    pub fn with_base_token(mut self, address: Address, min_balance: Option<u64>) -> Self {
+        self.base_token = Some(BaseToken::new(address, min_balance));
+        self
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    async fn check_balances(&mut self) -> anyhow::Result<Health> {
+        let now = Instant::now();
+        let base_token_decimals = match &mut self.base_token {
+            Some(base_token) => Some(base_token.decimals(self.eth_client.as_ref()).await?),
+            None => None,
+        };
+        let mut details = Vec::with_capacity(self.accounts.len());
+        for account in &mut self.accounts {
+            let balance = self
+                .eth_client
+                .eth_balance(account.address, "operator_balance_monitor")
+                .await?;
+            let spend_rate = account.record_balance(now, balance);
+            let runway = spend_rate
+                .filter(|&rate| rate > 0.0)
+                .map(|rate| Duration::from_secs_f64((u256_to_f64(balance) / rate).min(1e12)));
+
+            BALANCE_METRICS.balance_eth[&account.role].set(u256_to_f64(balance) / WEI_IN_ETH);
+            if let Some(runway) = runway {
+                BALANCE_METRICS.runway_hours[&account.role].set(runway.as_secs_f64() / 3_600.0);
+            }
+
+            let is_balance_low = self
+                .thresholds
+                .min_balance
+                .map_or(false, |min_balance| balance < min_balance);
+            let is_runway_low = self
+                .thresholds
+                .min_runway
+                .zip(runway)
+                .map_or(false, |(min_runway, runway)| runway < min_runway);
+            if is_balance_low || is_runway_low {
+                tracing::warn!(
+                    "Balance of {} account {:?} is low: {balance} wei, estimated runway: {runway:?}",
+                    account.role,
+                    account.address
+                );
+            }
+
+            let mut base_token_balance = None;
+            let mut is_base_token_balance_low = false;
+            if let Some((base_token, decimals)) = self.base_token.as_ref().zip(base_token_decimals)
+            {
+                let balance = base_token
+                    .balance(self.eth_client.as_ref(), account.address)
+                    .await?;
+                BALANCE_METRICS.balance_base_token[&account.role]
+                    .set(to_whole_tokens(balance, decimals));
+                is_base_token_balance_low = base_token.min_balance.map_or(false, |min_balance| {
+                    balance < U256::from(min_balance) * U256::exp10(decimals.into())
+                });
+                if is_base_token_balance_low {
+                    tracing::warn!(
+                        "Base token balance of {} account {:?} is low: {balance} (in smallest token units)",
+                        account.role,
+                        account.address
+                    );
+                }
+                base_token_balance = Some(balance);
+            }
+
+            details.push(AccountBalanceDetails {
+                role: account.role,
+                address: account.address,
+                balance,
+                runway_hours: runway.map(|runway| runway.as_secs_f64() / 3_600.0),
+                base_token_balance,
+                is_low: is_balance_low || is_runway_low || is_base_token_balance_low,
+            });
+        }
+
+        let status = if details.iter().any(|account| account.is_low) {
+            HealthStatus::Affected
+        } else {
+            HealthStatus::Ready
+        };
+        Ok(Health::from(status).with_details(details))
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, operator balance monitor is shutting down");
+                break;
+            }
+
+            match self.check_balances().await {
+                Ok(health) => {
+                    self.health_updater.update(health);
+                }
+                Err(err) => {
+                    // L1 client errors are transient; the latest known health is retained.
+                    tracing::warn!("Failed checking operator balances: {err:#}");
+                }
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(POLL_INTERVAL, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimating_spend_rate() {
+        let mut account = MonitoredAccount::new("operator", Address::repeat_byte(1));
+        let start = Instant::now();
+        assert_eq!(account.record_balance(start, U256::from(1_000)), None);
+
+        let rate = account
+            .record_balance(start + Duration::from_secs(10), U256::from(900))
+            .unwrap();
+        assert_eq!(rate, 10.0);
+        // Top-ups should not be counted as negative spending.
+        let rate = account
+            .record_balance(start + Duration::from_secs(20), U256::from(2_000))
+            .unwrap();
+        assert_eq!(rate, 5.0);
+        let rate = account
+            .record_balance(start + Duration::from_secs(40), U256::from(1_800))
+            .unwrap();
+        assert_eq!(rate, 7.5);
+
+        // The first samples should be evicted from the window.
+        let rate = account
+            .record_balance(
+                start + SPEND_RATE_WINDOW + Duration::from_secs(20),
+                U256::from(1_800),
+            )
+            .unwrap();
+        assert!(rate < 1.0, "{rate}");
+    }
+
+    #[test]
+    fn converting_base_token_balance() {
+        assert_eq!(to_whole_tokens(U256::from(1_500_000), 6), 1.5);
+        assert_eq!(to_whole_tokens(U256::exp10(18) * 3, 18), 3.0);
+        assert_eq!(to_whole_tokens(U256::from(42), 0), 42.0);
+    }
+}
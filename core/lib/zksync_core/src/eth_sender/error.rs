@@ -1,3 +1,4 @@
+use zksync_eth_client::ErrorKind;
 use zksync_types::web3::contract;
 
 #[derive(Debug, thiserror::Error)]
@@ -6,4 +7,28 @@ pub enum ETHSenderError {
     EthereumGateWayError(#[from] zksync_eth_client::Error),
     #[error("Token parsing Error: {0}")]
     ParseError(#[from] contract::Error),
+    #[error(
+        "Commit operation with {l1_batch_count} L1 batches requires {blob_count} blobs, while at most \
+         {max_blobs} blobs can be attached to a commit transaction"
+    )]
+    BlobLimitExceeded {
+        l1_batch_count: usize,
+        blob_count: usize,
+        max_blobs: usize,
+    },
+}
+
+impl ETHSenderError {
+    /// Returns the kind of the underlying L1 client error, if any.
+    pub fn l1_error_kind(&self) -> Option<ErrorKind> {
+        match self {
+            Self::EthereumGateWayError(err) => Some(err.kind()),
+            Self::ParseError(_) | Self::BlobLimitExceeded { .. } => None,
+        }
+    }
+
+    /// Checks whether the error is caused by a transient L1 client issue, so that the operation can be retried.
+    pub fn is_transient(&self) -> bool {
+        self.l1_error_kind().map_or(false, ErrorKind::is_retriable)
+    }
 }
@@ -19,7 +19,7 @@ use zksync_types::{
     protocol_version::{L1VerifierConfig, VerifierParams},
     pubdata_da::PubdataDA,
     web3::{contract::Error as Web3ContractError, types::BlockNumber},
-    Address, L2ChainId, ProtocolVersionId, H256, U256,
+    Address, L2ChainId, PriorityOpId, ProtocolVersionId, H256, U256,
 };
 
 use super::aggregated_operations::AggregatedOperation;
@@ -63,6 +63,9 @@ pub struct EthTxAggregator {
     /// transactions. The `Some` then contains the address of this custom operator
     /// address.
     custom_commit_sender_addr: Option<Address>,
+    /// ID of the first priority operation processed via the priority tree on L1. The index is set once
+    /// on the contract upgrade, so it's cached after being loaded.
+    priority_tree_start_index: Option<PriorityOpId>,
 }
 
 struct TxData {
@@ -113,6 +116,7 @@ impl EthTxAggregator {
             rollup_chain_id,
             kzg_settings,
             custom_commit_sender_addr,
+            priority_tree_start_index: None,
         }
     }
 
@@ -342,6 +346,23 @@ impl EthTxAggregator {
         Ok(H256::from_tokens(vk_hash)?)
     }
 
+    /// Loads the ID of the first priority operation processed via the priority tree on L1.
+    async fn get_priority_tree_start_index(&mut self) -> Result<PriorityOpId, ETHSenderError> {
+        if let Some(start_index) = self.priority_tree_start_index {
+            return Ok(start_index);
+        }
+
+        let get_start_index = &self.functions.get_priority_tree_start_index;
+        let args = CallFunctionArgs::new(&get_start_index.name, ()).for_contract(
+            self.main_zksync_contract_address,
+            self.functions.priority_tree_contract.clone(),
+        );
+        let start_index = self.eth_client.call_contract_function(args).await?;
+        let start_index = PriorityOpId(U256::from_tokens(start_index)?.as_u64());
+        self.priority_tree_start_index = Some(start_index);
+        Ok(start_index)
+    }
+
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(
         &mut self,
@@ -369,6 +390,15 @@ impl EthTxAggregator {
             params: verifier_params,
             recursion_scheduler_level_vk_hash,
         };
+        let priority_tree_start_index = if protocol_version_id.supports_priority_tree() {
+            let start_index = self.get_priority_tree_start_index().await.map_err(|err| {
+                tracing::error!("Failed to get priority tree start index {err:?}");
+                err
+            })?;
+            Some(start_index)
+        } else {
+            None
+        };
         if let Some(agg_op) = self
             .aggregator
             .get_next_ready_operation(
@@ -376,6 +406,7 @@ impl EthTxAggregator {
                 base_system_contracts_hashes,
                 protocol_version_id,
                 l1_verifier_config,
+                priority_tree_start_index,
             )
             .await
         {
@@ -424,7 +455,7 @@ impl EthTxAggregator {
         &self,
         op: &AggregatedOperation,
         contracts_are_pre_shared_bridge: bool,
-    ) -> TxData {
+    ) -> Result<TxData, ETHSenderError> {
         let operation_is_pre_shared_bridge = op.protocol_version().is_pre_shared_bridge();
         assert_eq!(
             contracts_are_pre_shared_bridge,
@@ -437,7 +468,7 @@ impl EthTxAggregator {
             AggregatedOperation::Commit(op) => {
                 if contracts_are_pre_shared_bridge {
                     if let (Some(kzg_settings), PubdataDA::Blobs) =
-                        (&self.kzg_settings, self.aggregator.pubdata_da())
+                        (&self.kzg_settings, op.pubdata_da)
                     {
                         let calldata = self
                             .functions
@@ -445,22 +476,43 @@ impl EthTxAggregator {
                             .encode_input(&op.clone().into_tokens())
                             .expect("Failed to encode commit transaction data");
 
-                        let side_car = op.l1_batches[0]
-                            .header
-                            .pubdata_input
-                            .clone()
-                            .unwrap()
-                            .chunks(ZK_SYNC_BYTES_PER_BLOB)
-                            .map(|blob| {
-                                let kzg_info = KzgInfo::new(kzg_settings, blob);
-                                SidecarBlobV1 {
-                                    blob: kzg_info.blob.to_vec(),
-                                    commitment: kzg_info.kzg_commitment.to_vec(),
-                                    proof: kzg_info.blob_proof.to_vec(),
-                                    versioned_hash: kzg_info.versioned_hash.to_vec(),
-                                }
-                            })
-                            .collect::<Vec<SidecarBlobV1>>();
+                        // Each L1 batch occupies its own blobs; blobs are ordered in the same way as batches.
+                        // L1 batches cannot share blobs: blob commitments in a batch commitment are computed
+                        // on L2 from the batch's own pubdata (each blob zero-padded), and `Executor.sol` checks
+                        // that the blob at the corresponding index of the commit transaction opens to the linear
+                        // hash from the batch's system logs. Packing pubdata of several batches into shared blobs
+                        // would thus require changes to the L2 pubdata publisher and the L1 executor contract.
+                        let mut side_car = vec![];
+                        for l1_batch in &op.l1_batches {
+                            let precomputed_kzg_info =
+                                op.precomputed_kzg_info.get(&l1_batch.header.number);
+                            let pubdata = l1_batch.header.pubdata_input.as_deref().unwrap();
+                            let blobs = pubdata.chunks(ZK_SYNC_BYTES_PER_BLOB).enumerate().map(
+                                |(i, blob)| {
+                                    let kzg_info = precomputed_kzg_info
+                                        .and_then(|kzg_info| kzg_info.get(i))
+                                        .cloned()
+                                        .unwrap_or_else(|| KzgInfo::new(kzg_settings, blob));
+                                    SidecarBlobV1 {
+                                        blob: kzg_info.blob.to_vec(),
+                                        commitment: kzg_info.kzg_commitment.to_vec(),
+                                        proof: kzg_info.blob_proof.to_vec(),
+                                        versioned_hash: kzg_info.versioned_hash.to_vec(),
+                                    }
+                                },
+                            );
+                            side_car.extend(blobs);
+                        }
+                        // Blob counts are limited by the aggregator; this is a safety net in case
+                        // the limit is exceeded, since L1 would reject such a transaction.
+                        let max_blobs = self.config.max_blobs_per_eth_tx as usize;
+                        if side_car.len() > max_blobs {
+                            return Err(ETHSenderError::BlobLimitExceeded {
+                                l1_batch_count: op.l1_batches.len(),
+                                blob_count: side_car.len(),
+                                max_blobs,
+                            });
+                        }
 
                         let eth_tx_sidecar = EthTxBlobSidecarV1 { blobs: side_car };
                         (calldata, Some(eth_tx_sidecar.into()))
@@ -501,6 +553,21 @@ impl EthTxAggregator {
                 };
                 (calldata, None)
             }
+            AggregatedOperation::Execute(op) if op.priority_ops_proofs.is_some() => {
+                let calldata = if contracts_are_pre_shared_bridge {
+                    self.functions
+                        .priority_tree_execute
+                        .encode_input(&op.into_tokens())
+                        .expect("Failed to encode execute transaction data")
+                } else {
+                    args.extend(op.into_tokens());
+                    self.functions
+                        .priority_tree_execute_shared_bridge
+                        .encode_input(&args)
+                        .expect("Failed to encode execute transaction data")
+                };
+                (calldata, None)
+            }
             AggregatedOperation::Execute(op) => {
                 let calldata = if contracts_are_pre_shared_bridge {
                     self.functions
@@ -519,7 +586,7 @@ impl EthTxAggregator {
                 (calldata, None)
             }
         };
-        TxData { calldata, sidecar }
+        Ok(TxData { calldata, sidecar })
     }
 
     pub(super) async fn save_eth_tx(
@@ -539,7 +606,7 @@ impl EthTxAggregator {
         };
         let nonce = self.get_next_nonce(&mut transaction, sender_addr).await?;
         let encoded_aggregated_op =
-            self.encode_aggregated_op(aggregated_op, contracts_are_pre_shared_bridge);
+            self.encode_aggregated_op(aggregated_op, contracts_are_pre_shared_bridge)?;
         let l1_batch_number_range = aggregated_op.l1_batch_range();
 
         let predicted_gas_for_batches = transaction
@@ -548,6 +615,20 @@ impl EthTxAggregator {
             .await
             .unwrap();
         let eth_tx_predicted_gas = agg_l1_batch_base_cost(op_type) + predicted_gas_for_batches;
+        let blob_utilization = match (aggregated_op, &encoded_aggregated_op.sidecar) {
+            (
+                AggregatedOperation::Commit(op),
+                Some(EthTxBlobSidecar::EthTxBlobSidecarV1(sidecar)),
+            ) if !sidecar.blobs.is_empty() => {
+                let pubdata_bytes = op
+                    .l1_batches
+                    .iter()
+                    .map(|batch| batch.header.pubdata_input.as_ref().map_or(0, Vec::len) as u64)
+                    .sum::<u64>();
+                Some((sidecar.blobs.len() as u32, pubdata_bytes))
+            }
+            _ => None,
+        };
 
         let eth_tx = transaction
             .eth_sender_dal()
@@ -563,6 +644,31 @@ impl EthTxAggregator {
             .await
             .unwrap();
 
+        if let Some((blob_count, pubdata_bytes)) = blob_utilization {
+            METRICS.commit_blob_utilization.observe(
+                pubdata_bytes as f64
+                    / (u64::from(blob_count) * ZK_SYNC_BYTES_PER_BLOB as u64) as f64,
+            );
+            transaction
+                .eth_sender_dal()
+                .save_blob_utilization(
+                    eth_tx.id,
+                    l1_batch_number_range.clone(),
+                    blob_count,
+                    pubdata_bytes,
+                )
+                .await
+                .unwrap();
+        }
+
+        if let AggregatedOperation::Commit(op) = aggregated_op {
+            // The DA mode may be selected per commit operation, so we record it for the consistency checker.
+            transaction
+                .blocks_dal()
+                .set_commit_pubdata_da(l1_batch_number_range.clone(), op.pubdata_da)
+                .await
+                .unwrap();
+        }
         transaction
             .blocks_dal()
             .set_eth_tx_id(l1_batch_number_range, eth_tx.id, op_type)
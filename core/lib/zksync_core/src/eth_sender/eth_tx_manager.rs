@@ -1,12 +1,15 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{
-    encode_blob_tx_with_sidecar, BoundEthInterface, Error, EthInterface, ExecutedTxStatus, Options,
-    RawTransactionBytes, SignedCallResult,
+    encode_blob_tx_with_sidecar, BoundEthInterface, Error, ErrorKind, EthInterface,
+    ExecutedTxStatus, Options, RawTransactionBytes, SignedCallResult,
 };
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
@@ -58,6 +61,9 @@ pub struct EthTxManager {
     ethereum_gateway_blobs: Option<Arc<dyn BoundEthInterface>>,
     config: SenderConfig,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
+    /// ID of the last `eth_tx` logged in the dry run mode. Since signed transactions are not persisted
+    /// in this mode, this is used to log each transaction only once.
+    last_dry_run_eth_tx_id: u32,
 }
 
 impl EthTxManager {
@@ -72,6 +78,7 @@ impl EthTxManager {
             ethereum_gateway_blobs,
             config,
             gas_adjuster,
+            last_dry_run_eth_tx_id: 0,
         }
     }
 
@@ -254,6 +261,23 @@ impl EthTxManager {
             ));
         }
 
+        if self.config.dry_run {
+            tracing::info!(
+                "Dry run: not broadcasting eth_tx {} ({}) with nonce {}, hash {:?}, contract {:?}, \
+                 base_fee_per_gas {base_fee_per_gas}, priority_fee_per_gas {priority_fee_per_gas}, \
+                 blob_base_fee_per_gas {blob_base_fee_per_gas:?}, predicted gas cost {}, raw tx size {}B",
+                tx.id,
+                tx.tx_type,
+                tx.nonce,
+                signed_tx.hash,
+                tx.contract_address,
+                tx.predicted_gas_cost,
+                signed_tx.raw_tx.as_ref().len()
+            );
+            self.last_dry_run_eth_tx_id = self.last_dry_run_eth_tx_id.max(tx.id);
+            return Ok(signed_tx.hash);
+        }
+
         if let Some(tx_history_id) = storage
             .eth_sender_dal()
             .insert_tx_history(
@@ -272,11 +296,12 @@ impl EthTxManager {
                 .await
             {
                 tracing::warn!(
-                    "Error when sending new signed tx for tx {}, base_fee_per_gas {}, priority_fee_per_gas: {}: {}",
+                    "Error when sending new signed tx for tx {}, base_fee_per_gas {}, priority_fee_per_gas: {}: {} ({:?})",
                     tx.id,
                     base_fee_per_gas,
                     priority_fee_per_gas,
-                    error
+                    error,
+                    error.l1_error_kind()
                 );
             }
         }
@@ -289,15 +314,28 @@ impl EthTxManager {
         tx_history_id: u32,
         raw_tx: RawTransactionBytes,
         current_block: L1BlockNumber,
-    ) -> Result<H256, ETHSenderError> {
+    ) -> Result<(), ETHSenderError> {
         match self.ethereum_gateway.send_raw_tx(raw_tx).await {
-            Ok(tx_hash) => {
+            Ok(_) => {
                 storage
                     .eth_sender_dal()
                     .set_sent_at_block(tx_history_id, current_block.0)
                     .await
                     .unwrap();
-                Ok(tx_hash)
+                Ok(())
+            }
+            // The transaction is already in the L1 mempool (e.g., it was sent before the server restart),
+            // so it should be tracked as sent rather than resent.
+            Err(error) if error.kind() == ErrorKind::AlreadyKnown => {
+                tracing::info!(
+                    "Transaction for tx history #{tx_history_id} is already known to L1 node"
+                );
+                storage
+                    .eth_sender_dal()
+                    .set_sent_at_block(tx_history_id, current_block.0)
+                    .await
+                    .unwrap();
+                Ok(())
             }
             Err(error) => {
                 storage
@@ -716,7 +754,12 @@ impl EthTxManager {
         pool: ConnectionPool,
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
-        {
+        if self.config.dry_run {
+            // Unsent transactions are not broadcast in the dry run mode, and there's nothing to monitor.
+            tracing::warn!(
+                "eth_tx_manager is running in dry run mode; L1 transactions will not be sent"
+            );
+        } else {
             let l1_block_numbers = self
                 .get_l1_block_numbers()
                 .await
@@ -728,21 +771,54 @@ impl EthTxManager {
         // It's mandatory to set `last_known_l1_block` to zero, otherwise the first iteration
         // will never check in-flight txs status
         let mut last_known_l1_block = L1BlockNumber(0);
+        // Set once a stop signal is received if in-flight transactions should be tracked before shutting down.
+        let mut drain_deadline = None;
         loop {
             let mut storage = pool.access_storage_tagged("eth_sender").await.unwrap();
 
-            if *stop_receiver.borrow() {
-                tracing::info!("Stop signal received, eth_tx_manager is shutting down");
-                break;
+            if *stop_receiver.borrow() && drain_deadline.is_none() {
+                let drain_timeout = self.config.shutdown_drain_timeout();
+                let Some(drain_timeout) = drain_timeout.filter(|_| !self.config.dry_run) else {
+                    tracing::info!("Stop signal received, eth_tx_manager is shutting down");
+                    break;
+                };
+                tracing::info!(
+                    "Stop signal received, eth_tx_manager stops sending new L1 transactions \
+                     and tracks in-flight ones for up to {drain_timeout:?}"
+                );
+                drain_deadline = Some(Instant::now() + drain_timeout);
+            }
+            if let Some(deadline) = drain_deadline {
+                let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
+                if inflight_txs.is_empty() {
+                    tracing::info!(
+                        "All in-flight L1 transactions are confirmed, eth_tx_manager is shutting down"
+                    );
+                    break;
+                } else if Instant::now() >= deadline {
+                    tracing::warn!(
+                        "{} L1 transactions are still in flight after the drain timeout; \
+                         eth_tx_manager is shutting down anyway",
+                        inflight_txs.len()
+                    );
+                    break;
+                }
             }
 
-            match self.loop_iteration(&mut storage, last_known_l1_block).await {
+            let send_new_txs = drain_deadline.is_none();
+            match self
+                .loop_iteration(&mut storage, last_known_l1_block, send_new_txs)
+                .await
+            {
                 Ok(block) => last_known_l1_block = block,
-                Err(e) => {
+                Err(e) if e.is_transient() => {
                     // Web3 API request failures can cause this,
                     // and anything more important is already properly reported.
                     tracing::warn!("eth_sender error {:?}", e);
                 }
+                Err(e) => {
+                    tracing::error!("Non-retriable eth_sender error {:?}", e);
+                }
             }
 
             tokio::time::sleep(self.config.tx_poll_period()).await;
@@ -755,26 +831,46 @@ impl EthTxManager {
         storage: &mut StorageProcessor<'_>,
         current_block: L1BlockNumber,
     ) {
-        let number_inflight_txs = storage
-            .eth_sender_dal()
-            .get_inflight_txs()
-            .await
-            .unwrap()
-            .len();
+        let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
         let number_of_available_slots_for_eth_txs = self
             .config
             .max_txs_in_flight
-            .saturating_sub(number_inflight_txs as u64);
+            .saturating_sub(inflight_txs.len() as u64);
+        // Blob-carrying transactions in flight are limited by the blob capacity of a single L1 block,
+        // which is taken from the blob schedule detected on L1 so that it adapts to L1 hard forks.
+        let max_blobs_in_flight = self.gas_adjuster.get_max_blobs_per_block();
+        let mut blobs_in_flight: u64 = inflight_txs.iter().map(blob_count).sum();
 
         if number_of_available_slots_for_eth_txs > 0 {
             // Get the new eth tx and create history item for them
-            let new_eth_tx = storage
-                .eth_sender_dal()
-                .get_new_eth_txs(number_of_available_slots_for_eth_txs)
+            let mut dal = storage.eth_sender_dal();
+            let new_eth_tx = if self.config.dry_run {
+                dal.get_new_eth_txs_after(
+                    self.last_dry_run_eth_tx_id,
+                    number_of_available_slots_for_eth_txs,
+                )
                 .await
-                .unwrap();
+            } else {
+                dal.get_new_eth_txs(number_of_available_slots_for_eth_txs)
+                    .await
+            };
+            let new_eth_tx = new_eth_tx.unwrap();
 
             for tx in new_eth_tx {
+                let tx_blobs = blob_count(&tx);
+                if tx_blobs > 0
+                    && blobs_in_flight > 0
+                    && blobs_in_flight + tx_blobs > max_blobs_in_flight
+                {
+                    // Transactions must be sent in order, so we cannot skip this one.
+                    tracing::debug!(
+                        "Postponing sending eth_tx {}: {blobs_in_flight} blobs are already in flight, \
+                         while at most {max_blobs_in_flight} fit into an L1 block",
+                        tx.id
+                    );
+                    break;
+                }
+                blobs_in_flight += tx_blobs;
                 let _ = self.send_eth_tx(storage, &tx, 0, current_block).await;
             }
         }
@@ -785,11 +881,14 @@ impl EthTxManager {
         &mut self,
         storage: &mut StorageProcessor<'_>,
         previous_block: L1BlockNumber,
+        send_new_txs: bool,
     ) -> Result<L1BlockNumber, ETHSenderError> {
         let l1_block_numbers = self.get_l1_block_numbers().await?;
 
-        self.send_new_eth_txs(storage, l1_block_numbers.latest)
-            .await;
+        if send_new_txs {
+            self.send_new_eth_txs(storage, l1_block_numbers.latest)
+                .await;
+        }
 
         if l1_block_numbers.latest <= previous_block {
             // Nothing to do - no new blocks were mined.
@@ -814,3 +913,10 @@ impl EthTxManager {
         Ok(l1_block_numbers.latest)
     }
 }
+
+fn blob_count(tx: &EthTx) -> u64 {
+    match &tx.blob_sidecar {
+        Some(EthTxBlobSidecar::EthTxBlobSidecarV1(sidecar)) => sidecar.blobs.len() as u64,
+        None => 0,
+    }
+}
@@ -0,0 +1,301 @@
+//! Policies deciding whether a proven L1 batch can be executed on L1.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::Serialize;
+use zksync_eth_client::{CallFunctionArgs, EthInterface};
+use zksync_types::{block::L1BatchHeader, ethabi, ethabi::Token, Address, L1BatchNumber, U256};
+
+const SECONDS_IN_DAY: u64 = 24 * 3_600;
+
+/// Reason why execution of an L1 batch is held by a policy.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HoldReason {
+    pub reason: String,
+    /// UNIX timestamp (in seconds) until which the batch will be held, if known.
+    pub until: Option<u64>,
+}
+
+/// Status of a held L1 batch reported via the health check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionHold {
+    pub policy: &'static str,
+    pub l1_batch_number: L1BatchNumber,
+    #[serde(flatten)]
+    pub reason: HoldReason,
+}
+
+/// Policy applied to proven L1 batches before they are executed. Policies are checked in order
+/// for each batch, and the first batch held by any policy ends the range of batches to be executed.
+#[async_trait]
+pub trait ExecutionPolicy: fmt::Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Returns `Some(_)` if the batch must not be executed yet.
+    async fn check(
+        &self,
+        header: &L1BatchHeader,
+        now_secs: u64,
+    ) -> anyhow::Result<Option<HoldReason>>;
+}
+
+/// Holds L1 batches until they reach the specified age.
+#[derive(Debug)]
+pub struct FixedDelayPolicy {
+    pub delay: Duration,
+}
+
+#[async_trait]
+impl ExecutionPolicy for FixedDelayPolicy {
+    fn name(&self) -> &'static str {
+        "fixed_delay"
+    }
+
+    async fn check(
+        &self,
+        header: &L1BatchHeader,
+        now_secs: u64,
+    ) -> anyhow::Result<Option<HoldReason>> {
+        let executable_at = header.timestamp + self.delay.as_secs();
+        Ok((now_secs < executable_at).then(|| HoldReason {
+            reason: format!("L1 batch must be at least {:?} old", self.delay),
+            until: Some(executable_at),
+        }))
+    }
+}
+
+/// Time-of-day window (UTC) represented as offsets in seconds from the start of the day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TimeWindow {
+    start: u64,
+    end: u64,
+}
+
+impl TimeWindow {
+    fn parse_time(s: &str) -> anyhow::Result<u64> {
+        let (hours, minutes) = s
+            .split_once(':')
+            .context("time must be in the HH:MM format")?;
+        let hours: u64 = hours.trim().parse().context("invalid hours")?;
+        let minutes: u64 = minutes.trim().parse().context("invalid minutes")?;
+        anyhow::ensure!(hours < 24 && minutes < 60, "time is out of range");
+        Ok(hours * 3_600 + minutes * 60)
+    }
+
+    fn parse(s: &str) -> anyhow::Result<Self> {
+        let (start, end) = s
+            .split_once('-')
+            .context("window must be in the HH:MM-HH:MM format")?;
+        let window = Self {
+            start: Self::parse_time(start)?,
+            end: Self::parse_time(end)?,
+        };
+        anyhow::ensure!(window.start != window.end, "window is empty");
+        Ok(window)
+    }
+
+    fn contains(&self, time_of_day: u64) -> bool {
+        if self.start < self.end {
+            (self.start..self.end).contains(&time_of_day)
+        } else {
+            // The window wraps around midnight.
+            time_of_day >= self.start || time_of_day < self.end
+        }
+    }
+}
+
+/// Only allows executing L1 batches during the specified UTC time-of-day windows.
+#[derive(Debug)]
+pub struct TimeWindowsPolicy {
+    windows: Vec<TimeWindow>,
+}
+
+impl TimeWindowsPolicy {
+    pub fn new(windows: &[String]) -> anyhow::Result<Self> {
+        let windows = windows
+            .iter()
+            .map(|window| {
+                TimeWindow::parse(window).with_context(|| format!("invalid time window `{window}`"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { windows })
+    }
+
+    fn next_window_start(&self, now_secs: u64) -> Option<u64> {
+        let day_start = now_secs - now_secs % SECONDS_IN_DAY;
+        let time_of_day = now_secs - day_start;
+        self.windows
+            .iter()
+            .map(|window| {
+                if window.start > time_of_day {
+                    day_start + window.start
+                } else {
+                    day_start + SECONDS_IN_DAY + window.start
+                }
+            })
+            .min()
+    }
+}
+
+#[async_trait]
+impl ExecutionPolicy for TimeWindowsPolicy {
+    fn name(&self) -> &'static str {
+        "time_windows"
+    }
+
+    async fn check(
+        &self,
+        _header: &L1BatchHeader,
+        now_secs: u64,
+    ) -> anyhow::Result<Option<HoldReason>> {
+        let time_of_day = now_secs % SECONDS_IN_DAY;
+        if self.windows.is_empty() || self.windows.iter().any(|w| w.contains(time_of_day)) {
+            return Ok(None);
+        }
+        Ok(Some(HoldReason {
+            reason: "outside of the allowed execution time windows".to_owned(),
+            until: self.next_window_start(now_secs),
+        }))
+    }
+}
+
+const APPROVAL_CONTRACT_ABI: &str = r#"[{
+    "type": "function",
+    "name": "isBatchExecutionApproved",
+    "inputs": [{ "name": "batchNumber", "type": "uint256" }],
+    "outputs": [{ "name": "", "type": "bool" }],
+    "stateMutability": "view"
+}]"#;
+
+/// Holds L1 batches until their execution is approved in an L1 contract (e.g., by the security council).
+/// Errors querying the contract hold execution as well.
+#[derive(Debug)]
+pub struct L1ApprovalPolicy {
+    eth_client: Arc<dyn EthInterface>,
+    contract_address: Address,
+    contract: ethabi::Contract,
+}
+
+impl L1ApprovalPolicy {
+    pub fn new(eth_client: Arc<dyn EthInterface>, contract_address: Address) -> Self {
+        let contract = ethabi::Contract::load(APPROVAL_CONTRACT_ABI.as_bytes())
+            .expect("approval contract ABI is invalid");
+        Self {
+            eth_client,
+            contract_address,
+            contract,
+        }
+    }
+
+    async fn is_approved(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        let args =
+            CallFunctionArgs::new("isBatchExecutionApproved", (U256::from(l1_batch_number.0),))
+                .for_contract(self.contract_address, self.contract.clone());
+        let output = self.eth_client.call_contract_function(args).await?;
+        match output.as_slice() {
+            [Token::Bool(approved)] => Ok(*approved),
+            _ => anyhow::bail!("unexpected output of `isBatchExecutionApproved`: {output:?}"),
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionPolicy for L1ApprovalPolicy {
+    fn name(&self) -> &'static str {
+        "l1_approval"
+    }
+
+    async fn check(
+        &self,
+        header: &L1BatchHeader,
+        _now_secs: u64,
+    ) -> anyhow::Result<Option<HoldReason>> {
+        let reason = match self.is_approved(header.number).await {
+            Ok(true) => return Ok(None),
+            Ok(false) => format!("not approved in {:?}", self.contract_address),
+            Err(err) => {
+                tracing::warn!(
+                    "Failed checking execution approval for L1 batch #{}: {err:#}",
+                    header.number
+                );
+                format!(
+                    "failed checking approval in {:?}: {err}",
+                    self.contract_address
+                )
+            }
+        };
+        Ok(Some(HoldReason {
+            reason,
+            until: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::ProtocolVersionId;
+
+    use super::*;
+
+    fn header(timestamp: u64) -> L1BatchHeader {
+        L1BatchHeader::new(
+            L1BatchNumber(1),
+            timestamp,
+            Default::default(),
+            ProtocolVersionId::latest(),
+        )
+    }
+
+    #[test]
+    fn parsing_time_windows() {
+        let window = TimeWindow::parse("08:30-12:00").unwrap();
+        assert_eq!(
+            window,
+            TimeWindow {
+                start: 8 * 3_600 + 30 * 60,
+                end: 12 * 3_600
+            }
+        );
+        assert!(window.contains(9 * 3_600));
+        assert!(!window.contains(12 * 3_600));
+
+        let wrapping_window = TimeWindow::parse("22:00-02:00").unwrap();
+        assert!(wrapping_window.contains(23 * 3_600));
+        assert!(wrapping_window.contains(3_600));
+        assert!(!wrapping_window.contains(12 * 3_600));
+
+        for invalid in ["08:00", "8-12", "25:00-26:00", "10:00-10:00"] {
+            TimeWindow::parse(invalid).unwrap_err();
+        }
+    }
+
+    #[tokio::test]
+    async fn time_windows_policy() {
+        let policy = TimeWindowsPolicy::new(&["08:00-12:00".into(), "22:00-02:00".into()]).unwrap();
+        let day_start = 100 * SECONDS_IN_DAY;
+        let header = header(0);
+
+        let hold = policy.check(&header, day_start + 9 * 3_600).await.unwrap();
+        assert_eq!(hold, None);
+        let hold = policy.check(&header, day_start + 3_600).await.unwrap();
+        assert_eq!(hold, None);
+        let hold = policy.check(&header, day_start + 13 * 3_600).await.unwrap();
+        assert_eq!(hold.unwrap().until, Some(day_start + 22 * 3_600));
+        let hold = policy.check(&header, day_start + 5 * 3_600).await.unwrap();
+        assert_eq!(hold.unwrap().until, Some(day_start + 8 * 3_600));
+    }
+
+    #[tokio::test]
+    async fn fixed_delay_policy() {
+        let policy = FixedDelayPolicy {
+            delay: Duration::from_secs(3_600),
+        };
+        let header = header(1_000);
+        let hold = policy.check(&header, 2_000).await.unwrap();
+        assert_eq!(hold.unwrap().until, Some(4_600));
+        let hold = policy.check(&header, 4_600).await.unwrap();
+        assert_eq!(hold, None);
+    }
+}
@@ -0,0 +1,131 @@
+//! Precomputation of KZG info for the pubdata of sealed L1 batches.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_l1_contract_interface::i_executor::commit::kzg::{
+    KzgInfo, KzgSettings, ZK_SYNC_BYTES_PER_BLOB,
+};
+use zksync_types::L1BatchNumber;
+
+use super::metrics::METRICS;
+
+/// Number of L1 batches processed in a single iteration.
+const BATCH_SIZE: usize = 10;
+
+/// Computes KZG info (blob commitments, opening proofs, etc.) for the pubdata of L1 batches right after
+/// they are sealed and persists it in Postgres. This way, `eth_sender` doesn't need to recompute KZG info
+/// each time it encodes commit data, which happens multiple times per batch (e.g., in publish criteria)
+/// and is CPU-intensive.
+///
+/// KZG info is usually persisted by the commitment generator when computing blob commitments, so this component
+/// only handles L1 batches for which it wasn't persisted (e.g., if the commitment generator runs on a separate node).
+/// KZG info is only needed until the commit transaction for the batch is created, so it's pruned afterwards.
+#[derive(Debug)]
+pub struct KzgInfoPrecomputer {
+    pool: ConnectionPool,
+    kzg_settings: Arc<KzgSettings>,
+    poll_interval: Duration,
+}
+
+impl KzgInfoPrecomputer {
+    pub fn new(pool: ConnectionPool, kzg_settings: Arc<KzgSettings>) -> Self {
+        Self {
+            pool,
+            kzg_settings,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Processes a single batch of L1 batches. Returns the number of processed L1 batches.
+    async fn precompute_batch(&self) -> anyhow::Result<usize> {
+        let mut storage = self.pool.access_storage_tagged("eth_sender").await?;
+        let pruned_count = storage
+            .blocks_dal()
+            .prune_committed_kzg_info()
+            .await
+            .context("prune_committed_kzg_info()")?;
+        if pruned_count > 0 {
+            tracing::debug!("Pruned KZG info for {pruned_count} committed L1 batches");
+        }
+        let l1_batches = storage
+            .blocks_dal()
+            .get_l1_batches_without_kzg_info(BATCH_SIZE)
+            .await
+            .context("get_l1_batches_without_kzg_info()")?;
+        drop(storage);
+
+        let processed_count = l1_batches.len();
+        for (l1_batch_number, pubdata) in l1_batches {
+            let kzg_settings = self.kzg_settings.clone();
+            let latency = METRICS.kzg_info_precomputation_latency.start();
+            let kzg_info = tokio::task::spawn_blocking(move || {
+                pubdata
+                    .chunks(ZK_SYNC_BYTES_PER_BLOB)
+                    .map(|blob| KzgInfo::new(&kzg_settings, blob).to_bytes().to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .context("KZG info computation panicked")?;
+            latency.observe();
+
+            let mut storage = self.pool.access_storage_tagged("eth_sender").await?;
+            storage
+                .blocks_dal()
+                .save_l1_batch_kzg_info(l1_batch_number, &kzg_info)
+                .await
+                .context("save_l1_batch_kzg_info()")?;
+            tracing::debug!("Precomputed KZG info for L1 batch #{l1_batch_number}");
+        }
+        Ok(processed_count)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, KZG info precomputer is shutting down");
+                break;
+            }
+
+            let processed_count = self.precompute_batch().await?;
+            if processed_count == BATCH_SIZE {
+                continue;
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+}
+
+/// Loads precomputed KZG info for the specified L1 batch. Returns `None` if it's not precomputed
+/// (e.g., the precomputer is lagging behind), in which case KZG info should be computed on the fly.
+pub(super) async fn load_precomputed_kzg_info(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+) -> Option<Vec<KzgInfo>> {
+    let serialized = storage
+        .blocks_dal()
+        .get_l1_batch_kzg_info(l1_batch_number)
+        .await
+        .unwrap()?;
+    if serialized
+        .iter()
+        .any(|bytes| bytes.len() != KzgInfo::SERIALIZED_SIZE)
+    {
+        tracing::warn!(
+            "Precomputed KZG info for L1 batch #{l1_batch_number} has unexpected size; ignoring"
+        );
+        return None;
+    }
+    Some(
+        serialized
+            .iter()
+            .map(|bytes| KzgInfo::from_slice(bytes))
+            .collect(),
+    )
+}
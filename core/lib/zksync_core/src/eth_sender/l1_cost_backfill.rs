@@ -0,0 +1,119 @@
+//! Backfill of actual L1 costs for ETH transactions sent by the server.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::EthInterface;
+use zksync_types::{eth_sender::EthTxL1Costs, L1BlockNumber};
+
+use super::metrics::METRICS;
+
+/// Number of ETH transactions processed in a single iteration.
+const BATCH_SIZE: usize = 50;
+
+/// Loads actual gas used and effective gas price for confirmed commit / prove / execute transactions
+/// from their L1 receipts and persists them in the `eth_txs_l1_costs` table.
+///
+/// On the first run, the task goes through all historical transactions; afterwards, it picks up
+/// newly confirmed transactions. Transactions are selected based on the absence of their costs in Postgres,
+/// so transactions whose receipts cannot be loaded (e.g., receipts not yet available or pruned by the L1 node,
+/// or bogus transactions inserted on external nodes) are retried on each poll.
+#[derive(Debug)]
+pub struct L1CostBackfill {
+    pool: ConnectionPool,
+    eth_client: Arc<dyn EthInterface>,
+    poll_interval: Duration,
+}
+
+impl L1CostBackfill {
+    pub fn new(pool: ConnectionPool, eth_client: Arc<dyn EthInterface>) -> Self {
+        Self {
+            pool,
+            eth_client,
+            poll_interval: Duration::from_secs(60),
+        }
+    }
+
+    /// Processes a single batch of transactions without L1 costs. Returns the number of loaded transactions
+    /// and the number of transactions with backfilled costs.
+    async fn backfill_batch(&self) -> anyhow::Result<(usize, usize)> {
+        let mut storage = self.pool.access_storage_tagged("eth_sender").await?;
+        let txs = storage
+            .eth_sender_dal()
+            .get_confirmed_txs_without_l1_costs(BATCH_SIZE)
+            .await
+            .context("get_confirmed_txs_without_l1_costs()")?;
+        drop(storage);
+
+        let mut costs_batch = Vec::with_capacity(txs.len());
+        for &(eth_tx_id, tx_hash) in &txs {
+            let receipt = self
+                .eth_client
+                .tx_receipt(tx_hash, "l1_cost_backfill")
+                .await
+                .with_context(|| format!("failed getting receipt for tx {tx_hash:?}"))?;
+
+            let costs = receipt.and_then(|receipt| {
+                Some(EthTxL1Costs {
+                    tx_hash,
+                    gas_used: receipt.gas_used?,
+                    effective_gas_price: receipt.effective_gas_price?,
+                    l1_block_number: L1BlockNumber(receipt.block_number?.as_u32()),
+                })
+            });
+            if let Some(costs) = costs {
+                costs_batch.push((eth_tx_id, costs));
+            } else {
+                tracing::warn!(
+                    "Receipt for ETH tx #{eth_tx_id} with hash {tx_hash:?} is missing or incomplete; \
+                     will retry on the next poll"
+                );
+                METRICS.l1_costs_missing_receipts.inc();
+            }
+        }
+
+        let mut storage = self.pool.access_storage_tagged("eth_sender").await?;
+        let mut transaction = storage.start_transaction().await?;
+        for (eth_tx_id, costs) in &costs_batch {
+            transaction
+                .eth_sender_dal()
+                .save_l1_costs(*eth_tx_id, costs)
+                .await
+                .context("save_l1_costs()")?;
+        }
+        transaction.commit().await?;
+        METRICS.l1_costs_backfilled.inc_by(costs_batch.len() as u64);
+        Ok((txs.len(), costs_batch.len()))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, L1 cost backfill is shutting down");
+                break;
+            }
+
+            let (loaded_count, backfilled_count) = match self.backfill_batch().await {
+                Ok(counts) => counts,
+                Err(err) => {
+                    // L1 client errors are transient; the batch will be retried on the next iteration.
+                    tracing::warn!("Failed backfilling L1 costs: {err:#}");
+                    (0, 0)
+                }
+            };
+            // If the batch was full, there may be more transactions to backfill. Transactions with missing receipts
+            // remain in the batch, so don't spin if none of the loaded transactions was backfilled.
+            if loaded_count == BATCH_SIZE && backfilled_count > 0 {
+                tracing::debug!("Backfilled L1 costs for {backfilled_count} ETH txs");
+                continue;
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+}
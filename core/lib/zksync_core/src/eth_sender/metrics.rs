@@ -2,7 +2,11 @@
 
 use std::{fmt, time::Duration};
 
-use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use serde::Serialize;
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    Metrics,
+};
 use zksync_dal::StorageProcessor;
 use zksync_types::{aggregated_operations::AggregatedActionType, eth_sender::EthTx};
 use zksync_utils::time::seconds_since_epoch;
@@ -31,6 +35,29 @@ pub(super) enum BlockNumberVariant {
     Safe,
 }
 
+/// Status of the oldest unexecuted priority operation relative to its processing deadline.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EncodeLabelSet,
+    EncodeLabelValue,
+    Serialize,
+)]
+#[metrics(label = "status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub(super) enum PriorityOpDeadlineStatus {
+    Ok,
+    Warning,
+    Critical,
+    Expired,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
 #[metrics(label = "type")]
 pub(super) struct ActionTypeLabel(AggregatedActionType);
@@ -100,6 +127,22 @@ pub(super) struct EthSenderMetrics {
     pub l1_blocks_waited_in_mempool: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of L1 batches aggregated for publishing with a specific reason.
     pub block_aggregation_reason: Family<AggregationReasonLabels, Counter>,
+    /// Number of ETH transactions which had their actual L1 costs loaded from receipts.
+    pub l1_costs_backfilled: Counter,
+    /// Number of attempts to load L1 costs that failed because the L1 receipt is missing or incomplete.
+    pub l1_costs_missing_receipts: Counter,
+    /// Latency of precomputing KZG info for the pubdata of a single L1 batch.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub kzg_info_precomputation_latency: Histogram<Duration>,
+    /// Age of the oldest priority operation not executed on L1.
+    pub oldest_unexecuted_priority_op_age: Gauge<Duration>,
+    /// Number of priority ops watchdog checks by the deadline status of the oldest unexecuted priority operation.
+    pub priority_op_deadline_status: Family<PriorityOpDeadlineStatus, Counter>,
+    /// Number of proofs loaded from the object store that don't match the hash recorded when they were saved.
+    pub proof_hash_mismatches: Counter,
+    /// Share of blob space filled with pubdata by a commit transaction publishing pubdata in blobs.
+    #[metrics(buckets = Buckets::linear(0.1..=1.0, 0.1))]
+    pub commit_blob_utilization: Histogram<f64>,
 }
 
 impl EthSenderMetrics {
@@ -148,3 +191,20 @@ impl EthSenderMetrics {
 
 #[vise::register]
 pub(super) static METRICS: vise::Global<EthSenderMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_operator_balance")]
+pub(super) struct OperatorBalanceMetrics {
+    /// Current balance of an operator account in ETH.
+    #[metrics(labels = ["account"])]
+    pub balance_eth: LabeledFamily<&'static str, Gauge<f64>>,
+    /// Estimated time until an operator account runs out of funds based on the recent spend rate.
+    #[metrics(labels = ["account"])]
+    pub runway_hours: LabeledFamily<&'static str, Gauge<f64>>,
+    /// Current balance of an operator account in the custom base token of the chain (in whole tokens).
+    #[metrics(labels = ["account"])]
+    pub balance_base_token: LabeledFamily<&'static str, Gauge<f64>>,
+}
+
+#[vise::register]
+pub(super) static BALANCE_METRICS: vise::Global<OperatorBalanceMetrics> = vise::Global::new();
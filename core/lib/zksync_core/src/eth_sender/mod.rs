@@ -1,16 +1,32 @@
 mod aggregated_operations;
 mod aggregator;
+mod balance_monitor;
 mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
+mod execution_policy;
+mod kzg_precomputer;
+mod l1_cost_backfill;
 mod metrics;
+mod priority_ops_watchdog;
+mod priority_tree;
+mod pubdata_da_selection;
 mod publish_criterion;
+mod signer_health;
 mod zksync_functions;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::{
-    aggregator::Aggregator, error::ETHSenderError, eth_tx_aggregator::EthTxAggregator,
+    aggregator::Aggregator,
+    balance_monitor::{BalanceThresholds, OperatorBalanceMonitor},
+    error::ETHSenderError,
+    eth_tx_aggregator::EthTxAggregator,
     eth_tx_manager::EthTxManager,
+    kzg_precomputer::KzgInfoPrecomputer,
+    l1_cost_backfill::L1CostBackfill,
+    priority_ops_watchdog::PriorityOpsWatchdog,
+    publish_criterion::L1BatchPublishCriterion,
+    signer_health::SignerHealthCheck,
 };
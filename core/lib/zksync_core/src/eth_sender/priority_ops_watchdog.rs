@@ -0,0 +1,194 @@
+//! Watchdog for priority operation processing deadlines.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{L1BatchNumber, PriorityOpId};
+
+use super::metrics::{PriorityOpDeadlineStatus, METRICS};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+impl PriorityOpDeadlineStatus {
+    /// Share of the deadline after which the status is escalated to [`Self::Warning`].
+    const WARNING_RATIO: f64 = 0.5;
+    /// Share of the deadline after which the status is escalated to [`Self::Critical`].
+    const CRITICAL_RATIO: f64 = 0.8;
+
+    fn new(age: Duration, deadline: Duration) -> Self {
+        if age >= deadline {
+            Self::Expired
+        } else if age >= deadline.mul_f64(Self::CRITICAL_RATIO) {
+            Self::Critical
+        } else if age >= deadline.mul_f64(Self::WARNING_RATIO) {
+            Self::Warning
+        } else {
+            Self::Ok
+        }
+    }
+}
+
+/// Oldest priority operation not executed on L1 yet.
+#[derive(Debug, Clone)]
+pub(super) struct UnexecutedPriorityOp {
+    pub priority_op_id: PriorityOpId,
+    /// L1 batch the operation is included into, or `None` if it's not included into a sealed L1 batch yet.
+    pub l1_batch_number: Option<L1BatchNumber>,
+    pub age: Duration,
+    pub status: PriorityOpDeadlineStatus,
+}
+
+impl UnexecutedPriorityOp {
+    /// Loads the oldest unexecuted priority operation from Postgres.
+    pub async fn load(
+        storage: &mut StorageProcessor<'_>,
+        deadline: Duration,
+    ) -> anyhow::Result<Option<Self>> {
+        let op = storage
+            .transactions_dal()
+            .get_oldest_unexecuted_priority_op()
+            .await
+            .context("get_oldest_unexecuted_priority_op()")?;
+        Ok(op.map(|(priority_op_id, l1_batch_number, received_at)| {
+            Self::new(priority_op_id, l1_batch_number, received_at, deadline)
+        }))
+    }
+
+    fn new(
+        priority_op_id: PriorityOpId,
+        l1_batch_number: Option<L1BatchNumber>,
+        received_at: NaiveDateTime,
+        deadline: Duration,
+    ) -> Self {
+        // `received_at` is the time the operation was observed by `eth_watch`, so the age can be slightly
+        // underestimated compared to the L1 block timestamp.
+        let age = (Utc::now().naive_utc() - received_at)
+            .to_std()
+            .unwrap_or_default();
+        Self {
+            priority_op_id,
+            l1_batch_number,
+            age,
+            status: PriorityOpDeadlineStatus::new(age, deadline),
+        }
+    }
+}
+
+/// Health details reported by [`PriorityOpsWatchdog`].
+#[derive(Debug, Serialize)]
+struct PriorityOpsWatchdogDetails {
+    priority_op_id: PriorityOpId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    l1_batch_number: Option<L1BatchNumber>,
+    age_sec: u64,
+    deadline_sec: u64,
+    status: PriorityOpDeadlineStatus,
+}
+
+/// Tracks the age of the oldest priority operation not executed on L1 against the configured processing deadline,
+/// and reports it via metrics and health checks. The component health is set to [`HealthStatus::Affected`]
+/// once the operation is past [`PriorityOpDeadlineStatus::Warning`] share of the deadline.
+///
+/// The watchdog doesn't influence L1 batch processing by itself; forced execution of L1 batches containing
+/// priority operations close to their deadline is implemented by a separate publish criterion.
+#[derive(Debug)]
+pub struct PriorityOpsWatchdog {
+    pool: ConnectionPool,
+    deadline: Duration,
+    poll_interval: Duration,
+    health_updater: HealthUpdater,
+}
+
+impl PriorityOpsWatchdog {
+    pub fn new(pool: ConnectionPool, deadline: Duration) -> Self {
+        Self {
+            pool,
+            deadline,
+            poll_interval: POLL_INTERVAL,
+            health_updater: ReactiveHealthCheck::new("priority_ops_watchdog").1,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    async fn check(&self) -> anyhow::Result<Health> {
+        let mut storage = self.pool.access_storage_tagged("eth_sender").await?;
+        let Some(op) = UnexecutedPriorityOp::load(&mut storage, self.deadline).await? else {
+            METRICS
+                .oldest_unexecuted_priority_op_age
+                .set(Duration::ZERO);
+            return Ok(Health::from(HealthStatus::Ready));
+        };
+        drop(storage);
+
+        METRICS.oldest_unexecuted_priority_op_age.set(op.age);
+        METRICS.priority_op_deadline_status[&op.status].inc();
+        let status = match op.status {
+            PriorityOpDeadlineStatus::Ok => HealthStatus::Ready,
+            PriorityOpDeadlineStatus::Warning => {
+                tracing::warn!("Priority op is approaching its processing deadline: {op:?}");
+                HealthStatus::Affected
+            }
+            PriorityOpDeadlineStatus::Critical | PriorityOpDeadlineStatus::Expired => {
+                tracing::error!("Priority op is close to or past its processing deadline: {op:?}");
+                HealthStatus::Affected
+            }
+        };
+        let details = PriorityOpsWatchdogDetails {
+            priority_op_id: op.priority_op_id,
+            l1_batch_number: op.l1_batch_number,
+            age_sec: op.age.as_secs(),
+            deadline_sec: self.deadline.as_secs(),
+            status: op.status,
+        };
+        Ok(Health::from(status).with_details(details))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, priority ops watchdog is shutting down");
+                break;
+            }
+
+            let health = self.check().await?;
+            self.health_updater.update(health);
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escalating_deadline_status() {
+        let deadline = Duration::from_secs(100);
+        let expected_statuses = [
+            (0, PriorityOpDeadlineStatus::Ok),
+            (49, PriorityOpDeadlineStatus::Ok),
+            (50, PriorityOpDeadlineStatus::Warning),
+            (79, PriorityOpDeadlineStatus::Warning),
+            (80, PriorityOpDeadlineStatus::Critical),
+            (99, PriorityOpDeadlineStatus::Critical),
+            (100, PriorityOpDeadlineStatus::Expired),
+            (1_000, PriorityOpDeadlineStatus::Expired),
+        ];
+        for (age, expected_status) in expected_statuses {
+            let status = PriorityOpDeadlineStatus::new(Duration::from_secs(age), deadline);
+            assert_eq!(status, expected_status, "age={age}");
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Merkle tree of priority operations mirroring the priority tree in L1 contracts.
+
+use anyhow::Context as _;
+use zksync_dal::StorageProcessor;
+use zksync_l1_contract_interface::i_executor::structures::PriorityOpsBatchInfo;
+use zksync_mini_merkle_tree::IncrementalMerkleTree;
+use zksync_types::{web3::signing::keccak256, L1BatchNumber, PriorityOpId, H256};
+
+/// Merkle tree with canonical hashes of priority operations as leaves, used to generate
+/// inclusion proofs for priority operations when executing L1 batches.
+///
+/// The tree on L1 only contains operations starting from `start_index` (i.e., the total number of priority operations
+/// at the moment of the contract upgrade); earlier operations are processed via the legacy priority queue
+/// with a rolling hash. Tree leaves are persisted by the L1 watcher, so the tree is restored from the storage
+/// on the first use.
+#[derive(Debug)]
+pub(super) struct PriorityOpsTree {
+    start_index: PriorityOpId,
+    tree: IncrementalMerkleTree,
+}
+
+impl PriorityOpsTree {
+    pub fn new(start_index: PriorityOpId) -> Self {
+        // Matches the zero leaf used for the priority tree on L1.
+        let zero_leaf = H256(keccak256(&[]));
+        Self {
+            start_index,
+            tree: IncrementalMerkleTree::new(zero_leaf),
+        }
+    }
+
+    pub fn start_index(&self) -> PriorityOpId {
+        self.start_index
+    }
+
+    /// Returns the ID of the first priority operation not in the tree.
+    fn next_op_id(&self) -> PriorityOpId {
+        self.start_index + self.tree.len() as u64
+    }
+
+    /// Ensures that the tree contains all priority operations with IDs less than `next_op_id`
+    /// by loading missing leaves from the storage.
+    async fn sync(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        next_op_id: PriorityOpId,
+    ) -> anyhow::Result<()> {
+        let tree_next_op_id = self.next_op_id();
+        if next_op_id <= tree_next_op_id {
+            return Ok(());
+        }
+
+        let leaves = storage
+            .priority_tree_dal()
+            .get_leaves(tree_next_op_id..next_op_id)
+            .await
+            .context("get_leaves()")?;
+        let expected_len = (next_op_id.0 - tree_next_op_id.0) as usize;
+        anyhow::ensure!(
+            leaves.len() == expected_len,
+            "Some of priority tree leaves #{tree_next_op_id}..#{next_op_id} are missing in storage"
+        );
+        self.tree.extend(leaves);
+        Ok(())
+    }
+
+    /// Returns the inclusion proof for priority operations in the specified L1 batch. The proof is empty
+    /// if the batch has no priority operations, or if its operations are processed via the legacy priority queue.
+    pub async fn batch_info(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<PriorityOpsBatchInfo> {
+        let op_ids = storage
+            .transactions_dal()
+            .get_priority_op_ids_for_l1_batch(l1_batch_number)
+            .await
+            .context("get_priority_op_ids_for_l1_batch()")?;
+        let Some(op_ids) = op_ids else {
+            return Ok(PriorityOpsBatchInfo::default());
+        };
+
+        let (first_op_id, last_op_id) = op_ids.into_inner();
+        if first_op_id < self.start_index {
+            anyhow::ensure!(
+                last_op_id < self.start_index,
+                "L1 batch #{l1_batch_number} contains priority ops #{first_op_id}..=#{last_op_id} both from \
+                 the legacy priority queue and the priority tree (starts from #{})",
+                self.start_index
+            );
+            return Ok(PriorityOpsBatchInfo::default());
+        }
+
+        self.sync(storage, last_op_id.next()).await?;
+        let range = (first_op_id.0 - self.start_index.0) as usize
+            ..(last_op_id.0 - self.start_index.0) as usize + 1;
+        let (_, paths) = self.tree.merkle_root_and_paths_for_range(range.clone());
+        Ok(PriorityOpsBatchInfo {
+            left_path: paths.left_path,
+            right_path: paths.right_path,
+            item_hashes: self.tree.leaves()[range].to_vec(),
+        })
+    }
+}
@@ -0,0 +1,181 @@
+//! Fee-market-aware selection between publishing pubdata in calldata and in blobs.
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use zksync_l1_contract_interface::{
+    i_executor::{
+        commit::kzg::{KzgInfo, KzgSettings, ZK_SYNC_BYTES_PER_BLOB},
+        structures::CommitBatchInfo,
+    },
+    Tokenizable,
+};
+use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
+use zksync_types::{commitment::L1BatchWithMetadata, ethabi, pubdata_da::PubdataDA, L1BatchNumber};
+
+use crate::l1_gas_price::L1TxParamsProvider;
+
+/// Blob gas consumed by a single blob (EIP-4844).
+const BLOB_GAS_PER_BLOB: u128 = 1 << 17;
+/// Execution gas spent on L1 per blob: the pubdata commitment in calldata (144 bytes) and the call
+/// to the point evaluation precompile.
+const L1_GAS_PER_BLOB_COMMITMENT: u128 = 144 * 16 + 50_000;
+
+/// Estimated L1 costs (in wei) of publishing pubdata of a commit operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct PubdataCosts {
+    pub calldata: u128,
+    pub blobs: u128,
+}
+
+impl PubdataCosts {
+    /// Estimates costs for L1 batches with the specified pubdata lengths. Each L1 batch occupies its own blobs.
+    pub fn estimate(
+        pubdata_lengths: impl IntoIterator<Item = usize>,
+        gas_price: u64,
+        blob_base_fee: u64,
+    ) -> Self {
+        let (mut calldata_gas, mut blob_count) = (0_u128, 0_u128);
+        for len in pubdata_lengths {
+            calldata_gas += len as u128 * u128::from(L1_GAS_PER_PUBDATA_BYTE);
+            blob_count += len.div_ceil(ZK_SYNC_BYTES_PER_BLOB).max(1) as u128;
+        }
+        Self {
+            calldata: calldata_gas * u128::from(gas_price),
+            blobs: blob_count
+                * (BLOB_GAS_PER_BLOB * u128::from(blob_base_fee)
+                    + L1_GAS_PER_BLOB_COMMITMENT * u128::from(gas_price)),
+        }
+    }
+
+    /// Returns the cheapest DA mode. Blobs are preferred if costs are equal.
+    pub fn cheapest(&self) -> PubdataDA {
+        if self.calldata < self.blobs {
+            PubdataDA::Calldata
+        } else {
+            PubdataDA::Blobs
+        }
+    }
+}
+
+/// Chooses between [`PubdataDA::Calldata`] and [`PubdataDA::Blobs`] for each commit operation based on
+/// the current execution gas price and blob base fee. Calldata is only chosen if all L1 batches in the operation
+/// can be committed with it without exceeding the transaction data limit.
+pub(super) struct PubdataDASelector {
+    gas_adjuster: Arc<dyn L1TxParamsProvider>,
+    kzg_settings: Option<Arc<KzgSettings>>,
+    data_limit: usize,
+}
+
+impl fmt::Debug for PubdataDASelector {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("PubdataDASelector")
+            .finish_non_exhaustive()
+    }
+}
+
+impl PubdataDASelector {
+    pub fn new(
+        gas_adjuster: Arc<dyn L1TxParamsProvider>,
+        kzg_settings: Option<Arc<KzgSettings>>,
+        data_limit: usize,
+    ) -> Self {
+        Self {
+            gas_adjuster,
+            kzg_settings,
+            data_limit,
+        }
+    }
+
+    fn pubdata_len(l1_batch: &L1BatchWithMetadata) -> usize {
+        match &l1_batch.header.pubdata_input {
+            Some(pubdata) => pubdata.len(),
+            None => l1_batch.construct_pubdata().len(),
+        }
+    }
+
+    /// Checks whether the L1 batches can be committed with calldata DA within the transaction data limit.
+    fn fits_into_calldata(
+        &self,
+        l1_batches: &[L1BatchWithMetadata],
+        precomputed_kzg_info: &HashMap<L1BatchNumber, Vec<KzgInfo>>,
+    ) -> bool {
+        const STORED_BLOCK_INFO_SIZE: usize = 96; // size of `StoredBlockInfo` solidity struct
+
+        let mut data_size = STORED_BLOCK_INFO_SIZE;
+        for l1_batch in l1_batches {
+            let kzg_info = precomputed_kzg_info.get(&l1_batch.header.number);
+            let info = CommitBatchInfo::builder(l1_batch, PubdataDA::Calldata)
+                .with_kzg_settings(self.kzg_settings.clone())
+                .with_precomputed_kzg_info(kzg_info.map(Vec::as_slice))
+                .build();
+            let info = match info {
+                Ok(info) => info,
+                Err(err) => {
+                    // E.g., pubdata doesn't fit into a single blob
+                    tracing::debug!(
+                        "L1 batch #{} cannot be committed with calldata DA: {err}",
+                        l1_batch.header.number
+                    );
+                    return false;
+                }
+            };
+            data_size += ethabi::encode(&[ethabi::Token::Array(vec![info.into_token()])]).len();
+        }
+        data_size <= self.data_limit
+    }
+
+    pub fn select(
+        &self,
+        l1_batches: &[L1BatchWithMetadata],
+        precomputed_kzg_info: &HashMap<L1BatchNumber, Vec<KzgInfo>>,
+    ) -> PubdataDA {
+        if !self.fits_into_calldata(l1_batches, precomputed_kzg_info) {
+            return PubdataDA::Blobs;
+        }
+
+        let pubdata_lengths = l1_batches.iter().map(Self::pubdata_len);
+        let gas_price = self.gas_adjuster.get_base_fee(0) + self.gas_adjuster.get_priority_fee();
+        let blob_base_fee = self.gas_adjuster.get_blob_base_fee();
+        let costs = PubdataCosts::estimate(pubdata_lengths, gas_price, blob_base_fee);
+        let pubdata_da = costs.cheapest();
+        tracing::debug!(
+            "Selected {pubdata_da:?} for committing {} L1 batches: {costs:?} (gas price {gas_price}, \
+             blob base fee {blob_base_fee})",
+            l1_batches.len()
+        );
+        pubdata_da
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimating_pubdata_costs() {
+        let costs = PubdataCosts::estimate([1_000, ZK_SYNC_BYTES_PER_BLOB + 1], 10, 1);
+        let expected_calldata_gas =
+            (1_000 + ZK_SYNC_BYTES_PER_BLOB as u128 + 1) * u128::from(L1_GAS_PER_PUBDATA_BYTE);
+        assert_eq!(costs.calldata, expected_calldata_gas * 10);
+        assert_eq!(
+            costs.blobs,
+            3 * (BLOB_GAS_PER_BLOB + L1_GAS_PER_BLOB_COMMITMENT * 10)
+        );
+    }
+
+    #[test]
+    fn selecting_cheapest_pubdata_da() {
+        // Blobs are cheap compared to calldata.
+        let costs = PubdataCosts::estimate([100_000], 30_000_000_000, 1);
+        assert_eq!(costs.cheapest(), PubdataDA::Blobs);
+
+        // Blob base fee spikes; small pubdata is cheaper to publish in calldata.
+        let costs = PubdataCosts::estimate([1_000], 30_000_000_000, 100_000_000_000);
+        assert_eq!(costs.cheapest(), PubdataDA::Calldata);
+
+        // Empty pubdata still occupies a blob.
+        let costs = PubdataCosts::estimate([0], 1, 1);
+        assert_eq!(costs.cheapest(), PubdataDA::Calldata);
+    }
+}
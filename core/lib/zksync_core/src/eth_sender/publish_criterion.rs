@@ -1,18 +1,28 @@
-use std::{fmt, sync::Arc};
+use std::{fmt, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use chrono::Utc;
 use zksync_dal::StorageProcessor;
 use zksync_l1_contract_interface::{
-    i_executor::{commit::kzg::KzgSettings, structures::CommitBatchInfo},
+    i_executor::{
+        commit::kzg::{KzgSettings, ZK_SYNC_BYTES_PER_BLOB},
+        structures::CommitBatchInfo,
+    },
     Tokenizable,
 };
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata, ethabi,
-    pubdata_da::PubdataDA, L1BatchNumber,
+    aggregated_operations::AggregatedActionType,
+    commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+    ethabi,
+    pubdata_da::PubdataDA,
+    L1BatchNumber,
 };
 
-use super::metrics::METRICS;
+use super::{
+    kzg_precomputer::load_precomputed_kzg_info,
+    metrics::{PriorityOpDeadlineStatus, METRICS},
+    priority_ops_watchdog::UnexecutedPriorityOp,
+};
 use crate::gas_tracker::agg_l1_batch_base_cost;
 
 #[async_trait]
@@ -30,6 +40,141 @@ pub trait L1BatchPublishCriterion: fmt::Debug + Send + Sync {
     ) -> Option<L1BatchNumber>;
 }
 
+/// Names of the built-in publish criteria.
+pub(super) const BUILTIN_CRITERIA: &[&str] =
+    &["l1_batch_number", "gas_limit", "data_size", "timestamp"];
+
+/// Criteria capping the size of aggregated operations, so that the produced L1 transactions fit into
+/// the L1 block gas limit and into the limits of L1 contracts. These criteria cannot be disabled.
+const SAFETY_CAP_CRITERIA: &[&str] = &["l1_batch_number", "gas_limit", "data_size"];
+
+/// Set of publish criteria applied to L1 batches ready for a certain aggregated operation. An operation
+/// is formed once any of the criteria triggers; the range of L1 batches is cut at the smallest L1 batch number
+/// returned by the triggered criteria.
+#[derive(Debug)]
+pub struct PublishCriteria {
+    op: AggregatedActionType,
+    criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
+}
+
+impl PublishCriteria {
+    /// Creates a criteria set. Criteria with names in `disabled` are skipped.
+    pub fn new(
+        op: AggregatedActionType,
+        criteria: Vec<Box<dyn L1BatchPublishCriterion>>,
+        disabled: &[String],
+    ) -> Self {
+        let criteria = criteria
+            .into_iter()
+            .filter(|criterion| {
+                let is_disabled = disabled.iter().any(|name| name == criterion.name());
+                if is_disabled {
+                    tracing::info!(
+                        "`{}` publish criterion is disabled for op {op}",
+                        criterion.name()
+                    );
+                }
+                !is_disabled
+            })
+            .collect();
+        Self { op, criteria }
+    }
+
+    /// Checks that all disabled criteria names refer to built-in criteria, none of which is a safety cap.
+    pub(super) fn validate_disabled(disabled: &[String]) -> anyhow::Result<()> {
+        for name in disabled {
+            anyhow::ensure!(
+                BUILTIN_CRITERIA.contains(&name.as_str()),
+                "unknown publish criterion `{name}`; expected one of {BUILTIN_CRITERIA:?}"
+            );
+            anyhow::ensure!(
+                !SAFETY_CAP_CRITERIA.contains(&name.as_str()),
+                "publish criterion `{name}` caps the size of L1 transactions and cannot be disabled"
+            );
+        }
+        Ok(())
+    }
+
+    /// Adds a custom criterion to the set.
+    pub fn push(&mut self, criterion: Box<dyn L1BatchPublishCriterion>) {
+        self.criteria.push(criterion);
+    }
+
+    /// Returns names of the enabled criteria.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.criteria
+            .iter()
+            .map(|criterion| criterion.name())
+            .collect()
+    }
+
+    /// Returns the prefix of `unpublished_l1_batches` that should be published, or `None` if no criteria
+    /// are triggered. Logs the decision together with the outcome of each criterion.
+    pub async fn extract_ready_subrange(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        unpublished_l1_batches: Vec<L1BatchWithMetadata>,
+        last_sealed_l1_batch: L1BatchNumber,
+    ) -> Option<Vec<L1BatchWithMetadata>> {
+        let (first, last) = match (
+            unpublished_l1_batches.first(),
+            unpublished_l1_batches.last(),
+        ) {
+            (Some(first), Some(last)) => (first.header.number, last.header.number),
+            _ => return None,
+        };
+
+        let mut outcomes = Vec::with_capacity(self.criteria.len());
+        for criterion in &mut self.criteria {
+            let l1_batch_by_criterion = criterion
+                .last_l1_batch_to_publish(storage, &unpublished_l1_batches, last_sealed_l1_batch)
+                .await;
+            outcomes.push((criterion.name(), l1_batch_by_criterion));
+        }
+        let decision = describe_outcomes(&outcomes);
+
+        let Some(last_l1_batch) = outcomes.iter().filter_map(|(_, number)| *number).min() else {
+            tracing::debug!(
+                "Not forming {} operation for ready L1 batches #{first}..=#{last}: {decision}",
+                self.op
+            );
+            return None;
+        };
+        if last_l1_batch < first {
+            tracing::warn!(
+                "Not forming {} operation for ready L1 batches #{first}..=#{last}: no batches can be published ({decision})",
+                self.op
+            );
+            return None;
+        }
+        tracing::info!(
+            "Forming {} operation for L1 batches #{first}..=#{last_l1_batch} (ready: #{first}..=#{last}): {decision}",
+            self.op
+        );
+        Some(
+            unpublished_l1_batches
+                .into_iter()
+                .take_while(|l1_batch| l1_batch.header.number <= last_l1_batch)
+                .collect(),
+        )
+    }
+}
+
+/// Describes outcomes of applying publish criteria in a human-readable form.
+fn describe_outcomes(outcomes: &[(&'static str, Option<L1BatchNumber>)]) -> String {
+    if outcomes.is_empty() {
+        return "no publish criteria are enabled".to_owned();
+    }
+    let descriptions: Vec<_> = outcomes
+        .iter()
+        .map(|(name, number)| match number {
+            Some(number) => format!("`{name}` triggered up to #{number}"),
+            None => format!("`{name}` not triggered"),
+        })
+        .collect();
+    descriptions.join(", ")
+}
+
 #[derive(Debug)]
 pub struct NumberCriterion {
     pub op: AggregatedActionType,
@@ -203,6 +348,7 @@ pub struct DataSizeCriterion {
     pub op: AggregatedActionType,
     pub data_limit: usize,
     pub pubdata_da: PubdataDA,
+    pub commitment_mode: L1BatchCommitmentMode,
     pub kzg_settings: Option<Arc<KzgSettings>>,
 }
 
@@ -214,7 +360,7 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
 
     async fn last_l1_batch_to_publish(
         &mut self,
-        _storage: &mut StorageProcessor<'_>,
+        storage: &mut StorageProcessor<'_>,
         consecutive_l1_batches: &[L1BatchWithMetadata],
         _last_sealed_l1_batch: L1BatchNumber,
     ) -> Option<L1BatchNumber> {
@@ -223,14 +369,34 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
 
         for (index, l1_batch) in consecutive_l1_batches.iter().enumerate() {
             // TODO (PLA-771): Make sure that this estimation is correct.
+            let kzg_info = load_precomputed_kzg_info(storage, l1_batch.header.number).await;
+            let da_inclusion_data = if self.pubdata_da == PubdataDA::Custom {
+                storage
+                    .data_availability_dal()
+                    .get_da_blob(l1_batch.header.number)
+                    .await
+                    .unwrap()
+                    .and_then(|blob| blob.inclusion_data)
+            } else {
+                None
+            };
+            let commit_batch_info = CommitBatchInfo::builder(l1_batch, self.pubdata_da)
+                .with_commitment_mode(self.commitment_mode)
+                .with_kzg_settings(self.kzg_settings.clone())
+                .with_precomputed_kzg_info(kzg_info.as_deref())
+                .with_da_inclusion_data(da_inclusion_data.as_deref())
+                .build();
+            let commit_batch_info = match commit_batch_info {
+                Ok(info) => info,
+                Err(err) => {
+                    // Batches are validated by the aggregator before applying criteria, so this shouldn't happen.
+                    // Still, we don't want to publish a batch with invalid data.
+                    tracing::error!("Cannot estimate commit data size: {err}");
+                    return (index > 0).then(|| l1_batch.header.number - 1);
+                }
+            };
             let l1_commit_data_size =
-                ethabi::encode(&[ethabi::Token::Array(vec![CommitBatchInfo::new(
-                    l1_batch,
-                    self.pubdata_da,
-                    self.kzg_settings.clone(),
-                )
-                .into_token()])])
-                .len();
+                ethabi::encode(&[ethabi::Token::Array(vec![commit_batch_info.into_token()])]).len();
             if data_size_left < l1_commit_data_size {
                 if index == 0 {
                     panic!(
@@ -256,3 +422,179 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
         None
     }
 }
+
+/// Criterion enforcing the maximum number of blobs attached to a single commit transaction. It only triggers
+/// once the L1 batches ready for commitment don't fit into the limit.
+/// Always applied if pubdata is published in blobs; cannot be disabled.
+#[derive(Debug)]
+pub struct BlobLimitCriterion {
+    pub max_blobs_per_eth_tx: u32,
+}
+
+impl BlobLimitCriterion {
+    /// Returns the number of blobs occupied by the pubdata of the specified L1 batch.
+    pub fn l1_batch_blob_count(l1_batch: &L1BatchWithMetadata) -> u32 {
+        let pubdata_len = match &l1_batch.header.pubdata_input {
+            Some(pubdata) => pubdata.len(),
+            None => l1_batch.construct_pubdata().len(),
+        };
+        pubdata_len.div_ceil(ZK_SYNC_BYTES_PER_BLOB).max(1) as u32
+    }
+
+    /// Returns the number of leading L1 batches with the specified blob counts fitting into the limit,
+    /// or `None` if all batches fit.
+    fn fitting_batch_count(&self, blob_counts: impl IntoIterator<Item = u32>) -> Option<usize> {
+        let mut total_blobs = 0;
+        for (index, blob_count) in blob_counts.into_iter().enumerate() {
+            total_blobs += blob_count;
+            if total_blobs > self.max_blobs_per_eth_tx {
+                return Some(index);
+            }
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl L1BatchPublishCriterion for BlobLimitCriterion {
+    fn name(&self) -> &'static str {
+        "blob_limit"
+    }
+
+    async fn last_l1_batch_to_publish(
+        &mut self,
+        _storage: &mut StorageProcessor<'_>,
+        consecutive_l1_batches: &[L1BatchWithMetadata],
+        _last_sealed_l1_batch: L1BatchNumber,
+    ) -> Option<L1BatchNumber> {
+        let blob_counts = consecutive_l1_batches.iter().map(Self::l1_batch_blob_count);
+        let batch_count = self.fitting_batch_count(blob_counts)?;
+        let first_l1_batch_number = consecutive_l1_batches[0].header.number;
+        if batch_count == 0 {
+            // Blob counts of batches are validated by the aggregator before applying criteria, so this shouldn't
+            // happen. Still, we don't want to publish a commit transaction that would be rejected by L1.
+            tracing::error!(
+                "L1 batch #{first_l1_batch_number} occupies more than {} blobs",
+                self.max_blobs_per_eth_tx
+            );
+            return Some(first_l1_batch_number - 1);
+        }
+
+        let output = consecutive_l1_batches[batch_count - 1].header.number;
+        tracing::debug!(
+            "`blob_limit` publish criterion (max_blobs={}) triggered for op {} with L1 batch range {:?}",
+            self.max_blobs_per_eth_tx,
+            AggregatedActionType::Commit,
+            first_l1_batch_number.0..=output.0
+        );
+        METRICS.block_aggregation_reason[&(AggregatedActionType::Commit, "blob_limit").into()]
+            .inc();
+        Some(output)
+    }
+}
+
+/// Criterion forcing execution of L1 batches once the oldest unexecuted priority operation contained in them
+/// reaches [`PriorityOpDeadlineStatus::Critical`] share of its processing deadline.
+#[derive(Debug)]
+pub struct PriorityOpDeadlineCriterion {
+    pub deadline: Duration,
+}
+
+#[async_trait]
+impl L1BatchPublishCriterion for PriorityOpDeadlineCriterion {
+    fn name(&self) -> &'static str {
+        "priority_op_deadline"
+    }
+
+    async fn last_l1_batch_to_publish(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        consecutive_l1_batches: &[L1BatchWithMetadata],
+        _last_sealed_l1_batch: L1BatchNumber,
+    ) -> Option<L1BatchNumber> {
+        let first_l1_batch_number = consecutive_l1_batches.first()?.header.number;
+        let last_l1_batch_number = consecutive_l1_batches.last()?.header.number;
+        let op = match UnexecutedPriorityOp::load(storage, self.deadline).await {
+            Ok(op) => op?,
+            Err(err) => {
+                tracing::warn!("Failed loading oldest unexecuted priority op: {err:#}");
+                return None;
+            }
+        };
+        if op.status < PriorityOpDeadlineStatus::Critical {
+            return None;
+        }
+        let op_l1_batch_number = op.l1_batch_number?;
+        if !(first_l1_batch_number..=last_l1_batch_number).contains(&op_l1_batch_number) {
+            return None;
+        }
+
+        tracing::info!(
+            "`priority_op_deadline` publish criterion triggered with L1 batch range {:?}: {op:?}",
+            first_l1_batch_number.0..=last_l1_batch_number.0
+        );
+        METRICS.block_aggregation_reason
+            [&(AggregatedActionType::Execute, "priority_op_deadline").into()]
+            .inc();
+        Some(last_l1_batch_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabling_criteria() {
+        let criteria: Vec<Box<dyn L1BatchPublishCriterion>> = vec![
+            Box::new(NumberCriterion {
+                op: AggregatedActionType::Execute,
+                limit: 10,
+            }),
+            Box::new(TimestampDeadlineCriterion {
+                op: AggregatedActionType::Execute,
+                deadline_seconds: 10,
+                max_allowed_lag: None,
+            }),
+        ];
+        let disabled = ["timestamp".to_owned()];
+        PublishCriteria::validate_disabled(&disabled).unwrap();
+        let criteria = PublishCriteria::new(AggregatedActionType::Execute, criteria, &disabled);
+        assert_eq!(criteria.names(), ["l1_batch_number"]);
+
+        let err = PublishCriteria::validate_disabled(&["unknown".to_owned()]).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown publish criterion"),
+            "{err}"
+        );
+        for safety_cap in SAFETY_CAP_CRITERIA {
+            let err = PublishCriteria::validate_disabled(&[safety_cap.to_string()]).unwrap_err();
+            assert!(err.to_string().contains("cannot be disabled"), "{err}");
+        }
+    }
+
+    #[test]
+    fn describing_criteria_outcomes() {
+        let outcomes = [
+            ("l1_batch_number", None),
+            ("gas_limit", Some(L1BatchNumber(5))),
+        ];
+        assert_eq!(
+            describe_outcomes(&outcomes),
+            "`l1_batch_number` not triggered, `gas_limit` triggered up to #5"
+        );
+        assert_eq!(describe_outcomes(&[]), "no publish criteria are enabled");
+    }
+
+    #[test]
+    fn limiting_blobs_per_commit_tx() {
+        let criterion = BlobLimitCriterion {
+            max_blobs_per_eth_tx: 3,
+        };
+        assert_eq!(criterion.fitting_batch_count([1, 1]), None);
+        assert_eq!(criterion.fitting_batch_count([1, 2]), None);
+        assert_eq!(criterion.fitting_batch_count([1, 2, 1]), Some(2));
+        assert_eq!(criterion.fitting_batch_count([2, 2]), Some(1));
+        assert_eq!(criterion.fitting_batch_count([4]), Some(0));
+    }
+}
@@ -0,0 +1,69 @@
+//! Health check for signers of operator accounts.
+
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use zksync_eth_signer::OperatorSigner;
+use zksync_health_check::{CheckHealth, Health, HealthStatus};
+use zksync_types::Address;
+
+#[derive(Debug, Serialize)]
+struct SignerHealthDetails {
+    backend: String,
+    address: Address,
+    latency_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Health check for an [`OperatorSigner`]. Checks that the signing backend is reachable and manages
+/// the operator key each time the health is requested.
+#[derive(Debug)]
+pub struct SignerHealthCheck {
+    name: &'static str,
+    signer: OperatorSigner,
+}
+
+impl SignerHealthCheck {
+    /// Creates a check for the main operator account.
+    pub fn operator(signer: OperatorSigner) -> Self {
+        Self {
+            name: "operator_signer",
+            signer,
+        }
+    }
+
+    /// Creates a check for the blobs operator account.
+    pub fn blobs_operator(signer: OperatorSigner) -> Self {
+        Self {
+            name: "blobs_operator_signer",
+            signer,
+        }
+    }
+}
+
+#[async_trait]
+impl CheckHealth for SignerHealthCheck {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn check_health(&self) -> Health {
+        let started_at = Instant::now();
+        let result = self.signer.check_health().await;
+
+        let status = if result.is_ok() {
+            HealthStatus::Ready
+        } else {
+            HealthStatus::NotReady
+        };
+        let details = SignerHealthDetails {
+            backend: format!("{:?}", self.signer.backend_kind()),
+            address: self.signer.address(),
+            latency_ms: started_at.elapsed().as_millis(),
+            error: result.err().map(|err| err.to_string()),
+        };
+        Health::from(status).with_details(details)
+    }
+}
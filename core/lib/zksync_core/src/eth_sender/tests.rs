@@ -1,8 +1,9 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use assert_matches::assert_matches;
 use once_cell::sync::Lazy;
 use test_casing::test_casing;
+use tokio::sync::watch;
 use zksync_config::{
     configs::{
         eth_sender::{ProofSendingMode, PubdataSendingMode, SenderConfig},
@@ -13,27 +14,39 @@ use zksync_config::{
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{clients::MockEthereum, EthInterface};
 use zksync_l1_contract_interface::i_executor::{
-    commit::kzg::KzgSettings,
+    commit::kzg::{KzgSettings, KzgSettingsProvider, ZK_SYNC_BYTES_PER_BLOB},
     methods::{CommitBatches, ExecuteBatches, ProveBatches},
+    structures::{CommitBatchInfo, CommitBatchInfoError, PriorityOpsBatchInfo, PubdataPayload},
 };
 use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     block::L1BatchHeader,
-    commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
+    commitment::{
+        L1BatchCommitmentMode, L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata,
+    },
     ethabi::Token,
     helpers::unix_timestamp_ms,
+    l1::{L1Tx, OpProcessingType, PriorityQueueType},
     pubdata_da::PubdataDA,
-    web3::contract::Error,
-    Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256,
+    tx::TransactionExecutionResult,
+    web3::{contract::Error, signing::keccak256},
+    Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, PriorityOpId,
+    ProtocolVersionId, H256, U256,
 };
 
 use crate::{
     eth_sender::{
-        aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers, Aggregator,
-        ETHSenderError, EthTxAggregator, EthTxManager,
+        aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers,
+        priority_tree::PriorityOpsTree, Aggregator, ETHSenderError, EthTxAggregator, EthTxManager,
     },
     l1_gas_price::GasAdjuster,
-    utils::testonly::{create_l1_batch, l1_batch_metadata_to_commitment_artifacts},
+    utils::{
+        simulation::Simulation,
+        testonly::{
+            create_l1_batch, create_l2_transaction, execute_l2_transaction,
+            l1_batch_metadata_to_commitment_artifacts,
+        },
+    },
 };
 
 // Alias to conveniently call static methods of `ETHSender`.
@@ -46,6 +59,7 @@ static DUMMY_OPERATION: Lazy<AggregatedOperation> = Lazy::new(|| {
             metadata: default_l1_batch_metadata(),
             raw_published_factory_deps: Vec::new(),
         }],
+        priority_ops_proofs: None,
     })
 });
 
@@ -104,7 +118,10 @@ impl EthSenderTester {
         );
         let store_factory = ObjectStoreFactory::mock();
 
-        let kzg_settings = Arc::new(KzgSettings::new(&KzgConfig::for_tests().trusted_setup_path));
+        let kzg_settings =
+            KzgSettingsProvider::from_path(KzgConfig::for_tests().trusted_setup_path)
+                .get()
+                .unwrap();
         let aggregator = EthTxAggregator::new(
             SenderConfig {
                 proof_sending_mode: ProofSendingMode::SkipEveryProof,
@@ -116,8 +133,10 @@ impl EthSenderTester {
                 store_factory.create_store().await,
                 aggregator_operate_4844_mode,
                 PubdataDA::Calldata,
+                L1BatchCommitmentMode::Rollup,
                 Some(kzg_settings.clone()),
-            ),
+            )
+            .unwrap(),
             gateway.clone(),
             // zkSync contract address
             Address::random(),
@@ -242,6 +261,149 @@ async fn confirm_many(aggregator_operate_4844_mode: bool) -> anyhow::Result<()>
     Ok(())
 }
 
+#[tokio::test]
+async fn dry_run_does_not_broadcast_transactions() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false, false).await;
+    tester.manager = EthTxManager::new(
+        SenderConfig {
+            dry_run: true,
+            ..ETHSenderConfig::for_tests().sender
+        },
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+        None,
+    );
+
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await?;
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &tx,
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await?.as_u32()),
+        )
+        .await?;
+
+    assert_eq!(tester.gateway.sent_tx_count(), 0);
+    let mut storage = tester.storage().await;
+    // The signed transaction is neither sent nor recorded in the tx history.
+    assert!(storage
+        .eth_sender_dal()
+        .get_unsent_txs()
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(storage
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await
+        .unwrap()
+        .is_empty());
+    let new_txs = storage.eth_sender_dal().get_new_eth_txs(10).await.unwrap();
+    assert_eq!(new_txs.len(), 1);
+    assert_eq!(new_txs[0].id, tx.id);
+    // The logged transaction is not returned again.
+    assert!(storage
+        .eth_sender_dal()
+        .get_new_eth_txs_after(tx.id, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn draining_inflight_txs_on_shutdown() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false, false).await;
+    let sender_config = SenderConfig {
+        tx_poll_period: 0,
+        shutdown_drain_timeout_sec: Some(60),
+        ..ETHSenderConfig::for_tests().sender
+    };
+
+    let hash = send_operation(&mut tester, DUMMY_OPERATION.clone(), false).await;
+    let new_tx = tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, true)
+        .await?;
+    tester
+        .gateway
+        .execute_tx(hash, true, EthSenderTester::WAIT_CONFIRMATIONS);
+
+    // The stop signal is sent before the manager is started, so it should only track the in-flight transaction.
+    let (_stop_sender, stop_receiver) = watch::channel(true);
+    let manager = EthTxManager::new(
+        sender_config.clone(),
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+        None,
+    );
+    tokio::time::timeout(
+        Duration::from_secs(10),
+        manager.run(tester.conn.clone(), stop_receiver.clone()),
+    )
+    .await??;
+
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+    let mut storage = tester.storage().await;
+    assert!(storage
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await
+        .unwrap()
+        .is_empty());
+    let new_txs = storage.eth_sender_dal().get_new_eth_txs(10).await.unwrap();
+    assert_eq!(new_txs.len(), 1);
+    assert_eq!(new_txs[0].id, new_tx.id);
+    drop(storage);
+
+    // If an in-flight transaction is not confirmed within the drain timeout, the manager should stop anyway.
+    let current_block = tester.gateway.block_number("").await?.as_u32();
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &new_tx,
+            0,
+            L1BlockNumber(current_block),
+        )
+        .await?;
+    let manager = EthTxManager::new(
+        SenderConfig {
+            shutdown_drain_timeout_sec: Some(0),
+            ..sender_config
+        },
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+        None,
+    );
+    tokio::time::timeout(
+        Duration::from_secs(10),
+        manager.run(tester.conn.clone(), stop_receiver),
+    )
+    .await??;
+    let inflight_txs = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await
+        .unwrap();
+    assert_eq!(inflight_txs.len(), 1);
+    assert_eq!(inflight_txs[0].id, new_tx.id);
+    Ok(())
+}
+
 // Tests that we resend first un-mined transaction every block with an increased gas price.
 #[tokio::test]
 async fn resend_each_block() -> anyhow::Result<()> {
@@ -426,6 +588,95 @@ async fn dont_resend_already_mined() -> anyhow::Result<()> {
     Ok(())
 }
 
+// Tests that a transaction dropped from L1 by a reorg is resent and eventually confirmed.
+#[tokio::test]
+async fn resend_after_l1_reorg() -> anyhow::Result<()> {
+    const L1_BLOCK_TIME: Duration = Duration::from_secs(12);
+
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false, false).await;
+    let mut simulation = Simulation::new(tester.gateway.clone(), L1_BLOCK_TIME);
+
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await?;
+    let hash = tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &tx,
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await?.as_u32()),
+        )
+        .await?;
+
+    // The transaction is mined in the current block, and then reorged out after 2 more blocks are mined.
+    simulation.at(L1_BLOCK_TIME / 2, move |l1| l1.execute_tx(hash, true, 0));
+    simulation.at(L1_BLOCK_TIME * 5 / 2, |l1| l1.reorg(3));
+
+    simulation.advance_l1_blocks(2);
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    assert!(to_resend.is_none(), "mined tx was resent");
+
+    simulation.advance_l1_blocks(1);
+    let block_numbers = tester.get_block_numbers().await;
+    let (to_resend, _) = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            block_numbers,
+        )
+        .await?
+        .expect("reorged tx was not resent");
+    assert_eq!(to_resend.id, tx.id);
+
+    let resent_hash = tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &to_resend,
+            1,
+            block_numbers.latest,
+        )
+        .await?;
+    assert_ne!(resent_hash, hash);
+    assert_eq!(tester.gateway.sent_tx_count(), 2);
+
+    simulation.at(simulation.elapsed(), move |l1| {
+        l1.execute_tx(resent_hash, true, 0)
+    });
+    simulation.advance_l1_blocks(EthSenderTester::WAIT_CONFIRMATIONS as u32 + 1);
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    assert!(to_resend.is_none());
+    assert!(tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await
+        .unwrap()
+        .is_empty());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn three_scenarios() -> anyhow::Result<()> {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -826,6 +1077,83 @@ async fn skipped_l1_batch_in_the_middle() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn encoding_commit_data_with_custom_da() {
+    let l1_batch = l1_batch_with_metadata(create_l1_batch(1));
+    let err = CommitBatchInfo::builder(&l1_batch, PubdataDA::Custom)
+        .build()
+        .unwrap_err();
+    assert_matches!(
+        err,
+        CommitBatchInfoError::MissingDAInclusionData(L1BatchNumber(1))
+    );
+
+    let inclusion_data = [1, 2, 3];
+    let info = CommitBatchInfo::builder(&l1_batch, PubdataDA::Custom)
+        .with_da_inclusion_data(Some(&inclusion_data))
+        .build()
+        .unwrap();
+    assert_eq!(
+        info.pubdata_payload().unwrap(),
+        PubdataPayload::Custom {
+            inclusion_data: &inclusion_data
+        }
+    );
+}
+
+#[tokio::test]
+async fn validating_max_blobs_per_eth_tx() {
+    let config = SenderConfig {
+        max_blobs_per_eth_tx: 1,
+        ..ETHSenderConfig::for_tests().sender
+    };
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let aggregator = Aggregator::new(
+        config.clone(),
+        blob_store.clone(),
+        true,
+        PubdataDA::Blobs,
+        L1BatchCommitmentMode::Rollup,
+        None,
+    )
+    .unwrap();
+    aggregator
+        .validate_max_pubdata_per_batch(ZK_SYNC_BYTES_PER_BLOB as u64)
+        .unwrap();
+    let err = aggregator
+        .validate_max_pubdata_per_batch(ZK_SYNC_BYTES_PER_BLOB as u64 + 1)
+        .unwrap_err();
+    assert!(err.to_string().contains("max_blobs_per_eth_tx"), "{err}");
+
+    let aggregator = Aggregator::new(
+        config,
+        blob_store,
+        false,
+        PubdataDA::Calldata,
+        L1BatchCommitmentMode::Rollup,
+        None,
+    )
+    .unwrap();
+    aggregator
+        .validate_max_pubdata_per_batch(ZK_SYNC_BYTES_PER_BLOB as u64 + 1)
+        .unwrap();
+}
+
+#[tokio::test]
+async fn blobs_are_rejected_in_validium_mode() {
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let err = Aggregator::new(
+        ETHSenderConfig::for_tests().sender,
+        blob_store,
+        true,
+        PubdataDA::Blobs,
+        L1BatchCommitmentMode::Validium,
+        None,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("validium"), "{err}");
+}
+
 #[tokio::test]
 async fn test_parse_multicall_data() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -914,6 +1242,111 @@ async fn get_multicall_data() {
     assert!(multicall_data.is_ok());
 }
 
+fn create_l1_tx(serial_id: u64) -> L1Tx {
+    L1Tx {
+        execute: Execute {
+            contract_address: Address::repeat_byte(0x11),
+            calldata: vec![1, 2, 3],
+            factory_deps: None,
+            value: U256::zero(),
+        },
+        common_data: L1TxCommonData {
+            serial_id: PriorityOpId(serial_id),
+            sender: Address::repeat_byte(1),
+            deadline_block: 0,
+            eth_hash: H256::repeat_byte(2),
+            eth_block: 1,
+            gas_limit: U256::zero(),
+            max_fee_per_gas: U256::zero(),
+            gas_per_pubdata_limit: 1_u32.into(),
+            full_fee: U256::zero(),
+            layer_2_tip_fee: U256::zero(),
+            refund_recipient: Address::zero(),
+            to_mint: U256::zero(),
+            priority_queue_type: PriorityQueueType::Deque,
+            op_processing_type: OpProcessingType::Common,
+            canonical_tx_hash: H256::from_low_u64_be(serial_id + 1),
+        },
+        received_timestamp_ms: 0,
+    }
+}
+
+#[tokio::test]
+async fn generating_priority_ops_proofs() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+    // L1 batch #1 contains priority ops #0 and #1, #2 contains #2..=#4, #3 contains no priority ops.
+    let batch_op_ids = [vec![0, 1], vec![2, 3, 4], vec![]];
+    let mut op_hashes = vec![];
+    for (i, op_ids) in batch_op_ids.iter().enumerate() {
+        let l1_batch_number = L1BatchNumber(i as u32 + 1);
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(l1_batch_number.0))
+            .await
+            .unwrap();
+        let mut tx_results = vec![];
+        for &op_id in op_ids {
+            let tx = create_l1_tx(op_id);
+            op_hashes.push(tx.hash());
+            tx_results.push(TransactionExecutionResult {
+                hash: tx.hash(),
+                ..execute_l2_transaction(create_l2_transaction(10, 100))
+            });
+            storage
+                .priority_tree_dal()
+                .insert_leaves(PriorityOpId(op_id), &[tx.hash()])
+                .await
+                .unwrap();
+            storage
+                .transactions_dal()
+                .insert_transaction_l1(tx, L1BlockNumber(1))
+                .await;
+        }
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(l1_batch_number, &tx_results)
+            .await;
+    }
+
+    // The priority tree on L1 starts from priority op #2.
+    let mut tree = PriorityOpsTree::new(PriorityOpId(2));
+    let proof = tree
+        .batch_info(&mut storage, L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(proof, PriorityOpsBatchInfo::default());
+    let proof = tree
+        .batch_info(&mut storage, L1BatchNumber(3))
+        .await
+        .unwrap();
+    assert_eq!(proof, PriorityOpsBatchInfo::default());
+
+    let proof = tree
+        .batch_info(&mut storage, L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert_eq!(proof.item_hashes, op_hashes[2..]);
+    let zero_leaf = H256(keccak256(&[]));
+    let first_node = H256(keccak256(&[op_hashes[2].0, op_hashes[3].0].concat()));
+    let second_node = H256(keccak256(&[op_hashes[4].0, zero_leaf.0].concat()));
+    assert_eq!(proof.left_path, [op_hashes[3], second_node]);
+    assert_eq!(proof.right_path, [zero_leaf, first_node]);
+
+    // L1 batch #1 cannot be processed neither via the legacy priority queue, nor via the priority tree.
+    let mut tree = PriorityOpsTree::new(PriorityOpId(1));
+    let err = tree
+        .batch_info(&mut storage, L1BatchNumber(1))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("both from the legacy priority queue"), "{err}");
+}
+
 async fn insert_genesis_protocol_version(tester: &EthSenderTester) {
     tester
         .storage()
@@ -962,6 +1395,7 @@ async fn execute_l1_batches(
 ) -> H256 {
     let operation = AggregatedOperation::Execute(ExecuteBatches {
         l1_batches: l1_batches.into_iter().map(l1_batch_with_metadata).collect(),
+        priority_ops_proofs: None,
     });
     send_operation(tester, operation, confirm).await
 }
@@ -992,7 +1426,10 @@ async fn commit_l1_batch(
         last_committed_l1_batch: l1_batch_with_metadata(last_committed_l1_batch),
         l1_batches: vec![l1_batch_with_metadata(l1_batch)],
         pubdata_da: PubdataDA::Calldata,
+        commitment_mode: L1BatchCommitmentMode::Rollup,
         kzg_settings: Some(kzg_settings),
+        precomputed_kzg_info: HashMap::new(),
+        da_inclusion_data: HashMap::new(),
     });
     send_operation(tester, operation, confirm).await
 }
@@ -20,8 +20,86 @@ pub(super) struct ZkSyncFunctions {
 
     pub(super) multicall_contract: Contract,
     pub(super) aggregate3: Function,
+
+    /// `executeBatches` accepting priority operation proofs; used by contracts with the priority tree.
+    pub(super) priority_tree_execute: Function,
+    /// `executeBatchesSharedBridge` accepting priority operation proofs.
+    pub(super) priority_tree_execute_shared_bridge: Function,
+    pub(super) priority_tree_contract: Contract,
+    pub(super) get_priority_tree_start_index: Function,
 }
 
+/// ABI of the main zkSync contract methods introduced together with the priority tree. `executeBatches*` methods
+/// have the same names as in the legacy ABI, but accept an additional argument with priority operation proofs.
+const PRIORITY_TREE_ABI: &str = r#"[{
+    "type": "function",
+    "name": "executeBatches",
+    "inputs": [
+        {
+            "name": "_batchesData",
+            "type": "tuple[]",
+            "components": [
+                { "name": "batchNumber", "type": "uint64" },
+                { "name": "batchHash", "type": "bytes32" },
+                { "name": "indexRepeatedStorageChanges", "type": "uint64" },
+                { "name": "numberOfLayer1Txs", "type": "uint256" },
+                { "name": "priorityOperationsHash", "type": "bytes32" },
+                { "name": "l2LogsTreeRoot", "type": "bytes32" },
+                { "name": "timestamp", "type": "uint256" },
+                { "name": "commitment", "type": "bytes32" }
+            ]
+        },
+        {
+            "name": "_priorityOpsData",
+            "type": "tuple[]",
+            "components": [
+                { "name": "leftPath", "type": "bytes32[]" },
+                { "name": "rightPath", "type": "bytes32[]" },
+                { "name": "itemHashes", "type": "bytes32[]" }
+            ]
+        }
+    ],
+    "outputs": [],
+    "stateMutability": "nonpayable"
+}, {
+    "type": "function",
+    "name": "executeBatchesSharedBridge",
+    "inputs": [
+        { "name": "_chainId", "type": "uint256" },
+        {
+            "name": "_batchesData",
+            "type": "tuple[]",
+            "components": [
+                { "name": "batchNumber", "type": "uint64" },
+                { "name": "batchHash", "type": "bytes32" },
+                { "name": "indexRepeatedStorageChanges", "type": "uint64" },
+                { "name": "numberOfLayer1Txs", "type": "uint256" },
+                { "name": "priorityOperationsHash", "type": "bytes32" },
+                { "name": "l2LogsTreeRoot", "type": "bytes32" },
+                { "name": "timestamp", "type": "uint256" },
+                { "name": "commitment", "type": "bytes32" }
+            ]
+        },
+        {
+            "name": "_priorityOpsData",
+            "type": "tuple[]",
+            "components": [
+                { "name": "leftPath", "type": "bytes32[]" },
+                { "name": "rightPath", "type": "bytes32[]" },
+                { "name": "itemHashes", "type": "bytes32[]" }
+            ]
+        }
+    ],
+    "outputs": [],
+    "stateMutability": "nonpayable"
+}, {
+    "type": "function",
+    "name": "getPriorityTreeStartIndex",
+    "inputs": [],
+    "outputs": [{ "name": "", "type": "uint256" }],
+    "stateMutability": "view"
+}]"#;
+
 fn get_function(contract: &Contract, name: &str) -> Function {
     contract
         .functions
@@ -64,6 +142,13 @@ impl Default for ZkSyncFunctions {
         let get_protocol_version = get_function(&zksync_contract, "getProtocolVersion");
         let aggregate3 = get_function(&multicall_contract, "aggregate3");
         let verification_key_hash = get_function(&verifier_contract, "verificationKeyHash");
+        let priority_tree_contract =
+            Contract::load(PRIORITY_TREE_ABI.as_bytes()).expect("priority tree ABI is invalid");
+        let priority_tree_execute = get_function(&priority_tree_contract, "executeBatches");
+        let priority_tree_execute_shared_bridge =
+            get_function(&priority_tree_contract, "executeBatchesSharedBridge");
+        let get_priority_tree_start_index =
+            get_function(&priority_tree_contract, "getPriorityTreeStartIndex");
 
         ZkSyncFunctions {
             pre_shared_bridge_commit,
@@ -81,6 +166,10 @@ impl Default for ZkSyncFunctions {
             verification_key_hash,
             multicall_contract,
             aggregate3,
+            priority_tree_execute,
+            priority_tree_execute_shared_bridge,
+            priority_tree_contract,
+            get_priority_tree_start_index,
         }
     }
 }
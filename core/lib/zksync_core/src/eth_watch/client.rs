@@ -1,14 +1,17 @@
-use std::{fmt, sync::Arc};
+use std::{fmt, sync::Arc, time::Duration};
 
+use zksync_config::configs::eth_client::L1FinalitySource;
 use zksync_contracts::verifier_contract;
-use zksync_eth_client::{CallFunctionArgs, Error as EthClientError, EthInterface};
+use zksync_eth_client::{
+    finalized_block_number, CallFunctionArgs, Error as EthClientError, ErrorKind, EthInterface,
+};
 use zksync_l1_contract_interface::pre_boojum_verifier::old_l1_vk_commitment;
 use zksync_types::{
     ethabi::{Contract, Token},
     web3::{
         self,
         contract::tokens::Detokenize,
-        types::{BlockId, BlockNumber, FilterBuilder, Log},
+        types::{BlockNumber, FilterBuilder, Log},
     },
     Address, H256,
 };
@@ -49,8 +52,8 @@ pub trait EthClient: 'static + fmt::Debug + Send + Sync {
 }
 
 pub const RETRY_LIMIT: usize = 5;
-const TOO_MANY_RESULTS_INFURA: &str = "query returned more than";
-const TOO_MANY_RESULTS_ALCHEMY: &str = "response size exceeded";
+/// Base backoff before retrying a rate-limited request.
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_millis(500);
 
 #[derive(Debug)]
 pub struct EthHttpQueryClient {
@@ -61,7 +64,7 @@ pub struct EthHttpQueryClient {
     /// If address is some then client will listen to events coming from it.
     governance_address: Option<Address>,
     verifier_contract_abi: Contract,
-    confirmations_for_eth_event: Option<u64>,
+    finality_source: L1FinalitySource,
 }
 
 impl EthHttpQueryClient {
@@ -69,7 +72,7 @@ impl EthHttpQueryClient {
         client: Arc<dyn EthInterface>,
         zksync_contract_addr: Address,
         governance_address: Option<Address>,
-        confirmations_for_eth_event: Option<u64>,
+        finality_source: L1FinalitySource,
     ) -> Self {
         tracing::debug!(
             "New eth client, zkSync addr: {:x}, governance addr: {:?}",
@@ -82,7 +85,7 @@ impl EthHttpQueryClient {
             zksync_contract_addr,
             governance_address,
             verifier_contract_abi: verifier_contract(),
-            confirmations_for_eth_event,
+            finality_source,
         }
     }
 
@@ -139,28 +142,12 @@ impl EthClient for EthHttpQueryClient {
         let latency = METRICS.get_priority_op_events.start();
         let mut result = self.get_filter_logs(from, to, self.topics.clone()).await;
 
-        // This code is compatible with both Infura and Alchemy API providers.
-        // Note: we don't handle rate-limits here - assumption is that we're never going to hit them.
-        if let Err(Error::EthClient(EthClientError::EthereumGateway(err))) = &result {
-            tracing::warn!("Provider returned error message: {:?}", err);
-            let err_message = err.to_string();
-            let err_code = if let web3::Error::Rpc(err) = err {
-                Some(err.code.code())
-            } else {
-                None
-            };
-
-            let should_retry = |err_code, err_message: String| {
-                // All of these can be emitted by either API provider.
-                err_code == Some(-32603)             // Internal error
-                    || err_message.contains("failed")    // Server error
-                    || err_message.contains("timed out") // Time-out error
-            };
+        if let Err(Error::EthClient(err)) = &result {
+            tracing::warn!("Provider returned error message: {err}");
+            let err_kind = err.kind();
 
             // check whether the error is related to having too many results
-            if err_message.contains(TOO_MANY_RESULTS_INFURA)
-                || err_message.contains(TOO_MANY_RESULTS_ALCHEMY)
-            {
+            if err_kind == ErrorKind::ResponseTooLarge {
                 // get the numeric block ids
                 let from_number = match from {
                     BlockNumber::Number(num) => num,
@@ -200,7 +187,14 @@ impl EthClient for EthHttpQueryClient {
 
                 first_half.append(&mut second_half);
                 result = Ok(first_half);
-            } else if should_retry(err_code, err_message) && retries_left > 0 {
+            } else if err_kind.is_retriable() && retries_left > 0 {
+                if err_kind == ErrorKind::RateLimited {
+                    // Back off linearly with the number of used retries.
+                    let used_retries = RETRY_LIMIT.saturating_sub(retries_left) as u32;
+                    let backoff = RATE_LIMIT_BACKOFF * (used_retries + 1);
+                    tracing::warn!("Rate-limited by L1 provider; backing off for {backoff:?}");
+                    tokio::time::sleep(backoff).await;
+                }
                 tracing::warn!("Retrying. Retries left: {:?}", retries_left);
                 result = self.get_events(from, to, retries_left - 1).await;
             }
@@ -211,21 +205,9 @@ impl EthClient for EthHttpQueryClient {
     }
 
     async fn finalized_block_number(&self) -> Result<u64, Error> {
-        if let Some(confirmations) = self.confirmations_for_eth_event {
-            let latest_block_number = self.client.block_number("watch").await?.as_u64();
-            Ok(latest_block_number.saturating_sub(confirmations))
-        } else {
-            self.client
-                .block(BlockId::Number(BlockNumber::Finalized), "watch")
-                .await
-                .map_err(Into::into)
-                .map(|res| {
-                    res.expect("Finalized block must be present on L1")
-                        .number
-                        .expect("Finalized block must contain number")
-                        .as_u64()
-                })
-        }
+        let number =
+            finalized_block_number(self.client.as_ref(), self.finality_source, "watch").await?;
+        Ok(number.as_u64())
     }
 
     fn set_topics(&mut self, topics: Vec<H256>) {
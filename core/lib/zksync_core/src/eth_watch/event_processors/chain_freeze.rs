@@ -0,0 +1,75 @@
+use zksync_dal::StorageProcessor;
+use zksync_types::{chain_freeze::ChainFreezeSource, ethabi, web3::types::Log, Address, H256};
+
+use crate::eth_watch::{
+    client::{Error, EthClient},
+    event_processors::EventProcessor,
+};
+
+/// Listens to `Freeze` / `Unfreeze` events emitted by the diamond proxy and records chain freeze state changes
+/// to the database.
+#[derive(Debug)]
+pub struct ChainFreezeEventProcessor {
+    diamond_proxy_address: Address,
+    freeze_signature: H256,
+    unfreeze_signature: H256,
+}
+
+impl ChainFreezeEventProcessor {
+    pub fn new(diamond_proxy_address: Address) -> Self {
+        Self {
+            diamond_proxy_address,
+            freeze_signature: ethabi::long_signature("Freeze", &[]),
+            unfreeze_signature: ethabi::long_signature("Unfreeze", &[]),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventProcessor for ChainFreezeEventProcessor {
+    async fn process_events(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        _client: &dyn EthClient,
+        events: Vec<Log>,
+    ) -> Result<(), Error> {
+        let relevant_events = events
+            .into_iter()
+            .filter(|event| event.address == self.diamond_proxy_address);
+        for event in relevant_events {
+            let is_frozen = if event.topics[0] == self.freeze_signature {
+                true
+            } else if event.topics[0] == self.unfreeze_signature {
+                false
+            } else {
+                continue;
+            };
+            let l1_tx_hash = event
+                .transaction_hash
+                .ok_or_else(|| Error::LogParse("freeze event without tx hash".to_owned()))?;
+
+            // Events are deduplicated by the L1 transaction hash, so re-processing L1 blocks after a restart
+            // doesn't change the freeze state.
+            let recorded_event = storage
+                .chain_freeze_dal()
+                .insert_event(is_frozen, ChainFreezeSource::L1, None, Some(l1_tx_hash))
+                .await
+                .expect("Failed recording chain freeze event");
+            if recorded_event.is_some() {
+                tracing::warn!(
+                    "Chain was {} on L1 in transaction {l1_tx_hash:?}",
+                    if is_frozen { "frozen" } else { "unfrozen" }
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn relevant_topic(&self) -> H256 {
+        self.freeze_signature
+    }
+
+    fn relevant_topics(&self) -> Vec<H256> {
+        vec![self.freeze_signature, self.unfreeze_signature]
+    }
+}
@@ -5,6 +5,7 @@ use zksync_types::{web3::types::Log, H256};
 
 use crate::eth_watch::client::{Error, EthClient};
 
+pub mod chain_freeze;
 pub mod governance_upgrades;
 pub mod priority_ops;
 pub mod upgrades;
@@ -21,4 +22,9 @@ pub trait EventProcessor: 'static + fmt::Debug + Send + Sync {
 
     /// Relevant topic which defines what events to be processed
     fn relevant_topic(&self) -> H256;
+
+    /// All relevant topics; should be overridden by processors handling multiple event types.
+    fn relevant_topics(&self) -> Vec<H256> {
+        vec![self.relevant_topic()]
+    }
 }
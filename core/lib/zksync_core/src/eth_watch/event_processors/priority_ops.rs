@@ -2,7 +2,7 @@ use std::convert::TryFrom;
 
 use zksync_contracts::zksync_contract;
 use zksync_dal::StorageProcessor;
-use zksync_types::{l1::L1Tx, web3::types::Log, PriorityOpId, H256};
+use zksync_types::{l1::L1Tx, web3::types::Log, PriorityOpId, H256, U256};
 
 use crate::{
     eth_watch::{
@@ -18,16 +18,53 @@ use crate::{
 pub struct PriorityOpsEventProcessor {
     next_expected_priority_id: PriorityOpId,
     new_priority_request_signature: H256,
+    /// Multiplier applied to the base fee per gas to get the minimal fee per gas priority transactions should pay.
+    priority_tx_fee_multiplier: f64,
 }
 
 impl PriorityOpsEventProcessor {
-    pub fn new(next_expected_priority_id: PriorityOpId) -> Self {
+    pub fn new(next_expected_priority_id: PriorityOpId, priority_tx_fee_multiplier: f64) -> Self {
         Self {
             next_expected_priority_id,
             new_priority_request_signature: zksync_contract()
                 .event("NewPriorityRequest")
                 .expect("NewPriorityRequest event is missing in abi")
                 .signature(),
+            priority_tx_fee_multiplier,
+        }
+    }
+
+    /// Checks that priority transactions pay the configured premium on top of the base fee of the last sealed miniblock.
+    /// Priority transactions cannot be skipped (L1 requires them to be processed in order), so underpriced transactions
+    /// are still accepted, but they are reported so that the operator can adjust L1 fee parameters. The premium itself
+    /// is charged by the state keeper, which raises the fee input of L1 batches including priority transactions.
+    async fn validate_fee_premium(&self, storage: &mut StorageProcessor<'_>, txs: &[L1Tx]) {
+        if self.priority_tx_fee_multiplier <= 1.0 {
+            return;
+        }
+        let last_miniblock = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .expect("Failed getting last sealed miniblock header");
+        let Some(last_miniblock) = last_miniblock else {
+            return;
+        };
+        let min_fee_per_gas = U256::from(
+            (last_miniblock.base_fee_per_gas as f64 * self.priority_tx_fee_multiplier) as u64,
+        );
+
+        for tx in txs {
+            if tx.common_data.max_fee_per_gas < min_fee_per_gas {
+                tracing::warn!(
+                    "Priority transaction #{} ({:?}) pays {} wei per gas, which is less than {min_fee_per_gas} wei \
+                     required by the configured fee premium",
+                    tx.serial_id(),
+                    tx.hash(),
+                    tx.common_data.max_fee_per_gas
+                );
+                METRICS.underpriced_priority_txs.inc();
+            }
         }
     }
 }
@@ -84,16 +121,34 @@ impl EventProcessor for PriorityOpsEventProcessor {
             "priority transaction serial id mismatch"
         );
 
+        self.validate_fee_premium(storage, &new_ops).await;
+
         let stage_latency = METRICS.poll_eth_node[&PollStage::PersistL1Txs].start();
         APP_METRICS.processed_txs[&TxStage::added_to_mempool()].inc();
         APP_METRICS.processed_l1_txs[&TxStage::added_to_mempool()].inc();
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .expect("Failed starting DB transaction");
+        // Leaves of the priority tree are persisted together with the operations, so that the tree
+        // can be restored without recomputing hashes for all operations.
+        let tree_leaves: Vec<_> = new_ops.iter().map(L1Tx::hash).collect();
+        transaction
+            .priority_tree_dal()
+            .insert_leaves(first_new.serial_id(), &tree_leaves)
+            .await
+            .expect("Failed persisting priority tree leaves");
         for new_op in new_ops {
             let eth_block = new_op.eth_block();
-            storage
+            transaction
                 .transactions_dal()
                 .insert_transaction_l1(new_op, eth_block)
                 .await;
         }
+        transaction
+            .commit()
+            .await
+            .expect("Failed committing DB transaction");
         stage_latency.observe();
         self.next_expected_priority_id = last_new.serial_id().next();
         Ok(())
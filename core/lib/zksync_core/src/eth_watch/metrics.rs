@@ -20,6 +20,8 @@ pub(super) struct EthWatcherMetrics {
     pub poll_eth_node: Family<PollStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub get_priority_op_events: Histogram<Duration>,
+    /// Number of priority transactions paying less than required by the configured fee premium.
+    pub underpriced_priority_txs: Counter,
 }
 
 #[vise::register]
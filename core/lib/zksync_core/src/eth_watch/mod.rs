@@ -7,7 +7,7 @@
 use std::{sync::Arc, time::Duration};
 
 use tokio::{sync::watch, task::JoinHandle};
-use zksync_config::ETHWatchConfig;
+use zksync_config::{configs::eth_client::L1FinalitySource, ETHWatchConfig};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::EthInterface;
 use zksync_system_constants::PRIORITY_EXPIRATION;
@@ -19,6 +19,7 @@ use zksync_types::{
 use self::{
     client::{Error, EthClient, EthHttpQueryClient, RETRY_LIMIT},
     event_processors::{
+        chain_freeze::ChainFreezeEventProcessor,
         governance_upgrades::GovernanceUpgradesEventProcessor,
         priority_ops::PriorityOpsEventProcessor, upgrades::UpgradesEventProcessor, EventProcessor,
     },
@@ -55,6 +56,7 @@ impl EthWatch {
         mut client: Box<dyn EthClient>,
         pool: ConnectionPool,
         poll_interval: Duration,
+        priority_tx_fee_multiplier: f64,
     ) -> Self {
         let mut storage = pool.access_storage_tagged("eth_watch").await.unwrap();
 
@@ -64,12 +66,15 @@ impl EthWatch {
 
         drop(storage);
 
-        let priority_ops_processor =
-            PriorityOpsEventProcessor::new(state.next_expected_priority_id);
+        let priority_ops_processor = PriorityOpsEventProcessor::new(
+            state.next_expected_priority_id,
+            priority_tx_fee_multiplier,
+        );
         let upgrades_processor = UpgradesEventProcessor::new(state.last_seen_version_id);
         let mut event_processors: Vec<Box<dyn EventProcessor>> = vec![
             Box::new(priority_ops_processor),
             Box::new(upgrades_processor),
+            Box::new(ChainFreezeEventProcessor::new(diamond_proxy_address)),
         ];
 
         if let Some(governance_contract) = governance_contract {
@@ -83,7 +88,7 @@ impl EthWatch {
 
         let topics = event_processors
             .iter()
-            .map(|p| p.relevant_topic())
+            .flat_map(|p| p.relevant_topics())
             .collect();
         client.set_topics(topics);
 
@@ -195,13 +200,16 @@ pub async fn start_eth_watch(
     eth_gateway: Arc<dyn EthInterface>,
     diamond_proxy_addr: Address,
     governance: (Contract, Address),
+    priority_tx_fee_multiplier: f64,
+    finality_source: Option<L1FinalitySource>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
+    let finality_source = finality_source.unwrap_or_else(|| config.legacy_finality_source());
     let eth_client = EthHttpQueryClient::new(
         eth_gateway,
         diamond_proxy_addr,
         Some(governance.1),
-        config.confirmations_for_eth_event,
+        finality_source,
     );
 
     let eth_watch = EthWatch::new(
@@ -210,6 +218,7 @@ pub async fn start_eth_watch(
         Box::new(eth_client),
         pool,
         config.poll_interval(),
+        priority_tx_fee_multiplier,
     )
     .await;
 
@@ -4,7 +4,8 @@ use tokio::sync::RwLock;
 use zksync_contracts::{governance_contract, zksync_contract};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::{
-    ethabi::{encode, Hash, Token},
+    chain_freeze::ChainFreezeSource,
+    ethabi::{self, encode, Hash, Token},
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
     protocol_version::{ProtocolUpgradeTx, ProtocolUpgradeTxCommonData},
     web3::types::{Address, BlockNumber, Log},
@@ -22,6 +23,7 @@ struct FakeEthClientData {
     transactions: HashMap<u64, Vec<Log>>,
     diamond_upgrades: HashMap<u64, Vec<Log>>,
     governance_upgrades: HashMap<u64, Vec<Log>>,
+    freeze_events: HashMap<u64, Vec<Log>>,
     last_finalized_block_number: u64,
 }
 
@@ -31,6 +33,7 @@ impl FakeEthClientData {
             transactions: Default::default(),
             diamond_upgrades: Default::default(),
             governance_upgrades: Default::default(),
+            freeze_events: Default::default(),
             last_finalized_block_number: 0,
         }
     }
@@ -63,6 +66,13 @@ impl FakeEthClientData {
         }
     }
 
+    fn add_freeze_event(&mut self, is_frozen: bool, eth_block: u64) {
+        self.freeze_events
+            .entry(eth_block)
+            .or_default()
+            .push(freeze_into_diamond_proxy_log(is_frozen, eth_block));
+    }
+
     fn set_last_finalized_block_number(&mut self, number: u64) {
         self.last_finalized_block_number = number;
     }
@@ -92,6 +102,13 @@ impl FakeEthClient {
         self.inner.write().await.add_governance_upgrades(upgrades);
     }
 
+    async fn add_freeze_event(&mut self, is_frozen: bool, eth_block: u64) {
+        self.inner
+            .write()
+            .await
+            .add_freeze_event(is_frozen, eth_block);
+    }
+
     async fn set_last_finalized_block_number(&mut self, number: u64) {
         self.inner
             .write()
@@ -132,6 +149,9 @@ impl EthClient for FakeEthClient {
             if let Some(ops) = self.inner.read().await.governance_upgrades.get(&number) {
                 logs.extend_from_slice(ops);
             }
+            if let Some(ops) = self.inner.read().await.freeze_events.get(&number) {
+                logs.extend_from_slice(ops);
+            }
         }
         Ok(logs)
     }
@@ -212,6 +232,7 @@ async fn test_normal_operation_l1_txs() {
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
+        1.0,
     )
     .await;
 
@@ -246,6 +267,14 @@ async fn test_normal_operation_l1_txs() {
     assert_eq!(db_txs.len(), 3);
     let db_tx = db_txs[2].clone();
     assert_eq!(db_tx.common_data.serial_id.0, 2);
+
+    let tree_leaves = storage
+        .priority_tree_dal()
+        .get_leaves(PriorityOpId(0)..PriorityOpId(3))
+        .await
+        .unwrap();
+    let expected_leaves: Vec<_> = db_txs.iter().map(L1Tx::hash).collect();
+    assert_eq!(tree_leaves, expected_leaves);
 }
 
 #[tokio::test]
@@ -260,6 +289,7 @@ async fn test_normal_operation_upgrades() {
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
+        1.0,
     )
     .await;
 
@@ -321,6 +351,7 @@ async fn test_gap_in_upgrades() {
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
+        1.0,
     )
     .await;
 
@@ -360,6 +391,7 @@ async fn test_normal_operation_governance_upgrades() {
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
+        1.0,
     )
     .await;
 
@@ -422,6 +454,7 @@ async fn test_gap_in_single_batch() {
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
+        1.0,
     )
     .await;
 
@@ -452,6 +485,7 @@ async fn test_gap_between_batches() {
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
+        1.0,
     )
     .await;
 
@@ -487,6 +521,7 @@ async fn test_overlapping_batches() {
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
+        1.0,
     )
     .await;
 
@@ -523,6 +558,48 @@ async fn test_overlapping_batches() {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+#[tokio::test]
+async fn test_chain_freeze_events() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        Address::repeat_byte(0x1),
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+        1.0,
+    )
+    .await;
+
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    client.add_freeze_event(true, 10).await;
+    client.add_freeze_event(false, 18).await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let event = storage
+        .chain_freeze_dal()
+        .get_latest_event()
+        .await
+        .unwrap()
+        .expect("no chain freeze event");
+    assert!(event.is_frozen);
+    assert_eq!(event.source, ChainFreezeSource::L1);
+    assert!(event.l1_tx_hash.is_some());
+
+    // Re-processing the same L1 blocks (e.g., after a restart) must not record the event again.
+    watcher.last_processed_ethereum_block = 0;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let latest_event = storage.chain_freeze_dal().get_latest_event().await.unwrap();
+    assert_eq!(latest_event.unwrap().id, event.id);
+
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert!(!storage.chain_freeze_dal().is_chain_frozen().await.unwrap());
+}
+
 async fn get_all_db_txs(storage: &mut StorageProcessor<'_>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await.unwrap();
     storage
@@ -603,6 +680,23 @@ fn upgrade_into_diamond_proxy_log(upgrade: ProtocolUpgrade, eth_block: u64) -> L
     }
 }
 
+fn freeze_into_diamond_proxy_log(is_frozen: bool, eth_block: u64) -> Log {
+    let event_name = if is_frozen { "Freeze" } else { "Unfreeze" };
+    Log {
+        address: Address::repeat_byte(0x1),
+        topics: vec![ethabi::long_signature(event_name, &[])],
+        data: vec![].into(),
+        block_hash: Some(H256::repeat_byte(0x11)),
+        block_number: Some(eth_block.into()),
+        transaction_hash: Some(H256::random()),
+        transaction_index: Some(0u64.into()),
+        log_index: Some(0u64.into()),
+        transaction_log_index: Some(0u64.into()),
+        log_type: None,
+        removed: None,
+    }
+}
+
 fn upgrade_into_governor_log(upgrade: ProtocolUpgrade, eth_block: u64) -> Log {
     let diamond_cut = upgrade_into_diamond_cut(upgrade);
     let execute_upgrade_selector = zksync_contract()
@@ -12,6 +12,8 @@ use zksync_utils::ceil_div_u256;
 
 use crate::l1_gas_price::GasAdjuster;
 
+pub mod simulation;
+
 /// Trait responsible for providing fee info for a batch
 #[async_trait::async_trait]
 pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
@@ -46,6 +48,12 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
 
     /// Returns the fee model parameters.
     fn get_fee_model_params(&self) -> FeeParams;
+
+    /// Checks whether L1 data used to compute fee model parameters is stale. New transactions should not be accepted
+    /// in this case.
+    fn is_l1_data_stale(&self) -> bool {
+        false
+    }
 }
 
 /// The struct that represents the batch fee input provider to be used in the main node of the server, i.e.
@@ -71,6 +79,10 @@ impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
             }),
         }
     }
+
+    fn is_l1_data_stale(&self) -> bool {
+        self.provider.is_l1_data_stale()
+    }
 }
 
 impl MainNodeFeeInputProvider {
@@ -79,6 +91,33 @@ impl MainNodeFeeInputProvider {
     }
 }
 
+/// Fee input provider with fixed fee model parameters, used by nodes without L1 access (e.g., in the forensics mode).
+/// When wrapped in [`ApiFeeInputProvider`] with zero L1 prices, fee inputs are effectively taken from the last sealed miniblock.
+#[derive(Debug)]
+pub(crate) struct FixedFeeInputProvider(FeeParams);
+
+impl FixedFeeInputProvider {
+    pub fn without_l1_prices(config: FeeModelConfig) -> Self {
+        Self(match config {
+            FeeModelConfig::V1(config) => FeeParams::V1(FeeParamsV1 {
+                config,
+                l1_gas_price: 0,
+            }),
+            FeeModelConfig::V2(config) => FeeParams::V2(FeeParamsV2 {
+                config,
+                l1_gas_price: 0,
+                l1_pubdata_price: 0,
+            }),
+        })
+    }
+}
+
+impl BatchFeeModelInputProvider for FixedFeeInputProvider {
+    fn get_fee_model_params(&self) -> FeeParams {
+        self.0
+    }
+}
+
 /// The fee model provider to be used in the API. It returns the maximal batch fee input between the projected main node one and
 /// the one from the last sealed miniblock.
 #[derive(Debug)]
@@ -129,6 +168,10 @@ impl BatchFeeModelInputProvider for ApiFeeInputProvider {
     fn get_fee_model_params(&self) -> FeeParams {
         self.inner.get_fee_model_params()
     }
+
+    fn is_l1_data_stale(&self) -> bool {
+        self.inner.is_l1_data_stale()
+    }
 }
 
 /// Calculates the batch fee input based on the main node parameters.
@@ -174,35 +217,39 @@ fn compute_batch_fee_model_input_v2(
     // While the final results of the calculations are not expected to have any overflows, the intermediate computations
     // might, so we use U256 for them.
     let l1_batch_overhead_wei = U256::from(l1_gas_price) * U256::from(batch_overhead_l1_gas);
+    // Fee params may be received from the main node, so we guard against division by zero for misconfigured limits
+    // (zero limits are treated as 1, i.e., the entire batch overhead is charged for each unit of gas / pubdata).
 
     let fair_l2_gas_price = {
         // Firstly, we calculate which part of the overall overhead overhead each unit of L2 gas should cover.
         let l1_batch_overhead_per_gas =
-            ceil_div_u256(l1_batch_overhead_wei, U256::from(max_gas_per_batch));
+            ceil_div_u256(l1_batch_overhead_wei, U256::from(max_gas_per_batch.max(1)));
 
         // Then, we multiply by the `compute_overhead_part` to get the overhead for the computation for each gas.
         // Also, this means that if we almost never close batches because of compute, the `compute_overhead_part` should be zero and so
         // it is possible that the computation costs include for no overhead.
         let gas_overhead_wei =
-            (l1_batch_overhead_per_gas.as_u64() as f64 * compute_overhead_part) as u64;
+            (saturating_u64(l1_batch_overhead_per_gas) as f64 * compute_overhead_part) as u64;
 
         // We sum up the minimal L2 gas price (i.e. the raw prover/compute cost of a single L2 gas) and the overhead for batch being closed.
-        minimal_l2_gas_price + gas_overhead_wei
+        minimal_l2_gas_price.saturating_add(gas_overhead_wei)
     };
 
     let fair_pubdata_price = {
         // Firstly, we calculate which part of the overall overhead overhead each pubdata byte should cover.
-        let l1_batch_overhead_per_pubdata =
-            ceil_div_u256(l1_batch_overhead_wei, U256::from(max_pubdata_per_batch));
+        let l1_batch_overhead_per_pubdata = ceil_div_u256(
+            l1_batch_overhead_wei,
+            U256::from(max_pubdata_per_batch.max(1)),
+        );
 
         // Then, we multiply by the `pubdata_overhead_part` to get the overhead for each pubdata byte.
         // Also, this means that if we almost never close batches because of pubdata, the `pubdata_overhead_part` should be zero and so
         // it is possible that the pubdata costs include no overhead.
         let pubdata_overhead_wei =
-            (l1_batch_overhead_per_pubdata.as_u64() as f64 * pubdata_overhead_part) as u64;
+            (saturating_u64(l1_batch_overhead_per_pubdata) as f64 * pubdata_overhead_part) as u64;
 
         // We sum up the raw L1 pubdata price (i.e. the expected price of publishing a single pubdata byte) and the overhead for batch being closed.
-        l1_pubdata_price + pubdata_overhead_wei
+        l1_pubdata_price.saturating_add(pubdata_overhead_wei)
     };
 
     PubdataIndependentBatchFeeModelInput {
@@ -212,8 +259,25 @@ fn compute_batch_fee_model_input_v2(
     }
 }
 
+/// Converts a value to `u64`, saturating on overflow. Overflows are only possible for extreme fee params,
+/// in which case the saturated price is still large enough to effectively block transactions.
+fn saturating_u64(value: U256) -> u64 {
+    if value > U256::from(u64::MAX) {
+        u64::MAX
+    } else {
+        value.as_u64()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use multivm::utils::{
+        adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata,
+        get_max_gas_per_pubdata_byte,
+    };
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+    use zksync_types::VmVersion;
+
     use super::*;
 
     // To test that overflow never happens, we'll use giant L1 gas price, i.e.
@@ -437,4 +501,141 @@ mod tests {
             "Max pubdata increase lowers pubdata price"
         );
     }
+
+    /// Number of random cases checked by each property test.
+    const PROPERTY_TEST_CASES: usize = 10_000;
+
+    /// Generates a value biased towards boundary cases.
+    fn gen_extreme_u64(rng: &mut impl Rng) -> u64 {
+        match rng.gen_range(0..4) {
+            0 => *[0, 1, u32::MAX.into(), u64::MAX].choose(rng).unwrap(),
+            1 => rng.gen_range(0..1_000),
+            // Realistic values up to 10,000 gwei
+            2 => rng.gen_range(0..10_000_000_000_000),
+            _ => rng.gen(),
+        }
+    }
+
+    fn gen_overhead_part(rng: &mut impl Rng) -> f64 {
+        if rng.gen() {
+            *[0.0, 1.0, 5.0].choose(rng).unwrap()
+        } else {
+            rng.gen_range(0.0..=1.0)
+        }
+    }
+
+    fn gen_fee_params(rng: &mut impl Rng) -> FeeParamsV2 {
+        FeeParamsV2 {
+            config: FeeModelConfigV2 {
+                minimal_l2_gas_price: gen_extreme_u64(rng),
+                compute_overhead_part: gen_overhead_part(rng),
+                pubdata_overhead_part: gen_overhead_part(rng),
+                batch_overhead_l1_gas: gen_extreme_u64(rng),
+                max_gas_per_batch: gen_extreme_u64(rng),
+                max_pubdata_per_batch: gen_extreme_u64(rng),
+            },
+            l1_gas_price: gen_extreme_u64(rng),
+            l1_pubdata_price: gen_extreme_u64(rng),
+        }
+    }
+
+    #[test]
+    fn compute_batch_fee_model_input_v2_is_monotonic_in_l1_prices() {
+        let rng = &mut StdRng::seed_from_u64(123);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let params = gen_fee_params(rng);
+            let input = compute_batch_fee_model_input_v2(params, 1.0, 1.0);
+
+            let larger_gas_price = params.l1_gas_price.saturating_add(gen_extreme_u64(rng));
+            let input_with_larger_gas_price = compute_batch_fee_model_input_v2(
+                FeeParamsV2 {
+                    l1_gas_price: larger_gas_price,
+                    ..params
+                },
+                1.0,
+                1.0,
+            );
+            assert!(
+                input_with_larger_gas_price.fair_l2_gas_price >= input.fair_l2_gas_price
+                    && input_with_larger_gas_price.fair_pubdata_price >= input.fair_pubdata_price,
+                "L1 gas price increase lowered prices: {params:?}, new L1 gas price: {larger_gas_price}"
+            );
+
+            let larger_pubdata_price = params.l1_pubdata_price.saturating_add(gen_extreme_u64(rng));
+            let input_with_larger_pubdata_price = compute_batch_fee_model_input_v2(
+                FeeParamsV2 {
+                    l1_pubdata_price: larger_pubdata_price,
+                    ..params
+                },
+                1.0,
+                1.0,
+            );
+            assert!(
+                input_with_larger_pubdata_price.fair_l2_gas_price == input.fair_l2_gas_price
+                    && input_with_larger_pubdata_price.fair_pubdata_price
+                        >= input.fair_pubdata_price,
+                "Unexpected effect of L1 pubdata price increase: {params:?}, \
+                 new L1 pubdata price: {larger_pubdata_price}"
+            );
+        }
+    }
+
+    #[test]
+    fn compute_batch_fee_model_input_v2_respects_bounds() {
+        let rng = &mut StdRng::seed_from_u64(456);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let params = gen_fee_params(rng);
+            let input = compute_batch_fee_model_input_v2(params, 1.0, 1.0);
+            // Prices are scaled in floating-point arithmetic, so large prices may be rounded.
+            let unscaled = |price: u64| (price as f64) as u64;
+            assert_eq!(
+                input.l1_gas_price,
+                unscaled(params.l1_gas_price),
+                "{params:?}"
+            );
+            assert!(
+                input.fair_l2_gas_price >= params.config.minimal_l2_gas_price,
+                "{params:?}"
+            );
+            assert!(
+                input.fair_pubdata_price >= unscaled(params.l1_pubdata_price),
+                "{params:?}"
+            );
+
+            let scale_factor = rng.gen_range(1.0..=5.0);
+            let scaled_input = compute_batch_fee_model_input_v2(params, scale_factor, scale_factor);
+            assert!(scaled_input.fair_l2_gas_price >= input.fair_l2_gas_price);
+            assert!(scaled_input.fair_pubdata_price >= input.fair_pubdata_price);
+        }
+    }
+
+    #[test]
+    fn gas_per_pubdata_is_bounded_for_computed_fee_input() {
+        let vm_version = VmVersion::latest();
+        let max_gas_per_pubdata = get_max_gas_per_pubdata_byte(vm_version);
+        let rng = &mut StdRng::seed_from_u64(789);
+        for _ in 0..PROPERTY_TEST_CASES {
+            let params = gen_fee_params(rng);
+            let input = BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
+                params, 1.0, 1.0,
+            ));
+            let (base_fee, gas_per_pubdata) =
+                derive_base_fee_and_gas_per_pubdata(input, vm_version);
+            assert!(gas_per_pubdata <= max_gas_per_pubdata, "{params:?}");
+            if input.fair_pubdata_price() > 0 {
+                assert!(base_fee > 0, "{params:?}");
+            }
+
+            let tx_gas_per_pubdata_limit = rng.gen_range(0..=2 * max_gas_per_pubdata);
+            let adjusted_input =
+                adjust_pubdata_price_for_tx(input, tx_gas_per_pubdata_limit.into(), vm_version);
+            let (_, adjusted_gas_per_pubdata) =
+                derive_base_fee_and_gas_per_pubdata(adjusted_input, vm_version);
+            assert!(
+                adjusted_gas_per_pubdata <= tx_gas_per_pubdata_limit,
+                "{params:?}, gas per pubdata limit: {tx_gas_per_pubdata_limit}"
+            );
+            assert!(adjusted_input.fair_pubdata_price() <= input.fair_pubdata_price());
+        }
+    }
 }
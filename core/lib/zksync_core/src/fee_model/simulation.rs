@@ -0,0 +1,525 @@
+//! Simulation of fee model changes against historical traffic.
+//!
+//! The simulator replays fee-related data of historical L2 transactions (computational gas and published pubdata)
+//! through a candidate fee model configuration, and compares the resulting fees with fees for the same transactions
+//! derived from the historical fee inputs. Both fees are computed in the same way (as `base_fee * gas_used`,
+//! where the pubdata part of the gas is derived from `gas_per_pubdata`), so the deltas are not skewed by refunds
+//! or user-provided gas limits. Fees under the candidate model are converted back to wei using the candidate
+//! base token ratio before they are compared to the historical fees.
+//!
+//! The simulation doesn't account for changes in user behavior caused by fee changes.
+
+use std::collections::BTreeMap;
+
+use multivm::utils::derive_base_fee_and_gas_per_pubdata;
+use serde::{Deserialize, Serialize};
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::transactions_dal::TxFeeRecord;
+use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
+use zksync_types::{
+    fee_model::{BatchFeeInput, FeeModelConfigV2, FeeParamsV2},
+    L1BatchNumber, ProtocolVersionId, VmVersion,
+};
+
+use super::compute_batch_fee_model_input_v2;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+fn default_factor() -> f64 {
+    1.0
+}
+
+/// Pricing of L1 pubdata used in the simulation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum PubdataPricing {
+    /// Pubdata is published as calldata, i.e., its price is derived from the historical L1 gas price.
+    #[default]
+    Calldata,
+    /// Pubdata has a fixed price per byte (in wei); can be used to estimate publishing pubdata in blobs.
+    Fixed { price_per_byte: u64 },
+}
+
+/// Candidate fee model configuration.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FeeModelCandidate {
+    pub fee_model: FeeModelConfigV2,
+    #[serde(default = "default_factor")]
+    pub l1_gas_price_scale_factor: f64,
+    #[serde(default = "default_factor")]
+    pub l1_pubdata_price_scale_factor: f64,
+    #[serde(default)]
+    pub pubdata_pricing: PubdataPricing,
+    /// Number of base token units per 1 wei. Should be set to 1 for chains with ETH as the base token.
+    /// Historical fees are assumed to be computed with the ratio equal to 1.
+    #[serde(default = "default_factor")]
+    pub base_token_ratio: f64,
+}
+
+impl FeeModelCandidate {
+    /// Converts an amount in base token units to wei.
+    fn to_wei(&self, base_token_amount: u128) -> f64 {
+        base_token_amount as f64 / self.base_token_ratio
+    }
+
+    fn fee_input(&self, historical_input: BatchFeeInput) -> BatchFeeInput {
+        let l1_gas_price = historical_input.l1_gas_price();
+        let l1_pubdata_price = match self.pubdata_pricing {
+            PubdataPricing::Calldata => l1_gas_price * u64::from(L1_GAS_PER_PUBDATA_BYTE),
+            PubdataPricing::Fixed { price_per_byte } => price_per_byte,
+        };
+        let params = FeeParamsV2 {
+            config: self.fee_model,
+            l1_gas_price: (l1_gas_price as f64 * self.base_token_ratio) as u64,
+            l1_pubdata_price: (l1_pubdata_price as f64 * self.base_token_ratio) as u64,
+        };
+        BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
+            params,
+            self.l1_gas_price_scale_factor,
+            self.l1_pubdata_price_scale_factor,
+        ))
+    }
+}
+
+/// Statistics of relative changes of transaction fees.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FeeDeltaStats {
+    pub mean_percent: f64,
+    pub median_percent: f64,
+    pub p95_percent: f64,
+    pub max_percent: f64,
+    /// Share of transactions which fees increase, in percent.
+    pub increased_share_percent: f64,
+}
+
+impl FeeDeltaStats {
+    fn new(mut deltas: Vec<f64>) -> Self {
+        if deltas.is_empty() {
+            return Self::default();
+        }
+        deltas.sort_unstable_by(f64::total_cmp);
+        let len = deltas.len();
+        let percentile = |p: usize| deltas[(len - 1) * p / 100];
+        Self {
+            mean_percent: deltas.iter().sum::<f64>() / len as f64,
+            median_percent: percentile(50),
+            p95_percent: percentile(95),
+            max_percent: deltas[len - 1],
+            increased_share_percent: deltas.iter().filter(|&&delta| delta > 0.0).count() as f64
+                * 100.0
+                / len as f64,
+        }
+    }
+}
+
+/// Simulation results for a single day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailySimulationReport {
+    /// UNIX timestamp of the day start.
+    pub day_start: u64,
+    pub tx_count: u64,
+    /// Fees derived from the historical fee inputs, in wei.
+    pub baseline_revenue: u128,
+    /// Fees derived from the candidate fee model, in base token units.
+    pub projected_revenue: u128,
+    /// Number of L1 batches started during the day.
+    pub l1_batches: u64,
+    /// Number of L1 batches required to fit the same traffic provided the batch capacity
+    /// from the candidate fee model.
+    pub projected_l1_batches: u64,
+}
+
+/// Overall simulation results.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimulationReport {
+    pub tx_count: u64,
+    /// Fees derived from the historical fee inputs, in wei.
+    pub baseline_revenue: u128,
+    /// Fees derived from the candidate fee model, in base token units.
+    pub projected_revenue: u128,
+    /// Change of revenue in wei, i.e. with projected revenue converted using the candidate base token ratio.
+    pub revenue_change_percent: f64,
+    /// Per-transaction relative fee changes.
+    pub fee_deltas: FeeDeltaStats,
+    pub days: Vec<DailySimulationReport>,
+}
+
+#[derive(Debug, Default)]
+struct DayAccumulator {
+    tx_count: u64,
+    baseline_revenue: u128,
+    projected_revenue: u128,
+}
+
+/// Batch capacity used to project the number of L1 batches. Mirrors the limits enforced by the state keeper
+/// seal criteria, rather than the batch capacity assumed by the fee model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SealLimits {
+    pub transaction_slots: u64,
+    pub max_gas_per_batch: u64,
+    /// Pubdata size after which the state keeper seals a batch.
+    pub max_pubdata_per_batch: u64,
+}
+
+impl SealLimits {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            transaction_slots: config.transaction_slots as u64,
+            max_gas_per_batch: config.max_gas_per_batch,
+            max_pubdata_per_batch: (config.max_pubdata_per_batch as f64
+                * config.close_block_at_eth_params_percentage)
+                .round() as u64,
+        }
+    }
+}
+
+/// Resources used by a historical L1 batch.
+#[derive(Debug)]
+struct BatchUsage {
+    day: u64,
+    tx_count: u64,
+    computational_gas: u64,
+    pubdata: u64,
+}
+
+/// Simulator of fee model changes. Transactions are fed to the simulator one by one via [`Self::process()`].
+#[derive(Debug)]
+pub struct FeeModelSimulator {
+    candidate: FeeModelCandidate,
+    seal_limits: SealLimits,
+    days: BTreeMap<u64, DayAccumulator>,
+    batches: BTreeMap<L1BatchNumber, BatchUsage>,
+    fee_deltas: Vec<f64>,
+}
+
+impl FeeModelSimulator {
+    pub fn new(candidate: FeeModelCandidate, seal_limits: SealLimits) -> Self {
+        Self {
+            candidate,
+            seal_limits,
+            days: BTreeMap::new(),
+            batches: BTreeMap::new(),
+            fee_deltas: vec![],
+        }
+    }
+
+    fn tx_fee(record: &TxFeeRecord, fee_input: BatchFeeInput, vm_version: VmVersion) -> u128 {
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, vm_version);
+        let gas_used = u128::from(record.computational_gas_used)
+            + u128::from(record.pubdata_published) * u128::from(gas_per_pubdata);
+        gas_used * u128::from(base_fee)
+    }
+
+    /// Processes a single historical transaction.
+    pub fn process(&mut self, record: &TxFeeRecord) {
+        let historical_version = record
+            .protocol_version
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        let baseline_fee = Self::tx_fee(record, record.batch_fee_input, historical_version.into());
+        let projected_input = self.candidate.fee_input(record.batch_fee_input);
+        // The candidate fee model is assumed to be applied to the latest protocol version.
+        let projected_fee =
+            Self::tx_fee(record, projected_input, ProtocolVersionId::latest().into());
+
+        let day = record.miniblock_timestamp / SECONDS_PER_DAY;
+        let day_acc = self.days.entry(day).or_default();
+        day_acc.tx_count += 1;
+        day_acc.baseline_revenue += baseline_fee;
+        day_acc.projected_revenue += projected_fee;
+        if baseline_fee > 0 {
+            let projected_fee_wei = self.candidate.to_wei(projected_fee);
+            let delta = (projected_fee_wei - baseline_fee as f64) / baseline_fee as f64;
+            self.fee_deltas.push(delta * 100.0);
+        }
+
+        if let Some(l1_batch_number) = record.l1_batch_number {
+            let usage = self.batches.entry(l1_batch_number).or_insert(BatchUsage {
+                day,
+                tx_count: 0,
+                computational_gas: 0,
+                pubdata: 0,
+            });
+            usage.tx_count += 1;
+            usage.computational_gas += record.computational_gas_used;
+            usage.pubdata += record.pubdata_published;
+        }
+    }
+
+    /// Returns the number of batches required to fit the historical batch usage with the state keeper seal limits.
+    /// Batches are never merged (i.e., the returned value is at least 1) since historical batches may be sealed
+    /// by criteria not related to capacity (e.g., timeouts).
+    fn projected_batch_count(&self, usage: &BatchUsage) -> u64 {
+        let limits = &self.seal_limits;
+        let by_slots = usage.tx_count.div_ceil(limits.transaction_slots.max(1));
+        let by_gas = usage
+            .computational_gas
+            .div_ceil(limits.max_gas_per_batch.max(1));
+        let by_pubdata = usage.pubdata.div_ceil(limits.max_pubdata_per_batch.max(1));
+        by_slots.max(by_gas).max(by_pubdata).max(1)
+    }
+
+    /// Finalizes the simulation.
+    pub fn report(self) -> SimulationReport {
+        let mut batch_counts = BTreeMap::<u64, (u64, u64)>::new();
+        for usage in self.batches.values() {
+            let counts = batch_counts.entry(usage.day).or_default();
+            counts.0 += 1;
+            counts.1 += self.projected_batch_count(usage);
+        }
+
+        let days: Vec<_> = self
+            .days
+            .iter()
+            .map(|(&day, acc)| {
+                let (l1_batches, projected_l1_batches) =
+                    batch_counts.get(&day).copied().unwrap_or_default();
+                DailySimulationReport {
+                    day_start: day * SECONDS_PER_DAY,
+                    tx_count: acc.tx_count,
+                    baseline_revenue: acc.baseline_revenue,
+                    projected_revenue: acc.projected_revenue,
+                    l1_batches,
+                    projected_l1_batches,
+                }
+            })
+            .collect();
+
+        let tx_count = days.iter().map(|day| day.tx_count).sum();
+        let baseline_revenue: u128 = days.iter().map(|day| day.baseline_revenue).sum();
+        let projected_revenue: u128 = days.iter().map(|day| day.projected_revenue).sum();
+        let revenue_change_percent = if baseline_revenue == 0 {
+            0.0
+        } else {
+            let projected_revenue_wei = self.candidate.to_wei(projected_revenue);
+            (projected_revenue_wei - baseline_revenue as f64) * 100.0 / baseline_revenue as f64
+        };
+        SimulationReport {
+            tx_count,
+            baseline_revenue,
+            projected_revenue,
+            revenue_change_percent,
+            fee_deltas: FeeDeltaStats::new(self.fee_deltas),
+            days,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{fee_model::PubdataIndependentBatchFeeModelInput, MiniblockNumber};
+
+    use super::*;
+
+    const GWEI: u64 = 1_000_000_000;
+
+    fn baseline_config() -> FeeModelConfigV2 {
+        FeeModelConfigV2 {
+            minimal_l2_gas_price: 100_000_000,
+            compute_overhead_part: 0.0,
+            pubdata_overhead_part: 1.0,
+            batch_overhead_l1_gas: 800_000,
+            max_gas_per_batch: 200_000_000,
+            max_pubdata_per_batch: 100_000,
+        }
+    }
+
+    fn seal_limits() -> SealLimits {
+        SealLimits {
+            transaction_slots: 250,
+            max_gas_per_batch: 200_000_000,
+            max_pubdata_per_batch: 95_000,
+        }
+    }
+
+    fn candidate(fee_model: FeeModelConfigV2) -> FeeModelCandidate {
+        FeeModelCandidate {
+            fee_model,
+            l1_gas_price_scale_factor: 1.0,
+            l1_pubdata_price_scale_factor: 1.0,
+            pubdata_pricing: PubdataPricing::Calldata,
+            base_token_ratio: 1.0,
+        }
+    }
+
+    fn records() -> Vec<TxFeeRecord> {
+        let l1_gas_price = 10 * GWEI;
+        let historical_input = compute_batch_fee_model_input_v2(
+            FeeParamsV2 {
+                config: baseline_config(),
+                l1_gas_price,
+                l1_pubdata_price: l1_gas_price * u64::from(L1_GAS_PER_PUBDATA_BYTE),
+            },
+            1.0,
+            1.0,
+        );
+        let record = TxFeeRecord {
+            miniblock_number: MiniblockNumber(1),
+            miniblock_timestamp: 10,
+            l1_batch_number: Some(L1BatchNumber(1)),
+            protocol_version: Some(ProtocolVersionId::latest()),
+            batch_fee_input: BatchFeeInput::PubdataIndependent(historical_input),
+            computational_gas_used: 100_000,
+            pubdata_published: 200,
+        };
+        vec![
+            record.clone(),
+            TxFeeRecord {
+                miniblock_number: MiniblockNumber(2),
+                miniblock_timestamp: SECONDS_PER_DAY + 10,
+                l1_batch_number: Some(L1BatchNumber(2)),
+                pubdata_published: 60_000,
+                ..record.clone()
+            },
+            TxFeeRecord {
+                miniblock_number: MiniblockNumber(3),
+                miniblock_timestamp: SECONDS_PER_DAY + 20,
+                l1_batch_number: Some(L1BatchNumber(2)),
+                pubdata_published: 60_000,
+                ..record
+            },
+        ]
+    }
+
+    #[test]
+    fn simulating_unchanged_fee_model() {
+        let mut simulator = FeeModelSimulator::new(candidate(baseline_config()), seal_limits());
+        for record in &records() {
+            simulator.process(record);
+        }
+        let report = simulator.report();
+
+        assert_eq!(report.tx_count, 3);
+        assert!(report.baseline_revenue > 0);
+        assert_eq!(report.projected_revenue, report.baseline_revenue);
+        assert_eq!(report.fee_deltas.max_percent, 0.0);
+        assert_eq!(report.days.len(), 2);
+        assert_eq!(report.days[0].tx_count, 1);
+        assert_eq!(report.days[1].tx_count, 2);
+        assert_eq!(report.days[1].day_start, SECONDS_PER_DAY);
+        assert_eq!(report.days[1].l1_batches, 1);
+        // The batch #2 has 120,000 bytes of pubdata, which doesn't fit into a single batch.
+        assert_eq!(report.days[1].projected_l1_batches, 2);
+    }
+
+    #[test]
+    fn simulating_fee_model_changes() {
+        let mut fee_model = baseline_config();
+        fee_model.minimal_l2_gas_price *= 2;
+        // Batch capacity assumed by the fee model doesn't influence the projected number of batches.
+        fee_model.max_pubdata_per_batch = 150_000;
+        let mut simulator = FeeModelSimulator::new(
+            FeeModelCandidate {
+                l1_pubdata_price_scale_factor: 1.5,
+                ..candidate(fee_model)
+            },
+            seal_limits(),
+        );
+        for record in &records() {
+            simulator.process(record);
+        }
+        let report = simulator.report();
+
+        assert!(report.projected_revenue > report.baseline_revenue);
+        assert!(report.revenue_change_percent > 0.0);
+        assert!(report.fee_deltas.median_percent > 0.0);
+        assert_eq!(report.fee_deltas.increased_share_percent, 100.0);
+        assert_eq!(report.days[1].projected_l1_batches, 2);
+
+        let mut simulator = FeeModelSimulator::new(
+            candidate(baseline_config()),
+            SealLimits {
+                max_pubdata_per_batch: 150_000,
+                ..seal_limits()
+            },
+        );
+        for record in &records() {
+            simulator.process(record);
+        }
+        assert_eq!(simulator.report().days[1].projected_l1_batches, 1);
+
+        let mut simulator = FeeModelSimulator::new(
+            candidate(baseline_config()),
+            SealLimits {
+                transaction_slots: 1,
+                max_pubdata_per_batch: 150_000,
+                ..seal_limits()
+            },
+        );
+        for record in &records() {
+            simulator.process(record);
+        }
+        assert_eq!(simulator.report().days[1].projected_l1_batches, 2);
+    }
+
+    #[test]
+    fn revenue_change_is_measured_in_wei() {
+        // Base token is twice cheaper than ETH, and all prices in the fee model are scaled accordingly,
+        // so the revenue in wei doesn't change.
+        let mut fee_model = baseline_config();
+        fee_model.minimal_l2_gas_price *= 2;
+        let mut simulator = FeeModelSimulator::new(
+            FeeModelCandidate {
+                base_token_ratio: 2.0,
+                ..candidate(fee_model)
+            },
+            seal_limits(),
+        );
+        for record in &records() {
+            simulator.process(record);
+        }
+        let report = simulator.report();
+
+        assert!(report.projected_revenue > report.baseline_revenue);
+        assert!(
+            report.revenue_change_percent.abs() < 0.01,
+            "{}",
+            report.revenue_change_percent
+        );
+        assert!(
+            report.fee_deltas.max_percent.abs() < 0.01,
+            "{:?}",
+            report.fee_deltas
+        );
+    }
+
+    #[test]
+    fn candidate_fee_input_with_base_token() {
+        let fee_input = BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+            l1_gas_price: 10 * GWEI,
+            fair_l2_gas_price: 0,
+            fair_pubdata_price: 0,
+        });
+        let eth_candidate = FeeModelCandidate {
+            pubdata_pricing: PubdataPricing::Fixed {
+                price_per_byte: GWEI,
+            },
+            ..candidate(baseline_config())
+        };
+        let eth_input = eth_candidate.fee_input(fee_input);
+        let token_input = FeeModelCandidate {
+            base_token_ratio: 2.0,
+            ..eth_candidate
+        }
+        .fee_input(fee_input);
+
+        assert_eq!(token_input.l1_gas_price(), 2 * eth_input.l1_gas_price());
+        assert!(token_input.fair_pubdata_price() > eth_input.fair_pubdata_price());
+    }
+
+    #[test]
+    fn deserializing_candidate() {
+        let json = serde_json::json!({
+            "fee_model": baseline_config(),
+            "pubdata_pricing": { "mode": "fixed", "price_per_byte": 1_000 },
+        });
+        let candidate: FeeModelCandidate = serde_json::from_value(json).unwrap();
+        assert_eq!(candidate.base_token_ratio, 1.0);
+        assert_eq!(
+            candidate.pubdata_pricing,
+            PubdataPricing::Fixed {
+                price_per_byte: 1_000
+            }
+        );
+    }
+}
@@ -8,25 +8,31 @@ use multivm::{
     zk_evm_latest::aux_structures::{LogQuery as MultiVmLogQuery, Timestamp as MultiVMTimestamp},
     zkevm_test_harness_latest::witness::sort_storage_access::sort_storage_access_queries,
 };
-use zksync_contracts::{BaseSystemContracts, SET_CHAIN_ID_EVENT};
+use zksync_config::ContractsConfig;
+use zksync_contracts::{zksync_contract, BaseSystemContracts, SET_CHAIN_ID_EVENT};
 use zksync_dal::StorageProcessor;
-use zksync_eth_client::{clients::QueryClient, EthInterface};
+use zksync_eth_client::{clients::QueryClient, CallFunctionArgs, EthInterface};
+use zksync_l1_contract_interface::{i_executor::structures::StoredBatchInfo, Tokenizable};
 use zksync_merkle_tree::domain::ZkSyncTree;
-use zksync_system_constants::PRIORITY_EXPIRATION;
+use zksync_system_constants::{PRIORITY_EXPIRATION, SYSTEM_CONTEXT_CHAIN_ID_POSITION};
 use zksync_types::{
     block::{
         BlockGasCount, DeployedContract, L1BatchHeader, L1BatchTreeData, MiniblockHasher,
         MiniblockHeader,
     },
-    commitment::{CommitmentInput, L1BatchCommitment},
+    commitment::{CommitmentInput, L1BatchCommitment, L1BatchWithMetadata},
+    ethabi,
     fee_model::BatchFeeInput,
-    get_code_key, get_system_context_init_logs,
+    get_code_key, get_system_context_init_logs, get_system_context_key,
     protocol_version::{decode_set_chain_id_event, L1VerifierConfig, ProtocolVersion},
     tokens::{TokenInfo, TokenMetadata, ETHEREUM_ADDRESS},
-    web3::types::{BlockNumber, FilterBuilder},
+    web3::{
+        signing::keccak256,
+        types::{BlockNumber, FilterBuilder},
+    },
     zk_evm_types::{LogQuery, Timestamp},
     AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId,
-    StorageKey, StorageLog, StorageLogKind, H256,
+    StorageKey, StorageLog, StorageLogKind, H256, U256,
 };
 use zksync_utils::{be_words_to_bytes, bytecode::hash_bytecode, h256_to_u256, u256_to_h256};
 
@@ -150,6 +156,126 @@ pub async fn ensure_genesis_state(
     Ok(genesis_root_hash)
 }
 
+/// Checks that the genesis L1 batch persisted in Postgres agrees with the node configuration and with the genesis batch
+/// stored by the L1 diamond proxy (queried via its getters facet). A mismatch means that the node is misconfigured
+/// (e.g., uses a wrong chain ID, or Postgres / the diamond proxy belong to another chain); without this check,
+/// it would only surface as opaque reverts when committing batches. Since the genesis root hash covers the chain ID
+/// stored in the system context, the L1 check also validates the chain ID.
+///
+/// A successful L1 check is persisted in Postgres, so that subsequent startups don't require L1 to be available
+/// unless the diamond proxy address or the genesis batch change.
+pub async fn validate_genesis_state(
+    storage: &mut StorageProcessor<'_>,
+    eth_client: &dyn EthInterface,
+    zksync_chain_id: L2ChainId,
+    contracts_config: &ContractsConfig,
+) -> anyhow::Result<()> {
+    let diamond_proxy_addr = contracts_config.diamond_proxy_addr;
+    let genesis_batch =
+        check_local_genesis_state(storage, zksync_chain_id, contracts_config.genesis_root).await?;
+    let genesis_batch_hash = stored_batch_hash(&genesis_batch);
+    let is_validated = storage
+        .blocks_dal()
+        .is_genesis_batch_validated(diamond_proxy_addr, genesis_batch_hash)
+        .await
+        .context("failed checking cached genesis validation")?;
+    if is_validated {
+        tracing::info!(
+            "Genesis L1 batch {genesis_batch_hash:?} was validated against diamond proxy at {diamond_proxy_addr:?} before; \
+             skipping L1 check"
+        );
+        return Ok(());
+    }
+
+    let args = CallFunctionArgs::new("storedBatchHash", (U256::zero(),))
+        .for_contract(diamond_proxy_addr, zksync_contract());
+    let output = eth_client
+        .call_contract_function(args)
+        .await
+        .with_context(|| {
+            format!(
+                "failed getting genesis batch hash from diamond proxy at {diamond_proxy_addr:?}"
+            )
+        })?;
+    let l1_genesis_batch_hash = match output.as_slice() {
+        [ethabi::Token::FixedBytes(bytes)] if bytes.len() == 32 => H256::from_slice(bytes),
+        _ => anyhow::bail!("unexpected output of `storedBatchHash`: {output:?}"),
+    };
+
+    check_l1_genesis_batch_hash(&genesis_batch, l1_genesis_batch_hash)?;
+    storage
+        .blocks_dal()
+        .mark_genesis_batch_validated(diamond_proxy_addr, genesis_batch_hash)
+        .await
+        .context("failed persisting genesis validation")?;
+    tracing::info!(
+        "Validated genesis L1 batch against configuration and L1 (root hash: {:?}, chain ID: {})",
+        genesis_batch.metadata.root_hash,
+        zksync_chain_id.as_u64()
+    );
+    Ok(())
+}
+
+fn stored_batch_hash(l1_batch: &L1BatchWithMetadata) -> H256 {
+    let batch_info = StoredBatchInfo::from(l1_batch).into_token();
+    H256(keccak256(&ethabi::encode(&[batch_info])))
+}
+
+/// Checks the genesis L1 batch persisted in Postgres against the node configuration and returns it.
+async fn check_local_genesis_state(
+    storage: &mut StorageProcessor<'_>,
+    zksync_chain_id: L2ChainId,
+    configured_genesis_root: Option<H256>,
+) -> anyhow::Result<L1BatchWithMetadata> {
+    let genesis_batch = storage
+        .blocks_dal()
+        .get_l1_batch_metadata(L1BatchNumber(0))
+        .await
+        .context("failed loading genesis L1 batch")?
+        .context(
+            "genesis L1 batch with metadata is not present in Postgres; was genesis performed?",
+        )?;
+
+    let chain_id_key = get_system_context_key(SYSTEM_CONTEXT_CHAIN_ID_POSITION);
+    let genesis_chain_id = storage
+        .storage_web3_dal()
+        .get_historical_value_unchecked(&chain_id_key, MiniblockNumber(0))
+        .await
+        .context("failed loading chain ID from genesis state")?;
+    let genesis_chain_id = h256_to_u256(genesis_chain_id);
+    anyhow::ensure!(
+        genesis_chain_id == U256::from(zksync_chain_id.as_u64()),
+        "Configured L2 chain ID {} differs from chain ID {genesis_chain_id} used in genesis persisted in Postgres",
+        zksync_chain_id.as_u64()
+    );
+
+    let genesis_root = genesis_batch.metadata.root_hash;
+    if let Some(configured_genesis_root) = configured_genesis_root {
+        anyhow::ensure!(
+            configured_genesis_root == genesis_root,
+            "Configured genesis root {configured_genesis_root:?} differs from the root {genesis_root:?} \
+             of the genesis L1 batch persisted in Postgres"
+        );
+    }
+    Ok(genesis_batch)
+}
+
+fn check_l1_genesis_batch_hash(
+    genesis_batch: &L1BatchWithMetadata,
+    l1_genesis_batch_hash: H256,
+) -> anyhow::Result<()> {
+    let genesis_root = genesis_batch.metadata.root_hash;
+    let genesis_batch_hash = stored_batch_hash(genesis_batch);
+    anyhow::ensure!(
+        l1_genesis_batch_hash == genesis_batch_hash,
+        "Genesis L1 batch hash {l1_genesis_batch_hash:?} stored by the diamond proxy differs from the hash \
+         {genesis_batch_hash:?} of the genesis L1 batch persisted in Postgres (root hash: {genesis_root:?}, \
+         commitment: {:?}); check the diamond proxy address, L2 chain ID and genesis parameters",
+        genesis_batch.metadata.commitment
+    );
+    Ok(())
+}
+
 // Default account and bootloader are not a regular system contracts
 // they have never been actually deployed anywhere,
 // They are the initial code that is fed into the VM upon its start.
@@ -326,6 +452,7 @@ pub(crate) async fn create_genesis_l1_batch(
         base_system_contracts_hashes: base_system_contracts.hashes(),
         protocol_version: Some(protocol_version),
         virtual_blocks: 0,
+        gas_limit: None,
     };
 
     let mut transaction = storage.start_transaction().await?;
@@ -540,4 +667,77 @@ mod tests {
             .unwrap();
         assert!(!conn.blocks_dal().is_genesis_needed().await.unwrap());
     }
+
+    #[tokio::test]
+    async fn validating_genesis_state() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let chain_id = L2ChainId::from(270);
+        let genesis_root = ensure_genesis_state(&mut conn, chain_id, &GenesisParams::mock())
+            .await
+            .unwrap();
+        let genesis_batch = check_local_genesis_state(&mut conn, chain_id, Some(genesis_root))
+            .await
+            .unwrap();
+        let l1_genesis_batch_hash = stored_batch_hash(&genesis_batch);
+        check_l1_genesis_batch_hash(&genesis_batch, l1_genesis_batch_hash).unwrap();
+        check_local_genesis_state(&mut conn, chain_id, None)
+            .await
+            .unwrap();
+
+        let err = check_local_genesis_state(&mut conn, L2ChainId::from(271), Some(genesis_root))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("chain ID"), "{err}");
+
+        let err = check_local_genesis_state(&mut conn, chain_id, Some(H256::repeat_byte(1)))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("genesis root"), "{err}");
+
+        let err = check_l1_genesis_batch_hash(&genesis_batch, H256::repeat_byte(1))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("diamond proxy"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn caching_genesis_validation() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let diamond_proxy_addr = Address::repeat_byte(1);
+        let genesis_batch_hash = H256::repeat_byte(2);
+        let is_validated = conn
+            .blocks_dal()
+            .is_genesis_batch_validated(diamond_proxy_addr, genesis_batch_hash)
+            .await
+            .unwrap();
+        assert!(!is_validated);
+
+        conn.blocks_dal()
+            .mark_genesis_batch_validated(diamond_proxy_addr, genesis_batch_hash)
+            .await
+            .unwrap();
+        let is_validated = conn
+            .blocks_dal()
+            .is_genesis_batch_validated(diamond_proxy_addr, genesis_batch_hash)
+            .await
+            .unwrap();
+        assert!(is_validated);
+        // The cached validation must not apply to another genesis batch or diamond proxy.
+        let is_validated = conn
+            .blocks_dal()
+            .is_genesis_batch_validated(diamond_proxy_addr, H256::repeat_byte(3))
+            .await
+            .unwrap();
+        assert!(!is_validated);
+        let is_validated = conn
+            .blocks_dal()
+            .is_genesis_batch_validated(Address::repeat_byte(3), genesis_batch_hash)
+            .await
+            .unwrap();
+        assert!(!is_validated);
+    }
 }
@@ -0,0 +1,277 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::Utc;
+use zksync_config::configs::house_keeper::HouseKeeperConfig;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{ObjectStore, RetentionPolicy};
+use zksync_prover_interface::{inputs::PrepareBasicCircuitsJob, outputs::L1BatchProofForL1};
+use zksync_types::{witness_block_state::WitnessBlockState, L1BatchNumber};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Maximum number of L1 batches processed for a single policy during one iteration.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 100;
+
+/// Removes object store artifacts (witness inputs, proofs etc.) for L1 batches that were executed on L1 longer ago
+/// than the retention period of the corresponding [`RetentionPolicy`]. Progress is tracked in Postgres per policy,
+/// so each batch is processed once.
+///
+/// In the dry-run mode, the manager only reports artifacts that would be removed and doesn't persist progress.
+#[derive(Debug)]
+pub struct ArtifactsRetentionManager {
+    pool: ConnectionPool,
+    object_store: Arc<dyn ObjectStore>,
+    policies: Vec<RetentionPolicy>,
+    dry_run: bool,
+    retention_interval_ms: u64,
+}
+
+impl ArtifactsRetentionManager {
+    pub fn new(
+        pool: ConnectionPool,
+        object_store: Arc<dyn ObjectStore>,
+        policies: Vec<RetentionPolicy>,
+        dry_run: bool,
+        retention_interval_ms: u64,
+    ) -> Self {
+        Self {
+            pool,
+            object_store,
+            policies,
+            dry_run,
+            retention_interval_ms,
+        }
+    }
+
+    /// Returns retention policies enabled in the provided config.
+    pub fn policies(config: &HouseKeeperConfig) -> Vec<RetentionPolicy> {
+        const SECONDS_IN_DAY: u64 = 86_400;
+
+        let mut policies = vec![];
+        if let Some(days) = config.witness_inputs_retention_days {
+            let retention = Duration::from_secs(u64::from(days) * SECONDS_IN_DAY);
+            policies.push(RetentionPolicy::new::<WitnessBlockState>(
+                "witness_inputs",
+                retention,
+            ));
+            policies.push(RetentionPolicy::new::<PrepareBasicCircuitsJob>(
+                "merkle_paths",
+                retention,
+            ));
+        }
+        if let Some(days) = config.proofs_retention_days {
+            let retention = Duration::from_secs(u64::from(days) * SECONDS_IN_DAY);
+            policies.push(RetentionPolicy::new::<L1BatchProofForL1>(
+                "proofs", retention,
+            ));
+        }
+        policies
+    }
+
+    /// Returns the range of L1 batches for which artifacts covered by `policy` have expired, but are not removed yet.
+    async fn expired_l1_batches(
+        &self,
+        policy: &RetentionPolicy,
+    ) -> anyhow::Result<Option<(L1BatchNumber, L1BatchNumber)>> {
+        let retention = chrono::Duration::from_std(policy.retention())
+            .context("retention period is too large")?;
+        let executed_before = (Utc::now() - retention).naive_utc();
+
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await?;
+        let Some(last_expired) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_before(executed_before)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let last_removed = storage
+            .object_store_retention_dal()
+            .get_last_removed_l1_batch(policy.name())
+            .await?;
+        // The genesis L1 batch has no artifacts.
+        let first_expired = last_removed.map_or(L1BatchNumber(1), |number| number + 1);
+        Ok((first_expired <= last_expired).then_some((first_expired, last_expired)))
+    }
+
+    async fn apply_policy(&self, policy: &RetentionPolicy) -> anyhow::Result<()> {
+        let Some((first_expired, last_expired)) = self.expired_l1_batches(policy).await? else {
+            return Ok(());
+        };
+
+        if self.dry_run {
+            let count = last_expired.0 - first_expired.0 + 1;
+            tracing::info!(
+                "[dry run] Artifacts for {count} L1 batches would be removed according to policy `{name}` \
+                 (bucket `{bucket}`): {first_key} ..= {last_key}",
+                name = policy.name(),
+                bucket = policy.bucket(),
+                first_key = policy.key(first_expired),
+                last_key = policy.key(last_expired)
+            );
+            metrics::gauge!(
+                "server.house_keeper.expired_artifacts_pending",
+                f64::from(count),
+                "policy" => policy.name()
+            );
+            return Ok(());
+        }
+
+        let last_to_remove = L1BatchNumber(
+            last_expired
+                .0
+                .min(first_expired.0 + MAX_L1_BATCHES_PER_ITERATION - 1),
+        );
+        let mut removed_count = 0_u64;
+        for number in first_expired.0..=last_to_remove.0 {
+            let removed = self
+                .object_store
+                .remove_expired(policy, L1BatchNumber(number))
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed removing `{}` for L1 batch #{number}",
+                        policy.key(L1BatchNumber(number))
+                    )
+                })?;
+            removed_count += u64::from(removed);
+        }
+
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await?;
+        storage
+            .object_store_retention_dal()
+            .set_last_removed_l1_batch(policy.name(), last_to_remove)
+            .await?;
+        tracing::info!(
+            "Removed {removed_count} expired artifacts for L1 batches #{first_expired}..=#{last_to_remove} \
+             according to policy `{}`",
+            policy.name()
+        );
+        metrics::counter!(
+            "server.house_keeper.expired_artifacts_removed",
+            removed_count,
+            "policy" => policy.name()
+        );
+        metrics::gauge!(
+            "server.house_keeper.expired_artifacts_pending",
+            f64::from(last_expired.0 - last_to_remove.0),
+            "policy" => policy.name()
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ArtifactsRetentionManager {
+    const SERVICE_NAME: &'static str = "ArtifactsRetentionManager";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        for policy in &self.policies {
+            self.apply_policy(policy)
+                .await
+                .with_context(|| format!("failed applying retention policy `{}`", policy.name()))?;
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.retention_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::StorageProcessor;
+    use zksync_object_store::{ObjectStoreError, ObjectStoreFactory};
+    use zksync_types::{aggregated_operations::AggregatedActionType, ProtocolVersion, H256};
+
+    use super::*;
+    use crate::utils::testonly::create_l1_batch;
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    async fn prepare_executed_l1_batches(storage: &mut StorageProcessor<'_>, days_ago: &[i64]) {
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let now = Utc::now();
+        for (i, &days_ago) in days_ago.iter().enumerate() {
+            let number = i as u32 + 1;
+            storage
+                .blocks_dal()
+                .insert_mock_l1_batch(&create_l1_batch(number))
+                .await
+                .unwrap();
+            storage
+                .eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(number),
+                    AggregatedActionType::Execute,
+                    H256::from_low_u64_be(number.into()),
+                    now - chrono::Duration::days(days_ago),
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn existing_l1_batches(object_store: &dyn ObjectStore, count: u32) -> Vec<u32> {
+        let mut existing = vec![];
+        for number in 1..=count {
+            match object_store
+                .get::<WitnessBlockState>(L1BatchNumber(number))
+                .await
+            {
+                Ok(_) => existing.push(number),
+                Err(ObjectStoreError::KeyNotFound(_)) => { /* do nothing */ }
+                Err(err) => panic!("{err}"),
+            }
+        }
+        existing
+    }
+
+    #[tokio::test]
+    async fn removing_expired_artifacts() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        prepare_executed_l1_batches(&mut storage, &[10, 8, 2, 1]).await;
+        drop(storage);
+
+        let object_store = ObjectStoreFactory::mock().create_store().await;
+        for number in 1..=4 {
+            object_store
+                .put(L1BatchNumber(number), &WitnessBlockState::default())
+                .await
+                .unwrap();
+        }
+        let policy = RetentionPolicy::new::<WitnessBlockState>("witness_inputs", DAY * 5);
+
+        let mut manager = ArtifactsRetentionManager::new(
+            pool.clone(),
+            object_store.clone(),
+            vec![policy.clone()],
+            true,
+            1_000,
+        );
+        manager.run_routine_task().await.unwrap();
+        assert_eq!(existing_l1_batches(&*object_store, 4).await, [1, 2, 3, 4]);
+
+        manager.dry_run = false;
+        manager.run_routine_task().await.unwrap();
+        assert_eq!(existing_l1_batches(&*object_store, 4).await, [3, 4]);
+        let mut storage = pool.access_storage().await.unwrap();
+        let last_removed = storage
+            .object_store_retention_dal()
+            .get_last_removed_l1_batch("witness_inputs")
+            .await
+            .unwrap();
+        assert_eq!(last_removed, Some(L1BatchNumber(2)));
+
+        // Repeated runs shouldn't touch the remaining artifacts.
+        manager.run_routine_task().await.unwrap();
+        assert_eq!(existing_l1_batches(&*object_store, 4).await, [3, 4]);
+    }
+}
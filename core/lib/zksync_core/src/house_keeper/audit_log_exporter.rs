@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{Bucket, ObjectStore};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Name of the export destination used to track progress in Postgres.
+const DESTINATION: &str = "object_store";
+/// Maximum number of audit log entries in a single exported object.
+const MAX_ENTRIES_PER_OBJECT: usize = 1_000;
+
+/// Exports new audit log entries to the object store as JSON Lines objects, so that the log can be retained
+/// outside of the node database (e.g., in a write-once bucket). Progress is tracked in Postgres, so each entry
+/// is exported once.
+#[derive(Debug)]
+pub struct AuditLogExporter {
+    pool: ConnectionPool,
+    object_store: Arc<dyn ObjectStore>,
+    export_interval_ms: u64,
+}
+
+impl AuditLogExporter {
+    pub fn new(
+        pool: ConnectionPool,
+        object_store: Arc<dyn ObjectStore>,
+        export_interval_ms: u64,
+    ) -> Self {
+        Self {
+            pool,
+            object_store,
+            export_interval_ms,
+        }
+    }
+
+    fn object_key(first_id: u64, last_id: u64) -> String {
+        // Zero-padded IDs make lexicographic and numeric ordering of keys coincide.
+        format!("audit_log_{first_id:020}_{last_id:020}.jsonl")
+    }
+
+    /// Exports a single batch of entries. Returns the number of exported entries.
+    async fn export_batch(&self) -> anyhow::Result<usize> {
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await?;
+        let last_exported_id = storage
+            .audit_log_dal()
+            .get_last_exported_id(DESTINATION)
+            .await?
+            .unwrap_or(0);
+        let entries = storage
+            .audit_log_dal()
+            .get_entries(last_exported_id, MAX_ENTRIES_PER_OBJECT)
+            .await?;
+        drop(storage);
+        let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+            return Ok(0);
+        };
+        let (first_id, last_id) = (first.id, last.id);
+
+        let mut object = vec![];
+        for entry in &entries {
+            serde_json::to_writer(&mut object, entry).context("failed serializing entry")?;
+            object.push(b'\n');
+        }
+        let key = Self::object_key(first_id, last_id);
+        self.object_store
+            .put_raw(Bucket::AuditLog, &key, object)
+            .await
+            .with_context(|| format!("failed exporting audit log entries to `{key}`"))?;
+
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await?;
+        storage
+            .audit_log_dal()
+            .set_last_exported_id(DESTINATION, last_id)
+            .await?;
+        tracing::info!("Exported audit log entries #{first_id}..=#{last_id} to `{key}`");
+        Ok(entries.len())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for AuditLogExporter {
+    const SERVICE_NAME: &'static str = "AuditLogExporter";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        while self.export_batch().await? == MAX_ENTRIES_PER_OBJECT {
+            // Continue exporting until the exporter catches up with the log.
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.export_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::audit_log::{AuditAction, AuditLogEntry};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn exporting_audit_log() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let mut ids = vec![];
+        for i in 0..3 {
+            let id = storage
+                .audit_log_dal()
+                .insert_entry(AuditAction::BlockRevert, "alice", &json!({ "i": i }))
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+        drop(storage);
+
+        let object_store = ObjectStoreFactory::mock().create_store().await;
+        let mut exporter = AuditLogExporter::new(pool.clone(), object_store.clone(), 1_000);
+        exporter.run_routine_task().await.unwrap();
+
+        let key = AuditLogExporter::object_key(ids[0], ids[2]);
+        let object = object_store.get_raw(Bucket::AuditLog, &key).await.unwrap();
+        let entries: Vec<AuditLogEntry> = String::from_utf8(object)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].params, json!({ "i": 2 }));
+
+        // Only new entries should be exported on the next run.
+        let mut storage = pool.access_storage().await.unwrap();
+        let new_id = storage
+            .audit_log_dal()
+            .insert_entry(AuditAction::ConfigReload, "0x01", &json!({}))
+            .await
+            .unwrap();
+        drop(storage);
+        exporter.run_routine_task().await.unwrap();
+        let key = AuditLogExporter::object_key(new_id, new_id);
+        let object = object_store.get_raw(Bucket::AuditLog, &key).await.unwrap();
+        assert_eq!(String::from_utf8(object).unwrap().lines().count(), 1);
+    }
+}
@@ -0,0 +1,291 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::Utc;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::ObjectStore;
+use zksync_types::L1BatchNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Maximum number of L1 batches archived during one iteration.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 100;
+
+/// Moves call traces and events for L1 batches executed on L1 longer than the threshold ago from Postgres
+/// to the object store. Each L1 batch is stored as a single compressed archive; the API falls back to loading
+/// these archives if the requested data is not in Postgres.
+///
+/// An archive is uploaded before the data is removed from Postgres, so the archiver can be safely interrupted
+/// at any point; the batch is re-archived on the next run in this case.
+#[derive(Debug)]
+pub struct ColdStorageArchiver {
+    pool: ConnectionPool,
+    object_store: Arc<dyn ObjectStore>,
+    threshold: Duration,
+    archiving_interval_ms: u64,
+}
+
+impl ColdStorageArchiver {
+    pub fn new(
+        pool: ConnectionPool,
+        object_store: Arc<dyn ObjectStore>,
+        threshold: Duration,
+        archiving_interval_ms: u64,
+    ) -> Self {
+        Self {
+            pool,
+            object_store,
+            threshold,
+            archiving_interval_ms,
+        }
+    }
+
+    /// Returns the range of L1 batches that should be archived, but aren't yet.
+    async fn l1_batches_to_archive(
+        &self,
+    ) -> anyhow::Result<Option<(L1BatchNumber, L1BatchNumber)>> {
+        let threshold =
+            chrono::Duration::from_std(self.threshold).context("threshold is too large")?;
+        let executed_before = (Utc::now() - threshold).naive_utc();
+
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await?;
+        let Some(last_expired) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_before(executed_before)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let last_archived = storage
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await?;
+        let first_expired = last_archived.map_or(L1BatchNumber(0), |number| number + 1);
+        Ok((first_expired <= last_expired).then_some((first_expired, last_expired)))
+    }
+
+    async fn archive_l1_batch(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await?;
+        let archive = storage
+            .cold_storage_dal()
+            .get_l1_batch_archive(l1_batch_number)
+            .await?;
+        drop(storage);
+
+        let key = self
+            .object_store
+            .put(l1_batch_number, &archive)
+            .await
+            .context("failed uploading archive")?;
+
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await?;
+        storage
+            .cold_storage_dal()
+            .mark_l1_batch_archived(&archive, &key)
+            .await?;
+        tracing::debug!(
+            "Moved {} call traces and {} events for L1 batch #{l1_batch_number} to `{key}`",
+            archive.call_traces.len(),
+            archive.events.len()
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ColdStorageArchiver {
+    const SERVICE_NAME: &'static str = "ColdStorageArchiver";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let Some((first_l1_batch, last_l1_batch)) = self.l1_batches_to_archive().await? else {
+            return Ok(());
+        };
+        let last_to_archive = L1BatchNumber(
+            last_l1_batch
+                .0
+                .min(first_l1_batch.0 + MAX_L1_BATCHES_PER_ITERATION - 1),
+        );
+        for number in first_l1_batch.0..=last_to_archive.0 {
+            self.archive_l1_batch(L1BatchNumber(number))
+                .await
+                .with_context(|| format!("failed archiving L1 batch #{number}"))?;
+        }
+        tracing::info!(
+            "Moved call traces and events for L1 batches #{first_l1_batch}..=#{last_to_archive} to the object store"
+        );
+        metrics::gauge!(
+            "server.house_keeper.cold_storage_pending_l1_batches",
+            f64::from(last_l1_batch.0 - last_to_archive.0)
+        );
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.archiving_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use zksync_dal::StorageProcessor;
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType, cold_storage::L1BatchColdArchive,
+        tx::IncludedTxLocation, vm_trace::Call, Address, MiniblockNumber, ProtocolVersion, VmEvent,
+        H256,
+    };
+
+    use super::*;
+    use crate::utils::testonly::{
+        create_l1_batch, create_l2_transaction, create_miniblock, execute_l2_transaction,
+    };
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    /// Creates L1 batches each containing a single miniblock with a single transaction emitting an event.
+    async fn prepare_executed_l1_batches(
+        storage: &mut StorageProcessor<'_>,
+        days_ago: &[i64],
+    ) -> Vec<H256> {
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let now = Utc::now();
+        let mut tx_hashes = vec![];
+        for (i, &days_ago) in days_ago.iter().enumerate() {
+            let number = i as u32 + 1;
+            storage
+                .blocks_dal()
+                .insert_miniblock(&create_miniblock(number))
+                .await
+                .unwrap();
+            let tx = create_l2_transaction(10, 100);
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(tx.clone(), Default::default())
+                .await;
+            let mut tx_result = execute_l2_transaction(tx);
+            tx_result.call_traces.push(Call {
+                to: Address::repeat_byte(number as u8),
+                ..Call::default()
+            });
+            storage
+                .transactions_dal()
+                .mark_txs_as_executed_in_miniblock(
+                    MiniblockNumber(number),
+                    slice::from_ref(&tx_result),
+                    1.into(),
+                )
+                .await;
+            let location = IncludedTxLocation {
+                tx_hash: tx_result.hash,
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::default(),
+            };
+            let event = VmEvent {
+                location: (L1BatchNumber(number), 0),
+                address: Address::repeat_byte(number as u8),
+                indexed_topics: vec![H256::repeat_byte(1)],
+                value: vec![],
+            };
+            storage
+                .events_dal()
+                .save_events(MiniblockNumber(number), &[(location, vec![&event])])
+                .await;
+            tx_hashes.push(tx_result.hash);
+
+            storage
+                .blocks_dal()
+                .insert_mock_l1_batch(&create_l1_batch(number))
+                .await
+                .unwrap();
+            storage
+                .blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+                .await
+                .unwrap();
+            storage
+                .eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(number),
+                    AggregatedActionType::Execute,
+                    H256::from_low_u64_be(number.into()),
+                    now - chrono::Duration::days(days_ago),
+                )
+                .await
+                .unwrap();
+        }
+        tx_hashes
+    }
+
+    #[tokio::test]
+    async fn archiving_call_traces_and_events() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let tx_hashes = prepare_executed_l1_batches(&mut storage, &[10, 8, 1]).await;
+        drop(storage);
+
+        let object_store = ObjectStoreFactory::mock().create_store().await;
+        let mut archiver =
+            ColdStorageArchiver::new(pool.clone(), object_store.clone(), DAY * 5, 1_000);
+        archiver.run_routine_task().await.unwrap();
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let last_archived = storage
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await
+            .unwrap();
+        assert_eq!(last_archived, Some(L1BatchNumber(2)));
+
+        for (i, tx_hash) in tx_hashes[..2].iter().enumerate() {
+            let number = L1BatchNumber(i as u32 + 1);
+            let archive: L1BatchColdArchive = object_store.get(number).await.unwrap();
+            assert_eq!(archive.l1_batch_number, number);
+            let trace = archive.trace_for_tx(*tx_hash).unwrap();
+            assert_eq!(trace.to, Address::repeat_byte(number.0 as u8));
+            assert_eq!(archive.events.len(), 1);
+            assert_eq!(
+                archive.events[0].address,
+                Address::repeat_byte(number.0 as u8)
+            );
+
+            let trace = storage
+                .transactions_dal()
+                .get_call_trace(*tx_hash)
+                .await
+                .unwrap();
+            assert!(trace.is_none());
+        }
+
+        // Data for the last L1 batch should be retained in Postgres.
+        let trace = storage
+            .transactions_dal()
+            .get_call_trace(tx_hashes[2])
+            .await
+            .unwrap();
+        assert!(trace.is_some());
+        let logs = storage
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_number, Some(3.into()));
+        drop(storage);
+
+        // Repeated runs should be no-ops.
+        archiver.run_routine_task().await.unwrap();
+        let mut storage = pool.access_storage().await.unwrap();
+        let last_archived = storage
+            .cold_storage_dal()
+            .get_last_archived_l1_batch()
+            .await
+            .unwrap();
+        assert_eq!(last_archived, Some(L1BatchNumber(2)));
+    }
+}
@@ -1,4 +1,7 @@
+pub mod artifacts_retention_manager;
+pub mod audit_log_exporter;
 pub mod blocks_state_reporter;
+pub mod cold_storage_archiver;
 pub mod fri_proof_compressor_job_retry_manager;
 pub mod fri_proof_compressor_queue_monitor;
 pub mod fri_prover_job_retry_manager;
@@ -7,4 +10,5 @@ pub mod fri_scheduler_circuit_queuer;
 pub mod fri_witness_generator_jobs_retry_manager;
 pub mod fri_witness_generator_queue_monitor;
 pub mod periodic_job;
+pub mod prover_db_consistency_checker;
 pub mod waiting_to_queued_fri_witness_job_mover;
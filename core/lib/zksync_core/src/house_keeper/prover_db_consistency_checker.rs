@@ -0,0 +1,221 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Maximum number of discrepancies of each kind reported during one iteration.
+const MAX_REPORTED_DISCREPANCIES: usize = 100;
+
+/// Discrepancy between the prover DB and the core DB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsistencyIssue {
+    /// The prover DB has a finished proof for an L1 batch that is not known to the core DB.
+    ProvenButUnknown(L1BatchNumber),
+    /// The L1 batch is executed on L1, but the core DB has no record of its proof being generated.
+    ExecutedWithoutProof(L1BatchNumber),
+}
+
+impl ConsistencyIssue {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::ProvenButUnknown(_) => "proven_but_unknown",
+            Self::ExecutedWithoutProof(_) => "executed_without_proof",
+        }
+    }
+
+    /// Returns a human-readable suggestion on how the issue can be repaired.
+    pub fn repair_suggestion(&self) -> String {
+        match self {
+            Self::ProvenButUnknown(number) => format!(
+                "L1 batch #{number} is proven in the prover DB, but is not sealed in the core DB; \
+                 the prover DB was likely not reverted together with the core DB. Remove prover jobs \
+                 for L1 batches starting from #{number}"
+            ),
+            Self::ExecutedWithoutProof(number) => format!(
+                "L1 batch #{number} is executed on L1, but its proof is not marked as generated in `proof_generation_details`; \
+                 check proof compression job for this batch in the prover DB and mark the proof as generated (or skipped) \
+                 in the core DB"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ConsistencyIssue {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.repair_suggestion())
+    }
+}
+
+/// Periodically cross-validates job states in the prover DB against L1 batch states in the core DB.
+/// Discrepancies are reported via metrics and logged together with repair suggestions; the checker never
+/// modifies either of the databases.
+#[derive(Debug)]
+pub struct ProverDbConsistencyChecker {
+    pool: ConnectionPool,
+    prover_pool: ConnectionPool,
+    check_interval_ms: u64,
+}
+
+impl ProverDbConsistencyChecker {
+    pub fn new(pool: ConnectionPool, prover_pool: ConnectionPool, check_interval_ms: u64) -> Self {
+        Self {
+            pool,
+            prover_pool,
+            check_interval_ms,
+        }
+    }
+
+    /// Returns the list of detected discrepancies. Each kind of discrepancies is capped
+    /// at [`MAX_REPORTED_DISCREPANCIES`].
+    pub async fn check(&self) -> anyhow::Result<Vec<ConsistencyIssue>> {
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await?;
+        let Some(last_sealed_l1_batch) = storage.blocks_dal().get_sealed_l1_batch_number().await?
+        else {
+            // The core DB is not initialized yet; nothing to compare against.
+            return Ok(vec![]);
+        };
+        let executed_without_proof = storage
+            .proof_generation_dal()
+            .get_executed_l1_batches_without_proof(MAX_REPORTED_DISCREPANCIES)
+            .await?;
+        drop(storage);
+
+        let mut prover_storage = self
+            .prover_pool
+            .access_storage_tagged("house_keeper")
+            .await?;
+        let proven_but_unknown = prover_storage
+            .fri_proof_compressor_dal()
+            .get_proven_l1_batches_after(last_sealed_l1_batch, MAX_REPORTED_DISCREPANCIES)
+            .await?;
+        drop(prover_storage);
+
+        let issues = proven_but_unknown
+            .into_iter()
+            .map(ConsistencyIssue::ProvenButUnknown)
+            .chain(
+                executed_without_proof
+                    .into_iter()
+                    .map(ConsistencyIssue::ExecutedWithoutProof),
+            );
+        Ok(issues.collect())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ProverDbConsistencyChecker {
+    const SERVICE_NAME: &'static str = "ProverDbConsistencyChecker";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let issues = self.check().await?;
+
+        for kind in ["proven_but_unknown", "executed_without_proof"] {
+            let count = issues.iter().filter(|issue| issue.kind() == kind).count();
+            metrics::gauge!(
+                "server.house_keeper.prover_db_inconsistencies",
+                count as f64,
+                "kind" => kind
+            );
+        }
+        if issues.is_empty() {
+            tracing::debug!("Prover DB is consistent with the core DB");
+        } else {
+            tracing::warn!(
+                "Found {} inconsistencies between the prover DB and the core DB",
+                issues.len()
+            );
+            for issue in &issues {
+                tracing::warn!("{issue}");
+            }
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.check_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use zksync_types::{aggregated_operations::AggregatedActionType, ProtocolVersion, H256};
+
+    use super::*;
+    use crate::utils::testonly::create_l1_batch;
+
+    #[tokio::test]
+    async fn detecting_inconsistencies() {
+        // Core and prover tables share the schema, so a single pool is used for both DBs.
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=3 {
+            storage
+                .blocks_dal()
+                .insert_mock_l1_batch(&create_l1_batch(number))
+                .await
+                .unwrap();
+            storage
+                .proof_generation_dal()
+                .insert_proof_generation_details(L1BatchNumber(number), "data")
+                .await;
+            storage
+                .fri_proof_compressor_dal()
+                .insert_proof_compression_job(L1BatchNumber(number), "fri_proof")
+                .await;
+        }
+
+        let checker = ProverDbConsistencyChecker::new(pool.clone(), pool.clone(), 1_000);
+        assert_eq!(checker.check().await.unwrap(), []);
+
+        // Batch #1 is executed and properly proven, batch #2 is executed without a proof.
+        storage
+            .proof_generation_dal()
+            .save_proof_artifacts_metadata(L1BatchNumber(1), "proof")
+            .await
+            .unwrap();
+        for number in 1..=2 {
+            storage
+                .eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(number),
+                    AggregatedActionType::Execute,
+                    H256::from_low_u64_be(number.into()),
+                    Utc::now(),
+                )
+                .await
+                .unwrap();
+        }
+        // Batch #5 is proven in the prover DB, but is unknown to the core DB.
+        storage
+            .fri_proof_compressor_dal()
+            .insert_proof_compression_job(L1BatchNumber(5), "fri_proof")
+            .await;
+        storage
+            .fri_proof_compressor_dal()
+            .mark_proof_compression_job_successful(
+                L1BatchNumber(5),
+                std::time::Duration::from_secs(1),
+                "l1_proof",
+            )
+            .await;
+        drop(storage);
+
+        let issues = checker.check().await.unwrap();
+        assert_eq!(
+            issues,
+            [
+                ConsistencyIssue::ProvenButUnknown(L1BatchNumber(5)),
+                ConsistencyIssue::ExecutedWithoutProof(L1BatchNumber(2)),
+            ]
+        );
+        assert!(issues[0].repair_suggestion().contains("#5"));
+    }
+}
@@ -0,0 +1,144 @@
+//! Detection of blob parameters of the L1 network.
+
+use zksync_types::U256;
+
+/// Blob gas consumed by a single blob (EIP-4844).
+pub(super) const GAS_PER_BLOB: u64 = 1 << 17;
+
+/// Blob parameters of an L1 hard fork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobSchedule {
+    pub name: &'static str,
+    pub target_blobs_per_block: u64,
+    pub max_blobs_per_block: u64,
+    pub base_fee_update_fraction: u64,
+}
+
+impl BlobSchedule {
+    /// Schedule introduced in the Dencun hard fork (EIP-4844).
+    pub const CANCUN: Self = Self {
+        name: "cancun",
+        target_blobs_per_block: 3,
+        max_blobs_per_block: 6,
+        base_fee_update_fraction: 3_338_477,
+    };
+
+    /// Known schedules ordered by activation. Schedules only ever increase blob throughput.
+    const KNOWN: [Self; 4] = [
+        Self::CANCUN,
+        // EIP-7691
+        Self {
+            name: "prague",
+            target_blobs_per_block: 6,
+            max_blobs_per_block: 9,
+            base_fee_update_fraction: 5_007_716,
+        },
+        // Blob-parameter-only forks (EIP-7892)
+        Self {
+            name: "bpo1",
+            target_blobs_per_block: 10,
+            max_blobs_per_block: 15,
+            base_fee_update_fraction: 8_346_193,
+        },
+        Self {
+            name: "bpo2",
+            target_blobs_per_block: 14,
+            max_blobs_per_block: 21,
+            base_fee_update_fraction: 11_684_671,
+        },
+    ];
+
+    /// Detects the schedule from two consecutive L1 blocks using the excess blob gas update rule:
+    /// `excess_blob_gas(next) = excess_blob_gas(prev) + blob_gas_used(prev) - target_blob_gas`.
+    ///
+    /// Returns `None` if the target cannot be inferred (the excess blob gas is clamped at zero), or if it
+    /// doesn't correspond to a known schedule. Since EIP-7918 the inferred target may be lower than the real one
+    /// when the blob base fee is low, so callers should only use detected schedules to upgrade the current one.
+    pub(super) fn detect(prev: BlobGasSample, next_excess_blob_gas: u64) -> Option<Self> {
+        if next_excess_blob_gas == 0 {
+            return None;
+        }
+        let target_blob_gas =
+            (prev.excess_blob_gas + prev.blob_gas_used).checked_sub(next_excess_blob_gas)?;
+        Self::KNOWN
+            .into_iter()
+            .find(|schedule| schedule.target_blobs_per_block * GAS_PER_BLOB == target_blob_gas)
+    }
+
+    /// Calculates `blob_base_fee` given `excess_blob_gas`.
+    pub fn blob_base_fee(&self, excess_blob_gas: u64) -> U256 {
+        // Constants and formula are taken from EIP4844 specification.
+        const MIN_BLOB_BASE_FEE: u32 = 1;
+
+        fake_exponential(
+            MIN_BLOB_BASE_FEE.into(),
+            excess_blob_gas.into(),
+            self.base_fee_update_fraction.into(),
+        )
+    }
+}
+
+/// Blob gas values from an L1 block header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct BlobGasSample {
+    pub excess_blob_gas: u64,
+    pub blob_gas_used: u64,
+}
+
+/// approximates `factor * e ** (numerator / denominator)` using Taylor expansion.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let mut i = 1_u32;
+    let mut output = U256::zero();
+    let mut accum = factor * denominator;
+    while !accum.is_zero() {
+        output += accum;
+
+        accum *= numerator;
+        accum /= denominator;
+        accum /= U256::from(i);
+
+        i += 1;
+    }
+
+    output / denominator
+}
+
+/// Tracks the blob schedule of the L1 network based on the observed block headers.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct BlobScheduleTracker {
+    pub schedule: BlobSchedule,
+    last_sample: Option<BlobGasSample>,
+}
+
+impl Default for BlobScheduleTracker {
+    fn default() -> Self {
+        Self {
+            schedule: BlobSchedule::CANCUN,
+            last_sample: None,
+        }
+    }
+}
+
+impl BlobScheduleTracker {
+    /// Observes blob gas values of the next L1 block. Returns the new schedule if it was upgraded.
+    pub fn observe(
+        &mut self,
+        excess_blob_gas: u64,
+        blob_gas_used: Option<u64>,
+    ) -> Option<BlobSchedule> {
+        let detected = self
+            .last_sample
+            .and_then(|prev| BlobSchedule::detect(prev, excess_blob_gas));
+        self.last_sample = blob_gas_used.map(|blob_gas_used| BlobGasSample {
+            excess_blob_gas,
+            blob_gas_used,
+        });
+
+        let detected = detected?;
+        if detected.target_blobs_per_block <= self.schedule.target_blobs_per_block {
+            return None;
+        }
+        self.schedule = detected;
+        Some(detected)
+    }
+}
@@ -0,0 +1,56 @@
+//! Detection of stale L1 data used by the gas adjuster.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Reason why L1 data is considered stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum StalenessReason {
+    /// No new L1 blocks were observed for too long.
+    NoNewBlocks,
+    /// The latest block returned by the L1 provider is too old, i.e. the provider lags behind L1.
+    ProviderLag,
+}
+
+/// Tracks when the gas adjuster last observed new L1 blocks.
+#[derive(Debug)]
+pub(super) struct L1Liveness {
+    max_staleness: Duration,
+    last_new_block_at: Instant,
+    /// Timestamp (in seconds since UNIX epoch) of the latest observed L1 block, if known.
+    last_block_timestamp: Option<u64>,
+}
+
+impl L1Liveness {
+    pub fn new(max_staleness: Duration, now: Instant, last_block_timestamp: Option<u64>) -> Self {
+        Self {
+            max_staleness,
+            last_new_block_at: now,
+            last_block_timestamp,
+        }
+    }
+
+    pub fn observe_new_blocks(&mut self, now: Instant, last_block_timestamp: Option<u64>) {
+        self.last_new_block_at = now;
+        if last_block_timestamp.is_some() {
+            self.last_block_timestamp = last_block_timestamp;
+        }
+    }
+
+    /// Returns the staleness reason together with the age of L1 data if the data is stale.
+    pub fn staleness(
+        &self,
+        now: Instant,
+        now_timestamp: u64,
+    ) -> Option<(StalenessReason, Duration)> {
+        let since_new_block = now.saturating_duration_since(self.last_new_block_at);
+        if since_new_block > self.max_staleness {
+            return Some((StalenessReason::NoNewBlocks, since_new_block));
+        }
+        let block_age =
+            Duration::from_secs(now_timestamp.saturating_sub(self.last_block_timestamp?));
+        (block_age > self.max_staleness).then_some((StalenessReason::ProviderLag, block_age))
+    }
+}
@@ -10,6 +10,9 @@ pub(super) struct GasAdjusterMetrics {
     pub median_base_fee_per_gas: Gauge<u64>,
     pub median_blob_base_fee_per_gas: Gauge<u64>,
     pub median_blob_base_fee: Gauge<u64>,
+    pub max_blobs_per_block: Gauge<u64>,
+    /// Set to 1 while L1 data is stale and fee inputs are frozen.
+    pub l1_data_stale: Gauge<u64>,
 }
 
 #[vise::register]
@@ -3,26 +3,62 @@
 use std::{
     collections::VecDeque,
     ops::RangeInclusive,
-    sync::{Arc, RwLock},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
 };
 
+use serde::Serialize;
 use tokio::sync::watch;
 use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
-use zksync_eth_client::{Error, EthInterface};
+use zksync_eth_client::{Error, ErrorKind, EthInterface};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{U256, U64};
+use zksync_utils::time::seconds_since_epoch;
 
-use self::metrics::METRICS;
-use super::L1TxParamsProvider;
+pub use self::blob_schedule::BlobSchedule;
+use self::{
+    blob_schedule::BlobScheduleTracker,
+    liveness::{L1Liveness, StalenessReason},
+    metrics::METRICS,
+};
+use super::{L1BlockTimestampProvider, L1TxParamsProvider};
 use crate::state_keeper::metrics::KEEPER_METRICS;
 
+mod blob_schedule;
+mod liveness;
 mod metrics;
 #[cfg(test)]
 mod tests;
 
+/// Fee inputs frozen while L1 data is stale.
+#[derive(Debug, Clone, Copy, Serialize)]
+struct FrozenFeeInputs {
+    effective_gas_price: u64,
+    effective_pubdata_price: u64,
+}
+
+/// Health details reported by the gas adjuster while L1 data is stale.
+#[derive(Debug, Serialize)]
+struct StaleL1DataDetails {
+    reason: StalenessReason,
+    stale_for_sec: u64,
+    frozen_fee_inputs: FrozenFeeInputs,
+}
+
 /// This component keeps track of the median `base_fee` from the last `max_base_fee_samples` blocks
 /// and of the median `blob_base_fee` from the last `max_blob_base_fee_sample` blocks.
 /// It is used to adjust the base_fee of transactions sent to L1.
+///
+/// Unlike other components reading L1 state, the adjuster deliberately ignores the shared L1 finality source
+/// (`ETHClientConfig::finality_mode`) and always samples fees from the latest L1 blocks. Fees aren't L1 state
+/// that can be invalidated by a reorg: a reorged-out block only affects one sample among `max_base_fee_samples`,
+/// and the median tolerates it. Conversely, sampling up to a deep reorg-safe block (e.g., `finalized`, which lags
+/// ~13 minutes behind the head) would make fee estimates lag behind the L1 fee market, leading to underpriced
+/// and stuck L1 transactions. Staleness of L1 data is tracked separately via `max_l1_staleness_sec`.
 #[derive(Debug)]
 pub struct GasAdjuster {
     pub(super) base_fee_statistics: GasStatistics<u64>,
@@ -30,9 +66,19 @@ pub struct GasAdjuster {
     // In practice, it's very unlikely to overflow `u64` (if `blob_base_fee_statistics` = 10 ^ 18, then price for one blob is 2 ^ 17 ETH).
     // But it's still possible and code shouldn't panic if that happens. One more argument is that geth uses big int type for blob prices.
     pub(super) blob_base_fee_statistics: GasStatistics<U256>,
+    /// Blob parameters of L1 detected from block headers. Used to compute blob base fees
+    /// and to limit the number of blobs in flight, so that they adapt to L1 hard forks automatically.
+    blob_schedule: RwLock<BlobScheduleTracker>,
     pub(super) config: GasAdjusterConfig,
     pubdata_sending_mode: PubdataSendingMode,
     eth_client: Arc<dyn EthInterface>,
+    /// Timestamp of the latest observed L1 block; 0 if not known.
+    last_l1_block_timestamp: AtomicU64,
+    /// Liveness of L1 data. `None` if staleness checks are disabled in the config.
+    liveness: Option<RwLock<L1Liveness>>,
+    /// Fee inputs returned instead of the estimated ones while L1 data is stale.
+    frozen_fee_inputs: RwLock<Option<FrozenFeeInputs>>,
+    health_updater: HealthUpdater,
 }
 
 impl GasAdjuster {
@@ -55,8 +101,27 @@ impl GasAdjuster {
 
         // Web3 API doesn't provide a method to fetch blob fees for multiple blocks using single request,
         // so we request blob base fee only for the latest block.
-        let (_, last_block_blob_base_fee) =
-            Self::get_base_fees_history(&eth_client, current_block..=current_block).await?;
+        let mut blob_schedule = BlobScheduleTracker::default();
+        let (_, last_block_blob_base_fee, last_block_timestamp) = Self::get_base_fees_history(
+            &eth_client,
+            current_block..=current_block,
+            &mut blob_schedule,
+        )
+        .await?;
+        METRICS
+            .max_blobs_per_block
+            .set(blob_schedule.schedule.max_blobs_per_block);
+
+        let liveness = config.max_l1_staleness().map(|max_staleness| {
+            RwLock::new(L1Liveness::new(
+                max_staleness,
+                Instant::now(),
+                last_block_timestamp,
+            ))
+        });
+        let health_updater = ReactiveHealthCheck::new("gas_adjuster").1;
+        health_updater.update(HealthStatus::Ready.into());
+        METRICS.l1_data_stale.set(0);
 
         Ok(Self {
             base_fee_statistics: GasStatistics::new(
@@ -69,12 +134,22 @@ impl GasAdjuster {
                 current_block,
                 &last_block_blob_base_fee,
             ),
+            blob_schedule: RwLock::new(blob_schedule),
             config,
             pubdata_sending_mode,
             eth_client,
+            last_l1_block_timestamp: AtomicU64::new(last_block_timestamp.unwrap_or(0)),
+            liveness,
+            frozen_fee_inputs: RwLock::new(None),
+            health_updater,
         })
     }
 
+    /// Returns the health check for this gas adjuster. The health is affected while L1 data is stale.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
     /// Performs an actualization routine for `GasAdjuster`.
     /// This method is intended to be invoked periodically.
     pub async fn keep_updated(&self) -> Result<(), Error> {
@@ -91,11 +166,25 @@ impl GasAdjuster {
         let last_processed_block = self.base_fee_statistics.last_processed_block();
 
         if current_block > last_processed_block {
-            let (base_fee_history, blob_base_fee_history) = Self::get_base_fees_history(
-                &self.eth_client,
-                (last_processed_block + 1)..=current_block,
-            )
-            .await?;
+            let mut blob_schedule = *self.blob_schedule.read().unwrap();
+            let (base_fee_history, blob_base_fee_history, last_block_timestamp) =
+                Self::get_base_fees_history(
+                    &self.eth_client,
+                    (last_processed_block + 1)..=current_block,
+                    &mut blob_schedule,
+                )
+                .await?;
+            *self.blob_schedule.write().unwrap() = blob_schedule;
+            if let Some(timestamp) = last_block_timestamp {
+                self.last_l1_block_timestamp
+                    .store(timestamp, Ordering::Relaxed);
+            }
+            if let Some(liveness) = &self.liveness {
+                liveness
+                    .write()
+                    .unwrap()
+                    .observe_new_blocks(Instant::now(), last_block_timestamp);
+            }
 
             METRICS
                 .current_base_fee_per_gas
@@ -150,18 +239,103 @@ impl GasAdjuster {
                 break;
             }
 
+            let mut poll_period = self.config.poll_period();
             if let Err(err) = self.keep_updated().await {
-                tracing::warn!("Cannot add the base fee to gas statistics: {}", err);
+                match err.kind() {
+                    ErrorKind::RateLimited => {
+                        // Back off to let the rate limit reset.
+                        poll_period *= 2;
+                        tracing::warn!(
+                            "Rate-limited by L1 provider, backing off for {poll_period:?}: {err}"
+                        );
+                    }
+                    kind if kind.is_retriable() => {
+                        tracing::warn!("Cannot add the base fee to gas statistics: {err}");
+                    }
+                    kind => {
+                        tracing::error!(
+                            "Cannot add the base fee to gas statistics because of a non-retriable error ({kind:?}): {err}"
+                        );
+                    }
+                }
             }
+            self.check_liveness(Instant::now(), seconds_since_epoch());
 
-            tokio::time::sleep(self.config.poll_period()).await;
+            tokio::time::sleep(poll_period).await;
         }
         Ok(())
     }
 
+    /// Freezes fee inputs if L1 data has become stale, or unfreezes them once fresh data has arrived.
+    /// While fee inputs are frozen, the API rejects new transactions (see [`Self::is_l1_data_stale()`]).
+    fn check_liveness(&self, now: Instant, now_timestamp: u64) {
+        let Some(liveness) = &self.liveness else {
+            return;
+        };
+        let staleness = liveness.read().unwrap().staleness(now, now_timestamp);
+        let mut frozen_fee_inputs = self.frozen_fee_inputs.write().unwrap();
+        match (staleness, *frozen_fee_inputs) {
+            (Some((reason, stale_for)), None) => {
+                let inputs = FrozenFeeInputs {
+                    effective_gas_price: self.calculate_effective_gas_price(),
+                    effective_pubdata_price: self.calculate_effective_pubdata_price(),
+                };
+                tracing::warn!(
+                    "L1 data is stale ({reason:?}, for {stale_for:?}); freezing fee inputs at {inputs:?} \
+                     and rejecting new transactions"
+                );
+                *frozen_fee_inputs = Some(inputs);
+                METRICS.l1_data_stale.set(1);
+                self.update_stale_health(reason, stale_for, inputs);
+            }
+            (Some((reason, stale_for)), Some(inputs)) => {
+                self.update_stale_health(reason, stale_for, inputs);
+            }
+            (None, Some(_)) => {
+                tracing::info!(
+                    "L1 data is fresh again; unfreezing fee inputs and accepting transactions"
+                );
+                *frozen_fee_inputs = None;
+                METRICS.l1_data_stale.set(0);
+                self.health_updater.update(HealthStatus::Ready.into());
+            }
+            (None, None) => { /* L1 data is fresh; nothing to do */ }
+        }
+    }
+
+    /// Checks whether L1 data is stale, i.e., fee inputs are frozen. Transactions should not be accepted
+    /// in this case, since they would be priced off outdated L1 data.
+    pub(crate) fn is_l1_data_stale(&self) -> bool {
+        self.frozen_fee_inputs.read().unwrap().is_some()
+    }
+
+    fn update_stale_health(
+        &self,
+        reason: StalenessReason,
+        stale_for: Duration,
+        frozen_fee_inputs: FrozenFeeInputs,
+    ) {
+        let details = StaleL1DataDetails {
+            reason,
+            stale_for_sec: stale_for.as_secs(),
+            frozen_fee_inputs,
+        };
+        let health = Health::from(HealthStatus::Affected).with_details(details);
+        self.health_updater.update(health);
+    }
+
     /// Returns the sum of base and priority fee, in wei, not considering time in mempool.
     /// Can be used to get an estimate of current gas price.
+    ///
+    /// While L1 data is stale, returns the price frozen when staleness was detected.
     pub(crate) fn estimate_effective_gas_price(&self) -> u64 {
+        if let Some(inputs) = *self.frozen_fee_inputs.read().unwrap() {
+            return inputs.effective_gas_price;
+        }
+        self.calculate_effective_gas_price()
+    }
+
+    fn calculate_effective_gas_price(&self) -> u64 {
         if let Some(price) = self.config.internal_enforced_l1_gas_price {
             return price;
         }
@@ -175,7 +349,16 @@ impl GasAdjuster {
         self.bound_gas_price(calculated_price)
     }
 
+    /// Returns the price of a pubdata byte, in wei. While L1 data is stale, returns the price frozen
+    /// when staleness was detected.
     pub(crate) fn estimate_effective_pubdata_price(&self) -> u64 {
+        if let Some(inputs) = *self.frozen_fee_inputs.read().unwrap() {
+            return inputs.effective_pubdata_price;
+        }
+        self.calculate_effective_pubdata_price()
+    }
+
+    fn calculate_effective_pubdata_price(&self) -> u64 {
         match self.pubdata_sending_mode {
             PubdataSendingMode::Blobs => {
                 const BLOB_GAS_PER_BYTE: u64 = 1; // `BYTES_PER_BLOB` = `GAS_PER_BLOB` = 2 ^ 17.
@@ -198,23 +381,38 @@ impl GasAdjuster {
                 self.bound_blob_base_fee(calculated_price)
             }
             PubdataSendingMode::Calldata => {
-                self.estimate_effective_gas_price() * L1_GAS_PER_PUBDATA_BYTE as u64
+                self.calculate_effective_gas_price() * L1_GAS_PER_PUBDATA_BYTE as u64
             }
+            // Pubdata is not published on L1, so the price of the external DA layer is taken from the config.
+            PubdataSendingMode::Custom => self
+                .config
+                .internal_enforced_pubdata_price
+                .expect("`internal_enforced_pubdata_price` must be set for custom DA"),
         }
     }
 
-    /// Returns vector of base fees and blob base fees for given block range.
+    /// Returns the blob parameters of L1 as detected from the latest processed blocks.
+    pub fn blob_schedule(&self) -> BlobSchedule {
+        self.blob_schedule.read().unwrap().schedule
+    }
+
+    /// Returns vector of base fees and blob base fees for given block range, together with the timestamp
+    /// of the last block in the range.
     /// Note, that data for pre-dencun blocks won't be included in the vector returned.
+    /// `blob_schedule` is updated if the processed blocks indicate a change of the L1 blob parameters.
     async fn get_base_fees_history(
         eth_client: &Arc<dyn EthInterface>,
         block_range: RangeInclusive<usize>,
-    ) -> Result<(Vec<u64>, Vec<U256>), Error> {
+        blob_schedule: &mut BlobScheduleTracker,
+    ) -> Result<(Vec<u64>, Vec<U256>, Option<u64>), Error> {
         let mut base_fee_history = Vec::new();
         let mut blob_base_fee_history = Vec::new();
+        let mut last_block_timestamp = None;
         for block_number in block_range {
             let header = eth_client
                 .block(U64::from(block_number).into(), "gas_adjuster")
                 .await?;
+            last_block_timestamp = header.as_ref().map(|header| header.timestamp.as_u64());
             if let Some(base_fee_per_gas) =
                 header.as_ref().and_then(|header| header.base_fee_per_gas)
             {
@@ -223,42 +421,28 @@ impl GasAdjuster {
 
             if let Some(excess_blob_gas) = header.as_ref().and_then(|header| header.excess_blob_gas)
             {
-                blob_base_fee_history.push(Self::blob_base_fee(excess_blob_gas.as_u64()))
+                let excess_blob_gas = excess_blob_gas.as_u64();
+                let blob_gas_used = header
+                    .as_ref()
+                    .and_then(|header| header.blob_gas_used)
+                    .map(|gas| gas.as_u64());
+                if let Some(schedule) = blob_schedule.observe(excess_blob_gas, blob_gas_used) {
+                    tracing::info!(
+                        "Detected L1 blob schedule change at block #{block_number}: {schedule:?}"
+                    );
+                    METRICS
+                        .max_blobs_per_block
+                        .set(schedule.max_blobs_per_block);
+                }
+                blob_base_fee_history.push(blob_schedule.schedule.blob_base_fee(excess_blob_gas))
             }
         }
 
-        Ok((base_fee_history, blob_base_fee_history))
-    }
-
-    /// Calculates `blob_base_fee` given `excess_blob_gas`.
-    fn blob_base_fee(excess_blob_gas: u64) -> U256 {
-        // Constants and formula are taken from EIP4844 specification.
-        const MIN_BLOB_BASE_FEE: u32 = 1;
-        const BLOB_BASE_FEE_UPDATE_FRACTION: u32 = 3338477;
-
-        Self::fake_exponential(
-            MIN_BLOB_BASE_FEE.into(),
-            excess_blob_gas.into(),
-            BLOB_BASE_FEE_UPDATE_FRACTION.into(),
-        )
-    }
-
-    /// approximates `factor * e ** (numerator / denominator)` using Taylor expansion.
-    fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
-        let mut i = 1_u32;
-        let mut output = U256::zero();
-        let mut accum = factor * denominator;
-        while !accum.is_zero() {
-            output += accum;
-
-            accum *= numerator;
-            accum /= denominator;
-            accum /= U256::from(i);
-
-            i += 1;
-        }
-
-        output / denominator
+        Ok((
+            base_fee_history,
+            blob_base_fee_history,
+            last_block_timestamp,
+        ))
     }
 }
 
@@ -298,6 +482,10 @@ impl L1TxParamsProvider for GasAdjuster {
         new_fee as u64
     }
 
+    fn get_max_blobs_per_block(&self) -> u64 {
+        self.blob_schedule().max_blobs_per_block
+    }
+
     fn get_next_block_minimal_base_fee(&self) -> u64 {
         let last_block_base_fee = self.base_fee_statistics.last_added_value();
 
@@ -317,6 +505,17 @@ impl L1TxParamsProvider for GasAdjuster {
     }
 }
 
+impl L1BlockTimestampProvider for GasAdjuster {
+    fn latest_l1_block_timestamp(&self) -> Option<u64> {
+        let timestamp = self.last_l1_block_timestamp.load(Ordering::Relaxed);
+        (timestamp > 0).then_some(timestamp)
+    }
+
+    fn is_l1_data_stale(&self) -> bool {
+        GasAdjuster::is_l1_data_stale(self)
+    }
+}
+
 /// Helper structure responsible for collecting the data about recent transactions,
 /// calculating the median base fee.
 #[derive(Debug, Clone, Default)]
@@ -1,9 +1,19 @@
-use std::{collections::VecDeque, sync::Arc};
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use assert_matches::assert_matches;
 use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
 use zksync_eth_client::clients::MockEthereum;
+use zksync_health_check::{CheckHealth, HealthStatus};
 
-use super::{GasAdjuster, GasStatisticsInner};
+use super::{
+    blob_schedule::{BlobGasSample, BlobScheduleTracker, GAS_PER_BLOB},
+    liveness::{L1Liveness, StalenessReason},
+    BlobSchedule, GasAdjuster, GasStatisticsInner,
+};
 
 /// Check that we compute the median correctly
 #[test]
@@ -59,6 +69,8 @@ async fn kept_updated() {
             num_samples_for_blob_base_fee_estimate: 3,
             internal_pubdata_pricing_multiplier: 1.0,
             max_blob_base_fee: None,
+            max_l1_staleness_sec: None,
+            internal_enforced_pubdata_price: None,
         },
         PubdataSendingMode::Calldata,
     )
@@ -71,7 +83,7 @@ async fn kept_updated() {
     );
     assert_eq!(adjuster.base_fee_statistics.0.read().unwrap().median(), 6);
 
-    let expected_median_blob_base_fee = GasAdjuster::blob_base_fee(393216);
+    let expected_median_blob_base_fee = BlobSchedule::CANCUN.blob_base_fee(393216);
     assert_eq!(
         adjuster
             .blob_base_fee_statistics
@@ -96,7 +108,7 @@ async fn kept_updated() {
     );
     assert_eq!(adjuster.base_fee_statistics.0.read().unwrap().median(), 7);
 
-    let expected_median_blob_base_fee = GasAdjuster::blob_base_fee(393216 * 3);
+    let expected_median_blob_base_fee = BlobSchedule::CANCUN.blob_base_fee(393216 * 3);
     assert_eq!(
         adjuster
             .blob_base_fee_statistics
@@ -118,6 +130,170 @@ fn blob_base_fee_formula() {
     const EXCESS_BLOB_GAS: u64 = 0x4b80000;
     const EXPECTED_BLOB_BASE_FEE: u64 = 19893400088;
 
-    let blob_base_fee = GasAdjuster::blob_base_fee(EXCESS_BLOB_GAS);
+    let blob_base_fee = BlobSchedule::CANCUN.blob_base_fee(EXCESS_BLOB_GAS);
     assert_eq!(blob_base_fee.as_u64(), EXPECTED_BLOB_BASE_FEE);
 }
+
+#[test]
+fn detecting_blob_schedule() {
+    let prev = BlobGasSample {
+        excess_blob_gas: 10 * GAS_PER_BLOB,
+        blob_gas_used: 9 * GAS_PER_BLOB,
+    };
+    let schedule = BlobSchedule::detect(prev, 13 * GAS_PER_BLOB).unwrap();
+    assert_eq!(schedule.target_blobs_per_block, 6);
+    assert_eq!(schedule.max_blobs_per_block, 9);
+    let schedule = BlobSchedule::detect(prev, 16 * GAS_PER_BLOB).unwrap();
+    assert_eq!(schedule, BlobSchedule::CANCUN);
+
+    // Excess blob gas is clamped at zero, so the target cannot be inferred.
+    assert_eq!(BlobSchedule::detect(prev, 0), None);
+    // Unknown target
+    assert_eq!(BlobSchedule::detect(prev, 14 * GAS_PER_BLOB), None);
+}
+
+#[test]
+fn tracking_blob_schedule() {
+    let mut tracker = BlobScheduleTracker::default();
+    assert_eq!(tracker.schedule, BlobSchedule::CANCUN);
+
+    assert_eq!(
+        tracker.observe(10 * GAS_PER_BLOB, Some(9 * GAS_PER_BLOB)),
+        None
+    );
+    let upgraded = tracker
+        .observe(13 * GAS_PER_BLOB, Some(3 * GAS_PER_BLOB))
+        .unwrap();
+    assert_eq!(upgraded.target_blobs_per_block, 6);
+    assert_eq!(tracker.schedule, upgraded);
+
+    // A lower inferred target (e.g., caused by EIP-7918) must not downgrade the schedule.
+    assert_eq!(tracker.observe(13 * GAS_PER_BLOB, None), None);
+    assert_eq!(tracker.schedule, upgraded);
+}
+
+#[test]
+fn detecting_stale_l1_data() {
+    const MAX_STALENESS: Duration = Duration::from_secs(60);
+
+    let start = Instant::now();
+    let mut liveness = L1Liveness::new(MAX_STALENESS, start, Some(1_000));
+    assert_eq!(liveness.staleness(start, 1_000), None);
+    assert_eq!(liveness.staleness(start + MAX_STALENESS, 1_060), None);
+
+    let (reason, stale_for) = liveness
+        .staleness(start + Duration::from_secs(90), 1_090)
+        .unwrap();
+    assert_eq!(reason, StalenessReason::NoNewBlocks);
+    assert_eq!(stale_for, Duration::from_secs(90));
+
+    // New blocks are returned by the provider, but they are old.
+    let now = start + Duration::from_secs(100);
+    liveness.observe_new_blocks(now, Some(1_010));
+    let (reason, stale_for) = liveness.staleness(now, 1_100).unwrap();
+    assert_eq!(reason, StalenessReason::ProviderLag);
+    assert_eq!(stale_for, Duration::from_secs(90));
+
+    liveness.observe_new_blocks(now, Some(1_095));
+    assert_eq!(liveness.staleness(now, 1_100), None);
+}
+
+#[tokio::test]
+async fn freezing_fee_inputs_on_stale_l1_data() {
+    let eth_client =
+        Arc::new(MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]));
+    eth_client.advance_block_number(5);
+
+    let adjuster = GasAdjuster::new(
+        eth_client.clone(),
+        GasAdjusterConfig {
+            default_priority_fee_per_gas: 5,
+            max_base_fee_samples: 5,
+            pricing_formula_parameter_a: 1.5,
+            pricing_formula_parameter_b: 1.0005,
+            internal_l1_pricing_multiplier: 0.8,
+            internal_enforced_l1_gas_price: None,
+            poll_period: 5,
+            max_l1_gas_price: None,
+            num_samples_for_blob_base_fee_estimate: 3,
+            internal_pubdata_pricing_multiplier: 1.0,
+            max_blob_base_fee: None,
+            max_l1_staleness_sec: Some(60),
+            internal_enforced_pubdata_price: None,
+        },
+        PubdataSendingMode::Calldata,
+    )
+    .await
+    .unwrap();
+    let health_check = adjuster.health_check();
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+
+    // Mock blocks have zero timestamps, so the provider lag is not detected with a zero current timestamp.
+    adjuster.check_liveness(Instant::now(), 0);
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+
+    let gas_price = adjuster.estimate_effective_gas_price();
+    let pubdata_price = adjuster.estimate_effective_pubdata_price();
+    assert!(!adjuster.is_l1_data_stale());
+    adjuster.check_liveness(Instant::now() + Duration::from_secs(120), 0);
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Affected
+    );
+    assert!(adjuster.is_l1_data_stale());
+
+    // Fee inputs must stay frozen until the liveness check observes fresh data.
+    eth_client.advance_block_number(3);
+    adjuster.keep_updated().await.unwrap();
+    assert_ne!(adjuster.calculate_effective_gas_price(), gas_price);
+    assert_eq!(adjuster.estimate_effective_gas_price(), gas_price);
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), pubdata_price);
+
+    adjuster.check_liveness(Instant::now(), 0);
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+    assert!(!adjuster.is_l1_data_stale());
+    assert_eq!(
+        adjuster.estimate_effective_gas_price(),
+        adjuster.calculate_effective_gas_price()
+    );
+}
+
+#[tokio::test]
+async fn pubdata_price_for_custom_da() {
+    let eth_client =
+        Arc::new(MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]));
+    eth_client.advance_block_number(5);
+
+    let adjuster = GasAdjuster::new(
+        eth_client,
+        GasAdjusterConfig {
+            default_priority_fee_per_gas: 5,
+            max_base_fee_samples: 5,
+            pricing_formula_parameter_a: 1.5,
+            pricing_formula_parameter_b: 1.0005,
+            internal_l1_pricing_multiplier: 0.8,
+            internal_enforced_l1_gas_price: None,
+            poll_period: 5,
+            max_l1_gas_price: None,
+            num_samples_for_blob_base_fee_estimate: 3,
+            internal_pubdata_pricing_multiplier: 1.0,
+            max_blob_base_fee: None,
+            max_l1_staleness_sec: None,
+            internal_enforced_pubdata_price: Some(1_234),
+        },
+        PubdataSendingMode::Custom,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(adjuster.estimate_effective_pubdata_price(), 1_234);
+}
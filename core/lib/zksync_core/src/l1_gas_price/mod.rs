@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-pub use gas_adjuster::GasAdjuster;
+pub use gas_adjuster::{BlobSchedule, GasAdjuster};
 pub use main_node_fetcher::MainNodeFeeParamsFetcher;
 pub use singleton::GasAdjusterSingleton;
 
@@ -21,9 +21,24 @@ pub trait L1TxParamsProvider: fmt::Debug + 'static + Send + Sync {
     /// Returns the recommended `max_blob_fee_per_gas` value (EIP4844).
     fn get_blob_base_fee(&self) -> u64;
 
+    /// Returns the maximum number of blobs in a single L1 block according to the current L1 blob schedule.
+    fn get_max_blobs_per_block(&self) -> u64;
+
     /// Returns the recommended `max_priority_fee_per_gas` value (EIP1559).
     fn get_priority_fee(&self) -> u64;
 
     /// Returns a lower bound for the `base_fee` value for the next L1 block.
     fn get_next_block_minimal_base_fee(&self) -> u64;
 }
+
+/// Provider of the timestamp of the latest known L1 block. Used by the state keeper to bound the drift
+/// of L1 batch timestamps relative to L1.
+pub trait L1BlockTimestampProvider: fmt::Debug + 'static + Send + Sync {
+    /// Returns the timestamp (in seconds since UNIX epoch) of the latest L1 block known to the provider,
+    /// or `None` if it is not known yet.
+    fn latest_l1_block_timestamp(&self) -> Option<u64>;
+
+    /// Checks whether L1 data of the provider is stale (e.g., because of an L1 RPC outage). In this case,
+    /// the returned L1 block timestamp may lag behind L1 and must not be relied upon.
+    fn is_l1_data_stale(&self) -> bool;
+}
@@ -7,6 +7,7 @@ use tokio::{
 };
 use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
 use zksync_eth_client::clients::QueryClient;
+use zksync_health_check::ReactiveHealthCheck;
 
 use crate::l1_gas_price::GasAdjuster;
 
@@ -48,6 +49,14 @@ impl GasAdjusterSingleton {
         let adjuster = self
             .singleton
             .get_or_init(|| async {
+                if self.pubdata_sending_mode == PubdataSendingMode::Custom
+                    && self.gas_adjuster_config.internal_enforced_pubdata_price.is_none()
+                {
+                    return Err(anyhow::anyhow!(
+                        "`internal_enforced_pubdata_price` must be set when pubdata is sent to a custom DA layer"
+                    )
+                    .into());
+                }
                 let query_client =
                     QueryClient::new(&self.web3_url).context("QueryClient::new()")?;
                 let adjuster = GasAdjuster::new(
@@ -63,6 +72,12 @@ impl GasAdjusterSingleton {
         adjuster.clone()
     }
 
+    /// Returns the health check of the gas adjuster if it was successfully initialized.
+    pub fn health_check(&self) -> Option<ReactiveHealthCheck> {
+        let gas_adjuster = self.singleton.get()?.as_ref().ok()?;
+        Some(gas_adjuster.health_check())
+    }
+
     pub fn run_if_initialized(
         self,
         stop_signal: watch::Receiver<bool>,
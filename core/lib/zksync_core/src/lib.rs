@@ -1,48 +1,67 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
-use std::{net::Ipv4Addr, str::FromStr, sync::Arc, time::Instant};
+use std::{
+    collections::HashSet,
+    net::Ipv4Addr,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use api_server::tx_sender::master_pool_sink::MasterPoolSink;
-use fee_model::{ApiFeeInputProvider, BatchFeeModelInputProvider, MainNodeFeeInputProvider};
+use fee_model::{
+    ApiFeeInputProvider, BatchFeeModelInputProvider, FixedFeeInputProvider,
+    MainNodeFeeInputProvider,
+};
 use futures::channel::oneshot;
 use prometheus_exporter::PrometheusExporterConfig;
 use temp_config_store::TempConfigStore;
-use tokio::{sync::watch, task::JoinHandle};
+use tokio::{
+    sync::{broadcast, watch},
+    task::JoinHandle,
+};
 use zksync_circuit_breaker::{
-    l1_txs::FailedL1TransactionChecker, replication_lag::ReplicationLagChecker, CircuitBreaker,
-    CircuitBreakerChecker, CircuitBreakerError,
+    base_token_ratio::{BaseTokenRatioChecker, BaseTokenRatioLimits, HttpBaseTokenRatioSource},
+    l1_txs::FailedL1TransactionChecker,
+    replication_lag::ReplicationLagChecker,
+    CircuitBreaker, CircuitBreakerChecker, CircuitBreakerError,
 };
 use zksync_concurrency::{ctx, scope};
 use zksync_config::{
     configs::{
         api::{MerkleTreeApiConfig, Web3JsonRpcConfig},
         chain::{
-            CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
-            StateKeeperConfig,
+            CircuitBreakerConfig, FeeModelVersion, MempoolConfig, NetworkConfig,
+            OperationsManagerConfig, StateKeeperConfig,
         },
         contracts::ProverAtGenesis,
         database::{MerkleTreeConfig, MerkleTreeMode},
+        eth_sender::PubdataSendingMode,
+        DAClientConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, PostgresConfig,
 };
 use zksync_contracts::{governance_contract, BaseSystemContracts};
 use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
 use zksync_eth_client::{
-    clients::{PKSigningClient, QueryClient},
+    clients::{OperatorSigningClient, QueryClient},
     BoundEthInterface, CallFunctionArgs, EthInterface,
 };
+use zksync_eth_signer::{OperatorSigner, PrivateKeySigner};
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
-use zksync_l1_contract_interface::i_executor::commit::kzg::KzgSettings;
+use zksync_l1_contract_interface::i_executor::commit::kzg::KzgSettingsProvider;
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
-use zksync_state::PostgresStorageCaches;
+use zksync_state::{PostgresStorageCaches, RedisCache};
 use zksync_types::{
+    api,
     fee_model::FeeModelConfig,
     protocol_version::{L1VerifierConfig, VerifierParams},
     system_contracts::get_system_smart_contracts,
     web3::contract::tokens::Detokenize,
-    L2ChainId, PackedEthSignature, ProtocolVersionId,
+    Address, L2ChainId, PackedEthSignature, ProtocolVersionId,
 };
 
 use crate::{
@@ -50,16 +69,37 @@ use crate::{
         contract_verification,
         execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
         healthcheck::HealthCheckHandle,
-        tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
+        tx_forwarding::{IntakeTlsConfig, TxForwarder},
+        tx_sender::{
+            sponsorship::SponsorshipPolicy,
+            tx_sink::{ReadOnlySink, TxSink},
+            ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig,
+        },
         web3,
-        web3::{state::InternalApiConfig, ApiServerHandles, Namespace},
+        web3::{
+            admin_server::AdminServer,
+            custom_errors::CustomErrorsRegistry,
+            state::{InternalApiConfig, ResponseSigner},
+            ApiServerHandles, Namespace,
+        },
     },
     basic_witness_input_producer::BasicWitnessInputProducer,
+    batch_status_reconciler::BatchStatusReconciler,
+    chain_freeze::ChainFreezeWatcher,
     commitment_generator::CommitmentGenerator,
-    eth_sender::{Aggregator, EthTxAggregator, EthTxManager},
+    da_dispatcher::{
+        AvailClient, CelestiaClient, DataAvailabilityClient, DataAvailabilityDispatcher,
+        EigenDAClient, HttpDataAvailabilityClient, ObjectStoreDAClient,
+    },
+    eth_sender::{
+        Aggregator, BalanceThresholds, EthTxAggregator, EthTxManager, KzgInfoPrecomputer,
+        L1CostBackfill, OperatorBalanceMonitor, PriorityOpsWatchdog, SignerHealthCheck,
+    },
     eth_watch::start_eth_watch,
     house_keeper::{
-        blocks_state_reporter::L1BatchMetricsReporter,
+        artifacts_retention_manager::ArtifactsRetentionManager,
+        audit_log_exporter::AuditLogExporter, blocks_state_reporter::L1BatchMetricsReporter,
+        cold_storage_archiver::ColdStorageArchiver,
         fri_proof_compressor_job_retry_manager::FriProofCompressorJobRetryManager,
         fri_proof_compressor_queue_monitor::FriProofCompressorStatsReporter,
         fri_prover_job_retry_manager::FriProverJobRetryManager,
@@ -67,23 +107,32 @@ use crate::{
         fri_scheduler_circuit_queuer::SchedulerCircuitQueuer,
         fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager,
         fri_witness_generator_queue_monitor::FriWitnessGeneratorStatsReporter,
-        periodic_job::PeriodicJob,
+        periodic_job::PeriodicJob, prover_db_consistency_checker::ProverDbConsistencyChecker,
         waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
     },
-    l1_gas_price::GasAdjusterSingleton,
+    l1_gas_price::{GasAdjusterSingleton, L1BlockTimestampProvider},
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
     metrics::{InitStage, APP_METRICS},
+    remote_config::RemoteConfig,
+    shadow_sequencer::ShadowSequencer,
+    solvency_reporter::{BridgeAddresses, EthHttpBalancesClient, SolvencyReporter},
     state_keeper::{
-        create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer, SequencerSealer,
+        create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer, MiniblockWriteSet,
+        ScheduledTxsSender, SequencerSealer, TxSizeLimits,
     },
 };
 
 pub mod api_server;
+pub mod audit_log;
 pub mod basic_witness_input_producer;
+pub mod batch_status_reconciler;
 pub mod block_reverter;
+pub mod bytecode_repair;
+pub mod chain_freeze;
 pub mod commitment_generator;
 pub mod consensus;
 pub mod consistency_checker;
+pub mod da_dispatcher;
 pub mod eth_sender;
 pub mod eth_watch;
 pub mod fee_model;
@@ -94,7 +143,10 @@ pub mod l1_gas_price;
 pub mod metadata_calculator;
 mod metrics;
 pub mod proof_data_handler;
+pub mod remote_config;
 pub mod reorg_detector;
+pub mod shadow_sequencer;
+pub mod solvency_reporter;
 pub mod state_keeper;
 pub mod sync_layer;
 pub mod temp_config_store;
@@ -254,6 +306,18 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// Monitor of operator account balances on L1.
+    OperatorBalanceMonitor,
+    /// Component dispatching L1 batch pubdata to an external DA layer and tracking inclusion proofs.
+    DADispatcher,
+    /// Component reconciling local L1 batch statuses with the statuses derived from L1 events.
+    BatchStatusReconciler,
+    /// Server accepting transactions forwarded by API replicas and inserting them to the mempool.
+    TxIntake,
+    /// Read-only state keeper re-executing sealed L1 batches with a candidate VM / protocol version.
+    ShadowSequencer,
+    /// Component reconciling bridged value against the L2 total supply and L1 bridge balances.
+    SolvencyReporter,
 }
 
 #[derive(Debug)]
@@ -290,6 +354,12 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "operator_balance_monitor" => Ok(Components(vec![Component::OperatorBalanceMonitor])),
+            "da_dispatcher" => Ok(Components(vec![Component::DADispatcher])),
+            "batch_status_reconciler" => Ok(Components(vec![Component::BatchStatusReconciler])),
+            "tx_intake" => Ok(Components(vec![Component::TxIntake])),
+            "shadow_sequencer" => Ok(Components(vec![Component::ShadowSequencer])),
+            "solvency_reporter" => Ok(Components(vec![Component::SolvencyReporter])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -308,12 +378,7 @@ pub async fn initialize_components(
 
     let db_config = configs.db_config.clone().context("db_config")?;
     let postgres_config = configs.postgres_config.clone().context("postgres_config")?;
-    if let Some(threshold) = postgres_config.slow_query_threshold() {
-        ConnectionPool::global_config().set_slow_query_threshold(threshold)?;
-    }
-    if let Some(threshold) = postgres_config.long_connection_threshold() {
-        ConnectionPool::global_config().set_long_connection_threshold(threshold)?;
-    }
+    set_global_pool_config(&postgres_config)?;
 
     let pool_size = postgres_config.max_connections()?;
     let connection_pool = ConnectionPool::builder(postgres_config.master_url()?, pool_size)
@@ -363,6 +428,21 @@ pub async fn initialize_components(
     });
 
     let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
+    let network_config = configs.network_config.clone().context("network_config")?;
+    let mut storage = connection_pool
+        .access_storage()
+        .await
+        .context("access_storage()")?;
+    genesis::validate_genesis_state(
+        &mut storage,
+        &query_client,
+        network_config.zksync_network_id,
+        &contracts_config,
+    )
+    .await
+    .context("genesis state doesn't match configuration or L1 state")?;
+    drop(storage);
+
     let gas_adjuster_config = configs.gas_adjuster_config.context("gas_adjuster_config")?;
 
     let eth_sender_config = configs
@@ -401,6 +481,12 @@ pub async fn initialize_components(
         tokio::spawn(circuit_breaker_checker.run(cb_sender, stop_receiver.clone())),
     ];
 
+    // If the state keeper and WS API server run in the same process, storage subscriptions are served
+    // from miniblock write sets published by the state keeper rather than by polling Postgres.
+    let miniblock_write_sets = (components.contains(&Component::WsApi)
+        && components.contains(&Component::StateKeeper))
+    .then(|| broadcast::channel(MINIBLOCK_WRITE_SETS_CAPACITY).0);
+
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
         || components.contains(&Component::ContractVerificationApi)
@@ -416,10 +502,14 @@ pub async fn initialize_components(
             &api_config.web3_json_rpc,
             network_config.zksync_network_id,
         );
-        let internal_api_config = InternalApiConfig::new(
+        let internal_api_config = build_internal_api_config(
+            &tx_sender_config,
+            &state_keeper_config,
             &network_config,
-            &api_config.web3_json_rpc,
+            &api_config,
             &contracts_config,
+            &eth_sender_config,
+            &components,
         );
 
         // Lazily initialize storage caches only when they are needed (e.g., skip their initialization
@@ -450,12 +540,14 @@ pub async fn initialize_components(
                 &state_keeper_config,
                 &internal_api_config,
                 &api_config,
-                connection_pool.clone(),
+                Some(connection_pool.clone()),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 batch_fee_input_provider,
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
+                load_cold_storage(configs).await?,
+                load_response_signer(&api_config.web3_json_rpc, &eth_sender_config)?,
             )
             .await
             .context("run_http_api")?;
@@ -468,6 +560,14 @@ pub async fn initialize_components(
                 "Initialized HTTP API on port {:?} in {elapsed:?}",
                 api_config.web3_json_rpc.http_port
             );
+
+            if let Some(admin_port) = api_config.web3_json_rpc.admin_port {
+                let admin_server =
+                    AdminServer::new(admin_port, connection_pool.clone(), stop_receiver.clone())
+                        .await
+                        .context("AdminServer::new()")?;
+                task_futures.push(tokio::spawn(admin_server.run()));
+            }
         }
 
         if components.contains(&Component::WsApi) {
@@ -494,10 +594,13 @@ pub async fn initialize_components(
                 &internal_api_config,
                 &api_config,
                 batch_fee_input_provider,
-                connection_pool.clone(),
+                Some(connection_pool.clone()),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 storage_caches,
+                load_cold_storage(configs).await?,
+                load_response_signer(&api_config.web3_json_rpc, &eth_sender_config)?,
+                miniblock_write_sets.clone(),
             )
             .await
             .context("run_ws_api")?;
@@ -536,28 +639,100 @@ pub async fn initialize_components(
     if components.contains(&Component::StateKeeper) {
         let started_at = Instant::now();
         tracing::info!("initializing State Keeper");
-        let bounded_gas_adjuster = gas_adjuster
-            .get_or_init()
-            .await
-            .context("gas_adjuster.get_or_init()")?;
         let state_keeper_config = configs
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
+        let scheduled_txs_private_key = state_keeper_config
+            .scheduled_txs_private_key()
+            .context("scheduled_txs_private_key()")?;
+        let network_config = configs.network_config.clone().context("network_config")?;
+        let bounded_gas_adjuster = gas_adjuster
+            .get_or_init()
+            .await
+            .context("gas_adjuster.get_or_init()")?;
         let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-            bounded_gas_adjuster,
+            bounded_gas_adjuster.clone(),
             FeeModelConfig::from_state_keeper_config(&state_keeper_config),
         ));
+
+        // Sponsored transactions are checked by the state keeper as well, since they may reach the mempool
+        // bypassing the API server of this node.
+        let sponsorship_policy = match &configs.api_config {
+            Some(api_config) => load_sponsorship_policy(&api_config.web3_json_rpc)?,
+            None => None,
+        };
+        let max_tx_size = configs
+            .api_config
+            .as_ref()
+            .map(|api_config| api_config.web3_json_rpc.max_tx_size);
+        let tx_size_limits = TxSizeLimits::new(&state_keeper_config, max_tx_size);
+
+        let mut scheduled_txs_account = None;
+        if let Some(private_key) = scheduled_txs_private_key {
+            // Scheduled transactions are validated and executed in the same way as transactions submitted
+            // via API. The master pool is used for reads so that the pending nonce is always up to date.
+            let mut web3_config = configs
+                .web3_json_rpc_config
+                .clone()
+                .context("web3_json_rpc_config")?;
+            web3_config.tx_forwarding_url = None;
+            let tx_sender_config = TxSenderConfig::new(
+                &state_keeper_config,
+                &web3_config,
+                network_config.zksync_network_id,
+            );
+            let storage_caches = build_storage_caches(configs, &connection_pool, &mut task_futures)
+                .context("build_storage_caches()")?;
+            let (tx_sender, vm_barrier) = build_tx_sender(
+                &tx_sender_config,
+                &web3_config,
+                &state_keeper_config,
+                connection_pool.clone(),
+                Some(connection_pool.clone()),
+                batch_fee_input_provider.clone(),
+                storage_caches,
+                stop_receiver.clone(),
+            )
+            .await
+            .context("build_tx_sender()")?;
+
+            let signer = OperatorSigner::new(PrivateKeySigner::new(private_key))
+                .await
+                .context("failed creating signer for scheduled transactions")?;
+            let scheduled_txs_sender = ScheduledTxsSender::new(
+                connection_pool.clone(),
+                tx_sender,
+                batch_fee_input_provider.clone(),
+                network_config.zksync_network_id,
+                signer,
+            );
+            scheduled_txs_account = Some(scheduled_txs_sender.address());
+            let stop_receiver = stop_receiver.clone();
+            task_futures.push(tokio::spawn(async move {
+                scheduled_txs_sender.run(stop_receiver).await?;
+                vm_barrier.close();
+                vm_barrier.wait_until_stopped().await;
+                Ok(())
+            }));
+        }
+
         add_state_keeper_to_task_futures(
             &mut task_futures,
             &postgres_config,
             &contracts_config,
             state_keeper_config,
-            &configs.network_config.clone().context("network_config")?,
+            &network_config,
             &db_config,
             &configs.mempool_config.clone().context("mempool_config")?,
             batch_fee_input_provider,
+            bounded_gas_adjuster,
             store_factory.create_store().await,
+            scheduled_txs_account,
+            sponsorship_policy,
+            configs.remote_config_updates.clone(),
+            tx_size_limits,
+            miniblock_write_sets,
             stop_receiver.clone(),
         )
         .await
@@ -615,6 +790,10 @@ pub async fn initialize_components(
             .eth_watch_config
             .clone()
             .context("eth_watch_config")?;
+        let state_keeper_config = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?;
         task_futures.push(
             start_eth_watch(
                 eth_watch_config,
@@ -622,6 +801,8 @@ pub async fn initialize_components(
                 Arc::new(query_client.clone()),
                 main_zksync_contract_address,
                 governance,
+                state_keeper_config.priority_tx_fee_multiplier(),
+                eth_client_config.finality_source(),
                 stop_receiver.clone(),
             )
             .await
@@ -632,13 +813,18 @@ pub async fn initialize_components(
         tracing::info!("initialized ETH-Watcher in {elapsed:?}");
     }
 
-    let kzg_settings = configs
+    let kzg_settings_provider = configs
         .kzg_config
         .as_ref()
-        .map(|k| Arc::new(KzgSettings::new(&k.trusted_setup_path)));
+        .map(|config| KzgSettingsProvider::from_path(config.trusted_setup_path.as_ref()));
     if components.contains(&Component::EthTxAggregator) {
         let started_at = Instant::now();
         tracing::info!("initializing ETH-TxAggregator");
+        let kzg_settings = kzg_settings_provider
+            .as_ref()
+            .map(KzgSettingsProvider::get)
+            .transpose()
+            .context("failed loading KZG settings")?;
         let eth_sender_pool = ConnectionPool::singleton(postgres_config.master_url()?)
             .build()
             .await
@@ -649,21 +835,71 @@ pub async fn initialize_components(
             .clone()
             .context("eth_sender_config")?;
         let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_client_blobs_addr =
-            PKSigningClient::from_config_blobs(&eth_sender, &contracts_config, &eth_client_config)
-                .map(|k| k.sender_account());
+            OperatorSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .await
+                .context("failed creating operator signing client")?;
+        let eth_client_blobs_addr = OperatorSigningClient::from_config_blobs(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+        )
+        .await
+        .context("failed creating blobs operator signing client")?
+        .map(|k| k.sender_account());
+
+        let eth_client = Arc::new(eth_client);
+
+        let state_keeper_config = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?;
+        let mut aggregator = Aggregator::new(
+            eth_sender.sender.clone(),
+            store_factory.create_store().await,
+            eth_client_blobs_addr.is_some(),
+            eth_sender.sender.pubdata_sending_mode.into(),
+            state_keeper_config
+                .l1_batch_commit_data_generator_mode
+                .into(),
+            kzg_settings.clone(),
+        )
+        .context("failed initializing aggregator")?;
+        aggregator
+            .validate_max_pubdata_per_batch(state_keeper_config.max_pubdata_per_batch)
+            .context("max_blobs_per_eth_tx")?;
+        if let Some(contract_address) = eth_sender.sender.execution_approval_contract_addr {
+            aggregator = aggregator.with_l1_approval_policy(eth_client.clone(), contract_address);
+        }
+        // Committing L1 batches is gated on DA inclusion in the custom pubdata sending mode.
+        anyhow::ensure!(
+            eth_sender.sender.pubdata_sending_mode != PubdataSendingMode::Custom
+                || configs.da_dispatcher_config.is_some(),
+            "Custom pubdata sending mode requires the DA dispatcher to be configured"
+        );
+        if eth_sender.sender.auto_select_pubdata_da {
+            anyhow::ensure!(
+                eth_sender.sender.pubdata_sending_mode == PubdataSendingMode::Blobs,
+                "Automatic pubdata DA selection requires blobs pubdata sending mode"
+            );
+            let gas_adjuster = gas_adjuster
+                .get_or_init()
+                .await
+                .context("gas_adjuster.get_or_init()")?;
+            aggregator = aggregator.with_pubdata_da_selection(gas_adjuster);
+        }
+        let priority_op_deadline = eth_sender.sender.priority_op_deadline();
+        if let Some(deadline) = priority_op_deadline {
+            if eth_sender.sender.force_execute_on_priority_op_deadline {
+                aggregator = aggregator.with_priority_op_deadline_enforcement(deadline);
+            }
+        }
+        app_health.insert_component(aggregator.execution_policy_health_check());
+        app_health.insert_component(aggregator.proof_integrity_health_check());
 
         let eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender.sender.clone(),
-            Aggregator::new(
-                eth_sender.sender.clone(),
-                store_factory.create_store().await,
-                eth_client_blobs_addr.is_some(),
-                eth_sender.sender.pubdata_sending_mode.into(),
-                kzg_settings.clone(),
-            ),
-            Arc::new(eth_client),
+            aggregator,
+            eth_client,
             contracts_config.validator_timelock_addr,
             contracts_config.l1_multicall3_addr,
             main_zksync_contract_address,
@@ -679,6 +915,24 @@ pub async fn initialize_components(
         task_futures.push(tokio::spawn(
             eth_tx_aggregator_actor.run(eth_sender_pool, stop_receiver.clone()),
         ));
+
+        if let Some(kzg_settings) = kzg_settings.clone() {
+            let kzg_precomputer_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build kzg_precomputer_pool")?;
+            let kzg_precomputer = KzgInfoPrecomputer::new(kzg_precomputer_pool, kzg_settings);
+            task_futures.push(tokio::spawn(kzg_precomputer.run(stop_receiver.clone())));
+        }
+        if let Some(deadline) = priority_op_deadline {
+            let watchdog_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build priority_ops_watchdog_pool")?;
+            let watchdog = PriorityOpsWatchdog::new(watchdog_pool, deadline);
+            app_health.insert_component(watchdog.health_check());
+            task_futures.push(tokio::spawn(watchdog.run(stop_receiver.clone())));
+        }
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::EthTxAggregator].set(elapsed);
         tracing::info!("initialized ETH-TxAggregator in {elapsed:?}");
@@ -696,9 +950,33 @@ pub async fn initialize_components(
             .clone()
             .context("eth_sender_config")?;
         let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_client_blobs =
-            PKSigningClient::from_config_blobs(&eth_sender, &contracts_config, &eth_client_config);
+            OperatorSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .await
+                .context("failed creating operator signing client")?;
+        let eth_client_blobs = OperatorSigningClient::from_config_blobs(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+        )
+        .await
+        .context("failed creating blobs operator signing client")?;
+        app_health.insert_custom_component(Arc::new(SignerHealthCheck::operator(
+            eth_client.signer().clone(),
+        )));
+        if let Some(client) = &eth_client_blobs {
+            app_health.insert_custom_component(Arc::new(SignerHealthCheck::blobs_operator(
+                client.signer().clone(),
+            )));
+        }
+
+        let l1_cost_backfill_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build l1_cost_backfill_pool")?;
+        let l1_cost_backfill =
+            L1CostBackfill::new(l1_cost_backfill_pool, Arc::new(eth_client.clone()));
+        task_futures.push(tokio::spawn(l1_cost_backfill.run(stop_receiver.clone())));
+
         let eth_tx_manager_actor = EthTxManager::new(
             eth_sender.sender,
             gas_adjuster
@@ -716,6 +994,45 @@ pub async fn initialize_components(
         tracing::info!("initialized ETH-TxManager in {elapsed:?}");
     }
 
+    if components.contains(&Component::OperatorBalanceMonitor) {
+        let eth_sender = configs
+            .eth_sender_config
+            .clone()
+            .context("eth_sender_config")?;
+        let eth_client =
+            OperatorSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .await
+                .context("failed creating operator signing client")?;
+        let blobs_operator_address = OperatorSigningClient::from_config_blobs(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+        )
+        .await
+        .context("failed creating blobs operator signing client")?
+        .map(|client| client.sender_account());
+        let thresholds = BalanceThresholds::new(
+            eth_sender.sender.operator_balance_alert_threshold_gwei,
+            eth_sender.sender.operator_runway_alert_threshold_hours,
+        );
+        let mut monitor = OperatorBalanceMonitor::new(
+            Arc::new(eth_client.clone()),
+            eth_client.sender_account(),
+            blobs_operator_address,
+            thresholds,
+        );
+        if let Some(base_token_addr) = eth_sender.sender.base_token_addr {
+            monitor = monitor.with_base_token(
+                base_token_addr,
+                eth_sender
+                    .sender
+                    .operator_base_token_balance_alert_threshold,
+            );
+        }
+        app_health.insert_component(monitor.health_check());
+        task_futures.push(tokio::spawn(monitor.run(stop_receiver.clone())));
+    }
+
     add_trees_to_task_futures(
         configs,
         &mut task_futures,
@@ -766,20 +1083,209 @@ pub async fn initialize_components(
         )));
     }
 
+    if components.contains(&Component::TxIntake) {
+        let web3_config = &configs
+            .api_config
+            .as_ref()
+            .context("api_config")?
+            .web3_json_rpc;
+        let bind_address = web3_config
+            .tx_intake_bind_addr()
+            .context("tx_intake_port")?;
+        let auth_token = web3_config
+            .tx_forwarding_auth_token
+            .clone()
+            .filter(|token| !token.is_empty())
+            .context("tx_forwarding_auth_token must be set for the transaction intake server")?;
+        let tls_cert_path = web3_config
+            .tx_intake_tls_cert_path
+            .as_deref()
+            .context("tx_intake_tls_cert_path must be set for the transaction intake server")?;
+        let tls_key_path = web3_config
+            .tx_intake_tls_key_path
+            .as_deref()
+            .context("tx_intake_tls_key_path must be set for the transaction intake server")?;
+        let tls_config = IntakeTlsConfig::load(tls_cert_path.as_ref(), tls_key_path.as_ref())
+            .context("IntakeTlsConfig::load()")?;
+
+        // Forwarded transactions are validated and executed by the sequencer in the same way
+        // as transactions submitted directly to its API.
+        let state_keeper_config = configs
+            .state_keeper_config
+            .clone()
+            .context("state_keeper_config")?;
+        let network_config = configs.network_config.clone().context("network_config")?;
+        let mut intake_web3_config = web3_config.clone();
+        intake_web3_config.tx_forwarding_url = None;
+        let tx_sender_config = TxSenderConfig::new(
+            &state_keeper_config,
+            &intake_web3_config,
+            network_config.zksync_network_id,
+        );
+        let bounded_gas_adjuster = gas_adjuster
+            .get_or_init()
+            .await
+            .context("gas_adjuster.get_or_init()")?;
+        let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
+            bounded_gas_adjuster,
+            FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+        ));
+        let storage_caches =
+            build_storage_caches(configs, &replica_connection_pool, &mut task_futures)
+                .context("build_storage_caches()")?;
+        let (tx_sender, vm_barrier) = build_tx_sender(
+            &tx_sender_config,
+            &intake_web3_config,
+            &state_keeper_config,
+            replica_connection_pool.clone(),
+            Some(connection_pool.clone()),
+            batch_fee_input_provider,
+            storage_caches,
+            stop_receiver.clone(),
+        )
+        .await
+        .context("build_tx_sender()")?;
+
+        let intake_server = api_server::tx_forwarding::run_intake_server(
+            bind_address,
+            tls_config,
+            auth_token,
+            tx_sender,
+            network_config.zksync_network_id,
+            web3_config.max_tx_size,
+            stop_receiver.clone(),
+        );
+        task_futures.push(tokio::spawn(async move {
+            intake_server.await?;
+            // Wait until all VM invocations for submitted transactions are completed.
+            vm_barrier.close();
+            vm_barrier.wait_until_stopped().await;
+            Ok(())
+        }));
+    }
+
     if components.contains(&Component::CommitmentGenerator) {
-        let kzg_config = configs.kzg_config.clone().context("kzg_config")?;
+        let kzg_settings_provider = kzg_settings_provider
+            .clone()
+            .unwrap_or_else(KzgSettingsProvider::embedded);
+        // Load the trusted setup eagerly, so that a misconfigured setup is detected on startup.
+        // Loaded settings are cached, so the generator will reuse them.
+        kzg_settings_provider
+            .get()
+            .context("failed loading KZG settings")?;
         let commitment_generator_pool = ConnectionPool::singleton(postgres_config.master_url()?)
             .build()
             .await
             .context("failed to build commitment_generator_pool")?;
-        let commitment_generator =
-            CommitmentGenerator::new(commitment_generator_pool, &kzg_config.trusted_setup_path);
+        let mut commitment_generator =
+            CommitmentGenerator::new(commitment_generator_pool, kzg_settings_provider);
+        if components.contains(&Component::EthTxAggregator) {
+            // Persisted KZG info is consumed and pruned by `eth_sender`.
+            commitment_generator = commitment_generator.with_kzg_info_persistence();
+        }
         app_health.insert_component(commitment_generator.health_check());
         task_futures.push(tokio::spawn(
             commitment_generator.run(stop_receiver.clone()),
         ));
     }
 
+    if components.contains(&Component::DADispatcher) {
+        let da_config = configs
+            .da_dispatcher_config
+            .clone()
+            .context("da_dispatcher_config")?;
+        let da_dispatcher_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build da_dispatcher_pool")?;
+        let da_client: Arc<dyn DataAvailabilityClient> = match configs.da_client_config.clone() {
+            Some(DAClientConfig::Avail(avail_config)) => {
+                let client = AvailClient::new(&da_config.da_client_url, avail_config)
+                    .context("failed initializing Avail client")?;
+                Arc::new(client)
+            }
+            Some(DAClientConfig::EigenDA(eigen_da_config)) => {
+                let client = EigenDAClient::new(&da_config.da_client_url, eigen_da_config)
+                    .context("failed initializing EigenDA client")?;
+                Arc::new(client)
+            }
+            Some(DAClientConfig::Celestia(celestia_config)) => {
+                let client = CelestiaClient::new(&da_config.da_client_url, celestia_config)
+                    .context("failed initializing Celestia client")?;
+                Arc::new(client)
+            }
+            Some(DAClientConfig::ObjectStore(object_store_config)) => {
+                let object_store = ObjectStoreFactory::new(object_store_config)
+                    .create_store()
+                    .await;
+                Arc::new(ObjectStoreDAClient::new(object_store))
+            }
+            None => {
+                let client = HttpDataAvailabilityClient::new(&da_config.da_client_url)
+                    .context("failed initializing DA client")?;
+                Arc::new(client)
+            }
+        };
+        let da_dispatcher =
+            DataAvailabilityDispatcher::new(da_dispatcher_pool, da_config, da_client);
+        task_futures.push(tokio::spawn(da_dispatcher.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::BatchStatusReconciler) {
+        let reconciler_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build reconciler_pool")?;
+        let reconciler = BatchStatusReconciler::new(
+            Arc::new(query_client.clone()),
+            contracts_config.diamond_proxy_addr,
+            eth_sender_config.sender.wait_confirmations.unwrap_or(0),
+            reconciler_pool,
+        );
+        task_futures.push(tokio::spawn(reconciler.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::ShadowSequencer) {
+        let state_keeper_config = configs
+            .state_keeper_config
+            .clone()
+            .context("state_keeper_config")?;
+        let network_config = configs.network_config.clone().context("network_config")?;
+        let protocol_version = state_keeper_config
+            .shadow_protocol_version
+            .map(ProtocolVersionId::try_from)
+            .transpose()
+            .context("unknown shadow_protocol_version")?;
+        // One connection is held by the VM storage, and another one is used to follow the main state keeper.
+        let shadow_sequencer_pool = ConnectionPool::builder(postgres_config.master_url()?, 2)
+            .build()
+            .await
+            .context("failed to build shadow_sequencer_pool")?;
+        let shadow_sequencer = ShadowSequencer::new(
+            shadow_sequencer_pool,
+            network_config.zksync_network_id,
+            protocol_version,
+        );
+        task_futures.push(tokio::spawn(shadow_sequencer.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::SolvencyReporter) {
+        let solvency_reporter_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build solvency_reporter_pool")?;
+        let solvency_reporter = SolvencyReporter::new(
+            solvency_reporter_pool,
+            Box::new(EthHttpBalancesClient::new(Arc::new(query_client.clone()))),
+            BridgeAddresses::new(&contracts_config),
+        );
+        task_futures.push(tokio::spawn(solvency_reporter.run(stop_receiver.clone())));
+    }
+
+    if let Some(health_check) = gas_adjuster.health_check() {
+        app_health.insert_component(health_check);
+    }
+
     // Run healthcheck server for all components.
     let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
     app_health.insert_custom_component(Arc::new(db_health_check));
@@ -792,6 +1298,211 @@ pub async fn initialize_components(
     Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
 }
 
+/// Initializes components for the forensics mode, in which the node serves the read API and debug traces
+/// over a restored (and potentially stale) database. Only the HTTP and WS APIs are supported; the state keeper,
+/// Ethereum-related components and consistency checkers are not started, and L1 is never accessed.
+///
+/// All DB connections are opened in the read-only mode, so the database is guaranteed not to be modified.
+/// Transaction submission is disabled, and fee inputs are taken from the last sealed miniblock.
+pub async fn initialize_forensics_components(
+    configs: &TempConfigStore,
+    components: Vec<Component>,
+) -> anyhow::Result<(
+    Vec<JoinHandle<anyhow::Result<()>>>,
+    watch::Sender<bool>,
+    oneshot::Receiver<CircuitBreakerError>,
+    HealthCheckHandle,
+)> {
+    tracing::info!("Starting the components in the forensics mode: {components:?}");
+    anyhow::ensure!(
+        components
+            .iter()
+            .all(|component| matches!(component, Component::HttpApi | Component::WsApi)),
+        "Only HTTP and WS APIs are supported in the forensics mode; requested: {components:?}"
+    );
+
+    let postgres_config = configs.postgres_config.clone().context("postgres_config")?;
+    set_global_pool_config(&postgres_config)?;
+    let pool_size = postgres_config.max_connections()?;
+    let replica_connection_pool =
+        ConnectionPool::builder(postgres_config.replica_url()?, pool_size)
+            .set_acquire_timeout(postgres_config.acquire_timeout())
+            .set_statement_timeout(postgres_config.statement_timeout())
+            .set_read_only(true)
+            .build()
+            .await
+            .context("failed to build replica_connection_pool")?;
+
+    let health_check_config = configs
+        .health_check_config
+        .clone()
+        .context("health_check_config")?;
+    let app_health = Arc::new(AppHealthCheck::new(
+        health_check_config.slow_time_limit(),
+        health_check_config.hard_time_limit(),
+    ));
+    let circuit_breaker_config = configs
+        .circuit_breaker_config
+        .clone()
+        .context("circuit_breaker_config")?;
+    // No circuit breakers are relevant for the forensics mode, but the checker is still run so that
+    // the returned receiver is not closed prematurely.
+    let circuit_breaker_checker = CircuitBreakerChecker::new(vec![], &circuit_breaker_config);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (cb_sender, cb_receiver) = oneshot::channel();
+
+    let prom_config = configs
+        .prometheus_config
+        .clone()
+        .context("prometheus_config")?;
+    let prom_config = PrometheusExporterConfig::pull(prom_config.listener_port);
+    let mut task_futures: Vec<JoinHandle<anyhow::Result<()>>> = vec![
+        tokio::spawn(prom_config.run(stop_receiver.clone())),
+        tokio::spawn(circuit_breaker_checker.run(cb_sender, stop_receiver.clone())),
+    ];
+
+    let mut api_config = configs.api_config.clone().context("api_config")?;
+    // Transaction submission must stay disabled, so the transaction sender uses the read-only sink
+    // regardless of the forwarding settings.
+    if let Some(url) = api_config.web3_json_rpc.tx_forwarding_url.take() {
+        tracing::warn!("Ignoring transaction forwarding to {url} in the forensics mode");
+    }
+    let state_keeper_config = configs
+        .state_keeper_config
+        .clone()
+        .context("state_keeper_config")?;
+    let network_config = configs.network_config.clone().context("network_config")?;
+    let contracts_config = configs
+        .contracts_config
+        .clone()
+        .context("contracts_config")?;
+    let eth_sender_config = configs
+        .eth_sender_config
+        .clone()
+        .context("eth_sender_config")?;
+    let tx_sender_config = TxSenderConfig::new(
+        &state_keeper_config,
+        &api_config.web3_json_rpc,
+        network_config.zksync_network_id,
+    );
+    let internal_api_config = build_internal_api_config(
+        &tx_sender_config,
+        &state_keeper_config,
+        &network_config,
+        &api_config,
+        &contracts_config,
+        &eth_sender_config,
+        &components,
+    );
+    let storage_caches = build_storage_caches(configs, &replica_connection_pool, &mut task_futures)
+        .context("build_storage_caches()")?;
+    let batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider> =
+        Arc::new(FixedFeeInputProvider::without_l1_prices(
+            FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+        ));
+
+    if components.contains(&Component::HttpApi) {
+        let server_handles = run_http_api(
+            &postgres_config,
+            &tx_sender_config,
+            &state_keeper_config,
+            &internal_api_config,
+            &api_config,
+            None,
+            replica_connection_pool.clone(),
+            stop_receiver.clone(),
+            batch_fee_input_provider.clone(),
+            true,
+            storage_caches.clone(),
+            load_cold_storage(configs).await?,
+            load_response_signer(&api_config.web3_json_rpc, &eth_sender_config)?,
+        )
+        .await
+        .context("run_http_api")?;
+        task_futures.extend(server_handles.tasks);
+        app_health.insert_component(server_handles.health_check);
+        tracing::info!(
+            "Initialized HTTP API on port {:?} in the forensics mode",
+            api_config.web3_json_rpc.http_port
+        );
+    }
+
+    if components.contains(&Component::WsApi) {
+        let server_handles = run_ws_api(
+            &postgres_config,
+            &tx_sender_config,
+            &state_keeper_config,
+            &internal_api_config,
+            &api_config,
+            batch_fee_input_provider,
+            None,
+            replica_connection_pool.clone(),
+            stop_receiver.clone(),
+            storage_caches,
+            load_cold_storage(configs).await?,
+            load_response_signer(&api_config.web3_json_rpc, &eth_sender_config)?,
+            None,
+        )
+        .await
+        .context("run_ws_api")?;
+        task_futures.extend(server_handles.tasks);
+        app_health.insert_component(server_handles.health_check);
+        tracing::info!(
+            "Initialized WS API on port {} in the forensics mode",
+            api_config.web3_json_rpc.ws_port
+        );
+    }
+
+    let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
+    app_health.insert_custom_component(Arc::new(db_health_check));
+    let health_check_handle =
+        HealthCheckHandle::spawn_server(health_check_config.bind_addr(), app_health);
+    Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
+}
+
+fn set_global_pool_config(postgres_config: &PostgresConfig) -> anyhow::Result<()> {
+    if let Some(threshold) = postgres_config.slow_query_threshold() {
+        ConnectionPool::global_config().set_slow_query_threshold(threshold)?;
+    }
+    if let Some(threshold) = postgres_config.long_connection_threshold() {
+        ConnectionPool::global_config().set_long_connection_threshold(threshold)?;
+    }
+    if let Some(threshold) = postgres_config.leak_detection_threshold() {
+        ConnectionPool::global_config().set_leak_detection_threshold(threshold)?;
+    }
+    Ok(())
+}
+
+fn build_internal_api_config(
+    tx_sender_config: &TxSenderConfig,
+    state_keeper_config: &StateKeeperConfig,
+    network_config: &NetworkConfig,
+    api_config: &ApiConfig,
+    contracts_config: &ContractsConfig,
+    eth_sender_config: &ETHSenderConfig,
+    components: &[Component],
+) -> InternalApiConfig {
+    InternalApiConfig {
+        tx_size_limits: tx_sender_config.tx_size_limits,
+        fee_model_version: match state_keeper_config.fee_model_version {
+            FeeModelVersion::V1 => api::FeeModelVersion::V1,
+            FeeModelVersion::V2 => api::FeeModelVersion::V2,
+        },
+        pubdata_da: eth_sender_config.sender.pubdata_sending_mode.into(),
+        batch_seal_limits: api::BatchSealLimits {
+            transaction_slots: (state_keeper_config.transaction_slots as u64).into(),
+            max_gas_per_batch: state_keeper_config.max_gas_per_batch.into(),
+            max_pubdata_per_batch: state_keeper_config.max_pubdata_per_batch.into(),
+        },
+        components: components
+            .iter()
+            .map(|component| format!("{component:?}"))
+            .collect(),
+        ..InternalApiConfig::new(network_config, &api_config.web3_json_rpc, contracts_config)
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn add_state_keeper_to_task_futures(
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
@@ -802,7 +1513,13 @@ async fn add_state_keeper_to_task_futures(
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    l1_block_timestamp_provider: Arc<dyn L1BlockTimestampProvider>,
     object_store: Arc<dyn ObjectStore>,
+    scheduled_txs_account: Option<Address>,
+    sponsorship_policy: Option<SponsorshipPolicy>,
+    remote_config_updates: Option<watch::Receiver<RemoteConfig>>,
+    tx_size_limits: TxSizeLimits,
+    miniblock_write_sets: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let pool_builder = ConnectionPool::singleton(postgres_config.master_url()?);
@@ -815,21 +1532,51 @@ async fn add_state_keeper_to_task_futures(
             .access_storage()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity).await;
+        let mut force_include_accounts: HashSet<_> = state_keeper_config
+            .force_include_addresses
+            .iter()
+            .copied()
+            .collect();
+        // Scheduled transactions are executed ahead of user transactions.
+        force_include_accounts.extend(scheduled_txs_account);
+        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity)
+            .await
+            .with_force_include_accounts(force_include_accounts)
+            .with_fair_ordering(mempool_config.fair_ordering_max_reordering.is_some());
         mempool.register_metrics();
         mempool
     };
+    if let Some(updates) = remote_config_updates {
+        task_futures.push(tokio::spawn(remote_config::update_mempool_capacity(
+            updates,
+            mempool.clone(),
+            mempool_config.capacity,
+            stop_receiver.clone(),
+        )));
+    }
 
     let miniblock_sealer_pool = pool_builder
         .build()
         .await
         .context("failed to build miniblock_sealer_pool")?;
-    let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
-        miniblock_sealer_pool,
-        state_keeper_config.miniblock_seal_queue_capacity,
-    );
+    let command_capacity = state_keeper_config.miniblock_seal_queue_capacity;
+    let (miniblock_sealer, miniblock_sealer_handle) = match miniblock_write_sets {
+        Some(sender) => {
+            MiniblockSealer::with_write_sets(miniblock_sealer_pool, command_capacity, sender)
+        }
+        None => MiniblockSealer::new(miniblock_sealer_pool, command_capacity),
+    };
     task_futures.push(tokio::spawn(miniblock_sealer.run()));
 
+    let (chain_freeze_watcher, chain_freeze_receiver) = ChainFreezeWatcher::new(
+        state_keeper_pool.clone(),
+        ChainFreezeWatcher::DEFAULT_POLL_INTERVAL,
+    )
+    .await?;
+    task_futures.push(tokio::spawn(
+        chain_freeze_watcher.run(stop_receiver.clone()),
+    ));
+
     let state_keeper = create_state_keeper(
         contracts_config,
         state_keeper_config,
@@ -839,9 +1586,13 @@ async fn add_state_keeper_to_task_futures(
         state_keeper_pool.clone(),
         mempool.clone(),
         batch_fee_input_provider.clone(),
+        l1_block_timestamp_provider,
         miniblock_sealer_handle,
         object_store,
         stop_receiver.clone(),
+        chain_freeze_receiver,
+        sponsorship_policy,
+        tx_size_limits,
     )
     .await;
 
@@ -900,6 +1651,11 @@ async fn add_trees_to_task_futures(
         MerkleTreeMode::Lightweight => None,
         MerkleTreeMode::Full => Some(store_factory.create_store().await),
     };
+    // Used to validate backfilled tree data against L1 commitments.
+    let l1_client_url = configs
+        .eth_client_config
+        .as_ref()
+        .map(|config| config.web3_url.as_str());
 
     run_tree(
         task_futures,
@@ -909,6 +1665,7 @@ async fn add_trees_to_task_futures(
         api_config,
         &operation_config,
         object_store,
+        l1_client_url,
         stop_receiver,
     )
     .await
@@ -924,6 +1681,7 @@ async fn run_tree(
     api_config: Option<&MerkleTreeApiConfig>,
     operation_manager: &OperationsManagerConfig,
     object_store: Option<Arc<dyn ObjectStore>>,
+    l1_client_url: Option<&str>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let started_at = Instant::now();
@@ -935,9 +1693,12 @@ async fn run_tree(
     tracing::info!("Initializing Merkle tree in {mode_str} mode");
 
     let config = MetadataCalculatorConfig::for_main_node(merkle_tree_config, operation_manager);
-    let metadata_calculator = MetadataCalculator::new(config, object_store)
+    let mut metadata_calculator = MetadataCalculator::new(config, object_store)
         .await
         .context("failed initializing metadata_calculator")?;
+    if let Some(l1_client_url) = l1_client_url {
+        metadata_calculator = metadata_calculator.with_l1_client(l1_client_url)?;
+    }
     if let Some(api_config) = api_config {
         let address = (Ipv4Addr::UNSPECIFIED, api_config.port).into();
         let tree_reader = metadata_calculator.tree_reader();
@@ -1093,9 +1854,88 @@ async fn add_house_keeper_to_task_futures(
         prover_connection_pool.clone(),
     );
     task_futures.push(tokio::spawn(fri_proof_compressor_retry_manager.run()));
+
+    let prover_db_consistency_checker = ProverDbConsistencyChecker::new(
+        connection_pool.clone(),
+        prover_connection_pool.clone(),
+        house_keeper_config.prover_db_consistency_check_interval_ms,
+    );
+    task_futures.push(tokio::spawn(prover_db_consistency_checker.run()));
+
+    let retention_policies = ArtifactsRetentionManager::policies(&house_keeper_config);
+    if !retention_policies.is_empty() {
+        let object_store_config = configs
+            .object_store_config
+            .clone()
+            .context("object_store_config")?;
+        let object_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        // The manager persists its progress, so it needs the master DB.
+        let retention_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build retention_pool")?;
+        let artifacts_retention_manager = ArtifactsRetentionManager::new(
+            retention_pool,
+            object_store,
+            retention_policies,
+            house_keeper_config.artifacts_retention_dry_run,
+            house_keeper_config.artifacts_retention_interval_ms,
+        );
+        task_futures.push(tokio::spawn(artifacts_retention_manager.run()));
+    }
+
+    if let Some(export_interval_ms) = house_keeper_config.audit_log_export_interval_ms {
+        let object_store_config = configs
+            .object_store_config
+            .clone()
+            .context("object_store_config")?;
+        let object_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        // The exporter persists its progress, so it needs the master DB.
+        let audit_log_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build audit_log_pool")?;
+        let audit_log_exporter =
+            AuditLogExporter::new(audit_log_pool, object_store, export_interval_ms);
+        task_futures.push(tokio::spawn(audit_log_exporter.run()));
+    }
+
+    if let Some(threshold_days) = house_keeper_config.cold_storage_threshold_days {
+        let object_store_config = configs
+            .object_store_config
+            .clone()
+            .context("object_store_config")?;
+        let object_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        // The archiver removes data from Postgres, so it needs the master DB.
+        let cold_storage_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build cold_storage_pool")?;
+        let threshold = Duration::from_secs(u64::from(threshold_days) * 86_400);
+        let cold_storage_archiver = ColdStorageArchiver::new(
+            cold_storage_pool,
+            object_store,
+            threshold,
+            house_keeper_config.cold_storage_interval_ms,
+        );
+        task_futures.push(tokio::spawn(cold_storage_archiver.run()));
+    }
     Ok(())
 }
 
+/// Number of miniblock write sets buffered for the WS API server. If the storage subscriptions notifier lags
+/// further behind, it loads skipped storage changes from Postgres.
+const MINIBLOCK_WRITE_SETS_CAPACITY: usize = 128;
+
+/// Time-to-live for entries in the shared VM execution cache.
+const SHARED_CACHE_TTL: Duration = Duration::from_secs(3_600);
+
 fn build_storage_caches(
     configs: &TempConfigStore,
     replica_connection_pool: &ConnectionPool,
@@ -1110,6 +1950,20 @@ fn build_storage_caches(
     let values_capacity = rpc_config.latest_values_cache_size() as u64;
     let mut storage_caches =
         PostgresStorageCaches::new(factory_deps_capacity, initial_writes_capacity);
+    if let Some(url) = &rpc_config.shared_cache_url {
+        let chain_id = configs
+            .network_config
+            .as_ref()
+            .context("network_config")?
+            .zksync_network_id;
+        let shared_cache = RedisCache::new(
+            url,
+            format!("zksync:{}:", chain_id.as_u64()),
+            SHARED_CACHE_TTL,
+        )
+        .context("failed initializing shared VM execution cache")?;
+        storage_caches = storage_caches.with_shared_cache(Arc::new(shared_cache));
+    }
 
     if values_capacity > 0 {
         let values_cache_task = storage_caches.configure_storage_values_cache(
@@ -1122,23 +1976,63 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn build_tx_sender(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
     state_keeper_config: &StateKeeperConfig,
     replica_pool: ConnectionPool,
-    master_pool: ConnectionPool,
+    master_pool: Option<ConnectionPool>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     storage_caches: PostgresStorageCaches,
-) -> (TxSender, VmConcurrencyBarrier) {
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<(TxSender, VmConcurrencyBarrier)> {
     let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
-    let master_pool_sink = MasterPoolSink::new(master_pool);
-    let tx_sender_builder = TxSenderBuilder::new(
-        tx_sender_config.clone(),
-        replica_pool.clone(),
-        Arc::new(master_pool_sink),
-    )
-    .with_sealer(Arc::new(sequencer_sealer));
+    let accepts_txs = web3_json_config.tx_forwarding_url.is_some() || master_pool.is_some();
+    let tx_sink: Arc<dyn TxSink> = if let Some(url) = &web3_json_config.tx_forwarding_url {
+        let auth_token = web3_json_config
+            .tx_forwarding_auth_token
+            .clone()
+            .context("tx_forwarding_auth_token must be set if transaction forwarding is enabled")?;
+        let ca_cert_pem = web3_json_config
+            .tx_forwarding_ca_cert_path
+            .as_ref()
+            .map(|path| {
+                std::fs::read(path)
+                    .with_context(|| format!("failed reading tx_forwarding_ca_cert_path {path:?}"))
+            })
+            .transpose()?;
+        let (forwarder, forwarder_task) = TxForwarder::new(
+            url,
+            ca_cert_pem.as_deref(),
+            auth_token,
+            web3_json_config.tx_forwarding_queue_capacity(),
+        )
+        .context("TxForwarder::new()")?;
+        // The task terminates once the transaction sender is dropped.
+        tokio::spawn(forwarder_task.run());
+        tracing::info!("Transactions will be forwarded to the intake server at {url}");
+        Arc::new(forwarder)
+    } else if let Some(master_pool) = master_pool {
+        Arc::new(MasterPoolSink::new(master_pool))
+    } else {
+        tracing::info!("Transaction submission is disabled");
+        Arc::new(ReadOnlySink)
+    };
+    let mut tx_sender_builder =
+        TxSenderBuilder::new(tx_sender_config.clone(), replica_pool.clone(), tx_sink)
+            .with_sealer(Arc::new(sequencer_sealer))
+            .with_sponsorship_policy(load_sponsorship_policy(web3_json_config)?);
+    if accepts_txs {
+        let (chain_freeze_watcher, chain_freeze_receiver) = ChainFreezeWatcher::new(
+            replica_pool.clone(),
+            ChainFreezeWatcher::DEFAULT_POLL_INTERVAL,
+        )
+        .await?;
+        // The task terminates on a stop signal or once the transaction sender is dropped.
+        tokio::spawn(chain_freeze_watcher.run(stop_receiver));
+        tx_sender_builder = tx_sender_builder.with_chain_freeze(chain_freeze_receiver);
+    }
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
@@ -1154,7 +2048,7 @@ async fn build_tx_sender(
             storage_caches,
         )
         .await;
-    (tx_sender, vm_barrier)
+    Ok((tx_sender, vm_barrier))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1164,12 +2058,14 @@ async fn run_http_api(
     state_keeper_config: &StateKeeperConfig,
     internal_api: &InternalApiConfig,
     api_config: &ApiConfig,
-    master_connection_pool: ConnectionPool,
+    master_connection_pool: Option<ConnectionPool>,
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
+    cold_storage: Option<Arc<dyn ObjectStore>>,
+    response_signer: Option<ResponseSigner>,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1179,8 +2075,9 @@ async fn run_http_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        stop_receiver.clone(),
     )
-    .await;
+    .await?;
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
     if with_debug_namespace {
@@ -1199,10 +2096,30 @@ async fn run_http_api(
             .with_updaters_pool(updaters_pool)
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
+            .with_custom_errors(load_custom_errors(&api_config.web3_json_rpc)?)
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
+            .with_shutdown_delay(api_config.web3_json_rpc.shutdown_delay())
+            .with_shutdown_timeout(api_config.web3_json_rpc.shutdown_timeout())
+            .with_debug_namespace_concurrency_limit(
+                api_config.web3_json_rpc.debug_namespace_concurrency_limit,
+            )
+            .with_debug_namespace_requests_per_minute_limit(
+                api_config
+                    .web3_json_rpc
+                    .debug_namespace_requests_per_minute_limit,
+            )
+            .with_debug_trace_block_tx_limit(api_config.web3_json_rpc.debug_trace_block_tx_limit)
             .with_tx_sender(tx_sender, vm_barrier)
             .enable_api_namespaces(namespaces);
+    let api_builder = match response_signer {
+        Some(signer) => api_builder.with_response_signer(signer),
+        None => api_builder,
+    };
+    let api_builder = match cold_storage {
+        Some(cold_storage) => api_builder.with_cold_storage(cold_storage),
+        None => api_builder,
+    };
     api_builder.build(stop_receiver).await
 }
 
@@ -1214,11 +2131,15 @@ async fn run_ws_api(
     internal_api: &InternalApiConfig,
     api_config: &ApiConfig,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
-    master_connection_pool: ConnectionPool,
+    master_connection_pool: Option<ConnectionPool>,
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
+    cold_storage: Option<Arc<dyn ObjectStore>>,
+    response_signer: Option<ResponseSigner>,
+    miniblock_write_sets: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
 ) -> anyhow::Result<ApiServerHandles> {
+    let handoff_pool = master_connection_pool.clone();
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
         &api_config.web3_json_rpc,
@@ -1227,8 +2148,9 @@ async fn run_ws_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        stop_receiver.clone(),
     )
-    .await;
+    .await?;
     let last_miniblock_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
         .build()
         .await
@@ -1252,12 +2174,109 @@ async fn run_ws_api(
             )
             .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
+            .with_custom_errors(load_custom_errors(&api_config.web3_json_rpc)?)
+            .with_shutdown_delay(api_config.web3_json_rpc.shutdown_delay())
+            .with_shutdown_timeout(api_config.web3_json_rpc.shutdown_timeout())
             .with_tx_sender(tx_sender, vm_barrier)
             .enable_api_namespaces(namespaces);
+    let handoff_ttl = api_config.web3_json_rpc.subscription_handoff_ttl();
+    let api_builder = match handoff_pool {
+        Some(handoff_pool) => api_builder.with_subscription_handoff(handoff_pool, handoff_ttl),
+        None => {
+            if handoff_ttl.is_some() {
+                tracing::warn!(
+                    "Subscription handoff is disabled since the WS API server has no access to the master database"
+                );
+            }
+            api_builder
+        }
+    };
+    let api_builder = match response_signer {
+        Some(signer) => api_builder.with_response_signer(signer),
+        None => api_builder,
+    };
+    let api_builder = match cold_storage {
+        Some(cold_storage) => api_builder.with_cold_storage(cold_storage),
+        None => api_builder,
+    };
+    let api_builder = match miniblock_write_sets {
+        Some(sender) => api_builder.with_storage_write_sets(sender),
+        None => api_builder,
+    };
 
     api_builder.build(stop_receiver.clone()).await
 }
 
+/// Creates an object store to load call traces and events moved from Postgres if the cold storage is enabled.
+async fn load_cold_storage(
+    configs: &TempConfigStore,
+) -> anyhow::Result<Option<Arc<dyn ObjectStore>>> {
+    let cold_storage_enabled = configs
+        .house_keeper_config
+        .as_ref()
+        .map_or(false, |config| config.cold_storage_threshold_days.is_some());
+    if !cold_storage_enabled {
+        return Ok(None);
+    }
+    let object_store_config = configs
+        .object_store_config
+        .clone()
+        .context("object_store_config")?;
+    let object_store = ObjectStoreFactory::new(object_store_config)
+        .create_store()
+        .await;
+    Ok(Some(object_store))
+}
+
+fn load_response_signer(
+    web3_config: &Web3JsonRpcConfig,
+    eth_sender_config: &ETHSenderConfig,
+) -> anyhow::Result<Option<ResponseSigner>> {
+    let Some(private_key) = web3_config.response_signing_key() else {
+        return Ok(None);
+    };
+    // The signing key is loaded by every API server, so it must not be able to authorize L1 transactions.
+    let operator_keys = [
+        eth_sender_config.sender.private_key(),
+        eth_sender_config.sender.private_key_blobs(),
+    ];
+    anyhow::ensure!(
+        !operator_keys.contains(&Some(private_key)),
+        "Response signing key must be a dedicated key; it cannot be the same as an operator key"
+    );
+    let signer = ResponseSigner::new(private_key)?;
+    tracing::info!(
+        "Enabled signing of API responses with address {:?}",
+        signer.address()
+    );
+    Ok(Some(signer))
+}
+
+fn load_custom_errors(
+    web3_config: &Web3JsonRpcConfig,
+) -> anyhow::Result<Option<CustomErrorsRegistry>> {
+    let Some(path) = &web3_config.custom_errors_abi_path else {
+        return Ok(None);
+    };
+    let registry = CustomErrorsRegistry::load(Path::new(path))?;
+    Ok(Some(registry))
+}
+
+fn load_sponsorship_policy(
+    web3_config: &Web3JsonRpcConfig,
+) -> anyhow::Result<Option<SponsorshipPolicy>> {
+    let Some(path) = &web3_config.sponsorship_policy_path else {
+        return Ok(None);
+    };
+    let policy = SponsorshipPolicy::load(Path::new(path))?;
+    tracing::info!(
+        "Loaded fee sponsorship policy with {} rules, paymaster: {:?}",
+        policy.rules.len(),
+        policy.paymaster
+    );
+    Ok(Some(policy))
+}
+
 async fn circuit_breakers_for_components(
     components: &[Component],
     postgres_config: &PostgresConfig,
@@ -1290,5 +2309,22 @@ async fn circuit_breakers_for_components(
             replication_lag_limit_sec: circuit_breaker_config.replication_lag_limit_sec,
         }));
     }
+
+    // Transactions are priced using the base token ratio, so transaction intake is halted if the ratio is unreliable.
+    if let Some(url) = &circuit_breaker_config.base_token_ratio_url {
+        if components.iter().any(|c| {
+            matches!(
+                c,
+                Component::StateKeeper | Component::HttpApi | Component::WsApi
+            )
+        }) {
+            let source = HttpBaseTokenRatioSource::new(url.clone())
+                .context("failed creating base token ratio source")?;
+            circuit_breakers.push(Box::new(BaseTokenRatioChecker::new(
+                Box::new(source),
+                BaseTokenRatioLimits::new(circuit_breaker_config),
+            )));
+        }
+    }
     Ok(circuit_breakers)
 }
@@ -20,7 +20,10 @@ use zksync_merkle_tree::{
     Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction,
 };
 use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries};
-use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
+use zksync_types::{
+    block::{L1BatchHeader, L1BatchTreeData},
+    L1BatchNumber, StorageKey, H256,
+};
 
 use super::metrics::{LoadChangesStage, TreeUpdateStage, METRICS};
 
@@ -219,6 +222,22 @@ impl AsyncTreeReader {
         .unwrap()
     }
 
+    /// Returns tree data for the specified L1 batch, or `None` if the tree has no version for the batch.
+    pub async fn l1_batch_tree_data(
+        self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Option<L1BatchTreeData> {
+        tokio::task::spawn_blocking(move || {
+            let (hash, leaf_count) = self.inner.root_info(l1_batch_number)?;
+            Some(L1BatchTreeData {
+                hash,
+                rollup_last_leaf_index: leaf_count + 1,
+            })
+        })
+        .await
+        .unwrap()
+    }
+
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
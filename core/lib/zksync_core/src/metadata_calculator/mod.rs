@@ -14,18 +14,21 @@ use zksync_config::configs::{
     database::{MerkleTreeConfig, MerkleTreeMode},
 };
 use zksync_dal::ConnectionPool;
+use zksync_eth_client::clients::QueryClient;
 use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
 use zksync_object_store::ObjectStore;
 
 pub(crate) use self::helpers::{AsyncTreeReader, L1BatchWithLogs, MerkleTreeInfo};
 use self::{
     helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth},
+    repair::{L1Commitments, MetadataRepair},
     updater::TreeUpdater,
 };
 
 mod helpers;
 mod metrics;
 mod recovery;
+mod repair;
 #[cfg(test)]
 pub(crate) mod tests;
 mod updater;
@@ -76,6 +79,7 @@ pub struct MetadataCalculator {
     config: MetadataCalculatorConfig,
     tree_reader: watch::Sender<Option<AsyncTreeReader>>,
     object_store: Option<Arc<dyn ObjectStore>>,
+    l1_commitments: Option<L1Commitments>,
     delayer: Delayer,
     health_updater: HealthUpdater,
     max_l1_batches_per_iter: usize,
@@ -96,6 +100,7 @@ impl MetadataCalculator {
         Ok(Self {
             tree_reader: watch::channel(None).0,
             object_store,
+            l1_commitments: None,
             delayer: Delayer::new(config.delay_interval),
             health_updater,
             max_l1_batches_per_iter: config.max_l1_batches_per_iter,
@@ -103,6 +108,13 @@ impl MetadataCalculator {
         })
     }
 
+    /// Enables validating tree data backfilled for L1 batches against commitments published on L1.
+    pub fn with_l1_client(mut self, l1_client_url: &str) -> anyhow::Result<Self> {
+        let l1_client = QueryClient::new(l1_client_url).context("failed creating L1 client")?;
+        self.l1_commitments = Some(L1Commitments::new(Box::new(l1_client))?);
+        Ok(self)
+    }
+
     /// Returns a health check for this calculator.
     pub fn tree_health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
@@ -171,7 +183,13 @@ impl MetadataCalculator {
         );
         self.tree_reader.send_replace(Some(tree_reader));
 
-        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
+        let repair = MetadataRepair::new(self.l1_commitments);
+        let updater = TreeUpdater::new(
+            tree,
+            self.max_l1_batches_per_iter,
+            self.object_store,
+            repair,
+        );
         updater
             .loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater)
             .await
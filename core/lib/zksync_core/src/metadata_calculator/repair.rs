@@ -0,0 +1,213 @@
+//! Backfilling of tree data missing from Postgres.
+//!
+//! The Merkle tree may have already processed L1 batches for which Postgres has no tree data, e.g. after
+//! the tree or Postgres was restored from a backup, or after a partial revert. The tree updater doesn't revisit
+//! such batches, so without backfilling, commitments for them are never generated and `eth_sender` stalls.
+//! Commitments are regenerated by the commitment generator as soon as the tree data is present.
+
+use anyhow::Context as _;
+use zksync_config::configs::database::MerkleTreeMode;
+use zksync_dal::StorageProcessor;
+use zksync_eth_client::EthInterface;
+use zksync_types::{block::L1BatchTreeData, web3::ethabi, L1BatchNumber, H256, U256};
+
+use super::{helpers::AsyncTreeReader, metrics::METRICS};
+use crate::consistency_checker::ConsistencyChecker;
+
+/// L1 commitments used to validate backfilled tree data.
+#[derive(Debug)]
+pub(super) struct L1Commitments {
+    l1_client: Box<dyn EthInterface>,
+    commit_function: ethabi::Function,
+}
+
+impl L1Commitments {
+    pub fn new(l1_client: Box<dyn EthInterface>) -> anyhow::Result<Self> {
+        let commit_function = zksync_contracts::zksync_contract()
+            .function("commitBatches")
+            .context("L1 contract does not have `commitBatches` function")?
+            .clone();
+        Ok(Self {
+            l1_client,
+            commit_function,
+        })
+    }
+
+    /// Returns the commitment for the specified L1 batch published on L1, or `None` if the batch isn't committed.
+    async fn commitment(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<ethabi::Token>> {
+        let commit_tx_hash = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(l1_batch_number)
+            .await?
+            .and_then(|details| details.base.commit_tx_hash);
+        let Some(commit_tx_hash) = commit_tx_hash else {
+            return Ok(None);
+        };
+
+        let commit_tx = self
+            .l1_client
+            .get_tx(commit_tx_hash, "metadata_calculator")
+            .await?
+            .with_context(|| format!("commit tx {commit_tx_hash:?} not found on L1"))?;
+        let commitment = ConsistencyChecker::extract_commit_data(
+            &commit_tx.input.0,
+            &self.commit_function,
+            l1_batch_number,
+        )?;
+        Ok(Some(commitment))
+    }
+}
+
+/// Checks that tree data matches the L1 batch commitment published on L1.
+fn check_l1_commitment(
+    commitment: &ethabi::Token,
+    tree_data: &L1BatchTreeData,
+) -> anyhow::Result<()> {
+    let ethabi::Token::Tuple(fields) = commitment else {
+        anyhow::bail!("unexpected signature for L1 commit function");
+    };
+    let (Some(ethabi::Token::Uint(leaf_index)), Some(ethabi::Token::FixedBytes(root_hash))) =
+        (fields.get(2), fields.get(3))
+    else {
+        anyhow::bail!("unexpected signature for L1 commit function");
+    };
+    anyhow::ensure!(
+        root_hash.len() == 32 && H256::from_slice(root_hash) == tree_data.hash,
+        "state root hash mismatch: L1 has 0x{}, tree has {:?}",
+        hex::encode(root_hash),
+        tree_data.hash
+    );
+    anyhow::ensure!(
+        *leaf_index == U256::from(tree_data.rollup_last_leaf_index),
+        "rollup last leaf index mismatch: L1 has {leaf_index}, tree has {}",
+        tree_data.rollup_last_leaf_index
+    );
+    Ok(())
+}
+
+/// Backfills tree data for L1 batches already processed by the Merkle tree.
+#[derive(Debug)]
+pub(super) struct MetadataRepair {
+    l1_commitments: Option<L1Commitments>,
+}
+
+impl MetadataRepair {
+    pub fn new(l1_commitments: Option<L1Commitments>) -> Self {
+        Self { l1_commitments }
+    }
+
+    /// Backfills tree data for all L1 batches missing it that precede the next L1 batch to be processed
+    /// by the tree. Returns the number of repaired batches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree data doesn't match the commitment already published on L1; this requires
+    /// manual intervention.
+    pub async fn run(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tree_reader: &AsyncTreeReader,
+    ) -> anyhow::Result<usize> {
+        let tree_info = tree_reader.clone().info().await;
+        let l1_batch_numbers = storage
+            .blocks_dal()
+            .get_l1_batches_without_tree_data(tree_info.next_l1_batch_number)
+            .await
+            .context("failed getting L1 batches without tree data")?;
+        if l1_batch_numbers.is_empty() {
+            return Ok(0);
+        }
+        tracing::warn!(
+            "L1 batches {l1_batch_numbers:?} are processed by the Merkle tree, but have no tree data in Postgres; \
+             backfilling it"
+        );
+        if matches!(tree_info.mode, MerkleTreeMode::Full) {
+            tracing::warn!(
+                "Witness inputs are not regenerated for backfilled L1 batches; \
+                 if they are missing, the batches need to be re-processed by the tree"
+            );
+        }
+
+        let mut repaired_count = 0;
+        for l1_batch_number in l1_batch_numbers {
+            let Some(tree_data) = tree_reader
+                .clone()
+                .l1_batch_tree_data(l1_batch_number)
+                .await
+            else {
+                tracing::warn!(
+                    "Merkle tree doesn't have a version for L1 batch #{l1_batch_number}; \
+                     its tree data cannot be backfilled"
+                );
+                continue;
+            };
+
+            if let Some(l1_commitments) = &self.l1_commitments {
+                let commitment = l1_commitments
+                    .commitment(storage, l1_batch_number)
+                    .await
+                    .with_context(|| {
+                        format!("failed getting L1 commitment for L1 batch #{l1_batch_number}")
+                    })?;
+                if let Some(commitment) = commitment {
+                    check_l1_commitment(&commitment, &tree_data).with_context(|| {
+                        format!("tree data for L1 batch #{l1_batch_number} diverges from L1")
+                    })?;
+                }
+            }
+
+            storage
+                .blocks_dal()
+                .save_l1_batch_tree_data(l1_batch_number, &tree_data)
+                .await?;
+            tracing::info!("Backfilled tree data for L1 batch #{l1_batch_number}: {tree_data:?}");
+            METRICS.repaired_l1_batches.inc();
+            repaired_count += 1;
+        }
+        Ok(repaired_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_commitment(tree_data: &L1BatchTreeData) -> ethabi::Token {
+        ethabi::Token::Tuple(vec![
+            ethabi::Token::Uint(1.into()),
+            ethabi::Token::Uint(1_000.into()),
+            ethabi::Token::Uint(tree_data.rollup_last_leaf_index.into()),
+            ethabi::Token::FixedBytes(tree_data.hash.as_bytes().to_vec()),
+        ])
+    }
+
+    #[test]
+    fn checking_l1_commitment() {
+        let tree_data = L1BatchTreeData {
+            hash: H256::repeat_byte(1),
+            rollup_last_leaf_index: 42,
+        };
+        check_l1_commitment(&mock_commitment(&tree_data), &tree_data).unwrap();
+
+        let other_hash = L1BatchTreeData {
+            hash: H256::repeat_byte(2),
+            ..tree_data
+        };
+        let err = check_l1_commitment(&mock_commitment(&other_hash), &tree_data).unwrap_err();
+        assert!(err.to_string().contains("root hash mismatch"), "{err}");
+
+        let other_index = L1BatchTreeData {
+            rollup_last_leaf_index: 23,
+            ..tree_data
+        };
+        let err = check_l1_commitment(&mock_commitment(&other_index), &tree_data).unwrap_err();
+        assert!(err.to_string().contains("leaf index mismatch"), "{err}");
+
+        let err = check_l1_commitment(&ethabi::Token::Bool(true), &tree_data).unwrap_err();
+        assert!(err.to_string().contains("unexpected signature"), "{err}");
+    }
+}
@@ -352,6 +352,60 @@ async fn postgres_backup_recovery_with_excluded_metadata() {
     test_postgres_backup_recovery(false, true).await;
 }
 
+#[tokio::test]
+async fn backfilling_tree_data_after_partial_revert() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool.clone()).await;
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let mut expected_tree_data = vec![];
+    for l1_batch_number in 2..=5 {
+        let tree_data = storage
+            .blocks_dal()
+            .get_l1_batch_tree_data(L1BatchNumber(l1_batch_number))
+            .await
+            .unwrap()
+            .expect("no tree data");
+        expected_tree_data.push(tree_data);
+    }
+
+    // Simulate a partial revert of Postgres that erased tree data for L1 batches #2 and #3,
+    // but retained it for the following batches.
+    let removed_batches = remove_l1_batches(&mut storage, L1BatchNumber(1)).await;
+    for (batch_header, tree_data) in removed_batches.iter().zip(&expected_tree_data) {
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(batch_header)
+            .await
+            .unwrap();
+        insert_initial_writes_for_batch(&mut storage, batch_header.number).await;
+        if batch_header.number > L1BatchNumber(3) {
+            storage
+                .blocks_dal()
+                .save_l1_batch_tree_data(batch_header.number, tree_data)
+                .await
+                .unwrap();
+        }
+    }
+    drop(storage);
+
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    run_calculator(calculator, pool.clone()).await;
+
+    let mut storage = pool.access_storage().await.unwrap();
+    for (l1_batch_number, expected) in (2..=5).zip(&expected_tree_data) {
+        let tree_data = storage
+            .blocks_dal()
+            .get_l1_batch_tree_data(L1BatchNumber(l1_batch_number))
+            .await
+            .unwrap();
+        assert_eq!(tree_data.as_ref(), Some(expected));
+    }
+}
+
 pub(crate) async fn setup_calculator(
     db_path: &Path,
     pool: &ConnectionPool,
@@ -1,32 +1,41 @@
 //! Tree updater trait and its implementations.
 
-use std::{ops, sync::Arc, time::Instant};
+use std::{
+    ops,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use futures::{future, FutureExt};
 use tokio::sync::watch;
-use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_dal::{proof_generation_dal::ProverArtifact, ConnectionPool, StorageProcessor};
 use zksync_health_check::HealthUpdater;
 use zksync_merkle_tree::domain::TreeMetadata;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
     block::{L1BatchHeader, L1BatchTreeData},
     writes::InitialStorageWrite,
-    L1BatchNumber, U256,
+    L1BatchNumber, H256, U256,
 };
 
 use super::{
     helpers::{AsyncTree, Delayer, L1BatchWithLogs},
     metrics::{TreeUpdateStage, METRICS},
+    repair::MetadataRepair,
     MetadataCalculator,
 };
 use crate::utils::wait_for_l1_batch;
 
+/// Interval between checks for L1 batches processed by the tree, but missing tree data in Postgres.
+const REPAIR_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Debug)]
 pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
     object_store: Option<Arc<dyn ObjectStore>>,
+    repair: MetadataRepair,
 }
 
 impl TreeUpdater {
@@ -34,18 +43,20 @@ impl TreeUpdater {
         tree: AsyncTree,
         max_l1_batches_per_iter: usize,
         object_store: Option<Arc<dyn ObjectStore>>,
+        repair: MetadataRepair,
     ) -> Self {
         Self {
             tree,
             max_l1_batches_per_iter,
             object_store,
+            repair,
         }
     }
 
     async fn process_l1_batch(
         &mut self,
         l1_batch: L1BatchWithLogs,
-    ) -> (L1BatchHeader, TreeMetadata, Option<String>) {
+    ) -> (L1BatchHeader, TreeMetadata, Option<(String, H256)>) {
         let compute_latency = METRICS.start_stage(TreeUpdateStage::Compute);
         let mut metadata = self.tree.process_l1_batch(l1_batch.storage_logs).await;
         compute_latency.observe();
@@ -56,8 +67,8 @@ impl TreeUpdater {
             let witness_input =
                 witness_input.expect("No witness input provided by tree; this is a bug");
             let save_witnesses_latency = METRICS.start_stage(TreeUpdateStage::SaveGcs);
-            let object_key = object_store
-                .put(l1_batch_number, &witness_input)
+            let (object_key, witness_input_hash) = object_store
+                .put_with_hash(l1_batch_number, &witness_input)
                 .await
                 .unwrap();
             save_witnesses_latency.observe();
@@ -65,7 +76,7 @@ impl TreeUpdater {
             tracing::info!(
                 "Saved witnesses for L1 batch #{l1_batch_number} to object storage at `{object_key}`"
             );
-            Some(object_key)
+            Some((object_key, witness_input_hash))
         } else {
             None
         };
@@ -139,7 +150,7 @@ impl TreeUpdater {
             // That is, if we run multiple tree instances, we'll get metadata correspondence
             // right away without having to implement dedicated code.
 
-            if let Some(object_key) = &object_key {
+            if let Some((object_key, witness_input_hash)) = &object_key {
                 storage
                     .basic_witness_input_producer_dal()
                     .create_basic_witness_input_producer_job(l1_batch_number)
@@ -149,6 +160,15 @@ impl TreeUpdater {
                     .proof_generation_dal()
                     .insert_proof_generation_details(l1_batch_number, object_key)
                     .await;
+                storage
+                    .proof_generation_dal()
+                    .save_artifact_hash(
+                        l1_batch_number,
+                        ProverArtifact::WitnessInput,
+                        *witness_input_hash,
+                    )
+                    .await
+                    .unwrap();
             }
             save_postgres_latency.observe();
             tracing::info!("Updated metadata for L1 batch #{l1_batch_number} in Postgres");
@@ -196,6 +216,15 @@ impl TreeUpdater {
     }
 
     /// The processing loop for this updater.
+    async fn repair_tree_data(&self, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+        let repaired_count = self.repair.run(&mut storage, &self.tree.reader()).await?;
+        if repaired_count > 0 {
+            tracing::info!("Backfilled tree data for {repaired_count} L1 batches");
+        }
+        Ok(())
+    }
+
     pub async fn loop_updating_tree(
         mut self,
         delayer: Delayer,
@@ -272,11 +301,28 @@ impl TreeUpdater {
             }
         }
 
+        // After truncation, the tree is not ahead of Postgres, so all L1 batches processed by the tree but missing
+        // tree data in Postgres are followed by batches with tree data (e.g., after a partial revert of Postgres).
+        // Hence, tree data for them can be safely taken from the tree.
+        self.repair_tree_data(pool)
+            .await
+            .context("failed backfilling missing tree data")?;
+        let mut last_repair_at = Instant::now();
+
         loop {
             if *stop_receiver.borrow_and_update() {
                 tracing::info!("Stop signal received, metadata_calculator is shutting down");
                 break;
             }
+            // Tree data may go missing while the node is running as well (e.g., if Postgres is partially reverted),
+            // so the check is repeated periodically. Unlike on startup, errors are not fatal; e.g., L1 may be
+            // temporarily unavailable.
+            if last_repair_at.elapsed() >= REPAIR_INTERVAL {
+                if let Err(err) = self.repair_tree_data(pool).await {
+                    tracing::error!("Failed backfilling missing tree data: {err:#}");
+                }
+                last_repair_at = Instant::now();
+            }
             let storage = pool.access_storage_tagged("metadata_calculator").await?;
 
             let snapshot = *next_l1_batch_to_seal;
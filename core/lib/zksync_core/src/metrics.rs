@@ -87,6 +87,7 @@ impl fmt::Display for BlockStage {
 pub(crate) enum TxStage {
     Mempool(L2TxSubmissionResult),
     Proxied,
+    Forwarded,
     StateKeeper,
     Block(BlockStage),
 }
@@ -100,6 +101,7 @@ impl fmt::Display for TxStage {
             }
             Self::Mempool(result) => write!(formatter, "mempool_{result}"),
             Self::Proxied => formatter.write_str("proxied"),
+            Self::Forwarded => formatter.write_str("forwarded"),
             Self::StateKeeper => formatter.write_str("state_keeper"),
             Self::Block(stage) => fmt::Display::fmt(stage, formatter),
         }
@@ -9,17 +9,20 @@ use axum::{
 use zksync_config::configs::{
     proof_data_handler::ProtocolVersionLoadingMode, ProofDataHandlerConfig,
 };
-use zksync_dal::{ConnectionPool, SqlxError};
+use zksync_dal::{proof_generation_dal::ProverArtifact, ConnectionPool, SqlxError};
 use zksync_object_store::{ObjectStore, ObjectStoreError};
-use zksync_prover_interface::api::{
-    ProofGenerationData, ProofGenerationDataRequest, ProofGenerationDataResponse,
-    SubmitProofRequest, SubmitProofResponse,
+use zksync_prover_interface::{
+    api::{
+        ProofGenerationData, ProofGenerationDataRequest, ProofGenerationDataResponse,
+        SubmitProofRequest, SubmitProofResponse,
+    },
+    outputs::L1BatchProofForL1,
 };
 use zksync_types::{
-    commitment::serialize_commitments,
+    commitment::{serialize_commitments, L1BatchWithMetadata},
     protocol_version::{FriProtocolVersionId, L1VerifierConfig},
     web3::signing::keccak256,
-    L1BatchNumber, H256,
+    L1BatchNumber, ProtocolVersionId, H256,
 };
 use zksync_utils::u256_to_h256;
 
@@ -34,6 +37,11 @@ pub(crate) struct RequestProcessor {
 pub(crate) enum RequestProcessorError {
     ObjectStore(ObjectStoreError),
     Sqlx(SqlxError),
+    /// Artifact loaded from the object store doesn't match the hash recorded when it was saved.
+    ArtifactHashMismatch {
+        l1_batch_number: L1BatchNumber,
+        artifact: ProverArtifact,
+    },
 }
 
 impl IntoResponse for RequestProcessorError {
@@ -58,11 +66,54 @@ impl IntoResponse for RequestProcessorError {
                     ),
                 }
             }
+            RequestProcessorError::ArtifactHashMismatch {
+                l1_batch_number,
+                artifact,
+            } => {
+                tracing::error!(
+                    "Hash of {artifact} for L1 batch #{l1_batch_number} loaded from GCS doesn't match \
+                     the recorded hash; the artifact may have been substituted"
+                );
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Integrity check failed for {artifact} of L1 batch #{l1_batch_number}"),
+                )
+            }
         };
         (status_code, message).into_response()
     }
 }
 
+/// Computes the fingerprint of the proof for `l1_batch`. The proof public input is fully determined by
+/// the commitments of the batch and the previous batch, and the proof is only accepted by L1 if it's generated
+/// with the verification keys from `l1_verifier_config`. Thus, if a batch is reverted and re-sealed with identical
+/// contents and the verifier config is unchanged, its fingerprint doesn't change, and the proof generated
+/// before the revert can be reused.
+fn proof_fingerprint(
+    prev_l1_batch: &L1BatchWithMetadata,
+    l1_batch: &L1BatchWithMetadata,
+    l1_verifier_config: &L1VerifierConfig,
+) -> H256 {
+    let protocol_version = l1_batch
+        .header
+        .protocol_version
+        .map_or(0, |version| version as u16);
+    let verifier_params = &l1_verifier_config.params;
+    let mut bytes = Vec::with_capacity(194);
+    bytes.extend_from_slice(prev_l1_batch.metadata.commitment.as_bytes());
+    bytes.extend_from_slice(l1_batch.metadata.commitment.as_bytes());
+    bytes.extend_from_slice(&protocol_version.to_be_bytes());
+    bytes.extend_from_slice(verifier_params.recursion_node_level_vk_hash.as_bytes());
+    bytes.extend_from_slice(verifier_params.recursion_leaf_level_vk_hash.as_bytes());
+    bytes.extend_from_slice(verifier_params.recursion_circuits_set_vks_hash.as_bytes());
+    bytes.extend_from_slice(
+        l1_verifier_config
+            .recursion_scheduler_level_vk_hash
+            .as_bytes(),
+    );
+    H256(keccak256(&bytes))
+}
+
 impl RequestProcessor {
     pub(crate) fn new(
         blob_store: Arc<dyn ObjectStore>,
@@ -78,31 +129,178 @@ impl RequestProcessor {
         }
     }
 
-    pub(crate) async fn get_proof_generation_data(
+    /// Returns the L1 verifier config used to prove batches with the specified protocol version, or `None`
+    /// if it's not known.
+    async fn l1_verifier_config(
         &self,
-        request: Json<ProofGenerationDataRequest>,
-    ) -> Result<Json<ProofGenerationDataResponse>, RequestProcessorError> {
-        tracing::info!("Received request for proof generation data: {:?}", request);
+        protocol_version: Option<ProtocolVersionId>,
+    ) -> Option<L1VerifierConfig> {
+        match self.config.protocol_version_loading_mode {
+            ProtocolVersionLoadingMode::FromDb => {
+                self.pool
+                    .access_storage()
+                    .await
+                    .unwrap()
+                    .protocol_versions_dal()
+                    .l1_verifier_config_for_version(protocol_version?)
+                    .await
+            }
+            ProtocolVersionLoadingMode::FromEnvVar => self.l1_verifier_config,
+        }
+    }
 
-        let l1_batch_number_result = self
+    /// Checks the hash of an artifact loaded from the object store against the hash recorded when it was saved.
+    /// Artifacts without a recorded hash (e.g., ones saved before hashes were recorded) are not checked.
+    async fn check_artifact_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        artifact: ProverArtifact,
+        hash: H256,
+    ) -> Result<(), RequestProcessorError> {
+        let expected_hash = self
             .pool
             .access_storage()
             .await
             .unwrap()
             .proof_generation_dal()
-            .get_next_block_to_be_proven(self.config.proof_generation_timeout())
+            .get_artifact_hash(l1_batch_number, artifact)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        match expected_hash {
+            Some(expected_hash) if expected_hash != hash => {
+                Err(RequestProcessorError::ArtifactHashMismatch {
+                    l1_batch_number,
+                    artifact,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks whether a proof generated for an L1 batch with identical contents (e.g., before the batch was reverted
+    /// and re-sealed) can be reused for the specified batch. If so, marks proof generation for the batch as finished,
+    /// so that the prover pipeline isn't run for it again.
+    ///
+    /// Errors when checking the proof are logged, but are not propagated; the batch is proven in the usual way instead.
+    async fn try_reuse_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<bool, RequestProcessorError> {
+        let mut storage = self.pool.access_storage().await.unwrap();
+        let Some((fingerprint, proof_blob_url)) = storage
+            .proof_generation_dal()
+            .get_proof_fingerprint(l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?
+        else {
+            return Ok(false); // The batch was never proven
+        };
+
+        let prev_l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number - 1)
             .await;
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(l1_batch_number)
+            .await;
+        let (prev_l1_batch, l1_batch) = match (prev_l1_batch, l1_batch) {
+            (Ok(Some(prev_l1_batch)), Ok(Some(l1_batch))) => (prev_l1_batch, l1_batch),
+            (Err(err), _) | (_, Err(err)) => {
+                tracing::warn!(
+                    "Failed loading metadata to check proof reuse for L1 batch #{l1_batch_number}: {err:#}"
+                );
+                return Ok(false);
+            }
+            _ => {
+                // Commitments aren't computed yet, so the fingerprint cannot be checked.
+                tracing::info!(
+                    "Cannot check proof reuse for L1 batch #{l1_batch_number}: metadata is not computed yet"
+                );
+                return Ok(false);
+            }
+        };
+        let Some(l1_verifier_config) = self
+            .l1_verifier_config(l1_batch.header.protocol_version)
+            .await
+        else {
+            tracing::info!(
+                "Cannot check proof reuse for L1 batch #{l1_batch_number}: L1 verifier config is unknown"
+            );
+            return Ok(false);
+        };
+        if proof_fingerprint(&prev_l1_batch, &l1_batch, &l1_verifier_config) != fingerprint {
+            return Ok(false);
+        }
+
+        // The proof may have been removed by the artifacts retention policy.
+        let proof_hash = match self
+            .blob_store
+            .get_with_hash::<L1BatchProofForL1>(l1_batch_number)
+            .await
+        {
+            Ok((_, hash)) => hash,
+            Err(err) => {
+                tracing::info!(
+                    "Proof for L1 batch #{l1_batch_number} with matching fingerprint cannot be loaded: {err}"
+                );
+                return Ok(false);
+            }
+        };
+        if let Err(RequestProcessorError::ArtifactHashMismatch { .. }) = self
+            .check_artifact_hash(l1_batch_number, ProverArtifact::Proof, proof_hash)
+            .await
+        {
+            tracing::error!(
+                "Proof for L1 batch #{l1_batch_number} with matching fingerprint doesn't match the recorded hash; \
+                 it may have been substituted, so it won't be reused"
+            );
+            return Ok(false);
+        }
+
+        storage
+            .proof_generation_dal()
+            .save_proof_artifacts_metadata(l1_batch_number, &proof_blob_url)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        tracing::info!(
+            "Reused proof for L1 batch #{l1_batch_number} generated for a batch with identical contents"
+        );
+        Ok(true)
+    }
+
+    pub(crate) async fn get_proof_generation_data(
+        &self,
+        request: Json<ProofGenerationDataRequest>,
+    ) -> Result<Json<ProofGenerationDataResponse>, RequestProcessorError> {
+        tracing::info!("Received request for proof generation data: {:?}", request);
+
+        let l1_batch_number = loop {
+            let l1_batch_number_result = self
+                .pool
+                .access_storage()
+                .await
+                .unwrap()
+                .proof_generation_dal()
+                .get_next_block_to_be_proven(self.config.proof_generation_timeout())
+                .await;
 
-        let l1_batch_number = match l1_batch_number_result {
-            Some(number) => number,
-            None => return Ok(Json(ProofGenerationDataResponse::Success(None))), // no batches pending to be proven
+            let l1_batch_number = match l1_batch_number_result {
+                Some(number) => number,
+                None => return Ok(Json(ProofGenerationDataResponse::Success(None))), // no batches pending to be proven
+            };
+            if !self.try_reuse_proof(l1_batch_number).await? {
+                break l1_batch_number;
+            }
         };
 
-        let blob = self
+        let (blob, blob_hash) = self
             .blob_store
-            .get(l1_batch_number)
+            .get_with_hash(l1_batch_number)
             .await
             .map_err(RequestProcessorError::ObjectStore)?;
+        self.check_artifact_hash(l1_batch_number, ProverArtifact::WitnessInput, blob_hash)
+            .await?;
 
         let (l1_verifier_config, fri_protocol_version_id) = match self.config.protocol_version_loading_mode {
             ProtocolVersionLoadingMode::FromDb => {
@@ -164,9 +362,9 @@ impl RequestProcessor {
         let l1_batch_number = L1BatchNumber(l1_batch_number);
         match payload {
             SubmitProofRequest::Proof(proof) => {
-                let blob_url = self
+                let (blob_url, proof_hash) = self
                     .blob_store
-                    .put(l1_batch_number, &*proof)
+                    .put_with_hash(l1_batch_number, &*proof)
                     .await
                     .map_err(RequestProcessorError::ObjectStore)?;
 
@@ -223,7 +421,7 @@ impl RequestProcessor {
                     let state_diff_hash = l1_batch
                         .header
                         .system_logs
-                        .into_iter()
+                        .iter()
                         .find(|elem| elem.0.key == u256_to_h256(2.into()))
                         .expect("No state diff hash key")
                         .0
@@ -240,11 +438,37 @@ impl RequestProcessor {
                         );
                     }
                 }
-                storage
+                let prev_l1_batch = storage
+                    .blocks_dal()
+                    .get_l1_batch_metadata(l1_batch_number - 1)
+                    .await
+                    .unwrap();
+                let l1_verifier_config = self
+                    .l1_verifier_config(l1_batch.header.protocol_version)
+                    .await;
+                let mut transaction = storage.start_transaction().await.unwrap();
+                transaction
                     .proof_generation_dal()
                     .save_proof_artifacts_metadata(l1_batch_number, &blob_url)
                     .await
                     .map_err(RequestProcessorError::Sqlx)?;
+                transaction
+                    .proof_generation_dal()
+                    .save_artifact_hash(l1_batch_number, ProverArtifact::Proof, proof_hash)
+                    .await
+                    .map_err(RequestProcessorError::Sqlx)?;
+                if let (Some(prev_l1_batch), Some(l1_verifier_config)) =
+                    (prev_l1_batch, l1_verifier_config)
+                {
+                    let fingerprint =
+                        proof_fingerprint(&prev_l1_batch, &l1_batch, &l1_verifier_config);
+                    transaction
+                        .proof_generation_dal()
+                        .save_proof_fingerprint(l1_batch_number, fingerprint, &blob_url)
+                        .await
+                        .map_err(RequestProcessorError::Sqlx)?;
+                }
+                transaction.commit().await.unwrap();
             }
             SubmitProofRequest::SkippedProofGeneration => {
                 self.pool
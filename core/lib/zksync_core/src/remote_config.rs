@@ -0,0 +1,544 @@
+//! Support of a remote config service allowing to toggle node components and tune selected parameters
+//! without changing the local configuration.
+//!
+//! The service is expected to return a JSON envelope with a signed payload:
+//!
+//! ```json
+//! { "payload": "{\"version\":3,\"components\":{\"housekeeper\":false}}", "signature": "0x..." }
+//! ```
+//!
+//! The signature is an Ethereum signature over `keccak256(payload)`; payloads not signed by the configured
+//! signer are rejected. Any HTTP-accessible KV store (e.g., etcd gRPC gateway or Consul KV with `?raw`) can serve
+//! the envelope.
+//!
+//! The signed payload must include a version. Applied versions are recorded in the audit log, and configs
+//! with a version lower than the last applied one are rejected, so that a previously signed config cannot be
+//! replayed to roll back the node configuration.
+
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use anyhow::Context as _;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use tokio::sync::watch;
+use zksync_config::configs::RemoteConfigServiceConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    audit_log::AuditAction, tx::primitives::PackedEthSignature, web3::types::Bytes, Address,
+};
+
+use crate::{
+    audit_log::AuditLogger, state_keeper::MempoolGuard, temp_config_store::TempConfigStore,
+    Component, Components,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize)]
+struct SignedPayload {
+    payload: String,
+    signature: Bytes,
+}
+
+/// Config fetched from the remote config service.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct RemoteConfig {
+    /// Version of the config. Runtime updates are only accepted if their version is greater than the current one.
+    /// On startup, the version must not be lower than the last applied version recorded in the audit log.
+    pub version: u64,
+    /// Component toggles keyed by component names as accepted by the `--components` CLI arg
+    /// (e.g., `eth` or `housekeeper`). `true` enables components, `false` disables them.
+    #[serde(default)]
+    pub components: HashMap<String, bool>,
+    /// Values of the parameters overriding the local config. See [`Self::apply_to_configs()`]
+    /// for the list of supported parameters.
+    #[serde(default)]
+    pub parameters: HashMap<String, serde_json::Value>,
+}
+
+impl RemoteConfig {
+    /// Applies component toggles to the provided list of components.
+    pub fn apply_component_toggles(&self, components: &mut Vec<Component>) -> anyhow::Result<()> {
+        // Sort toggles so that the result doesn't depend on the `HashMap` iteration order.
+        let mut toggles: Vec<_> = self.components.iter().collect();
+        toggles.sort_unstable();
+        for (name, &enabled) in toggles {
+            let toggled = Components::from_str(name)
+                .map_err(|err| anyhow::anyhow!("invalid component toggle: {err}"))?
+                .0;
+            if enabled {
+                for component in toggled {
+                    if !components.contains(&component) {
+                        components.push(component);
+                    }
+                }
+            } else {
+                components.retain(|component| !toggled.contains(component));
+            }
+        }
+        Ok(())
+    }
+
+    fn audit_log_params(&self, prev_version: Option<u64>) -> serde_json::Value {
+        json!({
+            "prev_version": prev_version,
+            "version": self.version,
+            "components": self.components,
+            "parameters": self.parameters,
+        })
+    }
+
+    /// Returns the value of the specified parameter, or `None` if it is not set.
+    pub fn parameter<T: DeserializeOwned>(&self, name: &str) -> anyhow::Result<Option<T>> {
+        self.parameters
+            .get(name)
+            .map(|value| serde_json::from_value(value.clone()))
+            .transpose()
+            .with_context(|| format!("invalid value for parameter `{name}`"))
+    }
+
+    /// Overrides the local config with the parameters provided by the remote config.
+    /// Unknown parameters are logged and ignored.
+    ///
+    /// Of the supported parameters, only `mempool.capacity` is updated at runtime (see [`update_mempool_capacity()`]);
+    /// changes in other parameters require a node restart.
+    pub fn apply_to_configs(&self, configs: &mut TempConfigStore) -> anyhow::Result<()> {
+        for name in self.parameters.keys() {
+            match name.as_str() {
+                "state_keeper.transaction_slots" => {
+                    let config = configs.state_keeper_config.as_mut();
+                    if let (Some(config), Some(value)) = (config, self.parameter(name)?) {
+                        config.transaction_slots = value;
+                    }
+                }
+                "state_keeper.block_commit_deadline_ms" => {
+                    let config = configs.state_keeper_config.as_mut();
+                    if let (Some(config), Some(value)) = (config, self.parameter(name)?) {
+                        config.block_commit_deadline_ms = value;
+                    }
+                }
+                "mempool.capacity" => {
+                    let config = configs.mempool_config.as_mut();
+                    if let (Some(config), Some(value)) = (config, self.parameter(name)?) {
+                        config.capacity = value;
+                    }
+                }
+                "api.web3_json_rpc.req_entities_limit" => {
+                    let config = configs.web3_json_rpc_config.as_mut();
+                    if let Some(config) = config {
+                        config.req_entities_limit = self.parameter(name)?;
+                    }
+                }
+                _ => {
+                    tracing::warn!("Unknown parameter `{name}` in the remote config; ignoring");
+                    continue;
+                }
+            }
+            tracing::info!("Parameter `{name}` is overridden by the remote config");
+        }
+        Ok(())
+    }
+}
+
+/// Client for the remote config service.
+#[derive(Debug)]
+pub struct RemoteConfigClient {
+    client: reqwest::Client,
+    url: String,
+    signer_address: Address,
+}
+
+impl RemoteConfigClient {
+    pub fn new(config: &RemoteConfigServiceConfig) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("failed building HTTP client")?;
+        Ok(Self {
+            client,
+            url: config.url.clone(),
+            signer_address: config.signer_address,
+        })
+    }
+
+    fn audit_log_actor(&self) -> String {
+        format!("remote_config:{:?}", self.signer_address)
+    }
+
+    /// Fetches the config on node startup and checks it against the last applied version recorded in the audit log.
+    /// If the config has a newer version and `record` is set, it is recorded as applied.
+    pub async fn fetch_on_startup(
+        &self,
+        pool: &ConnectionPool,
+        record: bool,
+    ) -> anyhow::Result<RemoteConfig> {
+        let config = self.fetch().await?;
+        self.check_version(pool, &config, record).await?;
+        Ok(config)
+    }
+
+    async fn check_version(
+        &self,
+        pool: &ConnectionPool,
+        config: &RemoteConfig,
+        record: bool,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage_tagged("remote_config").await?;
+        let last_entry = storage
+            .audit_log_dal()
+            .get_last_entry(AuditAction::ConfigReload)
+            .await
+            .context("failed getting last remote config version")?;
+        drop(storage);
+
+        let last_version = last_entry
+            .map(|entry| {
+                serde_json::from_value::<u64>(entry.params["version"].clone())
+                    .context("malformed remote config version in audit log")
+            })
+            .transpose()?;
+        if let Some(last_version) = last_version {
+            anyhow::ensure!(
+                config.version >= last_version,
+                "remote config has version {}, which is lower than the last applied version {last_version}; \
+                 this may be a replay of an outdated config",
+                config.version
+            );
+        }
+        if record && last_version.map_or(true, |last_version| config.version > last_version) {
+            let audit_log = AuditLogger::new(pool.clone(), self.audit_log_actor());
+            audit_log
+                .record(
+                    AuditAction::ConfigReload,
+                    config.audit_log_params(last_version),
+                )
+                .await
+                .context("failed recording remote config in audit log")?;
+        }
+        Ok(())
+    }
+
+    /// Fetches the config and verifies its signature.
+    pub async fn fetch(&self) -> anyhow::Result<RemoteConfig> {
+        let signed_payload: SignedPayload = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .context("failed requesting remote config")?
+            .error_for_status()
+            .context("remote config service returned an error")?
+            .json()
+            .await
+            .context("failed deserializing signed remote config")?;
+        verify_payload(&signed_payload, self.signer_address)
+    }
+}
+
+fn verify_payload(
+    signed_payload: &SignedPayload,
+    signer_address: Address,
+) -> anyhow::Result<RemoteConfig> {
+    let signature = PackedEthSignature::deserialize_packed(&signed_payload.signature.0)
+        .context("malformed remote config signature")?;
+    let signed_bytes =
+        PackedEthSignature::message_to_signed_bytes(signed_payload.payload.as_bytes());
+    let signer = signature
+        .signature_recover_signer(&signed_bytes)
+        .context("cannot recover remote config signer")?;
+    anyhow::ensure!(
+        signer == signer_address,
+        "remote config is signed by {signer:?}, while {signer_address:?} is expected"
+    );
+    serde_json::from_str(&signed_payload.payload).context("failed deserializing remote config")
+}
+
+/// Periodically polls the remote config service and publishes config updates for hot-reloadable settings
+/// to the receivers returned by [`Self::subscribe()`]. Changes in component toggles are not applied at runtime
+/// and require a node restart.
+#[derive(Debug)]
+pub struct RemoteConfigWatcher {
+    client: RemoteConfigClient,
+    poll_interval: Duration,
+    sender: watch::Sender<RemoteConfig>,
+    audit_log: Option<AuditLogger>,
+}
+
+impl RemoteConfigWatcher {
+    pub fn new(client: RemoteConfigClient, initial: RemoteConfig, poll_interval: Duration) -> Self {
+        Self {
+            client,
+            poll_interval,
+            sender: watch::channel(initial).0,
+            audit_log: None,
+        }
+    }
+
+    /// Records applied config updates in the audit log. The signer of the config is recorded as the actor.
+    pub fn with_audit_log(mut self, pool: ConnectionPool) -> Self {
+        let actor = self.client.audit_log_actor();
+        self.audit_log = Some(AuditLogger::new(pool, actor));
+        self
+    }
+
+    /// Subscribes to remote config updates.
+    pub fn subscribe(&self) -> watch::Receiver<RemoteConfig> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the version of the previous config if the update was applied.
+    fn update(&self, new_config: RemoteConfig) -> Option<u64> {
+        let current = self.sender.borrow();
+        if new_config.version <= current.version {
+            if new_config != *current {
+                tracing::warn!(
+                    "Remote config changed without increasing version ({})",
+                    current.version
+                );
+            }
+            return None;
+        }
+        if new_config.components != current.components {
+            tracing::warn!(
+                "Component toggles in the remote config have changed; the node must be restarted to apply them"
+            );
+        }
+        tracing::info!(
+            "Remote config updated from version {} to {}",
+            current.version,
+            new_config.version
+        );
+        let prev_version = current.version;
+        drop(current);
+        self.sender.send_replace(new_config);
+        Some(prev_version)
+    }
+
+    async fn record_update(&self, prev_version: u64) {
+        let Some(audit_log) = &self.audit_log else {
+            return;
+        };
+        let params = self.sender.borrow().audit_log_params(Some(prev_version));
+        if let Err(err) = audit_log.record(AuditAction::ConfigReload, params).await {
+            tracing::error!("Failed recording remote config update in audit log: {err:#}");
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, remote config watcher is shutting down");
+                break;
+            }
+
+            match self.client.fetch().await {
+                Ok(config) => {
+                    if let Some(prev_version) = self.update(config) {
+                        self.record_update(prev_version).await;
+                    }
+                }
+                Err(err) => tracing::warn!("Failed fetching remote config: {err:#}"),
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+}
+
+/// Applies `mempool.capacity` updates published by [`RemoteConfigWatcher`] to the mempool. If the parameter
+/// is removed from the remote config, the capacity is reset to `default_capacity`.
+pub async fn update_mempool_capacity(
+    mut updates: watch::Receiver<RemoteConfig>,
+    mempool: MempoolGuard,
+    default_capacity: u64,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    loop {
+        tokio::select! {
+            res = updates.changed() => {
+                if res.is_err() {
+                    // The watcher has stopped; wait for the stop signal so that the task doesn't finish prematurely.
+                    stop_receiver.changed().await.ok();
+                    break;
+                }
+            }
+            _ = stop_receiver.changed() => break,
+        }
+
+        let capacity = updates.borrow().parameter::<u64>("mempool.capacity");
+        match capacity {
+            Ok(capacity) => {
+                let capacity = capacity.unwrap_or(default_capacity);
+                tracing::info!("Setting mempool capacity to {capacity} after remote config update");
+                mempool.set_capacity(capacity);
+            }
+            Err(err) => tracing::warn!("Cannot apply remote config update: {err:#}"),
+        }
+    }
+    tracing::info!("Stop signal received, mempool capacity updater is shutting down");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{PriorityOpId, H256};
+
+    use super::*;
+
+    fn sign_payload(payload: &str, private_key: &H256) -> SignedPayload {
+        let signed_bytes = PackedEthSignature::message_to_signed_bytes(payload.as_bytes());
+        let signature = PackedEthSignature::sign_raw(private_key, &signed_bytes).unwrap();
+        SignedPayload {
+            payload: payload.to_owned(),
+            signature: Bytes(signature.serialize_packed().to_vec()),
+        }
+    }
+
+    #[test]
+    fn verifying_payload_signature() {
+        let private_key = H256::repeat_byte(0x11);
+        let signer_address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let payload = r#"{ "version": 1, "components": { "housekeeper": false } }"#;
+        let signed_payload = sign_payload(payload, &private_key);
+
+        let config = verify_payload(&signed_payload, signer_address).unwrap();
+        assert_eq!(config.version, 1);
+        assert_eq!(
+            config.components,
+            HashMap::from([("housekeeper".to_owned(), false)])
+        );
+
+        let err = verify_payload(&signed_payload, Address::repeat_byte(1)).unwrap_err();
+        assert!(err.to_string().contains("signed by"), "{err}");
+
+        let mut tampered_payload = signed_payload;
+        tampered_payload.payload = r#"{ "version": 2 }"#.to_owned();
+        verify_payload(&tampered_payload, signer_address).unwrap_err();
+    }
+
+    #[test]
+    fn applying_component_toggles() {
+        let config = RemoteConfig {
+            version: 1,
+            components: HashMap::from([
+                ("eth_tx_manager".to_owned(), false),
+                ("commitment_generator".to_owned(), true),
+                ("housekeeper".to_owned(), true),
+            ]),
+            parameters: HashMap::new(),
+        };
+        let mut components = vec![
+            Component::StateKeeper,
+            Component::EthTxAggregator,
+            Component::EthTxManager,
+            Component::Housekeeper,
+        ];
+        config.apply_component_toggles(&mut components).unwrap();
+        assert_eq!(
+            components,
+            [
+                Component::StateKeeper,
+                Component::EthTxAggregator,
+                Component::Housekeeper,
+                Component::CommitmentGenerator,
+            ]
+        );
+
+        let invalid_config = RemoteConfig {
+            components: HashMap::from([("unknown".to_owned(), true)]),
+            ..RemoteConfig::default()
+        };
+        invalid_config
+            .apply_component_toggles(&mut components)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn getting_parameters() {
+        let config: RemoteConfig = serde_json::from_str(
+            r#"{ "version": 1, "parameters": { "mempool.capacity": 100000 } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.parameter::<u64>("mempool.capacity").unwrap(),
+            Some(100_000)
+        );
+        assert_eq!(config.parameter::<u64>("unknown").unwrap(), None);
+        config.parameter::<String>("mempool.capacity").unwrap_err();
+    }
+
+    fn test_client(signer_address: Address) -> RemoteConfigClient {
+        let config = RemoteConfigServiceConfig {
+            url: "http://127.0.0.1:8500/config".to_owned(),
+            signer_address,
+            poll_interval_secs: None,
+            required: true,
+        };
+        RemoteConfigClient::new(&config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn outdated_configs_are_rejected_on_startup() {
+        let pool = ConnectionPool::test_pool().await;
+        let client = test_client(Address::repeat_byte(1));
+        let config = RemoteConfig {
+            version: 3,
+            ..RemoteConfig::default()
+        };
+        client.check_version(&pool, &config, true).await.unwrap();
+        // Re-applying the same version is allowed (e.g., on node restart), but isn't recorded.
+        client.check_version(&pool, &config, true).await.unwrap();
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let entries = storage.audit_log_dal().get_entries(0, 10).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::ConfigReload);
+        assert_eq!(entries[0].params["version"], 3);
+
+        let outdated_config = RemoteConfig {
+            version: 2,
+            ..RemoteConfig::default()
+        };
+        let err = client
+            .check_version(&pool, &outdated_config, true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("lower than"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn updating_mempool_capacity() {
+        let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let (updates_sender, updates) = watch::channel(RemoteConfig::default());
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let updater = tokio::spawn(update_mempool_capacity(
+            updates,
+            mempool.clone(),
+            100,
+            stop_receiver,
+        ));
+
+        updates_sender.send_replace(
+            serde_json::from_str(r#"{ "version": 1, "parameters": { "mempool.capacity": 5 } }"#)
+                .unwrap(),
+        );
+        wait_for_capacity(&mempool, 5).await;
+        updates_sender.send_replace(RemoteConfig {
+            version: 2,
+            ..RemoteConfig::default()
+        });
+        wait_for_capacity(&mempool, 100).await;
+
+        stop_sender.send_replace(true);
+        updater.await.unwrap().unwrap();
+    }
+
+    async fn wait_for_capacity(mempool: &MempoolGuard, expected: u64) {
+        while mempool.capacity() != expected {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Metrics for the shadow sequencer.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+
+/// Kind of divergence between the main state keeper and the shadow sequencer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(super) enum DivergenceKind {
+    /// Transaction execution status (success or failure).
+    TxStatus,
+    /// Amount of gas refunded for a transaction.
+    RefundedGas,
+    /// Storage slots written in the L1 batch and their values.
+    StorageWrites,
+    /// User L2-to-L1 logs emitted in the L1 batch.
+    L2ToL1Logs,
+}
+
+impl DivergenceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TxStatus => "tx_status",
+            Self::RefundedGas => "refunded_gas",
+            Self::StorageWrites => "storage_writes",
+            Self::L2ToL1Logs => "l2_to_l1_logs",
+        }
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_shadow_sequencer")]
+pub(super) struct ShadowSequencerMetrics {
+    /// Latency of executing a single miniblock.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub miniblock_execution_latency: Histogram<Duration>,
+    /// Number of divergences from the main state keeper.
+    pub divergences: Family<DivergenceKind, Counter>,
+    /// Number of miniblocks with diverged transaction outcomes.
+    pub diverged_miniblocks: Counter,
+    /// Number of L1 batches with at least one divergence.
+    pub diverged_l1_batches: Counter,
+    /// Number of L1 batches that the shadow sequencer has failed to execute.
+    pub failed_l1_batches: Counter,
+    /// Last L1 batch processed by the shadow sequencer.
+    pub last_processed_l1_batch: Gauge<u64>,
+    /// Last miniblock executed by the shadow sequencer.
+    pub last_executed_miniblock: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<ShadowSequencerMetrics> = vise::Global::new();
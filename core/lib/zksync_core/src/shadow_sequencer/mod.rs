@@ -0,0 +1,434 @@
+//! Shadow sequencer executing miniblocks sealed by the main state keeper in step with it using a candidate VM /
+//! protocol version, and reporting divergences from the persisted execution results. Allows to canary sequencer
+//! upgrades against live traffic.
+
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Context as _;
+use multivm::interface::{L2BlockEnv, VmInterface};
+use tokio::{runtime::Handle, sync::watch};
+use vm_utils::{
+    create_vm, create_vm_with_protocol_version, execute_tx, storage::L1BatchParamsProvider,
+};
+use zksync_dal::{transactions_dal::TxExecutionOutcome, ConnectionPool};
+use zksync_types::{
+    l2_to_l1_log::UserL2ToL1Log, storage_writes_deduplicator::StorageWritesDeduplicator,
+    tx::tx_execution_info::TxExecutionStatus, L1BatchNumber, L2ChainId, MiniblockNumber,
+    ProtocolVersionId, StorageKey, H256,
+};
+use zksync_utils::u256_to_h256;
+
+use self::metrics::{DivergenceKind, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Outcome of waiting for the main state keeper to make progress after a miniblock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NextMiniblock {
+    /// The next miniblock in the same L1 batch is sealed.
+    InBatch,
+    /// The L1 batch is sealed, and the awaited miniblock is the last one in it.
+    BatchSealed,
+    /// Stop signal was received.
+    Stopped,
+}
+
+/// Returns divergences of `shadow` transaction outcomes from the `reference` ones.
+fn tx_divergences(
+    reference: &[TxExecutionOutcome],
+    shadow: &[TxExecutionOutcome],
+) -> Vec<DivergenceKind> {
+    let mut divergences = vec![];
+    if reference.len() != shadow.len()
+        || reference
+            .iter()
+            .zip(shadow)
+            .any(|(outcome, shadow_outcome)| {
+                outcome.hash != shadow_outcome.hash || outcome.status != shadow_outcome.status
+            })
+    {
+        divergences.push(DivergenceKind::TxStatus);
+    }
+    if reference
+        .iter()
+        .zip(shadow)
+        .any(|(outcome, shadow_outcome)| outcome.refunded_gas != shadow_outcome.refunded_gas)
+    {
+        divergences.push(DivergenceKind::RefundedGas);
+    }
+    divergences
+}
+
+/// Results of executing an L1 batch, either by the main state keeper (loaded from Postgres),
+/// or by the shadow sequencer.
+#[derive(Debug, Default)]
+struct BatchExecution {
+    tx_outcomes: Vec<TxExecutionOutcome>,
+    /// Latest values of the written storage slots.
+    storage_writes: HashMap<StorageKey, H256>,
+    user_l2_to_l1_logs: Vec<UserL2ToL1Log>,
+}
+
+impl BatchExecution {
+    fn extend_storage_writes(&mut self, deduplicator: StorageWritesDeduplicator) {
+        let modified_slots = deduplicator.into_modified_key_values();
+        self.storage_writes.extend(
+            modified_slots
+                .into_iter()
+                .map(|(key, slot)| (key, u256_to_h256(slot.value))),
+        );
+    }
+
+    /// Returns divergences of `shadow` execution from this (reference) execution.
+    fn divergences(&self, shadow: &Self) -> Vec<DivergenceKind> {
+        let mut divergences = tx_divergences(&self.tx_outcomes, &shadow.tx_outcomes);
+        if self.storage_writes != shadow.storage_writes {
+            divergences.push(DivergenceKind::StorageWrites);
+        }
+        if self.user_l2_to_l1_logs != shadow.user_l2_to_l1_logs {
+            divergences.push(DivergenceKind::L2ToL1Logs);
+        }
+        divergences
+    }
+}
+
+/// Shadow sequencer. Follows miniblocks sealed by the main state keeper and executes them in step with it
+/// with the candidate protocol version: each miniblock is executed as soon as it's sealed, and the VM is kept
+/// across miniblocks until the L1 batch is sealed, similarly to the main state keeper. Transaction outcomes
+/// are compared with the persisted ones after each miniblock; storage writes and L2-to-L1 logs are compared once
+/// the L1 batch is sealed. Divergences are reported via metrics and logs, and are persisted together with
+/// the candidate protocol version for each L1 batch, so that the shadow sequencer resumes after a restart.
+///
+/// Besides its own results, the shadow sequencer never modifies Postgres, so it's safe to run alongside the main node.
+#[derive(Debug, Clone)]
+pub struct ShadowSequencer {
+    pool: ConnectionPool,
+    l2_chain_id: L2ChainId,
+    protocol_version: Option<ProtocolVersionId>,
+    poll_interval: Duration,
+}
+
+impl ShadowSequencer {
+    /// Creates a new shadow sequencer. If `protocol_version` is not specified, batches are executed with
+    /// the protocol version they were originally executed with. The pool must have at least 2 connections:
+    /// one is held by the VM storage for the duration of an L1 batch.
+    pub fn new(
+        pool: ConnectionPool,
+        l2_chain_id: L2ChainId,
+        protocol_version: Option<ProtocolVersionId>,
+    ) -> Self {
+        Self {
+            pool,
+            l2_chain_id,
+            protocol_version,
+            poll_interval: Duration::from_millis(100),
+        }
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("shadow_sequencer").await?;
+        let last_processed_l1_batch = storage
+            .shadow_sequencer_dal()
+            .get_last_processed_l1_batch()
+            .await?;
+        let mut next_l1_batch = match last_processed_l1_batch {
+            Some(number) => number + 1,
+            // Start from the L1 batch currently executed by the main state keeper.
+            None => storage
+                .blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await?
+                .map_or(L1BatchNumber(1), |number| number + 1),
+        };
+        drop(storage);
+        tracing::info!(
+            "Starting shadow sequencer from L1 batch #{next_l1_batch} with protocol version {:?}",
+            self.protocol_version
+        );
+
+        while !*stop_receiver.borrow() {
+            let (divergences, error) = match self
+                .process_l1_batch(next_l1_batch, stop_receiver.clone())
+                .await
+            {
+                Ok(Some(divergences)) => (divergences, None),
+                Ok(None) => break,
+                Err(err) => {
+                    // Errors are not propagated since the shadow sequencer shouldn't affect the main node.
+                    tracing::warn!("Failed executing L1 batch #{next_l1_batch}: {err:#}");
+                    METRICS.failed_l1_batches.inc();
+                    (vec![], Some(format!("{err:#}")))
+                }
+            };
+
+            let divergences: Vec<_> = divergences
+                .into_iter()
+                .map(|kind| kind.as_str().to_owned())
+                .collect();
+            let mut storage = self.pool.access_storage_tagged("shadow_sequencer").await?;
+            storage
+                .shadow_sequencer_dal()
+                .insert_l1_batch_result(
+                    next_l1_batch,
+                    self.protocol_version,
+                    &divergences,
+                    error.as_deref(),
+                )
+                .await?;
+            drop(storage);
+            METRICS.last_processed_l1_batch.set(next_l1_batch.0.into());
+            next_l1_batch += 1;
+        }
+        tracing::info!("Stop signal received, shadow sequencer is shutting down");
+        Ok(())
+    }
+
+    /// Executes the specified L1 batch in step with the main state keeper and returns divergences from it.
+    /// Returns `None` if the stop signal was received before the L1 batch was sealed.
+    async fn process_l1_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<Vec<DivergenceKind>>> {
+        let this = self.clone();
+        let shadow_execution = tokio::task::spawn_blocking(move || {
+            this.execute_l1_batch(Handle::current(), l1_batch_number, stop_receiver)
+        })
+        .await
+        .context("L1 batch execution panicked")??;
+        let Some(shadow_execution) = shadow_execution else {
+            return Ok(None);
+        };
+
+        let reference_execution = self.load_l1_batch_execution(l1_batch_number).await?;
+        let divergences = reference_execution.divergences(&shadow_execution);
+        if divergences.is_empty() {
+            tracing::debug!("Executed L1 batch #{l1_batch_number} without divergences");
+        } else {
+            tracing::warn!(
+                "Executing L1 batch #{l1_batch_number} diverged from the main state keeper: {divergences:?}"
+            );
+            METRICS.diverged_l1_batches.inc();
+            for &kind in &divergences {
+                METRICS.divergences[&kind].inc();
+            }
+        }
+        Ok(Some(divergences))
+    }
+
+    fn execute_l1_batch(
+        &self,
+        rt_handle: Handle,
+        l1_batch_number: L1BatchNumber,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<BatchExecution>> {
+        let first_miniblock = rt_handle
+            .block_on(self.wait_for_first_miniblock(l1_batch_number, &mut stop_receiver))?;
+        let Some(first_miniblock) = first_miniblock else {
+            return Ok(None);
+        };
+
+        let connection = rt_handle.block_on(self.pool.access_storage_tagged("shadow_sequencer"))?;
+        let (mut vm, _) = if let Some(protocol_version) = self.protocol_version {
+            create_vm_with_protocol_version(
+                rt_handle.clone(),
+                l1_batch_number,
+                connection,
+                self.l2_chain_id,
+                protocol_version,
+            )
+        } else {
+            create_vm(
+                rt_handle.clone(),
+                l1_batch_number,
+                connection,
+                self.l2_chain_id,
+            )
+        }
+        .context("failed creating VM")?;
+
+        let mut execution = BatchExecution::default();
+        let mut miniblock_number = first_miniblock;
+        loop {
+            let latency = METRICS.miniblock_execution_latency.start();
+            let mut storage =
+                rt_handle.block_on(self.pool.access_storage_tagged("shadow_sequencer"))?;
+            let miniblock_data = rt_handle
+                .block_on(
+                    storage
+                        .transactions_dal()
+                        .get_miniblock_to_execute(miniblock_number),
+                )?
+                .with_context(|| {
+                    format!("miniblock #{miniblock_number} disappeared from Postgres")
+                })?;
+            let reference_outcomes = rt_handle.block_on(
+                storage
+                    .transactions_dal()
+                    .get_execution_outcomes_for_miniblock(miniblock_number),
+            )?;
+            drop(storage);
+
+            // The first miniblock is started when the VM is created.
+            if miniblock_number != first_miniblock {
+                vm.start_new_l2_block(L2BlockEnv::from_miniblock_data(&miniblock_data));
+            }
+            // Storage writes are deduplicated per miniblock, similarly to how the state keeper persists them.
+            let mut storage_writes_deduplicator = StorageWritesDeduplicator::new();
+            let mut tx_outcomes = Vec::with_capacity(miniblock_data.txs.len());
+            for tx in &miniblock_data.txs {
+                let result = execute_tx(tx, &mut vm)
+                    .with_context(|| format!("failed executing transaction {:?}", tx.hash()))?;
+                storage_writes_deduplicator.apply(
+                    result
+                        .logs
+                        .storage_logs
+                        .iter()
+                        .filter(|log| log.log_query.rw_flag),
+                );
+                tx_outcomes.push(TxExecutionOutcome {
+                    hash: tx.hash(),
+                    status: if result.result.is_failed() {
+                        TxExecutionStatus::Failure
+                    } else {
+                        TxExecutionStatus::Success
+                    },
+                    refunded_gas: result.refunds.gas_refunded.into(),
+                });
+            }
+            execution.extend_storage_writes(storage_writes_deduplicator);
+            latency.observe();
+
+            // Transaction outcomes are compared right away rather than after the L1 batch is sealed.
+            let divergences = tx_divergences(&reference_outcomes, &tx_outcomes);
+            if !divergences.is_empty() {
+                tracing::warn!(
+                    "Executing miniblock #{miniblock_number} diverged from the main state keeper: {divergences:?}"
+                );
+                METRICS.diverged_miniblocks.inc();
+            }
+            execution.tx_outcomes.extend(tx_outcomes);
+            METRICS
+                .last_executed_miniblock
+                .set(miniblock_number.0.into());
+
+            let next_miniblock = rt_handle.block_on(self.wait_for_next_miniblock(
+                l1_batch_number,
+                miniblock_number,
+                &mut stop_receiver,
+            ))?;
+            match next_miniblock {
+                NextMiniblock::InBatch => miniblock_number += 1,
+                NextMiniblock::BatchSealed => break,
+                NextMiniblock::Stopped => return Ok(None),
+            }
+        }
+
+        let finished_batch = vm.finish_batch();
+        let mut storage_writes_deduplicator = StorageWritesDeduplicator::new();
+        storage_writes_deduplicator.apply(
+            finished_batch
+                .block_tip_execution_result
+                .logs
+                .storage_logs
+                .iter()
+                .filter(|log| log.log_query.rw_flag),
+        );
+        execution.extend_storage_writes(storage_writes_deduplicator);
+        execution.user_l2_to_l1_logs = finished_batch.final_execution_state.user_l2_to_l1_logs;
+        Ok(Some(execution))
+    }
+
+    /// Waits until the first miniblock in the specified L1 batch is sealed and returns its number.
+    async fn wait_for_first_miniblock(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<MiniblockNumber>> {
+        let mut storage = self.pool.access_storage_tagged("shadow_sequencer").await?;
+        let params_provider = L1BatchParamsProvider::new(&mut storage).await?;
+        drop(storage);
+        while !*stop_receiver.borrow() {
+            let mut storage = self.pool.access_storage_tagged("shadow_sequencer").await?;
+            let first_miniblock = params_provider
+                .load_first_miniblock_in_batch(&mut storage, l1_batch_number)
+                .await?;
+            drop(storage);
+            if let Some(first_miniblock) = first_miniblock {
+                return Ok(Some(first_miniblock.number()));
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(None)
+    }
+
+    /// Waits until the main state keeper seals either the next miniblock in the L1 batch, or the L1 batch itself.
+    async fn wait_for_next_miniblock(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<NextMiniblock> {
+        while !*stop_receiver.borrow() {
+            let mut storage = self.pool.access_storage_tagged("shadow_sequencer").await?;
+            // The sealed miniblock must be loaded before checking the L1 batch: if the L1 batch is not sealed
+            // afterwards, the loaded miniblock is guaranteed to belong to it.
+            let sealed_miniblock = storage.blocks_dal().get_sealed_miniblock_number().await?;
+            let l1_batch_range = storage
+                .blocks_dal()
+                .get_miniblock_range_of_l1_batch(l1_batch_number)
+                .await?;
+            drop(storage);
+
+            if let Some((_, last_miniblock)) = l1_batch_range {
+                anyhow::ensure!(
+                    last_miniblock >= miniblock_number,
+                    "L1 batch #{l1_batch_number} was sealed with last miniblock #{last_miniblock}, \
+                     but miniblock #{miniblock_number} was executed in it"
+                );
+                return Ok(if last_miniblock == miniblock_number {
+                    NextMiniblock::BatchSealed
+                } else {
+                    NextMiniblock::InBatch
+                });
+            }
+            if sealed_miniblock.map_or(false, |number| number > miniblock_number) {
+                return Ok(NextMiniblock::InBatch);
+            }
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(NextMiniblock::Stopped)
+    }
+
+    async fn load_l1_batch_execution(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<BatchExecution> {
+        let mut storage = self.pool.access_storage_tagged("shadow_sequencer").await?;
+        let tx_outcomes = storage
+            .transactions_dal()
+            .get_execution_outcomes_for_l1_batch(l1_batch_number)
+            .await?;
+        let storage_writes = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_l1_batch(l1_batch_number)
+            .await?;
+        let header = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} disappeared from Postgres"))?;
+        Ok(BatchExecution {
+            tx_outcomes,
+            storage_writes,
+            user_l2_to_l1_logs: header.l2_to_l1_logs,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use zksync_types::{l2_to_l1_log::L2ToL1Log, AccountTreeId, Address};
+
+use super::*;
+
+fn mock_execution() -> BatchExecution {
+    let tx_outcomes = (0_u64..3)
+        .map(|i| TxExecutionOutcome {
+            hash: H256::from_low_u64_be(i),
+            status: TxExecutionStatus::Success,
+            refunded_gas: 1_000 * i,
+        })
+        .collect();
+    let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+    BatchExecution {
+        tx_outcomes,
+        storage_writes: HashMap::from([(key, H256::repeat_byte(0xff))]),
+        user_l2_to_l1_logs: vec![UserL2ToL1Log(L2ToL1Log::default())],
+    }
+}
+
+#[test]
+fn no_divergences_for_identical_executions() {
+    assert_eq!(mock_execution().divergences(&mock_execution()), []);
+}
+
+#[test]
+fn detecting_divergences() {
+    let reference = mock_execution();
+
+    let mut shadow = mock_execution();
+    shadow.tx_outcomes[1].status = TxExecutionStatus::Failure;
+    shadow.tx_outcomes[1].refunded_gas = 0;
+    assert_eq!(
+        reference.divergences(&shadow),
+        [DivergenceKind::TxStatus, DivergenceKind::RefundedGas]
+    );
+
+    let mut shadow = mock_execution();
+    shadow.tx_outcomes.pop();
+    assert_eq!(reference.divergences(&shadow), [DivergenceKind::TxStatus]);
+
+    let mut shadow = mock_execution();
+    for value in shadow.storage_writes.values_mut() {
+        *value = H256::zero();
+    }
+    shadow.user_l2_to_l1_logs.clear();
+    assert_eq!(
+        reference.divergences(&shadow),
+        [DivergenceKind::StorageWrites, DivergenceKind::L2ToL1Logs]
+    );
+}
+
+#[test]
+fn detecting_tx_divergences_in_miniblock() {
+    let reference = mock_execution().tx_outcomes;
+    assert_eq!(tx_divergences(&reference, &reference), []);
+
+    let mut shadow = reference.clone();
+    shadow[0].refunded_gas += 1;
+    assert_eq!(
+        tx_divergences(&reference, &shadow),
+        [DivergenceKind::RefundedGas]
+    );
+    shadow.swap(0, 1);
+    assert_eq!(
+        tx_divergences(&reference, &shadow),
+        [DivergenceKind::TxStatus, DivergenceKind::RefundedGas]
+    );
+}
@@ -0,0 +1,64 @@
+//! L1 client used by the solvency reporter.
+
+use std::{fmt, sync::Arc};
+
+use zksync_eth_client::{CallFunctionArgs, EthInterface};
+use zksync_types::{
+    ethabi::{self, Token},
+    Address, U256,
+};
+
+const ERC20_ABI: &str = r#"[{
+    "type": "function",
+    "name": "balanceOf",
+    "inputs": [{ "name": "account", "type": "address" }],
+    "outputs": [{ "name": "", "type": "uint256" }],
+    "stateMutability": "view"
+}]"#;
+
+/// Client querying balances of the L1 contracts holding bridged funds.
+#[async_trait::async_trait]
+pub trait L1BalancesClient: 'static + fmt::Debug + Send + Sync {
+    /// Returns the ETH balance of the specified account.
+    async fn eth_balance(&self, account: Address) -> anyhow::Result<U256>;
+
+    /// Returns the balance of the specified account in the specified ERC-20 token.
+    async fn erc20_balance(&self, token: Address, account: Address) -> anyhow::Result<U256>;
+}
+
+#[derive(Debug)]
+pub struct EthHttpBalancesClient {
+    client: Arc<dyn EthInterface>,
+    erc20_contract: ethabi::Contract,
+}
+
+impl EthHttpBalancesClient {
+    pub fn new(client: Arc<dyn EthInterface>) -> Self {
+        let erc20_contract =
+            ethabi::Contract::load(ERC20_ABI.as_bytes()).expect("ERC-20 ABI is invalid");
+        Self {
+            client,
+            erc20_contract,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl L1BalancesClient for EthHttpBalancesClient {
+    async fn eth_balance(&self, account: Address) -> anyhow::Result<U256> {
+        Ok(self
+            .client
+            .eth_balance(account, "solvency_reporter")
+            .await?)
+    }
+
+    async fn erc20_balance(&self, token: Address, account: Address) -> anyhow::Result<U256> {
+        let args = CallFunctionArgs::new("balanceOf", (account,))
+            .for_contract(token, self.erc20_contract.clone());
+        let output = self.client.call_contract_function(args).await?;
+        match output.as_slice() {
+            [Token::Uint(balance)] => Ok(*balance),
+            _ => anyhow::bail!("unexpected output of `balanceOf` for token {token:?}: {output:?}"),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! Metrics for the solvency reporter.
+
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "asset", rename_all = "snake_case")]
+pub(super) enum AssetKind {
+    BaseToken,
+    Erc20,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_solvency_reporter")]
+pub(super) struct SolvencyReporterMetrics {
+    /// Last L1 batch for which a solvency report was produced.
+    pub last_reported_l1_batch: Gauge<u64>,
+    /// Difference between the actual and expected L2 total supply of the base token in the last report, in ether.
+    pub supply_discrepancy: Gauge<f64>,
+    /// Number of reports in which L2 total supply doesn't match deposits and withdrawals.
+    pub imbalanced_reports: Counter,
+    /// Last observed total balance of the L1 contracts holding the bridged base token, in ether.
+    pub l1_bridge_balance: Gauge<f64>,
+    /// Number of checks in which L1 bridge balances didn't cover the value outstanding on L2.
+    pub insolvent_checks: Family<AssetKind, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<SolvencyReporterMetrics> = vise::Global::new();
@@ -0,0 +1,546 @@
+//! Reconciliation of bridged value.
+//!
+//! For each sealed L1 batch, the reporter accumulates the value bridged to L2 (minted by L1 transactions)
+//! and the value of withdrawals initiated on L2, and checks that the L2 total supply of the base token
+//! matches these flows. If it doesn't, either the accounting is broken or the base token is minted / burned
+//! outside of the bridge. ERC-20 tokens bridged via the L2 ERC-20 bridge are accounted in the same way based on
+//! the bridge events.
+//!
+//! After processing each chunk of L1 batches, the reporter checks that the L1 contracts holding bridged funds
+//! have enough balance to back the value outstanding on L2, for the base token and for each bridged ERC-20 token.
+//! The resulting reports are persisted and are available via `zks_getSolvencyReport`.
+
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::ContractsConfig;
+use zksync_dal::{
+    solvency_reports_dal::{SolvencyReport, TokenSolvencyReport},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_system_constants::L2_ETH_TOKEN_ADDRESS;
+use zksync_types::{
+    ethabi::{self, ParamType},
+    AccountTreeId, Address, L1BatchNumber, StorageKey, H256, U256,
+};
+use zksync_utils::{h256_to_account_address, h256_to_u256};
+
+pub use self::client::{EthHttpBalancesClient, L1BalancesClient};
+use self::metrics::{AssetKind, METRICS};
+
+mod client;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+const COMPONENT: &str = "solvency_reporter";
+
+/// Storage slot of `totalSupply` in the `L2EthToken` system contract; slot 0 holds the `balance` mapping.
+const TOTAL_SUPPLY_SLOT: u64 = 1;
+
+fn total_supply_key() -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
+        H256::from_low_u64_be(TOTAL_SUPPLY_SLOT),
+    )
+}
+
+/// Returns signatures of the `L2EthToken` events emitted when a withdrawal is initiated. In both events,
+/// the withdrawn amount is the first non-indexed field.
+fn withdrawal_topics() -> [H256; 2] {
+    let sender_and_receiver = [ParamType::Address, ParamType::Address, ParamType::Uint(256)];
+    let mut with_message = sender_and_receiver.to_vec();
+    with_message.push(ParamType::Bytes);
+    [
+        ethabi::long_signature("Withdrawal", &sender_and_receiver),
+        ethabi::long_signature("WithdrawalWithMessage", &with_message),
+    ]
+}
+
+/// Returns signatures of the L2 ERC-20 bridge events emitted on a deposit and on a withdrawal, respectively.
+/// In both events, the L2 token address is the third indexed field, and the amount is the only non-indexed field.
+fn bridge_topics() -> (H256, H256) {
+    let params = [
+        ParamType::Address,
+        ParamType::Address,
+        ParamType::Address,
+        ParamType::Uint(256),
+    ];
+    (
+        ethabi::long_signature("FinalizeDeposit", &params),
+        ethabi::long_signature("WithdrawalInitiated", &params),
+    )
+}
+
+fn withdrawn_amount(event_data: &[u8]) -> anyhow::Result<U256> {
+    anyhow::ensure!(
+        event_data.len() >= 32,
+        "withdrawal event data is too short: {} bytes",
+        event_data.len()
+    );
+    Ok(U256::from_big_endian(&event_data[..32]))
+}
+
+/// Value flows of the base token in a single L1 batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct BatchFlows {
+    minted: U256,
+    withdrawn: U256,
+    l2_total_supply: U256,
+}
+
+impl BatchFlows {
+    /// Creates a report for an L1 batch based on the report for the previous batch. If there is no previous report
+    /// (the reporter has just started on a fresh or recovered node), the actual total supply is used as a baseline.
+    ///
+    /// Returns an error if withdrawals exceed the expected supply, since this means that more value was withdrawn
+    /// than was ever bridged.
+    fn into_report(
+        self,
+        number: L1BatchNumber,
+        prev: Option<&SolvencyReport>,
+    ) -> anyhow::Result<SolvencyReport> {
+        let Some(prev) = prev else {
+            return Ok(SolvencyReport {
+                l1_batch_number: number,
+                total_deposited: self.minted,
+                total_withdrawn: self.withdrawn,
+                expected_supply: self.l2_total_supply,
+                l2_total_supply: self.l2_total_supply,
+                l1_bridge_balance: None,
+            });
+        };
+        let expected_supply = (prev.expected_supply + self.minted)
+            .checked_sub(self.withdrawn)
+            .with_context(|| {
+                format!(
+                    "withdrawals in L1 batch #{number} ({}) exceed the expected L2 supply of the base token ({})",
+                    self.withdrawn,
+                    prev.expected_supply + self.minted
+                )
+            })?;
+        Ok(SolvencyReport {
+            l1_batch_number: number,
+            total_deposited: prev.total_deposited + self.minted,
+            total_withdrawn: prev.total_withdrawn + self.withdrawn,
+            expected_supply,
+            l2_total_supply: self.l2_total_supply,
+            l1_bridge_balance: None,
+        })
+    }
+}
+
+/// Flows of an ERC-20 token via the L2 ERC-20 bridge in a single L1 batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct TokenFlows {
+    deposited: U256,
+    withdrawn: U256,
+}
+
+/// Applies token flows in an L1 batch to the cumulative token reports.
+fn apply_token_flows(
+    reports: &mut HashMap<Address, TokenSolvencyReport>,
+    flows: &HashMap<Address, TokenFlows>,
+) {
+    for (&l2_token_address, flows) in flows {
+        let report = reports
+            .entry(l2_token_address)
+            .or_insert_with(|| TokenSolvencyReport {
+                l2_token_address,
+                l1_token_address: None,
+                total_deposited: U256::zero(),
+                total_withdrawn: U256::zero(),
+                l1_bridge_balance: None,
+            });
+        report.total_deposited += flows.deposited;
+        report.total_withdrawn += flows.withdrawn;
+        report.l1_bridge_balance = None;
+    }
+}
+
+/// Returns the token value outstanding on L2, or an error if more value was withdrawn than deposited.
+fn outstanding_token_value(report: &TokenSolvencyReport) -> anyhow::Result<U256> {
+    report
+        .total_deposited
+        .checked_sub(report.total_withdrawn)
+        .with_context(|| {
+            format!(
+                "withdrawals of token {:?} ({}) exceed its deposits ({})",
+                report.l2_token_address, report.total_withdrawn, report.total_deposited
+            )
+        })
+}
+
+/// Addresses of the bridge contracts relevant for the solvency reporter.
+#[derive(Debug, Clone)]
+pub struct BridgeAddresses {
+    /// L1 contracts holding the bridged base token, e.g. the main (diamond proxy) contract and the shared bridge.
+    pub l1_base_token_holders: Vec<Address>,
+    /// L1 contracts holding bridged ERC-20 tokens, e.g. the legacy ERC-20 bridge and the shared bridge.
+    pub l1_erc20_holders: Vec<Address>,
+    /// L2 ERC-20 bridge emitting events on deposits and withdrawals.
+    pub l2_erc20_bridge: Address,
+}
+
+impl BridgeAddresses {
+    pub fn new(contracts: &ContractsConfig) -> Self {
+        let shared_bridge = contracts.l1_shared_bridge_proxy_addr;
+        Self {
+            l1_base_token_holders: [Some(contracts.diamond_proxy_addr), shared_bridge]
+                .into_iter()
+                .flatten()
+                .collect(),
+            l1_erc20_holders: [Some(contracts.l1_erc20_bridge_proxy_addr), shared_bridge]
+                .into_iter()
+                .flatten()
+                .collect(),
+            l2_erc20_bridge: contracts.l2_erc20_bridge_addr,
+        }
+    }
+}
+
+fn to_ether(wei: U256) -> f64 {
+    if wei.bits() > 128 {
+        f64::INFINITY
+    } else {
+        wei.as_u128() as f64 / 1e18
+    }
+}
+
+/// Component producing solvency reports for sealed L1 batches.
+#[derive(Debug)]
+pub struct SolvencyReporter {
+    pool: ConnectionPool,
+    l1_client: Box<dyn L1BalancesClient>,
+    addresses: BridgeAddresses,
+    poll_interval: Duration,
+}
+
+impl SolvencyReporter {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+    pub fn new(
+        pool: ConnectionPool,
+        l1_client: Box<dyn L1BalancesClient>,
+        addresses: BridgeAddresses,
+    ) -> Self {
+        Self {
+            pool,
+            l1_client,
+            addresses,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, solvency reporter is shutting down");
+                break;
+            }
+
+            let reported_count = self.report_sealed_l1_batches().await?;
+            if reported_count > 0 {
+                // There may be more L1 batches to report; don't wait.
+                continue;
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// Produces reports for a chunk of sealed L1 batches without reports. Returns the number of reported batches.
+    async fn report_sealed_l1_batches(&self) -> anyhow::Result<usize> {
+        const MAX_BATCHES_PER_ITERATION: u32 = 100;
+
+        let mut storage = self.pool.access_storage_tagged(COMPONENT).await?;
+        let Some(sealed_l1_batch) = storage.blocks_dal().get_sealed_l1_batch_number().await? else {
+            return Ok(0);
+        };
+        let last_reported = storage
+            .solvency_reports_dal()
+            .get_last_report_l1_batch()
+            .await?;
+        let (first_l1_batch, mut prev_report) = match last_reported {
+            Some(number) => {
+                let report = storage.solvency_reports_dal().get_report(number).await?;
+                (number + 1, report)
+            }
+            None => {
+                let earliest = storage
+                    .blocks_dal()
+                    .get_earliest_l1_batch_number()
+                    .await?
+                    .context("no L1 batches in storage")?;
+                (earliest, None)
+            }
+        };
+        if first_l1_batch > sealed_l1_batch {
+            return Ok(0);
+        }
+        let last_l1_batch = sealed_l1_batch.min(first_l1_batch + MAX_BATCHES_PER_ITERATION - 1);
+
+        // Bridged value can only be compared to L1 balances if flows are known from genesis; a node recovered
+        // from a snapshot doesn't have deposits and withdrawals for the pruned L1 batches.
+        let earliest_l1_batch = storage
+            .blocks_dal()
+            .get_earliest_l1_batch_number()
+            .await?
+            .context("no L1 batches in storage")?;
+        let has_full_history = earliest_l1_batch == L1BatchNumber(0);
+        if !has_full_history && last_reported.is_none() {
+            tracing::warn!(
+                "Earliest L1 batch in storage is #{earliest_l1_batch}; L1 bridge balances and ERC-20 tokens \
+                 will not be reconciled"
+            );
+        }
+        let mut token_reports: HashMap<_, _> =
+            if has_full_history && first_l1_batch > L1BatchNumber(0) {
+                storage
+                    .solvency_reports_dal()
+                    .get_token_reports(first_l1_batch - 1)
+                    .await?
+                    .into_iter()
+                    .map(|report| (report.l2_token_address, report))
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+
+        let mut transaction = storage.start_transaction().await?;
+        for number in first_l1_batch.0..=last_l1_batch.0 {
+            let number = L1BatchNumber(number);
+            let flows = Self::load_flows(&mut transaction, number).await?;
+            let report = flows.into_report(number, prev_report.as_ref())?;
+            Self::check_report(&report);
+            transaction
+                .solvency_reports_dal()
+                .insert_report(&report)
+                .await?;
+            prev_report = Some(report);
+
+            if has_full_history {
+                let token_flows = self.load_token_flows(&mut transaction, number).await?;
+                apply_token_flows(&mut token_reports, &token_flows);
+                for l2_token_address in token_flows.keys() {
+                    let report = &token_reports[l2_token_address];
+                    outstanding_token_value(report)
+                        .with_context(|| format!("invalid token flows in L1 batch #{number}"))?;
+                    transaction
+                        .solvency_reports_dal()
+                        .insert_token_report(number, report)
+                        .await?;
+                }
+            }
+        }
+
+        if has_full_history {
+            let last_report = prev_report.context("no report for the last processed L1 batch")?;
+            self.check_l1_balances(&mut transaction, &last_report)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        METRICS.last_reported_l1_batch.set(last_l1_batch.0.into());
+        Ok((last_l1_batch.0 - first_l1_batch.0 + 1) as usize)
+    }
+
+    /// Checks that L1 bridge balances cover the value outstanding on L2 after the specified report and persists
+    /// the balances.
+    ///
+    /// Balances are checked after a chunk of L1 batches is processed, so they can be newer than the last reported
+    /// batch. This is fine: withdrawals are finalized on L1 only after they are initiated on L2, so L1 balances
+    /// must back the value outstanding on L2 at any point in time.
+    async fn check_l1_balances(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        last_report: &SolvencyReport,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = last_report.l1_batch_number;
+        if let Some(balance) = self.base_token_l1_balance().await {
+            Self::check_base_token_balance(last_report, balance);
+            storage
+                .solvency_reports_dal()
+                .set_l1_bridge_balance(l1_batch_number, balance)
+                .await?;
+        }
+        // Token reports are reloaded, so that they include L1 addresses of the tokens.
+        let token_reports = storage
+            .solvency_reports_dal()
+            .get_token_reports(l1_batch_number)
+            .await?;
+        for mut report in token_reports {
+            let Some(balance) = self.erc20_l1_balance(&report).await else {
+                continue;
+            };
+            report.l1_bridge_balance = Some(balance);
+            Self::check_token_balance(&report, balance)?;
+            storage
+                .solvency_reports_dal()
+                .insert_token_report(l1_batch_number, &report)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn load_flows(
+        storage: &mut StorageProcessor<'_>,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<BatchFlows> {
+        let (_, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(number)
+            .await?
+            .with_context(|| format!("L1 batch #{number} has no miniblocks"))?;
+        let minted = storage
+            .solvency_reports_dal()
+            .get_l1_batch_minted_value(number)
+            .await?;
+        let withdrawal_events = storage
+            .solvency_reports_dal()
+            .get_l1_batch_event_data(number, L2_ETH_TOKEN_ADDRESS, &withdrawal_topics())
+            .await?;
+        let mut withdrawn = U256::zero();
+        for event_data in &withdrawal_events {
+            withdrawn += withdrawn_amount(event_data)
+                .with_context(|| format!("invalid withdrawal event in L1 batch #{number}"))?;
+        }
+        let l2_total_supply = storage
+            .storage_web3_dal()
+            .get_historical_value_unchecked(&total_supply_key(), last_miniblock)
+            .await?;
+
+        Ok(BatchFlows {
+            minted,
+            withdrawn,
+            l2_total_supply: h256_to_u256(l2_total_supply),
+        })
+    }
+
+    async fn load_token_flows(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<HashMap<Address, TokenFlows>> {
+        let (deposit_topic, withdrawal_topic) = bridge_topics();
+        let events = storage
+            .solvency_reports_dal()
+            .get_l1_batch_indexed_events(
+                number,
+                self.addresses.l2_erc20_bridge,
+                &[deposit_topic, withdrawal_topic],
+            )
+            .await?;
+
+        let mut flows = HashMap::<_, TokenFlows>::new();
+        for (topic, l2_token, event_data) in events {
+            let amount = withdrawn_amount(&event_data)
+                .with_context(|| format!("invalid bridge event in L1 batch #{number}"))?;
+            let token_flows = flows.entry(h256_to_account_address(&l2_token)).or_default();
+            if topic == deposit_topic {
+                token_flows.deposited += amount;
+            } else {
+                token_flows.withdrawn += amount;
+            }
+        }
+        Ok(flows)
+    }
+
+    /// Returns the total balance of the L1 contracts holding the base token, or `None` if it cannot be fetched.
+    async fn base_token_l1_balance(&self) -> Option<U256> {
+        let mut total = U256::zero();
+        for &holder in &self.addresses.l1_base_token_holders {
+            match self.l1_client.eth_balance(holder).await {
+                Ok(balance) => total += balance,
+                Err(err) => {
+                    tracing::warn!("Failed getting L1 balance of {holder:?}: {err:#}");
+                    return None;
+                }
+            }
+        }
+        Some(total)
+    }
+
+    /// Returns the total balance of the L1 contracts holding the token, or `None` if it cannot be fetched.
+    async fn erc20_l1_balance(&self, report: &TokenSolvencyReport) -> Option<U256> {
+        let Some(l1_token_address) = report.l1_token_address else {
+            tracing::warn!(
+                "L1 address of token {:?} is unknown; skipping L1 balance check",
+                report.l2_token_address
+            );
+            return None;
+        };
+        let mut total = U256::zero();
+        for &holder in &self.addresses.l1_erc20_holders {
+            match self.l1_client.erc20_balance(l1_token_address, holder).await {
+                Ok(balance) => total += balance,
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed getting L1 balance of {holder:?} in token {l1_token_address:?}: {err:#}"
+                    );
+                    return None;
+                }
+            }
+        }
+        Some(total)
+    }
+
+    fn check_report(report: &SolvencyReport) {
+        let (discrepancy, is_surplus) = if report.l2_total_supply >= report.expected_supply {
+            (report.l2_total_supply - report.expected_supply, true)
+        } else {
+            (report.expected_supply - report.l2_total_supply, false)
+        };
+        let discrepancy_ether = to_ether(discrepancy);
+        METRICS.supply_discrepancy.set(if is_surplus {
+            discrepancy_ether
+        } else {
+            -discrepancy_ether
+        });
+
+        if !discrepancy.is_zero() {
+            METRICS.imbalanced_reports.inc();
+            tracing::error!(
+                "L2 total supply after L1 batch #{} ({}) doesn't match bridged value: expected {}, \
+                 total deposited {}, total withdrawn {}",
+                report.l1_batch_number,
+                report.l2_total_supply,
+                report.expected_supply,
+                report.total_deposited,
+                report.total_withdrawn
+            );
+        }
+    }
+
+    fn check_base_token_balance(report: &SolvencyReport, l1_balance: U256) {
+        METRICS.l1_bridge_balance.set(to_ether(l1_balance));
+        // The genesis supply isn't backed by L1 funds, so only the bridged value is compared to the L1 balance.
+        // The check is written without subtraction since genesis funds may be withdrawn as well.
+        if l1_balance + report.total_withdrawn < report.total_deposited {
+            METRICS.insolvent_checks[&AssetKind::BaseToken].inc();
+            tracing::error!(
+                "L1 balance of the base token ({l1_balance}) doesn't cover the value bridged to L2 after \
+                 L1 batch #{}: total deposited {}, total withdrawn {}",
+                report.l1_batch_number,
+                report.total_deposited,
+                report.total_withdrawn
+            );
+        }
+    }
+
+    fn check_token_balance(report: &TokenSolvencyReport, l1_balance: U256) -> anyhow::Result<()> {
+        let outstanding = outstanding_token_value(report)?;
+        if l1_balance < outstanding {
+            METRICS.insolvent_checks[&AssetKind::Erc20].inc();
+            tracing::error!(
+                "L1 balance of token {:?} ({l1_balance}) doesn't cover the value outstanding on L2 ({outstanding})",
+                report.l1_token_address.unwrap_or(report.l2_token_address)
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+//! Tests for the solvency reporter.
+
+use zksync_types::{
+    tokens::{TokenInfo, TokenMetadata},
+    tx::IncludedTxLocation,
+    L2ChainId, MiniblockNumber, StorageLog, VmEvent,
+};
+use zksync_utils::{address_to_h256, u256_to_h256};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l1_batch, create_miniblock},
+};
+
+fn withdrawal_event(number: u32, amount: U256) -> VmEvent {
+    VmEvent {
+        location: (L1BatchNumber(number), 0),
+        address: L2_ETH_TOKEN_ADDRESS,
+        indexed_topics: vec![
+            withdrawal_topics()[0],
+            H256::repeat_byte(1),
+            H256::repeat_byte(2),
+        ],
+        value: ethabi::encode(&[ethabi::Token::Uint(amount)]),
+    }
+}
+
+const L1_BASE_TOKEN_HOLDER: Address = Address::repeat_byte(0xa0);
+const L1_ERC20_HOLDER: Address = Address::repeat_byte(0xb0);
+const L2_ERC20_BRIDGE: Address = Address::repeat_byte(0xc0);
+
+#[derive(Debug, Default)]
+struct MockL1BalancesClient {
+    eth_balances: HashMap<Address, U256>,
+    erc20_balances: HashMap<(Address, Address), U256>,
+}
+
+#[async_trait::async_trait]
+impl L1BalancesClient for MockL1BalancesClient {
+    async fn eth_balance(&self, account: Address) -> anyhow::Result<U256> {
+        self.eth_balances
+            .get(&account)
+            .copied()
+            .with_context(|| format!("unexpected account {account:?}"))
+    }
+
+    async fn erc20_balance(&self, token: Address, account: Address) -> anyhow::Result<U256> {
+        self.erc20_balances
+            .get(&(token, account))
+            .copied()
+            .with_context(|| format!("unexpected token {token:?} / account {account:?}"))
+    }
+}
+
+fn create_reporter(pool: &ConnectionPool, l1_client: MockL1BalancesClient) -> SolvencyReporter {
+    let addresses = BridgeAddresses {
+        l1_base_token_holders: vec![L1_BASE_TOKEN_HOLDER],
+        l1_erc20_holders: vec![L1_ERC20_HOLDER],
+        l2_erc20_bridge: L2_ERC20_BRIDGE,
+    };
+    SolvencyReporter::new(pool.clone(), Box::new(l1_client), addresses)
+}
+
+fn bridge_event(number: u32, is_deposit: bool, l2_token: Address, amount: U256) -> VmEvent {
+    let (deposit_topic, withdrawal_topic) = bridge_topics();
+    VmEvent {
+        location: (L1BatchNumber(number), 0),
+        address: L2_ERC20_BRIDGE,
+        indexed_topics: vec![
+            if is_deposit {
+                deposit_topic
+            } else {
+                withdrawal_topic
+            },
+            H256::repeat_byte(1),
+            H256::repeat_byte(2),
+            address_to_h256(&l2_token),
+        ],
+        value: ethabi::encode(&[ethabi::Token::Uint(amount)]),
+    }
+}
+
+async fn seal_l1_batch(
+    storage: &mut StorageProcessor<'_>,
+    number: u32,
+    total_supply: U256,
+    events: &[VmEvent],
+) {
+    let miniblock_number = MiniblockNumber(number);
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    let supply_log = StorageLog::new_write_log(total_supply_key(), u256_to_h256(total_supply));
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(miniblock_number, &[(H256::zero(), vec![supply_log])])
+        .await
+        .unwrap();
+    let location = IncludedTxLocation {
+        tx_hash: H256::zero(),
+        tx_index_in_miniblock: 0,
+        tx_initiator_address: Address::zero(),
+    };
+    storage
+        .events_dal()
+        .save_events(miniblock_number, &[(location, events.iter().collect())])
+        .await;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(number))
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+        .await
+        .unwrap();
+}
+
+#[test]
+fn decoding_withdrawn_amount() {
+    let event = withdrawal_event(1, 12_345.into());
+    assert_eq!(withdrawn_amount(&event.value).unwrap(), 12_345.into());
+
+    let data_with_message = ethabi::encode(&[
+        ethabi::Token::Uint(42.into()),
+        ethabi::Token::Bytes(vec![1, 2, 3]),
+    ]);
+    assert_eq!(withdrawn_amount(&data_with_message).unwrap(), 42.into());
+    withdrawn_amount(&[0; 16]).unwrap_err();
+}
+
+#[test]
+fn computing_reports() {
+    let flows = BatchFlows {
+        minted: 100.into(),
+        withdrawn: 0.into(),
+        l2_total_supply: 1_100.into(),
+    };
+    let report = flows.into_report(L1BatchNumber(5), None).unwrap();
+    assert_eq!(report.expected_supply, report.l2_total_supply);
+    assert_eq!(report.total_deposited, 100.into());
+
+    let flows = BatchFlows {
+        minted: 50.into(),
+        withdrawn: 30.into(),
+        l2_total_supply: 1_120.into(),
+    };
+    let report = flows.into_report(L1BatchNumber(6), Some(&report)).unwrap();
+    assert_eq!(report.total_deposited, 150.into());
+    assert_eq!(report.total_withdrawn, 30.into());
+    assert_eq!(report.expected_supply, 1_120.into());
+    assert_eq!(report.expected_supply, report.l2_total_supply);
+
+    let flows = BatchFlows {
+        minted: 0.into(),
+        withdrawn: 2_000.into(),
+        l2_total_supply: 0.into(),
+    };
+    let err = flows
+        .into_report(L1BatchNumber(7), Some(&report))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("exceed the expected L2 supply"), "{err}");
+}
+
+#[test]
+fn computing_token_flows() {
+    let token = Address::repeat_byte(1);
+    let mut reports = HashMap::new();
+    let flows = HashMap::from([(
+        token,
+        TokenFlows {
+            deposited: 100.into(),
+            withdrawn: 40.into(),
+        },
+    )]);
+    apply_token_flows(&mut reports, &flows);
+    apply_token_flows(&mut reports, &flows);
+    let report = &reports[&token];
+    assert_eq!(report.total_deposited, 200.into());
+    assert_eq!(report.total_withdrawn, 80.into());
+    assert_eq!(outstanding_token_value(report).unwrap(), 120.into());
+
+    let flows = HashMap::from([(
+        token,
+        TokenFlows {
+            deposited: 0.into(),
+            withdrawn: 500.into(),
+        },
+    )]);
+    apply_token_flows(&mut reports, &flows);
+    outstanding_token_value(&reports[&token]).unwrap_err();
+}
+
+#[tokio::test]
+async fn reporting_l1_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let l1_client = MockL1BalancesClient {
+        eth_balances: HashMap::from([(L1_BASE_TOKEN_HOLDER, 1_000.into())]),
+        ..MockL1BalancesClient::default()
+    };
+    let reporter = create_reporter(&pool, l1_client);
+
+    assert_eq!(reporter.report_sealed_l1_batches().await.unwrap(), 1);
+    assert_eq!(reporter.report_sealed_l1_batches().await.unwrap(), 0);
+    let genesis_report = storage
+        .solvency_reports_dal()
+        .get_report(L1BatchNumber(0))
+        .await
+        .unwrap()
+        .expect("no genesis report");
+    assert_eq!(
+        genesis_report.expected_supply,
+        genesis_report.l2_total_supply
+    );
+    let genesis_supply = genesis_report.l2_total_supply;
+
+    // Batch #1 increases the supply without a deposit; batch #2 contains a withdrawal burning the withdrawn value.
+    let supply = genesis_supply + 1_000;
+    seal_l1_batch(&mut storage, 1, supply, &[]).await;
+    let supply = supply - 300;
+    let withdrawn = genesis_supply.min(300.into());
+    seal_l1_batch(&mut storage, 2, supply, &[withdrawal_event(2, withdrawn)]).await;
+    assert_eq!(reporter.report_sealed_l1_batches().await.unwrap(), 2);
+
+    let report = storage
+        .solvency_reports_dal()
+        .get_report(L1BatchNumber(2))
+        .await
+        .unwrap()
+        .expect("no report");
+    assert_eq!(report.total_withdrawn, withdrawn);
+    assert_eq!(report.l2_total_supply, supply);
+    assert_eq!(report.expected_supply, genesis_supply - withdrawn);
+    assert_ne!(report.expected_supply, report.l2_total_supply);
+    assert_eq!(report.l1_bridge_balance, Some(1_000.into()));
+
+    // Withdrawing more than the expected supply is an accounting error rather than an imbalance.
+    let withdrawn = report.expected_supply + 1;
+    seal_l1_batch(&mut storage, 3, supply, &[withdrawal_event(3, withdrawn)]).await;
+    let err = reporter
+        .report_sealed_l1_batches()
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("exceed the expected L2 supply"), "{err}");
+}
+
+#[tokio::test]
+async fn reporting_erc20_tokens() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let l1_token = Address::repeat_byte(0x11);
+    let l2_token = Address::repeat_byte(0x22);
+    storage
+        .tokens_dal()
+        .add_tokens(&[TokenInfo {
+            l1_address: l1_token,
+            l2_address: l2_token,
+            metadata: TokenMetadata::default(l1_token),
+        }])
+        .await
+        .unwrap();
+    let l1_client = MockL1BalancesClient {
+        eth_balances: HashMap::from([(L1_BASE_TOKEN_HOLDER, 0.into())]),
+        erc20_balances: HashMap::from([((l1_token, L1_ERC20_HOLDER), 700.into())]),
+    };
+    let reporter = create_reporter(&pool, l1_client);
+    assert_eq!(reporter.report_sealed_l1_batches().await.unwrap(), 1);
+
+    let genesis_supply = storage
+        .solvency_reports_dal()
+        .get_report(L1BatchNumber(0))
+        .await
+        .unwrap()
+        .expect("no genesis report")
+        .l2_total_supply;
+    let events = [
+        bridge_event(1, true, l2_token, 1_000.into()),
+        bridge_event(1, false, l2_token, 300.into()),
+    ];
+    seal_l1_batch(&mut storage, 1, genesis_supply, &events).await;
+    seal_l1_batch(&mut storage, 2, genesis_supply, &[]).await;
+    assert_eq!(reporter.report_sealed_l1_batches().await.unwrap(), 2);
+
+    let token_reports = storage
+        .solvency_reports_dal()
+        .get_token_reports(L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert_eq!(
+        token_reports,
+        [TokenSolvencyReport {
+            l2_token_address: l2_token,
+            l1_token_address: Some(l1_token),
+            total_deposited: 1_000.into(),
+            total_withdrawn: 300.into(),
+            l1_bridge_balance: Some(700.into()),
+        }]
+    );
+
+    // Withdrawing more than was deposited is an accounting error.
+    let events = [bridge_event(3, false, l2_token, 701.into())];
+    seal_l1_batch(&mut storage, 3, genesis_supply, &events).await;
+    let err = reporter
+        .report_sealed_l1_batches()
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid token flows"), "{err}");
+}
@@ -17,14 +17,16 @@ use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
-    protocol_version::ProtocolUpgradeTx, witness_block_state::WitnessBlockState, Address,
-    L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+    fee_model::BatchFeeInput, protocol_version::ProtocolUpgradeTx,
+    witness_block_state::WitnessBlockState, Address, ExecuteTransactionCommon, L1BatchNumber,
+    L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256, U256,
 };
 // TODO (SMA-1206): use seconds instead of milliseconds.
 use zksync_utils::time::millis_since_epoch;
 
 use crate::{
     fee_model::BatchFeeModelInputProvider,
+    l1_gas_price::L1BlockTimestampProvider,
     state_keeper::{
         extractors,
         io::{
@@ -33,7 +35,7 @@ use crate::{
             StateKeeperIO,
         },
         mempool_actor::l2_tx_filter,
-        metrics::KEEPER_METRICS,
+        metrics::{MetadataLagComponent, TimestampDriftDirection, KEEPER_METRICS},
         seal_criteria::{IoSealCriteria, TimeoutSealer},
         updates::{MiniblockUpdates, UpdatesManager},
         MempoolGuard,
@@ -67,17 +69,274 @@ pub struct MempoolIO {
 
     virtual_blocks_interval: u32,
     virtual_blocks_per_miniblock: u32,
+    fair_ordering: Option<FairOrdering>,
+    l1_timestamp_drift_guard: Option<L1TimestampDriftGuard>,
+    metadata_lag_guard: Option<MetadataLagGuard>,
+    priority_tx_fee_multiplier: f64,
+}
+
+/// Enforces bounded reordering of L2 transactions w.r.t. their arrival sequence numbers within a miniblock.
+#[derive(Debug)]
+struct FairOrdering {
+    max_reordering: u64,
+    max_arrival_seq: Option<u64>,
+    seal_requested: bool,
+}
+
+impl FairOrdering {
+    fn new(max_reordering: u64) -> Self {
+        Self {
+            max_reordering,
+            max_arrival_seq: None,
+            seal_requested: false,
+        }
+    }
+
+    /// Checks whether a transaction with the specified arrival sequence number can be included
+    /// into the current miniblock. If it can't, requests to seal the miniblock.
+    fn accept(&mut self, arrival_seq: u64) -> bool {
+        if let Some(max_arrival_seq) = self.max_arrival_seq {
+            if max_arrival_seq.saturating_sub(arrival_seq) > self.max_reordering {
+                self.seal_requested = true;
+                return false;
+            }
+        }
+        self.max_arrival_seq = Some(
+            self.max_arrival_seq
+                .map_or(arrival_seq, |seq| seq.max(arrival_seq)),
+        );
+        true
+    }
+
+    fn reset(&mut self) {
+        self.max_arrival_seq = None;
+        self.seal_requested = false;
+    }
+}
+
+/// Bounds drift of L1 batch timestamps relative to the latest known L1 block. L1 contracts reject commitments
+/// for batches with timestamps too far ahead of the L1 block timestamp, so that a batch produced while the local
+/// clock is ahead of L1 (e.g., because of clock issues on the host) would revert on commit.
+#[derive(Debug)]
+struct L1TimestampDriftGuard {
+    provider: Arc<dyn L1BlockTimestampProvider>,
+    max_drift: Duration,
+}
+
+impl L1TimestampDriftGuard {
+    /// Checks the timestamp of a new L1 batch. Returns `false` if the timestamp is too far ahead of L1,
+    /// i.e., the batch must not be opened yet.
+    ///
+    /// The check fails open if L1 data is stale: the latest known L1 block timestamp lags behind L1 in this case,
+    /// so the state keeper would halt until the L1 RPC recovers.
+    fn check(&self, timestamp: u64, l1_batch: L1BatchNumber) -> bool {
+        if self.provider.is_l1_data_stale() {
+            tracing::warn!(
+                "L1 data is stale; skipping L1 timestamp drift check for L1 batch #{l1_batch}"
+            );
+            KEEPER_METRICS.l1_timestamp_drift_checks_skipped.inc();
+            return true;
+        }
+        let Some(l1_timestamp) = self.provider.latest_l1_block_timestamp() else {
+            tracing::debug!("Latest L1 block timestamp is not known; skipping drift check for L1 batch #{l1_batch}");
+            return true;
+        };
+        let drift = timestamp as i64 - l1_timestamp as i64;
+        KEEPER_METRICS.l1_timestamp_drift.set(drift);
+        let max_drift = self.max_drift.as_secs() as i64;
+
+        if drift > max_drift {
+            tracing::warn!(
+                "Timestamp {} for L1 batch #{l1_batch} is {drift}s ahead of the latest L1 block timestamp {}, \
+                 which exceeds the allowed drift {max_drift}s; waiting until L1 catches up",
+                extractors::display_timestamp(timestamp),
+                extractors::display_timestamp(l1_timestamp)
+            );
+            KEEPER_METRICS.l1_timestamp_drift_exceeded[&TimestampDriftDirection::Ahead].inc();
+            false
+        } else {
+            if drift < -max_drift {
+                // Waiting won't help in this case; the batch will still be accepted by L1 contracts unless it's
+                // very old, but the local clock should be fixed.
+                tracing::error!(
+                    "Timestamp {} for L1 batch #{l1_batch} is {}s behind the latest L1 block timestamp {}, \
+                     which exceeds the allowed drift {max_drift}s; check the system clock",
+                    extractors::display_timestamp(timestamp),
+                    -drift,
+                    extractors::display_timestamp(l1_timestamp)
+                );
+                KEEPER_METRICS.l1_timestamp_drift_exceeded[&TimestampDriftDirection::Behind].inc();
+            }
+            true
+        }
+    }
+}
+
+/// Status of the lag of a component processing sealed L1 batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MetadataLagStatus {
+    Normal,
+    /// Lag exceeds half of the limit; L1 batches are sealed less eagerly.
+    Elevated,
+    /// Lag exceeds the limit; new L1 batches are not opened.
+    Exceeded,
+}
+
+/// Applies backpressure from components processing sealed L1 batches (the metadata calculator and the commitment
+/// generator). Without it, a lagging component lets the queue of unprocessed L1 batches grow unboundedly. Instead,
+/// L1 batches are made longer once the lag is elevated, and intake is throttled once the lag exceeds the limit.
+#[derive(Debug)]
+struct MetadataLagGuard {
+    max_tree_lag: Option<u32>,
+    max_commitment_lag: Option<u32>,
+    /// Status observed when the current L1 batch was opened.
+    status: MetadataLagStatus,
+}
+
+impl MetadataLagGuard {
+    /// Multiplier for the L1 batch commit deadline applied while the lag is elevated.
+    const ELEVATED_LAG_DEADLINE_MULTIPLIER: u64 = 4;
+
+    fn new(max_tree_lag: Option<u32>, max_commitment_lag: Option<u32>) -> Self {
+        Self {
+            max_tree_lag,
+            max_commitment_lag,
+            status: MetadataLagStatus::Normal,
+        }
+    }
+
+    /// Checks whether a new L1 batch can be opened. Returns `false` if any component lags too much,
+    /// i.e., the batch must not be opened yet.
+    async fn check(
+        &mut self,
+        pool: &ConnectionPool,
+        l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<bool> {
+        let mut storage = pool.access_storage_tagged("state_keeper").await?;
+        let last_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .context("failed getting last L1 batch with metadata")?;
+        let last_with_commitment = if self.max_commitment_lag.is_some() {
+            storage
+                .blocks_dal()
+                .get_last_l1_batch_number_with_commitment()
+                .await
+                .context("failed getting last L1 batch with commitment")?
+        } else {
+            None
+        };
+        drop(storage);
+
+        let last_sealed = l1_batch.0.saturating_sub(1);
+        let last_with_metadata = last_with_metadata.map_or(0, |number| number.0);
+        let tree_lag = last_sealed.saturating_sub(last_with_metadata);
+        // The commitment generator only processes L1 batches with metadata, so its lag doesn't include the tree lag.
+        let commitment_lag =
+            last_with_metadata.saturating_sub(last_with_commitment.map_or(0, |number| number.0));
+        Ok(self.update_status(l1_batch, tree_lag, commitment_lag))
+    }
+
+    fn update_status(
+        &mut self,
+        l1_batch: L1BatchNumber,
+        tree_lag: u32,
+        commitment_lag: u32,
+    ) -> bool {
+        let tree_status = self.max_tree_lag.map(|max_lag| {
+            Self::lag_status(MetadataLagComponent::Tree, tree_lag, max_lag, l1_batch)
+        });
+        let commitment_status = self.max_commitment_lag.map(|max_lag| {
+            Self::lag_status(
+                MetadataLagComponent::Commitment,
+                commitment_lag,
+                max_lag,
+                l1_batch,
+            )
+        });
+        self.status = tree_status
+            .into_iter()
+            .chain(commitment_status)
+            .max()
+            .unwrap_or(MetadataLagStatus::Normal);
+        self.status != MetadataLagStatus::Exceeded
+    }
+
+    fn lag_status(
+        component: MetadataLagComponent,
+        lag: u32,
+        max_lag: u32,
+        l1_batch: L1BatchNumber,
+    ) -> MetadataLagStatus {
+        KEEPER_METRICS.metadata_lag[&component].set(lag.into());
+        if lag > max_lag {
+            tracing::warn!(
+                "{component:?} lags {lag} L1 batches behind the state keeper, which exceeds the limit {max_lag}; \
+                 postponing opening L1 batch #{l1_batch}"
+            );
+            KEEPER_METRICS.metadata_lag_exceeded[&component].inc();
+            MetadataLagStatus::Exceeded
+        } else if lag > max_lag / 2 {
+            tracing::info!(
+                "{component:?} lags {lag} L1 batches behind the state keeper (limit: {max_lag}); \
+                 L1 batch #{l1_batch} will be sealed less eagerly"
+            );
+            MetadataLagStatus::Elevated
+        } else {
+            MetadataLagStatus::Normal
+        }
+    }
+
+    fn is_lag_elevated(&self) -> bool {
+        self.status != MetadataLagStatus::Normal
+    }
 }
 
 impl IoSealCriteria for MempoolIO {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        if let Some(guard) = &self.metadata_lag_guard {
+            if guard.is_lag_elevated() {
+                return self
+                    .timeout_sealer
+                    .should_seal_l1_batch_with_extended_deadline(
+                        manager,
+                        MetadataLagGuard::ELEVATED_LAG_DEADLINE_MULTIPLIER,
+                    );
+            }
+        }
         self.timeout_sealer
             .should_seal_l1_batch_unconditionally(manager)
     }
 
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
+        if let Some(fair_ordering) = &mut self.fair_ordering {
+            if fair_ordering.seal_requested {
+                if !manager.miniblock.executed_transactions.is_empty() {
+                    return true;
+                }
+                // All transactions that have set the reordering bound were excluded from the miniblock.
+                fair_ordering.reset();
+            }
+        }
         self.timeout_sealer.should_seal_miniblock(manager)
     }
+
+    fn l1_batch_seal_reason(&self) -> Option<&'static str> {
+        self.timeout_sealer.l1_batch_seal_reason()
+    }
+
+    fn miniblock_seal_reason(&self) -> Option<&'static str> {
+        let seal_requested = self
+            .fair_ordering
+            .as_ref()
+            .map_or(false, |fair_ordering| fair_ordering.seal_requested);
+        if seal_requested {
+            Some("fair_ordering")
+        } else {
+            self.timeout_sealer.miniblock_seal_reason()
+        }
+    }
 }
 
 #[async_trait]
@@ -172,6 +431,21 @@ impl StateKeeperIO for MempoolIO {
             let Some(current_timestamp) = current_timestamp.await.ok() else {
                 return Ok(None);
             };
+            if let Some(guard) = &self.l1_timestamp_drift_guard {
+                if !guard.check(current_timestamp, self.current_l1_batch_number) {
+                    tokio::time::sleep(self.delay_interval).await;
+                    continue;
+                }
+            }
+            if let Some(guard) = &mut self.metadata_lag_guard {
+                if !guard
+                    .check(&self.pool, self.current_l1_batch_number)
+                    .await?
+                {
+                    tokio::time::sleep(self.delay_interval).await;
+                    continue;
+                }
+            }
 
             tracing::trace!(
                 "Fee input for L1 batch #{} is {:#?}",
@@ -193,6 +467,7 @@ impl StateKeeperIO for MempoolIO {
                 protocol_version.into(),
             )
             .await;
+            self.apply_priority_tx_fee_premium(protocol_version);
             if !self.mempool.has_next(&self.filter) {
                 tokio::time::sleep(self.delay_interval).await;
                 continue;
@@ -247,6 +522,34 @@ impl StateKeeperIO for MempoolIO {
             let res = self.mempool.next_transaction(&self.filter);
             get_latency.observe();
             if let Some(res) = res {
+                if !res.is_l1()
+                    && self
+                        .mempool
+                        .is_force_include_account(&res.initiator_account())
+                {
+                    // Audit log for operator transactions bypassing the regular mempool ordering.
+                    tracing::info!(
+                        "Force-including operator transaction {:?} from {:?} with nonce {:?} \
+                         into miniblock #{} (L1 batch #{})",
+                        res.hash(),
+                        res.initiator_account(),
+                        res.nonce(),
+                        self.current_miniblock_number,
+                        self.current_l1_batch_number
+                    );
+                    KEEPER_METRICS.force_included_transactions.inc();
+                    return Some(res);
+                }
+                if !self.accept_for_fair_ordering(&res) {
+                    tracing::debug!(
+                        "Deferring transaction {} to the next miniblock to bound reordering",
+                        res.hash()
+                    );
+                    KEEPER_METRICS.deferred_transactions.inc();
+                    self.mempool.rollback(&res);
+                    self.mempool.insert(vec![res], HashMap::new());
+                    return None;
+                }
                 return Some(res);
             } else {
                 tokio::time::sleep(self.delay_interval).await;
@@ -343,6 +646,8 @@ impl StateKeeperIO for MempoolIO {
                 self.l2_erc20_bridge_addr,
             )
             .await;
+        self.miniblock_sealer_handle
+            .publish_write_set(&fictive_miniblock);
         self.update_miniblock_fields(&fictive_miniblock);
         self.current_l1_batch_number += 1;
         Ok(())
@@ -372,13 +677,29 @@ impl StateKeeperIO for MempoolIO {
     }
 }
 
+/// Multiplies the fair L2 gas price in the provided fee input by `multiplier`.
+fn scale_fair_l2_gas_price(fee_input: BatchFeeInput, multiplier: f64) -> BatchFeeInput {
+    let scale = |price: u64| (price as f64 * multiplier) as u64;
+    match fee_input {
+        BatchFeeInput::L1Pegged(mut input) => {
+            input.fair_l2_gas_price = scale(input.fair_l2_gas_price);
+            BatchFeeInput::L1Pegged(input)
+        }
+        BatchFeeInput::PubdataIndependent(mut input) => {
+            input.fair_l2_gas_price = scale(input.fair_l2_gas_price);
+            BatchFeeInput::PubdataIndependent(input)
+        }
+    }
+}
+
 /// Sleeps until the current timestamp is larger than the provided `timestamp`.
 ///
 /// Returns the current timestamp after the sleep. It is guaranteed to be larger than `timestamp`.
 async fn sleep_past(timestamp: u64, miniblock: MiniblockNumber) -> u64 {
+    let started_at = Instant::now();
     let mut current_timestamp_millis = millis_since_epoch();
     let mut current_timestamp = (current_timestamp_millis / 1_000) as u64;
-    match timestamp.cmp(&current_timestamp) {
+    let is_behind = match timestamp.cmp(&current_timestamp) {
         cmp::Ordering::Less => return current_timestamp,
         cmp::Ordering::Equal => {
             tracing::info!(
@@ -386,6 +707,7 @@ async fn sleep_past(timestamp: u64, miniblock: MiniblockNumber) -> u64 {
                  timestamp increases",
                 extractors::display_timestamp(current_timestamp)
             );
+            false
         }
         cmp::Ordering::Greater => {
             // This situation can be triggered if the system keeper is started on a pod with a different
@@ -396,8 +718,10 @@ async fn sleep_past(timestamp: u64, miniblock: MiniblockNumber) -> u64 {
                 extractors::display_timestamp(timestamp),
                 extractors::display_timestamp(current_timestamp)
             );
+            KEEPER_METRICS.timestamp_behind_previous.inc();
+            true
         }
-    }
+    };
 
     // This loop should normally run once, since `tokio::time::sleep` sleeps *at least* the specified duration.
     // The logic is organized in a loop for marginal cases, such as the system time getting changed during `sleep()`.
@@ -414,6 +738,11 @@ async fn sleep_past(timestamp: u64, miniblock: MiniblockNumber) -> u64 {
         current_timestamp = (current_timestamp_millis / 1_000) as u64;
 
         if current_timestamp > timestamp {
+            if is_behind {
+                KEEPER_METRICS
+                    .timestamp_catch_up_wait
+                    .observe(started_at.elapsed());
+            }
             return current_timestamp;
         }
     }
@@ -473,9 +802,98 @@ impl MempoolIO {
             chain_id,
             virtual_blocks_interval: config.virtual_blocks_interval,
             virtual_blocks_per_miniblock: config.virtual_blocks_per_miniblock,
+            fair_ordering: None,
+            l1_timestamp_drift_guard: None,
+            metadata_lag_guard: None,
+            priority_tx_fee_multiplier: 1.0,
         })
     }
 
+    /// Bounds drift of L1 batch timestamps relative to the latest L1 block reported by `provider`. New L1 batches
+    /// are not opened while the local clock is ahead of L1 by more than `max_drift`.
+    pub fn with_l1_timestamp_drift_limit(
+        mut self,
+        provider: Arc<dyn L1BlockTimestampProvider>,
+        max_drift: Duration,
+    ) -> Self {
+        self.l1_timestamp_drift_guard = Some(L1TimestampDriftGuard {
+            provider,
+            max_drift,
+        });
+        self
+    }
+
+    /// Applies backpressure from the metadata calculator and / or the commitment generator. If a component lags
+    /// behind the state keeper by more than half of the specified number of L1 batches, batches are sealed
+    /// less eagerly; if the lag exceeds the limit, new L1 batches are not opened until the component catches up.
+    pub fn with_metadata_lag_limits(
+        mut self,
+        max_tree_lag: Option<u32>,
+        max_commitment_lag: Option<u32>,
+    ) -> Self {
+        if max_tree_lag.is_some() || max_commitment_lag.is_some() {
+            self.metadata_lag_guard = Some(MetadataLagGuard::new(max_tree_lag, max_commitment_lag));
+        }
+        self
+    }
+
+    /// Enables fair ordering: L2 transactions are included in the order of their arrival sequence numbers,
+    /// with at most `max_reordering` deviation within a miniblock. Transactions exceeding this bound
+    /// are deferred to the next miniblock.
+    pub fn with_fair_ordering(mut self, max_reordering: u64) -> Self {
+        self.fair_ordering = Some(FairOrdering::new(max_reordering));
+        self
+    }
+
+    /// Charges a premium for priority transactions: while the mempool contains priority transactions,
+    /// the fair L2 gas price of new L1 batches is multiplied by `multiplier`. The premium is capped so that
+    /// the resulting base fee doesn't exceed `max_fee_per_gas` of any pending priority transaction,
+    /// since such a transaction would otherwise fail in the bootloader.
+    pub fn with_priority_tx_fee_multiplier(mut self, multiplier: f64) -> Self {
+        self.priority_tx_fee_multiplier = multiplier;
+        self
+    }
+
+    /// Applies the priority transaction fee premium to the current filter, if applicable.
+    fn apply_priority_tx_fee_premium(&mut self, protocol_version: ProtocolVersionId) {
+        if self.priority_tx_fee_multiplier <= 1.0 {
+            return;
+        }
+        let Some(min_l1_tx_fee_per_gas) = self.mempool.min_l1_tx_fee_per_gas() else {
+            return;
+        };
+
+        let fee_input =
+            scale_fair_l2_gas_price(self.filter.fee_input, self.priority_tx_fee_multiplier);
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+        if U256::from(base_fee) > min_l1_tx_fee_per_gas {
+            tracing::debug!(
+                "Not applying priority tx fee premium for L1 batch #{}: base fee {base_fee} exceeds \
+                 max fee per gas {min_l1_tx_fee_per_gas} of a pending priority tx",
+                self.current_l1_batch_number
+            );
+            return;
+        }
+        self.filter = L2TxFilter {
+            fee_input,
+            fee_per_gas: base_fee,
+            gas_per_pubdata: gas_per_pubdata as u32,
+        };
+    }
+
+    fn accept_for_fair_ordering(&mut self, tx: &Transaction) -> bool {
+        let Some(fair_ordering) = &mut self.fair_ordering else {
+            return true;
+        };
+        match &tx.common_data {
+            ExecuteTransactionCommon::L2(data) => data
+                .arrival_seq
+                .map_or(true, |arrival_seq| fair_ordering.accept(arrival_seq)),
+            _ => true,
+        }
+    }
+
     fn update_miniblock_fields(&mut self, miniblock: &MiniblockUpdates) {
         assert_eq!(
             miniblock.number, self.current_miniblock_number.0,
@@ -484,6 +902,9 @@ impl MempoolIO {
         self.current_miniblock_number += 1;
         self.prev_miniblock_hash = miniblock.get_miniblock_hash();
         self.prev_miniblock_timestamp = miniblock.timestamp;
+        if let Some(fair_ordering) = &mut self.fair_ordering {
+            fair_ordering.reset();
+        }
     }
 
     async fn wait_for_previous_l1_batch_hash(&self) -> anyhow::Result<H256> {
@@ -585,4 +1006,106 @@ mod tests {
         .await;
         assert!(result.is_err());
     }
+
+    #[test]
+    fn fair_ordering_bounds_reordering() {
+        let mut fair_ordering = FairOrdering::new(2);
+        assert!(fair_ordering.accept(10));
+        assert!(fair_ordering.accept(8));
+        assert!(fair_ordering.accept(15));
+        assert!(fair_ordering.accept(13));
+        assert!(!fair_ordering.seal_requested);
+
+        assert!(!fair_ordering.accept(12));
+        assert!(fair_ordering.seal_requested);
+
+        fair_ordering.reset();
+        assert!(fair_ordering.accept(12));
+        assert!(!fair_ordering.seal_requested);
+    }
+
+    #[test]
+    fn scaling_fair_l2_gas_price() {
+        let fee_input = BatchFeeInput::l1_pegged(1_000, 100);
+        let scaled = scale_fair_l2_gas_price(fee_input, 1.5);
+        assert_eq!(scaled, BatchFeeInput::l1_pegged(1_000, 150));
+
+        let fee_input = BatchFeeInput::pubdata_independent(1_000, 100, 10);
+        let scaled = scale_fair_l2_gas_price(fee_input, 1.5);
+        assert_eq!(scaled, BatchFeeInput::pubdata_independent(1_000, 150, 10));
+    }
+
+    #[derive(Debug)]
+    struct MockL1TimestampProvider {
+        timestamp: Option<u64>,
+        is_stale: bool,
+    }
+
+    impl MockL1TimestampProvider {
+        fn new(timestamp: Option<u64>) -> Self {
+            Self {
+                timestamp,
+                is_stale: false,
+            }
+        }
+    }
+
+    impl L1BlockTimestampProvider for MockL1TimestampProvider {
+        fn latest_l1_block_timestamp(&self) -> Option<u64> {
+            self.timestamp
+        }
+
+        fn is_l1_data_stale(&self) -> bool {
+            self.is_stale
+        }
+    }
+
+    #[test]
+    fn l1_timestamp_drift_guard() {
+        let guard = L1TimestampDriftGuard {
+            provider: Arc::new(MockL1TimestampProvider::new(Some(1_000))),
+            max_drift: Duration::from_secs(60),
+        };
+        assert!(guard.check(1_000, L1BatchNumber(1)));
+        assert!(guard.check(1_060, L1BatchNumber(1)));
+        assert!(!guard.check(1_061, L1BatchNumber(1)));
+        // Timestamps behind L1 are not fixable by waiting, so they are allowed.
+        assert!(guard.check(900, L1BatchNumber(1)));
+
+        let guard = L1TimestampDriftGuard {
+            provider: Arc::new(MockL1TimestampProvider::new(None)),
+            max_drift: Duration::from_secs(60),
+        };
+        assert!(guard.check(1_000_000, L1BatchNumber(1)));
+
+        // The check fails open if L1 data is stale.
+        let guard = L1TimestampDriftGuard {
+            provider: Arc::new(MockL1TimestampProvider {
+                timestamp: Some(1_000),
+                is_stale: true,
+            }),
+            max_drift: Duration::from_secs(60),
+        };
+        assert!(guard.check(1_000_000, L1BatchNumber(1)));
+    }
+
+    #[test]
+    fn metadata_lag_guard() {
+        let mut guard = MetadataLagGuard::new(Some(10), None);
+        assert!(guard.update_status(L1BatchNumber(1), 0, 100));
+        assert!(!guard.is_lag_elevated());
+        assert!(guard.update_status(L1BatchNumber(10), 6, 100));
+        assert!(guard.is_lag_elevated());
+        assert!(!guard.update_status(L1BatchNumber(20), 11, 100));
+        assert!(guard.is_lag_elevated());
+        assert!(guard.update_status(L1BatchNumber(20), 5, 0));
+        assert!(!guard.is_lag_elevated());
+
+        let mut guard = MetadataLagGuard::new(Some(10), Some(4));
+        assert!(guard.update_status(L1BatchNumber(10), 0, 3));
+        assert!(guard.is_lag_elevated());
+        assert!(!guard.update_status(L1BatchNumber(10), 0, 5));
+        assert!(guard.update_status(L1BatchNumber(10), 1, 1));
+        assert!(!guard.is_lag_elevated());
+    }
 }
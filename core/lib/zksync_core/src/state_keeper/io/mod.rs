@@ -1,22 +1,25 @@
 use std::{
+    collections::HashMap,
     fmt,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
 use async_trait::async_trait;
 use multivm::interface::{FinishedL1Batch, L1BatchEnv, SystemEnv};
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
     block::MiniblockExecutionData, protocol_version::ProtocolUpgradeTx,
-    witness_block_state::WitnessBlockState, L1BatchNumber, MiniblockNumber, ProtocolVersionId,
-    Transaction,
+    storage_writes_deduplicator::StorageWritesDeduplicator, witness_block_state::WitnessBlockState,
+    L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, Transaction, H256,
 };
+use zksync_utils::u256_to_h256;
 
 use super::{
     metrics::{MiniblockQueueStage, MINIBLOCK_METRICS},
     seal_criteria::IoSealCriteria,
-    updates::{MiniblockSealCommand, UpdatesManager},
+    updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
 };
 
 pub(crate) mod common;
@@ -133,6 +136,42 @@ struct Completable<T> {
     completion_sender: oneshot::Sender<()>,
 }
 
+/// Storage slots modified in a sealed miniblock together with their values at the end of the miniblock.
+/// Writes that don't change the slot value are not included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MiniblockWriteSet {
+    pub number: MiniblockNumber,
+    pub writes: HashMap<StorageKey, H256>,
+}
+
+impl MiniblockWriteSet {
+    fn new(miniblock: &MiniblockUpdates) -> Self {
+        let mut storage_writes_deduplicator = StorageWritesDeduplicator::new();
+        storage_writes_deduplicator.apply(&miniblock.storage_logs);
+        let writes = storage_writes_deduplicator
+            .into_modified_key_values()
+            .into_iter()
+            .map(|(key, slot)| (key, u256_to_h256(slot.value)))
+            .collect();
+        Self {
+            number: MiniblockNumber(miniblock.number),
+            writes,
+        }
+    }
+}
+
+/// Publishes the write set of a sealed `miniblock` if write sets are requested.
+fn publish_write_set(
+    write_sets_sender: Option<&broadcast::Sender<Arc<MiniblockWriteSet>>>,
+    miniblock: &MiniblockUpdates,
+) {
+    if let Some(sender) = write_sets_sender {
+        let write_set = MiniblockWriteSet::new(miniblock);
+        // Errors only on 0 receivers, which is fine (e.g., if the API server has not started yet).
+        sender.send(Arc::new(write_set)).ok();
+    }
+}
+
 /// Handle for [`MiniblockSealer`] allowing to submit [`MiniblockSealCommand`]s.
 #[derive(Debug)]
 pub struct MiniblockSealerHandle {
@@ -140,6 +179,7 @@ pub struct MiniblockSealerHandle {
     latest_completion_receiver: Option<oneshot::Receiver<()>>,
     // If true, `submit()` will wait for the operation to complete.
     is_sync: bool,
+    write_sets_sender: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
 }
 
 impl MiniblockSealerHandle {
@@ -210,6 +250,12 @@ impl MiniblockSealerHandle {
                 .observe(elapsed);
         }
     }
+
+    /// Publishes the write set of a miniblock persisted outside the sealer (i.e., a fictive miniblock
+    /// persisted together with its L1 batch). Must be called after the miniblock is committed to Postgres.
+    pub(crate) fn publish_write_set(&self, miniblock: &MiniblockUpdates) {
+        publish_write_set(self.write_sets_sender.as_ref(), miniblock);
+    }
 }
 
 /// Component responsible for sealing miniblocks (i.e., storing their data to Postgres).
@@ -220,12 +266,31 @@ pub struct MiniblockSealer {
     // Weak sender handle to get queue capacity stats.
     commands_sender: mpsc::WeakSender<Completable<MiniblockSealCommand>>,
     commands_receiver: mpsc::Receiver<Completable<MiniblockSealCommand>>,
+    write_sets_sender: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
 }
 
 impl MiniblockSealer {
     /// Creates a sealer that will use the provided Postgres connection and will have the specified
     /// `command_capacity` for unprocessed sealing commands.
-    pub fn new(pool: ConnectionPool, mut command_capacity: usize) -> (Self, MiniblockSealerHandle) {
+    pub fn new(pool: ConnectionPool, command_capacity: usize) -> (Self, MiniblockSealerHandle) {
+        Self::new_inner(pool, command_capacity, None)
+    }
+
+    /// Same as [`Self::new()`], but additionally publishes [`MiniblockWriteSet`]s of sealed miniblocks
+    /// (including fictive ones) to the provided channel once they are persisted.
+    pub fn with_write_sets(
+        pool: ConnectionPool,
+        command_capacity: usize,
+        write_sets_sender: broadcast::Sender<Arc<MiniblockWriteSet>>,
+    ) -> (Self, MiniblockSealerHandle) {
+        Self::new_inner(pool, command_capacity, Some(write_sets_sender))
+    }
+
+    fn new_inner(
+        pool: ConnectionPool,
+        mut command_capacity: usize,
+        write_sets_sender: Option<broadcast::Sender<Arc<MiniblockWriteSet>>>,
+    ) -> (Self, MiniblockSealerHandle) {
         let is_sync = command_capacity == 0;
         command_capacity = command_capacity.max(1);
 
@@ -235,11 +300,13 @@ impl MiniblockSealer {
             is_sync,
             commands_sender: commands_sender.downgrade(),
             commands_receiver,
+            write_sets_sender: write_sets_sender.clone(),
         };
         let handle = MiniblockSealerHandle {
             commands_sender,
             latest_completion_receiver: None,
             is_sync,
+            write_sets_sender,
         };
         (this, handle)
     }
@@ -268,6 +335,10 @@ impl MiniblockSealer {
                 .await
                 .unwrap();
             completable.command.seal(&mut conn).await;
+            publish_write_set(
+                self.write_sets_sender.as_ref(),
+                &completable.command.miniblock,
+            );
             if let Some(delta) = miniblock_seal_delta {
                 MINIBLOCK_METRICS.seal_delta.observe(delta.elapsed());
             }
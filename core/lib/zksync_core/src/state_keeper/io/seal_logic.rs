@@ -37,6 +37,7 @@ use crate::{
             L1BatchSealStage, MiniblockSealStage, TxExecutionType, KEEPER_METRICS,
             L1_BATCH_METRICS, MINIBLOCK_METRICS,
         },
+        seal_criteria::L1_BATCH_SEALED_REASON,
         types::ExecutionMetricsForCriteria,
         updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
     },
@@ -71,6 +72,7 @@ impl UpdatesManager {
             batch_tip_execution_metrics,
         );
         // Seal fictive miniblock with last events and storage logs.
+        self.set_miniblock_seal_reason(Some(L1_BATCH_SEALED_REASON));
         let miniblock_command = self.seal_miniblock_command(
             l1_batch_env.number,
             current_miniblock_number,
@@ -153,6 +155,19 @@ impl UpdatesManager {
             )
             .await
             .unwrap();
+        transaction
+            .blocks_dal()
+            .save_l1_batch_operation_statistic(
+                l1_batch_env.number,
+                &self.pending_execution_metrics().operation_statistic,
+            )
+            .await
+            .unwrap();
+        transaction
+            .blocks_dal()
+            .save_l1_batch_seal_info(l1_batch_env.number, &self.l1_batch_seal_info())
+            .await
+            .unwrap();
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::SetL1BatchNumberForMiniblocks);
@@ -348,11 +363,12 @@ impl MiniblockSealCommand {
                     .into(),
             ),
             virtual_blocks: self.miniblock.virtual_blocks,
+            gas_limit: self.gas_limit,
         };
 
         transaction
             .blocks_dal()
-            .insert_miniblock(&miniblock_header)
+            .insert_miniblock_with_seal_info(&miniblock_header, &self.seal_info)
             .await
             .unwrap();
         progress.observe(None);
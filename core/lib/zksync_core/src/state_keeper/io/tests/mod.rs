@@ -1,12 +1,13 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use futures::FutureExt;
 use multivm::utils::derive_base_fee_and_gas_per_pubdata;
+use tokio::sync::broadcast;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
 use zksync_types::{
-    block::{BlockGasCount, MiniblockHasher},
+    block::{BlockGasCount, MiniblockHasher, SealInfo},
     fee::TransactionExecutionMetrics,
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
     tx::ExecutionMetrics,
@@ -258,6 +259,8 @@ async fn processing_storage_logs_when_sealing_miniblock() {
         base_fee_per_gas: 10,
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
         protocol_version: Some(ProtocolVersionId::latest()),
+        gas_limit: None,
+        seal_info: SealInfo::default(),
         l2_erc20_bridge_addr: Address::default(),
         pre_insert_txs: false,
     };
@@ -336,6 +339,8 @@ async fn processing_events_when_sealing_miniblock() {
         base_fee_per_gas: 10,
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
         protocol_version: Some(ProtocolVersionId::latest()),
+        gas_limit: None,
+        seal_info: SealInfo::default(),
         l2_erc20_bridge_addr: Address::default(),
         pre_insert_txs: false,
     };
@@ -656,6 +661,60 @@ async fn miniblock_sealer_handle_parallel_processing() {
     sealer_handle.wait_for_all_commands().await;
 }
 
+#[tokio::test]
+async fn miniblock_sealer_publishes_write_sets() {
+    let pool = ConnectionPool::constrained_test_pool(1).await;
+    let mut conn = pool.access_storage().await.unwrap();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+    drop(conn);
+
+    let (write_sets_sender, mut write_sets) = broadcast::channel(1);
+    let (sealer, mut sealer_handle) = MiniblockSealer::with_write_sets(pool, 1, write_sets_sender);
+    let sealer_task = tokio::spawn(sealer.run());
+
+    let mut updates_manager = create_updates_manager();
+    let storage_logs = [
+        (U256::from(1), Query::Read(U256::from(0))),
+        (U256::from(2), Query::InitialWrite(U256::from(1))),
+        (
+            U256::from(2),
+            Query::RepeatedWrite(U256::from(1), U256::from(4)),
+        ),
+        // No-op write that must not be included into the write set.
+        (
+            U256::from(3),
+            Query::RepeatedWrite(U256::from(5), U256::from(5)),
+        ),
+    ];
+    updates_manager.extend_from_executed_transaction(
+        create_transaction(10, 100),
+        create_execution_result(0, storage_logs),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    let seal_command = updates_manager.seal_miniblock_command(
+        L1BatchNumber(1),
+        MiniblockNumber(1),
+        Address::default(),
+        false,
+    );
+    sealer_handle.submit(seal_command).await;
+    sealer_handle.wait_for_all_commands().await;
+
+    let write_set = write_sets.try_recv().unwrap();
+    assert_eq!(write_set.number, MiniblockNumber(1));
+    let key = StorageKey::new(AccountTreeId::default(), H256::from_low_u64_be(2));
+    let expected_writes = HashMap::from([(key, H256::from_low_u64_be(4))]);
+    assert_eq!(write_set.writes, expected_writes);
+
+    drop(sealer_handle);
+    sealer_task.await.unwrap().unwrap();
+}
+
 /// Ensure that subsequent miniblocks that belong to the same L1 batch have different timestamps
 #[tokio::test]
 async fn different_timestamp_for_miniblocks_in_same_batch() {
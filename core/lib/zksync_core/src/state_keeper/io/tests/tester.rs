@@ -63,6 +63,8 @@ impl Tester {
             num_samples_for_blob_base_fee_estimate: 10,
             internal_pubdata_pricing_multiplier: 1.0,
             max_blob_base_fee: None,
+            max_l1_staleness_sec: None,
+            internal_enforced_pubdata_price: None,
         };
 
         GasAdjuster::new(
@@ -1,6 +1,7 @@
 use std::{
+    collections::BTreeMap,
     convert::Infallible,
-    future::{self, Future},
+    future::Future,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -11,7 +12,7 @@ use tokio::sync::watch;
 use zksync_dal::ConnectionPool;
 use zksync_types::{
     block::MiniblockExecutionData,
-    l2::TransactionType,
+    l2::{TransactionType, TX_EXPIRED_ERROR},
     protocol_version::{ProtocolUpgradeTx, ProtocolVersionId},
     storage_writes_deduplicator::StorageWritesDeduplicator,
     L1BatchNumber, Transaction,
@@ -22,11 +23,17 @@ use super::{
     extractors,
     io::{MiniblockParams, PendingBatchData, StateKeeperIO},
     metrics::{AGGREGATION_METRICS, KEEPER_METRICS, L1_BATCH_METRICS},
-    seal_criteria::{ConditionalSealer, SealData, SealResolution},
+    seal_criteria::{
+        ConditionalSealer, SealData, SealResolution, CHAIN_FROZEN_SEAL_REASON,
+        L1_BATCH_SEALED_REASON, MINIBLOCK_GAS_LIMIT_CRITERION,
+    },
+    tx_hook::{ExecutedTxInfo, TxHookRunner},
+    tx_size_limits::TxSizeLimits,
     types::ExecutionMetricsForCriteria,
-    updates::UpdatesManager,
+    updates::{miniblock_updates::gas_used_by_tx, UpdatesManager},
 };
 use crate::{
+    api_server::tx_sender::sponsorship::SponsorshipPolicy,
     gas_tracker::gas_count_from_writes,
     state_keeper::{io::fee_address_migration, metrics::BATCH_TIP_METRICS},
 };
@@ -68,6 +75,12 @@ pub struct ZkSyncStateKeeper {
     io: Box<dyn StateKeeperIO>,
     batch_executor_base: Box<dyn BatchExecutor>,
     sealer: Arc<dyn ConditionalSealer>,
+    tx_hook: Option<TxHookRunner>,
+    tx_size_limits: Option<TxSizeLimits>,
+    sponsorship_policy: Option<SponsorshipPolicy>,
+    miniblock_gas_limit: Option<u64>,
+    seal_miniblock_on_shutdown: bool,
+    chain_freeze_receiver: Option<watch::Receiver<bool>>,
 }
 
 impl ZkSyncStateKeeper {
@@ -82,20 +95,78 @@ impl ZkSyncStateKeeper {
             io,
             batch_executor_base,
             sealer,
+            tx_hook: None,
+            tx_size_limits: None,
+            sponsorship_policy: None,
+            miniblock_gas_limit: None,
+            seal_miniblock_on_shutdown: false,
+            chain_freeze_receiver: None,
         }
     }
 
+    /// Sets a hook notified about executed transactions before they are included into a miniblock.
+    #[must_use]
+    pub fn with_tx_hook(mut self, tx_hook: TxHookRunner) -> Self {
+        self.tx_hook = Some(tx_hook);
+        self
+    }
+
+    /// Makes the state keeper reject L2 transactions exceeding the specified size limits before executing them.
+    /// This must only be enabled on the main node.
+    #[must_use]
+    pub fn with_tx_size_limits(mut self, tx_size_limits: TxSizeLimits) -> Self {
+        self.tx_size_limits = Some(tx_size_limits);
+        self
+    }
+
+    /// Makes the state keeper reject L2 transactions using the sponsoring paymaster that don't satisfy
+    /// the sponsorship policy. This must only be enabled on the main node.
+    #[must_use]
+    pub fn with_sponsorship_policy(mut self, policy: SponsorshipPolicy) -> Self {
+        self.sponsorship_policy = Some(policy);
+        self
+    }
+
+    /// Makes the state keeper enforce the specified gas limit for miniblocks in L1 batches with protocol versions
+    /// supporting it. A transaction not fitting into the current miniblock is moved to the next miniblock.
+    /// This must only be enabled on the main node; external nodes take miniblock gas limits from the main node.
+    #[must_use]
+    pub fn with_miniblock_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.miniblock_gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Makes the state keeper seal the current miniblock (if it contains any transactions) when a stop signal
+    /// is received, so that executed transactions are persisted before the process exits. This must only be enabled
+    /// on the main node; on external nodes, miniblocks must match the ones produced by the main node.
+    #[must_use]
+    pub fn with_miniblock_sealing_on_shutdown(mut self) -> Self {
+        self.seal_miniblock_on_shutdown = true;
+        self
+    }
+
+    /// Makes the state keeper stop at a clean boundary while the chain is frozen: no new transactions are executed,
+    /// the current L1 batch is sealed (unless it's empty), and a new L1 batch is not started until the chain
+    /// is unfrozen. This must only be enabled on the main node.
+    #[must_use]
+    pub fn with_chain_freeze(mut self, freeze_receiver: watch::Receiver<bool>) -> Self {
+        self.chain_freeze_receiver = Some(freeze_receiver);
+        self
+    }
+
     /// Temporary method to migrate fee addresses from L1 batches to miniblocks.
     pub fn run_fee_address_migration(
         &self,
         pool: ConnectionPool,
     ) -> impl Future<Output = anyhow::Result<()>> {
         let last_miniblock = self.io.current_miniblock_number() - 1;
-        let stop_receiver = self.stop_receiver.clone();
+        let mut stop_receiver = self.stop_receiver.clone();
         async move {
-            fee_address_migration::migrate_miniblocks(pool, last_miniblock, stop_receiver).await?;
-            future::pending::<()>().await;
-            // ^ Since this is run as a task, we don't want it to exit on success (this would shut down the node).
+            fee_address_migration::migrate_miniblocks(pool, last_miniblock, stop_receiver.clone())
+                .await?;
+            // Since this is run as a task, we don't want it to exit on success (this would shut down the node).
+            // We still want it to exit on a stop signal, so that it doesn't hold up a graceful shutdown.
+            stop_receiver.wait_for(|stop| *stop).await.ok();
             anyhow::Ok(())
         }
     }
@@ -145,6 +216,7 @@ impl ZkSyncStateKeeper {
             }
             None => {
                 tracing::info!("There is no open pending batch, starting a new empty batch");
+                self.wait_while_frozen().await?;
                 let (system_env, l1_batch_env) = self
                     .wait_for_new_batch_params()
                     .await
@@ -158,7 +230,7 @@ impl ZkSyncStateKeeper {
         };
 
         let protocol_version = system_env.version;
-        let mut updates_manager = UpdatesManager::new(&l1_batch_env, &system_env);
+        let mut updates_manager = self.create_updates_manager(&l1_batch_env, &system_env);
 
         let mut protocol_upgrade_tx: Option<ProtocolUpgradeTx> = self
             .load_protocol_upgrade_tx(&pending_miniblocks, protocol_version, l1_batch_env.number)
@@ -185,6 +257,7 @@ impl ZkSyncStateKeeper {
 
             // Finish current batch.
             if !updates_manager.miniblock.executed_transactions.is_empty() {
+                self.set_miniblock_seal_info(&mut updates_manager, Some(L1_BATCH_SEALED_REASON));
                 self.io.seal_miniblock(&updates_manager).await;
                 // We've sealed the miniblock that we had, but we still need to setup the timestamp
                 // for the fictive miniblock.
@@ -196,6 +269,11 @@ impl ZkSyncStateKeeper {
                 )
                 .await;
             }
+            let capacity_filled = self.l1_batch_capacity_filled(&updates_manager);
+            // The fictive miniblock sealed together with the batch has the same capacity filled as the batch.
+            updates_manager.set_miniblock_capacity_filled(capacity_filled.clone());
+            updates_manager.set_l1_batch_capacity_filled(capacity_filled);
+
             let (finished_batch, witness_block_state) = batch_executor.finish_batch().await;
             let sealed_batch_protocol_version = updates_manager.protocol_version();
             self.io
@@ -207,14 +285,16 @@ impl ZkSyncStateKeeper {
                 )
                 .await
                 .with_context(|| format!("failed sealing L1 batch {l1_batch_env:?}"))?;
+            self.sealer.reset_capacity_metrics();
             if let Some(delta) = l1_batch_seal_delta {
                 L1_BATCH_METRICS.seal_delta.observe(delta.elapsed());
             }
             l1_batch_seal_delta = Some(Instant::now());
 
             // Start the new batch.
+            self.wait_while_frozen().await?;
             (system_env, l1_batch_env) = self.wait_for_new_batch_params().await?;
-            updates_manager = UpdatesManager::new(&l1_batch_env, &system_env);
+            updates_manager = self.create_updates_manager(&l1_batch_env, &system_env);
             batch_executor = self
                 .batch_executor_base
                 .init_batch(
@@ -235,6 +315,19 @@ impl ZkSyncStateKeeper {
         Err(Error::Canceled)
     }
 
+    fn create_updates_manager(
+        &self,
+        l1_batch_env: &L1BatchEnv,
+        system_env: &SystemEnv,
+    ) -> UpdatesManager {
+        let mut updates_manager = UpdatesManager::new(l1_batch_env, system_env);
+        let miniblock_gas_limit = self
+            .miniblock_gas_limit
+            .filter(|_| system_env.version.supports_miniblock_gas_limit());
+        updates_manager.set_miniblock_gas_limit(miniblock_gas_limit);
+        updates_manager
+    }
+
     /// This function is meant to be called only once during the state-keeper initialization.
     /// It will check if we should load a protocol upgrade or a `setChainId` transaction,
     /// perform some checks and return it.
@@ -283,10 +376,60 @@ impl ZkSyncStateKeeper {
         Ok(protocol_upgrade_tx)
     }
 
+    /// Returns the percentage of the L1 batch capacity filled by the executed transactions according
+    /// to each seal criterion.
+    fn l1_batch_capacity_filled(
+        &self,
+        updates_manager: &UpdatesManager,
+    ) -> BTreeMap<&'static str, f64> {
+        let seal_data = SealData::for_l1_batch(updates_manager);
+        self.sealer.capacity_filled(
+            updates_manager.pending_executed_transactions_len(),
+            &seal_data,
+            updates_manager.protocol_version(),
+        )
+    }
+
+    /// Sets the seal reason for the current miniblock together with the L1 batch capacity filled
+    /// as of sealing the miniblock.
+    fn set_miniblock_seal_info(
+        &self,
+        updates_manager: &mut UpdatesManager,
+        reason: Option<&'static str>,
+    ) {
+        updates_manager.set_miniblock_seal_reason(reason);
+        updates_manager
+            .set_miniblock_capacity_filled(self.l1_batch_capacity_filled(updates_manager));
+    }
+
     fn is_canceled(&self) -> bool {
         *self.stop_receiver.borrow()
     }
 
+    fn is_frozen(&self) -> bool {
+        self.chain_freeze_receiver
+            .as_ref()
+            .map_or(false, |receiver| *receiver.borrow())
+    }
+
+    async fn wait_while_frozen(&mut self) -> Result<(), Error> {
+        if !self.is_frozen() {
+            return Ok(());
+        }
+        tracing::warn!(
+            "Chain is frozen; state keeper is paused before L1 batch #{}",
+            self.io.current_l1_batch_number()
+        );
+        while self.is_frozen() {
+            if self.is_canceled() {
+                return Err(Error::Canceled);
+            }
+            tokio::time::sleep(POLL_WAIT_DURATION).await;
+        }
+        tracing::info!("Chain is unfrozen; resuming state keeper");
+        Ok(())
+    }
+
     async fn load_upgrade_tx(
         &mut self,
         protocol_version: ProtocolVersionId,
@@ -450,7 +593,22 @@ impl ZkSyncStateKeeper {
                 .await;
         }
 
+        // Set if the last executed transaction didn't fit into the current miniblock according to its gas limit.
+        let mut miniblock_gas_exhausted = false;
         while !self.is_canceled() {
+            if self.is_frozen() {
+                if updates_manager.pending_executed_transactions_len() > 0 {
+                    tracing::warn!(
+                        "Chain is frozen; sealing L1 batch #{}",
+                        self.io.current_l1_batch_number()
+                    );
+                    updates_manager.set_l1_batch_seal_reason(Some(CHAIN_FROZEN_SEAL_REASON));
+                    return Ok(());
+                }
+                self.wait_while_frozen().await?;
+                continue;
+            }
+
             if self
                 .io
                 .should_seal_l1_batch_unconditionally(updates_manager)
@@ -459,16 +617,26 @@ impl ZkSyncStateKeeper {
                     "L1 batch #{} should be sealed unconditionally as per sealing rules",
                     self.io.current_l1_batch_number()
                 );
+                updates_manager.set_l1_batch_seal_reason(self.io.l1_batch_seal_reason());
                 return Ok(());
             }
 
-            if self.io.should_seal_miniblock(updates_manager) {
+            let should_seal_by_gas = miniblock_gas_exhausted
+                && !updates_manager.miniblock.executed_transactions.is_empty();
+            if should_seal_by_gas || self.io.should_seal_miniblock(updates_manager) {
                 tracing::debug!(
                     "Miniblock #{} (L1 batch #{}) should be sealed as per sealing rules",
                     self.io.current_miniblock_number(),
                     self.io.current_l1_batch_number()
                 );
+                let seal_reason = if should_seal_by_gas {
+                    Some(MINIBLOCK_GAS_LIMIT_CRITERION)
+                } else {
+                    self.io.miniblock_seal_reason()
+                };
+                self.set_miniblock_seal_info(updates_manager, seal_reason);
                 self.io.seal_miniblock(updates_manager).await;
+                miniblock_gas_exhausted = false;
 
                 let new_miniblock_params = self
                     .wait_for_new_miniblock_params()
@@ -493,6 +661,46 @@ impl ZkSyncStateKeeper {
             waiting_latency.observe();
 
             let tx_hash = tx.hash();
+            let is_expired = tx.valid_until().map_or(false, |valid_until| {
+                valid_until.is_expired(
+                    self.io.current_miniblock_number(),
+                    updates_manager.miniblock.timestamp,
+                )
+            });
+            if is_expired {
+                self.io
+                    .reject(&tx, TX_EXPIRED_ERROR)
+                    .await
+                    .with_context(|| format!("cannot reject expired transaction {tx_hash:?}"))?;
+                continue;
+            }
+            let size_limit_error = self
+                .tx_size_limits
+                .filter(|_| !tx.is_l1())
+                .and_then(|limits| limits.check_tx(&tx).err());
+            if let Some(err) = size_limit_error {
+                self.io
+                    .reject(&tx, &err.to_string())
+                    .await
+                    .with_context(|| format!("cannot reject oversized transaction {tx_hash:?}"))?;
+                continue;
+            }
+            let sponsorship_error = self
+                .sponsorship_policy
+                .as_ref()
+                .and_then(|policy| policy.check_transaction(&tx).err());
+            if let Some(err) = sponsorship_error {
+                self.io
+                    .reject(
+                        &tx,
+                        &format!("transaction is not eligible for sponsorship: {err}"),
+                    )
+                    .await
+                    .with_context(|| {
+                        format!("cannot reject unsponsored transaction {tx_hash:?}")
+                    })?;
+                continue;
+            }
             let (seal_resolution, exec_result) = self
                 .process_one_tx(batch_executor, updates_manager, tx.clone())
                 .await;
@@ -511,6 +719,63 @@ impl ZkSyncStateKeeper {
                             "Tx inclusion seal resolution must be a result of a successful tx execution",
                         );
                     };
+
+                    let tx_gas_used = gas_used_by_tx(&tx, tx_result.refunds.gas_refunded);
+                    if !updates_manager.fits_into_miniblock(tx_gas_used) {
+                        miniblock_gas_exhausted = true;
+                        if updates_manager.miniblock.executed_transactions.is_empty() {
+                            tracing::warn!(
+                                "Transaction {tx_hash:?} uses {tx_gas_used} gas, which exceeds the miniblock gas limit; \
+                                 it will be included into a separate miniblock"
+                            );
+                        } else {
+                            tracing::debug!(
+                                "Transaction {tx_hash:?} using {tx_gas_used} gas doesn't fit into miniblock #{}; \
+                                 moving it to the next miniblock",
+                                self.io.current_miniblock_number()
+                            );
+                            batch_executor.rollback_last_tx().await;
+                            self.io.rollback(tx).await;
+                            continue;
+                        }
+                    }
+
+                    let sponsorship_error = self.sponsorship_policy.as_ref().and_then(|policy| {
+                        let pubdata_published = tx_metrics.execution_metrics.pubdata_published;
+                        policy
+                            .check_transaction_pubdata(&tx, pubdata_published)
+                            .err()
+                    });
+                    if let Some(err) = sponsorship_error {
+                        batch_executor.rollback_last_tx().await;
+                        self.io
+                            .reject(
+                                &tx,
+                                &format!("transaction is not eligible for sponsorship: {err}"),
+                            )
+                            .await
+                            .with_context(|| {
+                                format!("cannot reject unsponsored transaction {tx_hash:?}")
+                            })?;
+                        continue;
+                    }
+
+                    if let Some(tx_hook) = &self.tx_hook {
+                        let tx_info = ExecutedTxInfo::new(
+                            &tx,
+                            &tx_result,
+                            self.io.current_l1_batch_number(),
+                            self.io.current_miniblock_number(),
+                        );
+                        if let Some(reason) = tx_hook.run(tx_info).await {
+                            batch_executor.rollback_last_tx().await;
+                            self.io.reject(&tx, &reason).await.with_context(|| {
+                                format!("cannot reject transaction {tx_hash:?}")
+                            })?;
+                            continue;
+                        }
+                    }
+
                     let ExecutionMetricsForCriteria {
                         l1_gas: tx_l1_gas_this_tx,
                         execution_metrics: tx_execution_metrics,
@@ -546,6 +811,19 @@ impl ZkSyncStateKeeper {
                 return Ok(());
             }
         }
+
+        if self.seal_miniblock_on_shutdown
+            && !updates_manager.miniblock.executed_transactions.is_empty()
+        {
+            tracing::info!(
+                "Sealing miniblock #{} (L1 batch #{}) with {} transactions before shutting down",
+                self.io.current_miniblock_number(),
+                self.io.current_l1_batch_number(),
+                updates_manager.miniblock.executed_transactions.len()
+            );
+            self.set_miniblock_seal_info(updates_manager, Some("shutdown"));
+            self.io.seal_miniblock(updates_manager).await;
+        }
         Err(Error::Canceled)
     }
 
@@ -615,8 +893,9 @@ impl ZkSyncStateKeeper {
     /// 1. The VM entered an incorrect state (e.g. out of gas). In that case, we must revert the transaction and seal
     /// the block.
     /// 2. Seal manager decided that batch is ready to be sealed.
-    /// Note: this method doesn't mutate `updates_manager` in the end. However, reference should be mutable
-    /// because we use `apply_and_rollback` method of `updates_manager.storage_writes_deduplicator`.
+    /// Note: this method doesn't mutate `updates_manager` in the end, except for recording the L1 batch seal reason
+    /// if the batch should be sealed. Also, the reference should be mutable because we use `apply_and_rollback` method
+    /// of `updates_manager.storage_writes_deduplicator`.
     async fn process_one_tx(
         &mut self,
         batch_executor: &BatchExecutorHandle,
@@ -654,6 +933,7 @@ impl ZkSyncStateKeeper {
                 let resolution = if is_first_tx {
                     SealResolution::Unexecutable(error_message.to_string())
                 } else {
+                    updates_manager.set_l1_batch_seal_reason(Some(error_message));
                     SealResolution::ExcludeAndSeal
                 };
                 AGGREGATION_METRICS.inc(error_message, &resolution);
@@ -746,14 +1026,18 @@ impl ZkSyncStateKeeper {
                     gas_remaining: *gas_remaining,
                 };
 
-                self.sealer.should_seal_l1_batch(
+                let (resolution, seal_reason) = self.sealer.should_seal_l1_batch(
                     self.io.current_l1_batch_number().0,
                     updates_manager.batch_timestamp() as u128 * 1_000,
                     updates_manager.pending_executed_transactions_len() + 1,
                     &block_data,
                     &tx_data,
                     updates_manager.protocol_version(),
-                )
+                );
+                if resolution.should_seal() {
+                    updates_manager.set_l1_batch_seal_reason(seal_reason);
+                }
+                resolution
             }
         };
         (resolution, exec_result)
@@ -10,7 +10,10 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_mempool::L2TxFilter;
 #[cfg(test)]
 use zksync_types::H256;
-use zksync_types::{get_nonce_key, Address, Nonce, Transaction, VmVersion};
+use zksync_types::{
+    get_is_account_key, get_nonce_key, AccountNonceOrdering, Address, Nonce, Transaction, VmVersion,
+};
+use zksync_utils::time::seconds_since_epoch;
 
 use super::{metrics::KEEPER_METRICS, types::MempoolGuard};
 use crate::{fee_model::BatchFeeModelInputProvider, utils::pending_protocol_version};
@@ -98,6 +101,24 @@ impl MempoolFetcher {
             )
             .await;
 
+            let sealed_miniblock_number = storage
+                .blocks_dal()
+                .get_sealed_miniblock_number()
+                .await
+                .context("failed getting sealed miniblock number")?
+                .unwrap_or_default();
+            let expired_txs = storage
+                .transactions_dal()
+                .mark_expired_txs(sealed_miniblock_number, seconds_since_epoch())
+                .await
+                .context("failed marking expired transactions")?;
+            if expired_txs > 0 {
+                tracing::info!("Marked {expired_txs} transactions with passed deadline as expired");
+                KEEPER_METRICS
+                    .expired_transactions
+                    .inc_by(expired_txs as u64);
+            }
+
             let transactions = storage
                 .transactions_dal()
                 .sync_mempool(
@@ -110,6 +131,8 @@ impl MempoolFetcher {
                 .await
                 .context("failed syncing mempool")?;
             let nonces = get_transaction_nonces(&mut storage, &transactions).await?;
+            let nonce_orderings =
+                get_transaction_nonce_orderings(&mut storage, &transactions).await?;
             drop(storage);
 
             #[cfg(test)]
@@ -118,7 +141,8 @@ impl MempoolFetcher {
                 self.transaction_hashes_sender.send(transaction_hashes).ok();
             }
             let all_transactions_loaded = transactions.len() < self.sync_batch_size;
-            self.mempool.insert(transactions, nonces);
+            self.mempool
+                .insert_with_nonce_orderings(transactions, nonces, &nonce_orderings);
             latency.observe();
 
             if all_transactions_loaded {
@@ -158,6 +182,35 @@ async fn get_transaction_nonces(
         .collect())
 }
 
+/// Loads nonce orderings for all distinct `transactions` initiators from the storage.
+async fn get_transaction_nonce_orderings(
+    storage: &mut StorageProcessor<'_>,
+    transactions: &[Transaction],
+) -> anyhow::Result<HashMap<Address, AccountNonceOrdering>> {
+    let (account_info_keys, address_by_key): (Vec<_>, HashMap<_, _>) = transactions
+        .iter()
+        .map(|tx| {
+            let address = tx.initiator_account();
+            let account_info_key = get_is_account_key(&address).hashed_key();
+            (account_info_key, (account_info_key, address))
+        })
+        .unzip();
+
+    let account_infos = storage
+        .storage_web3_dal()
+        .get_values(&account_info_keys)
+        .await
+        .context("failed getting account infos from storage")?;
+
+    Ok(account_infos
+        .into_iter()
+        .map(|(key, account_info)| {
+            let nonce_ordering = AccountNonceOrdering::from_account_info(account_info);
+            (address_by_key[&key], nonce_ordering)
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_types::{
@@ -179,6 +232,7 @@ mod tests {
         stuck_tx_timeout: 0,
         remove_stuck_txs: false,
         delay_interval: 10,
+        fair_ordering_max_reordering: None,
     };
 
     #[tokio::test]
@@ -215,6 +269,47 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn getting_transaction_nonce_orderings() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+
+        let transaction = create_l2_transaction(10, 100);
+        let transaction_initiator = transaction.initiator_account();
+        let account_info_key = get_is_account_key(&transaction_initiator);
+        // AA version 1, arbitrary nonce ordering
+        let account_info = u256_to_h256(0x0101.into());
+        let account_info_log = StorageLog::new_write_log(account_info_key, account_info);
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(
+                MiniblockNumber(0),
+                &[(H256::zero(), vec![account_info_log])],
+            )
+            .await
+            .unwrap();
+
+        let other_transaction = create_l2_transaction(10, 100);
+        let other_transaction_initiator = other_transaction.initiator_account();
+
+        let nonce_orderings = get_transaction_nonce_orderings(
+            &mut storage,
+            &[transaction.into(), other_transaction.into()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            nonce_orderings,
+            HashMap::from([
+                (transaction_initiator, AccountNonceOrdering::Arbitrary),
+                (
+                    other_transaction_initiator,
+                    AccountNonceOrdering::Sequential
+                ),
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn syncing_mempool_basics() {
         let pool = ConnectionPool::constrained_test_pool(1).await;
@@ -7,8 +7,8 @@ use std::{
 
 use multivm::interface::VmExecutionResultAndLogs;
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    LatencyObserver, Metrics,
 };
 use zksync_mempool::MempoolStore;
 use zksync_types::{tx::tx_execution_info::DeduplicatedWritesMetrics, ProtocolVersionId};
@@ -49,6 +49,35 @@ impl TxExecutionType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(crate) enum TxHookOutcome {
+    Accepted,
+    Rejected,
+    Error,
+    Timeout,
+}
+
+/// Direction in which an L1 batch timestamp exceeds the allowed drift relative to L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "direction", rename_all = "snake_case")]
+pub(crate) enum TimestampDriftDirection {
+    /// Batch timestamp is too far ahead of L1; the state keeper waits until L1 catches up.
+    Ahead,
+    /// Batch timestamp is too far behind L1; this cannot be fixed by waiting and requires operator attention.
+    Behind,
+}
+
+/// Component processing sealed L1 batches that the state keeper applies backpressure from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "component", rename_all = "snake_case")]
+pub(crate) enum MetadataLagComponent {
+    /// Metadata calculator.
+    Tree,
+    /// Commitment generator.
+    Commitment,
+}
+
 const INCLUSION_DELAY_BUCKETS: Buckets = Buckets::values(&[
     0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1, 1.2, 1.3, 1.4, 1.5, 1.6, 1.7, 1.8, 1.9,
     2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 20.0, 30.0, 60.0, 120.0, 240.0,
@@ -69,6 +98,19 @@ pub(crate) struct StateKeeperMetrics {
     pub get_tx_from_mempool: Histogram<Duration>,
     /// Number of transactions rejected by the state keeper.
     pub rejected_transactions: Counter,
+    /// Number of transactions marked as expired by the mempool fetcher because their deadline has passed.
+    pub expired_transactions: Counter,
+    /// Number of transactions deferred to the next miniblock to bound reordering in the fair ordering mode.
+    pub deferred_transactions: Counter,
+    /// Number of operator transactions force-included ahead of other mempool transactions.
+    pub force_included_transactions: Counter,
+    /// Number of scheduled system transactions submitted to the mempool.
+    pub scheduled_txs_submitted: Counter,
+    /// Number of stuck scheduled system transactions replaced with ones with a bumped fee.
+    pub scheduled_txs_replaced: Counter,
+    /// Number of scheduled system transactions that failed to be submitted (e.g., because they were rejected
+    /// during validation).
+    pub scheduled_txs_failed: Counter,
     /// Time spent waiting for the hash of a previous L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub wait_for_prev_hash_time: Histogram<Duration>,
@@ -85,6 +127,28 @@ pub(crate) struct StateKeeperMetrics {
     pub gas_price_too_high: Counter,
     /// Number of times blob base fee was reported as too high.
     pub blob_base_fee_too_high: Counter,
+    /// Outcomes of transaction execution hook calls.
+    pub tx_hook_outcomes: Family<TxHookOutcome, Counter>,
+    /// Latency of blocking transaction execution hook calls.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub tx_hook_latency: Histogram<Duration>,
+    /// Number of times the local clock was behind the timestamp of the previous miniblock, so that the state keeper
+    /// had to wait to keep timestamps monotonic.
+    pub timestamp_behind_previous: Counter,
+    /// Time spent waiting for the local clock to catch up with the timestamp of the previous miniblock.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub timestamp_catch_up_wait: Histogram<Duration>,
+    /// Difference between the timestamp of the latest opened L1 batch and the latest known L1 block, in seconds.
+    /// Positive values mean that the batch timestamp is ahead of L1.
+    pub l1_timestamp_drift: Gauge<i64>,
+    /// Number of times an L1 batch timestamp exceeded the allowed drift relative to L1.
+    pub l1_timestamp_drift_exceeded: Family<TimestampDriftDirection, Counter>,
+    /// Number of L1 timestamp drift checks skipped because L1 data is stale.
+    pub l1_timestamp_drift_checks_skipped: Counter,
+    /// Number of sealed L1 batches not processed by a component, as observed when opening the latest L1 batch.
+    pub metadata_lag: Family<MetadataLagComponent, Gauge<u64>>,
+    /// Number of times opening a new L1 batch was postponed because a component lagged too much.
+    pub metadata_lag_exceeded: Family<MetadataLagComponent, Counter>,
 }
 
 #[vise::register]
@@ -235,6 +299,10 @@ pub(crate) struct L1BatchMetrics {
     /// stored in the stage.
     #[metrics(buckets = Buckets::LATENCIES)]
     sealed_entity_per_unit: Family<L1BatchSealStage, Histogram<Duration>>,
+    /// Fraction of the capacity of the currently open L1 batch filled according to a seal criterion,
+    /// as of the last executed transaction.
+    #[metrics(labels = ["criterion"])]
+    pub capacity_filled: LabeledFamily<&'static str, Gauge<f64>>,
 }
 
 impl L1BatchMetrics {
@@ -10,13 +10,25 @@ use zksync_object_store::ObjectStore;
 
 pub use self::{
     batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
-    io::{mempool::MempoolIO, MiniblockSealer, MiniblockSealerHandle, StateKeeperIO},
+    io::{
+        mempool::MempoolIO, MiniblockSealer, MiniblockSealerHandle, MiniblockWriteSet,
+        StateKeeperIO,
+    },
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
+    scheduled_txs::ScheduledTxsSender,
     seal_criteria::SequencerSealer,
+    tx_hook::{
+        ExecutedTxEvent, ExecutedTxInfo, HttpTxHook, TxExecutionHook, TxHookMode, TxHookRunner,
+        TxHookVerdict,
+    },
+    tx_size_limits::{TxSizeLimitError, TxSizeLimits},
     types::MempoolGuard,
 };
-use crate::fee_model::BatchFeeModelInputProvider;
+use crate::{
+    api_server::tx_sender::sponsorship::SponsorshipPolicy, fee_model::BatchFeeModelInputProvider,
+    l1_gas_price::L1BlockTimestampProvider,
+};
 
 mod batch_executor;
 pub(crate) mod extractors;
@@ -24,9 +36,12 @@ pub(crate) mod io;
 mod keeper;
 mod mempool_actor;
 pub(crate) mod metrics;
+mod scheduled_txs;
 pub mod seal_criteria;
 #[cfg(test)]
 pub(crate) mod tests;
+mod tx_hook;
+mod tx_size_limits;
 pub(crate) mod types;
 pub(crate) mod updates;
 
@@ -40,9 +55,13 @@ pub(crate) async fn create_state_keeper(
     pool: ConnectionPool,
     mempool: MempoolGuard,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    l1_block_timestamp_provider: Arc<dyn L1BlockTimestampProvider>,
     miniblock_sealer_handle: MiniblockSealerHandle,
     object_store: Arc<dyn ObjectStore>,
     stop_receiver: watch::Receiver<bool>,
+    chain_freeze_receiver: watch::Receiver<bool>,
+    sponsorship_policy: Option<SponsorshipPolicy>,
+    tx_size_limits: TxSizeLimits,
 ) -> ZkSyncStateKeeper {
     let batch_executor_base = MainBatchExecutor::new(
         db_config.state_keeper_db_path.clone(),
@@ -54,7 +73,7 @@ pub(crate) async fn create_state_keeper(
         false,
     );
 
-    let io = MempoolIO::new(
+    let mut io = MempoolIO::new(
         mempool,
         object_store,
         miniblock_sealer_handle,
@@ -68,12 +87,44 @@ pub(crate) async fn create_state_keeper(
     )
     .await
     .expect("Failed initializing main node I/O for state keeper");
+    if let Some(max_reordering) = mempool_config.fair_ordering_max_reordering {
+        io = io.with_fair_ordering(max_reordering);
+    }
+    if let Some(max_drift) = state_keeper_config.max_l1_timestamp_drift() {
+        io = io.with_l1_timestamp_drift_limit(l1_block_timestamp_provider, max_drift);
+    }
+    io = io.with_metadata_lag_limits(
+        state_keeper_config.max_tree_lag_batches,
+        state_keeper_config.max_commitment_lag_batches,
+    );
+    io = io.with_priority_tx_fee_multiplier(state_keeper_config.priority_tx_fee_multiplier());
 
+    let tx_hook = state_keeper_config.tx_hook_url.clone().map(|url| {
+        let mode = match state_keeper_config.tx_hook_timeout() {
+            Some(timeout) => TxHookMode::Blocking { timeout },
+            None => TxHookMode::FireAndForget,
+        };
+        TxHookRunner::new(Arc::new(HttpTxHook::new(url)), mode)
+    });
+    let miniblock_gas_limit = state_keeper_config.miniblock_gas_limit;
     let sealer = SequencerSealer::new(state_keeper_config);
-    ZkSyncStateKeeper::new(
+    let mut state_keeper = ZkSyncStateKeeper::new(
         stop_receiver,
         Box::new(io),
         Box::new(batch_executor_base),
         Arc::new(sealer),
     )
+    .with_tx_size_limits(tx_size_limits)
+    .with_miniblock_sealing_on_shutdown()
+    .with_chain_freeze(chain_freeze_receiver);
+    if let Some(gas_limit) = miniblock_gas_limit {
+        state_keeper = state_keeper.with_miniblock_gas_limit(gas_limit);
+    }
+    if let Some(policy) = sponsorship_policy {
+        state_keeper = state_keeper.with_sponsorship_policy(policy);
+    }
+    match tx_hook {
+        Some(tx_hook) => state_keeper.with_tx_hook(tx_hook),
+        None => state_keeper,
+    }
 }
@@ -0,0 +1,536 @@
+//! Submission of recurring system transactions (e.g., oracle updates or fee collection) scheduled by the operator.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{
+    scheduled_txs_dal::{PendingScheduledTx, ScheduledTx},
+    transactions_dal::L2TxSubmissionResult,
+    ConnectionPool,
+};
+use zksync_eth_signer::{EthereumSigner, OperatorSigner};
+use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
+use zksync_types::{
+    audit_log::AuditAction, fee::Fee, get_nonce_key, l2::L2Tx,
+    transaction_request::TransactionRequest, Address, Eip712Domain, L2ChainId, Nonce, H256, U256,
+};
+
+use super::{mempool_actor::l2_tx_filter, metrics::KEEPER_METRICS};
+use crate::{
+    api_server::tx_sender::TxSender, audit_log::AuditLogger, fee_model::BatchFeeModelInputProvider,
+    utils::pending_protocol_version,
+};
+
+/// Multiplier applied to the current base fee per gas when signing scheduled transactions, so that they remain
+/// executable if the fee grows moderately before they are included. Transactions stuck because the fee has grown
+/// more are replaced, see [`REPLACEMENT_FEE_BUMP_PERCENT`].
+const FEE_PER_GAS_MULTIPLIER: u64 = 2;
+/// Minimum increase of the max fee per gas (in percent) of a replacement for a stuck scheduled transaction
+/// relative to the replaced transaction.
+const REPLACEMENT_FEE_BUMP_PERCENT: u64 = 20;
+
+#[derive(Debug, Serialize)]
+struct SubmissionParams<'a> {
+    name: &'a str,
+    tx_hash: H256,
+    nonce: u32,
+    gas_limit: u64,
+    max_fee_per_gas: U256,
+    /// Hash of the stuck transaction replaced by this submission.
+    replaced_tx_hash: Option<H256>,
+}
+
+/// Submits due scheduled transactions to the mempool on behalf of the state keeper.
+///
+/// Transactions are signed by a dedicated operator account, which is force-included by the state keeper,
+/// so scheduled transactions are executed ahead of user transactions. Transactions are validated and executed
+/// by [`TxSender`] before being inserted to the mempool, in the same way as transactions received via API.
+/// Transactions pending for too long (e.g., because the fee has grown after they were signed) are replaced
+/// with transactions having the same nonce and a bumped fee, so that they don't block the schedule.
+/// Each submission and replacement is recorded in the audit log.
+#[derive(Debug)]
+pub struct ScheduledTxsSender {
+    pool: ConnectionPool,
+    tx_sender: TxSender,
+    batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    chain_id: L2ChainId,
+    signer: OperatorSigner,
+    audit_log: AuditLogger,
+    poll_interval: Duration,
+    stuck_tx_timeout: Duration,
+}
+
+impl ScheduledTxsSender {
+    pub fn new(
+        pool: ConnectionPool,
+        tx_sender: TxSender,
+        batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+        chain_id: L2ChainId,
+        signer: OperatorSigner,
+    ) -> Self {
+        Self {
+            audit_log: AuditLogger::new(pool.clone(), format!("{:?}", signer.address())),
+            pool,
+            tx_sender,
+            batch_fee_input_provider,
+            chain_id,
+            signer,
+            poll_interval: Duration::from_secs(1),
+            stuck_tx_timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Returns the address of the account signing scheduled transactions.
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting scheduled transactions sender for account {:?}",
+            self.address()
+        );
+        while !*stop_receiver.borrow() {
+            if let Err(err) = self.replace_stuck_txs().await {
+                tracing::warn!("Failed replacing stuck scheduled transactions: {err:#}");
+            }
+            if let Err(err) = self.submit_due_txs().await {
+                tracing::warn!("Failed submitting scheduled transactions: {err:#}");
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, scheduled transactions sender is shutting down");
+        Ok(())
+    }
+
+    /// Submits all due scheduled transactions. Returns the number of submitted transactions. Failure to submit
+    /// a transaction is logged and doesn't prevent submitting other transactions; the failed transaction
+    /// remains due and is retried on the next iteration.
+    async fn submit_due_txs(&self) -> anyhow::Result<usize> {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let due_txs = storage
+            .scheduled_txs_dal()
+            .get_due_scheduled_txs()
+            .await
+            .context("failed getting due scheduled transactions")?;
+        drop(storage);
+
+        let mut submitted_count = 0;
+        for scheduled_tx in &due_txs {
+            match self.submit(scheduled_tx).await {
+                Ok(true) => submitted_count += 1,
+                Ok(false) => { /* already logged */ }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed submitting scheduled transaction `{}`: {err:#}",
+                        scheduled_tx.name
+                    );
+                    KEEPER_METRICS.scheduled_txs_failed.inc();
+                }
+            }
+        }
+        Ok(submitted_count)
+    }
+
+    /// Replaces scheduled transactions pending for longer than `stuck_tx_timeout` with transactions having
+    /// the same nonce and a bumped fee. Returns the number of replaced transactions. As with submissions,
+    /// failure to replace a transaction is logged, and the replacement is retried on the next iteration.
+    async fn replace_stuck_txs(&self) -> anyhow::Result<usize> {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let stuck_txs = storage
+            .scheduled_txs_dal()
+            .get_stuck_submissions(self.stuck_tx_timeout)
+            .await
+            .context("failed getting stuck scheduled transactions")?;
+        drop(storage);
+
+        let mut replaced_count = 0;
+        for stuck_tx in &stuck_txs {
+            match self.replace(stuck_tx).await {
+                Ok(true) => replaced_count += 1,
+                Ok(false) => { /* already logged */ }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed replacing scheduled transaction `{}` with hash {:?}: {err:#}",
+                        stuck_tx.scheduled_tx.name,
+                        stuck_tx.tx_hash
+                    );
+                    KEEPER_METRICS.scheduled_txs_failed.inc();
+                }
+            }
+        }
+        Ok(replaced_count)
+    }
+
+    async fn next_nonce(&self) -> anyhow::Result<Nonce> {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let nonce_key = get_nonce_key(&self.address()).hashed_key();
+        let nonce_values = storage
+            .storage_web3_dal()
+            .get_values(&[nonce_key])
+            .await
+            .context("failed getting nonce from storage")?;
+        let committed_nonce = nonce_values
+            .get(&nonce_key)
+            .map_or(0, |value| zksync_utils::h256_to_u32(*value));
+        // Account for transactions submitted previously, but not executed yet.
+        let next_nonce = storage
+            .transactions_web3_dal()
+            .next_nonce_by_initiator_account(self.address(), committed_nonce.into())
+            .await
+            .context("failed getting pending nonce")?;
+        let next_nonce = u32::try_from(next_nonce).map_err(|err| anyhow::anyhow!(err))?;
+        Ok(Nonce(next_nonce))
+    }
+
+    /// Signs a transaction for `scheduled_tx`. If the transaction replaces a stuck one, `replaced_max_fee_per_gas`
+    /// is the max fee per gas of the replaced transaction.
+    async fn sign(
+        &self,
+        scheduled_tx: &ScheduledTx,
+        nonce: Nonce,
+        replaced_max_fee_per_gas: Option<U256>,
+    ) -> anyhow::Result<L2Tx> {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let protocol_version = pending_protocol_version(&mut storage).await?;
+        drop(storage);
+        let filter = l2_tx_filter(
+            self.batch_fee_input_provider.as_ref(),
+            protocol_version.into(),
+        )
+        .await;
+
+        let mut max_fee_per_gas = U256::from(filter.fee_per_gas * FEE_PER_GAS_MULTIPLIER);
+        if let Some(replaced_fee) = replaced_max_fee_per_gas {
+            let bumped_fee = replaced_fee * (100 + REPLACEMENT_FEE_BUMP_PERCENT) / 100;
+            max_fee_per_gas = max_fee_per_gas.max(bumped_fee);
+        }
+        let fee = Fee {
+            gas_limit: scheduled_tx.gas_limit.into(),
+            max_fee_per_gas,
+            max_priority_fee_per_gas: U256::zero(),
+            gas_per_pubdata_limit: DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE.into(),
+        };
+        let mut tx = L2Tx::new(
+            scheduled_tx.contract_address,
+            scheduled_tx.calldata.clone(),
+            nonce,
+            fee,
+            self.address(),
+            U256::zero(),
+            None,
+            Default::default(),
+        );
+        let domain = Eip712Domain::new(self.chain_id);
+        let signature = self
+            .signer
+            .sign_typed_data(&domain, &TransactionRequest::from(tx.clone()))
+            .await
+            .context("failed signing transaction")?;
+        tx.set_signature(signature.clone());
+
+        // Set the hash and raw bytes in the same way as for transactions received via API.
+        let request = TransactionRequest::from(tx.clone());
+        let hash = request
+            .get_tx_hash(self.chain_id)
+            .context("failed computing transaction hash")?;
+        let raw = request.get_signed_bytes(&signature, self.chain_id);
+        tx.set_input(raw, hash);
+        Ok(tx)
+    }
+
+    /// Returns `Ok(false)` if the transaction was not added to the mempool (e.g., because it's a duplicate).
+    async fn submit(&self, scheduled_tx: &ScheduledTx) -> anyhow::Result<bool> {
+        let nonce = self.next_nonce().await?;
+        let tx = self.sign(scheduled_tx, nonce, None).await?;
+        let tx_hash = tx.hash();
+        let max_fee_per_gas = tx.common_data.fee.max_fee_per_gas;
+
+        let submission_result = self
+            .tx_sender
+            .submit_tx(tx)
+            .await
+            .context("transaction was rejected")?;
+        if submission_result != L2TxSubmissionResult::Added {
+            tracing::warn!(
+                "Scheduled transaction `{}` with hash {tx_hash:?} and nonce {nonce} was not added to mempool: {submission_result}",
+                scheduled_tx.name
+            );
+            return Ok(false);
+        }
+
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        storage
+            .scheduled_txs_dal()
+            .record_submission(&scheduled_tx.name, tx_hash, nonce.0)
+            .await
+            .context("failed recording submission")?;
+        drop(storage);
+
+        tracing::info!(
+            "Submitted scheduled transaction `{}` with hash {tx_hash:?} and nonce {nonce}",
+            scheduled_tx.name
+        );
+        KEEPER_METRICS.scheduled_txs_submitted.inc();
+        let params = SubmissionParams {
+            name: &scheduled_tx.name,
+            tx_hash,
+            nonce: nonce.0,
+            gas_limit: scheduled_tx.gas_limit,
+            max_fee_per_gas,
+            replaced_tx_hash: None,
+        };
+        self.audit_log
+            .record(AuditAction::ScheduledTxSubmission, params)
+            .await?;
+        Ok(true)
+    }
+
+    /// Returns `Ok(false)` if the stuck transaction was not replaced in the mempool (e.g., because it was executed
+    /// in the meantime).
+    async fn replace(&self, stuck_tx: &PendingScheduledTx) -> anyhow::Result<bool> {
+        let scheduled_tx = &stuck_tx.scheduled_tx;
+        let nonce = Nonce(stuck_tx.nonce);
+        let tx = self
+            .sign(scheduled_tx, nonce, Some(stuck_tx.max_fee_per_gas))
+            .await?;
+        let tx_hash = tx.hash();
+        let max_fee_per_gas = tx.common_data.fee.max_fee_per_gas;
+
+        let submission_result = self
+            .tx_sender
+            .submit_tx(tx)
+            .await
+            .context("replacement transaction was rejected")?;
+        if submission_result != L2TxSubmissionResult::Replaced {
+            tracing::warn!(
+                "Replacement {tx_hash:?} for scheduled transaction `{}` with hash {:?} and nonce {nonce} was not added to mempool: {submission_result}",
+                scheduled_tx.name,
+                stuck_tx.tx_hash
+            );
+            return Ok(false);
+        }
+
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        storage
+            .scheduled_txs_dal()
+            .record_replacement(stuck_tx.tx_hash, tx_hash)
+            .await
+            .context("failed recording replacement")?;
+        drop(storage);
+
+        tracing::info!(
+            "Replaced stuck scheduled transaction `{}` with hash {:?} and nonce {nonce} by {tx_hash:?} with max fee per gas {max_fee_per_gas}",
+            scheduled_tx.name,
+            stuck_tx.tx_hash
+        );
+        KEEPER_METRICS.scheduled_txs_replaced.inc();
+        let params = SubmissionParams {
+            name: &scheduled_tx.name,
+            tx_hash,
+            nonce: nonce.0,
+            gas_limit: scheduled_tx.gas_limit,
+            max_fee_per_gas,
+            replaced_tx_hash: Some(stuck_tx.tx_hash),
+        };
+        self.audit_log
+            .record(AuditAction::ScheduledTxSubmission, params)
+            .await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multivm::interface::{ExecutionResult, VmRevertReason};
+    use zksync_eth_signer::PrivateKeySigner;
+    use zksync_types::{api::TransactionId, MiniblockNumber, PackedEthSignature, StorageLog};
+    use zksync_utils::u256_to_h256;
+
+    use super::*;
+    use crate::{
+        api_server::{
+            execution_sandbox::testonly::MockTransactionExecutor,
+            tx_sender::tests::create_test_tx_sender,
+        },
+        genesis::{ensure_genesis_state, GenesisParams},
+        utils::testonly::MockBatchFeeParamsProvider,
+    };
+
+    const PRIVATE_KEY: H256 = H256::repeat_byte(0x11);
+
+    fn test_scheduled_tx() -> ScheduledTx {
+        ScheduledTx {
+            name: "oracle".to_owned(),
+            contract_address: Address::repeat_byte(1),
+            calldata: vec![1, 2, 3],
+            gas_limit: 1_000_000,
+            interval: Duration::ZERO,
+        }
+    }
+
+    async fn prepare_storage(pool: &ConnectionPool, scheduled_tx: &ScheduledTx) {
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        storage
+            .scheduled_txs_dal()
+            .upsert_scheduled_tx(scheduled_tx)
+            .await
+            .unwrap();
+
+        // Fund the signing account so that scheduled transactions pass validation.
+        let address = PackedEthSignature::address_from_private_key(&PRIVATE_KEY).unwrap();
+        let balance_key = zksync_types::utils::storage_key_for_eth_balance(&address);
+        let balance_log = StorageLog::new_write_log(balance_key, u256_to_h256(U256::one() << 64));
+        storage
+            .storage_logs_dal()
+            .append_storage_logs(MiniblockNumber(0), &[(H256::zero(), vec![balance_log])])
+            .await
+            .unwrap();
+    }
+
+    async fn create_sender(
+        pool: &ConnectionPool,
+        tx_executor: MockTransactionExecutor,
+    ) -> ScheduledTxsSender {
+        let (tx_sender, _) =
+            create_test_tx_sender(pool.clone(), L2ChainId::default(), tx_executor.into()).await;
+        let signer = OperatorSigner::new(PrivateKeySigner::new(PRIVATE_KEY))
+            .await
+            .unwrap();
+        ScheduledTxsSender::new(
+            pool.clone(),
+            tx_sender,
+            Arc::new(MockBatchFeeParamsProvider::default()),
+            L2ChainId::default(),
+            signer,
+        )
+    }
+
+    #[tokio::test]
+    async fn submitting_scheduled_txs() {
+        let pool = ConnectionPool::test_pool().await;
+        let scheduled_tx = test_scheduled_tx();
+        prepare_storage(&pool, &scheduled_tx).await;
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_tx_responses(|_, _| ExecutionResult::Success { output: vec![] });
+        let sender = create_sender(&pool, tx_executor).await;
+
+        assert_eq!(sender.submit_due_txs().await.unwrap(), 1);
+        // The submitted transaction is pending, so the schedule isn't due.
+        assert_eq!(sender.submit_due_txs().await.unwrap(), 0);
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let stats = storage
+            .scheduled_txs_dal()
+            .get_stats(&scheduled_tx.name)
+            .await
+            .unwrap();
+        assert_eq!(stats.submitted_count, 1);
+        assert_eq!(stats.executed_count, 0);
+
+        let entries = storage.audit_log_dal().get_entries(0, 10).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::ScheduledTxSubmission);
+        assert_eq!(entries[0].actor, format!("{:?}", sender.address()));
+        let tx_hash: H256 = serde_json::from_value(entries[0].params["tx_hash"].clone()).unwrap();
+
+        let tx = storage
+            .transactions_web3_dal()
+            .get_transaction(TransactionId::Hash(tx_hash), L2ChainId::default())
+            .await
+            .unwrap()
+            .expect("scheduled transaction is not persisted");
+        assert_eq!(tx.from, Some(sender.address()));
+        assert_eq!(tx.to, Some(scheduled_tx.contract_address));
+        assert_eq!(tx.nonce, U256::zero());
+        assert_eq!(tx.gas, scheduled_tx.gas_limit.into());
+        assert!(!tx.max_fee_per_gas.unwrap().is_zero());
+    }
+
+    #[tokio::test]
+    async fn rejected_scheduled_txs_are_not_recorded() {
+        let pool = ConnectionPool::test_pool().await;
+        let scheduled_tx = test_scheduled_tx();
+        prepare_storage(&pool, &scheduled_tx).await;
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_tx_responses(|_, _| ExecutionResult::Revert {
+            output: VmRevertReason::General {
+                msg: "oops".to_owned(),
+                data: vec![],
+            },
+        });
+        let sender = create_sender(&pool, tx_executor).await;
+
+        assert_eq!(sender.submit_due_txs().await.unwrap(), 0);
+        // The schedule remains due, so the transaction is retried.
+        assert_eq!(sender.submit_due_txs().await.unwrap(), 0);
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let stats = storage
+            .scheduled_txs_dal()
+            .get_stats(&scheduled_tx.name)
+            .await
+            .unwrap();
+        assert_eq!(stats.submitted_count, 0);
+        let entries = storage.audit_log_dal().get_entries(0, 10).await.unwrap();
+        assert!(entries.is_empty(), "{entries:?}");
+    }
+
+    #[tokio::test]
+    async fn replacing_stuck_scheduled_txs() {
+        let pool = ConnectionPool::test_pool().await;
+        let scheduled_tx = test_scheduled_tx();
+        prepare_storage(&pool, &scheduled_tx).await;
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_tx_responses(|_, _| ExecutionResult::Success { output: vec![] });
+        let mut sender = create_sender(&pool, tx_executor).await;
+
+        assert_eq!(sender.submit_due_txs().await.unwrap(), 1);
+        // The submitted transaction isn't stuck yet.
+        assert_eq!(sender.replace_stuck_txs().await.unwrap(), 0);
+        sender.stuck_tx_timeout = Duration::ZERO;
+        assert_eq!(sender.replace_stuck_txs().await.unwrap(), 1);
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let entries = storage.audit_log_dal().get_entries(0, 10).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        let tx_hash: H256 = serde_json::from_value(entries[0].params["tx_hash"].clone()).unwrap();
+        let max_fee_per_gas: U256 =
+            serde_json::from_value(entries[0].params["max_fee_per_gas"].clone()).unwrap();
+        let replaced_tx_hash: H256 =
+            serde_json::from_value(entries[1].params["replaced_tx_hash"].clone()).unwrap();
+        assert_eq!(replaced_tx_hash, tx_hash);
+        let replacement_tx_hash: H256 =
+            serde_json::from_value(entries[1].params["tx_hash"].clone()).unwrap();
+        assert_ne!(replacement_tx_hash, tx_hash);
+
+        let replacement = storage
+            .transactions_web3_dal()
+            .get_transaction(
+                TransactionId::Hash(replacement_tx_hash),
+                L2ChainId::default(),
+            )
+            .await
+            .unwrap()
+            .expect("replacement transaction is not persisted");
+        assert_eq!(replacement.nonce, U256::zero());
+        assert_eq!(
+            replacement.max_fee_per_gas.unwrap(),
+            max_fee_per_gas * (100 + REPLACEMENT_FEE_BUMP_PERCENT) / 100
+        );
+        let stats = storage
+            .scheduled_txs_dal()
+            .get_stats(&scheduled_tx.name)
+            .await
+            .unwrap();
+        assert_eq!(stats.submitted_count, 1);
+    }
+}
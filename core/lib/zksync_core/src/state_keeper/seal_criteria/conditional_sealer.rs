@@ -4,12 +4,13 @@
 //! The conditional sealer abstraction allows to implement different sealing strategies, e.g. the actual
 //! sealing strategy for the main node or noop sealer for the external node.
 
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::ProtocolVersionId;
 
 use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
+use crate::state_keeper::metrics::L1_BATCH_METRICS;
 
 /// Checks if an L1 batch should be sealed after executing a transaction.
 pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
@@ -22,7 +23,8 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
         protocol_version: ProtocolVersionId,
     ) -> Option<&'static str>;
 
-    /// Returns the action that should be taken by the state keeper after executing a transaction.
+    /// Returns the action that should be taken by the state keeper after executing a transaction,
+    /// together with the name of the criterion that has determined this action (if any).
     fn should_seal_l1_batch(
         &self,
         l1_batch_number: u32,
@@ -31,7 +33,20 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
         block_data: &SealData,
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
-    ) -> SealResolution;
+    ) -> (SealResolution, Option<&'static str>);
+
+    /// Returns the percentage of the L1 batch capacity filled according to each capacity-limiting criterion.
+    fn capacity_filled(
+        &self,
+        tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> BTreeMap<&'static str, f64>;
+
+    /// Resets metrics tracking the capacity of the open L1 batch. Called by the state keeper once the batch is sealed.
+    fn reset_capacity_metrics(&self) {
+        // Do nothing by default
+    }
 }
 
 /// Implementation of [`ConditionalSealer`] used by the main node.
@@ -78,7 +93,7 @@ impl ConditionalSealer for SequencerSealer {
         block_data: &SealData,
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
-    ) -> SealResolution {
+    ) -> (SealResolution, Option<&'static str>) {
         tracing::trace!(
             "Determining seal resolution for L1 batch #{l1_batch_number} with {tx_count} transactions \
              and metrics {:?}",
@@ -86,6 +101,7 @@ impl ConditionalSealer for SequencerSealer {
         );
 
         let mut final_seal_resolution = SealResolution::NoSeal;
+        let mut seal_reason = None;
         for sealer in &self.sealers {
             let seal_resolution = sealer.should_seal(
                 &self.config,
@@ -108,9 +124,52 @@ impl ConditionalSealer for SequencerSealer {
                 SealResolution::NoSeal => { /* Don't do anything */ }
             }
 
-            final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
+            let stricter_resolution = final_seal_resolution.clone().stricter(seal_resolution);
+            if stricter_resolution != final_seal_resolution {
+                seal_reason = Some(sealer.prom_criterion_name());
+            }
+            final_seal_resolution = stricter_resolution;
+        }
+        self.report_capacity_filled(
+            tx_count,
+            block_data,
+            protocol_version,
+            &final_seal_resolution,
+        );
+        (final_seal_resolution, seal_reason)
+    }
+
+    fn capacity_filled(
+        &self,
+        tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> BTreeMap<&'static str, f64> {
+        self.sealers
+            .iter()
+            .filter_map(|sealer| {
+                let capacity_filled =
+                    sealer.capacity_filled(&self.config, tx_count, block_data, protocol_version)?;
+                Some((sealer.prom_criterion_name(), capacity_filled * 100.0))
+            })
+            .collect()
+    }
+
+    fn reset_capacity_metrics(&self) {
+        // Gauges are set to the capacity filled by an empty batch.
+        let empty_batch_data = SealData::default();
+        for sealer in &self.sealers {
+            let capacity_filled = sealer.capacity_filled(
+                &self.config,
+                0,
+                &empty_batch_data,
+                ProtocolVersionId::latest(),
+            );
+            if let Some(capacity_filled) = capacity_filled {
+                L1_BATCH_METRICS.capacity_filled[&sealer.prom_criterion_name()]
+                    .set(capacity_filled);
+            }
         }
-        final_seal_resolution
     }
 }
 
@@ -128,6 +187,29 @@ impl SequencerSealer {
         Self { config, sealers }
     }
 
+    /// Updates batch capacity gauges for all criteria. If the batch is going to be sealed or the transaction
+    /// is unexecutable, `block_data` doesn't reflect the open batch (it includes a transaction that won't be
+    /// in the batch), so the gauges are left as is; they are reset by the state keeper after sealing the batch.
+    fn report_capacity_filled(
+        &self,
+        tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+        resolution: &SealResolution,
+    ) {
+        if *resolution != SealResolution::NoSeal {
+            return;
+        }
+        for sealer in &self.sealers {
+            let capacity_filled =
+                sealer.capacity_filled(&self.config, tx_count, block_data, protocol_version);
+            if let Some(capacity_filled) = capacity_filled {
+                L1_BATCH_METRICS.capacity_filled[&sealer.prom_criterion_name()]
+                    .set(capacity_filled);
+            }
+        }
+    }
+
     fn default_sealers(config: &StateKeeperConfig) -> Vec<Box<dyn SealCriterion>> {
         vec![
             Box::new(criteria::SlotsCriterion),
@@ -165,7 +247,16 @@ impl ConditionalSealer for NoopSealer {
         _block_data: &SealData,
         _tx_data: &SealData,
         _protocol_version: ProtocolVersionId,
-    ) -> SealResolution {
-        SealResolution::NoSeal
+    ) -> (SealResolution, Option<&'static str>) {
+        (SealResolution::NoSeal, None)
+    }
+
+    fn capacity_filled(
+        &self,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> BTreeMap<&'static str, f64> {
+        BTreeMap::new()
     }
 }
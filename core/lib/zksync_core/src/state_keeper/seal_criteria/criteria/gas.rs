@@ -43,6 +43,18 @@ impl SealCriterion for GasCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let gas_count = &block_data.gas_count;
+        let max_gas = gas_count.commit.max(gas_count.prove).max(gas_count.execute);
+        Some((f64::from(max_gas) / f64::from(config.max_single_tx_gas)).min(1.0))
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "gas"
     }
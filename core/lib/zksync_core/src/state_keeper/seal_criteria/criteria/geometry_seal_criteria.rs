@@ -51,6 +51,17 @@ where
         }
     }
 
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version_id: ProtocolVersionId,
+    ) -> Option<f64> {
+        let used = T::extract(&block_data.execution_metrics);
+        Some((used as f64 / T::limit_per_block(protocol_version_id) as f64).min(1.0))
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         T::PROM_METRIC_CRITERION_NAME
     }
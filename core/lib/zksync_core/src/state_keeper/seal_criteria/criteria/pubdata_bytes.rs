@@ -51,6 +51,18 @@ impl SealCriterion for PubDataBytesCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let block_size =
+            block_data.execution_metrics.size() + block_data.writes_metrics.size(protocol_version);
+        Some((block_size as f64 / self.max_pubdata_per_batch as f64).min(1.0))
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "pub_data_size"
     }
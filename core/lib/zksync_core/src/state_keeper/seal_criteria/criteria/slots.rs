@@ -33,6 +33,16 @@ impl SealCriterion for SlotsCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        config: &StateKeeperConfig,
+        tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        Some((tx_count as f64 / config.transaction_slots as f64).min(1.0))
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "slots"
     }
@@ -71,5 +81,21 @@ mod tests {
             ProtocolVersionId::latest(),
         );
         assert_eq!(full_block_resolution, SealResolution::IncludeAndSeal);
+
+        let capacity_filled = criterion.capacity_filled(
+            &config,
+            1,
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(capacity_filled, Some(0.5));
+        // The filled capacity is capped if the block data overflows the batch.
+        let capacity_filled = criterion.capacity_filled(
+            &config,
+            3,
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(capacity_filled, Some(1.0));
     }
 }
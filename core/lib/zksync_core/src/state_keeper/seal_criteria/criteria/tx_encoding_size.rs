@@ -39,6 +39,18 @@ impl SealCriterion for TxEncodingSizeCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version_id: ProtocolVersionId,
+    ) -> Option<f64> {
+        let bootloader_tx_encoding_space =
+            get_bootloader_encoding_space(protocol_version_id.into());
+        Some((block_data.cumulative_size as f64 / f64::from(bootloader_tx_encoding_space)).min(1.0))
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "tx_encoding_size"
     }
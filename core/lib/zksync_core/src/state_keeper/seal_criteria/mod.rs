@@ -104,6 +104,20 @@ impl SealData {
             gas_remaining: tx_metrics.gas_remaining,
         }
     }
+
+    /// Creates sealing data for all transactions executed in the L1 batch managed by `manager`.
+    /// The batch tip is not taken into account.
+    pub(crate) fn for_l1_batch(manager: &UpdatesManager) -> Self {
+        let writes_metrics = manager.storage_writes_deduplicator.metrics();
+        Self {
+            execution_metrics: manager.pending_execution_metrics(),
+            gas_count: manager.pending_l1_gas_count()
+                + gas_count_from_writes(&writes_metrics, manager.protocol_version()),
+            cumulative_size: manager.pending_txs_encoding_size(),
+            writes_metrics,
+            gas_remaining: 0,
+        }
+    }
 }
 
 pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
@@ -117,6 +131,19 @@ pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
         protocol_version: ProtocolVersionId,
     ) -> SealResolution;
 
+    /// Returns the fraction of the L1 batch capacity filled according to this criterion, or `None` if the criterion
+    /// doesn't limit capacity. Only used for metrics. The fraction is capped at 1 since `block_data` may exceed
+    /// the capacity (e.g., if it includes a transaction that will be excluded from the batch).
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        None
+    }
+
     // We need self here only for rust restrictions for creating an object from trait
     // https://doc.rust-lang.org/reference/items/traits.html#object-safety
     fn prom_criterion_name(&self) -> &'static str;
@@ -129,8 +156,27 @@ pub trait IoSealCriteria {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool;
     /// Checks whether a miniblock should be sealed given the provided `manager` state.
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool;
+
+    /// Returns the name of the rule that has made [`Self::should_seal_l1_batch_unconditionally()`] return `true`.
+    /// The returned name is persisted as the L1 batch seal reason; `None` means that the reason is unknown.
+    fn l1_batch_seal_reason(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the name of the rule that has made [`Self::should_seal_miniblock()`] return `true`.
+    /// The returned name is persisted as the miniblock seal reason; `None` means that the reason is unknown.
+    fn miniblock_seal_reason(&self) -> Option<&'static str> {
+        None
+    }
 }
 
+/// Seal reason for miniblocks sealed together with the L1 batch they belong to.
+pub(super) const L1_BATCH_SEALED_REASON: &str = "l1_batch_sealed";
+/// Name of the criterion sealing miniblocks that have reached the miniblock gas limit.
+pub(super) const MINIBLOCK_GAS_LIMIT_CRITERION: &str = "miniblock_gas_limit";
+/// Seal reason for L1 batches sealed because the chain was frozen.
+pub(super) const CHAIN_FROZEN_SEAL_REASON: &str = "chain_frozen";
+
 #[derive(Debug, Clone, Copy)]
 pub(super) struct TimeoutSealer {
     block_commit_deadline_ms: u64,
@@ -144,42 +190,71 @@ impl TimeoutSealer {
             miniblock_commit_deadline_ms: config.miniblock_commit_deadline_ms,
         }
     }
-}
 
-impl IoSealCriteria for TimeoutSealer {
-    fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
-        const RULE_NAME: &str = "no_txs_timeout";
+    /// Same as [`IoSealCriteria::should_seal_l1_batch_unconditionally()`], but with the L1 batch commit deadline
+    /// multiplied by `multiplier`. Used to make L1 batches longer.
+    pub fn should_seal_l1_batch_with_extended_deadline(
+        &self,
+        manager: &UpdatesManager,
+        multiplier: u64,
+    ) -> bool {
+        self.should_seal_l1_batch_by_timeout(manager, self.block_commit_deadline_ms * multiplier)
+    }
 
+    const L1_BATCH_RULE_NAME: &'static str = "no_txs_timeout";
+    const MINIBLOCK_RULE_NAME: &'static str = "miniblock_timeout";
+
+    fn should_seal_l1_batch_by_timeout(
+        &self,
+        manager: &UpdatesManager,
+        block_commit_deadline_ms: u64,
+    ) -> bool {
         if manager.pending_executed_transactions_len() == 0 {
             // Regardless of which sealers are provided, we never want to seal an empty batch.
             return false;
         }
 
-        let block_commit_deadline_ms = self.block_commit_deadline_ms;
         // Verify timestamp
         let should_seal_timeout =
             millis_since(manager.batch_timestamp()) > block_commit_deadline_ms;
 
         if should_seal_timeout {
-            AGGREGATION_METRICS.inc_criterion(RULE_NAME);
+            AGGREGATION_METRICS.inc_criterion(Self::L1_BATCH_RULE_NAME);
             tracing::debug!(
-                "Decided to seal L1 batch using rule `{RULE_NAME}`; batch timestamp: {}, \
+                "Decided to seal L1 batch using rule `{}`; batch timestamp: {}, \
                  commit deadline: {block_commit_deadline_ms}ms",
+                Self::L1_BATCH_RULE_NAME,
                 extractors::display_timestamp(manager.batch_timestamp())
             );
         }
         should_seal_timeout
     }
+}
+
+impl IoSealCriteria for TimeoutSealer {
+    fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        self.should_seal_l1_batch_by_timeout(manager, self.block_commit_deadline_ms)
+    }
 
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
         !manager.miniblock.executed_transactions.is_empty()
             && millis_since(manager.miniblock.timestamp) > self.miniblock_commit_deadline_ms
     }
+
+    fn l1_batch_seal_reason(&self) -> Option<&'static str> {
+        Some(Self::L1_BATCH_RULE_NAME)
+    }
+
+    fn miniblock_seal_reason(&self) -> Option<&'static str> {
+        Some(Self::MINIBLOCK_RULE_NAME)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use zksync_utils::time::seconds_since_epoch;
+    use std::time::Duration;
+
+    use zksync_utils::time::{seconds_since_epoch, VirtualClock};
 
     use super::*;
     use crate::state_keeper::tests::{
@@ -230,4 +305,27 @@ mod tests {
             "Non-empty miniblock with too recent timestamp shouldn't be sealed"
         );
     }
+
+    #[test]
+    fn timeout_sealers_with_virtual_time() {
+        let mut timeout_sealer = TimeoutSealer {
+            block_commit_deadline_ms: 10_000,
+            miniblock_commit_deadline_ms: 2_000,
+        };
+        let mut manager = create_updates_manager();
+        let clock = VirtualClock::new(Duration::from_secs(manager.batch_timestamp()));
+        manager.miniblock.timestamp = manager.batch_timestamp();
+        apply_tx_to_manager(&mut manager);
+
+        clock.advance(Duration::from_millis(2_000));
+        assert!(!timeout_sealer.should_seal_miniblock(&manager));
+        clock.advance(Duration::from_millis(1));
+        assert!(timeout_sealer.should_seal_miniblock(&manager));
+        assert!(!timeout_sealer.should_seal_l1_batch_unconditionally(&manager));
+
+        clock.advance(Duration::from_millis(7_999));
+        assert!(!timeout_sealer.should_seal_l1_batch_unconditionally(&manager));
+        clock.advance(Duration::from_millis(1));
+        assert!(timeout_sealer.should_seal_l1_batch_unconditionally(&manager));
+    }
 }
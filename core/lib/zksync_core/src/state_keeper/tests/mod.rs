@@ -22,10 +22,11 @@ use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{BlockGasCount, MiniblockExecutionData, MiniblockHasher},
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
+    l2::{ValidUntil, TX_EXPIRED_ERROR},
     tx::tx_execution_info::ExecutionMetrics,
     zk_evm_types::{LogQuery, Timestamp},
-    Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, StorageLogQuery,
-    StorageLogQueryType, Transaction, H256, U256,
+    Address, ExecuteTransactionCommon, L1BatchNumber, L2ChainId, MiniblockNumber,
+    ProtocolVersionId, StorageLogQuery, StorageLogQueryType, Transaction, H256, U256,
 };
 
 mod tester;
@@ -36,6 +37,7 @@ use self::tester::{
 };
 pub(crate) use self::tester::{MockBatchExecutor, TestBatchExecutorBuilder};
 use crate::{
+    api_server::tx_sender::sponsorship::{SponsorshipPolicy, SponsorshipRule},
     gas_tracker::l1_batch_base_cost,
     state_keeper::{
         keeper::POLL_WAIT_DURATION,
@@ -45,7 +47,7 @@ use crate::{
         },
         types::ExecutionMetricsForCriteria,
         updates::UpdatesManager,
-        ZkSyncStateKeeper,
+        TxSizeLimits, ZkSyncStateKeeper,
     },
     utils::testonly::create_l2_transaction,
 };
@@ -152,6 +154,7 @@ pub(super) fn create_execution_result(
             total_log_queries,
             pubdata_published: 0,
             circuit_statistic: Default::default(),
+            operation_statistic: Default::default(),
         },
         refunds: Refunds::default(),
     }
@@ -216,6 +219,54 @@ async fn sealed_by_number_of_txs() {
         .await;
 }
 
+#[tokio::test]
+async fn seal_reason_and_capacity_are_recorded() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .miniblock_sealed_with("Miniblock 1", |updates| {
+            let seal_info = updates.miniblock_seal_info();
+            assert_eq!(seal_info.capacity_filled["slots"], 50.0);
+        })
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .miniblock_sealed_with("Miniblock 2", |updates| {
+            let seal_info = updates.miniblock_seal_info();
+            assert_eq!(seal_info.reason.as_deref(), Some("l1_batch_sealed"));
+            assert_eq!(seal_info.capacity_filled["slots"], 100.0);
+        })
+        .batch_sealed_with("Batch 1", |_, updates, _| {
+            let seal_info = updates.l1_batch_seal_info();
+            assert_eq!(seal_info.reason.as_deref(), Some("slots"));
+            assert_eq!(seal_info.capacity_filled["slots"], 100.0);
+        })
+        .run(sealer)
+        .await;
+}
+
+#[tokio::test]
+async fn miniblock_sealed_on_shutdown() {
+    let config = StateKeeperConfig {
+        transaction_slots: 10,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    TestScenario::new()
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .miniblock_sealed_on_shutdown("Miniblock 1", |updates| {
+            assert_eq!(updates.miniblock.executed_transactions.len(), 2);
+        })
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn sealed_by_gas() {
     let config = StateKeeperConfig {
@@ -351,6 +402,115 @@ async fn rejected_tx() {
         .await;
 }
 
+#[tokio::test]
+async fn expired_tx() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    let mut expired_tx = random_tx(1);
+    let ExecuteTransactionCommon::L2(common_data) = &mut expired_tx.common_data else {
+        unreachable!();
+    };
+    // The first miniblock has number 1, so the transaction cannot be included into it.
+    common_data.valid_until = Some(ValidUntil::Block(MiniblockNumber(1)));
+
+    TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("Expired tx", expired_tx.clone(), successful_exec())
+        .tx_rejected(
+            "Expired tx got rejected",
+            expired_tx,
+            Some(TX_EXPIRED_ERROR.to_owned()),
+        )
+        .next_tx("Successful tx", random_tx(2), successful_exec())
+        .miniblock_sealed("Miniblock with successful tx")
+        .next_tx("Second successful tx", random_tx(3), successful_exec())
+        .miniblock_sealed("Second miniblock")
+        .batch_sealed("Batch with 2 successful txs")
+        .run(sealer)
+        .await;
+}
+
+#[tokio::test]
+async fn oversized_tx() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+    let tx_size_limits = TxSizeLimits {
+        max_calldata_size: 1_000,
+        ..TxSizeLimits::default()
+    };
+
+    let mut oversized_tx = random_tx(1);
+    oversized_tx.execute.calldata = vec![0; 1_001];
+
+    TestScenario::new()
+        .with_tx_size_limits(tx_size_limits)
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("Oversized tx", oversized_tx.clone(), successful_exec())
+        .tx_rejected(
+            "Oversized tx got rejected",
+            oversized_tx,
+            Some("calldata size".to_owned()),
+        )
+        .next_tx("Successful tx", random_tx(2), successful_exec())
+        .miniblock_sealed("Miniblock with successful tx")
+        .next_tx("Second successful tx", random_tx(3), successful_exec())
+        .miniblock_sealed("Second miniblock")
+        .batch_sealed("Batch with 2 successful txs")
+        .run(sealer)
+        .await;
+}
+
+#[tokio::test]
+async fn unsponsored_tx() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+    let paymaster = Address::repeat_byte(0xbe);
+    let policy = SponsorshipPolicy {
+        paymaster,
+        rules: vec![SponsorshipRule {
+            contract: Address::repeat_byte(1),
+            selectors: vec![],
+            max_gas_limit: u64::MAX,
+            max_pubdata: u32::MAX,
+        }],
+    };
+
+    // The transaction uses the sponsoring paymaster, but calls a contract not covered by the policy.
+    let mut unsponsored_tx = random_tx(1);
+    let ExecuteTransactionCommon::L2(data) = &mut unsponsored_tx.common_data else {
+        unreachable!();
+    };
+    data.paymaster_params.paymaster = paymaster;
+    unsponsored_tx.execute.contract_address = Address::repeat_byte(2);
+
+    TestScenario::new()
+        .with_sponsorship_policy(policy)
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("Unsponsored tx", unsponsored_tx.clone(), successful_exec())
+        .tx_rejected(
+            "Unsponsored tx got rejected",
+            unsponsored_tx,
+            Some("not eligible for sponsorship".to_owned()),
+        )
+        .next_tx("Successful tx", random_tx(2), successful_exec())
+        .miniblock_sealed("Miniblock with successful tx")
+        .next_tx("Second successful tx", random_tx(3), successful_exec())
+        .miniblock_sealed("Second miniblock")
+        .batch_sealed("Batch with 2 successful txs")
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn bootloader_tip_out_of_gas_flow() {
     let config = StateKeeperConfig {
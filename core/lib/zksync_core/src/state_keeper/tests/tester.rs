@@ -22,6 +22,7 @@ use zksync_types::{
 };
 
 use crate::{
+    api_server::tx_sender::sponsorship::SponsorshipPolicy,
     state_keeper::{
         batch_executor::{BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult},
         io::{MiniblockParams, PendingBatchData, StateKeeperIO},
@@ -29,7 +30,7 @@ use crate::{
         tests::{default_l1_batch_env, default_vm_block_result, BASE_SYSTEM_CONTRACTS},
         types::ExecutionMetricsForCriteria,
         updates::UpdatesManager,
-        ZkSyncStateKeeper,
+        TxSizeLimits, ZkSyncStateKeeper,
     },
     utils::testonly::create_l2_transaction,
 };
@@ -53,6 +54,9 @@ pub(crate) struct TestScenario {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     miniblock_seal_fn: Box<SealFn>,
+    seal_miniblock_on_shutdown: bool,
+    tx_size_limits: Option<TxSizeLimits>,
+    sponsorship_policy: Option<SponsorshipPolicy>,
 }
 
 type SealFn = dyn FnMut(&UpdatesManager) -> bool + Send;
@@ -74,6 +78,9 @@ impl TestScenario {
             pending_batch: None,
             l1_batch_seal_fn: Box::new(|_| false),
             miniblock_seal_fn: Box::new(|_| false),
+            seal_miniblock_on_shutdown: false,
+            tx_size_limits: None,
+            sponsorship_policy: None,
         }
     }
 
@@ -154,6 +161,19 @@ impl TestScenario {
         self
     }
 
+    /// Expects the miniblock to be sealed after the state keeper receives a stop signal. This must be the last action
+    /// in the scenario; the stop signal is sent once the preceding action is done.
+    pub(crate) fn miniblock_sealed_on_shutdown<F: FnOnce(&UpdatesManager) + Send + 'static>(
+        mut self,
+        description: &'static str,
+        f: F,
+    ) -> Self {
+        self.seal_miniblock_on_shutdown = true;
+        self.actions
+            .push_back(ScenarioItem::MiniblockSeal(description, Some(Box::new(f))));
+        self
+    }
+
     /// Expects the batch to be sealed.
     pub(crate) fn batch_sealed(mut self, description: &'static str) -> Self {
         self.actions
@@ -189,20 +209,44 @@ impl TestScenario {
         self
     }
 
+    /// Makes the state keeper enforce the specified transaction size limits.
+    pub(crate) fn with_tx_size_limits(mut self, tx_size_limits: TxSizeLimits) -> Self {
+        self.tx_size_limits = Some(tx_size_limits);
+        self
+    }
+
+    /// Makes the state keeper enforce the specified sponsorship policy.
+    pub(crate) fn with_sponsorship_policy(mut self, policy: SponsorshipPolicy) -> Self {
+        self.sponsorship_policy = Some(policy);
+        self
+    }
+
     /// Launches the test.
     /// Provided `SealManager` is expected to be externally configured to adhere the written scenario logic.
-    pub(crate) async fn run(self, sealer: SequencerSealer) {
+    pub(crate) async fn run(mut self, sealer: SequencerSealer) {
         assert!(!self.actions.is_empty(), "Test scenario can't be empty");
 
         let batch_executor_base = TestBatchExecutorBuilder::new(&self);
+        let seal_miniblock_on_shutdown = self.seal_miniblock_on_shutdown;
+        let tx_size_limits = self.tx_size_limits;
+        let sponsorship_policy = self.sponsorship_policy.take();
         let (stop_sender, stop_receiver) = watch::channel(false);
         let io = TestIO::new(stop_sender, self);
-        let sk = ZkSyncStateKeeper::new(
+        let mut sk = ZkSyncStateKeeper::new(
             stop_receiver,
             Box::new(io),
             Box::new(batch_executor_base),
             Arc::new(sealer),
         );
+        if seal_miniblock_on_shutdown {
+            sk = sk.with_miniblock_sealing_on_shutdown();
+        }
+        if let Some(tx_size_limits) = tx_size_limits {
+            sk = sk.with_tx_size_limits(tx_size_limits);
+        }
+        if let Some(policy) = sponsorship_policy {
+            sk = sk.with_sponsorship_policy(policy);
+        }
         let sk_thread = tokio::spawn(sk.run());
 
         // We must assume that *theoretically* state keeper may ignore the stop signal from IO once scenario is
@@ -615,8 +659,10 @@ impl TestIO {
             return self.pop_next_item(request);
         }
 
-        // If that was a last action, tell the state keeper to stop after that.
-        if self.scenario.actions.is_empty() {
+        // If that was a last action (not counting the miniblock sealed on shutdown), tell the state keeper
+        // to stop after that.
+        let remaining_actions = usize::from(self.scenario.seal_miniblock_on_shutdown);
+        if self.scenario.actions.len() <= remaining_actions {
             self.stop_sender.send(true).unwrap();
         }
         action
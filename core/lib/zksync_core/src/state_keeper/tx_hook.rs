@@ -0,0 +1,308 @@
+//! Hooks notifying external services (e.g., real-time risk or compliance engines) about transactions
+//! executed by the state keeper before the containing miniblock is sealed.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    web3::types::Bytes, Address, L1BatchNumber, MiniblockNumber, Transaction, H256,
+};
+
+use super::metrics::{TxHookOutcome, KEEPER_METRICS};
+
+/// Event emitted by an executed transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutedTxEvent {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+/// Information about an executed transaction passed to a [`TxExecutionHook`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutedTxInfo {
+    pub tx_hash: H256,
+    pub initiator: Address,
+    pub contract_address: Address,
+    pub is_l1: bool,
+    pub l1_batch_number: L1BatchNumber,
+    pub miniblock_number: MiniblockNumber,
+    /// Revert or halt reason if the transaction has failed.
+    pub failure_reason: Option<String>,
+    pub gas_used: u32,
+    pub events: Vec<ExecutedTxEvent>,
+}
+
+impl ExecutedTxInfo {
+    pub(super) fn new(
+        tx: &Transaction,
+        tx_result: &VmExecutionResultAndLogs,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+    ) -> Self {
+        let failure_reason = match &tx_result.result {
+            ExecutionResult::Success { .. } => None,
+            ExecutionResult::Revert { output } => Some(output.to_string()),
+            ExecutionResult::Halt { reason } => Some(reason.to_string()),
+        };
+        let events = tx_result
+            .logs
+            .events
+            .iter()
+            .map(|event| ExecutedTxEvent {
+                address: event.address,
+                topics: event.indexed_topics.clone(),
+                data: event.value.clone().into(),
+            })
+            .collect();
+
+        Self {
+            tx_hash: tx.hash(),
+            initiator: tx.initiator_account(),
+            contract_address: tx.execute.contract_address,
+            is_l1: tx.is_l1(),
+            l1_batch_number,
+            miniblock_number,
+            failure_reason,
+            gas_used: tx_result.statistics.gas_used,
+            events,
+        }
+    }
+}
+
+/// Verdict on an executed transaction returned by a [`TxExecutionHook`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "camelCase")]
+pub enum TxHookVerdict {
+    /// Transaction should be included into the miniblock.
+    Accept,
+    /// Transaction should be rejected. Only has effect for L2 transactions in the blocking mode;
+    /// L1 transactions cannot be rejected.
+    Reject { reason: String },
+}
+
+/// Hook notified about transactions executed by the state keeper before the containing miniblock is sealed.
+/// The hook is not called for protocol upgrade transactions or transactions re-executed after a restart.
+#[async_trait]
+pub trait TxExecutionHook: fmt::Debug + Send + Sync + 'static {
+    async fn on_tx_executed(&self, tx: &ExecutedTxInfo) -> anyhow::Result<TxHookVerdict>;
+}
+
+/// Hook posting [`ExecutedTxInfo`] as JSON to an HTTP endpoint. The endpoint may respond with a [`TxHookVerdict`];
+/// an empty response body is treated as [`TxHookVerdict::Accept`].
+#[derive(Debug)]
+pub struct HttpTxHook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpTxHook {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait]
+impl TxExecutionHook for HttpTxHook {
+    async fn on_tx_executed(&self, tx: &ExecutedTxInfo) -> anyhow::Result<TxHookVerdict> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(tx)
+            .send()
+            .await
+            .context("failed sending request")?
+            .error_for_status()?;
+        let body = response.bytes().await.context("failed reading response")?;
+        if body.is_empty() {
+            return Ok(TxHookVerdict::Accept);
+        }
+        serde_json::from_slice(&body).context("failed parsing verdict")
+    }
+}
+
+/// Mode of calling a [`TxExecutionHook`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TxHookMode {
+    /// The hook is called in the background; its verdict is ignored.
+    FireAndForget,
+    /// The state keeper waits for the hook verdict. If the hook errors or doesn't respond in time,
+    /// the transaction is accepted.
+    Blocking { timeout: Duration },
+}
+
+/// [`TxExecutionHook`] together with the mode of calling it.
+#[derive(Debug, Clone)]
+pub struct TxHookRunner {
+    hook: Arc<dyn TxExecutionHook>,
+    mode: TxHookMode,
+}
+
+impl TxHookRunner {
+    pub fn new(hook: Arc<dyn TxExecutionHook>, mode: TxHookMode) -> Self {
+        Self { hook, mode }
+    }
+
+    /// Returns the rejection reason if the transaction should be rejected.
+    pub(super) async fn run(&self, tx: ExecutedTxInfo) -> Option<String> {
+        let timeout = match self.mode {
+            TxHookMode::FireAndForget => {
+                let hook = self.hook.clone();
+                tokio::spawn(async move {
+                    match hook.on_tx_executed(&tx).await {
+                        Ok(_) => KEEPER_METRICS.tx_hook_outcomes[&TxHookOutcome::Accepted].inc(),
+                        Err(err) => {
+                            tracing::warn!(
+                                "Transaction hook failed for tx {:?}: {err:#}",
+                                tx.tx_hash
+                            );
+                            KEEPER_METRICS.tx_hook_outcomes[&TxHookOutcome::Error].inc();
+                        }
+                    }
+                });
+                return None;
+            }
+            TxHookMode::Blocking { timeout } => timeout,
+        };
+
+        let latency = KEEPER_METRICS.tx_hook_latency.start();
+        let verdict = tokio::time::timeout(timeout, self.hook.on_tx_executed(&tx)).await;
+        latency.observe();
+        match verdict {
+            Ok(Ok(TxHookVerdict::Accept)) => {
+                KEEPER_METRICS.tx_hook_outcomes[&TxHookOutcome::Accepted].inc();
+                None
+            }
+            Ok(Ok(TxHookVerdict::Reject { reason })) if tx.is_l1 => {
+                tracing::warn!(
+                    "Transaction hook requested to reject L1 tx {:?} ({reason}); ignoring",
+                    tx.tx_hash
+                );
+                KEEPER_METRICS.tx_hook_outcomes[&TxHookOutcome::Accepted].inc();
+                None
+            }
+            Ok(Ok(TxHookVerdict::Reject { reason })) => {
+                KEEPER_METRICS.tx_hook_outcomes[&TxHookOutcome::Rejected].inc();
+                Some(reason)
+            }
+            Ok(Err(err)) => {
+                tracing::warn!(
+                    "Transaction hook failed for tx {:?}; accepting it: {err:#}",
+                    tx.tx_hash
+                );
+                KEEPER_METRICS.tx_hook_outcomes[&TxHookOutcome::Error].inc();
+                None
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "Transaction hook timed out after {timeout:?} for tx {:?}; accepting it",
+                    tx.tx_hash
+                );
+                KEEPER_METRICS.tx_hook_outcomes[&TxHookOutcome::Timeout].inc();
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockHook {
+        verdict: TxHookVerdict,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl TxExecutionHook for MockHook {
+        async fn on_tx_executed(&self, _tx: &ExecutedTxInfo) -> anyhow::Result<TxHookVerdict> {
+            tokio::time::sleep(self.delay).await;
+            Ok(self.verdict.clone())
+        }
+    }
+
+    fn mock_tx_info(is_l1: bool) -> ExecutedTxInfo {
+        ExecutedTxInfo {
+            tx_hash: H256::repeat_byte(1),
+            initiator: Address::repeat_byte(2),
+            contract_address: Address::repeat_byte(3),
+            is_l1,
+            l1_batch_number: L1BatchNumber(1),
+            miniblock_number: MiniblockNumber(1),
+            failure_reason: None,
+            gas_used: 21_000,
+            events: vec![],
+        }
+    }
+
+    fn blocking_runner(verdict: TxHookVerdict, delay: Duration) -> TxHookRunner {
+        let hook = MockHook { verdict, delay };
+        let mode = TxHookMode::Blocking {
+            timeout: Duration::from_millis(100),
+        };
+        TxHookRunner::new(Arc::new(hook), mode)
+    }
+
+    #[tokio::test]
+    async fn blocking_hook_rejecting_transactions() {
+        let verdict = TxHookVerdict::Reject {
+            reason: "sanctioned".to_owned(),
+        };
+        let runner = blocking_runner(verdict, Duration::ZERO);
+
+        let rejection = runner.run(mock_tx_info(false)).await;
+        assert_eq!(rejection.as_deref(), Some("sanctioned"));
+        // L1 transactions cannot be rejected.
+        let rejection = runner.run(mock_tx_info(true)).await;
+        assert_eq!(rejection, None);
+    }
+
+    #[tokio::test]
+    async fn blocking_hook_timing_out() {
+        let verdict = TxHookVerdict::Reject {
+            reason: "sanctioned".to_owned(),
+        };
+        let runner = blocking_runner(verdict, Duration::from_secs(10));
+
+        let rejection = runner.run(mock_tx_info(false)).await;
+        assert_eq!(rejection, None);
+    }
+
+    #[tokio::test]
+    async fn fire_and_forget_hook_never_rejects_transactions() {
+        let hook = MockHook {
+            verdict: TxHookVerdict::Reject {
+                reason: "sanctioned".to_owned(),
+            },
+            delay: Duration::ZERO,
+        };
+        let runner = TxHookRunner::new(Arc::new(hook), TxHookMode::FireAndForget);
+
+        let rejection = runner.run(mock_tx_info(false)).await;
+        assert_eq!(rejection, None);
+    }
+
+    #[test]
+    fn verdict_serialization() {
+        let verdict: TxHookVerdict = serde_json::from_str(r#"{ "verdict": "accept" }"#).unwrap();
+        assert_eq!(verdict, TxHookVerdict::Accept);
+        let verdict: TxHookVerdict =
+            serde_json::from_str(r#"{ "verdict": "reject", "reason": "sanctioned" }"#).unwrap();
+        assert_eq!(
+            verdict,
+            TxHookVerdict::Reject {
+                reason: "sanctioned".to_owned()
+            }
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Per-chain size limits for L2 transactions.
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_system_constants::{MAX_ENCODED_TX_SIZE, MAX_NEW_FACTORY_DEPS};
+use zksync_types::{api::TxLimits, Bytes, Execute, Transaction, U64};
+
+/// Error returned if a transaction exceeds one of [`TxSizeLimits`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TxSizeLimitError {
+    #[error("encoded transaction size is {0} bytes, while only {1} bytes are allowed")]
+    EncodedSize(usize, usize),
+    #[error(
+        "too many factory dependencies in the transaction. {0} provided, while only {1} allowed"
+    )]
+    FactoryDeps(usize, usize),
+    #[error("transaction calldata size is {0} bytes, while only {1} bytes are allowed")]
+    CalldataSize(usize, usize),
+}
+
+/// Size limits for L2 transactions enforced both by the API server and the state keeper.
+/// Limits can be configured per chain, but cannot exceed the bounds imposed by the protocol.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TxSizeLimits {
+    pub max_encoded_size: usize,
+    pub max_factory_deps: usize,
+    pub max_calldata_size: usize,
+}
+
+impl Default for TxSizeLimits {
+    /// Returns the limits imposed by the protocol.
+    fn default() -> Self {
+        Self {
+            max_encoded_size: MAX_ENCODED_TX_SIZE,
+            max_factory_deps: MAX_NEW_FACTORY_DEPS,
+            // Calldata is a part of the encoded transaction, so it's implicitly bounded by the encoded size.
+            max_calldata_size: MAX_ENCODED_TX_SIZE,
+        }
+    }
+}
+
+impl TxSizeLimits {
+    /// Creates limits based on the state keeper config. The encoded transaction size is limited by `max_tx_size`
+    /// from the Web3 API config, so that the API server and the state keeper enforce the same limit.
+    pub fn new(config: &StateKeeperConfig, max_tx_size: Option<usize>) -> Self {
+        let bounds = Self::default();
+        Self {
+            max_encoded_size: max_tx_size.map_or(bounds.max_encoded_size, |size| {
+                size.min(bounds.max_encoded_size)
+            }),
+            max_factory_deps: config
+                .max_factory_deps
+                .map_or(bounds.max_factory_deps, |count| {
+                    count.min(bounds.max_factory_deps)
+                }),
+            max_calldata_size: config
+                .max_tx_calldata_size
+                .map_or(bounds.max_calldata_size, |size| {
+                    size.min(bounds.max_calldata_size)
+                }),
+        }
+    }
+
+    /// Creates limits based on the chain parameters reported by the main node.
+    pub fn from_api_limits(limits: &TxLimits) -> Self {
+        let bounds = Self::default();
+        let clamp = |value: U64, bound: usize| {
+            usize::try_from(value.as_u64()).map_or(bound, |value| value.min(bound))
+        };
+        Self {
+            max_encoded_size: clamp(limits.max_encoded_tx_size, bounds.max_encoded_size),
+            max_factory_deps: clamp(limits.max_factory_deps, bounds.max_factory_deps),
+            max_calldata_size: clamp(limits.max_calldata_size, bounds.max_calldata_size),
+        }
+    }
+
+    pub fn to_api_limits(self) -> TxLimits {
+        TxLimits {
+            max_encoded_tx_size: (self.max_encoded_size as u64).into(),
+            max_factory_deps: (self.max_factory_deps as u64).into(),
+            max_calldata_size: (self.max_calldata_size as u64).into(),
+        }
+    }
+
+    /// Checks whether an L2 transaction with the specified contents satisfies the limits. The encoded size
+    /// is only checked if raw transaction bytes are available.
+    pub fn check(
+        &self,
+        execute: &Execute,
+        raw_bytes: Option<&Bytes>,
+    ) -> Result<(), TxSizeLimitError> {
+        if let Some(raw_bytes) = raw_bytes {
+            if raw_bytes.0.len() > self.max_encoded_size {
+                return Err(TxSizeLimitError::EncodedSize(
+                    raw_bytes.0.len(),
+                    self.max_encoded_size,
+                ));
+            }
+        }
+        if execute.factory_deps_length() > self.max_factory_deps {
+            return Err(TxSizeLimitError::FactoryDeps(
+                execute.factory_deps_length(),
+                self.max_factory_deps,
+            ));
+        }
+        if execute.calldata.len() > self.max_calldata_size {
+            return Err(TxSizeLimitError::CalldataSize(
+                execute.calldata.len(),
+                self.max_calldata_size,
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_tx(&self, tx: &Transaction) -> Result<(), TxSizeLimitError> {
+        self.check(&tx.execute, tx.raw_bytes.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_are_bounded_by_protocol() {
+        let config = StateKeeperConfig {
+            max_factory_deps: Some(10),
+            ..StateKeeperConfig::for_tests()
+        };
+        let limits = TxSizeLimits::new(&config, Some(usize::MAX));
+        assert_eq!(limits.max_encoded_size, MAX_ENCODED_TX_SIZE);
+        assert_eq!(limits.max_factory_deps, 10);
+        assert_eq!(limits.max_calldata_size, MAX_ENCODED_TX_SIZE);
+
+        let api_limits = limits.to_api_limits();
+        assert_eq!(TxSizeLimits::from_api_limits(&api_limits), limits);
+    }
+
+    #[test]
+    fn checking_limits() {
+        let limits = TxSizeLimits {
+            max_encoded_size: 100,
+            max_factory_deps: 1,
+            max_calldata_size: 10,
+        };
+        let mut execute = Execute {
+            calldata: vec![0; 10],
+            factory_deps: Some(vec![vec![0; 32]]),
+            ..Execute::default()
+        };
+        limits.check(&execute, Some(&Bytes(vec![0; 100]))).unwrap();
+
+        assert_eq!(
+            limits.check(&execute, Some(&Bytes(vec![0; 101]))),
+            Err(TxSizeLimitError::EncodedSize(101, 100))
+        );
+        execute.calldata.push(0);
+        assert_eq!(
+            limits.check(&execute, None),
+            Err(TxSizeLimitError::CalldataSize(11, 10))
+        );
+        execute.factory_deps.as_mut().unwrap().push(vec![0; 32]);
+        assert_eq!(
+            limits.check(&execute, None),
+            Err(TxSizeLimitError::FactoryDeps(2, 1))
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
@@ -7,7 +7,8 @@ use multivm::interface::VmExecutionResultAndLogs;
 use zksync_dal::StorageProcessor;
 use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    block::BlockGasCount, tx::ExecutionMetrics, AccountNonceOrdering, Address, Nonce, PriorityOpId,
+    Transaction, U256,
 };
 
 use super::metrics::StateKeeperGauges;
@@ -25,11 +26,58 @@ impl MempoolGuard {
         Self::new(next_priority_id, capacity)
     }
 
-    pub(super) fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
+    pub(crate) fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
         let store = MempoolStore::new(next_priority_id, capacity);
         Self(Arc::new(Mutex::new(store)))
     }
 
+    /// Sets operator accounts whose transactions are force-included ahead of other L2 transactions.
+    pub fn with_force_include_accounts(self, accounts: HashSet<Address>) -> Self {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .set_force_include_accounts(accounts);
+        self
+    }
+
+    /// Enables ordering L2 transactions by their arrival sequence numbers.
+    pub fn with_fair_ordering(self, fair_ordering: bool) -> Self {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .set_fair_ordering(fair_ordering);
+        self
+    }
+
+    /// Changes the capacity of the mempool at runtime.
+    pub fn set_capacity(&self, capacity: u64) {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .set_capacity(capacity);
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .capacity()
+    }
+
+    pub fn min_l1_tx_fee_per_gas(&self) -> Option<U256> {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .min_l1_tx_fee_per_gas()
+    }
+
+    pub fn is_force_include_account(&self, account: &Address) -> bool {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .is_force_include_account(account)
+    }
+
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
         self.0
             .lock()
@@ -37,6 +85,18 @@ impl MempoolGuard {
             .insert(transactions, nonces);
     }
 
+    pub fn insert_with_nonce_orderings(
+        &mut self,
+        transactions: Vec<Transaction>,
+        nonces: HashMap<Address, Nonce>,
+        nonce_orderings: &HashMap<Address, AccountNonceOrdering>,
+    ) {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .insert_with_nonce_orderings(transactions, nonces, nonce_orderings);
+    }
+
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
         self.0
             .lock()
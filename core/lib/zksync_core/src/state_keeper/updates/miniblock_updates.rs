@@ -106,6 +106,10 @@ impl MiniblockUpdates {
             ExecutionResult::Revert { output } => Some(output.to_string()),
             ExecutionResult::Halt { reason } => Some(reason.to_string()),
         };
+        let revert_data = match &tx_execution_result.result {
+            ExecutionResult::Revert { output } => Some(output.encoded_data()),
+            _ => None,
+        };
 
         // Get transaction factory deps
         let factory_deps = tx.execute.factory_deps.as_deref().unwrap_or_default();
@@ -143,6 +147,7 @@ impl MiniblockUpdates {
             compressed_bytecodes,
             call_traces,
             revert_reason,
+            revert_data,
         });
     }
 
@@ -159,6 +164,14 @@ impl MiniblockUpdates {
         digest.finalize(self.protocol_version)
     }
 
+    /// Returns the amount of gas used by transactions in this miniblock.
+    pub(crate) fn gas_used(&self) -> u64 {
+        self.executed_transactions
+            .iter()
+            .map(|tx| gas_used_by_tx(&tx.transaction, tx.refunded_gas))
+            .sum()
+    }
+
     pub(crate) fn get_miniblock_env(&self) -> L2BlockEnv {
         L2BlockEnv {
             number: self.number,
@@ -169,6 +182,12 @@ impl MiniblockUpdates {
     }
 }
 
+/// Computes the amount of gas used by a transaction in the same way as the API does, i.e. as the transaction
+/// gas limit minus the refunded gas.
+pub(crate) fn gas_used_by_tx(tx: &Transaction, refunded_gas: u32) -> u64 {
+    tx.gas_limit().low_u64().saturating_sub(refunded_gas.into())
+}
+
 #[cfg(test)]
 mod tests {
     use multivm::vm_latest::TransactionVmExt;
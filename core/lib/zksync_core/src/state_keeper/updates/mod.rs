@@ -1,18 +1,22 @@
+use std::collections::BTreeMap;
+
 use multivm::{
     interface::{L1BatchEnv, SystemEnv, VmExecutionResultAndLogs},
     utils::get_batch_base_fee,
 };
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
-    block::BlockGasCount, fee_model::BatchFeeInput,
+    block::{BlockGasCount, SealInfo},
+    fee_model::BatchFeeInput,
     storage_writes_deduplicator::StorageWritesDeduplicator,
-    tx::tx_execution_info::ExecutionMetrics, vm_trace::Call, Address, L1BatchNumber,
-    MiniblockNumber, ProtocolVersionId, Transaction,
+    tx::tx_execution_info::ExecutionMetrics,
+    vm_trace::Call,
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, Transaction,
 };
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
 pub(crate) use self::{l1_batch_updates::L1BatchUpdates, miniblock_updates::MiniblockUpdates};
-use super::io::MiniblockParams;
+use super::{io::MiniblockParams, seal_criteria::MINIBLOCK_GAS_LIMIT_CRITERION};
 
 pub mod l1_batch_updates;
 pub mod miniblock_updates;
@@ -31,6 +35,11 @@ pub struct UpdatesManager {
     base_fee_per_gas: u64,
     base_system_contract_hashes: BaseSystemContractsHashes,
     protocol_version: ProtocolVersionId,
+    miniblock_gas_limit: Option<u64>,
+    l1_batch_seal_reason: Option<&'static str>,
+    l1_batch_capacity_filled: BTreeMap<&'static str, f64>,
+    miniblock_seal_reason: Option<&'static str>,
+    miniblock_capacity_filled: BTreeMap<&'static str, f64>,
     pub l1_batch: L1BatchUpdates,
     pub miniblock: MiniblockUpdates,
     pub storage_writes_deduplicator: StorageWritesDeduplicator,
@@ -46,6 +55,11 @@ impl UpdatesManager {
             base_fee_per_gas: get_batch_base_fee(l1_batch_env, protocol_version.into()),
             protocol_version,
             base_system_contract_hashes: system_env.base_system_smart_contracts.hashes(),
+            miniblock_gas_limit: None,
+            l1_batch_seal_reason: None,
+            l1_batch_capacity_filled: BTreeMap::new(),
+            miniblock_seal_reason: None,
+            miniblock_capacity_filled: BTreeMap::new(),
             l1_batch: L1BatchUpdates::new(),
             miniblock: MiniblockUpdates::new(
                 l1_batch_env.first_l2_block.timestamp,
@@ -66,6 +80,72 @@ impl UpdatesManager {
         self.base_system_contract_hashes
     }
 
+    /// Sets the gas limit for miniblocks in the L1 batch. The limit is persisted with each sealed miniblock.
+    pub(crate) fn set_miniblock_gas_limit(&mut self, gas_limit: Option<u64>) {
+        self.miniblock_gas_limit = gas_limit;
+    }
+
+    /// Checks whether a transaction using the specified amount of gas fits into the current miniblock.
+    pub(crate) fn fits_into_miniblock(&self, tx_gas_used: u64) -> bool {
+        self.miniblock_gas_limit.map_or(true, |limit| {
+            self.miniblock.gas_used() + tx_gas_used <= limit
+        })
+    }
+
+    /// Sets the reason for sealing the L1 batch. The reason is persisted when the batch is sealed.
+    pub(crate) fn set_l1_batch_seal_reason(&mut self, reason: Option<&'static str>) {
+        self.l1_batch_seal_reason = reason;
+    }
+
+    /// Sets the percentage of the L1 batch capacity filled according to each seal criterion.
+    pub(crate) fn set_l1_batch_capacity_filled(
+        &mut self,
+        capacity_filled: BTreeMap<&'static str, f64>,
+    ) {
+        self.l1_batch_capacity_filled = capacity_filled;
+    }
+
+    /// Sets the reason for sealing the current miniblock. The reason is reset once a new miniblock is started.
+    pub(crate) fn set_miniblock_seal_reason(&mut self, reason: Option<&'static str>) {
+        self.miniblock_seal_reason = reason;
+    }
+
+    /// Sets the percentage of the L1 batch capacity filled as of sealing the current miniblock according
+    /// to each seal criterion. Reset once a new miniblock is started.
+    pub(crate) fn set_miniblock_capacity_filled(
+        &mut self,
+        capacity_filled: BTreeMap<&'static str, f64>,
+    ) {
+        self.miniblock_capacity_filled = capacity_filled;
+    }
+
+    pub(crate) fn l1_batch_seal_info(&self) -> SealInfo {
+        SealInfo {
+            reason: self.l1_batch_seal_reason.map(str::to_owned),
+            capacity_filled: self
+                .l1_batch_capacity_filled
+                .iter()
+                .map(|(&name, &capacity_filled)| (name.to_owned(), capacity_filled))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn miniblock_seal_info(&self) -> SealInfo {
+        let mut capacity_filled: BTreeMap<_, _> = self
+            .miniblock_capacity_filled
+            .iter()
+            .map(|(&name, &capacity_filled)| (name.to_owned(), capacity_filled))
+            .collect();
+        if let Some(limit) = self.miniblock_gas_limit {
+            let gas_limit_filled = self.miniblock.gas_used() as f64 / limit as f64 * 100.0;
+            capacity_filled.insert(MINIBLOCK_GAS_LIMIT_CRITERION.to_owned(), gas_limit_filled);
+        }
+        SealInfo {
+            reason: self.miniblock_seal_reason.map(str::to_owned),
+            capacity_filled,
+        }
+    }
+
     pub(crate) fn seal_miniblock_command(
         &self,
         l1_batch_number: L1BatchNumber,
@@ -83,6 +163,8 @@ impl UpdatesManager {
             base_fee_per_gas: self.base_fee_per_gas,
             base_system_contracts_hashes: self.base_system_contract_hashes,
             protocol_version: Some(self.protocol_version),
+            gas_limit: self.miniblock_gas_limit,
+            seal_info: self.miniblock_seal_info(),
             l2_erc20_bridge_addr,
             pre_insert_txs,
         }
@@ -136,6 +218,8 @@ impl UpdatesManager {
             self.protocol_version,
         );
         let old_miniblock_updates = std::mem::replace(&mut self.miniblock, new_miniblock_updates);
+        self.miniblock_seal_reason = None;
+        self.miniblock_capacity_filled.clear();
         self.l1_batch
             .extend_from_sealed_miniblock(old_miniblock_updates);
     }
@@ -169,6 +253,8 @@ pub(crate) struct MiniblockSealCommand {
     pub base_fee_per_gas: u64,
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
     pub protocol_version: Option<ProtocolVersionId>,
+    pub gas_limit: Option<u64>,
+    pub seal_info: SealInfo,
     pub l2_erc20_bridge_addr: Address,
     /// Whether transactions should be pre-inserted to DB.
     /// Should be set to `true` for EN's IO as EN doesn't store transactions in DB
@@ -220,4 +306,33 @@ mod tests {
         assert_eq!(updates_manager.miniblock.executed_transactions.len(), 0);
         assert_eq!(updates_manager.l1_batch.executed_transactions.len(), 1);
     }
+
+    #[test]
+    fn checking_miniblock_gas_limit() {
+        let mut updates_manager = create_updates_manager();
+        assert!(updates_manager.fits_into_miniblock(u64::MAX));
+
+        // Each test transaction has a 1,000 gas limit and no refunds.
+        updates_manager.set_miniblock_gas_limit(Some(2_500));
+        assert!(updates_manager.fits_into_miniblock(1_000));
+        updates_manager.extend_from_executed_transaction(
+            create_transaction(10, 100),
+            create_execution_result(0, []),
+            vec![],
+            new_block_gas_count(),
+            ExecutionMetrics::default(),
+            vec![],
+        );
+        assert_eq!(updates_manager.miniblock.gas_used(), 1_000);
+        assert!(updates_manager.fits_into_miniblock(1_500));
+        assert!(!updates_manager.fits_into_miniblock(1_501));
+
+        // The limit applies to each miniblock separately.
+        updates_manager.push_miniblock(MiniblockParams {
+            timestamp: 2,
+            virtual_blocks: 1,
+        });
+        assert_eq!(updates_manager.miniblock.gas_used(), 0);
+        assert!(updates_manager.fits_into_miniblock(2_500));
+    }
 }
@@ -48,6 +48,8 @@ pub struct ExternalIO {
 
     current_l1_batch_number: L1BatchNumber,
     current_miniblock_number: MiniblockNumber,
+    /// Gas limit of the current miniblock as reported by the main node.
+    current_miniblock_gas_limit: Option<u64>,
     prev_miniblock_hash: H256,
     l1_batch_params_provider: L1BatchParamsProvider,
     actions: ActionQueue,
@@ -98,6 +100,7 @@ impl ExternalIO {
             pool,
             current_l1_batch_number: cursor.l1_batch,
             current_miniblock_number: cursor.next_miniblock,
+            current_miniblock_gas_limit: None,
             prev_miniblock_hash: cursor.prev_miniblock_hash,
             l1_batch_params_provider,
             actions,
@@ -356,12 +359,14 @@ impl StateKeeperIO for ExternalIO {
                     operator_address,
                     protocol_version,
                     first_miniblock_info: (miniblock_number, virtual_blocks),
+                    first_miniblock_gas_limit,
                 }) => {
                     anyhow::ensure!(
                         number == self.current_l1_batch_number,
                         "Batch number mismatch: expected {}, got {number}",
                         self.current_l1_batch_number
                     );
+                    self.current_miniblock_gas_limit = first_miniblock_gas_limit;
                     let previous_l1_batch_hash = self.wait_for_previous_l1_batch_hash().await?;
                     tracing::info!(
                         "Previous L1 batch hash: {previous_l1_batch_hash:?}, previous miniblock hash: {:?}",
@@ -417,6 +422,7 @@ impl StateKeeperIO for ExternalIO {
                     number,
                     timestamp,
                     virtual_blocks,
+                    gas_limit,
                 }) => {
                     self.actions.pop_action(); // We found the miniblock, remove it from the queue.
                     anyhow::ensure!(
@@ -424,6 +430,7 @@ impl StateKeeperIO for ExternalIO {
                         "Miniblock number mismatch: expected {}, got {number}",
                         self.current_miniblock_number
                     );
+                    self.current_miniblock_gas_limit = gas_limit;
                     return Ok(Some(MiniblockParams {
                         timestamp,
                         virtual_blocks,
@@ -499,12 +506,14 @@ impl StateKeeperIO for ExternalIO {
         };
 
         // Now transactions are stored, and we may mark them as executed.
-        let command = updates_manager.seal_miniblock_command(
+        let mut command = updates_manager.seal_miniblock_command(
             self.current_l1_batch_number,
             self.current_miniblock_number,
             self.l2_erc20_bridge_addr,
             true,
         );
+        // Miniblock gas limits are not enforced by the external node; they are copied from the main node instead.
+        command.gas_limit = self.current_miniblock_gas_limit;
         self.miniblock_sealer_handle.submit(command).await;
 
         self.sync_state
@@ -517,7 +526,7 @@ impl StateKeeperIO for ExternalIO {
         &mut self,
         // needed as part of the interface, to be removed once we transition to Merkle Paths
         _witness_block_state: Option<WitnessBlockState>,
-        updates_manager: UpdatesManager,
+        mut updates_manager: UpdatesManager,
         l1_batch_env: &L1BatchEnv,
         finished_batch: FinishedL1Batch,
     ) -> anyhow::Result<()> {
@@ -531,6 +540,8 @@ impl StateKeeperIO for ExternalIO {
         // We cannot start sealing an L1 batch until we've sealed all miniblocks included in it.
         self.miniblock_sealer_handle.wait_for_all_commands().await;
 
+        // Set the gas limit for the fictive miniblock.
+        updates_manager.set_miniblock_gas_limit(self.current_miniblock_gas_limit);
         let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
         let fictive_miniblock = updates_manager
             .seal_l1_batch(
@@ -542,6 +553,8 @@ impl StateKeeperIO for ExternalIO {
             )
             .await;
         drop(storage);
+        self.miniblock_sealer_handle
+            .publish_write_set(&fictive_miniblock);
 
         self.update_miniblock_fields(&fictive_miniblock);
         tracing::info!("Batch {} is sealed", self.current_l1_batch_number);
@@ -4,8 +4,9 @@ use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_dal::StorageProcessor;
 use zksync_types::{
-    api::en::SyncBlock, block::MiniblockHasher, Address, L1BatchNumber, MiniblockNumber,
-    ProtocolVersionId, H256,
+    api::en::SyncBlock,
+    block::{MiniblockHashScheme, MiniblockHasher},
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256,
 };
 use zksync_web3_decl::{error::EnrichedClientError, jsonrpsee::core::ClientError as RpcError};
 
@@ -38,15 +39,16 @@ pub(crate) struct FetchedBlock {
     pub virtual_blocks: u32,
     pub operator_address: Address,
     pub transactions: Vec<zksync_types::Transaction>,
+    pub gas_limit: Option<u64>,
 }
 
 impl FetchedBlock {
-    fn compute_hash(&self, prev_miniblock_hash: H256) -> H256 {
+    fn hasher(&self, prev_miniblock_hash: H256) -> MiniblockHasher {
         let mut hasher = MiniblockHasher::new(self.number, self.timestamp, prev_miniblock_hash);
         for tx in &self.transactions {
             hasher.push_tx_hash(tx.hash());
         }
-        hasher.finalize(self.protocol_version)
+        hasher
     }
 }
 
@@ -77,6 +79,7 @@ impl TryFrom<SyncBlock> for FetchedBlock {
             virtual_blocks: block.virtual_blocks.unwrap_or(0),
             operator_address: block.operator_address,
             transactions,
+            gas_limit: block.gas_limit,
         })
     }
 }
@@ -100,16 +103,28 @@ impl IoCursor {
 
     pub(crate) fn advance(&mut self, block: FetchedBlock) -> Vec<SyncAction> {
         assert_eq!(block.number, self.next_miniblock);
-        let local_block_hash = block.compute_hash(self.prev_miniblock_hash);
+        let hasher = block.hasher(self.prev_miniblock_hash);
+        let local_block_hash = hasher.hash_with_scheme(MiniblockHashScheme::for_protocol_version(
+            block.protocol_version,
+        ));
         if let Some(reference_hash) = block.reference_hash {
-            if local_block_hash != reference_hash {
-                // This is a warning, not an assertion because hash mismatch may occur after a reorg.
-                // Indeed, `self.prev_miniblock_hash` may differ from the hash of the updated previous miniblock.
-                tracing::warn!(
-                    "Mismatch between the locally computed and received miniblock hash for {block:?}; \
-                     local_block_hash = {local_block_hash:?}, prev_miniblock_hash = {:?}",
-                    self.prev_miniblock_hash
-                );
+            match hasher.verify(Some(block.protocol_version), reference_hash) {
+                Ok(_) => { /* hash is correct */ }
+                Err(err) if err.detected_scheme.is_some() => {
+                    // The main node uses a hashing scheme not matching the protocol version of the miniblock.
+                    tracing::error!(
+                        "Main node uses an unexpected hashing scheme for {block:?}: {err}"
+                    );
+                }
+                Err(_) => {
+                    // This is a warning, not an assertion because hash mismatch may occur after a reorg.
+                    // Indeed, `self.prev_miniblock_hash` may differ from the hash of the updated previous miniblock.
+                    tracing::warn!(
+                        "Mismatch between the locally computed and received miniblock hash for {block:?}; \
+                         local_block_hash = {local_block_hash:?}, prev_miniblock_hash = {:?}",
+                        self.prev_miniblock_hash
+                    );
+                }
             }
         }
 
@@ -137,6 +152,7 @@ impl IoCursor {
                 protocol_version: block.protocol_version,
                 // `block.virtual_blocks` can be `None` only for old VM versions where it's not used, so it's fine to provide any number.
                 first_miniblock_info: (block.number, block.virtual_blocks),
+                first_miniblock_gas_limit: block.gas_limit,
             });
             FETCHER_METRICS.l1_batch[&L1BatchStage::Open].set(block.l1_batch_number.0.into());
             self.l1_batch += 1;
@@ -148,6 +164,7 @@ impl IoCursor {
                 timestamp: block.timestamp,
                 // `block.virtual_blocks` can be `None` only for old VM versions where it's not used, so it's fine to provide any number.
                 virtual_blocks: block.virtual_blocks,
+                gas_limit: block.gas_limit,
             });
             FETCHER_METRICS.miniblock.set(block.number.0.into());
         }
@@ -134,11 +134,15 @@ pub(crate) enum SyncAction {
         protocol_version: ProtocolVersionId,
         // Miniblock number and virtual blocks count.
         first_miniblock_info: (MiniblockNumber, u32),
+        /// Gas limit of the first miniblock enforced by the main node, if any.
+        first_miniblock_gas_limit: Option<u64>,
     },
     Miniblock {
         number: MiniblockNumber,
         timestamp: u64,
         virtual_blocks: u32,
+        /// Gas limit of the miniblock enforced by the main node, if any.
+        gas_limit: Option<u64>,
     },
     Tx(Box<Transaction>),
     /// We need an explicit action for the miniblock sealing, since we fetch the whole miniblocks and already know
@@ -175,6 +179,7 @@ mod tests {
             operator_address: Default::default(),
             protocol_version: ProtocolVersionId::latest(),
             first_miniblock_info: (1.into(), 1),
+            first_miniblock_gas_limit: None,
         }
     }
 
@@ -183,6 +188,7 @@ mod tests {
             number: 1.into(),
             timestamp: 1,
             virtual_blocks: 1,
+            gas_limit: None,
         }
     }
 
@@ -50,6 +50,7 @@ fn open_l1_batch(number: u32, timestamp: u64, first_miniblock_number: u32) -> Sy
         operator_address: OPERATOR_ADDRESS,
         protocol_version: ProtocolVersionId::latest(),
         first_miniblock_info: (MiniblockNumber(first_miniblock_number), 1),
+        first_miniblock_gas_limit: None,
     }
 }
 
@@ -351,6 +352,7 @@ pub(super) async fn run_state_keeper_with_multiple_miniblocks(
         number: snapshot.miniblock_number + 2,
         timestamp: snapshot.miniblock_timestamp + 2,
         virtual_blocks: 1,
+        gas_limit: None,
     };
     let more_txs = (0..3).map(|_| {
         let tx = create_l2_transaction(10, 100);
@@ -448,6 +450,7 @@ async fn test_external_io_recovery(
         number: snapshot.miniblock_number + 3,
         timestamp: snapshot.miniblock_timestamp + 3,
         virtual_blocks: 1,
+        gas_limit: None,
     };
     let actions = vec![open_miniblock, new_tx, SyncAction::SealMiniblock];
     actions_sender.push_actions(actions).await;
@@ -517,6 +520,7 @@ pub(super) async fn run_state_keeper_with_multiple_l1_batches(
         number: snapshot.miniblock_number + 2,
         timestamp: snapshot.miniblock_timestamp + 2,
         virtual_blocks: 0,
+        gas_limit: None,
     };
     let seal_l1_batch = SyncAction::SealBatch { virtual_blocks: 0 };
     let fictive_miniblock_actions = vec![fictive_miniblock, seal_l1_batch];
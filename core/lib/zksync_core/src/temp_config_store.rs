@@ -1,3 +1,4 @@
+use tokio::sync::watch;
 use zksync_config::{
     configs::{
         api::{HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig},
@@ -7,14 +8,15 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, KzgConfig,
-        PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
+        DAClientConfig, DADispatcherConfig, FriProofCompressorConfig, FriProverConfig,
+        FriWitnessGeneratorConfig, KzgConfig, PrometheusConfig, ProofDataHandlerConfig,
+        WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     GasAdjusterConfig, ObjectStoreConfig, PostgresConfig,
 };
 
-use crate::consensus;
+use crate::{consensus, remote_config::RemoteConfig};
 
 // TODO (QIT-22): This structure is going to be removed when components will be responsible for their own configs.
 /// A temporary config store allowing to pass deserialized configs from `zksync_server` to `zksync_core`.
@@ -47,5 +49,9 @@ pub struct TempConfigStore {
     pub gas_adjuster_config: Option<GasAdjusterConfig>,
     pub object_store_config: Option<ObjectStoreConfig>,
     pub kzg_config: Option<KzgConfig>,
+    pub da_dispatcher_config: Option<DADispatcherConfig>,
+    pub da_client_config: Option<DAClientConfig>,
     pub consensus_config: Option<consensus::MainNodeConfig>,
+    /// Runtime updates of the remote config, if the remote config service is polled.
+    pub remote_config_updates: Option<watch::Receiver<RemoteConfig>>,
 }
@@ -0,0 +1,7 @@
+//! Information about the node build embedded at compile time.
+
+/// Git commit the node is built from. Can be set with the `ZKSYNC_GIT_COMMIT` env var during the build;
+/// otherwise, it's determined using `git`. `None` if neither is available.
+pub const GIT_COMMIT: Option<&str> = option_env!("ZKSYNC_GIT_COMMIT");
+/// Cargo profile the node is built with (e.g., `release` or `debug`).
+pub const BUILD_PROFILE: &str = env!("ZKSYNC_BUILD_PROFILE");
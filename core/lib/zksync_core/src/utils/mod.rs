@@ -12,6 +12,9 @@ use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::{L1BatchNumber, ProtocolVersionId};
 
+pub(crate) mod build_info;
+#[cfg(test)]
+pub(crate) mod simulation;
 #[cfg(test)]
 pub(crate) mod testonly;
 
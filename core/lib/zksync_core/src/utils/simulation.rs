@@ -0,0 +1,134 @@
+//! Deterministic simulation harness for tests.
+//!
+//! The harness combines a [`VirtualClock`] with a scripted L1 based on [`MockEthereum`]. Tests drive components
+//! (the state keeper, mempool, `eth_sender` etc.) step by step, advancing virtual time in between, so that
+//! time-dependent behavior (seal deadlines, transaction resends, L1 reorgs) is reproducible regardless of the machine
+//! load. Tokio's paused time is not used because it auto-advances while waiting on DB queries, which triggers
+//! connection timeouts.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use zksync_eth_client::clients::MockEthereum;
+use zksync_utils::time::VirtualClock;
+
+/// Timestamp used as the start of simulations: 2024-01-01T00:00:00Z.
+const SIMULATION_START: Duration = Duration::from_secs(1_704_067_200);
+
+type L1Event = Box<dyn FnOnce(&MockEthereum)>;
+
+/// Simulation with virtual time and a scripted L1.
+///
+/// L1 blocks are mined with a fixed block time as virtual time advances. Additionally, events affecting L1
+/// (e.g., mining transactions or reorgs) can be scheduled at specific virtual timestamps relative to the start.
+pub(crate) struct Simulation {
+    clock: VirtualClock,
+    start: Duration,
+    l1: Arc<MockEthereum>,
+    l1_block_time: Duration,
+    next_l1_block_at: Duration,
+    /// Scheduled events ordered by time; events with the same time are ordered by scheduling.
+    events: Vec<(Duration, L1Event)>,
+}
+
+impl fmt::Debug for Simulation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Simulation")
+            .field("clock", &self.clock)
+            .field("l1_block_time", &self.l1_block_time)
+            .field("next_l1_block_at", &self.next_l1_block_at)
+            .field("events", &self.events.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Simulation {
+    pub fn new(l1: Arc<MockEthereum>, l1_block_time: Duration) -> Self {
+        assert!(!l1_block_time.is_zero(), "L1 block time must be positive");
+        Self {
+            clock: VirtualClock::new(SIMULATION_START),
+            start: SIMULATION_START,
+            l1,
+            l1_block_time,
+            next_l1_block_at: SIMULATION_START + l1_block_time,
+            events: vec![],
+        }
+    }
+
+    pub fn l1(&self) -> &Arc<MockEthereum> {
+        &self.l1
+    }
+
+    /// Returns virtual time elapsed since the simulation start.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.now() - self.start
+    }
+
+    /// Schedules an L1 event at the specified time since the simulation start. Events scheduled in the past
+    /// are executed on the next [`Self::advance()`] call.
+    pub fn at(&mut self, elapsed: Duration, event: impl FnOnce(&MockEthereum) + 'static) {
+        let at = self.start + elapsed;
+        let idx = self.events.partition_point(|(time, _)| *time <= at);
+        self.events.insert(idx, (at, Box::new(event)));
+    }
+
+    /// Advances virtual time by the specified duration, mining L1 blocks and executing scheduled events
+    /// in the chronological order. Blocks mined at the same time as an event are mined before the event.
+    pub fn advance(&mut self, duration: Duration) {
+        let target = self.clock.now() + duration;
+        loop {
+            let next_event_at = self.events.first().map(|(time, _)| *time);
+            let next_step_at = next_event_at.map_or(self.next_l1_block_at, |time| {
+                time.max(self.clock.now()).min(self.next_l1_block_at)
+            });
+            if next_step_at > target {
+                break;
+            }
+            self.clock.advance(next_step_at - self.clock.now());
+
+            if next_step_at == self.next_l1_block_at {
+                self.l1.advance_block_number(1);
+                self.next_l1_block_at += self.l1_block_time;
+            } else {
+                let (_, event) = self.events.remove(0);
+                event(&self.l1);
+            }
+        }
+        self.clock.advance(target - self.clock.now());
+    }
+
+    /// Advances virtual time by the specified number of L1 blocks.
+    pub fn advance_l1_blocks(&mut self, count: u32) {
+        self.advance(self.l1_block_time * count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_eth_client::EthInterface;
+    use zksync_utils::time::seconds_since_epoch;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn simulation_basics() {
+        let l1 = Arc::new(MockEthereum::default());
+        let mut simulation = Simulation::new(l1.clone(), Duration::from_secs(12));
+        assert_eq!(seconds_since_epoch(), SIMULATION_START.as_secs());
+
+        simulation.at(Duration::from_secs(30), |l1| l1.advance_block_number(100));
+        simulation.advance(Duration::from_secs(25));
+        assert_eq!(simulation.elapsed(), Duration::from_secs(25));
+        assert_eq!(l1.block_number("test").await.unwrap(), 2.into());
+
+        simulation.advance_l1_blocks(1);
+        assert_eq!(simulation.elapsed(), Duration::from_secs(37));
+        assert_eq!(l1.block_number("test").await.unwrap(), 103.into());
+        assert_eq!(seconds_since_epoch(), SIMULATION_START.as_secs() + 37);
+
+        // Events scheduled in the past are executed immediately.
+        simulation.at(Duration::ZERO, |l1| l1.advance_block_number(10));
+        simulation.advance(Duration::ZERO);
+        assert_eq!(l1.block_number("test").await.unwrap(), 113.into());
+    }
+}
@@ -40,6 +40,7 @@ pub(crate) fn create_miniblock(number: u32) -> MiniblockHeader {
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
         protocol_version: Some(ProtocolVersionId::latest()),
         virtual_blocks: 1,
+        gas_limit: None,
     }
 }
 
@@ -104,6 +105,7 @@ pub(crate) fn l1_batch_metadata_to_commitment_artifacts(
             }
             _ => None,
         },
+        blob_commitments: None,
     }
 }
 
@@ -145,6 +147,7 @@ pub(crate) fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionR
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        revert_data: None,
     }
 }
 
@@ -110,10 +110,11 @@ impl MainNodeBuilder {
     }
 
     fn add_eth_watch_layer(mut self) -> anyhow::Result<Self> {
-        self.node.add_layer(EthWatchLayer::new(
-            ETHWatchConfig::from_env()?,
-            ContractsConfig::from_env()?,
-        ));
+        let eth_client_config = ETHClientConfig::from_env()?;
+        self.node.add_layer(
+            EthWatchLayer::new(ETHWatchConfig::from_env()?, ContractsConfig::from_env()?)
+                .with_finality_source(eth_client_config.finality_source()),
+        );
         Ok(self)
     }
 
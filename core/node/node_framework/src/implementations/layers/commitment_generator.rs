@@ -0,0 +1,67 @@
+use zksync_config::configs::KzgConfig;
+use zksync_core::commitment_generator::CommitmentGenerator;
+use zksync_l1_contract_interface::i_executor::commit::kzg::KzgSettingsProvider;
+
+use crate::{
+    implementations::resources::pools::MasterPoolResource,
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Builder for the commitment generator.
+///
+/// ## Effects
+///
+/// - Resolves `MasterPoolResource`.
+/// - Adds `commitment_generator` to the node.
+#[derive(Debug)]
+pub struct CommitmentGeneratorLayer {
+    kzg_config: KzgConfig,
+}
+
+impl CommitmentGeneratorLayer {
+    pub fn new(kzg_config: KzgConfig) -> Self {
+        Self { kzg_config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for CommitmentGeneratorLayer {
+    fn layer_name(&self) -> &'static str {
+        "commitment_generator_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<MasterPoolResource>().await?;
+        let main_pool = pool_resource.get().await?;
+
+        let kzg_settings =
+            KzgSettingsProvider::from_path(self.kzg_config.trusted_setup_path.as_ref());
+        // Load the trusted setup eagerly, so that a misconfigured setup is detected during wiring.
+        kzg_settings.get().map_err(|err| {
+            WiringError::Configuration(format!("failed loading KZG settings: {err}"))
+        })?;
+        let commitment_generator = CommitmentGenerator::new(main_pool, kzg_settings);
+        context.add_task(Box::new(CommitmentGeneratorTask {
+            commitment_generator,
+        }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct CommitmentGeneratorTask {
+    commitment_generator: CommitmentGenerator,
+}
+
+#[async_trait::async_trait]
+impl Task for CommitmentGeneratorTask {
+    fn name(&self) -> &'static str {
+        "commitment_generator"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.commitment_generator.run(stop_receiver.0).await
+    }
+}
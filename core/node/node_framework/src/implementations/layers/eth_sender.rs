@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use zksync_config::{
+    configs::{
+        chain::NetworkConfig,
+        eth_sender::{PubdataSendingMode, SenderConfig},
+    },
+    ContractsConfig, ETHSenderConfig,
+};
+use zksync_core::{
+    eth_sender::{Aggregator, EthTxAggregator, EthTxManager},
+    l1_gas_price::L1TxParamsProvider,
+};
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::BoundEthInterface;
+use zksync_types::commitment::L1BatchCommitmentMode;
+
+use crate::{
+    implementations::resources::{
+        eth_interface::{BoundEthInterfaceResource, EthInterfaceResource},
+        l1_tx_params::L1TxParamsResource,
+        object_store::ObjectStoreResource,
+        pools::MasterPoolResource,
+    },
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Builder for the Ethereum sender, i.e. `eth_tx_aggregator` and `eth_tx_manager`.
+///
+/// Only sending pubdata in calldata is supported; sending pubdata in blobs requires a separate operator account
+/// and KZG settings, which are not wired yet.
+///
+/// ## Effects
+///
+/// - Resolves `MasterPoolResource`.
+/// - Resolves `ObjectStoreResource`.
+/// - Resolves `EthInterfaceResource`.
+/// - Resolves `BoundEthInterfaceResource`.
+/// - Resolves `L1TxParamsResource`.
+/// - Adds `eth_tx_aggregator` and `eth_tx_manager` to the node.
+#[derive(Debug)]
+pub struct EthSenderLayer {
+    eth_sender_config: ETHSenderConfig,
+    contracts_config: ContractsConfig,
+    network_config: NetworkConfig,
+    commitment_mode: L1BatchCommitmentMode,
+}
+
+impl EthSenderLayer {
+    pub fn new(
+        eth_sender_config: ETHSenderConfig,
+        contracts_config: ContractsConfig,
+        network_config: NetworkConfig,
+    ) -> Self {
+        Self {
+            eth_sender_config,
+            contracts_config,
+            network_config,
+            commitment_mode: L1BatchCommitmentMode::Rollup,
+        }
+    }
+
+    /// Sets the mode used to encode L1 batch commitments. If not set, L1 batches are committed in the rollup mode.
+    pub fn with_commitment_mode(mut self, commitment_mode: L1BatchCommitmentMode) -> Self {
+        self.commitment_mode = commitment_mode;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for EthSenderLayer {
+    fn layer_name(&self) -> &'static str {
+        "eth_sender_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let sender_config = self.eth_sender_config.sender;
+        if sender_config.pubdata_sending_mode == PubdataSendingMode::Blobs {
+            return Err(WiringError::Configuration(
+                "Sending pubdata in blobs is not supported by EthSenderLayer".to_string(),
+            ));
+        }
+
+        let pool_resource = context.get_resource::<MasterPoolResource>().await?;
+        let aggregator_pool = pool_resource.get().await?;
+        let manager_pool = pool_resource.get().await?;
+
+        let object_store = context.get_resource::<ObjectStoreResource>().await?.0;
+        let query_client = context.get_resource::<EthInterfaceResource>().await?.0;
+        let eth_client = context.get_resource::<BoundEthInterfaceResource>().await?.0;
+        let l1_tx_params = context.get_resource::<L1TxParamsResource>().await?.0;
+
+        let mut aggregator = Aggregator::new(
+            sender_config.clone(),
+            object_store,
+            false,
+            sender_config.pubdata_sending_mode.into(),
+            self.commitment_mode,
+            None,
+        )
+        .context("failed initializing aggregator")?;
+        if let Some(contract_address) = sender_config.execution_approval_contract_addr {
+            aggregator = aggregator.with_l1_approval_policy(query_client, contract_address);
+        }
+
+        let eth_tx_aggregator = EthTxAggregator::new(
+            sender_config.clone(),
+            aggregator,
+            eth_client.clone(),
+            self.contracts_config.validator_timelock_addr,
+            self.contracts_config.l1_multicall3_addr,
+            self.contracts_config.diamond_proxy_addr,
+            self.network_config.zksync_network_id,
+            None,
+            None,
+        )
+        .await;
+        context.add_task(Box::new(EthTxAggregatorTask {
+            eth_tx_aggregator,
+            pool: aggregator_pool,
+        }));
+
+        context.add_task(Box::new(EthTxManagerTask {
+            config: sender_config,
+            l1_tx_params,
+            eth_client,
+            pool: manager_pool,
+        }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct EthTxAggregatorTask {
+    eth_tx_aggregator: EthTxAggregator,
+    pool: ConnectionPool,
+}
+
+#[async_trait::async_trait]
+impl Task for EthTxAggregatorTask {
+    fn name(&self) -> &'static str {
+        "eth_tx_aggregator"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.eth_tx_aggregator.run(self.pool, stop_receiver.0).await
+    }
+}
+
+#[derive(Debug)]
+struct EthTxManagerTask {
+    config: SenderConfig,
+    l1_tx_params: Arc<dyn L1TxParamsProvider>,
+    eth_client: Arc<dyn BoundEthInterface>,
+    pool: ConnectionPool,
+}
+
+#[async_trait::async_trait]
+impl Task for EthTxManagerTask {
+    fn name(&self) -> &'static str {
+        "eth_tx_manager"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let eth_tx_manager =
+            EthTxManager::new(self.config, self.l1_tx_params, self.eth_client, None);
+        eth_tx_manager.run(self.pool, stop_receiver.0).await
+    }
+}
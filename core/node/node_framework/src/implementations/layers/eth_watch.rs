@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use zksync_config::{ContractsConfig, ETHWatchConfig};
+use zksync_config::{configs::eth_client::L1FinalitySource, ContractsConfig, ETHWatchConfig};
 use zksync_contracts::governance_contract;
 use zksync_core::eth_watch::{client::EthHttpQueryClient, EthWatch};
 use zksync_dal::ConnectionPool;
@@ -17,6 +17,7 @@ use crate::{
 pub struct EthWatchLayer {
     eth_watch_config: ETHWatchConfig,
     contracts_config: ContractsConfig,
+    finality_source: Option<L1FinalitySource>,
 }
 
 impl EthWatchLayer {
@@ -24,8 +25,16 @@ impl EthWatchLayer {
         Self {
             eth_watch_config,
             contracts_config,
+            finality_source: None,
         }
     }
+
+    /// Sets the L1 finality source shared by all components. If not set, the source is determined
+    /// by the `eth_watch` config.
+    pub fn with_finality_source(mut self, finality_source: Option<L1FinalitySource>) -> Self {
+        self.finality_source = finality_source;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -44,7 +53,8 @@ impl WiringLayer for EthWatchLayer {
             client,
             self.contracts_config.diamond_proxy_addr,
             Some(self.contracts_config.governance_addr),
-            self.eth_watch_config.confirmations_for_eth_event,
+            self.finality_source
+                .unwrap_or_else(|| self.eth_watch_config.legacy_finality_source()),
         );
         context.add_task(Box::new(EthWatchTask {
             main_pool,
@@ -10,7 +10,9 @@ use zksync_types::fee_model::FeeModelConfig;
 
 use crate::{
     implementations::resources::{
-        eth_interface::EthInterfaceResource, fee_input::FeeInputResource,
+        eth_interface::EthInterfaceResource,
+        fee_input::FeeInputResource,
+        l1_tx_params::{L1BlockTimestampResource, L1TxParamsResource},
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -45,6 +47,17 @@ impl WiringLayer for SequencerFeeInputLayer {
     }
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        if self.pubdata_sending_mode == PubdataSendingMode::Custom
+            && self
+                .gas_adjuster_config
+                .internal_enforced_pubdata_price
+                .is_none()
+        {
+            return Err(WiringError::Configuration(
+                "`internal_enforced_pubdata_price` must be set when pubdata is sent to a custom DA layer"
+                    .to_owned(),
+            ));
+        }
         let client = context.get_resource::<EthInterfaceResource>().await?.0;
         let adjuster =
             GasAdjuster::new(client, self.gas_adjuster_config, self.pubdata_sending_mode)
@@ -57,6 +70,8 @@ impl WiringLayer for SequencerFeeInputLayer {
             FeeModelConfig::from_state_keeper_config(&self.state_keeper_config),
         ));
         context.insert_resource(FeeInputResource(batch_fee_input_provider))?;
+        context.insert_resource(L1TxParamsResource(gas_adjuster.clone()))?;
+        context.insert_resource(L1BlockTimestampResource(gas_adjuster.clone()))?;
 
         context.add_task(Box::new(GasAdjusterTask { gas_adjuster }));
         Ok(())
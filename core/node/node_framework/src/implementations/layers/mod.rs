@@ -1,3 +1,5 @@
+pub mod commitment_generator;
+pub mod eth_sender;
 pub mod eth_watch;
 pub mod fee_input;
 pub mod healtcheck_server;
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
-use zksync_eth_client::clients::PKSigningClient;
+use zksync_eth_client::clients::OperatorSigningClient;
 
 use crate::{
     implementations::resources::eth_interface::BoundEthInterfaceResource,
@@ -37,17 +37,15 @@ impl WiringLayer for PKSigningEthClientLayer {
     }
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
-        if self.eth_sender_config.sender.private_key().is_none() {
-            return Err(WiringError::Configuration(
-                "Private key is missing in ETHSenderConfig".to_string(),
-            ));
-        }
-
-        let signing_client = PKSigningClient::from_config(
+        let signing_client = OperatorSigningClient::from_config(
             &self.eth_sender_config,
             &self.contracts_config,
             &self.eth_client_config,
-        );
+        )
+        .await
+        .map_err(|err| {
+            WiringError::Configuration(format!("Operator signer is misconfigured: {err}"))
+        })?;
         context.insert_resource(BoundEthInterfaceResource(Arc::new(signing_client)))?;
         Ok(())
     }
@@ -12,6 +12,7 @@ use zksync_core::state_keeper::{
 use crate::{
     implementations::resources::{
         fee_input::FeeInputResource,
+        l1_tx_params::L1BlockTimestampResource,
         object_store::ObjectStoreResource,
         pools::MasterPoolResource,
         state_keeper::{ConditionalSealerResource, StateKeeperIOResource},
@@ -57,7 +58,16 @@ impl MempoolIOLayer {
             .access_storage()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity).await;
+        let force_include_accounts = self
+            .state_keeper_config
+            .force_include_addresses
+            .iter()
+            .copied()
+            .collect();
+        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity)
+            .await
+            .with_force_include_accounts(force_include_accounts)
+            .with_fair_ordering(self.mempool_config.fair_ordering_max_reordering.is_some());
         mempool.register_metrics();
         Ok(mempool)
     }
@@ -104,7 +114,7 @@ impl WiringLayer for MempoolIOLayer {
             .get_singleton()
             .await
             .context("Get master pool")?;
-        let io = MempoolIO::new(
+        let mut io = MempoolIO::new(
             mempool_guard,
             object_store,
             miniblock_sealer_handle,
@@ -117,6 +127,13 @@ impl WiringLayer for MempoolIOLayer {
             self.network_config.zksync_network_id,
         )
         .await?;
+        if let Some(max_reordering) = self.mempool_config.fair_ordering_max_reordering {
+            io = io.with_fair_ordering(max_reordering);
+        }
+        if let Some(max_drift) = self.state_keeper_config.max_l1_timestamp_drift() {
+            let provider = context.get_resource::<L1BlockTimestampResource>().await?.0;
+            io = io.with_l1_timestamp_drift_limit(provider, max_drift);
+        }
         context.insert_resource(StateKeeperIOResource(Unique::new(Box::new(io))))?;
 
         // Create sealer.
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use zksync_core::l1_gas_price::{L1BlockTimestampProvider, L1TxParamsProvider};
+
+use crate::resource::{Resource, ResourceId};
+
+/// Wrapper for the L1 transaction params provider (i.e., the gas adjuster).
+#[derive(Debug, Clone)]
+pub struct L1TxParamsResource(pub Arc<dyn L1TxParamsProvider>);
+
+impl Resource for L1TxParamsResource {
+    fn resource_id() -> ResourceId {
+        "common/l1_tx_params".into()
+    }
+}
+
+/// Wrapper for the provider of the latest L1 block timestamp (i.e., the gas adjuster).
+#[derive(Debug, Clone)]
+pub struct L1BlockTimestampResource(pub Arc<dyn L1BlockTimestampProvider>);
+
+impl Resource for L1BlockTimestampResource {
+    fn resource_id() -> ResourceId {
+        "common/l1_block_timestamp".into()
+    }
+}
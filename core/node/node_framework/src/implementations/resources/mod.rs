@@ -1,6 +1,7 @@
 pub mod eth_interface;
 pub mod fee_input;
 pub mod healthcheck;
+pub mod l1_tx_params;
 pub mod object_store;
 pub mod pools;
 pub mod state_keeper;
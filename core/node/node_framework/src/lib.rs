@@ -17,8 +17,12 @@
 //! - Create a [`ZkStackService`](node::ZkStackService) with that [`ResourceProvider`](resource::ResourceProvider).
 //! - Add tasks to the node.
 //! - Run it.
+//!
+//! Components implemented outside of this crate can be bundled into a [`NodePlugin`](plugin::NodePlugin)
+//! and added to the service via [`ZkStackService::add_plugin`](service::ZkStackService::add_plugin).
 
 pub mod implementations;
+pub mod plugin;
 pub mod resource;
 pub mod service;
 pub mod task;
@@ -0,0 +1,68 @@
+//! Plugins allow out-of-tree crates to extend the node with their own wiring layers.
+//!
+//! A plugin is a set of wiring layers (and, through them, tasks and resources) that is registered
+//! in the [`ZkStackService`] via a single call. This allows downstream operators to ship custom
+//! components (e.g., indexers or policy engines) as separate crates and wire them into the node
+//! binary without modifying the framework itself:
+//!
+//! ```ignore
+//! pub struct MyIndexerPlugin { /* config */ }
+//!
+//! impl NodePlugin for MyIndexerPlugin {
+//!     fn plugin_name(&self) -> &'static str {
+//!         "my_indexer"
+//!     }
+//!
+//!     fn register(self: Box<Self>, registry: &mut PluginRegistry<'_>) -> anyhow::Result<()> {
+//!         registry.add_layer(MyIndexerLayer::new(self.config));
+//!         Ok(())
+//!     }
+//! }
+//!
+//! // In the node binary:
+//! service.add_plugin(MyIndexerPlugin { /* ... */ })?;
+//! ```
+//!
+//! Layers added by a plugin are wired in the same way as the layers added directly to the service,
+//! i.e. in the order of registration, and can use any resources provided by other layers.
+
+use crate::{service::ZkStackService, wiring_layer::WiringLayer};
+
+/// A plugin extending the node with a set of wiring layers.
+pub trait NodePlugin: 'static + Send + Sync {
+    /// Unique name of the plugin.
+    fn plugin_name(&self) -> &'static str;
+
+    /// Registers wiring layers of the plugin. This method will be called once, when the plugin is added
+    /// to the service.
+    fn register(self: Box<Self>, registry: &mut PluginRegistry<'_>) -> anyhow::Result<()>;
+}
+
+/// Interface provided to a [`NodePlugin`] during registration.
+#[derive(Debug)]
+pub struct PluginRegistry<'a> {
+    plugin: &'static str,
+    service: &'a mut ZkStackService,
+}
+
+impl<'a> PluginRegistry<'a> {
+    pub(crate) fn new(plugin: &'static str, service: &'a mut ZkStackService) -> Self {
+        Self { plugin, service }
+    }
+
+    /// Returns the name of the plugin being registered.
+    pub fn plugin_name(&self) -> &'static str {
+        self.plugin
+    }
+
+    /// Adds a wiring layer to the service.
+    pub fn add_layer<T: WiringLayer>(&mut self, layer: T) -> &mut Self {
+        tracing::info!(
+            "Plugin {} has added wiring layer {}",
+            self.plugin,
+            layer.layer_name()
+        );
+        self.service.add_layer(layer);
+        self
+    }
+}
@@ -1,10 +1,14 @@
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 use futures::{future::BoxFuture, FutureExt};
 use tokio::{runtime::Runtime, sync::watch};
 
 pub use self::{context::ServiceContext, stop_receiver::StopReceiver};
 use crate::{
+    plugin::{NodePlugin, PluginRegistry},
     resource::{ResourceId, StoredResource},
     task::Task,
     wiring_layer::{WiringError, WiringLayer},
@@ -35,6 +39,8 @@ pub struct ZkStackService {
     resources: HashMap<ResourceId, Box<dyn StoredResource>>,
     /// List of wiring layers.
     layers: Vec<Box<dyn WiringLayer>>,
+    /// Names of the registered plugins.
+    plugins: HashSet<&'static str>,
     /// Tasks added to the service.
     tasks: Vec<Box<dyn Task>>,
 
@@ -66,6 +72,7 @@ impl ZkStackService {
         let self_ = Self {
             resources: HashMap::default(),
             layers: Vec::new(),
+            plugins: HashSet::new(),
             tasks: Vec::new(),
             stop_sender,
             runtime,
@@ -82,6 +89,22 @@ impl ZkStackService {
         self
     }
 
+    /// Adds a plugin, i.e. a set of wiring layers provided by a potentially out-of-tree crate.
+    /// Layers of the plugin are added in the order specified by the plugin, after all the previously
+    /// added layers.
+    ///
+    /// Returns an error if a plugin with the same name is already added, or if the plugin fails to register.
+    pub fn add_plugin<T: NodePlugin>(&mut self, plugin: T) -> anyhow::Result<&mut Self> {
+        let name = plugin.plugin_name();
+        if !self.plugins.insert(name) {
+            anyhow::bail!("Plugin {name} is already added");
+        }
+        Box::new(plugin)
+            .register(&mut PluginRegistry::new(name, self))
+            .map_err(|err| err.context(format!("failed registering plugin {name}")))?;
+        Ok(self)
+    }
+
     /// Runs the system.
     pub fn run(mut self) -> anyhow::Result<()> {
         // Initialize tasks.
@@ -3,8 +3,9 @@ use std::sync::{Arc, Mutex};
 use anyhow::anyhow;
 use tokio::runtime::Runtime;
 
-use crate::service::{
-    ServiceContext, StopReceiver, Task, WiringError, WiringLayer, ZkStackService,
+use crate::{
+    plugin::{NodePlugin, PluginRegistry},
+    service::{ServiceContext, StopReceiver, Task, WiringError, WiringLayer, ZkStackService},
 };
 
 // `ZkStack` Service's `new()` method has to have a check for nested runtime.
@@ -209,3 +210,46 @@ fn test_task_run() {
     let res2 = *remaining_task_was_run.lock().unwrap();
     assert!(res2, "Incorrect resource value");
 }
+
+#[derive(Debug)]
+struct LayersPlugin {
+    layer_count: usize,
+}
+
+impl NodePlugin for LayersPlugin {
+    fn plugin_name(&self) -> &'static str {
+        "layers_plugin"
+    }
+
+    fn register(self: Box<Self>, registry: &mut PluginRegistry<'_>) -> anyhow::Result<()> {
+        for _ in 0..self.layer_count {
+            registry.add_layer(DefaultLayer);
+        }
+        Ok(())
+    }
+}
+
+// `ZkStack` Service's `add_plugin()` method has to add all the plugin layers and reject duplicate plugins.
+#[test]
+fn test_add_plugin() {
+    let mut zk_stack_service = ZkStackService::new().unwrap();
+    zk_stack_service.add_layer(DefaultLayer);
+    zk_stack_service
+        .add_plugin(LayersPlugin { layer_count: 2 })
+        .unwrap();
+    assert_eq!(
+        3,
+        zk_stack_service.layers.len(),
+        "Incorrect number of layers in the service"
+    );
+
+    let err = zk_stack_service
+        .add_plugin(LayersPlugin { layer_count: 1 })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Plugin layers_plugin is already added");
+    assert_eq!(
+        3,
+        zk_stack_service.layers.len(),
+        "Duplicate plugin must not add layers"
+    );
+}
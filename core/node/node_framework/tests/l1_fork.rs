@@ -0,0 +1,428 @@
+//! L1 fork test harness.
+//!
+//! Spins up an `anvil` L1 with the era contracts deployed, runs the main node components producing and
+//! publishing L1 batches (state keeper, Merkle tree, commitment generator, `eth_watch` and `eth_sender`) against it,
+//! and checks that an L1 batch goes through the full commit → prove → execute flow on L1. Proofs are mocked
+//! (`ProofSendingMode::SkipEveryProof`), so the contracts must be deployed with the testnet verifier.
+//!
+//! The test is ignored by default, since it requires an external environment:
+//!
+//! - The `anvil` binary (can be overridden with the `L1_FORK_TEST_ANVIL` env var).
+//! - L1 state with the deployed contracts: either an RPC URL of a network to fork (`L1_FORK_TEST_FORK_URL`,
+//!   optionally with `L1_FORK_TEST_FORK_BLOCK_NUMBER`), or an anvil state dump (`L1_FORK_TEST_LOAD_STATE`).
+//! - Main node env configuration (e.g., loaded with `zk env`) for the deployed contracts. The Postgres DB
+//!   must be initialized with the genesis matching the contracts. The L1 RPC URL from the configuration
+//!   is replaced with the URL of the spawned anvil instance (port can be set with `L1_FORK_TEST_ANVIL_PORT`).
+//! - A funded L1 account sending a priority operation, so that there's a transaction to seal an L1 batch with
+//!   (`L1_FORK_TEST_DEPOSITOR_PRIVATE_KEY`; defaults to the second anvil dev account). It must differ
+//!   from the operator account, so that the transaction doesn't interfere with nonces managed by `eth_sender`.
+//!
+//! Hyperchain operators can use the harness to validate a release against their contracts and configuration:
+//!
+//! ```text
+//! zk env my-chain
+//! L1_FORK_TEST_FORK_URL=https://... cargo test -p zksync_node_framework --test l1_fork -- --ignored
+//! ```
+
+use std::{
+    env,
+    net::TcpStream,
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use zksync_config::{
+    configs::{
+        chain::{MempoolConfig, NetworkConfig, OperationsManagerConfig, StateKeeperConfig},
+        eth_sender::ProofSendingMode,
+        KzgConfig,
+    },
+    ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig, ObjectStoreConfig,
+    PostgresConfig,
+};
+use zksync_contracts::zksync_contract;
+use zksync_core::metadata_calculator::MetadataCalculatorConfig;
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_eth_client::{
+    clients::{PKSigningClient, SigningClient},
+    BoundEthInterface, CallFunctionArgs, EthInterface, Options,
+};
+use zksync_eth_signer::PrivateKeySigner;
+use zksync_node_framework::{
+    implementations::{
+        layers::{
+            commitment_generator::CommitmentGeneratorLayer,
+            eth_sender::EthSenderLayer,
+            eth_watch::EthWatchLayer,
+            fee_input::SequencerFeeInputLayer,
+            metadata_calculator::MetadataCalculatorLayer,
+            object_store::ObjectStoreLayer,
+            pk_signing_eth_client::PKSigningEthClientLayer,
+            pools_layer::PoolsLayerBuilder,
+            query_eth_client::QueryEthClientLayer,
+            state_keeper::{
+                main_batch_executor::MainBatchExecutorLayer, mempool_io::MempoolIOLayer,
+                StateKeeperLayer,
+            },
+        },
+        resources::pools::MasterPoolResource,
+    },
+    service::{ServiceContext, StopReceiver, ZkStackService},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+use zksync_system_constants::REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE;
+use zksync_types::{
+    ethabi::Token, web3::transports::Http, Address, L1BatchNumber, L1ChainId, PackedEthSignature,
+    H256, U256,
+};
+
+/// Private key of the second account in the default anvil mnemonic.
+const DEFAULT_DEPOSITOR_PRIVATE_KEY: &str =
+    "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+const DEFAULT_ANVIL_PORT: u16 = 8546;
+const ANVIL_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+const TEST_TIMEOUT: Duration = Duration::from_secs(600);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const PRIORITY_OP_L2_GAS_LIMIT: u64 = 1_000_000;
+const PRIORITY_OP_L1_GAS_LIMIT: u64 = 1_000_000;
+const COMPONENT: &str = "l1_fork_test";
+
+/// Source of the L1 state with the deployed contracts.
+#[derive(Debug)]
+enum L1State {
+    Fork {
+        url: String,
+        block_number: Option<u64>,
+    },
+    Dump(String),
+}
+
+impl L1State {
+    fn from_env() -> anyhow::Result<Self> {
+        if let Ok(url) = env::var("L1_FORK_TEST_FORK_URL") {
+            let block_number = env::var("L1_FORK_TEST_FORK_BLOCK_NUMBER")
+                .ok()
+                .map(|number| number.parse())
+                .transpose()
+                .context("invalid L1_FORK_TEST_FORK_BLOCK_NUMBER")?;
+            return Ok(Self::Fork { url, block_number });
+        }
+        let path = env::var("L1_FORK_TEST_LOAD_STATE")
+            .context("either L1_FORK_TEST_FORK_URL or L1_FORK_TEST_LOAD_STATE must be set")?;
+        Ok(Self::Dump(path))
+    }
+}
+
+/// `anvil` process, which is killed on drop.
+#[derive(Debug)]
+struct Anvil {
+    process: Child,
+    url: String,
+}
+
+impl Anvil {
+    fn spawn(state: &L1State, chain_id: u64) -> anyhow::Result<Self> {
+        let binary = env::var("L1_FORK_TEST_ANVIL").unwrap_or_else(|_| "anvil".to_owned());
+        let port = match env::var("L1_FORK_TEST_ANVIL_PORT") {
+            Ok(port) => port.parse().context("invalid L1_FORK_TEST_ANVIL_PORT")?,
+            Err(_) => DEFAULT_ANVIL_PORT,
+        };
+
+        let mut command = Command::new(&binary);
+        command
+            .args(["--port", &port.to_string()])
+            .args(["--chain-id", &chain_id.to_string()])
+            .stdout(Stdio::null());
+        match state {
+            L1State::Fork { url, block_number } => {
+                command.args(["--fork-url", url]);
+                if let Some(number) = block_number {
+                    command.args(["--fork-block-number", &number.to_string()]);
+                }
+            }
+            L1State::Dump(path) => {
+                command.args(["--load-state", path]);
+            }
+        }
+        let process = command
+            .spawn()
+            .with_context(|| format!("failed spawning `{binary}`"))?;
+        let anvil = Self {
+            process,
+            url: format!("http://127.0.0.1:{port}"),
+        };
+        anvil.wait_until_ready(port)?;
+        Ok(anvil)
+    }
+
+    fn wait_until_ready(&self, port: u16) -> anyhow::Result<()> {
+        let started_at = Instant::now();
+        while TcpStream::connect(("127.0.0.1", port)).is_err() {
+            anyhow::ensure!(
+                started_at.elapsed() < ANVIL_STARTUP_TIMEOUT,
+                "anvil didn't start listening on port {port} in {ANVIL_STARTUP_TIMEOUT:?}"
+            );
+            thread::sleep(Duration::from_millis(100));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Anvil {
+    fn drop(&mut self) {
+        self.process.kill().ok();
+        self.process.wait().ok();
+    }
+}
+
+/// Layer adding a task that sends a priority operation to L1 and waits until the L1 batch containing it
+/// is executed on L1. Once the task finishes, the node is stopped.
+#[derive(Debug)]
+struct L1ForkCheckLayer {
+    depositor: PKSigningClient,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for L1ForkCheckLayer {
+    fn layer_name(&self) -> &'static str {
+        "l1_fork_check_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<MasterPoolResource>().await?;
+        let pool = pool_resource.get().await?;
+        context.add_task(Box::new(L1ForkCheckTask {
+            depositor: self.depositor,
+            pool,
+        }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct L1ForkCheckTask {
+    depositor: PKSigningClient,
+    pool: ConnectionPool,
+}
+
+impl L1ForkCheckTask {
+    async fn send_priority_op(&self) -> anyhow::Result<()> {
+        let depositor = &self.depositor;
+        let gas_price = depositor.get_gas_price(COMPONENT).await?;
+        let l2_gas_limit = U256::from(PRIORITY_OP_L2_GAS_LIMIT);
+        let gas_per_pubdata = U256::from(REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE);
+        let base_cost = depositor
+            .call_main_contract_function(CallFunctionArgs::new(
+                "l2TransactionBaseCost",
+                (gas_price, l2_gas_limit, gas_per_pubdata),
+            ))
+            .await?;
+        let base_cost = base_cost
+            .into_iter()
+            .next()
+            .and_then(Token::into_uint)
+            .context("unexpected `l2TransactionBaseCost` output")?;
+
+        // An empty L2 call to the depositor, i.e. a deposit of zero value.
+        let sender = depositor.sender_account();
+        let data = depositor.encode_tx_data(
+            "requestL2Transaction",
+            vec![
+                Token::Address(sender),
+                Token::Uint(U256::zero()),
+                Token::Bytes(vec![]),
+                Token::Uint(l2_gas_limit),
+                Token::Uint(gas_per_pubdata),
+                Token::Array(vec![]),
+                Token::Address(sender),
+            ],
+        );
+        let options = Options::with(|options| {
+            options.value = Some(base_cost);
+            options.gas = Some(PRIORITY_OP_L1_GAS_LIMIT.into());
+        });
+        let signed_tx = depositor.sign_prepared_tx(data, options, COMPONENT).await?;
+        let tx_hash = depositor.send_raw_tx(signed_tx.raw_tx).await?;
+        tracing::info!("Sent priority operation in L1 transaction {tx_hash:?}");
+
+        let started_at = Instant::now();
+        let status = loop {
+            if let Some(status) = depositor.get_tx_status(tx_hash, COMPONENT).await? {
+                break status;
+            }
+            anyhow::ensure!(
+                started_at.elapsed() < TEST_TIMEOUT,
+                "priority operation transaction wasn't mined in {TEST_TIMEOUT:?}"
+            );
+            tokio::time::sleep(POLL_INTERVAL).await;
+        };
+        anyhow::ensure!(status.success, "priority operation transaction failed");
+        Ok(())
+    }
+
+    async fn wait_for_execution(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
+        let started_at = Instant::now();
+        loop {
+            let mut storage = self.pool.access_storage().await?;
+            let committed = storage
+                .blocks_dal()
+                .get_number_of_last_l1_batch_committed_on_eth()
+                .await?;
+            let proven = storage
+                .blocks_dal()
+                .get_number_of_last_l1_batch_proven_on_eth()
+                .await?;
+            let executed = storage
+                .blocks_dal()
+                .get_number_of_last_l1_batch_executed_on_eth()
+                .await?;
+            drop(storage);
+
+            tracing::info!(
+                "Waiting for L1 batch #{l1_batch_number} to be executed on L1; last committed: {committed:?}, \
+                 proven: {proven:?}, executed: {executed:?}"
+            );
+            if executed >= Some(l1_batch_number) {
+                // Execution requires the batch to be committed and proven first, so the full flow is covered.
+                anyhow::ensure!(
+                    committed >= Some(l1_batch_number) && proven >= Some(l1_batch_number),
+                    "L1 batch #{l1_batch_number} is executed, but not committed or proven"
+                );
+                return Ok(());
+            }
+            anyhow::ensure!(
+                started_at.elapsed() < TEST_TIMEOUT,
+                "L1 batch #{l1_batch_number} wasn't executed on L1 in {TEST_TIMEOUT:?}"
+            );
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for L1ForkCheckTask {
+    fn name(&self) -> &'static str {
+        "l1_fork_check"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage().await?;
+        let last_sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("genesis L1 batch is missing")?;
+        drop(storage);
+
+        self.send_priority_op().await?;
+        // The priority operation will be included into the next L1 batch, or into one of subsequent batches
+        // if there are other pending priority ops. In both cases, the next L1 batch should be executed.
+        self.wait_for_execution(last_sealed_l1_batch + 1).await
+    }
+}
+
+fn create_depositor(
+    anvil: &Anvil,
+    eth_client_config: &ETHClientConfig,
+    eth_sender_config: &ETHSenderConfig,
+    contracts_config: &ContractsConfig,
+) -> anyhow::Result<PKSigningClient> {
+    let private_key = env::var("L1_FORK_TEST_DEPOSITOR_PRIVATE_KEY")
+        .unwrap_or_else(|_| DEFAULT_DEPOSITOR_PRIVATE_KEY.to_owned());
+    let private_key: H256 = private_key
+        .parse()
+        .context("invalid L1_FORK_TEST_DEPOSITOR_PRIVATE_KEY")?;
+    let address: Address = PackedEthSignature::address_from_private_key(&private_key)
+        .context("invalid depositor private key")?;
+    anyhow::ensure!(
+        eth_sender_config.sender.private_key() != Some(private_key),
+        "depositor must differ from the operator"
+    );
+
+    let transport = Http::new(&anvil.url).context("failed creating transport")?;
+    Ok(SigningClient::new(
+        transport,
+        zksync_contract(),
+        address,
+        PrivateKeySigner::new(private_key),
+        contracts_config.diamond_proxy_addr,
+        eth_sender_config
+            .gas_adjuster
+            .default_priority_fee_per_gas
+            .into(),
+        L1ChainId(eth_client_config.chain_id),
+    ))
+}
+
+#[test]
+#[ignore] // requires `anvil`, era contracts deployed on L1 and an initialized Postgres DB; see the module docs
+fn l1_batch_is_committed_proven_and_executed_on_l1_fork() -> anyhow::Result<()> {
+    let _guard = vlog::ObservabilityBuilder::new().build();
+
+    let mut eth_client_config = ETHClientConfig::from_env()?;
+    let anvil = Anvil::spawn(&L1State::from_env()?, eth_client_config.chain_id)?;
+    tracing::info!("Started anvil at {}", anvil.url);
+    eth_client_config.web3_url = anvil.url.clone();
+
+    let mut eth_sender_config = ETHSenderConfig::from_env()?;
+    eth_sender_config.sender.proof_sending_mode = ProofSendingMode::SkipEveryProof;
+    let contracts_config = ContractsConfig::from_env()?;
+    let network_config = NetworkConfig::from_env()?;
+    let state_keeper_config = StateKeeperConfig::from_env()?;
+    let db_config = DBConfig::from_env()?;
+    let depositor = create_depositor(
+        &anvil,
+        &eth_client_config,
+        &eth_sender_config,
+        &contracts_config,
+    )?;
+
+    let metadata_calculator_config = MetadataCalculatorConfig::for_main_node(
+        &db_config.merkle_tree,
+        &OperationsManagerConfig::from_env()?,
+    );
+    let pools_layer = PoolsLayerBuilder::empty(PostgresConfig::from_env()?)
+        .with_master(true)
+        .build();
+
+    let mut node = ZkStackService::new()?;
+    node.add_layer(pools_layer)
+        .add_layer(QueryEthClientLayer::new(eth_client_config.web3_url.clone()))
+        .add_layer(PKSigningEthClientLayer::new(
+            eth_sender_config.clone(),
+            contracts_config.clone(),
+            eth_client_config,
+        ))
+        .add_layer(SequencerFeeInputLayer::new(
+            eth_sender_config.gas_adjuster.clone(),
+            state_keeper_config.clone(),
+            eth_sender_config.sender.pubdata_sending_mode,
+        ))
+        .add_layer(ObjectStoreLayer::new(ObjectStoreConfig::from_env()?))
+        .add_layer(MetadataCalculatorLayer(metadata_calculator_config))
+        .add_layer(MempoolIOLayer::new(
+            network_config.clone(),
+            contracts_config.clone(),
+            state_keeper_config.clone(),
+            MempoolConfig::from_env()?,
+        ))
+        .add_layer(MainBatchExecutorLayer::new(db_config, state_keeper_config))
+        .add_layer(StateKeeperLayer)
+        .add_layer(CommitmentGeneratorLayer::new(KzgConfig::from_env()?))
+        .add_layer(EthWatchLayer::new(
+            ETHWatchConfig::from_env()?,
+            contracts_config.clone(),
+        ))
+        .add_layer(EthSenderLayer::new(
+            eth_sender_config,
+            contracts_config,
+            network_config,
+        ))
+        .add_layer(L1ForkCheckLayer { depositor });
+    // The node runs until the check task finishes, and returns its result.
+    node.run()
+}
@@ -0,0 +1,30 @@
+//! Fuzzes extraction of L1 batch commitments from the calldata of L1 commit transactions,
+//! as performed by the consistency checker.
+
+#![no_main]
+
+use libfuzzer_sys::{arbitrary, fuzz_target};
+use once_cell::sync::Lazy;
+use zksync_core::consistency_checker::ConsistencyChecker;
+use zksync_types::{ethabi, L1BatchNumber};
+
+static COMMIT_FUNCTION: Lazy<ethabi::Function> = Lazy::new(|| {
+    zksync_contracts::zksync_contract()
+        .function("commitBatches")
+        .expect("no `commitBatches` function")
+        .clone()
+});
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Input<'a> {
+    l1_batch_number: u32,
+    calldata: &'a [u8],
+}
+
+fuzz_target!(|input: Input<'_>| {
+    let _ = ConsistencyChecker::extract_commit_data(
+        input.calldata,
+        &COMMIT_FUNCTION,
+        L1BatchNumber(input.l1_batch_number),
+    );
+});
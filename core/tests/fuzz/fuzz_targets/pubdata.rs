@@ -0,0 +1,10 @@
+//! Fuzzes decoding (including state diff decompression) of L1 batch pubdata published on L1.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zksync_l1_contract_interface::i_executor::commit::pubdata::decode_pubdata;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_pubdata(data);
+});
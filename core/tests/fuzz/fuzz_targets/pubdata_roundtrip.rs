@@ -0,0 +1,102 @@
+//! Structured fuzzing of pubdata: pubdata encoded the same way as by the bootloader must be decoded
+//! into the original components.
+
+#![no_main]
+
+use libfuzzer_sys::{arbitrary, fuzz_target};
+use zksync_l1_contract_interface::i_executor::commit::pubdata::{decode_pubdata, StateDiffKey};
+use zksync_types::{
+    commitment::SerializeCommitment,
+    l2_to_l1_log::L2ToL1Log,
+    writes::{compress_state_diffs, StateDiffRecord},
+    Address, H256, U256,
+};
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Log {
+    shard_id: u8,
+    is_service: bool,
+    tx_number_in_block: u16,
+    sender: [u8; 20],
+    key: [u8; 32],
+    value: [u8; 32],
+}
+
+impl From<Log> for L2ToL1Log {
+    fn from(log: Log) -> Self {
+        Self {
+            shard_id: log.shard_id,
+            is_service: log.is_service,
+            tx_number_in_block: log.tx_number_in_block,
+            sender: Address::from(log.sender),
+            key: H256(log.key),
+            value: H256(log.value),
+        }
+    }
+}
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct StateDiff {
+    address: [u8; 20],
+    key: [u8; 32],
+    derived_key: [u8; 32],
+    /// Enumeration indices are limited to 4 bytes by the compression format.
+    enumeration_index: u32,
+    initial_value: [u8; 32],
+    final_value: [u8; 32],
+}
+
+impl From<StateDiff> for StateDiffRecord {
+    fn from(diff: StateDiff) -> Self {
+        Self {
+            address: Address::from(diff.address),
+            key: U256::from_big_endian(&diff.key),
+            derived_key: diff.derived_key,
+            enumeration_index: diff.enumeration_index.into(),
+            initial_value: U256::from_big_endian(&diff.initial_value),
+            final_value: U256::from_big_endian(&diff.final_value),
+        }
+    }
+}
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Input {
+    logs: Vec<Log>,
+    messages: Vec<Vec<u8>>,
+    bytecodes: Vec<Vec<u8>>,
+    state_diffs: Vec<StateDiff>,
+}
+
+fuzz_target!(|input: Input| {
+    let logs: Vec<L2ToL1Log> = input.logs.into_iter().map(Into::into).collect();
+    let state_diffs: Vec<StateDiffRecord> = input.state_diffs.into_iter().map(Into::into).collect();
+    let initial_writes_count = state_diffs
+        .iter()
+        .filter(|diff| diff.enumeration_index == 0)
+        .count();
+
+    let mut pubdata = (logs.len() as u32).to_be_bytes().to_vec();
+    for log in &logs {
+        pubdata.extend(log.to_bytes());
+    }
+    for items in [&input.messages, &input.bytecodes] {
+        pubdata.extend((items.len() as u32).to_be_bytes());
+        for item in items {
+            pubdata.extend((item.len() as u32).to_be_bytes());
+            pubdata.extend(item);
+        }
+    }
+    pubdata.extend(compress_state_diffs(state_diffs.clone()));
+
+    let decoded = decode_pubdata(&pubdata).expect("failed decoding valid pubdata");
+    assert_eq!(decoded.user_l2_to_l1_logs, logs);
+    assert_eq!(decoded.l2_to_l1_messages, input.messages);
+    assert_eq!(decoded.published_bytecodes, input.bytecodes);
+    assert_eq!(decoded.state_diffs.len(), state_diffs.len());
+    let decoded_initial_writes_count = decoded
+        .state_diffs
+        .iter()
+        .filter(|diff| matches!(diff.key, StateDiffKey::Initial(_)))
+        .count();
+    assert_eq!(decoded_initial_writes_count, initial_writes_count);
+});
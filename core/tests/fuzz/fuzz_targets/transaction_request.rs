@@ -0,0 +1,20 @@
+//! Fuzzes parsing of raw L2 transactions as received by `eth_sendRawTransaction` (legacy, EIP-1559 and EIP-712
+//! envelopes), followed by conversion into an `L2Tx`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zksync_system_constants::MAX_ENCODED_TX_SIZE;
+use zksync_types::{l2::L2Tx, transaction_request::TransactionRequest, L2ChainId};
+
+/// Chain ID used by the corpus transactions.
+const CHAIN_ID: u32 = 270;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok((request, _hash)) = TransactionRequest::from_bytes(data, L2ChainId::from(CHAIN_ID))
+    else {
+        return;
+    };
+    // Mirrors the transaction processing in the `eth_sendRawTransaction` handler.
+    let _ = L2Tx::from_request(request, MAX_ENCODED_TX_SIZE);
+});
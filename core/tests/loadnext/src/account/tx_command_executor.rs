@@ -44,6 +44,7 @@ impl AccountLifespan {
                 self.execute_withdraw(command).await
             }
             TxType::Deposit => self.execute_deposit(command).await,
+            TxType::Transfer => self.execute_transfer(command).await,
             TxType::DeployContract => self.execute_deploy_contract(command).await,
             TxType::L2Execute => {
                 self.execute_loadnext_contract(command, ExecutionType::L2)
@@ -212,6 +213,46 @@ impl AccountLifespan {
         Ok(result)
     }
 
+    async fn execute_transfer(&mut self, command: &TxCommand) -> Result<SubmitResult, ClientError> {
+        let tx = self.build_transfer(command).await?;
+        self.execute_submit(tx, command.modifier).await
+    }
+
+    async fn build_transfer(&self, command: &TxCommand) -> Result<L2Tx, ClientError> {
+        let wallet = self.wallet.wallet.clone();
+
+        let mut builder = wallet
+            .start_transfer()
+            .to(command.to)
+            .amount(command.amount)
+            .token(self.main_l2_token);
+
+        let fee = builder
+            .estimate_fee(Some(get_approval_based_paymaster_input_for_estimation(
+                self.paymaster_address,
+                self.main_l2_token,
+                MIN_ALLOWANCE_FOR_PAYMASTER_ESTIMATE.into(),
+            )))
+            .await?;
+
+        let paymaster_params = get_approval_based_paymaster_input(
+            self.paymaster_address,
+            self.main_l2_token,
+            fee.max_total_fee(),
+            Vec::new(),
+        );
+        builder = builder.fee(fee);
+        builder = builder.paymaster_params(paymaster_params);
+
+        if let Some(nonce) = self.current_nonce {
+            builder = builder.nonce(nonce);
+        }
+
+        let tx = builder.tx().await.map_err(Self::tx_creation_error)?;
+
+        Ok(self.apply_modifier(tx, command.modifier).await)
+    }
+
     async fn execute_withdraw(&mut self, command: &TxCommand) -> Result<SubmitResult, ClientError> {
         let tx = self.build_withdraw(command).await?;
         self.execute_submit(tx, command.modifier).await
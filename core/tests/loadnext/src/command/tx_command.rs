@@ -10,13 +10,14 @@ use crate::{
     rng::{LoadtestRng, WeightedRandom},
 };
 
-static WEIGHTS: OnceCell<[(TxType, f32); 5]> = OnceCell::new();
+static WEIGHTS: OnceCell<[(TxType, f32); 7]> = OnceCell::new();
 
 /// Type of transaction. It doesn't copy the zkSync operation list, because
 /// it divides some transactions in subcategories (e.g. to new account / to existing account; to self / to other; etc)/
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum TxType {
     Deposit,
+    Transfer,
     WithdrawToSelf,
     WithdrawToOther,
     DeployContract,
@@ -29,6 +30,8 @@ impl TxType {
         WEIGHTS
             .set([
                 (TxType::Deposit, transaction_weights.deposit),
+                (TxType::Transfer, transaction_weights.transfer),
+                (TxType::DeployContract, transaction_weights.deploy_contract),
                 (TxType::L2Execute, transaction_weights.l2_transactions),
                 (TxType::L1Execute, transaction_weights.l1_transactions),
                 (TxType::WithdrawToSelf, transaction_weights.withdrawal / 2.0),
@@ -57,8 +60,10 @@ impl TxType {
     const fn const_all() -> &'static [Self] {
         &[
             Self::Deposit,
+            Self::Transfer,
             Self::WithdrawToSelf,
             Self::WithdrawToOther,
+            Self::DeployContract,
             Self::L1Execute,
             Self::L2Execute,
         ]
@@ -301,6 +301,12 @@ pub struct TransactionWeights {
     pub withdrawal: f32,
     pub l1_transactions: f32,
     pub l2_transactions: f32,
+    /// ERC-20 transfers between the test accounts.
+    #[serde(default)]
+    pub transfer: f32,
+    /// Deployments of the loadnext contract.
+    #[serde(default)]
+    pub deploy_contract: f32,
 }
 
 impl TransactionWeights {
@@ -316,6 +322,8 @@ impl Default for TransactionWeights {
             withdrawal: 0.5,
             l1_transactions: 0.05,
             l2_transactions: 1.0,
+            transfer: 0.5,
+            deploy_contract: 0.05,
         }
     }
 }
@@ -515,6 +515,7 @@ impl Executor {
             self.config.duration(),
             self.config.prometheus_label.clone(),
             self.config.fail_fast,
+            self.pool.master_wallet.provider.clone(),
         );
         let report_collector_future = tokio::spawn(report_collector.run());
 
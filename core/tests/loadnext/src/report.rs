@@ -119,6 +119,7 @@ impl ReportLabel {
 /// Denotes the type of executed transaction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TxActionType {
+    Transfer,
     Withdraw,
     Deposit,
     DeployContract,
@@ -128,6 +129,7 @@ pub enum TxActionType {
 impl All for TxActionType {
     fn all() -> &'static [Self] {
         const ALL: &[TxActionType] = &[
+            TxActionType::Transfer,
             TxActionType::Withdraw,
             TxActionType::Deposit,
             TxActionType::DeployContract,
@@ -143,6 +145,7 @@ impl From<TxType> for TxActionType {
     fn from(command: TxType) -> Self {
         match command {
             TxType::Deposit => Self::Deposit,
+            TxType::Transfer => Self::Transfer,
             TxType::WithdrawToSelf | TxType::WithdrawToOther => Self::Withdraw,
             TxType::L2Execute => Self::Execute(ExecutionType::L2),
             TxType::L1Execute => Self::Execute(ExecutionType::L1),
@@ -130,15 +130,19 @@ impl MetricsCollector {
     }
 
     pub fn report(&self) {
-        tracing::info!("Action: [10 percentile, 50 percentile, 90 percentile]");
+        tracing::info!(
+            "Action: [10 percentile, 50 percentile, 90 percentile, 95 percentile, 99 percentile]"
+        );
         for (action, histogram) in &self.action_stats {
             // Only report data that was actually gathered.
             if !histogram.is_empty() {
                 tracing::info!(
-                    "{action:?}: [>{}ms >{}ms >{}ms]",
+                    "{action:?}: [>{}ms >{}ms >{}ms >{}ms >{}ms]",
                     histogram.percentile(10).0.as_millis(),
                     histogram.percentile(50).0.as_millis(),
                     histogram.percentile(90).0.as_millis(),
+                    histogram.percentile(95).0.as_millis(),
+                    histogram.percentile(99).0.as_millis(),
                 );
             }
         }
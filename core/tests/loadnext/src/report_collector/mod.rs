@@ -2,14 +2,18 @@ use std::time::{Duration, Instant};
 
 use futures::{channel::mpsc::Receiver, StreamExt};
 use operation_results_collector::OperationResultsCollector;
+use zksync::HttpClient;
 
 use crate::{
     report::{ActionType, Report, ReportLabel},
-    report_collector::metrics_collector::MetricsCollector,
+    report_collector::{
+        metrics_collector::MetricsCollector, seal_rate_collector::SealRateCollector,
+    },
 };
 
 mod metrics_collector;
 mod operation_results_collector;
+mod seal_rate_collector;
 
 /// Decision on whether loadtest considered passed or failed.
 #[derive(Debug, Clone, Copy)]
@@ -82,6 +86,8 @@ impl Collectors {
 /// - MetricsCollector, which builds time distribution histograms for each kind of performed action.
 /// - OperationResultsCollector, a primitive collector that counts the amount of failures and decides whether
 ///   test is passed.
+/// - SealRateCollector, which measures how many miniblocks and L1 batches the node seals per minute during the test.
+///   Unlike other collectors, it queries the node directly rather than analyzing reports.
 ///
 /// Other possible collectors that can be implemented:
 ///
@@ -103,6 +109,7 @@ pub struct ReportCollector {
     loadtest_duration: Duration,
     prometheus_label: String,
     fail_fast: bool,
+    seal_rate: SealRateCollector,
 }
 
 impl ReportCollector {
@@ -112,6 +119,7 @@ impl ReportCollector {
         loadtest_duration: Duration,
         prometheus_label: String,
         fail_fast: bool,
+        l2_client: HttpClient,
     ) -> Self {
         Self {
             reports_stream,
@@ -119,6 +127,7 @@ impl ReportCollector {
             loadtest_duration,
             prometheus_label,
             fail_fast,
+            seal_rate: SealRateCollector::new(l2_client),
         }
     }
 
@@ -134,6 +143,7 @@ impl ReportCollector {
                 tracing::info!("Test initialization and warm-up took {:?}", start.elapsed());
                 start = Instant::now();
                 collectors = Some(Collectors::new(self.loadtest_duration));
+                self.seal_rate.start().await;
                 continue;
             }
 
@@ -177,7 +187,8 @@ impl ReportCollector {
         // All the receivers are gone, it's likely the end of the test.
         // Now we can output the statistics.
         if let Some(collectors) = collectors {
-            collectors.report(self.prometheus_label);
+            collectors.report(self.prometheus_label.clone());
+            self.seal_rate.report(self.prometheus_label).await;
             collectors.final_resolution(self.expected_tx_count)
         } else {
             tracing::error!("Test failed before initialization was completed");
@@ -0,0 +1,113 @@
+use std::time::{Duration, Instant};
+
+use zksync::{EthNamespaceClient, HttpClient, ZksNamespaceClient};
+
+/// Numbers of the last sealed miniblock and L1 batch at a certain moment.
+#[derive(Debug, Clone, Copy)]
+struct SealsSnapshot {
+    miniblock: u64,
+    l1_batch: u64,
+    timestamp: Instant,
+}
+
+impl SealsSnapshot {
+    async fn fetch(client: &HttpClient) -> anyhow::Result<Self> {
+        let miniblock = client.get_block_number().await?.as_u64();
+        let l1_batch = client.get_l1_batch_number().await?.as_u64();
+        Ok(Self {
+            miniblock,
+            l1_batch,
+            timestamp: Instant::now(),
+        })
+    }
+
+    /// Returns the number of miniblocks and L1 batches sealed per minute since the `earlier` snapshot.
+    fn seals_per_minute(&self, earlier: &Self) -> (f64, f64) {
+        let elapsed = self.timestamp.saturating_duration_since(earlier.timestamp);
+        let minutes = elapsed.max(Duration::from_millis(1)).as_secs_f64() / 60.0;
+        let miniblocks = self.miniblock.saturating_sub(earlier.miniblock) as f64;
+        let l1_batches = self.l1_batch.saturating_sub(earlier.l1_batch) as f64;
+        (miniblocks / minutes, l1_batches / minutes)
+    }
+}
+
+/// Collector for the seal rate of the node under test. Measured as the number of miniblocks and L1 batches sealed
+/// by the node during the test (i.e., after initialization is complete), so it includes seals caused
+/// by other load on the node, if any.
+#[derive(Debug)]
+pub struct SealRateCollector {
+    client: HttpClient,
+    start: Option<SealsSnapshot>,
+}
+
+impl SealRateCollector {
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            start: None,
+        }
+    }
+
+    pub async fn start(&mut self) {
+        match SealsSnapshot::fetch(&self.client).await {
+            Ok(snapshot) => self.start = Some(snapshot),
+            Err(err) => tracing::warn!("Failed getting the initial seal state: {err}"),
+        }
+    }
+
+    pub async fn report(&self, prometheus_label: String) {
+        let Some(start) = &self.start else {
+            return;
+        };
+        let end = match SealsSnapshot::fetch(&self.client).await {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                tracing::warn!("Failed getting the final seal state: {err}");
+                return;
+            }
+        };
+
+        let (miniblocks_per_minute, l1_batches_per_minute) = end.seals_per_minute(start);
+        tracing::info!(
+            "Sealed miniblocks #{}..={} and L1 batches #{}..={}: \
+             {miniblocks_per_minute:.2} miniblocks / min, {l1_batches_per_minute:.2} L1 batches / min",
+            start.miniblock + 1,
+            end.miniblock,
+            start.l1_batch + 1,
+            end.l1_batch
+        );
+        metrics::gauge!(
+            "loadtest.miniblocks_per_minute",
+            miniblocks_per_minute,
+            "label" => prometheus_label.clone(),
+        );
+        metrics::gauge!(
+            "loadtest.l1_batches_per_minute",
+            l1_batches_per_minute,
+            "label" => prometheus_label,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computing_seals_per_minute() {
+        let start = SealsSnapshot {
+            miniblock: 100,
+            l1_batch: 10,
+            timestamp: Instant::now(),
+        };
+        let end = SealsSnapshot {
+            miniblock: 160,
+            l1_batch: 13,
+            timestamp: start.timestamp + Duration::from_secs(30),
+        };
+        assert_eq!(end.seals_per_minute(&start), (120.0, 6.0));
+
+        // Reverted or stale snapshots shouldn't lead to negative rates.
+        assert_eq!(start.seals_per_minute(&end), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT
+
+pragma solidity ^0.8.0;
+
+import "./interfaces/IPaymaster.sol";
+import "./interfaces/IPaymasterFlow.sol";
+import "./TransactionHelper.sol";
+import "./Constants.sol";
+
+// Operator-funded paymaster paying fees for transactions sponsored by the operator.
+// It approves any transaction using the general paymaster flow; the sponsorship policy (allowed contracts,
+// methods and gas / pubdata limits) is enforced by the sequencer, which rejects non-compliant transactions
+// using this paymaster both in the API and in the state keeper.
+contract SponsoringPaymaster is IPaymaster {
+    using TransactionHelper for Transaction;
+
+    address public owner;
+
+    constructor() {
+        owner = msg.sender;
+    }
+
+    modifier onlyBootloader() {
+        require(msg.sender == BOOTLOADER_FORMAL_ADDRESS, "Only bootloader can call this method");
+        _;
+    }
+
+    function validateAndPayForPaymasterTransaction(bytes32, bytes32, Transaction calldata _transaction) override external payable onlyBootloader returns (bytes4 magic, bytes memory context) {
+        magic = PAYMASTER_VALIDATION_SUCCESS_MAGIC;
+        require(_transaction.paymasterInput.length >= 4, "The standard paymaster input must be at least 4 bytes long");
+
+        bytes4 paymasterInputSelector = bytes4(_transaction.paymasterInput[0:4]);
+        require(paymasterInputSelector == IPaymasterFlow.general.selector, "Unsupported paymaster flow");
+
+        bool success = _transaction.payToTheBootloader();
+        require(success, "Failed to transfer funds to the bootloader");
+    }
+
+    function postTransaction(
+        bytes calldata,
+        Transaction calldata,
+        bytes32,
+        bytes32,
+        ExecutionResult,
+        uint256
+    ) override external payable onlyBootloader {
+        // Refunds are sent to the paymaster by the bootloader; nothing to do here.
+    }
+
+    function withdraw(address payable _to, uint256 _amount) external {
+        require(msg.sender == owner, "Only owner can withdraw funds");
+        (bool success, ) = _to.call{value: _amount}("");
+        require(success, "Failed to withdraw funds");
+    }
+
+    receive() external payable {}
+}
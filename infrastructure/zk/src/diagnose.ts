@@ -0,0 +1,414 @@
+import { Command } from 'commander';
+
+import { Pool } from 'pg';
+import { ethers } from 'ethers';
+import * as fs from 'fs';
+import { query } from './status';
+
+// Postgres connection pools - must be initialized later - as the ENV variables are set later.
+let main_pool: Pool | null = null;
+let prover_pool: Pool | null = null;
+
+const redStart = '\x1b[31m';
+const yellowStart = '\x1b[33m';
+const greenStart = '\x1b[32m';
+const resetColor = '\x1b[0m';
+
+type StageStatus = 'done' | 'skipped' | 'pending' | 'stuck' | 'failed';
+
+interface StageReport {
+    stage: string;
+    status: StageStatus;
+    details: string[];
+    // Explanation of why the stage is not done and what to check next.
+    reason?: string;
+}
+
+interface Diagnosis {
+    l1_batch_number: number;
+    stages: StageReport[];
+    // First stage that is not done, if any.
+    stuck_stage?: string;
+    summary: string;
+}
+
+async function firstRow(pool: Pool, text: string, params?: any[]): Promise<any | undefined> {
+    const result = await query(pool, text, params);
+    return result.rows[0];
+}
+
+function minutesSince(timestamp: Date | null | undefined): number {
+    if (timestamp == null) {
+        return 0;
+    }
+    // Timestamps are stored without time zone in UTC, but `pg` parses them as local time.
+    const utcTimestamp = timestamp.getTime() - timestamp.getTimezoneOffset() * 60_000;
+    return Math.floor((Date.now() - utcTimestamp) / 60_000);
+}
+
+// Classifies a queued / in-progress job as stuck if it wasn't updated for too long.
+function waitingStatus(updatedAt: Date, stuckAfterMinutes: number): StageStatus {
+    return minutesSince(updatedAt) >= stuckAfterMinutes ? 'stuck' : 'pending';
+}
+
+// Checks whether the object is present in the core object store. Only the file-backed store can be checked locally.
+function objectPresence(bucket: string, key: string): string {
+    const mode = process.env.OBJECT_STORE_MODE;
+    if (mode != 'FileBacked') {
+        return `${bucket}/${key}: not checked (object store mode is ${mode})`;
+    }
+    const path = `${process.env.OBJECT_STORE_FILE_BACKED_BASE_PATH}/${bucket}/${key}`;
+    return `${bucket}/${key}: ${fs.existsSync(path) ? 'present' : 'missing'}`;
+}
+
+async function diagnoseSealing(batch: number): Promise<[StageReport, any | undefined]> {
+    const row = await firstRow(
+        main_pool!,
+        'select number, hash, commitment, eth_commit_tx_id, eth_prove_tx_id, eth_execute_tx_id, created_at from l1_batches where number = $1',
+        [batch]
+    );
+    if (row === undefined) {
+        const last = await firstRow(main_pool!, 'select max(number) as number from l1_batches');
+        return [
+            {
+                stage: 'sealing',
+                status: 'pending',
+                details: [`last sealed L1 batch: ${last?.number}`],
+                reason: 'The batch is not sealed yet. Check that the state keeper is running and receives transactions.'
+            },
+            undefined
+        ];
+    }
+    return [{ stage: 'sealing', status: 'done', details: [`sealed at ${row.created_at}`] }, row];
+}
+
+function diagnoseTree(batchRow: any, stuckAfterMinutes: number): StageReport {
+    if (batchRow.hash != null) {
+        return { stage: 'tree', status: 'done', details: [`root hash: ${ethers.utils.hexlify(batchRow.hash)}`] };
+    }
+    return {
+        stage: 'tree',
+        status: waitingStatus(batchRow.created_at, stuckAfterMinutes),
+        details: [],
+        reason: 'The Merkle tree has not processed the batch. Check the `tree` component logs; a tree that is stuck on the same batch usually indicates a storage or RocksDB issue.'
+    };
+}
+
+function diagnoseCommitment(batchRow: any, stuckAfterMinutes: number): StageReport {
+    if (batchRow.commitment != null) {
+        return {
+            stage: 'commitment',
+            status: 'done',
+            details: [`commitment: ${ethers.utils.hexlify(batchRow.commitment)}`]
+        };
+    }
+    return {
+        stage: 'commitment',
+        status: waitingStatus(batchRow.created_at, stuckAfterMinutes),
+        details: [],
+        reason: 'The batch commitment is not generated. Check that the `commitment_generator` component is running.'
+    };
+}
+
+async function diagnoseWitnessInputs(batch: number, stuckAfterMinutes: number): Promise<StageReport> {
+    const job = await firstRow(
+        main_pool!,
+        'select status, attempts, error, updated_at from basic_witness_input_producer_jobs where l1_batch_number = $1',
+        [batch]
+    );
+    const details = [objectPresence('witness_inputs', `witness_block_state_for_l1_batch_${batch}.bin`)];
+    if (job === undefined) {
+        return {
+            stage: 'witness_inputs',
+            status: 'pending',
+            details,
+            reason: 'No basic witness input producer job exists for the batch. The job is created when the batch is sealed; check state keeper logs.'
+        };
+    }
+    details.unshift(`job status: ${job.status}, attempts: ${job.attempts}`);
+    switch (job.status) {
+        case 'Successful':
+            return { stage: 'witness_inputs', status: 'done', details };
+        case 'ManuallySkipped':
+            return { stage: 'witness_inputs', status: 'skipped', details };
+        case 'Failed':
+            return {
+                stage: 'witness_inputs',
+                status: 'failed',
+                details,
+                reason: `Basic witness input producer failed: ${job.error}. If the error is persistent, check the VM run for this batch.`
+            };
+        default:
+            return {
+                stage: 'witness_inputs',
+                status: waitingStatus(job.updated_at, stuckAfterMinutes),
+                details,
+                reason: 'Witness inputs are not produced yet. Check that the `basic_witness_input_producer` component is running.'
+            };
+    }
+}
+
+async function diagnoseProofGenerationData(batch: number, stuckAfterMinutes: number): Promise<StageReport> {
+    const details = await firstRow(
+        main_pool!,
+        'select status, prover_taken_at, updated_at from proof_generation_details where l1_batch_number = $1',
+        [batch]
+    );
+    if (details === undefined) {
+        if (process.env.ETH_SENDER_SENDER_PROOF_SENDING_MODE == 'SkipEveryProof') {
+            return { stage: 'proof', status: 'skipped', details: ['proofs are skipped for all batches'] };
+        }
+        return {
+            stage: 'proof',
+            status: 'pending',
+            details: [],
+            reason: 'Proof generation data is not saved yet. Check the `tree` component (it saves Merkle paths for the prover).'
+        };
+    }
+    const reportDetails = [
+        `status: ${details.status}`,
+        objectPresence('witness_inputs', `merkel_tree_paths_${batch}.bin`)
+    ];
+    switch (details.status) {
+        case 'generated':
+            reportDetails.push(objectPresence('proofs_fri', `l1_batch_proof_${batch}.bin`));
+            return { stage: 'proof', status: 'done', details: reportDetails };
+        case 'skipped':
+            return { stage: 'proof', status: 'skipped', details: reportDetails };
+        case 'ready_to_be_proven':
+            return {
+                stage: 'proof',
+                status: waitingStatus(details.updated_at, stuckAfterMinutes),
+                details: reportDetails,
+                reason: 'The batch was not picked by the prover gateway. Check that the `proof_data_handler` component and the prover gateway are running.'
+            };
+        default:
+            return {
+                stage: 'proof',
+                status: waitingStatus(details.prover_taken_at ?? details.updated_at, stuckAfterMinutes),
+                details: reportDetails,
+                reason: 'The batch is being proven. See the `prover` stage for the state of the prover pipeline.'
+            };
+    }
+}
+
+async function diagnoseProver(batch: number, stuckAfterMinutes: number): Promise<StageReport> {
+    const details: string[] = [];
+    const rounds: [string, string][] = [
+        ['basic witness generator', 'witness_inputs_fri'],
+        ['leaf aggregation', 'leaf_aggregation_witness_jobs_fri'],
+        ['node aggregation', 'node_aggregation_witness_jobs_fri'],
+        ['scheduler', 'scheduler_witness_jobs_fri']
+    ];
+    for (const [name, table] of rounds) {
+        const result = await query(
+            prover_pool!,
+            `select status, count(*) as count, max(updated_at) as updated_at, max(error) as error from ${table} where l1_batch_number = $1 group by status`,
+            [batch]
+        );
+        const statuses = result.rows.map((row: any) => `${row.count} ${row.status}`).join(', ');
+        details.push(`${name} jobs: ${statuses || 'none'}`);
+        const failed = result.rows.find((row: any) => row.status == 'failed');
+        if (failed !== undefined) {
+            return {
+                stage: 'prover',
+                status: 'failed',
+                details,
+                reason: `${failed.count} ${name} job(s) failed: ${failed.error}. Jobs are retried by the house keeper until max attempts are reached.`
+            };
+        }
+    }
+
+    const proverJobs = await query(
+        prover_pool!,
+        'select status, count(*) as count from prover_jobs_fri where l1_batch_number = $1 group by status',
+        [batch]
+    );
+    const proverStatuses = proverJobs.rows.map((row: any) => `${row.count} ${row.status}`).join(', ');
+    details.push(`prover jobs: ${proverStatuses || 'none'}`);
+
+    const compression = await firstRow(
+        prover_pool!,
+        'select status, attempts, error, updated_at from proof_compression_jobs_fri where l1_batch_number = $1',
+        [batch]
+    );
+    if (compression === undefined) {
+        return {
+            stage: 'prover',
+            status: 'pending',
+            details,
+            reason: 'No proof compression job exists yet; the proof is still being generated. Check prover queue sizes with `zk status prover`.'
+        };
+    }
+    details.push(`compression job: ${compression.status}, attempts: ${compression.attempts}`);
+    switch (compression.status) {
+        case 'successful':
+        case 'sent_to_server':
+            return { stage: 'prover', status: 'done', details };
+        case 'skipped':
+            return { stage: 'prover', status: 'skipped', details };
+        case 'failed':
+            return {
+                stage: 'prover',
+                status: 'failed',
+                details,
+                reason: `Proof compression failed: ${compression.error}.`
+            };
+        default:
+            return {
+                stage: 'prover',
+                status: waitingStatus(compression.updated_at, stuckAfterMinutes),
+                details,
+                reason: 'The proof is not compressed yet. Check that the proof compressor is running.'
+            };
+    }
+}
+
+async function diagnoseEthTx(
+    provider: ethers.providers.Provider,
+    stage: string,
+    ethTxId: number | null,
+    stuckAfterMinutes: number
+): Promise<StageReport> {
+    if (ethTxId == null) {
+        return {
+            stage,
+            status: 'pending',
+            details: [],
+            reason: `No ${stage} transaction is created for the batch. The \`eth_tx_aggregator\` creates transactions in order, so check that the previous batch is processed and that the aggregation criteria (e.g., deadlines) are met.`
+        };
+    }
+    const ethTx = await firstRow(
+        main_pool!,
+        'select id, nonce, has_failed, confirmed_eth_tx_history_id, created_at from eth_txs where id = $1',
+        [ethTxId]
+    );
+    const history = await query(
+        main_pool!,
+        'select tx_hash, sent_at_block, confirmed_at from eth_txs_history where eth_tx_id = $1 order by created_at',
+        [ethTxId]
+    );
+    const details = [`eth_tx #${ethTx.id}, nonce ${ethTx.nonce}, ${history.rowCount} sent attempt(s)`];
+
+    let reverted = false;
+    for (const attempt of history.rows) {
+        let receiptStatus;
+        try {
+            const receipt = await provider.getTransactionReceipt(attempt.tx_hash);
+            if (receipt == null) {
+                receiptStatus = 'not mined';
+            } else {
+                reverted = reverted || receipt.status == 0;
+                receiptStatus = `${receipt.status == 1 ? 'succeeded' : 'reverted'} in L1 block ${receipt.blockNumber}`;
+            }
+        } catch (error) {
+            receiptStatus = `failed getting receipt: ${error}`;
+        }
+        details.push(`${attempt.tx_hash} (sent at block ${attempt.sent_at_block}): ${receiptStatus}`);
+    }
+
+    if (ethTx.confirmed_eth_tx_history_id != null) {
+        return { stage, status: 'done', details };
+    }
+    if (ethTx.has_failed || reverted) {
+        return {
+            stage,
+            status: 'failed',
+            details,
+            reason: `The ${stage} transaction has failed on L1. The \`eth_tx_manager\` doesn't send further transactions until failed transactions are investigated and cleared.`
+        };
+    }
+    if (history.rowCount == 0) {
+        return {
+            stage,
+            status: waitingStatus(ethTx.created_at, stuckAfterMinutes),
+            details,
+            reason: 'The transaction was never sent. Check that the `eth_tx_manager` component is running and that the operator account has enough funds.'
+        };
+    }
+    return {
+        stage,
+        status: waitingStatus(ethTx.created_at, stuckAfterMinutes),
+        details,
+        reason: 'The transaction is not confirmed yet. If it is not mined for a long time, it is likely underpriced; check gas adjuster metrics and the operator nonce.'
+    };
+}
+
+export async function diagnoseBatch(batch: number, stuckAfterMinutes: number): Promise<Diagnosis> {
+    const stages: StageReport[] = [];
+    const [sealing, batchRow] = await diagnoseSealing(batch);
+    stages.push(sealing);
+    if (batchRow !== undefined) {
+        const provider = new ethers.providers.JsonRpcProvider(process.env.ETH_CLIENT_WEB3_URL);
+        stages.push(diagnoseTree(batchRow, stuckAfterMinutes));
+        stages.push(diagnoseCommitment(batchRow, stuckAfterMinutes));
+        stages.push(await diagnoseWitnessInputs(batch, stuckAfterMinutes));
+        const proof = await diagnoseProofGenerationData(batch, stuckAfterMinutes);
+        stages.push(proof);
+        if (proof.status != 'skipped') {
+            stages.push(await diagnoseProver(batch, stuckAfterMinutes));
+        }
+        stages.push(await diagnoseEthTx(provider, 'commit', batchRow.eth_commit_tx_id, stuckAfterMinutes));
+        stages.push(await diagnoseEthTx(provider, 'prove', batchRow.eth_prove_tx_id, stuckAfterMinutes));
+        stages.push(await diagnoseEthTx(provider, 'execute', batchRow.eth_execute_tx_id, stuckAfterMinutes));
+    }
+
+    const stuck = stages.find((stage) => stage.status != 'done' && stage.status != 'skipped');
+    let summary;
+    if (stuck === undefined) {
+        summary = `L1 batch ${batch} is executed on L1.`;
+    } else if (stuck.status == 'pending') {
+        summary = `L1 batch ${batch} is waiting at stage \`${stuck.stage}\`: ${stuck.reason}`;
+    } else {
+        summary = `L1 batch ${batch} is ${stuck.status} at stage \`${stuck.stage}\`: ${stuck.reason}`;
+    }
+    return { l1_batch_number: batch, stages, stuck_stage: stuck?.stage, summary };
+}
+
+function printDiagnosis(diagnosis: Diagnosis) {
+    console.log(`==== Diagnosis for L1 batch ${diagnosis.l1_batch_number} ====`);
+    const colors: Record<StageStatus, string> = {
+        done: greenStart,
+        skipped: greenStart,
+        pending: yellowStart,
+        stuck: redStart,
+        failed: redStart
+    };
+    for (const stage of diagnosis.stages) {
+        console.log(`${colors[stage.status]}[${stage.status}]${resetColor} ${stage.stage}`);
+        for (const detail of stage.details) {
+            console.log(`    ${detail}`);
+        }
+    }
+    const summaryColor = diagnosis.stuck_stage === undefined ? greenStart : redStart;
+    console.log(`${summaryColor}${diagnosis.summary}${resetColor}`);
+}
+
+export async function batch(number: string, cmd: Command) {
+    const batchNumber = parseInt(number);
+    if (isNaN(batchNumber)) {
+        throw new Error(`Invalid L1 batch number: ${number}`);
+    }
+    main_pool = new Pool({ connectionString: process.env.DATABASE_URL });
+    prover_pool = new Pool({ connectionString: process.env.DATABASE_PROVER_URL });
+    try {
+        const diagnosis = await diagnoseBatch(batchNumber, parseInt(cmd.stuckAfter));
+        if (cmd.json) {
+            console.log(JSON.stringify(diagnosis, null, 4));
+        } else {
+            printDiagnosis(diagnosis);
+        }
+    } finally {
+        await main_pool.end();
+        await prover_pool.end();
+    }
+}
+
+export const command = new Command('diagnose').description('diagnose problems with the local system');
+
+command
+    .command('batch <number>')
+    .description('inspect the state of an L1 batch and determine the stage at which it is stuck')
+    .option('--stuck-after <minutes>', 'minutes after which a waiting stage is considered stuck', '30')
+    .option('--json', 'output the diagnosis as JSON')
+    .action(batch);
@@ -22,6 +22,8 @@ import { command as db } from './database';
 import { command as verifyUpgrade } from './verify-upgrade';
 import { proverCommand } from './prover_setup';
 import { command as status } from './status';
+import { command as diagnose } from './diagnose';
+import { command as top } from './top';
 import { command as spellcheck } from './spellcheck';
 import { command as linkcheck } from './linkcheck';
 import * as env from './env';
@@ -50,6 +52,8 @@ const COMMANDS = [
     proverCommand,
     env.command,
     status,
+    diagnose,
+    top,
     spellcheck,
     linkcheck,
     completion(program as Command)
@@ -0,0 +1,284 @@
+import { Command } from 'commander';
+
+import { Pool } from 'pg';
+import fetch from 'node-fetch';
+import { query } from './status';
+
+// Postgres connection pool - must be initialized later - as the ENV variables are set later.
+let main_pool: Pool | null = null;
+
+const redStart = '\x1b[31m';
+const yellowStart = '\x1b[33m';
+const greenStart = '\x1b[32m';
+const resetColor = '\x1b[0m';
+const clearScreen = '\x1b[2J\x1b[H';
+
+const BAR_WIDTH = 30;
+const MAX_IN_FLIGHT_TXS = 10;
+
+interface OpenBatch {
+    number: number;
+    miniblocks: number;
+    txs: number;
+    // Age of the first miniblock in the batch.
+    ageSeconds: number;
+}
+
+interface Lag {
+    component: string;
+    lastBatch: number | null;
+}
+
+interface InFlightTx {
+    id: number;
+    txType: string;
+    nonce: number;
+    ageSeconds: number;
+    txHash: string | null;
+    baseFeePerGas: number | null;
+    priorityFeePerGas: number | null;
+    blobBaseFeePerGas: number | null;
+}
+
+interface NodeState {
+    lastSealedBatch: number;
+    lastMiniblock: number;
+    openBatch: OpenBatch;
+    // Fill level of the open batch for each seal criterion, in [0, 1].
+    fillLevels: Map<string, number>;
+    metricsError?: string;
+    lags: Lag[];
+    inFlightTxs: InFlightTx[];
+}
+
+async function firstRow(text: string, params?: any[]): Promise<any | undefined> {
+    const result = await query(main_pool!, text, params);
+    return result.rows[0];
+}
+
+function secondsSince(timestamp: Date | null | undefined): number {
+    if (timestamp == null) {
+        return 0;
+    }
+    // Timestamps are stored without time zone in UTC, but `pg` parses them as local time.
+    const utcTimestamp = timestamp.getTime() - timestamp.getTimezoneOffset() * 60_000;
+    return Math.max(0, Math.floor((Date.now() - utcTimestamp) / 1_000));
+}
+
+function toNumber(value: any): number | null {
+    return value == null ? null : Number(value);
+}
+
+// Parses fill levels of the open batch from the state keeper metrics in the Prometheus text format.
+function parseFillLevels(metrics: string): Map<string, number> {
+    const fillLevels = new Map<string, number>();
+    const pattern = /^server_state_keeper_l1_batch_capacity_filled\{criterion="([^"]+)"\}\s+(\S+)$/;
+    for (const line of metrics.split('\n')) {
+        const match = line.trim().match(pattern);
+        if (match) {
+            fillLevels.set(match[1], parseFloat(match[2]));
+        }
+    }
+    return fillLevels;
+}
+
+async function fetchFillLevels(metricsUrl: string): Promise<Map<string, number>> {
+    const response = await fetch(metricsUrl);
+    if (!response.ok) {
+        throw new Error(`${metricsUrl} responded with ${response.status}`);
+    }
+    return parseFillLevels(await response.text());
+}
+
+async function getOpenBatch(lastSealedBatch: number): Promise<OpenBatch> {
+    const row = await firstRow(
+        `SELECT COUNT(*) AS miniblocks, COALESCE(SUM(l2_tx_count + l1_tx_count), 0) AS txs,
+            MIN(created_at) AS started_at
+        FROM miniblocks WHERE l1_batch_number IS NULL OR l1_batch_number > $1`,
+        [lastSealedBatch]
+    );
+    return {
+        number: lastSealedBatch + 1,
+        miniblocks: Number(row.miniblocks),
+        txs: Number(row.txs),
+        ageSeconds: secondsSince(row.started_at)
+    };
+}
+
+async function getLags(): Promise<Lag[]> {
+    const row = await firstRow(
+        `SELECT
+            (SELECT MAX(number) FROM l1_batches WHERE hash IS NOT NULL) AS tree,
+            (SELECT MAX(number) FROM l1_batches WHERE commitment IS NOT NULL) AS commitment,
+            (SELECT MAX(l1_batch_number) FROM proof_generation_details WHERE status = 'generated') AS prover`
+    );
+    const lags: Lag[] = [
+        { component: 'tree', lastBatch: toNumber(row.tree) },
+        { component: 'commitment', lastBatch: toNumber(row.commitment) },
+        { component: 'prover', lastBatch: toNumber(row.prover) }
+    ];
+
+    for (const action of ['commit', 'prove', 'execute']) {
+        const row = await firstRow(
+            `SELECT MAX(l1_batches.number) AS number FROM l1_batches
+            JOIN eth_txs_history ON l1_batches.eth_${action}_tx_id = eth_txs_history.eth_tx_id
+            WHERE eth_txs_history.confirmed_at IS NOT NULL`
+        );
+        lags.push({ component: `eth_sender (${action})`, lastBatch: toNumber(row.number) });
+    }
+    return lags;
+}
+
+async function getInFlightTxs(): Promise<InFlightTx[]> {
+    const result = await query(
+        main_pool!,
+        `SELECT eth_txs.id, eth_txs.tx_type, eth_txs.nonce, eth_txs.created_at,
+            history.tx_hash, history.base_fee_per_gas, history.priority_fee_per_gas, history.blob_base_fee_per_gas
+        FROM eth_txs
+        LEFT JOIN LATERAL (
+            SELECT * FROM eth_txs_history WHERE eth_txs_history.eth_tx_id = eth_txs.id
+            ORDER BY eth_txs_history.created_at DESC LIMIT 1
+        ) history ON TRUE
+        WHERE eth_txs.confirmed_eth_tx_history_id IS NULL AND NOT eth_txs.has_failed
+        ORDER BY eth_txs.id LIMIT $1`,
+        [MAX_IN_FLIGHT_TXS]
+    );
+    return result.rows.map((row: any) => ({
+        id: Number(row.id),
+        txType: row.tx_type,
+        nonce: Number(row.nonce),
+        ageSeconds: secondsSince(row.created_at),
+        txHash: row.tx_hash,
+        baseFeePerGas: toNumber(row.base_fee_per_gas),
+        priorityFeePerGas: toNumber(row.priority_fee_per_gas),
+        blobBaseFeePerGas: toNumber(row.blob_base_fee_per_gas)
+    }));
+}
+
+async function getNodeState(metricsUrl: string): Promise<NodeState> {
+    const sealed = await firstRow('SELECT MAX(number) AS number FROM l1_batches');
+    const lastSealedBatch = Number(sealed.number ?? 0);
+    const miniblock = await firstRow('SELECT MAX(number) AS number FROM miniblocks');
+
+    let fillLevels = new Map<string, number>();
+    let metricsError;
+    try {
+        fillLevels = await fetchFillLevels(metricsUrl);
+    } catch (err: any) {
+        metricsError = err.message;
+    }
+
+    return {
+        lastSealedBatch,
+        lastMiniblock: Number(miniblock.number ?? 0),
+        openBatch: await getOpenBatch(lastSealedBatch),
+        fillLevels,
+        metricsError,
+        lags: await getLags(),
+        inFlightTxs: await getInFlightTxs()
+    };
+}
+
+function colorFor(ratio: number, warnAt: number, alertAt: number): string {
+    if (ratio >= alertAt) {
+        return redStart;
+    }
+    return ratio >= warnAt ? yellowStart : greenStart;
+}
+
+function bar(ratio: number): string {
+    const clamped = Math.min(Math.max(ratio, 0), 1);
+    const filled = Math.round(clamped * BAR_WIDTH);
+    const color = colorFor(ratio, 0.7, 0.9);
+    return `${color}[${'#'.repeat(filled)}${' '.repeat(BAR_WIDTH - filled)}]${resetColor} ${(ratio * 100).toFixed(1)}%`;
+}
+
+function formatGwei(wei: number | null): string {
+    return wei == null ? '-' : `${(wei / 1e9).toFixed(3)} gwei`;
+}
+
+function printNodeState(state: NodeState) {
+    const { openBatch } = state;
+    console.log(`Time: ${new Date().toISOString()}`);
+    console.log(`Last sealed L1 batch: ${state.lastSealedBatch}, last miniblock: ${state.lastMiniblock}`);
+    console.log(
+        `Open L1 batch #${openBatch.number}: ${openBatch.miniblocks} miniblocks, ${openBatch.txs} txs, ` +
+            `open for ${openBatch.ageSeconds}s`
+    );
+
+    console.log('\nOpen batch fill levels:');
+    const fillLevels = new Map(state.fillLevels);
+    if (state.metricsError !== undefined) {
+        console.log(`    ${yellowStart}State keeper metrics are unavailable: ${state.metricsError}${resetColor}`);
+        const slots = parseInt(process.env.CHAIN_STATE_KEEPER_TRANSACTION_SLOTS!);
+        if (!isNaN(slots) && slots > 0) {
+            fillLevels.set('slots', openBatch.txs / slots);
+        }
+    }
+    const commitDeadlineMs = parseInt(process.env.CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE_MS!);
+    if (!isNaN(commitDeadlineMs) && commitDeadlineMs > 0 && openBatch.miniblocks > 0) {
+        fillLevels.set('timeout', (openBatch.ageSeconds * 1_000) / commitDeadlineMs);
+    }
+    const nameWidth = Math.max(0, ...[...fillLevels.keys()].map((name) => name.length));
+    for (const [criterion, ratio] of [...fillLevels.entries()].sort()) {
+        console.log(`    ${criterion.padEnd(nameWidth)} ${bar(ratio)}`);
+    }
+
+    console.log('\nLag behind the last sealed L1 batch:');
+    const componentWidth = Math.max(...state.lags.map((lag) => lag.component.length));
+    for (const lag of state.lags) {
+        const name = lag.component.padEnd(componentWidth);
+        if (lag.lastBatch == null) {
+            console.log(`    ${name} ${yellowStart}no batches processed${resetColor}`);
+            continue;
+        }
+        const batches = state.lastSealedBatch - lag.lastBatch;
+        const color = colorFor(batches, 2, 10);
+        console.log(`    ${name} ${color}${batches} batches${resetColor} (last processed: #${lag.lastBatch})`);
+    }
+
+    console.log('\nIn-flight L1 transactions:');
+    if (state.inFlightTxs.length == 0) {
+        console.log(`    ${greenStart}none${resetColor}`);
+    }
+    for (const tx of state.inFlightTxs) {
+        const hash = tx.txHash ?? `${yellowStart}not sent${resetColor}`;
+        console.log(
+            `    #${tx.id} ${tx.txType} (nonce ${tx.nonce}, ${tx.ageSeconds}s old) ${hash}: ` +
+                `base fee ${formatGwei(tx.baseFeePerGas)}, priority fee ${formatGwei(tx.priorityFeePerGas)}, ` +
+                `blob base fee ${formatGwei(tx.blobBaseFeePerGas)}`
+        );
+    }
+}
+
+export async function top(cmd: Command) {
+    const interval = parseFloat(cmd.interval);
+    if (isNaN(interval) || interval <= 0) {
+        throw new Error(`Invalid refresh interval: ${cmd.interval}`);
+    }
+    const metricsUrl = cmd.metricsUrl ?? `http://127.0.0.1:${process.env.API_PROMETHEUS_LISTENER_PORT}/metrics`;
+
+    main_pool = new Pool({ connectionString: process.env.DATABASE_URL });
+    try {
+        while (true) {
+            const state = await getNodeState(metricsUrl);
+            if (!cmd.once) {
+                process.stdout.write(clearScreen);
+            }
+            printNodeState(state);
+            if (cmd.once) {
+                break;
+            }
+            await new Promise((resolve) => setTimeout(resolve, interval * 1_000));
+        }
+    } finally {
+        await main_pool.end();
+    }
+}
+
+export const command = new Command('top')
+    .description('show live state of the local node: open batch fill levels, component lags and in-flight L1 txs')
+    .option('--interval <seconds>', 'refresh interval', '2')
+    .option('--metrics-url <url>', 'URL of the server Prometheus exporter (defaults to the local exporter)')
+    .option('--once', 'print the state once and exit')
+    .action(top);
@@ -0,0 +1,283 @@
+//! Operator tool re-proving an executed L1 batch under a specific prover protocol version, e.g. to validate
+//! a prover migration. Re-proven batches go through the regular prover pipeline, but the resulting proofs
+//! are not sent to the server, so re-proving doesn't affect L1 state.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use zksync_config::configs::PostgresConfig;
+use zksync_dal::{fri_proof_compressor_dal::ProofCompressionJobStatus, ConnectionPool};
+use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
+use zksync_object_store::{
+    serialize_using_bincode, Bucket, ObjectStore, ObjectStoreFactory, StoredObject,
+};
+use zksync_prover_interface::{inputs::PrepareBasicCircuitsJob, outputs::L1BatchProofForL1};
+use zksync_types::{protocol_version::FriProtocolVersionId, L1BatchNumber};
+
+/// Copy of the proof generated for a batch before re-proving it. Re-proving overwrites the original proof,
+/// so it's copied to a separate key first.
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct OriginalProof(L1BatchProofForL1);
+
+impl StoredObject for OriginalProof {
+    const BUCKET: Bucket = Bucket::ProofsFri;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("l1_batch_proof_{key}_before_reproof.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+/// Report comparing artifacts produced by re-proving with the original ones.
+#[derive(Debug, Serialize)]
+struct ReproofReport {
+    l1_batch_number: L1BatchNumber,
+    original_protocol_version: Option<u16>,
+    protocol_version: u16,
+    /// Whether public outputs of the proofs (system logs, state diffs, bootloader heap and events queue
+    /// commitments) match. A mismatch indicates a bug in one of the prover versions.
+    aggregation_result_coords_match: bool,
+    /// Whether the final proofs are byte-for-byte identical.
+    scheduler_proofs_match: bool,
+    /// Numbers of prover jobs per aggregation round.
+    original_circuit_counts: BTreeMap<u8, u64>,
+    circuit_counts: BTreeMap<u8, u64>,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Batch re-proving tool", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Requeues an executed L1 batch to be re-proven under the specified protocol version.
+    Enqueue {
+        /// Number of the L1 batch to re-prove.
+        #[arg(long)]
+        l1_batch_number: u32,
+        /// Prover protocol version to re-prove the batch under. Must be registered in the prover DB.
+        #[arg(long)]
+        protocol_version: u16,
+    },
+    /// Outputs the report comparing the original and re-proven artifacts for an L1 batch.
+    Report {
+        /// Number of the re-proven L1 batch.
+        #[arg(long)]
+        l1_batch_number: u32,
+    },
+}
+
+async fn enqueue(
+    pool: &ConnectionPool,
+    prover_pool: &ConnectionPool,
+    blob_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+    protocol_version: FriProtocolVersionId,
+) -> anyhow::Result<()> {
+    let last_executed_l1_batch = pool
+        .access_storage()
+        .await?
+        .blocks_dal()
+        .get_number_of_last_l1_batch_executed_on_eth()
+        .await?;
+    anyhow::ensure!(
+        last_executed_l1_batch.map_or(false, |number| number >= l1_batch_number),
+        "L1 batch #{l1_batch_number} is not executed on L1 (last executed batch: {last_executed_l1_batch:?})"
+    );
+
+    let mut storage = prover_pool.access_storage().await?;
+    let compression_status = storage
+        .fri_proof_compressor_dal()
+        .get_proof_compression_job_status(l1_batch_number)
+        .await;
+    anyhow::ensure!(
+        matches!(
+            compression_status,
+            Some(ProofCompressionJobStatus::Successful | ProofCompressionJobStatus::SentToServer)
+        ),
+        "L1 batch #{l1_batch_number} doesn't have a proof (proof compression status: {compression_status:?})"
+    );
+    let existing_reproof = storage
+        .fri_reproof_dal()
+        .get_reproof(l1_batch_number)
+        .await?;
+    anyhow::ensure!(
+        existing_reproof
+            .as_ref()
+            .map_or(true, |reproof| reproof.report.is_some()),
+        "L1 batch #{l1_batch_number} is already being re-proven"
+    );
+
+    let current_protocol_version = storage
+        .fri_witness_generator_dal()
+        .protocol_version_for_l1_batch(l1_batch_number)
+        .await;
+    anyhow::ensure!(
+        protocol_version >= current_protocol_version,
+        "L1 batch #{l1_batch_number} was proven under protocol version {current_protocol_version:?}, \
+         so it cannot be re-proven under the older version {protocol_version:?}"
+    );
+
+    blob_store
+        .get::<PrepareBasicCircuitsJob>(l1_batch_number)
+        .await
+        .with_context(|| {
+            format!("witness inputs for L1 batch #{l1_batch_number} are not available")
+        })?;
+    // If the batch was re-proven before, the stored proof is produced by the previous re-proof; the original proof
+    // and circuit counts copied on the first re-proof must be preserved.
+    let (original_proof_blob_url, original_circuit_counts) = match existing_reproof {
+        Some(reproof) => (
+            reproof.original_proof_blob_url,
+            reproof.original_circuit_counts,
+        ),
+        None => {
+            let original_proof: L1BatchProofForL1 = blob_store
+                .get(l1_batch_number)
+                .await
+                .with_context(|| format!("failed loading proof for L1 batch #{l1_batch_number}"))?;
+            let original_proof_blob_url = blob_store
+                .put(l1_batch_number, &OriginalProof(original_proof))
+                .await
+                .context("failed copying original proof")?;
+            let circuit_counts = storage
+                .fri_reproof_dal()
+                .get_circuit_counts(l1_batch_number)
+                .await?;
+            (
+                original_proof_blob_url,
+                serde_json::to_value(&circuit_counts)?,
+            )
+        }
+    };
+
+    let original_protocol_version = storage
+        .fri_reproof_dal()
+        .enqueue_reproof(
+            l1_batch_number,
+            protocol_version,
+            &original_proof_blob_url,
+            &original_circuit_counts,
+        )
+        .await
+        .context(
+            "failed enqueuing re-proof; is the protocol version registered in the prover DB?",
+        )?;
+    tracing::info!(
+        "Enqueued L1 batch #{l1_batch_number} to be re-proven under protocol version {protocol_version:?} \
+         (originally proven under {original_protocol_version:?}); the original proof is stored at `{original_proof_blob_url}`"
+    );
+    Ok(())
+}
+
+async fn report(
+    prover_pool: &ConnectionPool,
+    blob_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<serde_json::Value> {
+    let mut storage = prover_pool.access_storage().await?;
+    let reproof = storage
+        .fri_reproof_dal()
+        .get_reproof(l1_batch_number)
+        .await?
+        .with_context(|| format!("L1 batch #{l1_batch_number} is not re-proven"))?;
+    if let Some(report) = reproof.report {
+        return Ok(report);
+    }
+
+    let compression_status = storage
+        .fri_proof_compressor_dal()
+        .get_proof_compression_job_status(l1_batch_number)
+        .await;
+    anyhow::ensure!(
+        matches!(compression_status, Some(ProofCompressionJobStatus::Successful)),
+        "Re-proving L1 batch #{l1_batch_number} is not finished (proof compression status: {compression_status:?})"
+    );
+
+    let OriginalProof(original_proof) = blob_store
+        .get::<OriginalProof>(l1_batch_number)
+        .await
+        .context("failed loading original proof")?;
+    let proof: L1BatchProofForL1 = blob_store
+        .get(l1_batch_number)
+        .await
+        .context("failed loading re-proven proof")?;
+    let original_circuit_counts = serde_json::from_value(reproof.original_circuit_counts)
+        .context("malformed original circuit counts")?;
+    let circuit_counts = storage
+        .fri_reproof_dal()
+        .get_circuit_counts(l1_batch_number)
+        .await?;
+
+    let report = ReproofReport {
+        l1_batch_number,
+        original_protocol_version: reproof.original_protocol_version.map(|id| id as u16),
+        protocol_version: reproof.protocol_version as u16,
+        aggregation_result_coords_match: original_proof.aggregation_result_coords
+            == proof.aggregation_result_coords,
+        scheduler_proofs_match: OriginalProof(original_proof).serialize()?
+            == OriginalProof(proof).serialize()?,
+        original_circuit_counts,
+        circuit_counts,
+    };
+    let report = serde_json::to_value(&report)?;
+    storage
+        .fri_reproof_dal()
+        .save_report(l1_batch_number, &report)
+        .await?;
+    Ok(report)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(vlog::LogFormat::Plain)
+        .build();
+    let cli = Cli::parse();
+
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let prover_pool = ConnectionPool::singleton(postgres_config.prover_url()?)
+        .build()
+        .await
+        .context("failed to build a prover connection pool")?;
+    let object_store_config =
+        ProverObjectStoreConfig::from_env().context("ProverObjectStoreConfig::from_env()")?;
+    let blob_store = ObjectStoreFactory::new(object_store_config.0)
+        .create_store()
+        .await;
+
+    match cli.command {
+        Command::Enqueue {
+            l1_batch_number,
+            protocol_version,
+        } => {
+            let protocol_version = FriProtocolVersionId::try_from(protocol_version)
+                .context("unknown protocol version")?;
+            let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            enqueue(
+                &pool,
+                &prover_pool,
+                &*blob_store,
+                L1BatchNumber(l1_batch_number),
+                protocol_version,
+            )
+            .await
+        }
+        Command::Report { l1_batch_number } => {
+            let report = report(&prover_pool, &*blob_store, L1BatchNumber(l1_batch_number)).await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            Ok(())
+        }
+    }
+}
@@ -33,6 +33,8 @@ async fn test_leaf_witness_gen() {
             file_backed_base_path: "./tests/data/leaf/".to_owned(),
         },
         max_retries: 5,
+        compression_level: None,
+        replica_bucket_base_url: None,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
@@ -73,6 +75,8 @@ async fn test_node_witness_gen() {
             file_backed_base_path: "./tests/data/node/".to_owned(),
         },
         max_retries: 5,
+        compression_level: None,
+        replica_bucket_base_url: None,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
@@ -114,6 +118,8 @@ async fn test_scheduler_witness_gen() {
             file_backed_base_path: "./tests/data/scheduler/".to_owned(),
         },
         max_retries: 5,
+        compression_level: None,
+        replica_bucket_base_url: None,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()